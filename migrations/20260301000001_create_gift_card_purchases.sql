@@ -0,0 +1,41 @@
+-- ============================================================================
+-- Migration: Gift card purchases
+-- Created: 2026-03-01
+-- Description: Gift card orders funded by completed swaps, with encrypted codes
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS gift_card_purchases (
+    id VARCHAR(36) PRIMARY KEY,
+    user_id VARCHAR(36) NOT NULL,
+    provider VARCHAR(50) NOT NULL,
+    card_id VARCHAR(100) NOT NULL,
+    card_name VARCHAR(255) NOT NULL,
+    country VARCHAR(10),
+    amount DOUBLE NOT NULL,
+
+    -- Funding
+    funding_swap_id VARCHAR(36),
+    provider_order_id VARCHAR(100),
+    payment_currency VARCHAR(20) NOT NULL,
+    payment_network VARCHAR(50) NOT NULL,
+    payment_amount DOUBLE NOT NULL,
+    payment_address VARCHAR(255),
+    payment_extra_id VARCHAR(100),
+
+    -- Fulfillment (code is AES-256-GCM encrypted, see services::encryption)
+    status ENUM('pending', 'processing', 'delivered', 'failed') NOT NULL DEFAULT 'pending',
+    delivery_email VARCHAR(255) NOT NULL,
+    encrypted_code TEXT,
+    delivered_at TIMESTAMP NULL,
+    error TEXT,
+
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+
+    UNIQUE KEY uk_gift_card_funding_swap (funding_swap_id),
+    INDEX idx_gift_card_purchases_user (user_id, created_at),
+    INDEX idx_gift_card_purchases_status (status),
+
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (funding_swap_id) REFERENCES swaps(id) ON DELETE SET NULL
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
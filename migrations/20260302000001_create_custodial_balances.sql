@@ -0,0 +1,90 @@
+-- ============================================================================
+-- Migration: Custodial balances
+-- Created: 2026-03-02
+-- Description: Opt-in per-user balances backed by an append-only ledger,
+--              deposit-to-balance swaps, and withdrawal requests with approval
+-- ============================================================================
+
+ALTER TABLE users
+    ADD COLUMN is_admin BOOLEAN NOT NULL DEFAULT FALSE AFTER two_factor_secret,
+    ADD COLUMN custody_enabled BOOLEAN NOT NULL DEFAULT FALSE AFTER is_admin;
+
+-- 'address' = pay out on-chain to recipient_address, 'balance' = credit custodial balance
+ALTER TABLE swaps
+    ADD COLUMN payout_mode ENUM('address', 'balance') NOT NULL DEFAULT 'address' AFTER rate_type;
+
+-- =============================================================================
+-- BALANCE ACCOUNTS
+-- One row per user/asset; `balance` is a cached SUM of ledger_entries.amount
+-- =============================================================================
+
+CREATE TABLE IF NOT EXISTS balance_accounts (
+    id VARCHAR(36) PRIMARY KEY,
+    user_id VARCHAR(36) NOT NULL,
+    currency VARCHAR(20) NOT NULL,
+    network VARCHAR(50) NOT NULL,
+    balance DECIMAL(36, 18) NOT NULL DEFAULT 0,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+
+    UNIQUE KEY uk_balance_account (user_id, currency, network),
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE RESTRICT,
+    CONSTRAINT chk_balance_non_negative CHECK (balance >= 0)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+-- =============================================================================
+-- LEDGER ENTRIES (append-only)
+-- =============================================================================
+
+CREATE TABLE IF NOT EXISTS ledger_entries (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    account_id VARCHAR(36) NOT NULL,
+    entry_type ENUM(
+        'swap_credit',
+        'transfer_in',
+        'transfer_out',
+        'withdrawal_hold',
+        'withdrawal_release'
+    ) NOT NULL,
+    amount DECIMAL(36, 18) NOT NULL,
+    balance_after DECIMAL(36, 18) NOT NULL,
+    reference_type VARCHAR(30) NOT NULL,
+    reference_id VARCHAR(36) NOT NULL,
+    memo VARCHAR(255),
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    -- Each business event posts at most once per account
+    UNIQUE KEY uk_ledger_reference (account_id, entry_type, reference_type, reference_id),
+    INDEX idx_ledger_account_time (account_id, created_at),
+
+    FOREIGN KEY (account_id) REFERENCES balance_accounts(id) ON DELETE RESTRICT
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+-- =============================================================================
+-- WITHDRAWAL REQUESTS
+-- =============================================================================
+
+CREATE TABLE IF NOT EXISTS withdrawal_requests (
+    id VARCHAR(36) PRIMARY KEY,
+    user_id VARCHAR(36) NOT NULL,
+    account_id VARCHAR(36) NOT NULL,
+    currency VARCHAR(20) NOT NULL,
+    network VARCHAR(50) NOT NULL,
+    amount DECIMAL(36, 18) NOT NULL,
+    destination_address VARCHAR(255) NOT NULL,
+    destination_extra_id VARCHAR(100),
+    status ENUM('pending_approval', 'approved', 'rejected', 'processing', 'completed', 'failed') NOT NULL DEFAULT 'pending_approval',
+    reviewed_by VARCHAR(36),
+    reviewed_at TIMESTAMP NULL,
+    rejection_reason TEXT,
+    tx_hash VARCHAR(255),
+    error TEXT,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+
+    INDEX idx_withdrawals_user (user_id, created_at),
+    INDEX idx_withdrawals_status (status, created_at),
+
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE RESTRICT,
+    FOREIGN KEY (account_id) REFERENCES balance_accounts(id) ON DELETE RESTRICT
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
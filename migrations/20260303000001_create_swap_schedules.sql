@@ -0,0 +1,54 @@
+-- ============================================================================
+-- Migration: Recurring swap schedules (DCA)
+-- Created: 2026-03-03
+-- Description: User-defined recurring swaps executed by the schedule worker
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS swap_schedules (
+    id VARCHAR(36) PRIMARY KEY,
+    user_id VARCHAR(36) NOT NULL,
+
+    -- Swap template
+    from_currency VARCHAR(20) NOT NULL,
+    from_network VARCHAR(50) NOT NULL,
+    to_currency VARCHAR(20) NOT NULL,
+    to_network VARCHAR(50) NOT NULL,
+    amount DECIMAL(36, 18) NOT NULL,
+    provider VARCHAR(50) NOT NULL,
+    recipient_address VARCHAR(255) NOT NULL,
+    recipient_extra_id VARCHAR(100),
+    refund_address VARCHAR(255),
+    refund_extra_id VARCHAR(100),
+    payout_to_balance BOOLEAN NOT NULL DEFAULT FALSE,
+
+    -- Cadence
+    frequency ENUM('daily', 'weekly', 'monthly') NOT NULL,
+    status ENUM('active', 'paused', 'cancelled') NOT NULL DEFAULT 'active',
+    next_run_at TIMESTAMP NOT NULL,
+    last_run_at TIMESTAMP NULL,
+    last_swap_id VARCHAR(36),
+    consecutive_failures INT NOT NULL DEFAULT 0,
+    last_error TEXT,
+
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+
+    INDEX idx_schedules_user (user_id, created_at),
+    INDEX idx_schedules_due (status, next_run_at),
+
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+-- One row per execution attempt
+CREATE TABLE IF NOT EXISTS swap_schedule_runs (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    schedule_id VARCHAR(36) NOT NULL,
+    swap_id VARCHAR(36),
+    succeeded BOOLEAN NOT NULL,
+    error TEXT,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    INDEX idx_schedule_runs (schedule_id, created_at),
+
+    FOREIGN KEY (schedule_id) REFERENCES swap_schedules(id) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
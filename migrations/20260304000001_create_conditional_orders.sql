@@ -0,0 +1,42 @@
+-- ============================================================================
+-- Migration: Conditional (limit-order style) swaps
+-- Created: 2026-03-04
+-- Description: Swaps that are created automatically once quotes reach a target rate
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS conditional_orders (
+    id VARCHAR(36) PRIMARY KEY,
+    user_id VARCHAR(36) NOT NULL,
+
+    -- Swap template
+    from_currency VARCHAR(20) NOT NULL,
+    from_network VARCHAR(50) NOT NULL,
+    to_currency VARCHAR(20) NOT NULL,
+    to_network VARCHAR(50) NOT NULL,
+    amount DECIMAL(36, 18) NOT NULL,
+    provider VARCHAR(50),                  -- NULL = best available provider
+    recipient_address VARCHAR(255) NOT NULL,
+    recipient_extra_id VARCHAR(100),
+    refund_address VARCHAR(255),
+    refund_extra_id VARCHAR(100),
+    payout_to_balance BOOLEAN NOT NULL DEFAULT FALSE,
+
+    -- Trigger: fire when quoted rate (to per from) >= target_rate
+    target_rate DECIMAL(36, 18) NOT NULL,
+    status ENUM('pending', 'triggered', 'cancelled', 'expired', 'failed') NOT NULL DEFAULT 'pending',
+    expires_at TIMESTAMP NOT NULL,
+    last_checked_rate DECIMAL(36, 18),
+    last_checked_at TIMESTAMP NULL,
+    triggered_rate DECIMAL(36, 18),
+    triggered_at TIMESTAMP NULL,
+    swap_id VARCHAR(36),
+    error TEXT,
+
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+
+    INDEX idx_orders_user (user_id, status, created_at),
+    INDEX idx_orders_pending (status, expires_at),
+
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
@@ -0,0 +1,22 @@
+-- ============================================================================
+-- Migration: Address book
+-- Created: 2026-03-05
+-- Description: Saved, validated recipient addresses per user
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS address_book_entries (
+    id VARCHAR(36) PRIMARY KEY,
+    user_id VARCHAR(36) NOT NULL,
+    label VARCHAR(100) NOT NULL,
+    currency VARCHAR(20) NOT NULL,
+    network VARCHAR(50) NOT NULL,
+    address VARCHAR(255) NOT NULL,
+    extra_id VARCHAR(100),
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+
+    UNIQUE KEY uk_address_book_entry (user_id, currency, network, address),
+    INDEX idx_address_book_user (user_id, currency, network),
+
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
@@ -0,0 +1,26 @@
+-- ============================================================================
+-- Migration: Provider order intents
+-- Created: 2026-03-06
+-- Description: Durable record of every provider order placed during swap
+--              creation so orders without a committed swap can be reconciled
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS provider_order_intents (
+    swap_id VARCHAR(36) PRIMARY KEY,
+    provider_id VARCHAR(50) NOT NULL,
+    provider_swap_id VARCHAR(100),
+    our_address VARCHAR(255) NOT NULL,
+    address_index INT UNSIGNED NOT NULL,
+    -- pending:   recorded before the provider call, outcome unknown
+    -- created:   provider order exists, swap not yet committed
+    -- committed: swap row committed alongside the order
+    -- orphaned:  provider order without a swap, needs review
+    -- resolved:  orphan confirmed harmless (expired or never funded)
+    status ENUM('pending', 'created', 'committed', 'orphaned', 'resolved') NOT NULL DEFAULT 'pending',
+    last_error TEXT,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+
+    INDEX idx_provider_order_intents_status (status, updated_at),
+    INDEX idx_provider_order_intents_index (address_index)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
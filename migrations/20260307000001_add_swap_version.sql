@@ -0,0 +1,14 @@
+-- ============================================================================
+-- Migration: Swap version column
+-- Created: 2026-03-07
+-- Description: Optimistic concurrency for swap status transitions. Every
+--              status write bumps version and is conditioned on the version
+--              the writer last read.
+-- ============================================================================
+
+SET @sql = IFNULL((SELECT 'SELECT 1' FROM INFORMATION_SCHEMA.COLUMNS
+    WHERE table_name = 'swaps' AND column_name = 'version' AND table_schema = DATABASE()),
+    'ALTER TABLE swaps ADD COLUMN version INT UNSIGNED NOT NULL DEFAULT 0 AFTER status');
+PREPARE stmt FROM @sql;
+EXECUTE stmt;
+DEALLOCATE PREPARE stmt;
@@ -0,0 +1,18 @@
+-- ============================================================================
+-- Migration: Encrypted PII columns
+-- Created: 2026-03-08
+-- Description: Room for field-level ciphertext in users.email and the
+--              recipient address columns, plus a blind index so users can
+--              still be looked up by email once it is encrypted.
+-- ============================================================================
+
+ALTER TABLE users MODIFY COLUMN email VARCHAR(512) NOT NULL;
+ALTER TABLE swaps MODIFY COLUMN recipient_address VARCHAR(512) NOT NULL;
+ALTER TABLE swap_address_info MODIFY COLUMN recipient_address VARCHAR(512) NOT NULL;
+
+SET @sql = IFNULL((SELECT 'SELECT 1' FROM INFORMATION_SCHEMA.COLUMNS
+    WHERE table_name = 'users' AND column_name = 'email_hash' AND table_schema = DATABASE()),
+    'ALTER TABLE users ADD COLUMN email_hash CHAR(64) NULL AFTER email, ADD UNIQUE INDEX idx_users_email_hash (email_hash)');
+PREPARE stmt FROM @sql;
+EXECUTE stmt;
+DEALLOCATE PREPARE stmt;
@@ -0,0 +1,29 @@
+-- ============================================================================
+-- Migration: Export jobs
+-- Created: 2026-03-09
+-- Description: Asynchronous CSV exports. A worker writes the file to object
+--              storage; clients poll the job and download through a
+--              short-lived signed URL.
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS export_jobs (
+    id VARCHAR(36) PRIMARY KEY,
+    user_id VARCHAR(36) NOT NULL,
+    kind ENUM('swaps', 'ledger') NOT NULL,
+    status ENUM('pending', 'processing', 'completed', 'failed', 'expired') NOT NULL DEFAULT 'pending',
+    range_from TIMESTAMP NULL,
+    range_to TIMESTAMP NULL,
+    object_key VARCHAR(255),
+    row_count INT UNSIGNED,
+    error TEXT,
+    attempts INT UNSIGNED NOT NULL DEFAULT 0,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    started_at TIMESTAMP NULL,
+    completed_at TIMESTAMP NULL,
+    expires_at TIMESTAMP NULL,
+
+    INDEX idx_export_jobs_user (user_id, created_at),
+    INDEX idx_export_jobs_status (status, created_at),
+
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
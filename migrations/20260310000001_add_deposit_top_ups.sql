@@ -0,0 +1,28 @@
+-- ============================================================================
+-- Migration: Deposit top-ups
+-- Created: 2026-03-10
+-- Description: Underpaid swaps can be completed with further deposits to the
+--              same address. Each balance increase is recorded as a receipt
+--              and swap_address_info keeps the running total.
+-- ============================================================================
+
+SET @sql = IFNULL((SELECT 'SELECT 1' FROM INFORMATION_SCHEMA.COLUMNS
+    WHERE table_name = 'swap_address_info' AND column_name = 'deposit_count' AND table_schema = DATABASE()),
+    'ALTER TABLE swap_address_info ADD COLUMN deposit_count INT UNSIGNED NOT NULL DEFAULT 0 AFTER actual_received');
+PREPARE stmt FROM @sql;
+EXECUTE stmt;
+DEALLOCATE PREPARE stmt;
+
+CREATE TABLE IF NOT EXISTS deposit_receipts (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    swap_id VARCHAR(36) NOT NULL,
+    -- Increase in address balance since the previous receipt
+    amount DOUBLE NOT NULL,
+    -- Address balance after this receipt
+    cumulative DOUBLE NOT NULL,
+    detected_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    INDEX idx_deposit_receipts_swap (swap_id, detected_at),
+
+    FOREIGN KEY (swap_id) REFERENCES swaps(id) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
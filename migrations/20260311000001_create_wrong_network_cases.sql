@@ -0,0 +1,36 @@
+-- ============================================================================
+-- Migration: Wrong-network deposit cases
+-- Created: 2026-03-11
+-- Description: EVM deposit addresses are valid on every EVM chain, so users
+--              sometimes send to the right address on the wrong network
+--              (BEP-20 instead of ERC-20). The listener records each such
+--              deposit as a case; admins recover the funds with a payout on
+--              the chain where they landed, or dismiss the case.
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS wrong_network_cases (
+    id VARCHAR(36) PRIMARY KEY,
+    swap_id VARCHAR(36) NOT NULL,
+    address VARCHAR(255) NOT NULL,
+    address_index INT UNSIGNED NOT NULL,
+    expected_network VARCHAR(50) NOT NULL,
+    -- Canonical listener chain name where the funds were found
+    detected_network VARCHAR(50) NOT NULL,
+    -- Native balance on detected_network when last seen
+    amount DOUBLE NOT NULL,
+    status ENUM('open', 'recovering', 'recovered', 'failed', 'dismissed') NOT NULL DEFAULT 'open',
+    recovery_address VARCHAR(255),
+    recovery_tx_hash VARCHAR(255),
+    recovered_amount DOUBLE,
+    error TEXT,
+    dismiss_reason TEXT,
+    resolved_by VARCHAR(36),
+    resolved_at TIMESTAMP NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+
+    UNIQUE KEY uq_wrong_network_cases_swap_network (swap_id, detected_network),
+    INDEX idx_wrong_network_cases_status (status, created_at),
+
+    FOREIGN KEY (swap_id) REFERENCES swaps(id) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
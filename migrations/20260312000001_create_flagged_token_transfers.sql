@@ -0,0 +1,30 @@
+-- ============================================================================
+-- Migration: Flagged token transfers
+-- Created: 2026-03-12
+-- Description: Token deposits are only credited from the canonical contract
+--              for the expected token. Transfers from any other contract
+--              (look-alike "USDT", airdrop spam) or of a different token are
+--              recorded here instead, once per log.
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS flagged_token_transfers (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    swap_id VARCHAR(36) NOT NULL,
+    network VARCHAR(50) NOT NULL,
+    contract_address VARCHAR(42) NOT NULL,
+    from_address VARCHAR(42) NOT NULL,
+    to_address VARCHAR(42) NOT NULL,
+    -- Raw token units as a decimal string; spam tokens often exceed DECIMAL range
+    amount_raw VARCHAR(80) NOT NULL,
+    tx_hash VARCHAR(66) NOT NULL,
+    log_index INT UNSIGNED NOT NULL,
+    block_number BIGINT UNSIGNED NOT NULL,
+    reason ENUM('non_canonical', 'unexpected_token') NOT NULL,
+    detected_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    UNIQUE KEY uq_flagged_token_transfers_log (network, tx_hash, log_index),
+    INDEX idx_flagged_token_transfers_swap (swap_id),
+    INDEX idx_flagged_token_transfers_contract (network, contract_address),
+
+    FOREIGN KEY (swap_id) REFERENCES swaps(id) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
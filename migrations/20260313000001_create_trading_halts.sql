@@ -0,0 +1,27 @@
+-- ============================================================================
+-- Migration: Trading halts (kill-switch)
+-- Created: 2026-03-13
+-- Description: Operator and automatic halts that block rates and swap
+--              creation. `currency` halts a coin on one network, `pair`
+--              halts both directions between two legs, and `chain` halts
+--              every currency settling on a chain (set when RPC health for
+--              the chain drops). Unused columns hold '' so the unique key
+--              applies to every scope.
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS trading_halts (
+    id VARCHAR(36) PRIMARY KEY,
+    scope ENUM('currency', 'pair', 'chain') NOT NULL,
+    currency VARCHAR(20) NOT NULL DEFAULT '',
+    network VARCHAR(50) NOT NULL DEFAULT '',
+    to_currency VARCHAR(20) NOT NULL DEFAULT '',
+    to_network VARCHAR(50) NOT NULL DEFAULT '',
+    reason VARCHAR(255),
+    source ENUM('manual', 'rpc_health') NOT NULL DEFAULT 'manual',
+    -- Admin who set the halt; NULL for automatic halts
+    created_by VARCHAR(36),
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    UNIQUE KEY uq_trading_halts_target (scope, currency, network, to_currency, to_network),
+    INDEX idx_trading_halts_source (source)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
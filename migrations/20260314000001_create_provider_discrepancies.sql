@@ -0,0 +1,44 @@
+-- ============================================================================
+-- Migration: Provider reconciliation
+-- Created: 2026-03-14
+-- Description: A nightly job compares recent swaps against the provider's
+--              view of each order (status, deposit amount, existence) and
+--              records drift for admin review. One row per swap and kind;
+--              later runs refresh the values, and a resolved row reopens if
+--              the drift is still there.
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS provider_reconciliation_runs (
+    id VARCHAR(36) PRIMARY KEY,
+    started_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    finished_at TIMESTAMP NULL,
+    swaps_checked INT UNSIGNED NOT NULL DEFAULT 0,
+    discrepancies INT UNSIGNED NOT NULL DEFAULT 0,
+    -- Orders the provider could not be asked about (network/API errors)
+    errors INT UNSIGNED NOT NULL DEFAULT 0,
+
+    INDEX idx_provider_reconciliation_runs_started (started_at)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+CREATE TABLE IF NOT EXISTS provider_discrepancies (
+    id VARCHAR(36) PRIMARY KEY,
+    run_id VARCHAR(36) NOT NULL,
+    swap_id VARCHAR(36) NOT NULL,
+    provider_id VARCHAR(50) NOT NULL,
+    provider_swap_id VARCHAR(100) NOT NULL,
+    kind ENUM('status_mismatch', 'amount_mismatch', 'missing_at_provider') NOT NULL,
+    our_value VARCHAR(100) NOT NULL,
+    provider_value VARCHAR(100) NOT NULL,
+    status ENUM('open', 'resolved', 'dismissed') NOT NULL DEFAULT 'open',
+    note VARCHAR(500),
+    reviewed_by VARCHAR(36),
+    reviewed_at TIMESTAMP NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+
+    UNIQUE KEY uq_provider_discrepancies_swap_kind (swap_id, kind),
+    INDEX idx_provider_discrepancies_status (status, created_at),
+
+    FOREIGN KEY (run_id) REFERENCES provider_reconciliation_runs(id) ON DELETE CASCADE,
+    FOREIGN KEY (swap_id) REFERENCES swaps(id) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
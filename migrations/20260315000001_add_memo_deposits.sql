@@ -0,0 +1,59 @@
+-- ============================================================================
+-- Migration: Memo-matched deposits
+-- Created: 2026-03-15
+-- Description: On XRP, XLM, HBAR and ATOM a swap can settle into one shared
+--              hot address, told apart by a per-swap memo/destination tag.
+--              Memos are unique per chain (checked when the provider order is
+--              recorded). Every transfer seen at a hot address is logged once
+--              in memo_deposits; transfers that cannot be tied to exactly one
+--              open swap are quarantined there for an operator.
+-- ============================================================================
+
+SET @sql = IFNULL((SELECT 'SELECT 1' FROM INFORMATION_SCHEMA.COLUMNS
+    WHERE table_name = 'provider_order_intents' AND column_name = 'our_memo' AND table_schema = DATABASE()),
+    'ALTER TABLE provider_order_intents
+        ADD COLUMN memo_network VARCHAR(20) NULL AFTER address_index,
+        ADD COLUMN our_memo VARCHAR(64) NULL AFTER memo_network,
+        ADD UNIQUE KEY uq_provider_order_intents_memo (memo_network, our_memo)');
+PREPARE stmt FROM @sql;
+EXECUTE stmt;
+DEALLOCATE PREPARE stmt;
+
+SET @sql = IFNULL((SELECT 'SELECT 1' FROM INFORMATION_SCHEMA.COLUMNS
+    WHERE table_name = 'swap_address_info' AND column_name = 'our_memo' AND table_schema = DATABASE()),
+    'ALTER TABLE swap_address_info
+        ADD COLUMN memo_network VARCHAR(20) NULL AFTER our_address,
+        ADD COLUMN our_memo VARCHAR(64) NULL AFTER memo_network,
+        ADD UNIQUE KEY uq_swap_address_info_memo (memo_network, our_memo)');
+PREPARE stmt FROM @sql;
+EXECUTE stmt;
+DEALLOCATE PREPARE stmt;
+
+CREATE TABLE IF NOT EXISTS memo_deposits (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    network VARCHAR(20) NOT NULL,
+    tx_hash VARCHAR(100) NOT NULL,
+    memo VARCHAR(64),
+    amount DOUBLE NOT NULL,
+    -- Set when credited, or when an operator releases a quarantined deposit
+    swap_id VARCHAR(36),
+    status ENUM('credited', 'quarantined', 'released', 'dismissed') NOT NULL,
+    reason ENUM('missing_memo', 'unknown_memo', 'memo_collision', 'swap_closed'),
+    note VARCHAR(500),
+    resolved_by VARCHAR(36),
+    resolved_at TIMESTAMP NULL,
+    detected_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    UNIQUE KEY uq_memo_deposits_tx (network, tx_hash),
+    INDEX idx_memo_deposits_status (status, detected_at),
+    INDEX idx_memo_deposits_swap (swap_id),
+
+    FOREIGN KEY (swap_id) REFERENCES swaps(id) ON DELETE SET NULL
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+-- Position in each hot address's transaction history already processed
+CREATE TABLE IF NOT EXISTS memo_scan_cursors (
+    network VARCHAR(20) PRIMARY KEY,
+    cursor_value VARCHAR(255) NOT NULL,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
@@ -0,0 +1,46 @@
+-- ============================================================================
+-- Migration: OAuth login
+-- Created: 2026-03-16
+-- Description: External identities (Google, Apple) linked to users, keyed by
+--              the provider's stable subject id, and the short-lived state of
+--              authorization-code logins in progress (PKCE verifier, and the
+--              pending user while a 2FA code is awaited).
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS oauth_identities (
+    id VARCHAR(36) PRIMARY KEY,
+    user_id VARCHAR(36) NOT NULL,
+    provider ENUM('google', 'apple') NOT NULL,
+    subject VARCHAR(255) NOT NULL,
+    -- Email the provider reported at link time (Apple only sends it once)
+    email VARCHAR(255),
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_login_at TIMESTAMP NULL,
+
+    UNIQUE KEY uq_oauth_identities_subject (provider, subject),
+    UNIQUE KEY uq_oauth_identities_user_provider (user_id, provider),
+
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+CREATE TABLE IF NOT EXISTS oauth_login_states (
+    -- SHA-256 of the `state` parameter, or of the 2FA token for a login
+    -- waiting on a code
+    state_hash CHAR(64) PRIMARY KEY,
+    provider ENUM('google', 'apple') NOT NULL,
+    code_verifier VARCHAR(128) NOT NULL DEFAULT '',
+    redirect_uri VARCHAR(500) NOT NULL DEFAULT '',
+    nonce VARCHAR(64) NOT NULL DEFAULT '',
+    -- Set when an authenticated user is linking another login method
+    link_user_id VARCHAR(36),
+    -- Set once the provider step succeeded but the user still owes a 2FA code
+    pending_user_id VARCHAR(36),
+    attempts INT NOT NULL DEFAULT 0,
+    expires_at TIMESTAMP NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    INDEX idx_oauth_login_states_expires (expires_at),
+
+    FOREIGN KEY (link_user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (pending_user_id) REFERENCES users(id) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
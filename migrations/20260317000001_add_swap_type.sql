@@ -0,0 +1,16 @@
+-- ============================================================================
+-- Migration: Swap type
+-- Created: 2026-03-17
+-- Description: Labels swaps as a regular exchange or a same-asset cross-chain
+--              bridge (USDT ERC20 -> USDT TRC20) for analytics and filtering.
+--              Existing swaps are backfilled from their currencies/networks.
+-- ============================================================================
+
+ALTER TABLE swaps
+    ADD COLUMN swap_type ENUM('exchange', 'bridge') NOT NULL DEFAULT 'exchange' AFTER rate_type,
+    ADD INDEX idx_swaps_swap_type (swap_type, created_at);
+
+UPDATE swaps
+SET swap_type = 'bridge'
+WHERE LOWER(from_currency) = LOWER(to_currency)
+  AND LOWER(from_network) <> LOWER(to_network);
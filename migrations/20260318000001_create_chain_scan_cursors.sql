@@ -0,0 +1,30 @@
+-- ============================================================================
+-- Migration: Deposit catch-up after downtime
+-- Created: 2026-03-18
+-- Description: The blockchain listener saves the head block of each EVM chain
+--              after every completed check. On startup it re-scans the blocks
+--              mined since then for swaps that were open at the time, and
+--              logs each catch-up so recovered gaps can be audited.
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS chain_scan_cursors (
+    network VARCHAR(20) PRIMARY KEY,
+    last_block BIGINT UNSIGNED NOT NULL,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+CREATE TABLE IF NOT EXISTS chain_catch_up_runs (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    network VARCHAR(20) NOT NULL,
+    from_block BIGINT UNSIGNED NOT NULL,
+    to_block BIGINT UNSIGNED NOT NULL,
+    -- Blocks behind at startup; more than to_block - from_block + 1 when truncated
+    gap_blocks BIGINT UNSIGNED NOT NULL,
+    truncated BOOLEAN NOT NULL DEFAULT FALSE,
+    downtime_secs BIGINT NOT NULL,
+    swaps_checked INT UNSIGNED NOT NULL,
+    deposits_recovered INT UNSIGNED NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    INDEX idx_chain_catch_up_runs_network (network, created_at)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
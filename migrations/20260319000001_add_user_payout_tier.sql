@@ -0,0 +1,14 @@
+-- ============================================================================
+-- Migration: Payout priority lanes
+-- Created: 2026-03-19
+-- Description: Per-user payout tier. Swaps of 'vip' and 'partner' users are
+--              paid out in the high-priority lane, ahead of standard payouts
+--              and with concurrency reserved for them. Set by operators.
+-- ============================================================================
+
+SET @sql = IFNULL((SELECT 'SELECT 1' FROM INFORMATION_SCHEMA.COLUMNS
+    WHERE table_name = 'users' AND column_name = 'payout_tier' AND table_schema = DATABASE()),
+    'ALTER TABLE users ADD COLUMN payout_tier ENUM(''standard'', ''vip'', ''partner'') NOT NULL DEFAULT ''standard'' AFTER custody_enabled');
+PREPARE stmt FROM @sql;
+EXECUTE stmt;
+DEALLOCATE PREPARE stmt;
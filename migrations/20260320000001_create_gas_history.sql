@@ -0,0 +1,26 @@
+-- ============================================================================
+-- Migration: Gas price history
+-- Created: 2026-03-20
+-- Description: Every fresh gas estimate and the gas actually spent by each
+--              payout, per network. Feeds the admin gas analytics and the
+--              estimator's fallback when a chain's RPC is unavailable.
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS gas_history (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    network VARCHAR(20) NOT NULL,
+    kind ENUM('estimate', 'payout') NOT NULL,
+    tx_type VARCHAR(30) NOT NULL,
+    -- Wei for EVM, sat/vB for Bitcoin, lamports for Solana
+    gas_price BIGINT UNSIGNED NOT NULL,
+    gas_limit BIGINT UNSIGNED NOT NULL,
+    -- Payouts only: gas units (EVM) or vbytes (Bitcoin) the transaction used
+    gas_used BIGINT UNSIGNED NULL,
+    fee_native DOUBLE NOT NULL,
+    swap_id VARCHAR(36) NULL,
+    tx_hash VARCHAR(128) NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    INDEX idx_gas_history_network (network, kind, created_at),
+    INDEX idx_gas_history_created (created_at)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
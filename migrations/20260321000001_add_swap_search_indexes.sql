@@ -0,0 +1,49 @@
+-- ============================================================================
+-- Migration: Swap search indexes
+-- Created: 2026-03-21
+-- Description: Indexes behind GET /admin/swaps/search, which finds swaps by
+--              deposit/refund address or by deposit/payout transaction hash.
+--              Exact and prefix lookups both use them.
+-- ============================================================================
+
+SET @sql = IFNULL((SELECT 'SELECT 1' FROM INFORMATION_SCHEMA.STATISTICS
+    WHERE table_name = 'swaps' AND index_name = 'idx_swaps_deposit_address' AND table_schema = DATABASE()),
+    'CREATE INDEX idx_swaps_deposit_address ON swaps (deposit_address)');
+PREPARE stmt FROM @sql;
+EXECUTE stmt;
+DEALLOCATE PREPARE stmt;
+
+SET @sql = IFNULL((SELECT 'SELECT 1' FROM INFORMATION_SCHEMA.STATISTICS
+    WHERE table_name = 'swaps' AND index_name = 'idx_swaps_refund_address' AND table_schema = DATABASE()),
+    'CREATE INDEX idx_swaps_refund_address ON swaps (refund_address)');
+PREPARE stmt FROM @sql;
+EXECUTE stmt;
+DEALLOCATE PREPARE stmt;
+
+SET @sql = IFNULL((SELECT 'SELECT 1' FROM INFORMATION_SCHEMA.STATISTICS
+    WHERE table_name = 'swaps' AND index_name = 'idx_swaps_tx_hash_in' AND table_schema = DATABASE()),
+    'CREATE INDEX idx_swaps_tx_hash_in ON swaps (tx_hash_in)');
+PREPARE stmt FROM @sql;
+EXECUTE stmt;
+DEALLOCATE PREPARE stmt;
+
+SET @sql = IFNULL((SELECT 'SELECT 1' FROM INFORMATION_SCHEMA.STATISTICS
+    WHERE table_name = 'swaps' AND index_name = 'idx_swaps_tx_hash_out' AND table_schema = DATABASE()),
+    'CREATE INDEX idx_swaps_tx_hash_out ON swaps (tx_hash_out)');
+PREPARE stmt FROM @sql;
+EXECUTE stmt;
+DEALLOCATE PREPARE stmt;
+
+SET @sql = IFNULL((SELECT 'SELECT 1' FROM INFORMATION_SCHEMA.STATISTICS
+    WHERE table_name = 'swap_address_info' AND index_name = 'idx_swap_address_our_address' AND table_schema = DATABASE()),
+    'CREATE INDEX idx_swap_address_our_address ON swap_address_info (our_address)');
+PREPARE stmt FROM @sql;
+EXECUTE stmt;
+DEALLOCATE PREPARE stmt;
+
+SET @sql = IFNULL((SELECT 'SELECT 1' FROM INFORMATION_SCHEMA.STATISTICS
+    WHERE table_name = 'swap_address_info' AND index_name = 'idx_swap_address_payout_tx' AND table_schema = DATABASE()),
+    'CREATE INDEX idx_swap_address_payout_tx ON swap_address_info (payout_tx_hash)');
+PREPARE stmt FROM @sql;
+EXECUTE stmt;
+DEALLOCATE PREPARE stmt;
@@ -0,0 +1,39 @@
+-- ============================================================================
+-- Migration: Swap status history actors and timing
+-- Created: 2026-03-22
+-- Description: Every transition records who made it (user, provider,
+--              listener, monitor, payout, admin, system) with millisecond
+--              timestamps, so time spent in each stage can be measured.
+--              Also adds 'funds_received', which swaps gained in 20260217
+--              but the history table never did.
+-- ============================================================================
+
+ALTER TABLE swap_status_history MODIFY COLUMN status ENUM(
+    'waiting',
+    'confirming',
+    'exchanging',
+    'sending',
+    'funds_received',
+    'completed',
+    'failed',
+    'refunded',
+    'expired'
+) NOT NULL;
+
+ALTER TABLE swap_status_history
+    MODIFY COLUMN created_at TIMESTAMP(3) NOT NULL DEFAULT CURRENT_TIMESTAMP(3);
+
+SET @sql = IFNULL((SELECT 'SELECT 1' FROM INFORMATION_SCHEMA.COLUMNS
+    WHERE table_name = 'swap_status_history' AND column_name = 'actor' AND table_schema = DATABASE()),
+    'ALTER TABLE swap_status_history ADD COLUMN actor VARCHAR(20) NOT NULL DEFAULT ''system'' AFTER status');
+PREPARE stmt FROM @sql;
+EXECUTE stmt;
+DEALLOCATE PREPARE stmt;
+
+-- A swap's timeline is always read in order
+SET @sql = IFNULL((SELECT 'SELECT 1' FROM INFORMATION_SCHEMA.STATISTICS
+    WHERE table_name = 'swap_status_history' AND index_name = 'idx_swap_status_history_timeline' AND table_schema = DATABASE()),
+    'CREATE INDEX idx_swap_status_history_timeline ON swap_status_history (swap_id, created_at, id)');
+PREPARE stmt FROM @sql;
+EXECUTE stmt;
+DEALLOCATE PREPARE stmt;
@@ -0,0 +1,27 @@
+-- ============================================================================
+-- Migration: Exact amount columns
+-- Created: 2026-03-23
+-- Description: Swap, refund and payout amounts move to DECIMAL(36, 18), the
+--              precision already used by balances, schedules and orders.
+--              DECIMAL(20, 8) truncated EVM amounts past 8 places, and the
+--              DOUBLE payout columns stored float approximations of what
+--              was actually sent.
+-- ============================================================================
+
+ALTER TABLE swaps
+    MODIFY COLUMN amount DECIMAL(36, 18) NOT NULL,
+    MODIFY COLUMN estimated_receive DECIMAL(36, 18) NOT NULL,
+    MODIFY COLUMN actual_receive DECIMAL(36, 18),
+    MODIFY COLUMN network_fee DECIMAL(36, 18) NOT NULL DEFAULT 0,
+    MODIFY COLUMN provider_fee DECIMAL(36, 18) NOT NULL DEFAULT 0,
+    MODIFY COLUMN platform_fee DECIMAL(36, 18) NOT NULL DEFAULT 0,
+    MODIFY COLUMN total_fee DECIMAL(36, 18) NOT NULL DEFAULT 0;
+
+ALTER TABLE refunds
+    MODIFY COLUMN refund_amount DECIMAL(36, 18) NOT NULL,
+    MODIFY COLUMN total_fee DECIMAL(36, 18);
+
+ALTER TABLE swap_address_info
+    MODIFY COLUMN payout_amount DECIMAL(36, 18),
+    MODIFY COLUMN actual_received DECIMAL(36, 18) DEFAULT NULL,
+    MODIFY COLUMN commission_taken DECIMAL(36, 18) DEFAULT NULL;
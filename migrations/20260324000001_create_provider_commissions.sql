@@ -0,0 +1,41 @@
+-- ============================================================================
+-- Migration: Per-provider commissions and revenue ledger
+-- Created: 2026-03-24
+-- Description: Aggregators pay different referral rates, so each provider
+--              can carry its own commission rate (replacing the pricing
+--              strategy's tier rate for its quotes) and the share of volume
+--              it pays us. Completed swaps write what we earned to an
+--              append-only revenue ledger.
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS provider_commissions (
+    provider_id VARCHAR(50) PRIMARY KEY,
+    -- NULL keeps the strategy's tier rate
+    commission_rate DOUBLE NULL,
+    -- Fraction of each completed swap's deposit the provider pays us
+    revenue_share_rate DOUBLE NOT NULL DEFAULT 0,
+    updated_by VARCHAR(36) NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+
+    FOREIGN KEY (provider_id) REFERENCES providers(id) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+CREATE TABLE IF NOT EXISTS revenue_entries (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    swap_id VARCHAR(36) NOT NULL,
+    provider_id VARCHAR(50) NOT NULL,
+    -- platform_fee: our markup, in the destination asset
+    -- revenue_share: the provider's referral payment, in the deposit asset
+    entry_type ENUM('platform_fee', 'revenue_share') NOT NULL,
+    currency VARCHAR(20) NOT NULL,
+    network VARCHAR(50) NOT NULL,
+    amount DECIMAL(36, 18) NOT NULL,
+    -- Rate in effect when the entry was written
+    rate DOUBLE NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    UNIQUE KEY uk_revenue_entry (swap_id, entry_type),
+    INDEX idx_revenue_provider_time (provider_id, created_at),
+    INDEX idx_revenue_created (created_at)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
@@ -0,0 +1,32 @@
+-- ============================================================================
+-- Migration: Gas station top-ups
+-- Created: 2026-03-25
+-- Description: Deposit addresses that only receive ERC-20 tokens have no
+--              native coin for gas. The gas station funds them from the hot
+--              wallet before token transfers; each top-up is recorded here
+--              and booked against its swap as a gas_cost revenue entry.
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS gas_topups (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    -- Top-ups sent together from one hot wallet nonce read
+    batch_id VARCHAR(36) NOT NULL,
+    network VARCHAR(50) NOT NULL,
+    address VARCHAR(100) NOT NULL,
+    swap_id VARCHAR(36) NULL,
+    -- Native coin sent to the address
+    amount DECIMAL(36, 18) NOT NULL,
+    -- Gas the hot wallet paid to send it
+    network_fee DECIMAL(36, 18) NOT NULL,
+    gas_price BIGINT UNSIGNED NOT NULL,
+    tx_hash VARCHAR(100) NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    INDEX idx_gas_topups_swap (swap_id),
+    INDEX idx_gas_topups_batch (batch_id),
+    INDEX idx_gas_topups_created (created_at)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+-- Gas costs share the revenue ledger; rate is 0 for them
+ALTER TABLE revenue_entries
+    MODIFY COLUMN entry_type ENUM('platform_fee', 'revenue_share', 'gas_cost') NOT NULL;
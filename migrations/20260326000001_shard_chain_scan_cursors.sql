@@ -0,0 +1,15 @@
+-- ============================================================================
+-- Migration: Listener sharding
+-- Created: 2026-03-26
+-- Description: Pending-swap scanning is split across listener shards by
+--              CRC32 of the deposit address. Each shard keeps its own scan
+--              cursor per chain, so a slow or failed-over shard catches up
+--              on its own addresses without holding back the others.
+--              Existing cursors belong to the unsharded listener (0 of 1).
+-- ============================================================================
+
+ALTER TABLE chain_scan_cursors
+    ADD COLUMN shard_count INT UNSIGNED NOT NULL DEFAULT 1 AFTER network,
+    ADD COLUMN shard_index INT UNSIGNED NOT NULL DEFAULT 0 AFTER shard_count,
+    DROP PRIMARY KEY,
+    ADD PRIMARY KEY (network, shard_count, shard_index);
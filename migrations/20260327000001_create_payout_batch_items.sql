@@ -0,0 +1,31 @@
+-- ============================================================================
+-- Migration: Batched EVM payouts
+-- Created: 2026-03-27
+-- Description: On chains with a Disperse-style contract configured, native
+--              payouts collected over a short window are sent from the hot
+--              wallet as one contract call. Each recipient of a batch gets a
+--              row here with its amount and even share of the batch's gas.
+--              The hot wallet fronts the payout, so the row also names the
+--              deposit address still holding the swap's funds.
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS payout_batch_items (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    batch_id VARCHAR(36) NOT NULL,
+    network VARCHAR(50) NOT NULL,
+    contract_address VARCHAR(100) NOT NULL,
+    swap_id VARCHAR(36) NOT NULL,
+    deposit_address VARCHAR(100) NOT NULL,
+    recipient VARCHAR(100) NOT NULL,
+    -- Native coin sent to the recipient
+    amount DECIMAL(36, 18) NOT NULL,
+    -- This recipient's share of the batch's gas limit at its gas price
+    network_fee DECIMAL(36, 18) NOT NULL,
+    gas_price BIGINT UNSIGNED NOT NULL,
+    tx_hash VARCHAR(100) NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    INDEX idx_payout_batch_items_swap (swap_id),
+    INDEX idx_payout_batch_items_batch (batch_id),
+    INDEX idx_payout_batch_items_created (created_at)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
@@ -0,0 +1,17 @@
+-- ============================================================================
+-- Migration: Recorded HD derivation paths
+-- Created: 2026-03-28
+-- Description: Bitcoin and EVM derivation paths are configurable per
+--              deployment (HD_ACCOUNT, HD_CHANGE, HD_PATH_*). The path in
+--              use is recorded here on startup, and a changed path is
+--              refused while swap addresses derived on the old one exist.
+--              No row means the built-in BIP44 default.
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS hd_derivation_paths (
+    -- 'bitcoin' or 'evm'
+    chain VARCHAR(20) PRIMARY KEY,
+    -- Resolved template, e.g. m/44'/60'/0'/0/{index}
+    path_template VARCHAR(255) NOT NULL,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
@@ -0,0 +1,20 @@
+-- ============================================================================
+-- Migration: Per-user API usage
+-- Created: 2026-03-29
+-- Description: Daily request, error and rate-limit counts per account, as
+--              counted in Redis and flushed here by the usage flusher. Rows
+--              hold running totals for the day and are overwritten on each
+--              flush. Backs GET /account/usage.
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS api_usage_daily (
+    user_id VARCHAR(36) NOT NULL,
+    day DATE NOT NULL,
+    requests BIGINT UNSIGNED NOT NULL DEFAULT 0,
+    -- 4xx and 5xx responses other than 429
+    errors BIGINT UNSIGNED NOT NULL DEFAULT 0,
+    rate_limited BIGINT UNSIGNED NOT NULL DEFAULT 0,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    PRIMARY KEY (user_id, day),
+    INDEX idx_api_usage_day (day)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
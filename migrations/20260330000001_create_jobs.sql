@@ -0,0 +1,27 @@
+-- ============================================================================
+-- Migration: Background jobs
+-- Created: 2026-03-30
+-- Description: One status record for every background operation a user or
+--              admin waits on (exports, reconciliation runs), polled through
+--              GET /jobs/{id}. A job shares its id with the record it works
+--              on. Jobs without a user are system jobs only admins can see.
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS jobs (
+    id VARCHAR(36) PRIMARY KEY,
+    user_id VARCHAR(36) NULL,
+    kind ENUM('export', 'reconciliation') NOT NULL,
+    status ENUM('queued', 'running', 'succeeded', 'failed') NOT NULL DEFAULT 'queued',
+    -- Percent complete, 0-100
+    progress TINYINT UNSIGNED NOT NULL DEFAULT 0,
+    error TEXT,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    started_at TIMESTAMP NULL,
+    finished_at TIMESTAMP NULL,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+
+    INDEX idx_jobs_user (user_id, created_at),
+    INDEX idx_jobs_status (status, created_at),
+
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
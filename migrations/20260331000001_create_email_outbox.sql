@@ -0,0 +1,36 @@
+-- ============================================================================
+-- Migration: Email outbox and suppression list
+-- Created: 2026-03-31
+-- Description: Outbound email is queued in email_outbox and delivered by a
+--              worker with retries and transport failover. Addresses that
+--              hard-bounce or complain are kept in email_suppressions, keyed
+--              by the SHA-256 of the normalized address, and never mailed
+--              again.
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS email_outbox (
+    id VARCHAR(36) PRIMARY KEY,
+    -- Encrypted at rest when PII encryption is enabled
+    recipient TEXT NOT NULL,
+    recipient_hash CHAR(64) NOT NULL,
+    subject VARCHAR(255) NOT NULL,
+    -- Cleared once the message is sent
+    body TEXT NOT NULL,
+    status ENUM('pending', 'sending', 'sent', 'failed', 'suppressed') NOT NULL DEFAULT 'pending',
+    attempts INT UNSIGNED NOT NULL DEFAULT 0,
+    next_attempt_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_error TEXT,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    claimed_at TIMESTAMP NULL,
+    sent_at TIMESTAMP NULL,
+
+    INDEX idx_email_outbox_due (status, next_attempt_at),
+    INDEX idx_email_outbox_recipient (recipient_hash, status)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+CREATE TABLE IF NOT EXISTS email_suppressions (
+    email_hash CHAR(64) PRIMARY KEY,
+    reason ENUM('bounce', 'complaint') NOT NULL,
+    detail VARCHAR(255) NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
@@ -0,0 +1,13 @@
+-- ============================================================================
+-- Migration: Integrator references on swaps
+-- Created: 2026-04-01
+-- Description: Partners can attach their own order id (client_reference) and
+--              a small JSON object (metadata) when creating a swap. Both are
+--              returned in swap history and webhook payloads; history can be
+--              filtered by client_reference.
+-- ============================================================================
+
+ALTER TABLE swaps
+    ADD COLUMN client_reference VARCHAR(128) NULL AFTER user_id,
+    ADD COLUMN metadata JSON NULL AFTER client_reference,
+    ADD INDEX idx_swaps_user_client_reference (user_id, client_reference);
@@ -0,0 +1,12 @@
+-- ============================================================================
+-- Migration: Anti-phishing codes
+-- Created: 2026-04-02
+-- Description: A code each user chooses (or has generated) that is shown at
+--              the top of every email we send them, so mail without it can
+--              be recognised as an imitation. Encrypted at rest like other
+--              PII columns.
+-- ============================================================================
+
+ALTER TABLE users
+    ADD COLUMN anti_phishing_code VARCHAR(255) NULL,
+    ADD COLUMN anti_phishing_updated_at TIMESTAMP NULL;
@@ -0,0 +1,17 @@
+-- ============================================================================
+-- Migration: Network status samples
+-- Created: 2026-04-03
+-- Description: Each chain's severity, sampled once a minute, for the uptime
+--              figures on GET /status/public. Keyed by chain and minute so
+--              replicas sampling the same minute write one row. Samples are
+--              kept for 30 days.
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS network_status_samples (
+    network VARCHAR(50) NOT NULL,
+    sampled_at TIMESTAMP NOT NULL,
+    severity ENUM('operational', 'degraded', 'down') NOT NULL,
+
+    PRIMARY KEY (network, sampled_at),
+    INDEX idx_network_status_samples_time (sampled_at)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
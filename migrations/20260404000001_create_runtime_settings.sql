@@ -0,0 +1,15 @@
+-- ============================================================================
+-- Migration: Runtime settings
+-- Created: 2026-04-04
+-- Description: Operator overrides of settings that apply without a restart
+--              (rate limits, swap monitor polling). They take precedence over
+--              the environment and RUNTIME_CONFIG_FILE and are re-read every
+--              RUNTIME_CONFIG_RELOAD_SECS; GET /admin/config shows the
+--              effective values.
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS runtime_settings (
+    setting_key VARCHAR(100) NOT NULL PRIMARY KEY,
+    setting_value VARCHAR(255) NOT NULL,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
@@ -0,0 +1,11 @@
+-- ============================================================================
+-- Migration: Webhook payload versions
+-- Created: 2026-04-05
+-- Description: Payload version each webhook subscription receives. NULL keeps
+--              the original unversioned payloads; a version receives the
+--              typed events published under GET /webhooks/event-types
+--              (e.g. swap.v1.completed).
+-- ============================================================================
+
+ALTER TABLE webhooks
+    ADD COLUMN payload_version INT UNSIGNED NULL AFTER events;
@@ -0,0 +1,60 @@
+-- ============================================================================
+-- Migration: Promotional campaigns
+-- Created: 2026-04-06
+-- Description: Campaigns that waive some or all of the platform fee on
+--              matching quotes (pair, provider, date range, user segment).
+--              Every swap created under a campaign is recorded as a
+--              redemption, and the fee given up is booked in the revenue
+--              ledger as a promotion_discount entry.
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS promotions (
+    id VARCHAR(36) PRIMARY KEY,
+    name VARCHAR(100) NOT NULL,
+    description TEXT NULL,
+    -- NULL matches any currency, network or provider
+    from_currency VARCHAR(20) NULL,
+    from_network VARCHAR(50) NULL,
+    to_currency VARCHAR(20) NULL,
+    to_network VARCHAR(50) NULL,
+    provider_id VARCHAR(50) NULL,
+    segment ENUM('all', 'anonymous', 'registered', 'new_users', 'vip', 'partner') NOT NULL DEFAULT 'all',
+    -- Fraction of the platform fee waived; 1 makes the swap fee-free
+    fee_discount DOUBLE NOT NULL,
+    -- Higher wins when several campaigns match a quote
+    priority INT NOT NULL DEFAULT 0,
+    starts_at TIMESTAMP NOT NULL,
+    ends_at TIMESTAMP NOT NULL,
+    max_uses INT UNSIGNED NULL,
+    max_uses_per_user INT UNSIGNED NULL,
+    uses INT UNSIGNED NOT NULL DEFAULT 0,
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_by VARCHAR(36) NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+
+    INDEX idx_promotions_window (is_active, starts_at, ends_at)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+CREATE TABLE IF NOT EXISTS promotion_redemptions (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    promotion_id VARCHAR(36) NOT NULL,
+    swap_id VARCHAR(36) NOT NULL,
+    user_id VARCHAR(36) NULL,
+    -- The fee as it would have been charged and as it was, in the
+    -- destination asset
+    currency VARCHAR(20) NOT NULL,
+    network VARCHAR(50) NOT NULL,
+    standard_fee DECIMAL(36, 18) NOT NULL,
+    charged_fee DECIMAL(36, 18) NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    UNIQUE KEY uk_promotion_redemptions_swap (swap_id),
+    INDEX idx_promotion_redemptions_promotion (promotion_id, user_id),
+    FOREIGN KEY (promotion_id) REFERENCES promotions(id),
+    FOREIGN KEY (swap_id) REFERENCES swaps(id) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+-- Foregone fees share the revenue ledger; like gas costs they are not income
+ALTER TABLE revenue_entries
+    MODIFY COLUMN entry_type ENUM('platform_fee', 'revenue_share', 'gas_cost', 'promotion_discount') NOT NULL;
@@ -0,0 +1,30 @@
+-- ============================================================================
+-- Migration: Create pair liquidity matrix
+-- Created: 2026-04-07
+-- Description: Per-provider limits and rate types for each trading pair,
+--              refreshed in the background from provider quotes and served
+--              by GET /swap/pairs/matrix
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS pair_liquidity (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    trading_pair_id BIGINT NOT NULL,
+    provider_id VARCHAR(50) NOT NULL,
+    -- NULL when the provider did not report a bound
+    min_amount DOUBLE DEFAULT NULL,
+    max_amount DOUBLE DEFAULT NULL,
+    fixed_rate BOOLEAN NOT NULL DEFAULT FALSE,
+    floating_rate BOOLEAN NOT NULL DEFAULT TRUE,
+    refreshed_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    UNIQUE KEY uk_pair_liquidity (trading_pair_id, provider_id),
+    INDEX idx_pair_liquidity_refreshed (refreshed_at),
+
+    FOREIGN KEY (trading_pair_id) REFERENCES trading_pairs(id) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+-- When each pair was last probed, so the refresher walks stalest first even
+-- when a probe found no providers
+ALTER TABLE trading_pairs
+ADD COLUMN liquidity_refreshed_at TIMESTAMP NULL AFTER is_active,
+ADD INDEX idx_pairs_liquidity_refreshed (liquidity_refreshed_at);
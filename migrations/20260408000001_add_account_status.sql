@@ -0,0 +1,49 @@
+-- ============================================================================
+-- Migration: Account suspensions and bans
+-- Created: 2026-04-08
+-- Description: Per-user account status. Suspended accounts are refused
+--              until suspended_until (or until lifted when it is NULL);
+--              banned accounts for good. Every change, by an operator or
+--              by the risk engine, is kept in account_status_events. Risk
+--              signals (chargebacks, fraud reports) are recorded per user
+--              and their weighted sum over a window triggers automatic
+--              suspensions and bans.
+-- ============================================================================
+
+ALTER TABLE users
+    ADD COLUMN account_status ENUM('active', 'suspended', 'banned') NOT NULL DEFAULT 'active',
+    -- Shown to the user with the refusal
+    ADD COLUMN status_reason VARCHAR(500) NULL,
+    ADD COLUMN suspended_until TIMESTAMP NULL;
+
+CREATE TABLE IF NOT EXISTS account_status_events (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    user_id VARCHAR(36) NOT NULL,
+    status ENUM('active', 'suspended', 'banned') NOT NULL,
+    reason VARCHAR(500) NULL,
+    suspended_until TIMESTAMP NULL,
+    source ENUM('admin', 'risk') NOT NULL,
+    -- Admin who made the change; NULL for the risk engine
+    actor_id VARCHAR(36) NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    INDEX idx_account_status_events_user (user_id, created_at),
+
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+CREATE TABLE IF NOT EXISTS risk_signals (
+    id BIGINT PRIMARY KEY AUTO_INCREMENT,
+    user_id VARCHAR(36) NOT NULL,
+    kind ENUM('chargeback', 'fraud_report', 'stolen_funds') NOT NULL,
+    -- Weight at the time of recording, so policy changes don't rewrite history
+    weight INT UNSIGNED NOT NULL,
+    swap_id VARCHAR(36) NULL,
+    detail VARCHAR(500) NULL,
+    reported_by VARCHAR(36) NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    INDEX idx_risk_signals_user (user_id, created_at),
+
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
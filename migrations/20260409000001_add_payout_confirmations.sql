@@ -0,0 +1,23 @@
+-- ============================================================================
+-- Migration: Payout confirmation tracking
+-- Created: 2026-04-09
+-- Description: Broadcast payouts are followed on chain until final.
+--              confirmation_status moves pending -> confirmed -> finalized,
+--              or to dropped (the network forgot the transaction) or failed
+--              (it was mined but reverted). confirmed_at is now set when
+--              the transaction is first seen in a block rather than at
+--              broadcast.
+-- ============================================================================
+
+ALTER TABLE swap_address_info
+    ADD COLUMN confirmation_status ENUM('pending', 'confirmed', 'finalized', 'dropped', 'failed') NULL,
+    -- Depth when last checked
+    ADD COLUMN confirmations INT UNSIGNED NULL,
+    ADD COLUMN finalized_at TIMESTAMP NULL,
+    ADD COLUMN last_tracked_at TIMESTAMP NULL,
+    ADD INDEX idx_swap_address_info_confirmation (confirmation_status, last_tracked_at);
+
+-- Payouts broadcast before tracking existed are taken as final
+UPDATE swap_address_info
+SET confirmation_status = 'finalized'
+WHERE payout_tx_hash IS NOT NULL;
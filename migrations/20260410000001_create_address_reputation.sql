@@ -0,0 +1,53 @@
+-- ============================================================================
+-- Migration: Address reputation
+-- Created: 2026-04-10
+-- Description: Recipient addresses seen on swaps, keyed by a hash of the
+--              network and normalized address, with the accounts that used each one.
+--              An address paid out to by many unrelated accounts is a
+--              common sign of fraud (mules, drainers, cash-out wallets): it
+--              is scored, flagged for admin review past one threshold, and
+--              past another every account using it gets an address_reuse
+--              risk signal.
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS address_reputation (
+    -- SHA-256 of "network:normalized address"
+    address_hash CHAR(64) PRIMARY KEY,
+    -- Sealed like other recipient addresses
+    address VARCHAR(512) NOT NULL,
+    network VARCHAR(50) NOT NULL,
+    -- Distinct signed-in accounts that used it; guest swaps only count as swaps
+    account_count INT UNSIGNED NOT NULL DEFAULT 0,
+    swap_count INT UNSIGNED NOT NULL DEFAULT 0,
+    -- Linked accounts currently suspended or banned
+    restricted_accounts INT UNSIGNED NOT NULL DEFAULT 0,
+    score INT UNSIGNED NOT NULL DEFAULT 0,
+    status ENUM('normal', 'flagged', 'cleared', 'confirmed') NOT NULL DEFAULT 'normal',
+    flagged_at TIMESTAMP NULL,
+    review_note VARCHAR(500) NULL,
+    reviewed_by VARCHAR(36) NULL,
+    reviewed_at TIMESTAMP NULL,
+    first_seen_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_seen_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    INDEX idx_address_reputation_review (status, score)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+CREATE TABLE IF NOT EXISTS address_reputation_accounts (
+    address_hash CHAR(64) NOT NULL,
+    user_id VARCHAR(36) NOT NULL,
+    swap_count INT UNSIGNED NOT NULL DEFAULT 0,
+    -- Set once the account got a risk signal for this address
+    escalated_at TIMESTAMP NULL,
+    first_seen_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_seen_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    PRIMARY KEY (address_hash, user_id),
+    INDEX idx_address_reputation_accounts_user (user_id),
+
+    FOREIGN KEY (address_hash) REFERENCES address_reputation(address_hash) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+ALTER TABLE risk_signals
+    MODIFY COLUMN kind ENUM('chargeback', 'fraud_report', 'stolen_funds', 'address_reuse') NOT NULL;
@@ -0,0 +1,49 @@
+-- ============================================================================
+-- Migration: KYC verification
+-- Created: 2026-04-11
+-- Description: Identity verification through an external KYC provider
+--              (SumSub-style). One applicant per user holds the level the
+--              user has been verified to and the state of any verification
+--              in progress; the provider reports review outcomes by
+--              webhook. Document images go straight to the provider and are
+--              never stored here, only what was submitted and when.
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS kyc_applicants (
+    user_id VARCHAR(36) PRIMARY KEY,
+    provider VARCHAR(32) NOT NULL,
+    -- The provider's id for the applicant
+    applicant_id VARCHAR(128) NOT NULL,
+    -- Level verified so far; kept through later, higher verifications
+    level ENUM('none', 'basic', 'full') NOT NULL DEFAULT 'none',
+    -- Level of the verification in progress or last reviewed
+    requested_level ENUM('basic', 'full') NOT NULL,
+    status ENUM('init', 'pending', 'approved', 'rejected', 'retry') NOT NULL DEFAULT 'init',
+    -- Shown to the user after a rejection
+    reject_reason VARCHAR(500) NULL,
+    submitted_at TIMESTAMP NULL,
+    reviewed_at TIMESTAMP NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+
+    UNIQUE KEY uk_kyc_applicants_provider (provider, applicant_id),
+    INDEX idx_kyc_applicants_status (status),
+
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+CREATE TABLE IF NOT EXISTS kyc_documents (
+    id VARCHAR(36) PRIMARY KEY,
+    user_id VARCHAR(36) NOT NULL,
+    document_type ENUM('passport', 'id_card', 'driving_license', 'residence_permit', 'selfie', 'proof_of_address') NOT NULL,
+    side ENUM('front', 'back') NULL,
+    -- ISO 3166-1 alpha-3 issuing country
+    country CHAR(3) NOT NULL,
+    -- The provider's id for the uploaded image, when it returns one
+    provider_document_id VARCHAR(128) NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    INDEX idx_kyc_documents_user (user_id, created_at),
+
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
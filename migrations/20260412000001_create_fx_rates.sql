@@ -0,0 +1,23 @@
+-- ============================================================================
+-- Migration: FX rate snapshots
+-- Created: 2026-04-12
+-- Description: Daily fiat exchange rates against USD, so revenue and
+--              promotion reports can be shown in a reporting currency other
+--              than USD at the rate of the day each amount was earned. One
+--              row per currency and day, taken by the snapshot worker or
+--              backfilled with `fx_backfill`. Days without a rate (weekends,
+--              bank holidays) use the latest earlier one.
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS fx_rates (
+    -- ISO 4217, e.g. EUR
+    currency CHAR(3) NOT NULL,
+    rate_date DATE NOT NULL,
+    -- Units of `currency` per 1 USD
+    per_usd DECIMAL(24, 10) NOT NULL,
+    source VARCHAR(32) NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+
+    PRIMARY KEY (currency, rate_date)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
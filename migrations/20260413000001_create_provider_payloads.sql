@@ -0,0 +1,31 @@
+-- ============================================================================
+-- Migration: Raw provider payloads
+-- Created: 2026-04-13
+-- Description: The provider's own create and status responses for each swap,
+--              kept as returned for dispute resolution and shown on
+--              GET /admin/swaps/{id}/debug. Payloads are JSON compressed with
+--              COMPRESS(); oversized ones are cut to a prefix in a JSON
+--              envelope. A status payload is only stored when it differs
+--              from the swap's previous one. Rows older than
+--              PROVIDER_PAYLOAD_RETENTION_DAYS are purged.
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS provider_payloads (
+    id BIGINT AUTO_INCREMENT PRIMARY KEY,
+    swap_id VARCHAR(36) NOT NULL,
+    provider VARCHAR(50) NOT NULL,
+    kind ENUM('create', 'status') NOT NULL,
+    -- COMPRESS() of the JSON text
+    payload MEDIUMBLOB NOT NULL,
+    -- SHA-256 of the JSON as received, to skip repeated status payloads
+    payload_hash CHAR(64) NOT NULL,
+    -- Size of the JSON as received
+    original_bytes INT UNSIGNED NOT NULL,
+    truncated BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    INDEX idx_provider_payloads_swap (swap_id, kind, id),
+    INDEX idx_provider_payloads_created (created_at),
+
+    FOREIGN KEY (swap_id) REFERENCES swaps(id) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
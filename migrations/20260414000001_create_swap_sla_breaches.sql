@@ -0,0 +1,31 @@
+-- ============================================================================
+-- Migration: Swap SLA breaches
+-- Created: 2026-04-14
+-- Description: Swaps that stayed in one in-flight status longer than its
+--              SLA (SWAP_SLA_<STATUS>_MINUTES), and how far the breach was
+--              escalated: the provider re-polled, admins notified, then a
+--              support ticket opened. A breach is resolved once the swap
+--              leaves the status. Open breaches are the queue behind
+--              GET /admin/swaps/stuck.
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS swap_sla_breaches (
+    id BIGINT AUTO_INCREMENT PRIMARY KEY,
+    swap_id VARCHAR(36) NOT NULL,
+    status VARCHAR(32) NOT NULL,
+    -- When the swap entered the status
+    entered_at TIMESTAMP NOT NULL,
+    -- SLA in force when the breach was detected
+    sla_minutes INT UNSIGNED NOT NULL,
+    escalation ENUM('reping', 'notified', 'ticketed') NOT NULL,
+    ticket_reference VARCHAR(128) NULL,
+    detected_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    escalated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    resolved_at TIMESTAMP NULL,
+
+    -- One breach per stay in a status
+    UNIQUE KEY uniq_swap_sla_breach (swap_id, status, entered_at),
+    INDEX idx_swap_sla_breaches_open (resolved_at, detected_at),
+
+    FOREIGN KEY (swap_id) REFERENCES swaps(id) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
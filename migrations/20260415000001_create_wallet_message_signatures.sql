@@ -0,0 +1,30 @@
+-- ============================================================================
+-- Migration: Hot wallet message signatures
+-- Created: 2026-04-15
+-- Description: Audit trail of POST /admin/wallet/sign-message, used when an
+--              operator must prove control of the hot wallet (exchange
+--              listings, provider onboarding). Every attempt by an admin is
+--              recorded, including refused and failed ones, with the exact
+--              message and stated purpose. Rows are never updated or
+--              deleted, and outlive the admin's account.
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS wallet_message_signatures (
+    id BIGINT AUTO_INCREMENT PRIMARY KEY,
+    admin_id VARCHAR(36) NOT NULL,
+    chain ENUM('evm', 'bitcoin') NOT NULL,
+    -- Hot wallet address whose key signed; NULL when refused before lookup
+    address VARCHAR(128) NULL,
+    message TEXT NOT NULL,
+    message_sha256 CHAR(64) NOT NULL,
+    purpose VARCHAR(255) NOT NULL,
+    outcome ENUM('signed', 'denied', 'failed') NOT NULL,
+    signature VARCHAR(255) NULL,
+    -- Why the attempt was refused or failed
+    error VARCHAR(255) NULL,
+    client_ip VARCHAR(45) NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    INDEX idx_wallet_message_signatures_admin (admin_id, created_at),
+    INDEX idx_wallet_message_signatures_created (created_at)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
@@ -0,0 +1,37 @@
+-- ============================================================================
+-- Migration: Swap funnel
+-- Created: 2026-04-16
+-- Description: One row per swap journey, following it from rates quote to
+--              swap creation, deposit and completion, for conversion and
+--              drop-off reporting (GET /admin/analytics/funnel). A journey
+--              starts at the quote (keyed by its trade id) or, for clients
+--              that skip quoting, at creation. abandoned_stage is the last
+--              stage reached by a journey that went no further: a quote
+--              never used, a swap never funded, or a funded swap that
+--              failed, expired or was refunded.
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS swap_funnel (
+    id BIGINT AUTO_INCREMENT PRIMARY KEY,
+    trade_id VARCHAR(128) NULL,
+    swap_id VARCHAR(36) NULL,
+    from_currency VARCHAR(20) NOT NULL,
+    from_network VARCHAR(50) NOT NULL,
+    to_currency VARCHAR(20) NOT NULL,
+    to_network VARCHAR(50) NOT NULL,
+    -- Chosen at creation; NULL while only quoted
+    provider VARCHAR(50) NULL,
+    quoted_at TIMESTAMP NULL,
+    created_at TIMESTAMP NULL,
+    deposited_at TIMESTAMP NULL,
+    completed_at TIMESTAMP NULL,
+    abandoned_stage ENUM('quoted', 'created', 'deposited') NULL,
+    abandoned_at TIMESTAMP NULL,
+    started_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    UNIQUE KEY uniq_swap_funnel_trade (trade_id),
+    UNIQUE KEY uniq_swap_funnel_swap (swap_id),
+    INDEX idx_swap_funnel_started (started_at),
+    -- Quotes awaiting the abandonment sweep
+    INDEX idx_swap_funnel_open_quotes (created_at, abandoned_stage, quoted_at)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
@@ -0,0 +1,31 @@
+-- ============================================================================
+-- Migration: Encrypted backups
+-- Created: 2026-04-17
+-- Description: Nightly encrypted, compressed snapshots of the critical tables
+--              (swaps, ledger, wallet address indexes) in object storage.
+--              One row per run, shared with the system job tracking it; a
+--              run counts as good only once the uploaded object has been
+--              downloaded, decrypted and checked against its manifest.
+-- ============================================================================
+
+ALTER TABLE jobs
+    MODIFY COLUMN kind ENUM('export', 'reconciliation', 'backup') NOT NULL;
+
+CREATE TABLE IF NOT EXISTS backup_runs (
+    id VARCHAR(36) PRIMARY KEY,
+    -- First 16 hex chars of SHA-256 over the encryption key, to tell which
+    -- key a snapshot needs without storing the key
+    key_fingerprint CHAR(16) NOT NULL,
+    object_key VARCHAR(255) NULL,
+    size_bytes BIGINT UNSIGNED NULL,
+    -- SHA-256 of the uploaded (encrypted) object
+    sha256 CHAR(64) NULL,
+    -- JSON: per-table row count and digest, as recorded in the snapshot
+    manifest TEXT NULL,
+    started_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    finished_at TIMESTAMP NULL,
+    verified_at TIMESTAMP NULL,
+    error TEXT NULL,
+
+    INDEX idx_backup_runs_started (started_at)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
@@ -0,0 +1,43 @@
+-- ============================================================================
+-- Migration: Payout guard
+-- Created: 2026-04-18
+-- Description: Fail-closed circuit breaker over every outgoing payout (swap
+--              payouts, balance withdrawals, late deposit refunds). Each send
+--              is recorded so the failure rate, hourly USD volume and amount
+--              distribution can be checked before the next one; a trip halts
+--              all payouts until an admin re-arms the guard.
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS payout_attempts (
+    id BIGINT UNSIGNED AUTO_INCREMENT PRIMARY KEY,
+    kind ENUM('swap', 'withdrawal', 'refund') NOT NULL,
+    -- Swap, withdrawal or refund id
+    reference VARCHAR(36) NOT NULL,
+    chain VARCHAR(50) NOT NULL,
+    currency VARCHAR(20) NOT NULL,
+    amount DECIMAL(36, 18) NOT NULL,
+    -- Approximate value at send time
+    usd_value DOUBLE NOT NULL,
+    succeeded BOOLEAN NOT NULL,
+    error TEXT NULL,
+    created_at TIMESTAMP(3) NOT NULL DEFAULT CURRENT_TIMESTAMP(3),
+
+    INDEX idx_payout_attempts_created (created_at),
+    INDEX idx_payout_attempts_kind_created (kind, created_at)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+-- A trip with no rearmed_at is active and halts every payout
+CREATE TABLE IF NOT EXISTS payout_guard_trips (
+    id VARCHAR(36) PRIMARY KEY,
+    cause ENUM('failure_rate', 'usd_volume', 'outlier_amount', 'manual') NOT NULL,
+    reason TEXT NOT NULL,
+    -- Admin who tripped the guard by hand
+    tripped_by VARCHAR(36) NULL,
+    tripped_at TIMESTAMP(3) NOT NULL DEFAULT CURRENT_TIMESTAMP(3),
+    rearmed_at TIMESTAMP(3) NULL,
+    rearmed_by VARCHAR(36) NULL,
+    rearm_note TEXT NULL,
+
+    INDEX idx_payout_guard_trips_rearmed (rearmed_at),
+    INDEX idx_payout_guard_trips_tripped (tripped_at)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
@@ -0,0 +1,14 @@
+-- ============================================================================
+-- Migration: Swap ETA index
+-- Created: 2026-04-19
+-- Description: Index behind the completion-time estimate in swap status
+--              responses, which reads the most recent completed swaps of a
+--              pair and how long each stage took to reach completion.
+-- ============================================================================
+
+SET @sql = IFNULL((SELECT 'SELECT 1' FROM INFORMATION_SCHEMA.STATISTICS
+    WHERE table_name = 'swaps' AND index_name = 'idx_swaps_pair_completed' AND table_schema = DATABASE()),
+    'CREATE INDEX idx_swaps_pair_completed ON swaps (from_currency, to_currency, status, completed_at)');
+PREPARE stmt FROM @sql;
+EXECUTE stmt;
+DEALLOCATE PREPARE stmt;
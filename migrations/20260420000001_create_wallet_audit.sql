@@ -0,0 +1,58 @@
+-- ============================================================================
+-- Migration: HD wallet audit
+-- Created: 2026-04-20
+-- Description: A nightly job walks every allocated derivation index (swap
+--              deposit addresses and provider order intents), reads the
+--              address's native balance on each configured EVM chain and
+--              compares it with what the swap says should be there. Once a
+--              swap is finished its address should be empty; funds that are
+--              still there land in the admin recovery queue as orphaned
+--              funds, to be swept out or dismissed.
+-- ============================================================================
+
+ALTER TABLE jobs
+    MODIFY COLUMN kind ENUM('export', 'reconciliation', 'backup', 'wallet_audit') NOT NULL;
+
+CREATE TABLE IF NOT EXISTS wallet_audit_runs (
+    id VARCHAR(36) PRIMARY KEY,
+    started_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    finished_at TIMESTAMP NULL,
+    indices_checked INT UNSIGNED NOT NULL DEFAULT 0,
+    -- One per index and chain
+    balance_checks INT UNSIGNED NOT NULL DEFAULT 0,
+    findings INT UNSIGNED NOT NULL DEFAULT 0,
+    -- Balances the chain could not be asked for (RPC errors)
+    errors INT UNSIGNED NOT NULL DEFAULT 0,
+
+    INDEX idx_wallet_audit_runs_started (started_at)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+CREATE TABLE IF NOT EXISTS orphaned_funds (
+    id VARCHAR(36) PRIMARY KEY,
+    -- Run that last saw the funds
+    run_id VARCHAR(36) NOT NULL,
+    address_index INT UNSIGNED NOT NULL,
+    address VARCHAR(255) NOT NULL,
+    -- Canonical listener chain name where the funds are
+    chain VARCHAR(50) NOT NULL,
+    -- The swap the index was allocated to; for an order intent that never
+    -- became a swap there is no swaps row with this id
+    swap_id VARCHAR(36) NOT NULL,
+    swap_status VARCHAR(20) NULL,
+    kind ENUM('unswept', 'unmatched_deposit') NOT NULL,
+    -- Native balance when last seen
+    amount DOUBLE NOT NULL,
+    status ENUM('open', 'recovering', 'recovered', 'failed', 'dismissed') NOT NULL DEFAULT 'open',
+    recovery_address VARCHAR(255),
+    recovery_tx_hash VARCHAR(255),
+    recovered_amount DOUBLE,
+    error TEXT,
+    dismiss_reason TEXT,
+    resolved_by VARCHAR(36),
+    resolved_at TIMESTAMP NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+
+    UNIQUE KEY uq_orphaned_funds_index_chain (address_index, chain),
+    INDEX idx_orphaned_funds_status (status, created_at)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
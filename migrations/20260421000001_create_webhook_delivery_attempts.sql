@@ -0,0 +1,32 @@
+-- ============================================================================
+-- Migration: Webhook delivery attempts
+-- Created: 2026-04-21
+-- Description: Every request we send to a webhook endpoint is recorded with
+--              the response code and timing, whether it carried an event, a
+--              scheduled retry, a test event the integrator fired or a
+--              redelivery they asked for. webhook_deliveries keeps the latest
+--              outcome per payload; this table keeps the history.
+-- ============================================================================
+
+-- Test events are stored like any delivery so they can be redelivered, but
+-- are never retried
+ALTER TABLE webhook_deliveries
+    ADD COLUMN is_test BOOLEAN NOT NULL DEFAULT false AFTER is_dlq;
+
+CREATE TABLE IF NOT EXISTS webhook_delivery_attempts (
+    id BIGINT AUTO_INCREMENT PRIMARY KEY,
+    delivery_id VARCHAR(36) NOT NULL,
+    webhook_id VARCHAR(36) NOT NULL,
+    source ENUM('event', 'retry', 'test', 'redelivery') NOT NULL,
+    -- User who fired a test or redelivery
+    requested_by VARCHAR(36) NULL,
+    -- NULL when no response arrived (timeout, connection refused)
+    response_status INT NULL,
+    response_time_ms INT NOT NULL,
+    succeeded BOOLEAN NOT NULL,
+    error_message TEXT,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    INDEX idx_webhook_delivery_attempts_delivery (delivery_id, created_at),
+    INDEX idx_webhook_delivery_attempts_webhook (webhook_id, source, created_at)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
@@ -0,0 +1,29 @@
+-- ============================================================================
+-- Migration: Password reset hardening
+-- Created: 2026-04-22
+-- Description: Reset tokens are stored as SHA-256 hashes, so the table can't
+--              be used to take over accounts. Each request records the IP
+--              and user agent it came from, which are also put in the reset
+--              email. A user has at most one usable token: a new request
+--              retires the previous ones.
+--
+--              users.sessions_revoked_at is set by a successful reset.
+--              Access and refresh tokens issued before it are refused, which
+--              signs the account out everywhere.
+-- ============================================================================
+
+-- Outstanding tokens were issued under the old rules (long-lived, several
+-- per user); retire them instead of honouring them
+UPDATE password_resets SET used = TRUE WHERE used = FALSE;
+UPDATE password_resets SET token = SHA2(token, 256) WHERE token NOT LIKE 'scrubbed:%';
+
+ALTER TABLE password_resets
+    CHANGE COLUMN token token_hash VARCHAR(255) NOT NULL,
+    ADD COLUMN requested_ip VARCHAR(45) NULL AFTER used,
+    ADD COLUMN user_agent VARCHAR(255) NULL AFTER requested_ip,
+    DROP INDEX idx_password_resets_token,
+    ADD UNIQUE INDEX uq_password_resets_token_hash (token_hash),
+    ADD INDEX idx_password_resets_user (user_id, used, created_at);
+
+ALTER TABLE users
+    ADD COLUMN sessions_revoked_at TIMESTAMP NULL;
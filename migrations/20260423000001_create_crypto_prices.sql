@@ -0,0 +1,21 @@
+-- ============================================================================
+-- Migration: Crypto price snapshots
+-- Created: 2026-04-23
+-- Description: Daily USD prices of the assets we swap, taken next to the
+--              `fx_rates` snapshot, so revenue and promotion reports value
+--              each asset at the price of the day it was earned instead of a
+--              fixed approximation. One row per ticker and day, from the
+--              snapshot worker or `fx_backfill`.
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS crypto_prices (
+    -- Lower-case ticker as stored on swaps, e.g. eth
+    ticker VARCHAR(20) NOT NULL,
+    price_date DATE NOT NULL,
+    usd_price DECIMAL(36, 18) NOT NULL,
+    source VARCHAR(32) NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+
+    PRIMARY KEY (ticker, price_date)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
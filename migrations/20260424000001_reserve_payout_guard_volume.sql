@@ -0,0 +1,22 @@
+-- ============================================================================
+-- Migration: Payout guard reservations
+-- Created: 2026-04-24
+-- Description: A payout cleared by the guard is reserved as an attempt with
+--              no outcome, in the same transaction as the hourly cap check,
+--              so concurrent payouts can't each pass the cap on their own.
+--              The outcome is filled in once the send returns; a payout that
+--              is never sent has its reservation removed. Checks serialize on
+--              the single `payout_guard_lock` row.
+--              usd_value is now priced from the latest `crypto_prices`
+--              snapshot rather than a fixed approximation.
+-- ============================================================================
+
+ALTER TABLE payout_attempts
+    -- NULL while reserved and not yet sent
+    MODIFY COLUMN succeeded BOOLEAN NULL;
+
+CREATE TABLE IF NOT EXISTS payout_guard_lock (
+    id TINYINT UNSIGNED PRIMARY KEY
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+INSERT IGNORE INTO payout_guard_lock (id) VALUES (1);
@@ -0,0 +1,18 @@
+-- ============================================================================
+-- Migration: Rekey address reputation
+-- Created: 2026-04-25
+-- Description: address_hash is now the PII blind index of the network and
+--              normalized address rather than a plain SHA-256, which anyone
+--              could compute for a candidate address. Rows recorded while
+--              encryption was off keep the plain hash until the PII rotation
+--              job seals their address and moves them to the blind index;
+--              account links follow the key on update.
+-- ============================================================================
+
+ALTER TABLE address_reputation_accounts
+    DROP FOREIGN KEY address_reputation_accounts_ibfk_1;
+
+ALTER TABLE address_reputation_accounts
+    ADD CONSTRAINT fk_address_reputation_accounts_address
+        FOREIGN KEY (address_hash) REFERENCES address_reputation(address_hash)
+        ON DELETE CASCADE ON UPDATE CASCADE;
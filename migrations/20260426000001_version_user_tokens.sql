@@ -0,0 +1,20 @@
+-- ============================================================================
+-- Migration: Token versions
+-- Created: 2026-04-26
+-- Description: Access and refresh tokens carry users.token_version, which a
+--              successful password reset bumps, so every token issued before
+--              the reset is refused. Replaces sessions_revoked_at, which was
+--              compared with the token's issue time and could only tell
+--              tokens apart to the second.
+--
+--              Accounts that were reset sign out once more, since tokens
+--              issued before this migration carry no version.
+-- ============================================================================
+
+ALTER TABLE users
+    ADD COLUMN token_version INT UNSIGNED NOT NULL DEFAULT 0;
+
+UPDATE users SET token_version = 1 WHERE sessions_revoked_at IS NOT NULL;
+
+ALTER TABLE users
+    DROP COLUMN sessions_revoked_at;
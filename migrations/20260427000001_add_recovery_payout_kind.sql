@@ -0,0 +1,10 @@
+-- ============================================================================
+-- Migration: Recovery payouts in the payout guard
+-- Created: 2026-04-27
+-- Description: Admin sweeps of deposit addresses, for wrong-network cases
+--              and orphaned funds, now go through the payout guard like
+--              every other payout and are recorded under their own kind.
+-- ============================================================================
+
+ALTER TABLE payout_attempts
+    MODIFY COLUMN kind ENUM('swap', 'withdrawal', 'refund', 'recovery') NOT NULL;
@@ -0,0 +1,36 @@
+-- ============================================================================
+-- Migration: Wallet audit tokens and unaudited chains
+-- Created: 2026-04-28
+-- Description: The wallet audit now reads canonical ERC-20 balances (USDT,
+--              USDC, ...) next to the native coin on every EVM chain, and
+--              Bitcoin and Solana balances where a node is configured, so a
+--              finding is keyed by currency as well as chain. Indices whose
+--              chain has no node configured are counted per run and their
+--              chains listed, instead of being skipped silently.
+-- ============================================================================
+
+ALTER TABLE orphaned_funds
+    -- Lower-case ticker: the chain's native coin or a canonical token
+    ADD COLUMN currency VARCHAR(20) NOT NULL DEFAULT '' AFTER chain,
+    -- ERC-20 contract of token funds; NULL for the native coin
+    ADD COLUMN token_contract VARCHAR(64) NULL AFTER currency;
+
+-- Every finding so far was a native EVM balance
+UPDATE orphaned_funds
+SET currency = CASE chain
+    WHEN 'bsc' THEN 'bnb'
+    WHEN 'polygon' THEN 'pol'
+    WHEN 'avalanche' THEN 'avax'
+    ELSE 'eth'
+END
+WHERE currency = '';
+
+ALTER TABLE orphaned_funds
+    DROP INDEX uq_orphaned_funds_index_chain,
+    ADD UNIQUE KEY uq_orphaned_funds_index_chain_currency (address_index, chain, currency);
+
+ALTER TABLE wallet_audit_runs
+    -- Indices with a chain no node is configured for
+    ADD COLUMN unaudited_indices INT UNSIGNED NOT NULL DEFAULT 0,
+    -- Comma-separated chains those indices could not be checked on
+    ADD COLUMN unaudited_chains VARCHAR(500) NOT NULL DEFAULT '';
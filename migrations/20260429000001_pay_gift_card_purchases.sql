@@ -0,0 +1,30 @@
+-- ============================================================================
+-- Migration: Gift card payments
+-- Created: 2026-04-29
+-- Description: Purchases are reserved before the provider order is placed,
+--              can be funded from a custodial balance, and are paid to the
+--              provider from custody under the payout guard. Amounts move to
+--              DECIMAL(36, 18) like every other amount column.
+-- ============================================================================
+
+ALTER TABLE gift_card_purchases
+    MODIFY COLUMN amount DECIMAL(36, 18) NOT NULL,
+    MODIFY COLUMN payment_amount DECIMAL(36, 18) NOT NULL DEFAULT 0,
+    MODIFY COLUMN status ENUM('pending', 'paying', 'processing', 'delivered', 'failed') NOT NULL DEFAULT 'pending',
+    ADD COLUMN funded_at TIMESTAMP NULL AFTER payment_extra_id,
+    ADD COLUMN payment_tx_hash VARCHAR(100) NULL AFTER funded_at,
+    ADD INDEX idx_gift_card_purchases_unpaid (status, funded_at);
+
+ALTER TABLE ledger_entries
+    MODIFY COLUMN entry_type ENUM(
+        'swap_credit',
+        'transfer_in',
+        'transfer_out',
+        'withdrawal_hold',
+        'withdrawal_release',
+        'gift_card_purchase',
+        'gift_card_refund'
+    ) NOT NULL;
+
+ALTER TABLE payout_attempts
+    MODIFY COLUMN kind ENUM('swap', 'withdrawal', 'refund', 'recovery', 'gift_card') NOT NULL;
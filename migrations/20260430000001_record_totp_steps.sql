@@ -0,0 +1,11 @@
+-- ============================================================================
+-- Migration: Spent TOTP codes
+-- Created: 2026-04-30
+-- Description: The time step of the last 2FA code a user spent. A code is
+--              accepted once, and never for a step at or before this one, so
+--              a code seen over a shoulder or in a log can't be replayed
+--              within its window.
+-- ============================================================================
+
+ALTER TABLE users
+    ADD COLUMN totp_last_step BIGINT UNSIGNED NULL AFTER two_factor_secret;
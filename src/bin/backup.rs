@@ -0,0 +1,128 @@
+//! Take, check and restore encrypted backups by hand.
+//!
+//! `run` takes a backup now, exactly like the nightly job: the snapshot is
+//! uploaded under BACKUP_S3_PREFIX, read back and verified, and the run is
+//! recorded in `backup_runs` and `jobs`. `verify` downloads a snapshot (or
+//! reads a local copy with `--file`), decrypts it and checks every table
+//! against its manifest. `restore` loads a snapshot into an empty, migrated
+//! database; the target has to be given explicitly so a restore never lands
+//! on DATABASE_URL by accident.
+//!
+//! ```text
+//! cargo run --bin backup -- run
+//! cargo run --bin backup -- verify backups/2026/04/17/<run id>.ndjson.gz.enc
+//! cargo run --bin backup -- restore --file ./snapshot.enc --database-url mysql://root@localhost/restore_drill
+//! ```
+
+use exchange_shared::services::backup::snapshot::verify_snapshot;
+use exchange_shared::services::backup::{restore, BackupCipher, BackupConfig, BackupService, Manifest};
+use exchange_shared::services::storage::{ObjectStorage, S3Storage};
+
+const USAGE: &str = "usage: backup run [--database-url <url>]
+       backup verify (<object key> | --file <path>)
+       backup restore (<object key> | --file <path>) --database-url <url>";
+
+enum Source {
+    Object(String),
+    File(String),
+}
+
+#[tokio::main]
+async fn main() {
+    dotenvy::dotenv().ok();
+
+    let mut args = std::env::args().skip(1);
+    let command = args.next().unwrap_or_else(|| fail("a command is required"));
+    if command == "-h" || command == "--help" {
+        println!("{}", USAGE);
+        return;
+    }
+
+    let mut database_url = None;
+    let mut source = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--database-url" => database_url = Some(args.next().unwrap_or_else(|| fail("--database-url needs a value"))),
+            "--file" => source = Some(Source::File(args.next().unwrap_or_else(|| fail("--file needs a path")))),
+            other if !other.starts_with('-') && source.is_none() => source = Some(Source::Object(other.to_string())),
+            other => fail(&format!("unknown argument `{}`", other)),
+        }
+    }
+
+    let config = BackupConfig::from_env().unwrap_or_else(|e| fail(&e));
+    let cipher = config.cipher.unwrap_or_else(|| fail("BACKUP_ENCRYPTION_KEY is not set"));
+
+    match command.as_str() {
+        "run" => {
+            let database_url = database_url
+                .or_else(|| std::env::var("DATABASE_URL").ok())
+                .unwrap_or_else(|| fail("--database-url or DATABASE_URL is required"));
+            let pool = connect(&database_url).await;
+            let service = BackupService::from_env(pool.clone()).unwrap_or_else(|e| exit(&e.to_string()));
+            match service.run().await {
+                Ok(run) => println!(
+                    "backup {} verified: {} ({} bytes, key {})",
+                    run.id,
+                    run.object_key.unwrap_or_default(),
+                    run.size_bytes.unwrap_or_default(),
+                    run.key_fingerprint
+                ),
+                Err(e) => exit(&e.to_string()),
+            }
+            pool.close().await;
+        }
+        "verify" => {
+            let compressed = load(source, &cipher).await;
+            let manifest = verify_snapshot(&compressed).unwrap_or_else(|e| exit(&e));
+            print_manifest(&manifest);
+            println!("snapshot verified");
+        }
+        "restore" => {
+            let database_url = database_url.unwrap_or_else(|| fail("restore needs an explicit --database-url"));
+            let compressed = load(source, &cipher).await;
+            let pool = connect(&database_url).await;
+            let manifest = restore(&pool, &compressed).await.unwrap_or_else(|e| exit(&e.to_string()));
+            print_manifest(&manifest);
+            println!("snapshot restored");
+            pool.close().await;
+        }
+        other => fail(&format!("unknown command `{}`", other)),
+    }
+}
+
+/// Read a sealed snapshot and decrypt it
+async fn load(source: Option<Source>, cipher: &BackupCipher) -> Vec<u8> {
+    let sealed = match source {
+        Some(Source::File(path)) => {
+            std::fs::read(&path).unwrap_or_else(|e| exit(&format!("failed to read {}: {}", path, e)))
+        }
+        Some(Source::Object(key)) => {
+            let storage = S3Storage::from_env().unwrap_or_else(|e| exit(&e.to_string()));
+            storage.get_object(&key).await.unwrap_or_else(|e| exit(&e.to_string()))
+        }
+        None => fail("an object key or --file is required"),
+    };
+    cipher.open(&sealed).unwrap_or_else(|e| exit(&e))
+}
+
+async fn connect(database_url: &str) -> sqlx::MySqlPool {
+    sqlx::MySqlPool::connect(database_url)
+        .await
+        .unwrap_or_else(|e| exit(&format!("failed to connect to database: {}", e)))
+}
+
+fn print_manifest(manifest: &Manifest) {
+    for (table, digest) in manifest {
+        println!("{:<24} {:>10} rows  {}", table, digest.rows, digest.sha256);
+    }
+}
+
+fn exit(message: &str) -> ! {
+    eprintln!("error: {}", message);
+    std::process::exit(1);
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("error: {}\n{}", message, USAGE);
+    std::process::exit(2);
+}
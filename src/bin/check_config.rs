@@ -0,0 +1,47 @@
+//! Check the environment the server would start with, without starting it.
+//!
+//! Prints the same report startup does: missing and half-configured
+//! settings, the wallet mnemonic, worker settings, payout RPCs and RPC
+//! pings. Exits 1 when anything is an error.
+//!
+//! ```text
+//! cargo run --bin check_config
+//! cargo run --bin check_config -- --no-ping
+//! ```
+
+use exchange_shared::services::preflight::{Preflight, PreflightConfig};
+
+const USAGE: &str = "usage: check_config [--no-ping]";
+
+#[tokio::main]
+async fn main() {
+    dotenvy::dotenv().ok();
+
+    let mut config = match PreflightConfig::from_env() {
+        Ok(config) => config,
+        Err(e) => fail(&e),
+    };
+    config.enabled = true;
+
+    for arg in std::env::args().skip(1) {
+        match arg.as_str() {
+            "--no-ping" => config.ping_rpcs = false,
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return;
+            }
+            other => fail(&format!("unknown argument `{}`", other)),
+        }
+    }
+
+    let report = Preflight::new(config).run().await;
+    println!("{}", report);
+    if report.has_errors() {
+        std::process::exit(1);
+    }
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("error: {}\n{}", message, USAGE);
+    std::process::exit(2);
+}
@@ -0,0 +1,64 @@
+//! Emit a typed API client (or the raw route manifest) from the server's
+//! route definitions.
+//!
+//! ```text
+//! cargo run --bin codegen -- --lang ts --out clients/exchange.ts
+//! cargo run --bin codegen -- --lang rust --out clients/exchange.rs
+//! cargo run --bin codegen -- --lang json
+//! cargo run --bin codegen -- --lang ts --api-version v2 --out clients/exchange-v2.ts
+//! ```
+//!
+//! Clients target v1, the stable API, unless `--api-version` says otherwise.
+
+use exchange_shared::manifest::{route_manifest_for, rust_client, typescript};
+use exchange_shared::services::api_version::ApiVersion;
+
+const USAGE: &str = "usage: codegen --lang <ts|rust|json> [--api-version <v1|v2>] [--out <path>]";
+
+fn main() {
+    let mut lang = None;
+    let mut out = None;
+    let mut api_version = ApiVersion::V1;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--lang" => lang = args.next(),
+            "--out" => out = args.next(),
+            "--api-version" => {
+                let value = args.next().unwrap_or_default();
+                api_version = ApiVersion::parse(&value)
+                    .unwrap_or_else(|| fail(&format!("unsupported API version `{}`", value)));
+            }
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return;
+            }
+            other => fail(&format!("unknown argument `{}`", other)),
+        }
+    }
+
+    let manifest = route_manifest_for(api_version);
+    let output = match lang.as_deref() {
+        Some("ts") | Some("typescript") => typescript::generate(&manifest),
+        Some("rust") => rust_client::generate(&manifest),
+        Some("json") => manifest.to_json(),
+        Some(other) => fail(&format!("unsupported language `{}`", other)),
+        None => fail("--lang is required"),
+    };
+
+    match out {
+        Some(path) => {
+            if let Err(e) = std::fs::write(&path, output) {
+                fail(&format!("failed to write {}: {}", path, e));
+            }
+            eprintln!("Wrote {} routes to {}", manifest.routes.len(), path);
+        }
+        None => print!("{}", output),
+    }
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("error: {}\n{}", message, USAGE);
+    std::process::exit(2);
+}
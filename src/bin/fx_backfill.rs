@@ -0,0 +1,97 @@
+//! Fill `fx_rates` and `crypto_prices` with history so reports over past
+//! periods can be valued in the reporting currency.
+//!
+//! Fetches every published day between `--from` and `--to` (default today)
+//! for the currencies the snapshot worker tracks (FX_CURRENCIES plus
+//! REPORTING_CURRENCY), or those given with `--currency`, and the daily USD
+//! price of every asset it prices. Existing rows are corrected in place, so
+//! the tool can be re-run over the same range.
+//!
+//! ```text
+//! cargo run --bin fx_backfill -- --from 2025-01-01
+//! cargo run --bin fx_backfill -- --from 2024-01-01 --to 2024-12-31 --currency EUR --currency GBP
+//! ```
+
+use chrono::{NaiveDate, Utc};
+use exchange_shared::services::fx::{parse_currency, FxConfig, FxSnapshotter};
+
+const USAGE: &str = "usage: fx_backfill --from <YYYY-MM-DD> [--to <YYYY-MM-DD>] [--currency <ISO 4217>]... [--database-url <url>]";
+
+#[tokio::main]
+async fn main() {
+    dotenvy::dotenv().ok();
+
+    let mut database_url = std::env::var("DATABASE_URL").ok();
+    let mut from = None;
+    let mut to = Utc::now().date_naive();
+    let mut currencies = Vec::new();
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--from" => from = Some(date_arg(args.next())),
+            "--to" => to = date_arg(args.next()),
+            "--currency" => match args.next().as_deref().map(parse_currency) {
+                Some(Ok(code)) => currencies.push(code),
+                Some(Err(e)) => fail(&e.to_string()),
+                None => fail("--currency needs a code"),
+            },
+            "--database-url" => database_url = args.next(),
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return;
+            }
+            other => fail(&format!("unknown argument `{}`", other)),
+        }
+    }
+
+    let Some(from) = from else {
+        fail("--from is required");
+    };
+    if from > to {
+        fail("--from must not be after --to");
+    }
+    let Some(database_url) = database_url else {
+        fail("--database-url or DATABASE_URL is required");
+    };
+
+    let mut config = FxConfig::from_env().unwrap_or_else(|e| fail(&e.to_string()));
+    if !currencies.is_empty() {
+        config.currencies = currencies;
+    }
+    let pool = match sqlx::MySqlPool::connect(&database_url).await {
+        Ok(pool) => pool,
+        Err(e) => {
+            eprintln!("error: failed to connect to database: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let snapshotter = FxSnapshotter::new(pool.clone(), reqwest::Client::new(), config);
+    match snapshotter.backfill(from, to).await {
+        Ok(changed) => println!(
+            "{} rates and prices stored for [{}] and crypto assets from {} to {}",
+            changed,
+            snapshotter.currencies().join(","),
+            from,
+            to
+        ),
+        Err(e) => {
+            eprintln!("error: {}", e);
+            std::process::exit(1);
+        }
+    }
+    pool.close().await;
+}
+
+fn date_arg(value: Option<String>) -> NaiveDate {
+    value
+        .as_deref()
+        .and_then(|v| NaiveDate::parse_from_str(v, "%Y-%m-%d").ok())
+        .unwrap_or_else(|| fail("dates are YYYY-MM-DD"))
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("error: {}\n{}", message, USAGE);
+    std::process::exit(2);
+}
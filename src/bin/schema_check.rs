@@ -0,0 +1,86 @@
+//! Verify the migration set before a deploy.
+//!
+//! Applies every migration to a scratch database on the server named by
+//! `--database-url`, then checks model structs and `.sqlx` offline query
+//! data against the result. With `--target`, also compares that database's
+//! migration history with the local set. Exits 1 when anything is an error.
+//!
+//! ```text
+//! cargo run --bin schema_check
+//! cargo run --bin schema_check -- --target mysql://deploy@prod-db/exchange
+//! ```
+
+use exchange_shared::schema_check::{migrations, verify, Severity};
+use std::path::PathBuf;
+
+const USAGE: &str = "usage: schema_check [--database-url <url>] [--sqlx-dir <path>] [--target <url>]";
+
+#[tokio::main]
+async fn main() {
+    dotenvy::dotenv().ok();
+
+    let mut database_url = std::env::var("DATABASE_URL").ok();
+    let mut sqlx_dir = PathBuf::from(".sqlx");
+    let mut target = None;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--database-url" => database_url = args.next(),
+            "--sqlx-dir" => sqlx_dir = args.next().map(PathBuf::from).unwrap_or_else(|| fail("--sqlx-dir needs a path")),
+            "--target" => target = args.next(),
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return;
+            }
+            other => fail(&format!("unknown argument `{}`", other)),
+        }
+    }
+
+    let Some(database_url) = database_url else {
+        fail("--database-url or DATABASE_URL is required");
+    };
+
+    let report = match verify(&database_url, Some(&sqlx_dir)).await {
+        Ok(report) => report,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            std::process::exit(1);
+        }
+    };
+    println!("{}", report);
+    let mut failed = report.has_errors();
+
+    if let Some(target) = target {
+        let pool = match sqlx::MySqlPool::connect(&target).await {
+            Ok(pool) => pool,
+            Err(e) => {
+                eprintln!("error: failed to connect to target: {}", e);
+                std::process::exit(1);
+            }
+        };
+        match migrations::history_drift(&pool).await {
+            Ok(issues) => {
+                for issue in &issues {
+                    println!("{}", issue);
+                }
+                println!("target history: {} issues", issues.len());
+                failed |= issues.iter().any(|i| i.severity == Severity::Error);
+            }
+            Err(e) => {
+                eprintln!("error: failed to read target migration history: {}", e);
+                failed = true;
+            }
+        }
+        pool.close().await;
+    }
+
+    if failed {
+        std::process::exit(1);
+    }
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("error: {}\n{}", message, USAGE);
+    std::process::exit(2);
+}
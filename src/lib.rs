@@ -1,20 +1,56 @@
 pub mod config;
+pub mod manifest;
 pub mod modules;
+pub mod schema_check;
 pub mod services;
+#[cfg(feature = "test-support")]
+pub mod test_support;
 
-use axum::{middleware, routing::get, Json, Router};
+use axum::{
+    http::{header, HeaderName, HeaderValue, Method},
+    middleware,
+    routing::get,
+    Json, Router,
+};
 use serde::Serialize;
 use std::sync::Arc;
+use tower::Layer;
 use tower_http::{cors::CorsLayer, limit::RequestBodyLimitLayer, trace::TraceLayer};
 
 use config::DbPool;
+use modules::account::account_routes;
+use modules::address_book::address_book_routes;
+use modules::admin::{admin_routes, admin_ws_routes};
 use modules::auth::auth_routes;
+use modules::auth::guard::route_auth;
+use modules::balances::balance_routes;
+use modules::email::email_routes;
+use modules::exports::export_routes;
+use modules::gift_cards::gift_card_routes;
+use modules::graphql::graphql_routes;
+use modules::jobs::job_routes;
+use modules::kyc::kyc_routes;
+use modules::status::status_routes;
 use modules::swap::swap_routes;
+use modules::v2::v2_routes;
+use modules::webhooks::webhook_routes;
+use services::api_version::{ApiVersion, ApiVersionLayer, VersionPolicy, API_VERSION_HEADER};
+use services::client_ip::{ClientIpLayer, TrustedProxies};
+use services::geo::{GeoBlockLayer, GeoLocator, GeoPolicy};
 use services::jwt::JwtService;
-use services::rate_limit::{create_rate_limiter, RateLimitLayer};
+use services::metrics::{LatencyBudgetLayer, LatencyBudgets};
+use services::rate_limit::{create_quota_limiter, RateLimitLayer, RedisTokenBucket};
+use services::runtime_config::runtime_config;
 use services::security::security_headers;
+use services::session::{csrf_protection, SessionConfig, CSRF_HEADER};
+use services::telemetry::{make_request_span, record_response};
+use services::usage::{UsageCounters, UsageLayer};
+use services::watch_only::watch_only_guard;
 use services::redis_cache::RedisService;
 
+/// Request body limit for every route that doesn't set its own
+pub const MAX_BODY_BYTES: usize = 1024 * 100; // 100KB
+
 pub struct AppState {
     pub db: DbPool,
     pub redis: RedisService, // Changed from redis::Client
@@ -32,20 +68,136 @@ pub async fn create_app(db: DbPool, redis: RedisService, jwt_service: JwtService
         wallet_mnemonic,
     });
 
-    // Rate limit: burst of 10, then 1 per minute
-    let rate_limiter = create_rate_limiter(10);
+    // Rate limit: burst of 10, then 1 per minute, unless the runtime
+    // settings say otherwise; changes apply without a restart.
+    // Multi-instance deployments set RATE_LIMIT_BACKEND=redis so replicas
+    // share buckets; the in-memory limiter stays as the fallback when Redis
+    // is unreachable.
+    let http_quota = runtime_config().current().rate_limit_quota();
+    let mut rate_limit_layer = RateLimitLayer::new(create_quota_limiter(http_quota))
+        .with_runtime_config(runtime_config().subscribe());
+    if std::env::var("RATE_LIMIT_BACKEND").map(|v| v.eq_ignore_ascii_case("redis")).unwrap_or(false) {
+        let bucket = RedisTokenBucket::new(state.redis.clone(), http_quota);
+        rate_limit_layer = rate_limit_layer.with_redis(bucket);
+    }
+
+    // Behind a load balancer the TCP peer is the proxy; TRUSTED_PROXIES
+    // lists the proxies whose forwarding headers we believe. Without them
+    // every client would share the proxy's rate limit and geo decision, so
+    // a broken list stops startup.
+    let trusted_proxies =
+        TrustedProxies::from_env().unwrap_or_else(|e| panic!("Invalid trusted proxy config: {}", e));
+
+    // Jurisdiction policy; a no-op unless blocked countries are configured.
+    // A broken config would serve blocked countries, so it stops startup.
+    let geo_policy = GeoPolicy::from_env().unwrap_or_else(|e| panic!("Invalid geo policy config: {}", e));
+    let geo_locator = GeoLocator::from_env(state.http_client.clone())
+        .unwrap_or_else(|e| panic!("Invalid geo lookup config: {}", e))
+        .with_redis(state.redis.clone());
+
+    // Per-route latency budgets; slow requests are logged with the
+    // operations that took the time
+    let latency_budgets =
+        LatencyBudgets::from_env().unwrap_or_else(|e| panic!("Invalid latency budget config: {}", e));
+
+    // Per-user request, error and rate-limit counts behind /account/usage
+    let usage_layer = UsageLayer::new(UsageCounters::new(state.redis.clone()), state.jwt_service.clone());
+
+    // Version of unprefixed requests that don't ask for one
+    let version_policy =
+        VersionPolicy::from_env().unwrap_or_else(|e| panic!("Invalid API version config: {}", e));
+
+    // Each module mounts its handlers at the paths the route manifest
+    // declares for them, relative to the version prefix
+    let v1 = Router::new()
+        .merge(auth_routes())
+        .merge(swap_routes())
+        .merge(gift_card_routes())
+        .merge(balance_routes())
+        .merge(admin_routes())
+        .merge(admin_ws_routes())
+        .merge(address_book_routes())
+        .merge(account_routes())
+        .merge(export_routes())
+        .merge(job_routes())
+        .merge(email_routes())
+        .merge(status_routes())
+        .merge(webhook_routes())
+        .merge(graphql_routes());
+
+    // Dev-only seeding API; release builds leave the feature off
+    #[cfg(feature = "seed")]
+    let v1 = v1.merge(modules::seed::seed_routes());
 
-    Router::new()
+    // Staging-only deposit simulation; release builds leave the feature off
+    #[cfg(feature = "deposit-simulation")]
+    let v1 = v1.merge(modules::simulation::simulation_routes());
+
+    // v2 mounts only the handlers that changed; ApiVersionLayer sends other
+    // /v2 requests to v1
+    let routes = Router::new()
         .route("/", get(root))
         .route("/health", get(health_check))
-        .nest("/auth", auth_routes())
-        .nest("/swap", swap_routes())
+        .nest(ApiVersion::V1.prefix(), v1)
+        .nest(ApiVersion::V2.prefix(), v2_routes());
+
+    let app = routes
+        .layer(RequestBodyLimitLayer::new(MAX_BODY_BYTES))
+        // Nested after the body limit: document uploads need a larger one
+        .nest(ApiVersion::V1.prefix(), kyc_routes())
+        // Auth declared in the route manifest, checked before any handler
+        .layer(middleware::from_fn_with_state(state.clone(), route_auth))
+        .layer(LatencyBudgetLayer::new(latency_budgets))
+        .layer(middleware::from_fn(watch_only_guard))
+        .layer(middleware::from_fn(csrf_protection))
         .layer(middleware::from_fn(security_headers))
-        .layer(RequestBodyLimitLayer::new(1024 * 100)) // 100KB max body
-        .layer(RateLimitLayer::new(rate_limiter))
-        .layer(TraceLayer::new_for_http())
-        .layer(CorsLayer::permissive())
-        .with_state(state)
+        .layer(GeoBlockLayer::new(geo_policy, geo_locator))
+        .layer(rate_limit_layer)
+        .layer(usage_layer)
+        .layer(ClientIpLayer::new(trusted_proxies))
+        .layer(
+            TraceLayer::new_for_http()
+                .make_span_with(make_request_span::<axum::body::Body>)
+                .on_response(record_response::<axum::body::Body>),
+        )
+        .layer(cors_layer(SessionConfig::global()))
+        .with_state(state);
+
+    // The version is negotiated before routing, so the layer wraps the
+    // router instead of being added to it
+    Router::new().fallback_service(ApiVersionLayer::new(version_policy).layer(app))
+}
+
+/// Cookie sessions need credentialed CORS, which only works with an explicit
+/// origin list; bearer-only deployments keep the permissive policy
+fn cors_layer(sessions: &SessionConfig) -> CorsLayer {
+    if !sessions.enabled || sessions.allowed_origins.is_empty() {
+        return CorsLayer::permissive();
+    }
+
+    let origins: Vec<HeaderValue> = sessions
+        .allowed_origins
+        .iter()
+        .filter_map(|o| HeaderValue::from_str(o).ok())
+        .collect();
+
+    CorsLayer::new()
+        .allow_origin(origins)
+        .allow_credentials(true)
+        .allow_methods([Method::GET, Method::POST, Method::PUT, Method::PATCH, Method::DELETE])
+        .allow_headers([
+            header::CONTENT_TYPE,
+            header::AUTHORIZATION,
+            HeaderName::from_static(CSRF_HEADER),
+            HeaderName::from_static(API_VERSION_HEADER),
+        ])
+        // Lets frontends see which version answered and when an unprefixed
+        // path is deprecated
+        .expose_headers([
+            HeaderName::from_static(API_VERSION_HEADER),
+            HeaderName::from_static("deprecation"),
+            header::LINK,
+        ])
 }
 
 async fn root() -> &'static str {
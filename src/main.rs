@@ -1,18 +1,74 @@
-use exchange_shared::config::{environment::Config, init_db};
+use exchange_shared::config::{environment::Config, init_db, DbPool};
 use exchange_shared::services::{jwt::JwtService, redis_cache::RedisService, blockchain::BlockchainListener};
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+use exchange_shared::services::blockchain::listener::evm_rpc_url;
+use exchange_shared::services::blockchain::{shards::shard_count_from_env, Shard};
+use exchange_shared::services::leader::LeaderElection;
+use exchange_shared::services::liquidity::{PairLiquidityRefresher, TrocadorLiquiditySource};
+use exchange_shared::services::custody::{GiftCardPayer, WithdrawalProcessor};
+use exchange_shared::services::email::{email_sender_from_env, queued_email_sender, EmailOutboxWorker};
+use exchange_shared::services::events::{
+    spawn_subscriber, AddressReputationSubscriber, EventStreamConfig, MetricsSubscriber, NotificationSubscriber,
+    RevenueSubscriber, StreamSubscriber, WebhookSubscriber,
+};
+use exchange_shared::services::metrics::MetricsRegistry;
+use exchange_shared::services::webhook::{RetryConfig, WebhookDispatcher};
+use exchange_shared::services::schedule::ScheduleWorker;
+use exchange_shared::services::orders::OrderWatcher;
+use exchange_shared::services::refund::LateDepositRefunder;
+use exchange_shared::services::monitor::{MonitorEngine, SwapPayoutHandler};
+use exchange_shared::services::payout::{
+    EvmTxStatus, PayoutBatchConfig, PayoutBatcher, PayoutExecutor, PayoutExecutorConfig, PayoutGuard, SolanaTxStatus,
+    TxTracker,
+};
+use exchange_shared::services::simulation::simulated;
+use exchange_shared::services::gas::{GasStation, GasStationConfig};
+use exchange_shared::services::token::{PermitPolicy, PermitRelayer};
+use exchange_shared::services::wallet::paths::{guard_path_changes, path_change_allowed, DerivationPaths};
+use exchange_shared::services::wallet::rpc::HttpRpcClient;
+use exchange_shared::services::wallet::solana_rpc::SolanaRpcClient;
+use exchange_shared::services::reconciliation::{
+    OrphanOrderReconciler, ProviderReconciler, WalletAuditConfig, WalletAuditor,
+};
+use exchange_shared::services::pii::{pii_cipher, PiiRotationJob};
+use exchange_shared::services::retention::{AuthRetentionSweeper, RetentionPolicy};
+use exchange_shared::services::runtime_config::ConfigReloader;
+use exchange_shared::services::usage::{UsageCounters, UsageFlusher};
+use exchange_shared::services::sandbox::SandboxConfig;
+use exchange_shared::services::status_page::StatusSampler;
+use exchange_shared::services::exports::ExportWorker;
+use exchange_shared::services::backup::{BackupConfig, BackupService, BackupWorker};
+use exchange_shared::services::funnel::{FunnelPolicy, FunnelSubscriber, FunnelSweeper};
+use exchange_shared::services::fx::{FxConfig, FxSnapshotter};
+use exchange_shared::services::preflight::{Preflight, PreflightConfig};
+use exchange_shared::services::provider_payloads::{PayloadPolicy, ProviderPayloadSweeper};
+use exchange_shared::services::storage::S3Storage;
+use exchange_shared::services::swap_sla::{SlaPolicy, SwapSlaMonitor};
+use exchange_shared::services::telemetry;
+use exchange_shared::services::kill_switch::RpcHealthGuard;
+use exchange_shared::services::rpc::{load_rpc_config, RpcManager};
+use exchange_shared::services::warmup::{Warmup, WarmupConfig};
+use exchange_shared::services::watch_only::watch_only;
+use std::net::SocketAddr;
+use std::sync::Arc;
 
 #[tokio::main]
 async fn main() {
     dotenvy::dotenv().ok();
 
-    tracing_subscriber::registry()
-        .with(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| "exchange_shared=debug,tower_http=debug".into()),
-        )
-        .with(tracing_subscriber::fmt::layer())
-        .init();
+    // Held until main returns so buffered spans are flushed on shutdown
+    let _telemetry = telemetry::init_tracing();
+
+    // Check the whole configuration before anything starts, so a missing
+    // setting stops startup with one report instead of failing in a worker
+    let preflight_config = PreflightConfig::from_env().expect("Invalid preflight configuration");
+    let preflight = Preflight::new(preflight_config).run().await;
+    if preflight.has_errors() {
+        eprintln!("{}", preflight);
+        std::process::exit(1);
+    }
+    for warning in preflight.warnings() {
+        tracing::warn!("Configuration: {}: {}", warning.subject, warning.detail);
+    }
 
     // Load configuration
     let config = Config::from_env().expect("Failed to load environment configuration");
@@ -20,23 +76,408 @@ async fn main() {
     let db = init_db().await;
     tracing::info!("Connected to MySQL");
 
+    // Refuse to derive keys on a path other than the one existing addresses
+    // use. Recording the paths writes, and a watch-only deployment derives
+    // nothing, so it leaves the record to the deployment it audits.
+    if !watch_only() {
+        let derivation_paths = DerivationPaths::global();
+        guard_path_changes(&db, derivation_paths, path_change_allowed())
+            .await
+            .expect("HD derivation path check failed");
+    }
+
+    // Staging against test networks; invalid sandbox settings stop startup
+    let sandbox = SandboxConfig::global();
+    if sandbox.enabled {
+        let mut networks: Vec<_> = sandbox.networks.iter().map(|(m, n)| format!("{}={}", m, n.name)).collect();
+        networks.sort();
+        tracing::warn!("Sandbox mode: swaps run on test networks ({})", networks.join(", "));
+    }
+
+    // Rate limits and monitor polling pick up database and file overrides
+    // without a restart; the first reload runs before the server starts
+    let config_reloader = ConfigReloader::new(db.clone());
+    config_reloader.reload().await;
+    tokio::spawn(async move {
+        config_reloader.run().await;
+    });
+
     // Initialize Redis Service
     let redis_service = RedisService::new(&config.redis_url);
     tracing::info!("Connected to Redis");
 
     let jwt_service = JwtService::new(config.jwt_secret);
 
-    // Start blockchain listener in background
-    let listener_db = db.clone();
-    tokio::spawn(async move {
-        let listener = BlockchainListener::new(listener_db);
-        listener.run().await;
-    });
-    tracing::info!("Blockchain listener started");
+    // Swap lifecycle subscribers, started before anything can publish
+    let metrics = match MetricsRegistry::new() {
+        Ok(metrics) => Some(metrics),
+        Err(e) => {
+            tracing::warn!("Metrics disabled: {}", e);
+            None
+        }
+    };
+    if let Some(metrics) = &metrics {
+        // Provider clients are built all over and record through the global registry
+        metrics.install_global();
+        spawn_subscriber(MetricsSubscriber::new(db.clone(), metrics.clone()));
+    }
+    // The rest write deliveries, emails, revenue, reputation, funnel steps
+    // and the event stream
+    if !watch_only() {
+        let webhook_dispatcher = Arc::new(WebhookDispatcher::new(db.clone(), RetryConfig::default()));
+        spawn_subscriber(WebhookSubscriber::new(db.clone(), webhook_dispatcher));
+        spawn_subscriber(NotificationSubscriber::new(db.clone(), queued_email_sender(db.clone())));
+        spawn_subscriber(RevenueSubscriber::new(db.clone()));
+        spawn_subscriber(AddressReputationSubscriber::new(db.clone()));
+        spawn_subscriber(FunnelSubscriber::new(db.clone()));
+        // Durable copy of the lifecycle for fraud, analytics and other outside consumers
+        let stream_config = EventStreamConfig::from_env().expect("Invalid event stream configuration");
+        if stream_config.enabled {
+            spawn_subscriber(StreamSubscriber::new(redis_service.clone(), stream_config));
+        }
+    }
+    tracing::info!("Domain event subscribers started");
+
+    // Start blockchain listener shards in background; each scans only
+    // while this process leads it, so replicas split the shards between them.
+    // Detected deposits are written to swaps, so watch-only runs none.
+    let election = Arc::new(LeaderElection::from_env(redis_service.clone()));
+    if !watch_only() {
+        let listener_shards = shard_count_from_env().expect("Invalid listener sharding configuration");
+        for shard in Shard::all(listener_shards) {
+            let listener_db = db.clone();
+            let listener_election = election.clone();
+            let listener_metrics = metrics.clone();
+            tokio::spawn(async move {
+                let mut listener = BlockchainListener::new(listener_db)
+                    .with_shard(shard)
+                    .with_leader_election(listener_election);
+                if let Some(metrics) = listener_metrics {
+                    listener = listener.with_metrics(metrics);
+                }
+                listener.run().await;
+            });
+        }
+        tracing::info!("Blockchain listener started with {} shards", listener_shards);
+    }
+
+    // Workers that broadcast transactions or place provider orders; a
+    // watch-only deployment must never move funds
+    if watch_only() {
+        tracing::warn!("Watch-only mode: mutating endpoints and background writers are disabled");
+    } else {
+        spawn_transaction_workers(&db, &redis_service, &config.wallet_mnemonic, metrics.clone());
+    }
+
+    // Track RPC health and halt trading on chains whose endpoints go
+    // unhealthy; halts are written, so watch-only only tracks
+    let mut rpc_manager = None;
+    match std::env::var("RPC_CONFIG_PATH") {
+        Ok(path) => match load_rpc_config(&path) {
+            Ok(configs) => {
+                let manager = Arc::new(RpcManager::new(configs));
+                manager.install_global();
+                tokio::spawn(manager.clone().health_check_loop());
+                if !watch_only() {
+                    let guard = RpcHealthGuard::new(db.clone(), manager.clone());
+                    tokio::spawn(async move {
+                        guard.run().await;
+                    });
+                    tracing::info!("RPC health kill-switch started");
+                }
+                rpc_manager = Some(manager);
+            }
+            Err(e) => tracing::warn!("RPC health kill-switch disabled: failed to load {}: {}", path, e),
+        },
+        Err(_) => tracing::info!("RPC health kill-switch disabled (RPC_CONFIG_PATH not set)"),
+    }
+
+    // Sweepers, reconcilers, snapshots and other workers that write; a
+    // watch-only deployment leaves production data to the deployment it
+    // audits
+    if !watch_only() {
+        spawn_maintenance_workers(&db, &redis_service, &election, metrics);
+    }
+
+    // Open connections, prepare statements and fill caches before taking traffic
+    let warmup_config = WarmupConfig::from_env().expect("Invalid startup warm-up configuration");
+    let mut warmup = Warmup::new(db.clone(), redis_service.clone(), config.wallet_mnemonic.clone())
+        .with_config(warmup_config);
+    if let Some(manager) = rpc_manager {
+        warmup = warmup.with_rpc_manager(manager);
+    }
+    warmup.run().await;
 
     let app = exchange_shared::create_app(db, redis_service, jwt_service, config.wallet_mnemonic).await;
 
     let listener = tokio::net::TcpListener::bind("0.0.0.0:3000").await.unwrap();
     tracing::info!("Server running on http://localhost:3000");
-    axum::serve(listener, app).await.unwrap();
+    axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>())
+        .await
+        .unwrap();
+}
+
+/// Withdrawals, gift card payments, late deposit refunds, recurring swaps, price-triggered orders and swap payouts
+fn spawn_transaction_workers(
+    db: &DbPool,
+    redis_service: &RedisService,
+    wallet_mnemonic: &str,
+    metrics: Option<Arc<MetricsRegistry>>,
+) {
+    // Execute approved custodial withdrawals in background
+    let withdrawal_db = db.clone();
+    let withdrawal_seed = wallet_mnemonic.to_string();
+    tokio::spawn(async move {
+        let processor = WithdrawalProcessor::new(withdrawal_db, withdrawal_seed);
+        processor.run().await;
+    });
+    tracing::info!("Withdrawal processor started");
+
+    // Pay funded gift card orders to the provider
+    let gift_card_db = db.clone();
+    let gift_card_seed = wallet_mnemonic.to_string();
+    tokio::spawn(async move {
+        let payer = GiftCardPayer::new(gift_card_db, gift_card_seed);
+        payer.run().await;
+    });
+    tracing::info!("Gift card payer started");
+
+    // Send deposits that arrived after their swap expired back to the user
+    let refund_db = db.clone();
+    let refund_seed = wallet_mnemonic.to_string();
+    tokio::spawn(async move {
+        let refunder = LateDepositRefunder::new(refund_db, refund_seed);
+        refunder.run().await;
+    });
+    tracing::info!("Late deposit refunder started");
+
+    // Execute recurring swap schedules in background
+    let schedule_db = db.clone();
+    let schedule_redis = redis_service.clone();
+    let schedule_seed = wallet_mnemonic.to_string();
+    tokio::spawn(async move {
+        let worker = ScheduleWorker::new(schedule_db, schedule_redis, schedule_seed);
+        worker.run().await;
+    });
+    tracing::info!("Schedule worker started");
+
+    // Watch quotes for price-triggered swap orders in background
+    let order_db = db.clone();
+    let order_redis = redis_service.clone();
+    let order_seed = wallet_mnemonic.to_string();
+    tokio::spawn(async move {
+        let watcher = OrderWatcher::new(order_db, order_redis, order_seed);
+        watcher.run().await;
+    });
+    tracing::info!("Order watcher started");
+
+    // Poll swap status and hand funded swaps to a bounded payout pool
+    let payout_config = PayoutExecutorConfig::from_env().expect("Invalid payout executor configuration");
+    let gas_station_config = GasStationConfig::from_env().expect("Invalid gas station configuration");
+    let payout_batch_config = PayoutBatchConfig::from_env().expect("Invalid payout batching configuration");
+    let gas_station_rpc = evm_rpc_url("ethereum").unwrap_or_else(|| "http://localhost:8545".to_string());
+    let hot_wallet_provider = Arc::new(HttpRpcClient::new(gas_station_rpc));
+    let gas_station = GasStation::new(db.clone(), hot_wallet_provider.clone(), wallet_mnemonic.to_string())
+        .with_config(gas_station_config);
+    let mut payout_handler =
+        SwapPayoutHandler::new(db.clone(), wallet_mnemonic.to_string()).with_gas_station(Arc::new(gas_station));
+    if PermitPolicy::global().enabled {
+        let relayer = PermitRelayer::new(db.clone(), hot_wallet_provider.clone(), wallet_mnemonic.to_string());
+        payout_handler = payout_handler.with_permit_relayer(Arc::new(relayer));
+        tracing::info!("Token payouts use EIP-2612/Permit2 permits where supported");
+    }
+    if !payout_batch_config.contracts.is_empty() {
+        let chains: Vec<_> = payout_batch_config.contracts.keys().cloned().collect();
+        let batcher = PayoutBatcher::new(db.clone(), hot_wallet_provider, wallet_mnemonic.to_string())
+            .with_config(payout_batch_config);
+        payout_handler = payout_handler.with_payout_batcher(Arc::new(batcher));
+        tracing::info!("Payout batching enabled on {}", chains.join(", "));
+    }
+    let solana_rpc = std::env::var("SOLANA_PRIMARY_RPC").ok().filter(|v| !v.trim().is_empty());
+    if let Some(url) = &solana_rpc {
+        payout_handler = payout_handler.with_solana_provider(Arc::new(SolanaRpcClient::new(url.trim().to_string())));
+    }
+    let payout_handler = Arc::new(payout_handler);
+    let mut payout_executor =
+        PayoutExecutor::new(payout_handler, payout_config).with_guard(PayoutGuard::new(db.clone()));
+    if let Some(metrics) = metrics {
+        payout_executor = payout_executor.with_metrics(metrics);
+    }
+    payout_executor.install_global();
+    let executor = payout_executor.clone();
+    tokio::spawn(async move {
+        executor.run().await;
+    });
+
+    let monitor_db = db.clone();
+    let monitor_redis = redis_service.clone();
+    let monitor_seed = wallet_mnemonic.to_string();
+    tokio::spawn(async move {
+        let engine = MonitorEngine::new(monitor_db, monitor_redis, monitor_seed)
+            .with_payout_executor(payout_executor);
+        engine.run().await;
+    });
+    tracing::info!("Swap monitor and payout executor started");
+
+    // Follow broadcast payouts on chain until they are final
+    let mut tracker = TxTracker::new(db.clone());
+    if let Some(url) = evm_rpc_url("ethereum") {
+        let provider = simulated(Arc::new(HttpRpcClient::new(url)));
+        let finality = tracker.config().evm_confirmations;
+        tracker = tracker.with_source("ethereum", Arc::new(EvmTxStatus::new(provider, finality)));
+    }
+    if let Some(url) = solana_rpc {
+        let provider = Arc::new(SolanaRpcClient::new(url.trim().to_string()));
+        tracker = tracker.with_source("solana", Arc::new(SolanaTxStatus::new(provider)));
+    }
+    tokio::spawn(async move {
+        tracker.run().await;
+    });
+    tracing::info!("Payout transaction tracker started");
+}
+
+/// Reconcilers, sweepers, snapshots, exports, backups and audits; every one
+/// of them writes, so none runs in a watch-only deployment
+fn spawn_maintenance_workers(
+    db: &DbPool,
+    redis_service: &RedisService,
+    election: &Arc<LeaderElection>,
+    metrics: Option<Arc<MetricsRegistry>>,
+) {
+    // Settle provider orders left behind by interrupted swap creation
+    let reconciler = OrphanOrderReconciler::new(db.clone());
+    tokio::spawn(async move {
+        reconciler.run().await;
+    });
+    tracing::info!("Provider order reconciler started");
+
+    // Nightly diff of recent swaps against the provider's records
+    let provider_reconciler = ProviderReconciler::new(db.clone());
+    tokio::spawn(async move {
+        provider_reconciler.run().await;
+    });
+
+    // Move PII columns onto the active encryption key
+    if let Some(cipher) = pii_cipher() {
+        let rotation = PiiRotationJob::new(db.clone(), cipher.clone());
+        tokio::spawn(async move {
+            rotation.run().await;
+        });
+        tracing::info!("PII key rotation job started (active key v{})", cipher.active_version());
+    }
+
+    // Scrub and purge expired verification, reset and refresh tokens
+    let retention_policy = RetentionPolicy::from_env().expect("Invalid auth retention configuration");
+    let mut retention = AuthRetentionSweeper::new(db.clone(), retention_policy);
+    if let Some(metrics) = metrics {
+        retention = retention.with_metrics(metrics);
+    }
+    tokio::spawn(async move {
+        retention.run().await;
+    });
+    tracing::info!("Auth retention sweeper started");
+
+    // Purge raw provider payloads past their retention window
+    let payload_policy = PayloadPolicy::from_env().expect("Invalid provider payload configuration");
+    let payload_sweeper = ProviderPayloadSweeper::new(db.clone(), payload_policy);
+    tokio::spawn(async move {
+        payload_sweeper.run().await;
+    });
+    tracing::info!("Provider payload sweeper started");
+
+    // Flag swaps stuck in one status past its SLA and escalate them
+    let sla_policy = SlaPolicy::from_env().expect("Invalid swap SLA configuration");
+    let sla_monitor = SwapSlaMonitor::new(db.clone(), sla_policy);
+    tokio::spawn(async move {
+        sla_monitor.run().await;
+    });
+    tracing::info!("Swap SLA monitor started");
+
+    // Close out quotes that never became swaps so the funnel shows the drop-off
+    let funnel_policy = FunnelPolicy::from_env().expect("Invalid funnel configuration");
+    let funnel_sweeper = FunnelSweeper::new(db.clone(), funnel_policy);
+    tokio::spawn(async move {
+        funnel_sweeper.run().await;
+    });
+    tracing::info!("Swap funnel sweeper started");
+
+    // Probe providers for the limits behind /swap/pairs/matrix
+    let liquidity_refresher = PairLiquidityRefresher::new(db.clone(), Arc::new(TrocadorLiquiditySource::from_env()))
+        .with_redis(redis_service.clone());
+    tokio::spawn(async move {
+        liquidity_refresher.run().await;
+    });
+    tracing::info!("Pair liquidity refresher started");
+
+    // Daily FX rates and crypto USD prices for valued reports
+    match FxConfig::from_env() {
+        Ok(config) => {
+            let fx_snapshotter = FxSnapshotter::new(db.clone(), reqwest::Client::new(), config);
+            tokio::spawn(async move {
+                fx_snapshotter.run().await;
+            });
+        }
+        Err(e) => tracing::warn!("FX snapshots disabled: {}", e),
+    }
+
+    // Copy per-user API usage counters from Redis into MySQL
+    let usage_flusher = UsageFlusher::new(db.clone(), UsageCounters::new(redis_service.clone()));
+    tokio::spawn(async move {
+        usage_flusher.run().await;
+    });
+    tracing::info!("API usage flusher started");
+
+    // Deliver queued email
+    let email_outbox = EmailOutboxWorker::new(db.clone(), email_sender_from_env());
+    tokio::spawn(async move {
+        email_outbox.run().await;
+    });
+    tracing::info!("Email outbox worker started");
+
+    // Build queued CSV exports into object storage
+    match S3Storage::from_env() {
+        Ok(storage) => {
+            let export_worker = ExportWorker::new(db.clone(), Arc::new(storage));
+            tokio::spawn(async move {
+                export_worker.run().await;
+            });
+            tracing::info!("Export worker started");
+        }
+        Err(e) => tracing::warn!("Export worker disabled: {}", e),
+    }
+
+    // Nightly encrypted snapshot of swaps, the ledger and wallet indexes
+    let backup_config = BackupConfig::from_env().expect("Invalid backup configuration");
+    match (backup_config.cipher, S3Storage::from_env()) {
+        (None, _) => tracing::warn!("Backups disabled: BACKUP_ENCRYPTION_KEY not set"),
+        (Some(_), Err(e)) => tracing::warn!("Backups disabled: {}", e),
+        (Some(cipher), Ok(storage)) => {
+            let fingerprint = cipher.fingerprint().to_string();
+            let service = BackupService::new(db.clone(), Arc::new(storage), cipher, &backup_config.prefix);
+            let backup_worker = BackupWorker::new(service, backup_config.hour).with_leader_election(election.clone());
+            tokio::spawn(async move {
+                backup_worker.run().await;
+            });
+            tracing::info!(
+                "Backup worker started: daily at {:02}:00 UTC with key {}",
+                backup_config.hour, fingerprint
+            );
+        }
+    }
+
+    // Nightly check of every allocated HD index for funds nothing accounts for
+    let audit_config = WalletAuditConfig::from_env().expect("Invalid wallet audit configuration");
+    let audit_hour = audit_config.hour;
+    let wallet_auditor = WalletAuditor::new(db.clone(), audit_config).with_leader_election(election.clone());
+    tokio::spawn(async move {
+        wallet_auditor.run().await;
+    });
+    tracing::info!("Wallet audit started: daily at {:02}:00 UTC", audit_hour);
+
+    // Sample chain health for the uptime on /status/public
+    let status_sampler = StatusSampler::new(db.clone());
+    tokio::spawn(async move {
+        status_sampler.run().await;
+    });
+    tracing::info!("Status sampler started");
 }
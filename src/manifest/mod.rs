@@ -0,0 +1,658 @@
+//! Machine-readable description of the public HTTP API.
+//!
+//! Every route mounted by `create_app` is listed in [`routes`] together with
+//! the types its handler extracts and returns. [`route_manifest`] turns that
+//! list into JSON schemas (via `schemars`) so the `codegen` binary can emit
+//! typed clients that stay in step with the server structs. The same list
+//! is what [`route_access`] consults, so the auth a route is documented with
+//! is the auth `create_app` enforces, and module routers mount their
+//! handlers through [`DeclaredRoutes`], so a handler is only ever served at
+//! the method and path declared for it.
+//!
+//! Paths are relative to the version prefix (`/v1`). A route declared for a
+//! later version replaces the earlier one at the same path for that version
+//! and those after it; see [`crate::services::api_version`].
+
+pub mod routes;
+pub mod rust_client;
+pub mod typescript;
+
+use axum::{
+    handler::Handler,
+    routing::{on, MethodFilter},
+    Router,
+};
+use schemars::{JsonSchema, Schema, SchemaGenerator};
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::collections::BTreeSet;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use crate::services::api_version::{split_version, ApiVersion};
+use crate::services::jwt::{Scope, Scopes};
+use crate::AppState;
+
+/// Prefix used by schemars for references into `definitions`
+pub const DEFINITIONS_REF_PREFIX: &str = "#/$defs/";
+
+type SchemaFn = fn(&mut SchemaGenerator) -> Schema;
+
+fn subschema<T: JsonSchema>(generator: &mut SchemaGenerator) -> Schema {
+    generator.subschema_for::<T>()
+}
+
+// =============================================================================
+// ROUTE DEFINITIONS
+// =============================================================================
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum AuthRequirement {
+    /// Public; any token sent is ignored
+    None,
+    /// A bearer token is accepted but not required
+    Optional,
+    /// A valid token for an account in good standing (401/403 otherwise)
+    User,
+    /// As `User`, for an administrator
+    Admin,
+}
+
+/// A single route as declared in [`routes::routes`]. Schemas are captured as
+/// function pointers and only generated when the manifest is built.
+#[derive(Clone)]
+pub struct Route {
+    name: &'static str,
+    method: &'static str,
+    path: &'static str,
+    auth: AuthRequirement,
+    scope: Option<Scope>,
+    any_scope: bool,
+    internal: bool,
+    version: ApiVersion,
+    success_status: u16,
+    query: Option<SchemaFn>,
+    body: Option<SchemaFn>,
+    response: Option<SchemaFn>,
+    error: Option<SchemaFn>,
+}
+
+impl Route {
+    fn new(method: &'static str, name: &'static str, path: &'static str) -> Self {
+        Self {
+            name,
+            method,
+            path,
+            auth: AuthRequirement::None,
+            scope: None,
+            any_scope: false,
+            internal: false,
+            version: ApiVersion::V1,
+            success_status: 200,
+            query: None,
+            body: None,
+            response: None,
+            error: None,
+        }
+    }
+
+    pub fn get(name: &'static str, path: &'static str) -> Self {
+        Self::new("GET", name, path)
+    }
+
+    pub fn post(name: &'static str, path: &'static str) -> Self {
+        Self::new("POST", name, path)
+    }
+
+    pub fn put(name: &'static str, path: &'static str) -> Self {
+        Self::new("PUT", name, path)
+    }
+
+    pub fn patch(name: &'static str, path: &'static str) -> Self {
+        Self::new("PATCH", name, path)
+    }
+
+    pub fn delete(name: &'static str, path: &'static str) -> Self {
+        Self::new("DELETE", name, path)
+    }
+
+    pub fn auth(mut self, auth: AuthRequirement) -> Self {
+        self.auth = auth;
+        self
+    }
+
+    /// Scope that lets a downscoped token call the route; without one the
+    /// route needs a full-access token
+    pub fn scope(mut self, scope: Scope) -> Self {
+        self.scope = Some(scope);
+        self
+    }
+
+    /// Accept a token whatever its scopes; the handler checks them itself
+    pub fn any_scope(mut self) -> Self {
+        self.any_scope = true;
+        self
+    }
+
+    /// Served and enforced, but left out of the manifest and the generated
+    /// clients: provider callbacks, sockets and dev-only endpoints
+    pub fn internal(mut self) -> Self {
+        self.internal = true;
+        self
+    }
+
+    /// First version served by this handler; earlier versions keep the
+    /// route declared before it
+    pub fn version(mut self, version: ApiVersion) -> Self {
+        self.version = version;
+        self
+    }
+
+    pub fn status(mut self, status: u16) -> Self {
+        self.success_status = status;
+        self
+    }
+
+    pub fn query<T: JsonSchema>(mut self) -> Self {
+        self.query = Some(subschema::<T>);
+        self
+    }
+
+    pub fn body<T: JsonSchema>(mut self) -> Self {
+        self.body = Some(subschema::<T>);
+        self
+    }
+
+    pub fn response<T: JsonSchema>(mut self) -> Self {
+        self.response = Some(subschema::<T>);
+        self
+    }
+
+    pub fn error<T: JsonSchema>(mut self) -> Self {
+        self.error = Some(subschema::<T>);
+        self
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    pub fn method(&self) -> &'static str {
+        self.method
+    }
+
+    pub fn path(&self) -> &'static str {
+        self.path
+    }
+
+    pub fn auth_requirement(&self) -> AuthRequirement {
+        self.auth
+    }
+
+    /// Version whose router mounts the handler
+    pub fn api_version(&self) -> ApiVersion {
+        self.version
+    }
+
+    /// Whether a token granting `scopes` may call the route, once it is known
+    /// to be valid
+    pub fn admits(&self, scopes: &Scopes) -> bool {
+        match self.scope {
+            _ if self.any_scope => true,
+            Some(scope) => scopes.contains(scope),
+            None => scopes.is_full(),
+        }
+    }
+}
+
+// =============================================================================
+// ROUTE ACCESS
+// =============================================================================
+
+/// The declared route a request is for, matched the way the router does:
+/// `{param}` segments match any one segment and static segments win. HEAD
+/// is matched as GET. A version prefix picks that version's routes;
+/// unprefixed paths are matched as v1. Returns None for paths the manifest
+/// doesn't list, which nothing mounts.
+pub fn route_access(method: &str, path: &str) -> Option<&'static Route> {
+    let (version, path) = split_version(path);
+    match_route(declared_routes(), version.unwrap_or(ApiVersion::V1), method, path)
+}
+
+fn declared_routes() -> &'static [Route] {
+    static ROUTES: OnceLock<Vec<Route>> = OnceLock::new();
+    ROUTES.get_or_init(routes::routes)
+}
+
+/// Best match among the routes `version` serves; for the same path the
+/// latest declaration wins
+fn match_route<'a>(routes: &'a [Route], version: ApiVersion, method: &str, path: &str) -> Option<&'a Route> {
+    let method = if method == "HEAD" { "GET" } else { method };
+    let segments: Vec<&str> = path.trim_end_matches('/').split('/').collect();
+
+    let mut best: Option<(&'a Route, (usize, ApiVersion))> = None;
+    for route in routes.iter().filter(|r| r.method == method && r.version <= version) {
+        let Some(specificity) = match_path(route.path, &segments) else {
+            continue;
+        };
+        let rank = (specificity, route.version);
+        if best.is_none_or(|(_, b)| rank > b) {
+            best = Some((route, rank));
+        }
+    }
+    best.map(|(route, _)| route)
+}
+
+/// Number of static segments when `template` matches, None otherwise
+fn match_path(template: &str, segments: &[&str]) -> Option<usize> {
+    let template: Vec<&str> = template.split('/').collect();
+    if template.len() != segments.len() {
+        return None;
+    }
+    let mut specificity = 0;
+    for (expected, actual) in template.iter().zip(segments) {
+        if expected.starts_with('{') && expected.ends_with('}') {
+            if actual.is_empty() {
+                return None;
+            }
+        } else if expected == actual {
+            specificity += 1;
+        } else {
+            return None;
+        }
+    }
+    Some(specificity)
+}
+
+// =============================================================================
+// MOUNTING
+// =============================================================================
+
+/// Names of the routes mounted so far through [`DeclaredRoutes`]
+static MOUNTED: Mutex<BTreeSet<&'static str>> = Mutex::new(BTreeSet::new());
+
+/// How module routers mount handlers: by the name a route is declared with
+/// in [`routes::routes`], at its declared method and path (relative to the
+/// version prefix). Mounting a name the manifest doesn't declare panics at
+/// startup.
+pub trait DeclaredRoutes {
+    fn declared<H, T>(self, name: &'static str, handler: H) -> Self
+    where
+        H: Handler<T, Arc<AppState>>,
+        T: 'static;
+}
+
+impl DeclaredRoutes for Router<Arc<AppState>> {
+    fn declared<H, T>(self, name: &'static str, handler: H) -> Self
+    where
+        H: Handler<T, Arc<AppState>>,
+        T: 'static,
+    {
+        let route = declared_routes()
+            .iter()
+            .find(|route| route.name == name)
+            .unwrap_or_else(|| panic!("Route {} is not declared in the manifest", name));
+        let filter = match route.method {
+            "GET" => MethodFilter::GET,
+            "POST" => MethodFilter::POST,
+            "PUT" => MethodFilter::PUT,
+            "PATCH" => MethodFilter::PATCH,
+            "DELETE" => MethodFilter::DELETE,
+            other => panic!("Route {} has unsupported method {}", name, other),
+        };
+        MOUNTED.lock().unwrap_or_else(|e| e.into_inner()).insert(name);
+        self.route(route.path, on(filter, handler))
+    }
+}
+
+/// Names of every declared route a router has mounted in this process, to
+/// check the app against [`routes::routes`]
+pub fn mounted_routes() -> BTreeSet<&'static str> {
+    MOUNTED.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+// =============================================================================
+// MANIFEST
+// =============================================================================
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RouteSpec {
+    /// camelCase operation name, used for generated client methods
+    pub name: String,
+    pub method: String,
+    /// Full path including the nest prefix, with `{param}` placeholders
+    pub path: String,
+    pub path_params: Vec<String>,
+    /// Version that introduced the handler now serving the route
+    pub since: ApiVersion,
+    pub auth: AuthRequirement,
+    /// e.g. `history:read`, when a downscoped token is accepted
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+    pub success_status: u16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub query: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<Value>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RouteManifest {
+    pub version: String,
+    pub api_version: ApiVersion,
+    /// Prefix every route path is mounted under
+    pub base_path: String,
+    pub routes: Vec<RouteSpec>,
+    /// Named schemas referenced from routes as `#/$defs/<Name>`
+    pub definitions: Map<String, Value>,
+}
+
+impl RouteManifest {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+
+    /// Look up a `$ref` target; returns the schema unchanged if it is not a reference
+    pub fn resolve<'a>(&'a self, schema: &'a Value) -> &'a Value {
+        match ref_name(schema) {
+            Some(name) => self.definitions.get(name).unwrap_or(schema),
+            None => schema,
+        }
+    }
+}
+
+/// Build the manifest of the stable API (v1)
+pub fn route_manifest() -> RouteManifest {
+    route_manifest_for(ApiVersion::V1)
+}
+
+/// Build the manifest of every route `api_version` serves, in
+/// [`routes::routes`] order
+pub fn route_manifest_for(api_version: ApiVersion) -> RouteManifest {
+    let mut generator = SchemaGenerator::default();
+    let mut generate = |f: Option<SchemaFn>| f.map(|f| f(&mut generator).to_value());
+
+    let declared = routes::routes();
+    let served: Vec<&Route> = declared
+        .iter()
+        .filter(|route| {
+            // Replaced by a later declaration this version also serves
+            !route.internal
+                && route.version <= api_version
+                && !declared.iter().any(|other| {
+                    other.method == route.method
+                        && other.path == route.path
+                        && other.version > route.version
+                        && other.version <= api_version
+                })
+        })
+        .collect();
+
+    let routes = served
+        .into_iter()
+        .map(|route| RouteSpec {
+            name: route.name.to_string(),
+            method: route.method.to_string(),
+            path: route.path.to_string(),
+            path_params: path_params(route.path),
+            since: route.version,
+            auth: route.auth,
+            scope: route.scope.map(|s| s.as_str().to_string()),
+            success_status: route.success_status,
+            query: generate(route.query),
+            body: generate(route.body),
+            response: generate(route.response),
+            error: generate(route.error),
+        })
+        .collect();
+
+    RouteManifest {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        api_version,
+        base_path: api_version.prefix().to_string(),
+        routes,
+        definitions: generator.take_definitions(true),
+    }
+}
+
+// =============================================================================
+// HELPERS
+// =============================================================================
+
+/// Names of the `{param}` segments in a route path
+pub fn path_params(path: &str) -> Vec<String> {
+    path.split('/')
+        .filter_map(|segment| segment.strip_prefix('{')?.strip_suffix('}'))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Definition name of a `{"$ref": "#/$defs/Name"}` schema
+pub fn ref_name(schema: &Value) -> Option<&str> {
+    schema.get("$ref")?.as_str()?.strip_prefix(DEFINITIONS_REF_PREFIX)
+}
+
+/// Instance types of a schema with `null` removed, plus whether `null` was present
+pub fn schema_types(schema: &Value) -> (Vec<&str>, bool) {
+    let types: Vec<&str> = match schema.get("type") {
+        Some(Value::String(t)) => vec![t.as_str()],
+        Some(Value::Array(ts)) => ts.iter().filter_map(Value::as_str).collect(),
+        _ => Vec::new(),
+    };
+    let nullable = types.contains(&"null");
+    (types.into_iter().filter(|t| *t != "null").collect(), nullable)
+}
+
+/// String values of a unit-variant enum, in either of the shapes schemars emits
+pub fn string_enum_values(schema: &Value) -> Option<Vec<String>> {
+    if let Some(values) = schema.get("enum").and_then(Value::as_array) {
+        return values.iter().map(|v| v.as_str().map(str::to_string)).collect();
+    }
+    if let Some(value) = schema.get("const").and_then(Value::as_str) {
+        return Some(vec![value.to_string()]);
+    }
+    let variants = schema.get("oneOf").or_else(|| schema.get("anyOf"))?.as_array()?;
+    let mut values = Vec::new();
+    for variant in variants {
+        values.extend(string_enum_values(variant)?);
+    }
+    Some(values)
+}
+
+/// The non-null branch of an `anyOf: [T, null]` schema
+pub fn non_null_variant(schema: &Value) -> Option<&Value> {
+    let variants = schema.get("anyOf")?.as_array()?;
+    if variants.len() != 2 {
+        return None;
+    }
+    let is_null = |v: &Value| v.get("type").and_then(Value::as_str) == Some("null");
+    match (is_null(&variants[0]), is_null(&variants[1])) {
+        (false, true) => Some(&variants[0]),
+        (true, false) => Some(&variants[1]),
+        _ => None,
+    }
+}
+
+pub fn description(schema: &Value) -> Option<&str> {
+    schema.get("description").and_then(Value::as_str)
+}
+
+/// `create_swap` / `swap-status` -> `CreateSwap` / `SwapStatus`
+pub fn pascal_case(name: &str) -> String {
+    name.split(['_', '-', ' '])
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect::<String>(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+/// `getSwapStatus` -> `get_swap_status`
+pub fn snake_case(name: &str) -> String {
+    let mut out = String::with_capacity(name.len() + 4);
+    for (i, c) in name.chars().enumerate() {
+        if c.is_uppercase() {
+            if i > 0 {
+                out.push('_');
+            }
+            out.extend(c.to_lowercase());
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_path_params() {
+        assert_eq!(path_params("/swap/{id}"), vec!["id"]);
+        assert_eq!(path_params("/admin/withdrawals/{id}/approve"), vec!["id"]);
+        assert!(path_params("/swap/create").is_empty());
+    }
+
+    #[test]
+    fn test_case_conversion() {
+        assert_eq!(snake_case("getSwapStatus"), "get_swap_status");
+        assert_eq!(pascal_case("payout_to_balance"), "PayoutToBalance");
+        assert_eq!(pascal_case("swap_transfer-in"), "SwapTransferIn");
+    }
+
+    #[test]
+    fn test_manifest_names_are_unique() {
+        let manifest = route_manifest();
+        let mut names = HashSet::new();
+        let mut endpoints = HashSet::new();
+        for route in &manifest.routes {
+            assert!(names.insert(route.name.clone()), "duplicate name {}", route.name);
+            assert!(
+                endpoints.insert((route.method.clone(), route.path.clone())),
+                "duplicate route {} {}",
+                route.method,
+                route.path
+            );
+        }
+    }
+
+    #[test]
+    fn test_manifest_refs_resolve() {
+        let manifest = route_manifest();
+        for route in &manifest.routes {
+            assert!(route.error.is_some(), "{} has no error schema", route.name);
+            for schema in [&route.query, &route.body, &route.response].into_iter().flatten() {
+                if let Some(name) = ref_name(schema) {
+                    assert!(manifest.definitions.contains_key(name), "missing definition {}", name);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_query_schemas_are_objects() {
+        let manifest = route_manifest();
+        for route in &manifest.routes {
+            if let Some(query) = &route.query {
+                let resolved = manifest.resolve(query);
+                assert!(resolved.get("properties").is_some(), "{} query is not an object", route.name);
+            }
+        }
+    }
+
+    #[test]
+    fn test_route_access_matches_like_the_router() {
+        let route = route_access("GET", "/swap/rates").unwrap();
+        assert_eq!(route.name, "getRates");
+        let route = route_access("GET", "/swap/abc-123").unwrap();
+        assert_eq!(route.name, "getSwapStatus");
+        let route = route_access("HEAD", "/admin/revenue/").unwrap();
+        assert_eq!(route.auth_requirement(), AuthRequirement::Admin);
+        assert!(route_access("GET", "/swap").is_none());
+        assert!(route_access("GET", "/nowhere/abc").is_none());
+
+        // Versioned paths match the same routes
+        assert_eq!(route_access("GET", "/v1/swap/rates").unwrap().name, "getRates");
+        assert_eq!(route_access("GET", "/v2/swap/abc-123").unwrap().name, "getSwapStatus");
+    }
+
+    #[test]
+    fn test_later_versions_replace_routes() {
+        let routes = vec![
+            Route::get("getSwap", "/swap/{id}"),
+            Route::get("getRates", "/swap/rates"),
+            Route::get("getSwapV2", "/swap/{id}").version(ApiVersion::V2).auth(AuthRequirement::User),
+        ];
+        let v1 = match_route(&routes, ApiVersion::V1, "GET", "/swap/abc").unwrap();
+        assert_eq!(v1.name, "getSwap");
+        let v2 = match_route(&routes, ApiVersion::V2, "GET", "/swap/abc").unwrap();
+        assert_eq!(v2.name, "getSwapV2");
+        assert_eq!(v2.api_version(), ApiVersion::V2);
+        // Static segments still win over a newer template
+        let rates = match_route(&routes, ApiVersion::V2, "GET", "/swap/rates").unwrap();
+        assert_eq!(rates.name, "getRates");
+    }
+
+    #[test]
+    fn test_manifest_per_version() {
+        let v1 = route_manifest();
+        assert_eq!(v1.base_path, "/v1");
+        assert!(v1.routes.iter().all(|r| r.since == ApiVersion::V1));
+
+        // v2 serves every v1 endpoint it doesn't replace, once
+        let v2 = route_manifest_for(ApiVersion::V2);
+        assert_eq!(v2.base_path, "/v2");
+        let endpoints: HashSet<_> = v2.routes.iter().map(|r| (r.method.clone(), r.path.clone())).collect();
+        assert_eq!(endpoints.len(), v2.routes.len());
+        for route in &v1.routes {
+            assert!(endpoints.contains(&(route.method.clone(), route.path.clone())), "v2 lost {}", route.name);
+        }
+    }
+
+    #[test]
+    fn test_scoped_routes_require_a_token() {
+        for route in routes::routes() {
+            if route.scope.is_some() || route.any_scope {
+                assert_ne!(route.auth, AuthRequirement::None, "{} names a scope but is public", route.name);
+            }
+        }
+    }
+
+    #[test]
+    fn test_route_admits_scopes() {
+        let history = Scopes::parse("history:read").unwrap();
+        let user = Route::get("a", "/a").auth(AuthRequirement::User);
+        assert!(!user.admits(&history));
+        assert!(user.admits(&Scopes::all()));
+        assert!(user.clone().scope(Scope::HistoryRead).admits(&history));
+        assert!(!user.clone().scope(Scope::SwapCreate).admits(&history));
+        assert!(user.any_scope().admits(&history));
+    }
+
+    #[test]
+    fn test_string_enum_values() {
+        let plain = serde_json::json!({ "type": "string", "enum": ["fixed", "floating"] });
+        assert_eq!(string_enum_values(&plain).unwrap(), vec!["fixed", "floating"]);
+
+        let documented = serde_json::json!({
+            "oneOf": [
+                { "type": "string", "const": "daily", "description": "Every day" },
+                { "type": "string", "enum": ["weekly", "monthly"] }
+            ]
+        });
+        assert_eq!(string_enum_values(&documented).unwrap(), vec!["daily", "weekly", "monthly"]);
+
+        let object = serde_json::json!({ "type": "object", "properties": {} });
+        assert!(string_enum_values(&object).is_none());
+    }
+}
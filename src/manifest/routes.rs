@@ -0,0 +1,823 @@
+//! Route table behind the routers in `crate::modules::*::routes`, which
+//! mount each handler by its name here. A route not declared here can't be
+//! mounted, and `create_app` answers 404 for any path this table doesn't
+//! list. Routes marked `internal` are served but left out of the generated
+//! clients.
+//!
+//! The `auth` and `scope` declared here are enforced: `create_app` refuses
+//! requests to `User` and `Admin` routes before the handler runs, whatever
+//! extractor the handler uses. A route the handler protects must be declared
+//! with the same requirement, or its clients and the server disagree.
+
+use super::{AuthRequirement, Route};
+use crate::modules::account::schema as account;
+use crate::modules::admin::schema as admin;
+use crate::modules::address_book::schema as address_book;
+use crate::modules::analytics::schema as analytics;
+use crate::modules::auth::schema as auth;
+use crate::modules::backups::schema as backups;
+use crate::modules::balances::schema as balances;
+use crate::modules::commissions::schema as commissions;
+use crate::modules::email::schema as email;
+use crate::modules::exports::schema as exports;
+use crate::modules::gift_cards::schema as gift_cards;
+use crate::modules::graphql::schema as graphql;
+use crate::modules::halts::schema as halts;
+use crate::modules::jobs::schema as jobs;
+use crate::modules::kyc::schema as kyc;
+use crate::modules::moderation::schema as moderation;
+use crate::modules::orders::schema as orders;
+use crate::modules::payout_guard::schema as payout_guard;
+use crate::modules::promotions::schema as promotions;
+use crate::modules::reconciliation::schema as reconciliation;
+use crate::modules::recovery::schema as recovery;
+use crate::modules::schedules::schema as schedules;
+use crate::modules::status::schema as status;
+use crate::modules::swap::schema as swap;
+use crate::modules::webhooks::schema as webhooks;
+use crate::services::jwt::Scope;
+
+pub fn routes() -> Vec<Route> {
+    let mut routes = Vec::new();
+    routes.extend(auth_routes());
+    routes.extend(swap_routes());
+    routes.extend(schedule_routes());
+    routes.extend(order_routes());
+    routes.extend(gift_card_routes());
+    routes.extend(balance_routes());
+    routes.extend(address_book_routes());
+    routes.extend(account_routes());
+    routes.extend(export_routes());
+    routes.extend(job_routes());
+    routes.extend(email_routes());
+    routes.extend(kyc_routes());
+    routes.extend(status_routes());
+    routes.extend(webhook_routes());
+    routes.extend(graphql_routes());
+    routes.extend(admin_routes());
+    routes.extend(socket_routes());
+    #[cfg(feature = "seed")]
+    routes.extend(seed_routes());
+    #[cfg(feature = "deposit-simulation")]
+    routes.extend(simulation_routes());
+    routes
+}
+
+// =============================================================================
+// /auth
+// =============================================================================
+
+fn auth_routes() -> Vec<Route> {
+    vec![
+        Route::post("register", "/auth/register")
+            .status(201)
+            .body::<auth::RegisterRequest>()
+            .response::<auth::RegisterResponse>()
+            .error::<auth::ErrorResponse>(),
+        Route::post("login", "/auth/login")
+            .body::<auth::LoginRequest>()
+            .response::<auth::LoginResponse>()
+            .error::<auth::ErrorResponse>(),
+        Route::post("forgotPassword", "/auth/forgot-password")
+            .body::<auth::ForgotPasswordRequest>()
+            .response::<auth::PasswordResetResponse>()
+            .error::<auth::ErrorResponse>(),
+        Route::post("resetPassword", "/auth/reset-password")
+            .body::<auth::ResetPasswordRequest>()
+            .response::<auth::PasswordResetResponse>()
+            .error::<auth::ErrorResponse>(),
+        Route::post("createSession", "/auth/session")
+            .body::<auth::LoginRequest>()
+            .response::<auth::SessionResponse>()
+            .error::<auth::ErrorResponse>(),
+        Route::post("refreshSession", "/auth/session/refresh")
+            .response::<auth::SessionResponse>()
+            .error::<auth::ErrorResponse>(),
+        Route::delete("deleteSession", "/auth/session")
+            .response::<auth::SessionEndedResponse>()
+            .error::<auth::ErrorResponse>(),
+        Route::post("oauthStart", "/auth/oauth/{provider}/start")
+            .auth(AuthRequirement::Optional)
+            .body::<auth::OAuthStartRequest>()
+            .response::<auth::OAuthStartResponse>()
+            .error::<auth::ErrorResponse>(),
+        Route::post("oauthCallback", "/auth/oauth/{provider}/callback")
+            .body::<auth::OAuthCallbackRequest>()
+            .response::<auth::OAuthLoginResponse>()
+            .error::<auth::ErrorResponse>(),
+        Route::post("oauthTwoFactor", "/auth/oauth/2fa")
+            .body::<auth::OAuthTwoFactorRequest>()
+            .response::<auth::LoginResponse>()
+            .error::<auth::ErrorResponse>(),
+        Route::get("listOAuthIdentities", "/auth/oauth/identities")
+            .auth(AuthRequirement::User)
+            .scope(Scope::AccountManage)
+            .response::<auth::OAuthIdentitiesResponse>()
+            .error::<auth::ErrorResponse>(),
+        Route::post("downscopeToken", "/auth/token/downscope")
+            .auth(AuthRequirement::User)
+            .any_scope()
+            .body::<auth::DownscopeTokenRequest>()
+            .response::<auth::DownscopedTokenResponse>()
+            .error::<auth::ErrorResponse>(),
+    ]
+}
+
+// =============================================================================
+// /swap
+// =============================================================================
+
+fn swap_routes() -> Vec<Route> {
+    vec![
+        Route::get("listCurrencies", "/swap/currencies")
+            .query::<swap::CurrenciesQuery>()
+            .response::<Vec<swap::CurrencyResponse>>()
+            .error::<swap::SwapErrorResponse>(),
+        Route::get("getAddressSpec", "/swap/currencies/{ticker}/address-spec")
+            .query::<swap::AddressSpecQuery>()
+            .response::<swap::AddressSpecResponse>()
+            .error::<swap::SwapErrorResponse>(),
+        Route::get("listProviders", "/swap/providers")
+            .query::<swap::ProvidersQuery>()
+            .response::<Vec<swap::ProviderResponse>>()
+            .error::<swap::SwapErrorResponse>(),
+        Route::get("listPairs", "/swap/pairs")
+            .query::<swap::PairsQuery>()
+            .response::<swap::PairsResponse>()
+            .error::<swap::SwapErrorResponse>(),
+        Route::get("getPairMatrix", "/swap/pairs/matrix")
+            .query::<swap::PairMatrixQuery>()
+            .response::<swap::PairMatrixResponse>()
+            .error::<swap::SwapErrorResponse>(),
+        Route::get("getRates", "/swap/rates")
+            .auth(AuthRequirement::Optional)
+            .query::<swap::RatesQuery>()
+            .response::<swap::RatesResponse>()
+            .error::<swap::SwapErrorResponse>(),
+        Route::get("getQuoteKey", "/swap/quote-key")
+            .response::<swap::QuoteKeyResponse>()
+            .error::<swap::SwapErrorResponse>(),
+        Route::get("getEstimate", "/swap/estimate")
+            .query::<swap::EstimateQuery>()
+            .response::<swap::EstimateResponse>()
+            .error::<swap::SwapErrorResponse>(),
+        Route::get("getDetailedEstimate", "/swap/estimate/detailed")
+            .query::<swap::EstimateQuery>()
+            .response::<swap::DetailedEstimateResponse>()
+            .error::<swap::SwapErrorResponse>(),
+        Route::post("createSwap", "/swap/create")
+            .auth(AuthRequirement::Optional)
+            .scope(Scope::SwapCreate)
+            .status(201)
+            .body::<swap::CreateSwapRequest>()
+            .response::<swap::CreateSwapResponse>()
+            .error::<swap::SwapErrorResponse>(),
+        Route::get("getSwapHistory", "/swap/history")
+            .auth(AuthRequirement::User)
+            .scope(Scope::HistoryRead)
+            .query::<swap::HistoryQuery>()
+            .response::<swap::HistoryResponse>()
+            .error::<swap::SwapErrorResponse>(),
+        Route::get("getSwapStatus", "/swap/{id}")
+            .response::<swap::SwapStatusResponse>()
+            .error::<swap::SwapErrorResponse>(),
+        Route::get("getSwapReceipt", "/swap/{id}/receipt")
+            .auth(AuthRequirement::Optional)
+            .query::<swap::ReceiptQuery>()
+            .response::<swap::SwapReceipt>()
+            .error::<swap::SwapErrorResponse>(),
+        Route::post("createAddressProof", "/swap/{id}/address-proof")
+            .body::<swap::AddressProofRequest>()
+            .response::<swap::AddressProofResponse>()
+            .error::<swap::SwapErrorResponse>(),
+        Route::post("validateAddress", "/swap/validate-address")
+            .body::<swap::ValidateAddressRequest>()
+            .response::<swap::ValidateAddressResponse>()
+            .error::<swap::SwapErrorResponse>(),
+    ]
+}
+
+fn schedule_routes() -> Vec<Route> {
+    vec![
+        Route::get("listSchedules", "/swap/schedules")
+            .auth(AuthRequirement::User)
+            .scope(Scope::HistoryRead)
+            .response::<schedules::SchedulesResponse>()
+            .error::<schedules::ScheduleErrorResponse>(),
+        Route::post("createSchedule", "/swap/schedules")
+            .auth(AuthRequirement::User)
+            .scope(Scope::SwapCreate)
+            .status(201)
+            .body::<schedules::CreateScheduleRequest>()
+            .response::<schedules::ScheduleResponse>()
+            .error::<schedules::ScheduleErrorResponse>(),
+        Route::get("getSchedule", "/swap/schedules/{id}")
+            .auth(AuthRequirement::User)
+            .scope(Scope::HistoryRead)
+            .response::<schedules::ScheduleDetailResponse>()
+            .error::<schedules::ScheduleErrorResponse>(),
+        Route::patch("updateSchedule", "/swap/schedules/{id}")
+            .auth(AuthRequirement::User)
+            .scope(Scope::SwapCreate)
+            .body::<schedules::UpdateScheduleRequest>()
+            .response::<schedules::ScheduleResponse>()
+            .error::<schedules::ScheduleErrorResponse>(),
+        Route::delete("cancelSchedule", "/swap/schedules/{id}")
+            .auth(AuthRequirement::User)
+            .scope(Scope::SwapCreate)
+            .status(204)
+            .error::<schedules::ScheduleErrorResponse>(),
+    ]
+}
+
+fn order_routes() -> Vec<Route> {
+    vec![
+        Route::get("listOrders", "/swap/orders")
+            .auth(AuthRequirement::User)
+            .scope(Scope::HistoryRead)
+            .query::<orders::OrdersQuery>()
+            .response::<orders::OrdersResponse>()
+            .error::<orders::OrderErrorResponse>(),
+        Route::post("createOrder", "/swap/orders")
+            .auth(AuthRequirement::User)
+            .scope(Scope::SwapCreate)
+            .status(201)
+            .body::<orders::CreateOrderRequest>()
+            .response::<orders::OrderResponse>()
+            .error::<orders::OrderErrorResponse>(),
+        Route::get("getOrder", "/swap/orders/{id}")
+            .auth(AuthRequirement::User)
+            .scope(Scope::HistoryRead)
+            .response::<orders::OrderResponse>()
+            .error::<orders::OrderErrorResponse>(),
+        Route::delete("cancelOrder", "/swap/orders/{id}")
+            .auth(AuthRequirement::User)
+            .scope(Scope::SwapCreate)
+            .response::<orders::OrderResponse>()
+            .error::<orders::OrderErrorResponse>(),
+    ]
+}
+
+// =============================================================================
+// /gift-cards
+// =============================================================================
+
+fn gift_card_routes() -> Vec<Route> {
+    vec![
+        Route::get("getGiftCardCatalog", "/gift-cards/catalog")
+            .query::<gift_cards::CatalogQuery>()
+            .response::<gift_cards::CatalogResponse>()
+            .error::<gift_cards::GiftCardErrorResponse>(),
+        Route::post("purchaseGiftCard", "/gift-cards/purchase")
+            .auth(AuthRequirement::User)
+            .status(201)
+            .body::<gift_cards::PurchaseGiftCardRequest>()
+            .response::<gift_cards::GiftCardPurchaseResponse>()
+            .error::<gift_cards::GiftCardErrorResponse>(),
+        Route::get("getGiftCardPurchase", "/gift-cards/purchases/{id}")
+            .auth(AuthRequirement::User)
+            .response::<gift_cards::GiftCardPurchaseResponse>()
+            .error::<gift_cards::GiftCardErrorResponse>(),
+    ]
+}
+
+// =============================================================================
+// /balances
+// =============================================================================
+
+fn balance_routes() -> Vec<Route> {
+    vec![
+        Route::get("getBalances", "/balances")
+            .auth(AuthRequirement::User)
+            .response::<balances::BalancesResponse>()
+            .error::<balances::BalanceErrorResponse>(),
+        Route::post("optInToCustody", "/balances/opt-in")
+            .auth(AuthRequirement::User)
+            .response::<balances::BalancesResponse>()
+            .error::<balances::BalanceErrorResponse>(),
+        Route::get("getLedger", "/balances/ledger")
+            .auth(AuthRequirement::User)
+            .query::<balances::LedgerQuery>()
+            .response::<balances::LedgerResponse>()
+            .error::<balances::BalanceErrorResponse>(),
+        Route::post("createTransfer", "/balances/transfers")
+            .auth(AuthRequirement::User)
+            .status(201)
+            .body::<balances::TransferRequest>()
+            .response::<balances::TransferResponse>()
+            .error::<balances::BalanceErrorResponse>(),
+        Route::post("createWithdrawal", "/balances/withdrawals")
+            .auth(AuthRequirement::User)
+            .status(201)
+            .body::<balances::CreateWithdrawalRequest>()
+            .response::<balances::WithdrawalResponse>()
+            .error::<balances::BalanceErrorResponse>(),
+        Route::get("listWithdrawals", "/balances/withdrawals")
+            .auth(AuthRequirement::User)
+            .response::<balances::WithdrawalsResponse>()
+            .error::<balances::BalanceErrorResponse>(),
+    ]
+}
+
+// =============================================================================
+// /address-book
+// =============================================================================
+
+fn address_book_routes() -> Vec<Route> {
+    vec![
+        Route::get("listAddresses", "/address-book")
+            .auth(AuthRequirement::User)
+            .scope(Scope::AccountManage)
+            .query::<address_book::AddressBookQuery>()
+            .response::<address_book::AddressBookResponse>()
+            .error::<address_book::AddressBookErrorResponse>(),
+        Route::post("createAddress", "/address-book")
+            .auth(AuthRequirement::User)
+            .scope(Scope::AccountManage)
+            .status(201)
+            .body::<address_book::CreateAddressRequest>()
+            .response::<address_book::AddressResponse>()
+            .error::<address_book::AddressBookErrorResponse>(),
+        Route::patch("updateAddress", "/address-book/{id}")
+            .auth(AuthRequirement::User)
+            .scope(Scope::AccountManage)
+            .body::<address_book::UpdateAddressRequest>()
+            .response::<address_book::AddressResponse>()
+            .error::<address_book::AddressBookErrorResponse>(),
+        Route::delete("deleteAddress", "/address-book/{id}")
+            .auth(AuthRequirement::User)
+            .scope(Scope::AccountManage)
+            .status(204)
+            .error::<address_book::AddressBookErrorResponse>(),
+    ]
+}
+
+// =============================================================================
+// /account
+// =============================================================================
+
+fn account_routes() -> Vec<Route> {
+    vec![
+        Route::get("getUsage", "/account/usage")
+            .auth(AuthRequirement::User)
+            .scope(Scope::AccountManage)
+            .query::<account::UsageQuery>()
+            .response::<account::UsageResponse>()
+            .error::<account::UsageErrorResponse>(),
+        Route::get("getAntiPhishingCode", "/account/anti-phishing")
+            .auth(AuthRequirement::User)
+            .scope(Scope::AccountManage)
+            .response::<account::AntiPhishingResponse>()
+            .error::<account::AntiPhishingErrorResponse>(),
+        Route::put("setAntiPhishingCode", "/account/anti-phishing")
+            .auth(AuthRequirement::User)
+            .scope(Scope::AccountManage)
+            .body::<account::SetAntiPhishingCodeRequest>()
+            .response::<account::AntiPhishingResponse>()
+            .error::<account::AntiPhishingErrorResponse>(),
+        Route::post("rotateAntiPhishingCode", "/account/anti-phishing/rotate")
+            .auth(AuthRequirement::User)
+            .scope(Scope::AccountManage)
+            .response::<account::AntiPhishingResponse>()
+            .error::<account::AntiPhishingErrorResponse>(),
+    ]
+}
+
+// =============================================================================
+// /exports
+// =============================================================================
+
+fn export_routes() -> Vec<Route> {
+    vec![
+        Route::post("createExport", "/exports")
+            .auth(AuthRequirement::User)
+            .scope(Scope::HistoryRead)
+            .status(202)
+            .body::<exports::CreateExportRequest>()
+            .response::<exports::ExportResponse>()
+            .error::<exports::ExportErrorResponse>(),
+        Route::get("getExport", "/exports/{id}")
+            .auth(AuthRequirement::User)
+            .scope(Scope::HistoryRead)
+            .response::<exports::ExportResponse>()
+            .error::<exports::ExportErrorResponse>(),
+    ]
+}
+
+// =============================================================================
+// /jobs
+// =============================================================================
+
+fn job_routes() -> Vec<Route> {
+    vec![
+        Route::get("getJob", "/jobs/{id}")
+            .auth(AuthRequirement::User)
+            .response::<jobs::JobResponse>()
+            .error::<jobs::JobErrorResponse>(),
+    ]
+}
+
+// =============================================================================
+// /email
+// =============================================================================
+
+fn email_routes() -> Vec<Route> {
+    vec![
+        Route::post("ingestEmailEvents", "/email/events")
+            .query::<email::EmailEventsQuery>()
+            .body::<serde_json::Value>()
+            .response::<email::EmailEventsResponse>()
+            .error::<email::EmailErrorResponse>(),
+    ]
+}
+
+// =============================================================================
+// /kyc
+// =============================================================================
+
+fn kyc_routes() -> Vec<Route> {
+    vec![
+        Route::get("getKycStatus", "/kyc")
+            .auth(AuthRequirement::User)
+            .scope(Scope::AccountManage)
+            .response::<kyc::KycStatusResponse>()
+            .error::<kyc::KycErrorResponse>(),
+        Route::post("startVerification", "/kyc/verification")
+            .auth(AuthRequirement::User)
+            .scope(Scope::AccountManage)
+            .body::<kyc::StartVerificationRequest>()
+            .response::<kyc::StartVerificationResponse>()
+            .error::<kyc::KycErrorResponse>(),
+        Route::post("submitKycDocument", "/kyc/documents")
+            .auth(AuthRequirement::User)
+            .scope(Scope::AccountManage)
+            .status(201)
+            .body::<kyc::SubmitDocumentRequest>()
+            .response::<kyc::KycDocumentResponse>()
+            .error::<kyc::KycErrorResponse>(),
+        Route::post("submitKycForReview", "/kyc/submit")
+            .auth(AuthRequirement::User)
+            .scope(Scope::AccountManage)
+            .response::<kyc::KycStatusResponse>()
+            .error::<kyc::KycErrorResponse>(),
+        Route::post("ingestKycWebhook", "/kyc/webhooks/{provider}")
+            .body::<serde_json::Value>()
+            .response::<kyc::KycWebhookResponse>()
+            .error::<kyc::KycErrorResponse>(),
+    ]
+}
+
+// =============================================================================
+// /status
+// =============================================================================
+
+fn status_routes() -> Vec<Route> {
+    vec![
+        Route::get("getNetworkStatus", "/status/networks")
+            .response::<status::NetworkStatusReport>()
+            .error::<status::StatusErrorResponse>(),
+        Route::get("getPublicStatus", "/status/public")
+            .response::<status::PublicStatusResponse>()
+            .error::<status::StatusErrorResponse>(),
+    ]
+}
+
+// =============================================================================
+// /webhooks
+// =============================================================================
+
+fn webhook_routes() -> Vec<Route> {
+    vec![
+        Route::get("listWebhookEventTypes", "/webhooks/event-types")
+            .response::<webhooks::EventTypesResponse>()
+            .error::<webhooks::WebhookErrorResponse>(),
+        Route::get("listWebhookDeliveries", "/webhooks/{id}/deliveries")
+            .auth(AuthRequirement::User)
+            .scope(Scope::HistoryRead)
+            .query::<webhooks::DeliveriesQuery>()
+            .response::<webhooks::DeliveriesResponse>()
+            .error::<webhooks::WebhookErrorResponse>(),
+        Route::post("testWebhook", "/webhooks/{id}/test")
+            .auth(AuthRequirement::User)
+            .scope(Scope::SwapCreate)
+            .response::<webhooks::DeliveryAttemptResponse>()
+            .error::<webhooks::WebhookErrorResponse>(),
+        Route::post("redeliverWebhook", "/webhooks/{id}/redeliver/{delivery_id}")
+            .auth(AuthRequirement::User)
+            .scope(Scope::SwapCreate)
+            .response::<webhooks::DeliveryAttemptResponse>()
+            .error::<webhooks::WebhookErrorResponse>(),
+        // Called by providers, who authenticate with a signature
+        Route::post("providerCallback", "/webhooks/providers/{provider}").internal(),
+    ]
+}
+
+// =============================================================================
+// /graphql
+// =============================================================================
+
+fn graphql_routes() -> Vec<Route> {
+    vec![
+        Route::post("graphql", "/graphql")
+            .auth(AuthRequirement::Optional)
+            .scope(Scope::HistoryRead)
+            .body::<graphql::GraphQLRequest>()
+            .response::<graphql::GraphQLResponse>(),
+    ]
+}
+
+// =============================================================================
+// /admin
+// =============================================================================
+
+fn admin_routes() -> Vec<Route> {
+    vec![
+        Route::post("approveWithdrawal", "/admin/withdrawals/{id}/approve")
+            .auth(AuthRequirement::Admin)
+            .response::<balances::WithdrawalResponse>()
+            .error::<balances::BalanceErrorResponse>(),
+        Route::post("rejectWithdrawal", "/admin/withdrawals/{id}/reject")
+            .auth(AuthRequirement::Admin)
+            .body::<balances::RejectWithdrawalRequest>()
+            .response::<balances::WithdrawalResponse>()
+            .error::<balances::BalanceErrorResponse>(),
+        Route::get("listWrongNetworkCases", "/admin/wrong-network-cases")
+            .auth(AuthRequirement::Admin)
+            .query::<recovery::WrongNetworkCasesQuery>()
+            .response::<recovery::WrongNetworkCasesResponse>()
+            .error::<recovery::RecoveryErrorResponse>(),
+        Route::get("getWrongNetworkCase", "/admin/wrong-network-cases/{id}")
+            .auth(AuthRequirement::Admin)
+            .response::<recovery::WrongNetworkCaseResponse>()
+            .error::<recovery::RecoveryErrorResponse>(),
+        Route::post("recoverWrongNetworkCase", "/admin/wrong-network-cases/{id}/recover")
+            .auth(AuthRequirement::Admin)
+            .body::<recovery::RecoverWrongNetworkRequest>()
+            .response::<recovery::WrongNetworkCaseResponse>()
+            .error::<recovery::RecoveryErrorResponse>(),
+        Route::post("dismissWrongNetworkCase", "/admin/wrong-network-cases/{id}/dismiss")
+            .auth(AuthRequirement::Admin)
+            .body::<recovery::DismissWrongNetworkRequest>()
+            .response::<recovery::WrongNetworkCaseResponse>()
+            .error::<recovery::RecoveryErrorResponse>(),
+        Route::get("listMemoDeposits", "/admin/memo-deposits")
+            .auth(AuthRequirement::Admin)
+            .query::<recovery::MemoDepositsQuery>()
+            .response::<recovery::MemoDepositsResponse>()
+            .error::<recovery::RecoveryErrorResponse>(),
+        Route::post("releaseMemoDeposit", "/admin/memo-deposits/{id}/release")
+            .auth(AuthRequirement::Admin)
+            .body::<recovery::ReleaseMemoDepositRequest>()
+            .response::<recovery::MemoDepositResponse>()
+            .error::<recovery::RecoveryErrorResponse>(),
+        Route::post("dismissMemoDeposit", "/admin/memo-deposits/{id}/dismiss")
+            .auth(AuthRequirement::Admin)
+            .body::<recovery::DismissMemoDepositRequest>()
+            .response::<recovery::MemoDepositResponse>()
+            .error::<recovery::RecoveryErrorResponse>(),
+        Route::get("listWalletAuditRuns", "/admin/wallet-audit/runs")
+            .auth(AuthRequirement::Admin)
+            .query::<recovery::WalletAuditRunsQuery>()
+            .response::<recovery::WalletAuditRunsResponse>()
+            .error::<recovery::RecoveryErrorResponse>(),
+        Route::post("startWalletAudit", "/admin/wallet-audit/runs")
+            .auth(AuthRequirement::Admin)
+            .status(202)
+            .response::<recovery::WalletAuditRunResponse>()
+            .error::<recovery::RecoveryErrorResponse>(),
+        Route::get("listOrphanedFunds", "/admin/orphaned-funds")
+            .auth(AuthRequirement::Admin)
+            .query::<recovery::OrphanedFundsQuery>()
+            .response::<recovery::OrphanedFundsListResponse>()
+            .error::<recovery::RecoveryErrorResponse>(),
+        Route::post("recoverOrphanedFunds", "/admin/orphaned-funds/{id}/recover")
+            .auth(AuthRequirement::Admin)
+            .body::<recovery::RecoverOrphanedFundsRequest>()
+            .response::<recovery::OrphanedFundsResponse>()
+            .error::<recovery::RecoveryErrorResponse>(),
+        Route::post("dismissOrphanedFunds", "/admin/orphaned-funds/{id}/dismiss")
+            .auth(AuthRequirement::Admin)
+            .body::<recovery::DismissOrphanedFundsRequest>()
+            .response::<recovery::OrphanedFundsResponse>()
+            .error::<recovery::RecoveryErrorResponse>(),
+        Route::get("listTradingHalts", "/admin/halts")
+            .auth(AuthRequirement::Admin)
+            .response::<halts::TradingHaltsResponse>()
+            .error::<halts::HaltErrorResponse>(),
+        Route::post("setCurrencyEnabled", "/admin/halts/currency")
+            .auth(AuthRequirement::Admin)
+            .body::<halts::SetCurrencyEnabledRequest>()
+            .response::<halts::TradingHaltsResponse>()
+            .error::<halts::HaltErrorResponse>(),
+        Route::post("setPairEnabled", "/admin/halts/pair")
+            .auth(AuthRequirement::Admin)
+            .body::<halts::SetPairEnabledRequest>()
+            .response::<halts::TradingHaltsResponse>()
+            .error::<halts::HaltErrorResponse>(),
+        Route::post("liftTradingHalt", "/admin/halts/{id}/lift")
+            .auth(AuthRequirement::Admin)
+            .response::<halts::TradingHaltsResponse>()
+            .error::<halts::HaltErrorResponse>(),
+        Route::get("listProviderCommissions", "/admin/providers/commissions")
+            .auth(AuthRequirement::Admin)
+            .response::<commissions::ProviderCommissionsResponse>()
+            .error::<commissions::CommissionErrorResponse>(),
+        Route::put("setProviderCommission", "/admin/providers/{id}/commission")
+            .auth(AuthRequirement::Admin)
+            .body::<commissions::SetProviderCommissionRequest>()
+            .response::<commissions::ProviderCommissionResponse>()
+            .error::<commissions::CommissionErrorResponse>(),
+        Route::delete("removeProviderCommission", "/admin/providers/{id}/commission")
+            .auth(AuthRequirement::Admin)
+            .status(204)
+            .error::<commissions::CommissionErrorResponse>(),
+        Route::get("getRevenueSummary", "/admin/revenue")
+            .auth(AuthRequirement::Admin)
+            .query::<commissions::RevenueQuery>()
+            .response::<commissions::RevenueSummaryResponse>()
+            .error::<commissions::CommissionErrorResponse>(),
+        Route::get("listPromotions", "/admin/promotions")
+            .auth(AuthRequirement::Admin)
+            .response::<promotions::PromotionsResponse>()
+            .error::<promotions::PromotionErrorResponse>(),
+        Route::post("createPromotion", "/admin/promotions")
+            .auth(AuthRequirement::Admin)
+            .status(201)
+            .body::<promotions::CreatePromotionRequest>()
+            .response::<promotions::PromotionResponse>()
+            .error::<promotions::PromotionErrorResponse>(),
+        Route::patch("updatePromotion", "/admin/promotions/{id}")
+            .auth(AuthRequirement::Admin)
+            .body::<promotions::UpdatePromotionRequest>()
+            .response::<promotions::PromotionResponse>()
+            .error::<promotions::PromotionErrorResponse>(),
+        Route::delete("endPromotion", "/admin/promotions/{id}")
+            .auth(AuthRequirement::Admin)
+            .status(204)
+            .error::<promotions::PromotionErrorResponse>(),
+        Route::get("getPromotionReport", "/admin/promotions/report")
+            .auth(AuthRequirement::Admin)
+            .query::<promotions::PromotionReportQuery>()
+            .response::<promotions::PromotionReportResponse>()
+            .error::<promotions::PromotionErrorResponse>(),
+        Route::get("listReconciliationRuns", "/admin/reconciliation/runs")
+            .auth(AuthRequirement::Admin)
+            .query::<reconciliation::ReconciliationRunsQuery>()
+            .response::<reconciliation::ReconciliationRunsResponse>()
+            .error::<reconciliation::ReconciliationErrorResponse>(),
+        Route::get("listBackups", "/admin/backups")
+            .auth(AuthRequirement::Admin)
+            .query::<backups::BackupRunsQuery>()
+            .response::<backups::BackupRunsResponse>()
+            .error::<backups::BackupErrorResponse>(),
+        Route::post("startBackup", "/admin/backups")
+            .auth(AuthRequirement::Admin)
+            .status(202)
+            .response::<backups::BackupRunResponse>()
+            .error::<backups::BackupErrorResponse>(),
+        Route::get("getPayoutGuard", "/admin/payouts/guard")
+            .auth(AuthRequirement::Admin)
+            .response::<payout_guard::PayoutGuardStatusResponse>()
+            .error::<payout_guard::PayoutGuardErrorResponse>(),
+        Route::post("tripPayoutGuard", "/admin/payouts/guard/trip")
+            .auth(AuthRequirement::Admin)
+            .body::<payout_guard::TripPayoutGuardRequest>()
+            .response::<payout_guard::PayoutGuardStatusResponse>()
+            .error::<payout_guard::PayoutGuardErrorResponse>(),
+        Route::post("rearmPayoutGuard", "/admin/payouts/guard/rearm")
+            .auth(AuthRequirement::Admin)
+            .body::<payout_guard::RearmPayoutGuardRequest>()
+            .response::<payout_guard::PayoutGuardStatusResponse>()
+            .error::<payout_guard::PayoutGuardErrorResponse>(),
+        Route::get("listDiscrepancies", "/admin/reconciliation/discrepancies")
+            .auth(AuthRequirement::Admin)
+            .query::<reconciliation::DiscrepanciesQuery>()
+            .response::<reconciliation::DiscrepanciesResponse>()
+            .error::<reconciliation::ReconciliationErrorResponse>(),
+        Route::post("resolveDiscrepancy", "/admin/reconciliation/discrepancies/{id}/resolve")
+            .auth(AuthRequirement::Admin)
+            .body::<reconciliation::ReviewDiscrepancyRequest>()
+            .response::<reconciliation::DiscrepancyResponse>()
+            .error::<reconciliation::ReconciliationErrorResponse>(),
+        Route::post("dismissDiscrepancy", "/admin/reconciliation/discrepancies/{id}/dismiss")
+            .auth(AuthRequirement::Admin)
+            .body::<reconciliation::ReviewDiscrepancyRequest>()
+            .response::<reconciliation::DiscrepancyResponse>()
+            .error::<reconciliation::ReconciliationErrorResponse>(),
+        Route::get("getGasAnalytics", "/admin/analytics/gas")
+            .auth(AuthRequirement::Admin)
+            .query::<analytics::GasAnalyticsQuery>()
+            .response::<analytics::GasAnalyticsResponse>()
+            .error::<analytics::AnalyticsErrorResponse>(),
+        Route::get("getSwapFunnel", "/admin/analytics/funnel")
+            .auth(AuthRequirement::Admin)
+            .query::<analytics::FunnelQuery>()
+            .response::<analytics::FunnelResponse>()
+            .error::<analytics::AnalyticsErrorResponse>(),
+        Route::get("listFxRates", "/admin/analytics/fx-rates")
+            .auth(AuthRequirement::Admin)
+            .query::<analytics::FxRatesQuery>()
+            .response::<analytics::FxRatesResponse>()
+            .error::<analytics::AnalyticsErrorResponse>(),
+        Route::get("searchSwaps", "/admin/swaps/search")
+            .auth(AuthRequirement::Admin)
+            .query::<swap::SwapSearchQuery>()
+            .response::<swap::SwapSearchResponse>()
+            .error::<swap::SwapErrorResponse>(),
+        Route::get("listStuckSwaps", "/admin/swaps/stuck")
+            .auth(AuthRequirement::Admin)
+            .query::<swap::StuckSwapsQuery>()
+            .response::<swap::StuckSwapsResponse>()
+            .error::<swap::SwapErrorResponse>(),
+        Route::get("getSwapDebug", "/admin/swaps/{id}/debug")
+            .auth(AuthRequirement::Admin)
+            .response::<swap::SwapDebugResponse>()
+            .error::<swap::SwapErrorResponse>(),
+        Route::get("getRuntimeConfig", "/admin/config")
+            .auth(AuthRequirement::Admin)
+            .response::<admin::EffectiveConfigResponse>(),
+        Route::post("signWalletMessage", "/admin/wallet/sign-message")
+            .auth(AuthRequirement::Admin)
+            .body::<admin::SignMessageRequest>()
+            .response::<admin::SignedMessage>()
+            .error::<admin::WalletSigningErrorResponse>(),
+        Route::get("readEventStream", "/admin/events/stream")
+            .auth(AuthRequirement::Admin)
+            .query::<admin::EventStreamQuery>()
+            .response::<admin::EventStreamResponse>()
+            .error::<admin::EventStreamErrorResponse>(),
+        Route::get("listStreamGroups", "/admin/events/stream/groups")
+            .auth(AuthRequirement::Admin)
+            .response::<admin::StreamGroupsResponse>()
+            .error::<admin::EventStreamErrorResponse>(),
+        Route::post("createStreamGroup", "/admin/events/stream/groups")
+            .auth(AuthRequirement::Admin)
+            .status(201)
+            .body::<admin::CreateStreamGroupRequest>()
+            .response::<admin::StreamGroup>()
+            .error::<admin::EventStreamErrorResponse>(),
+        Route::get("getAccountStatus", "/admin/users/{id}/status")
+            .auth(AuthRequirement::Admin)
+            .response::<moderation::AccountStatusResponse>()
+            .error::<moderation::ModerationErrorResponse>(),
+        Route::put("setAccountStatus", "/admin/users/{id}/status")
+            .auth(AuthRequirement::Admin)
+            .body::<moderation::SetAccountStatusRequest>()
+            .response::<moderation::AccountStatusResponse>()
+            .error::<moderation::ModerationErrorResponse>(),
+        Route::post("reportRiskSignal", "/admin/users/{id}/risk-signals")
+            .auth(AuthRequirement::Admin)
+            .status(201)
+            .body::<moderation::ReportRiskSignalRequest>()
+            .response::<moderation::RiskAssessmentResponse>()
+            .error::<moderation::ModerationErrorResponse>(),
+        Route::get("listAddressReputation", "/admin/address-reputation")
+            .auth(AuthRequirement::Admin)
+            .query::<moderation::AddressReputationQuery>()
+            .response::<moderation::AddressReputationsResponse>()
+            .error::<moderation::ModerationErrorResponse>(),
+        Route::get("getAddressReputation", "/admin/address-reputation/{hash}")
+            .auth(AuthRequirement::Admin)
+            .response::<moderation::AddressReputationDetailResponse>()
+            .error::<moderation::ModerationErrorResponse>(),
+        Route::post("clearAddress", "/admin/address-reputation/{hash}/clear")
+            .auth(AuthRequirement::Admin)
+            .body::<moderation::ReviewAddressRequest>()
+            .response::<moderation::AddressReputationDetailResponse>()
+            .error::<moderation::ModerationErrorResponse>(),
+        Route::post("confirmAddress", "/admin/address-reputation/{hash}/confirm")
+            .auth(AuthRequirement::Admin)
+            .body::<moderation::ReviewAddressRequest>()
+            .response::<moderation::AddressReputationDetailResponse>()
+            .error::<moderation::ModerationErrorResponse>(),
+    ]
+}
+
+// =============================================================================
+// /ws
+// =============================================================================
+
+fn socket_routes() -> Vec<Route> {
+    vec![
+        // Operational events for the admin dashboard, over a websocket
+        Route::get("adminEventsStream", "/ws/admin").auth(AuthRequirement::Admin).internal(),
+    ]
+}
+
+// =============================================================================
+// DEV-ONLY
+// =============================================================================
+
+#[cfg(feature = "seed")]
+fn seed_routes() -> Vec<Route> {
+    vec![Route::post("seed", "/_seed").auth(AuthRequirement::Admin).internal()]
+}
+
+#[cfg(feature = "deposit-simulation")]
+fn simulation_routes() -> Vec<Route> {
+    vec![
+        Route::post("simulateDeposit", "/_test/simulate-deposit/{swap_id}")
+            .auth(AuthRequirement::Admin)
+            .internal(),
+    ]
+}
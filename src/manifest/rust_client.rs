@@ -0,0 +1,356 @@
+//! Rust client emitter: serde structs/enums for every definition and an async
+//! `Client` built on reqwest. The output is a single module file that depends
+//! on `reqwest` (json), `serde`, `serde_json` and `chrono`.
+
+use serde_json::Value;
+use std::fmt::Write;
+
+use super::{
+    description, non_null_variant, pascal_case, ref_name, schema_types, snake_case,
+    string_enum_values, AuthRequirement, RouteManifest, RouteSpec,
+};
+
+const PRELUDE: &str = r#"#![allow(dead_code, clippy::all)]
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+#[derive(Debug)]
+pub enum Error {
+    Http(reqwest::Error),
+    Decode(serde_json::Error),
+    Api { status: u16, body: serde_json::Value },
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Http(e) => write!(f, "HTTP error: {}", e),
+            Error::Decode(e) => write!(f, "Invalid response body: {}", e),
+            Error::Api { status, body } => write!(f, "Request failed with status {}: {}", status, body),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<reqwest::Error> for Error {
+    fn from(err: reqwest::Error) -> Self {
+        Error::Http(err)
+    }
+}
+"#;
+
+const CLIENT_PRELUDE: &str = r#"
+#[derive(Debug, Clone)]
+pub struct Client {
+    base_url: String,
+    http: reqwest::Client,
+    token: Option<String>,
+}
+
+impl Client {
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self::with_http_client(base_url, reqwest::Client::new())
+    }
+
+    pub fn with_http_client(base_url: impl Into<String>, http: reqwest::Client) -> Self {
+        Self {
+            base_url: format!("{}{}", base_url.into().trim_end_matches('/'), BASE_PATH),
+            http,
+            token: None,
+        }
+    }
+
+    /// Bearer access token sent on authenticated routes
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    pub fn set_token(&mut self, token: Option<String>) {
+        self.token = token;
+    }
+
+    async fn send<T: DeserializeOwned>(&self, request: reqwest::RequestBuilder, auth: bool) -> Result<T, Error> {
+        let request = match (&self.token, auth) {
+            (Some(token), true) => request.bearer_auth(token),
+            _ => request,
+        };
+        let response = request.send().await?;
+        let status = response.status();
+        let bytes = response.bytes().await?;
+
+        if !status.is_success() {
+            let body = serde_json::from_slice(&bytes).unwrap_or(serde_json::Value::Null);
+            return Err(Error::Api { status: status.as_u16(), body });
+        }
+
+        let bytes: &[u8] = if bytes.is_empty() { b"null" } else { &bytes };
+        serde_json::from_slice(bytes).map_err(Error::Decode)
+    }
+"#;
+
+const RUST_KEYWORDS: &[&str] = &[
+    "as", "async", "await", "box", "break", "const", "continue", "crate", "dyn", "else", "enum",
+    "extern", "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move",
+    "mut", "pub", "ref", "return", "self", "static", "struct", "super", "trait", "true", "type",
+    "unsafe", "use", "where", "while", "yield",
+];
+
+pub fn generate(manifest: &RouteManifest) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "// Generated by `cargo run --bin codegen -- --lang rust` from exchange-shared {}.", manifest.version);
+    let _ = writeln!(out, "// Do not edit by hand.\n");
+    out.push_str(PRELUDE);
+
+    for (name, schema) in &manifest.definitions {
+        out.push('\n');
+        write_definition(&mut out, name, schema);
+    }
+
+    let _ = writeln!(out, "\n/// API version the client is generated for\nconst BASE_PATH: &str = \"{}\";", manifest.base_path);
+    out.push_str(CLIENT_PRELUDE);
+    for route in &manifest.routes {
+        out.push('\n');
+        write_method(&mut out, route);
+    }
+    out.push_str("}\n");
+    out
+}
+
+fn field_ident(name: &str) -> String {
+    if RUST_KEYWORDS.contains(&name) {
+        format!("r#{}", name)
+    } else {
+        name.to_string()
+    }
+}
+
+fn write_doc(out: &mut String, indent: &str, doc: Option<&str>) {
+    if let Some(doc) = doc {
+        for line in doc.lines() {
+            let _ = writeln!(out, "{}/// {}", indent, line);
+        }
+    }
+}
+
+fn write_definition(out: &mut String, name: &str, schema: &Value) {
+    write_doc(out, "", description(schema));
+
+    if let Some(values) = string_enum_values(schema) {
+        out.push_str("#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]\n");
+        let _ = writeln!(out, "pub enum {} {{", name);
+        for value in values {
+            let _ = writeln!(out, "    #[serde(rename = {:?})]", value);
+            let _ = writeln!(out, "    {},", pascal_case(&value));
+        }
+        out.push_str("}\n");
+        return;
+    }
+
+    let Some(properties) = schema.get("properties").and_then(Value::as_object) else {
+        let _ = writeln!(out, "pub type {} = {};", name, rust_type(schema));
+        return;
+    };
+
+    let required: Vec<&str> = schema
+        .get("required")
+        .and_then(Value::as_array)
+        .map(|r| r.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+
+    out.push_str("#[derive(Debug, Clone, Serialize, Deserialize)]\n");
+    let _ = writeln!(out, "pub struct {} {{", name);
+    for (field, field_schema) in properties {
+        write_doc(out, "    ", description(field_schema));
+        let ty = rust_type(field_schema);
+        if required.contains(&field.as_str()) {
+            let _ = writeln!(out, "    pub {}: {},", field_ident(field), ty);
+        } else {
+            let ty = if ty.starts_with("Option<") { ty } else { format!("Option<{}>", ty) };
+            out.push_str("    #[serde(default, skip_serializing_if = \"Option::is_none\")]\n");
+            let _ = writeln!(out, "    pub {}: {},", field_ident(field), ty);
+        }
+    }
+    out.push_str("}\n");
+}
+
+/// Rust type expression for a schema
+pub fn rust_type(schema: &Value) -> String {
+    if schema == &Value::Bool(true) || schema.as_object().is_some_and(|o| o.is_empty()) {
+        return "serde_json::Value".to_string();
+    }
+    if let Some(name) = ref_name(schema) {
+        return name.to_string();
+    }
+    if let Some(inner) = non_null_variant(schema) {
+        return format!("Option<{}>", rust_type(inner));
+    }
+
+    let (types, nullable) = schema_types(schema);
+    let format = schema.get("format").and_then(Value::as_str);
+    let base = match types.first().copied() {
+        Some("string") if format == Some("date-time") => "chrono::DateTime<chrono::Utc>".to_string(),
+        Some("string") => "String".to_string(),
+        Some("integer") => match format {
+            Some("uint8") => "u8",
+            Some("uint16") => "u16",
+            Some("uint32") => "u32",
+            Some("uint64") | Some("uint") => "u64",
+            Some("int8") => "i8",
+            Some("int16") => "i16",
+            Some("int32") => "i32",
+            _ => "i64",
+        }
+        .to_string(),
+        Some("number") if format == Some("float") => "f32".to_string(),
+        Some("number") => "f64".to_string(),
+        Some("boolean") => "bool".to_string(),
+        Some("array") => format!(
+            "Vec<{}>",
+            schema.get("items").map(rust_type).unwrap_or_else(|| "serde_json::Value".to_string())
+        ),
+        Some("object") => match schema.get("additionalProperties") {
+            Some(values) if values.is_object() && schema.get("properties").is_none() => {
+                format!("std::collections::HashMap<String, {}>", rust_type(values))
+            }
+            _ => "serde_json::Value".to_string(),
+        },
+        _ => "serde_json::Value".to_string(),
+    };
+
+    if nullable {
+        format!("Option<{}>", base)
+    } else {
+        base
+    }
+}
+
+fn write_method(out: &mut String, route: &RouteSpec) {
+    let mut params: Vec<String> = route
+        .path_params
+        .iter()
+        .map(|p| format!("{}: &str", field_ident(p)))
+        .collect();
+    if let Some(body) = &route.body {
+        params.push(format!("body: &{}", rust_type(body)));
+    }
+    if let Some(query) = &route.query {
+        params.push(format!("query: &{}", rust_type(query)));
+    }
+
+    let response = route.response.as_ref().map(rust_type).unwrap_or_else(|| "()".to_string());
+
+    let mut path = route.path.clone();
+    for param in &route.path_params {
+        path = path.replace(&format!("{{{}}}", param), "{}");
+    }
+    let mut url_args = String::from("self.base_url");
+    for param in &route.path_params {
+        let _ = write!(url_args, ", {}", field_ident(param));
+    }
+
+    let _ = writeln!(out, "    /// {} {}", route.method, route.path);
+    let mut signature = String::from("&self");
+    for param in &params {
+        signature.push_str(", ");
+        signature.push_str(param);
+    }
+    let _ = writeln!(
+        out,
+        "    pub async fn {}({}) -> Result<{}, Error> {{",
+        snake_case(&route.name),
+        signature,
+        response
+    );
+    let _ = writeln!(
+        out,
+        "        let request = self.http.{}(format!(\"{{}}{}\", {}));",
+        route.method.to_lowercase(),
+        path,
+        url_args
+    );
+    if route.query.is_some() {
+        out.push_str("        let request = request.query(query);\n");
+    }
+    if route.body.is_some() {
+        out.push_str("        let request = request.json(body);\n");
+    }
+    let _ = writeln!(
+        out,
+        "        self.send(request, {}).await",
+        route.auth != AuthRequirement::None
+    );
+    out.push_str("    }\n");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_rust_primitive_types() {
+        assert_eq!(rust_type(&json!({ "type": "string" })), "String");
+        assert_eq!(
+            rust_type(&json!({ "type": "string", "format": "date-time" })),
+            "chrono::DateTime<chrono::Utc>"
+        );
+        assert_eq!(rust_type(&json!({ "type": "integer", "format": "uint32", "minimum": 0 })), "u32");
+        assert_eq!(rust_type(&json!({ "type": "integer", "format": "int64" })), "i64");
+        assert_eq!(rust_type(&json!({ "type": ["number", "null"], "format": "double" })), "Option<f64>");
+        assert_eq!(rust_type(&json!(true)), "serde_json::Value");
+    }
+
+    #[test]
+    fn test_rust_refs_and_arrays() {
+        assert_eq!(
+            rust_type(&json!({ "anyOf": [{ "$ref": "#/$defs/RateType" }, { "type": "null" }] })),
+            "Option<RateType>"
+        );
+        assert_eq!(
+            rust_type(&json!({ "type": "array", "items": { "$ref": "#/$defs/ScheduleRun" } })),
+            "Vec<ScheduleRun>"
+        );
+    }
+
+    #[test]
+    fn test_rust_struct_definition() {
+        let mut out = String::new();
+        write_definition(
+            &mut out,
+            "Example",
+            &json!({
+                "type": "object",
+                "properties": {
+                    "type": { "type": "string" },
+                    "limit": { "type": ["integer", "null"], "format": "uint32" }
+                },
+                "required": ["type"]
+            }),
+        );
+        assert!(out.contains("pub struct Example {"));
+        assert!(out.contains("    pub r#type: String,"));
+        assert!(out.contains("    #[serde(default, skip_serializing_if = \"Option::is_none\")]\n    pub limit: Option<u32>,"));
+    }
+
+    #[test]
+    fn test_rust_enum_definition() {
+        let mut out = String::new();
+        write_definition(&mut out, "EntryType", &json!({ "type": "string", "enum": ["swap_credit", "transfer_in"] }));
+        assert!(out.contains("pub enum EntryType {"));
+        assert!(out.contains("    #[serde(rename = \"swap_credit\")]\n    SwapCredit,"));
+        assert!(out.contains("    TransferIn,"));
+    }
+
+    #[test]
+    fn test_generate_full_manifest() {
+        let output = generate(&super::super::route_manifest());
+        assert!(output.contains("pub struct CreateSwapRequest {"));
+        assert!(output.contains("pub async fn create_swap(&self, body: &CreateSwapRequest) -> Result<CreateSwapResponse, Error> {"));
+        assert!(output.contains("pub async fn get_swap_status(&self, id: &str) -> Result<SwapStatusResponse, Error> {"));
+        assert!(output.contains("self.http.get(format!(\"{}/swap/{}\", self.base_url, id))"));
+        assert!(output.contains("const BASE_PATH: &str = \"/v1\";"));
+        assert!(output.contains("pub async fn cancel_schedule(&self, id: &str) -> Result<(), Error> {"));
+    }
+}
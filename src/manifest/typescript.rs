@@ -0,0 +1,287 @@
+//! TypeScript client emitter: one interface or union type per definition and
+//! an `ExchangeClient` class with a method per route. Uses `fetch`, so the
+//! output runs unmodified in browsers and Node 18+.
+
+use serde_json::Value;
+use std::fmt::Write;
+
+use super::{
+    description, non_null_variant, ref_name, schema_types, string_enum_values, AuthRequirement,
+    RouteManifest, RouteSpec,
+};
+
+const PRELUDE: &str = r#"export class ApiError extends Error {
+  constructor(public readonly status: number, public readonly body: unknown) {
+    super(`Request failed with status ${status}`);
+    this.name = "ApiError";
+  }
+}
+
+export interface ClientOptions {
+  /** Bearer access token sent on authenticated routes */
+  token?: string;
+  fetch?: typeof fetch;
+}
+
+type QueryValue = string | number | boolean | null | undefined;
+"#;
+
+const REQUEST_HELPER: &str = r#"  private async request<T>(
+    method: string,
+    path: string,
+    options: { query?: object; body?: unknown; auth?: boolean } = {},
+  ): Promise<T> {
+    const url = new URL(this.baseUrl.replace(/\/$/, "") + BASE_PATH + path);
+    for (const [key, value] of Object.entries(options.query ?? {}) as [string, QueryValue][]) {
+      if (value !== undefined && value !== null) url.searchParams.set(key, String(value));
+    }
+    const headers: Record<string, string> = {};
+    if (options.body !== undefined) headers["Content-Type"] = "application/json";
+    if (options.auth && this.options.token) headers["Authorization"] = `Bearer ${this.options.token}`;
+
+    const response = await (this.options.fetch ?? fetch)(url.toString(), {
+      method,
+      headers,
+      body: options.body === undefined ? undefined : JSON.stringify(options.body),
+    });
+    const text = await response.text();
+    const payload = text ? JSON.parse(text) : undefined;
+    if (!response.ok) throw new ApiError(response.status, payload);
+    return payload as T;
+  }
+"#;
+
+pub fn generate(manifest: &RouteManifest) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "// Generated by `cargo run --bin codegen -- --lang ts` from exchange-shared {}.", manifest.version);
+    let _ = writeln!(out, "// Do not edit by hand.\n");
+    out.push_str(PRELUDE);
+    let _ = writeln!(out, "\n/** API version the client is generated for */\nconst BASE_PATH = \"{}\";", manifest.base_path);
+
+    for (name, schema) in &manifest.definitions {
+        out.push('\n');
+        write_definition(&mut out, name, schema);
+    }
+
+    out.push_str("\nexport class ExchangeClient {\n");
+    out.push_str("  constructor(private readonly baseUrl: string, private options: ClientOptions = {}) {}\n\n");
+    out.push_str("  setToken(token: string | undefined): void {\n    this.options.token = token;\n  }\n");
+    for route in &manifest.routes {
+        out.push('\n');
+        write_method(&mut out, manifest, route);
+    }
+    out.push('\n');
+    out.push_str(REQUEST_HELPER);
+    out.push_str("}\n");
+    out
+}
+
+fn write_doc(out: &mut String, indent: &str, doc: Option<&str>) {
+    if let Some(doc) = doc {
+        let _ = writeln!(out, "{}/** {} */", indent, doc.replace('\n', " ").replace("*/", "*\\/"));
+    }
+}
+
+fn write_definition(out: &mut String, name: &str, schema: &Value) {
+    write_doc(out, "", description(schema));
+    if schema.get("properties").is_some() {
+        let _ = writeln!(out, "export interface {} {}", name, object_body(schema, ""));
+    } else {
+        let _ = writeln!(out, "export type {} = {};", name, ts_type(schema));
+    }
+}
+
+fn object_body(schema: &Value, indent: &str) -> String {
+    let required: Vec<&str> = schema
+        .get("required")
+        .and_then(Value::as_array)
+        .map(|r| r.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+
+    let mut body = String::from("{\n");
+    if let Some(properties) = schema.get("properties").and_then(Value::as_object) {
+        let field_indent = format!("{}  ", indent);
+        for (field, field_schema) in properties {
+            write_doc(&mut body, &field_indent, description(field_schema));
+            let optional = if required.contains(&field.as_str()) { "" } else { "?" };
+            let _ = writeln!(body, "{}{}{}: {};", field_indent, field, optional, ts_type(field_schema));
+        }
+    }
+    body.push_str(indent);
+    body.push('}');
+    body
+}
+
+/// TypeScript type expression for a schema
+pub fn ts_type(schema: &Value) -> String {
+    if schema == &Value::Bool(true) || schema.as_object().is_some_and(|o| o.is_empty()) {
+        return "unknown".to_string();
+    }
+    if let Some(name) = ref_name(schema) {
+        return name.to_string();
+    }
+    if let Some(inner) = non_null_variant(schema) {
+        return format!("{} | null", ts_type(inner));
+    }
+    if let Some(values) = string_enum_values(schema) {
+        return values.iter().map(|v| format!("{:?}", v)).collect::<Vec<_>>().join(" | ");
+    }
+    if let Some(variants) = schema.get("oneOf").or_else(|| schema.get("anyOf")).and_then(Value::as_array) {
+        return variants.iter().map(ts_type).collect::<Vec<_>>().join(" | ");
+    }
+
+    let (types, nullable) = schema_types(schema);
+    let base = match types.first().copied() {
+        Some("string") => "string".to_string(),
+        Some("integer") | Some("number") => "number".to_string(),
+        Some("boolean") => "boolean".to_string(),
+        Some("array") => {
+            let item = schema.get("items").map(ts_type).unwrap_or_else(|| "unknown".to_string());
+            if item.contains(' ') {
+                format!("({})[]", item)
+            } else {
+                format!("{}[]", item)
+            }
+        }
+        Some("object") if schema.get("properties").is_some() => object_body(schema, ""),
+        Some("object") => match schema.get("additionalProperties") {
+            Some(values) if values.is_object() => format!("Record<string, {}>", ts_type(values)),
+            _ => "Record<string, unknown>".to_string(),
+        },
+        _ => "unknown".to_string(),
+    };
+
+    if nullable {
+        format!("{} | null", base)
+    } else {
+        base
+    }
+}
+
+fn write_method(out: &mut String, manifest: &RouteManifest, route: &RouteSpec) {
+    let mut params: Vec<String> = route
+        .path_params
+        .iter()
+        .map(|p| format!("{}: string", p))
+        .collect();
+    if let Some(body) = &route.body {
+        params.push(format!("body: {}", ts_type(body)));
+    }
+    if let Some(query) = &route.query {
+        // Queries where every field is optional may be omitted entirely
+        let has_required = manifest
+            .resolve(query)
+            .get("required")
+            .and_then(Value::as_array)
+            .is_some_and(|r| !r.is_empty());
+        let default = if has_required { "" } else { " = {}" };
+        params.push(format!("query: {}{}", ts_type(query), default));
+    }
+
+    let response = route.response.as_ref().map(ts_type).unwrap_or_else(|| "void".to_string());
+
+    let mut path = format!("`{}`", route.path);
+    for param in &route.path_params {
+        path = path.replace(
+            &format!("{{{}}}", param),
+            &format!("${{encodeURIComponent({})}}", param),
+        );
+    }
+
+    let mut options = Vec::new();
+    if route.query.is_some() {
+        options.push("query".to_string());
+    }
+    if route.body.is_some() {
+        options.push("body".to_string());
+    }
+    if route.auth != AuthRequirement::None {
+        options.push("auth: true".to_string());
+    }
+
+    let _ = writeln!(out, "  /** {} {} */", route.method, route.path);
+    let _ = writeln!(
+        out,
+        "  {}({}): Promise<{}> {{",
+        route.name,
+        params.join(", "),
+        response
+    );
+    let options = if options.is_empty() {
+        String::new()
+    } else {
+        format!(", {{ {} }}", options.join(", "))
+    };
+    let _ = writeln!(
+        out,
+        "    return this.request<{}>({:?}, {}{});",
+        response, route.method, path, options
+    );
+    out.push_str("  }\n");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_ts_primitive_types() {
+        assert_eq!(ts_type(&json!({ "type": "string", "format": "date-time" })), "string");
+        assert_eq!(ts_type(&json!({ "type": "integer", "format": "uint32" })), "number");
+        assert_eq!(ts_type(&json!({ "type": ["number", "null"] })), "number | null");
+        assert_eq!(ts_type(&json!(true)), "unknown");
+    }
+
+    #[test]
+    fn test_ts_refs_and_arrays() {
+        assert_eq!(ts_type(&json!({ "$ref": "#/$defs/SwapStatus" })), "SwapStatus");
+        assert_eq!(
+            ts_type(&json!({ "anyOf": [{ "$ref": "#/$defs/RateType" }, { "type": "null" }] })),
+            "RateType | null"
+        );
+        assert_eq!(
+            ts_type(&json!({ "type": "array", "items": { "$ref": "#/$defs/CurrencyResponse" } })),
+            "CurrencyResponse[]"
+        );
+        assert_eq!(
+            ts_type(&json!({ "type": "array", "items": { "type": "string", "enum": ["a", "b"] } })),
+            "(\"a\" | \"b\")[]"
+        );
+    }
+
+    #[test]
+    fn test_ts_definitions() {
+        let mut out = String::new();
+        write_definition(
+            &mut out,
+            "Example",
+            &json!({
+                "type": "object",
+                "properties": {
+                    "id": { "type": "string" },
+                    "note": { "type": ["string", "null"], "description": "Free text" }
+                },
+                "required": ["id"]
+            }),
+        );
+        assert!(out.contains("export interface Example {"));
+        assert!(out.contains("  id: string;"));
+        assert!(out.contains("  /** Free text */\n  note?: string | null;"));
+
+        let mut out = String::new();
+        write_definition(&mut out, "RateType", &json!({ "type": "string", "enum": ["fixed", "floating"] }));
+        assert_eq!(out, "export type RateType = \"fixed\" | \"floating\";\n");
+    }
+
+    #[test]
+    fn test_generate_full_manifest() {
+        let output = generate(&super::super::route_manifest());
+        assert!(output.contains("export class ExchangeClient"));
+        assert!(output.contains("export interface CreateSwapRequest"));
+        assert!(output.contains("createSwap(body: CreateSwapRequest): Promise<CreateSwapResponse>"));
+        assert!(output.contains("getSwapStatus(id: string): Promise<SwapStatusResponse>"));
+        assert!(output.contains("`/swap/${encodeURIComponent(id)}`"));
+        assert!(output.contains("const BASE_PATH = \"/v1\";"));
+    }
+}
@@ -0,0 +1,86 @@
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    Json,
+};
+use std::sync::Arc;
+
+use crate::AppState;
+use crate::modules::auth::interface::{scope::AccountManage, Scoped};
+use crate::services::usage::UsageCounters;
+use super::crud::{AntiPhishingCrud, AntiPhishingError, UsageCrud};
+use super::schema::{
+    AntiPhishingErrorResponse, AntiPhishingResponse, SetAntiPhishingCodeRequest, UsageErrorResponse, UsageQuery,
+    UsageResponse,
+};
+
+fn anti_phishing_error(e: AntiPhishingError) -> (StatusCode, Json<AntiPhishingErrorResponse>) {
+    (e.status_code(), Json(AntiPhishingErrorResponse::new(e.to_string())))
+}
+
+// =============================================================================
+// GET /account/usage - Daily API usage and rate limit status
+// =============================================================================
+
+pub async fn get_usage(
+    State(state): State<Arc<AppState>>,
+    user: Scoped<AccountManage>,
+    Query(query): Query<UsageQuery>,
+) -> Result<Json<UsageResponse>, (StatusCode, Json<UsageErrorResponse>)> {
+    let crud = UsageCrud::new(state.db.clone(), UsageCounters::new(state.redis.clone()));
+    let usage = crud
+        .usage(&user.0.id, query.days)
+        .await
+        .map_err(|e| (e.status_code(), Json(UsageErrorResponse::new(e.to_string()))))?;
+
+    Ok(Json(usage))
+}
+
+// =============================================================================
+// GET /account/anti-phishing - The code shown in our emails
+// =============================================================================
+
+pub async fn get_anti_phishing_code(
+    State(state): State<Arc<AppState>>,
+    user: Scoped<AccountManage>,
+) -> Result<Json<AntiPhishingResponse>, (StatusCode, Json<AntiPhishingErrorResponse>)> {
+    let code = AntiPhishingCrud::new(state.db.clone())
+        .get(&user.0.id)
+        .await
+        .map_err(anti_phishing_error)?;
+
+    Ok(Json(code))
+}
+
+// =============================================================================
+// PUT /account/anti-phishing - Choose a code
+// =============================================================================
+
+pub async fn set_anti_phishing_code(
+    State(state): State<Arc<AppState>>,
+    user: Scoped<AccountManage>,
+    Json(payload): Json<SetAntiPhishingCodeRequest>,
+) -> Result<Json<AntiPhishingResponse>, (StatusCode, Json<AntiPhishingErrorResponse>)> {
+    let code = AntiPhishingCrud::new(state.db.clone())
+        .set(&user.0.id, Some(&payload.code))
+        .await
+        .map_err(anti_phishing_error)?;
+
+    Ok(Json(code))
+}
+
+// =============================================================================
+// POST /account/anti-phishing/rotate - Replace the code with a generated one
+// =============================================================================
+
+pub async fn rotate_anti_phishing_code(
+    State(state): State<Arc<AppState>>,
+    user: Scoped<AccountManage>,
+) -> Result<Json<AntiPhishingResponse>, (StatusCode, Json<AntiPhishingErrorResponse>)> {
+    let code = AntiPhishingCrud::new(state.db.clone())
+        .set(&user.0.id, None)
+        .await
+        .map_err(anti_phishing_error)?;
+
+    Ok(Json(code))
+}
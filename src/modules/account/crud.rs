@@ -0,0 +1,286 @@
+use axum::http::StatusCode;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use rand::Rng;
+use sqlx::{MySql, Pool};
+use std::collections::BTreeMap;
+
+use super::schema::{error_rate, AntiPhishingResponse, DailyUsage, RateLimitStatus, UsageResponse, UsageTotals};
+use crate::services::pii::SealedString;
+use crate::services::runtime_config::runtime_config;
+use crate::services::usage::{UsageCounters, UsageCounts};
+
+const DEFAULT_WINDOW_DAYS: i64 = 30;
+const MAX_WINDOW_DAYS: i64 = 90;
+
+const MIN_CODE_LEN: usize = 4;
+const MAX_CODE_LEN: usize = 20;
+const GENERATED_CODE_LEN: usize = 8;
+/// Generated codes leave out look-alikes (0/O, 1/I)
+const CODE_CHARSET: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+
+// =============================================================================
+// USAGE ERROR
+// =============================================================================
+
+#[derive(Debug)]
+pub enum UsageError {
+    InvalidWindow(i64),
+    DatabaseError(String),
+}
+
+impl std::fmt::Display for UsageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UsageError::InvalidWindow(days) => {
+                write!(f, "days must be between 1 and {} (got {})", MAX_WINDOW_DAYS, days)
+            }
+            UsageError::DatabaseError(e) => write!(f, "Database error: {}", e),
+        }
+    }
+}
+
+impl UsageError {
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            UsageError::InvalidWindow(_) => StatusCode::BAD_REQUEST,
+            UsageError::DatabaseError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+impl From<sqlx::Error> for UsageError {
+    fn from(err: sqlx::Error) -> Self {
+        UsageError::DatabaseError(err.to_string())
+    }
+}
+
+// =============================================================================
+// USAGE CRUD
+// =============================================================================
+
+pub struct UsageCrud {
+    pool: Pool<MySql>,
+    counters: UsageCounters,
+}
+
+impl UsageCrud {
+    pub fn new(pool: Pool<MySql>, counters: UsageCounters) -> Self {
+        Self { pool, counters }
+    }
+
+    /// Daily usage of the last `days` days, today included
+    pub async fn usage(&self, user_id: &str, days: Option<i64>) -> Result<UsageResponse, UsageError> {
+        let days = days.unwrap_or(DEFAULT_WINDOW_DAYS);
+        if !(1..=MAX_WINDOW_DAYS).contains(&days) {
+            return Err(UsageError::InvalidWindow(days));
+        }
+
+        let now = Utc::now();
+        let today = now.date_naive();
+        let first = today - Duration::days(days - 1);
+
+        let rows: Vec<(NaiveDate, u64, u64, u64)> = sqlx::query_as(
+            r#"
+            SELECT day, requests, errors, rate_limited
+            FROM api_usage_daily
+            WHERE user_id = ? AND day >= ?
+            "#,
+        )
+        .bind(user_id)
+        .bind(first)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut by_day: BTreeMap<NaiveDate, UsageCounts> = rows
+            .into_iter()
+            .map(|(day, requests, errors, rate_limited)| {
+                (day, UsageCounts { requests, errors, rate_limited, last_limited_at: None })
+            })
+            .collect();
+
+        // Redis runs ahead of the last flush for today and yesterday
+        let mut last_limited_at = None;
+        for day in [today.pred_opt(), Some(today)].into_iter().flatten().filter(|d| *d >= first) {
+            match self.counters.read(user_id, day).await {
+                Ok(live) => {
+                    let stored = by_day.entry(day).or_default();
+                    *stored = merge(stored, &live);
+                    last_limited_at = last_limited_at.max(live.last_limited_at);
+                }
+                Err(e) => tracing::warn!("Live API usage unavailable, showing flushed totals: {}", e),
+            }
+        }
+
+        let daily: Vec<DailyUsage> = first
+            .iter_days()
+            .take_while(|day| *day <= today)
+            .map(|day| {
+                let counts = by_day.get(&day).copied().unwrap_or_default();
+                DailyUsage::new(day, counts.requests, counts.errors, counts.rate_limited)
+            })
+            .collect();
+
+        let (requests, errors, rate_limited) = daily.iter().fold((0, 0, 0), |(r, e, l), d| {
+            (r + d.requests, e + d.errors, l + d.rate_limited)
+        });
+
+        Ok(UsageResponse {
+            days,
+            daily,
+            totals: UsageTotals { requests, errors, rate_limited, error_rate: error_rate(errors, requests) },
+            limit: limit_status(last_limited_at, now.timestamp()),
+        })
+    }
+}
+
+/// Larger of the flushed and live value of each counter
+fn merge(stored: &UsageCounts, live: &UsageCounts) -> UsageCounts {
+    UsageCounts {
+        requests: stored.requests.max(live.requests),
+        errors: stored.errors.max(live.errors),
+        rate_limited: stored.rate_limited.max(live.rate_limited),
+        last_limited_at: stored.last_limited_at.max(live.last_limited_at),
+    }
+}
+
+/// Limited while the latest 429 is less than one replenish interval old
+pub fn limit_status(last_limited_at: Option<i64>, now: i64) -> RateLimitStatus {
+    let quota = runtime_config().current().rate_limit_quota();
+    let interval = quota.replenish_every.as_secs();
+    let retry_after_secs = last_limited_at
+        .map(|at| interval as i64 - (now - at))
+        .filter(|remaining| *remaining > 0)
+        .map(|remaining| remaining as u64);
+
+    RateLimitStatus {
+        burst: quota.burst,
+        replenish_every_secs: interval,
+        limited: retry_after_secs.is_some(),
+        retry_after_secs,
+    }
+}
+
+// =============================================================================
+// ANTI-PHISHING ERROR
+// =============================================================================
+
+#[derive(Debug)]
+pub enum AntiPhishingError {
+    InvalidCode,
+    DatabaseError(String),
+}
+
+impl std::fmt::Display for AntiPhishingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AntiPhishingError::InvalidCode => {
+                write!(f, "Code must be {}-{} letters or digits", MIN_CODE_LEN, MAX_CODE_LEN)
+            }
+            AntiPhishingError::DatabaseError(e) => write!(f, "Database error: {}", e),
+        }
+    }
+}
+
+impl AntiPhishingError {
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            AntiPhishingError::InvalidCode => StatusCode::BAD_REQUEST,
+            AntiPhishingError::DatabaseError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+impl From<sqlx::Error> for AntiPhishingError {
+    fn from(err: sqlx::Error) -> Self {
+        AntiPhishingError::DatabaseError(err.to_string())
+    }
+}
+
+pub fn valid_code(code: &str) -> bool {
+    (MIN_CODE_LEN..=MAX_CODE_LEN).contains(&code.chars().count()) && code.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
+pub fn generate_code() -> String {
+    let mut rng = rand::rng();
+    (0..GENERATED_CODE_LEN)
+        .map(|_| CODE_CHARSET[rng.random_range(0..CODE_CHARSET.len())] as char)
+        .collect()
+}
+
+// =============================================================================
+// ANTI-PHISHING CRUD
+// =============================================================================
+
+pub struct AntiPhishingCrud {
+    pool: Pool<MySql>,
+}
+
+impl AntiPhishingCrud {
+    pub fn new(pool: Pool<MySql>) -> Self {
+        Self { pool }
+    }
+
+    pub async fn get(&self, user_id: &str) -> Result<AntiPhishingResponse, AntiPhishingError> {
+        let row: Option<(Option<SealedString>, Option<DateTime<Utc>>)> =
+            sqlx::query_as("SELECT anti_phishing_code, anti_phishing_updated_at FROM users WHERE id = ?")
+                .bind(user_id)
+                .fetch_optional(&self.pool)
+                .await?;
+        let (code, updated_at) = row.unwrap_or_default();
+        Ok(AntiPhishingResponse { code: code.map(String::from), updated_at })
+    }
+
+    /// Set the user's code; `None` generates a fresh one
+    pub async fn set(&self, user_id: &str, code: Option<&str>) -> Result<AntiPhishingResponse, AntiPhishingError> {
+        let code = match code.map(str::trim) {
+            Some(code) if valid_code(code) => code.to_string(),
+            Some(_) => return Err(AntiPhishingError::InvalidCode),
+            None => generate_code(),
+        };
+
+        sqlx::query("UPDATE users SET anti_phishing_code = ?, anti_phishing_updated_at = NOW() WHERE id = ?")
+            .bind(SealedString(code))
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+
+        self.get(user_id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_anti_phishing_codes() {
+        assert!(valid_code("Blue42"));
+        assert!(!valid_code("abc"));
+        assert!(!valid_code("with space"));
+        assert!(!valid_code(&"a".repeat(MAX_CODE_LEN + 1)));
+
+        let generated = generate_code();
+        assert_eq!(generated.len(), GENERATED_CODE_LEN);
+        assert!(valid_code(&generated));
+    }
+
+    #[test]
+    fn test_limit_status() {
+        let now = 1_700_000_000;
+        let status = limit_status(Some(now - 20), now);
+        assert!(status.limited);
+        assert_eq!(status.retry_after_secs, Some(40));
+
+        assert!(!limit_status(Some(now - 60), now).limited);
+        assert!(!limit_status(None, now).limited);
+    }
+
+    #[test]
+    fn test_merge_takes_larger_counters() {
+        let stored = UsageCounts { requests: 100, errors: 4, rate_limited: 0, last_limited_at: None };
+        let live = UsageCounts { requests: 120, errors: 3, rate_limited: 2, last_limited_at: Some(5) };
+        let merged = merge(&stored, &live);
+        assert_eq!((merged.requests, merged.errors, merged.rate_limited), (120, 4, 2));
+        assert_eq!(merged.last_limited_at, Some(5));
+    }
+}
@@ -0,0 +1,14 @@
+use axum::Router;
+use std::sync::Arc;
+
+use crate::AppState;
+use crate::manifest::DeclaredRoutes;
+use super::controller::{get_anti_phishing_code, get_usage, rotate_anti_phishing_code, set_anti_phishing_code};
+
+pub fn account_routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .declared("getUsage", get_usage)
+        .declared("getAntiPhishingCode", get_anti_phishing_code)
+        .declared("setAntiPhishingCode", set_anti_phishing_code)
+        .declared("rotateAntiPhishingCode", rotate_anti_phishing_code)
+}
@@ -0,0 +1,114 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+// =============================================================================
+// REQUESTS
+// =============================================================================
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct UsageQuery {
+    /// Days of history including today, 1-90 (default 30)
+    pub days: Option<i64>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct SetAntiPhishingCodeRequest {
+    /// 4-20 letters or digits
+    pub code: String,
+}
+
+// =============================================================================
+// RESPONSES
+// =============================================================================
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct DailyUsage {
+    /// UTC day
+    pub date: NaiveDate,
+    pub requests: u64,
+    /// Responses with a 4xx or 5xx status, rate-limit rejections excluded
+    pub errors: u64,
+    /// Requests rejected with 429
+    pub rate_limited: u64,
+    /// `errors / requests`, 0 without requests
+    pub error_rate: f64,
+}
+
+impl DailyUsage {
+    pub fn new(date: NaiveDate, requests: u64, errors: u64, rate_limited: u64) -> Self {
+        Self { date, requests, errors, rate_limited, error_rate: error_rate(errors, requests) }
+    }
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct UsageTotals {
+    pub requests: u64,
+    pub errors: u64,
+    pub rate_limited: u64,
+    pub error_rate: f64,
+}
+
+/// The API rate limit and where the caller stands against it. Limits apply
+/// per client address; `limited` reflects this account's latest rejection.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct RateLimitStatus {
+    /// Requests allowed at once
+    pub burst: u32,
+    /// Seconds to earn back one request after the burst is spent
+    pub replenish_every_secs: u64,
+    /// Whether a request from this account was rejected within the last
+    /// replenish interval
+    pub limited: bool,
+    /// Seconds until another request is allowed, when limited
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry_after_secs: Option<u64>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct UsageResponse {
+    pub days: i64,
+    /// Oldest day first; days without traffic are included with zeros
+    pub daily: Vec<DailyUsage>,
+    pub totals: UsageTotals,
+    pub limit: RateLimitStatus,
+}
+
+/// Shown at the top of every email we send the account
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct AntiPhishingResponse {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct AntiPhishingErrorResponse {
+    pub error: String,
+}
+
+impl AntiPhishingErrorResponse {
+    pub fn new(error: impl Into<String>) -> Self {
+        Self { error: error.into() }
+    }
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct UsageErrorResponse {
+    pub error: String,
+}
+
+impl UsageErrorResponse {
+    pub fn new(error: impl Into<String>) -> Self {
+        Self { error: error.into() }
+    }
+}
+
+pub fn error_rate(errors: u64, requests: u64) -> f64 {
+    if requests == 0 {
+        0.0
+    } else {
+        errors as f64 / requests as f64
+    }
+}
@@ -0,0 +1,82 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use std::sync::Arc;
+
+use crate::AppState;
+use crate::modules::auth::interface::{scope::AccountManage, Scoped};
+use super::crud::{AddressBookCrud, AddressBookError};
+use super::schema::{
+    AddressBookErrorResponse, AddressBookQuery, AddressBookResponse, AddressResponse,
+    CreateAddressRequest, UpdateAddressRequest,
+};
+
+fn to_error(e: AddressBookError) -> (StatusCode, Json<AddressBookErrorResponse>) {
+    (e.status_code(), Json(AddressBookErrorResponse::new(e.to_string())))
+}
+
+// =============================================================================
+// GET  /address-book - List saved addresses
+// POST /address-book - Save an address (requires 2FA code)
+// =============================================================================
+
+pub async fn list_addresses(
+    State(state): State<Arc<AppState>>,
+    user: Scoped<AccountManage>,
+    Query(query): Query<AddressBookQuery>,
+) -> Result<Json<AddressBookResponse>, (StatusCode, Json<AddressBookErrorResponse>)> {
+    let crud = AddressBookCrud::new(state.db.clone(), Some(state.redis.clone()));
+
+    let entries = crud
+        .list(&user.0.id, query.currency.as_deref(), query.network.as_deref())
+        .await
+        .map_err(to_error)?;
+
+    Ok(Json(AddressBookResponse {
+        addresses: entries.into_iter().map(Into::into).collect(),
+    }))
+}
+
+pub async fn create_address(
+    State(state): State<Arc<AppState>>,
+    user: Scoped<AccountManage>,
+    Json(payload): Json<CreateAddressRequest>,
+) -> Result<(StatusCode, Json<AddressResponse>), (StatusCode, Json<AddressBookErrorResponse>)> {
+    let crud = AddressBookCrud::new(state.db.clone(), Some(state.redis.clone()));
+
+    let entry = crud.create(&user.0, &payload).await.map_err(to_error)?;
+
+    Ok((StatusCode::CREATED, Json(entry.into())))
+}
+
+// =============================================================================
+// PATCH  /address-book/{id} - Rename
+// DELETE /address-book/{id} - Remove
+// =============================================================================
+
+pub async fn update_address(
+    State(state): State<Arc<AppState>>,
+    user: Scoped<AccountManage>,
+    Path(entry_id): Path<String>,
+    Json(payload): Json<UpdateAddressRequest>,
+) -> Result<Json<AddressResponse>, (StatusCode, Json<AddressBookErrorResponse>)> {
+    let crud = AddressBookCrud::new(state.db.clone(), Some(state.redis.clone()));
+
+    let entry = crud.rename(&user.0.id, &entry_id, &payload.label).await.map_err(to_error)?;
+
+    Ok(Json(entry.into()))
+}
+
+pub async fn delete_address(
+    State(state): State<Arc<AppState>>,
+    user: Scoped<AccountManage>,
+    Path(entry_id): Path<String>,
+) -> Result<StatusCode, (StatusCode, Json<AddressBookErrorResponse>)> {
+    let crud = AddressBookCrud::new(state.db.clone(), Some(state.redis.clone()));
+
+    crud.delete(&user.0.id, &entry_id).await.map_err(to_error)?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,239 @@
+use axum::http::StatusCode;
+use sqlx::{MySql, Pool};
+use uuid::Uuid;
+
+use super::model::AddressBookEntry;
+use super::schema::CreateAddressRequest;
+use crate::modules::auth::model::User;
+use crate::modules::swap::crud::SwapCrud;
+use crate::modules::swap::schema::ValidateAddressRequest;
+use crate::services::redis_cache::RedisService;
+use crate::services::totp::redeem_totp;
+
+pub const MAX_ENTRIES_PER_USER: i64 = 200;
+const MAX_LABEL_LEN: usize = 100;
+
+// =============================================================================
+// ADDRESS BOOK ERROR
+// =============================================================================
+
+#[derive(Debug)]
+pub enum AddressBookError {
+    TwoFactorRequired,
+    InvalidTwoFactorCode,
+    InvalidLabel,
+    InvalidAddress,
+    DuplicateAddress,
+    TooManyEntries,
+    EntryNotFound,
+    ValidationUnavailable(String),
+    DatabaseError(String),
+}
+
+impl std::fmt::Display for AddressBookError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AddressBookError::TwoFactorRequired => {
+                write!(f, "Enable two-factor authentication to save addresses")
+            }
+            AddressBookError::InvalidTwoFactorCode => write!(f, "Invalid two-factor code"),
+            AddressBookError::InvalidLabel => {
+                write!(f, "Label must be between 1 and {} characters", MAX_LABEL_LEN)
+            }
+            AddressBookError::InvalidAddress => write!(f, "Invalid address"),
+            AddressBookError::DuplicateAddress => write!(f, "Address is already saved"),
+            AddressBookError::TooManyEntries => write!(
+                f,
+                "A maximum of {} saved addresses is allowed per account",
+                MAX_ENTRIES_PER_USER
+            ),
+            AddressBookError::EntryNotFound => write!(f, "Address book entry not found"),
+            AddressBookError::ValidationUnavailable(e) => {
+                write!(f, "Address validation unavailable: {}", e)
+            }
+            AddressBookError::DatabaseError(e) => write!(f, "Database error: {}", e),
+        }
+    }
+}
+
+impl AddressBookError {
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            AddressBookError::TwoFactorRequired => StatusCode::FORBIDDEN,
+            AddressBookError::InvalidTwoFactorCode => StatusCode::UNAUTHORIZED,
+            AddressBookError::InvalidLabel => StatusCode::BAD_REQUEST,
+            AddressBookError::InvalidAddress => StatusCode::BAD_REQUEST,
+            AddressBookError::DuplicateAddress => StatusCode::CONFLICT,
+            AddressBookError::TooManyEntries => StatusCode::CONFLICT,
+            AddressBookError::EntryNotFound => StatusCode::NOT_FOUND,
+            AddressBookError::ValidationUnavailable(_) => StatusCode::BAD_GATEWAY,
+            AddressBookError::DatabaseError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+impl From<sqlx::Error> for AddressBookError {
+    fn from(err: sqlx::Error) -> Self {
+        match &err {
+            sqlx::Error::Database(db) if db.is_unique_violation() => AddressBookError::DuplicateAddress,
+            _ => AddressBookError::DatabaseError(err.to_string()),
+        }
+    }
+}
+
+fn validate_label(label: &str) -> Result<&str, AddressBookError> {
+    let label = label.trim();
+    if label.is_empty() || label.chars().count() > MAX_LABEL_LEN {
+        return Err(AddressBookError::InvalidLabel);
+    }
+    Ok(label)
+}
+
+// =============================================================================
+// ADDRESS BOOK CRUD
+// =============================================================================
+
+pub struct AddressBookCrud {
+    pool: Pool<MySql>,
+    redis_service: Option<RedisService>,
+}
+
+impl AddressBookCrud {
+    pub fn new(pool: Pool<MySql>, redis_service: Option<RedisService>) -> Self {
+        Self { pool, redis_service }
+    }
+
+    /// Save a new address after checking 2FA and validating it with the provider
+    pub async fn create(
+        &self,
+        user: &User,
+        request: &CreateAddressRequest,
+    ) -> Result<AddressBookEntry, AddressBookError> {
+        let secret = match (&user.two_factor_enabled, &user.two_factor_secret) {
+            (true, Some(secret)) => secret,
+            _ => return Err(AddressBookError::TwoFactorRequired),
+        };
+        // A code is spent here, so one seen in transit can't add a second address
+        if !redeem_totp(&self.pool, &user.id, secret, &request.two_factor_code).await? {
+            return Err(AddressBookError::InvalidTwoFactorCode);
+        }
+
+        let label = validate_label(&request.label)?;
+        let address = request.address.trim();
+        if address.is_empty() {
+            return Err(AddressBookError::InvalidAddress);
+        }
+
+        let (count,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM address_book_entries WHERE user_id = ?")
+            .bind(&user.id)
+            .fetch_one(&self.pool)
+            .await?;
+        if count >= MAX_ENTRIES_PER_USER {
+            return Err(AddressBookError::TooManyEntries);
+        }
+
+        let swap_crud = SwapCrud::new(self.pool.clone(), self.redis_service.clone(), None);
+        let validation = swap_crud
+            .validate_address(&ValidateAddressRequest {
+                ticker: request.currency.clone(),
+                network: request.network.clone(),
+                address: address.to_string(),
+            })
+            .await
+            .map_err(|e| AddressBookError::ValidationUnavailable(e.to_string()))?;
+        if !validation.valid {
+            return Err(AddressBookError::InvalidAddress);
+        }
+
+        let id = Uuid::new_v4().to_string();
+
+        sqlx::query(
+            r#"
+            INSERT INTO address_book_entries (id, user_id, label, currency, network, address, extra_id)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&id)
+        .bind(&user.id)
+        .bind(label)
+        .bind(request.currency.to_lowercase())
+        .bind(request.network.to_lowercase())
+        .bind(address)
+        .bind(&request.extra_id)
+        .execute(&self.pool)
+        .await?;
+
+        self.get(&user.id, &id).await
+    }
+
+    pub async fn list(
+        &self,
+        user_id: &str,
+        currency: Option<&str>,
+        network: Option<&str>,
+    ) -> Result<Vec<AddressBookEntry>, AddressBookError> {
+        let entries = sqlx::query_as::<_, AddressBookEntry>(
+            r#"
+            SELECT id, user_id, label, currency, network, address, extra_id, created_at, updated_at
+            FROM address_book_entries
+            WHERE user_id = ?
+              AND (? IS NULL OR currency = ?)
+              AND (? IS NULL OR network = ?)
+            ORDER BY label ASC
+            "#,
+        )
+        .bind(user_id)
+        .bind(currency.map(str::to_lowercase))
+        .bind(currency.map(str::to_lowercase))
+        .bind(network.map(str::to_lowercase))
+        .bind(network.map(str::to_lowercase))
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(entries)
+    }
+
+    pub async fn get(&self, user_id: &str, entry_id: &str) -> Result<AddressBookEntry, AddressBookError> {
+        sqlx::query_as::<_, AddressBookEntry>(
+            r#"
+            SELECT id, user_id, label, currency, network, address, extra_id, created_at, updated_at
+            FROM address_book_entries
+            WHERE id = ? AND user_id = ?
+            "#,
+        )
+        .bind(entry_id)
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or(AddressBookError::EntryNotFound)
+    }
+
+    pub async fn rename(&self, user_id: &str, entry_id: &str, label: &str) -> Result<AddressBookEntry, AddressBookError> {
+        let label = validate_label(label)?;
+
+        let result = sqlx::query("UPDATE address_book_entries SET label = ?, updated_at = NOW() WHERE id = ? AND user_id = ?")
+            .bind(label)
+            .bind(entry_id)
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AddressBookError::EntryNotFound);
+        }
+        self.get(user_id, entry_id).await
+    }
+
+    pub async fn delete(&self, user_id: &str, entry_id: &str) -> Result<(), AddressBookError> {
+        let result = sqlx::query("DELETE FROM address_book_entries WHERE id = ? AND user_id = ?")
+            .bind(entry_id)
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AddressBookError::EntryNotFound);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,7 @@
+pub mod schema;
+pub mod model;
+pub mod crud;
+pub mod controller;
+pub mod routes;
+
+pub use routes::address_book_routes;
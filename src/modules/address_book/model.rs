@@ -0,0 +1,20 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+// =============================================================================
+// ADDRESS BOOK ENTRY
+// =============================================================================
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct AddressBookEntry {
+    pub id: String,
+    pub user_id: String,
+    pub label: String,
+    pub currency: String,
+    pub network: String,
+    pub address: String,
+    pub extra_id: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
@@ -0,0 +1,14 @@
+use axum::Router;
+use std::sync::Arc;
+
+use crate::AppState;
+use crate::manifest::DeclaredRoutes;
+use super::controller::{create_address, delete_address, list_addresses, update_address};
+
+pub fn address_book_routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .declared("listAddresses", list_addresses)
+        .declared("createAddress", create_address)
+        .declared("updateAddress", update_address)
+        .declared("deleteAddress", delete_address)
+}
@@ -0,0 +1,82 @@
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::model::AddressBookEntry;
+
+// =============================================================================
+// REQUESTS
+// =============================================================================
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CreateAddressRequest {
+    pub label: String,
+    pub currency: String,
+    pub network: String,
+    pub address: String,
+    #[serde(default)]
+    pub extra_id: Option<String>,
+    /// Current TOTP code; saving addresses requires 2FA
+    pub two_factor_code: String,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct UpdateAddressRequest {
+    pub label: String,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct AddressBookQuery {
+    pub currency: Option<String>,
+    pub network: Option<String>,
+}
+
+// =============================================================================
+// RESPONSES
+// =============================================================================
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct AddressResponse {
+    pub id: String,
+    pub label: String,
+    pub currency: String,
+    pub network: String,
+    pub address: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extra_id: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<AddressBookEntry> for AddressResponse {
+    fn from(e: AddressBookEntry) -> Self {
+        Self {
+            id: e.id,
+            label: e.label,
+            currency: e.currency,
+            network: e.network,
+            address: e.address,
+            extra_id: e.extra_id,
+            created_at: e.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct AddressBookResponse {
+    pub addresses: Vec<AddressResponse>,
+}
+
+// =============================================================================
+// ERROR RESPONSE
+// =============================================================================
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct AddressBookErrorResponse {
+    pub error: String,
+}
+
+impl AddressBookErrorResponse {
+    pub fn new(error: impl Into<String>) -> Self {
+        Self { error: error.into() }
+    }
+}
@@ -0,0 +1,1597 @@
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Path, Query, State,
+    },
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::AppState;
+use crate::modules::analytics::crud::AnalyticsCrud;
+use crate::modules::analytics::schema::{
+    AnalyticsErrorResponse, FunnelQuery, FunnelResponse, FxRatesQuery, FxRatesResponse, GasAnalyticsQuery,
+    GasAnalyticsResponse,
+};
+use crate::modules::auth::interface::AdminUser;
+use crate::modules::backups::crud::BackupCrud;
+use crate::modules::backups::schema::{BackupErrorResponse, BackupRunResponse, BackupRunsQuery, BackupRunsResponse};
+use crate::modules::balances::controller::to_withdrawal_response;
+use crate::modules::balances::crud::BalanceCrud;
+use crate::modules::balances::schema::{BalanceErrorResponse, RejectWithdrawalRequest, WithdrawalResponse};
+use crate::modules::commissions::crud::{CommissionCrud, CommissionError};
+use crate::modules::commissions::schema::{
+    CommissionErrorResponse, ProviderCommissionResponse, ProviderCommissionsResponse, RevenueQuery,
+    RevenueSummaryResponse, SetProviderCommissionRequest,
+};
+use crate::modules::halts::crud::{HaltCrud, HaltError};
+use crate::modules::moderation::crud::{ModerationCrud, ModerationError, StatusChange};
+use crate::modules::moderation::model::{AccountStatus, ReputationStatus, StatusSource};
+use crate::modules::moderation::schema::{
+    AccountStatusResponse, AddressReputationDetailResponse, AddressReputationQuery, AddressReputationsResponse,
+    ModerationErrorResponse, ReportRiskSignalRequest, ReviewAddressRequest, RiskAssessmentResponse,
+    SetAccountStatusRequest,
+};
+use crate::modules::payout_guard::crud::PayoutGuardCrud;
+use crate::modules::payout_guard::model::PayoutKind;
+use crate::modules::payout_guard::schema::{
+    PayoutGuardErrorResponse, PayoutGuardStatusResponse, RearmPayoutGuardRequest, TripPayoutGuardRequest,
+};
+use crate::modules::promotions::crud::{PromotionCrud, PromotionError};
+use crate::modules::promotions::schema::{
+    CreatePromotionRequest, PromotionErrorResponse, PromotionReportQuery, PromotionReportResponse, PromotionResponse,
+    PromotionsResponse, UpdatePromotionRequest,
+};
+use crate::modules::halts::schema::{
+    HaltErrorResponse, SetCurrencyEnabledRequest, SetPairEnabledRequest, TradingHaltsResponse,
+};
+use crate::modules::reconciliation::crud::{ReconciliationCrud, ReconciliationError};
+use crate::modules::reconciliation::model::DiscrepancyStatus;
+use crate::modules::reconciliation::schema::{
+    DiscrepanciesQuery, DiscrepanciesResponse, DiscrepancyResponse, ReconciliationErrorResponse,
+    ReconciliationRunsQuery, ReconciliationRunsResponse, ReviewDiscrepancyRequest,
+};
+use crate::modules::recovery::crud::{evm_chain_id, is_evm_address, RecoveryCrud, RecoveryError};
+use crate::modules::recovery::schema::{
+    DismissMemoDepositRequest, DismissOrphanedFundsRequest, DismissWrongNetworkRequest, MemoDepositResponse,
+    MemoDepositsQuery, MemoDepositsResponse, OrphanedFundsListResponse, OrphanedFundsQuery, OrphanedFundsResponse,
+    RecoverOrphanedFundsRequest, RecoverWrongNetworkRequest, RecoveryErrorResponse, ReleaseMemoDepositRequest,
+    WalletAuditRunResponse, WalletAuditRunsQuery, WalletAuditRunsResponse, WrongNetworkCaseResponse,
+    WrongNetworkCasesQuery, WrongNetworkCasesResponse,
+};
+use crate::modules::swap::crud::{SwapCrud, SwapError};
+use crate::modules::swap::schema::{
+    StuckSwapsQuery, StuckSwapsResponse, SwapDebugResponse, SwapErrorResponse, SwapSearchQuery, SwapSearchResponse,
+};
+use crate::modules::swap::search::SwapSearch;
+use crate::modules::wallet::crud::WalletCrud;
+use crate::services::amount::{self, Decimal};
+use crate::services::backup::{BackupError, BackupService};
+use crate::services::payout::{PayoutGuard, PayoutGuardError, PlannedPayout};
+use crate::services::reconciliation::{WalletAuditConfig, WalletAuditor};
+use crate::services::fx::{reporting_currency, FxError, FxStore};
+use super::schema::{
+    CreateStreamGroupRequest, EffectiveConfigResponse, EventStreamErrorResponse, EventStreamQuery,
+    EventStreamResponse, SignMessageRequest, SignedMessage, StreamGroup, StreamGroupsResponse,
+    WalletSigningErrorResponse,
+};
+use crate::services::blockchain::listener::{evm_rpc_url, BlockchainListener};
+use crate::services::gas::station::native_currency;
+use crate::services::token::TokenRegistry;
+use crate::services::sandbox::signing_chain_id;
+use crate::services::events::ops_events;
+use crate::services::events::stream::{EventStream, EventStreamConfig, EventStreamError, StreamOffset};
+use crate::services::address_reputation::AddressReputationService;
+use crate::services::risk::{RiskEngine, RiskPolicy, SignalContext};
+use crate::services::runtime_config::runtime_config;
+use crate::services::session::{websocket_origin_ok, SessionConfig};
+use crate::services::swap_sla::SlaBreachStore;
+use crate::services::client_ip::ClientIp;
+use crate::services::wallet::manager::WalletManager;
+use crate::services::wallet::message_signing::{HotWalletMessageSigner, MessageSigningPolicy, MessageSigningRequest};
+use crate::services::wallet::rpc::{BlockchainProvider, HttpRpcClient};
+
+// =============================================================================
+// POST /admin/withdrawals/{id}/approve - Release a withdrawal to the payout pipeline
+// =============================================================================
+
+pub async fn approve_withdrawal(
+    State(state): State<Arc<AppState>>,
+    admin: AdminUser,
+    Path(withdrawal_id): Path<String>,
+) -> Result<Json<WithdrawalResponse>, (StatusCode, Json<BalanceErrorResponse>)> {
+    let crud = BalanceCrud::new(state.db.clone());
+
+    let withdrawal = crud.approve_withdrawal(&admin.0.id, &withdrawal_id).await.map_err(|e| {
+        (e.status_code(), Json(BalanceErrorResponse::new(e.to_string())))
+    })?;
+
+    tracing::info!("Withdrawal {} approved by {}", withdrawal_id, admin.0.id);
+
+    Ok(Json(to_withdrawal_response(withdrawal)))
+}
+
+// =============================================================================
+// POST /admin/withdrawals/{id}/reject - Reject and release held funds
+// =============================================================================
+
+pub async fn reject_withdrawal(
+    State(state): State<Arc<AppState>>,
+    admin: AdminUser,
+    Path(withdrawal_id): Path<String>,
+    Json(payload): Json<RejectWithdrawalRequest>,
+) -> Result<Json<WithdrawalResponse>, (StatusCode, Json<BalanceErrorResponse>)> {
+    let crud = BalanceCrud::new(state.db.clone());
+
+    let withdrawal = crud
+        .reject_withdrawal(&admin.0.id, &withdrawal_id, payload.reason.as_deref())
+        .await
+        .map_err(|e| (e.status_code(), Json(BalanceErrorResponse::new(e.to_string()))))?;
+
+    tracing::info!("Withdrawal {} rejected by {}", withdrawal_id, admin.0.id);
+
+    Ok(Json(to_withdrawal_response(withdrawal)))
+}
+
+fn recovery_error(e: RecoveryError) -> (StatusCode, Json<RecoveryErrorResponse>) {
+    (e.status_code(), Json(RecoveryErrorResponse::new(e.to_string())))
+}
+
+// =============================================================================
+// GET /admin/wrong-network-cases - Deposits found on an unexpected EVM chain
+// =============================================================================
+
+pub async fn list_wrong_network_cases(
+    State(state): State<Arc<AppState>>,
+    _admin: AdminUser,
+    Query(query): Query<WrongNetworkCasesQuery>,
+) -> Result<Json<WrongNetworkCasesResponse>, (StatusCode, Json<RecoveryErrorResponse>)> {
+    let crud = RecoveryCrud::new(state.db.clone());
+    let cases = crud.list_cases(query.status, query.limit).await.map_err(recovery_error)?;
+
+    Ok(Json(WrongNetworkCasesResponse {
+        cases: cases.into_iter().map(Into::into).collect(),
+    }))
+}
+
+// =============================================================================
+// GET /admin/wrong-network-cases/{id}
+// =============================================================================
+
+pub async fn get_wrong_network_case(
+    State(state): State<Arc<AppState>>,
+    _admin: AdminUser,
+    Path(case_id): Path<String>,
+) -> Result<Json<WrongNetworkCaseResponse>, (StatusCode, Json<RecoveryErrorResponse>)> {
+    let crud = RecoveryCrud::new(state.db.clone());
+    let case = crud
+        .get_case(&case_id)
+        .await
+        .map_err(recovery_error)?
+        .ok_or_else(|| recovery_error(RecoveryError::CaseNotFound))?;
+
+    Ok(Json(case.into()))
+}
+
+// =============================================================================
+// POST /admin/wrong-network-cases/{id}/recover - Pay out on the chain the funds landed on
+// =============================================================================
+
+pub async fn recover_wrong_network_case(
+    State(state): State<Arc<AppState>>,
+    admin: AdminUser,
+    Path(case_id): Path<String>,
+    Json(payload): Json<RecoverWrongNetworkRequest>,
+) -> Result<Json<WrongNetworkCaseResponse>, (StatusCode, Json<RecoveryErrorResponse>)> {
+    let recipient = payload.recipient_address.trim();
+    if !is_evm_address(recipient) {
+        return Err(recovery_error(RecoveryError::InvalidAddress));
+    }
+
+    let crud = RecoveryCrud::new(state.db.clone());
+    let case = crud.claim_for_recovery(&admin.0.id, &case_id, recipient).await.map_err(recovery_error)?;
+
+    let (tx_hash, amount) = match send_recovery_payout(&state, &case_id, &case.detected_network, case.address_index, recipient, None).await {
+        Ok(sent) => sent,
+        Err(e) => {
+            tracing::error!("❌ Recovery for wrong-network case {} failed: {}", case_id, e);
+            crud.fail_recovery(&case_id, &e.to_string()).await.map_err(recovery_error)?;
+            return Err(recovery_error(e));
+        }
+    };
+
+    let case = crud.complete_recovery(&case_id, &tx_hash, amount).await.map_err(recovery_error)?;
+
+    tracing::info!(
+        "✅ Wrong-network case {} recovered by {}: {} on {} (tx {})",
+        case_id, admin.0.id, amount, case.detected_network, tx_hash
+    );
+
+    Ok(Json(case.into()))
+}
+
+/// Sweep the address at `address_index` on `network` with its own key,
+/// under the payout guard like every other payout: the native balance, or
+/// the whole balance of the canonical token at `token_contract`.
+/// `reference` is the case or finding being recovered.
+async fn send_recovery_payout(
+    state: &AppState,
+    reference: &str,
+    network: &str,
+    address_index: u32,
+    recipient: &str,
+    token_contract: Option<&str>,
+) -> Result<(String, Decimal), RecoveryError> {
+    let guard = PayoutGuard::new(state.db.clone());
+    // Nothing is looked up on chain while payouts are halted
+    if let Some(trip) = guard.active_trip().await.map_err(|e| RecoveryError::PayoutRefused(e.to_string()))? {
+        return Err(RecoveryError::PayoutRefused(PayoutGuardError::Halted(trip.reason).to_string()));
+    }
+
+    let rpc_url = evm_rpc_url(network).ok_or_else(|| RecoveryError::NetworkNotConfigured(network.to_string()))?;
+    // Signed for the test network in a sandbox deployment
+    let chain_id = evm_chain_id(network)
+        .map(|_| signing_chain_id(network))
+        .ok_or_else(|| RecoveryError::NetworkNotConfigured(network.to_string()))?;
+
+    let token = match token_contract {
+        Some(contract) => Some(
+            TokenRegistry::new(state.db.clone())
+                .canonical_tokens(network)
+                .await
+                .map_err(|e| RecoveryError::DatabaseError(e.to_string()))?
+                .into_iter()
+                .find(|token| token.contract_address.eq_ignore_ascii_case(contract))
+                .ok_or_else(|| {
+                    RecoveryError::PayoutFailed(format!("{} is not a canonical token on {}", contract, network))
+                })?,
+        ),
+        None => None,
+    };
+
+    let provider: Arc<dyn BlockchainProvider> = Arc::new(HttpRpcClient::new(rpc_url));
+    let wallet_manager = WalletManager::new(WalletCrud::new(state.db.clone()), state.wallet_mnemonic.clone(), provider);
+
+    let (amount, gas_price) = match &token {
+        Some(token) => wallet_manager.plan_token_recovery_sweep(address_index, token).await,
+        None => wallet_manager.plan_recovery_sweep(address_index).await,
+    }
+    .map_err(RecoveryError::PayoutFailed)?;
+
+    let currency = token.as_ref().map_or(native_currency(network), |token| token.symbol.as_str());
+    let planned = PlannedPayout::new(PayoutKind::Recovery, reference, network, currency, amount);
+    let reservation = guard.check(&planned).await.map_err(|e| RecoveryError::PayoutRefused(e.to_string()))?;
+
+    let result = match &token {
+        Some(token) => {
+            wallet_manager
+                .send_token_recovery_sweep(address_index, chain_id, token, recipient, amount, gas_price)
+                .await
+        }
+        None => {
+            wallet_manager
+                .send_recovery_sweep(address_index, chain_id, recipient, amount, gas_price)
+                .await
+        }
+    };
+    guard.record(&reservation, result.as_ref().err().map(String::as_str)).await;
+
+    let tx_hash = result.map_err(RecoveryError::PayoutFailed)?;
+    Ok((tx_hash, amount))
+}
+
+// =============================================================================
+// POST /admin/wrong-network-cases/{id}/dismiss - Close without moving funds
+// =============================================================================
+
+pub async fn dismiss_wrong_network_case(
+    State(state): State<Arc<AppState>>,
+    admin: AdminUser,
+    Path(case_id): Path<String>,
+    Json(payload): Json<DismissWrongNetworkRequest>,
+) -> Result<Json<WrongNetworkCaseResponse>, (StatusCode, Json<RecoveryErrorResponse>)> {
+    let crud = RecoveryCrud::new(state.db.clone());
+    let case = crud
+        .dismiss_case(&admin.0.id, &case_id, payload.reason.as_deref())
+        .await
+        .map_err(recovery_error)?;
+
+    tracing::info!("Wrong-network case {} dismissed by {}", case_id, admin.0.id);
+
+    Ok(Json(case.into()))
+}
+
+// =============================================================================
+// GET /admin/memo-deposits - Hot address payments on memo chains
+// =============================================================================
+
+pub async fn list_memo_deposits(
+    State(state): State<Arc<AppState>>,
+    _admin: AdminUser,
+    Query(query): Query<MemoDepositsQuery>,
+) -> Result<Json<MemoDepositsResponse>, (StatusCode, Json<RecoveryErrorResponse>)> {
+    let crud = RecoveryCrud::new(state.db.clone());
+    let deposits = crud.list_memo_deposits(query.status, query.limit).await.map_err(recovery_error)?;
+
+    Ok(Json(MemoDepositsResponse {
+        deposits: deposits.into_iter().map(Into::into).collect(),
+    }))
+}
+
+// =============================================================================
+// POST /admin/memo-deposits/{id}/release - Credit a quarantined deposit to a swap
+// =============================================================================
+
+pub async fn release_memo_deposit(
+    State(state): State<Arc<AppState>>,
+    admin: AdminUser,
+    Path(deposit_id): Path<i64>,
+    Json(payload): Json<ReleaseMemoDepositRequest>,
+) -> Result<Json<MemoDepositResponse>, (StatusCode, Json<RecoveryErrorResponse>)> {
+    let crud = RecoveryCrud::new(state.db.clone());
+    let deposit = crud
+        .release_memo_deposit(&admin.0.id, deposit_id, payload.swap_id.trim(), payload.note.as_deref())
+        .await
+        .map_err(recovery_error)?;
+
+    tracing::warn!(
+        "Memo deposit {} ({} {}) released to swap {} by {}",
+        deposit_id, deposit.amount, deposit.network, payload.swap_id, admin.0.id
+    );
+
+    // The release is recorded first so a failed credit can't be released twice
+    let listener = BlockchainListener::with_providers(state.db.clone(), Default::default());
+    let amount = amount::from_f64(deposit.amount)
+        .map_err(|e| recovery_error(RecoveryError::DatabaseError(e.to_string())))?;
+    if let Err(e) = listener.credit_deposit(payload.swap_id.trim(), &deposit.network, amount).await {
+        tracing::error!("Failed to credit released memo deposit {}: {}", deposit_id, e);
+        return Err(recovery_error(RecoveryError::DatabaseError(e)));
+    }
+
+    Ok(Json(deposit.into()))
+}
+
+// =============================================================================
+// POST /admin/memo-deposits/{id}/dismiss - Close without crediting a swap
+// =============================================================================
+
+pub async fn dismiss_memo_deposit(
+    State(state): State<Arc<AppState>>,
+    admin: AdminUser,
+    Path(deposit_id): Path<i64>,
+    Json(payload): Json<DismissMemoDepositRequest>,
+) -> Result<Json<MemoDepositResponse>, (StatusCode, Json<RecoveryErrorResponse>)> {
+    let crud = RecoveryCrud::new(state.db.clone());
+    let deposit = crud
+        .dismiss_memo_deposit(&admin.0.id, deposit_id, payload.note.as_deref())
+        .await
+        .map_err(recovery_error)?;
+
+    tracing::info!("Memo deposit {} dismissed by {}", deposit_id, admin.0.id);
+
+    Ok(Json(deposit.into()))
+}
+
+// =============================================================================
+// GET /admin/wallet-audit/runs - Recent HD wallet audits, newest first
+// =============================================================================
+
+pub async fn list_wallet_audit_runs(
+    State(state): State<Arc<AppState>>,
+    _admin: AdminUser,
+    Query(query): Query<WalletAuditRunsQuery>,
+) -> Result<Json<WalletAuditRunsResponse>, (StatusCode, Json<RecoveryErrorResponse>)> {
+    let crud = RecoveryCrud::new(state.db.clone());
+    let runs = crud.list_audit_runs(query.limit).await.map_err(recovery_error)?;
+
+    Ok(Json(WalletAuditRunsResponse { runs: runs.into_iter().map(Into::into).collect() }))
+}
+
+// =============================================================================
+// POST /admin/wallet-audit/runs - Audit the HD wallet now, outside the nightly schedule
+// =============================================================================
+
+pub async fn start_wallet_audit(
+    State(state): State<Arc<AppState>>,
+    admin: AdminUser,
+) -> Result<(StatusCode, Json<WalletAuditRunResponse>), (StatusCode, Json<RecoveryErrorResponse>)> {
+    let config = WalletAuditConfig::from_env()
+        .map_err(|e| (StatusCode::SERVICE_UNAVAILABLE, Json(RecoveryErrorResponse::new(e))))?;
+    let auditor = WalletAuditor::new(state.db.clone(), config);
+    let run_id = auditor
+        .start()
+        .await
+        .map_err(|e| recovery_error(RecoveryError::DatabaseError(e)))?;
+    let run = RecoveryCrud::new(state.db.clone())
+        .get_audit_run(&run_id)
+        .await
+        .map_err(recovery_error)?
+        .ok_or_else(|| recovery_error(RecoveryError::DatabaseError(format!("Wallet audit run {} disappeared", run_id))))?;
+    tracing::info!("Wallet audit {} started by {}", run_id, admin.0.id);
+
+    // Failures are recorded on the run's job
+    tokio::spawn(async move {
+        if let Err(e) = auditor.complete(&run_id).await {
+            tracing::error!("Wallet audit {} failed: {}", run_id, e);
+        }
+    });
+
+    Ok((StatusCode::ACCEPTED, Json(run.into())))
+}
+
+// =============================================================================
+// GET /admin/orphaned-funds - Funds at allocated addresses nothing accounts for
+// =============================================================================
+
+pub async fn list_orphaned_funds(
+    State(state): State<Arc<AppState>>,
+    _admin: AdminUser,
+    Query(query): Query<OrphanedFundsQuery>,
+) -> Result<Json<OrphanedFundsListResponse>, (StatusCode, Json<RecoveryErrorResponse>)> {
+    let crud = RecoveryCrud::new(state.db.clone());
+    let funds = crud
+        .list_orphaned_funds(query.status, query.kind, query.limit)
+        .await
+        .map_err(recovery_error)?;
+
+    Ok(Json(OrphanedFundsListResponse {
+        funds: funds.into_iter().map(Into::into).collect(),
+    }))
+}
+
+// =============================================================================
+// POST /admin/orphaned-funds/{id}/recover - Sweep the address to a recipient
+// =============================================================================
+
+pub async fn recover_orphaned_funds(
+    State(state): State<Arc<AppState>>,
+    admin: AdminUser,
+    Path(funds_id): Path<String>,
+    Json(payload): Json<RecoverOrphanedFundsRequest>,
+) -> Result<Json<OrphanedFundsResponse>, (StatusCode, Json<RecoveryErrorResponse>)> {
+    let crud = RecoveryCrud::new(state.db.clone());
+    let found = crud
+        .get_orphaned_funds(&funds_id)
+        .await
+        .map_err(recovery_error)?
+        .ok_or_else(|| recovery_error(RecoveryError::OrphanedFundsNotFound))?;
+    // Bitcoin and Solana findings are swept by hand
+    if evm_chain_id(&found.chain).is_none() {
+        return Err(recovery_error(RecoveryError::SweepUnsupported(found.chain)));
+    }
+
+    let recipient = payload.recipient_address.trim();
+    if !is_evm_address(recipient) {
+        return Err(recovery_error(RecoveryError::InvalidAddress));
+    }
+
+    let funds = crud.claim_orphaned_funds(&admin.0.id, &funds_id, recipient).await.map_err(recovery_error)?;
+
+    let sent = send_recovery_payout(
+        &state,
+        &funds_id,
+        &funds.chain,
+        funds.address_index,
+        recipient,
+        funds.token_contract.as_deref(),
+    )
+    .await;
+    let (tx_hash, amount) = match sent {
+        Ok(sent) => sent,
+        Err(e) => {
+            tracing::error!("❌ Recovery of orphaned funds {} failed: {}", funds_id, e);
+            crud.fail_orphaned_funds_recovery(&funds_id, &e.to_string()).await.map_err(recovery_error)?;
+            return Err(recovery_error(e));
+        }
+    };
+
+    let funds = crud
+        .complete_orphaned_funds_recovery(&funds_id, &tx_hash, amount)
+        .await
+        .map_err(recovery_error)?;
+
+    tracing::info!(
+        "✅ Orphaned funds {} recovered by {}: {} {} on {} from index {} (tx {})",
+        funds_id, admin.0.id, amount, funds.currency, funds.chain, funds.address_index, tx_hash
+    );
+
+    Ok(Json(funds.into()))
+}
+
+// =============================================================================
+// POST /admin/orphaned-funds/{id}/dismiss - Close without moving funds
+// =============================================================================
+
+pub async fn dismiss_orphaned_funds(
+    State(state): State<Arc<AppState>>,
+    admin: AdminUser,
+    Path(funds_id): Path<String>,
+    Json(payload): Json<DismissOrphanedFundsRequest>,
+) -> Result<Json<OrphanedFundsResponse>, (StatusCode, Json<RecoveryErrorResponse>)> {
+    let crud = RecoveryCrud::new(state.db.clone());
+    let funds = crud
+        .dismiss_orphaned_funds(&admin.0.id, &funds_id, payload.reason.as_deref())
+        .await
+        .map_err(recovery_error)?;
+
+    tracing::info!("Orphaned funds {} dismissed by {}", funds_id, admin.0.id);
+
+    Ok(Json(funds.into()))
+}
+
+fn halt_error(e: HaltError) -> (StatusCode, Json<HaltErrorResponse>) {
+    (e.status_code(), Json(HaltErrorResponse::new(e.to_string())))
+}
+
+async fn halts_response(crud: &HaltCrud) -> Result<Json<TradingHaltsResponse>, (StatusCode, Json<HaltErrorResponse>)> {
+    let halts = crud.list_halts().await.map_err(halt_error)?;
+    Ok(Json(TradingHaltsResponse {
+        halts: halts.into_iter().map(Into::into).collect(),
+    }))
+}
+
+// =============================================================================
+// GET /admin/halts - Active kill-switch entries
+// =============================================================================
+
+pub async fn list_trading_halts(
+    State(state): State<Arc<AppState>>,
+    _admin: AdminUser,
+) -> Result<Json<TradingHaltsResponse>, (StatusCode, Json<HaltErrorResponse>)> {
+    halts_response(&HaltCrud::new(state.db.clone())).await
+}
+
+// =============================================================================
+// POST /admin/halts/currency - Enable or disable a currency on one network
+// =============================================================================
+
+pub async fn set_currency_enabled(
+    State(state): State<Arc<AppState>>,
+    admin: AdminUser,
+    Json(payload): Json<SetCurrencyEnabledRequest>,
+) -> Result<Json<TradingHaltsResponse>, (StatusCode, Json<HaltErrorResponse>)> {
+    use validator::Validate;
+    if let Err(e) = payload.validate() {
+        return Err((StatusCode::BAD_REQUEST, Json(HaltErrorResponse::new(e.to_string()))));
+    }
+
+    let crud = HaltCrud::new(state.db.clone());
+    crud.set_currency_enabled(
+        &admin.0.id,
+        &payload.currency,
+        &payload.network,
+        payload.enabled,
+        payload.reason.as_deref(),
+    )
+    .await
+    .map_err(halt_error)?;
+
+    tracing::warn!(
+        "Currency {} on {} {} by {}",
+        payload.currency,
+        payload.network,
+        if payload.enabled { "enabled" } else { "halted" },
+        admin.0.id
+    );
+
+    halts_response(&crud).await
+}
+
+// =============================================================================
+// POST /admin/halts/pair - Enable or disable a pair in both directions
+// =============================================================================
+
+pub async fn set_pair_enabled(
+    State(state): State<Arc<AppState>>,
+    admin: AdminUser,
+    Json(payload): Json<SetPairEnabledRequest>,
+) -> Result<Json<TradingHaltsResponse>, (StatusCode, Json<HaltErrorResponse>)> {
+    use validator::Validate;
+    if let Err(e) = payload.validate() {
+        return Err((StatusCode::BAD_REQUEST, Json(HaltErrorResponse::new(e.to_string()))));
+    }
+
+    let crud = HaltCrud::new(state.db.clone());
+    crud.set_pair_enabled(
+        &admin.0.id,
+        (&payload.from, &payload.network_from),
+        (&payload.to, &payload.network_to),
+        payload.enabled,
+        payload.reason.as_deref(),
+    )
+    .await
+    .map_err(halt_error)?;
+
+    tracing::warn!(
+        "Pair {} ({}) <-> {} ({}) {} by {}",
+        payload.from,
+        payload.network_from,
+        payload.to,
+        payload.network_to,
+        if payload.enabled { "enabled" } else { "halted" },
+        admin.0.id
+    );
+
+    halts_response(&crud).await
+}
+
+// =============================================================================
+// POST /admin/halts/{id}/lift - Remove any halt, including automatic ones
+// =============================================================================
+
+pub async fn lift_trading_halt(
+    State(state): State<Arc<AppState>>,
+    admin: AdminUser,
+    Path(halt_id): Path<String>,
+) -> Result<Json<TradingHaltsResponse>, (StatusCode, Json<HaltErrorResponse>)> {
+    let crud = HaltCrud::new(state.db.clone());
+    crud.lift_halt(&halt_id).await.map_err(halt_error)?;
+
+    tracing::warn!("Trading halt {} lifted by {}", halt_id, admin.0.id);
+
+    halts_response(&crud).await
+}
+
+fn reconciliation_error(e: ReconciliationError) -> (StatusCode, Json<ReconciliationErrorResponse>) {
+    (e.status_code(), Json(ReconciliationErrorResponse::new(e.to_string())))
+}
+
+// =============================================================================
+// GET /admin/reconciliation/runs - Recent provider reconciliation passes
+// =============================================================================
+
+pub async fn list_reconciliation_runs(
+    State(state): State<Arc<AppState>>,
+    _admin: AdminUser,
+    Query(query): Query<ReconciliationRunsQuery>,
+) -> Result<Json<ReconciliationRunsResponse>, (StatusCode, Json<ReconciliationErrorResponse>)> {
+    let crud = ReconciliationCrud::new(state.db.clone());
+    let runs = crud.list_runs(query.limit).await.map_err(reconciliation_error)?;
+
+    Ok(Json(ReconciliationRunsResponse {
+        runs: runs.into_iter().map(Into::into).collect(),
+    }))
+}
+
+// =============================================================================
+// GET /admin/reconciliation/discrepancies - Provider drift review queue
+// =============================================================================
+
+pub async fn list_discrepancies(
+    State(state): State<Arc<AppState>>,
+    _admin: AdminUser,
+    Query(query): Query<DiscrepanciesQuery>,
+) -> Result<Json<DiscrepanciesResponse>, (StatusCode, Json<ReconciliationErrorResponse>)> {
+    let crud = ReconciliationCrud::new(state.db.clone());
+    let discrepancies = crud
+        .list_discrepancies(query.status, query.kind, query.limit)
+        .await
+        .map_err(reconciliation_error)?;
+
+    Ok(Json(DiscrepanciesResponse {
+        discrepancies: discrepancies.into_iter().map(Into::into).collect(),
+    }))
+}
+
+// =============================================================================
+// POST /admin/reconciliation/discrepancies/{id}/resolve - Drift fixed on our side
+// =============================================================================
+
+pub async fn resolve_discrepancy(
+    State(state): State<Arc<AppState>>,
+    admin: AdminUser,
+    Path(discrepancy_id): Path<String>,
+    Json(payload): Json<ReviewDiscrepancyRequest>,
+) -> Result<Json<DiscrepancyResponse>, (StatusCode, Json<ReconciliationErrorResponse>)> {
+    review_discrepancy(&state, &admin, &discrepancy_id, DiscrepancyStatus::Resolved, payload).await
+}
+
+// =============================================================================
+// POST /admin/reconciliation/discrepancies/{id}/dismiss - Accept the drift
+// =============================================================================
+
+pub async fn dismiss_discrepancy(
+    State(state): State<Arc<AppState>>,
+    admin: AdminUser,
+    Path(discrepancy_id): Path<String>,
+    Json(payload): Json<ReviewDiscrepancyRequest>,
+) -> Result<Json<DiscrepancyResponse>, (StatusCode, Json<ReconciliationErrorResponse>)> {
+    review_discrepancy(&state, &admin, &discrepancy_id, DiscrepancyStatus::Dismissed, payload).await
+}
+
+async fn review_discrepancy(
+    state: &AppState,
+    admin: &AdminUser,
+    discrepancy_id: &str,
+    outcome: DiscrepancyStatus,
+    payload: ReviewDiscrepancyRequest,
+) -> Result<Json<DiscrepancyResponse>, (StatusCode, Json<ReconciliationErrorResponse>)> {
+    let crud = ReconciliationCrud::new(state.db.clone());
+    let discrepancy = crud
+        .review_discrepancy(&admin.0.id, discrepancy_id, outcome, payload.note.as_deref())
+        .await
+        .map_err(reconciliation_error)?;
+
+    tracing::info!("Discrepancy {} {} by {}", discrepancy_id, outcome.as_str(), admin.0.id);
+
+    Ok(Json(discrepancy.into()))
+}
+
+fn commission_error(e: CommissionError) -> (StatusCode, Json<CommissionErrorResponse>) {
+    (e.status_code(), Json(CommissionErrorResponse::new(e.to_string())))
+}
+
+// =============================================================================
+// GET /admin/providers/commissions - Negotiated per-provider terms
+// =============================================================================
+
+pub async fn list_provider_commissions(
+    State(state): State<Arc<AppState>>,
+    _admin: AdminUser,
+) -> Result<Json<ProviderCommissionsResponse>, (StatusCode, Json<CommissionErrorResponse>)> {
+    let commissions = CommissionCrud::new(state.db.clone())
+        .list_commissions()
+        .await
+        .map_err(commission_error)?;
+
+    Ok(Json(ProviderCommissionsResponse {
+        commissions: commissions.into_iter().map(Into::into).collect(),
+    }))
+}
+
+// =============================================================================
+// PUT /admin/providers/{id}/commission - Set a provider's commission and revenue share
+// =============================================================================
+
+pub async fn set_provider_commission(
+    State(state): State<Arc<AppState>>,
+    admin: AdminUser,
+    Path(provider_id): Path<String>,
+    Json(payload): Json<SetProviderCommissionRequest>,
+) -> Result<Json<ProviderCommissionResponse>, (StatusCode, Json<CommissionErrorResponse>)> {
+    use validator::Validate;
+    if let Err(e) = payload.validate() {
+        return Err((StatusCode::BAD_REQUEST, Json(CommissionErrorResponse::new(e.to_string()))));
+    }
+
+    let commission = CommissionCrud::new(state.db.clone())
+        .set_commission(&admin.0.id, &provider_id, payload.commission_rate, payload.revenue_share_rate)
+        .await
+        .map_err(commission_error)?;
+
+    tracing::warn!(
+        "Commission for {} set to {:?} (revenue share {}) by {}",
+        commission.provider_id,
+        commission.commission_rate,
+        commission.revenue_share_rate,
+        admin.0.id
+    );
+
+    Ok(Json(commission.into()))
+}
+
+// =============================================================================
+// DELETE /admin/providers/{id}/commission - Back to the strategy's rates
+// =============================================================================
+
+pub async fn remove_provider_commission(
+    State(state): State<Arc<AppState>>,
+    admin: AdminUser,
+    Path(provider_id): Path<String>,
+) -> Result<StatusCode, (StatusCode, Json<CommissionErrorResponse>)> {
+    CommissionCrud::new(state.db.clone())
+        .remove_commission(&provider_id)
+        .await
+        .map_err(commission_error)?;
+
+    tracing::warn!("Commission override for {} removed by {}", provider_id, admin.0.id);
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// =============================================================================
+// GET /admin/revenue - Platform fees and revenue shares per provider and asset
+// =============================================================================
+
+pub async fn revenue_summary(
+    State(state): State<Arc<AppState>>,
+    _admin: AdminUser,
+    Query(query): Query<RevenueQuery>,
+) -> Result<Json<RevenueSummaryResponse>, (StatusCode, Json<CommissionErrorResponse>)> {
+    let to = query.to.unwrap_or_else(chrono::Utc::now);
+    let from = query.from.unwrap_or(to - chrono::Duration::days(30));
+    if from >= to {
+        return Err((StatusCode::BAD_REQUEST, Json(CommissionErrorResponse::new("from must be before to"))));
+    }
+    let fx_error = |e: FxError| (e.status_code(), Json(CommissionErrorResponse::new(e.to_string())));
+    let currency = reporting_currency(query.currency.as_deref()).map_err(fx_error)?;
+    let rates = FxStore::new(state.db.clone())
+        .table(&currency, from.date_naive(), to.date_naive())
+        .await
+        .map_err(fx_error)?;
+
+    let totals = CommissionCrud::new(state.db.clone())
+        .valued_revenue_totals(from, to, &rates)
+        .await
+        .map_err(commission_error)?;
+
+    Ok(Json(RevenueSummaryResponse::new(from, to, &currency, totals)))
+}
+
+fn promotion_error(e: PromotionError) -> (StatusCode, Json<PromotionErrorResponse>) {
+    (e.status_code(), Json(PromotionErrorResponse::new(e.to_string())))
+}
+
+// =============================================================================
+// GET /admin/promotions - Every fee campaign, newest first
+// =============================================================================
+
+pub async fn list_promotions(
+    State(state): State<Arc<AppState>>,
+    _admin: AdminUser,
+) -> Result<Json<PromotionsResponse>, (StatusCode, Json<PromotionErrorResponse>)> {
+    let promotions = PromotionCrud::new(state.db.clone())
+        .list_promotions()
+        .await
+        .map_err(promotion_error)?;
+
+    Ok(Json(PromotionsResponse {
+        promotions: promotions.into_iter().map(Into::into).collect(),
+    }))
+}
+
+// =============================================================================
+// POST /admin/promotions - Start a fee campaign
+// =============================================================================
+
+pub async fn create_promotion(
+    State(state): State<Arc<AppState>>,
+    admin: AdminUser,
+    Json(payload): Json<CreatePromotionRequest>,
+) -> Result<(StatusCode, Json<PromotionResponse>), (StatusCode, Json<PromotionErrorResponse>)> {
+    use validator::Validate;
+    if let Err(e) = payload.validate() {
+        return Err((StatusCode::BAD_REQUEST, Json(PromotionErrorResponse::new(e.to_string()))));
+    }
+
+    let promotion = PromotionCrud::new(state.db.clone())
+        .create_promotion(&admin.0.id, &payload)
+        .await
+        .map_err(promotion_error)?;
+
+    tracing::warn!(
+        "Promotion {} ({}) created by {}: {}% off platform fees from {} to {}",
+        promotion.id,
+        promotion.name,
+        admin.0.id,
+        promotion.fee_discount * 100.0,
+        promotion.starts_at,
+        promotion.ends_at
+    );
+
+    Ok((StatusCode::CREATED, Json(promotion.into())))
+}
+
+// =============================================================================
+// PATCH /admin/promotions/{id} - Change a campaign's terms, window or caps
+// =============================================================================
+
+pub async fn update_promotion(
+    State(state): State<Arc<AppState>>,
+    admin: AdminUser,
+    Path(promotion_id): Path<String>,
+    Json(payload): Json<UpdatePromotionRequest>,
+) -> Result<Json<PromotionResponse>, (StatusCode, Json<PromotionErrorResponse>)> {
+    use validator::Validate;
+    if let Err(e) = payload.validate() {
+        return Err((StatusCode::BAD_REQUEST, Json(PromotionErrorResponse::new(e.to_string()))));
+    }
+
+    let promotion = PromotionCrud::new(state.db.clone())
+        .update_promotion(&promotion_id, &payload)
+        .await
+        .map_err(promotion_error)?;
+
+    tracing::warn!("Promotion {} updated by {}", promotion.id, admin.0.id);
+
+    Ok(Json(promotion.into()))
+}
+
+// =============================================================================
+// DELETE /admin/promotions/{id} - End a campaign early
+// =============================================================================
+
+pub async fn end_promotion(
+    State(state): State<Arc<AppState>>,
+    admin: AdminUser,
+    Path(promotion_id): Path<String>,
+) -> Result<StatusCode, (StatusCode, Json<PromotionErrorResponse>)> {
+    PromotionCrud::new(state.db.clone())
+        .end_promotion(&promotion_id)
+        .await
+        .map_err(promotion_error)?;
+
+    tracing::warn!("Promotion {} ended by {}", promotion_id, admin.0.id);
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// =============================================================================
+// GET /admin/promotions/report - Redemptions and fees given up per campaign
+// =============================================================================
+
+pub async fn promotion_report(
+    State(state): State<Arc<AppState>>,
+    _admin: AdminUser,
+    Query(query): Query<PromotionReportQuery>,
+) -> Result<Json<PromotionReportResponse>, (StatusCode, Json<PromotionErrorResponse>)> {
+    let to = query.to.unwrap_or_else(chrono::Utc::now);
+    let from = query.from.unwrap_or(to - chrono::Duration::days(30));
+    if from >= to {
+        return Err((StatusCode::BAD_REQUEST, Json(PromotionErrorResponse::new("from must be before to"))));
+    }
+    let fx_error = |e: FxError| (e.status_code(), Json(PromotionErrorResponse::new(e.to_string())));
+    let currency = reporting_currency(query.currency.as_deref()).map_err(fx_error)?;
+    let rates = FxStore::new(state.db.clone())
+        .table(&currency, from.date_naive(), to.date_naive())
+        .await
+        .map_err(fx_error)?;
+
+    let totals = PromotionCrud::new(state.db.clone())
+        .valued_totals(from, to, &rates)
+        .await
+        .map_err(promotion_error)?;
+
+    Ok(Json(PromotionReportResponse {
+        from,
+        to,
+        reporting_currency: currency,
+        totals: totals.into_iter().map(Into::into).collect(),
+    }))
+}
+
+// =============================================================================
+// GET /admin/backups - Recent backup runs, newest first
+// =============================================================================
+
+pub async fn list_backups(
+    State(state): State<Arc<AppState>>,
+    _admin: AdminUser,
+    Query(query): Query<BackupRunsQuery>,
+) -> Result<Json<BackupRunsResponse>, (StatusCode, Json<BackupErrorResponse>)> {
+    let runs = BackupCrud::new(state.db.clone())
+        .list_runs(query.limit)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, Json(BackupErrorResponse::new(e.to_string()))))?;
+
+    Ok(Json(BackupRunsResponse { runs: runs.into_iter().map(Into::into).collect() }))
+}
+
+// =============================================================================
+// POST /admin/backups - Take a backup now, outside the nightly schedule
+// =============================================================================
+
+pub async fn start_backup(
+    State(state): State<Arc<AppState>>,
+    admin: AdminUser,
+) -> Result<(StatusCode, Json<BackupRunResponse>), (StatusCode, Json<BackupErrorResponse>)> {
+    let backup_error = |e: BackupError| (e.status_code(), Json(BackupErrorResponse::new(e.to_string())));
+    let service = BackupService::from_env(state.db.clone()).map_err(backup_error)?;
+    let run_id = service.start().await.map_err(backup_error)?;
+    let run = BackupCrud::new(state.db.clone())
+        .get_run(&run_id)
+        .await
+        .map_err(|e| backup_error(e.into()))?
+        .ok_or_else(|| backup_error(BackupError::Job(format!("Backup run {} disappeared", run_id))))?;
+    tracing::info!("Backup {} started by {}", run_id, admin.0.id);
+
+    // Failures are recorded on the run and published by the service
+    tokio::spawn(async move {
+        let _ = service.complete(&run_id).await;
+    });
+
+    Ok((StatusCode::ACCEPTED, Json(run.into())))
+}
+
+fn guard_error(e: PayoutGuardError) -> (StatusCode, Json<PayoutGuardErrorResponse>) {
+    (e.status_code(), Json(PayoutGuardErrorResponse::new(e.to_string())))
+}
+
+/// Guard state as reported after every guard endpoint
+async fn payout_guard_status(state: &AppState, guard: &PayoutGuard) -> Result<PayoutGuardStatusResponse, PayoutGuardError> {
+    let active_trip = guard.active_trip().await?;
+    let recent_trips = PayoutGuardCrud::new(state.db.clone()).list_trips(Some(10)).await?;
+
+    Ok(PayoutGuardStatusResponse {
+        tripped: active_trip.is_some(),
+        active_trip: active_trip.map(Into::into),
+        thresholds: guard.policy().clone(),
+        window: guard.window_stats().await?.into(),
+        last_hour: guard.hourly_stats().await?.into(),
+        recent_trips: recent_trips.into_iter().map(Into::into).collect(),
+    })
+}
+
+// =============================================================================
+// GET /admin/payouts/guard - Payout guard state, thresholds and recent trips
+// =============================================================================
+
+pub async fn get_payout_guard(
+    State(state): State<Arc<AppState>>,
+    _admin: AdminUser,
+) -> Result<Json<PayoutGuardStatusResponse>, (StatusCode, Json<PayoutGuardErrorResponse>)> {
+    let guard = PayoutGuard::new(state.db.clone());
+    let status = payout_guard_status(&state, &guard).await.map_err(guard_error)?;
+    Ok(Json(status))
+}
+
+// =============================================================================
+// POST /admin/payouts/guard/trip - Halt all payouts by hand
+// =============================================================================
+
+pub async fn trip_payout_guard(
+    State(state): State<Arc<AppState>>,
+    admin: AdminUser,
+    Json(payload): Json<TripPayoutGuardRequest>,
+) -> Result<Json<PayoutGuardStatusResponse>, (StatusCode, Json<PayoutGuardErrorResponse>)> {
+    let guard = PayoutGuard::new(state.db.clone());
+    let trip_id = guard.trip_manually(&admin.0.id, &payload.reason).await.map_err(guard_error)?;
+    tracing::info!("Payout guard tripped ({}) by {}", trip_id, admin.0.id);
+
+    let status = payout_guard_status(&state, &guard).await.map_err(guard_error)?;
+    Ok(Json(status))
+}
+
+// =============================================================================
+// POST /admin/payouts/guard/rearm - Resume payouts after a trip
+// =============================================================================
+
+pub async fn rearm_payout_guard(
+    State(state): State<Arc<AppState>>,
+    admin: AdminUser,
+    Json(payload): Json<RearmPayoutGuardRequest>,
+) -> Result<Json<PayoutGuardStatusResponse>, (StatusCode, Json<PayoutGuardErrorResponse>)> {
+    let guard = PayoutGuard::new(state.db.clone());
+    let trip = guard.rearm(&admin.0.id, &payload.note).await.map_err(guard_error)?;
+    tracing::info!("Payout guard trip {} re-armed by {}", trip.id, admin.0.id);
+
+    let status = payout_guard_status(&state, &guard).await.map_err(guard_error)?;
+    Ok(Json(status))
+}
+
+// =============================================================================
+// GET /admin/analytics/gas - Per-chain gas percentiles and trends
+// =============================================================================
+
+pub async fn gas_analytics(
+    State(state): State<Arc<AppState>>,
+    _admin: AdminUser,
+    Query(query): Query<GasAnalyticsQuery>,
+) -> Result<Json<GasAnalyticsResponse>, (StatusCode, Json<AnalyticsErrorResponse>)> {
+    let crud = AnalyticsCrud::new(state.db.clone());
+    let analytics = crud
+        .gas_analytics(query.network.as_deref(), query.days)
+        .await
+        .map_err(|e| (e.status_code(), Json(AnalyticsErrorResponse::new(e.to_string()))))?;
+
+    Ok(Json(analytics))
+}
+
+// =============================================================================
+// GET /admin/analytics/funnel - Quote to completion conversion and drop-off by pair and provider
+// =============================================================================
+
+pub async fn swap_funnel(
+    State(state): State<Arc<AppState>>,
+    _admin: AdminUser,
+    Query(query): Query<FunnelQuery>,
+) -> Result<Json<FunnelResponse>, (StatusCode, Json<AnalyticsErrorResponse>)> {
+    let crud = AnalyticsCrud::new(state.db.clone());
+    let funnel = crud
+        .funnel(query.from.as_deref(), query.to.as_deref(), query.provider.as_deref(), query.days)
+        .await
+        .map_err(|e| (e.status_code(), Json(AnalyticsErrorResponse::new(e.to_string()))))?;
+
+    Ok(Json(funnel))
+}
+
+// =============================================================================
+// GET /admin/analytics/fx-rates - Stored daily FX snapshots for a currency
+// =============================================================================
+
+pub async fn list_fx_rates(
+    State(state): State<Arc<AppState>>,
+    _admin: AdminUser,
+    Query(query): Query<FxRatesQuery>,
+) -> Result<Json<FxRatesResponse>, (StatusCode, Json<AnalyticsErrorResponse>)> {
+    let fx_error = |e: FxError| (e.status_code(), Json(AnalyticsErrorResponse::new(e.to_string())));
+    let currency = reporting_currency(query.currency.as_deref()).map_err(fx_error)?;
+    let to = query.to.unwrap_or_else(|| chrono::Utc::now().date_naive());
+    let from = query.from.unwrap_or(to - chrono::Duration::days(30));
+    if from > to {
+        return Err((StatusCode::BAD_REQUEST, Json(AnalyticsErrorResponse::new("from must not be after to"))));
+    }
+
+    let rates = FxStore::new(state.db.clone()).list(&currency, from, to).await.map_err(fx_error)?;
+
+    Ok(Json(FxRatesResponse {
+        currency,
+        rates: rates.into_iter().map(Into::into).collect(),
+    }))
+}
+
+// =============================================================================
+// GET /admin/swaps/search - Find swaps by address, tx hash or account email
+// =============================================================================
+
+pub async fn search_swaps(
+    State(state): State<Arc<AppState>>,
+    _admin: AdminUser,
+    Query(query): Query<SwapSearchQuery>,
+) -> Result<Json<SwapSearchResponse>, (StatusCode, Json<SwapErrorResponse>)> {
+    let search = SwapSearch::new(state.db.clone());
+    let results = search
+        .search(&query)
+        .await
+        .map_err(|e| (e.status_code(), Json(SwapErrorResponse::new(e.to_string()))))?;
+
+    Ok(Json(results))
+}
+
+// =============================================================================
+// GET /admin/swaps/stuck - Swaps past their status SLA, longest-stuck first
+// =============================================================================
+
+pub async fn list_stuck_swaps(
+    State(state): State<Arc<AppState>>,
+    _admin: AdminUser,
+    Query(query): Query<StuckSwapsQuery>,
+) -> Result<Json<StuckSwapsResponse>, (StatusCode, Json<SwapErrorResponse>)> {
+    let limit = query.limit.unwrap_or(100).clamp(1, 500);
+    let swaps = SlaBreachStore::new(state.db.clone())
+        .list_open(query.status.as_ref(), limit)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, Json(SwapErrorResponse::new(e.to_string()))))?;
+
+    Ok(Json(StuckSwapsResponse { swaps: swaps.into_iter().map(Into::into).collect() }))
+}
+
+// =============================================================================
+// GET /admin/swaps/{id}/debug - Swap history and raw provider responses
+// =============================================================================
+
+pub async fn get_swap_debug(
+    State(state): State<Arc<AppState>>,
+    _admin: AdminUser,
+    Path(id): Path<String>,
+) -> Result<Json<SwapDebugResponse>, (StatusCode, Json<SwapErrorResponse>)> {
+    let crud = SwapCrud::new(state.db.clone(), None, None);
+    let debug = crud.debug_info(&id).await.map_err(|e| {
+        let status = match e {
+            SwapError::SwapNotFound => StatusCode::NOT_FOUND,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        (status, Json(SwapErrorResponse::new(e.to_string())))
+    })?;
+
+    Ok(Json(debug))
+}
+
+// =============================================================================
+// GET /admin/config - Effective runtime settings and where each came from
+// =============================================================================
+
+pub async fn get_runtime_config(_admin: AdminUser) -> Json<EffectiveConfigResponse> {
+    let config = runtime_config();
+    Json(config.current().report(config.file()))
+}
+
+// =============================================================================
+// POST /admin/wallet/sign-message - Prove control of the hot wallet
+// =============================================================================
+
+pub async fn sign_wallet_message(
+    State(state): State<Arc<AppState>>,
+    admin: AdminUser,
+    client_ip: Option<ClientIp>,
+    Json(payload): Json<SignMessageRequest>,
+) -> Result<Json<SignedMessage>, (StatusCode, Json<WalletSigningErrorResponse>)> {
+    let signer = HotWalletMessageSigner::new(
+        state.db.clone(),
+        state.wallet_mnemonic.clone(),
+        MessageSigningPolicy::global().clone(),
+    );
+    let request = MessageSigningRequest {
+        chain: payload.chain,
+        message: payload.message,
+        purpose: payload.purpose,
+        two_factor_code: payload.two_factor_code,
+        client_ip: client_ip.map(|c| c.0.to_string()),
+    };
+
+    let signed = signer
+        .sign(&admin.0, &request)
+        .await
+        .map_err(|e| (e.status_code(), Json(WalletSigningErrorResponse::new(e.to_string()))))?;
+
+    Ok(Json(signed))
+}
+
+fn event_stream(state: &AppState) -> EventStream {
+    EventStream::new(state.redis.clone(), EventStreamConfig::global().clone())
+}
+
+fn stream_error(e: EventStreamError) -> (StatusCode, Json<EventStreamErrorResponse>) {
+    (e.status_code(), Json(EventStreamErrorResponse::new(e.to_string())))
+}
+
+// =============================================================================
+// GET /admin/events/stream - Replay swap lifecycle events from an offset
+// =============================================================================
+
+pub async fn read_event_stream(
+    State(state): State<Arc<AppState>>,
+    _admin: AdminUser,
+    Query(query): Query<EventStreamQuery>,
+) -> Result<Json<EventStreamResponse>, (StatusCode, Json<EventStreamErrorResponse>)> {
+    let after = StreamOffset::parse(query.after.as_deref().unwrap_or("start")).map_err(stream_error)?;
+    let limit = query.limit.unwrap_or(100).clamp(1, 1000);
+    let stream = event_stream(&state);
+
+    let entries = stream.read(&after, limit).await.map_err(stream_error)?;
+    let next = match (entries.last(), after) {
+        (Some(entry), _) => entry.offset.clone(),
+        (None, StreamOffset::After(offset)) => offset,
+        (None, StreamOffset::Start) => "0-0".to_string(),
+        (None, StreamOffset::End) => {
+            stream.last_offset().await.map_err(stream_error)?.unwrap_or_else(|| "0-0".to_string())
+        }
+    };
+
+    Ok(Json(EventStreamResponse { entries, next }))
+}
+
+// =============================================================================
+// GET /admin/events/stream/groups - Consumer groups and how far behind they are
+// =============================================================================
+
+pub async fn list_stream_groups(
+    State(state): State<Arc<AppState>>,
+    _admin: AdminUser,
+) -> Result<Json<StreamGroupsResponse>, (StatusCode, Json<EventStreamErrorResponse>)> {
+    let stream = event_stream(&state);
+    let groups = stream.groups().await.map_err(stream_error)?;
+
+    Ok(Json(StreamGroupsResponse { stream: stream.key().to_string(), groups }))
+}
+
+// =============================================================================
+// POST /admin/events/stream/groups - Register a downstream consumer group
+// =============================================================================
+
+pub async fn create_stream_group(
+    State(state): State<Arc<AppState>>,
+    admin: AdminUser,
+    Json(payload): Json<CreateStreamGroupRequest>,
+) -> Result<(StatusCode, Json<StreamGroup>), (StatusCode, Json<EventStreamErrorResponse>)> {
+    let from = StreamOffset::parse(payload.from.as_deref().unwrap_or("end")).map_err(stream_error)?;
+    let stream = event_stream(&state);
+    stream.create_group(&payload.name, &from).await.map_err(stream_error)?;
+
+    tracing::info!("Event stream group {} created by {}", payload.name, admin.0.id);
+
+    let group = stream
+        .groups()
+        .await
+        .map_err(stream_error)?
+        .into_iter()
+        .find(|g| g.name == payload.name)
+        .ok_or_else(|| stream_error(EventStreamError::Redis("group missing after creation".to_string())))?;
+    Ok((StatusCode::CREATED, Json(group)))
+}
+
+fn moderation_error(e: ModerationError) -> (StatusCode, Json<ModerationErrorResponse>) {
+    (e.status_code(), Json(ModerationErrorResponse::new(e.to_string())))
+}
+
+/// The account's status, its status history and the risk signals inside
+/// the scoring window
+async fn account_status_response(
+    state: &AppState,
+    user_id: &str,
+) -> Result<AccountStatusResponse, (StatusCode, Json<ModerationErrorResponse>)> {
+    let crud = ModerationCrud::new(state.db.clone());
+    let standing = crud
+        .standing(user_id)
+        .await
+        .map_err(|e| moderation_error(e.into()))?
+        .ok_or_else(|| moderation_error(ModerationError::UserNotFound))?;
+    let events = crud.list_events(user_id).await.map_err(moderation_error)?;
+    let since = chrono::Utc::now() - RiskPolicy::global().window;
+    let signals = crud.list_signals(user_id, since).await.map_err(moderation_error)?;
+
+    let restriction = standing.restriction(chrono::Utc::now());
+    Ok(AccountStatusResponse {
+        user_id: user_id.to_string(),
+        status: restriction.as_ref().map(|r| r.status).unwrap_or(AccountStatus::Active),
+        reason: restriction.as_ref().and_then(|r| r.reason.clone()),
+        suspended_until: restriction.as_ref().and_then(|r| r.until),
+        risk_score: signals.iter().map(|s| s.weight).sum(),
+        events: events.into_iter().map(Into::into).collect(),
+        signals: signals.into_iter().map(Into::into).collect(),
+    })
+}
+
+// =============================================================================
+// GET /admin/users/{id}/status - Account status, its history and risk signals
+// =============================================================================
+
+pub async fn get_account_status(
+    State(state): State<Arc<AppState>>,
+    _admin: AdminUser,
+    Path(user_id): Path<String>,
+) -> Result<Json<AccountStatusResponse>, (StatusCode, Json<ModerationErrorResponse>)> {
+    Ok(Json(account_status_response(&state, &user_id).await?))
+}
+
+// =============================================================================
+// PUT /admin/users/{id}/status - Suspend, ban or reinstate an account
+// =============================================================================
+
+pub async fn set_account_status(
+    State(state): State<Arc<AppState>>,
+    admin: AdminUser,
+    Path(user_id): Path<String>,
+    Json(payload): Json<SetAccountStatusRequest>,
+) -> Result<Json<AccountStatusResponse>, (StatusCode, Json<ModerationErrorResponse>)> {
+    use validator::Validate;
+    if let Err(e) = payload.validate() {
+        return Err((StatusCode::BAD_REQUEST, Json(ModerationErrorResponse::new(e.to_string()))));
+    }
+    if user_id == admin.0.id {
+        return Err((StatusCode::BAD_REQUEST, Json(ModerationErrorResponse::new("Admins can't change their own status"))));
+    }
+    if payload.duration_hours.is_some() && payload.status != AccountStatus::Suspended {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ModerationErrorResponse::new("duration_hours only applies to suspensions")),
+        ));
+    }
+
+    let change = StatusChange {
+        status: payload.status,
+        reason: payload.reason.as_deref(),
+        until: payload.duration_hours.map(|h| chrono::Utc::now() + chrono::Duration::hours(h.into())),
+        source: StatusSource::Admin,
+        actor_id: Some(&admin.0.id),
+    };
+    let standing = ModerationCrud::new(state.db.clone())
+        .set_status(&user_id, &change)
+        .await
+        .map_err(moderation_error)?;
+
+    tracing::warn!(
+        "Account {} set to {} by {}{}",
+        user_id,
+        standing.account_status.as_str(),
+        admin.0.id,
+        standing.suspended_until.map(|until| format!(" until {}", until)).unwrap_or_default()
+    );
+
+    Ok(Json(account_status_response(&state, &user_id).await?))
+}
+
+// =============================================================================
+// POST /admin/users/{id}/risk-signals - Record a chargeback or fraud report
+// =============================================================================
+
+pub async fn report_risk_signal(
+    State(state): State<Arc<AppState>>,
+    admin: AdminUser,
+    Path(user_id): Path<String>,
+    Json(payload): Json<ReportRiskSignalRequest>,
+) -> Result<(StatusCode, Json<RiskAssessmentResponse>), (StatusCode, Json<ModerationErrorResponse>)> {
+    use validator::Validate;
+    if let Err(e) = payload.validate() {
+        return Err((StatusCode::BAD_REQUEST, Json(ModerationErrorResponse::new(e.to_string()))));
+    }
+
+    let context = SignalContext {
+        swap_id: payload.swap_id.as_deref(),
+        detail: payload.detail.as_deref(),
+        reported_by: Some(&admin.0.id),
+    };
+    let assessment = RiskEngine::new(state.db.clone())
+        .report(&user_id, payload.kind, &context)
+        .await
+        .map_err(moderation_error)?;
+
+    tracing::info!(
+        "{} signal against {} reported by {}; risk score now {}",
+        payload.kind.as_str(),
+        user_id,
+        admin.0.id,
+        assessment.score
+    );
+
+    Ok((
+        StatusCode::CREATED,
+        Json(RiskAssessmentResponse {
+            signal: assessment.signal.into(),
+            risk_score: assessment.score,
+            applied: assessment.applied,
+        }),
+    ))
+}
+
+// =============================================================================
+// GET /admin/address-reputation - Payout addresses shared across accounts
+// =============================================================================
+
+pub async fn list_address_reputation(
+    State(state): State<Arc<AppState>>,
+    _admin: AdminUser,
+    Query(query): Query<AddressReputationQuery>,
+) -> Result<Json<AddressReputationsResponse>, (StatusCode, Json<ModerationErrorResponse>)> {
+    let addresses = ModerationCrud::new(state.db.clone())
+        .list_addresses(query.status.unwrap_or(ReputationStatus::Flagged), query.limit)
+        .await
+        .map_err(moderation_error)?;
+
+    Ok(Json(AddressReputationsResponse { addresses: addresses.into_iter().map(Into::into).collect() }))
+}
+
+/// The address with the accounts that used it
+async fn address_detail_response(
+    state: &AppState,
+    address_hash: &str,
+) -> Result<AddressReputationDetailResponse, (StatusCode, Json<ModerationErrorResponse>)> {
+    let crud = ModerationCrud::new(state.db.clone());
+    let reputation = crud.get_address(address_hash).await.map_err(moderation_error)?;
+    let accounts = crud.address_accounts(address_hash).await.map_err(moderation_error)?;
+
+    Ok(AddressReputationDetailResponse {
+        reputation: reputation.into(),
+        accounts: accounts.into_iter().map(Into::into).collect(),
+    })
+}
+
+// =============================================================================
+// GET /admin/address-reputation/{hash} - An address and the accounts using it
+// =============================================================================
+
+pub async fn get_address_reputation(
+    State(state): State<Arc<AppState>>,
+    _admin: AdminUser,
+    Path(address_hash): Path<String>,
+) -> Result<Json<AddressReputationDetailResponse>, (StatusCode, Json<ModerationErrorResponse>)> {
+    Ok(Json(address_detail_response(&state, &address_hash).await?))
+}
+
+// =============================================================================
+// POST /admin/address-reputation/{hash}/clear - Legitimate shared address
+// =============================================================================
+
+pub async fn clear_address(
+    State(state): State<Arc<AppState>>,
+    admin: AdminUser,
+    Path(address_hash): Path<String>,
+    Json(payload): Json<ReviewAddressRequest>,
+) -> Result<Json<AddressReputationDetailResponse>, (StatusCode, Json<ModerationErrorResponse>)> {
+    review_address(&state, &admin, &address_hash, ReputationStatus::Cleared, payload).await
+}
+
+// =============================================================================
+// POST /admin/address-reputation/{hash}/confirm - Fraud; escalate every account
+// =============================================================================
+
+pub async fn confirm_address(
+    State(state): State<Arc<AppState>>,
+    admin: AdminUser,
+    Path(address_hash): Path<String>,
+    Json(payload): Json<ReviewAddressRequest>,
+) -> Result<Json<AddressReputationDetailResponse>, (StatusCode, Json<ModerationErrorResponse>)> {
+    review_address(&state, &admin, &address_hash, ReputationStatus::Confirmed, payload).await
+}
+
+async fn review_address(
+    state: &AppState,
+    admin: &AdminUser,
+    address_hash: &str,
+    outcome: ReputationStatus,
+    payload: ReviewAddressRequest,
+) -> Result<Json<AddressReputationDetailResponse>, (StatusCode, Json<ModerationErrorResponse>)> {
+    use validator::Validate;
+    if let Err(e) = payload.validate() {
+        return Err((StatusCode::BAD_REQUEST, Json(ModerationErrorResponse::new(e.to_string()))));
+    }
+
+    ModerationCrud::new(state.db.clone())
+        .review_address(&admin.0.id, address_hash, outcome, payload.note.as_deref())
+        .await
+        .map_err(moderation_error)?;
+    tracing::info!("Address {} {} by {}", address_hash, outcome.as_str(), admin.0.id);
+
+    if outcome == ReputationStatus::Confirmed {
+        let reported = AddressReputationService::new(state.db.clone())
+            .escalate(address_hash)
+            .await
+            .map_err(moderation_error)?;
+        tracing::warn!("{} accounts reported for confirmed fraud address {}", reported, address_hash);
+    }
+
+    Ok(Json(address_detail_response(state, address_hash).await?))
+}
+
+// =============================================================================
+// GET /ws/admin - Live operational events for dashboards (WebSocket)
+// =============================================================================
+
+const ADMIN_STREAM_HEARTBEAT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Deserialize)]
+pub struct AdminEventsQuery {
+    /// Comma-separated event types to receive; all when omitted
+    pub types: Option<String>,
+}
+
+pub async fn admin_events_stream(
+    admin: AdminUser,
+    Query(query): Query<AdminEventsQuery>,
+    headers: HeaderMap,
+    ws: WebSocketUpgrade,
+) -> Response {
+    if !websocket_origin_ok(SessionConfig::global(), &headers) {
+        return (StatusCode::FORBIDDEN, "Origin not allowed").into_response();
+    }
+
+    let types: Option<HashSet<String>> = query.types.map(|t| {
+        t.split(',').map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect()
+    });
+
+    tracing::info!("Admin {} subscribed to operational events", admin.0.id);
+    ws.on_upgrade(move |socket| stream_ops_events(socket, types))
+}
+
+/// Forward bus events until the client goes away. A dashboard that can't keep
+/// up is told how many events it missed rather than being disconnected.
+async fn stream_ops_events(mut socket: WebSocket, types: Option<HashSet<String>>) {
+    let mut events = ops_events().subscribe();
+    let mut heartbeat = tokio::time::interval(ADMIN_STREAM_HEARTBEAT);
+    heartbeat.tick().await;
+
+    loop {
+        let message = tokio::select! {
+            received = events.recv() => match received {
+                Ok(notification) => {
+                    if types.as_ref().is_some_and(|t| !t.contains(notification.event.kind())) {
+                        continue;
+                    }
+                    match serde_json::to_string(&notification) {
+                        Ok(text) => Message::Text(text.into()),
+                        Err(_) => continue,
+                    }
+                }
+                Err(RecvError::Lagged(missed)) => {
+                    Message::Text(serde_json::json!({ "type": "lagged", "missed": missed }).to_string().into())
+                }
+                Err(RecvError::Closed) => break,
+            },
+            _ = heartbeat.tick() => Message::Ping(Vec::new().into()),
+            incoming = socket.recv() => match incoming {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                _ => continue,
+            },
+        };
+
+        if socket.send(message).await.is_err() {
+            break;
+        }
+    }
+}
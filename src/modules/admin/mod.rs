@@ -0,0 +1,5 @@
+pub mod controller;
+pub mod routes;
+pub mod schema;
+
+pub use routes::{admin_routes, admin_ws_routes};
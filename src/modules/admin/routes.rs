@@ -0,0 +1,80 @@
+use axum::Router;
+use std::sync::Arc;
+
+use crate::AppState;
+use crate::manifest::DeclaredRoutes;
+use super::controller::{
+    admin_events_stream, approve_withdrawal, dismiss_discrepancy, dismiss_memo_deposit, dismiss_wrong_network_case,
+    create_stream_group, gas_analytics, swap_funnel, list_backups, start_backup, get_payout_guard, rearm_payout_guard, trip_payout_guard, sign_wallet_message, list_fx_rates, list_stream_groups, read_event_stream, get_runtime_config, get_wrong_network_case, lift_trading_halt, list_discrepancies, list_memo_deposits, list_reconciliation_runs,
+    create_promotion, end_promotion, list_promotions, promotion_report, update_promotion,
+    list_provider_commissions, list_trading_halts, list_wrong_network_cases, recover_wrong_network_case,
+    list_wallet_audit_runs, start_wallet_audit, list_orphaned_funds, recover_orphaned_funds, dismiss_orphaned_funds,
+    reject_withdrawal, release_memo_deposit, remove_provider_commission, resolve_discrepancy, revenue_summary,
+    get_swap_debug, list_stuck_swaps, search_swaps, set_currency_enabled, set_pair_enabled, set_provider_commission,
+    get_account_status, report_risk_signal, set_account_status,
+    clear_address, confirm_address, get_address_reputation, list_address_reputation,
+};
+
+pub fn admin_routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .declared("approveWithdrawal", approve_withdrawal)
+        .declared("rejectWithdrawal", reject_withdrawal)
+        .declared("listWrongNetworkCases", list_wrong_network_cases)
+        .declared("getWrongNetworkCase", get_wrong_network_case)
+        .declared("recoverWrongNetworkCase", recover_wrong_network_case)
+        .declared("dismissWrongNetworkCase", dismiss_wrong_network_case)
+        .declared("listMemoDeposits", list_memo_deposits)
+        .declared("releaseMemoDeposit", release_memo_deposit)
+        .declared("dismissMemoDeposit", dismiss_memo_deposit)
+        .declared("listWalletAuditRuns", list_wallet_audit_runs)
+        .declared("startWalletAudit", start_wallet_audit)
+        .declared("listOrphanedFunds", list_orphaned_funds)
+        .declared("recoverOrphanedFunds", recover_orphaned_funds)
+        .declared("dismissOrphanedFunds", dismiss_orphaned_funds)
+        .declared("listTradingHalts", list_trading_halts)
+        .declared("setCurrencyEnabled", set_currency_enabled)
+        .declared("setPairEnabled", set_pair_enabled)
+        .declared("liftTradingHalt", lift_trading_halt)
+        .declared("listReconciliationRuns", list_reconciliation_runs)
+        .declared("listBackups", list_backups)
+        .declared("startBackup", start_backup)
+        .declared("getPayoutGuard", get_payout_guard)
+        .declared("tripPayoutGuard", trip_payout_guard)
+        .declared("rearmPayoutGuard", rearm_payout_guard)
+        .declared("listDiscrepancies", list_discrepancies)
+        .declared("resolveDiscrepancy", resolve_discrepancy)
+        .declared("dismissDiscrepancy", dismiss_discrepancy)
+        .declared("listProviderCommissions", list_provider_commissions)
+        .declared("setProviderCommission", set_provider_commission)
+        .declared("removeProviderCommission", remove_provider_commission)
+        .declared("getRevenueSummary", revenue_summary)
+        .declared("listPromotions", list_promotions)
+        .declared("createPromotion", create_promotion)
+        .declared("getPromotionReport", promotion_report)
+        .declared("updatePromotion", update_promotion)
+        .declared("endPromotion", end_promotion)
+        .declared("getGasAnalytics", gas_analytics)
+        .declared("getSwapFunnel", swap_funnel)
+        .declared("listFxRates", list_fx_rates)
+        .declared("searchSwaps", search_swaps)
+        .declared("listStuckSwaps", list_stuck_swaps)
+        .declared("getSwapDebug", get_swap_debug)
+        .declared("getAccountStatus", get_account_status)
+        .declared("setAccountStatus", set_account_status)
+        .declared("reportRiskSignal", report_risk_signal)
+        .declared("listAddressReputation", list_address_reputation)
+        .declared("getAddressReputation", get_address_reputation)
+        .declared("clearAddress", clear_address)
+        .declared("confirmAddress", confirm_address)
+        .declared("getRuntimeConfig", get_runtime_config)
+        .declared("readEventStream", read_event_stream)
+        .declared("signWalletMessage", sign_wallet_message)
+        .declared("listStreamGroups", list_stream_groups)
+        .declared("createStreamGroup", create_stream_group)
+}
+
+/// WebSocket streams, mounted under /ws
+pub fn admin_ws_routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .declared("adminEventsStream", admin_events_stream)
+}
@@ -0,0 +1,77 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+pub use crate::services::events::stream::{StreamEntry, StreamGroup};
+pub use crate::services::wallet::message_signing::{HotWalletChain, SignedMessage};
+pub use crate::services::runtime_config::{
+    EffectiveConfigResponse, EffectiveSettingResponse, RejectedSetting, SettingSource,
+};
+
+// =============================================================================
+// EVENT STREAM
+// =============================================================================
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct EventStreamQuery {
+    /// `start` (default), `end` or the offset of the last entry already read
+    pub after: Option<String>,
+    /// 1-1000, default 100
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct EventStreamResponse {
+    pub entries: Vec<StreamEntry>,
+    /// Pass as `after` to read on; the request's own `after` when nothing
+    /// newer has been appended
+    pub next: String,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct StreamGroupsResponse {
+    pub stream: String,
+    pub groups: Vec<StreamGroup>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CreateStreamGroupRequest {
+    pub name: String,
+    /// Where the group starts: `start`, `end` (default) or an entry offset
+    pub from: Option<String>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct EventStreamErrorResponse {
+    pub error: String,
+}
+
+impl EventStreamErrorResponse {
+    pub fn new(error: impl Into<String>) -> Self {
+        Self { error: error.into() }
+    }
+}
+
+// =============================================================================
+// HOT WALLET MESSAGE SIGNING
+// =============================================================================
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct SignMessageRequest {
+    pub chain: HotWalletChain,
+    /// Text to sign, exactly as the verifier expects it
+    pub message: String,
+    /// Why it is being signed; kept in the audit trail
+    pub purpose: String,
+    pub two_factor_code: String,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct WalletSigningErrorResponse {
+    pub error: String,
+}
+
+impl WalletSigningErrorResponse {
+    pub fn new(error: impl Into<String>) -> Self {
+        Self { error: error.into() }
+    }
+}
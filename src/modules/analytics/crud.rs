@@ -0,0 +1,365 @@
+use axum::http::StatusCode;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use sqlx::{MySql, Pool};
+use std::collections::BTreeMap;
+
+use super::schema::{
+    ChainGasAnalytics, FunnelAbandonment, FunnelConversion, FunnelResponse, FunnelRow, FunnelStages, FunnelStepTimes,
+    GasAnalyticsResponse, GasDay,
+};
+use crate::services::gas::history::{change_pct, GasHistory, GasSample, GasSampleKind, Percentiles};
+use crate::services::gas::TxType;
+
+const DEFAULT_WINDOW_DAYS: i64 = 7;
+const MAX_WINDOW_DAYS: i64 = 90;
+
+// =============================================================================
+// ANALYTICS ERROR
+// =============================================================================
+
+#[derive(Debug)]
+pub enum AnalyticsError {
+    InvalidWindow(i64),
+    DatabaseError(String),
+}
+
+impl std::fmt::Display for AnalyticsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AnalyticsError::InvalidWindow(days) => {
+                write!(f, "days must be between 1 and {} (got {})", MAX_WINDOW_DAYS, days)
+            }
+            AnalyticsError::DatabaseError(e) => write!(f, "Database error: {}", e),
+        }
+    }
+}
+
+impl AnalyticsError {
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            AnalyticsError::InvalidWindow(_) => StatusCode::BAD_REQUEST,
+            AnalyticsError::DatabaseError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+impl From<sqlx::Error> for AnalyticsError {
+    fn from(err: sqlx::Error) -> Self {
+        AnalyticsError::DatabaseError(err.to_string())
+    }
+}
+
+// =============================================================================
+// ANALYTICS CRUD
+// =============================================================================
+
+pub struct AnalyticsCrud {
+    pool: Pool<MySql>,
+}
+
+impl AnalyticsCrud {
+    pub fn new(pool: Pool<MySql>) -> Self {
+        Self { pool }
+    }
+
+    /// Per-chain gas percentiles and trends over the last `days`
+    pub async fn gas_analytics(
+        &self,
+        network: Option<&str>,
+        days: Option<i64>,
+    ) -> Result<GasAnalyticsResponse, AnalyticsError> {
+        let days = days.unwrap_or(DEFAULT_WINDOW_DAYS);
+        if !(1..=MAX_WINDOW_DAYS).contains(&days) {
+            return Err(AnalyticsError::InvalidWindow(days));
+        }
+
+        let now = Utc::now();
+        let since = now - Duration::days(days);
+        let network = network.map(|n| n.trim().to_lowercase()).filter(|n| !n.is_empty());
+
+        let history = GasHistory::new(self.pool.clone());
+        let samples = history.samples(network.as_deref(), since).await?;
+
+        let mut by_network: BTreeMap<String, Vec<GasSample>> = BTreeMap::new();
+        for sample in samples {
+            by_network.entry(sample.network.clone()).or_default().push(sample);
+        }
+
+        let mut chains = Vec::with_capacity(by_network.len());
+        for (network, samples) in by_network {
+            let mut chain = summarize_chain(&network, &samples, now);
+            chain.tuned_fallback = history.tuned_fallback(&network, TxType::NativeTransfer).await;
+            chains.push(chain);
+        }
+
+        Ok(GasAnalyticsResponse { days, since, chains })
+    }
+
+    /// Quote-to-completion funnel of journeys started in the last `days`,
+    /// by pair and provider
+    pub async fn funnel(
+        &self,
+        from: Option<&str>,
+        to: Option<&str>,
+        provider: Option<&str>,
+        days: Option<i64>,
+    ) -> Result<FunnelResponse, AnalyticsError> {
+        let days = days.unwrap_or(DEFAULT_WINDOW_DAYS);
+        if !(1..=MAX_WINDOW_DAYS).contains(&days) {
+            return Err(AnalyticsError::InvalidWindow(days));
+        }
+        let since = Utc::now() - Duration::days(days);
+        let filter = |v: Option<&str>| v.map(|v| v.trim().to_lowercase()).filter(|v| !v.is_empty());
+        let (from, to, provider) = (filter(from), filter(to), filter(provider));
+
+        let rows: Vec<FunnelGroup> = sqlx::query_as(
+            r#"
+            SELECT from_currency, to_currency, provider,
+                   COUNT(quoted_at) AS quoted,
+                   COUNT(created_at) AS created,
+                   CAST(SUM(quoted_at IS NOT NULL AND created_at IS NOT NULL) AS SIGNED) AS created_from_quote,
+                   COUNT(deposited_at) AS deposited,
+                   COUNT(completed_at) AS completed,
+                   CAST(SUM(abandoned_stage = 'quoted') AS SIGNED) AS abandoned_quoted,
+                   CAST(SUM(abandoned_stage = 'created') AS SIGNED) AS abandoned_created,
+                   CAST(SUM(abandoned_stage = 'deposited') AS SIGNED) AS abandoned_deposited,
+                   MAX(abandoned_at) AS last_abandoned_at,
+                   CAST(AVG(TIMESTAMPDIFF(SECOND, quoted_at, created_at)) AS DOUBLE) AS secs_to_create,
+                   CAST(AVG(TIMESTAMPDIFF(SECOND, created_at, deposited_at)) AS DOUBLE) AS secs_to_deposit,
+                   CAST(AVG(TIMESTAMPDIFF(SECOND, deposited_at, completed_at)) AS DOUBLE) AS secs_to_complete
+            FROM swap_funnel
+            WHERE started_at >= ?
+              AND (? IS NULL OR from_currency = ?)
+              AND (? IS NULL OR to_currency = ?)
+              AND (? IS NULL OR provider = ?)
+            GROUP BY from_currency, to_currency, provider
+            ORDER BY COUNT(*) DESC, from_currency, to_currency, provider
+            "#,
+        )
+        .bind(since)
+        .bind(&from)
+        .bind(&from)
+        .bind(&to)
+        .bind(&to)
+        .bind(&provider)
+        .bind(&provider)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let rows: Vec<FunnelRow> = rows.into_iter().map(Into::into).collect();
+        let (reached, abandoned) = funnel_totals(&rows);
+        Ok(FunnelResponse { days, since, conversion: conversion(&reached), reached, abandoned, rows })
+    }
+}
+
+/// One pair and provider's counts as the funnel query returns them
+#[derive(Debug, sqlx::FromRow)]
+struct FunnelGroup {
+    from_currency: String,
+    to_currency: String,
+    provider: Option<String>,
+    quoted: i64,
+    created: i64,
+    created_from_quote: i64,
+    deposited: i64,
+    completed: i64,
+    abandoned_quoted: i64,
+    abandoned_created: i64,
+    abandoned_deposited: i64,
+    last_abandoned_at: Option<DateTime<Utc>>,
+    secs_to_create: Option<f64>,
+    secs_to_deposit: Option<f64>,
+    secs_to_complete: Option<f64>,
+}
+
+impl From<FunnelGroup> for FunnelRow {
+    fn from(g: FunnelGroup) -> Self {
+        let reached = FunnelStages {
+            quoted: g.quoted,
+            created: g.created,
+            created_from_quote: g.created_from_quote,
+            deposited: g.deposited,
+            completed: g.completed,
+        };
+        Self {
+            from: g.from_currency,
+            to: g.to_currency,
+            provider: g.provider,
+            conversion: conversion(&reached),
+            reached,
+            abandoned: FunnelAbandonment {
+                quoted: g.abandoned_quoted,
+                created: g.abandoned_created,
+                deposited: g.abandoned_deposited,
+                last_at: g.last_abandoned_at,
+            },
+            avg_step_secs: FunnelStepTimes {
+                to_create: g.secs_to_create,
+                to_deposit: g.secs_to_deposit,
+                to_complete: g.secs_to_complete,
+            },
+        }
+    }
+}
+
+/// Stage and abandonment counts summed over every row
+pub fn funnel_totals(rows: &[FunnelRow]) -> (FunnelStages, FunnelAbandonment) {
+    let mut reached = FunnelStages::default();
+    let mut abandoned = FunnelAbandonment::default();
+    for row in rows {
+        reached.quoted += row.reached.quoted;
+        reached.created += row.reached.created;
+        reached.created_from_quote += row.reached.created_from_quote;
+        reached.deposited += row.reached.deposited;
+        reached.completed += row.reached.completed;
+        abandoned.quoted += row.abandoned.quoted;
+        abandoned.created += row.abandoned.created;
+        abandoned.deposited += row.abandoned.deposited;
+        abandoned.last_at = abandoned.last_at.max(row.abandoned.last_at);
+    }
+    (reached, abandoned)
+}
+
+/// Stage-to-stage conversion. Quotes convert through `created_from_quote`,
+/// since swaps created without a quote never entered that stage.
+pub fn conversion(reached: &FunnelStages) -> FunnelConversion {
+    let ratio = |part: i64, whole: i64| (whole > 0).then(|| part as f64 / whole as f64);
+    FunnelConversion {
+        quote_to_create: ratio(reached.created_from_quote, reached.quoted),
+        create_to_deposit: ratio(reached.deposited, reached.created),
+        deposit_to_complete: ratio(reached.completed, reached.deposited),
+    }
+}
+
+/// Percentiles, daily medians and the 24h fee trend for one network's samples
+pub fn summarize_chain(network: &str, samples: &[GasSample], now: DateTime<Utc>) -> ChainGasAnalytics {
+    let fees_of = |kind: GasSampleKind| -> Vec<f64> {
+        samples.iter().filter(|s| s.kind == kind).map(|s| s.fee_native).collect()
+    };
+    let gas_prices: Vec<f64> = samples.iter().map(|s| s.gas_price as f64).collect();
+
+    let recent_cutoff = now - Duration::hours(24);
+    let (recent, earlier): (Vec<&GasSample>, Vec<&GasSample>) =
+        samples.iter().partition(|s| s.created_at >= recent_cutoff);
+    let median_fee = |set: &[&GasSample]| -> Option<f64> {
+        let fees: Vec<f64> = set.iter().map(|s| s.fee_native).collect();
+        Percentiles::from_samples(&fees).map(|p| p.p50)
+    };
+    let fee_change_pct = match (median_fee(&earlier), median_fee(&recent)) {
+        (Some(previous), Some(current)) => change_pct(previous, current),
+        _ => None,
+    };
+
+    let mut days: BTreeMap<NaiveDate, Vec<f64>> = BTreeMap::new();
+    for sample in samples {
+        days.entry(sample.created_at.date_naive()).or_default().push(sample.fee_native);
+    }
+    let daily = days
+        .into_iter()
+        .filter_map(|(date, fees)| {
+            Percentiles::from_samples(&fees).map(|p| GasDay { date, samples: p.samples, median_fee: p.p50 })
+        })
+        .collect();
+
+    ChainGasAnalytics {
+        network: network.to_string(),
+        estimated_fee: Percentiles::from_samples(&fees_of(GasSampleKind::Estimate)).map(Into::into),
+        payout_fee: Percentiles::from_samples(&fees_of(GasSampleKind::Payout)).map(Into::into),
+        gas_price: Percentiles::from_samples(&gas_prices).map(Into::into),
+        fee_change_pct,
+        tuned_fallback: None,
+        daily,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn sample(kind: GasSampleKind, fee: f64, gas_price: u64, at: DateTime<Utc>) -> GasSample {
+        GasSample {
+            network: "ethereum".to_string(),
+            kind,
+            gas_price,
+            fee_native: fee,
+            created_at: at,
+        }
+    }
+
+    #[test]
+    fn test_summarize_chain() {
+        let now = Utc.with_ymd_and_hms(2026, 3, 20, 12, 0, 0).unwrap();
+        let two_days_ago = now - Duration::days(2);
+        let samples = vec![
+            sample(GasSampleKind::Estimate, 0.001, 40_000_000_000, two_days_ago),
+            sample(GasSampleKind::Estimate, 0.001, 40_000_000_000, two_days_ago),
+            sample(GasSampleKind::Payout, 0.001, 40_000_000_000, two_days_ago),
+            sample(GasSampleKind::Estimate, 0.0015, 60_000_000_000, now - Duration::hours(1)),
+            sample(GasSampleKind::Payout, 0.0015, 60_000_000_000, now - Duration::hours(2)),
+        ];
+
+        let chain = summarize_chain("ethereum", &samples, now);
+        assert_eq!(chain.estimated_fee.as_ref().unwrap().samples, 3);
+        assert_eq!(chain.payout_fee.as_ref().unwrap().samples, 2);
+        assert_eq!(chain.gas_price.as_ref().unwrap().max, 60_000_000_000.0);
+        assert!((chain.fee_change_pct.unwrap() - 50.0).abs() < 1e-9);
+
+        assert_eq!(chain.daily.len(), 2);
+        assert_eq!(chain.daily[0].date, two_days_ago.date_naive());
+        assert_eq!(chain.daily[0].samples, 3);
+        assert_eq!(chain.daily[1].median_fee, 0.0015);
+    }
+
+    #[test]
+    fn test_no_trend_without_both_periods() {
+        let now = Utc::now();
+        let samples = vec![sample(GasSampleKind::Payout, 0.002, 1, now)];
+
+        let chain = summarize_chain("ethereum", &samples, now);
+        assert!(chain.fee_change_pct.is_none());
+        assert!(chain.estimated_fee.is_none());
+        assert_eq!(chain.daily.len(), 1);
+    }
+
+    fn group(quoted: i64, created: i64, from_quote: i64, deposited: i64, completed: i64) -> FunnelGroup {
+        FunnelGroup {
+            from_currency: "btc".to_string(),
+            to_currency: "eth".to_string(),
+            provider: Some("changenow".to_string()),
+            quoted,
+            created,
+            created_from_quote: from_quote,
+            deposited,
+            completed,
+            abandoned_quoted: quoted - from_quote,
+            abandoned_created: created - deposited,
+            abandoned_deposited: deposited - completed,
+            last_abandoned_at: None,
+            secs_to_create: None,
+            secs_to_deposit: None,
+            secs_to_complete: None,
+        }
+    }
+
+    #[test]
+    fn test_funnel_totals_and_conversion() {
+        let last = Utc.with_ymd_and_hms(2026, 4, 16, 9, 0, 0).unwrap();
+        let mut recent = group(10, 6, 4, 3, 3);
+        recent.last_abandoned_at = Some(last);
+        let rows: Vec<FunnelRow> = vec![recent.into(), group(0, 2, 0, 1, 0).into()];
+        assert_eq!(rows[0].conversion.quote_to_create, Some(0.4));
+        assert_eq!(rows[1].conversion.quote_to_create, None);
+
+        let (reached, abandoned) = funnel_totals(&rows);
+        assert_eq!((reached.quoted, reached.created, reached.deposited, reached.completed), (10, 8, 4, 3));
+        assert_eq!((abandoned.quoted, abandoned.created, abandoned.deposited), (6, 4, 1));
+        assert_eq!(abandoned.last_at, Some(last));
+
+        // Swaps created without a quote don't lift quote conversion
+        let rates = conversion(&reached);
+        assert_eq!(rates.quote_to_create, Some(0.4));
+        assert_eq!(rates.create_to_deposit, Some(0.5));
+        assert_eq!(rates.deposit_to_complete, Some(0.75));
+    }
+}
@@ -0,0 +1,2 @@
+pub mod crud;
+pub mod schema;
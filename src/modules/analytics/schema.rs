@@ -0,0 +1,206 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::services::fx::FxRate;
+use crate::services::gas::Percentiles;
+
+// =============================================================================
+// GAS
+// =============================================================================
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GasAnalyticsQuery {
+    /// Limit to one network, e.g. "ethereum"
+    pub network: Option<String>,
+    /// Days of history, 1-90 (default 7)
+    pub days: Option<i64>,
+}
+
+/// Distribution of fees (native units) or gas prices (smallest unit)
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct GasStats {
+    pub samples: usize,
+    pub min: f64,
+    pub p50: f64,
+    pub p90: f64,
+    pub p99: f64,
+    pub max: f64,
+    pub mean: f64,
+}
+
+impl From<Percentiles> for GasStats {
+    fn from(p: Percentiles) -> Self {
+        Self {
+            samples: p.samples,
+            min: p.min,
+            p50: p.p50,
+            p90: p.p90,
+            p99: p.p99,
+            max: p.max,
+            mean: p.mean,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct GasDay {
+    pub date: NaiveDate,
+    pub samples: usize,
+    pub median_fee: f64,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct ChainGasAnalytics {
+    pub network: String,
+    /// Fees quoted by the gas estimator
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub estimated_fee: Option<GasStats>,
+    /// Fees paid by payouts
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payout_fee: Option<GasStats>,
+    /// Gas price across estimates and payouts (wei, sat/vB or lamports)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gas_price: Option<GasStats>,
+    /// Median fee of the last 24 hours against the rest of the window, in percent
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fee_change_pct: Option<f64>,
+    /// Cost the estimator falls back to when the chain's RPC is unavailable,
+    /// when there is enough recent history to tune it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tuned_fallback: Option<f64>,
+    pub daily: Vec<GasDay>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct GasAnalyticsResponse {
+    pub days: i64,
+    pub since: DateTime<Utc>,
+    pub chains: Vec<ChainGasAnalytics>,
+}
+
+// =============================================================================
+// FUNNEL
+// =============================================================================
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct FunnelQuery {
+    /// Source currency, e.g. "btc"
+    pub from: Option<String>,
+    /// Destination currency
+    pub to: Option<String>,
+    pub provider: Option<String>,
+    /// Journeys started in the last 1-90 days (default 7)
+    pub days: Option<i64>,
+}
+
+/// Journeys that reached each stage
+#[derive(Debug, Clone, Default, PartialEq, Serialize, JsonSchema)]
+pub struct FunnelStages {
+    pub quoted: i64,
+    pub created: i64,
+    /// Of `created`, those created from one of our quotes
+    pub created_from_quote: i64,
+    pub deposited: i64,
+    pub completed: i64,
+}
+
+/// Journeys that went no further than each stage
+#[derive(Debug, Clone, Default, PartialEq, Serialize, JsonSchema)]
+pub struct FunnelAbandonment {
+    /// Quoted, but no swap was created in time
+    pub quoted: i64,
+    /// Created, but never funded
+    pub created: i64,
+    /// Funded, but failed, expired or was refunded
+    pub deposited: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_at: Option<DateTime<Utc>>,
+}
+
+/// Share of journeys at one stage that reached the next, 0-1; absent when
+/// none reached the first
+#[derive(Debug, Clone, Default, PartialEq, Serialize, JsonSchema)]
+pub struct FunnelConversion {
+    pub quote_to_create: Option<f64>,
+    pub create_to_deposit: Option<f64>,
+    pub deposit_to_complete: Option<f64>,
+}
+
+/// Mean seconds from the previous stage
+#[derive(Debug, Clone, Default, PartialEq, Serialize, JsonSchema)]
+pub struct FunnelStepTimes {
+    pub to_create: Option<f64>,
+    pub to_deposit: Option<f64>,
+    pub to_complete: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct FunnelRow {
+    pub from: String,
+    pub to: String,
+    /// None for quotes no swap was created from
+    pub provider: Option<String>,
+    pub reached: FunnelStages,
+    pub abandoned: FunnelAbandonment,
+    pub conversion: FunnelConversion,
+    pub avg_step_secs: FunnelStepTimes,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct FunnelResponse {
+    pub days: i64,
+    pub since: DateTime<Utc>,
+    pub reached: FunnelStages,
+    pub abandoned: FunnelAbandonment,
+    pub conversion: FunnelConversion,
+    /// By pair and provider, busiest first
+    pub rows: Vec<FunnelRow>,
+}
+
+// =============================================================================
+// FX
+// =============================================================================
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct FxRatesQuery {
+    /// ISO 4217; defaults to REPORTING_CURRENCY
+    pub currency: Option<String>,
+    /// Defaults to 30 days before `to`
+    pub from: Option<NaiveDate>,
+    /// Defaults to today
+    pub to: Option<NaiveDate>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct FxRateResponse {
+    pub date: NaiveDate,
+    /// Units of the currency per 1 USD, exact decimal string
+    pub per_usd: String,
+    pub source: String,
+}
+
+impl From<FxRate> for FxRateResponse {
+    fn from(r: FxRate) -> Self {
+        Self { date: r.rate_date, per_usd: r.per_usd.normalize().to_string(), source: r.source }
+    }
+}
+
+/// Snapshots by day; days without one (weekends, holidays) use the latest
+/// earlier rate
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct FxRatesResponse {
+    pub currency: String,
+    pub rates: Vec<FxRateResponse>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct AnalyticsErrorResponse {
+    pub error: String,
+}
+
+impl AnalyticsErrorResponse {
+    pub fn new(error: impl Into<String>) -> Self {
+        Self { error: error.into() }
+    }
+}
@@ -1,16 +1,32 @@
-use axum::{extract::State, http::StatusCode, Json};
+use axum::{
+    extract::{Path, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
 use chrono::Utc;
 use std::sync::Arc;
 use uuid::Uuid;
 use validator::Validate;
 
 use crate::AppState;
+use crate::services::api_version::RequestedVersion;
+use crate::services::client_ip::ClientIp;
 use crate::modules::auth::{
-    crud::{AuthError, UserCrud},
+    crud::{AuthError, LoginResult, OAuthCrud, OAuthLogin, PasswordResetCrud, ResetRequester, UserCrud},
+    interface::{scope::AccountManage, OptionalUser, Scoped, ScopedUser},
     model::User,
-    schema::{LoginRequest, LoginResponse, RegisterRequest, RegisterResponse, UserResponse, ErrorResponse},
+    schema::{
+        DownscopeTokenRequest, DownscopedTokenResponse, ErrorResponse, ForgotPasswordRequest, LoginRequest, LoginResponse,
+        OAuthCallbackRequest, OAuthIdentitiesResponse, OAuthIdentityResponse, OAuthLoginResponse, OAuthStartRequest,
+        OAuthStartResponse, OAuthTwoFactorRequest, PasswordResetResponse, RegisterRequest, RegisterResponse,
+        ResetPasswordRequest, SessionEndedResponse, SessionResponse, UserResponse,
+    },
 };
 use crate::services::hashing;
+use crate::services::jwt::Scopes;
+use crate::services::oauth::{generate_code_verifier, random_token, OAuthClient, OAuthError, OAuthProvider};
+use crate::services::session::{cookie_value, generate_csrf_token, with_cookies, SessionConfig};
 
 pub async fn register(
     State(state): State<Arc<AppState>>,
@@ -62,6 +78,7 @@ pub async fn register(
         two_factor_secret: None,
         created_at: now,
         updated_at: now,
+        token_version: 0,
     };
 
     if let Err(e) = crud.create(&user).await {
@@ -96,16 +113,23 @@ pub async fn register(
 
 pub async fn login(
     State(state): State<Arc<AppState>>,
+    client_ip: Option<ClientIp>,
     Json(req): Json<LoginRequest>,
 ) -> Result<(StatusCode, Json<LoginResponse>), (StatusCode, Json<ErrorResponse>)> {
+    let scopes = requested_scopes(req.scope.as_deref())?;
     let crud = UserCrud::new(state.db.clone(), &state.jwt_service);
+    let ip = client_ip.map(|c| c.0.to_string()).unwrap_or_else(|| "unknown".to_string());
 
-    let result = crud.login(&req.email, &req.password).await.map_err(|e| {
+    let result = crud.login(&req.email, &req.password, &scopes).await.map_err(|e| {
         match e {
-            AuthError::InvalidCredentials => (
-                StatusCode::UNAUTHORIZED,
-                Json(ErrorResponse::new("Invalid email or password")),
-            ),
+            AuthError::InvalidCredentials => {
+                tracing::warn!(client_ip = %ip, "Failed login attempt");
+                (
+                    StatusCode::UNAUTHORIZED,
+                    Json(ErrorResponse::new("Invalid email or password")),
+                )
+            }
+            AuthError::AccountRestricted(_) => auth_error(e),
             _ => (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 Json(ErrorResponse::new(e.to_string())),
@@ -113,13 +137,409 @@ pub async fn login(
         }
     })?;
 
-    Ok((
-        StatusCode::OK,
-        Json(LoginResponse {
-            access_token: result.access_token,
-            refresh_token: result.refresh_token,
-            token_type: "Bearer",
-            expires_in: result.expires_in,
-        }),
+    tracing::info!(client_ip = %ip, "Login succeeded");
+
+    Ok((StatusCode::OK, Json(to_login_response(result))))
+}
+
+fn auth_error(e: AuthError) -> (StatusCode, Json<ErrorResponse>) {
+    match &e {
+        // The reason is for the user to read
+        AuthError::AccountRestricted(restriction) => match &restriction.reason {
+            Some(reason) => (e.status_code(), Json(ErrorResponse::with_message(e.to_string(), reason.clone()))),
+            None => (e.status_code(), Json(ErrorResponse::new(e.to_string()))),
+        },
+        _ => (e.status_code(), Json(ErrorResponse::new(e.to_string()))),
+    }
+}
+
+/// Scopes asked for at login; every scope when none were named
+fn requested_scopes(scope: Option<&str>) -> Result<Scopes, (StatusCode, Json<ErrorResponse>)> {
+    match scope {
+        None => Ok(Scopes::all()),
+        Some(scope) => Scopes::parse(scope).map_err(|e| (StatusCode::BAD_REQUEST, Json(ErrorResponse::new(e)))),
+    }
+}
+
+fn parse_provider(provider: &str) -> Result<OAuthProvider, (StatusCode, Json<ErrorResponse>)> {
+    OAuthProvider::parse(provider)
+        .ok_or_else(|| (StatusCode::NOT_FOUND, Json(ErrorResponse::new("Unknown OAuth provider"))))
+}
+
+fn to_login_response(result: LoginResult) -> LoginResponse {
+    LoginResponse {
+        access_token: result.access_token,
+        refresh_token: result.refresh_token,
+        token_type: "Bearer",
+        expires_in: result.expires_in,
+        scope: result.scopes.to_claim(),
+    }
+}
+
+// =============================================================================
+// POST /auth/oauth/{provider}/start - Begin an authorization-code login
+// =============================================================================
+
+pub async fn oauth_start(
+    State(state): State<Arc<AppState>>,
+    OptionalUser(user): OptionalUser,
+    Path(provider): Path<String>,
+    Json(req): Json<OAuthStartRequest>,
+) -> Result<Json<OAuthStartResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let provider = parse_provider(&provider)?;
+
+    let link_user_id = match (req.link, &user) {
+        (true, Some(user)) => Some(user.id.as_str()),
+        (true, None) => {
+            return Err((
+                StatusCode::UNAUTHORIZED,
+                Json(ErrorResponse::new("Sign in to link another login method")),
+            ))
+        }
+        (false, _) => None,
+    };
+
+    let client = OAuthClient::from_env();
+    client.check_redirect_uri(&req.redirect_uri).map_err(|e| auth_error(e.into()))?;
+
+    let verifier = generate_code_verifier();
+    let nonce = random_token(32);
+    let oauth_state = OAuthCrud::new(state.db.clone())
+        .create_login_state(provider, &req.redirect_uri, &verifier, &nonce, link_user_id)
+        .await
+        .map_err(auth_error)?;
+
+    let authorization_url = client
+        .authorization_url(provider, &req.redirect_uri, &oauth_state, &verifier, &nonce)
+        .map_err(|e| auth_error(e.into()))?;
+
+    Ok(Json(OAuthStartResponse { authorization_url, state: oauth_state }))
+}
+
+// =============================================================================
+// POST /auth/oauth/{provider}/callback - Redeem the code the provider returned
+// =============================================================================
+
+pub async fn oauth_callback(
+    State(state): State<Arc<AppState>>,
+    client_ip: Option<ClientIp>,
+    Path(provider): Path<String>,
+    Json(req): Json<OAuthCallbackRequest>,
+) -> Result<Json<OAuthLoginResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let provider = parse_provider(&provider)?;
+    let ip = client_ip.map(|c| c.0.to_string()).unwrap_or_else(|| "unknown".to_string());
+    let users = UserCrud::new(state.db.clone(), &state.jwt_service);
+    let crud = OAuthCrud::new(state.db.clone());
+
+    let login = crud.take_login_state(provider, &req.state).await.map_err(auth_error)?;
+
+    let claims = OAuthClient::from_env()
+        .exchange_code(provider, &req.code, &login.code_verifier, &login.redirect_uri)
+        .await
+        .map_err(|e| {
+            tracing::warn!(client_ip = %ip, provider = provider.as_str(), "OAuth code exchange failed: {}", e);
+            auth_error(e.into())
+        })?;
+    if claims.nonce.as_deref() != Some(login.nonce.as_str()) {
+        return Err(auth_error(OAuthError::InvalidIdToken("nonce mismatch".to_string()).into()));
+    }
+
+    let user = crud
+        .resolve_user(&users, provider, &claims, login.link_user_id.as_deref())
+        .await
+        .map_err(auth_error)?;
+
+    // Linking happens from an existing session, which already passed 2FA
+    let outcome = if login.link_user_id.is_some() {
+        tracing::info!(client_ip = %ip, provider = provider.as_str(), "OAuth identity linked");
+        OAuthLogin::Authenticated(user)
+    } else {
+        crud.finish_login(provider, user, req.two_factor_code.as_deref())
+            .await
+            .map_err(auth_error)?
+    };
+
+    match outcome {
+        OAuthLogin::Authenticated(user) => {
+            tracing::info!(client_ip = %ip, provider = provider.as_str(), "OAuth login succeeded");
+            let result = users.issue_tokens(user, &Scopes::all()).await.map_err(auth_error)?;
+            Ok(Json(OAuthLoginResponse {
+                requires_2fa: false,
+                two_factor_token: None,
+                tokens: Some(to_login_response(result)),
+            }))
+        }
+        OAuthLogin::TwoFactorRequired(token) => Ok(Json(OAuthLoginResponse {
+            requires_2fa: true,
+            two_factor_token: Some(token),
+            tokens: None,
+        })),
+    }
+}
+
+// =============================================================================
+// POST /auth/oauth/2fa - Finish an OAuth login with a 2FA code
+// =============================================================================
+
+pub async fn oauth_two_factor(
+    State(state): State<Arc<AppState>>,
+    client_ip: Option<ClientIp>,
+    Json(req): Json<OAuthTwoFactorRequest>,
+) -> Result<Json<LoginResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let ip = client_ip.map(|c| c.0.to_string()).unwrap_or_else(|| "unknown".to_string());
+    let users = UserCrud::new(state.db.clone(), &state.jwt_service);
+
+    let user = OAuthCrud::new(state.db.clone())
+        .complete_two_factor(&users, &req.two_factor_token, req.code.trim())
+        .await
+        .map_err(|e| {
+            if matches!(e, AuthError::InvalidTwoFactorCode) {
+                tracing::warn!(client_ip = %ip, "Failed OAuth 2FA attempt");
+            }
+            auth_error(e)
+        })?;
+
+    tracing::info!(client_ip = %ip, "OAuth login succeeded");
+    let result = users.issue_tokens(user, &Scopes::all()).await.map_err(auth_error)?;
+
+    Ok(Json(to_login_response(result)))
+}
+
+// =============================================================================
+// GET /auth/oauth/identities - Login providers linked to the current user
+// =============================================================================
+
+pub async fn list_oauth_identities(
+    State(state): State<Arc<AppState>>,
+    user: Scoped<AccountManage>,
+) -> Result<Json<OAuthIdentitiesResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let identities = OAuthCrud::new(state.db.clone())
+        .list_identities(&user.0.id)
+        .await
+        .map_err(auth_error)?;
+
+    Ok(Json(OAuthIdentitiesResponse {
+        identities: identities
+            .into_iter()
+            .map(|i| OAuthIdentityResponse {
+                provider: i.provider,
+                email: i.email,
+                created_at: i.created_at,
+                last_login_at: i.last_login_at,
+            })
+            .collect(),
+    }))
+}
+
+// =============================================================================
+// POST /auth/token/downscope - Narrower token for a third-party integration
+// =============================================================================
+
+pub async fn downscope_token(
+    State(state): State<Arc<AppState>>,
+    ScopedUser { user, scopes }: ScopedUser,
+    Json(req): Json<DownscopeTokenRequest>,
+) -> Result<Json<DownscopedTokenResponse>, (StatusCode, Json<ErrorResponse>)> {
+    if let Err(e) = req.validate() {
+        return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse::new(e.to_string()))));
+    }
+    let requested = Scopes::parse(&req.scope).map_err(|e| (StatusCode::BAD_REQUEST, Json(ErrorResponse::new(e))))?;
+    // A token can only hand on what it was given
+    if !requested.is_subset(&scopes) {
+        return Err((StatusCode::FORBIDDEN, Json(ErrorResponse::new("Requested scopes exceed the current token's"))));
+    }
+
+    let expires_in = req.expires_in.unwrap_or(3600);
+    let client = req.client.trim().to_string();
+    let access_token = state
+        .jwt_service
+        .create_downscoped_token(&user.id, &user.email, user.token_version, &requested, &client, expires_in)
+        .map_err(|e| auth_error(AuthError::TokenError(e.to_string())))?;
+
+    tracing::info!(user_id = %user.id, client = %client, scope = %requested.to_claim(), "Downscoped token issued");
+
+    Ok(Json(DownscopedTokenResponse {
+        access_token,
+        token_type: "Bearer",
+        expires_in,
+        scope: requested.to_claim(),
+        client,
+    }))
+}
+
+fn session_config() -> Result<&'static SessionConfig, (StatusCode, Json<ErrorResponse>)> {
+    let config = SessionConfig::global();
+    if config.enabled {
+        Ok(config)
+    } else {
+        Err((StatusCode::NOT_FOUND, Json(ErrorResponse::new("Cookie sessions are not enabled"))))
+    }
+}
+
+/// Response carrying a fresh session in cookies
+fn session_response(
+    config: &SessionConfig,
+    version: RequestedVersion,
+    result: LoginResult,
+    refresh_max_age: i64,
+) -> Response {
+    let csrf_token = generate_csrf_token();
+    let cookies = config.session_cookies(
+        version.base_path(),
+        &result.access_token,
+        result.expires_in,
+        &result.refresh_token,
+        refresh_max_age,
+        &csrf_token,
+    );
+
+    with_cookies(
+        Json(SessionResponse { csrf_token, expires_in: result.expires_in }).into_response(),
+        cookies,
+    )
+}
+
+// =============================================================================
+// POST /auth/forgot-password - Email a password reset link
+// POST /auth/reset-password  - Set a new password with the emailed token
+// =============================================================================
+
+/// Parsed by hand so a missing field is a 400 like any other invalid input
+fn parse_body<T: serde::de::DeserializeOwned + Validate>(body: &str) -> Result<T, (StatusCode, Json<ErrorResponse>)> {
+    let req: T = serde_json::from_str(body)
+        .map_err(|e| (StatusCode::BAD_REQUEST, Json(ErrorResponse::new(format!("Invalid request: {}", e)))))?;
+    req.validate().map_err(|e| (StatusCode::BAD_REQUEST, Json(ErrorResponse::new(e.to_string()))))?;
+    Ok(req)
+}
+
+/// Answers the same whether or not the email has an account, so it can't
+/// be used to find out
+pub async fn forgot_password(
+    State(state): State<Arc<AppState>>,
+    client_ip: Option<ClientIp>,
+    headers: HeaderMap,
+    body: String,
+) -> Result<Json<PasswordResetResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let req: ForgotPasswordRequest = parse_body(&body)?;
+    let response = PasswordResetResponse {
+        message: "If an account exists for this email, a reset link has been sent",
+    };
+
+    let crud = UserCrud::new(state.db.clone(), &state.jwt_service);
+    let Some(user) = crud.find_by_email(&req.email).await.map_err(|e| auth_error(e.into()))? else {
+        return Ok(Json(response));
+    };
+
+    let requester = ResetRequester {
+        ip: client_ip.map(|c| c.0.to_string()),
+        user_agent: headers.get(header::USER_AGENT).and_then(|v| v.to_str().ok()).map(str::to_string),
+    };
+    let sent = PasswordResetCrud::new(state.db.clone())
+        .request(&user, &requester)
+        .await
+        .map_err(auth_error)?;
+    if !sent {
+        tracing::info!("Password reset for user {} requested again within a minute; not resent", user.id);
+    }
+
+    Ok(Json(response))
+}
+
+pub async fn reset_password(
+    State(state): State<Arc<AppState>>,
+    client_ip: Option<ClientIp>,
+    body: String,
+) -> Result<Json<PasswordResetResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let req: ResetPasswordRequest = parse_body(&body)?;
+
+    if req.password != req.password_confirm {
+        return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse::new("Passwords do not match"))));
+    }
+    if req.password.len() < 8 {
+        return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse::new("Password must be at least 8 characters"))));
+    }
+
+    let password_hash = hashing::hash_password(&req.password)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, Json(ErrorResponse::new(e.to_string()))))?;
+    let user_id = PasswordResetCrud::new(state.db.clone())
+        .reset(&req.token, &password_hash)
+        .await
+        .map_err(auth_error)?;
+
+    let ip = client_ip.map(|c| c.0.to_string()).unwrap_or_else(|| "unknown".to_string());
+    tracing::info!(client_ip = %ip, "Password reset for user {}; all sessions ended", user_id);
+
+    Ok(Json(PasswordResetResponse {
+        message: "Password updated. Log in again on every device",
+    }))
+}
+
+// =============================================================================
+// POST /auth/session - Log in with the session kept in httpOnly cookies
+// =============================================================================
+
+pub async fn create_session(
+    State(state): State<Arc<AppState>>,
+    version: RequestedVersion,
+    client_ip: Option<ClientIp>,
+    Json(req): Json<LoginRequest>,
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
+    let config = session_config()?;
+    let scopes = requested_scopes(req.scope.as_deref())?;
+    let crud = UserCrud::new(state.db.clone(), &state.jwt_service);
+    let ip = client_ip.map(|c| c.0.to_string()).unwrap_or_else(|| "unknown".to_string());
+
+    let result = crud.login(&req.email, &req.password, &scopes).await.map_err(|e| {
+        if matches!(e, AuthError::InvalidCredentials) {
+            tracing::warn!(client_ip = %ip, "Failed login attempt");
+            return (StatusCode::UNAUTHORIZED, Json(ErrorResponse::new("Invalid email or password")));
+        }
+        auth_error(e)
+    })?;
+
+    tracing::info!(client_ip = %ip, "Cookie session login succeeded");
+
+    Ok(session_response(config, version, result, state.jwt_service.get_refresh_token_duration_secs()))
+}
+
+// =============================================================================
+// POST /auth/session/refresh - Rotate the session cookies
+// =============================================================================
+
+pub async fn refresh_session(
+    State(state): State<Arc<AppState>>,
+    version: RequestedVersion,
+    headers: HeaderMap,
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
+    let config = session_config()?;
+    let unauthorized = || (StatusCode::UNAUTHORIZED, Json(ErrorResponse::new("Session expired")));
+
+    let refresh_token = cookie_value(&headers, &config.refresh_cookie).ok_or_else(unauthorized)?;
+    let claims = state.jwt_service.verify_refresh_token(refresh_token).map_err(|_| unauthorized())?;
+
+    let crud = UserCrud::new(state.db.clone(), &state.jwt_service);
+    let user = crud
+        .find_by_id(&claims.claims.sub)
+        .await
+        .map_err(|e| auth_error(e.into()))?
+        .ok_or_else(unauthorized)?;
+    if user.session_revoked(claims.claims.ver) {
+        return Err(unauthorized());
+    }
+    let result = crud.issue_tokens(user, &claims.claims.scopes()).await.map_err(auth_error)?;
+
+    Ok(session_response(config, version, result, state.jwt_service.get_refresh_token_duration_secs()))
+}
+
+// =============================================================================
+// DELETE /auth/session - Log out of a cookie session
+// =============================================================================
+
+pub async fn delete_session(version: RequestedVersion) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
+    let config = session_config()?;
+
+    Ok(with_cookies(
+        Json(SessionEndedResponse { message: "Logged out" }).into_response(),
+        config.clear_cookies(version.base_path()),
     ))
 }
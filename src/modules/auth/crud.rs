@@ -1,6 +1,29 @@
+use axum::http::StatusCode;
+use chrono::{Duration, Utc};
 use sqlx::{MySql, Pool};
-use crate::modules::auth::model::User;
-use crate::services::{hashing, jwt::JwtService};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::modules::auth::model::{OAuthIdentity, OAuthLoginState, User};
+use crate::modules::moderation::crud::ModerationCrud;
+use crate::modules::moderation::model::AccountRestriction;
+use crate::services::email::{queued_email_sender, EmailMessage, EmailSender};
+use crate::services::{hashing, jwt::{JwtService, Scopes}};
+use crate::services::oauth::{hash_state, random_token, IdTokenClaims, OAuthError, OAuthProvider};
+use crate::services::pii::{email_index, SealedString};
+use crate::services::totp::verify_totp;
+
+/// Time allowed between starting an OAuth login and the callback
+const OAUTH_STATE_MINUTES: i64 = 10;
+/// Time allowed to enter a 2FA code after the provider step
+const TWO_FACTOR_CHALLENGE_MINUTES: i64 = 5;
+/// Wrong 2FA codes before the challenge is dropped
+const MAX_TWO_FACTOR_ATTEMPTS: i32 = 5;
+/// Time a password reset token can be redeemed in
+pub const PASSWORD_RESET_MINUTES: i64 = 15;
+/// A new reset email goes out at most this often per account
+const PASSWORD_RESET_RESEND_SECS: i64 = 60;
+const PASSWORD_RESET_TOKEN_LEN: usize = 43;
 
 pub struct UserCrud<'a> {
     pool: Pool<MySql>,
@@ -14,6 +37,17 @@ pub enum AuthError {
     DatabaseError(String),
     HashingError(String),
     TokenError(String),
+    OAuth(OAuthError),
+    /// The `state` (or 2FA token) is unknown, used or expired
+    OAuthStateInvalid,
+    /// The provider account can't be tied to a user without a decision
+    /// from the user, e.g. it is linked elsewhere
+    IdentityConflict(&'static str),
+    InvalidTwoFactorCode,
+    /// The reset token is unknown, used, superseded or expired
+    InvalidResetToken,
+    /// The account is suspended or banned
+    AccountRestricted(AccountRestriction),
 }
 
 impl std::fmt::Display for AuthError {
@@ -24,15 +58,53 @@ impl std::fmt::Display for AuthError {
             AuthError::DatabaseError(e) => write!(f, "Database error: {}", e),
             AuthError::HashingError(e) => write!(f, "Hashing error: {}", e),
             AuthError::TokenError(e) => write!(f, "Token error: {}", e),
+            AuthError::OAuth(e) => write!(f, "{}", e),
+            AuthError::OAuthStateInvalid => write!(f, "Login session expired or invalid"),
+            AuthError::IdentityConflict(reason) => write!(f, "{}", reason),
+            AuthError::InvalidTwoFactorCode => write!(f, "Invalid 2FA code"),
+            AuthError::InvalidResetToken => write!(f, "Reset link is invalid or has expired"),
+            AuthError::AccountRestricted(restriction) => write!(f, "{}", restriction),
         }
     }
 }
 
+impl AuthError {
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            AuthError::InvalidCredentials | AuthError::InvalidTwoFactorCode => StatusCode::UNAUTHORIZED,
+            AuthError::UserNotFound => StatusCode::NOT_FOUND,
+            AuthError::OAuth(OAuthError::NotConfigured(_)) => StatusCode::NOT_FOUND,
+            AuthError::OAuth(OAuthError::RedirectUriNotAllowed) => StatusCode::BAD_REQUEST,
+            AuthError::OAuth(OAuthError::ProviderError(_)) => StatusCode::BAD_GATEWAY,
+            AuthError::OAuth(OAuthError::InvalidIdToken(_)) => StatusCode::UNAUTHORIZED,
+            AuthError::OAuthStateInvalid | AuthError::InvalidResetToken => StatusCode::BAD_REQUEST,
+            AuthError::IdentityConflict(_) => StatusCode::CONFLICT,
+            AuthError::AccountRestricted(_) => StatusCode::FORBIDDEN,
+            AuthError::DatabaseError(_) | AuthError::HashingError(_) | AuthError::TokenError(_) => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+        }
+    }
+}
+
+impl From<sqlx::Error> for AuthError {
+    fn from(err: sqlx::Error) -> Self {
+        AuthError::DatabaseError(err.to_string())
+    }
+}
+
+impl From<OAuthError> for AuthError {
+    fn from(err: OAuthError) -> Self {
+        AuthError::OAuth(err)
+    }
+}
+
 pub struct LoginResult {
     pub user: User,
     pub access_token: String,
     pub refresh_token: String,
     pub expires_in: i64,
+    pub scopes: Scopes,
 }
 
 impl<'a> UserCrud<'a> {
@@ -46,12 +118,13 @@ impl<'a> UserCrud<'a> {
     pub async fn create(&self, user: &User) -> Result<(), sqlx::Error> {
         sqlx::query(
             r#"
-            INSERT INTO users (id, email, password_hash, email_verified, two_factor_enabled, two_factor_secret, created_at, updated_at)
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            INSERT INTO users (id, email, email_hash, password_hash, email_verified, two_factor_enabled, two_factor_secret, created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#,
         )
         .bind(&user.id)
-        .bind(&user.email)
+        .bind(SealedString(user.email.clone()))
+        .bind(email_index(&user.email))
         .bind(&user.password_hash)
         .bind(user.email_verified)
         .bind(user.two_factor_enabled)
@@ -71,15 +144,19 @@ impl<'a> UserCrud<'a> {
             .await
     }
 
+    /// Matches the blind index, or the plaintext column for rows written
+    /// before encryption was enabled
     pub async fn find_by_email(&self, email: &str) -> Result<Option<User>, sqlx::Error> {
-        sqlx::query_as::<_, User>("SELECT * FROM users WHERE email = ?")
+        sqlx::query_as::<_, User>("SELECT * FROM users WHERE email_hash = ? OR email = ? LIMIT 1")
+            .bind(email_index(email))
             .bind(email)
             .fetch_optional(&self.pool)
             .await
     }
 
     pub async fn email_exists(&self, email: &str) -> Result<bool, sqlx::Error> {
-        let result: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM users WHERE email = ?")
+        let result: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM users WHERE email_hash = ? OR email = ?")
+            .bind(email_index(email))
             .bind(email)
             .fetch_one(&self.pool)
             .await?;
@@ -87,7 +164,16 @@ impl<'a> UserCrud<'a> {
         Ok(result.0 > 0)
     }
 
-    pub async fn login(&self, email: &str, password: &str) -> Result<LoginResult, AuthError> {
+    pub async fn is_admin(&self, user_id: &str) -> Result<bool, sqlx::Error> {
+        let result: Option<(bool,)> = sqlx::query_as("SELECT is_admin FROM users WHERE id = ?")
+            .bind(user_id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(result.map(|r| r.0).unwrap_or(false))
+    }
+
+    pub async fn login(&self, email: &str, password: &str, scopes: &Scopes) -> Result<LoginResult, AuthError> {
         let user = self.find_by_email(email)
             .await
             .map_err(|e| AuthError::DatabaseError(e.to_string()))?
@@ -100,12 +186,22 @@ impl<'a> UserCrud<'a> {
             return Err(AuthError::InvalidCredentials);
         }
 
+        self.issue_tokens(user, scopes).await
+    }
+
+    /// Access and refresh tokens for an authenticated user, limited to
+    /// `scopes`. Suspended and banned accounts get none.
+    pub async fn issue_tokens(&self, user: User, scopes: &Scopes) -> Result<LoginResult, AuthError> {
+        if let Some(restriction) = ModerationCrud::new(self.pool.clone()).restriction(&user.id).await? {
+            return Err(AuthError::AccountRestricted(restriction));
+        }
+
         let access_token = self.jwt_service
-            .create_access_token(&user.id, &user.email)
+            .create_access_token(&user.id, &user.email, user.token_version, scopes)
             .map_err(|e| AuthError::TokenError(e.to_string()))?;
 
         let refresh_token = self.jwt_service
-            .create_refresh_token(&user.id)
+            .create_refresh_token(&user.id, user.token_version, scopes)
             .map_err(|e| AuthError::TokenError(e.to_string()))?;
 
         Ok(LoginResult {
@@ -113,6 +209,423 @@ impl<'a> UserCrud<'a> {
             access_token,
             refresh_token,
             expires_in: self.jwt_service.get_access_token_duration_secs(),
+            scopes: scopes.clone(),
         })
     }
 }
+
+// =============================================================================
+// PASSWORD RESET
+// =============================================================================
+
+/// Where a reset was asked for, recorded with the token and shown in the
+/// email so the owner can tell whether it was them
+#[derive(Debug, Clone, Default)]
+pub struct ResetRequester {
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+pub struct PasswordResetCrud {
+    pool: Pool<MySql>,
+    email_sender: Arc<dyn EmailSender>,
+}
+
+impl PasswordResetCrud {
+    pub fn new(pool: Pool<MySql>) -> Self {
+        let email_sender = queued_email_sender(pool.clone());
+        Self { pool, email_sender }
+    }
+
+    pub fn with_email_sender(mut self, email_sender: Arc<dyn EmailSender>) -> Self {
+        self.email_sender = email_sender;
+        self
+    }
+
+    /// Issue a reset token for `user` and email it. Earlier tokens stop
+    /// working; only a hash of the new one is stored. Returns false without
+    /// sending anything if a token was issued in the last minute.
+    pub async fn request(&self, user: &User, requester: &ResetRequester) -> Result<bool, AuthError> {
+        let token = random_token(PASSWORD_RESET_TOKEN_LEN);
+        let expires_at = Utc::now() + Duration::minutes(PASSWORD_RESET_MINUTES);
+        let user_agent = requester.user_agent.as_deref().map(|ua| ua.chars().take(255).collect::<String>());
+
+        let mut tx = self.pool.begin().await?;
+        // Serializes concurrent requests for the account
+        sqlx::query("SELECT id FROM users WHERE id = ? FOR UPDATE")
+            .bind(&user.id)
+            .execute(&mut *tx)
+            .await?;
+
+        let recent: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM password_resets WHERE user_id = ? AND used = FALSE AND created_at > NOW() - INTERVAL ? SECOND",
+        )
+        .bind(&user.id)
+        .bind(PASSWORD_RESET_RESEND_SECS)
+        .fetch_one(&mut *tx)
+        .await?;
+        if recent > 0 {
+            return Ok(false);
+        }
+
+        sqlx::query("UPDATE password_resets SET used = TRUE WHERE user_id = ? AND used = FALSE")
+            .bind(&user.id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query(
+            r#"
+            INSERT INTO password_resets (id, user_id, token_hash, expires_at, used, requested_ip, user_agent)
+            VALUES (?, ?, ?, ?, FALSE, ?, ?)
+            "#,
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(&user.id)
+        .bind(hash_state(&token))
+        .bind(expires_at)
+        .bind(&requester.ip)
+        .bind(&user_agent)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        let message = EmailMessage {
+            to: user.email.clone(),
+            subject: "Reset your password".to_string(),
+            text: reset_email_text(&token, requester, Utc::now()),
+        };
+        if let Err(e) = self.email_sender.send(&message).await {
+            tracing::error!("Failed to send password reset email for user {}: {}", user.id, e);
+        }
+
+        Ok(true)
+    }
+
+    /// Set a new password with a reset token. The token and any other the
+    /// user holds are used up, and every session of the user is ended:
+    /// tokens issued before now are refused and stored refresh tokens are
+    /// revoked. Returns the user id.
+    pub async fn reset(&self, token: &str, password_hash: &str) -> Result<String, AuthError> {
+        let mut tx = self.pool.begin().await?;
+
+        let user_id: Option<String> = sqlx::query_scalar(
+            "SELECT user_id FROM password_resets WHERE token_hash = ? AND used = FALSE AND expires_at > NOW() FOR UPDATE",
+        )
+        .bind(hash_state(token))
+        .fetch_optional(&mut *tx)
+        .await?;
+        let user_id = user_id.ok_or(AuthError::InvalidResetToken)?;
+
+        sqlx::query("UPDATE password_resets SET used = TRUE WHERE user_id = ? AND used = FALSE")
+            .bind(&user_id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("UPDATE users SET password_hash = ?, token_version = token_version + 1, updated_at = NOW() WHERE id = ?")
+            .bind(password_hash)
+            .bind(&user_id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("UPDATE refresh_tokens SET revoked = TRUE WHERE user_id = ? AND revoked = FALSE")
+            .bind(&user_id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        Ok(user_id)
+    }
+}
+
+/// Reset email body: a link to the frontend's reset page when
+/// PASSWORD_RESET_URL is set, otherwise the bare token
+fn reset_email_text(token: &str, requester: &ResetRequester, at: chrono::DateTime<Utc>) -> String {
+    let action = match std::env::var("PASSWORD_RESET_URL").ok().filter(|url| !url.trim().is_empty()) {
+        Some(url) => {
+            let separator = if url.contains('?') { '&' } else { '?' };
+            format!("Reset your password: {}{}token={}", url.trim(), separator, token)
+        }
+        None => format!("Your password reset token: {}", token),
+    };
+
+    format!(
+        "We received a request to reset your password.\n\n\
+         {}\n\n\
+         It expires in {} minutes and replaces any reset email we sent before.\n\n\
+         Requested at {} from:\n\
+         IP address: {}\n\
+         Device: {}\n\n\
+         If this wasn't you, ignore this email. Your password stays the same.",
+        action,
+        PASSWORD_RESET_MINUTES,
+        at.format("%Y-%m-%d %H:%M UTC"),
+        requester.ip.as_deref().unwrap_or("unknown"),
+        requester.user_agent.as_deref().unwrap_or("unknown"),
+    )
+}
+
+// =============================================================================
+// OAUTH
+// =============================================================================
+
+/// Outcome of the provider step of an OAuth login
+pub enum OAuthLogin {
+    Authenticated(User),
+    /// The user has 2FA enabled; the token completes the login with a code
+    TwoFactorRequired(String),
+}
+
+pub struct OAuthCrud {
+    pool: Pool<MySql>,
+}
+
+impl OAuthCrud {
+    pub fn new(pool: Pool<MySql>) -> Self {
+        Self { pool }
+    }
+
+    /// Remember a login in progress and return its `state`
+    pub async fn create_login_state(
+        &self,
+        provider: OAuthProvider,
+        redirect_uri: &str,
+        code_verifier: &str,
+        nonce: &str,
+        link_user_id: Option<&str>,
+    ) -> Result<String, AuthError> {
+        let state = random_token(43);
+        sqlx::query(
+            r#"
+            INSERT INTO oauth_login_states (state_hash, provider, code_verifier, redirect_uri, nonce, link_user_id, expires_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(hash_state(&state))
+        .bind(provider.as_str())
+        .bind(code_verifier)
+        .bind(redirect_uri)
+        .bind(nonce)
+        .bind(link_user_id)
+        .bind(Utc::now() + Duration::minutes(OAUTH_STATE_MINUTES))
+        .execute(&self.pool)
+        .await?;
+
+        Ok(state)
+    }
+
+    /// Claim a login state; each one can be redeemed once
+    pub async fn take_login_state(&self, provider: OAuthProvider, state: &str) -> Result<OAuthLoginState, AuthError> {
+        let state_hash = hash_state(state);
+        let login = sqlx::query_as::<_, OAuthLoginState>(
+            r#"
+            SELECT CAST(provider AS CHAR) as provider, code_verifier, redirect_uri, nonce, link_user_id
+            FROM oauth_login_states
+            WHERE state_hash = ? AND provider = ? AND pending_user_id IS NULL AND expires_at > NOW()
+            "#,
+        )
+        .bind(&state_hash)
+        .bind(provider.as_str())
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or(AuthError::OAuthStateInvalid)?;
+
+        let claimed = sqlx::query("DELETE FROM oauth_login_states WHERE state_hash = ?")
+            .bind(&state_hash)
+            .execute(&self.pool)
+            .await?;
+        if claimed.rows_affected() == 0 {
+            return Err(AuthError::OAuthStateInvalid);
+        }
+
+        Ok(login)
+    }
+
+    /// Resolve the user behind verified ID token claims: an already linked
+    /// identity, the account being linked, an existing account with the same
+    /// verified email, or a new passwordless account
+    pub async fn resolve_user(
+        &self,
+        users: &UserCrud<'_>,
+        provider: OAuthProvider,
+        claims: &IdTokenClaims,
+        link_user_id: Option<&str>,
+    ) -> Result<User, AuthError> {
+        let linked: Option<(String,)> =
+            sqlx::query_as("SELECT user_id FROM oauth_identities WHERE provider = ? AND subject = ?")
+                .bind(provider.as_str())
+                .bind(&claims.sub)
+                .fetch_optional(&self.pool)
+                .await?;
+
+        if let Some((user_id,)) = linked {
+            if link_user_id.is_some_and(|id| id != user_id) {
+                return Err(AuthError::IdentityConflict("This account is already linked to another user"));
+            }
+            sqlx::query("UPDATE oauth_identities SET last_login_at = NOW() WHERE provider = ? AND subject = ?")
+                .bind(provider.as_str())
+                .bind(&claims.sub)
+                .execute(&self.pool)
+                .await?;
+            return users.find_by_id(&user_id).await?.ok_or(AuthError::UserNotFound);
+        }
+
+        let user = if let Some(user_id) = link_user_id {
+            users.find_by_id(user_id).await?.ok_or(AuthError::UserNotFound)?
+        } else {
+            let email = claims
+                .email
+                .as_deref()
+                .filter(|_| claims.email_verified())
+                .ok_or(AuthError::IdentityConflict("The provider did not share a verified email"))?;
+
+            match users.find_by_email(email).await? {
+                // Only a verified account is joined automatically; otherwise
+                // whoever registered the address first could take over the login
+                Some(existing) if existing.email_verified => existing,
+                Some(_) => {
+                    return Err(AuthError::IdentityConflict(
+                        "An account with this email exists; sign in with your password to link it",
+                    ))
+                }
+                None => self.create_passwordless_user(users, email).await?,
+            }
+        };
+
+        let inserted = sqlx::query(
+            r#"
+            INSERT INTO oauth_identities (id, user_id, provider, subject, email, last_login_at)
+            VALUES (?, ?, ?, ?, ?, NOW())
+            "#,
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(&user.id)
+        .bind(provider.as_str())
+        .bind(&claims.sub)
+        .bind(claims.email.as_deref())
+        .execute(&self.pool)
+        .await;
+
+        match inserted {
+            Ok(_) => Ok(user),
+            Err(sqlx::Error::Database(db)) if db.is_unique_violation() => Err(AuthError::IdentityConflict(
+                "Another account from this provider is already linked to this user",
+            )),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// The password is random and never shown, so only the provider can sign in
+    /// until the user sets one through password reset
+    async fn create_passwordless_user(&self, users: &UserCrud<'_>, email: &str) -> Result<User, AuthError> {
+        let password_hash = hashing::hash_password(&random_token(48))
+            .map_err(|e| AuthError::HashingError(e.to_string()))?;
+
+        let now = Utc::now();
+        let user = User {
+            id: Uuid::new_v4().to_string(),
+            email: email.to_string(),
+            password_hash,
+            email_verified: true,
+            two_factor_enabled: false,
+            two_factor_secret: None,
+            created_at: now,
+            updated_at: now,
+            token_version: 0,
+        };
+        users.create(&user).await?;
+
+        Ok(user)
+    }
+
+    /// Finish the provider step, holding the login back for a 2FA code when
+    /// the user has one enabled
+    pub async fn finish_login(
+        &self,
+        provider: OAuthProvider,
+        user: User,
+        two_factor_code: Option<&str>,
+    ) -> Result<OAuthLogin, AuthError> {
+        let secret = match (&user.two_factor_enabled, &user.two_factor_secret) {
+            (true, Some(secret)) => secret,
+            _ => return Ok(OAuthLogin::Authenticated(user)),
+        };
+
+        match two_factor_code {
+            Some(code) if verify_totp(secret, code) => Ok(OAuthLogin::Authenticated(user)),
+            Some(_) => Err(AuthError::InvalidTwoFactorCode),
+            None => {
+                let token = random_token(43);
+                sqlx::query(
+                    r#"
+                    INSERT INTO oauth_login_states (state_hash, provider, pending_user_id, expires_at)
+                    VALUES (?, ?, ?, ?)
+                    "#,
+                )
+                .bind(hash_state(&token))
+                .bind(provider.as_str())
+                .bind(&user.id)
+                .bind(Utc::now() + Duration::minutes(TWO_FACTOR_CHALLENGE_MINUTES))
+                .execute(&self.pool)
+                .await?;
+
+                Ok(OAuthLogin::TwoFactorRequired(token))
+            }
+        }
+    }
+
+    /// Complete a login held for 2FA. Repeated wrong codes drop the challenge.
+    pub async fn complete_two_factor(&self, users: &UserCrud<'_>, token: &str, code: &str) -> Result<User, AuthError> {
+        let token_hash = hash_state(token);
+        let (user_id, attempts): (String, i32) = sqlx::query_as(
+            r#"
+            SELECT pending_user_id, attempts FROM oauth_login_states
+            WHERE state_hash = ? AND pending_user_id IS NOT NULL AND expires_at > NOW()
+            "#,
+        )
+        .bind(&token_hash)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or(AuthError::OAuthStateInvalid)?;
+
+        let user = users.find_by_id(&user_id).await?.ok_or(AuthError::UserNotFound)?;
+        let valid = user.two_factor_secret.as_deref().is_some_and(|secret| verify_totp(secret, code));
+
+        if !valid {
+            if attempts + 1 >= MAX_TWO_FACTOR_ATTEMPTS {
+                sqlx::query("DELETE FROM oauth_login_states WHERE state_hash = ?")
+                    .bind(&token_hash)
+                    .execute(&self.pool)
+                    .await?;
+            } else {
+                sqlx::query("UPDATE oauth_login_states SET attempts = attempts + 1 WHERE state_hash = ?")
+                    .bind(&token_hash)
+                    .execute(&self.pool)
+                    .await?;
+            }
+            return Err(AuthError::InvalidTwoFactorCode);
+        }
+
+        let claimed = sqlx::query("DELETE FROM oauth_login_states WHERE state_hash = ?")
+            .bind(&token_hash)
+            .execute(&self.pool)
+            .await?;
+        if claimed.rows_affected() == 0 {
+            return Err(AuthError::OAuthStateInvalid);
+        }
+
+        Ok(user)
+    }
+
+    pub async fn list_identities(&self, user_id: &str) -> Result<Vec<OAuthIdentity>, AuthError> {
+        let identities = sqlx::query_as::<_, OAuthIdentity>(
+            r#"
+            SELECT id, user_id, CAST(provider AS CHAR) as provider, subject, email, created_at, last_login_at
+            FROM oauth_identities
+            WHERE user_id = ?
+            ORDER BY created_at
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(identities)
+    }
+}
@@ -0,0 +1,56 @@
+//! Route-level auth, enforced from the declarations in
+//! [`crate::manifest::routes`]. Handlers still extract the user they need;
+//! this makes sure a route documented as needing a token or an
+//! administrator refuses requests without one even if its handler forgets.
+//! Paths the manifest doesn't declare are refused outright.
+
+use axum::{
+    extract::{Request, State},
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::sync::Arc;
+
+use crate::AppState;
+use crate::manifest::{route_access, AuthRequirement};
+use crate::services::api_version::is_unversioned;
+use super::interface::{token_user, AuthRejection, Authenticated};
+
+pub async fn route_auth(State(state): State<Arc<AppState>>, request: Request, next: Next) -> Response {
+    let path = request.uri().path();
+    let Some(route) = route_access(request.method().as_str(), path) else {
+        if is_unversioned(path) {
+            return next.run(request).await;
+        }
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    let requirement = route.auth_requirement();
+    if matches!(requirement, AuthRequirement::None | AuthRequirement::Optional) {
+        return next.run(request).await;
+    }
+
+    let (mut parts, body) = request.into_parts();
+    let (user, scopes) = match token_user(&parts, &state).await {
+        Ok(found) => found,
+        Err(rejection) => return rejection.into_response(),
+    };
+    if !route.admits(&scopes) {
+        return AuthRejection::from((StatusCode::FORBIDDEN, "Token is not scoped for this route")).into_response();
+    }
+
+    let admin = requirement == AuthRequirement::Admin;
+    if admin {
+        let crud = super::crud::UserCrud::new(state.db.clone(), &state.jwt_service);
+        match crud.is_admin(&user.id).await {
+            Ok(true) => {}
+            Ok(false) => return AuthRejection::from((StatusCode::FORBIDDEN, "Admin access required")).into_response(),
+            Err(_) => {
+                return AuthRejection::from((StatusCode::INTERNAL_SERVER_ERROR, "Failed to fetch user")).into_response()
+            }
+        }
+    }
+
+    parts.extensions.insert(Authenticated { user, scopes, admin });
+    next.run(Request::from_parts(parts, body)).await
+}
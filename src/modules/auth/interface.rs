@@ -2,16 +2,51 @@ use async_trait::async_trait;
 use axum::{
     extract::{FromRequestParts, FromRef},
     http::{request::Parts, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
 };
+use std::marker::PhantomData;
 use std::sync::Arc;
 
 use crate::AppState;
+use crate::modules::moderation::crud::ModerationCrud;
+use crate::modules::moderation::model::AccountRestriction;
+use crate::modules::moderation::schema::AccountRestrictedResponse;
+use crate::services::jwt::{Scope, Scopes};
+use crate::services::session::access_token;
 use super::model::{BackupCode, EmailVerification, PasswordReset, RefreshToken, User as UserModel};
 
 // =============================================================================
 // EXTRACTORS
 // =============================================================================
 
+/// Why a request was refused by an auth extractor
+#[derive(Debug)]
+pub enum AuthRejection {
+    Refused(StatusCode, &'static str),
+    /// The account is suspended or banned (403 with the reason and end)
+    Restricted(AccountRestriction),
+}
+
+impl From<(StatusCode, &'static str)> for AuthRejection {
+    fn from((status, message): (StatusCode, &'static str)) -> Self {
+        AuthRejection::Refused(status, message)
+    }
+}
+
+impl IntoResponse for AuthRejection {
+    fn into_response(self) -> Response {
+        match self {
+            AuthRejection::Refused(status, message) => (status, message).into_response(),
+            AuthRejection::Restricted(restriction) => {
+                (StatusCode::FORBIDDEN, Json(AccountRestrictedResponse::from(restriction))).into_response()
+            }
+        }
+    }
+}
+
+// Signed-in user, or None without a valid token. Suspended and banned
+// accounts are treated as anonymous.
 pub struct OptionalUser(pub Option<UserModel>);
 
 impl<S> FromRequestParts<S> for OptionalUser
@@ -26,17 +61,18 @@ where
         state: &S,
     ) -> std::result::Result<Self, Self::Rejection> {
         let state = Arc::from_ref(state);
-        let auth_header = parts.headers.get(axum::http::header::AUTHORIZATION)
-            .and_then(|h| h.to_str().ok());
-
-        if let Some(auth_header) = auth_header {
-            if auth_header.starts_with("Bearer ") {
-                let token = &auth_header[7..];
-                if let Ok(claims) = state.jwt_service.verify_access_token(token) {
-                    let user_id = claims.claims.sub;
-                    let crud = super::crud::UserCrud::new(state.db.clone(), &state.jwt_service);
-                    if let Ok(user) = crud.find_by_id(&user_id).await {
-                        return Ok(OptionalUser(user));
+
+        if let Some(token) = access_token(&parts.headers) {
+            if let Ok(claims) = state.jwt_service.verify_access_token(token) {
+                let user_id = claims.claims.sub;
+                let crud = super::crud::UserCrud::new(state.db.clone(), &state.jwt_service);
+                if let Ok(Some(user)) = crud.find_by_id(&user_id).await {
+                    if user.session_revoked(claims.claims.ver) {
+                        return Ok(OptionalUser(None));
+                    }
+                    let moderation = ModerationCrud::new(state.db.clone());
+                    if let Ok(None) = moderation.restriction(&user.id).await {
+                        return Ok(OptionalUser(Some(user)));
                     }
                 }
             }
@@ -46,7 +82,57 @@ where
     }
 }
 
-// Required User extractor (returns 401 if not authenticated)
+/// Set on the request by [`super::guard::route_auth`] once it has checked
+/// the token, so extractors on the same request don't look the user up again
+#[derive(Clone)]
+pub(super) struct Authenticated {
+    pub user: UserModel,
+    pub scopes: Scopes,
+    /// Checked to be an administrator
+    pub admin: bool,
+}
+
+/// The user behind a verified access token and the scopes it grants.
+/// Suspended and banned accounts are refused whatever the token.
+pub(super) async fn token_user(parts: &Parts, state: &AppState) -> std::result::Result<(UserModel, Scopes), AuthRejection> {
+    if let Some(authenticated) = parts.extensions.get::<Authenticated>() {
+        return Ok((authenticated.user.clone(), authenticated.scopes.clone()));
+    }
+
+    // Bearer header, or the session cookie in cookie mode
+    let token = access_token(&parts.headers).ok_or_else(|| {
+        if parts.headers.contains_key(axum::http::header::AUTHORIZATION) {
+            (StatusCode::UNAUTHORIZED, "Invalid authorization header format")
+        } else {
+            (StatusCode::UNAUTHORIZED, "Missing authorization header")
+        }
+    })?;
+
+    let claims = state.jwt_service.verify_access_token(token)
+        .map_err(|_| (StatusCode::UNAUTHORIZED, "Invalid or expired token"))?;
+    let scopes = claims.claims.scopes();
+
+    let user_id = claims.claims.sub;
+    let crud = super::crud::UserCrud::new(state.db.clone(), &state.jwt_service);
+    let user = crud.find_by_id(&user_id).await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to fetch user"))?
+        .ok_or((StatusCode::UNAUTHORIZED, "User not found"))?;
+    // Signed out everywhere, e.g. by a password reset
+    if user.session_revoked(claims.claims.ver) {
+        return Err((StatusCode::UNAUTHORIZED, "Session was revoked, log in again").into());
+    }
+
+    let restriction = ModerationCrud::new(state.db.clone()).restriction(&user.id).await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to fetch user"))?;
+    if let Some(restriction) = restriction {
+        return Err(AuthRejection::Restricted(restriction));
+    }
+
+    Ok((user, scopes))
+}
+
+// Required User extractor (returns 401 if not authenticated). Routes that
+// don't name a scope only accept tokens with every scope (403 otherwise).
 pub struct User(pub UserModel);
 
 impl<S> FromRequestParts<S> for User
@@ -54,32 +140,159 @@ where
     Arc<AppState>: FromRef<S>,
     S: Send + Sync,
 {
-    type Rejection = (StatusCode, &'static str);
+    type Rejection = AuthRejection;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &S,
+    ) -> std::result::Result<Self, Self::Rejection> {
+        let state = Arc::from_ref(state);
+        let (user, scopes) = token_user(parts, &state).await?;
+        if !scopes.is_full() {
+            return Err((StatusCode::FORBIDDEN, "Token is not scoped for this route").into());
+        }
+
+        Ok(User(user))
+    }
+}
+
+// Any signed-in user with the scopes their token grants, for routes that
+// check scopes themselves
+pub struct ScopedUser {
+    pub user: UserModel,
+    pub scopes: Scopes,
+}
+
+impl<S> FromRequestParts<S> for ScopedUser
+where
+    Arc<AppState>: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = AuthRejection;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &S,
+    ) -> std::result::Result<Self, Self::Rejection> {
+        let state = Arc::from_ref(state);
+        let (user, scopes) = token_user(parts, &state).await?;
+
+        Ok(ScopedUser { user, scopes })
+    }
+}
+
+/// A scope a route requires, named by a marker type from [`scope`]
+pub trait RequiredScope: Send + Sync {
+    const SCOPE: Scope;
+}
+
+/// Marker types for [`Scoped`] and [`OptionalScoped`]
+pub mod scope {
+    use super::RequiredScope;
+    use crate::services::jwt::Scope;
+
+    pub struct SwapCreate;
+    pub struct HistoryRead;
+    pub struct AccountManage;
+
+    impl RequiredScope for SwapCreate {
+        const SCOPE: Scope = Scope::SwapCreate;
+    }
+
+    impl RequiredScope for HistoryRead {
+        const SCOPE: Scope = Scope::HistoryRead;
+    }
+
+    impl RequiredScope for AccountManage {
+        const SCOPE: Scope = Scope::AccountManage;
+    }
+}
+
+// Signed-in user whose token grants scope `R` (401 if not authenticated,
+// 403 if the token lacks the scope)
+pub struct Scoped<R: RequiredScope>(pub UserModel, PhantomData<R>);
+
+impl<S, R> FromRequestParts<S> for Scoped<R>
+where
+    Arc<AppState>: FromRef<S>,
+    S: Send + Sync,
+    R: RequiredScope,
+{
+    type Rejection = AuthRejection;
 
     async fn from_request_parts(
         parts: &mut Parts,
         state: &S,
     ) -> std::result::Result<Self, Self::Rejection> {
         let state = Arc::from_ref(state);
-        let auth_header = parts.headers.get(axum::http::header::AUTHORIZATION)
-            .and_then(|h| h.to_str().ok())
-            .ok_or((StatusCode::UNAUTHORIZED, "Missing authorization header"))?;
+        let (user, scopes) = token_user(parts, &state).await?;
+        if !scopes.contains(R::SCOPE) {
+            return Err((StatusCode::FORBIDDEN, "Token is not scoped for this route").into());
+        }
 
-        if !auth_header.starts_with("Bearer ") {
-            return Err((StatusCode::UNAUTHORIZED, "Invalid authorization header format"));
+        Ok(Scoped(user, PhantomData))
+    }
+}
+
+// Like OptionalUser, but a valid token without scope `R` is refused (403)
+// rather than treated as anonymous
+pub struct OptionalScoped<R: RequiredScope>(pub Option<UserModel>, PhantomData<R>);
+
+impl<S, R> FromRequestParts<S> for OptionalScoped<R>
+where
+    Arc<AppState>: FromRef<S>,
+    S: Send + Sync,
+    R: RequiredScope,
+{
+    type Rejection = AuthRejection;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &S,
+    ) -> std::result::Result<Self, Self::Rejection> {
+        let state = Arc::from_ref(state);
+        match token_user(parts, &state).await {
+            Ok((user, scopes)) if scopes.contains(R::SCOPE) => Ok(OptionalScoped(Some(user), PhantomData)),
+            Ok(_) => Err((StatusCode::FORBIDDEN, "Token is not scoped for this route").into()),
+            // A restricted account is refused rather than served anonymously
+            Err(e @ AuthRejection::Restricted(_)) => Err(e),
+            Err(AuthRejection::Refused(status, message)) if status == StatusCode::INTERNAL_SERVER_ERROR => {
+                Err((status, message).into())
+            }
+            Err(_) => Ok(OptionalScoped(None, PhantomData)),
         }
+    }
+}
 
-        let token = &auth_header[7..];
-        let claims = state.jwt_service.verify_access_token(token)
-            .map_err(|_| (StatusCode::UNAUTHORIZED, "Invalid or expired token"))?;
+// Admin extractor (401 if not authenticated, 403 if not an administrator)
+pub struct AdminUser(pub UserModel);
 
-        let user_id = claims.claims.sub;
+impl<S> FromRequestParts<S> for AdminUser
+where
+    Arc<AppState>: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = AuthRejection;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &S,
+    ) -> std::result::Result<Self, Self::Rejection> {
+        let User(user) = User::from_request_parts(parts, state).await?;
+        if parts.extensions.get::<Authenticated>().is_some_and(|a| a.admin) {
+            return Ok(AdminUser(user));
+        }
+
+        let state = Arc::from_ref(state);
         let crud = super::crud::UserCrud::new(state.db.clone(), &state.jwt_service);
-        let user = crud.find_by_id(&user_id).await
-            .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to fetch user"))?
-            .ok_or((StatusCode::UNAUTHORIZED, "User not found"))?;
+        let is_admin = crud.is_admin(&user.id).await
+            .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Failed to fetch user"))?;
 
-        Ok(User(user))
+        if !is_admin {
+            return Err((StatusCode::FORBIDDEN, "Admin access required").into());
+        }
+
+        Ok(AdminUser(user))
     }
 }
 
@@ -121,7 +334,7 @@ pub trait EmailVerificationRepository: Send + Sync {
 #[async_trait]
 pub trait PasswordResetRepository: Send + Sync {
     async fn create(&self, reset: &PasswordReset) -> AuthResult<()>;
-    async fn find_by_token(&self, token: &str) -> AuthResult<Option<PasswordReset>>;
+    async fn find_by_token_hash(&self, token_hash: &str) -> AuthResult<Option<PasswordReset>>;
     async fn mark_used(&self, id: &str) -> AuthResult<()>;
     async fn delete_for_user(&self, user_id: &str) -> AuthResult<()>;
     async fn delete_expired(&self) -> AuthResult<u64>;
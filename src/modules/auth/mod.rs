@@ -1,5 +1,6 @@
 pub mod controller;
 pub mod crud;
+pub mod guard;
 pub mod interface;
 pub mod model;
 pub mod routes;
@@ -1,9 +1,13 @@
 use chrono::{DateTime, Utc};
 use sqlx::FromRow;
 
+use crate::services::oauth::OAuthProvider;
+use crate::services::pii::SealedString;
+
 #[derive(Debug, Clone, FromRow)]
 pub struct User {
     pub id: String,
+    #[sqlx(try_from = "SealedString")]
     pub email: String,
     pub password_hash: String,
     pub email_verified: bool,
@@ -11,6 +15,16 @@ pub struct User {
     pub two_factor_secret: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// Carried by every token issued; a password reset bumps it, which
+    /// refuses the tokens issued before
+    pub token_version: u32,
+}
+
+impl User {
+    /// Whether a token carrying `version` was revoked
+    pub fn session_revoked(&self, version: u32) -> bool {
+        version != self.token_version
+    }
 }
 
 #[derive(Debug, Clone, FromRow)]
@@ -36,9 +50,12 @@ pub struct EmailVerification {
 pub struct PasswordReset {
     pub id: String,
     pub user_id: String,
-    pub token: String,
+    /// SHA-256 of the token; the token itself is only in the email
+    pub token_hash: String,
     pub expires_at: DateTime<Utc>,
     pub used: bool,
+    pub requested_ip: Option<String>,
+    pub user_agent: Option<String>,
     pub created_at: DateTime<Utc>,
 }
 
@@ -50,3 +67,53 @@ pub struct BackupCode {
     pub used: bool,
     pub created_at: DateTime<Utc>,
 }
+
+/// A Google or Apple account linked to a user
+#[derive(Debug, Clone, FromRow)]
+pub struct OAuthIdentity {
+    pub id: String,
+    pub user_id: String,
+    pub provider: OAuthProvider,
+    pub subject: String,
+    pub email: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_login_at: Option<DateTime<Utc>>,
+}
+
+/// An authorization-code login between `start` and `callback`
+#[derive(Debug, Clone, FromRow)]
+pub struct OAuthLoginState {
+    pub provider: OAuthProvider,
+    pub code_verifier: String,
+    pub redirect_uri: String,
+    pub nonce: String,
+    pub link_user_id: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user(token_version: u32) -> User {
+        let now = Utc::now();
+        User {
+            id: "user".to_string(),
+            email: "user@example.com".to_string(),
+            password_hash: String::new(),
+            email_verified: true,
+            two_factor_enabled: false,
+            two_factor_secret: None,
+            created_at: now,
+            updated_at: now,
+            token_version,
+        }
+    }
+
+    #[test]
+    fn test_tokens_from_before_a_reset_are_revoked() {
+        let reset_user = user(1);
+        assert!(reset_user.session_revoked(0));
+        assert!(!reset_user.session_revoked(1));
+        assert!(!user(0).session_revoked(0));
+    }
+}
@@ -1,11 +1,22 @@
-use axum::{routing::post, Router};
+use axum::Router;
 use std::sync::Arc;
 
 use crate::AppState;
+use crate::manifest::DeclaredRoutes;
 use super::controller;
 
 pub fn auth_routes() -> Router<Arc<AppState>> {
     Router::new()
-        .route("/register", post(controller::register))
-        .route("/login", post(controller::login))
+        .declared("register", controller::register)
+        .declared("login", controller::login)
+        .declared("forgotPassword", controller::forgot_password)
+        .declared("resetPassword", controller::reset_password)
+        .declared("createSession", controller::create_session)
+        .declared("deleteSession", controller::delete_session)
+        .declared("refreshSession", controller::refresh_session)
+        .declared("oauthStart", controller::oauth_start)
+        .declared("oauthCallback", controller::oauth_callback)
+        .declared("oauthTwoFactor", controller::oauth_two_factor)
+        .declared("listOAuthIdentities", controller::list_oauth_identities)
+        .declared("downscopeToken", controller::downscope_token)
 }
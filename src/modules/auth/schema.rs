@@ -1,11 +1,14 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use validator::Validate;
 
+use crate::services::oauth::OAuthProvider;
+
 // =============================================================================
 // REGISTER
 // =============================================================================
 
-#[derive(Debug, Deserialize, Validate)]
+#[derive(Debug, Deserialize, Validate, JsonSchema)]
 pub struct RegisterRequest {
     #[validate(email(message = "Invalid email format"))]
     pub email: String,
@@ -13,7 +16,7 @@ pub struct RegisterRequest {
     pub password_confirm: String,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct RegisterResponse {
     pub user: UserResponse,
 }
@@ -22,7 +25,7 @@ pub struct RegisterResponse {
 // LOGIN
 // =============================================================================
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct LoginRequest {
     pub email: String,
     pub password: String,
@@ -30,22 +33,158 @@ pub struct LoginRequest {
     pub two_factor_code: Option<String>,
     #[serde(default)]
     pub backup_code: Option<String>,
+    /// Space-separated scopes for the tokens (`swap:create`, `history:read`,
+    /// `account:manage`); every scope when omitted
+    #[serde(default)]
+    pub scope: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct LoginResponse {
     pub access_token: String,
     pub refresh_token: String,
     pub token_type: &'static str,
     pub expires_in: i64,
+    /// Space-separated scopes the access token grants
+    pub scope: String,
 }
 
-#[derive(Debug, Serialize)]
+// =============================================================================
+// DOWNSCOPED TOKENS
+// =============================================================================
+
+/// A narrower token for a third-party integration
+#[derive(Debug, Deserialize, Validate, JsonSchema)]
+pub struct DownscopeTokenRequest {
+    /// Space-separated scopes; must be ones the current token has
+    pub scope: String,
+    /// Name of the integration, recorded in the token
+    #[validate(length(min = 1, max = 64))]
+    pub client: String,
+    /// Lifetime in seconds; defaults to an hour, at most a day
+    #[validate(range(min = 60, max = 86400))]
+    pub expires_in: Option<i64>,
+}
+
+/// No refresh token: integrations ask the user's session for a new one
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct DownscopedTokenResponse {
+    pub access_token: String,
+    pub token_type: &'static str,
+    pub expires_in: i64,
+    pub scope: String,
+    pub client: String,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct LoginRequires2faResponse {
     pub requires_2fa: bool,
     pub two_factor_token: String,
 }
 
+// =============================================================================
+// COOKIE SESSION
+// =============================================================================
+
+/// Tokens are set as httpOnly cookies rather than returned
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct SessionResponse {
+    /// Echo in the `X-CSRF-Token` header on state-changing requests; also
+    /// set as a readable cookie
+    pub csrf_token: String,
+    pub expires_in: i64,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct SessionEndedResponse {
+    pub message: &'static str,
+}
+
+// =============================================================================
+// OAUTH LOGIN
+// =============================================================================
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct OAuthStartRequest {
+    /// Must be one of the configured OAUTH_REDIRECT_URIS
+    pub redirect_uri: String,
+    /// Link the provider account to the signed-in user instead of logging in
+    #[serde(default)]
+    pub link: bool,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct OAuthStartResponse {
+    /// Send the user here; the provider redirects back with `code` and `state`
+    pub authorization_url: String,
+    pub state: String,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct OAuthCallbackRequest {
+    pub code: String,
+    pub state: String,
+    /// Completes the login in one step for users with 2FA enabled
+    #[serde(default)]
+    pub two_factor_code: Option<String>,
+}
+
+/// Either tokens, or `requires_2fa` with a token for `/auth/oauth/2fa`
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct OAuthLoginResponse {
+    pub requires_2fa: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub two_factor_token: Option<String>,
+    #[serde(flatten)]
+    pub tokens: Option<LoginResponse>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct OAuthTwoFactorRequest {
+    pub two_factor_token: String,
+    pub code: String,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct OAuthIdentityResponse {
+    pub provider: OAuthProvider,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_login_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct OAuthIdentitiesResponse {
+    pub identities: Vec<OAuthIdentityResponse>,
+}
+
+// =============================================================================
+// PASSWORD RESET
+// =============================================================================
+
+#[derive(Debug, Deserialize, Validate, JsonSchema)]
+pub struct ForgotPasswordRequest {
+    #[validate(email(message = "Invalid email format"))]
+    pub email: String,
+}
+
+#[derive(Debug, Deserialize, Validate, JsonSchema)]
+pub struct ResetPasswordRequest {
+    /// From the reset email
+    #[validate(length(min = 1, max = 128))]
+    pub token: String,
+    pub password: String,
+    pub password_confirm: String,
+}
+
+/// The same whether or not the email belongs to an account
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct PasswordResetResponse {
+    pub message: &'static str,
+}
+
 // =============================================================================
 // LOGOUT
 // =============================================================================
@@ -81,7 +220,7 @@ pub struct RefreshTokenResponse {
 // ME (Current User)
 // =============================================================================
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct UserResponse {
     pub id: String,
     pub email: String,
@@ -91,32 +230,6 @@ pub struct UserResponse {
     pub updated_at: chrono::DateTime<chrono::Utc>,
 }
 
-// =============================================================================
-// PASSWORD RESET
-// =============================================================================
-
-#[derive(Debug, Deserialize)]
-pub struct ForgotPasswordRequest {
-    pub email: String,
-}
-
-#[derive(Debug, Serialize)]
-pub struct ForgotPasswordResponse {
-    pub message: &'static str,
-}
-
-#[derive(Debug, Deserialize)]
-pub struct ResetPasswordRequest {
-    pub token: String,
-    pub password: String,
-    pub password_confirm: String,
-}
-
-#[derive(Debug, Serialize)]
-pub struct ResetPasswordResponse {
-    pub message: &'static str,
-}
-
 // =============================================================================
 // EMAIL VERIFICATION
 // =============================================================================
@@ -180,7 +293,7 @@ pub struct BackupCodesResponse {
 // ERROR RESPONSE
 // =============================================================================
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct ErrorResponse {
     pub error: String,
     #[serde(skip_serializing_if = "Option::is_none")]
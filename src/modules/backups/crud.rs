@@ -0,0 +1,129 @@
+use sqlx::{MySql, Pool};
+use uuid::Uuid;
+
+use super::model::BackupRun;
+use crate::modules::jobs::crud::JobCrud;
+use crate::modules::jobs::model::JobKind;
+
+const MAX_PAGE: i64 = 200;
+/// A run still unfinished after this long is assumed to have died with its
+/// process and no longer blocks a new one
+const STALE_RUN_HOURS: i64 = 6;
+
+const RUN_COLUMNS: &str = r#"
+    id, key_fingerprint, object_key, size_bytes, sha256, manifest,
+    started_at, finished_at, verified_at, error
+"#;
+
+// =============================================================================
+// BACKUP CRUD
+// =============================================================================
+
+pub struct BackupCrud {
+    pool: Pool<MySql>,
+}
+
+impl BackupCrud {
+    pub fn new(pool: Pool<MySql>) -> Self {
+        Self { pool }
+    }
+
+    /// Record a run, with a system job of the same id admins can follow
+    pub async fn start_run(&self, key_fingerprint: &str) -> Result<String, sqlx::Error> {
+        let run_id = Uuid::new_v4().to_string();
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("INSERT INTO backup_runs (id, key_fingerprint) VALUES (?, ?)")
+            .bind(&run_id)
+            .bind(key_fingerprint)
+            .execute(&mut *tx)
+            .await?;
+        JobCrud::create(&mut tx, &run_id, JobKind::Backup, None).await?;
+        tx.commit().await?;
+
+        Ok(run_id)
+    }
+
+    /// A recent run that has neither finished nor failed
+    pub async fn running(&self) -> Result<Option<BackupRun>, sqlx::Error> {
+        let sql = format!(
+            r#"
+            SELECT {} FROM backup_runs
+            WHERE finished_at IS NULL AND started_at > NOW() - INTERVAL ? HOUR
+            ORDER BY started_at DESC
+            LIMIT 1
+            "#,
+            RUN_COLUMNS
+        );
+        sqlx::query_as::<_, BackupRun>(&sql)
+            .bind(STALE_RUN_HOURS)
+            .fetch_optional(&self.pool)
+            .await
+    }
+
+    pub async fn record_upload(
+        &self,
+        run_id: &str,
+        object_key: &str,
+        size_bytes: u64,
+        sha256: &str,
+        manifest: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            UPDATE backup_runs
+            SET object_key = ?, size_bytes = ?, sha256 = ?, manifest = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(object_key)
+        .bind(size_bytes)
+        .bind(sha256)
+        .bind(manifest)
+        .bind(run_id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn mark_verified(&self, run_id: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE backup_runs SET verified_at = NOW(), finished_at = NOW() WHERE id = ?")
+            .bind(run_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn mark_failed(&self, run_id: &str, error: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE backup_runs SET error = ?, finished_at = NOW() WHERE id = ? AND finished_at IS NULL")
+            .bind(error)
+            .bind(run_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get_run(&self, run_id: &str) -> Result<Option<BackupRun>, sqlx::Error> {
+        let sql = format!("SELECT {} FROM backup_runs WHERE id = ?", RUN_COLUMNS);
+        sqlx::query_as::<_, BackupRun>(&sql)
+            .bind(run_id)
+            .fetch_optional(&self.pool)
+            .await
+    }
+
+    /// Run that wrote `object_key`, for checking a download against it
+    pub async fn find_by_object(&self, object_key: &str) -> Result<Option<BackupRun>, sqlx::Error> {
+        let sql = format!("SELECT {} FROM backup_runs WHERE object_key = ?", RUN_COLUMNS);
+        sqlx::query_as::<_, BackupRun>(&sql)
+            .bind(object_key)
+            .fetch_optional(&self.pool)
+            .await
+    }
+
+    pub async fn list_runs(&self, limit: Option<i64>) -> Result<Vec<BackupRun>, sqlx::Error> {
+        let sql = format!("SELECT {} FROM backup_runs ORDER BY started_at DESC LIMIT ?", RUN_COLUMNS);
+        sqlx::query_as::<_, BackupRun>(&sql)
+            .bind(limit.unwrap_or(20).clamp(1, MAX_PAGE))
+            .fetch_all(&self.pool)
+            .await
+    }
+}
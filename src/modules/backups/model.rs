@@ -0,0 +1,27 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+// =============================================================================
+// BACKUP RUN
+// =============================================================================
+
+/// One encrypted snapshot of the critical tables. Shares its id with the
+/// system job tracking it.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct BackupRun {
+    pub id: String,
+    pub key_fingerprint: String,
+    /// Set once the snapshot is uploaded
+    pub object_key: Option<String>,
+    pub size_bytes: Option<u64>,
+    /// SHA-256 of the uploaded object
+    pub sha256: Option<String>,
+    /// Per-table row counts and digests, as JSON
+    pub manifest: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+    /// When the uploaded object was read back and matched its manifest
+    pub verified_at: Option<DateTime<Utc>>,
+    pub error: Option<String>,
+}
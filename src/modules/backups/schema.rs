@@ -0,0 +1,91 @@
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::model::BackupRun;
+use crate::services::backup::snapshot::Manifest;
+
+// =============================================================================
+// RUNS
+// =============================================================================
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct BackupRunsQuery {
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum BackupRunStatus {
+    Running,
+    /// Uploaded, read back and matched against its manifest
+    Verified,
+    Failed,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct BackupRunResponse {
+    /// Also the id of the job tracking the run, see GET /jobs/{id}
+    pub id: String,
+    pub status: BackupRunStatus,
+    /// Which encryption key the snapshot needs
+    pub key_fingerprint: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub object_key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size_bytes: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+    /// Row count and digest per table
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tables: Option<Manifest>,
+    pub started_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub finished_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verified_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl From<BackupRun> for BackupRunResponse {
+    fn from(r: BackupRun) -> Self {
+        let status = if r.error.is_some() {
+            BackupRunStatus::Failed
+        } else if r.verified_at.is_some() {
+            BackupRunStatus::Verified
+        } else {
+            BackupRunStatus::Running
+        };
+        Self {
+            id: r.id,
+            status,
+            key_fingerprint: r.key_fingerprint,
+            object_key: r.object_key,
+            size_bytes: r.size_bytes,
+            sha256: r.sha256,
+            tables: r.manifest.as_deref().and_then(|m| serde_json::from_str(m).ok()),
+            started_at: r.started_at,
+            finished_at: r.finished_at,
+            verified_at: r.verified_at,
+            error: r.error,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct BackupRunsResponse {
+    /// Newest first
+    pub runs: Vec<BackupRunResponse>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct BackupErrorResponse {
+    pub error: String,
+}
+
+impl BackupErrorResponse {
+    pub fn new(error: impl Into<String>) -> Self {
+        Self { error: error.into() }
+    }
+}
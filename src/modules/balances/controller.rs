@@ -0,0 +1,157 @@
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    Json,
+};
+use std::sync::Arc;
+
+use crate::AppState;
+use crate::modules::auth::interface::User;
+use super::crud::{BalanceCrud, BalanceError};
+use super::model::WithdrawalRequest;
+use super::schema::{
+    BalanceErrorResponse, BalanceResponse, BalancesResponse, CreateWithdrawalRequest,
+    LedgerEntryResponse, LedgerQuery, LedgerResponse, TransferRequest, TransferResponse,
+    WithdrawalResponse, WithdrawalsResponse,
+};
+
+fn to_error(e: BalanceError) -> (StatusCode, Json<BalanceErrorResponse>) {
+    (e.status_code(), Json(BalanceErrorResponse::new(e.to_string())))
+}
+
+pub(crate) fn to_withdrawal_response(w: WithdrawalRequest) -> WithdrawalResponse {
+    WithdrawalResponse {
+        id: w.id,
+        currency: w.currency,
+        network: w.network,
+        amount: w.amount,
+        address: w.destination_address,
+        extra_id: w.destination_extra_id,
+        status: w.status,
+        tx_hash: w.tx_hash,
+        rejection_reason: w.rejection_reason,
+        created_at: w.created_at,
+    }
+}
+
+// =============================================================================
+// GET /balances - Custodial balances for the current user
+// =============================================================================
+
+pub async fn get_balances(
+    State(state): State<Arc<AppState>>,
+    user: User,
+) -> Result<Json<BalancesResponse>, (StatusCode, Json<BalanceErrorResponse>)> {
+    let crud = BalanceCrud::new(state.db.clone());
+
+    let custody_enabled = crud.is_custody_enabled(&user.0.id).await.map_err(to_error)?;
+    let balances = crud.list_balances(&user.0.id).await.map_err(to_error)?;
+
+    Ok(Json(BalancesResponse {
+        custody_enabled,
+        balances: balances
+            .into_iter()
+            .map(|a| BalanceResponse {
+                currency: a.currency,
+                network: a.network,
+                balance: a.balance,
+                updated_at: a.updated_at,
+            })
+            .collect(),
+    }))
+}
+
+// =============================================================================
+// POST /balances/opt-in - Enable custodial balances
+// =============================================================================
+
+pub async fn opt_in(
+    State(state): State<Arc<AppState>>,
+    user: User,
+) -> Result<Json<BalancesResponse>, (StatusCode, Json<BalanceErrorResponse>)> {
+    let crud = BalanceCrud::new(state.db.clone());
+    crud.opt_in(&user.0.id).await.map_err(to_error)?;
+
+    get_balances(State(state), user).await
+}
+
+// =============================================================================
+// GET /balances/ledger - Ledger history
+// =============================================================================
+
+pub async fn get_ledger(
+    State(state): State<Arc<AppState>>,
+    user: User,
+    Query(query): Query<LedgerQuery>,
+) -> Result<Json<LedgerResponse>, (StatusCode, Json<BalanceErrorResponse>)> {
+    let crud = BalanceCrud::new(state.db.clone());
+
+    let entries = crud
+        .get_ledger(&user.0.id, query.currency.as_deref(), query.network.as_deref(), query.limit)
+        .await
+        .map_err(to_error)?;
+
+    Ok(Json(LedgerResponse {
+        entries: entries
+            .into_iter()
+            .map(|(account, e)| LedgerEntryResponse {
+                id: e.id,
+                currency: account.currency,
+                network: account.network,
+                entry_type: e.entry_type,
+                amount: e.amount,
+                balance_after: e.balance_after,
+                reference_type: e.reference_type,
+                reference_id: e.reference_id,
+                memo: e.memo,
+                created_at: e.created_at,
+            })
+            .collect(),
+    }))
+}
+
+// =============================================================================
+// POST /balances/transfers - Internal transfer to another user
+// =============================================================================
+
+pub async fn create_transfer(
+    State(state): State<Arc<AppState>>,
+    user: User,
+    Json(payload): Json<TransferRequest>,
+) -> Result<(StatusCode, Json<TransferResponse>), (StatusCode, Json<BalanceErrorResponse>)> {
+    let crud = BalanceCrud::new(state.db.clone());
+
+    let transfer = crud.transfer(&user.0.id, &payload).await.map_err(to_error)?;
+
+    Ok((StatusCode::CREATED, Json(transfer)))
+}
+
+// =============================================================================
+// POST /balances/withdrawals - Request a withdrawal (requires approval)
+// GET  /balances/withdrawals - List withdrawals
+// =============================================================================
+
+pub async fn create_withdrawal(
+    State(state): State<Arc<AppState>>,
+    user: User,
+    Json(payload): Json<CreateWithdrawalRequest>,
+) -> Result<(StatusCode, Json<WithdrawalResponse>), (StatusCode, Json<BalanceErrorResponse>)> {
+    let crud = BalanceCrud::new(state.db.clone());
+
+    let withdrawal = crud.request_withdrawal(&user.0.id, &payload).await.map_err(to_error)?;
+
+    Ok((StatusCode::CREATED, Json(to_withdrawal_response(withdrawal))))
+}
+
+pub async fn list_withdrawals(
+    State(state): State<Arc<AppState>>,
+    user: User,
+) -> Result<Json<WithdrawalsResponse>, (StatusCode, Json<BalanceErrorResponse>)> {
+    let crud = BalanceCrud::new(state.db.clone());
+
+    let withdrawals = crud.list_withdrawals(&user.0.id).await.map_err(to_error)?;
+
+    Ok(Json(WithdrawalsResponse {
+        withdrawals: withdrawals.into_iter().map(to_withdrawal_response).collect(),
+    }))
+}
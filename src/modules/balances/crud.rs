@@ -0,0 +1,739 @@
+use axum::http::StatusCode;
+use sqlx::{MySql, Pool, Transaction};
+use uuid::Uuid;
+
+use crate::services::amount::Decimal;
+use crate::services::pii::email_index;
+use super::model::{BalanceAccount, LedgerEntry, LedgerEntryType, SwapProceeds, WithdrawalRequest, WithdrawalStatus};
+use super::schema::{CreateWithdrawalRequest, TransferRequest, TransferResponse};
+
+/// Scale of the DECIMAL(36, 18) ledger columns
+const LEDGER_DECIMALS: u32 = 18;
+const MAX_LEDGER_PAGE: i64 = 200;
+
+/// Owner, currency, network, settled amount and platform fee of a balance swap
+type SwapProceedsRow = (Option<String>, String, String, Option<Decimal>, Decimal);
+
+// =============================================================================
+// BALANCE ERROR
+// =============================================================================
+
+#[derive(Debug)]
+pub enum BalanceError {
+    CustodyNotEnabled,
+    InvalidAmount(String),
+    InvalidAddress,
+    InsufficientBalance { requested: Decimal, available: Decimal },
+    RecipientNotFound,
+    RecipientCustodyNotEnabled,
+    SelfTransfer,
+    WithdrawalNotFound,
+    InvalidWithdrawalState(WithdrawalStatus),
+    DuplicateEntry,
+    DatabaseError(String),
+}
+
+impl std::fmt::Display for BalanceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BalanceError::CustodyNotEnabled => {
+                write!(f, "Custodial balances are not enabled for this account")
+            }
+            BalanceError::InvalidAmount(msg) => write!(f, "Invalid amount: {}", msg),
+            BalanceError::InvalidAddress => write!(f, "Invalid address"),
+            BalanceError::InsufficientBalance { requested, available } => {
+                write!(f, "Insufficient balance: requested={}, available={}", requested, available)
+            }
+            BalanceError::RecipientNotFound => write!(f, "Recipient not found"),
+            BalanceError::RecipientCustodyNotEnabled => {
+                write!(f, "Recipient has not enabled custodial balances")
+            }
+            BalanceError::SelfTransfer => write!(f, "Cannot transfer to your own account"),
+            BalanceError::WithdrawalNotFound => write!(f, "Withdrawal not found"),
+            BalanceError::InvalidWithdrawalState(status) => {
+                write!(f, "Withdrawal cannot be changed in state {:?}", status)
+            }
+            BalanceError::DuplicateEntry => write!(f, "Ledger entry already posted"),
+            BalanceError::DatabaseError(e) => write!(f, "Database error: {}", e),
+        }
+    }
+}
+
+impl BalanceError {
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            BalanceError::CustodyNotEnabled => StatusCode::FORBIDDEN,
+            BalanceError::InvalidAmount(_) => StatusCode::BAD_REQUEST,
+            BalanceError::InvalidAddress => StatusCode::BAD_REQUEST,
+            BalanceError::InsufficientBalance { .. } => StatusCode::PAYMENT_REQUIRED,
+            BalanceError::RecipientNotFound => StatusCode::NOT_FOUND,
+            BalanceError::RecipientCustodyNotEnabled => StatusCode::CONFLICT,
+            BalanceError::SelfTransfer => StatusCode::BAD_REQUEST,
+            BalanceError::WithdrawalNotFound => StatusCode::NOT_FOUND,
+            BalanceError::InvalidWithdrawalState(_) => StatusCode::CONFLICT,
+            BalanceError::DuplicateEntry => StatusCode::CONFLICT,
+            BalanceError::DatabaseError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+impl From<sqlx::Error> for BalanceError {
+    fn from(err: sqlx::Error) -> Self {
+        BalanceError::DatabaseError(err.to_string())
+    }
+}
+
+/// Validate a user-supplied amount for ledger postings. Digits past the
+/// ledger's scale are refused rather than rounded away by the database.
+pub fn validate_amount(amount: Decimal) -> Result<(), BalanceError> {
+    if amount <= Decimal::ZERO {
+        return Err(BalanceError::InvalidAmount("must be a positive number".to_string()));
+    }
+    if amount.normalize().scale() > LEDGER_DECIMALS {
+        return Err(BalanceError::InvalidAmount(format!(
+            "more than {} decimal places",
+            LEDGER_DECIMALS
+        )));
+    }
+    Ok(())
+}
+
+/// Whether a balance can cover a debit
+pub fn has_sufficient_balance(available: Decimal, requested: Decimal) -> bool {
+    available >= requested
+}
+
+// =============================================================================
+// BALANCE CRUD
+// =============================================================================
+
+pub struct BalanceCrud {
+    pool: Pool<MySql>,
+}
+
+impl BalanceCrud {
+    pub fn new(pool: Pool<MySql>) -> Self {
+        Self { pool }
+    }
+
+    // =========================================================================
+    // CUSTODY OPT-IN
+    // =========================================================================
+
+    pub async fn is_custody_enabled(&self, user_id: &str) -> Result<bool, BalanceError> {
+        let row: Option<(bool,)> = sqlx::query_as("SELECT custody_enabled FROM users WHERE id = ?")
+            .bind(user_id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(row.map(|r| r.0).unwrap_or(false))
+    }
+
+    pub async fn opt_in(&self, user_id: &str) -> Result<(), BalanceError> {
+        sqlx::query("UPDATE users SET custody_enabled = TRUE, updated_at = NOW() WHERE id = ?")
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn require_custody(&self, user_id: &str) -> Result<(), BalanceError> {
+        if !self.is_custody_enabled(user_id).await? {
+            return Err(BalanceError::CustodyNotEnabled);
+        }
+        Ok(())
+    }
+
+    // =========================================================================
+    // READS
+    // =========================================================================
+
+    pub async fn list_balances(&self, user_id: &str) -> Result<Vec<BalanceAccount>, BalanceError> {
+        let accounts = sqlx::query_as::<_, BalanceAccount>(
+            r#"
+            SELECT id, user_id, currency, network, balance,
+                   created_at, updated_at
+            FROM balance_accounts
+            WHERE user_id = ?
+            ORDER BY currency, network
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(accounts)
+    }
+
+    /// Ledger entries for a user, newest first, joined with their account's asset
+    pub async fn get_ledger(
+        &self,
+        user_id: &str,
+        currency: Option<&str>,
+        network: Option<&str>,
+        limit: Option<i64>,
+    ) -> Result<Vec<(BalanceAccount, LedgerEntry)>, BalanceError> {
+        let accounts = self.list_balances(user_id).await?;
+        let accounts: Vec<BalanceAccount> = accounts
+            .into_iter()
+            .filter(|a| currency.map(|c| a.currency.eq_ignore_ascii_case(c)).unwrap_or(true))
+            .filter(|a| network.map(|n| a.network.eq_ignore_ascii_case(n)).unwrap_or(true))
+            .collect();
+
+        if accounts.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let limit = limit.unwrap_or(50).clamp(1, MAX_LEDGER_PAGE);
+        let placeholders = vec!["?"; accounts.len()].join(", ");
+        let sql = format!(
+            r#"
+            SELECT id, account_id, CAST(entry_type AS CHAR) as entry_type,
+                   amount, balance_after,
+                   reference_type, reference_id, memo, created_at
+            FROM ledger_entries
+            WHERE account_id IN ({})
+            ORDER BY id DESC
+            LIMIT ?
+            "#,
+            placeholders
+        );
+
+        let mut query = sqlx::query_as::<_, LedgerEntry>(&sql);
+        for account in &accounts {
+            query = query.bind(&account.id);
+        }
+        let entries = query.bind(limit).fetch_all(&self.pool).await?;
+
+        Ok(entries
+            .into_iter()
+            .filter_map(|e| {
+                accounts
+                    .iter()
+                    .find(|a| a.id == e.account_id)
+                    .map(|a| (a.clone(), e))
+            })
+            .collect())
+    }
+
+    // =========================================================================
+    // LEDGER PRIMITIVES
+    // =========================================================================
+
+    /// Get (or lazily open) the account for an asset. Does not lock the row.
+    async fn account_id(
+        tx: &mut Transaction<'_, MySql>,
+        user_id: &str,
+        currency: &str,
+        network: &str,
+    ) -> Result<String, BalanceError> {
+        sqlx::query(
+            "INSERT IGNORE INTO balance_accounts (id, user_id, currency, network) VALUES (?, ?, ?, ?)",
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(user_id)
+        .bind(currency.to_lowercase())
+        .bind(network.to_lowercase())
+        .execute(&mut **tx)
+        .await?;
+
+        let row: (String,) = sqlx::query_as(
+            "SELECT id FROM balance_accounts WHERE user_id = ? AND currency = ? AND network = ?",
+        )
+        .bind(user_id)
+        .bind(currency.to_lowercase())
+        .bind(network.to_lowercase())
+        .fetch_one(&mut **tx)
+        .await?;
+
+        Ok(row.0)
+    }
+
+    /// Post a signed amount to an account inside a transaction.
+    /// Locks the account row, rejects overdrafts, and returns the new balance.
+    async fn post_entry(
+        tx: &mut Transaction<'_, MySql>,
+        account_id: &str,
+        entry_type: LedgerEntryType,
+        amount: Decimal,
+        reference_type: &str,
+        reference_id: &str,
+        memo: Option<&str>,
+    ) -> Result<Decimal, BalanceError> {
+        let (current,): (Decimal,) =
+            sqlx::query_as("SELECT balance FROM balance_accounts WHERE id = ? FOR UPDATE")
+                .bind(account_id)
+                .fetch_one(&mut **tx)
+                .await?;
+
+        if amount.is_sign_negative() && !has_sufficient_balance(current, -amount) {
+            return Err(BalanceError::InsufficientBalance { requested: -amount, available: current });
+        }
+
+        let balance_after = current + amount;
+
+        sqlx::query(
+            r#"
+            INSERT INTO ledger_entries (
+                account_id, entry_type, amount, balance_after,
+                reference_type, reference_id, memo
+            )
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(account_id)
+        .bind(entry_type.as_str())
+        .bind(amount)
+        .bind(balance_after)
+        .bind(reference_type)
+        .bind(reference_id)
+        .bind(memo)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| match &e {
+            sqlx::Error::Database(db) if db.is_unique_violation() => BalanceError::DuplicateEntry,
+            _ => BalanceError::from(e),
+        })?;
+
+        sqlx::query("UPDATE balance_accounts SET balance = ?, updated_at = NOW() WHERE id = ?")
+            .bind(balance_after)
+            .bind(account_id)
+            .execute(&mut **tx)
+            .await?;
+
+        Ok(balance_after)
+    }
+
+    // =========================================================================
+    // SWAP CREDITS
+    // =========================================================================
+
+    /// Net proceeds of a deposit-to-balance swap: what actually settled (the
+    /// amount our address received, or failing that the provider's reported
+    /// payout) less the platform fee, never the quoted estimate. `None` for
+    /// a swap that pays out to an address.
+    pub async fn swap_proceeds(&self, swap_id: &str) -> Result<Option<SwapProceeds>, BalanceError> {
+        let swap: Option<SwapProceedsRow> = sqlx::query_as(
+            r#"
+            SELECT s.user_id, s.to_currency, s.to_network,
+                   COALESCE(sa.actual_received, s.actual_receive), s.platform_fee
+            FROM swaps s
+            LEFT JOIN swap_address_info sa ON sa.swap_id = s.id
+            WHERE s.id = ? AND s.payout_mode = 'balance'
+            "#,
+        )
+        .bind(swap_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        let Some((user_id, currency, network, settled, platform_fee)) = swap else {
+            return Ok(None);
+        };
+        let user_id = user_id.ok_or(BalanceError::CustodyNotEnabled)?;
+        let settled = settled
+            .ok_or_else(|| BalanceError::InvalidAmount(format!("Swap {} has no settled amount yet", swap_id)))?;
+
+        Ok(Some(SwapProceeds { user_id, currency, network, amount: settled - platform_fee }))
+    }
+
+    /// Credit the proceeds of a deposit-to-balance swap to its owner.
+    /// Idempotent: returns `Ok(false)` if the swap was already credited.
+    pub async fn credit_swap_proceeds(&self, swap_id: &str) -> Result<bool, BalanceError> {
+        let SwapProceeds { user_id, currency, network, amount } = self
+            .swap_proceeds(swap_id)
+            .await?
+            .ok_or_else(|| BalanceError::DatabaseError(format!("Swap {} is not a balance swap", swap_id)))?;
+        validate_amount(amount)?;
+
+        let mut tx = self.pool.begin().await?;
+        let account_id = Self::account_id(&mut tx, &user_id, &currency, &network).await?;
+
+        match Self::post_entry(
+            &mut tx,
+            &account_id,
+            LedgerEntryType::SwapCredit,
+            amount,
+            "swap",
+            swap_id,
+            None,
+        )
+        .await
+        {
+            Ok(_) => {
+                tx.commit().await?;
+                Ok(true)
+            }
+            Err(BalanceError::DuplicateEntry) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    // =========================================================================
+    // INTERNAL TRANSFERS
+    // =========================================================================
+
+    pub async fn transfer(
+        &self,
+        sender_id: &str,
+        request: &TransferRequest,
+    ) -> Result<TransferResponse, BalanceError> {
+        let amount = request.amount.value();
+        validate_amount(amount)?;
+        self.require_custody(sender_id).await?;
+
+        let recipient: Option<(String, bool)> =
+            sqlx::query_as("SELECT id, custody_enabled FROM users WHERE email_hash = ? OR email = ? LIMIT 1")
+                .bind(email_index(&request.to_email))
+                .bind(&request.to_email)
+                .fetch_optional(&self.pool)
+                .await?;
+
+        let (recipient_id, recipient_custody) = recipient.ok_or(BalanceError::RecipientNotFound)?;
+        if recipient_id == sender_id {
+            return Err(BalanceError::SelfTransfer);
+        }
+        if !recipient_custody {
+            return Err(BalanceError::RecipientCustodyNotEnabled);
+        }
+
+        let transfer_id = Uuid::new_v4().to_string();
+        let memo = request.memo.as_deref();
+
+        let mut tx = self.pool.begin().await?;
+        let from_account = Self::account_id(&mut tx, sender_id, &request.currency, &request.network).await?;
+        let to_account = Self::account_id(&mut tx, &recipient_id, &request.currency, &request.network).await?;
+
+        // Lock accounts in a stable order so concurrent opposite transfers can't deadlock
+        let sender_balance = if from_account < to_account {
+            let balance = Self::post_entry(
+                &mut tx, &from_account, LedgerEntryType::TransferOut,
+                -amount, "transfer", &transfer_id, memo,
+            ).await?;
+            Self::post_entry(
+                &mut tx, &to_account, LedgerEntryType::TransferIn,
+                amount, "transfer", &transfer_id, memo,
+            ).await?;
+            balance
+        } else {
+            Self::post_entry(
+                &mut tx, &to_account, LedgerEntryType::TransferIn,
+                amount, "transfer", &transfer_id, memo,
+            ).await?;
+            Self::post_entry(
+                &mut tx, &from_account, LedgerEntryType::TransferOut,
+                -amount, "transfer", &transfer_id, memo,
+            ).await?
+        };
+
+        tx.commit().await?;
+
+        Ok(TransferResponse {
+            transfer_id,
+            currency: request.currency.to_lowercase(),
+            network: request.network.to_lowercase(),
+            amount,
+            balance_after: sender_balance,
+        })
+    }
+
+    // =========================================================================
+    // GIFT CARDS
+    // =========================================================================
+
+    /// Debit a gift card purchase from the buyer's balance. Returns the
+    /// balance left.
+    pub async fn debit_gift_card(
+        &self,
+        user_id: &str,
+        currency: &str,
+        network: &str,
+        amount: Decimal,
+        purchase_id: &str,
+    ) -> Result<Decimal, BalanceError> {
+        validate_amount(amount)?;
+        self.require_custody(user_id).await?;
+
+        let mut tx = self.pool.begin().await?;
+        let account_id = Self::account_id(&mut tx, user_id, currency, network).await?;
+        let balance = Self::post_entry(
+            &mut tx,
+            &account_id,
+            LedgerEntryType::GiftCardPurchase,
+            -amount,
+            "gift_card",
+            purchase_id,
+            None,
+        )
+        .await?;
+        tx.commit().await?;
+
+        Ok(balance)
+    }
+
+    /// Credit back the debit of a gift card purchase that was never paid.
+    /// Idempotent: returns `Ok(false)` if it was already refunded.
+    pub async fn refund_gift_card(
+        &self,
+        user_id: &str,
+        currency: &str,
+        network: &str,
+        amount: Decimal,
+        purchase_id: &str,
+    ) -> Result<bool, BalanceError> {
+        let mut tx = self.pool.begin().await?;
+        let account_id = Self::account_id(&mut tx, user_id, currency, network).await?;
+
+        match Self::post_entry(
+            &mut tx,
+            &account_id,
+            LedgerEntryType::GiftCardRefund,
+            amount,
+            "gift_card",
+            purchase_id,
+            None,
+        )
+        .await
+        {
+            Ok(_) => {
+                tx.commit().await?;
+                Ok(true)
+            }
+            Err(BalanceError::DuplicateEntry) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    // =========================================================================
+    // WITHDRAWALS
+    // =========================================================================
+
+    /// Place a hold on the balance and queue a withdrawal for admin approval
+    pub async fn request_withdrawal(
+        &self,
+        user_id: &str,
+        request: &CreateWithdrawalRequest,
+    ) -> Result<WithdrawalRequest, BalanceError> {
+        let amount = request.amount.value();
+        validate_amount(amount)?;
+        if request.address.trim().is_empty() {
+            return Err(BalanceError::InvalidAddress);
+        }
+        self.require_custody(user_id).await?;
+
+        let withdrawal_id = Uuid::new_v4().to_string();
+
+        let mut tx = self.pool.begin().await?;
+        let account_id = Self::account_id(&mut tx, user_id, &request.currency, &request.network).await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO withdrawal_requests (
+                id, user_id, account_id, currency, network, amount,
+                destination_address, destination_extra_id, status
+            )
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, 'pending_approval')
+            "#,
+        )
+        .bind(&withdrawal_id)
+        .bind(user_id)
+        .bind(&account_id)
+        .bind(request.currency.to_lowercase())
+        .bind(request.network.to_lowercase())
+        .bind(amount)
+        .bind(request.address.trim())
+        .bind(&request.extra_id)
+        .execute(&mut *tx)
+        .await?;
+
+        Self::post_entry(
+            &mut tx,
+            &account_id,
+            LedgerEntryType::WithdrawalHold,
+            -amount,
+            "withdrawal",
+            &withdrawal_id,
+            None,
+        )
+        .await?;
+
+        tx.commit().await?;
+
+        self.get_withdrawal(&withdrawal_id)
+            .await?
+            .ok_or(BalanceError::WithdrawalNotFound)
+    }
+
+    pub async fn get_withdrawal(&self, withdrawal_id: &str) -> Result<Option<WithdrawalRequest>, BalanceError> {
+        let withdrawal = sqlx::query_as::<_, WithdrawalRequest>(
+            r#"
+            SELECT id, user_id, account_id, currency, network, amount,
+                   destination_address, destination_extra_id, CAST(status AS CHAR) as status,
+                   reviewed_by, reviewed_at, rejection_reason, tx_hash, error,
+                   created_at, updated_at
+            FROM withdrawal_requests
+            WHERE id = ?
+            "#,
+        )
+        .bind(withdrawal_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(withdrawal)
+    }
+
+    pub async fn list_withdrawals(&self, user_id: &str) -> Result<Vec<WithdrawalRequest>, BalanceError> {
+        let withdrawals = sqlx::query_as::<_, WithdrawalRequest>(
+            r#"
+            SELECT id, user_id, account_id, currency, network, amount,
+                   destination_address, destination_extra_id, CAST(status AS CHAR) as status,
+                   reviewed_by, reviewed_at, rejection_reason, tx_hash, error,
+                   created_at, updated_at
+            FROM withdrawal_requests
+            WHERE user_id = ?
+            ORDER BY created_at DESC
+            LIMIT 100
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(withdrawals)
+    }
+
+    /// Approved withdrawals waiting for the payout pipeline
+    pub async fn get_approved_withdrawals(&self, limit: i64) -> Result<Vec<WithdrawalRequest>, BalanceError> {
+        let withdrawals = sqlx::query_as::<_, WithdrawalRequest>(
+            r#"
+            SELECT id, user_id, account_id, currency, network, amount,
+                   destination_address, destination_extra_id, CAST(status AS CHAR) as status,
+                   reviewed_by, reviewed_at, rejection_reason, tx_hash, error,
+                   created_at, updated_at
+            FROM withdrawal_requests
+            WHERE status = 'approved'
+            ORDER BY reviewed_at ASC
+            LIMIT ?
+            "#,
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(withdrawals)
+    }
+
+    async fn transition_withdrawal(
+        &self,
+        withdrawal_id: &str,
+        from: &str,
+        to: &str,
+    ) -> Result<(), BalanceError> {
+        let result = sqlx::query(
+            "UPDATE withdrawal_requests SET status = ?, updated_at = NOW() WHERE id = ? AND status = ?",
+        )
+        .bind(to)
+        .bind(withdrawal_id)
+        .bind(from)
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            let current = self.get_withdrawal(withdrawal_id).await?
+                .ok_or(BalanceError::WithdrawalNotFound)?;
+            return Err(BalanceError::InvalidWithdrawalState(current.status));
+        }
+        Ok(())
+    }
+
+    pub async fn approve_withdrawal(
+        &self,
+        admin_id: &str,
+        withdrawal_id: &str,
+    ) -> Result<WithdrawalRequest, BalanceError> {
+        self.transition_withdrawal(withdrawal_id, "pending_approval", "approved").await?;
+
+        sqlx::query("UPDATE withdrawal_requests SET reviewed_by = ?, reviewed_at = NOW() WHERE id = ?")
+            .bind(admin_id)
+            .bind(withdrawal_id)
+            .execute(&self.pool)
+            .await?;
+
+        self.get_withdrawal(withdrawal_id).await?.ok_or(BalanceError::WithdrawalNotFound)
+    }
+
+    /// Reject a pending withdrawal and release the held funds
+    pub async fn reject_withdrawal(
+        &self,
+        admin_id: &str,
+        withdrawal_id: &str,
+        reason: Option<&str>,
+    ) -> Result<WithdrawalRequest, BalanceError> {
+        self.transition_withdrawal(withdrawal_id, "pending_approval", "rejected").await?;
+
+        sqlx::query(
+            "UPDATE withdrawal_requests SET reviewed_by = ?, reviewed_at = NOW(), rejection_reason = ? WHERE id = ?",
+        )
+        .bind(admin_id)
+        .bind(reason)
+        .bind(withdrawal_id)
+        .execute(&self.pool)
+        .await?;
+
+        self.release_hold(withdrawal_id).await?;
+        self.get_withdrawal(withdrawal_id).await?.ok_or(BalanceError::WithdrawalNotFound)
+    }
+
+    /// Claim an approved withdrawal for execution (approved -> processing)
+    pub async fn claim_withdrawal(&self, withdrawal_id: &str) -> Result<(), BalanceError> {
+        self.transition_withdrawal(withdrawal_id, "approved", "processing").await
+    }
+
+    pub async fn complete_withdrawal(&self, withdrawal_id: &str, tx_hash: &str) -> Result<(), BalanceError> {
+        sqlx::query(
+            "UPDATE withdrawal_requests SET status = 'completed', tx_hash = ?, updated_at = NOW() WHERE id = ? AND status = 'processing'",
+        )
+        .bind(tx_hash)
+        .bind(withdrawal_id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Mark a withdrawal failed and return the held funds to the balance
+    pub async fn fail_withdrawal(&self, withdrawal_id: &str, error: &str) -> Result<(), BalanceError> {
+        self.transition_withdrawal(withdrawal_id, "processing", "failed").await?;
+
+        sqlx::query("UPDATE withdrawal_requests SET error = ? WHERE id = ?")
+            .bind(error)
+            .bind(withdrawal_id)
+            .execute(&self.pool)
+            .await?;
+
+        self.release_hold(withdrawal_id).await
+    }
+
+    async fn release_hold(&self, withdrawal_id: &str) -> Result<(), BalanceError> {
+        let withdrawal = self.get_withdrawal(withdrawal_id).await?
+            .ok_or(BalanceError::WithdrawalNotFound)?;
+
+        let mut tx = self.pool.begin().await?;
+        match Self::post_entry(
+            &mut tx,
+            &withdrawal.account_id,
+            LedgerEntryType::WithdrawalRelease,
+            withdrawal.amount,
+            "withdrawal",
+            withdrawal_id,
+            None,
+        )
+        .await
+        {
+            Ok(_) => {
+                tx.commit().await?;
+                Ok(())
+            }
+            Err(BalanceError::DuplicateEntry) => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+}
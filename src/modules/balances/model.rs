@@ -0,0 +1,116 @@
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+use crate::services::amount::Decimal;
+
+// =============================================================================
+// BALANCE ACCOUNT
+// =============================================================================
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct BalanceAccount {
+    pub id: String,
+    pub user_id: String,
+    pub currency: String,
+    pub network: String,
+    pub balance: Decimal,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+// =============================================================================
+// LEDGER ENTRY
+// =============================================================================
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct LedgerEntry {
+    pub id: i64,
+    pub account_id: String,
+    pub entry_type: LedgerEntryType,
+    /// Signed: credits are positive, debits negative
+    pub amount: Decimal,
+    pub balance_after: Decimal,
+    pub reference_type: String,
+    pub reference_id: String,
+    pub memo: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, sqlx::Type, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case")]
+pub enum LedgerEntryType {
+    SwapCredit,
+    TransferIn,
+    TransferOut,
+    WithdrawalHold,
+    WithdrawalRelease,
+    GiftCardPurchase,
+    GiftCardRefund,
+}
+
+impl LedgerEntryType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LedgerEntryType::SwapCredit => "swap_credit",
+            LedgerEntryType::TransferIn => "transfer_in",
+            LedgerEntryType::TransferOut => "transfer_out",
+            LedgerEntryType::WithdrawalHold => "withdrawal_hold",
+            LedgerEntryType::WithdrawalRelease => "withdrawal_release",
+            LedgerEntryType::GiftCardPurchase => "gift_card_purchase",
+            LedgerEntryType::GiftCardRefund => "gift_card_refund",
+        }
+    }
+}
+
+// =============================================================================
+// WITHDRAWAL REQUEST
+// =============================================================================
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct WithdrawalRequest {
+    pub id: String,
+    pub user_id: String,
+    pub account_id: String,
+    pub currency: String,
+    pub network: String,
+    pub amount: Decimal,
+    pub destination_address: String,
+    pub destination_extra_id: Option<String>,
+    pub status: WithdrawalStatus,
+    pub reviewed_by: Option<String>,
+    pub reviewed_at: Option<DateTime<Utc>>,
+    pub rejection_reason: Option<String>,
+    pub tx_hash: Option<String>,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, sqlx::Type, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case")]
+pub enum WithdrawalStatus {
+    PendingApproval,
+    Approved,
+    Rejected,
+    Processing,
+    Completed,
+    Failed,
+}
+
+// =============================================================================
+// SWAP PROCEEDS
+// =============================================================================
+
+/// What a deposit-to-balance swap credits its owner
+#[derive(Debug, Clone)]
+pub struct SwapProceeds {
+    pub user_id: String,
+    pub currency: String,
+    pub network: String,
+    /// Settled amount less the platform fee
+    pub amount: Decimal,
+}
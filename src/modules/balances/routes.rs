@@ -0,0 +1,18 @@
+use axum::Router;
+use std::sync::Arc;
+
+use crate::AppState;
+use crate::manifest::DeclaredRoutes;
+use super::controller::{
+    create_transfer, create_withdrawal, get_balances, get_ledger, list_withdrawals, opt_in,
+};
+
+pub fn balance_routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .declared("getBalances", get_balances)
+        .declared("optInToCustody", opt_in)
+        .declared("getLedger", get_ledger)
+        .declared("createTransfer", create_transfer)
+        .declared("createWithdrawal", create_withdrawal)
+        .declared("listWithdrawals", list_withdrawals)
+}
@@ -0,0 +1,148 @@
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::services::amount::{Decimal, WireAmount};
+use super::model::{LedgerEntryType, WithdrawalStatus};
+
+// =============================================================================
+// BALANCES
+// =============================================================================
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct BalanceResponse {
+    pub currency: String,
+    pub network: String,
+    #[serde(with = "rust_decimal::serde::float")]
+    #[schemars(with = "f64")]
+    pub balance: Decimal,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct BalancesResponse {
+    pub custody_enabled: bool,
+    pub balances: Vec<BalanceResponse>,
+}
+
+// =============================================================================
+// LEDGER
+// =============================================================================
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct LedgerQuery {
+    pub currency: Option<String>,
+    pub network: Option<String>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct LedgerEntryResponse {
+    pub id: i64,
+    pub currency: String,
+    pub network: String,
+    pub entry_type: LedgerEntryType,
+    #[serde(with = "rust_decimal::serde::float")]
+    #[schemars(with = "f64")]
+    pub amount: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    #[schemars(with = "f64")]
+    pub balance_after: Decimal,
+    pub reference_type: String,
+    pub reference_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct LedgerResponse {
+    pub entries: Vec<LedgerEntryResponse>,
+}
+
+// =============================================================================
+// TRANSFERS
+// =============================================================================
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct TransferRequest {
+    /// Email of the receiving account
+    pub to_email: String,
+    pub currency: String,
+    pub network: String,
+    pub amount: WireAmount,
+    #[serde(default)]
+    pub memo: Option<String>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct TransferResponse {
+    pub transfer_id: String,
+    pub currency: String,
+    pub network: String,
+    #[serde(with = "rust_decimal::serde::float")]
+    #[schemars(with = "f64")]
+    pub amount: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    #[schemars(with = "f64")]
+    pub balance_after: Decimal,
+}
+
+// =============================================================================
+// WITHDRAWALS
+// =============================================================================
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CreateWithdrawalRequest {
+    pub currency: String,
+    pub network: String,
+    pub amount: WireAmount,
+    pub address: String,
+    #[serde(default)]
+    pub extra_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct RejectWithdrawalRequest {
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct WithdrawalResponse {
+    pub id: String,
+    pub currency: String,
+    pub network: String,
+    #[serde(with = "rust_decimal::serde::float")]
+    #[schemars(with = "f64")]
+    pub amount: Decimal,
+    pub address: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extra_id: Option<String>,
+    pub status: WithdrawalStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tx_hash: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rejection_reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct WithdrawalsResponse {
+    pub withdrawals: Vec<WithdrawalResponse>,
+}
+
+// =============================================================================
+// ERROR RESPONSE
+// =============================================================================
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct BalanceErrorResponse {
+    pub error: String,
+}
+
+impl BalanceErrorResponse {
+    pub fn new(error: impl Into<String>) -> Self {
+        Self { error: error.into() }
+    }
+}
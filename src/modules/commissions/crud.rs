@@ -0,0 +1,501 @@
+use axum::http::StatusCode;
+use chrono::{DateTime, NaiveDate, Utc};
+use sqlx::{MySql, Pool};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, OnceLock, RwLock};
+use std::time::{Duration, Instant};
+use rust_decimal::prelude::ToPrimitive;
+
+use super::model::{ProviderCommission, RevenueEntry, RevenueEntryType};
+use crate::modules::swap::crud::SwapCrud;
+use crate::services::amount::{self, Decimal};
+use crate::services::fx::FxRateTable;
+
+/// Every rate quote looks commissions up, so they are cached per process.
+/// Admin changes invalidate it locally; other instances pick them up within
+/// this window.
+const COMMISSION_CACHE_TTL: Duration = Duration::from_secs(30);
+
+const COMMISSION_COLUMNS: &str =
+    "provider_id, commission_rate, revenue_share_rate, updated_by, created_at, updated_at";
+
+const REVENUE_COLUMNS: &str = r#"
+    id, swap_id, provider_id, CAST(entry_type AS CHAR) as entry_type, currency, network,
+    amount, rate, created_at
+"#;
+
+/// Provider, pair, deposit and platform fee of a swap whose revenue is recorded
+type SwapRevenueRow = (String, String, String, String, String, Decimal, Decimal);
+
+/// Overrides and when they were read
+type CommissionCache = RwLock<Option<(Instant, Arc<CommissionOverrides>)>>;
+
+static COMMISSION_CACHE: OnceLock<CommissionCache> = OnceLock::new();
+
+fn commission_cache() -> &'static CommissionCache {
+    COMMISSION_CACHE.get_or_init(|| RwLock::new(None))
+}
+
+/// Drop the cached overrides so the next quote reads the table
+pub fn invalidate_commission_cache() {
+    if let Ok(mut cache) = commission_cache().write() {
+        *cache = None;
+    }
+}
+
+// =============================================================================
+// COMMISSION ERROR
+// =============================================================================
+
+#[derive(Debug)]
+pub enum CommissionError {
+    ProviderNotFound,
+    CommissionNotFound,
+    DatabaseError(String),
+}
+
+impl std::fmt::Display for CommissionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CommissionError::ProviderNotFound => write!(f, "Provider not found"),
+            CommissionError::CommissionNotFound => write!(f, "Provider has no commission override"),
+            CommissionError::DatabaseError(e) => write!(f, "Database error: {}", e),
+        }
+    }
+}
+
+impl CommissionError {
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            CommissionError::ProviderNotFound | CommissionError::CommissionNotFound => StatusCode::NOT_FOUND,
+            CommissionError::DatabaseError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+impl From<sqlx::Error> for CommissionError {
+    fn from(err: sqlx::Error) -> Self {
+        CommissionError::DatabaseError(err.to_string())
+    }
+}
+
+// =============================================================================
+// COMMISSION OVERRIDES
+// =============================================================================
+
+/// Provider terms keyed by provider id. Quotes name providers the way
+/// Trocador does ("ChangeNOW"), so lookups normalize to the id first.
+#[derive(Debug, Default)]
+pub struct CommissionOverrides {
+    by_provider: HashMap<String, ProviderCommission>,
+}
+
+impl CommissionOverrides {
+    pub fn new(commissions: Vec<ProviderCommission>) -> Self {
+        Self {
+            by_provider: commissions.into_iter().map(|c| (c.provider_id.clone(), c)).collect(),
+        }
+    }
+
+    pub fn get(&self, provider: &str) -> Option<&ProviderCommission> {
+        self.by_provider.get(&SwapCrud::normalize_provider_id(provider))
+    }
+
+    /// The commission rate to charge on this provider's quotes, when it
+    /// overrides the strategy
+    pub fn commission_rate(&self, provider: &str) -> Option<f64> {
+        self.get(provider).and_then(|c| c.commission_rate)
+    }
+
+    pub fn revenue_share_rate(&self, provider: &str) -> f64 {
+        self.get(provider).map(|c| c.revenue_share_rate).unwrap_or(0.0)
+    }
+}
+
+// =============================================================================
+// REVENUE TOTALS
+// =============================================================================
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct RevenueTotal {
+    pub provider_id: String,
+    pub entry_type: RevenueEntryType,
+    pub currency: String,
+    pub network: String,
+    pub total: Decimal,
+    pub entries: i64,
+}
+
+/// A revenue total with its value in a reporting currency; None when a day
+/// it covers has no FX rate
+#[derive(Debug, Clone)]
+pub struct ValuedRevenueTotal {
+    pub total: RevenueTotal,
+    pub value: Option<Decimal>,
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+struct DailyRevenueTotal {
+    day: NaiveDate,
+    #[sqlx(flatten)]
+    total: RevenueTotal,
+}
+
+// =============================================================================
+// COMMISSION CRUD
+// =============================================================================
+
+pub struct CommissionCrud {
+    pool: Pool<MySql>,
+}
+
+impl CommissionCrud {
+    pub fn new(pool: Pool<MySql>) -> Self {
+        Self { pool }
+    }
+
+    pub async fn list_commissions(&self) -> Result<Vec<ProviderCommission>, CommissionError> {
+        let commissions = sqlx::query_as::<_, ProviderCommission>(&format!(
+            "SELECT {} FROM provider_commissions ORDER BY provider_id",
+            COMMISSION_COLUMNS
+        ))
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(commissions)
+    }
+
+    /// All overrides, served from the process cache when fresh
+    pub async fn overrides(&self) -> Result<Arc<CommissionOverrides>, CommissionError> {
+        if let Ok(cache) = commission_cache().read() {
+            if let Some((loaded_at, overrides)) = cache.as_ref() {
+                if loaded_at.elapsed() < COMMISSION_CACHE_TTL {
+                    return Ok(overrides.clone());
+                }
+            }
+        }
+
+        let overrides = Arc::new(CommissionOverrides::new(self.list_commissions().await?));
+        if let Ok(mut cache) = commission_cache().write() {
+            *cache = Some((Instant::now(), overrides.clone()));
+        }
+        Ok(overrides)
+    }
+
+    /// Create or replace a provider's terms
+    pub async fn set_commission(
+        &self,
+        admin_id: &str,
+        provider_id: &str,
+        commission_rate: Option<f64>,
+        revenue_share_rate: f64,
+    ) -> Result<ProviderCommission, CommissionError> {
+        let provider_id = SwapCrud::normalize_provider_id(provider_id);
+
+        let exists: Option<(String,)> = sqlx::query_as("SELECT id FROM providers WHERE id = ?")
+            .bind(&provider_id)
+            .fetch_optional(&self.pool)
+            .await?;
+        if exists.is_none() {
+            return Err(CommissionError::ProviderNotFound);
+        }
+
+        sqlx::query(
+            r#"
+            INSERT INTO provider_commissions (provider_id, commission_rate, revenue_share_rate, updated_by)
+            VALUES (?, ?, ?, ?)
+            ON DUPLICATE KEY UPDATE
+                commission_rate = VALUES(commission_rate),
+                revenue_share_rate = VALUES(revenue_share_rate),
+                updated_by = VALUES(updated_by)
+            "#,
+        )
+        .bind(&provider_id)
+        .bind(commission_rate)
+        .bind(revenue_share_rate)
+        .bind(admin_id)
+        .execute(&self.pool)
+        .await?;
+        invalidate_commission_cache();
+
+        self.get_commission(&provider_id).await?.ok_or(CommissionError::CommissionNotFound)
+    }
+
+    /// Back to the strategy's rate and no revenue share
+    pub async fn remove_commission(&self, provider_id: &str) -> Result<(), CommissionError> {
+        let result = sqlx::query("DELETE FROM provider_commissions WHERE provider_id = ?")
+            .bind(SwapCrud::normalize_provider_id(provider_id))
+            .execute(&self.pool)
+            .await?;
+        invalidate_commission_cache();
+
+        if result.rows_affected() == 0 {
+            return Err(CommissionError::CommissionNotFound);
+        }
+        Ok(())
+    }
+
+    pub async fn get_commission(&self, provider_id: &str) -> Result<Option<ProviderCommission>, CommissionError> {
+        let commission = sqlx::query_as::<_, ProviderCommission>(&format!(
+            "SELECT {} FROM provider_commissions WHERE provider_id = ?",
+            COMMISSION_COLUMNS
+        ))
+        .bind(provider_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(commission)
+    }
+
+    /// Write what a completed swap earned: our platform fee and, when the
+    /// provider shares revenue, its referral payment at the current rate.
+    /// A fee waived by a campaign is written alongside as foregone revenue.
+    /// Safe to call more than once per swap. Returns the entries written.
+    pub async fn record_swap_revenue(&self, swap_id: &str) -> Result<u64, CommissionError> {
+        let swap: Option<SwapRevenueRow> = sqlx::query_as(
+            r#"
+            SELECT provider_id, from_currency, from_network, to_currency, to_network, amount, platform_fee
+            FROM swaps WHERE id = ?
+            "#,
+        )
+        .bind(swap_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        let Some((provider_id, from, network_from, to, network_to, deposit, platform_fee)) = swap else {
+            return Ok(0);
+        };
+
+        let commission = self.get_commission(&provider_id).await?;
+        let share_rate = commission.as_ref().map(|c| c.revenue_share_rate).unwrap_or(0.0);
+        let commission_rate = if deposit > Decimal::ZERO { (platform_fee / deposit).to_f64().unwrap_or(0.0) } else { 0.0 };
+
+        let mut written = 0;
+        if platform_fee > Decimal::ZERO {
+            written += self
+                .insert_entry(swap_id, &provider_id, RevenueEntryType::PlatformFee, (&to, &network_to), platform_fee, commission_rate)
+                .await?;
+        }
+        if share_rate > 0.0 {
+            let share = deposit * amount::from_f64(share_rate).unwrap_or_default();
+            written += self
+                .insert_entry(swap_id, &provider_id, RevenueEntryType::RevenueShare, (&from, &network_from), share, share_rate)
+                .await?;
+        }
+
+        let discount: Option<(Decimal, Decimal, f64)> = sqlx::query_as(
+            r#"
+            SELECT r.standard_fee, r.charged_fee, p.fee_discount
+            FROM promotion_redemptions r
+            JOIN promotions p ON p.id = r.promotion_id
+            WHERE r.swap_id = ?
+            "#,
+        )
+        .bind(swap_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        if let Some((standard_fee, charged_fee, fee_discount)) = discount {
+            let foregone = standard_fee - charged_fee;
+            if foregone > Decimal::ZERO {
+                written += self
+                    .insert_entry(swap_id, &provider_id, RevenueEntryType::PromotionDiscount, (&to, &network_to), foregone, fee_discount)
+                    .await?;
+            }
+        }
+
+        Ok(written)
+    }
+
+    /// Book native coin spent on a swap's behalf. Repeated top-ups for the
+    /// same swap add up.
+    pub async fn record_gas_cost(
+        &self,
+        swap_id: &str,
+        (currency, network): (&str, &str),
+        amount: Decimal,
+    ) -> Result<(), CommissionError> {
+        sqlx::query(
+            r#"
+            INSERT INTO revenue_entries (swap_id, provider_id, entry_type, currency, network, amount, rate)
+            SELECT id, provider_id, 'gas_cost', ?, ?, ?, 0 FROM swaps WHERE id = ?
+            ON DUPLICATE KEY UPDATE amount = amount + VALUES(amount)
+            "#,
+        )
+        .bind(currency.to_lowercase())
+        .bind(network.to_lowercase())
+        .bind(amount)
+        .bind(swap_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn insert_entry(
+        &self,
+        swap_id: &str,
+        provider_id: &str,
+        entry_type: RevenueEntryType,
+        (currency, network): (&str, &str),
+        amount: Decimal,
+        rate: f64,
+    ) -> Result<u64, CommissionError> {
+        let result = sqlx::query(
+            r#"
+            INSERT IGNORE INTO revenue_entries (swap_id, provider_id, entry_type, currency, network, amount, rate)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(swap_id)
+        .bind(provider_id)
+        .bind(entry_type.as_str())
+        .bind(currency.to_lowercase())
+        .bind(network.to_lowercase())
+        .bind(amount)
+        .bind(rate)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    pub async fn revenue_entries(&self, swap_id: &str) -> Result<Vec<RevenueEntry>, CommissionError> {
+        let entries = sqlx::query_as::<_, RevenueEntry>(&format!(
+            "SELECT {} FROM revenue_entries WHERE swap_id = ? ORDER BY id",
+            REVENUE_COLUMNS
+        ))
+        .bind(swap_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(entries)
+    }
+
+    /// Revenue per provider, entry type and asset over a window
+    pub async fn revenue_totals(
+        &self,
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+    ) -> Result<Vec<RevenueTotal>, CommissionError> {
+        let totals = sqlx::query_as::<_, RevenueTotal>(
+            r#"
+            SELECT provider_id, CAST(entry_type AS CHAR) as entry_type, currency, network,
+                   SUM(amount) as total, COUNT(*) as entries
+            FROM revenue_entries
+            WHERE created_at >= ? AND created_at < ?
+            GROUP BY provider_id, entry_type, currency, network
+            ORDER BY provider_id, entry_type, currency, network
+            "#,
+        )
+        .bind(since)
+        .bind(until)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(totals)
+    }
+
+    /// Revenue totals like [`Self::revenue_totals`], each also valued in
+    /// `rates`' currency at the rate of the day it was earned
+    pub async fn valued_revenue_totals(
+        &self,
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+        rates: &FxRateTable,
+    ) -> Result<Vec<ValuedRevenueTotal>, CommissionError> {
+        let days = sqlx::query_as::<_, DailyRevenueTotal>(
+            r#"
+            SELECT DATE(created_at) as day, provider_id, CAST(entry_type AS CHAR) as entry_type, currency, network,
+                   SUM(amount) as total, COUNT(*) as entries
+            FROM revenue_entries
+            WHERE created_at >= ? AND created_at < ?
+            GROUP BY day, provider_id, entry_type, currency, network
+            "#,
+        )
+        .bind(since)
+        .bind(until)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(value_daily_totals(days, rates))
+    }
+}
+
+/// Fold per-day totals into one per provider, entry type and asset
+fn value_daily_totals(days: Vec<DailyRevenueTotal>, rates: &FxRateTable) -> Vec<ValuedRevenueTotal> {
+    let mut totals: BTreeMap<(String, &'static str, String, String), ValuedRevenueTotal> = BTreeMap::new();
+    for DailyRevenueTotal { day, total } in days {
+        let value = rates.value(total.total, &total.currency, day);
+        let key = (total.provider_id.clone(), total.entry_type.as_str(), total.currency.clone(), total.network.clone());
+        match totals.get_mut(&key) {
+            Some(valued) => {
+                valued.total.total += total.total;
+                valued.total.entries += total.entries;
+                valued.value = valued.value.zip(value).map(|(a, b)| a + b);
+            }
+            None => {
+                totals.insert(key, ValuedRevenueTotal { total, value });
+            }
+        }
+    }
+    totals.into_values().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commission(provider_id: &str, commission_rate: Option<f64>, revenue_share_rate: f64) -> ProviderCommission {
+        ProviderCommission {
+            provider_id: provider_id.to_string(),
+            commission_rate,
+            revenue_share_rate,
+            updated_by: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_overrides_match_trocador_names() {
+        let overrides = CommissionOverrides::new(vec![
+            commission("changenow", Some(0.003), 0.002),
+            commission("fixedfloat", None, 0.001),
+        ]);
+
+        assert_eq!(overrides.commission_rate("ChangeNOW"), Some(0.003));
+        assert_eq!(overrides.revenue_share_rate("Change-NOW"), 0.002);
+        // Revenue share only; the strategy still sets the commission
+        assert_eq!(overrides.commission_rate("FixedFloat"), None);
+        assert_eq!(overrides.revenue_share_rate("FixedFloat"), 0.001);
+        assert!(overrides.get("Exolix").is_none());
+        assert_eq!(overrides.revenue_share_rate("Exolix"), 0.0);
+    }
+
+    #[test]
+    fn test_daily_totals_are_valued_at_each_days_rate() {
+        let date = |d: u32| NaiveDate::from_ymd_opt(2026, 3, d).unwrap();
+        let day = |d: u32, amount: Decimal| DailyRevenueTotal {
+            day: date(d),
+            total: RevenueTotal {
+                provider_id: "changenow".to_string(),
+                entry_type: RevenueEntryType::PlatformFee,
+                currency: "usdt".to_string(),
+                network: "ethereum".to_string(),
+                total: amount,
+                entries: 1,
+            },
+        };
+        let rates = FxRateTable::new("EUR", [(date(2), Decimal::new(90, 2)), (date(3), Decimal::new(80, 2))])
+            .with_prices([("usdt".to_string(), date(1), Decimal::ONE)]);
+
+        let totals = value_daily_totals(vec![day(2, Decimal::from(10)), day(3, Decimal::from(10))], &rates);
+        assert_eq!(totals.len(), 1);
+        assert_eq!(totals[0].total.total, Decimal::from(20));
+        assert_eq!(totals[0].total.entries, 2);
+        assert_eq!(totals[0].value, Some(Decimal::from(17)));
+
+        // A day before the first rate snapshot leaves the value unknown
+        let totals = value_daily_totals(vec![day(1, Decimal::from(10)), day(2, Decimal::from(10))], &rates);
+        assert_eq!(totals[0].value, None);
+    }
+}
@@ -0,0 +1,67 @@
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+use crate::services::amount::Decimal;
+
+// =============================================================================
+// PROVIDER COMMISSION
+// =============================================================================
+
+/// Negotiated terms with one provider
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct ProviderCommission {
+    pub provider_id: String,
+    /// Replaces the pricing strategy's tier rate for this provider's quotes
+    pub commission_rate: Option<f64>,
+    /// Fraction of each completed swap's deposit the provider pays us
+    pub revenue_share_rate: f64,
+    pub updated_by: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+// =============================================================================
+// REVENUE ENTRY
+// =============================================================================
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct RevenueEntry {
+    pub id: i64,
+    pub swap_id: String,
+    pub provider_id: String,
+    pub entry_type: RevenueEntryType,
+    pub currency: String,
+    pub network: String,
+    pub amount: Decimal,
+    pub rate: f64,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, sqlx::Type, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case")]
+pub enum RevenueEntryType {
+    /// Our markup, in the destination asset
+    PlatformFee,
+    /// The provider's referral payment, in the deposit asset
+    RevenueShare,
+    /// Native coin the gas station spent so the swap's address could pay
+    /// for a token transfer; a cost, not income
+    GasCost,
+    /// Platform fee waived by a promotional campaign, in the destination
+    /// asset; revenue foregone, not income
+    PromotionDiscount,
+}
+
+impl RevenueEntryType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RevenueEntryType::PlatformFee => "platform_fee",
+            RevenueEntryType::RevenueShare => "revenue_share",
+            RevenueEntryType::GasCost => "gas_cost",
+            RevenueEntryType::PromotionDiscount => "promotion_discount",
+        }
+    }
+}
@@ -0,0 +1,144 @@
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use super::crud::ValuedRevenueTotal;
+use super::model::{ProviderCommission, RevenueEntryType};
+use crate::services::amount::Decimal;
+use crate::services::fx::format_value;
+
+// =============================================================================
+// REQUESTS
+// =============================================================================
+
+#[derive(Debug, Deserialize, Validate, JsonSchema)]
+pub struct SetProviderCommissionRequest {
+    /// Omit to keep the pricing strategy's tier rate
+    #[validate(range(min = 0.0, max = 0.1))]
+    pub commission_rate: Option<f64>,
+    #[serde(default)]
+    #[validate(range(min = 0.0, max = 1.0))]
+    pub revenue_share_rate: f64,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct RevenueQuery {
+    /// Defaults to 30 days before `to`
+    pub from: Option<DateTime<Utc>>,
+    /// Defaults to now
+    pub to: Option<DateTime<Utc>>,
+    /// ISO 4217 currency to value revenue in; defaults to REPORTING_CURRENCY
+    pub currency: Option<String>,
+}
+
+// =============================================================================
+// RESPONSES
+// =============================================================================
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ProviderCommissionResponse {
+    pub provider_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commission_rate: Option<f64>,
+    pub revenue_share_rate: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub updated_by: Option<String>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<ProviderCommission> for ProviderCommissionResponse {
+    fn from(c: ProviderCommission) -> Self {
+        Self {
+            provider_id: c.provider_id,
+            commission_rate: c.commission_rate,
+            revenue_share_rate: c.revenue_share_rate,
+            updated_by: c.updated_by,
+            updated_at: c.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ProviderCommissionsResponse {
+    pub commissions: Vec<ProviderCommissionResponse>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct RevenueTotalResponse {
+    pub provider_id: String,
+    pub entry_type: RevenueEntryType,
+    pub currency: String,
+    pub network: String,
+    /// Exact decimal string
+    pub total: String,
+    pub entries: i64,
+    /// In the reporting currency at each day's rate; absent when a day has
+    /// no FX rate yet
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+}
+
+impl From<ValuedRevenueTotal> for RevenueTotalResponse {
+    fn from(v: ValuedRevenueTotal) -> Self {
+        let t = v.total;
+        Self {
+            provider_id: t.provider_id,
+            entry_type: t.entry_type,
+            currency: t.currency,
+            network: t.network,
+            total: t.total.normalize().to_string(),
+            entries: t.entries,
+            value: v.value.map(format_value),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct RevenueSummaryResponse {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    /// ISO 4217 currency of every `value`
+    pub reporting_currency: String,
+    /// Platform fees and revenue shares less gas costs, in the reporting
+    /// currency; absent when any total could not be valued
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub net_value: Option<String>,
+    pub totals: Vec<RevenueTotalResponse>,
+}
+
+impl RevenueSummaryResponse {
+    pub fn new(from: DateTime<Utc>, to: DateTime<Utc>, reporting_currency: &str, totals: Vec<ValuedRevenueTotal>) -> Self {
+        let net_value = totals
+            .iter()
+            .map(|t| {
+                let sign = match t.total.entry_type {
+                    RevenueEntryType::PlatformFee | RevenueEntryType::RevenueShare => Decimal::ONE,
+                    RevenueEntryType::GasCost => Decimal::NEGATIVE_ONE,
+                    // Already missing from the platform fees; not a cost on top
+                    RevenueEntryType::PromotionDiscount => Decimal::ZERO,
+                };
+                t.value.map(|v| v * sign)
+            })
+            .sum::<Option<Decimal>>();
+
+        Self {
+            from,
+            to,
+            reporting_currency: reporting_currency.to_string(),
+            net_value: net_value.map(format_value),
+            totals: totals.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct CommissionErrorResponse {
+    pub error: String,
+}
+
+impl CommissionErrorResponse {
+    pub fn new(error: impl Into<String>) -> Self {
+        Self { error: error.into() }
+    }
+}
@@ -0,0 +1,52 @@
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    Json,
+};
+use std::sync::Arc;
+
+use crate::AppState;
+use crate::services::email::events::parse_events;
+use super::crud::{EmailCrud, EmailError};
+use super::schema::{EmailErrorResponse, EmailEventsQuery, EmailEventsResponse};
+
+fn to_error(e: EmailError) -> (StatusCode, Json<EmailErrorResponse>) {
+    (e.status_code(), Json(EmailErrorResponse::new(e.to_string())))
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+// =============================================================================
+// POST /email/events - Bounce and complaint webhook from the mail provider
+// =============================================================================
+
+pub async fn ingest_events(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<EmailEventsQuery>,
+    Json(payload): Json<serde_json::Value>,
+) -> Result<Json<EmailEventsResponse>, (StatusCode, Json<EmailErrorResponse>)> {
+    let expected = std::env::var("EMAIL_WEBHOOK_TOKEN")
+        .ok()
+        .filter(|t| !t.trim().is_empty())
+        .ok_or_else(|| to_error(EmailError::NotConfigured))?;
+    if !constant_time_eq(query.token.as_bytes(), expected.trim().as_bytes()) {
+        return Err(to_error(EmailError::InvalidToken));
+    }
+
+    let events = parse_events(&payload);
+    let crud = EmailCrud::new(state.db.clone());
+    let mut suppressed = 0;
+    for event in &events {
+        if crud.suppress(&event.email, event.reason, event.detail.as_deref()).await.map_err(to_error)? {
+            tracing::info!("Suppressed an address after a {}", event.reason.as_str());
+            suppressed += 1;
+        }
+    }
+
+    Ok(Json(EmailEventsResponse { received: events.len(), suppressed }))
+}
@@ -0,0 +1,273 @@
+use axum::http::StatusCode;
+use sha2::{Digest, Sha256};
+use sqlx::{MySql, Pool};
+use uuid::Uuid;
+
+use super::model::{OutboxEmail, SuppressionReason};
+use crate::services::email::{with_anti_phishing_code, EmailMessage};
+use crate::services::pii::{email_index, SealedString};
+
+const OUTBOX_COLUMNS: &str = r#"
+    id, recipient, recipient_hash, subject, body, CAST(status AS CHAR) as status,
+    attempts, next_attempt_at, last_error, created_at, claimed_at, sent_at
+"#;
+
+// =============================================================================
+// EMAIL ERROR
+// =============================================================================
+
+#[derive(Debug)]
+pub enum EmailError {
+    /// The recipient bounced or complained earlier
+    Suppressed,
+    InvalidToken,
+    /// EMAIL_WEBHOOK_TOKEN is not set
+    NotConfigured,
+    DatabaseError(String),
+}
+
+impl std::fmt::Display for EmailError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EmailError::Suppressed => write!(f, "Recipient is on the suppression list"),
+            EmailError::InvalidToken => write!(f, "Invalid webhook token"),
+            EmailError::NotConfigured => write!(f, "Email event ingestion is not configured"),
+            EmailError::DatabaseError(e) => write!(f, "Database error: {}", e),
+        }
+    }
+}
+
+impl EmailError {
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            EmailError::Suppressed => StatusCode::UNPROCESSABLE_ENTITY,
+            EmailError::InvalidToken => StatusCode::UNAUTHORIZED,
+            EmailError::NotConfigured => StatusCode::NOT_FOUND,
+            EmailError::DatabaseError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+impl From<sqlx::Error> for EmailError {
+    fn from(err: sqlx::Error) -> Self {
+        EmailError::DatabaseError(err.to_string())
+    }
+}
+
+/// Suppression and outbox key for an address
+pub fn recipient_hash(email: &str) -> String {
+    hex::encode(Sha256::digest(email.trim().to_lowercase().as_bytes()))
+}
+
+// =============================================================================
+// EMAIL CRUD
+// =============================================================================
+
+pub struct EmailCrud {
+    pool: Pool<MySql>,
+}
+
+impl EmailCrud {
+    pub fn new(pool: Pool<MySql>) -> Self {
+        Self { pool }
+    }
+
+    /// Queue a message for the outbox worker; returns its id
+    pub async fn enqueue(&self, message: &EmailMessage) -> Result<String, EmailError> {
+        if self.is_suppressed(&message.to).await? {
+            return Err(EmailError::Suppressed);
+        }
+
+        let body = with_anti_phishing_code(&message.text, self.anti_phishing_code(&message.to).await?.as_deref());
+        let id = Uuid::new_v4().to_string();
+        sqlx::query(
+            r#"
+            INSERT INTO email_outbox (id, recipient, recipient_hash, subject, body, status, next_attempt_at)
+            VALUES (?, ?, ?, ?, ?, 'pending', NOW())
+            "#,
+        )
+        .bind(&id)
+        .bind(SealedString(message.to.trim().to_string()))
+        .bind(recipient_hash(&message.to))
+        .bind(&message.subject)
+        .bind(SealedString(body))
+        .execute(&self.pool)
+        .await?;
+
+        Ok(id)
+    }
+
+    /// Code of the account the address belongs to, if it set one
+    async fn anti_phishing_code(&self, email: &str) -> Result<Option<String>, EmailError> {
+        let email = email.trim();
+        let row: Option<(Option<SealedString>,)> =
+            sqlx::query_as("SELECT anti_phishing_code FROM users WHERE email_hash = ? OR email = ? LIMIT 1")
+                .bind(email_index(email))
+                .bind(email)
+                .fetch_optional(&self.pool)
+                .await?;
+        Ok(row.and_then(|(code,)| code).map(String::from))
+    }
+
+    pub async fn get(&self, id: &str) -> Result<Option<OutboxEmail>, EmailError> {
+        let sql = format!("SELECT {} FROM email_outbox WHERE id = ?", OUTBOX_COLUMNS);
+        let email = sqlx::query_as::<_, OutboxEmail>(&sql)
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(email)
+    }
+
+    // =========================================================================
+    // WORKER
+    // =========================================================================
+
+    /// Move up to `limit` due messages to sending. Each claim is a
+    /// conditional update, so concurrent workers never take the same one.
+    pub async fn claim_due(&self, limit: i64) -> Result<Vec<OutboxEmail>, EmailError> {
+        let sql = format!(
+            r#"
+            SELECT {} FROM email_outbox
+            WHERE status = 'pending' AND next_attempt_at <= NOW()
+            ORDER BY next_attempt_at ASC
+            LIMIT ?
+            "#,
+            OUTBOX_COLUMNS
+        );
+        let candidates = sqlx::query_as::<_, OutboxEmail>(&sql)
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await?;
+
+        let mut claimed = Vec::with_capacity(candidates.len());
+        for email in candidates {
+            let result = sqlx::query(
+                r#"
+                UPDATE email_outbox
+                SET status = 'sending', claimed_at = NOW(), attempts = attempts + 1
+                WHERE id = ? AND status = 'pending'
+                "#,
+            )
+            .bind(&email.id)
+            .execute(&self.pool)
+            .await?;
+
+            if result.rows_affected() == 1 {
+                claimed.push(OutboxEmail { attempts: email.attempts + 1, ..email });
+            }
+        }
+
+        Ok(claimed)
+    }
+
+    /// Return messages whose worker died mid-send to the queue
+    pub async fn requeue_stale(&self, older_than_minutes: i64) -> Result<u64, EmailError> {
+        let requeued = sqlx::query(
+            r#"
+            UPDATE email_outbox
+            SET status = 'pending', next_attempt_at = NOW()
+            WHERE status = 'sending'
+              AND claimed_at < DATE_SUB(NOW(), INTERVAL ? MINUTE)
+            "#,
+        )
+        .bind(older_than_minutes)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(requeued.rows_affected())
+    }
+
+    /// Delivered; the body is no longer needed
+    pub async fn mark_sent(&self, id: &str) -> Result<(), EmailError> {
+        sqlx::query(
+            r#"
+            UPDATE email_outbox
+            SET status = 'sent', body = '', last_error = NULL, sent_at = NOW()
+            WHERE id = ? AND status = 'sending'
+            "#,
+        )
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn mark_suppressed(&self, id: &str) -> Result<(), EmailError> {
+        sqlx::query("UPDATE email_outbox SET status = 'suppressed', body = '' WHERE id = ? AND status = 'sending'")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn retry_later(&self, id: &str, error: &str, delay_secs: i64) -> Result<(), EmailError> {
+        sqlx::query(
+            r#"
+            UPDATE email_outbox
+            SET status = 'pending', last_error = ?, next_attempt_at = DATE_ADD(NOW(), INTERVAL ? SECOND)
+            WHERE id = ? AND status = 'sending'
+            "#,
+        )
+        .bind(error)
+        .bind(delay_secs)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn fail(&self, id: &str, error: &str) -> Result<(), EmailError> {
+        sqlx::query("UPDATE email_outbox SET status = 'failed', last_error = ? WHERE id = ? AND status = 'sending'")
+            .bind(error)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    // =========================================================================
+    // SUPPRESSION LIST
+    // =========================================================================
+
+    pub async fn is_suppressed(&self, email: &str) -> Result<bool, EmailError> {
+        let row: Option<(String,)> = sqlx::query_as("SELECT email_hash FROM email_suppressions WHERE email_hash = ?")
+            .bind(recipient_hash(email))
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(row.is_some())
+    }
+
+    /// Stop sending to `email`. Returns false when it was already suppressed.
+    pub async fn suppress(&self, email: &str, reason: SuppressionReason, detail: Option<&str>) -> Result<bool, EmailError> {
+        let result = sqlx::query(
+            "INSERT IGNORE INTO email_suppressions (email_hash, reason, detail) VALUES (?, ?, ?)",
+        )
+        .bind(recipient_hash(email))
+        .bind(reason)
+        .bind(detail.map(|d| d.chars().take(255).collect::<String>()))
+        .execute(&self.pool)
+        .await?;
+
+        // Queued mail to the address would only bounce again
+        sqlx::query(
+            "UPDATE email_outbox SET status = 'suppressed', body = '' WHERE recipient_hash = ? AND status = 'pending'",
+        )
+        .bind(recipient_hash(email))
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recipient_hash_is_normalized() {
+        assert_eq!(recipient_hash(" User@Example.com "), recipient_hash("user@example.com"));
+        assert_ne!(recipient_hash("user@example.com"), recipient_hash("other@example.com"));
+        assert_eq!(recipient_hash("user@example.com").len(), 64);
+    }
+}
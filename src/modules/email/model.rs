@@ -0,0 +1,66 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+use crate::services::pii::SealedString;
+
+// =============================================================================
+// OUTBOX
+// =============================================================================
+
+/// A queued outbound email. Recipient and body are encrypted at rest; the
+/// body is cleared once the message is sent.
+#[derive(Debug, Clone, FromRow)]
+pub struct OutboxEmail {
+    pub id: String,
+    #[sqlx(try_from = "SealedString")]
+    pub recipient: String,
+    /// SHA-256 of the normalized recipient, for suppression lookups
+    pub recipient_hash: String,
+    pub subject: String,
+    #[sqlx(try_from = "SealedString")]
+    pub body: String,
+    pub status: OutboxStatus,
+    pub attempts: u32,
+    pub next_attempt_at: DateTime<Utc>,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub claimed_at: Option<DateTime<Utc>>,
+    pub sent_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(rename_all = "lowercase")]
+pub enum OutboxStatus {
+    Pending,
+    Sending,
+    Sent,
+    /// Out of attempts
+    Failed,
+    /// Recipient was suppressed before delivery
+    Suppressed,
+}
+
+// =============================================================================
+// SUPPRESSION
+// =============================================================================
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(rename_all = "lowercase")]
+pub enum SuppressionReason {
+    /// Permanent bounce reported by the mail provider
+    Bounce,
+    /// Recipient marked a message as spam
+    Complaint,
+}
+
+impl SuppressionReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SuppressionReason::Bounce => "bounce",
+            SuppressionReason::Complaint => "complaint",
+        }
+    }
+}
@@ -0,0 +1,11 @@
+use axum::Router;
+use std::sync::Arc;
+
+use crate::AppState;
+use crate::manifest::DeclaredRoutes;
+use super::controller::ingest_events;
+
+pub fn email_routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .declared("ingestEmailEvents", ingest_events)
+}
@@ -0,0 +1,36 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+// =============================================================================
+// REQUESTS
+// =============================================================================
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct EmailEventsQuery {
+    /// Shared secret configured as EMAIL_WEBHOOK_TOKEN, set in the
+    /// provider's webhook URL
+    pub token: String,
+}
+
+// =============================================================================
+// RESPONSES
+// =============================================================================
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct EmailEventsResponse {
+    /// Bounces and complaints found in the payload
+    pub received: usize,
+    /// Addresses newly added to the suppression list
+    pub suppressed: usize,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct EmailErrorResponse {
+    pub error: String,
+}
+
+impl EmailErrorResponse {
+    pub fn new(error: impl Into<String>) -> Self {
+        Self { error: error.into() }
+    }
+}
@@ -0,0 +1,72 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::Utc;
+use std::sync::Arc;
+
+use crate::AppState;
+use crate::modules::auth::interface::{scope::HistoryRead, Scoped};
+use crate::services::exports::url_ttl;
+use crate::services::storage::{ObjectStorage, S3Storage};
+use super::crud::{ExportCrud, ExportError};
+use super::model::ExportStatus;
+use super::schema::{CreateExportRequest, ExportErrorResponse, ExportResponse};
+
+fn to_error(e: ExportError) -> (StatusCode, Json<ExportErrorResponse>) {
+    (e.status_code(), Json(ExportErrorResponse::new(e.to_string())))
+}
+
+fn storage() -> Result<S3Storage, ExportError> {
+    S3Storage::from_env().map_err(|e| ExportError::StorageUnavailable(e.to_string()))
+}
+
+// =============================================================================
+// POST /exports - Queue an export
+// =============================================================================
+
+pub async fn create_export(
+    State(state): State<Arc<AppState>>,
+    user: Scoped<HistoryRead>,
+    Json(payload): Json<CreateExportRequest>,
+) -> Result<(StatusCode, Json<ExportResponse>), (StatusCode, Json<ExportErrorResponse>)> {
+    // Don't queue work nobody can deliver
+    storage().map_err(to_error)?;
+
+    let crud = ExportCrud::new(state.db.clone());
+    let job = crud.create(&user.0.id, &payload).await.map_err(to_error)?;
+
+    Ok((StatusCode::ACCEPTED, Json(job.into())))
+}
+
+// =============================================================================
+// GET /exports/{id} - Job status, with a signed download URL once completed
+// =============================================================================
+
+pub async fn get_export(
+    State(state): State<Arc<AppState>>,
+    user: Scoped<HistoryRead>,
+    Path(export_id): Path<String>,
+) -> Result<Json<ExportResponse>, (StatusCode, Json<ExportErrorResponse>)> {
+    let crud = ExportCrud::new(state.db.clone());
+    let job = crud.get(&user.0.id, &export_id).await.map_err(to_error)?;
+
+    let object_key = job.object_key.clone();
+    let mut response = ExportResponse::from(job);
+
+    if let (ExportStatus::Completed, Some(key)) = (response.status, object_key) {
+        let ttl = url_ttl();
+        let url = storage()
+            .and_then(|s| {
+                s.presigned_get_url(&key, ttl)
+                    .map_err(|e| ExportError::StorageUnavailable(e.to_string()))
+            })
+            .map_err(to_error)?;
+
+        response.download_url = Some(url);
+        response.download_expires_at = Some(Utc::now() + chrono::Duration::seconds(ttl.as_secs() as i64));
+    }
+
+    Ok(Json(response))
+}
@@ -0,0 +1,281 @@
+use axum::http::StatusCode;
+use sqlx::{MySql, Pool};
+use uuid::Uuid;
+
+use super::model::ExportJob;
+use super::schema::CreateExportRequest;
+use crate::modules::jobs::crud::{JobCrud, JobError};
+use crate::modules::jobs::model::JobKind;
+
+/// Pending or running exports a user may have at once
+pub const MAX_ACTIVE_EXPORTS_PER_USER: i64 = 3;
+/// A job is failed after this many interrupted attempts
+pub const MAX_EXPORT_ATTEMPTS: u32 = 3;
+
+const EXPORT_COLUMNS: &str = r#"
+    id, user_id, CAST(kind AS CHAR) as kind, CAST(status AS CHAR) as status,
+    range_from, range_to, object_key, row_count, error, attempts,
+    created_at, started_at, completed_at, expires_at
+"#;
+
+// =============================================================================
+// EXPORT ERROR
+// =============================================================================
+
+#[derive(Debug)]
+pub enum ExportError {
+    InvalidRange,
+    TooManyExports,
+    ExportNotFound,
+    StorageUnavailable(String),
+    DatabaseError(String),
+}
+
+impl std::fmt::Display for ExportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExportError::InvalidRange => write!(f, "'from' must be before 'to'"),
+            ExportError::TooManyExports => write!(
+                f,
+                "A maximum of {} exports can be in progress at once",
+                MAX_ACTIVE_EXPORTS_PER_USER
+            ),
+            ExportError::ExportNotFound => write!(f, "Export not found"),
+            ExportError::StorageUnavailable(e) => write!(f, "Export storage unavailable: {}", e),
+            ExportError::DatabaseError(e) => write!(f, "Database error: {}", e),
+        }
+    }
+}
+
+impl ExportError {
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            ExportError::InvalidRange => StatusCode::BAD_REQUEST,
+            ExportError::TooManyExports => StatusCode::TOO_MANY_REQUESTS,
+            ExportError::ExportNotFound => StatusCode::NOT_FOUND,
+            ExportError::StorageUnavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+            ExportError::DatabaseError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+impl From<sqlx::Error> for ExportError {
+    fn from(err: sqlx::Error) -> Self {
+        ExportError::DatabaseError(err.to_string())
+    }
+}
+
+impl From<JobError> for ExportError {
+    fn from(err: JobError) -> Self {
+        ExportError::DatabaseError(err.to_string())
+    }
+}
+
+// =============================================================================
+// EXPORT CRUD
+// =============================================================================
+
+pub struct ExportCrud {
+    pool: Pool<MySql>,
+}
+
+impl ExportCrud {
+    pub fn new(pool: Pool<MySql>) -> Self {
+        Self { pool }
+    }
+
+    fn jobs(&self) -> JobCrud {
+        JobCrud::new(self.pool.clone())
+    }
+
+    /// Queue an export for the worker, tracked by a job with the same id
+    pub async fn create(&self, user_id: &str, request: &CreateExportRequest) -> Result<ExportJob, ExportError> {
+        if let (Some(from), Some(to)) = (request.from, request.to) {
+            if from >= to {
+                return Err(ExportError::InvalidRange);
+            }
+        }
+
+        let (active,): (i64,) = sqlx::query_as(
+            "SELECT COUNT(*) FROM export_jobs WHERE user_id = ? AND status IN ('pending', 'processing')",
+        )
+        .bind(user_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        if active >= MAX_ACTIVE_EXPORTS_PER_USER {
+            return Err(ExportError::TooManyExports);
+        }
+
+        let id = Uuid::new_v4().to_string();
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO export_jobs (id, user_id, kind, status, range_from, range_to)
+            VALUES (?, ?, ?, 'pending', ?, ?)
+            "#,
+        )
+        .bind(&id)
+        .bind(user_id)
+        .bind(request.kind)
+        .bind(request.from)
+        .bind(request.to)
+        .execute(&mut *tx)
+        .await?;
+
+        JobCrud::create(&mut tx, &id, JobKind::Export, Some(user_id)).await?;
+        tx.commit().await?;
+
+        self.get(user_id, &id).await
+    }
+
+    pub async fn get(&self, user_id: &str, export_id: &str) -> Result<ExportJob, ExportError> {
+        let sql = format!("SELECT {} FROM export_jobs WHERE id = ? AND user_id = ?", EXPORT_COLUMNS);
+
+        sqlx::query_as::<_, ExportJob>(&sql)
+            .bind(export_id)
+            .bind(user_id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or(ExportError::ExportNotFound)
+    }
+
+    // =========================================================================
+    // WORKER
+    // =========================================================================
+
+    /// Move up to `limit` pending jobs to processing. Each claim is a
+    /// conditional update, so concurrent workers never take the same job.
+    pub async fn claim_pending(&self, limit: i64) -> Result<Vec<ExportJob>, ExportError> {
+        let sql = format!(
+            "SELECT {} FROM export_jobs WHERE status = 'pending' ORDER BY created_at ASC LIMIT ?",
+            EXPORT_COLUMNS
+        );
+        let candidates = sqlx::query_as::<_, ExportJob>(&sql)
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await?;
+
+        let mut claimed = Vec::with_capacity(candidates.len());
+        for job in candidates {
+            let result = sqlx::query(
+                r#"
+                UPDATE export_jobs
+                SET status = 'processing', started_at = NOW(), attempts = attempts + 1
+                WHERE id = ? AND status = 'pending'
+                "#,
+            )
+            .bind(&job.id)
+            .execute(&self.pool)
+            .await?;
+
+            if result.rows_affected() == 1 {
+                self.jobs().start(&job.id).await?;
+                claimed.push(job);
+            }
+        }
+
+        Ok(claimed)
+    }
+
+    /// Return jobs whose worker died mid-export to the queue, or fail them
+    /// once they have used up their attempts
+    pub async fn requeue_stale(&self, older_than_minutes: i64) -> Result<u64, ExportError> {
+        let stale: Vec<(String, u32)> = sqlx::query_as(
+            r#"
+            SELECT id, attempts FROM export_jobs
+            WHERE status = 'processing'
+              AND started_at < DATE_SUB(NOW(), INTERVAL ? MINUTE)
+            "#,
+        )
+        .bind(older_than_minutes)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let requeued = sqlx::query(
+            r#"
+            UPDATE export_jobs
+            SET status = IF(attempts >= ?, 'failed', 'pending'),
+                error = IF(attempts >= ?, 'Export was interrupted too many times', error)
+            WHERE status = 'processing'
+              AND started_at < DATE_SUB(NOW(), INTERVAL ? MINUTE)
+            "#,
+        )
+        .bind(MAX_EXPORT_ATTEMPTS)
+        .bind(MAX_EXPORT_ATTEMPTS)
+        .bind(older_than_minutes)
+        .execute(&self.pool)
+        .await?;
+
+        for (id, attempts) in &stale {
+            if *attempts >= MAX_EXPORT_ATTEMPTS {
+                self.jobs().fail(id, "Export was interrupted too many times").await?;
+            } else {
+                self.jobs().requeue(id).await?;
+            }
+        }
+
+        Ok(requeued.rows_affected())
+    }
+
+    pub async fn complete(
+        &self,
+        export_id: &str,
+        object_key: &str,
+        row_count: u32,
+        retention_hours: i64,
+    ) -> Result<(), ExportError> {
+        sqlx::query(
+            r#"
+            UPDATE export_jobs
+            SET status = 'completed', object_key = ?, row_count = ?, error = NULL,
+                completed_at = NOW(), expires_at = DATE_ADD(NOW(), INTERVAL ? HOUR)
+            WHERE id = ? AND status = 'processing'
+            "#,
+        )
+        .bind(object_key)
+        .bind(row_count)
+        .bind(retention_hours)
+        .bind(export_id)
+        .execute(&self.pool)
+        .await?;
+
+        self.jobs().succeed(export_id).await?;
+        Ok(())
+    }
+
+    pub async fn fail(&self, export_id: &str, error: &str) -> Result<(), ExportError> {
+        sqlx::query(
+            "UPDATE export_jobs SET status = 'failed', error = ?, completed_at = NOW() WHERE id = ? AND status = 'processing'",
+        )
+        .bind(error)
+        .bind(export_id)
+        .execute(&self.pool)
+        .await?;
+
+        self.jobs().fail(export_id, error).await?;
+        Ok(())
+    }
+
+    /// Completed jobs past their retention window
+    pub async fn get_expired(&self, limit: i64) -> Result<Vec<ExportJob>, ExportError> {
+        let sql = format!(
+            "SELECT {} FROM export_jobs WHERE status = 'completed' AND expires_at <= NOW() LIMIT ?",
+            EXPORT_COLUMNS
+        );
+        let jobs = sqlx::query_as::<_, ExportJob>(&sql)
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(jobs)
+    }
+
+    pub async fn mark_expired(&self, export_id: &str) -> Result<(), ExportError> {
+        sqlx::query("UPDATE export_jobs SET status = 'expired' WHERE id = ? AND status = 'completed'")
+            .bind(export_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,58 @@
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+// =============================================================================
+// EXPORT JOB
+// =============================================================================
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct ExportJob {
+    pub id: String,
+    pub user_id: String,
+    pub kind: ExportKind,
+    pub status: ExportStatus,
+    pub range_from: Option<DateTime<Utc>>,
+    pub range_to: Option<DateTime<Utc>>,
+    /// Object storage key of the finished file
+    pub object_key: Option<String>,
+    pub row_count: Option<u32>,
+    pub error: Option<String>,
+    pub attempts: u32,
+    pub created_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+    /// When the file is deleted from storage
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, sqlx::Type, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(rename_all = "lowercase")]
+pub enum ExportKind {
+    /// Swap history
+    Swaps,
+    /// Custodial balance ledger
+    Ledger,
+}
+
+impl ExportKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExportKind::Swaps => "swaps",
+            ExportKind::Ledger => "ledger",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, sqlx::Type, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(rename_all = "lowercase")]
+pub enum ExportStatus {
+    Pending,
+    Processing,
+    Completed,
+    Failed,
+    Expired,
+}
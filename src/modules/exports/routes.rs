@@ -0,0 +1,12 @@
+use axum::Router;
+use std::sync::Arc;
+
+use crate::AppState;
+use crate::manifest::DeclaredRoutes;
+use super::controller::{create_export, get_export};
+
+pub fn export_routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .declared("createExport", create_export)
+        .declared("getExport", get_export)
+}
@@ -0,0 +1,72 @@
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::model::{ExportJob, ExportKind, ExportStatus};
+
+// =============================================================================
+// REQUESTS
+// =============================================================================
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CreateExportRequest {
+    pub kind: ExportKind,
+    /// Only include records created at or after this time
+    #[serde(default)]
+    pub from: Option<DateTime<Utc>>,
+    /// Only include records created before this time
+    #[serde(default)]
+    pub to: Option<DateTime<Utc>>,
+}
+
+// =============================================================================
+// RESPONSES
+// =============================================================================
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ExportResponse {
+    /// Also the id of the job tracking the export, see GET /jobs/{id}
+    pub id: String,
+    pub kind: ExportKind,
+    pub status: ExportStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub row_count: Option<u32>,
+    /// Signed download link, present once the export has completed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub download_url: Option<String>,
+    /// When `download_url` stops working; request the job again for a fresh one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub download_expires_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+impl From<ExportJob> for ExportResponse {
+    fn from(job: ExportJob) -> Self {
+        Self {
+            id: job.id,
+            kind: job.kind,
+            status: job.status,
+            row_count: job.row_count,
+            download_url: None,
+            download_expires_at: None,
+            error: job.error,
+            created_at: job.created_at,
+            completed_at: job.completed_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ExportErrorResponse {
+    pub error: String,
+}
+
+impl ExportErrorResponse {
+    pub fn new(error: impl Into<String>) -> Self {
+        Self { error: error.into() }
+    }
+}
@@ -0,0 +1,86 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use std::sync::Arc;
+
+use crate::AppState;
+use crate::modules::auth::interface::User;
+use super::crud::GiftCardCrud;
+use super::model::GiftCardPurchase;
+use super::schema::{
+    CatalogQuery, CatalogResponse, GiftCardErrorResponse, GiftCardPurchaseResponse,
+    PurchaseGiftCardRequest,
+};
+
+fn to_response(purchase: GiftCardPurchase) -> GiftCardPurchaseResponse {
+    GiftCardPurchaseResponse {
+        id: purchase.id,
+        card_id: purchase.card_id,
+        card_name: purchase.card_name,
+        amount: purchase.amount,
+        status: purchase.status,
+        funding_swap_id: purchase.funding_swap_id,
+        payment_currency: purchase.payment_currency,
+        payment_network: purchase.payment_network,
+        payment_amount: purchase.payment_amount,
+        payment_tx_hash: purchase.payment_tx_hash,
+        code_delivered: purchase.delivered_at.is_some(),
+        delivered_at: purchase.delivered_at,
+        created_at: purchase.created_at,
+    }
+}
+
+// =============================================================================
+// GET /gift-cards/catalog - List purchasable gift cards
+// =============================================================================
+
+pub async fn get_catalog(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<CatalogQuery>,
+) -> Result<Json<CatalogResponse>, (StatusCode, Json<GiftCardErrorResponse>)> {
+    let crud = GiftCardCrud::new(state.db.clone(), Some(state.redis.clone()));
+
+    let cards = crud.get_catalog(query.country.as_deref()).await.map_err(|e| {
+        (e.status_code(), Json(GiftCardErrorResponse::new(e.to_string())))
+    })?;
+
+    Ok(Json(CatalogResponse { cards }))
+}
+
+// =============================================================================
+// POST /gift-cards/purchase - Buy a gift card with swap proceeds
+// =============================================================================
+
+pub async fn purchase_gift_card(
+    State(state): State<Arc<AppState>>,
+    user: User,
+    Json(payload): Json<PurchaseGiftCardRequest>,
+) -> Result<(StatusCode, Json<GiftCardPurchaseResponse>), (StatusCode, Json<GiftCardErrorResponse>)> {
+    let crud = GiftCardCrud::new(state.db.clone(), Some(state.redis.clone()));
+
+    let purchase = crud.purchase(&user.0, &payload).await.map_err(|e| {
+        (e.status_code(), Json(GiftCardErrorResponse::new(e.to_string())))
+    })?;
+
+    Ok((StatusCode::CREATED, Json(to_response(purchase))))
+}
+
+// =============================================================================
+// GET /gift-cards/purchases/{id} - Purchase status
+// =============================================================================
+
+pub async fn get_purchase(
+    State(state): State<Arc<AppState>>,
+    user: User,
+    Path(purchase_id): Path<String>,
+) -> Result<Json<GiftCardPurchaseResponse>, (StatusCode, Json<GiftCardErrorResponse>)> {
+    let crud = GiftCardCrud::new(state.db.clone(), Some(state.redis.clone()));
+
+    let purchase = crud.get_purchase(&user.0.id, &purchase_id).await.map_err(|e| {
+        (e.status_code(), Json(GiftCardErrorResponse::new(e.to_string())))
+    })?;
+
+    Ok(Json(to_response(purchase)))
+}
@@ -0,0 +1,567 @@
+use axum::http::StatusCode;
+use sqlx::{MySql, Pool};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use super::model::{GiftCardPurchase, GiftCardPurchaseStatus};
+use super::provider::{Fulfillment, GiftCardProvider, TrocadorGiftCardProvider};
+use super::schema::{GiftCardResponse, PurchaseGiftCardRequest};
+use crate::modules::auth::model::User;
+use crate::modules::balances::crud::{BalanceCrud, BalanceError};
+use crate::modules::balances::model::SwapProceeds;
+use crate::services::amount::Decimal;
+use crate::services::email::{queued_email_sender, EmailMessage, EmailSender};
+use crate::services::encryption::FieldCipher;
+use crate::services::redis_cache::RedisService;
+use crate::services::trocador::TrocadorError;
+use crate::services::watch_only::watch_only;
+
+const CATALOG_CACHE_TTL_SECS: u64 = 3600;
+
+/// Where the money for a purchase comes from
+enum Funding<'a> {
+    /// Proceeds of a completed swap that were credited to the buyer's balance
+    Swap(&'a str),
+    /// The buyer's custodial balance
+    Balance { currency: String, network: String },
+}
+
+// =============================================================================
+// GIFT CARD ERROR
+// =============================================================================
+
+#[derive(Debug)]
+pub enum GiftCardError {
+    EmailNotVerified,
+    FundingRequired,
+    CustodyNotEnabled,
+    InvalidAmount(String),
+    CardNotFound,
+    PurchaseNotFound,
+    SwapNotFound,
+    SwapNotCompleted,
+    SwapAlreadyUsed,
+    SwapNotInCustody,
+    InsufficientFunds { required: Decimal, available: Decimal },
+    ProviderError(String),
+    EncryptionError(String),
+    DatabaseError(String),
+}
+
+impl std::fmt::Display for GiftCardError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GiftCardError::EmailNotVerified => {
+                write!(f, "Please verify your email before purchasing gift cards")
+            }
+            GiftCardError::FundingRequired => {
+                write!(f, "A completed swap_id, or the currency and network of a balance, is required to fund the purchase")
+            }
+            GiftCardError::CustodyNotEnabled => {
+                write!(f, "Custodial balances are not enabled for this account")
+            }
+            GiftCardError::InvalidAmount(msg) => write!(f, "Invalid amount: {}", msg),
+            GiftCardError::CardNotFound => write!(f, "Gift card not found"),
+            GiftCardError::PurchaseNotFound => write!(f, "Gift card purchase not found"),
+            GiftCardError::SwapNotFound => write!(f, "Swap not found"),
+            GiftCardError::SwapNotCompleted => write!(f, "Swap must be completed before it can fund a purchase"),
+            GiftCardError::SwapAlreadyUsed => write!(f, "Swap has already funded a gift card purchase"),
+            GiftCardError::SwapNotInCustody => {
+                write!(f, "Only a swap paid out to your balance can fund a purchase")
+            }
+            GiftCardError::InsufficientFunds { required, available } => {
+                write!(f, "Insufficient funds: required={}, available={}", required, available)
+            }
+            GiftCardError::ProviderError(e) => write!(f, "Provider error: {}", e),
+            GiftCardError::EncryptionError(e) => write!(f, "Encryption error: {}", e),
+            GiftCardError::DatabaseError(e) => write!(f, "Database error: {}", e),
+        }
+    }
+}
+
+impl GiftCardError {
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            GiftCardError::EmailNotVerified => StatusCode::FORBIDDEN,
+            GiftCardError::FundingRequired => StatusCode::BAD_REQUEST,
+            GiftCardError::CustodyNotEnabled => StatusCode::FORBIDDEN,
+            GiftCardError::InvalidAmount(_) => StatusCode::BAD_REQUEST,
+            GiftCardError::CardNotFound => StatusCode::NOT_FOUND,
+            GiftCardError::PurchaseNotFound => StatusCode::NOT_FOUND,
+            GiftCardError::SwapNotFound => StatusCode::NOT_FOUND,
+            GiftCardError::SwapNotCompleted => StatusCode::CONFLICT,
+            GiftCardError::SwapAlreadyUsed => StatusCode::CONFLICT,
+            GiftCardError::SwapNotInCustody => StatusCode::CONFLICT,
+            GiftCardError::InsufficientFunds { .. } => StatusCode::PAYMENT_REQUIRED,
+            GiftCardError::ProviderError(_) => StatusCode::BAD_GATEWAY,
+            GiftCardError::EncryptionError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            GiftCardError::DatabaseError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+impl From<TrocadorError> for GiftCardError {
+    fn from(err: TrocadorError) -> Self {
+        GiftCardError::ProviderError(err.to_string())
+    }
+}
+
+/// Ledger errors of a balance-funded purchase
+fn balance_error(err: BalanceError) -> GiftCardError {
+    match err {
+        BalanceError::InsufficientBalance { requested, available } => {
+            GiftCardError::InsufficientFunds { required: requested, available }
+        }
+        BalanceError::CustodyNotEnabled => GiftCardError::CustodyNotEnabled,
+        BalanceError::InvalidAmount(msg) => GiftCardError::InvalidAmount(msg),
+        other => GiftCardError::DatabaseError(other.to_string()),
+    }
+}
+
+impl From<sqlx::Error> for GiftCardError {
+    fn from(err: sqlx::Error) -> Self {
+        GiftCardError::DatabaseError(err.to_string())
+    }
+}
+
+// =============================================================================
+// GIFT CARD CRUD
+// =============================================================================
+
+pub struct GiftCardCrud {
+    pool: Pool<MySql>,
+    redis_service: Option<RedisService>,
+    provider: Arc<dyn GiftCardProvider>,
+    email_sender: Arc<dyn EmailSender>,
+    cipher: Option<FieldCipher>,
+}
+
+impl GiftCardCrud {
+    pub fn new(pool: Pool<MySql>, redis_service: Option<RedisService>) -> Self {
+        let email_sender = queued_email_sender(pool.clone());
+        Self {
+            pool,
+            redis_service,
+            provider: Arc::new(TrocadorGiftCardProvider::from_env()),
+            email_sender,
+            cipher: FieldCipher::from_env().ok(),
+        }
+    }
+
+    pub fn with_provider(mut self, provider: Arc<dyn GiftCardProvider>) -> Self {
+        self.provider = provider;
+        self
+    }
+
+    pub fn with_email_sender(mut self, email_sender: Arc<dyn EmailSender>) -> Self {
+        self.email_sender = email_sender;
+        self
+    }
+
+    pub fn with_cipher(mut self, cipher: FieldCipher) -> Self {
+        self.cipher = Some(cipher);
+        self
+    }
+
+    // =========================================================================
+    // CATALOG
+    // =========================================================================
+
+    /// Fetch the provider catalog, cached in Redis for an hour
+    pub async fn get_catalog(&self, country: Option<&str>) -> Result<Vec<GiftCardResponse>, GiftCardError> {
+        let cache_key = format!(
+            "gift_cards:catalog:{}:{}",
+            self.provider.name(),
+            country.unwrap_or("all").to_lowercase()
+        );
+
+        if let Some(redis) = &self.redis_service {
+            if let Ok(Some(cards)) = redis.get_json::<Vec<GiftCardResponse>>(&cache_key).await {
+                return Ok(cards);
+            }
+        }
+
+        let cards = self.provider.list_cards(country).await?;
+
+        if let Some(redis) = &self.redis_service {
+            let _ = redis.set_json(&cache_key, &cards, CATALOG_CACHE_TTL_SECS).await;
+        }
+
+        Ok(cards)
+    }
+
+    // =========================================================================
+    // PURCHASE
+    // =========================================================================
+
+    /// Place a gift card order funded from the buyer's balance, optionally
+    /// capped at the credited proceeds of one of their completed swaps. The
+    /// purchase is reserved before the order is placed, so a provider order
+    /// never exists without a row; the payer sends the payment once the
+    /// purchase is debited.
+    pub async fn purchase(
+        &self,
+        user: &User,
+        req: &PurchaseGiftCardRequest,
+    ) -> Result<GiftCardPurchase, GiftCardError> {
+        if !user.email_verified {
+            return Err(GiftCardError::EmailNotVerified);
+        }
+
+        let face_value = req.amount.value();
+        if face_value <= Decimal::ZERO {
+            return Err(GiftCardError::InvalidAmount("amount must be positive".to_string()));
+        }
+
+        let funding = match (req.swap_id.as_deref(), req.currency.as_deref(), req.network.as_deref()) {
+            (Some(swap_id), _, _) => Funding::Swap(swap_id),
+            (None, Some(currency), Some(network)) => Funding::Balance {
+                currency: currency.to_lowercase(),
+                network: network.to_lowercase(),
+            },
+            _ => return Err(GiftCardError::FundingRequired),
+        };
+
+        let card = self
+            .get_catalog(req.country.as_deref())
+            .await?
+            .into_iter()
+            .find(|c| c.card_id == req.card_id)
+            .ok_or(GiftCardError::CardNotFound)?;
+
+        if !card.accepts_amount(face_value) {
+            return Err(GiftCardError::InvalidAmount(format!(
+                "{} is not an available denomination for {}",
+                face_value, card.name
+            )));
+        }
+
+        let (currency, network, available) = match &funding {
+            Funding::Swap(swap_id) => {
+                let proceeds = self.get_funding_swap(&user.id, swap_id).await?;
+                if self.swap_already_used(swap_id).await? {
+                    return Err(GiftCardError::SwapAlreadyUsed);
+                }
+                (proceeds.currency, proceeds.network, Some(proceeds.amount))
+            }
+            Funding::Balance { currency, network } => {
+                if !BalanceCrud::new(self.pool.clone()).is_custody_enabled(&user.id).await.map_err(balance_error)? {
+                    return Err(GiftCardError::CustodyNotEnabled);
+                }
+                (currency.clone(), network.clone(), None)
+            }
+        };
+
+        let delivery_email = req.email.clone().unwrap_or_else(|| user.email.clone());
+        let id = Uuid::new_v4().to_string();
+        let funding_swap_id = match &funding {
+            Funding::Swap(swap_id) => Some(*swap_id),
+            Funding::Balance { .. } => None,
+        };
+
+        sqlx::query(
+            r#"
+            INSERT INTO gift_card_purchases (
+                id, user_id, provider, card_id, card_name, country, amount,
+                funding_swap_id, payment_currency, payment_network, status, delivery_email
+            )
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, 'pending', ?)
+            "#,
+        )
+        .bind(&id)
+        .bind(&user.id)
+        .bind(self.provider.name())
+        .bind(&card.card_id)
+        .bind(&card.name)
+        .bind(&card.country)
+        .bind(face_value)
+        .bind(funding_swap_id)
+        .bind(&currency)
+        .bind(&network)
+        .bind(&delivery_email)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| {
+            // uk_gift_card_funding_swap guards concurrent purchases against the same swap
+            if e.to_string().contains("Duplicate entry") {
+                GiftCardError::SwapAlreadyUsed
+            } else {
+                GiftCardError::DatabaseError(e.to_string())
+            }
+        })?;
+
+        let order = match self
+            .provider
+            .place_order(&card.card_id, face_value, &currency, &network, &delivery_email)
+            .await
+        {
+            Ok(order) => order,
+            Err(e) => {
+                self.mark_failed(&id, &e.to_string()).await?;
+                return Err(e);
+            }
+        };
+
+        sqlx::query(
+            r#"
+            UPDATE gift_card_purchases
+            SET provider_order_id = ?, payment_currency = ?, payment_network = ?,
+                payment_amount = ?, payment_address = ?, payment_extra_id = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(&order.order_id)
+        .bind(&order.payment_currency)
+        .bind(&order.payment_network)
+        .bind(order.payment_amount)
+        .bind(&order.payment_address)
+        .bind(&order.payment_extra_id)
+        .bind(&id)
+        .execute(&self.pool)
+        .await?;
+
+        // Swap proceeds sit in the buyer's balance, so both kinds of funding
+        // are a ledger debit; a swap only caps it at what the swap credited
+        let funded = match available {
+            Some(available) if order.payment_amount > available => Err(GiftCardError::InsufficientFunds {
+                required: order.payment_amount,
+                available,
+            }),
+            _ => BalanceCrud::new(self.pool.clone())
+                .debit_gift_card(&user.id, &order.payment_currency, &order.payment_network, order.payment_amount, &id)
+                .await
+                .map(|_| ())
+                .map_err(balance_error),
+        };
+        if let Err(e) = funded {
+            self.mark_failed(&id, &e.to_string()).await?;
+            return Err(e);
+        }
+
+        sqlx::query("UPDATE gift_card_purchases SET funded_at = NOW() WHERE id = ?")
+            .bind(&id)
+            .execute(&self.pool)
+            .await?;
+
+        self.find_purchase(&id).await?.ok_or(GiftCardError::PurchaseNotFound)
+    }
+
+    /// Fetch a user's purchase, refreshing fulfillment from the provider while
+    /// pending. A watch-only deployment returns the stored purchase.
+    pub async fn get_purchase(&self, user_id: &str, purchase_id: &str) -> Result<GiftCardPurchase, GiftCardError> {
+        let purchase = self
+            .find_purchase(purchase_id)
+            .await?
+            .filter(|p| p.user_id == user_id)
+            .ok_or(GiftCardError::PurchaseNotFound)?;
+
+        if !watch_only()
+            && matches!(
+                purchase.status,
+                GiftCardPurchaseStatus::Pending | GiftCardPurchaseStatus::Processing
+            )
+        {
+            return self.refresh_fulfillment(purchase).await;
+        }
+
+        Ok(purchase)
+    }
+
+    /// Poll the provider; on delivery the code is encrypted at rest and emailed to the buyer
+    pub async fn refresh_fulfillment(&self, purchase: GiftCardPurchase) -> Result<GiftCardPurchase, GiftCardError> {
+        let order_id = match &purchase.provider_order_id {
+            Some(id) => id.clone(),
+            None => return Ok(purchase),
+        };
+
+        match self.provider.fetch_fulfillment(&order_id).await? {
+            Fulfillment::Pending => Ok(purchase),
+            Fulfillment::Failed(reason) => {
+                // An order that expired before it was paid gives the debit back
+                if purchase.payment_tx_hash.is_none() {
+                    self.fail_payment(&purchase, &reason).await?;
+                } else {
+                    self.mark_failed(&purchase.id, &reason).await?;
+                }
+
+                self.find_purchase(&purchase.id).await?.ok_or(GiftCardError::PurchaseNotFound)
+            }
+            Fulfillment::Delivered { code } => {
+                let cipher = self
+                    .cipher
+                    .as_ref()
+                    .ok_or_else(|| GiftCardError::EncryptionError("DATA_ENCRYPTION_KEY not configured".to_string()))?;
+                let encrypted = cipher.encrypt(&code).map_err(GiftCardError::EncryptionError)?;
+
+                // Persist before emailing so a mail failure never loses the code
+                sqlx::query(
+                    "UPDATE gift_card_purchases SET status = 'processing', encrypted_code = ? WHERE id = ?"
+                )
+                .bind(&encrypted)
+                .bind(&purchase.id)
+                .execute(&self.pool)
+                .await?;
+
+                let message = EmailMessage {
+                    to: purchase.delivery_email.clone(),
+                    subject: format!("Your {} gift card", purchase.card_name),
+                    text: format!(
+                        "Your {} gift card ({}) is ready.\n\nRedeem code: {}\n",
+                        purchase.card_name, purchase.amount, code
+                    ),
+                };
+
+                match self.email_sender.send(&message).await {
+                    Ok(()) => {
+                        sqlx::query(
+                            "UPDATE gift_card_purchases SET status = 'delivered', delivered_at = NOW(), error = NULL WHERE id = ?"
+                        )
+                        .bind(&purchase.id)
+                        .execute(&self.pool)
+                        .await?;
+                    }
+                    Err(e) => {
+                        tracing::warn!("Gift card email delivery failed for {}: {}", purchase.id, e);
+                        sqlx::query("UPDATE gift_card_purchases SET error = ? WHERE id = ?")
+                            .bind(format!("Email delivery failed: {}", e))
+                            .bind(&purchase.id)
+                            .execute(&self.pool)
+                            .await?;
+                    }
+                }
+
+                self.find_purchase(&purchase.id).await?.ok_or(GiftCardError::PurchaseNotFound)
+            }
+        }
+    }
+
+    // =========================================================================
+    // PAYMENT
+    // =========================================================================
+
+    /// Funded purchases whose provider order is still unpaid, oldest first
+    pub async fn get_unpaid_purchases(&self, limit: i64) -> Result<Vec<GiftCardPurchase>, GiftCardError> {
+        let purchases = sqlx::query_as::<_, GiftCardPurchase>(
+            r#"
+            SELECT * FROM gift_card_purchases
+            WHERE status = 'pending' AND funded_at IS NOT NULL AND payment_tx_hash IS NULL
+            ORDER BY created_at ASC
+            LIMIT ?
+            "#,
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(purchases)
+    }
+
+    /// Take a purchase for payment; fails if another worker got there first
+    pub async fn claim_payment(&self, purchase_id: &str) -> Result<(), GiftCardError> {
+        let result = sqlx::query(
+            "UPDATE gift_card_purchases SET status = 'paying' WHERE id = ? AND status = 'pending' AND funded_at IS NOT NULL"
+        )
+        .bind(purchase_id)
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(GiftCardError::PurchaseNotFound);
+        }
+        Ok(())
+    }
+
+    /// Record the payment; the provider delivers the code from here
+    pub async fn complete_payment(&self, purchase_id: &str, tx_hash: &str) -> Result<(), GiftCardError> {
+        sqlx::query(
+            "UPDATE gift_card_purchases SET status = 'processing', payment_tx_hash = ?, error = NULL WHERE id = ?"
+        )
+        .bind(tx_hash)
+        .bind(purchase_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Fail a purchase that was never paid, returning its balance debit
+    pub async fn fail_payment(&self, purchase: &GiftCardPurchase, error: &str) -> Result<(), GiftCardError> {
+        self.mark_failed(&purchase.id, error).await?;
+
+        if purchase.funded_at.is_some() {
+            BalanceCrud::new(self.pool.clone())
+                .refund_gift_card(
+                    &purchase.user_id,
+                    &purchase.payment_currency,
+                    &purchase.payment_network,
+                    purchase.payment_amount,
+                    &purchase.id,
+                )
+                .await
+                .map_err(balance_error)?;
+        }
+        Ok(())
+    }
+
+    // =========================================================================
+    // HELPERS
+    // =========================================================================
+
+    /// Fail a purchase and release its funding swap for another attempt
+    async fn mark_failed(&self, purchase_id: &str, error: &str) -> Result<(), GiftCardError> {
+        sqlx::query(
+            "UPDATE gift_card_purchases SET status = 'failed', error = ?, funding_swap_id = NULL WHERE id = ?"
+        )
+            .bind(error)
+            .bind(purchase_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn find_purchase(&self, id: &str) -> Result<Option<GiftCardPurchase>, GiftCardError> {
+        let purchase = sqlx::query_as::<_, GiftCardPurchase>(
+            "SELECT * FROM gift_card_purchases WHERE id = ?"
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(purchase)
+    }
+
+    /// Credited proceeds of a completed deposit-to-balance swap of this user
+    async fn get_funding_swap(&self, user_id: &str, swap_id: &str) -> Result<SwapProceeds, GiftCardError> {
+        let swap: Option<(Option<String>, String)> =
+            sqlx::query_as("SELECT user_id, CAST(status AS CHAR) FROM swaps WHERE id = ?")
+                .bind(swap_id)
+                .fetch_optional(&self.pool)
+                .await?;
+
+        let (owner, status) = swap.ok_or(GiftCardError::SwapNotFound)?;
+        if owner.as_deref() != Some(user_id) {
+            return Err(GiftCardError::SwapNotFound);
+        }
+        if status != "completed" {
+            return Err(GiftCardError::SwapNotCompleted);
+        }
+
+        BalanceCrud::new(self.pool.clone())
+            .swap_proceeds(swap_id)
+            .await
+            .map_err(balance_error)?
+            .ok_or(GiftCardError::SwapNotInCustody)
+    }
+
+    async fn swap_already_used(&self, swap_id: &str) -> Result<bool, GiftCardError> {
+        let row: Option<(String,)> = sqlx::query_as(
+            "SELECT id FROM gift_card_purchases WHERE funding_swap_id = ? LIMIT 1"
+        )
+        .bind(swap_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.is_some())
+    }
+}
@@ -0,0 +1,58 @@
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+use crate::services::amount::Decimal;
+
+// =============================================================================
+// GIFT CARD PURCHASE
+// =============================================================================
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct GiftCardPurchase {
+    pub id: String,
+    pub user_id: String,
+    pub provider: String,
+    pub card_id: String,
+    pub card_name: String,
+    pub country: Option<String>,
+    pub amount: Decimal,
+
+    // Funding: a completed swap, or the buyer's balance when None
+    pub funding_swap_id: Option<String>,
+    pub provider_order_id: Option<String>,
+    pub payment_currency: String,
+    pub payment_network: String,
+    pub payment_amount: Decimal,
+    pub payment_address: Option<String>,
+    pub payment_extra_id: Option<String>,
+    /// Set once the swap proceeds were checked or the balance debited;
+    /// the payer only sends funded purchases
+    pub funded_at: Option<DateTime<Utc>>,
+    pub payment_tx_hash: Option<String>,
+
+    // Fulfillment
+    pub status: GiftCardPurchaseStatus,
+    pub delivery_email: String,
+    pub encrypted_code: Option<String>,
+    pub delivered_at: Option<DateTime<Utc>>,
+    pub error: Option<String>,
+
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, sqlx::Type, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(rename_all = "lowercase")]
+pub enum GiftCardPurchaseStatus {
+    /// Reserved; the provider order is being placed, funded or paid for
+    Pending,
+    /// Payment to the provider is being sent
+    Paying,
+    /// Paid; waiting for the provider to deliver the code
+    Processing,
+    Delivered,
+    Failed,
+}
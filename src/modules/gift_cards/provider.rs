@@ -0,0 +1,154 @@
+use async_trait::async_trait;
+
+use super::crud::GiftCardError;
+use super::schema::GiftCardResponse;
+use crate::services::amount::{self, Decimal};
+use crate::services::trocador::TrocadorClient;
+
+/// Order placed with a fulfillment provider, awaiting crypto payment
+#[derive(Debug, Clone)]
+pub struct ProviderOrder {
+    pub order_id: String,
+    pub payment_currency: String,
+    pub payment_network: String,
+    pub payment_amount: Decimal,
+    pub payment_address: String,
+    pub payment_extra_id: Option<String>,
+}
+
+/// Fulfillment state reported by the provider
+#[derive(Debug, Clone, PartialEq)]
+pub enum Fulfillment {
+    Pending,
+    Delivered { code: String },
+    Failed(String),
+}
+
+/// Adapter for gift card fulfillment providers
+#[async_trait]
+pub trait GiftCardProvider: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    async fn list_cards(&self, country: Option<&str>) -> Result<Vec<GiftCardResponse>, GiftCardError>;
+
+    async fn place_order(
+        &self,
+        card_id: &str,
+        amount: Decimal,
+        payment_currency: &str,
+        payment_network: &str,
+        email: &str,
+    ) -> Result<ProviderOrder, GiftCardError>;
+
+    async fn fetch_fulfillment(&self, order_id: &str) -> Result<Fulfillment, GiftCardError>;
+}
+
+// =============================================================================
+// TROCADOR
+// =============================================================================
+
+pub struct TrocadorGiftCardProvider {
+    client: TrocadorClient,
+}
+
+impl TrocadorGiftCardProvider {
+    pub fn new(api_key: String) -> Self {
+        Self { client: TrocadorClient::new(api_key) }
+    }
+
+    pub fn from_env() -> Self {
+        Self::new(std::env::var("TROCADOR_API_KEY").unwrap_or_default())
+    }
+}
+
+#[async_trait]
+impl GiftCardProvider for TrocadorGiftCardProvider {
+    fn name(&self) -> &'static str {
+        "trocador"
+    }
+
+    async fn list_cards(&self, country: Option<&str>) -> Result<Vec<GiftCardResponse>, GiftCardError> {
+        let cards = self.client.get_giftcards(country).await?;
+
+        // Trocador reports face values as JSON floats
+        let face_value = |value: f64| {
+            amount::from_f64(value).map_err(|e| GiftCardError::ProviderError(format!("Invalid card amount: {}", e)))
+        };
+
+        cards
+            .into_iter()
+            .map(|c| Ok(GiftCardResponse {
+                // product_id comes back as either a number or a string
+                card_id: match &c.product_id {
+                    serde_json::Value::String(s) => s.clone(),
+                    other => other.to_string(),
+                },
+                name: c.name,
+                category: c.category,
+                description: c.description,
+                country: c.country,
+                image_url: c.card_image_url,
+                min_amount: c.min_amount.map(face_value).transpose()?,
+                max_amount: c.max_amount.map(face_value).transpose()?,
+                denominations: c.denominations.into_iter().map(face_value).collect::<Result<_, _>>()?,
+                provider: self.name().to_string(),
+            }))
+            .collect()
+    }
+
+    async fn place_order(
+        &self,
+        card_id: &str,
+        amount: Decimal,
+        payment_currency: &str,
+        payment_network: &str,
+        email: &str,
+    ) -> Result<ProviderOrder, GiftCardError> {
+        let order = self
+            .client
+            .order_giftcard(card_id, payment_currency, payment_network, amount, email)
+            .await?;
+
+        // Trocador reports the amount to pay as a JSON float
+        let payment_amount = amount::from_f64(order.amount_from)
+            .map_err(|e| GiftCardError::ProviderError(format!("Invalid order amount: {}", e)))?;
+
+        Ok(ProviderOrder {
+            order_id: order.trade_id,
+            payment_currency: order.ticker_from,
+            payment_network: order.network_from,
+            payment_amount,
+            payment_address: order.address_provider,
+            payment_extra_id: order.address_provider_memo,
+        })
+    }
+
+    async fn fetch_fulfillment(&self, order_id: &str) -> Result<Fulfillment, GiftCardError> {
+        let trade = self.client.get_trade_raw(order_id).await?;
+        Ok(parse_trocador_fulfillment(&trade))
+    }
+}
+
+/// Extract redeem data from a Trocador trade payload
+pub fn parse_trocador_fulfillment(trade: &serde_json::Value) -> Fulfillment {
+    // /trade may return the trade wrapped in a single-element array
+    let trade = trade.as_array().and_then(|a| a.first()).unwrap_or(trade);
+
+    let status = trade.get("status").and_then(|s| s.as_str()).unwrap_or_default();
+    if matches!(status, "failed" | "expired" | "refunded") {
+        return Fulfillment::Failed(format!("Provider order {}", status));
+    }
+
+    let details = trade.get("details");
+    let code = details.and_then(|d| {
+        ["code", "redeem_code", "claim_code", "activation_link", "link"]
+            .iter()
+            .find_map(|k| d.get(*k).and_then(|v| v.as_str()))
+            .filter(|v| !v.is_empty())
+    });
+
+    match code {
+        Some(code) => Fulfillment::Delivered { code: code.to_string() },
+        None => Fulfillment::Pending,
+    }
+}
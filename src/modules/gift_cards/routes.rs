@@ -0,0 +1,13 @@
+use axum::Router;
+use std::sync::Arc;
+
+use crate::AppState;
+use crate::manifest::DeclaredRoutes;
+use super::controller::{get_catalog, get_purchase, purchase_gift_card};
+
+pub fn gift_card_routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .declared("getGiftCardCatalog", get_catalog)
+        .declared("purchaseGiftCard", purchase_gift_card)
+        .declared("getGiftCardPurchase", get_purchase)
+}
@@ -0,0 +1,143 @@
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::model::GiftCardPurchaseStatus;
+use crate::services::amount::{self, Decimal, WireAmount};
+
+// =============================================================================
+// CATALOG
+// =============================================================================
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CatalogQuery {
+    pub country: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GiftCardResponse {
+    pub card_id: String,
+    pub name: String,
+    pub category: Option<String>,
+    pub description: Option<String>,
+    pub country: Option<String>,
+    pub image_url: Option<String>,
+    #[serde(with = "rust_decimal::serde::float_option")]
+    #[schemars(with = "Option<f64>")]
+    pub min_amount: Option<Decimal>,
+    #[serde(with = "rust_decimal::serde::float_option")]
+    #[schemars(with = "Option<f64>")]
+    pub max_amount: Option<Decimal>,
+    #[serde(with = "amount::float_list")]
+    #[schemars(with = "Vec<f64>")]
+    pub denominations: Vec<Decimal>,
+    pub provider: String,
+}
+
+impl GiftCardResponse {
+    /// Whether the requested face value can be ordered for this card
+    pub fn accepts_amount(&self, amount: Decimal) -> bool {
+        if !self.denominations.is_empty() {
+            return self.denominations.contains(&amount);
+        }
+        let above_min = self.min_amount.map(|min| amount >= min).unwrap_or(true);
+        let below_max = self.max_amount.map(|max| amount <= max).unwrap_or(true);
+        above_min && below_max
+    }
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct CatalogResponse {
+    pub cards: Vec<GiftCardResponse>,
+}
+
+// =============================================================================
+// PURCHASE
+// =============================================================================
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct PurchaseGiftCardRequest {
+    pub card_id: String,
+    /// Face value; number or decimal string
+    pub amount: WireAmount,
+    /// Completed swap paid out to the buyer's balance; the card is paid from
+    /// that balance, up to what the swap credited
+    #[serde(default)]
+    pub swap_id: Option<String>,
+    /// Balance to pay from when there is no `swap_id`
+    #[serde(default)]
+    pub currency: Option<String>,
+    #[serde(default)]
+    pub network: Option<String>,
+    /// Delivery address for the code (defaults to account email)
+    #[serde(default)]
+    pub email: Option<String>,
+    #[serde(default)]
+    pub country: Option<String>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct GiftCardPurchaseResponse {
+    pub id: String,
+    pub card_id: String,
+    pub card_name: String,
+    #[serde(with = "rust_decimal::serde::float")]
+    #[schemars(with = "f64")]
+    pub amount: Decimal,
+    pub status: GiftCardPurchaseStatus,
+    pub funding_swap_id: Option<String>,
+    pub payment_currency: String,
+    pub payment_network: String,
+    #[serde(with = "rust_decimal::serde::float")]
+    #[schemars(with = "f64")]
+    pub payment_amount: Decimal,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payment_tx_hash: Option<String>,
+    pub code_delivered: bool,
+    pub delivered_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+// =============================================================================
+// TROCADOR API TYPES
+// =============================================================================
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TrocadorGiftCard {
+    pub product_id: serde_json::Value,
+    pub name: String,
+    pub category: Option<String>,
+    pub description: Option<String>,
+    pub country: Option<String>,
+    pub card_image_url: Option<String>,
+    pub min_amount: Option<f64>,
+    pub max_amount: Option<f64>,
+    #[serde(default)]
+    pub denominations: Vec<f64>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TrocadorGiftCardOrder {
+    pub trade_id: String,
+    pub ticker_from: String,
+    pub network_from: String,
+    pub amount_from: f64,
+    pub address_provider: String,
+    pub address_provider_memo: Option<String>,
+    pub status: String,
+}
+
+// =============================================================================
+// ERROR RESPONSE
+// =============================================================================
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct GiftCardErrorResponse {
+    pub error: String,
+}
+
+impl GiftCardErrorResponse {
+    pub fn new(error: impl Into<String>) -> Self {
+        Self { error: error.into() }
+    }
+}
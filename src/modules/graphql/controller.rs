@@ -0,0 +1,46 @@
+use axum::{extract::State, Json};
+use std::sync::{Arc, OnceLock};
+
+use crate::AppState;
+use crate::modules::auth::interface::{scope::HistoryRead, OptionalScoped};
+use super::query::{build_schema, ApiSchema, Viewer};
+use super::schema::{GraphQLRequest, GraphQLResponse};
+
+/// Built on first use; the schema is immutable, so one copy serves every request
+pub fn api_schema() -> &'static ApiSchema {
+    static SCHEMA: OnceLock<ApiSchema> = OnceLock::new();
+    SCHEMA.get_or_init(build_schema)
+}
+
+// =============================================================================
+// POST /graphql - Read-only GraphQL queries
+// =============================================================================
+
+/// Errors (including auth and complexity failures) come back in `errors`
+/// with a 200, per GraphQL-over-HTTP convention
+pub async fn graphql(
+    State(state): State<Arc<AppState>>,
+    user: OptionalScoped<HistoryRead>,
+    Json(payload): Json<GraphQLRequest>,
+) -> Json<GraphQLResponse> {
+    let mut request = async_graphql::Request::new(payload.query)
+        .data(state)
+        .data(Viewer(user.0));
+    if let Some(name) = payload.operation_name {
+        request = request.operation_name(name);
+    }
+    if let Some(variables) = payload.variables {
+        request = request.variables(async_graphql::Variables::from_json(variables));
+    }
+
+    let response = api_schema().execute(request).await;
+
+    Json(GraphQLResponse {
+        data: serde_json::to_value(&response.data).ok().filter(|v| !v.is_null()),
+        errors: response
+            .errors
+            .iter()
+            .filter_map(|e| serde_json::to_value(e).ok())
+            .collect(),
+    })
+}
@@ -0,0 +1,7 @@
+pub mod schema;
+pub mod query;
+pub mod controller;
+pub mod routes;
+
+pub use query::{build_schema, ApiSchema};
+pub use routes::graphql_routes;
@@ -0,0 +1,227 @@
+use async_graphql::{Context, EmptyMutation, EmptySubscription, ErrorExtensions, Object, Schema};
+use std::sync::Arc;
+
+use crate::AppState;
+use crate::modules::auth::model::User as UserModel;
+use crate::modules::swap::crud::{CurrenciesResult, ProvidersResult, SwapCrud, SwapError};
+use crate::modules::swap::schema::{CurrenciesQuery, HistoryQuery, ProvidersQuery, RateType, RatesQuery};
+use crate::services::amount::Decimal;
+use super::schema::{CurrencyObject, ProviderObject, RatesObject, SwapObject, SwapPage};
+
+pub type ApiSchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+
+/// Default ceiling on query cost; list fields cost `limit` times their children
+const DEFAULT_MAX_COMPLEXITY: usize = 500;
+const DEFAULT_MAX_DEPTH: usize = 8;
+const MAX_HISTORY_LIMIT: i32 = 100;
+
+/// The authenticated caller for this request, if any
+pub struct Viewer(pub Option<UserModel>);
+
+/// Build the read-only schema. Limits come from GRAPHQL_MAX_COMPLEXITY and
+/// GRAPHQL_MAX_DEPTH.
+pub fn build_schema() -> ApiSchema {
+    let max_complexity = env_limit("GRAPHQL_MAX_COMPLEXITY", DEFAULT_MAX_COMPLEXITY);
+    let max_depth = env_limit("GRAPHQL_MAX_DEPTH", DEFAULT_MAX_DEPTH);
+
+    Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+        .limit_complexity(max_complexity)
+        .limit_depth(max_depth)
+        .finish()
+}
+
+fn env_limit(name: &str, default: usize) -> usize {
+    std::env::var(name)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .filter(|v| *v > 0)
+        .unwrap_or(default)
+}
+
+fn swap_crud(ctx: &Context<'_>) -> async_graphql::Result<SwapCrud> {
+    let state = ctx.data::<Arc<AppState>>()?;
+    Ok(SwapCrud::new(state.db.clone(), Some(state.redis.clone()), Some(state.wallet_mnemonic.clone())))
+}
+
+fn viewer<'a>(ctx: &'a Context<'_>) -> async_graphql::Result<&'a UserModel> {
+    ctx.data::<Viewer>()
+        .ok()
+        .and_then(|v| v.0.as_ref())
+        .ok_or_else(|| async_graphql::Error::new("Authentication required").extend_with(|_, e| e.set("code", "UNAUTHENTICATED")))
+}
+
+/// Same status mapping as the REST controllers, carried as an error code
+fn to_error(e: SwapError) -> async_graphql::Error {
+    let code = match e {
+        SwapError::SwapNotFound | SwapError::PairNotAvailable => "NOT_FOUND",
+        SwapError::InvalidCursor(_)
+        | SwapError::InvalidAddress
+        | SwapError::AmountOutOfRange { .. }
+        | SwapError::InvalidBridgeRoute(_)
+        | SwapError::InvalidQuote(_)
+        | SwapError::InvalidClientData(_)
+        | SwapError::InvalidAmount(_) => "BAD_REQUEST",
+        SwapError::ExternalApiError(_) | SwapError::ProviderUnavailable(_) => "BAD_GATEWAY",
+        SwapError::TradingHalted(_) => "SERVICE_UNAVAILABLE",
+        SwapError::KycRequired { .. } => "FORBIDDEN",
+        _ => "INTERNAL_SERVER_ERROR",
+    };
+    async_graphql::Error::new(e.to_string()).extend_with(|_, ext| ext.set("code", code))
+}
+
+fn from_raw_json<T: serde::de::DeserializeOwned>(json: &str) -> async_graphql::Result<Vec<T>> {
+    serde_json::from_str(json).map_err(|e| async_graphql::Error::new(format!("Invalid cached data: {}", e)))
+}
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    /// A single swap by id, like `GET /swap/{id}`
+    async fn swap(&self, ctx: &Context<'_>, id: String) -> async_graphql::Result<SwapObject> {
+        let status = swap_crud(ctx)?.get_swap_status(&id).await.map_err(to_error)?;
+        Ok(status.into())
+    }
+
+    /// The authenticated user's swap history, newest first
+    #[graphql(complexity = "limit.unwrap_or(20).clamp(1, MAX_HISTORY_LIMIT) as usize * child_complexity")]
+    #[allow(clippy::too_many_arguments)]
+    async fn swaps(
+        &self,
+        ctx: &Context<'_>,
+        cursor: Option<String>,
+        limit: Option<i32>,
+        status: Option<String>,
+        from_currency: Option<String>,
+        to_currency: Option<String>,
+        provider: Option<String>,
+    ) -> async_graphql::Result<SwapPage> {
+        let user = viewer(ctx)?;
+        let query = HistoryQuery {
+            cursor,
+            limit: limit.unwrap_or(20).clamp(1, MAX_HISTORY_LIMIT) as u32,
+            status,
+            from_currency,
+            to_currency,
+            provider,
+            swap_type: None,
+            client_reference: None,
+            date_from: None,
+            date_to: None,
+            sort_by: None,
+            sort_order: None,
+            fields: None,
+        };
+
+        let history = swap_crud(ctx)?.get_swap_history(&user.id, query).await.map_err(to_error)?;
+        Ok(history.into())
+    }
+
+    /// Active exchange providers
+    async fn providers(
+        &self,
+        ctx: &Context<'_>,
+        rating: Option<String>,
+        markup_enabled: Option<bool>,
+        sort: Option<String>,
+    ) -> async_graphql::Result<Vec<ProviderObject>> {
+        let query = ProvidersQuery { rating, markup_enabled, sort, fields: None };
+        match swap_crud(ctx)?.get_providers_optimized(query).await.map_err(to_error)? {
+            ProvidersResult::Structured(providers) => Ok(providers.into_iter().map(Into::into).collect()),
+            ProvidersResult::RawJson(json) => from_raw_json(&json),
+        }
+    }
+
+    /// Supported currencies, paginated like `GET /swap/currencies`
+    #[graphql(complexity = "limit.unwrap_or(100).max(1) as usize * child_complexity")]
+    async fn currencies(
+        &self,
+        ctx: &Context<'_>,
+        ticker: Option<String>,
+        network: Option<String>,
+        memo: Option<bool>,
+        page: Option<i32>,
+        limit: Option<i32>,
+    ) -> async_graphql::Result<Vec<CurrencyObject>> {
+        let query = CurrenciesQuery {
+            ticker,
+            network,
+            memo,
+            page: page.map(|p| p.max(1) as usize),
+            limit: limit.map(|l| l.max(1) as usize),
+            fields: None,
+        };
+        match swap_crud(ctx)?.get_currencies_optimized(query).await.map_err(to_error)? {
+            CurrenciesResult::Structured(currencies) => Ok(currencies.into_iter().map(Into::into).collect()),
+            CurrenciesResult::RawJson(json) => from_raw_json(&json),
+        }
+    }
+
+    /// Live quotes from all providers. Each call reaches out to the
+    /// aggregator, so it is weighted heavier than cached lookups.
+    #[graphql(complexity = "10 + child_complexity")]
+    #[allow(clippy::too_many_arguments)]
+    async fn rates(
+        &self,
+        ctx: &Context<'_>,
+        from: String,
+        network_from: String,
+        to: String,
+        network_to: String,
+        amount: Decimal,
+        fixed: Option<bool>,
+        provider: Option<String>,
+    ) -> async_graphql::Result<RatesObject> {
+        let crud = swap_crud(ctx)?;
+        let amount = crud.normalize_amount(amount.into(), &from, &network_from).await.map_err(to_error)?;
+        let query = RatesQuery {
+            from,
+            network_from,
+            to,
+            network_to,
+            amount,
+            rate_type: fixed.map(|f| if f { RateType::Fixed } else { RateType::Floating }),
+            provider,
+        };
+
+        let user_id = ctx.data::<Viewer>().ok().and_then(|v| v.0.as_ref()).map(|u| u.id.as_str());
+        let rates = crud.get_rates_optimized(&query, user_id).await.map_err(to_error)?;
+        Ok(rates.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_swaps_requires_authentication() {
+        let schema = build_schema();
+        let request = async_graphql::Request::new("{ swaps { hasMore } }").data(Viewer(None));
+        let response = schema.execute(request).await;
+
+        assert_eq!(response.errors.len(), 1);
+        assert_eq!(response.errors[0].message, "Authentication required");
+    }
+
+    #[tokio::test]
+    async fn test_complexity_limit_rejects_expensive_queries() {
+        let schema = Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+            .limit_complexity(50)
+            .finish();
+        let request = async_graphql::Request::new("{ swaps(limit: 100) { swaps { id status } } }")
+            .data(Viewer(None));
+        let response = schema.execute(request).await;
+
+        assert!(response.errors.iter().any(|e| e.message.contains("too complex")));
+    }
+
+    #[test]
+    fn test_sdl_exposes_read_only_queries() {
+        let sdl = build_schema().sdl();
+        for field in ["swap(", "swaps(", "providers(", "currencies(", "rates("] {
+            assert!(sdl.contains(field), "missing {}", field);
+        }
+        assert!(!sdl.contains("type Mutation"));
+    }
+}
@@ -0,0 +1,11 @@
+use axum::Router;
+use std::sync::Arc;
+
+use crate::AppState;
+use crate::manifest::DeclaredRoutes;
+use super::controller::graphql;
+
+pub fn graphql_routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .declared("graphql", graphql)
+}
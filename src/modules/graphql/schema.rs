@@ -0,0 +1,303 @@
+use async_graphql::SimpleObject;
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::modules::swap::schema as swap;
+use crate::services::amount::Decimal;
+
+// =============================================================================
+// HTTP ENVELOPE
+// =============================================================================
+
+/// Standard GraphQL-over-HTTP request body
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphQLRequest {
+    pub query: String,
+    #[serde(default)]
+    pub operation_name: Option<String>,
+    #[serde(default)]
+    pub variables: Option<serde_json::Value>,
+}
+
+/// Standard GraphQL-over-HTTP response body
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct GraphQLResponse {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<serde_json::Value>,
+}
+
+// =============================================================================
+// OBJECTS
+// =============================================================================
+
+/// Full swap state, the GraphQL view of `GET /swap/{id}`
+#[derive(Debug, SimpleObject)]
+#[graphql(name = "Swap")]
+pub struct SwapObject {
+    pub id: String,
+    pub provider: String,
+    pub provider_swap_id: Option<String>,
+    pub status: String,
+    pub from: String,
+    pub to: String,
+    pub amount: Decimal,
+    pub deposit_address: String,
+    pub deposit_extra_id: Option<String>,
+    pub recipient_address: String,
+    pub recipient_extra_id: Option<String>,
+    pub rate: Decimal,
+    pub estimated_receive: Decimal,
+    pub actual_receive: Option<Decimal>,
+    pub network_fee: Decimal,
+    pub total_fee: Decimal,
+    pub rate_type: String,
+    pub is_sandbox: bool,
+    pub tx_hash_in: Option<String>,
+    pub tx_hash_out: Option<String>,
+    pub tx_in_url: Option<String>,
+    pub tx_out_url: Option<String>,
+    pub deposit_address_url: Option<String>,
+    pub recipient_address_url: Option<String>,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+impl From<swap::SwapStatusResponse> for SwapObject {
+    fn from(s: swap::SwapStatusResponse) -> Self {
+        Self {
+            id: s.swap_id,
+            provider: s.provider,
+            provider_swap_id: s.provider_swap_id,
+            status: s.status.to_string(),
+            from: s.from,
+            to: s.to,
+            amount: s.amount,
+            deposit_address: s.deposit_address,
+            deposit_extra_id: s.deposit_extra_id,
+            recipient_address: s.recipient_address,
+            recipient_extra_id: s.recipient_extra_id,
+            rate: s.rate,
+            estimated_receive: s.estimated_receive,
+            actual_receive: s.actual_receive,
+            network_fee: s.network_fee,
+            total_fee: s.total_fee,
+            rate_type: rate_type_name(&s.rate_type),
+            is_sandbox: s.is_sandbox,
+            tx_hash_in: s.tx_hash_in,
+            tx_hash_out: s.tx_hash_out,
+            tx_in_url: s.explorer.tx_in_url,
+            tx_out_url: s.explorer.tx_out_url,
+            deposit_address_url: s.explorer.deposit_address_url,
+            recipient_address_url: s.explorer.recipient_address_url,
+            error: s.error,
+            created_at: s.created_at,
+            updated_at: s.updated_at,
+            expires_at: s.expires_at,
+            completed_at: s.completed_at,
+        }
+    }
+}
+
+/// One row of the viewer's swap history
+#[derive(Debug, SimpleObject)]
+pub struct SwapSummaryObject {
+    pub id: String,
+    pub status: String,
+    pub from_currency: String,
+    pub from_network: String,
+    pub to_currency: String,
+    pub to_network: String,
+    pub amount: Decimal,
+    pub estimated_receive: Decimal,
+    pub actual_receive: Option<Decimal>,
+    pub rate: Decimal,
+    pub platform_fee: Decimal,
+    pub total_fee: Decimal,
+    pub deposit_address: String,
+    pub recipient_address: String,
+    pub provider: String,
+    pub rate_type: String,
+    pub swap_type: String,
+    pub is_sandbox: bool,
+    pub created_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+impl From<swap::SwapSummary> for SwapSummaryObject {
+    fn from(s: swap::SwapSummary) -> Self {
+        Self {
+            id: s.id,
+            status: s.status.to_string(),
+            from_currency: s.from_currency,
+            from_network: s.from_network,
+            to_currency: s.to_currency,
+            to_network: s.to_network,
+            amount: s.amount,
+            estimated_receive: s.estimated_receive,
+            actual_receive: s.actual_receive,
+            rate: s.rate,
+            platform_fee: s.platform_fee,
+            total_fee: s.total_fee,
+            deposit_address: s.deposit_address,
+            recipient_address: s.recipient_address,
+            provider: s.provider,
+            rate_type: rate_type_name(&s.rate_type),
+            swap_type: s.swap_type.as_str().to_string(),
+            is_sandbox: s.is_sandbox,
+            created_at: s.created_at,
+            completed_at: s.completed_at,
+        }
+    }
+}
+
+/// A page of swap history; pass `next_cursor` back as `cursor`
+#[derive(Debug, SimpleObject)]
+pub struct SwapPage {
+    pub swaps: Vec<SwapSummaryObject>,
+    pub has_more: bool,
+    pub next_cursor: Option<String>,
+}
+
+impl From<swap::HistoryResponse> for SwapPage {
+    fn from(h: swap::HistoryResponse) -> Self {
+        Self {
+            swaps: h.swaps.into_iter().map(Into::into).collect(),
+            has_more: h.pagination.has_more,
+            next_cursor: h.pagination.next_cursor,
+        }
+    }
+}
+
+/// Mirrors `swap::ProviderResponse`; also read back from the cached raw JSON
+#[derive(Debug, Deserialize, SimpleObject)]
+#[graphql(name = "Provider")]
+pub struct ProviderObject {
+    pub name: String,
+    pub rating: String,
+    pub insurance: f64,
+    pub markup_enabled: bool,
+    pub eta: i32,
+}
+
+impl From<swap::ProviderResponse> for ProviderObject {
+    fn from(p: swap::ProviderResponse) -> Self {
+        Self {
+            name: p.name,
+            rating: p.rating,
+            insurance: p.insurance,
+            markup_enabled: p.markup_enabled,
+            eta: p.eta,
+        }
+    }
+}
+
+/// Mirrors `swap::CurrencyResponse`; also read back from the cached raw JSON
+#[derive(Debug, Deserialize, SimpleObject)]
+#[graphql(name = "Currency")]
+pub struct CurrencyObject {
+    pub name: String,
+    pub ticker: String,
+    pub network: String,
+    pub memo: bool,
+    pub image: String,
+    pub minimum: f64,
+    pub maximum: f64,
+}
+
+impl From<swap::CurrencyResponse> for CurrencyObject {
+    fn from(c: swap::CurrencyResponse) -> Self {
+        Self {
+            name: c.name,
+            ticker: c.ticker,
+            network: c.network,
+            memo: c.memo,
+            image: c.image,
+            minimum: c.minimum,
+            maximum: c.maximum,
+        }
+    }
+}
+
+#[derive(Debug, SimpleObject)]
+#[graphql(name = "Rate")]
+pub struct RateObject {
+    pub provider: String,
+    pub provider_name: String,
+    pub rate: Decimal,
+    pub estimated_amount: Decimal,
+    pub min_amount: Decimal,
+    pub max_amount: Decimal,
+    pub network_fee: Decimal,
+    pub provider_fee: Decimal,
+    pub platform_fee: Decimal,
+    pub total_fee: Decimal,
+    pub rate_type: String,
+    pub kyc_required: bool,
+    pub kyc_rating: Option<String>,
+    pub eta_minutes: Option<u32>,
+}
+
+impl From<swap::RateResponse> for RateObject {
+    fn from(r: swap::RateResponse) -> Self {
+        Self {
+            provider: r.provider,
+            provider_name: r.provider_name,
+            rate: r.rate,
+            estimated_amount: r.estimated_amount,
+            min_amount: r.min_amount,
+            max_amount: r.max_amount,
+            network_fee: r.network_fee,
+            provider_fee: r.provider_fee,
+            platform_fee: r.platform_fee,
+            total_fee: r.total_fee,
+            rate_type: rate_type_name(&r.rate_type),
+            kyc_required: r.kyc_required,
+            kyc_rating: r.kyc_rating,
+            eta_minutes: r.eta_minutes,
+        }
+    }
+}
+
+#[derive(Debug, SimpleObject)]
+#[graphql(name = "Rates")]
+pub struct RatesObject {
+    pub trade_id: String,
+    pub from: String,
+    pub network_from: String,
+    pub to: String,
+    pub network_to: String,
+    pub amount: Decimal,
+    pub rates: Vec<RateObject>,
+    /// Some providers missed the deadline and are not in `rates`
+    pub partial: bool,
+}
+
+impl From<swap::RatesResponse> for RatesObject {
+    fn from(r: swap::RatesResponse) -> Self {
+        Self {
+            trade_id: r.trade_id,
+            from: r.from,
+            network_from: r.network_from,
+            to: r.to,
+            network_to: r.network_to,
+            amount: r.amount,
+            rates: r.rates.into_iter().map(Into::into).collect(),
+            partial: r.partial,
+        }
+    }
+}
+
+fn rate_type_name(rate_type: &swap::RateType) -> String {
+    match rate_type {
+        swap::RateType::Fixed => "fixed",
+        swap::RateType::Floating => "floating",
+    }
+    .to_string()
+}
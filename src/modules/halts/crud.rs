@@ -0,0 +1,388 @@
+use axum::http::StatusCode;
+use sqlx::{MySql, Pool};
+use std::sync::{Arc, OnceLock, RwLock};
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+use crate::services::explorer::chain_key;
+use super::model::{HaltScope, TradingHalt};
+
+/// Rates and swap creation read halts on every request, so the set is cached
+/// per process. Toggles invalidate it locally; other instances pick changes
+/// up within this window.
+const HALT_CACHE_TTL: Duration = Duration::from_secs(5);
+
+const HALT_COLUMNS: &str = r#"
+    id, CAST(scope AS CHAR) as scope, currency, network, to_currency, to_network,
+    reason, CAST(source AS CHAR) as source, created_by, created_at
+"#;
+
+/// Halts and when they were read
+type HaltCache = RwLock<Option<(Instant, Arc<HaltSet>)>>;
+
+static HALT_CACHE: OnceLock<HaltCache> = OnceLock::new();
+
+fn halt_cache() -> &'static HaltCache {
+    HALT_CACHE.get_or_init(|| RwLock::new(None))
+}
+
+/// Drop the cached halt set so the next check reads the table
+pub fn invalidate_halt_cache() {
+    if let Ok(mut cache) = halt_cache().write() {
+        *cache = None;
+    }
+}
+
+// =============================================================================
+// HALT ERROR
+// =============================================================================
+
+#[derive(Debug)]
+pub enum HaltError {
+    HaltNotFound,
+    DatabaseError(String),
+}
+
+impl std::fmt::Display for HaltError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HaltError::HaltNotFound => write!(f, "Trading halt not found"),
+            HaltError::DatabaseError(e) => write!(f, "Database error: {}", e),
+        }
+    }
+}
+
+impl HaltError {
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            HaltError::HaltNotFound => StatusCode::NOT_FOUND,
+            HaltError::DatabaseError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+impl From<sqlx::Error> for HaltError {
+    fn from(err: sqlx::Error) -> Self {
+        HaltError::DatabaseError(err.to_string())
+    }
+}
+
+// =============================================================================
+// HALT SET
+// =============================================================================
+
+/// Active halts, matched against a requested pair
+#[derive(Debug, Default)]
+pub struct HaltSet {
+    halts: Vec<TradingHalt>,
+}
+
+impl HaltSet {
+    pub fn new(halts: Vec<TradingHalt>) -> Self {
+        Self { halts }
+    }
+
+    /// The first halt that blocks swapping `from` on `network_from` into `to`
+    /// on `network_to`. Networks are compared by chain, so "ERC20" and
+    /// "ethereum" name the same leg.
+    pub fn blocking(&self, from: &str, network_from: &str, to: &str, network_to: &str) -> Option<&TradingHalt> {
+        let from = Leg::new(from, network_from);
+        let to = Leg::new(to, network_to);
+
+        self.halts.iter().find(|halt| match halt.scope {
+            HaltScope::Currency => {
+                let halted = Leg::new(&halt.currency, &halt.network);
+                halted == from || halted == to
+            }
+            HaltScope::Pair => {
+                let a = Leg::new(&halt.currency, &halt.network);
+                let b = Leg::new(&halt.to_currency, &halt.to_network);
+                (a == from && b == to) || (a == to && b == from)
+            }
+            HaltScope::Chain => from.chain == halt.network || to.chain == halt.network,
+        })
+    }
+
+    /// Halts covering a whole chain
+    pub fn chain_halts(&self) -> impl Iterator<Item = &TradingHalt> {
+        self.halts.iter().filter(|halt| halt.scope == HaltScope::Chain)
+    }
+
+    /// The halt covering all of `chain`, if any
+    pub fn chain_halt(&self, chain: &str) -> Option<&TradingHalt> {
+        self.chain_halts().find(|halt| halt.network == chain)
+    }
+}
+
+#[derive(PartialEq)]
+struct Leg {
+    currency: String,
+    chain: String,
+}
+
+impl Leg {
+    fn new(currency: &str, network: &str) -> Self {
+        Self {
+            currency: currency.trim().to_lowercase(),
+            chain: chain_key(currency, network),
+        }
+    }
+}
+
+fn normalize(value: &str) -> String {
+    value.trim().to_lowercase()
+}
+
+/// Pair legs in a stable order so A->B and B->A share one row
+fn sorted_legs(
+    from: &str,
+    network_from: &str,
+    to: &str,
+    network_to: &str,
+) -> ((String, String), (String, String)) {
+    let a = (normalize(from), normalize(network_from));
+    let b = (normalize(to), normalize(network_to));
+    if a <= b { (a, b) } else { (b, a) }
+}
+
+// =============================================================================
+// HALT CRUD
+// =============================================================================
+
+pub struct HaltCrud {
+    pool: Pool<MySql>,
+}
+
+impl HaltCrud {
+    pub fn new(pool: Pool<MySql>) -> Self {
+        Self { pool }
+    }
+
+    pub async fn list_halts(&self) -> Result<Vec<TradingHalt>, HaltError> {
+        let halts = sqlx::query_as::<_, TradingHalt>(&format!(
+            "SELECT {} FROM trading_halts ORDER BY created_at DESC",
+            HALT_COLUMNS
+        ))
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(halts)
+    }
+
+    /// Active halts, served from the process cache when fresh
+    pub async fn active_halts(&self) -> Result<Arc<HaltSet>, HaltError> {
+        if let Ok(cache) = halt_cache().read() {
+            if let Some((loaded_at, set)) = cache.as_ref() {
+                if loaded_at.elapsed() < HALT_CACHE_TTL {
+                    return Ok(set.clone());
+                }
+            }
+        }
+
+        let set = Arc::new(HaltSet::new(self.list_halts().await?));
+        if let Ok(mut cache) = halt_cache().write() {
+            *cache = Some((Instant::now(), set.clone()));
+        }
+        Ok(set)
+    }
+
+    /// The halt blocking a pair, if any
+    pub async fn check_pair(
+        &self,
+        from: &str,
+        network_from: &str,
+        to: &str,
+        network_to: &str,
+    ) -> Result<Option<TradingHalt>, HaltError> {
+        let set = self.active_halts().await?;
+        Ok(set.blocking(from, network_from, to, network_to).cloned())
+    }
+
+    /// Enable or disable a currency on one network. Returns the halt when
+    /// disabling.
+    pub async fn set_currency_enabled(
+        &self,
+        admin_id: &str,
+        currency: &str,
+        network: &str,
+        enabled: bool,
+        reason: Option<&str>,
+    ) -> Result<Option<TradingHalt>, HaltError> {
+        let target = (normalize(currency), normalize(network), String::new(), String::new());
+        self.set_enabled(admin_id, HaltScope::Currency, target, enabled, reason).await
+    }
+
+    /// Enable or disable a pair in both directions. Returns the halt when
+    /// disabling.
+    pub async fn set_pair_enabled(
+        &self,
+        admin_id: &str,
+        (from, network_from): (&str, &str),
+        (to, network_to): (&str, &str),
+        enabled: bool,
+        reason: Option<&str>,
+    ) -> Result<Option<TradingHalt>, HaltError> {
+        let ((a, a_net), (b, b_net)) = sorted_legs(from, network_from, to, network_to);
+        self.set_enabled(admin_id, HaltScope::Pair, (a, a_net, b, b_net), enabled, reason).await
+    }
+
+    async fn set_enabled(
+        &self,
+        admin_id: &str,
+        scope: HaltScope,
+        (currency, network, to_currency, to_network): (String, String, String, String),
+        enabled: bool,
+        reason: Option<&str>,
+    ) -> Result<Option<TradingHalt>, HaltError> {
+        if enabled {
+            sqlx::query(
+                r#"
+                DELETE FROM trading_halts
+                WHERE scope = ? AND currency = ? AND network = ? AND to_currency = ? AND to_network = ?
+                "#,
+            )
+            .bind(scope.as_str())
+            .bind(&currency)
+            .bind(&network)
+            .bind(&to_currency)
+            .bind(&to_network)
+            .execute(&self.pool)
+            .await?;
+            invalidate_halt_cache();
+            return Ok(None);
+        }
+
+        // Re-disabling updates the reason; an operator takes over an automatic halt
+        sqlx::query(
+            r#"
+            INSERT INTO trading_halts (id, scope, currency, network, to_currency, to_network, reason, source, created_by)
+            VALUES (?, ?, ?, ?, ?, ?, ?, 'manual', ?)
+            ON DUPLICATE KEY UPDATE reason = VALUES(reason), source = 'manual', created_by = VALUES(created_by)
+            "#,
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(scope.as_str())
+        .bind(&currency)
+        .bind(&network)
+        .bind(&to_currency)
+        .bind(&to_network)
+        .bind(reason)
+        .bind(admin_id)
+        .execute(&self.pool)
+        .await?;
+        invalidate_halt_cache();
+
+        let halt = sqlx::query_as::<_, TradingHalt>(&format!(
+            r#"
+            SELECT {} FROM trading_halts
+            WHERE scope = ? AND currency = ? AND network = ? AND to_currency = ? AND to_network = ?
+            "#,
+            HALT_COLUMNS
+        ))
+        .bind(scope.as_str())
+        .bind(&currency)
+        .bind(&network)
+        .bind(&to_currency)
+        .bind(&to_network)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        halt.ok_or(HaltError::HaltNotFound).map(Some)
+    }
+
+    /// Remove any halt by id, including automatic ones
+    pub async fn lift_halt(&self, halt_id: &str) -> Result<(), HaltError> {
+        let result = sqlx::query("DELETE FROM trading_halts WHERE id = ?")
+            .bind(halt_id)
+            .execute(&self.pool)
+            .await?;
+        invalidate_halt_cache();
+
+        if result.rows_affected() == 0 {
+            return Err(HaltError::HaltNotFound);
+        }
+        Ok(())
+    }
+
+    /// Halt a whole chain on behalf of the RPC health guard. Returns true when
+    /// the halt is new.
+    pub async fn halt_chain(&self, chain: &str, reason: &str) -> Result<bool, HaltError> {
+        let result = sqlx::query(
+            r#"
+            INSERT IGNORE INTO trading_halts (id, scope, network, reason, source)
+            VALUES (?, 'chain', ?, ?, 'rpc_health')
+            "#,
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(normalize(chain))
+        .bind(reason)
+        .execute(&self.pool)
+        .await?;
+        invalidate_halt_cache();
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    /// Lift an automatic chain halt. Manual chain halts stay in place.
+    pub async fn resume_chain(&self, chain: &str) -> Result<bool, HaltError> {
+        let result = sqlx::query(
+            "DELETE FROM trading_halts WHERE scope = 'chain' AND network = ? AND source = 'rpc_health'",
+        )
+        .bind(normalize(chain))
+        .execute(&self.pool)
+        .await?;
+        invalidate_halt_cache();
+
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::halts::model::HaltSource;
+    use chrono::Utc;
+
+    fn halt(scope: HaltScope, currency: &str, network: &str, to_currency: &str, to_network: &str) -> TradingHalt {
+        TradingHalt {
+            id: Uuid::new_v4().to_string(),
+            scope,
+            currency: currency.to_string(),
+            network: network.to_string(),
+            to_currency: to_currency.to_string(),
+            to_network: to_network.to_string(),
+            reason: None,
+            source: HaltSource::Manual,
+            created_by: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_currency_halt_blocks_either_leg() {
+        let set = HaltSet::new(vec![halt(HaltScope::Currency, "usdt", "erc20", "", "")]);
+
+        assert!(set.blocking("USDT", "ethereum", "btc", "Mainnet").is_some());
+        assert!(set.blocking("btc", "Mainnet", "usdt", "ERC20").is_some());
+        assert!(set.blocking("usdt", "TRC20", "btc", "Mainnet").is_none());
+    }
+
+    #[test]
+    fn test_pair_halt_covers_both_directions_only() {
+        let ((a, a_net), (b, b_net)) = sorted_legs("ETH", "ERC20", "BTC", "Mainnet");
+        let set = HaltSet::new(vec![halt(HaltScope::Pair, &a, &a_net, &b, &b_net)]);
+
+        assert!(set.blocking("eth", "erc20", "btc", "mainnet").is_some());
+        assert!(set.blocking("btc", "mainnet", "eth", "erc20").is_some());
+        assert!(set.blocking("eth", "erc20", "xmr", "mainnet").is_none());
+    }
+
+    #[test]
+    fn test_chain_halt_blocks_tokens_on_that_chain() {
+        let set = HaltSet::new(vec![halt(HaltScope::Chain, "", "bsc", "", "")]);
+
+        assert!(set.blocking("usdt", "BEP20", "btc", "Mainnet").is_some());
+        assert!(set.blocking("btc", "Mainnet", "bnb", "Mainnet").is_some());
+        assert!(set.blocking("usdt", "ERC20", "btc", "Mainnet").is_none());
+    }
+}
@@ -0,0 +1,77 @@
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+// =============================================================================
+// TRADING HALT
+// =============================================================================
+
+/// An active kill-switch entry. Currencies and networks are stored lowercased;
+/// pair legs are stored in sorted order since a pair halt covers both directions.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct TradingHalt {
+    pub id: String,
+    pub scope: HaltScope,
+    pub currency: String,
+    pub network: String,
+    pub to_currency: String,
+    pub to_network: String,
+    pub reason: Option<String>,
+    pub source: HaltSource,
+    pub created_by: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl TradingHalt {
+    /// What the halt covers, for error messages and logs
+    pub fn target(&self) -> String {
+        match self.scope {
+            HaltScope::Currency => format!("{} on {}", self.currency, self.network),
+            HaltScope::Pair => format!(
+                "{} ({}) <-> {} ({})",
+                self.currency, self.network, self.to_currency, self.to_network
+            ),
+            HaltScope::Chain => format!("all currencies on {}", self.network),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, sqlx::Type, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case")]
+pub enum HaltScope {
+    /// One currency on one network
+    Currency,
+    /// Two currency/network legs, either direction
+    Pair,
+    /// Every currency settling on a chain
+    Chain,
+}
+
+impl HaltScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HaltScope::Currency => "currency",
+            HaltScope::Pair => "pair",
+            HaltScope::Chain => "chain",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, sqlx::Type, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case")]
+pub enum HaltSource {
+    Manual,
+    RpcHealth,
+}
+
+impl HaltSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HaltSource::Manual => "manual",
+            HaltSource::RpcHealth => "rpc_health",
+        }
+    }
+}
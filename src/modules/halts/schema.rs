@@ -0,0 +1,96 @@
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use super::model::{HaltScope, HaltSource, TradingHalt};
+
+// =============================================================================
+// TOGGLES
+// =============================================================================
+
+#[derive(Debug, Deserialize, Validate, JsonSchema)]
+pub struct SetCurrencyEnabledRequest {
+    #[validate(length(min = 1, max = 20))]
+    pub currency: String,
+    #[validate(length(min = 1, max = 50))]
+    pub network: String,
+    pub enabled: bool,
+    #[validate(length(max = 255))]
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Validate, JsonSchema)]
+pub struct SetPairEnabledRequest {
+    #[validate(length(min = 1, max = 20))]
+    pub from: String,
+    #[validate(length(min = 1, max = 50))]
+    pub network_from: String,
+    #[validate(length(min = 1, max = 20))]
+    pub to: String,
+    #[validate(length(min = 1, max = 50))]
+    pub network_to: String,
+    pub enabled: bool,
+    #[validate(length(max = 255))]
+    pub reason: Option<String>,
+}
+
+// =============================================================================
+// RESPONSES
+// =============================================================================
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct TradingHaltResponse {
+    pub id: String,
+    pub scope: HaltScope,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub currency: Option<String>,
+    pub network: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to_currency: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to_network: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    pub source: HaltSource,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_by: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+fn non_empty(value: String) -> Option<String> {
+    (!value.is_empty()).then_some(value)
+}
+
+impl From<TradingHalt> for TradingHaltResponse {
+    fn from(h: TradingHalt) -> Self {
+        Self {
+            id: h.id,
+            scope: h.scope,
+            currency: non_empty(h.currency),
+            network: h.network,
+            to_currency: non_empty(h.to_currency),
+            to_network: non_empty(h.to_network),
+            reason: h.reason,
+            source: h.source,
+            created_by: h.created_by,
+            created_at: h.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct TradingHaltsResponse {
+    pub halts: Vec<TradingHaltResponse>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct HaltErrorResponse {
+    pub error: String,
+}
+
+impl HaltErrorResponse {
+    pub fn new(error: impl Into<String>) -> Self {
+        Self { error: error.into() }
+    }
+}
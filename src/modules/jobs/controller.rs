@@ -0,0 +1,37 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use std::sync::Arc;
+
+use crate::AppState;
+use crate::modules::auth::crud::UserCrud;
+use crate::modules::auth::interface::User;
+use super::crud::{JobCrud, JobError};
+use super::schema::{JobErrorResponse, JobResponse};
+
+// =============================================================================
+// GET /jobs/{id} - Status and progress of a background operation
+// =============================================================================
+
+pub async fn get_job(
+    State(state): State<Arc<AppState>>,
+    user: User,
+    Path(job_id): Path<String>,
+) -> Result<Json<JobResponse>, (StatusCode, Json<JobErrorResponse>)> {
+    let to_error = |e: JobError| (e.status_code(), Json(JobErrorResponse::new(e.to_string())));
+
+    // Admins can follow system jobs such as reconciliation runs
+    let is_admin = UserCrud::new(state.db.clone(), &state.jwt_service)
+        .is_admin(&user.0.id)
+        .await
+        .map_err(|e| to_error(e.into()))?;
+
+    let job = JobCrud::new(state.db.clone())
+        .get(&job_id, &user.0.id, is_admin)
+        .await
+        .map_err(to_error)?;
+
+    Ok(Json(job.into()))
+}
@@ -0,0 +1,175 @@
+use axum::http::StatusCode;
+use sqlx::{MySql, Pool, Transaction};
+
+use super::model::{Job, JobKind};
+
+const JOB_COLUMNS: &str = r#"
+    id, user_id, CAST(kind AS CHAR) as kind, CAST(status AS CHAR) as status,
+    progress, error, created_at, started_at, finished_at
+"#;
+
+// =============================================================================
+// JOB ERROR
+// =============================================================================
+
+#[derive(Debug)]
+pub enum JobError {
+    JobNotFound,
+    DatabaseError(String),
+}
+
+impl std::fmt::Display for JobError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JobError::JobNotFound => write!(f, "Job not found"),
+            JobError::DatabaseError(e) => write!(f, "Database error: {}", e),
+        }
+    }
+}
+
+impl JobError {
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            JobError::JobNotFound => StatusCode::NOT_FOUND,
+            JobError::DatabaseError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+impl From<sqlx::Error> for JobError {
+    fn from(err: sqlx::Error) -> Self {
+        JobError::DatabaseError(err.to_string())
+    }
+}
+
+// =============================================================================
+// JOB CRUD
+// =============================================================================
+
+pub struct JobCrud {
+    pool: Pool<MySql>,
+}
+
+impl JobCrud {
+    pub fn new(pool: Pool<MySql>) -> Self {
+        Self { pool }
+    }
+
+    /// Queue a job alongside the record it works on, in that record's
+    /// transaction, so one never exists without the other
+    pub async fn create(
+        tx: &mut Transaction<'_, MySql>,
+        id: &str,
+        kind: JobKind,
+        user_id: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query("INSERT INTO jobs (id, user_id, kind, status) VALUES (?, ?, ?, 'queued')")
+            .bind(id)
+            .bind(user_id)
+            .bind(kind)
+            .execute(&mut **tx)
+            .await?;
+        Ok(())
+    }
+
+    /// A job visible to this caller: their own, or any job for admins
+    pub async fn get(&self, job_id: &str, user_id: &str, is_admin: bool) -> Result<Job, JobError> {
+        let sql = format!("SELECT {} FROM jobs WHERE id = ? AND (user_id = ? OR ?)", JOB_COLUMNS);
+
+        sqlx::query_as::<_, Job>(&sql)
+            .bind(job_id)
+            .bind(user_id)
+            .bind(is_admin)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or(JobError::JobNotFound)
+    }
+
+    // =========================================================================
+    // TRANSITIONS
+    // =========================================================================
+
+    /// queued -> running. A retried job keeps its first start time.
+    pub async fn start(&self, job_id: &str) -> Result<(), JobError> {
+        sqlx::query(
+            r#"
+            UPDATE jobs
+            SET status = 'running', progress = 0, started_at = COALESCE(started_at, NOW())
+            WHERE id = ? AND status = 'queued'
+            "#,
+        )
+        .bind(job_id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Report progress of a running job, capped at 99 until it succeeds
+    pub async fn set_progress(&self, job_id: &str, percent: u8) -> Result<(), JobError> {
+        sqlx::query("UPDATE jobs SET progress = ? WHERE id = ? AND status = 'running'")
+            .bind(percent.min(99))
+            .bind(job_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn succeed(&self, job_id: &str) -> Result<(), JobError> {
+        sqlx::query(
+            r#"
+            UPDATE jobs
+            SET status = 'succeeded', progress = 100, error = NULL, finished_at = NOW()
+            WHERE id = ? AND status = 'running'
+            "#,
+        )
+        .bind(job_id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn fail(&self, job_id: &str, error: &str) -> Result<(), JobError> {
+        sqlx::query(
+            r#"
+            UPDATE jobs
+            SET status = 'failed', error = ?, finished_at = NOW()
+            WHERE id = ? AND status IN ('queued', 'running')
+            "#,
+        )
+        .bind(error)
+        .bind(job_id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// running -> queued, for work handed back to the queue after its
+    /// worker died
+    pub async fn requeue(&self, job_id: &str) -> Result<(), JobError> {
+        sqlx::query("UPDATE jobs SET status = 'queued', progress = 0 WHERE id = ? AND status = 'running'")
+            .bind(job_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+}
+
+/// Percent of `total` that `done` represents; 0 for an empty total
+pub fn percent(done: u64, total: u64) -> u8 {
+    if total == 0 {
+        return 0;
+    }
+    (done.min(total) * 100 / total) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percent() {
+        assert_eq!(percent(0, 0), 0);
+        assert_eq!(percent(1, 3), 33);
+        assert_eq!(percent(5, 4), 100);
+    }
+}
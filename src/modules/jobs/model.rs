@@ -0,0 +1,63 @@
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+// =============================================================================
+// JOB
+// =============================================================================
+
+/// Progress of a background operation. A job shares its id with the record
+/// it works on (an export, a reconciliation run), so clients poll
+/// GET /jobs/{id} with the id they were handed.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct Job {
+    pub id: String,
+    /// Owner; None for system jobs, which only admins can see
+    pub user_id: Option<String>,
+    pub kind: JobKind,
+    pub status: JobStatus,
+    /// 0-100
+    pub progress: u8,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, sqlx::Type, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(rename_all = "lowercase")]
+pub enum JobKind {
+    /// CSV export, see /exports
+    Export,
+    /// Provider reconciliation run
+    Reconciliation,
+    /// Encrypted snapshot of the critical tables
+    Backup,
+    /// HD wallet balance audit run
+    #[serde(rename = "wallet_audit")]
+    #[sqlx(rename = "wallet_audit")]
+    WalletAudit,
+}
+
+impl JobKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JobKind::Export => "export",
+            JobKind::Reconciliation => "reconciliation",
+            JobKind::Backup => "backup",
+            JobKind::WalletAudit => "wallet_audit",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, sqlx::Type, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(rename_all = "lowercase")]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Succeeded,
+    Failed,
+}
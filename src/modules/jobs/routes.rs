@@ -0,0 +1,10 @@
+use axum::Router;
+use std::sync::Arc;
+
+use crate::AppState;
+use crate::manifest::DeclaredRoutes;
+use super::controller::get_job;
+
+pub fn job_routes() -> Router<Arc<AppState>> {
+    Router::new().declared("getJob", get_job)
+}
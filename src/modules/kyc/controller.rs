@@ -0,0 +1,228 @@
+use axum::{
+    body::Bytes,
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    Json,
+};
+use base64::Engine;
+use std::sync::Arc;
+
+use crate::AppState;
+use crate::modules::auth::interface::{scope::AccountManage, Scoped};
+use crate::services::kyc::{kyc_provider, DocumentUpload, KycPolicy, KycProvider, ReviewOutcome};
+use super::crud::{KycCrud, KycError};
+use super::model::{KycLevel, KycStatus};
+use super::schema::{
+    KycDocumentResponse, KycErrorResponse, KycStatusResponse, KycWebhookResponse, StartVerificationRequest,
+    StartVerificationResponse, SubmitDocumentRequest,
+};
+
+const DOCUMENT_CONTENT_TYPES: &[&str] = &["image/jpeg", "image/png", "application/pdf"];
+
+fn to_error(e: KycError) -> (StatusCode, Json<KycErrorResponse>) {
+    (e.status_code(), Json(KycErrorResponse::new(e.to_string())))
+}
+
+fn provider() -> Result<Arc<dyn KycProvider>, (StatusCode, Json<KycErrorResponse>)> {
+    kyc_provider().ok_or_else(|| to_error(KycError::NotConfigured))
+}
+
+async fn status_response(crud: &KycCrud, user_id: &str) -> Result<KycStatusResponse, KycError> {
+    let applicant = crud.get_applicant(user_id).await?;
+    let documents = crud.list_documents(user_id).await?;
+    Ok(KycStatusResponse::new(applicant, documents))
+}
+
+// =============================================================================
+// GET /kyc - Verified level and the verification in progress
+// =============================================================================
+
+pub async fn get_kyc_status(
+    State(state): State<Arc<AppState>>,
+    user: Scoped<AccountManage>,
+) -> Result<Json<KycStatusResponse>, (StatusCode, Json<KycErrorResponse>)> {
+    let crud = KycCrud::new(state.db.clone());
+    Ok(Json(status_response(&crud, &user.0.id).await.map_err(to_error)?))
+}
+
+// =============================================================================
+// POST /kyc/verification - Start verifying to a level
+// =============================================================================
+
+pub async fn start_verification(
+    State(state): State<Arc<AppState>>,
+    user: Scoped<AccountManage>,
+    Json(payload): Json<StartVerificationRequest>,
+) -> Result<Json<StartVerificationResponse>, (StatusCode, Json<KycErrorResponse>)> {
+    let provider = provider()?;
+    let level = payload.level;
+    if level == KycLevel::None {
+        return Err(to_error(KycError::InvalidRequest("Choose the basic or full level".to_string())));
+    }
+
+    let crud = KycCrud::new(state.db.clone());
+    let user_id = &user.0.id;
+    match crud.get_applicant(user_id).await.map_err(to_error)? {
+        None => {
+            let applicant_id = provider.create_applicant(user_id, level).await.map_err(|e| to_error(e.into()))?;
+            crud.create_applicant(user_id, provider.name(), &applicant_id, level).await.map_err(to_error)?;
+            tracing::info!("KYC applicant created for {} at the {} level", user_id, level.as_str());
+        }
+        Some(applicant) if applicant.level >= level => {
+            return Err(to_error(KycError::AlreadyVerified(applicant.level)));
+        }
+        Some(applicant) if applicant.status == KycStatus::Rejected => {
+            return Err(to_error(KycError::NotAcceptingDocuments));
+        }
+        // Already underway at this level
+        Some(applicant) if applicant.requested_level == level => {}
+        Some(applicant) => {
+            if applicant.status == KycStatus::Pending {
+                return Err(to_error(KycError::NotAcceptingDocuments));
+            }
+            provider.change_level(&applicant.applicant_id, level).await.map_err(|e| to_error(e.into()))?;
+            crud.restart(user_id, level).await.map_err(to_error)?;
+        }
+    }
+
+    let sdk_token = provider.sdk_token(user_id, level).await.unwrap_or_else(|e| {
+        tracing::warn!("KYC SDK token for {} unavailable: {}", user_id, e);
+        None
+    });
+
+    Ok(Json(StartVerificationResponse {
+        status: status_response(&crud, user_id).await.map_err(to_error)?,
+        sdk_token,
+    }))
+}
+
+// =============================================================================
+// POST /kyc/documents - Forward a document image to the provider
+// =============================================================================
+
+pub async fn submit_document(
+    State(state): State<Arc<AppState>>,
+    user: Scoped<AccountManage>,
+    Json(payload): Json<SubmitDocumentRequest>,
+) -> Result<(StatusCode, Json<KycDocumentResponse>), (StatusCode, Json<KycErrorResponse>)> {
+    use validator::Validate;
+    if let Err(e) = payload.validate() {
+        return Err(to_error(KycError::InvalidRequest(e.to_string())));
+    }
+    let country = payload.country.trim().to_ascii_uppercase();
+    if !country.chars().all(|c| c.is_ascii_alphabetic()) {
+        return Err(to_error(KycError::InvalidRequest("country must be an ISO 3166-1 alpha-3 code".to_string())));
+    }
+    if payload.document_type.is_two_sided() && payload.side.is_none() {
+        return Err(to_error(KycError::InvalidRequest(format!(
+            "side is required for {}",
+            payload.document_type.as_str()
+        ))));
+    }
+    if !DOCUMENT_CONTENT_TYPES.contains(&payload.content_type.as_str()) {
+        return Err(to_error(KycError::InvalidRequest(format!(
+            "content_type must be one of {}",
+            DOCUMENT_CONTENT_TYPES.join(", ")
+        ))));
+    }
+    let content = base64::engine::general_purpose::STANDARD
+        .decode(payload.content.trim())
+        .map_err(|_| to_error(KycError::InvalidRequest("content is not valid base64".to_string())))?;
+    let max_bytes = KycPolicy::global().max_document_bytes;
+    if content.is_empty() {
+        return Err(to_error(KycError::InvalidRequest("content is empty".to_string())));
+    }
+    if content.len() > max_bytes {
+        return Err(to_error(KycError::DocumentTooLarge(max_bytes)));
+    }
+
+    let provider = provider()?;
+    let crud = KycCrud::new(state.db.clone());
+    let applicant = crud.get_applicant(&user.0.id).await.map_err(to_error)?.ok_or_else(|| to_error(KycError::NotStarted))?;
+    if !applicant.status.accepts_documents() {
+        return Err(to_error(KycError::NotAcceptingDocuments));
+    }
+
+    let upload = DocumentUpload {
+        document_type: payload.document_type,
+        side: payload.side,
+        country: &country,
+        file_name: &payload.file_name,
+        content_type: &payload.content_type,
+        content: &content,
+    };
+    let provider_document_id =
+        provider.upload_document(&applicant.applicant_id, &upload).await.map_err(|e| to_error(e.into()))?;
+    let document = crud
+        .record_document(&user.0.id, payload.document_type, payload.side, &country, provider_document_id.as_deref())
+        .await
+        .map_err(to_error)?;
+
+    Ok((StatusCode::CREATED, Json(document.into())))
+}
+
+// =============================================================================
+// POST /kyc/submit - Ask the provider to review the submitted documents
+// =============================================================================
+
+pub async fn submit_for_review(
+    State(state): State<Arc<AppState>>,
+    user: Scoped<AccountManage>,
+) -> Result<Json<KycStatusResponse>, (StatusCode, Json<KycErrorResponse>)> {
+    let provider = provider()?;
+    let crud = KycCrud::new(state.db.clone());
+    let user_id = &user.0.id;
+    let applicant = crud.get_applicant(user_id).await.map_err(to_error)?.ok_or_else(|| to_error(KycError::NotStarted))?;
+    if !applicant.status.accepts_documents() {
+        return Err(to_error(KycError::NotAcceptingDocuments));
+    }
+    if crud.list_documents(user_id).await.map_err(to_error)?.is_empty() {
+        return Err(to_error(KycError::NoDocuments));
+    }
+
+    provider.request_review(&applicant.applicant_id).await.map_err(|e| to_error(e.into()))?;
+    crud.mark_submitted(user_id).await.map_err(to_error)?;
+
+    Ok(Json(status_response(&crud, user_id).await.map_err(to_error)?))
+}
+
+// =============================================================================
+// POST /kyc/webhooks/{provider} - Review outcomes from the KYC provider
+// =============================================================================
+
+pub async fn ingest_webhook(
+    State(state): State<Arc<AppState>>,
+    Path(provider_name): Path<String>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Json<KycWebhookResponse>, (StatusCode, Json<KycErrorResponse>)> {
+    let provider = provider()?;
+    if provider.name() != provider_name {
+        return Err(to_error(KycError::UnknownProvider));
+    }
+
+    let event = match provider.parse_webhook(&headers, &body) {
+        Ok(Some(event)) => event,
+        Ok(None) => return Ok(Json(KycWebhookResponse { applied: false })),
+        Err(e) => return Err(to_error(e.into())),
+    };
+
+    let user_id = KycCrud::new(state.db.clone())
+        .apply_review(provider.name(), &event.applicant_id, &event.outcome)
+        .await
+        .map_err(to_error)?;
+    let Some(user_id) = user_id else {
+        tracing::warn!("KYC review for unknown {} applicant {}", provider.name(), event.applicant_id);
+        return Ok(Json(KycWebhookResponse { applied: false }));
+    };
+
+    match &event.outcome {
+        ReviewOutcome::Approved => tracing::info!("KYC approved for {}", user_id),
+        ReviewOutcome::Rejected { retry, .. } => {
+            tracing::info!("KYC rejected for {}{}", user_id, if *retry { ", resubmission allowed" } else { "" })
+        }
+        ReviewOutcome::Pending => tracing::debug!("KYC review pending for {}", user_id),
+    }
+
+    Ok(Json(KycWebhookResponse { applied: true }))
+}
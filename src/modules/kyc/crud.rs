@@ -0,0 +1,281 @@
+use axum::http::StatusCode;
+use sqlx::{MySql, Pool};
+use uuid::Uuid;
+
+use super::model::{DocumentSide, DocumentType, KycApplicant, KycDocument, KycLevel};
+use crate::services::kyc::{KycProviderError, ReviewOutcome};
+
+const APPLICANT_COLUMNS: &str = r#"
+    user_id, provider, applicant_id, CAST(level AS CHAR) as level, CAST(requested_level AS CHAR) as requested_level,
+    CAST(status AS CHAR) as status, reject_reason, submitted_at, reviewed_at, created_at, updated_at
+"#;
+
+const DOCUMENT_COLUMNS: &str = r#"
+    id, user_id, CAST(document_type AS CHAR) as document_type, CAST(side AS CHAR) as side, country,
+    provider_document_id, created_at
+"#;
+
+// =============================================================================
+// KYC ERROR
+// =============================================================================
+
+#[derive(Debug)]
+pub enum KycError {
+    NotConfigured,
+    NotStarted,
+    AlreadyVerified(KycLevel),
+    /// Under review or rejected for good
+    NotAcceptingDocuments,
+    NoDocuments,
+    InvalidRequest(String),
+    DocumentTooLarge(usize),
+    UnknownProvider,
+    InvalidWebhook(String),
+    Provider(KycProviderError),
+    DatabaseError(String),
+}
+
+impl std::fmt::Display for KycError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            KycError::NotConfigured => write!(f, "Identity verification is not available"),
+            KycError::NotStarted => write!(f, "Start a verification first"),
+            KycError::AlreadyVerified(level) => write!(f, "Already verified to the {} level", level.as_str()),
+            KycError::NotAcceptingDocuments => write!(f, "Documents can't be changed while under review or after a final rejection"),
+            KycError::NoDocuments => write!(f, "Submit at least one document before requesting a review"),
+            KycError::InvalidRequest(msg) => write!(f, "{}", msg),
+            KycError::DocumentTooLarge(max) => write!(f, "Documents are limited to {} bytes", max),
+            KycError::UnknownProvider => write!(f, "Unknown KYC provider"),
+            KycError::InvalidWebhook(msg) => write!(f, "Invalid webhook: {}", msg),
+            KycError::Provider(e) => write!(f, "{}", e),
+            KycError::DatabaseError(e) => write!(f, "Database error: {}", e),
+        }
+    }
+}
+
+impl KycError {
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            KycError::NotConfigured => StatusCode::SERVICE_UNAVAILABLE,
+            KycError::NotStarted => StatusCode::NOT_FOUND,
+            KycError::AlreadyVerified(_) | KycError::NotAcceptingDocuments | KycError::NoDocuments => {
+                StatusCode::CONFLICT
+            }
+            KycError::InvalidRequest(_) => StatusCode::BAD_REQUEST,
+            KycError::DocumentTooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
+            KycError::UnknownProvider => StatusCode::NOT_FOUND,
+            KycError::InvalidWebhook(_) => StatusCode::UNAUTHORIZED,
+            KycError::Provider(_) => StatusCode::BAD_GATEWAY,
+            KycError::DatabaseError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+impl From<sqlx::Error> for KycError {
+    fn from(err: sqlx::Error) -> Self {
+        KycError::DatabaseError(err.to_string())
+    }
+}
+
+impl From<KycProviderError> for KycError {
+    fn from(err: KycProviderError) -> Self {
+        match err {
+            KycProviderError::NotConfigured(_) => KycError::NotConfigured,
+            KycProviderError::InvalidSignature => KycError::InvalidWebhook(err.to_string()),
+            other => KycError::Provider(other),
+        }
+    }
+}
+
+// =============================================================================
+// KYC CRUD
+// =============================================================================
+
+pub struct KycCrud {
+    pool: Pool<MySql>,
+}
+
+impl KycCrud {
+    pub fn new(pool: Pool<MySql>) -> Self {
+        Self { pool }
+    }
+
+    pub async fn get_applicant(&self, user_id: &str) -> Result<Option<KycApplicant>, KycError> {
+        let applicant = sqlx::query_as::<_, KycApplicant>(&format!(
+            "SELECT {} FROM kyc_applicants WHERE user_id = ?",
+            APPLICANT_COLUMNS
+        ))
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(applicant)
+    }
+
+    /// Level the user has been verified to; `none` without an applicant
+    pub async fn verified_level(&self, user_id: &str) -> Result<KycLevel, KycError> {
+        let level: Option<(KycLevel,)> =
+            sqlx::query_as("SELECT CAST(level AS CHAR) as level FROM kyc_applicants WHERE user_id = ?")
+                .bind(user_id)
+                .fetch_optional(&self.pool)
+                .await?;
+
+        Ok(level.map(|(level,)| level).unwrap_or_default())
+    }
+
+    pub async fn create_applicant(
+        &self,
+        user_id: &str,
+        provider: &str,
+        applicant_id: &str,
+        level: KycLevel,
+    ) -> Result<KycApplicant, KycError> {
+        sqlx::query(
+            r#"
+            INSERT INTO kyc_applicants (user_id, provider, applicant_id, requested_level)
+            VALUES (?, ?, ?, ?)
+            "#,
+        )
+        .bind(user_id)
+        .bind(provider)
+        .bind(applicant_id)
+        .bind(level.as_str())
+        .execute(&self.pool)
+        .await?;
+
+        self.get_applicant(user_id).await?.ok_or(KycError::NotStarted)
+    }
+
+    /// Start a new verification at `level` on the existing applicant
+    pub async fn restart(&self, user_id: &str, level: KycLevel) -> Result<KycApplicant, KycError> {
+        sqlx::query(
+            r#"
+            UPDATE kyc_applicants
+            SET requested_level = ?, status = 'init', reject_reason = NULL, submitted_at = NULL, reviewed_at = NULL
+            WHERE user_id = ?
+            "#,
+        )
+        .bind(level.as_str())
+        .bind(user_id)
+        .execute(&self.pool)
+        .await?;
+
+        self.get_applicant(user_id).await?.ok_or(KycError::NotStarted)
+    }
+
+    pub async fn record_document(
+        &self,
+        user_id: &str,
+        document_type: DocumentType,
+        side: Option<DocumentSide>,
+        country: &str,
+        provider_document_id: Option<&str>,
+    ) -> Result<KycDocument, KycError> {
+        let id = Uuid::new_v4().to_string();
+        sqlx::query(
+            r#"
+            INSERT INTO kyc_documents (id, user_id, document_type, side, country, provider_document_id)
+            VALUES (?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&id)
+        .bind(user_id)
+        .bind(document_type.as_str())
+        .bind(side.map(|s| s.as_str()))
+        .bind(country)
+        .bind(provider_document_id)
+        .execute(&self.pool)
+        .await?;
+
+        let document = sqlx::query_as::<_, KycDocument>(&format!(
+            "SELECT {} FROM kyc_documents WHERE id = ?",
+            DOCUMENT_COLUMNS
+        ))
+        .bind(&id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(document)
+    }
+
+    /// Documents the user submitted, newest first
+    pub async fn list_documents(&self, user_id: &str) -> Result<Vec<KycDocument>, KycError> {
+        let documents = sqlx::query_as::<_, KycDocument>(&format!(
+            "SELECT {} FROM kyc_documents WHERE user_id = ? ORDER BY created_at DESC LIMIT 50",
+            DOCUMENT_COLUMNS
+        ))
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(documents)
+    }
+
+    /// Mark the verification as waiting for review. False when it was not
+    /// open for submission.
+    pub async fn mark_submitted(&self, user_id: &str) -> Result<bool, KycError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE kyc_applicants SET status = 'pending', submitted_at = NOW()
+            WHERE user_id = ? AND status IN ('init', 'retry')
+            "#,
+        )
+        .bind(user_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Apply a review reported by the provider. An approval grants the
+    /// requested level (never lowering one already held); a rejection
+    /// leaves the verified level as it was. Returns the user the applicant
+    /// belongs to, or None when the applicant is unknown.
+    pub async fn apply_review(
+        &self,
+        provider: &str,
+        applicant_id: &str,
+        outcome: &ReviewOutcome,
+    ) -> Result<Option<String>, KycError> {
+        let user_id: Option<(String,)> =
+            sqlx::query_as("SELECT user_id FROM kyc_applicants WHERE provider = ? AND applicant_id = ?")
+                .bind(provider)
+                .bind(applicant_id)
+                .fetch_optional(&self.pool)
+                .await?;
+        let Some((user_id,)) = user_id else {
+            return Ok(None);
+        };
+
+        let query = match outcome {
+            ReviewOutcome::Pending => sqlx::query(
+                r#"
+                UPDATE kyc_applicants SET status = 'pending', submitted_at = COALESCE(submitted_at, NOW())
+                WHERE user_id = ? AND status IN ('init', 'retry', 'pending')
+                "#,
+            )
+            .bind(&user_id),
+            ReviewOutcome::Approved => sqlx::query(
+                r#"
+                UPDATE kyc_applicants
+                SET status = 'approved', reject_reason = NULL, reviewed_at = NOW(),
+                    level = IF(level = 'full' OR requested_level = 'full', 'full', 'basic')
+                WHERE user_id = ?
+                "#,
+            )
+            .bind(&user_id),
+            ReviewOutcome::Rejected { retry, reason } => sqlx::query(
+                r#"
+                UPDATE kyc_applicants SET status = ?, reject_reason = ?, reviewed_at = NOW()
+                WHERE user_id = ?
+                "#,
+            )
+            .bind(if *retry { "retry" } else { "rejected" })
+            .bind(reason.as_deref().map(|r| r.chars().take(500).collect::<String>()))
+            .bind(&user_id),
+        };
+        query.execute(&self.pool).await?;
+
+        Ok(Some(user_id))
+    }
+}
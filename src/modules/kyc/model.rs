@@ -0,0 +1,155 @@
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+// =============================================================================
+// LEVELS AND STATUS
+// =============================================================================
+
+/// How thoroughly a user has been verified. Levels are ordered, so a user
+/// verified to `full` also meets a `basic` requirement.
+#[derive(
+    Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash, sqlx::Type, JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case")]
+pub enum KycLevel {
+    #[default]
+    None,
+    /// Identity document and selfie
+    Basic,
+    /// Basic plus proof of address
+    Full,
+}
+
+impl KycLevel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            KycLevel::None => "none",
+            KycLevel::Basic => "basic",
+            KycLevel::Full => "full",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "none" => Some(KycLevel::None),
+            "basic" => Some(KycLevel::Basic),
+            "full" => Some(KycLevel::Full),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, sqlx::Type, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case")]
+pub enum KycStatus {
+    /// Applicant created, documents not yet submitted for review
+    #[default]
+    Init,
+    /// Waiting for the provider's review
+    Pending,
+    Approved,
+    /// Rejected for good; support has to step in
+    Rejected,
+    /// Rejected, but the user may fix the documents and resubmit
+    Retry,
+}
+
+impl KycStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            KycStatus::Init => "init",
+            KycStatus::Pending => "pending",
+            KycStatus::Approved => "approved",
+            KycStatus::Rejected => "rejected",
+            KycStatus::Retry => "retry",
+        }
+    }
+
+    /// Whether documents may be added and a review requested
+    pub fn accepts_documents(&self) -> bool {
+        matches!(self, KycStatus::Init | KycStatus::Retry)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, sqlx::Type, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case")]
+pub enum DocumentType {
+    Passport,
+    IdCard,
+    DrivingLicense,
+    ResidencePermit,
+    Selfie,
+    ProofOfAddress,
+}
+
+impl DocumentType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DocumentType::Passport => "passport",
+            DocumentType::IdCard => "id_card",
+            DocumentType::DrivingLicense => "driving_license",
+            DocumentType::ResidencePermit => "residence_permit",
+            DocumentType::Selfie => "selfie",
+            DocumentType::ProofOfAddress => "proof_of_address",
+        }
+    }
+
+    /// Cards have a back; passports, selfies and utility bills do not
+    pub fn is_two_sided(&self) -> bool {
+        matches!(self, DocumentType::IdCard | DocumentType::DrivingLicense | DocumentType::ResidencePermit)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, sqlx::Type, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case")]
+pub enum DocumentSide {
+    Front,
+    Back,
+}
+
+impl DocumentSide {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DocumentSide::Front => "front",
+            DocumentSide::Back => "back",
+        }
+    }
+}
+
+// =============================================================================
+// ROWS
+// =============================================================================
+
+/// A user's applicant at the KYC provider
+#[derive(Debug, Clone, FromRow)]
+pub struct KycApplicant {
+    pub user_id: String,
+    pub provider: String,
+    pub applicant_id: String,
+    pub level: KycLevel,
+    pub requested_level: KycLevel,
+    pub status: KycStatus,
+    pub reject_reason: Option<String>,
+    pub submitted_at: Option<DateTime<Utc>>,
+    pub reviewed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A document image forwarded to the provider. The image itself is not kept.
+#[derive(Debug, Clone, FromRow)]
+pub struct KycDocument {
+    pub id: String,
+    pub user_id: String,
+    pub document_type: DocumentType,
+    pub side: Option<DocumentSide>,
+    pub country: String,
+    pub provider_document_id: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
@@ -0,0 +1,26 @@
+use axum::{extract::DefaultBodyLimit, Router};
+use std::sync::Arc;
+use tower_http::limit::RequestBodyLimitLayer;
+
+use crate::services::kyc::KycPolicy;
+use crate::manifest::DeclaredRoutes;
+use crate::{AppState, MAX_BODY_BYTES};
+use super::controller::{get_kyc_status, ingest_webhook, start_verification, submit_document, submit_for_review};
+
+pub fn kyc_routes() -> Router<Arc<AppState>> {
+    // Document images arrive base64-encoded, a third larger than the file
+    let upload_limit = KycPolicy::global().max_document_bytes.div_ceil(3) * 4 + 16 * 1024;
+
+    Router::new()
+        .declared("getKycStatus", get_kyc_status)
+        .declared("startVerification", start_verification)
+        .declared("submitKycForReview", submit_for_review)
+        .declared("ingestKycWebhook", ingest_webhook)
+        .layer(RequestBodyLimitLayer::new(MAX_BODY_BYTES))
+        .merge(
+            Router::new()
+                .declared("submitKycDocument", submit_document)
+                .layer(DefaultBodyLimit::max(upload_limit))
+                .layer(RequestBodyLimitLayer::new(upload_limit)),
+        )
+}
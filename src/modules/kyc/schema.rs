@@ -0,0 +1,125 @@
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use super::model::{DocumentSide, DocumentType, KycApplicant, KycDocument, KycLevel, KycStatus};
+
+// =============================================================================
+// REQUESTS
+// =============================================================================
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct StartVerificationRequest {
+    /// `basic` or `full`
+    pub level: KycLevel,
+}
+
+#[derive(Debug, Deserialize, Validate, JsonSchema)]
+pub struct SubmitDocumentRequest {
+    pub document_type: DocumentType,
+    /// Required for cards (ID card, driving license, residence permit)
+    pub side: Option<DocumentSide>,
+    /// ISO 3166-1 alpha-3 issuing country, e.g. `DEU`
+    #[validate(length(equal = 3))]
+    pub country: String,
+    #[validate(length(min = 1, max = 128))]
+    pub file_name: String,
+    /// `image/jpeg`, `image/png` or `application/pdf`
+    pub content_type: String,
+    /// Base64 of the image
+    pub content: String,
+}
+
+// =============================================================================
+// RESPONSES
+// =============================================================================
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct KycDocumentResponse {
+    pub id: String,
+    pub document_type: DocumentType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub side: Option<DocumentSide>,
+    pub country: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<KycDocument> for KycDocumentResponse {
+    fn from(d: KycDocument) -> Self {
+        Self { id: d.id, document_type: d.document_type, side: d.side, country: d.country, created_at: d.created_at }
+    }
+}
+
+/// Where the user's verification stands
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct KycStatusResponse {
+    /// Level verified so far; `none` until a review passes
+    pub level: KycLevel,
+    /// Verification in progress or last reviewed; absent before the user
+    /// starts one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub requested_level: Option<KycLevel>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<KycStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reject_reason: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub submitted_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reviewed_at: Option<DateTime<Utc>>,
+    pub documents: Vec<KycDocumentResponse>,
+}
+
+impl KycStatusResponse {
+    pub fn new(applicant: Option<KycApplicant>, documents: Vec<KycDocument>) -> Self {
+        let documents = documents.into_iter().map(Into::into).collect();
+        match applicant {
+            Some(a) => Self {
+                level: a.level,
+                requested_level: Some(a.requested_level),
+                status: Some(a.status),
+                reject_reason: a.reject_reason,
+                submitted_at: a.submitted_at,
+                reviewed_at: a.reviewed_at,
+                documents,
+            },
+            None => Self {
+                level: KycLevel::None,
+                requested_level: None,
+                status: None,
+                reject_reason: None,
+                submitted_at: None,
+                reviewed_at: None,
+                documents,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct StartVerificationResponse {
+    #[serde(flatten)]
+    pub status: KycStatusResponse,
+    /// Token for the provider's capture SDK, for clients that collect
+    /// documents there instead of through `/kyc/documents`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sdk_token: Option<String>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct KycWebhookResponse {
+    /// Whether the event changed an applicant
+    pub applied: bool,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct KycErrorResponse {
+    pub error: String,
+}
+
+impl KycErrorResponse {
+    pub fn new(error: impl Into<String>) -> Self {
+        Self { error: error.into() }
+    }
+}
@@ -2,3 +2,31 @@ pub mod auth;
 pub mod swap;
 pub mod wallet;
 pub mod monitor;
+pub mod gift_cards;
+pub mod balances;
+pub mod admin;
+pub mod schedules;
+pub mod orders;
+pub mod address_book;
+pub mod account;
+pub mod exports;
+pub mod email;
+pub mod jobs;
+pub mod graphql;
+pub mod recovery;
+pub mod halts;
+pub mod reconciliation;
+pub mod backups;
+pub mod payout_guard;
+pub mod analytics;
+pub mod commissions;
+pub mod promotions;
+pub mod status;
+pub mod webhooks;
+pub mod moderation;
+pub mod kyc;
+pub mod v2;
+#[cfg(feature = "seed")]
+pub mod seed;
+#[cfg(feature = "deposit-simulation")]
+pub mod simulation;
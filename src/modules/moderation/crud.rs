@@ -0,0 +1,509 @@
+use axum::http::StatusCode;
+use chrono::{DateTime, Utc};
+use sqlx::{MySql, Pool, Transaction};
+
+use crate::services::pii::SealedString;
+
+use super::model::{
+    AccountRestriction, AccountStanding, AccountStatus, AccountStatusEvent, AddressAccount, AddressReputation,
+    ReputationStatus, RiskSignal, RiskSignalKind, StatusSource,
+};
+
+/// Read on every authenticated request
+pub const STANDING_QUERY: &str =
+    "SELECT CAST(account_status AS CHAR) as account_status, status_reason, suspended_until FROM users WHERE id = ?";
+
+const EVENT_COLUMNS: &str = r#"
+    id, user_id, CAST(status AS CHAR) as status, reason, suspended_until,
+    CAST(source AS CHAR) as source, actor_id, created_at
+"#;
+
+const SIGNAL_COLUMNS: &str = r#"
+    id, user_id, CAST(kind AS CHAR) as kind, weight, swap_id, detail, reported_by, created_at
+"#;
+
+const REPUTATION_COLUMNS: &str = r#"
+    address_hash, address, network, account_count, swap_count, restricted_accounts, score,
+    CAST(status AS CHAR) as status, flagged_at, review_note, reviewed_by, reviewed_at, first_seen_at, last_seen_at
+"#;
+
+const ADDRESS_ACCOUNT_COLUMNS: &str = r#"
+    address_hash, user_id, swap_count, escalated_at, first_seen_at, last_seen_at
+"#;
+
+// =============================================================================
+// MODERATION ERROR
+// =============================================================================
+
+#[derive(Debug)]
+pub enum ModerationError {
+    UserNotFound,
+    AddressNotFound,
+    InvalidRequest(&'static str),
+    DatabaseError(String),
+}
+
+impl std::fmt::Display for ModerationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ModerationError::UserNotFound => write!(f, "User not found"),
+            ModerationError::AddressNotFound => write!(f, "Address not found"),
+            ModerationError::InvalidRequest(reason) => write!(f, "{}", reason),
+            ModerationError::DatabaseError(e) => write!(f, "Database error: {}", e),
+        }
+    }
+}
+
+impl ModerationError {
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            ModerationError::UserNotFound | ModerationError::AddressNotFound => StatusCode::NOT_FOUND,
+            ModerationError::InvalidRequest(_) => StatusCode::BAD_REQUEST,
+            ModerationError::DatabaseError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+impl From<sqlx::Error> for ModerationError {
+    fn from(err: sqlx::Error) -> Self {
+        ModerationError::DatabaseError(err.to_string())
+    }
+}
+
+// =============================================================================
+// STATUS CHANGES
+// =============================================================================
+
+/// A status to move an account to
+#[derive(Debug, Clone)]
+pub struct StatusChange<'a> {
+    pub status: AccountStatus,
+    pub reason: Option<&'a str>,
+    /// End of a timed suspension
+    pub until: Option<DateTime<Utc>>,
+    pub source: StatusSource,
+    pub actor_id: Option<&'a str>,
+}
+
+impl StatusChange<'_> {
+    fn validate(&self) -> Result<(), ModerationError> {
+        match self.status {
+            AccountStatus::Active => Ok(()),
+            _ if self.reason.map(str::trim).unwrap_or_default().is_empty() => {
+                Err(ModerationError::InvalidRequest("A reason is required to suspend or ban an account"))
+            }
+            AccountStatus::Banned if self.until.is_some() => {
+                Err(ModerationError::InvalidRequest("Bans have no duration; suspend the account instead"))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// How far a restriction goes, for deciding whether a change escalates it
+fn severity(restriction: Option<&AccountRestriction>) -> u8 {
+    match restriction.map(|r| r.status) {
+        None | Some(AccountStatus::Active) => 0,
+        Some(AccountStatus::Suspended) => 1,
+        Some(AccountStatus::Banned) => 2,
+    }
+}
+
+/// A risk signal to record
+#[derive(Debug, Clone)]
+pub struct NewRiskSignal<'a> {
+    pub kind: RiskSignalKind,
+    pub weight: u32,
+    pub swap_id: Option<&'a str>,
+    pub detail: Option<&'a str>,
+    pub reported_by: Option<&'a str>,
+}
+
+// =============================================================================
+// MODERATION CRUD
+// =============================================================================
+
+pub struct ModerationCrud {
+    pool: Pool<MySql>,
+}
+
+impl ModerationCrud {
+    pub fn new(pool: Pool<MySql>) -> Self {
+        Self { pool }
+    }
+
+    pub async fn standing(&self, user_id: &str) -> Result<Option<AccountStanding>, sqlx::Error> {
+        sqlx::query_as::<_, AccountStanding>(STANDING_QUERY)
+            .bind(user_id)
+            .fetch_optional(&self.pool)
+            .await
+    }
+
+    /// The suspension or ban in force on the account, if any
+    pub async fn restriction(&self, user_id: &str) -> Result<Option<AccountRestriction>, sqlx::Error> {
+        Ok(self.standing(user_id).await?.and_then(|standing| standing.restriction(Utc::now())))
+    }
+
+    /// Move the account to `change.status` whatever it is now
+    pub async fn set_status(&self, user_id: &str, change: &StatusChange<'_>) -> Result<AccountStanding, ModerationError> {
+        change.validate()?;
+        let mut tx = self.pool.begin().await?;
+        lock_standing(&mut tx, user_id).await?.ok_or(ModerationError::UserNotFound)?;
+        let standing = write_status(&mut tx, user_id, change).await?;
+        tx.commit().await?;
+
+        Ok(standing)
+    }
+
+    /// Move the account to `change.status` only if that restricts it
+    /// further than what is in force. Returns whether it did.
+    pub async fn escalate(&self, user_id: &str, change: &StatusChange<'_>) -> Result<bool, ModerationError> {
+        change.validate()?;
+        let mut tx = self.pool.begin().await?;
+        let current = lock_standing(&mut tx, user_id).await?.ok_or(ModerationError::UserNotFound)?;
+        let target = AccountRestriction { status: change.status, reason: None, until: change.until };
+        if severity(Some(&target)) <= severity(current.restriction(Utc::now()).as_ref()) {
+            return Ok(false);
+        }
+        write_status(&mut tx, user_id, change).await?;
+        tx.commit().await?;
+
+        Ok(true)
+    }
+
+    /// Status changes of the account, newest first
+    pub async fn list_events(&self, user_id: &str) -> Result<Vec<AccountStatusEvent>, ModerationError> {
+        let events = sqlx::query_as::<_, AccountStatusEvent>(&format!(
+            "SELECT {} FROM account_status_events WHERE user_id = ? ORDER BY created_at DESC, id DESC LIMIT 100",
+            EVENT_COLUMNS
+        ))
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(events)
+    }
+
+    pub async fn record_signal(&self, user_id: &str, signal: &NewRiskSignal<'_>) -> Result<RiskSignal, ModerationError> {
+        let inserted = sqlx::query(
+            r#"
+            INSERT INTO risk_signals (user_id, kind, weight, swap_id, detail, reported_by)
+            VALUES (?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(user_id)
+        .bind(signal.kind.as_str())
+        .bind(signal.weight)
+        .bind(signal.swap_id)
+        .bind(signal.detail)
+        .bind(signal.reported_by)
+        .execute(&self.pool)
+        .await;
+        let result = match inserted {
+            Err(sqlx::Error::Database(db)) if db.is_foreign_key_violation() => return Err(ModerationError::UserNotFound),
+            other => other?,
+        };
+
+        let signal = sqlx::query_as::<_, RiskSignal>(&format!("SELECT {} FROM risk_signals WHERE id = ?", SIGNAL_COLUMNS))
+            .bind(result.last_insert_id() as i64)
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(signal)
+    }
+
+    /// Signals against the account since `since`, newest first
+    pub async fn list_signals(&self, user_id: &str, since: DateTime<Utc>) -> Result<Vec<RiskSignal>, ModerationError> {
+        let signals = sqlx::query_as::<_, RiskSignal>(&format!(
+            "SELECT {} FROM risk_signals WHERE user_id = ? AND created_at >= ? ORDER BY created_at DESC, id DESC",
+            SIGNAL_COLUMNS
+        ))
+        .bind(user_id)
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(signals)
+    }
+
+    /// Summed weight of the account's signals since `since`
+    pub async fn risk_score(&self, user_id: &str, since: DateTime<Utc>) -> Result<u32, ModerationError> {
+        let score: Option<i64> = sqlx::query_scalar(
+            "SELECT CAST(SUM(weight) AS SIGNED) FROM risk_signals WHERE user_id = ? AND created_at >= ?",
+        )
+        .bind(user_id)
+        .bind(since)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(score.unwrap_or(0).clamp(0, u32::MAX as i64) as u32)
+    }
+
+    // =========================================================================
+    // ADDRESS REPUTATION
+    // =========================================================================
+
+    /// Count a swap by `user_id` (None for guests) paying out to the address
+    /// and refresh how many accounts share it. The address itself is stored
+    /// sealed when first seen.
+    pub async fn record_address_use(
+        &self,
+        address_hash: &str,
+        address: &str,
+        network: &str,
+        user_id: Option<&str>,
+    ) -> Result<AddressReputation, ModerationError> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query(
+            r#"
+            INSERT INTO address_reputation (address_hash, address, network, swap_count)
+            VALUES (?, ?, ?, 1)
+            ON DUPLICATE KEY UPDATE swap_count = swap_count + 1, last_seen_at = NOW()
+            "#,
+        )
+        .bind(address_hash)
+        .bind(SealedString(address.to_string()))
+        .bind(network)
+        .execute(&mut *tx)
+        .await?;
+
+        if let Some(user_id) = user_id {
+            sqlx::query(
+                r#"
+                INSERT INTO address_reputation_accounts (address_hash, user_id, swap_count)
+                VALUES (?, ?, 1)
+                ON DUPLICATE KEY UPDATE swap_count = swap_count + 1, last_seen_at = NOW()
+                "#,
+            )
+            .bind(address_hash)
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        refresh_account_counts(&mut tx, address_hash).await?;
+        let reputation = sqlx::query_as::<_, AddressReputation>(&format!(
+            "SELECT {} FROM address_reputation WHERE address_hash = ?",
+            REPUTATION_COLUMNS
+        ))
+        .bind(address_hash)
+        .fetch_one(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Ok(reputation)
+    }
+
+    /// Store a new score, flagging the address for review when `flag` is
+    /// set and nobody reviewed it yet
+    pub async fn set_address_score(&self, address_hash: &str, score: u32, flag: bool) -> Result<(), ModerationError> {
+        sqlx::query(
+            r#"
+            UPDATE address_reputation
+            SET score = ?,
+                flagged_at = IF(? AND status = 'normal', NOW(), flagged_at),
+                status = IF(? AND status = 'normal', 'flagged', status)
+            WHERE address_hash = ?
+            "#,
+        )
+        .bind(score)
+        .bind(flag)
+        .bind(flag)
+        .bind(address_hash)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_address(&self, address_hash: &str) -> Result<AddressReputation, ModerationError> {
+        sqlx::query_as::<_, AddressReputation>(&format!(
+            "SELECT {} FROM address_reputation WHERE address_hash = ?",
+            REPUTATION_COLUMNS
+        ))
+        .bind(address_hash)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or(ModerationError::AddressNotFound)
+    }
+
+    /// Addresses in `status`, highest score first
+    pub async fn list_addresses(
+        &self,
+        status: ReputationStatus,
+        limit: Option<i64>,
+    ) -> Result<Vec<AddressReputation>, ModerationError> {
+        let addresses = sqlx::query_as::<_, AddressReputation>(&format!(
+            "SELECT {} FROM address_reputation WHERE status = ? ORDER BY score DESC, last_seen_at DESC LIMIT ?",
+            REPUTATION_COLUMNS
+        ))
+        .bind(status.as_str())
+        .bind(limit.unwrap_or(50).clamp(1, 200))
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(addresses)
+    }
+
+    /// Accounts that used the address, most recent first
+    pub async fn address_accounts(&self, address_hash: &str) -> Result<Vec<AddressAccount>, ModerationError> {
+        let accounts = sqlx::query_as::<_, AddressAccount>(&format!(
+            "SELECT {} FROM address_reputation_accounts WHERE address_hash = ? ORDER BY last_seen_at DESC",
+            ADDRESS_ACCOUNT_COLUMNS
+        ))
+        .bind(address_hash)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(accounts)
+    }
+
+    /// Take the escalation of `user_id` for the address unless it was
+    /// already escalated. Returns whether it was taken.
+    pub async fn claim_escalation(&self, address_hash: &str, user_id: &str) -> Result<bool, ModerationError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE address_reputation_accounts SET escalated_at = NOW()
+            WHERE address_hash = ? AND user_id = ? AND escalated_at IS NULL
+            "#,
+        )
+        .bind(address_hash)
+        .bind(user_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Record an admin's review of the address
+    pub async fn review_address(
+        &self,
+        admin_id: &str,
+        address_hash: &str,
+        outcome: ReputationStatus,
+        note: Option<&str>,
+    ) -> Result<AddressReputation, ModerationError> {
+        if !matches!(outcome, ReputationStatus::Cleared | ReputationStatus::Confirmed) {
+            return Err(ModerationError::InvalidRequest("Addresses are reviewed as cleared or confirmed"));
+        }
+        let result = sqlx::query(
+            r#"
+            UPDATE address_reputation
+            SET status = ?, review_note = ?, reviewed_by = ?, reviewed_at = NOW()
+            WHERE address_hash = ?
+            "#,
+        )
+        .bind(outcome.as_str())
+        .bind(note.map(str::trim).filter(|n| !n.is_empty()))
+        .bind(admin_id)
+        .bind(address_hash)
+        .execute(&self.pool)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Err(ModerationError::AddressNotFound);
+        }
+
+        self.get_address(address_hash).await
+    }
+}
+
+/// Recount the accounts linked to an address, and how many of them are
+/// suspended or banned right now
+async fn refresh_account_counts(tx: &mut Transaction<'_, MySql>, address_hash: &str) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE address_reputation ar
+        SET account_count = (
+                SELECT COUNT(*) FROM address_reputation_accounts a WHERE a.address_hash = ar.address_hash
+            ),
+            restricted_accounts = (
+                SELECT COUNT(*) FROM address_reputation_accounts a
+                JOIN users u ON u.id = a.user_id
+                WHERE a.address_hash = ar.address_hash
+                AND (u.account_status = 'banned'
+                     OR (u.account_status = 'suspended' AND (u.suspended_until IS NULL OR u.suspended_until > NOW())))
+            )
+        WHERE ar.address_hash = ?
+        "#,
+    )
+    .bind(address_hash)
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
+
+async fn lock_standing(tx: &mut Transaction<'_, MySql>, user_id: &str) -> Result<Option<AccountStanding>, sqlx::Error> {
+    sqlx::query_as::<_, AccountStanding>(&format!("{} FOR UPDATE", STANDING_QUERY))
+        .bind(user_id)
+        .fetch_optional(&mut **tx)
+        .await
+}
+
+async fn write_status(
+    tx: &mut Transaction<'_, MySql>,
+    user_id: &str,
+    change: &StatusChange<'_>,
+) -> Result<AccountStanding, sqlx::Error> {
+    // Reinstating clears the reason and end along with the status
+    let (reason, until) = match change.status {
+        AccountStatus::Active => (None, None),
+        AccountStatus::Suspended => (change.reason.map(str::trim), change.until),
+        AccountStatus::Banned => (change.reason.map(str::trim), None),
+    };
+
+    sqlx::query("UPDATE users SET account_status = ?, status_reason = ?, suspended_until = ? WHERE id = ?")
+        .bind(change.status.as_str())
+        .bind(reason)
+        .bind(until)
+        .bind(user_id)
+        .execute(&mut **tx)
+        .await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO account_status_events (user_id, status, reason, suspended_until, source, actor_id)
+        VALUES (?, ?, ?, ?, ?, ?)
+        "#,
+    )
+    .bind(user_id)
+    .bind(change.status.as_str())
+    .bind(change.reason.map(str::trim))
+    .bind(until)
+    .bind(change.source.as_str())
+    .bind(change.actor_id)
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(AccountStanding {
+        account_status: change.status,
+        status_reason: reason.map(str::to_string),
+        suspended_until: until,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn change(status: AccountStatus, reason: Option<&str>, until: Option<DateTime<Utc>>) -> StatusChange<'_> {
+        StatusChange { status, reason, until, source: StatusSource::Admin, actor_id: None }
+    }
+
+    #[test]
+    fn test_restrictions_need_a_reason() {
+        assert!(change(AccountStatus::Active, None, None).validate().is_ok());
+        assert!(change(AccountStatus::Suspended, None, None).validate().is_err());
+        assert!(change(AccountStatus::Banned, Some("  "), None).validate().is_err());
+        assert!(change(AccountStatus::Suspended, Some("chargebacks"), Some(Utc::now())).validate().is_ok());
+        assert!(change(AccountStatus::Banned, Some("fraud"), Some(Utc::now())).validate().is_err());
+    }
+
+    #[test]
+    fn test_severity_orders_restrictions() {
+        let suspended = AccountRestriction { status: AccountStatus::Suspended, reason: None, until: None };
+        let banned = AccountRestriction { status: AccountStatus::Banned, reason: None, until: None };
+        assert!(severity(None) < severity(Some(&suspended)));
+        assert!(severity(Some(&suspended)) < severity(Some(&banned)));
+    }
+}
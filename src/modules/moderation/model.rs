@@ -0,0 +1,247 @@
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+use crate::services::pii::SealedString;
+
+// =============================================================================
+// ACCOUNT STATUS
+// =============================================================================
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, sqlx::Type, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case")]
+pub enum AccountStatus {
+    #[default]
+    Active,
+    /// Refused until `suspended_until`, or until lifted when it is unset
+    Suspended,
+    /// Refused for good
+    Banned,
+}
+
+impl AccountStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AccountStatus::Active => "active",
+            AccountStatus::Suspended => "suspended",
+            AccountStatus::Banned => "banned",
+        }
+    }
+}
+
+/// The status columns of a `users` row
+#[derive(Debug, Clone, FromRow)]
+pub struct AccountStanding {
+    pub account_status: AccountStatus,
+    pub status_reason: Option<String>,
+    pub suspended_until: Option<DateTime<Utc>>,
+}
+
+impl AccountStanding {
+    /// Why the account is refused at `now`, if it is. A suspension whose
+    /// end has passed no longer counts; the row is left for the next change
+    /// to overwrite.
+    pub fn restriction(&self, now: DateTime<Utc>) -> Option<AccountRestriction> {
+        match self.account_status {
+            AccountStatus::Active => None,
+            AccountStatus::Suspended if self.suspended_until.is_some_and(|until| until <= now) => None,
+            status => Some(AccountRestriction {
+                status,
+                reason: self.status_reason.clone(),
+                until: self.suspended_until.filter(|_| status == AccountStatus::Suspended),
+            }),
+        }
+    }
+}
+
+/// A suspension or ban in force
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccountRestriction {
+    pub status: AccountStatus,
+    pub reason: Option<String>,
+    /// End of a timed suspension
+    pub until: Option<DateTime<Utc>>,
+}
+
+impl std::fmt::Display for AccountRestriction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.status, self.until) {
+            (AccountStatus::Banned, _) => write!(f, "Account banned"),
+            (_, Some(until)) => write!(f, "Account suspended until {}", until.to_rfc3339()),
+            _ => write!(f, "Account suspended"),
+        }
+    }
+}
+
+// =============================================================================
+// STATUS EVENTS
+// =============================================================================
+
+/// Who changed an account's status
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, sqlx::Type, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case")]
+pub enum StatusSource {
+    Admin,
+    /// Automatic, from accumulated risk signals
+    Risk,
+}
+
+impl StatusSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StatusSource::Admin => "admin",
+            StatusSource::Risk => "risk",
+        }
+    }
+}
+
+/// One status change, kept for the account's history
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct AccountStatusEvent {
+    pub id: i64,
+    pub user_id: String,
+    pub status: AccountStatus,
+    pub reason: Option<String>,
+    pub suspended_until: Option<DateTime<Utc>>,
+    pub source: StatusSource,
+    pub actor_id: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+// =============================================================================
+// RISK SIGNALS
+// =============================================================================
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, sqlx::Type, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case")]
+pub enum RiskSignalKind {
+    /// A card or bank payment behind a purchase was reversed
+    Chargeback,
+    /// Another user, a provider or law enforcement reported the account
+    FraudReport,
+    /// Funds the account sent in were traced to theft
+    StolenFunds,
+    /// The account pays out to an address shared by many unrelated accounts
+    AddressReuse,
+}
+
+impl RiskSignalKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RiskSignalKind::Chargeback => "chargeback",
+            RiskSignalKind::FraudReport => "fraud_report",
+            RiskSignalKind::StolenFunds => "stolen_funds",
+            RiskSignalKind::AddressReuse => "address_reuse",
+        }
+    }
+}
+
+/// A recorded signal against an account
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct RiskSignal {
+    pub id: i64,
+    pub user_id: String,
+    pub kind: RiskSignalKind,
+    pub weight: u32,
+    pub swap_id: Option<String>,
+    pub detail: Option<String>,
+    pub reported_by: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+// =============================================================================
+// ADDRESS REPUTATION
+// =============================================================================
+
+/// Where an address stands in admin review
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, sqlx::Type, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case")]
+pub enum ReputationStatus {
+    /// Below the review threshold
+    #[default]
+    Normal,
+    /// Waiting for an admin
+    Flagged,
+    /// Reviewed and legitimate, e.g. a merchant or exchange address; no
+    /// longer escalated
+    Cleared,
+    /// Reviewed and fraudulent; every account using it is escalated
+    Confirmed,
+}
+
+impl ReputationStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReputationStatus::Normal => "normal",
+            ReputationStatus::Flagged => "flagged",
+            ReputationStatus::Cleared => "cleared",
+            ReputationStatus::Confirmed => "confirmed",
+        }
+    }
+}
+
+/// A recipient address and how widely it is shared
+#[derive(Debug, Clone, FromRow)]
+pub struct AddressReputation {
+    pub address_hash: String,
+    #[sqlx(try_from = "SealedString")]
+    pub address: String,
+    pub network: String,
+    pub account_count: u32,
+    pub swap_count: u32,
+    pub restricted_accounts: u32,
+    pub score: u32,
+    pub status: ReputationStatus,
+    pub flagged_at: Option<DateTime<Utc>>,
+    pub review_note: Option<String>,
+    pub reviewed_by: Option<String>,
+    pub reviewed_at: Option<DateTime<Utc>>,
+    pub first_seen_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+}
+
+/// An account's use of an address
+#[derive(Debug, Clone, FromRow)]
+pub struct AddressAccount {
+    pub address_hash: String,
+    pub user_id: String,
+    pub swap_count: u32,
+    pub escalated_at: Option<DateTime<Utc>>,
+    pub first_seen_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn standing(status: AccountStatus, until: Option<DateTime<Utc>>) -> AccountStanding {
+        AccountStanding { account_status: status, status_reason: Some("abuse".to_string()), suspended_until: until }
+    }
+
+    #[test]
+    fn test_expired_suspension_is_lifted() {
+        let now = Utc::now();
+        assert!(standing(AccountStatus::Active, None).restriction(now).is_none());
+        assert!(standing(AccountStatus::Suspended, Some(now - Duration::minutes(1))).restriction(now).is_none());
+
+        let restriction = standing(AccountStatus::Suspended, Some(now + Duration::hours(1))).restriction(now).unwrap();
+        assert_eq!(restriction.until, Some(now + Duration::hours(1)));
+        assert!(standing(AccountStatus::Suspended, None).restriction(now).is_some());
+    }
+
+    #[test]
+    fn test_ban_ignores_suspension_end() {
+        let now = Utc::now();
+        let restriction = standing(AccountStatus::Banned, Some(now - Duration::days(1))).restriction(now).unwrap();
+        assert_eq!(restriction.status, AccountStatus::Banned);
+        assert_eq!(restriction.until, None);
+        assert_eq!(restriction.to_string(), "Account banned");
+    }
+}
@@ -0,0 +1,241 @@
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use super::model::{
+    AccountRestriction, AccountStatus, AccountStatusEvent, AddressAccount, AddressReputation, ReputationStatus,
+    RiskSignal, RiskSignalKind, StatusSource,
+};
+
+// =============================================================================
+// REQUESTS
+// =============================================================================
+
+#[derive(Debug, Deserialize, Validate, JsonSchema)]
+pub struct SetAccountStatusRequest {
+    pub status: AccountStatus,
+    /// Shown to the user when they are refused. Required unless `status`
+    /// is `active`.
+    #[validate(length(min = 1, max = 500))]
+    pub reason: Option<String>,
+    /// Length of a suspension; omit to suspend until lifted. Only valid
+    /// with `suspended`.
+    #[validate(range(min = 1, max = 8760))]
+    pub duration_hours: Option<u32>,
+}
+
+#[derive(Debug, Deserialize, Validate, JsonSchema)]
+pub struct ReportRiskSignalRequest {
+    pub kind: RiskSignalKind,
+    pub swap_id: Option<String>,
+    #[validate(length(max = 500))]
+    pub detail: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct AddressReputationQuery {
+    /// Defaults to `flagged`, the review queue
+    pub status: Option<ReputationStatus>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Deserialize, Validate, JsonSchema)]
+pub struct ReviewAddressRequest {
+    #[validate(length(max = 500))]
+    pub note: Option<String>,
+}
+
+// =============================================================================
+// RESPONSES
+// =============================================================================
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct AccountStatusEventResponse {
+    pub status: AccountStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suspended_until: Option<DateTime<Utc>>,
+    pub source: StatusSource,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub actor_id: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<AccountStatusEvent> for AccountStatusEventResponse {
+    fn from(e: AccountStatusEvent) -> Self {
+        Self {
+            status: e.status,
+            reason: e.reason,
+            suspended_until: e.suspended_until,
+            source: e.source,
+            actor_id: e.actor_id,
+            created_at: e.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct RiskSignalResponse {
+    pub id: i64,
+    pub kind: RiskSignalKind,
+    pub weight: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub swap_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reported_by: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<RiskSignal> for RiskSignalResponse {
+    fn from(s: RiskSignal) -> Self {
+        Self {
+            id: s.id,
+            kind: s.kind,
+            weight: s.weight,
+            swap_id: s.swap_id,
+            detail: s.detail,
+            reported_by: s.reported_by,
+            created_at: s.created_at,
+        }
+    }
+}
+
+/// An account's status with how it got there
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct AccountStatusResponse {
+    pub user_id: String,
+    /// `active` once a timed suspension has run out
+    pub status: AccountStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suspended_until: Option<DateTime<Utc>>,
+    /// Weighted risk signals inside the scoring window
+    pub risk_score: u32,
+    pub events: Vec<AccountStatusEventResponse>,
+    pub signals: Vec<RiskSignalResponse>,
+}
+
+/// A recorded signal and what the risk engine did about it
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct RiskAssessmentResponse {
+    pub signal: RiskSignalResponse,
+    pub risk_score: u32,
+    /// Status the account was moved to, if the signal tipped it over a
+    /// threshold
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub applied: Option<AccountStatus>,
+}
+
+/// Body of the 403 a suspended or banned account gets
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct AccountRestrictedResponse {
+    pub error: String,
+    pub status: AccountStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suspended_until: Option<DateTime<Utc>>,
+}
+
+impl From<AccountRestriction> for AccountRestrictedResponse {
+    fn from(r: AccountRestriction) -> Self {
+        Self { error: r.to_string(), status: r.status, reason: r.reason, suspended_until: r.until }
+    }
+}
+
+/// A payout address and how widely it is shared
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct AddressReputationResponse {
+    pub address_hash: String,
+    pub address: String,
+    pub network: String,
+    pub account_count: u32,
+    pub swap_count: u32,
+    /// Accounts using the address that are suspended or banned
+    pub restricted_accounts: u32,
+    pub score: u32,
+    pub status: ReputationStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub flagged_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub review_note: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reviewed_by: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reviewed_at: Option<DateTime<Utc>>,
+    pub first_seen_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+}
+
+impl From<AddressReputation> for AddressReputationResponse {
+    fn from(r: AddressReputation) -> Self {
+        Self {
+            address_hash: r.address_hash,
+            address: r.address,
+            network: r.network,
+            account_count: r.account_count,
+            swap_count: r.swap_count,
+            restricted_accounts: r.restricted_accounts,
+            score: r.score,
+            status: r.status,
+            flagged_at: r.flagged_at,
+            review_note: r.review_note,
+            reviewed_by: r.reviewed_by,
+            reviewed_at: r.reviewed_at,
+            first_seen_at: r.first_seen_at,
+            last_seen_at: r.last_seen_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct AddressReputationsResponse {
+    pub addresses: Vec<AddressReputationResponse>,
+}
+
+/// An account that paid out to the address
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct AddressAccountResponse {
+    pub user_id: String,
+    pub swap_count: u32,
+    /// When the account was reported to the risk engine for the address
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub escalated_at: Option<DateTime<Utc>>,
+    pub first_seen_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+}
+
+impl From<AddressAccount> for AddressAccountResponse {
+    fn from(a: AddressAccount) -> Self {
+        Self {
+            user_id: a.user_id,
+            swap_count: a.swap_count,
+            escalated_at: a.escalated_at,
+            first_seen_at: a.first_seen_at,
+            last_seen_at: a.last_seen_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct AddressReputationDetailResponse {
+    #[serde(flatten)]
+    pub reputation: AddressReputationResponse,
+    pub accounts: Vec<AddressAccountResponse>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ModerationErrorResponse {
+    pub error: String,
+}
+
+impl ModerationErrorResponse {
+    pub fn new(error: impl Into<String>) -> Self {
+        Self { error: error.into() }
+    }
+}
@@ -50,4 +50,21 @@ impl MonitorCrud {
 
         Ok(())
     }
+
+    /// Have the monitor poll the swap's provider on its next pass
+    pub async fn request_poll(&self, swap_id: &str, status: &str) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO polling_states (swap_id, next_poll_at, last_status)
+            VALUES (?, NOW(), ?)
+            ON DUPLICATE KEY UPDATE next_poll_at = NOW()
+            "#
+        )
+        .bind(swap_id)
+        .bind(status)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
 }
@@ -0,0 +1,79 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use std::sync::Arc;
+
+use crate::AppState;
+use crate::modules::auth::interface::{scope::{HistoryRead, SwapCreate}, Scoped};
+use super::crud::{OrderCrud, OrderError};
+use super::model::OrderStatus;
+use super::schema::{CreateOrderRequest, OrderErrorResponse, OrderResponse, OrdersQuery, OrdersResponse};
+
+fn to_error(e: OrderError) -> (StatusCode, Json<OrderErrorResponse>) {
+    (e.status_code(), Json(OrderErrorResponse::new(e.to_string())))
+}
+
+// =============================================================================
+// POST /swap/orders - Arm a price-triggered swap
+// GET  /swap/orders - List orders (pending by default)
+// =============================================================================
+
+pub async fn create_order(
+    State(state): State<Arc<AppState>>,
+    user: Scoped<SwapCreate>,
+    Json(payload): Json<CreateOrderRequest>,
+) -> Result<(StatusCode, Json<OrderResponse>), (StatusCode, Json<OrderErrorResponse>)> {
+    let crud = OrderCrud::new(state.db.clone());
+
+    let order = crud.create(&user.0.id, &payload).await.map_err(to_error)?;
+
+    Ok((StatusCode::CREATED, Json(order.into())))
+}
+
+pub async fn list_orders(
+    State(state): State<Arc<AppState>>,
+    user: Scoped<HistoryRead>,
+    Query(query): Query<OrdersQuery>,
+) -> Result<Json<OrdersResponse>, (StatusCode, Json<OrderErrorResponse>)> {
+    let crud = OrderCrud::new(state.db.clone());
+
+    let orders = crud
+        .list(&user.0.id, query.status.unwrap_or(OrderStatus::Pending))
+        .await
+        .map_err(to_error)?;
+
+    Ok(Json(OrdersResponse {
+        orders: orders.into_iter().map(Into::into).collect(),
+    }))
+}
+
+// =============================================================================
+// GET    /swap/orders/{id} - Order status
+// DELETE /swap/orders/{id} - Cancel a pending order
+// =============================================================================
+
+pub async fn get_order(
+    State(state): State<Arc<AppState>>,
+    user: Scoped<HistoryRead>,
+    Path(order_id): Path<String>,
+) -> Result<Json<OrderResponse>, (StatusCode, Json<OrderErrorResponse>)> {
+    let crud = OrderCrud::new(state.db.clone());
+
+    let order = crud.get(&user.0.id, &order_id).await.map_err(to_error)?;
+
+    Ok(Json(order.into()))
+}
+
+pub async fn cancel_order(
+    State(state): State<Arc<AppState>>,
+    user: Scoped<SwapCreate>,
+    Path(order_id): Path<String>,
+) -> Result<Json<OrderResponse>, (StatusCode, Json<OrderErrorResponse>)> {
+    let crud = OrderCrud::new(state.db.clone());
+
+    let order = crud.cancel(&user.0.id, &order_id).await.map_err(to_error)?;
+
+    Ok(Json(order.into()))
+}
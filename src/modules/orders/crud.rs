@@ -0,0 +1,296 @@
+use axum::http::StatusCode;
+use chrono::Utc;
+use sqlx::{MySql, Pool};
+use uuid::Uuid;
+
+use crate::services::amount::Decimal;
+use crate::services::pii::SealedString;
+use super::model::{ConditionalOrder, OrderStatus};
+use super::schema::CreateOrderRequest;
+
+pub const DEFAULT_TTL_HOURS: i64 = 24;
+pub const MAX_TTL_HOURS: i64 = 24 * 30;
+pub const MAX_PENDING_ORDERS_PER_USER: i64 = 20;
+
+const ORDER_COLUMNS: &str = r#"
+    id, user_id, from_currency, from_network, to_currency, to_network,
+    amount, provider,
+    recipient_address, recipient_extra_id, refund_address, refund_extra_id, payout_to_balance,
+    target_rate, CAST(status AS CHAR) as status, expires_at,
+    last_checked_rate, last_checked_at,
+    triggered_rate, triggered_at, swap_id, error,
+    created_at, updated_at
+"#;
+
+// =============================================================================
+// ORDER ERROR
+// =============================================================================
+
+#[derive(Debug)]
+pub enum OrderError {
+    InvalidAmount,
+    InvalidTargetRate,
+    InvalidTtl,
+    InvalidAddress,
+    TooManyOrders,
+    OrderNotFound,
+    OrderNotPending(OrderStatus),
+    DatabaseError(String),
+}
+
+impl std::fmt::Display for OrderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OrderError::InvalidAmount => write!(f, "Amount must be a positive number"),
+            OrderError::InvalidTargetRate => write!(f, "target_rate must be a positive number"),
+            OrderError::InvalidTtl => write!(f, "ttl_hours must be between 1 and {}", MAX_TTL_HOURS),
+            OrderError::InvalidAddress => write!(f, "recipient_address is required"),
+            OrderError::TooManyOrders => write!(
+                f,
+                "A maximum of {} pending orders is allowed per account",
+                MAX_PENDING_ORDERS_PER_USER
+            ),
+            OrderError::OrderNotFound => write!(f, "Order not found"),
+            OrderError::OrderNotPending(status) => {
+                write!(f, "Order is no longer pending (status: {:?})", status)
+            }
+            OrderError::DatabaseError(e) => write!(f, "Database error: {}", e),
+        }
+    }
+}
+
+impl OrderError {
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            OrderError::InvalidAmount => StatusCode::BAD_REQUEST,
+            OrderError::InvalidTargetRate => StatusCode::BAD_REQUEST,
+            OrderError::InvalidTtl => StatusCode::BAD_REQUEST,
+            OrderError::InvalidAddress => StatusCode::BAD_REQUEST,
+            OrderError::TooManyOrders => StatusCode::CONFLICT,
+            OrderError::OrderNotFound => StatusCode::NOT_FOUND,
+            OrderError::OrderNotPending(_) => StatusCode::CONFLICT,
+            OrderError::DatabaseError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+impl From<sqlx::Error> for OrderError {
+    fn from(err: sqlx::Error) -> Self {
+        OrderError::DatabaseError(err.to_string())
+    }
+}
+
+// =============================================================================
+// ORDER CRUD
+// =============================================================================
+
+pub struct OrderCrud {
+    pool: Pool<MySql>,
+}
+
+impl OrderCrud {
+    pub fn new(pool: Pool<MySql>) -> Self {
+        Self { pool }
+    }
+
+    pub async fn create(
+        &self,
+        user_id: &str,
+        request: &CreateOrderRequest,
+    ) -> Result<ConditionalOrder, OrderError> {
+        if request.amount.value() <= Decimal::ZERO {
+            return Err(OrderError::InvalidAmount);
+        }
+        if request.target_rate.value() <= Decimal::ZERO {
+            return Err(OrderError::InvalidTargetRate);
+        }
+        if !request.payout_to_balance && request.recipient_address.trim().is_empty() {
+            return Err(OrderError::InvalidAddress);
+        }
+
+        let ttl_hours = request.ttl_hours.unwrap_or(DEFAULT_TTL_HOURS);
+        if !(1..=MAX_TTL_HOURS).contains(&ttl_hours) {
+            return Err(OrderError::InvalidTtl);
+        }
+
+        let (pending,): (i64,) = sqlx::query_as(
+            "SELECT COUNT(*) FROM conditional_orders WHERE user_id = ? AND status = 'pending'",
+        )
+        .bind(user_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        if pending >= MAX_PENDING_ORDERS_PER_USER {
+            return Err(OrderError::TooManyOrders);
+        }
+
+        let id = Uuid::new_v4().to_string();
+        let expires_at = Utc::now() + chrono::Duration::hours(ttl_hours);
+
+        sqlx::query(
+            r#"
+            INSERT INTO conditional_orders (
+                id, user_id, from_currency, from_network, to_currency, to_network,
+                amount, provider, recipient_address, recipient_extra_id,
+                refund_address, refund_extra_id, payout_to_balance,
+                target_rate, status, expires_at
+            )
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, 'pending', ?)
+            "#,
+        )
+        .bind(&id)
+        .bind(user_id)
+        .bind(&request.from)
+        .bind(&request.network_from)
+        .bind(&request.to)
+        .bind(&request.network_to)
+        .bind(request.amount.value())
+        .bind(&request.provider)
+        .bind(request.recipient_address.trim())
+        .bind(&request.recipient_extra_id)
+        .bind(&request.refund_address)
+        .bind(&request.refund_extra_id)
+        .bind(request.payout_to_balance)
+        .bind(request.target_rate.value())
+        .bind(expires_at)
+        .execute(&self.pool)
+        .await?;
+
+        self.get(user_id, &id).await
+    }
+
+    pub async fn list(&self, user_id: &str, status: OrderStatus) -> Result<Vec<ConditionalOrder>, OrderError> {
+        let sql = format!(
+            "SELECT {} FROM conditional_orders WHERE user_id = ? AND status = ? ORDER BY created_at DESC LIMIT 100",
+            ORDER_COLUMNS
+        );
+
+        let orders = sqlx::query_as::<_, ConditionalOrder>(&sql)
+            .bind(user_id)
+            .bind(status)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(orders)
+    }
+
+    pub async fn get(&self, user_id: &str, order_id: &str) -> Result<ConditionalOrder, OrderError> {
+        let sql = format!(
+            "SELECT {} FROM conditional_orders WHERE id = ? AND user_id = ?",
+            ORDER_COLUMNS
+        );
+
+        sqlx::query_as::<_, ConditionalOrder>(&sql)
+            .bind(order_id)
+            .bind(user_id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or(OrderError::OrderNotFound)
+    }
+
+    pub async fn cancel(&self, user_id: &str, order_id: &str) -> Result<ConditionalOrder, OrderError> {
+        let result = sqlx::query(
+            "UPDATE conditional_orders SET status = 'cancelled', updated_at = NOW() WHERE id = ? AND user_id = ? AND status = 'pending'",
+        )
+        .bind(order_id)
+        .bind(user_id)
+        .execute(&self.pool)
+        .await?;
+
+        let order = self.get(user_id, order_id).await?;
+        if result.rows_affected() == 0 {
+            return Err(OrderError::OrderNotPending(order.status));
+        }
+        Ok(order)
+    }
+
+    // =========================================================================
+    // WATCHER
+    // =========================================================================
+
+    /// Mark orders past their TTL as expired
+    pub async fn expire_stale(&self) -> Result<u64, OrderError> {
+        let result = sqlx::query(
+            "UPDATE conditional_orders SET status = 'expired', updated_at = NOW() WHERE status = 'pending' AND expires_at <= NOW()",
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    pub async fn get_pending(&self, limit: i64) -> Result<Vec<ConditionalOrder>, OrderError> {
+        let sql = format!(
+            r#"
+            SELECT {} FROM conditional_orders
+            WHERE status = 'pending' AND expires_at > NOW()
+            ORDER BY last_checked_at IS NOT NULL, last_checked_at ASC
+            LIMIT ?
+            "#,
+            ORDER_COLUMNS
+        );
+
+        let orders = sqlx::query_as::<_, ConditionalOrder>(&sql)
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(orders)
+    }
+
+    pub async fn record_check(&self, order_id: &str, rate: Decimal) -> Result<(), OrderError> {
+        sqlx::query(
+            "UPDATE conditional_orders SET last_checked_rate = ?, last_checked_at = NOW() WHERE id = ?",
+        )
+        .bind(rate)
+        .bind(order_id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Atomically move a pending order to triggered.
+    /// Returns false if it was cancelled or claimed in the meantime.
+    pub async fn claim_trigger(&self, order_id: &str, rate: Decimal) -> Result<bool, OrderError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE conditional_orders
+            SET status = 'triggered', triggered_rate = ?, triggered_at = NOW(), updated_at = NOW()
+            WHERE id = ? AND status = 'pending'
+            "#,
+        )
+        .bind(rate)
+        .bind(order_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    pub async fn attach_swap(&self, order_id: &str, swap_id: &str) -> Result<(), OrderError> {
+        sqlx::query("UPDATE conditional_orders SET swap_id = ?, updated_at = NOW() WHERE id = ?")
+            .bind(swap_id)
+            .bind(order_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn mark_failed(&self, order_id: &str, error: &str) -> Result<(), OrderError> {
+        sqlx::query("UPDATE conditional_orders SET status = 'failed', error = ?, updated_at = NOW() WHERE id = ?")
+            .bind(error)
+            .bind(order_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get_user_email(&self, user_id: &str) -> Result<Option<String>, OrderError> {
+        let row: Option<(SealedString,)> = sqlx::query_as("SELECT email FROM users WHERE id = ?")
+            .bind(user_id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(row.map(|r| r.0.into()))
+    }
+}
@@ -0,0 +1,61 @@
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+use crate::services::amount::Decimal;
+
+// =============================================================================
+// CONDITIONAL ORDER
+// =============================================================================
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct ConditionalOrder {
+    pub id: String,
+    pub user_id: String,
+
+    // Swap template
+    pub from_currency: String,
+    pub from_network: String,
+    pub to_currency: String,
+    pub to_network: String,
+    pub amount: Decimal,
+    pub provider: Option<String>,
+    pub recipient_address: String,
+    pub recipient_extra_id: Option<String>,
+    pub refund_address: Option<String>,
+    pub refund_extra_id: Option<String>,
+    pub payout_to_balance: bool,
+
+    // Trigger
+    pub target_rate: Decimal,
+    pub status: OrderStatus,
+    pub expires_at: DateTime<Utc>,
+    pub last_checked_rate: Option<Decimal>,
+    pub last_checked_at: Option<DateTime<Utc>>,
+    pub triggered_rate: Option<Decimal>,
+    pub triggered_at: Option<DateTime<Utc>>,
+    pub swap_id: Option<String>,
+    pub error: Option<String>,
+
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ConditionalOrder {
+    /// Whether a quoted rate satisfies this order's target
+    pub fn is_triggered_by(&self, rate: Decimal) -> bool {
+        rate >= self.target_rate
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, sqlx::Type, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(rename_all = "lowercase")]
+pub enum OrderStatus {
+    Pending,
+    Triggered,
+    Cancelled,
+    Expired,
+    Failed,
+}
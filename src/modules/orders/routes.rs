@@ -0,0 +1,14 @@
+use axum::Router;
+use std::sync::Arc;
+
+use crate::AppState;
+use crate::manifest::DeclaredRoutes;
+use super::controller::{cancel_order, create_order, get_order, list_orders};
+
+pub fn order_routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .declared("listOrders", list_orders)
+        .declared("createOrder", create_order)
+        .declared("getOrder", get_order)
+        .declared("cancelOrder", cancel_order)
+}
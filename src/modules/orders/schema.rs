@@ -0,0 +1,126 @@
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::services::amount::{Decimal, WireAmount};
+use super::model::{ConditionalOrder, OrderStatus};
+
+// =============================================================================
+// CREATE
+// =============================================================================
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CreateOrderRequest {
+    pub from: String,
+    pub network_from: String,
+    pub to: String,
+    pub network_to: String,
+    pub amount: WireAmount,
+    /// Minimum acceptable rate, in `to` units per `from` unit
+    pub target_rate: WireAmount,
+    /// Restrict to a single provider (defaults to best quote)
+    #[serde(default)]
+    pub provider: Option<String>,
+    #[serde(default)]
+    pub recipient_address: String,
+    #[serde(default)]
+    pub recipient_extra_id: Option<String>,
+    #[serde(default)]
+    pub refund_address: Option<String>,
+    #[serde(default)]
+    pub refund_extra_id: Option<String>,
+    #[serde(default)]
+    pub payout_to_balance: bool,
+    /// How long the order stays armed (defaults to 24h)
+    #[serde(default)]
+    pub ttl_hours: Option<i64>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct OrdersQuery {
+    /// Filter by status (defaults to pending)
+    pub status: Option<OrderStatus>,
+}
+
+// =============================================================================
+// RESPONSES
+// =============================================================================
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct OrderResponse {
+    pub id: String,
+    pub from: String,
+    pub network_from: String,
+    pub to: String,
+    pub network_to: String,
+    #[serde(with = "rust_decimal::serde::float")]
+    #[schemars(with = "f64")]
+    pub amount: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    #[schemars(with = "f64")]
+    pub target_rate: Decimal,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
+    pub recipient_address: String,
+    pub payout_to_balance: bool,
+    pub status: OrderStatus,
+    pub expires_at: DateTime<Utc>,
+    #[serde(with = "rust_decimal::serde::float_option", skip_serializing_if = "Option::is_none")]
+    #[schemars(with = "Option<f64>")]
+    pub last_checked_rate: Option<Decimal>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_checked_at: Option<DateTime<Utc>>,
+    #[serde(with = "rust_decimal::serde::float_option", skip_serializing_if = "Option::is_none")]
+    #[schemars(with = "Option<f64>")]
+    pub triggered_rate: Option<Decimal>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub swap_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<ConditionalOrder> for OrderResponse {
+    fn from(o: ConditionalOrder) -> Self {
+        Self {
+            id: o.id,
+            from: o.from_currency,
+            network_from: o.from_network,
+            to: o.to_currency,
+            network_to: o.to_network,
+            amount: o.amount,
+            target_rate: o.target_rate,
+            provider: o.provider,
+            recipient_address: o.recipient_address,
+            payout_to_balance: o.payout_to_balance,
+            status: o.status,
+            expires_at: o.expires_at,
+            last_checked_rate: o.last_checked_rate,
+            last_checked_at: o.last_checked_at,
+            triggered_rate: o.triggered_rate,
+            swap_id: o.swap_id,
+            error: o.error,
+            created_at: o.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct OrdersResponse {
+    pub orders: Vec<OrderResponse>,
+}
+
+// =============================================================================
+// ERROR RESPONSE
+// =============================================================================
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct OrderErrorResponse {
+    pub error: String,
+}
+
+impl OrderErrorResponse {
+    pub fn new(error: impl Into<String>) -> Self {
+        Self { error: error.into() }
+    }
+}
@@ -0,0 +1,201 @@
+use chrono::{DateTime, Utc};
+use sqlx::{MySql, Pool};
+use uuid::Uuid;
+
+use crate::services::amount::Decimal;
+use super::model::{AmountBaseline, AttemptStats, PayoutGuardTrip, PayoutKind, TripCause};
+
+const MAX_PAGE: i64 = 100;
+
+const TRIP_COLUMNS: &str = r#"
+    id, cause, reason, tripped_by, tripped_at, rearmed_at, rearmed_by, rearm_note
+"#;
+
+// =============================================================================
+// PAYOUT GUARD CRUD
+// =============================================================================
+
+pub struct PayoutGuardCrud {
+    pool: Pool<MySql>,
+}
+
+impl PayoutGuardCrud {
+    pub fn new(pool: Pool<MySql>) -> Self {
+        Self { pool }
+    }
+
+    /// Serialize guard checks: holds the single `payout_guard_lock` row until
+    /// the caller's transaction ends
+    pub async fn lock<'e, E>(executor: E) -> Result<(), sqlx::Error>
+    where
+        E: sqlx::Executor<'e, Database = MySql>,
+    {
+        sqlx::query("SELECT id FROM payout_guard_lock WHERE id = 1 FOR UPDATE")
+            .fetch_one(executor)
+            .await?;
+        Ok(())
+    }
+
+    /// Reserve a cleared payout as an attempt with no outcome yet. Returns
+    /// the attempt id for [`finish_attempt`](Self::finish_attempt).
+    #[allow(clippy::too_many_arguments)]
+    pub async fn reserve_attempt<'e, E>(
+        executor: E,
+        kind: PayoutKind,
+        reference: &str,
+        chain: &str,
+        currency: &str,
+        amount: Decimal,
+        usd_value: f64,
+    ) -> Result<u64, sqlx::Error>
+    where
+        E: sqlx::Executor<'e, Database = MySql>,
+    {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO payout_attempts (kind, reference, chain, currency, amount, usd_value, succeeded)
+            VALUES (?, ?, ?, ?, ?, ?, NULL)
+            "#,
+        )
+        .bind(kind)
+        .bind(reference)
+        .bind(chain)
+        .bind(currency)
+        .bind(amount)
+        .bind(usd_value)
+        .execute(executor)
+        .await?;
+        Ok(result.last_insert_id())
+    }
+
+    /// Fill in the outcome of a reserved attempt
+    pub async fn finish_attempt(&self, attempt_id: u64, error: Option<&str>) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE payout_attempts SET succeeded = ?, error = ? WHERE id = ? AND succeeded IS NULL")
+            .bind(error.is_none())
+            .bind(error)
+            .bind(attempt_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Drop a reservation for a payout that was never sent
+    pub async fn release_attempt(&self, attempt_id: u64) -> Result<(), sqlx::Error> {
+        sqlx::query("DELETE FROM payout_attempts WHERE id = ? AND succeeded IS NULL")
+            .bind(attempt_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Attempts of every kind made after `since`. Reserved payouts count
+    /// towards the USD sent but not towards attempts or failures.
+    pub async fn attempt_stats(&self, since: DateTime<Utc>) -> Result<AttemptStats, sqlx::Error> {
+        Self::attempt_stats_in(&self.pool, since).await
+    }
+
+    /// Same as `attempt_stats` on a caller-supplied executor, so the cap can
+    /// be checked under the guard lock
+    pub async fn attempt_stats_in<'e, E>(executor: E, since: DateTime<Utc>) -> Result<AttemptStats, sqlx::Error>
+    where
+        E: sqlx::Executor<'e, Database = MySql>,
+    {
+        sqlx::query_as::<_, AttemptStats>(
+            r#"
+            SELECT COUNT(succeeded) AS attempts,
+                   CAST(COALESCE(SUM(NOT succeeded), 0) AS SIGNED) AS failures,
+                   CAST(COALESCE(SUM(CASE WHEN succeeded OR succeeded IS NULL THEN usd_value END), 0) AS DOUBLE)
+                       AS usd_sent
+            FROM payout_attempts
+            WHERE created_at > ?
+            "#,
+        )
+        .bind(since)
+        .fetch_one(executor)
+        .await
+    }
+
+    /// Value distribution of successful payouts of `kind` after `since`
+    pub async fn amount_baseline(&self, kind: PayoutKind, since: DateTime<Utc>) -> Result<AmountBaseline, sqlx::Error> {
+        sqlx::query_as::<_, AmountBaseline>(
+            r#"
+            SELECT COUNT(*) AS samples,
+                   CAST(COALESCE(AVG(usd_value), 0) AS DOUBLE) AS mean_usd,
+                   CAST(COALESCE(STDDEV_POP(usd_value), 0) AS DOUBLE) AS stddev_usd
+            FROM payout_attempts
+            WHERE kind = ? AND succeeded AND created_at > ?
+            "#,
+        )
+        .bind(kind)
+        .bind(since)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    /// The trip halting payouts right now, if any
+    pub async fn active_trip(&self) -> Result<Option<PayoutGuardTrip>, sqlx::Error> {
+        let sql = format!(
+            "SELECT {} FROM payout_guard_trips WHERE rearmed_at IS NULL ORDER BY tripped_at LIMIT 1",
+            TRIP_COLUMNS
+        );
+        sqlx::query_as::<_, PayoutGuardTrip>(&sql).fetch_optional(&self.pool).await
+    }
+
+    /// When the guard was last re-armed; attempts before that no longer count
+    pub async fn last_rearmed_at(&self) -> Result<Option<DateTime<Utc>>, sqlx::Error> {
+        let row: (Option<DateTime<Utc>>,) = sqlx::query_as("SELECT MAX(rearmed_at) FROM payout_guard_trips")
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(row.0)
+    }
+
+    /// Record a trip unless one is already active. Returns the new trip's id,
+    /// or `None` when another caller tripped the guard first.
+    pub async fn trip(
+        &self,
+        cause: TripCause,
+        reason: &str,
+        tripped_by: Option<&str>,
+    ) -> Result<Option<String>, sqlx::Error> {
+        let id = Uuid::new_v4().to_string();
+        let result = sqlx::query(
+            r#"
+            INSERT INTO payout_guard_trips (id, cause, reason, tripped_by)
+            SELECT ?, ?, ?, ? FROM DUAL
+            WHERE NOT EXISTS (SELECT 1 FROM payout_guard_trips WHERE rearmed_at IS NULL)
+            "#,
+        )
+        .bind(&id)
+        .bind(cause)
+        .bind(reason)
+        .bind(tripped_by)
+        .execute(&self.pool)
+        .await?;
+
+        Ok((result.rows_affected() > 0).then_some(id))
+    }
+
+    /// Close every active trip. Returns how many were closed.
+    pub async fn rearm(&self, admin_id: &str, note: &str) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            UPDATE payout_guard_trips
+            SET rearmed_at = CURRENT_TIMESTAMP(3), rearmed_by = ?, rearm_note = ?
+            WHERE rearmed_at IS NULL
+            "#,
+        )
+        .bind(admin_id)
+        .bind(note)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    pub async fn list_trips(&self, limit: Option<i64>) -> Result<Vec<PayoutGuardTrip>, sqlx::Error> {
+        let sql = format!("SELECT {} FROM payout_guard_trips ORDER BY tripped_at DESC LIMIT ?", TRIP_COLUMNS);
+        sqlx::query_as::<_, PayoutGuardTrip>(&sql)
+            .bind(limit.unwrap_or(20).clamp(1, MAX_PAGE))
+            .fetch_all(&self.pool)
+            .await
+    }
+}
@@ -0,0 +1,118 @@
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+// =============================================================================
+// PAYOUT KIND
+// =============================================================================
+
+/// Which pipeline a payout went out through
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, sqlx::Type, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case")]
+pub enum PayoutKind {
+    Swap,
+    /// Custodial balance withdrawal
+    Withdrawal,
+    /// Late deposit refund
+    Refund,
+    /// Admin sweep of a deposit address, for wrong-network cases and
+    /// orphaned funds
+    Recovery,
+    /// Payment of a gift card order to the provider
+    GiftCard,
+}
+
+impl PayoutKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PayoutKind::Swap => "swap",
+            PayoutKind::Withdrawal => "withdrawal",
+            PayoutKind::Refund => "refund",
+            PayoutKind::Recovery => "recovery",
+            PayoutKind::GiftCard => "gift_card",
+        }
+    }
+}
+
+// =============================================================================
+// GUARD TRIP
+// =============================================================================
+
+/// Why the guard halted payouts
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, sqlx::Type, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case")]
+pub enum TripCause {
+    /// Too many sends failed within the window
+    FailureRate,
+    /// The hourly USD cap would have been passed
+    UsdVolume,
+    /// A payout far larger than recent ones
+    OutlierAmount,
+    /// Tripped by an admin
+    Manual,
+}
+
+impl TripCause {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TripCause::FailureRate => "failure_rate",
+            TripCause::UsdVolume => "usd_volume",
+            TripCause::OutlierAmount => "outlier_amount",
+            TripCause::Manual => "manual",
+        }
+    }
+}
+
+/// A halt of all payouts. Active until an admin re-arms the guard.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct PayoutGuardTrip {
+    pub id: String,
+    pub cause: TripCause,
+    pub reason: String,
+    /// Admin who tripped it, for manual trips
+    pub tripped_by: Option<String>,
+    pub tripped_at: DateTime<Utc>,
+    pub rearmed_at: Option<DateTime<Utc>>,
+    pub rearmed_by: Option<String>,
+    pub rearm_note: Option<String>,
+}
+
+impl PayoutGuardTrip {
+    pub fn is_active(&self) -> bool {
+        self.rearmed_at.is_none()
+    }
+}
+
+// =============================================================================
+// WINDOW STATS
+// =============================================================================
+
+/// Payout attempts since the window opened
+#[derive(Debug, Clone, Copy, Default, PartialEq, FromRow, Serialize, JsonSchema)]
+pub struct AttemptStats {
+    pub attempts: i64,
+    pub failures: i64,
+    /// USD sent successfully or reserved by payouts being sent
+    pub usd_sent: f64,
+}
+
+impl AttemptStats {
+    pub fn failure_rate(&self) -> f64 {
+        if self.attempts == 0 {
+            0.0
+        } else {
+            self.failures as f64 / self.attempts as f64
+        }
+    }
+}
+
+/// Distribution of recent successful payout values of one kind
+#[derive(Debug, Clone, Copy, Default, PartialEq, FromRow)]
+pub struct AmountBaseline {
+    pub samples: i64,
+    pub mean_usd: f64,
+    pub stddev_usd: f64,
+}
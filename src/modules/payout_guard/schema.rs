@@ -0,0 +1,96 @@
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::model::{AttemptStats, PayoutGuardTrip, TripCause};
+use crate::services::payout::PayoutGuardPolicy;
+
+// =============================================================================
+// STATUS
+// =============================================================================
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct PayoutGuardTripResponse {
+    pub id: String,
+    pub cause: TripCause,
+    pub reason: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tripped_by: Option<String>,
+    pub tripped_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rearmed_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rearmed_by: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rearm_note: Option<String>,
+}
+
+impl From<PayoutGuardTrip> for PayoutGuardTripResponse {
+    fn from(t: PayoutGuardTrip) -> Self {
+        Self {
+            id: t.id,
+            cause: t.cause,
+            reason: t.reason,
+            tripped_by: t.tripped_by,
+            tripped_at: t.tripped_at,
+            rearmed_at: t.rearmed_at,
+            rearmed_by: t.rearmed_by,
+            rearm_note: t.rearm_note,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct PayoutWindowResponse {
+    /// Attempts since the window opened, or since the last re-arm if later
+    #[serde(flatten)]
+    pub stats: AttemptStats,
+    pub failure_rate: f64,
+}
+
+impl From<AttemptStats> for PayoutWindowResponse {
+    fn from(stats: AttemptStats) -> Self {
+        Self { failure_rate: stats.failure_rate(), stats }
+    }
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct PayoutGuardStatusResponse {
+    /// True while every payout is halted
+    pub tripped: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub active_trip: Option<PayoutGuardTripResponse>,
+    pub thresholds: PayoutGuardPolicy,
+    /// Attempts in the failure-rate window
+    pub window: PayoutWindowResponse,
+    /// Attempts in the last hour, against the USD cap
+    pub last_hour: PayoutWindowResponse,
+    /// Newest first, including the active one
+    pub recent_trips: Vec<PayoutGuardTripResponse>,
+}
+
+// =============================================================================
+// TRIP / RE-ARM
+// =============================================================================
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct RearmPayoutGuardRequest {
+    /// What was checked before resuming payouts
+    pub note: String,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct TripPayoutGuardRequest {
+    pub reason: String,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct PayoutGuardErrorResponse {
+    pub error: String,
+}
+
+impl PayoutGuardErrorResponse {
+    pub fn new(error: impl Into<String>) -> Self {
+        Self { error: error.into() }
+    }
+}
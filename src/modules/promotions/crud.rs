@@ -0,0 +1,454 @@
+use axum::http::StatusCode;
+use chrono::{DateTime, NaiveDate, Utc};
+use sqlx::{MySql, Pool};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, OnceLock, RwLock};
+use std::time::{Duration, Instant};
+
+use super::model::{Promotion, PromotionRedemption};
+use super::schema::{CreatePromotionRequest, UpdatePromotionRequest};
+use crate::modules::swap::crud::SwapCrud;
+use crate::services::amount::Decimal;
+use crate::services::fx::FxRateTable;
+use crate::services::pricing::{ActivePromotions, Audience};
+
+/// Every rate quote looks campaigns up, so running ones are cached per
+/// process. Admin changes invalidate it locally; other instances pick them
+/// up within this window.
+const PROMOTION_CACHE_TTL: Duration = Duration::from_secs(30);
+
+const PROMOTION_COLUMNS: &str = r#"
+    id, name, description, from_currency, from_network, to_currency, to_network, provider_id,
+    CAST(segment AS CHAR) as segment, fee_discount, priority, starts_at, ends_at,
+    max_uses, max_uses_per_user, uses, is_active, created_by, created_at, updated_at
+"#;
+
+/// Active campaigns and when they were read
+type PromotionCache = RwLock<Option<(Instant, Arc<ActivePromotions>)>>;
+
+static PROMOTION_CACHE: OnceLock<PromotionCache> = OnceLock::new();
+
+fn promotion_cache() -> &'static PromotionCache {
+    PROMOTION_CACHE.get_or_init(|| RwLock::new(None))
+}
+
+/// Drop the cached campaigns so the next quote reads the table
+pub fn invalidate_promotion_cache() {
+    if let Ok(mut cache) = promotion_cache().write() {
+        *cache = None;
+    }
+}
+
+// =============================================================================
+// PROMOTION ERROR
+// =============================================================================
+
+#[derive(Debug)]
+pub enum PromotionError {
+    NotFound,
+    InvalidWindow,
+    DatabaseError(String),
+}
+
+impl std::fmt::Display for PromotionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PromotionError::NotFound => write!(f, "Promotion not found"),
+            PromotionError::InvalidWindow => write!(f, "starts_at must be before ends_at"),
+            PromotionError::DatabaseError(e) => write!(f, "Database error: {}", e),
+        }
+    }
+}
+
+impl PromotionError {
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            PromotionError::NotFound => StatusCode::NOT_FOUND,
+            PromotionError::InvalidWindow => StatusCode::BAD_REQUEST,
+            PromotionError::DatabaseError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+impl From<sqlx::Error> for PromotionError {
+    fn from(err: sqlx::Error) -> Self {
+        PromotionError::DatabaseError(err.to_string())
+    }
+}
+
+// =============================================================================
+// PROMOTION TOTALS
+// =============================================================================
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct PromotionTotal {
+    pub promotion_id: String,
+    pub name: String,
+    pub currency: String,
+    pub network: String,
+    pub redemptions: i64,
+    pub standard_fees: Decimal,
+    pub charged_fees: Decimal,
+}
+
+/// A campaign total with the fees given up valued in a reporting currency;
+/// None when a day it covers has no FX rate
+#[derive(Debug, Clone)]
+pub struct ValuedPromotionTotal {
+    pub total: PromotionTotal,
+    pub foregone_value: Option<Decimal>,
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+struct DailyPromotionTotal {
+    day: NaiveDate,
+    #[sqlx(flatten)]
+    total: PromotionTotal,
+}
+
+/// A campaign discount to record with the swap it was applied to
+#[derive(Debug, Clone)]
+pub struct NewRedemption<'a> {
+    pub promotion_id: &'a str,
+    pub swap_id: &'a str,
+    pub user_id: Option<&'a str>,
+    pub currency: &'a str,
+    pub network: &'a str,
+    pub standard_fee: Decimal,
+    pub charged_fee: Decimal,
+}
+
+// =============================================================================
+// PROMOTION CRUD
+// =============================================================================
+
+pub struct PromotionCrud {
+    pool: Pool<MySql>,
+}
+
+impl PromotionCrud {
+    pub fn new(pool: Pool<MySql>) -> Self {
+        Self { pool }
+    }
+
+    /// Every campaign, newest first
+    pub async fn list_promotions(&self) -> Result<Vec<Promotion>, PromotionError> {
+        let promotions = sqlx::query_as::<_, Promotion>(&format!(
+            "SELECT {} FROM promotions ORDER BY starts_at DESC, created_at DESC",
+            PROMOTION_COLUMNS
+        ))
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(promotions)
+    }
+
+    pub async fn get_promotion(&self, id: &str) -> Result<Option<Promotion>, PromotionError> {
+        let promotion = sqlx::query_as::<_, Promotion>(&format!("SELECT {} FROM promotions WHERE id = ?", PROMOTION_COLUMNS))
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(promotion)
+    }
+
+    /// Enabled campaigns that have not ended, served from the process cache
+    /// when fresh
+    pub async fn active(&self) -> Result<Arc<ActivePromotions>, PromotionError> {
+        if let Ok(cache) = promotion_cache().read() {
+            if let Some((loaded_at, active)) = cache.as_ref() {
+                if loaded_at.elapsed() < PROMOTION_CACHE_TTL {
+                    return Ok(active.clone());
+                }
+            }
+        }
+
+        let promotions = sqlx::query_as::<_, Promotion>(&format!(
+            "SELECT {} FROM promotions WHERE is_active = TRUE AND ends_at > NOW()",
+            PROMOTION_COLUMNS
+        ))
+        .fetch_all(&self.pool)
+        .await?;
+
+        let active = Arc::new(ActivePromotions::new(promotions));
+        if let Ok(mut cache) = promotion_cache().write() {
+            *cache = Some((Instant::now(), active.clone()));
+        }
+        Ok(active)
+    }
+
+    /// What campaigns need to know about a user: payout tier, completed
+    /// swaps and past redemptions
+    pub async fn audience(&self, user_id: Option<&str>) -> Result<Audience, PromotionError> {
+        let Some(user_id) = user_id else {
+            return Ok(Audience::anonymous());
+        };
+
+        let tier: Option<(String,)> =
+            sqlx::query_as("SELECT COALESCE(CAST(payout_tier AS CHAR), 'standard') FROM users WHERE id = ?")
+                .bind(user_id)
+                .fetch_optional(&self.pool)
+                .await?;
+        let (completed,): (i64,) =
+            sqlx::query_as("SELECT COUNT(*) FROM swaps WHERE user_id = ? AND status = 'completed'")
+                .bind(user_id)
+                .fetch_one(&self.pool)
+                .await?;
+        let redemptions: Vec<(String, i64)> = sqlx::query_as(
+            "SELECT promotion_id, COUNT(*) FROM promotion_redemptions WHERE user_id = ? GROUP BY promotion_id",
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(Audience {
+            user_id: Some(user_id.to_string()),
+            tier: tier.map(|(t,)| t),
+            completed_swaps: completed.max(0) as u64,
+            redemptions: redemptions.into_iter().map(|(id, n)| (id, n.max(0) as u32)).collect::<HashMap<_, _>>(),
+        })
+    }
+
+    pub async fn create_promotion(
+        &self,
+        admin_id: &str,
+        request: &CreatePromotionRequest,
+    ) -> Result<Promotion, PromotionError> {
+        if request.starts_at >= request.ends_at {
+            return Err(PromotionError::InvalidWindow);
+        }
+
+        let id = uuid::Uuid::new_v4().to_string();
+        let lower = |v: &Option<String>| v.as_deref().map(|v| v.trim().to_lowercase()).filter(|v| !v.is_empty());
+        sqlx::query(
+            r#"
+            INSERT INTO promotions (
+                id, name, description, from_currency, from_network, to_currency, to_network, provider_id,
+                segment, fee_discount, priority, starts_at, ends_at, max_uses, max_uses_per_user, created_by
+            )
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&id)
+        .bind(request.name.trim())
+        .bind(&request.description)
+        .bind(lower(&request.from_currency))
+        .bind(lower(&request.from_network))
+        .bind(lower(&request.to_currency))
+        .bind(lower(&request.to_network))
+        .bind(request.provider_id.as_deref().map(SwapCrud::normalize_provider_id))
+        .bind(request.segment.as_str())
+        .bind(request.fee_discount)
+        .bind(request.priority)
+        .bind(request.starts_at)
+        .bind(request.ends_at)
+        .bind(request.max_uses)
+        .bind(request.max_uses_per_user)
+        .bind(admin_id)
+        .execute(&self.pool)
+        .await?;
+        invalidate_promotion_cache();
+
+        self.get_promotion(&id).await?.ok_or(PromotionError::NotFound)
+    }
+
+    pub async fn update_promotion(
+        &self,
+        id: &str,
+        request: &UpdatePromotionRequest,
+    ) -> Result<Promotion, PromotionError> {
+        let current = self.get_promotion(id).await?.ok_or(PromotionError::NotFound)?;
+        if request.starts_at.unwrap_or(current.starts_at) >= request.ends_at.unwrap_or(current.ends_at) {
+            return Err(PromotionError::InvalidWindow);
+        }
+
+        sqlx::query(
+            r#"
+            UPDATE promotions SET
+                name = COALESCE(?, name),
+                description = COALESCE(?, description),
+                fee_discount = COALESCE(?, fee_discount),
+                priority = COALESCE(?, priority),
+                starts_at = COALESCE(?, starts_at),
+                ends_at = COALESCE(?, ends_at),
+                max_uses = COALESCE(?, max_uses),
+                max_uses_per_user = COALESCE(?, max_uses_per_user),
+                is_active = COALESCE(?, is_active)
+            WHERE id = ?
+            "#,
+        )
+        .bind(request.name.as_deref().map(str::trim))
+        .bind(&request.description)
+        .bind(request.fee_discount)
+        .bind(request.priority)
+        .bind(request.starts_at)
+        .bind(request.ends_at)
+        .bind(request.max_uses)
+        .bind(request.max_uses_per_user)
+        .bind(request.is_active)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        invalidate_promotion_cache();
+
+        self.get_promotion(id).await?.ok_or(PromotionError::NotFound)
+    }
+
+    /// Stop offering a campaign. Its redemptions stay for reporting.
+    pub async fn end_promotion(&self, id: &str) -> Result<(), PromotionError> {
+        let result = sqlx::query("UPDATE promotions SET is_active = FALSE WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        invalidate_promotion_cache();
+
+        if result.rows_affected() == 0 {
+            return Err(PromotionError::NotFound);
+        }
+        Ok(())
+    }
+
+    /// Take one use of a campaign for a new swap, unless its total or
+    /// per-user cap has been reached since the rates were served. Returns
+    /// whether the use was taken.
+    pub async fn claim(&self, promotion_id: &str, user_id: Option<&str>) -> Result<bool, PromotionError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE promotions SET uses = uses + 1
+            WHERE id = ? AND is_active = TRUE AND NOW() >= starts_at AND NOW() < ends_at
+              AND (max_uses IS NULL OR uses < max_uses)
+              AND (max_uses_per_user IS NULL OR (
+                  ? IS NOT NULL AND (
+                      SELECT COUNT(*) FROM promotion_redemptions r
+                      WHERE r.promotion_id = promotions.id AND r.user_id = ?
+                  ) < max_uses_per_user
+              ))
+            "#,
+        )
+        .bind(promotion_id)
+        .bind(user_id)
+        .bind(user_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Give back a use taken for a swap that was never created
+    pub async fn release(&self, promotion_id: &str) -> Result<(), PromotionError> {
+        sqlx::query("UPDATE promotions SET uses = GREATEST(uses, 1) - 1 WHERE id = ?")
+            .bind(promotion_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Record the discount a new swap was created with. Runs inside the
+    /// transaction that writes the swap.
+    pub async fn insert_redemption<'e, E>(executor: E, redemption: &NewRedemption<'_>) -> Result<(), sqlx::Error>
+    where
+        E: sqlx::Executor<'e, Database = MySql>,
+    {
+        sqlx::query(
+            r#"
+            INSERT INTO promotion_redemptions
+                (promotion_id, swap_id, user_id, currency, network, standard_fee, charged_fee)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(redemption.promotion_id)
+        .bind(redemption.swap_id)
+        .bind(redemption.user_id)
+        .bind(redemption.currency.to_lowercase())
+        .bind(redemption.network.to_lowercase())
+        .bind(redemption.standard_fee)
+        .bind(redemption.charged_fee)
+        .execute(executor)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn redemption_for_swap(&self, swap_id: &str) -> Result<Option<PromotionRedemption>, PromotionError> {
+        let redemption = sqlx::query_as::<_, PromotionRedemption>(
+            r#"
+            SELECT id, promotion_id, swap_id, user_id, currency, network, standard_fee, charged_fee, created_at
+            FROM promotion_redemptions WHERE swap_id = ?
+            "#,
+        )
+        .bind(swap_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(redemption)
+    }
+
+    /// Redemptions and fees given up per campaign and asset over a window
+    pub async fn totals(
+        &self,
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+    ) -> Result<Vec<PromotionTotal>, PromotionError> {
+        let totals = sqlx::query_as::<_, PromotionTotal>(
+            r#"
+            SELECT r.promotion_id, p.name, r.currency, r.network, COUNT(*) as redemptions,
+                   SUM(r.standard_fee) as standard_fees, SUM(r.charged_fee) as charged_fees
+            FROM promotion_redemptions r
+            JOIN promotions p ON p.id = r.promotion_id
+            WHERE r.created_at >= ? AND r.created_at < ?
+            GROUP BY r.promotion_id, p.name, r.currency, r.network
+            ORDER BY p.name, r.currency, r.network
+            "#,
+        )
+        .bind(since)
+        .bind(until)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(totals)
+    }
+
+    /// Campaign totals like [`Self::totals`], with the fees given up valued
+    /// in `rates`' currency at the rate of each redemption's day
+    pub async fn valued_totals(
+        &self,
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+        rates: &FxRateTable,
+    ) -> Result<Vec<ValuedPromotionTotal>, PromotionError> {
+        let days = sqlx::query_as::<_, DailyPromotionTotal>(
+            r#"
+            SELECT DATE(r.created_at) as day, r.promotion_id, p.name, r.currency, r.network,
+                   COUNT(*) as redemptions, SUM(r.standard_fee) as standard_fees, SUM(r.charged_fee) as charged_fees
+            FROM promotion_redemptions r
+            JOIN promotions p ON p.id = r.promotion_id
+            WHERE r.created_at >= ? AND r.created_at < ?
+            GROUP BY day, r.promotion_id, p.name, r.currency, r.network
+            "#,
+        )
+        .bind(since)
+        .bind(until)
+        .fetch_all(&self.pool)
+        .await?;
+
+        // Keyed like `totals` orders its rows
+        let mut totals: BTreeMap<(String, String, String, String), ValuedPromotionTotal> = BTreeMap::new();
+        for DailyPromotionTotal { day, total } in days {
+            let value = rates.value(total.standard_fees - total.charged_fees, &total.currency, day);
+            let key = (total.name.clone(), total.currency.clone(), total.network.clone(), total.promotion_id.clone());
+            match totals.get_mut(&key) {
+                Some(valued) => {
+                    valued.total.redemptions += total.redemptions;
+                    valued.total.standard_fees += total.standard_fees;
+                    valued.total.charged_fees += total.charged_fees;
+                    valued.foregone_value = valued.foregone_value.zip(value).map(|(a, b)| a + b);
+                }
+                None => {
+                    totals.insert(key, ValuedPromotionTotal { total, foregone_value: value });
+                }
+            }
+        }
+
+        Ok(totals.into_values().collect())
+    }
+}
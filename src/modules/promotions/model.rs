@@ -0,0 +1,90 @@
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+use crate::services::amount::Decimal;
+
+// =============================================================================
+// PROMOTION
+// =============================================================================
+
+/// A campaign waiving some or all of the platform fee on matching quotes.
+/// Unset pair and provider fields match anything.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct Promotion {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub from_currency: Option<String>,
+    pub from_network: Option<String>,
+    pub to_currency: Option<String>,
+    pub to_network: Option<String>,
+    pub provider_id: Option<String>,
+    pub segment: UserSegment,
+    /// Fraction of the platform fee waived; 1.0 is fee-free
+    pub fee_discount: f64,
+    /// Higher wins when several campaigns match
+    pub priority: i32,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    pub max_uses: Option<u32>,
+    pub max_uses_per_user: Option<u32>,
+    pub uses: u32,
+    pub is_active: bool,
+    pub created_by: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Who a campaign is offered to
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, Hash, sqlx::Type, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case")]
+pub enum UserSegment {
+    /// Everyone, signed in or not
+    #[default]
+    All,
+    /// Requests without an account
+    Anonymous,
+    /// Any signed-in user
+    Registered,
+    /// Signed-in users without a completed swap
+    NewUsers,
+    /// Users on the `vip` payout tier
+    Vip,
+    /// Users on the `partner` payout tier
+    Partner,
+}
+
+impl UserSegment {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UserSegment::All => "all",
+            UserSegment::Anonymous => "anonymous",
+            UserSegment::Registered => "registered",
+            UserSegment::NewUsers => "new_users",
+            UserSegment::Vip => "vip",
+            UserSegment::Partner => "partner",
+        }
+    }
+}
+
+// =============================================================================
+// REDEMPTION
+// =============================================================================
+
+/// One swap created under a campaign
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct PromotionRedemption {
+    pub id: i64,
+    pub promotion_id: String,
+    pub swap_id: String,
+    pub user_id: Option<String>,
+    pub currency: String,
+    pub network: String,
+    /// The platform fee without the campaign
+    pub standard_fee: Decimal,
+    pub charged_fee: Decimal,
+    pub created_at: DateTime<Utc>,
+}
@@ -0,0 +1,194 @@
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use super::crud::ValuedPromotionTotal;
+use super::model::{Promotion, UserSegment};
+use crate::services::fx::format_value;
+
+// =============================================================================
+// REQUESTS
+// =============================================================================
+
+#[derive(Debug, Deserialize, Validate, JsonSchema)]
+pub struct CreatePromotionRequest {
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+    #[validate(length(max = 1000))]
+    pub description: Option<String>,
+    /// Omit pair and provider fields to match any
+    pub from_currency: Option<String>,
+    pub from_network: Option<String>,
+    pub to_currency: Option<String>,
+    pub to_network: Option<String>,
+    pub provider_id: Option<String>,
+    #[serde(default)]
+    pub segment: UserSegment,
+    /// Fraction of the platform fee waived; 1.0 is fee-free
+    #[validate(range(min = 0.0, max = 1.0))]
+    pub fee_discount: f64,
+    /// Higher wins when several campaigns match a quote
+    #[serde(default)]
+    pub priority: i32,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    #[validate(range(min = 1))]
+    pub max_uses: Option<u32>,
+    #[validate(range(min = 1))]
+    pub max_uses_per_user: Option<u32>,
+}
+
+/// Omitted fields are left unchanged
+#[derive(Debug, Deserialize, Validate, JsonSchema)]
+pub struct UpdatePromotionRequest {
+    #[validate(length(min = 1, max = 100))]
+    pub name: Option<String>,
+    #[validate(length(max = 1000))]
+    pub description: Option<String>,
+    #[validate(range(min = 0.0, max = 1.0))]
+    pub fee_discount: Option<f64>,
+    pub priority: Option<i32>,
+    pub starts_at: Option<DateTime<Utc>>,
+    pub ends_at: Option<DateTime<Utc>>,
+    #[validate(range(min = 1))]
+    pub max_uses: Option<u32>,
+    #[validate(range(min = 1))]
+    pub max_uses_per_user: Option<u32>,
+    pub is_active: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct PromotionReportQuery {
+    /// Defaults to 30 days before `to`
+    pub from: Option<DateTime<Utc>>,
+    /// Defaults to now
+    pub to: Option<DateTime<Utc>>,
+    /// ISO 4217 currency to value foregone fees in; defaults to
+    /// REPORTING_CURRENCY
+    pub currency: Option<String>,
+}
+
+// =============================================================================
+// RESPONSES
+// =============================================================================
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct PromotionResponse {
+    pub id: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from_currency: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from_network: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to_currency: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to_network: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provider_id: Option<String>,
+    pub segment: UserSegment,
+    pub fee_discount: f64,
+    pub priority: i32,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_uses: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_uses_per_user: Option<u32>,
+    pub uses: u32,
+    pub is_active: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_by: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<Promotion> for PromotionResponse {
+    fn from(p: Promotion) -> Self {
+        Self {
+            id: p.id,
+            name: p.name,
+            description: p.description,
+            from_currency: p.from_currency,
+            from_network: p.from_network,
+            to_currency: p.to_currency,
+            to_network: p.to_network,
+            provider_id: p.provider_id,
+            segment: p.segment,
+            fee_discount: p.fee_discount,
+            priority: p.priority,
+            starts_at: p.starts_at,
+            ends_at: p.ends_at,
+            max_uses: p.max_uses,
+            max_uses_per_user: p.max_uses_per_user,
+            uses: p.uses,
+            is_active: p.is_active,
+            created_by: p.created_by,
+            created_at: p.created_at,
+            updated_at: p.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct PromotionsResponse {
+    pub promotions: Vec<PromotionResponse>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct PromotionTotalResponse {
+    pub promotion_id: String,
+    pub name: String,
+    pub currency: String,
+    pub network: String,
+    pub redemptions: i64,
+    /// Exact decimal strings, in `currency`
+    pub standard_fees: String,
+    pub charged_fees: String,
+    /// Platform fees given up to the campaign
+    pub foregone: String,
+    /// `foregone` in the reporting currency at each day's rate; absent when
+    /// a day has no FX rate yet
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub foregone_value: Option<String>,
+}
+
+impl From<ValuedPromotionTotal> for PromotionTotalResponse {
+    fn from(v: ValuedPromotionTotal) -> Self {
+        let t = v.total;
+        Self {
+            promotion_id: t.promotion_id,
+            name: t.name,
+            currency: t.currency,
+            network: t.network,
+            redemptions: t.redemptions,
+            standard_fees: t.standard_fees.normalize().to_string(),
+            charged_fees: t.charged_fees.normalize().to_string(),
+            foregone: (t.standard_fees - t.charged_fees).normalize().to_string(),
+            foregone_value: v.foregone_value.map(format_value),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct PromotionReportResponse {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    /// ISO 4217 currency of every `foregone_value`
+    pub reporting_currency: String,
+    pub totals: Vec<PromotionTotalResponse>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct PromotionErrorResponse {
+    pub error: String,
+}
+
+impl PromotionErrorResponse {
+    pub fn new(error: impl Into<String>) -> Self {
+        Self { error: error.into() }
+    }
+}
@@ -0,0 +1,283 @@
+use axum::http::StatusCode;
+use chrono::{DateTime, Utc};
+use sqlx::{MySql, Pool};
+use uuid::Uuid;
+
+use crate::modules::jobs::crud::JobCrud;
+use crate::modules::jobs::model::JobKind;
+use super::model::{
+    DiscrepancyKind, DiscrepancyStatus, ProviderDiscrepancy, ReconcilableSwap, ReconciliationRun,
+};
+
+const MAX_PAGE: i64 = 200;
+
+const DISCREPANCY_COLUMNS: &str = r#"
+    id, run_id, swap_id, provider_id, provider_swap_id,
+    CAST(kind AS CHAR) as kind, our_value, provider_value, CAST(status AS CHAR) as status,
+    note, reviewed_by, reviewed_at, created_at, updated_at
+"#;
+
+// =============================================================================
+// RECONCILIATION ERROR
+// =============================================================================
+
+#[derive(Debug)]
+pub enum ReconciliationError {
+    DiscrepancyNotFound,
+    InvalidDiscrepancyState(DiscrepancyStatus),
+    DatabaseError(String),
+}
+
+impl std::fmt::Display for ReconciliationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReconciliationError::DiscrepancyNotFound => write!(f, "Discrepancy not found"),
+            ReconciliationError::InvalidDiscrepancyState(status) => {
+                write!(f, "Discrepancy is already {}", status.as_str())
+            }
+            ReconciliationError::DatabaseError(e) => write!(f, "Database error: {}", e),
+        }
+    }
+}
+
+impl ReconciliationError {
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            ReconciliationError::DiscrepancyNotFound => StatusCode::NOT_FOUND,
+            ReconciliationError::InvalidDiscrepancyState(_) => StatusCode::CONFLICT,
+            ReconciliationError::DatabaseError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+impl From<sqlx::Error> for ReconciliationError {
+    fn from(err: sqlx::Error) -> Self {
+        ReconciliationError::DatabaseError(err.to_string())
+    }
+}
+
+// =============================================================================
+// RECONCILIATION CRUD
+// =============================================================================
+
+pub struct ReconciliationCrud {
+    pool: Pool<MySql>,
+}
+
+impl ReconciliationCrud {
+    pub fn new(pool: Pool<MySql>) -> Self {
+        Self { pool }
+    }
+
+    /// Record a run, with a system job of the same id admins can follow
+    pub async fn start_run(&self) -> Result<String, ReconciliationError> {
+        let run_id = Uuid::new_v4().to_string();
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("INSERT INTO provider_reconciliation_runs (id) VALUES (?)")
+            .bind(&run_id)
+            .execute(&mut *tx)
+            .await?;
+        JobCrud::create(&mut tx, &run_id, JobKind::Reconciliation, None).await?;
+        tx.commit().await?;
+
+        Ok(run_id)
+    }
+
+    pub async fn finish_run(
+        &self,
+        run_id: &str,
+        swaps_checked: u32,
+        discrepancies: u32,
+        errors: u32,
+    ) -> Result<(), ReconciliationError> {
+        sqlx::query(
+            r#"
+            UPDATE provider_reconciliation_runs
+            SET finished_at = NOW(), swaps_checked = ?, discrepancies = ?, errors = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(swaps_checked)
+        .bind(discrepancies)
+        .bind(errors)
+        .bind(run_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn list_runs(&self, limit: Option<i64>) -> Result<Vec<ReconciliationRun>, ReconciliationError> {
+        let runs = sqlx::query_as::<_, ReconciliationRun>(
+            r#"
+            SELECT id, started_at, finished_at, swaps_checked, discrepancies, errors
+            FROM provider_reconciliation_runs
+            ORDER BY started_at DESC
+            LIMIT ?
+            "#,
+        )
+        .bind(limit.unwrap_or(20).clamp(1, MAX_PAGE))
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(runs)
+    }
+
+    /// Number of swaps `swaps_to_reconcile` pages through
+    pub async fn count_swaps_to_reconcile(
+        &self,
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+    ) -> Result<u64, ReconciliationError> {
+        let (count,): (i64,) = sqlx::query_as(
+            r#"
+            SELECT COUNT(*)
+            FROM swaps
+            WHERE provider_swap_id IS NOT NULL AND provider_swap_id != ''
+              AND is_sandbox = FALSE
+              AND created_at >= ? AND created_at < ?
+            "#,
+        )
+        .bind(since)
+        .bind(until)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(count.max(0) as u64)
+    }
+
+    /// Swaps with a provider order created in `[since, until)`, paged by id
+    pub async fn swaps_to_reconcile(
+        &self,
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+        after_id: &str,
+        limit: i64,
+    ) -> Result<Vec<ReconcilableSwap>, ReconciliationError> {
+        let swaps = sqlx::query_as::<_, ReconcilableSwap>(
+            r#"
+            SELECT id, provider_id, provider_swap_id, CAST(status AS CHAR) as status,
+                   CAST(amount AS DOUBLE) as amount
+            FROM swaps
+            WHERE provider_swap_id IS NOT NULL AND provider_swap_id != ''
+              AND is_sandbox = FALSE
+              AND created_at >= ? AND created_at < ?
+              AND id > ?
+            ORDER BY id
+            LIMIT ?
+            "#,
+        )
+        .bind(since)
+        .bind(until)
+        .bind(after_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(swaps)
+    }
+
+    /// Record drift for a swap. A repeat sighting refreshes the values and
+    /// reopens a resolved entry; dismissed entries stay dismissed.
+    pub async fn record_discrepancy(
+        &self,
+        run_id: &str,
+        swap: &ReconcilableSwap,
+        kind: DiscrepancyKind,
+        our_value: &str,
+        provider_value: &str,
+    ) -> Result<(), ReconciliationError> {
+        sqlx::query(
+            r#"
+            INSERT INTO provider_discrepancies
+                (id, run_id, swap_id, provider_id, provider_swap_id, kind, our_value, provider_value)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            ON DUPLICATE KEY UPDATE
+                run_id = VALUES(run_id),
+                our_value = VALUES(our_value),
+                provider_value = VALUES(provider_value),
+                status = IF(status = 'resolved', 'open', status)
+            "#,
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(run_id)
+        .bind(&swap.id)
+        .bind(&swap.provider_id)
+        .bind(&swap.provider_swap_id)
+        .bind(kind.as_str())
+        .bind(our_value)
+        .bind(provider_value)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_discrepancy(&self, id: &str) -> Result<Option<ProviderDiscrepancy>, ReconciliationError> {
+        let discrepancy = sqlx::query_as::<_, ProviderDiscrepancy>(&format!(
+            "SELECT {} FROM provider_discrepancies WHERE id = ?",
+            DISCREPANCY_COLUMNS
+        ))
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(discrepancy)
+    }
+
+    pub async fn list_discrepancies(
+        &self,
+        status: Option<DiscrepancyStatus>,
+        kind: Option<DiscrepancyKind>,
+        limit: Option<i64>,
+    ) -> Result<Vec<ProviderDiscrepancy>, ReconciliationError> {
+        let discrepancies = sqlx::query_as::<_, ProviderDiscrepancy>(&format!(
+            r#"
+            SELECT {} FROM provider_discrepancies
+            WHERE (? IS NULL OR status = ?) AND (? IS NULL OR kind = ?)
+            ORDER BY created_at DESC
+            LIMIT ?
+            "#,
+            DISCREPANCY_COLUMNS
+        ))
+        .bind(status.map(|s| s.as_str()))
+        .bind(status.map(|s| s.as_str()))
+        .bind(kind.map(|k| k.as_str()))
+        .bind(kind.map(|k| k.as_str()))
+        .bind(limit.unwrap_or(50).clamp(1, MAX_PAGE))
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(discrepancies)
+    }
+
+    /// Close an open discrepancy as resolved (fixed on our side) or dismissed
+    /// (accepted drift)
+    pub async fn review_discrepancy(
+        &self,
+        admin_id: &str,
+        id: &str,
+        outcome: DiscrepancyStatus,
+        note: Option<&str>,
+    ) -> Result<ProviderDiscrepancy, ReconciliationError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE provider_discrepancies
+            SET status = ?, note = ?, reviewed_by = ?, reviewed_at = NOW()
+            WHERE id = ? AND status = 'open'
+            "#,
+        )
+        .bind(outcome.as_str())
+        .bind(note)
+        .bind(admin_id)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        let discrepancy = self.get_discrepancy(id).await?.ok_or(ReconciliationError::DiscrepancyNotFound)?;
+        if result.rows_affected() == 0 {
+            return Err(ReconciliationError::InvalidDiscrepancyState(discrepancy.status));
+        }
+        Ok(discrepancy)
+    }
+}
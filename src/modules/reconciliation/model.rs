@@ -0,0 +1,97 @@
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+// =============================================================================
+// RECONCILIATION RUN
+// =============================================================================
+
+/// One pass of the provider reconciliation job
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct ReconciliationRun {
+    pub id: String,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub swaps_checked: u32,
+    pub discrepancies: u32,
+    pub errors: u32,
+}
+
+// =============================================================================
+// PROVIDER DISCREPANCY
+// =============================================================================
+
+/// A swap whose record disagrees with the provider's view of the order
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct ProviderDiscrepancy {
+    pub id: String,
+    pub run_id: String,
+    pub swap_id: String,
+    pub provider_id: String,
+    pub provider_swap_id: String,
+    pub kind: DiscrepancyKind,
+    pub our_value: String,
+    pub provider_value: String,
+    pub status: DiscrepancyStatus,
+    pub note: Option<String>,
+    pub reviewed_by: Option<String>,
+    pub reviewed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, sqlx::Type, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case")]
+pub enum DiscrepancyKind {
+    /// The provider reports a different outcome (e.g. refunded directly)
+    StatusMismatch,
+    /// The provider received a different deposit amount
+    AmountMismatch,
+    /// The provider has no record of the order
+    MissingAtProvider,
+}
+
+impl DiscrepancyKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DiscrepancyKind::StatusMismatch => "status_mismatch",
+            DiscrepancyKind::AmountMismatch => "amount_mismatch",
+            DiscrepancyKind::MissingAtProvider => "missing_at_provider",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, sqlx::Type, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case")]
+pub enum DiscrepancyStatus {
+    Open,
+    Resolved,
+    Dismissed,
+}
+
+impl DiscrepancyStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DiscrepancyStatus::Open => "open",
+            DiscrepancyStatus::Resolved => "resolved",
+            DiscrepancyStatus::Dismissed => "dismissed",
+        }
+    }
+}
+
+// =============================================================================
+// RECONCILABLE SWAP
+// =============================================================================
+
+/// The fields of a swap compared against the provider
+#[derive(Debug, Clone, FromRow)]
+pub struct ReconcilableSwap {
+    pub id: String,
+    pub provider_id: String,
+    pub provider_swap_id: String,
+    pub status: String,
+    pub amount: f64,
+}
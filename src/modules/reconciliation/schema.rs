@@ -0,0 +1,118 @@
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::model::{DiscrepancyKind, DiscrepancyStatus, ProviderDiscrepancy, ReconciliationRun};
+
+// =============================================================================
+// RUNS
+// =============================================================================
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ReconciliationRunsQuery {
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ReconciliationRunResponse {
+    /// Also the id of the job tracking the run, see GET /jobs/{id}
+    pub id: String,
+    pub started_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub finished_at: Option<DateTime<Utc>>,
+    pub swaps_checked: u32,
+    pub discrepancies: u32,
+    pub errors: u32,
+}
+
+impl From<ReconciliationRun> for ReconciliationRunResponse {
+    fn from(r: ReconciliationRun) -> Self {
+        Self {
+            id: r.id,
+            started_at: r.started_at,
+            finished_at: r.finished_at,
+            swaps_checked: r.swaps_checked,
+            discrepancies: r.discrepancies,
+            errors: r.errors,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ReconciliationRunsResponse {
+    pub runs: Vec<ReconciliationRunResponse>,
+}
+
+// =============================================================================
+// DISCREPANCIES
+// =============================================================================
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct DiscrepanciesQuery {
+    pub status: Option<DiscrepancyStatus>,
+    pub kind: Option<DiscrepancyKind>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct DiscrepancyResponse {
+    pub id: String,
+    pub run_id: String,
+    pub swap_id: String,
+    pub provider_id: String,
+    pub provider_swap_id: String,
+    pub kind: DiscrepancyKind,
+    pub our_value: String,
+    pub provider_value: String,
+    pub status: DiscrepancyStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reviewed_by: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reviewed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<ProviderDiscrepancy> for DiscrepancyResponse {
+    fn from(d: ProviderDiscrepancy) -> Self {
+        Self {
+            id: d.id,
+            run_id: d.run_id,
+            swap_id: d.swap_id,
+            provider_id: d.provider_id,
+            provider_swap_id: d.provider_swap_id,
+            kind: d.kind,
+            our_value: d.our_value,
+            provider_value: d.provider_value,
+            status: d.status,
+            note: d.note,
+            reviewed_by: d.reviewed_by,
+            reviewed_at: d.reviewed_at,
+            created_at: d.created_at,
+            updated_at: d.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct DiscrepanciesResponse {
+    pub discrepancies: Vec<DiscrepancyResponse>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ReviewDiscrepancyRequest {
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ReconciliationErrorResponse {
+    pub error: String,
+}
+
+impl ReconciliationErrorResponse {
+    pub fn new(error: impl Into<String>) -> Self {
+        Self { error: error.into() }
+    }
+}
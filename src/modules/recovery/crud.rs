@@ -0,0 +1,757 @@
+use axum::http::StatusCode;
+use sqlx::{MySql, Pool};
+use uuid::Uuid;
+
+use crate::services::amount::Decimal;
+use crate::config::rpc_config::get_rpc_config;
+use crate::modules::jobs::crud::JobCrud;
+use crate::modules::jobs::model::JobKind;
+use super::model::{
+    AllocatedIndex, FundsAsset, MemoDeposit, MemoDepositStatus, OrphanedFunds, OrphanedFundsKind, OrphanedFundsStatus,
+    WalletAuditRun, WrongNetworkCase, WrongNetworkStatus,
+};
+
+const MAX_CASES_PAGE: i64 = 200;
+
+const CASE_COLUMNS: &str = r#"
+    id, swap_id, address, address_index, expected_network, detected_network,
+    CAST(amount AS DOUBLE) as amount, CAST(status AS CHAR) as status,
+    recovery_address, recovery_tx_hash, CAST(recovered_amount AS DOUBLE) as recovered_amount,
+    error, dismiss_reason, resolved_by, resolved_at, created_at, updated_at
+"#;
+
+const MEMO_DEPOSIT_COLUMNS: &str = r#"
+    id, network, tx_hash, memo, amount, swap_id, CAST(status AS CHAR) as status,
+    CAST(reason AS CHAR) as reason, note, resolved_by, resolved_at, detected_at
+"#;
+
+const ORPHANED_FUNDS_COLUMNS: &str = r#"
+    id, run_id, address_index, address, chain, currency, token_contract, swap_id, swap_status,
+    CAST(kind AS CHAR) as kind,
+    amount, CAST(status AS CHAR) as status, recovery_address, recovery_tx_hash, recovered_amount,
+    error, dismiss_reason, resolved_by, resolved_at, created_at, updated_at
+"#;
+
+// =============================================================================
+// RECOVERY ERROR
+// =============================================================================
+
+#[derive(Debug)]
+pub enum RecoveryError {
+    CaseNotFound,
+    InvalidCaseState(WrongNetworkStatus),
+    MemoDepositNotFound,
+    InvalidMemoDepositState(MemoDepositStatus),
+    OrphanedFundsNotFound,
+    InvalidOrphanedFundsState(OrphanedFundsStatus),
+    /// The swap doesn't exist or doesn't settle on the deposit's chain
+    SwapNotOnNetwork,
+    InvalidAddress,
+    NetworkNotConfigured(String),
+    /// Recovery sweeps only run on EVM chains
+    SweepUnsupported(String),
+    /// The payout guard refused the sweep
+    PayoutRefused(String),
+    PayoutFailed(String),
+    DatabaseError(String),
+}
+
+impl std::fmt::Display for RecoveryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RecoveryError::CaseNotFound => write!(f, "Wrong-network case not found"),
+            RecoveryError::InvalidCaseState(status) => {
+                write!(f, "Case cannot be changed in state {}", status.as_str())
+            }
+            RecoveryError::MemoDepositNotFound => write!(f, "Memo deposit not found"),
+            RecoveryError::InvalidMemoDepositState(status) => {
+                write!(f, "Memo deposit is already {}", status.as_str())
+            }
+            RecoveryError::OrphanedFundsNotFound => write!(f, "Orphaned funds not found"),
+            RecoveryError::InvalidOrphanedFundsState(status) => {
+                write!(f, "Orphaned funds cannot be changed in state {}", status.as_str())
+            }
+            RecoveryError::SwapNotOnNetwork => write!(f, "Swap does not settle on the deposit's network"),
+            RecoveryError::InvalidAddress => write!(f, "Invalid EVM recipient address"),
+            RecoveryError::NetworkNotConfigured(network) => {
+                write!(f, "No RPC endpoint configured for {}", network)
+            }
+            RecoveryError::SweepUnsupported(chain) => write!(
+                f,
+                "Recovery sweeps are not supported on {}; move the funds by hand and dismiss the entry",
+                chain
+            ),
+            RecoveryError::PayoutRefused(e) => write!(f, "Recovery payout refused: {}", e),
+            RecoveryError::PayoutFailed(e) => write!(f, "Recovery payout failed: {}", e),
+            RecoveryError::DatabaseError(e) => write!(f, "Database error: {}", e),
+        }
+    }
+}
+
+impl RecoveryError {
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            RecoveryError::CaseNotFound => StatusCode::NOT_FOUND,
+            RecoveryError::InvalidCaseState(_) => StatusCode::CONFLICT,
+            RecoveryError::MemoDepositNotFound => StatusCode::NOT_FOUND,
+            RecoveryError::InvalidMemoDepositState(_) => StatusCode::CONFLICT,
+            RecoveryError::OrphanedFundsNotFound => StatusCode::NOT_FOUND,
+            RecoveryError::InvalidOrphanedFundsState(_) => StatusCode::CONFLICT,
+            RecoveryError::SwapNotOnNetwork => StatusCode::BAD_REQUEST,
+            RecoveryError::InvalidAddress => StatusCode::BAD_REQUEST,
+            RecoveryError::NetworkNotConfigured(_) => StatusCode::SERVICE_UNAVAILABLE,
+            RecoveryError::SweepUnsupported(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            RecoveryError::PayoutRefused(_) => StatusCode::SERVICE_UNAVAILABLE,
+            RecoveryError::PayoutFailed(_) => StatusCode::BAD_GATEWAY,
+            RecoveryError::DatabaseError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+impl From<sqlx::Error> for RecoveryError {
+    fn from(err: sqlx::Error) -> Self {
+        RecoveryError::DatabaseError(err.to_string())
+    }
+}
+
+// =============================================================================
+// RECOVERY CRUD
+// =============================================================================
+
+pub struct RecoveryCrud {
+    pool: Pool<MySql>,
+}
+
+impl RecoveryCrud {
+    pub fn new(pool: Pool<MySql>) -> Self {
+        Self { pool }
+    }
+
+    /// Record funds seen on the wrong network. Repeated sightings update the
+    /// amount of an open case instead of opening another. Returns true when
+    /// a new case was created.
+    pub async fn record_detection(
+        &self,
+        swap_id: &str,
+        address: &str,
+        address_index: u32,
+        expected_network: &str,
+        detected_network: &str,
+        amount: Decimal,
+    ) -> Result<bool, RecoveryError> {
+        let updated = sqlx::query(
+            r#"
+            UPDATE wrong_network_cases
+            SET amount = ?, updated_at = NOW()
+            WHERE swap_id = ? AND detected_network = ? AND status = 'open'
+            "#,
+        )
+        .bind(amount)
+        .bind(swap_id)
+        .bind(detected_network)
+        .execute(&self.pool)
+        .await?;
+        if updated.rows_affected() > 0 {
+            return Ok(false);
+        }
+
+        // Cases already being recovered or closed are left alone
+        let inserted = sqlx::query(
+            r#"
+            INSERT IGNORE INTO wrong_network_cases
+                (id, swap_id, address, address_index, expected_network, detected_network, amount)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(swap_id)
+        .bind(address)
+        .bind(address_index)
+        .bind(expected_network)
+        .bind(detected_network)
+        .bind(amount)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(inserted.rows_affected() == 1)
+    }
+
+    pub async fn get_case(&self, case_id: &str) -> Result<Option<WrongNetworkCase>, RecoveryError> {
+        let case = sqlx::query_as::<_, WrongNetworkCase>(&format!(
+            "SELECT {} FROM wrong_network_cases WHERE id = ?",
+            CASE_COLUMNS
+        ))
+        .bind(case_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(case)
+    }
+
+    pub async fn list_cases(
+        &self,
+        status: Option<WrongNetworkStatus>,
+        limit: Option<i64>,
+    ) -> Result<Vec<WrongNetworkCase>, RecoveryError> {
+        let limit = limit.unwrap_or(50).clamp(1, MAX_CASES_PAGE);
+        let cases = sqlx::query_as::<_, WrongNetworkCase>(&format!(
+            r#"
+            SELECT {} FROM wrong_network_cases
+            WHERE (? IS NULL OR status = ?)
+            ORDER BY created_at DESC
+            LIMIT ?
+            "#,
+            CASE_COLUMNS
+        ))
+        .bind(status.map(|s| s.as_str()))
+        .bind(status.map(|s| s.as_str()))
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(cases)
+    }
+
+    /// Claim a case for a recovery payout (open/failed -> recovering), so two
+    /// admins cannot send the same funds twice
+    pub async fn claim_for_recovery(
+        &self,
+        admin_id: &str,
+        case_id: &str,
+        recipient_address: &str,
+    ) -> Result<WrongNetworkCase, RecoveryError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE wrong_network_cases
+            SET status = 'recovering', recovery_address = ?, resolved_by = ?, error = NULL, updated_at = NOW()
+            WHERE id = ? AND status IN ('open', 'failed')
+            "#,
+        )
+        .bind(recipient_address)
+        .bind(admin_id)
+        .bind(case_id)
+        .execute(&self.pool)
+        .await?;
+
+        let case = self.get_case(case_id).await?.ok_or(RecoveryError::CaseNotFound)?;
+        if result.rows_affected() == 0 {
+            return Err(RecoveryError::InvalidCaseState(case.status));
+        }
+        Ok(case)
+    }
+
+    pub async fn complete_recovery(
+        &self,
+        case_id: &str,
+        tx_hash: &str,
+        amount: Decimal,
+    ) -> Result<WrongNetworkCase, RecoveryError> {
+        sqlx::query(
+            r#"
+            UPDATE wrong_network_cases
+            SET status = 'recovered', recovery_tx_hash = ?, recovered_amount = ?, resolved_at = NOW(), updated_at = NOW()
+            WHERE id = ? AND status = 'recovering'
+            "#,
+        )
+        .bind(tx_hash)
+        .bind(amount)
+        .bind(case_id)
+        .execute(&self.pool)
+        .await?;
+
+        self.get_case(case_id).await?.ok_or(RecoveryError::CaseNotFound)
+    }
+
+    /// Return a case to a retryable state after a failed payout
+    pub async fn fail_recovery(&self, case_id: &str, error: &str) -> Result<(), RecoveryError> {
+        sqlx::query(
+            "UPDATE wrong_network_cases SET status = 'failed', error = ?, updated_at = NOW() WHERE id = ? AND status = 'recovering'",
+        )
+        .bind(error)
+        .bind(case_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Close a case without moving funds (dust, handled off-platform)
+    pub async fn dismiss_case(
+        &self,
+        admin_id: &str,
+        case_id: &str,
+        reason: Option<&str>,
+    ) -> Result<WrongNetworkCase, RecoveryError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE wrong_network_cases
+            SET status = 'dismissed', dismiss_reason = ?, resolved_by = ?, resolved_at = NOW(), updated_at = NOW()
+            WHERE id = ? AND status IN ('open', 'failed')
+            "#,
+        )
+        .bind(reason)
+        .bind(admin_id)
+        .bind(case_id)
+        .execute(&self.pool)
+        .await?;
+
+        let case = self.get_case(case_id).await?.ok_or(RecoveryError::CaseNotFound)?;
+        if result.rows_affected() == 0 {
+            return Err(RecoveryError::InvalidCaseState(case.status));
+        }
+        Ok(case)
+    }
+
+    pub async fn get_memo_deposit(&self, id: i64) -> Result<Option<MemoDeposit>, RecoveryError> {
+        let deposit = sqlx::query_as::<_, MemoDeposit>(&format!(
+            "SELECT {} FROM memo_deposits WHERE id = ?",
+            MEMO_DEPOSIT_COLUMNS
+        ))
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(deposit)
+    }
+
+    pub async fn list_memo_deposits(
+        &self,
+        status: Option<MemoDepositStatus>,
+        limit: Option<i64>,
+    ) -> Result<Vec<MemoDeposit>, RecoveryError> {
+        let limit = limit.unwrap_or(50).clamp(1, MAX_CASES_PAGE);
+        let deposits = sqlx::query_as::<_, MemoDeposit>(&format!(
+            r#"
+            SELECT {} FROM memo_deposits
+            WHERE (? IS NULL OR status = ?)
+            ORDER BY detected_at DESC
+            LIMIT ?
+            "#,
+            MEMO_DEPOSIT_COLUMNS
+        ))
+        .bind(status.map(|s| s.as_str()))
+        .bind(status.map(|s| s.as_str()))
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(deposits)
+    }
+
+    /// Attribute a quarantined deposit to a swap on the same memo chain. The
+    /// caller credits the swap once this succeeds.
+    pub async fn release_memo_deposit(
+        &self,
+        admin_id: &str,
+        id: i64,
+        swap_id: &str,
+        note: Option<&str>,
+    ) -> Result<MemoDeposit, RecoveryError> {
+        let deposit = self.get_memo_deposit(id).await?.ok_or(RecoveryError::MemoDepositNotFound)?;
+
+        let on_network: Option<(String,)> = sqlx::query_as(
+            "SELECT swap_id FROM swap_address_info WHERE swap_id = ? AND memo_network = ?",
+        )
+        .bind(swap_id)
+        .bind(&deposit.network)
+        .fetch_optional(&self.pool)
+        .await?;
+        if on_network.is_none() {
+            return Err(RecoveryError::SwapNotOnNetwork);
+        }
+
+        self.close_memo_deposit(admin_id, id, MemoDepositStatus::Released, Some(swap_id), note).await
+    }
+
+    /// Close a quarantined deposit without crediting a swap (refunded to the
+    /// sender off-platform, dust)
+    pub async fn dismiss_memo_deposit(
+        &self,
+        admin_id: &str,
+        id: i64,
+        note: Option<&str>,
+    ) -> Result<MemoDeposit, RecoveryError> {
+        self.close_memo_deposit(admin_id, id, MemoDepositStatus::Dismissed, None, note).await
+    }
+
+    async fn close_memo_deposit(
+        &self,
+        admin_id: &str,
+        id: i64,
+        outcome: MemoDepositStatus,
+        swap_id: Option<&str>,
+        note: Option<&str>,
+    ) -> Result<MemoDeposit, RecoveryError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE memo_deposits
+            SET status = ?, swap_id = COALESCE(?, swap_id), note = ?, resolved_by = ?, resolved_at = NOW()
+            WHERE id = ? AND status = 'quarantined'
+            "#,
+        )
+        .bind(outcome.as_str())
+        .bind(swap_id)
+        .bind(note)
+        .bind(admin_id)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        let deposit = self.get_memo_deposit(id).await?.ok_or(RecoveryError::MemoDepositNotFound)?;
+        if result.rows_affected() == 0 {
+            return Err(RecoveryError::InvalidMemoDepositState(deposit.status));
+        }
+        Ok(deposit)
+    }
+
+    // =========================================================================
+    // WALLET AUDIT
+    // =========================================================================
+
+    /// Record an audit run, with a system job of the same id admins can follow
+    pub async fn start_audit_run(&self) -> Result<String, RecoveryError> {
+        let run_id = Uuid::new_v4().to_string();
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("INSERT INTO wallet_audit_runs (id) VALUES (?)")
+            .bind(&run_id)
+            .execute(&mut *tx)
+            .await?;
+        JobCrud::create(&mut tx, &run_id, JobKind::WalletAudit, None).await?;
+        tx.commit().await?;
+
+        Ok(run_id)
+    }
+
+    pub async fn finish_audit_run(&self, run: &WalletAuditRun) -> Result<(), RecoveryError> {
+        sqlx::query(
+            r#"
+            UPDATE wallet_audit_runs
+            SET finished_at = NOW(), indices_checked = ?, balance_checks = ?, findings = ?, errors = ?,
+                unaudited_indices = ?, unaudited_chains = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(run.indices_checked)
+        .bind(run.balance_checks)
+        .bind(run.findings)
+        .bind(run.errors)
+        .bind(run.unaudited_indices)
+        .bind(&run.unaudited_chains)
+        .bind(&run.id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_audit_run(&self, run_id: &str) -> Result<Option<WalletAuditRun>, RecoveryError> {
+        let run = sqlx::query_as::<_, WalletAuditRun>(
+            r#"
+            SELECT id, started_at, finished_at, indices_checked, balance_checks, findings, errors,
+                   unaudited_indices, unaudited_chains
+            FROM wallet_audit_runs WHERE id = ?
+            "#,
+        )
+        .bind(run_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(run)
+    }
+
+    pub async fn list_audit_runs(&self, limit: Option<i64>) -> Result<Vec<WalletAuditRun>, RecoveryError> {
+        let runs = sqlx::query_as::<_, WalletAuditRun>(
+            r#"
+            SELECT id, started_at, finished_at, indices_checked, balance_checks, findings, errors,
+                   unaudited_indices, unaudited_chains
+            FROM wallet_audit_runs
+            ORDER BY started_at DESC
+            LIMIT ?
+            "#,
+        )
+        .bind(limit.unwrap_or(20).clamp(1, MAX_CASES_PAGE))
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(runs)
+    }
+
+    /// Number of indices `allocated_indices` pages through
+    pub async fn count_allocated_indices(&self) -> Result<u64, RecoveryError> {
+        let (count,): (i64,) = sqlx::query_as(
+            r#"
+            SELECT
+                (SELECT COUNT(*) FROM swap_address_info WHERE our_memo IS NULL)
+              + (SELECT COUNT(*) FROM provider_order_intents pi
+                 WHERE pi.our_memo IS NULL AND pi.status <> 'committed'
+                   AND NOT EXISTS (SELECT 1 FROM swap_address_info sa WHERE sa.swap_id = pi.swap_id))
+            "#,
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(count.max(0) as u64)
+    }
+
+    /// Derivation indices handed out to swaps, and to provider order intents
+    /// that never became one, in index order after `(after_index, after_swap_id)`.
+    /// Memo deposits share the chain's hot address and are left out.
+    pub async fn allocated_indices(
+        &self,
+        after_index: Option<u32>,
+        after_swap_id: &str,
+        limit: i64,
+    ) -> Result<Vec<AllocatedIndex>, RecoveryError> {
+        let indices = sqlx::query_as::<_, AllocatedIndex>(
+            r#"
+            SELECT i.address_index, i.address, i.swap_id,
+                   CAST(s.status AS CHAR) as swap_status, s.to_network as network,
+                   CAST(sa.status AS CHAR) as payout_status,
+                   COALESCE(s.updated_at, i.updated_at) as updated_at,
+                   EXISTS (
+                       SELECT 1 FROM refunds r WHERE r.swap_id = i.swap_id AND r.status <> 'COMPLETED'
+                   ) as refund_open
+            FROM (
+                SELECT address_index, our_address as address, swap_id, created_at as updated_at
+                FROM swap_address_info
+                WHERE our_memo IS NULL
+                UNION ALL
+                SELECT pi.address_index, pi.our_address, pi.swap_id, pi.updated_at
+                FROM provider_order_intents pi
+                WHERE pi.our_memo IS NULL AND pi.status <> 'committed'
+                  AND NOT EXISTS (SELECT 1 FROM swap_address_info sa WHERE sa.swap_id = pi.swap_id)
+            ) i
+            LEFT JOIN swaps s ON s.id = i.swap_id
+            LEFT JOIN swap_address_info sa ON sa.swap_id = i.swap_id
+            WHERE ? IS NULL OR (i.address_index, i.swap_id) > (?, ?)
+            ORDER BY i.address_index, i.swap_id
+            LIMIT ?
+            "#,
+        )
+        .bind(after_index)
+        .bind(after_index)
+        .bind(after_swap_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(indices)
+    }
+
+    /// Whether an open wrong-network case already covers funds for the swap
+    /// on `chain`
+    pub async fn has_open_wrong_network_case(&self, swap_id: &str, chain: &str) -> Result<bool, RecoveryError> {
+        let case: Option<(String,)> = sqlx::query_as(
+            r#"
+            SELECT id FROM wrong_network_cases
+            WHERE swap_id = ? AND detected_network = ? AND status IN ('open', 'recovering', 'failed')
+            "#,
+        )
+        .bind(swap_id)
+        .bind(chain)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(case.is_some())
+    }
+
+    /// Queue funds found by an audit run. A repeat sighting refreshes the
+    /// amount and reopens a recovered entry (new funds arrived since);
+    /// dismissed entries stay dismissed. Returns true for a new entry.
+    pub async fn record_orphaned_funds(
+        &self,
+        run_id: &str,
+        index: &AllocatedIndex,
+        asset: &FundsAsset,
+        kind: OrphanedFundsKind,
+        amount: Decimal,
+    ) -> Result<bool, RecoveryError> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO orphaned_funds
+                (id, run_id, address_index, address, chain, currency, token_contract, swap_id, swap_status, kind, amount)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            ON DUPLICATE KEY UPDATE
+                run_id = VALUES(run_id),
+                swap_status = VALUES(swap_status),
+                kind = VALUES(kind),
+                amount = VALUES(amount),
+                status = IF(status = 'recovered', 'open', status)
+            "#,
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(run_id)
+        .bind(index.address_index)
+        .bind(&index.address)
+        .bind(&asset.chain)
+        .bind(&asset.currency)
+        .bind(&asset.token_contract)
+        .bind(&index.swap_id)
+        .bind(&index.swap_status)
+        .bind(kind.as_str())
+        .bind(amount)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    pub async fn get_orphaned_funds(&self, id: &str) -> Result<Option<OrphanedFunds>, RecoveryError> {
+        let funds = sqlx::query_as::<_, OrphanedFunds>(&format!(
+            "SELECT {} FROM orphaned_funds WHERE id = ?",
+            ORPHANED_FUNDS_COLUMNS
+        ))
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(funds)
+    }
+
+    pub async fn list_orphaned_funds(
+        &self,
+        status: Option<OrphanedFundsStatus>,
+        kind: Option<OrphanedFundsKind>,
+        limit: Option<i64>,
+    ) -> Result<Vec<OrphanedFunds>, RecoveryError> {
+        let funds = sqlx::query_as::<_, OrphanedFunds>(&format!(
+            r#"
+            SELECT {} FROM orphaned_funds
+            WHERE (? IS NULL OR status = ?) AND (? IS NULL OR kind = ?)
+            ORDER BY created_at DESC
+            LIMIT ?
+            "#,
+            ORPHANED_FUNDS_COLUMNS
+        ))
+        .bind(status.map(|s| s.as_str()))
+        .bind(status.map(|s| s.as_str()))
+        .bind(kind.map(|k| k.as_str()))
+        .bind(kind.map(|k| k.as_str()))
+        .bind(limit.unwrap_or(50).clamp(1, MAX_CASES_PAGE))
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(funds)
+    }
+
+    /// Claim orphaned funds for a recovery payout (open/failed -> recovering),
+    /// so two admins cannot sweep them twice
+    pub async fn claim_orphaned_funds(
+        &self,
+        admin_id: &str,
+        id: &str,
+        recipient_address: &str,
+    ) -> Result<OrphanedFunds, RecoveryError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE orphaned_funds
+            SET status = 'recovering', recovery_address = ?, resolved_by = ?, error = NULL
+            WHERE id = ? AND status IN ('open', 'failed')
+            "#,
+        )
+        .bind(recipient_address)
+        .bind(admin_id)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        let funds = self.get_orphaned_funds(id).await?.ok_or(RecoveryError::OrphanedFundsNotFound)?;
+        if result.rows_affected() == 0 {
+            return Err(RecoveryError::InvalidOrphanedFundsState(funds.status));
+        }
+        Ok(funds)
+    }
+
+    pub async fn complete_orphaned_funds_recovery(
+        &self,
+        id: &str,
+        tx_hash: &str,
+        amount: Decimal,
+    ) -> Result<OrphanedFunds, RecoveryError> {
+        sqlx::query(
+            r#"
+            UPDATE orphaned_funds
+            SET status = 'recovered', recovery_tx_hash = ?, recovered_amount = ?, resolved_at = NOW()
+            WHERE id = ? AND status = 'recovering'
+            "#,
+        )
+        .bind(tx_hash)
+        .bind(amount)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        self.get_orphaned_funds(id).await?.ok_or(RecoveryError::OrphanedFundsNotFound)
+    }
+
+    /// Return orphaned funds to a retryable state after a failed payout
+    pub async fn fail_orphaned_funds_recovery(&self, id: &str, error: &str) -> Result<(), RecoveryError> {
+        sqlx::query("UPDATE orphaned_funds SET status = 'failed', error = ? WHERE id = ? AND status = 'recovering'")
+            .bind(error)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Close an entry without moving funds (dust, accounted for elsewhere)
+    pub async fn dismiss_orphaned_funds(
+        &self,
+        admin_id: &str,
+        id: &str,
+        reason: Option<&str>,
+    ) -> Result<OrphanedFunds, RecoveryError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE orphaned_funds
+            SET status = 'dismissed', dismiss_reason = ?, resolved_by = ?, resolved_at = NOW()
+            WHERE id = ? AND status IN ('open', 'failed')
+            "#,
+        )
+        .bind(reason)
+        .bind(admin_id)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+
+        let funds = self.get_orphaned_funds(id).await?.ok_or(RecoveryError::OrphanedFundsNotFound)?;
+        if result.rows_affected() == 0 {
+            return Err(RecoveryError::InvalidOrphanedFundsState(funds.status));
+        }
+        Ok(funds)
+    }
+}
+
+/// `0x` followed by 40 hex digits
+pub fn is_evm_address(address: &str) -> bool {
+    address
+        .strip_prefix("0x")
+        .is_some_and(|hex| hex.len() == 40 && hex.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+/// EIP-155 chain id of a canonical chain name, from the RPC config table
+pub fn evm_chain_id(chain: &str) -> Option<u32> {
+    let hex = get_rpc_config(chain)?.chain_id?;
+    u32::from_str_radix(hex.trim_start_matches("0x"), 16).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_evm_address() {
+        assert!(is_evm_address("0x742d35Cc6634C0532925a3b844Bc454e4438f44e"));
+        assert!(!is_evm_address("742d35Cc6634C0532925a3b844Bc454e4438f44e"));
+        assert!(!is_evm_address("0x742d35Cc6634C0532925a3b844Bc454e4438f4"));
+        assert!(!is_evm_address("0xZZ2d35Cc6634C0532925a3b844Bc454e4438f44e"));
+    }
+
+    #[test]
+    fn test_evm_chain_id() {
+        assert_eq!(evm_chain_id("ethereum"), Some(1));
+        assert_eq!(evm_chain_id("bsc"), Some(56));
+        assert_eq!(evm_chain_id("solana"), None);
+    }
+}
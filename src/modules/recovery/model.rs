@@ -0,0 +1,237 @@
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+// =============================================================================
+// WRONG-NETWORK CASE
+// =============================================================================
+
+/// Funds found at a swap's deposit address on an EVM chain other than the
+/// one the swap expects
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct WrongNetworkCase {
+    pub id: String,
+    pub swap_id: String,
+    pub address: String,
+    pub address_index: u32,
+    pub expected_network: String,
+    pub detected_network: String,
+    pub amount: f64,
+    pub status: WrongNetworkStatus,
+    pub recovery_address: Option<String>,
+    pub recovery_tx_hash: Option<String>,
+    pub recovered_amount: Option<f64>,
+    pub error: Option<String>,
+    pub dismiss_reason: Option<String>,
+    pub resolved_by: Option<String>,
+    pub resolved_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, sqlx::Type, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case")]
+pub enum WrongNetworkStatus {
+    Open,
+    Recovering,
+    Recovered,
+    Failed,
+    Dismissed,
+}
+
+impl WrongNetworkStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WrongNetworkStatus::Open => "open",
+            WrongNetworkStatus::Recovering => "recovering",
+            WrongNetworkStatus::Recovered => "recovered",
+            WrongNetworkStatus::Failed => "failed",
+            WrongNetworkStatus::Dismissed => "dismissed",
+        }
+    }
+}
+
+// =============================================================================
+// MEMO DEPOSIT
+// =============================================================================
+
+/// A payment into a memo chain's shared hot address
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct MemoDeposit {
+    pub id: i64,
+    pub network: String,
+    pub tx_hash: String,
+    pub memo: Option<String>,
+    pub amount: f64,
+    pub swap_id: Option<String>,
+    pub status: MemoDepositStatus,
+    pub reason: Option<String>,
+    pub note: Option<String>,
+    pub resolved_by: Option<String>,
+    pub resolved_at: Option<DateTime<Utc>>,
+    pub detected_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, sqlx::Type, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case")]
+pub enum MemoDepositStatus {
+    /// Matched to a swap by memo
+    Credited,
+    /// Waiting for an operator
+    Quarantined,
+    /// Attributed to a swap by an operator
+    Released,
+    /// Closed without crediting a swap
+    Dismissed,
+}
+
+impl MemoDepositStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MemoDepositStatus::Credited => "credited",
+            MemoDepositStatus::Quarantined => "quarantined",
+            MemoDepositStatus::Released => "released",
+            MemoDepositStatus::Dismissed => "dismissed",
+        }
+    }
+}
+
+// =============================================================================
+// WALLET AUDIT
+// =============================================================================
+
+/// One pass of the HD wallet audit
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct WalletAuditRun {
+    pub id: String,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub indices_checked: u32,
+    pub balance_checks: u32,
+    pub findings: u32,
+    pub errors: u32,
+    /// Indices with a chain no node is configured for
+    pub unaudited_indices: u32,
+    /// Comma-separated chains those indices could not be checked on
+    pub unaudited_chains: String,
+}
+
+impl WalletAuditRun {
+    pub fn unaudited_chains(&self) -> Vec<String> {
+        self.unaudited_chains.split(',').filter(|c| !c.is_empty()).map(str::to_string).collect()
+    }
+}
+
+/// A derivation index handed out to a swap or a provider order intent, with
+/// what the swap says should be at its address
+#[derive(Debug, Clone, FromRow)]
+pub struct AllocatedIndex {
+    pub address_index: u32,
+    pub address: String,
+    pub swap_id: String,
+    /// None when the order intent never became a swap
+    pub swap_status: Option<String>,
+    pub network: Option<String>,
+    /// `pending`, `success` or `failed` for the swap's payout
+    pub payout_status: Option<String>,
+    pub updated_at: Option<DateTime<Utc>>,
+    /// A refund of this swap is queued or in progress
+    pub refund_open: bool,
+}
+
+/// What a balance found by the audit is held in
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FundsAsset {
+    pub chain: String,
+    pub currency: String,
+    /// ERC-20 contract; None for the chain's native coin
+    pub token_contract: Option<String>,
+}
+
+impl FundsAsset {
+    pub fn native(chain: &str, currency: &str) -> Self {
+        Self { chain: chain.to_string(), currency: currency.to_lowercase(), token_contract: None }
+    }
+
+    pub fn token(chain: &str, symbol: &str, contract: &str) -> Self {
+        Self {
+            chain: chain.to_string(),
+            currency: symbol.to_lowercase(),
+            token_contract: Some(contract.to_lowercase()),
+        }
+    }
+}
+
+/// Funds at an allocated address that nothing accounts for
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct OrphanedFunds {
+    pub id: String,
+    pub run_id: String,
+    pub address_index: u32,
+    pub address: String,
+    pub chain: String,
+    /// Lower-case ticker: the chain's native coin or a canonical token
+    pub currency: String,
+    /// ERC-20 contract of token funds; None for the native coin
+    pub token_contract: Option<String>,
+    pub swap_id: String,
+    pub swap_status: Option<String>,
+    pub kind: OrphanedFundsKind,
+    pub amount: f64,
+    pub status: OrphanedFundsStatus,
+    pub recovery_address: Option<String>,
+    pub recovery_tx_hash: Option<String>,
+    pub recovered_amount: Option<f64>,
+    pub error: Option<String>,
+    pub dismiss_reason: Option<String>,
+    pub resolved_by: Option<String>,
+    pub resolved_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, sqlx::Type, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case")]
+pub enum OrphanedFundsKind {
+    /// Left behind after the swap's payout went out
+    Unswept,
+    /// Sent to an address whose swap never took a deposit there: expired,
+    /// failed, never committed, or on another chain
+    UnmatchedDeposit,
+}
+
+impl OrphanedFundsKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OrphanedFundsKind::Unswept => "unswept",
+            OrphanedFundsKind::UnmatchedDeposit => "unmatched_deposit",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, sqlx::Type, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case")]
+pub enum OrphanedFundsStatus {
+    Open,
+    Recovering,
+    Recovered,
+    Failed,
+    Dismissed,
+}
+
+impl OrphanedFundsStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OrphanedFundsStatus::Open => "open",
+            OrphanedFundsStatus::Recovering => "recovering",
+            OrphanedFundsStatus::Recovered => "recovered",
+            OrphanedFundsStatus::Failed => "failed",
+            OrphanedFundsStatus::Dismissed => "dismissed",
+        }
+    }
+}
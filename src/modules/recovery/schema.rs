@@ -0,0 +1,308 @@
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::model::{
+    MemoDeposit, MemoDepositStatus, OrphanedFunds, OrphanedFundsKind, OrphanedFundsStatus, WalletAuditRun,
+    WrongNetworkCase, WrongNetworkStatus,
+};
+
+// =============================================================================
+// WRONG-NETWORK CASES
+// =============================================================================
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct WrongNetworkCasesQuery {
+    pub status: Option<WrongNetworkStatus>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct WrongNetworkCaseResponse {
+    pub id: String,
+    pub swap_id: String,
+    pub address: String,
+    pub expected_network: String,
+    pub detected_network: String,
+    pub amount: f64,
+    pub status: WrongNetworkStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recovery_address: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recovery_tx_hash: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recovered_amount: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dismiss_reason: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resolved_by: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resolved_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<WrongNetworkCase> for WrongNetworkCaseResponse {
+    fn from(c: WrongNetworkCase) -> Self {
+        Self {
+            id: c.id,
+            swap_id: c.swap_id,
+            address: c.address,
+            expected_network: c.expected_network,
+            detected_network: c.detected_network,
+            amount: c.amount,
+            status: c.status,
+            recovery_address: c.recovery_address,
+            recovery_tx_hash: c.recovery_tx_hash,
+            recovered_amount: c.recovered_amount,
+            error: c.error,
+            dismiss_reason: c.dismiss_reason,
+            resolved_by: c.resolved_by,
+            resolved_at: c.resolved_at,
+            created_at: c.created_at,
+            updated_at: c.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct WrongNetworkCasesResponse {
+    pub cases: Vec<WrongNetworkCaseResponse>,
+}
+
+/// Send the stranded funds to `recipient_address` on the chain they landed on
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct RecoverWrongNetworkRequest {
+    pub recipient_address: String,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct DismissWrongNetworkRequest {
+    pub reason: Option<String>,
+}
+
+// =============================================================================
+// MEMO DEPOSITS
+// =============================================================================
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct MemoDepositsQuery {
+    pub status: Option<MemoDepositStatus>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct MemoDepositResponse {
+    pub id: i64,
+    pub network: String,
+    pub tx_hash: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+    pub amount: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub swap_id: Option<String>,
+    pub status: MemoDepositStatus,
+    /// Why the deposit was quarantined
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resolved_by: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resolved_at: Option<DateTime<Utc>>,
+    pub detected_at: DateTime<Utc>,
+}
+
+impl From<MemoDeposit> for MemoDepositResponse {
+    fn from(d: MemoDeposit) -> Self {
+        Self {
+            id: d.id,
+            network: d.network,
+            tx_hash: d.tx_hash,
+            memo: d.memo,
+            amount: d.amount,
+            swap_id: d.swap_id,
+            status: d.status,
+            reason: d.reason,
+            note: d.note,
+            resolved_by: d.resolved_by,
+            resolved_at: d.resolved_at,
+            detected_at: d.detected_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct MemoDepositsResponse {
+    pub deposits: Vec<MemoDepositResponse>,
+}
+
+/// Credit a quarantined deposit to `swap_id`, e.g. after the sender proves
+/// which swap a memo-less payment was for
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ReleaseMemoDepositRequest {
+    pub swap_id: String,
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct DismissMemoDepositRequest {
+    pub note: Option<String>,
+}
+
+// =============================================================================
+// WALLET AUDIT
+// =============================================================================
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct WalletAuditRunsQuery {
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct WalletAuditRunResponse {
+    /// Also the id of the job tracking the run, see GET /jobs/{id}
+    pub id: String,
+    pub started_at: DateTime<Utc>,
+    /// Unset while running, or if the run failed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub finished_at: Option<DateTime<Utc>>,
+    pub indices_checked: u32,
+    /// One per index and chain whose balance was asked for
+    pub balance_checks: u32,
+    pub findings: u32,
+    /// Balances that could not be read
+    pub errors: u32,
+    /// Indices with a chain no node is configured for; nothing was checked
+    /// for them there
+    pub unaudited_indices: u32,
+    /// Chains those indices could not be checked on
+    pub unaudited_chains: Vec<String>,
+}
+
+impl From<WalletAuditRun> for WalletAuditRunResponse {
+    fn from(r: WalletAuditRun) -> Self {
+        let unaudited_chains = r.unaudited_chains();
+        Self {
+            id: r.id,
+            started_at: r.started_at,
+            finished_at: r.finished_at,
+            indices_checked: r.indices_checked,
+            balance_checks: r.balance_checks,
+            findings: r.findings,
+            errors: r.errors,
+            unaudited_indices: r.unaudited_indices,
+            unaudited_chains,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct WalletAuditRunsResponse {
+    /// Newest first
+    pub runs: Vec<WalletAuditRunResponse>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct OrphanedFundsQuery {
+    pub status: Option<OrphanedFundsStatus>,
+    pub kind: Option<OrphanedFundsKind>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct OrphanedFundsResponse {
+    pub id: String,
+    /// Audit run that last saw the funds
+    pub run_id: String,
+    pub address_index: u32,
+    pub address: String,
+    pub chain: String,
+    /// The chain's native coin or a canonical token
+    pub currency: String,
+    /// ERC-20 contract; unset for the native coin
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token_contract: Option<String>,
+    pub swap_id: String,
+    /// Unset for an order intent that never became a swap
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub swap_status: Option<String>,
+    pub kind: OrphanedFundsKind,
+    /// Balance in `currency` when last seen
+    pub amount: f64,
+    pub status: OrphanedFundsStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recovery_address: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recovery_tx_hash: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recovered_amount: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dismiss_reason: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resolved_by: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resolved_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<OrphanedFunds> for OrphanedFundsResponse {
+    fn from(f: OrphanedFunds) -> Self {
+        Self {
+            id: f.id,
+            run_id: f.run_id,
+            address_index: f.address_index,
+            address: f.address,
+            chain: f.chain,
+            currency: f.currency,
+            token_contract: f.token_contract,
+            swap_id: f.swap_id,
+            swap_status: f.swap_status,
+            kind: f.kind,
+            amount: f.amount,
+            status: f.status,
+            recovery_address: f.recovery_address,
+            recovery_tx_hash: f.recovery_tx_hash,
+            recovered_amount: f.recovered_amount,
+            error: f.error,
+            dismiss_reason: f.dismiss_reason,
+            resolved_by: f.resolved_by,
+            resolved_at: f.resolved_at,
+            created_at: f.created_at,
+            updated_at: f.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct OrphanedFundsListResponse {
+    pub funds: Vec<OrphanedFundsResponse>,
+}
+
+/// Sweep the address to `recipient_address` on the chain the funds are on
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct RecoverOrphanedFundsRequest {
+    pub recipient_address: String,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct DismissOrphanedFundsRequest {
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct RecoveryErrorResponse {
+    pub error: String,
+}
+
+impl RecoveryErrorResponse {
+    pub fn new(error: impl Into<String>) -> Self {
+        Self { error: error.into() }
+    }
+}
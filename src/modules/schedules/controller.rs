@@ -0,0 +1,97 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use std::sync::Arc;
+
+use crate::AppState;
+use crate::modules::auth::interface::{scope::{HistoryRead, SwapCreate}, Scoped};
+use super::crud::{ScheduleCrud, ScheduleError};
+use super::schema::{
+    CreateScheduleRequest, ScheduleDetailResponse, ScheduleErrorResponse, ScheduleResponse,
+    SchedulesResponse, UpdateScheduleRequest,
+};
+
+const RECENT_RUNS: i64 = 20;
+
+fn to_error(e: ScheduleError) -> (StatusCode, Json<ScheduleErrorResponse>) {
+    (e.status_code(), Json(ScheduleErrorResponse::new(e.to_string())))
+}
+
+// =============================================================================
+// POST /swap/schedules - Create a recurring swap
+// GET  /swap/schedules - List schedules
+// =============================================================================
+
+pub async fn create_schedule(
+    State(state): State<Arc<AppState>>,
+    user: Scoped<SwapCreate>,
+    Json(payload): Json<CreateScheduleRequest>,
+) -> Result<(StatusCode, Json<ScheduleResponse>), (StatusCode, Json<ScheduleErrorResponse>)> {
+    let crud = ScheduleCrud::new(state.db.clone());
+
+    let schedule = crud.create(&user.0.id, &payload).await.map_err(to_error)?;
+
+    Ok((StatusCode::CREATED, Json(schedule.into())))
+}
+
+pub async fn list_schedules(
+    State(state): State<Arc<AppState>>,
+    user: Scoped<HistoryRead>,
+) -> Result<Json<SchedulesResponse>, (StatusCode, Json<ScheduleErrorResponse>)> {
+    let crud = ScheduleCrud::new(state.db.clone());
+
+    let schedules = crud.list(&user.0.id).await.map_err(to_error)?;
+
+    Ok(Json(SchedulesResponse {
+        schedules: schedules.into_iter().map(Into::into).collect(),
+    }))
+}
+
+// =============================================================================
+// GET    /swap/schedules/{id} - Schedule with recent runs
+// PATCH  /swap/schedules/{id} - Update amount/frequency, pause or resume
+// DELETE /swap/schedules/{id} - Cancel
+// =============================================================================
+
+pub async fn get_schedule(
+    State(state): State<Arc<AppState>>,
+    user: Scoped<HistoryRead>,
+    Path(schedule_id): Path<String>,
+) -> Result<Json<ScheduleDetailResponse>, (StatusCode, Json<ScheduleErrorResponse>)> {
+    let crud = ScheduleCrud::new(state.db.clone());
+
+    let schedule = crud.get(&user.0.id, &schedule_id).await.map_err(to_error)?;
+    let runs = crud.get_runs(&schedule.id, RECENT_RUNS).await.map_err(to_error)?;
+
+    Ok(Json(ScheduleDetailResponse {
+        schedule: schedule.into(),
+        runs,
+    }))
+}
+
+pub async fn update_schedule(
+    State(state): State<Arc<AppState>>,
+    user: Scoped<SwapCreate>,
+    Path(schedule_id): Path<String>,
+    Json(payload): Json<UpdateScheduleRequest>,
+) -> Result<Json<ScheduleResponse>, (StatusCode, Json<ScheduleErrorResponse>)> {
+    let crud = ScheduleCrud::new(state.db.clone());
+
+    let schedule = crud.update(&user.0.id, &schedule_id, &payload).await.map_err(to_error)?;
+
+    Ok(Json(schedule.into()))
+}
+
+pub async fn cancel_schedule(
+    State(state): State<Arc<AppState>>,
+    user: Scoped<SwapCreate>,
+    Path(schedule_id): Path<String>,
+) -> Result<StatusCode, (StatusCode, Json<ScheduleErrorResponse>)> {
+    let crud = ScheduleCrud::new(state.db.clone());
+
+    crud.cancel(&user.0.id, &schedule_id).await.map_err(to_error)?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
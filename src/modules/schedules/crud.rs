@@ -0,0 +1,386 @@
+use axum::http::StatusCode;
+use chrono::{DateTime, Utc};
+use sqlx::{MySql, Pool};
+use uuid::Uuid;
+
+use crate::services::amount::Decimal;
+use crate::services::pii::SealedString;
+use super::model::{ScheduleRun, ScheduleStatus, SwapSchedule};
+use super::schema::{CreateScheduleRequest, UpdateScheduleRequest};
+
+pub const MAX_ACTIVE_SCHEDULES_PER_USER: i64 = 20;
+/// Schedules are paused after this many failed runs in a row
+pub const MAX_CONSECUTIVE_FAILURES: i32 = 3;
+
+const SCHEDULE_COLUMNS: &str = r#"
+    id, user_id, from_currency, from_network, to_currency, to_network,
+    amount, provider,
+    recipient_address, recipient_extra_id, refund_address, refund_extra_id,
+    payout_to_balance, CAST(frequency AS CHAR) as frequency, CAST(status AS CHAR) as status,
+    next_run_at, last_run_at, last_swap_id, consecutive_failures, last_error,
+    created_at, updated_at
+"#;
+
+// =============================================================================
+// SCHEDULE ERROR
+// =============================================================================
+
+#[derive(Debug)]
+pub enum ScheduleError {
+    InvalidAmount,
+    InvalidAddress,
+    InvalidStartTime,
+    TooManySchedules,
+    ScheduleNotFound,
+    ScheduleCancelled,
+    DatabaseError(String),
+}
+
+impl std::fmt::Display for ScheduleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScheduleError::InvalidAmount => write!(f, "Amount must be a positive number"),
+            ScheduleError::InvalidAddress => write!(f, "recipient_address is required"),
+            ScheduleError::InvalidStartTime => write!(f, "start_at must not be in the past"),
+            ScheduleError::TooManySchedules => write!(
+                f,
+                "A maximum of {} active schedules is allowed per account",
+                MAX_ACTIVE_SCHEDULES_PER_USER
+            ),
+            ScheduleError::ScheduleNotFound => write!(f, "Schedule not found"),
+            ScheduleError::ScheduleCancelled => write!(f, "Schedule has been cancelled"),
+            ScheduleError::DatabaseError(e) => write!(f, "Database error: {}", e),
+        }
+    }
+}
+
+impl ScheduleError {
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            ScheduleError::InvalidAmount => StatusCode::BAD_REQUEST,
+            ScheduleError::InvalidAddress => StatusCode::BAD_REQUEST,
+            ScheduleError::InvalidStartTime => StatusCode::BAD_REQUEST,
+            ScheduleError::TooManySchedules => StatusCode::CONFLICT,
+            ScheduleError::ScheduleNotFound => StatusCode::NOT_FOUND,
+            ScheduleError::ScheduleCancelled => StatusCode::CONFLICT,
+            ScheduleError::DatabaseError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+impl From<sqlx::Error> for ScheduleError {
+    fn from(err: sqlx::Error) -> Self {
+        ScheduleError::DatabaseError(err.to_string())
+    }
+}
+
+fn validate_amount(amount: Decimal) -> Result<(), ScheduleError> {
+    if amount <= Decimal::ZERO {
+        return Err(ScheduleError::InvalidAmount);
+    }
+    Ok(())
+}
+
+// =============================================================================
+// SCHEDULE CRUD
+// =============================================================================
+
+pub struct ScheduleCrud {
+    pool: Pool<MySql>,
+}
+
+impl ScheduleCrud {
+    pub fn new(pool: Pool<MySql>) -> Self {
+        Self { pool }
+    }
+
+    pub async fn create(
+        &self,
+        user_id: &str,
+        request: &CreateScheduleRequest,
+    ) -> Result<SwapSchedule, ScheduleError> {
+        validate_amount(request.amount.value())?;
+        if !request.payout_to_balance && request.recipient_address.trim().is_empty() {
+            return Err(ScheduleError::InvalidAddress);
+        }
+
+        let now = Utc::now();
+        let next_run_at = match request.start_at {
+            // Allow a little clock skew between client and server
+            Some(start) if start < now - chrono::Duration::minutes(5) => {
+                return Err(ScheduleError::InvalidStartTime)
+            }
+            Some(start) => start,
+            None => now,
+        };
+
+        let (active,): (i64,) = sqlx::query_as(
+            "SELECT COUNT(*) FROM swap_schedules WHERE user_id = ? AND status != 'cancelled'",
+        )
+        .bind(user_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        if active >= MAX_ACTIVE_SCHEDULES_PER_USER {
+            return Err(ScheduleError::TooManySchedules);
+        }
+
+        let id = Uuid::new_v4().to_string();
+
+        sqlx::query(
+            r#"
+            INSERT INTO swap_schedules (
+                id, user_id, from_currency, from_network, to_currency, to_network,
+                amount, provider, recipient_address, recipient_extra_id,
+                refund_address, refund_extra_id, payout_to_balance,
+                frequency, status, next_run_at
+            )
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, 'active', ?)
+            "#,
+        )
+        .bind(&id)
+        .bind(user_id)
+        .bind(&request.from)
+        .bind(&request.network_from)
+        .bind(&request.to)
+        .bind(&request.network_to)
+        .bind(request.amount.value())
+        .bind(&request.provider)
+        .bind(request.recipient_address.trim())
+        .bind(&request.recipient_extra_id)
+        .bind(&request.refund_address)
+        .bind(&request.refund_extra_id)
+        .bind(request.payout_to_balance)
+        .bind(request.frequency)
+        .bind(next_run_at)
+        .execute(&self.pool)
+        .await?;
+
+        self.get(user_id, &id).await
+    }
+
+    pub async fn list(&self, user_id: &str) -> Result<Vec<SwapSchedule>, ScheduleError> {
+        let sql = format!(
+            "SELECT {} FROM swap_schedules WHERE user_id = ? AND status != 'cancelled' ORDER BY created_at DESC",
+            SCHEDULE_COLUMNS
+        );
+
+        let schedules = sqlx::query_as::<_, SwapSchedule>(&sql)
+            .bind(user_id)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(schedules)
+    }
+
+    pub async fn get(&self, user_id: &str, schedule_id: &str) -> Result<SwapSchedule, ScheduleError> {
+        let sql = format!(
+            "SELECT {} FROM swap_schedules WHERE id = ? AND user_id = ?",
+            SCHEDULE_COLUMNS
+        );
+
+        sqlx::query_as::<_, SwapSchedule>(&sql)
+            .bind(schedule_id)
+            .bind(user_id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or(ScheduleError::ScheduleNotFound)
+    }
+
+    pub async fn get_runs(&self, schedule_id: &str, limit: i64) -> Result<Vec<ScheduleRun>, ScheduleError> {
+        let runs = sqlx::query_as::<_, ScheduleRun>(
+            r#"
+            SELECT id, schedule_id, swap_id, succeeded, error, created_at
+            FROM swap_schedule_runs
+            WHERE schedule_id = ?
+            ORDER BY id DESC
+            LIMIT ?
+            "#,
+        )
+        .bind(schedule_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(runs)
+    }
+
+    /// Change amount, cadence, or pause/resume a schedule
+    pub async fn update(
+        &self,
+        user_id: &str,
+        schedule_id: &str,
+        request: &UpdateScheduleRequest,
+    ) -> Result<SwapSchedule, ScheduleError> {
+        let current = self.get(user_id, schedule_id).await?;
+        if current.status == ScheduleStatus::Cancelled {
+            return Err(ScheduleError::ScheduleCancelled);
+        }
+
+        if request.status == Some(ScheduleStatus::Cancelled) {
+            self.cancel(user_id, schedule_id).await?;
+            return self.get(user_id, schedule_id).await;
+        }
+
+        if let Some(amount) = request.amount {
+            validate_amount(amount.value())?;
+        }
+
+        let amount = request.amount.map(|a| a.value()).unwrap_or(current.amount);
+        let frequency = request.frequency.unwrap_or(current.frequency);
+        let status = request.status.unwrap_or(current.status);
+
+        // A resumed schedule should not replay runs it missed while paused
+        let now = Utc::now();
+        let resuming = current.status == ScheduleStatus::Paused && status == ScheduleStatus::Active;
+        let next_run_at = if resuming && current.next_run_at < now { now } else { current.next_run_at };
+        let failures = if resuming { 0 } else { current.consecutive_failures };
+
+        sqlx::query(
+            r#"
+            UPDATE swap_schedules
+            SET amount = ?, frequency = ?, status = ?, next_run_at = ?,
+                consecutive_failures = ?, updated_at = NOW()
+            WHERE id = ? AND user_id = ?
+            "#,
+        )
+        .bind(amount)
+        .bind(frequency)
+        .bind(status)
+        .bind(next_run_at)
+        .bind(failures)
+        .bind(schedule_id)
+        .bind(user_id)
+        .execute(&self.pool)
+        .await?;
+
+        self.get(user_id, schedule_id).await
+    }
+
+    pub async fn cancel(&self, user_id: &str, schedule_id: &str) -> Result<(), ScheduleError> {
+        let result = sqlx::query(
+            "UPDATE swap_schedules SET status = 'cancelled', updated_at = NOW() WHERE id = ? AND user_id = ?",
+        )
+        .bind(schedule_id)
+        .bind(user_id)
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(ScheduleError::ScheduleNotFound);
+        }
+        Ok(())
+    }
+
+    // =========================================================================
+    // WORKER
+    // =========================================================================
+
+    pub async fn get_due(&self, limit: i64) -> Result<Vec<SwapSchedule>, ScheduleError> {
+        let sql = format!(
+            r#"
+            SELECT {} FROM swap_schedules
+            WHERE status = 'active' AND next_run_at <= NOW()
+            ORDER BY next_run_at ASC
+            LIMIT ?
+            "#,
+            SCHEDULE_COLUMNS
+        );
+
+        let schedules = sqlx::query_as::<_, SwapSchedule>(&sql)
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(schedules)
+    }
+
+    /// Advance `next_run_at` if it still holds the value we read.
+    /// Returns false when another worker already claimed this run.
+    pub async fn claim_run(
+        &self,
+        schedule: &SwapSchedule,
+        next_run_at: DateTime<Utc>,
+    ) -> Result<bool, ScheduleError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE swap_schedules
+            SET next_run_at = ?, last_run_at = NOW(), updated_at = NOW()
+            WHERE id = ? AND status = 'active' AND next_run_at = ?
+            "#,
+        )
+        .bind(next_run_at)
+        .bind(&schedule.id)
+        .bind(schedule.next_run_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    pub async fn record_success(&self, schedule_id: &str, swap_id: &str) -> Result<(), ScheduleError> {
+        sqlx::query(
+            r#"
+            UPDATE swap_schedules
+            SET last_swap_id = ?, consecutive_failures = 0, last_error = NULL, updated_at = NOW()
+            WHERE id = ?
+            "#,
+        )
+        .bind(swap_id)
+        .bind(schedule_id)
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query("INSERT INTO swap_schedule_runs (schedule_id, swap_id, succeeded) VALUES (?, ?, TRUE)")
+            .bind(schedule_id)
+            .bind(swap_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Record a failed run. Pauses the schedule once it keeps failing and
+    /// returns true if it was paused.
+    pub async fn record_failure(&self, schedule_id: &str, error: &str) -> Result<bool, ScheduleError> {
+        sqlx::query(
+            r#"
+            UPDATE swap_schedules
+            SET consecutive_failures = consecutive_failures + 1, last_error = ?, updated_at = NOW()
+            WHERE id = ?
+            "#,
+        )
+        .bind(error)
+        .bind(schedule_id)
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query("INSERT INTO swap_schedule_runs (schedule_id, succeeded, error) VALUES (?, FALSE, ?)")
+            .bind(schedule_id)
+            .bind(error)
+            .execute(&self.pool)
+            .await?;
+
+        let paused = sqlx::query(
+            r#"
+            UPDATE swap_schedules SET status = 'paused'
+            WHERE id = ? AND status = 'active' AND consecutive_failures >= ?
+            "#,
+        )
+        .bind(schedule_id)
+        .bind(MAX_CONSECUTIVE_FAILURES)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(paused.rows_affected() == 1)
+    }
+
+    pub async fn get_user_email(&self, user_id: &str) -> Result<Option<String>, ScheduleError> {
+        let row: Option<(SealedString,)> = sqlx::query_as("SELECT email FROM users WHERE id = ?")
+            .bind(user_id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(row.map(|r| r.0.into()))
+    }
+}
@@ -0,0 +1,87 @@
+use chrono::{DateTime, Months, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+use crate::services::amount::Decimal;
+
+// =============================================================================
+// SWAP SCHEDULE
+// =============================================================================
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct SwapSchedule {
+    pub id: String,
+    pub user_id: String,
+
+    // Swap template
+    pub from_currency: String,
+    pub from_network: String,
+    pub to_currency: String,
+    pub to_network: String,
+    pub amount: Decimal,
+    pub provider: String,
+    pub recipient_address: String,
+    pub recipient_extra_id: Option<String>,
+    pub refund_address: Option<String>,
+    pub refund_extra_id: Option<String>,
+    pub payout_to_balance: bool,
+
+    // Cadence
+    pub frequency: ScheduleFrequency,
+    pub status: ScheduleStatus,
+    pub next_run_at: DateTime<Utc>,
+    pub last_run_at: Option<DateTime<Utc>>,
+    pub last_swap_id: Option<String>,
+    pub consecutive_failures: i32,
+    pub last_error: Option<String>,
+
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, sqlx::Type, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(rename_all = "lowercase")]
+pub enum ScheduleFrequency {
+    Daily,
+    Weekly,
+    Monthly,
+}
+
+impl ScheduleFrequency {
+    /// Next execution time after `from`
+    pub fn next_after(&self, from: DateTime<Utc>) -> DateTime<Utc> {
+        match self {
+            ScheduleFrequency::Daily => from + chrono::Duration::days(1),
+            ScheduleFrequency::Weekly => from + chrono::Duration::weeks(1),
+            // Clamps to the last day of shorter months (Jan 31 -> Feb 28)
+            ScheduleFrequency::Monthly => from
+                .checked_add_months(Months::new(1))
+                .unwrap_or(from + chrono::Duration::days(30)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, sqlx::Type, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(rename_all = "lowercase")]
+pub enum ScheduleStatus {
+    Active,
+    Paused,
+    Cancelled,
+}
+
+// =============================================================================
+// SCHEDULE RUN
+// =============================================================================
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, JsonSchema)]
+pub struct ScheduleRun {
+    pub id: i64,
+    pub schedule_id: String,
+    pub swap_id: Option<String>,
+    pub succeeded: bool,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
@@ -0,0 +1,17 @@
+use axum::Router;
+use std::sync::Arc;
+
+use crate::AppState;
+use crate::manifest::DeclaredRoutes;
+use super::controller::{
+    cancel_schedule, create_schedule, get_schedule, list_schedules, update_schedule,
+};
+
+pub fn schedule_routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .declared("listSchedules", list_schedules)
+        .declared("createSchedule", create_schedule)
+        .declared("getSchedule", get_schedule)
+        .declared("updateSchedule", update_schedule)
+        .declared("cancelSchedule", cancel_schedule)
+}
@@ -0,0 +1,125 @@
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::services::amount::{Decimal, WireAmount};
+use super::model::{ScheduleFrequency, ScheduleRun, ScheduleStatus, SwapSchedule};
+
+// =============================================================================
+// CREATE
+// =============================================================================
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CreateScheduleRequest {
+    pub from: String,
+    pub network_from: String,
+    pub to: String,
+    pub network_to: String,
+    pub amount: WireAmount,
+    pub provider: String,
+    #[serde(default)]
+    pub recipient_address: String,
+    #[serde(default)]
+    pub recipient_extra_id: Option<String>,
+    #[serde(default)]
+    pub refund_address: Option<String>,
+    #[serde(default)]
+    pub refund_extra_id: Option<String>,
+    #[serde(default)]
+    pub payout_to_balance: bool,
+    pub frequency: ScheduleFrequency,
+    /// First execution (defaults to now)
+    #[serde(default)]
+    pub start_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct UpdateScheduleRequest {
+    #[serde(default)]
+    pub amount: Option<WireAmount>,
+    #[serde(default)]
+    pub frequency: Option<ScheduleFrequency>,
+    #[serde(default)]
+    pub status: Option<ScheduleStatus>,
+}
+
+// =============================================================================
+// RESPONSES
+// =============================================================================
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ScheduleResponse {
+    pub id: String,
+    pub from: String,
+    pub network_from: String,
+    pub to: String,
+    pub network_to: String,
+    #[serde(with = "rust_decimal::serde::float")]
+    #[schemars(with = "f64")]
+    pub amount: Decimal,
+    pub provider: String,
+    pub recipient_address: String,
+    pub payout_to_balance: bool,
+    pub frequency: ScheduleFrequency,
+    pub status: ScheduleStatus,
+    pub next_run_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_run_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_swap_id: Option<String>,
+    pub consecutive_failures: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<SwapSchedule> for ScheduleResponse {
+    fn from(s: SwapSchedule) -> Self {
+        Self {
+            id: s.id,
+            from: s.from_currency,
+            network_from: s.from_network,
+            to: s.to_currency,
+            network_to: s.to_network,
+            amount: s.amount,
+            provider: s.provider,
+            recipient_address: s.recipient_address,
+            payout_to_balance: s.payout_to_balance,
+            frequency: s.frequency,
+            status: s.status,
+            next_run_at: s.next_run_at,
+            last_run_at: s.last_run_at,
+            last_swap_id: s.last_swap_id,
+            consecutive_failures: s.consecutive_failures,
+            last_error: s.last_error,
+            created_at: s.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ScheduleDetailResponse {
+    #[serde(flatten)]
+    pub schedule: ScheduleResponse,
+    pub runs: Vec<ScheduleRun>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct SchedulesResponse {
+    pub schedules: Vec<ScheduleResponse>,
+}
+
+// =============================================================================
+// ERROR RESPONSE
+// =============================================================================
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ScheduleErrorResponse {
+    pub error: String,
+}
+
+impl ScheduleErrorResponse {
+    pub fn new(error: impl Into<String>) -> Self {
+        Self { error: error.into() }
+    }
+}
@@ -0,0 +1,113 @@
+use axum::{extract::State, http::StatusCode, Json};
+use std::sync::Arc;
+
+use super::factories::{
+    seed_api_enabled, seed_baseline, CurrencyFactory, ProviderFactory, SeedError, SwapFactory, UserFactory,
+};
+use super::schema::{SeedErrorResponse, SeedRequest, SeedResponse};
+use crate::modules::auth::interface::AdminUser;
+use crate::AppState;
+
+fn seed_error(e: SeedError) -> (StatusCode, Json<SeedErrorResponse>) {
+    (e.status_code(), Json(SeedErrorResponse::new(e.to_string())))
+}
+
+// =============================================================================
+// POST /_seed - Upsert known test data (dev and staging only)
+// =============================================================================
+
+pub async fn seed(
+    State(state): State<Arc<AppState>>,
+    admin: AdminUser,
+    Json(payload): Json<SeedRequest>,
+) -> Result<Json<SeedResponse>, (StatusCode, Json<SeedErrorResponse>)> {
+    if !seed_api_enabled() {
+        return Err(seed_error(SeedError::Disabled));
+    }
+
+    let db = &state.db;
+    let mut report = SeedResponse::default();
+
+    if payload.baseline {
+        seed_baseline(db, &mut report).await.map_err(seed_error)?;
+    }
+
+    for p in payload.providers {
+        let mut provider = ProviderFactory::new(&p.id)
+            .active(p.is_active)
+            .kyc_required(p.kyc_required);
+        if let Some(name) = &p.name {
+            provider = provider.name(name);
+        }
+        if let Some(rating) = p.rating {
+            provider = provider.rating(rating);
+        }
+        report.providers.push(provider.create(db).await.map_err(seed_error)?);
+    }
+
+    for c in payload.currencies {
+        let mut currency = CurrencyFactory::new(&c.symbol, &c.network).active(c.is_active);
+        if let Some(name) = &c.name {
+            currency = currency.name(name);
+        }
+        if let Some(decimals) = c.decimals {
+            currency = currency.decimals(decimals);
+        }
+        if let Some(contract) = &c.contract_address {
+            currency = currency.token(contract);
+        }
+        if let Some(extra_id) = &c.extra_id_name {
+            currency = currency.extra_id(extra_id);
+        }
+        report.currencies.push(currency.create(db).await.map_err(seed_error)?);
+    }
+
+    for u in payload.users {
+        let mut user = UserFactory::new();
+        if let Some(id) = &u.id {
+            user = user.id(id);
+        }
+        if let Some(email) = &u.email {
+            user = user.email(email);
+        }
+        if let Some(password) = &u.password {
+            user = user.password(password);
+        }
+        if u.is_admin {
+            user = user.admin();
+        }
+        if u.email_verified {
+            user = user.verified();
+        }
+        report.users.push(user.create(db).await.map_err(seed_error)?);
+    }
+
+    for s in payload.swaps {
+        let mut swap = SwapFactory::new()
+            .pair(&s.from, &s.network_from, &s.to, &s.network_to)
+            .amount(s.amount, s.rate.unwrap_or(15.0))
+            .status(s.status);
+        if let Some(id) = &s.id {
+            swap = swap.id(id);
+        }
+        if let Some(user_id) = &s.user_id {
+            swap = swap.user(user_id);
+        }
+        if let Some(provider) = &s.provider {
+            swap = swap.provider(provider);
+        }
+        report.swaps.push(swap.create(db).await.map_err(seed_error)?);
+    }
+
+    tracing::warn!(
+        "Seeded {} providers, {} currencies, {} users, {} swaps (baseline: {}) by {}",
+        report.providers.len(),
+        report.currencies.len(),
+        report.users.len(),
+        report.swaps.len(),
+        payload.baseline,
+        admin.0.id
+    );
+
+    Ok(Json(report))
+}
@@ -0,0 +1,553 @@
+//! Factories for test and staging data. Every factory upserts, so seeding
+//! the same records twice leaves the database in the same state instead of
+//! failing on duplicates.
+
+use axum::http::StatusCode;
+use chrono::Utc;
+use uuid::Uuid;
+
+use super::schema::{SeedResponse, SeededUserResponse};
+use crate::config::DbPool;
+use crate::modules::swap::schema::SwapStatus;
+use crate::services::hashing;
+use crate::services::pii::{email_index, SealedString};
+
+/// Password of seeded users that don't set one
+pub const DEFAULT_SEED_PASSWORD: &str = "SeedPassword123!";
+
+/// The providers and ratings shipped in the initial migration
+pub const BASELINE_PROVIDERS: [(&str, &str, f64, bool, &str); 8] = [
+    ("changenow", "ChangeNOW", 4.5, true, "https://changenow.io"),
+    ("changelly", "Changelly", 4.3, true, "https://changelly.com"),
+    ("sideshift", "SideShift", 4.4, false, "https://sideshift.ai"),
+    ("exch", "Exch", 4.2, false, "https://exch.cx"),
+    ("stealthex", "StealthEX", 4.3, true, "https://stealthex.io"),
+    ("godex", "Godex", 4.1, false, "https://godex.io"),
+    ("letsexchange", "LetsExchange", 4.2, true, "https://letsexchange.io"),
+    ("simpleswap", "SimpleSwap", 4.3, true, "https://simpleswap.io"),
+];
+
+/// Fixed ids for the baseline users and swaps, so staging links and
+/// fixtures keep pointing at the same records after a reseed
+pub const BASELINE_ADMIN_ID: &str = "5eed0000-0000-4000-8000-000000000001";
+pub const BASELINE_USER_ID: &str = "5eed0000-0000-4000-8000-000000000002";
+pub const BASELINE_ADMIN_EMAIL: &str = "admin@seed.example.com";
+pub const BASELINE_USER_EMAIL: &str = "user@seed.example.com";
+
+// =============================================================================
+// SEED ERROR
+// =============================================================================
+
+#[derive(Debug)]
+pub enum SeedError {
+    Disabled,
+    InvalidRecord(String),
+    HashingError(String),
+    DatabaseError(String),
+}
+
+impl std::fmt::Display for SeedError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SeedError::Disabled => write!(f, "Seeding is disabled"),
+            SeedError::InvalidRecord(e) => write!(f, "Invalid seed record: {}", e),
+            SeedError::HashingError(e) => write!(f, "Password hashing failed: {}", e),
+            SeedError::DatabaseError(e) => write!(f, "Database error: {}", e),
+        }
+    }
+}
+
+impl SeedError {
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            SeedError::Disabled => StatusCode::NOT_FOUND,
+            SeedError::InvalidRecord(_) => StatusCode::BAD_REQUEST,
+            SeedError::HashingError(_) | SeedError::DatabaseError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+impl From<sqlx::Error> for SeedError {
+    fn from(e: sqlx::Error) -> Self {
+        SeedError::DatabaseError(e.to_string())
+    }
+}
+
+/// Whether the /_seed API accepts requests (SEED_API_ENABLED, default
+/// false). Builds without the `seed` feature don't have the API at all.
+pub fn seed_api_enabled() -> bool {
+    std::env::var("SEED_API_ENABLED")
+        .map(|v| matches!(v.trim().to_ascii_lowercase().as_str(), "1" | "true" | "yes"))
+        .unwrap_or(false)
+}
+
+// =============================================================================
+// PROVIDERS
+// =============================================================================
+
+pub struct ProviderFactory {
+    id: String,
+    name: String,
+    rating: f64,
+    is_active: bool,
+    kyc_required: bool,
+    supports_fixed_rate: bool,
+    website_url: Option<String>,
+}
+
+impl ProviderFactory {
+    /// An active, no-KYC provider rated 4.0; `id` doubles as the slug
+    pub fn new(id: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            name: id.to_string(),
+            rating: 4.0,
+            is_active: true,
+            kyc_required: false,
+            supports_fixed_rate: true,
+            website_url: None,
+        }
+    }
+
+    pub fn name(mut self, name: &str) -> Self {
+        self.name = name.to_string();
+        self
+    }
+
+    pub fn rating(mut self, rating: f64) -> Self {
+        self.rating = rating;
+        self
+    }
+
+    pub fn active(mut self, is_active: bool) -> Self {
+        self.is_active = is_active;
+        self
+    }
+
+    pub fn kyc_required(mut self, kyc_required: bool) -> Self {
+        self.kyc_required = kyc_required;
+        self
+    }
+
+    pub fn floating_only(mut self) -> Self {
+        self.supports_fixed_rate = false;
+        self
+    }
+
+    pub fn website(mut self, url: &str) -> Self {
+        self.website_url = Some(url.to_string());
+        self
+    }
+
+    pub async fn create(&self, db: &DbPool) -> Result<String, SeedError> {
+        if self.id.trim().is_empty() {
+            return Err(SeedError::InvalidRecord("provider id is empty".to_string()));
+        }
+
+        sqlx::query(
+            r#"
+            INSERT INTO providers (
+                id, name, slug, is_active, kyc_required, rating,
+                supports_fixed_rate, supports_floating_rate, website_url
+            )
+            VALUES (?, ?, ?, ?, ?, ?, ?, TRUE, ?)
+            ON DUPLICATE KEY UPDATE
+                name = VALUES(name), is_active = VALUES(is_active), kyc_required = VALUES(kyc_required),
+                rating = VALUES(rating), supports_fixed_rate = VALUES(supports_fixed_rate),
+                supports_floating_rate = TRUE, website_url = VALUES(website_url)
+            "#,
+        )
+        .bind(&self.id)
+        .bind(&self.name)
+        .bind(&self.id)
+        .bind(self.is_active)
+        .bind(self.kyc_required)
+        .bind(self.rating)
+        .bind(self.supports_fixed_rate)
+        .bind(&self.website_url)
+        .execute(db)
+        .await?;
+
+        Ok(self.id.clone())
+    }
+}
+
+// =============================================================================
+// CURRENCIES
+// =============================================================================
+
+pub struct CurrencyFactory {
+    symbol: String,
+    network: String,
+    name: String,
+    decimals: i32,
+    contract_address: Option<String>,
+    extra_id_name: Option<String>,
+    is_active: bool,
+}
+
+impl CurrencyFactory {
+    /// An active 8-decimal native coin
+    pub fn new(symbol: &str, network: &str) -> Self {
+        Self {
+            symbol: symbol.to_uppercase(),
+            network: network.to_lowercase(),
+            name: symbol.to_uppercase(),
+            decimals: 8,
+            contract_address: None,
+            extra_id_name: None,
+            is_active: true,
+        }
+    }
+
+    pub fn name(mut self, name: &str) -> Self {
+        self.name = name.to_string();
+        self
+    }
+
+    pub fn decimals(mut self, decimals: i32) -> Self {
+        self.decimals = decimals;
+        self
+    }
+
+    /// A token at `contract` rather than the chain's native coin
+    pub fn token(mut self, contract: &str) -> Self {
+        self.contract_address = Some(contract.to_string());
+        self
+    }
+
+    /// Deposits need a memo/tag named `name`
+    pub fn extra_id(mut self, name: &str) -> Self {
+        self.extra_id_name = Some(name.to_string());
+        self
+    }
+
+    pub fn active(mut self, is_active: bool) -> Self {
+        self.is_active = is_active;
+        self
+    }
+
+    /// Returns the currency as `SYMBOL/network`
+    pub async fn create(&self, db: &DbPool) -> Result<String, SeedError> {
+        if self.symbol.is_empty() || self.network.is_empty() {
+            return Err(SeedError::InvalidRecord("currency symbol and network are required".to_string()));
+        }
+
+        sqlx::query(
+            r#"
+            INSERT INTO currencies (
+                symbol, name, network, is_active, contract_address, decimals, requires_extra_id, extra_id_name
+            )
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            ON DUPLICATE KEY UPDATE
+                name = VALUES(name), is_active = VALUES(is_active), contract_address = VALUES(contract_address),
+                decimals = VALUES(decimals), requires_extra_id = VALUES(requires_extra_id),
+                extra_id_name = VALUES(extra_id_name)
+            "#,
+        )
+        .bind(&self.symbol)
+        .bind(&self.name)
+        .bind(&self.network)
+        .bind(self.is_active)
+        .bind(&self.contract_address)
+        .bind(self.decimals)
+        .bind(self.extra_id_name.is_some())
+        .bind(&self.extra_id_name)
+        .execute(db)
+        .await?;
+
+        Ok(format!("{}/{}", self.symbol, self.network))
+    }
+}
+
+// =============================================================================
+// USERS
+// =============================================================================
+
+pub struct UserFactory {
+    id: String,
+    email: String,
+    password: String,
+    is_admin: bool,
+    email_verified: bool,
+}
+
+impl Default for UserFactory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl UserFactory {
+    /// A regular, unverified user with a random address and the default
+    /// seed password
+    pub fn new() -> Self {
+        let id = Uuid::new_v4().to_string();
+        Self {
+            email: format!("seed_{}@example.com", id),
+            id,
+            password: DEFAULT_SEED_PASSWORD.to_string(),
+            is_admin: false,
+            email_verified: false,
+        }
+    }
+
+    pub fn id(mut self, id: &str) -> Self {
+        self.id = id.to_string();
+        self
+    }
+
+    pub fn email(mut self, email: &str) -> Self {
+        self.email = email.trim().to_lowercase();
+        self
+    }
+
+    pub fn password(mut self, password: &str) -> Self {
+        self.password = password.to_string();
+        self
+    }
+
+    pub fn admin(mut self) -> Self {
+        self.is_admin = true;
+        self
+    }
+
+    pub fn verified(mut self) -> Self {
+        self.email_verified = true;
+        self
+    }
+
+    /// Re-seeding an existing user resets its password and flags
+    pub async fn create(&self, db: &DbPool) -> Result<SeededUserResponse, SeedError> {
+        if !self.email.contains('@') {
+            return Err(SeedError::InvalidRecord(format!("invalid email {:?}", self.email)));
+        }
+
+        let password_hash =
+            hashing::hash_password(&self.password).map_err(|e| SeedError::HashingError(e.to_string()))?;
+        let now = Utc::now();
+
+        sqlx::query(
+            r#"
+            INSERT INTO users (
+                id, email, email_hash, password_hash, email_verified, two_factor_enabled,
+                is_admin, created_at, updated_at
+            )
+            VALUES (?, ?, ?, ?, ?, FALSE, ?, ?, ?)
+            ON DUPLICATE KEY UPDATE
+                password_hash = VALUES(password_hash), email_verified = VALUES(email_verified),
+                two_factor_enabled = FALSE, two_factor_secret = NULL, is_admin = VALUES(is_admin),
+                updated_at = VALUES(updated_at)
+            "#,
+        )
+        .bind(&self.id)
+        .bind(SealedString(self.email.clone()))
+        .bind(email_index(&self.email))
+        .bind(&password_hash)
+        .bind(self.email_verified)
+        .bind(self.is_admin)
+        .bind(now)
+        .bind(now)
+        .execute(db)
+        .await?;
+
+        Ok(SeededUserResponse {
+            id: self.id.clone(),
+            email: self.email.clone(),
+        })
+    }
+}
+
+// =============================================================================
+// SWAPS
+// =============================================================================
+
+pub struct SwapFactory {
+    id: String,
+    user_id: Option<String>,
+    provider_id: String,
+    from: (String, String),
+    to: (String, String),
+    amount: f64,
+    rate: f64,
+    status: SwapStatus,
+    is_sandbox: bool,
+}
+
+impl Default for SwapFactory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SwapFactory {
+    /// A waiting 0.1 BTC -> ETH sandbox swap through ChangeNOW
+    pub fn new() -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            user_id: None,
+            provider_id: "changenow".to_string(),
+            from: ("btc".to_string(), "bitcoin".to_string()),
+            to: ("eth".to_string(), "ethereum".to_string()),
+            amount: 0.1,
+            rate: 15.0,
+            status: SwapStatus::Waiting,
+            is_sandbox: true,
+        }
+    }
+
+    pub fn id(mut self, id: &str) -> Self {
+        self.id = id.to_string();
+        self
+    }
+
+    pub fn user(mut self, user_id: &str) -> Self {
+        self.user_id = Some(user_id.to_string());
+        self
+    }
+
+    pub fn provider(mut self, provider_id: &str) -> Self {
+        self.provider_id = provider_id.to_string();
+        self
+    }
+
+    pub fn pair(mut self, from: &str, network_from: &str, to: &str, network_to: &str) -> Self {
+        self.from = (from.to_lowercase(), network_from.to_lowercase());
+        self.to = (to.to_lowercase(), network_to.to_lowercase());
+        self
+    }
+
+    pub fn amount(mut self, amount: f64, rate: f64) -> Self {
+        self.amount = amount;
+        self.rate = rate;
+        self
+    }
+
+    pub fn status(mut self, status: SwapStatus) -> Self {
+        self.status = status;
+        self
+    }
+
+    /// Swaps are sandboxed by default so workers never pay them out
+    pub fn live(mut self) -> Self {
+        self.is_sandbox = false;
+        self
+    }
+
+    /// Re-seeding an existing swap puts it back in the requested state
+    pub async fn create(&self, db: &DbPool) -> Result<String, SeedError> {
+        if !(self.amount > 0.0 && self.rate > 0.0) {
+            return Err(SeedError::InvalidRecord("swap amount and rate must be positive".to_string()));
+        }
+
+        // Unique per swap so the blockchain listener never matches two
+        let deposit_address = format!("seed_deposit_{}", self.id);
+
+        sqlx::query(
+            r#"
+            INSERT INTO swaps (
+                id, user_id, provider_id, from_currency, from_network, to_currency, to_network,
+                amount, estimated_receive, rate, deposit_address, recipient_address,
+                status, rate_type, is_sandbox
+            )
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, 'seed_recipient', ?, 'floating', ?)
+            ON DUPLICATE KEY UPDATE
+                user_id = VALUES(user_id), provider_id = VALUES(provider_id),
+                amount = VALUES(amount), estimated_receive = VALUES(estimated_receive), rate = VALUES(rate),
+                status = VALUES(status), is_sandbox = VALUES(is_sandbox)
+            "#,
+        )
+        .bind(&self.id)
+        .bind(&self.user_id)
+        .bind(&self.provider_id)
+        .bind(&self.from.0)
+        .bind(&self.from.1)
+        .bind(&self.to.0)
+        .bind(&self.to.1)
+        .bind(self.amount)
+        .bind(self.amount * self.rate)
+        .bind(self.rate)
+        .bind(&deposit_address)
+        .bind(self.status.as_str())
+        .bind(self.is_sandbox)
+        .execute(db)
+        .await?;
+
+        Ok(self.id.clone())
+    }
+}
+
+// =============================================================================
+// BASELINE
+// =============================================================================
+
+/// The known starting point for staging: the migration's eight providers
+/// with their ratings, a handful of currencies, an admin and a regular user
+/// (both with the default seed password), and one of the user's swaps in
+/// every status
+pub async fn seed_baseline(db: &DbPool, report: &mut SeedResponse) -> Result<(), SeedError> {
+    for (id, name, rating, supports_fixed_rate, website) in BASELINE_PROVIDERS {
+        let mut provider = ProviderFactory::new(id).name(name).rating(rating).website(website);
+        if !supports_fixed_rate {
+            provider = provider.floating_only();
+        }
+        report.providers.push(provider.create(db).await?);
+    }
+
+    let currencies = [
+        CurrencyFactory::new("BTC", "bitcoin").name("Bitcoin"),
+        CurrencyFactory::new("ETH", "ethereum").name("Ethereum").decimals(18),
+        CurrencyFactory::new("USDT", "ethereum")
+            .name("Tether")
+            .decimals(6)
+            .token("0xdAC17F958D2ee523a2206206994597C13D831ec7"),
+        CurrencyFactory::new("XMR", "monero").name("Monero").decimals(12),
+        CurrencyFactory::new("XRP", "ripple").name("Ripple").decimals(6).extra_id("Destination Tag"),
+    ];
+    for currency in currencies {
+        report.currencies.push(currency.create(db).await?);
+    }
+
+    report.users.push(
+        UserFactory::new()
+            .id(BASELINE_ADMIN_ID)
+            .email(BASELINE_ADMIN_EMAIL)
+            .admin()
+            .verified()
+            .create(db)
+            .await?,
+    );
+    report.users.push(
+        UserFactory::new()
+            .id(BASELINE_USER_ID)
+            .email(BASELINE_USER_EMAIL)
+            .verified()
+            .create(db)
+            .await?,
+    );
+
+    let statuses = [
+        SwapStatus::Waiting,
+        SwapStatus::Confirming,
+        SwapStatus::Exchanging,
+        SwapStatus::Sending,
+        SwapStatus::FundsReceived,
+        SwapStatus::Completed,
+        SwapStatus::Failed,
+        SwapStatus::Refunded,
+        SwapStatus::Expired,
+    ];
+    for (n, status) in statuses.into_iter().enumerate() {
+        let id = format!("5eed0000-0000-4000-8000-1000000000{:02}", n);
+        report.swaps.push(
+            SwapFactory::new()
+                .id(&id)
+                .user(BASELINE_USER_ID)
+                .status(status)
+                .create(db)
+                .await?,
+        );
+    }
+
+    Ok(())
+}
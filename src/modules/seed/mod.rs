@@ -0,0 +1,10 @@
+//! Test-data factories and the dev-only /_seed API, compiled in with the
+//! `seed` feature. Staging seeds a known baseline through the API; tests
+//! build the records they need with the factories directly.
+
+pub mod controller;
+pub mod factories;
+pub mod routes;
+pub mod schema;
+
+pub use routes::seed_routes;
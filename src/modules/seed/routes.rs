@@ -0,0 +1,13 @@
+use axum::Router;
+use std::sync::Arc;
+
+use crate::AppState;
+use crate::manifest::DeclaredRoutes;
+use super::controller::seed;
+
+/// Declared `internal` in the route manifest: generated clients should
+/// never be able to call it.
+pub fn seed_routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .declared("seed", seed)
+}
@@ -0,0 +1,121 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::modules::swap::schema::SwapStatus;
+
+// =============================================================================
+// REQUESTS
+// =============================================================================
+
+/// Records to upsert. With `baseline` set the standard staging data is
+/// seeded first, then the listed records on top of it.
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub struct SeedRequest {
+    #[serde(default)]
+    pub baseline: bool,
+    #[serde(default)]
+    pub providers: Vec<SeedProvider>,
+    #[serde(default)]
+    pub currencies: Vec<SeedCurrency>,
+    #[serde(default)]
+    pub users: Vec<SeedUser>,
+    #[serde(default)]
+    pub swaps: Vec<SeedSwap>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct SeedProvider {
+    pub id: String,
+    pub name: Option<String>,
+    /// Defaults to 4.0
+    pub rating: Option<f64>,
+    #[serde(default = "default_true")]
+    pub is_active: bool,
+    #[serde(default)]
+    pub kyc_required: bool,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct SeedCurrency {
+    pub symbol: String,
+    pub network: String,
+    pub name: Option<String>,
+    /// Defaults to 8
+    pub decimals: Option<i32>,
+    pub contract_address: Option<String>,
+    /// Memo/tag name, for currencies that need one
+    pub extra_id_name: Option<String>,
+    #[serde(default = "default_true")]
+    pub is_active: bool,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct SeedUser {
+    /// Random when omitted
+    pub id: Option<String>,
+    /// Random when omitted
+    pub email: Option<String>,
+    /// Defaults to the shared seed password
+    pub password: Option<String>,
+    #[serde(default)]
+    pub is_admin: bool,
+    #[serde(default)]
+    pub email_verified: bool,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct SeedSwap {
+    /// Random when omitted
+    pub id: Option<String>,
+    pub user_id: Option<String>,
+    /// Defaults to changenow
+    pub provider: Option<String>,
+    pub from: String,
+    pub network_from: String,
+    pub to: String,
+    pub network_to: String,
+    pub amount: f64,
+    /// Defaults to 15.0
+    pub rate: Option<f64>,
+    #[serde(default = "default_status")]
+    pub status: SwapStatus,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_status() -> SwapStatus {
+    SwapStatus::Waiting
+}
+
+// =============================================================================
+// RESPONSES
+// =============================================================================
+
+/// Everything written, baseline included
+#[derive(Debug, Default, Serialize, JsonSchema)]
+pub struct SeedResponse {
+    pub providers: Vec<String>,
+    /// As `SYMBOL/network`
+    pub currencies: Vec<String>,
+    pub users: Vec<SeededUserResponse>,
+    pub swaps: Vec<String>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct SeededUserResponse {
+    pub id: String,
+    pub email: String,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct SeedErrorResponse {
+    pub error: String,
+}
+
+impl SeedErrorResponse {
+    pub fn new(error: impl Into<String>) -> Self {
+        Self { error: error.into() }
+    }
+}
@@ -0,0 +1,118 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use std::sync::Arc;
+
+use super::schema::{SimulateDepositRequest, SimulatedDepositResponse, SimulationErrorResponse};
+use crate::modules::auth::interface::AdminUser;
+use crate::services::amount::{self, Decimal};
+use crate::services::blockchain::listener::{chain_alias, evm_rpc_url};
+use crate::services::simulation::{simulation_enabled, SimulatedLedger};
+use crate::services::token::registry::TokenRegistry;
+use crate::services::wallet::rpc::{BlockchainProvider, HttpRpcClient};
+use crate::AppState;
+
+type SimulationResult<T> = Result<T, (StatusCode, Json<SimulationErrorResponse>)>;
+
+/// Swap status, payout currency and network, expected receive, platform fee,
+/// deposit address and its status
+type DepositTargetRow = (String, String, String, Decimal, Decimal, String, String);
+
+fn simulation_error(status: StatusCode, error: impl Into<String>) -> (StatusCode, Json<SimulationErrorResponse>) {
+    (status, Json(SimulationErrorResponse::new(error)))
+}
+
+fn internal_error(e: impl std::fmt::Display) -> (StatusCode, Json<SimulationErrorResponse>) {
+    tracing::error!("Deposit simulation failed: {}", e);
+    simulation_error(StatusCode::INTERNAL_SERVER_ERROR, "Deposit simulation failed")
+}
+
+// =============================================================================
+// POST /_test/simulate-deposit/{swap_id} - Credit a fake deposit (staging only)
+// =============================================================================
+
+pub async fn simulate_deposit(
+    State(state): State<Arc<AppState>>,
+    admin: AdminUser,
+    Path(swap_id): Path<String>,
+    Json(payload): Json<SimulateDepositRequest>,
+) -> SimulationResult<Json<SimulatedDepositResponse>> {
+    if !simulation_enabled() {
+        return Err(simulation_error(StatusCode::NOT_FOUND, "Deposit simulation is disabled"));
+    }
+
+    let row: Option<DepositTargetRow> = sqlx::query_as(
+        r#"
+        SELECT CAST(s.status AS CHAR), s.to_currency, s.to_network, s.estimated_receive, s.platform_fee,
+               sa.our_address, CAST(sa.status AS CHAR)
+        FROM swaps s
+        JOIN swap_address_info sa ON s.id = sa.swap_id
+        WHERE s.id = ?
+        "#,
+    )
+    .bind(&swap_id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(internal_error)?;
+
+    let Some((status, currency, network, estimated_receive, platform_fee, address, address_status)) = row else {
+        return Err(simulation_error(StatusCode::NOT_FOUND, "Swap not found"));
+    };
+    // The statuses the listener watches deposit addresses in
+    if !matches!(status.as_str(), "sending" | "exchanging" | "confirming") || address_status != "pending" {
+        return Err(simulation_error(StatusCode::CONFLICT, format!("Swap is {} and not awaiting a deposit", status)));
+    }
+
+    let normalized = network.to_lowercase();
+    let chain = chain_alias(&normalized).map(str::to_string).unwrap_or(normalized);
+    let Some(rpc_url) = evm_rpc_url(&chain) else {
+        return Err(simulation_error(
+            StatusCode::BAD_REQUEST,
+            format!("Deposits on {} are not watched by the listener", network),
+        ));
+    };
+
+    let amount = payload.amount.map(|a| a.value()).unwrap_or(estimated_receive + platform_fee);
+    if amount <= Decimal::ZERO {
+        return Err(simulation_error(StatusCode::BAD_REQUEST, "Amount must be positive"));
+    }
+
+    let ledger = SimulatedLedger::global();
+    let token = TokenRegistry::new(state.db.clone())
+        .canonical_token(&currency, &chain)
+        .await
+        .map_err(internal_error)?;
+    let (token_contract, tx_hash) = match token {
+        None => {
+            ledger.credit_native(&address, amount);
+            (None, None)
+        }
+        Some(token) => {
+            let units = amount::to_minor_units(amount, token.decimals as u32)
+                .map_err(|e| simulation_error(StatusCode::BAD_REQUEST, e.to_string()))?;
+            // Dated at the chain head so it falls inside the listener's lookback
+            let head = HttpRpcClient::new(rpc_url).get_block_number().await.map_err(|e| {
+                simulation_error(StatusCode::SERVICE_UNAVAILABLE, format!("{} RPC unavailable: {}", chain, e))
+            })?;
+            let tx_hash = ledger.credit_token(&address, &token.contract_address, units, head);
+            (Some(token.contract_address), Some(tx_hash))
+        }
+    };
+
+    tracing::warn!(
+        "🧪 Simulated deposit of {} {} on {} to swap {} by {}",
+        amount, currency, chain, swap_id, admin.0.id
+    );
+
+    Ok(Json(SimulatedDepositResponse {
+        swap_id,
+        address,
+        network: chain,
+        currency,
+        amount,
+        token_contract,
+        tx_hash,
+    }))
+}
@@ -0,0 +1,8 @@
+//! Staging-only deposit simulation, compiled in with the
+//! `deposit-simulation` feature. See `crate::services::simulation`.
+
+pub mod controller;
+pub mod routes;
+pub mod schema;
+
+pub use routes::simulation_routes;
@@ -0,0 +1,13 @@
+use axum::Router;
+use std::sync::Arc;
+
+use crate::AppState;
+use crate::manifest::DeclaredRoutes;
+use super::controller::simulate_deposit;
+
+/// Declared `internal` in the route manifest: generated clients should
+/// never be able to call it.
+pub fn simulation_routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .declared("simulateDeposit", simulate_deposit)
+}
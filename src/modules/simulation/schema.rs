@@ -0,0 +1,48 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::services::amount::{Decimal, WireAmount};
+
+// =============================================================================
+// REQUESTS
+// =============================================================================
+
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub struct SimulateDepositRequest {
+    /// Defaults to the full amount the swap expects; less simulates an
+    /// underpayment
+    pub amount: Option<WireAmount>,
+}
+
+// =============================================================================
+// RESPONSES
+// =============================================================================
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct SimulatedDepositResponse {
+    pub swap_id: String,
+    /// Deposit address credited
+    pub address: String,
+    pub network: String,
+    pub currency: String,
+    #[serde(with = "rust_decimal::serde::float")]
+    #[schemars(with = "f64")]
+    pub amount: Decimal,
+    /// Token contract the deposit was credited from; absent for native coins
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token_contract: Option<String>,
+    /// Fake transaction hash of a token deposit
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tx_hash: Option<String>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct SimulationErrorResponse {
+    pub error: String,
+}
+
+impl SimulationErrorResponse {
+    pub fn new(error: impl Into<String>) -> Self {
+        Self { error: error.into() }
+    }
+}
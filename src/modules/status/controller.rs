@@ -0,0 +1,60 @@
+use axum::{
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    response::Response,
+    Json,
+};
+use std::sync::Arc;
+
+use super::schema::{NetworkStatusReport, PublicStatusResponse, StatusErrorResponse};
+use crate::services::etag::conditional_json;
+use crate::services::status_page::{current_report, public_status};
+use crate::AppState;
+
+/// Shared caches may serve the public status for a minute
+const PUBLIC_CACHE_CONTROL: &str = "public, max-age=60, stale-while-revalidate=30";
+const PUBLIC_CACHE_KEY: &str = "status:public";
+const PUBLIC_CACHE_TTL_SECS: u64 = 60;
+
+// =============================================================================
+// GET /status/networks - Per-chain health for status banners
+// =============================================================================
+
+pub async fn get_network_status(State(state): State<Arc<AppState>>) -> Json<NetworkStatusReport> {
+    Json(current_report(&state.db).await)
+}
+
+// =============================================================================
+// GET /status/public - Uptime, completion time and chain flags for a status page
+// =============================================================================
+
+pub async fn get_public_status(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Response, (StatusCode, Json<StatusErrorResponse>)> {
+    let internal_error = |e: String| {
+        tracing::error!("Public status failed: {}", e);
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(StatusErrorResponse::new("Status is temporarily unavailable")))
+    };
+
+    // Built once per minute across replicas; without Redis, built per request
+    let db = state.db.clone();
+    let build = || async move { public_status(&db).await.map_err(|e| e.to_string()) };
+    let status = match state.redis.get_json::<PublicStatusResponse>(PUBLIC_CACHE_KEY).await {
+        Ok(Some(cached)) => cached,
+        Ok(None) => {
+            let status = build().await.map_err(internal_error)?;
+            if let Err(e) = state.redis.set_json(PUBLIC_CACHE_KEY, &status, PUBLIC_CACHE_TTL_SECS).await {
+                tracing::debug!("Failed to cache public status: {}", e);
+            }
+            status
+        }
+        Err(e) => {
+            tracing::debug!("Public status cache unavailable: {}", e);
+            build().await.map_err(internal_error)?
+        }
+    };
+
+    let body = serde_json::to_string(&status).map_err(|e| internal_error(e.to_string()))?;
+    Ok(conditional_json(&headers, body, PUBLIC_CACHE_CONTROL))
+}
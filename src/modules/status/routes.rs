@@ -0,0 +1,12 @@
+use axum::Router;
+use std::sync::Arc;
+
+use crate::AppState;
+use crate::manifest::DeclaredRoutes;
+use super::controller::{get_network_status, get_public_status};
+
+pub fn status_routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .declared("getNetworkStatus", get_network_status)
+        .declared("getPublicStatus", get_public_status)
+}
@@ -0,0 +1,51 @@
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+pub use crate::services::network_status::{NetworkHealth, NetworkSeverity, NetworkStatusReport};
+
+// =============================================================================
+// RESPONSES
+// =============================================================================
+
+/// Status page data safe to publish: no endpoints, scores or halt reasons
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct PublicStatusResponse {
+    /// Worst severity across all networks
+    pub status: NetworkSeverity,
+    pub uptime: UptimeSummary,
+    /// Average time from creation to completion of swaps completed in the
+    /// last 24 hours; absent when none completed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub average_completion_secs_24h: Option<u64>,
+    pub networks: Vec<PublicNetworkStatus>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Percentage of time networks were not down, 0-100. Absent for a window
+/// without samples.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct UptimeSummary {
+    pub last_24h: Option<f64>,
+    pub last_7d: Option<f64>,
+    pub last_30d: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct PublicNetworkStatus {
+    pub network: String,
+    pub status: NetworkSeverity,
+    /// Swaps on the network are being processed, possibly with delays
+    pub operational: bool,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct StatusErrorResponse {
+    pub error: String,
+}
+
+impl StatusErrorResponse {
+    pub fn new(error: impl Into<String>) -> Self {
+        Self { error: error.into() }
+    }
+}
@@ -0,0 +1,426 @@
+//! Local address format rules. /swap/validate-address rejects addresses
+//! that break these before asking the provider, and
+//! /swap/currencies/{ticker}/address-spec publishes the same rules, so a
+//! frontend pre-validating with them never disagrees with the server.
+//!
+//! Patterns stick to syntax that means the same in Rust's `regex` and
+//! JavaScript's `RegExp` (classes, counted repeats, alternation, anchors).
+
+use regex::Regex;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// Address formats shared by every currency on the same chain
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AddressFamily {
+    Bitcoin,
+    Litecoin,
+    Dogecoin,
+    Evm,
+    Tron,
+    Solana,
+    Monero,
+    Ripple,
+    Stellar,
+    Zcash,
+}
+
+/// Memo, tag or payment id some chains use to tell shared-address deposits apart
+#[derive(Debug, Clone, Copy)]
+pub struct MemoRule {
+    pub name: &'static str,
+    pub pattern: &'static str,
+    pub max_length: usize,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct AddressRule {
+    pub family: AddressFamily,
+    /// Human name of the format, as used in hints
+    pub name: &'static str,
+    pub pattern: &'static str,
+    pub prefixes: &'static [&'static str],
+    pub min_length: usize,
+    pub max_length: usize,
+    pub case_sensitive: bool,
+    pub example: &'static str,
+    pub memo: Option<MemoRule>,
+}
+
+static RULES: [AddressRule; 10] = [
+    AddressRule {
+        family: AddressFamily::Bitcoin,
+        name: "Bitcoin",
+        pattern: "^(bc1[02-9ac-hj-np-z]{11,71}|BC1[02-9AC-HJ-NP-Z]{11,71}|[13][1-9A-HJ-NP-Za-km-z]{25,34})$",
+        prefixes: &["bc1", "1", "3"],
+        min_length: 26,
+        max_length: 74,
+        case_sensitive: true,
+        example: "bc1qxy2kgdygjrsqtzq2n0yrf2493p83kkfjhx0wlh",
+        memo: None,
+    },
+    AddressRule {
+        family: AddressFamily::Litecoin,
+        name: "Litecoin",
+        pattern: "^(ltc1[02-9ac-hj-np-z]{11,71}|LTC1[02-9AC-HJ-NP-Z]{11,71}|[LM3][1-9A-HJ-NP-Za-km-z]{26,33})$",
+        prefixes: &["ltc1", "L", "M", "3"],
+        min_length: 27,
+        max_length: 75,
+        case_sensitive: true,
+        example: "LVg2kJoFNg45Nbpy53h7Fe1wKyeXVRhMH9",
+        memo: None,
+    },
+    AddressRule {
+        family: AddressFamily::Dogecoin,
+        name: "Dogecoin",
+        pattern: "^[DA9][1-9A-HJ-NP-Za-km-z]{33}$",
+        prefixes: &["D", "A", "9"],
+        min_length: 34,
+        max_length: 34,
+        case_sensitive: true,
+        example: "DH5yaieqoZN36fDVciNyRueRGvGLR3mr7L",
+        memo: None,
+    },
+    AddressRule {
+        family: AddressFamily::Evm,
+        name: "EVM",
+        pattern: "^0x[0-9a-fA-F]{40}$",
+        prefixes: &["0x"],
+        min_length: 42,
+        max_length: 42,
+        case_sensitive: false,
+        example: "0x742d35Cc6634C0532925a3b844Bc9e7595f5bE12",
+        memo: None,
+    },
+    AddressRule {
+        family: AddressFamily::Tron,
+        name: "Tron",
+        pattern: "^T[1-9A-HJ-NP-Za-km-z]{33}$",
+        prefixes: &["T"],
+        min_length: 34,
+        max_length: 34,
+        case_sensitive: true,
+        example: "TLa2f6VPqDgRE67v1736s7bJ8Ray5wYjU7",
+        memo: None,
+    },
+    AddressRule {
+        family: AddressFamily::Solana,
+        name: "Solana",
+        pattern: "^[1-9A-HJ-NP-Za-km-z]{32,44}$",
+        prefixes: &[],
+        min_length: 32,
+        max_length: 44,
+        case_sensitive: true,
+        example: "7EcDhSYGxXyscszYEp35KHN8vvw3svAuLKTzXwCFLtV",
+        memo: None,
+    },
+    AddressRule {
+        family: AddressFamily::Monero,
+        name: "Monero",
+        // Any alphanumerics rather than strict base58: the provider accepts
+        // addresses we would otherwise reject, and the server must not be
+        // stricter than the provider
+        pattern: "^[48][0-9A-Za-z]{94}$",
+        prefixes: &["4", "8"],
+        min_length: 95,
+        max_length: 95,
+        case_sensitive: true,
+        example: "44AFFq5kSiGBoZ4NMDwYtN18obc8AemS33DBLWs3H7otXft3XjrpDtQGvj85ngqVqWfdn4ufSXIzJRHWKJ32khHA7wGwwve",
+        memo: None,
+    },
+    AddressRule {
+        family: AddressFamily::Ripple,
+        name: "XRP Ledger",
+        pattern: "^r[1-9A-HJ-NP-Za-km-z]{24,34}$",
+        prefixes: &["r"],
+        min_length: 25,
+        max_length: 35,
+        case_sensitive: true,
+        example: "rEb8TK3gBgk5auZkwc6sHnwrGVJH8DuaLh",
+        memo: Some(MemoRule {
+            name: "Destination Tag",
+            pattern: "^[0-9]{1,10}$",
+            max_length: 10,
+        }),
+    },
+    AddressRule {
+        family: AddressFamily::Stellar,
+        name: "Stellar",
+        pattern: "^G[A-Z2-7]{55}$",
+        prefixes: &["G"],
+        min_length: 56,
+        max_length: 56,
+        case_sensitive: true,
+        example: "GCKFBEIYV2U22IO2BJ4KVJOIP7XPWQGQFKKWXR6DOSJBV7STMAQSMTGG",
+        memo: Some(MemoRule {
+            name: "Memo",
+            pattern: "^.{1,28}$",
+            max_length: 28,
+        }),
+    },
+    AddressRule {
+        family: AddressFamily::Zcash,
+        name: "Zcash transparent",
+        pattern: "^t[13][1-9A-HJ-NP-Za-km-z]{33}$",
+        prefixes: &["t1", "t3"],
+        min_length: 35,
+        max_length: 35,
+        case_sensitive: true,
+        example: "t1Hsc1LR8yKnbbe3twRp88p6vFfC5t7DLbs",
+        memo: None,
+    },
+];
+
+fn compiled() -> &'static HashMap<AddressFamily, (Regex, Option<Regex>)> {
+    static COMPILED: OnceLock<HashMap<AddressFamily, (Regex, Option<Regex>)>> = OnceLock::new();
+    COMPILED.get_or_init(|| {
+        RULES
+            .iter()
+            .map(|rule| {
+                let address = Regex::new(rule.pattern).expect("address pattern");
+                let memo = rule.memo.map(|memo| Regex::new(memo.pattern).expect("memo pattern"));
+                (rule.family, (address, memo))
+            })
+            .collect()
+    })
+}
+
+impl AddressRule {
+    pub fn matches(&self, address: &str) -> bool {
+        compiled()[&self.family].0.is_match(address)
+    }
+
+    /// Whether `memo` is acceptable; chains without memos accept none
+    pub fn memo_matches(&self, memo: &str) -> bool {
+        match &compiled()[&self.family].1 {
+            Some(pattern) => pattern.is_match(memo),
+            None => memo.is_empty(),
+        }
+    }
+}
+
+/// The rule for `ticker` on `network`. A token network (ERC20, TRC20, ...)
+/// decides the format; otherwise the coin's own chain does. None when we
+/// have no local rule and only the provider can tell.
+pub fn rule_for(ticker: &str, network: Option<&str>) -> Option<&'static AddressRule> {
+    let family = network
+        .and_then(|n| family_of_network(&n.trim().to_ascii_lowercase()))
+        .or_else(|| family_of_ticker(&ticker.trim().to_ascii_lowercase()))?;
+    RULES.iter().find(|rule| rule.family == family)
+}
+
+fn family_of_network(network: &str) -> Option<AddressFamily> {
+    Some(match network {
+        "erc20" | "ethereum" | "bep20" | "bsc" | "polygon" | "matic" | "arbitrum" | "optimism" | "base"
+        | "avaxc" | "avalanche" => AddressFamily::Evm,
+        "trc20" | "tron" => AddressFamily::Tron,
+        "spl" | "solana" => AddressFamily::Solana,
+        "bitcoin" => AddressFamily::Bitcoin,
+        "litecoin" => AddressFamily::Litecoin,
+        "dogecoin" => AddressFamily::Dogecoin,
+        "monero" => AddressFamily::Monero,
+        "ripple" => AddressFamily::Ripple,
+        "stellar" => AddressFamily::Stellar,
+        "zcash" => AddressFamily::Zcash,
+        _ => return None,
+    })
+}
+
+fn family_of_ticker(ticker: &str) -> Option<AddressFamily> {
+    Some(match ticker {
+        "btc" => AddressFamily::Bitcoin,
+        "ltc" => AddressFamily::Litecoin,
+        "doge" => AddressFamily::Dogecoin,
+        "eth" => AddressFamily::Evm,
+        "trx" => AddressFamily::Tron,
+        "sol" => AddressFamily::Solana,
+        "xmr" => AddressFamily::Monero,
+        "xrp" => AddressFamily::Ripple,
+        "xlm" => AddressFamily::Stellar,
+        "zec" => AddressFamily::Zcash,
+        _ => return None,
+    })
+}
+
+// =============================================================================
+// HINTS
+// =============================================================================
+
+/// Languages address hints are written in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HintLanguage {
+    En,
+    Es,
+    Fr,
+    De,
+    Pt,
+}
+
+impl HintLanguage {
+    /// First supported language in a `lang` value or Accept-Language
+    /// header (`pt-BR,pt;q=0.9,en;q=0.8`); English when none is
+    pub fn negotiate(value: &str) -> Self {
+        value
+            .split(',')
+            .filter_map(|part| {
+                let tag = part.split(';').next()?.trim().to_ascii_lowercase();
+                Some(match tag.split('-').next()? {
+                    "en" => HintLanguage::En,
+                    "es" => HintLanguage::Es,
+                    "fr" => HintLanguage::Fr,
+                    "de" => HintLanguage::De,
+                    "pt" => HintLanguage::Pt,
+                    _ => return None,
+                })
+            })
+            .next()
+            .unwrap_or(HintLanguage::En)
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            HintLanguage::En => "en",
+            HintLanguage::Es => "es",
+            HintLanguage::Fr => "fr",
+            HintLanguage::De => "de",
+            HintLanguage::Pt => "pt",
+        }
+    }
+}
+
+impl AddressRule {
+    /// One-line description of the format, e.g. "Bitcoin address: starts
+    /// with bc1, 1 or 3; 26 to 74 characters."
+    pub fn hint(&self, lang: HintLanguage) -> String {
+        let length = if self.min_length == self.max_length {
+            match lang {
+                HintLanguage::En => format!("{} characters", self.min_length),
+                HintLanguage::Es => format!("{} caracteres", self.min_length),
+                HintLanguage::Fr => format!("{} caractères", self.min_length),
+                HintLanguage::De => format!("{} Zeichen", self.min_length),
+                HintLanguage::Pt => format!("{} caracteres", self.min_length),
+            }
+        } else {
+            let (min, max) = (self.min_length, self.max_length);
+            match lang {
+                HintLanguage::En => format!("{} to {} characters", min, max),
+                HintLanguage::Es => format!("de {} a {} caracteres", min, max),
+                HintLanguage::Fr => format!("de {} à {} caractères", min, max),
+                HintLanguage::De => format!("{} bis {} Zeichen", min, max),
+                HintLanguage::Pt => format!("de {} a {} caracteres", min, max),
+            }
+        };
+
+        if self.prefixes.is_empty() {
+            return match lang {
+                HintLanguage::En => format!("{} address: {}.", self.name, length),
+                HintLanguage::Es => format!("Dirección {}: {}.", self.name, length),
+                HintLanguage::Fr => format!("Adresse {} : {}.", self.name, length),
+                HintLanguage::De => format!("{}-Adresse: {}.", self.name, length),
+                HintLanguage::Pt => format!("Endereço {}: {}.", self.name, length),
+            };
+        }
+
+        let or = match lang {
+            HintLanguage::En => "or",
+            HintLanguage::Es => "o",
+            HintLanguage::Fr => "ou",
+            HintLanguage::De => "oder",
+            HintLanguage::Pt => "ou",
+        };
+        let prefixes = match self.prefixes.split_last() {
+            Some((last, [])) => last.to_string(),
+            Some((last, rest)) => format!("{} {} {}", rest.join(", "), or, last),
+            None => String::new(),
+        };
+
+        match lang {
+            HintLanguage::En => format!("{} address: starts with {}; {}.", self.name, prefixes, length),
+            HintLanguage::Es => format!("Dirección {}: empieza por {}; {}.", self.name, prefixes, length),
+            HintLanguage::Fr => format!("Adresse {} : commence par {} ; {}.", self.name, prefixes, length),
+            HintLanguage::De => format!("{}-Adresse: beginnt mit {}; {}.", self.name, prefixes, length),
+            HintLanguage::Pt => format!("Endereço {}: começa com {}; {}.", self.name, prefixes, length),
+        }
+    }
+
+    /// When to fill in the memo, for chains that have one
+    pub fn memo_hint(&self, lang: HintLanguage) -> Option<String> {
+        let memo = self.memo?;
+        Some(match lang {
+            HintLanguage::En => format!(
+                "Sending to an exchange? Enter the {} it gave you, or the funds can't be credited.",
+                memo.name
+            ),
+            HintLanguage::Es => format!(
+                "¿Envías a un exchange? Indica el {} que te dio, o los fondos no podrán abonarse.",
+                memo.name
+            ),
+            HintLanguage::Fr => format!(
+                "Envoi vers une plateforme ? Indiquez le {} fourni, sinon les fonds ne pourront pas être crédités.",
+                memo.name
+            ),
+            HintLanguage::De => format!(
+                "Senden an eine Börse? Geben Sie das {} der Börse an, sonst kann das Guthaben nicht gutgeschrieben werden.",
+                memo.name
+            ),
+            HintLanguage::Pt => format!(
+                "Enviando para uma corretora? Informe o {} fornecido, ou os fundos não poderão ser creditados.",
+                memo.name
+            ),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_examples_match_their_own_rules() {
+        for rule in &RULES {
+            assert!(rule.matches(rule.example), "{} example fails its pattern", rule.name);
+            let len = rule.example.len();
+            assert!((rule.min_length..=rule.max_length).contains(&len), "{} example length", rule.name);
+            assert!(
+                rule.prefixes.is_empty() || rule.prefixes.iter().any(|p| rule.example.starts_with(p)),
+                "{} example prefix",
+                rule.name
+            );
+        }
+    }
+
+    #[test]
+    fn test_network_decides_token_format() {
+        assert_eq!(rule_for("usdt", Some("TRC20")).unwrap().family, AddressFamily::Tron);
+        assert_eq!(rule_for("usdt", Some("ERC20")).unwrap().family, AddressFamily::Evm);
+        assert_eq!(rule_for("btc", Some("Mainnet")).unwrap().family, AddressFamily::Bitcoin);
+        assert!(rule_for("usdt", None).is_none());
+        assert!(rule_for("ada", Some("Mainnet")).is_none());
+    }
+
+    #[test]
+    fn test_rejects_wrong_chain_addresses() {
+        let btc = rule_for("btc", None).unwrap();
+        assert!(!btc.matches("0x742d35Cc6634C0532925a3b844Bc9e7595f5bE12"));
+        assert!(!btc.matches("INVALID_BTC_ADDRESS_12345"));
+        assert!(!btc.matches("bc1qxy2kgdygjrsqtzq2n0yrf2493p83kkfjhx0wlB"), "Mixed-case bech32");
+
+        let xrp = rule_for("xrp", None).unwrap();
+        assert!(xrp.memo_matches("123456"));
+        assert!(!xrp.memo_matches("abc"));
+        assert!(btc.memo_matches(""));
+    }
+
+    #[test]
+    fn test_hint_language_negotiation() {
+        assert_eq!(HintLanguage::negotiate("pt-BR,pt;q=0.9,en;q=0.8"), HintLanguage::Pt);
+        assert_eq!(HintLanguage::negotiate("ja,de;q=0.5"), HintLanguage::De);
+        assert_eq!(HintLanguage::negotiate(""), HintLanguage::En);
+
+        let btc = rule_for("btc", None).unwrap();
+        assert_eq!(
+            btc.hint(HintLanguage::En),
+            "Bitcoin address: starts with bc1, 1 or 3; 26 to 74 characters."
+        );
+    }
+}
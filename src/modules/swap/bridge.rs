@@ -0,0 +1,97 @@
+//! Same-asset cross-chain bridging (e.g. USDT ERC20 -> USDT TRC20).
+//! Bridges skip the price leg entirely, so only providers that actually move
+//! the asset between the two networks should be offered.
+
+use super::schema::{SwapType, TrocadorQuote};
+use crate::services::amount::{self, Decimal};
+
+/// Classify a request by its currencies and networks
+pub fn swap_type(from: &str, network_from: &str, to: &str, network_to: &str) -> SwapType {
+    if from.trim().eq_ignore_ascii_case(to.trim()) && !network_from.trim().eq_ignore_ascii_case(network_to.trim()) {
+        SwapType::Bridge
+    } else {
+        SwapType::Exchange
+    }
+}
+
+/// Same ticker on the same network: nothing to swap or bridge
+pub fn is_same_route(from: &str, network_from: &str, to: &str, network_to: &str) -> bool {
+    from.trim().eq_ignore_ascii_case(to.trim()) && network_from.trim().eq_ignore_ascii_case(network_to.trim())
+}
+
+/// Providers allowed to serve bridges, from BRIDGE_PROVIDERS (comma separated).
+/// `None` when unset, in which case any provider quoting the route is used.
+pub fn bridge_providers() -> Option<Vec<String>> {
+    std::env::var("BRIDGE_PROVIDERS")
+        .ok()
+        .map(|v| {
+            v.split(',')
+                .map(|p| p.trim().to_lowercase())
+                .filter(|p| !p.is_empty())
+                .collect::<Vec<_>>()
+        })
+        .filter(|providers| !providers.is_empty())
+}
+
+pub fn supports_bridge(provider: &str, allowlist: Option<&[String]>) -> bool {
+    match allowlist {
+        Some(providers) => providers.iter().any(|p| p.eq_ignore_ascii_case(provider.trim())),
+        None => true,
+    }
+}
+
+/// Quotes from providers that can serve a bridge of `amount`: allowed by the
+/// allowlist, returning a usable amount, and within the provider's limits
+pub fn select_bridge_quotes(quotes: Vec<TrocadorQuote>, amount: Decimal, allowlist: Option<&[String]>) -> Vec<TrocadorQuote> {
+    quotes
+        .into_iter()
+        .filter(|q| supports_bridge(&q.provider, allowlist))
+        .filter(|q| q.amount_to.parse::<f64>().map(|a| a > 0.0).unwrap_or(false))
+        .filter(|q| q.min_amount.is_none_or(|min| amount::from_f64(min).is_ok_and(|min| amount >= min)))
+        .filter(|q| q.max_amount.is_none_or(|max| max <= 0.0 || amount::from_f64(max).is_ok_and(|max| amount <= max)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quote(provider: &str, amount_to: &str, min: Option<f64>, max: Option<f64>) -> TrocadorQuote {
+        TrocadorQuote {
+            provider: provider.to_string(),
+            amount_to: amount_to.to_string(),
+            min_amount: min,
+            max_amount: max,
+            kycrating: None,
+            waste: None,
+            eta: None,
+            fixed: None,
+        }
+    }
+
+    #[test]
+    fn test_swap_type() {
+        assert_eq!(swap_type("USDT", "ERC20", "usdt", "TRC20"), SwapType::Bridge);
+        assert_eq!(swap_type("USDT", "ERC20", "USDC", "ERC20"), SwapType::Exchange);
+        assert_eq!(swap_type("BTC", "Mainnet", "XMR", "Mainnet"), SwapType::Exchange);
+        assert!(is_same_route("usdt", "erc20", "USDT", "ERC20"));
+    }
+
+    #[test]
+    fn test_select_bridge_quotes() {
+        let allowlist = vec!["changenow".to_string(), "fixedfloat".to_string()];
+        let quotes = vec![
+            quote("ChangeNOW", "99.1", Some(10.0), Some(10_000.0)),
+            quote("FixedFloat", "0", None, None),
+            quote("fixedfloat", "98.7", Some(500.0), None),
+            quote("Exolix", "99.5", None, None),
+        ];
+
+        let selected = select_bridge_quotes(quotes, Decimal::ONE_HUNDRED, Some(&allowlist));
+        assert_eq!(selected.len(), 1);
+        assert_eq!(selected[0].provider, "ChangeNOW");
+
+        let open = select_bridge_quotes(vec![quote("Exolix", "99.5", None, None)], Decimal::ONE_HUNDRED, None);
+        assert_eq!(open.len(), 1);
+    }
+}
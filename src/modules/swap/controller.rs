@@ -1,37 +1,82 @@
 use axum::{
     extract::{Query, State, Path},
-    http::StatusCode,
+    http::{header, HeaderMap, StatusCode},
     response::{Response, IntoResponse},
     Json,
 };
 use std::sync::Arc;
 
 use crate::AppState;
-use super::crud::{SwapCrud, CurrenciesResult};
+use super::crud::{SwapCrud, CurrenciesResult, ProvidersResult};
 use super::schema::{
     CurrenciesQuery, ProvidersQuery, SwapErrorResponse,
     CreateSwapRequest, CreateSwapResponse, SwapStatusResponse, ValidateAddressRequest, ValidateAddressResponse,
-    HistoryQuery, HistoryResponse,
+    HistoryQuery, CurrencyResponse, ProviderResponse, SwapSummary, QuoteKeyResponse,
+    AddressSpecQuery, AddressSpecResponse, MemoSpec, ReceiptFormat, ReceiptQuery,
+    AddressProofRequest, AddressProofResponse,
 };
-use crate::modules::auth::interface::{OptionalUser, User};
+use super::address_rules::{rule_for, HintLanguage};
+use crate::modules::auth::interface::{
+    scope::{HistoryRead, SwapCreate},
+    OptionalScoped, OptionalUser, Scoped,
+};
+use crate::services::amount::WireAmount;
+use crate::services::etag::conditional_json;
+use crate::services::funnel::SwapFunnel;
+use crate::services::liquidity::PairKey;
+use crate::services::projection::FieldSelection;
+use crate::services::quote_signing::quote_signer;
+use crate::services::receipt::{pdf_renderer, receipt_signer};
+use crate::services::watch_only::watch_only;
+
+/// Catalog responses may be reused for a minute, then revalidated with
+/// If-None-Match; the Redis cache behind them refreshes every ten minutes
+const CATALOG_CACHE_CONTROL: &str = "public, max-age=60, must-revalidate";
 
 // ... (existing handlers)
 
+/// Request amounts are checked against the currency's decimals and
+/// normalized before anything prices them
+async fn normalize_amount(
+    crud: &SwapCrud,
+    amount: WireAmount,
+    ticker: &str,
+    network: &str,
+) -> Result<WireAmount, (StatusCode, Json<SwapErrorResponse>)> {
+    crud.normalize_amount(amount, ticker, network).await.map_err(|e| {
+        let status = match e {
+            super::crud::SwapError::InvalidAmount(_) => StatusCode::BAD_REQUEST,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        (status, Json(SwapErrorResponse::new(e.to_string())))
+    })
+}
+
 // =============================================================================
 // POST /swap/create - Create a new swap
 // =============================================================================
 
 pub async fn create_swap(
     State(state): State<Arc<AppState>>,
-    user: OptionalUser,
-    Json(payload): Json<CreateSwapRequest>,
+    user: OptionalScoped<SwapCreate>,
+    Json(mut payload): Json<CreateSwapRequest>,
 ) -> Result<(StatusCode, Json<CreateSwapResponse>), (StatusCode, Json<SwapErrorResponse>)> {
     let crud = SwapCrud::new(state.db.clone(), Some(state.redis.clone()), Some(state.wallet_mnemonic.clone()));
 
+    payload.amount = normalize_amount(&crud, payload.amount, &payload.from, &payload.network_from).await?;
+
     let response = crud.create_swap(&payload, user.0.map(|u| u.id)).await.map_err(|e| {
         let status = match e {
             super::crud::SwapError::AmountOutOfRange { .. } => StatusCode::BAD_REQUEST,
             super::crud::SwapError::InvalidAddress => StatusCode::BAD_REQUEST,
+            super::crud::SwapError::CustodyNotEnabled => StatusCode::FORBIDDEN,
+            super::crud::SwapError::KycRequired { .. } => StatusCode::FORBIDDEN,
+            super::crud::SwapError::AddressBookEntryNotFound => StatusCode::NOT_FOUND,
+            super::crud::SwapError::AddressBookEntryMismatch => StatusCode::BAD_REQUEST,
+            super::crud::SwapError::TradingHalted(_) => StatusCode::SERVICE_UNAVAILABLE,
+            super::crud::SwapError::InvalidBridgeRoute(_) => StatusCode::BAD_REQUEST,
+            super::crud::SwapError::InvalidQuote(_) => StatusCode::BAD_REQUEST,
+            super::crud::SwapError::InvalidClientData(_) => StatusCode::BAD_REQUEST,
             _ => StatusCode::INTERNAL_SERVER_ERROR,
         };
         (status, Json(SwapErrorResponse::new(e.to_string())))
@@ -40,10 +85,30 @@ pub async fn create_swap(
     Ok((StatusCode::CREATED, Json(response)))
 }
 
+// =============================================================================
+// GET /swap/quote-key - Public key for verifying signed quotes
+// =============================================================================
+
+pub async fn get_quote_key() -> Result<Json<QuoteKeyResponse>, (StatusCode, Json<SwapErrorResponse>)> {
+    let signer = quote_signer().ok_or_else(|| {
+        (StatusCode::NOT_FOUND, Json(SwapErrorResponse::new("Quote signing is not enabled")))
+    })?;
+
+    Ok(Json(QuoteKeyResponse {
+        algorithm: "ed25519".to_string(),
+        key_id: signer.key_id().to_string(),
+        public_key: signer.public_key_hex(),
+        ttl_seconds: signer.ttl_secs(),
+    }))
+}
+
 pub async fn get_currencies(
     State(state): State<Arc<AppState>>,
-    Query(query): Query<CurrenciesQuery>,
+    headers: HeaderMap,
+    Query(mut query): Query<CurrenciesQuery>,
 ) -> Result<Response, (StatusCode, Json<SwapErrorResponse>)> {
+    // Taken off the query so projections share the unfiltered cache entry
+    let selection = parse_fields::<CurrencyResponse>(query.fields.take())?;
     let crud = SwapCrud::new(state.db.clone(), Some(state.redis.clone()), Some(state.wallet_mnemonic.clone()));
 
     // The CRUD layer now handles caching, pagination, raw JSON, and background synchronization
@@ -54,25 +119,19 @@ pub async fn get_currencies(
         )
     })?;
 
-    match result {
-        CurrenciesResult::Structured(responses) => {
-            // Standard JSON response
-            Ok(Json(responses).into_response())
-        },
-        CurrenciesResult::RawJson(json_string) => {
-            // Optimized raw JSON response (avoids serialization overhead)
-            let response = Response::builder()
-                .header("content-type", "application/json")
-                .body(axum::body::Body::from(json_string))
-                .map_err(|e| {
-                    (
-                        StatusCode::INTERNAL_SERVER_ERROR,
-                        Json(SwapErrorResponse::new(e.to_string())),
-                    )
-                })?;
-            Ok(response)
+    let body = match (result, selection) {
+        // Cached raw JSON goes out as-is (avoids serialization overhead)
+        (CurrenciesResult::RawJson(json_string), None) => json_string,
+        (CurrenciesResult::Structured(responses), None) => serde_json::to_string(&responses).map_err(internal_error)?,
+        (CurrenciesResult::RawJson(json_string), Some(selection)) => {
+            project_json(&selection, serde_json::from_str(&json_string).map_err(internal_error)?)?
         }
-    }
+        (CurrenciesResult::Structured(responses), Some(selection)) => {
+            project_json(&selection, serde_json::to_value(responses).map_err(internal_error)?)?
+        }
+    };
+
+    Ok(conditional_json(&headers, body, CATALOG_CACHE_CONTROL))
 }
 
 // =============================================================================
@@ -81,8 +140,10 @@ pub async fn get_currencies(
 
 pub async fn get_providers(
     State(state): State<Arc<AppState>>,
-    Query(query): Query<ProvidersQuery>,
+    headers: HeaderMap,
+    Query(mut query): Query<ProvidersQuery>,
 ) -> Result<Response, (StatusCode, Json<SwapErrorResponse>)> {
+    let selection = parse_fields::<ProviderResponse>(query.fields.take())?;
     let crud = SwapCrud::new(state.db.clone(), Some(state.redis.clone()), Some(state.wallet_mnemonic.clone()));
 
     // The CRUD layer now handles caching, optimized filtering, and background synchronization
@@ -93,25 +154,18 @@ pub async fn get_providers(
         )
     })?;
 
-    match result {
-        super::crud::ProvidersResult::Structured(responses) => {
-            // Standard JSON response
-            Ok(Json(responses).into_response())
-        },
-        super::crud::ProvidersResult::RawJson(json_string) => {
-            // Optimized raw JSON response (avoids serialization overhead)
-            let response = Response::builder()
-                .header("content-type", "application/json")
-                .body(axum::body::Body::from(json_string))
-                .map_err(|e| {
-                    (
-                        StatusCode::INTERNAL_SERVER_ERROR,
-                        Json(SwapErrorResponse::new(e.to_string())),
-                    )
-                })?;
-            Ok(response)
+    let body = match (result, selection) {
+        (ProvidersResult::RawJson(json_string), None) => json_string,
+        (ProvidersResult::Structured(responses), None) => serde_json::to_string(&responses).map_err(internal_error)?,
+        (ProvidersResult::RawJson(json_string), Some(selection)) => {
+            project_json(&selection, serde_json::from_str(&json_string).map_err(internal_error)?)?
         }
-    }
+        (ProvidersResult::Structured(responses), Some(selection)) => {
+            project_json(&selection, serde_json::to_value(responses).map_err(internal_error)?)?
+        }
+    };
+
+    Ok(conditional_json(&headers, body, CATALOG_CACHE_CONTROL))
 }
 
 // =============================================================================
@@ -120,17 +174,40 @@ pub async fn get_providers(
 
 pub async fn get_rates(
     State(state): State<Arc<AppState>>,
-    Query(query): Query<super::schema::RatesQuery>,
+    user: OptionalUser,
+    Query(mut query): Query<super::schema::RatesQuery>,
 ) -> Result<Json<super::schema::RatesResponse>, (StatusCode, Json<super::schema::SwapErrorResponse>)> {
     let crud = SwapCrud::new(state.db.clone(), Some(state.redis.clone()), Some(state.wallet_mnemonic.clone()));
 
-    let response = crud.get_rates_optimized(&query).await.map_err(|e| {
-        (
-            StatusCode::BAD_GATEWAY,
-            Json(super::schema::SwapErrorResponse::new(e.to_string())),
-        )
+    query.amount = normalize_amount(&crud, query.amount, &query.from, &query.network_from).await?;
+
+    let response = crud.get_rates_optimized(&query, user.0.as_ref().map(|u| u.id.as_str())).await.map_err(|e| {
+        let status = match e {
+            super::crud::SwapError::TradingHalted(_) => StatusCode::SERVICE_UNAVAILABLE,
+            super::crud::SwapError::InvalidBridgeRoute(_) => StatusCode::BAD_REQUEST,
+            _ => StatusCode::BAD_GATEWAY,
+        };
+        (status, Json(super::schema::SwapErrorResponse::new(e.to_string())))
     })?;
 
+    // Off the request path; the funnel is reporting, not the quote. A
+    // watch-only deployment records nothing.
+    if !watch_only() {
+        let funnel = SwapFunnel::new(state.db.clone());
+        let trade_id = response.trade_id.clone();
+        let pair = PairKey {
+            from: response.from.clone(),
+            network_from: response.network_from.clone(),
+            to: response.to.clone(),
+            network_to: response.network_to.clone(),
+        };
+        tokio::spawn(async move {
+            if let Err(e) = funnel.record_quote(&trade_id, &pair).await {
+                tracing::warn!("Failed to record quote {} in the funnel: {}", trade_id, e);
+            }
+        });
+    }
+
     Ok(Json(response))
 }
 
@@ -157,6 +234,151 @@ pub async fn get_swap_status(
     Ok(Json(response))
 }
 
+// =============================================================================
+// GET /swap/{id}/receipt - Receipt of a completed swap, as JSON or PDF
+// =============================================================================
+
+/// The owner can fetch their receipt signed in; anyone holding a receipt
+/// token can fetch it without. Swaps created without an account are
+/// reachable by id alone, as with GET /swap/{id}.
+pub async fn get_swap_receipt(
+    State(state): State<Arc<AppState>>,
+    user: OptionalUser,
+    Path(swap_id): Path<String>,
+    Query(query): Query<ReceiptQuery>,
+) -> Result<Response, (StatusCode, Json<SwapErrorResponse>)> {
+    let crud = SwapCrud::new(state.db.clone(), Some(state.redis.clone()), Some(state.wallet_mnemonic.clone()));
+
+    let (owner, mut receipt) = crud.get_swap_receipt(&swap_id).await.map_err(|e| {
+        let status = match e {
+            super::crud::SwapError::SwapNotFound => StatusCode::NOT_FOUND,
+            super::crud::SwapError::ReceiptNotAvailable => StatusCode::CONFLICT,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        (status, Json(SwapErrorResponse::new(e.to_string())))
+    })?;
+
+    let is_owner = match (&owner, &user.0) {
+        (None, _) => true,
+        (Some(owner), Some(user)) => *owner == user.id,
+        (Some(_), None) => false,
+    };
+
+    receipt.receipt_token = match (query.token, is_owner) {
+        (Some(token), false) => {
+            let signer = receipt_signer().ok_or_else(|| {
+                (StatusCode::FORBIDDEN, Json(SwapErrorResponse::new("Receipt tokens are not enabled")))
+            })?;
+            signer
+                .verify(&receipt.swap_id, &token)
+                .map_err(|e| (StatusCode::FORBIDDEN, Json(SwapErrorResponse::new(e.to_string()))))?;
+            Some(token)
+        }
+        (None, false) if user.0.is_some() => {
+            return Err((StatusCode::NOT_FOUND, Json(SwapErrorResponse::new("Swap not found"))));
+        }
+        (None, false) => {
+            return Err((
+                StatusCode::UNAUTHORIZED,
+                Json(SwapErrorResponse::new("Sign in or pass a receipt token to view this receipt")),
+            ));
+        }
+        (_, true) => receipt_signer().map(|signer| signer.issue(&receipt.swap_id)),
+    };
+
+    let no_store = [(header::CACHE_CONTROL, "private, no-store")];
+    if query.format == ReceiptFormat::Json {
+        return Ok((no_store, Json(receipt)).into_response());
+    }
+
+    let renderer = pdf_renderer().ok_or_else(|| {
+        (StatusCode::NOT_IMPLEMENTED, Json(SwapErrorResponse::new("PDF receipts are not enabled")))
+    })?;
+    let pdf = renderer.render(&receipt).await.map_err(|e| {
+        tracing::warn!("Receipt PDF for swap {} failed: {}", receipt.swap_id, e);
+        (StatusCode::BAD_GATEWAY, Json(SwapErrorResponse::new("Could not render the PDF receipt")))
+    })?;
+    let disposition = format!("attachment; filename=\"receipt-{}.pdf\"", receipt.swap_id);
+    Ok((
+        no_store,
+        [(header::CONTENT_TYPE, "application/pdf".to_string()), (header::CONTENT_DISPOSITION, disposition)],
+        pdf,
+    )
+        .into_response())
+}
+
+// =============================================================================
+// POST /swap/{id}/address-proof - Prove our wallet holds the swap's address
+// =============================================================================
+
+/// Partners routing funds to one of our addresses send a challenge and get
+/// back a signature over it by that address's key; see
+/// `services::wallet::ownership` for the statement and schemes.
+pub async fn create_address_proof(
+    State(state): State<Arc<AppState>>,
+    Path(swap_id): Path<String>,
+    Json(payload): Json<AddressProofRequest>,
+) -> Result<Json<AddressProofResponse>, (StatusCode, Json<SwapErrorResponse>)> {
+    let crud = SwapCrud::new(state.db.clone(), Some(state.redis.clone()), Some(state.wallet_mnemonic.clone()));
+
+    let response = crud.address_proof(&swap_id, &payload.challenge).await.map_err(|e| {
+        let status = match e {
+            super::crud::SwapError::SwapNotFound => StatusCode::NOT_FOUND,
+            super::crud::SwapError::InvalidChallenge(_) => StatusCode::BAD_REQUEST,
+            super::crud::SwapError::AddressNotHeld => StatusCode::CONFLICT,
+            super::crud::SwapError::ExternalApiError(_) => StatusCode::BAD_GATEWAY,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        (status, Json(SwapErrorResponse::new(e.to_string())))
+    })?;
+
+    Ok(Json(response))
+}
+
+// =============================================================================
+// GET /swap/currencies/{ticker}/address-spec - Address format for client-side checks
+// =============================================================================
+
+pub async fn get_address_spec(
+    headers: HeaderMap,
+    Path(ticker): Path<String>,
+    Query(query): Query<AddressSpecQuery>,
+) -> Result<Json<AddressSpecResponse>, (StatusCode, Json<SwapErrorResponse>)> {
+    let rule = rule_for(&ticker, query.network.as_deref()).ok_or_else(|| {
+        let error = match &query.network {
+            Some(network) => format!("No address format known for {} on {}", ticker, network),
+            None => format!("No address format known for {}; try specifying a network", ticker),
+        };
+        (StatusCode::NOT_FOUND, Json(SwapErrorResponse::new(error)))
+    })?;
+
+    let lang = match query.lang.as_deref() {
+        Some(lang) => HintLanguage::negotiate(lang),
+        None => HintLanguage::negotiate(
+            headers.get(header::ACCEPT_LANGUAGE).and_then(|v| v.to_str().ok()).unwrap_or(""),
+        ),
+    };
+
+    Ok(Json(AddressSpecResponse {
+        ticker: ticker.to_lowercase(),
+        network: query.network,
+        pattern: rule.pattern.to_string(),
+        prefixes: rule.prefixes.iter().map(|p| p.to_string()).collect(),
+        min_length: rule.min_length,
+        max_length: rule.max_length,
+        case_sensitive: rule.case_sensitive,
+        example: rule.example.to_string(),
+        hint: rule.hint(lang),
+        memo: rule.memo.map(|memo| MemoSpec {
+            name: memo.name.to_string(),
+            pattern: memo.pattern.to_string(),
+            max_length: memo.max_length,
+            hint: rule.memo_hint(lang).unwrap_or_default(),
+        }),
+        lang: lang.code().to_string(),
+    }))
+}
+
 // =============================================================================
 // POST /swap/validate-address - Validate cryptocurrency address
 // =============================================================================
@@ -185,9 +407,10 @@ pub async fn validate_address(
 
 pub async fn get_swap_history(
     State(state): State<Arc<AppState>>,
-    user: User,  // Requires authentication
-    Query(query): Query<HistoryQuery>,
-) -> Result<Json<HistoryResponse>, (StatusCode, Json<SwapErrorResponse>)> {
+    user: Scoped<HistoryRead>,
+    Query(mut query): Query<HistoryQuery>,
+) -> Result<Response, (StatusCode, Json<SwapErrorResponse>)> {
+    let selection = parse_fields::<SwapSummary>(query.fields.take())?;
     let crud = SwapCrud::new(
         state.db.clone(),
         Some(state.redis.clone()),
@@ -202,8 +425,33 @@ pub async fn get_swap_history(
         };
         (status, Json(SwapErrorResponse::new(e.to_string())))
     })?;
-    
-    Ok(Json(response))
+
+    match selection {
+        Some(selection) => {
+            let value = serde_json::to_value(response).map_err(internal_error)?;
+            Ok(Json(selection.apply_at(value, "swaps")).into_response())
+        }
+        None => Ok(Json(response).into_response()),
+    }
+}
+
+/// Parse a `fields` parameter against the record type it projects
+fn parse_fields<T: schemars::JsonSchema>(
+    raw: Option<String>,
+) -> Result<Option<FieldSelection>, (StatusCode, Json<SwapErrorResponse>)> {
+    FieldSelection::parse::<T>(raw.as_deref())
+        .map_err(|e| (StatusCode::BAD_REQUEST, Json(SwapErrorResponse::new(e))))
+}
+
+fn project_json(
+    selection: &FieldSelection,
+    value: serde_json::Value,
+) -> Result<String, (StatusCode, Json<SwapErrorResponse>)> {
+    serde_json::to_string(&selection.apply(value)).map_err(internal_error)
+}
+
+fn internal_error(e: serde_json::Error) -> (StatusCode, Json<SwapErrorResponse>) {
+    (StatusCode::INTERNAL_SERVER_ERROR, Json(SwapErrorResponse::new(e.to_string())))
 }
 
 
@@ -232,13 +480,31 @@ pub async fn get_pairs(
     Ok(Json(response))
 }
 
+// =============================================================================
+// GET /swap/pairs/matrix - Tradable pairs with provider limits and rate types
+// =============================================================================
+
+pub async fn get_pair_matrix(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<super::schema::PairMatrixQuery>,
+) -> Result<Json<super::schema::PairMatrixResponse>, (StatusCode, Json<SwapErrorResponse>)> {
+    let crud = SwapCrud::new(state.db.clone(), Some(state.redis.clone()), None);
+
+    let response = crud
+        .get_pair_matrix(&query)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, Json(SwapErrorResponse::new(e.to_string()))))?;
+
+    Ok(Json(response))
+}
+
 // =============================================================================
 // GET /swap/estimate - Quick rate preview without creating swap
 // =============================================================================
 
 pub async fn get_estimate(
     State(state): State<Arc<AppState>>,
-    Query(query): Query<super::schema::EstimateQuery>,
+    Query(mut query): Query<super::schema::EstimateQuery>,
 ) -> Result<Json<super::schema::EstimateResponse>, (StatusCode, Json<SwapErrorResponse>)> {
     use validator::Validate;
     
@@ -252,11 +518,51 @@ pub async fn get_estimate(
     
     let crud = SwapCrud::new(state.db.clone(), Some(state.redis.clone()), Some(state.wallet_mnemonic.clone()));
 
+    query.amount = normalize_amount(&crud, query.amount, &query.from, &query.network_from).await?;
+
     let response = crud.get_estimate_optimized(&query).await.map_err(|e| {
         let status = match e {
             super::crud::SwapError::PairNotAvailable => StatusCode::NOT_FOUND,
             super::crud::SwapError::AmountOutOfRange { .. } => StatusCode::BAD_REQUEST,
             super::crud::SwapError::ExternalApiError(_) => StatusCode::BAD_GATEWAY,
+            super::crud::SwapError::TradingHalted(_) => StatusCode::SERVICE_UNAVAILABLE,
+            super::crud::SwapError::InvalidBridgeRoute(_) => StatusCode::BAD_REQUEST,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        (status, Json(SwapErrorResponse::new(e.to_string())))
+    })?;
+
+    Ok(Json(response))
+}
+
+// =============================================================================
+// GET /swap/estimate/detailed - Fee decomposition for a pair and amount
+// =============================================================================
+
+pub async fn get_estimate_detailed(
+    State(state): State<Arc<AppState>>,
+    Query(mut query): Query<super::schema::EstimateQuery>,
+) -> Result<Json<super::schema::DetailedEstimateResponse>, (StatusCode, Json<SwapErrorResponse>)> {
+    use validator::Validate;
+
+    if let Err(e) = query.validate() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(SwapErrorResponse::new(e.to_string())),
+        ));
+    }
+
+    let crud = SwapCrud::new(state.db.clone(), Some(state.redis.clone()), Some(state.wallet_mnemonic.clone()));
+
+    query.amount = normalize_amount(&crud, query.amount, &query.from, &query.network_from).await?;
+
+    let response = crud.get_estimate_detailed(&query).await.map_err(|e| {
+        let status = match e {
+            super::crud::SwapError::PairNotAvailable => StatusCode::NOT_FOUND,
+            super::crud::SwapError::AmountOutOfRange { .. } => StatusCode::BAD_REQUEST,
+            super::crud::SwapError::ExternalApiError(_) => StatusCode::BAD_GATEWAY,
+            super::crud::SwapError::TradingHalted(_) => StatusCode::SERVICE_UNAVAILABLE,
+            super::crud::SwapError::InvalidBridgeRoute(_) => StatusCode::BAD_REQUEST,
             _ => StatusCode::INTERNAL_SERVER_ERROR,
         };
         (status, Json(SwapErrorResponse::new(e.to_string())))
@@ -1,13 +1,44 @@
 use chrono::{Utc, DateTime};
 use sqlx::{MySql, Pool};
+use std::sync::Arc;
 use std::time::Duration;
+use rust_decimal::prelude::ToPrimitive;
 
-use super::model::{Currency, Provider};
-use super::schema::{CurrenciesQuery, ProvidersQuery, TrocadorCurrency, TrocadorProvider, CurrencyResponse, ProviderResponse};
-use crate::services::trocador::{TrocadorClient, TrocadorError};
+use super::model::{Currency, PayloadKind, Provider};
+use super::schema::{CurrenciesQuery, ProvidersQuery, TrocadorCurrency, TrocadorProvider, CurrencyResponse, ProviderResponse, SwapExplorerLinks};
+use crate::services::trocador::{self, TrocadorClient, TrocadorError};
+use crate::services::provider_payloads::ProviderPayloadStore;
 use crate::services::redis_cache::RedisService;
-use crate::services::pricing::PricingEngine;
+use crate::services::pricing::{approx_usd_price, ActivePromotions, PricingEngine, PromotionQuote, RoundingPolicy};
+use crate::services::pricing::promotions::discounted_fee;
+use crate::services::pricing::fanout::{FanoutResult, QuoteFanout};
+use crate::services::runtime_config::runtime_config;
+use crate::modules::commissions::crud::{CommissionCrud, CommissionOverrides};
+use crate::modules::promotions::crud::{NewRedemption, PromotionCrud};
+use crate::modules::promotions::model::Promotion;
 use crate::services::gas::GasEstimator;
+use crate::services::wallet::btc_fees::{BitcoinFeeOracle, FeeTarget};
+use crate::config::rpc_config::{get_rpc_config, BlockchainProtocol};
+use crate::services::events::DomainEvent;
+use crate::services::payout::{PayoutExecutor, PayoutQueueStatus};
+use crate::services::swap_state::{StatusActor, SwapStateMachine, Transition, TransitionError};
+use crate::services::pii::SealedString;
+use crate::services::explorer::{chain_key, ExplorerRegistry};
+use crate::services::network_status::NetworkStatusReport;
+use crate::services::rpc::RpcManager;
+use crate::services::quote_signing::{quote_signer, signed_quotes_required, QuoteError, QuotePayload};
+use crate::services::amount::{self, Decimal, WireAmount};
+use crate::services::refund::{RefundCalculator, RefundConfig};
+use crate::services::swap_eta::{SwapEtaEstimator, SwapPair};
+use crate::services::sandbox::SandboxConfig;
+use crate::services::watch_only::watch_only;
+use crate::services::kyc::KycPolicy;
+use crate::modules::kyc::crud::KycCrud;
+use crate::modules::kyc::model::KycLevel;
+use crate::services::liquidity::{build_matrix, LiquidityRow, PairKey, ProviderLiquidity, MATRIX_CACHE_KEY, MATRIX_CACHE_TTL_SECS};
+use super::address_rules;
+use super::bridge;
+use super::schema::SwapType;
 
 pub enum CurrenciesResult {
     RawJson(String),
@@ -19,6 +50,22 @@ pub enum ProvidersResult {
     Structured(Vec<ProviderResponse>),
 }
 
+/// Pair, provider, limits, rate types and refresh time of a stored
+/// liquidity entry
+type PairLiquidityRow = (String, String, String, String, String, Option<f64>, Option<f64>, bool, bool, DateTime<Utc>);
+
+/// Id, provider, provider trade id, status, error and timestamps of a swap
+/// being debugged
+type SwapDebugRow = (
+    String,
+    String,
+    Option<String>,
+    super::schema::SwapStatus,
+    Option<String>,
+    DateTime<Utc>,
+    DateTime<Utc>,
+);
+
 // =============================================================================
 // SWAP ERROR
 // =============================================================================
@@ -36,6 +83,23 @@ pub enum SwapError {
     ExternalApiError(String),
     RedisError(String),
     InvalidCursor(String), // Added for cursor validation errors
+    CustodyNotEnabled,
+    AddressBookEntryNotFound,
+    AddressBookEntryMismatch,
+    TradingHalted(String),
+    InvalidBridgeRoute(String),
+    InvalidQuote(QuoteError),
+    /// client_reference or metadata rejected
+    InvalidClientData(String),
+    /// Receipts are issued once a swap completes
+    ReceiptNotAvailable,
+    /// Negative, unparseable or more precise than the currency allows
+    InvalidAmount(String),
+    /// The provider's KYC rating calls for a verified user
+    KycRequired { provider: String, level: KycLevel },
+    /// The swap's address isn't one our wallet can sign for
+    AddressNotHeld,
+    InvalidChallenge(String),
 }
 
 impl std::fmt::Display for SwapError {
@@ -54,6 +118,24 @@ impl std::fmt::Display for SwapError {
             SwapError::ExternalApiError(e) => write!(f, "External API error: {}", e),
             SwapError::RedisError(e) => write!(f, "Redis error: {}", e),
             SwapError::InvalidCursor(e) => write!(f, "Invalid cursor: {}", e),
+            SwapError::CustodyNotEnabled => {
+                write!(f, "Deposit-to-balance requires an account with custodial balances enabled")
+            }
+            SwapError::AddressBookEntryNotFound => write!(f, "Address book entry not found"),
+            SwapError::AddressBookEntryMismatch => {
+                write!(f, "Address book entry does not match the destination currency/network")
+            }
+            SwapError::TradingHalted(target) => write!(f, "Trading is temporarily halted for {}", target),
+            SwapError::InvalidBridgeRoute(msg) => write!(f, "Invalid bridge route: {}", msg),
+            SwapError::InvalidQuote(e) => write!(f, "Invalid quote: {}", e),
+            SwapError::InvalidClientData(msg) => write!(f, "Invalid client data: {}", msg),
+            SwapError::ReceiptNotAvailable => write!(f, "A receipt is available once the swap has completed"),
+            SwapError::InvalidAmount(msg) => write!(f, "Invalid amount: {}", msg),
+            SwapError::KycRequired { provider, level } => {
+                write!(f, "{} requires identity verification to the {} level", provider, level.as_str())
+            }
+            SwapError::AddressNotHeld => write!(f, "This swap has no address held in our wallet"),
+            SwapError::InvalidChallenge(msg) => write!(f, "Invalid challenge: {}", msg),
         }
     }
 }
@@ -64,6 +146,60 @@ impl From<TrocadorError> for SwapError {
     }
 }
 
+/// Scale of the swaps.amount column
+const SWAP_AMOUNT_DECIMALS: u32 = 8;
+
+pub const MAX_CLIENT_REFERENCE_LEN: usize = 128;
+/// Serialized size limit of swap metadata
+pub const MAX_METADATA_BYTES: usize = 4096;
+
+/// Check an integrator's client_reference and metadata before storing them
+pub fn validate_client_data(
+    client_reference: Option<&str>,
+    metadata: Option<&serde_json::Map<String, serde_json::Value>>,
+) -> Result<(), SwapError> {
+    if let Some(reference) = client_reference {
+        if reference.is_empty() || reference.chars().count() > MAX_CLIENT_REFERENCE_LEN {
+            return Err(SwapError::InvalidClientData(format!(
+                "client_reference must be 1-{} characters",
+                MAX_CLIENT_REFERENCE_LEN
+            )));
+        }
+        if reference.chars().any(char::is_control) {
+            return Err(SwapError::InvalidClientData("client_reference contains control characters".to_string()));
+        }
+    }
+    if let Some(metadata) = metadata {
+        let size = serde_json::to_vec(metadata).map(|v| v.len()).unwrap_or(usize::MAX);
+        if size > MAX_METADATA_BYTES {
+            return Err(SwapError::InvalidClientData(format!(
+                "metadata is {} bytes; the limit is {}",
+                size, MAX_METADATA_BYTES
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Queue state of a swap's payout in this process's payout executor
+fn payout_progress(swap_id: &str) -> Option<super::schema::PayoutProgress> {
+    let status = PayoutExecutor::global()?.status(swap_id)?;
+    Some(match status {
+        PayoutQueueStatus::Queued(position) => super::schema::PayoutProgress {
+            state: "queued".to_string(),
+            lane: position.priority.as_str().to_string(),
+            queue_position: Some(position.position),
+            queue_length: Some(position.queued),
+        },
+        PayoutQueueStatus::InFlight { priority, .. } => super::schema::PayoutProgress {
+            state: "sending".to_string(),
+            lane: priority.as_str().to_string(),
+            queue_position: None,
+            queue_length: None,
+        },
+    })
+}
+
 // =============================================================================
 // SWAP CRUD
 // =============================================================================
@@ -77,17 +213,137 @@ pub struct SwapCrud {
 
 impl SwapCrud {
     pub fn new(pool: Pool<MySql>, redis_service: Option<RedisService>, wallet_mnemonic: Option<String>) -> Self {
-        let gas_estimator = GasEstimator::new(redis_service.clone());
+        let gas_estimator = GasEstimator::new(redis_service.clone()).with_history(pool.clone());
         Self { pool, redis_service, wallet_mnemonic, gas_estimator }
     }
 
     /// Normalize provider name from Trocador API to database ID format
-    fn normalize_provider_id(provider_name: &str) -> String {
+    pub(crate) fn normalize_provider_id(provider_name: &str) -> String {
         // Trocador returns names like "ChangeNOW", "FixedFloat", "Changelly"
         // Database uses lowercase slugs like "changenow", "fixedfloat", "changelly"
         provider_name.to_lowercase().replace(" ", "").replace("-", "")
     }
 
+    /// Reject pairs blocked by the kill-switch (manual or RPC-health halts)
+    async fn ensure_trading_enabled(
+        &self,
+        from: &str,
+        network_from: &str,
+        to: &str,
+        network_to: &str,
+    ) -> Result<(), SwapError> {
+        let halts = crate::modules::halts::crud::HaltCrud::new(self.pool.clone());
+        match halts.check_pair(from, network_from, to, network_to).await {
+            Ok(None) => Ok(()),
+            Ok(Some(halt)) => Err(SwapError::TradingHalted(halt.target())),
+            Err(e) => Err(SwapError::DatabaseError(e.to_string())),
+        }
+    }
+
+    /// Refuse providers whose KYC rating needs a higher verified level than
+    /// the user holds; guests hold none. A no-op unless KYC_REQUIRED_LEVELS
+    /// is set.
+    async fn ensure_kyc(&self, provider: &str, user_id: Option<&str>) -> Result<(), SwapError> {
+        let policy = KycPolicy::global();
+        if !policy.is_enabled() {
+            return Ok(());
+        }
+
+        let rating: Option<(String,)> =
+            sqlx::query_as("SELECT CAST(kyc_rating AS CHAR) FROM providers WHERE id = ?")
+                .bind(Self::normalize_provider_id(provider))
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(|e| SwapError::DatabaseError(e.to_string()))?;
+        let required = policy.required_level(rating.as_ref().map(|(r,)| r.as_str()));
+        if required == KycLevel::None {
+            return Ok(());
+        }
+
+        let verified = match user_id {
+            Some(user_id) => KycCrud::new(self.pool.clone())
+                .verified_level(user_id)
+                .await
+                .map_err(|e| SwapError::DatabaseError(e.to_string()))?,
+            None => KycLevel::None,
+        };
+        if verified < required {
+            return Err(SwapError::KycRequired { provider: provider.to_string(), level: required });
+        }
+        Ok(())
+    }
+
+    /// Decimal places a request amount of `ticker` on `network` may have:
+    /// the currency's `decimals` from the currencies table (the per-ticker
+    /// rounding policy when it isn't listed), capped at the 8 places
+    /// swaps.amount stores
+    pub async fn amount_decimals(&self, ticker: &str, network: &str) -> Result<u32, SwapError> {
+        let listed: Option<(i32,)> = sqlx::query_as(
+            "SELECT decimals FROM currencies WHERE LOWER(symbol) = LOWER(?) AND LOWER(network) = LOWER(?) LIMIT 1",
+        )
+        .bind(ticker.trim())
+        .bind(network.trim())
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| SwapError::DatabaseError(e.to_string()))?;
+
+        let decimals = listed
+            .and_then(|(decimals,)| u32::try_from(decimals).ok())
+            .unwrap_or_else(|| RoundingPolicy::for_currency(ticker).decimals);
+        Ok(decimals.min(SWAP_AMOUNT_DECIMALS))
+    }
+
+    /// A client's amount checked against the `from` currency's decimals and
+    /// rebuilt from its minor units, before it reaches pricing or a provider
+    pub async fn normalize_amount(
+        &self,
+        amount: WireAmount,
+        ticker: &str,
+        network: &str,
+    ) -> Result<WireAmount, SwapError> {
+        let decimals = self.amount_decimals(ticker, network).await?;
+        amount.normalize(decimals).map_err(|e| SwapError::InvalidAmount(e.to_string()))
+    }
+
+    /// Classify the request and, for a bridge, require both networks to be
+    /// listed for the ticker exactly as given
+    async fn check_route(
+        &self,
+        from: &str,
+        network_from: &str,
+        to: &str,
+        network_to: &str,
+    ) -> Result<SwapType, SwapError> {
+        if bridge::is_same_route(from, network_from, to, network_to) {
+            return Err(SwapError::InvalidBridgeRoute(format!(
+                "{} on {} cannot be swapped to itself",
+                from, network_from
+            )));
+        }
+
+        let swap_type = bridge::swap_type(from, network_from, to, network_to);
+        if swap_type == SwapType::Bridge {
+            let networks: Vec<(String,)> = sqlx::query_as(
+                "SELECT network FROM currencies WHERE LOWER(symbol) = LOWER(?) AND is_active = TRUE"
+            )
+            .bind(from.trim())
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| SwapError::DatabaseError(e.to_string()))?;
+
+            for network in [network_from, network_to] {
+                if !networks.iter().any(|(n,)| n.eq_ignore_ascii_case(network.trim())) {
+                    return Err(SwapError::InvalidBridgeRoute(format!(
+                        "{} is not supported on network '{}'",
+                        from.to_uppercase(), network
+                    )));
+                }
+            }
+        }
+
+        Ok(swap_type)
+    }
+
     /// Internal helper to estimate gas cost for payout on the target network
     /// Get the amount Trocador should have sent to our address
     pub async fn get_expected_trocador_amount(&self, swap_id: &str) -> Result<f64, SwapError> {
@@ -109,6 +365,60 @@ impl SwapCrud {
         self.gas_estimator.get_gas_cost_for_network(network).await
     }
 
+    /// Payout fee per confirmation target when `network` is Bitcoin; empty
+    /// elsewhere or when no fee source answers
+    async fn fee_targets(&self, network: &str) -> Vec<super::schema::FeeTargetOption> {
+        let is_bitcoin = get_rpc_config(&network.to_lowercase())
+            .is_some_and(|config| matches!(config.protocol, BlockchainProtocol::Bitcoin));
+        if !is_bitcoin {
+            return Vec::new();
+        }
+
+        let oracle = BitcoinFeeOracle::global();
+        let rates = match oracle.rates().await {
+            Ok(rates) => rates,
+            Err(e) => {
+                tracing::warn!("Bitcoin fee targets unavailable: {}", e);
+                return Vec::new();
+            }
+        };
+
+        FeeTarget::ALL
+            .into_iter()
+            .map(|target| super::schema::FeeTargetOption {
+                target,
+                blocks: target.blocks(),
+                sat_per_vbyte: rates.get(target),
+                network_fee_estimate: rates.payout_fee_sats(target) as f64 / 100_000_000.0,
+                payout_target: target == oracle.payout_target(),
+            })
+            .collect()
+    }
+
+    /// Negotiated provider rates. Quoting falls back to the strategy's rates
+    /// rather than failing when they can't be loaded.
+    async fn commission_overrides(&self) -> Arc<CommissionOverrides> {
+        match CommissionCrud::new(self.pool.clone()).overrides().await {
+            Ok(overrides) => overrides,
+            Err(e) => {
+                tracing::warn!("Failed to load provider commissions: {}", e);
+                Arc::new(CommissionOverrides::default())
+            }
+        }
+    }
+
+    /// Running campaigns. Quotes go out at full price rather than failing
+    /// when they can't be loaded.
+    async fn active_promotions(&self) -> Arc<ActivePromotions> {
+        match PromotionCrud::new(self.pool.clone()).active().await {
+            Ok(promotions) => promotions,
+            Err(e) => {
+                tracing::warn!("Failed to load promotions: {}", e);
+                Arc::new(ActivePromotions::default())
+            }
+        }
+    }
+
     // =========================================================================
     // CURRENCIES
     // =========================================================================
@@ -180,7 +490,9 @@ impl SwapCrud {
         // Store the sync duration (Delta) for PER and invalidate response cache
         if let Some(service) = &self.redis_service {
             let _ = service.set_string("currencies:sync_duration", &duration.to_string(), 3600).await;
-            let _ = service.set_string("currencies:response:all", "", 0).await;
+            // Drop the fresh response entries only; the stale copies keep
+            // serving while the next request refreshes them in the background
+            let _ = service.delete_prefix("trocador:currencies:CurrenciesQuery").await;
         }
 
         Ok(total_count)
@@ -595,7 +907,7 @@ impl SwapCrud {
                 "duration": duration
             });
             let _ = service.set_json("providers:sync_stats", &stats, 3600 * 24).await;
-            let _ = service.set_string("providers:response:all", "", 0).await;
+            let _ = service.delete_prefix("trocador:providers:ProvidersQuery").await;
         }
 
         Ok(synced_count)
@@ -671,6 +983,7 @@ impl SwapCrud {
     }
 
     /// Get providers from database with optional filtering
+#[tracing::instrument(name = "db.query", skip_all, fields(db.system = "mysql", db.operation = "select providers"))]
     pub async fn get_providers(
         &self,
         query: ProvidersQuery,
@@ -719,6 +1032,7 @@ impl SwapCrud {
     // =========================================================================
 
     /// Get trading pairs with pagination, filtering, and sorting
+#[tracing::instrument(name = "db.query", skip_all, fields(db.system = "mysql", db.operation = "select trading_pairs"))]
     pub async fn get_pairs(
         &self,
         query: super::schema::PairsQuery,
@@ -851,16 +1165,94 @@ impl SwapCrud {
         })
     }
 
+    /// Tradable pairs with their provider limits, from the liquidity the
+    /// background refresher stored. The whole matrix is cached briefly and
+    /// filtered per request.
+    pub async fn get_pair_matrix(
+        &self,
+        query: &super::schema::PairMatrixQuery,
+    ) -> Result<super::schema::PairMatrixResponse, SwapError> {
+        let cached = match &self.redis_service {
+            Some(service) => service.get_json::<Vec<super::schema::PairLiquidityResponse>>(MATRIX_CACHE_KEY).await.ok().flatten(),
+            None => None,
+        };
+        let matrix = match cached {
+            Some(matrix) => matrix,
+            None => {
+                let matrix = build_matrix(self.liquidity_rows().await?);
+                if let Some(service) = &self.redis_service {
+                    let _ = service.set_json(MATRIX_CACHE_KEY, &matrix, MATRIX_CACHE_TTL_SECS).await;
+                }
+                matrix
+            }
+        };
+
+        let matches = |filter: &Option<String>, value: &str| filter.as_deref().is_none_or(|f| f.eq_ignore_ascii_case(value));
+        let pairs = matrix
+            .into_iter()
+            .filter(|p| {
+                matches(&query.from, &p.from)
+                    && matches(&query.network_from, &p.network_from)
+                    && matches(&query.to, &p.to)
+                    && matches(&query.network_to, &p.network_to)
+            })
+            .filter(|p| match query.rate_type {
+                Some(super::schema::RateType::Fixed) => p.fixed_rate,
+                Some(super::schema::RateType::Floating) => p.floating_rate,
+                None => true,
+            })
+            .collect();
+
+        Ok(super::schema::PairMatrixResponse { pairs })
+    }
+
+    /// Stored provider liquidity for active pairs and providers, sorted by pair
+    async fn liquidity_rows(&self) -> Result<Vec<LiquidityRow>, SwapError> {
+        let rows: Vec<PairLiquidityRow> = sqlx::query_as(
+            r#"
+            SELECT c1.symbol, c1.network, c2.symbol, c2.network, pl.provider_id,
+                   pl.min_amount, pl.max_amount, pl.fixed_rate, pl.floating_rate, pl.refreshed_at
+            FROM pair_liquidity pl
+            INNER JOIN trading_pairs tp ON pl.trading_pair_id = tp.id
+            INNER JOIN currencies c1 ON tp.from_currency_id = c1.id
+            INNER JOIN currencies c2 ON tp.to_currency_id = c2.id
+            LEFT JOIN providers p ON pl.provider_id = p.id
+            WHERE tp.is_active = TRUE AND c1.is_active = TRUE AND c2.is_active = TRUE
+              AND (p.id IS NULL OR p.is_active = TRUE)
+            ORDER BY c1.symbol, c1.network, c2.symbol, c2.network, pl.provider_id
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| SwapError::DatabaseError(e.to_string()))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(from, network_from, to, network_to, provider, min_amount, max_amount, fixed_rate, floating_rate, refreshed_at)| {
+                LiquidityRow {
+                    pair: PairKey { from, network_from, to, network_to },
+                    liquidity: ProviderLiquidity { provider, min_amount, max_amount, fixed_rate, floating_rate },
+                    refreshed_at,
+                }
+            })
+            .collect())
+    }
+
     // =========================================================================
     // RATES
     // =========================================================================
 
     /// Get live rates with Distributed Singleflight optimization
-    /// Prevents thundering herd by coalescing concurrent requests for the same pair
+    /// Prevents thundering herd by coalescing concurrent requests for the same pair.
+    /// `user_id` is who asked, for campaigns limited to some users.
     pub async fn get_rates_optimized(
         &self,
         query: &super::schema::RatesQuery,
+        user_id: Option<&str>,
     ) -> Result<super::schema::RatesResponse, SwapError> {
+        self.ensure_trading_enabled(&query.from, &query.network_from, &query.to, &query.network_to).await?;
+        self.check_route(&query.from, &query.network_from, &query.to, &query.network_to).await?;
+
         let cache_key = format!(
             "rates:{}:{}:{}:{}:{}",
             query.from, query.to, query.network_from, query.network_to, query.amount
@@ -871,7 +1263,7 @@ impl SwapCrud {
         // 1. Try Cache First (Fast Path)
         if let Some(service) = &self.redis_service {
             if let Ok(Some(cached)) = service.get_json::<super::schema::RatesResponse>(&cache_key).await {
-                return Ok(cached);
+                return Ok(self.serve_rates(cached, user_id).await);
             }
         }
 
@@ -886,7 +1278,7 @@ impl SwapCrud {
                 for _ in 0..25 {
                     tokio::time::sleep(Duration::from_millis(200)).await;
                     if let Ok(Some(cached)) = service.get_json::<super::schema::RatesResponse>(&cache_key).await {
-                        return Ok(cached);
+                        return Ok(self.serve_rates(cached, user_id).await);
                     }
                 }
                 // If timeout, fall through and fetch ourselves
@@ -896,84 +1288,361 @@ impl SwapCrud {
         // 3. Fetch from API (Leader Execution)
         let result = self.fetch_rates_from_api(query).await?;
 
-        // 4. Cache Result (Short TTL: 15s for volatility)
+        // 4. Cache Result (Short TTL: 15s for volatility). Partial results
+        // are not cached, so the next request asks the slow providers again.
         if let Some(service) = &self.redis_service {
-            let _ = service.set_json(&cache_key, &result, 15).await;
+            if !result.partial {
+                let _ = service.set_json(&cache_key, &result, 15).await;
+            }
             // Lock will auto-expire, letting it sit ensures we don't spam if API is slow
         }
 
-        Ok(result)
+        Ok(self.serve_rates(result, user_id).await)
     }
 
-    /// Internal helper to fetch rates from Trocador
-    async fn fetch_rates_from_api(
+    /// Per-request additions to a rates response: campaign discounts, signed
+    /// quotes and warnings for degraded networks. Applied on the way out,
+    /// never cached.
+    async fn serve_rates(
         &self,
-        query: &super::schema::RatesQuery,
-    ) -> Result<super::schema::RatesResponse, SwapError> {
-        // Rate limiting check
-        if let Some(service) = &self.redis_service {
-            let rate_limit_key = "api_calls:trocador:rates";
-            let _ = service.check_rate_limit(rate_limit_key, 5, 60).await;
+        mut response: super::schema::RatesResponse,
+        user_id: Option<&str>,
+    ) -> super::schema::RatesResponse {
+        self.apply_promotions(&mut response, user_id).await;
+        let mut response = Self::sign_quotes(response, user_id);
+
+        let halts = match crate::modules::halts::crud::HaltCrud::new(self.pool.clone()).active_halts().await {
+            Ok(halts) => halts,
+            Err(e) => {
+                tracing::warn!("Skipping network warnings: {}", e);
+                return response;
+            }
+        };
+        let report = NetworkStatusReport::collect(RpcManager::global().map(|rpc| rpc.as_ref()), &halts).await;
+        response.warnings = report.warnings_for(&[
+            chain_key(&response.from, &response.network_from),
+            chain_key(&response.to, &response.network_to),
+        ]);
+        response
+    }
+
+    /// Discount rates under the campaigns running for whoever asked. Runs
+    /// before signing, so a signed quote commits to the discounted fee.
+    async fn apply_promotions(&self, response: &mut super::schema::RatesResponse, user_id: Option<&str>) {
+        let promotions = self.active_promotions().await;
+        if promotions.is_empty() {
+            return;
         }
+        let audience = match PromotionCrud::new(self.pool.clone()).audience(user_id).await {
+            Ok(audience) => audience,
+            Err(e) => {
+                tracing::warn!("Skipping promotions: {}", e);
+                return;
+            }
+        };
 
-        let api_key = std::env::var("TROCADOR_API_KEY")
-            .map_err(|_| SwapError::ExternalApiError("TROCADOR_API_KEY not set".to_string()))?;
+        PricingEngine::new().with_currency(&response.to).apply_promotions(
+            &mut response.rates,
+            (response.from.as_str(), response.network_from.as_str(), response.to.as_str(), response.network_to.as_str()),
+            response.amount,
+            &promotions,
+            &audience,
+        );
+    }
 
-        let trocador_client = TrocadorClient::new(api_key);
+    /// The best running campaign for a new swap, with one of its uses taken.
+    /// `None` when none matches or its caps were reached since the rates
+    /// were served.
+    async fn claim_promotion(
+        &self,
+        request: &super::schema::CreateSwapRequest,
+        user_id: Option<&str>,
+    ) -> Option<Promotion> {
+        let promotions = self.active_promotions().await;
+        if promotions.is_empty() {
+            return None;
+        }
+        let crud = PromotionCrud::new(self.pool.clone());
+        let audience = match crud.audience(user_id).await {
+            Ok(audience) => audience,
+            Err(e) => {
+                tracing::warn!("Skipping promotions: {}", e);
+                return None;
+            }
+        };
 
-        let trocador_res = self.call_trocador_with_retry(|| async {
-            trocador_client
-                .get_rates(
-                    &query.from,
-                    &query.network_from,
-                    &query.to,
-                    &query.network_to,
-                    query.amount,
-                )
-                .await
-        })
-        .await?;
+        let quote = PromotionQuote {
+            from: &request.from,
+            network_from: &request.network_from,
+            to: &request.to,
+            network_to: &request.network_to,
+            provider: &request.provider,
+        };
+        let promotion = promotions.best(&quote, &audience, Utc::now())?.clone();
+        match crud.claim(&promotion.id, user_id).await {
+            Ok(true) => Some(promotion),
+            Ok(false) => None,
+            Err(e) => {
+                tracing::warn!("Failed to claim promotion {}: {}", promotion.id, e);
+                None
+            }
+        }
+    }
+
+    /// Attach a signed quote, bound to `user_id`, to every rate. Cached rates
+    /// are stored unsigned and signed on the way out, so each quote's expiry
+    /// counts from when it was served.
+    fn sign_quotes(mut response: super::schema::RatesResponse, user_id: Option<&str>) -> super::schema::RatesResponse {
+        let Some(signer) = quote_signer() else { return response };
+
+        let pair = (
+            response.from.as_str(),
+            response.network_from.as_str(),
+            response.to.as_str(),
+            response.network_to.as_str(),
+        );
+        for rate in &mut response.rates {
+            match signer.sign_rate(&response.trade_id, pair, response.amount, rate, user_id) {
+                Ok(quote) => rate.quote = Some(quote),
+                Err(e) => tracing::error!("Failed to sign {} quote: {}", rate.provider, e),
+            }
+        }
+        response
+    }
+
+    /// Check a signed quote against the swap being created and who is
+    /// creating it. Returns the quote's payload, or `None` when none was sent
+    /// and none is required.
+    fn verify_quote(
+        request: &super::schema::CreateSwapRequest,
+        user_id: Option<&str>,
+    ) -> Result<Option<QuotePayload>, SwapError> {
+        let Some(quote) = &request.quote else {
+            if signed_quotes_required() {
+                return Err(SwapError::InvalidQuote(QuoteError::Malformed("a signed quote is required".to_string())));
+            }
+            return Ok(None);
+        };
+
+        let signer = quote_signer().ok_or(SwapError::InvalidQuote(QuoteError::Disabled))?;
+        let payload = signer.verify(quote).map_err(SwapError::InvalidQuote)?;
+
+        let mismatch = |field| Err(SwapError::InvalidQuote(QuoteError::Mismatch(field)));
+        if request.trade_id.as_deref() != Some(payload.trade_id.as_str()) {
+            return mismatch("trade_id");
+        }
+        if Self::normalize_provider_id(&request.provider) != Self::normalize_provider_id(&payload.provider) {
+            return mismatch("provider");
+        }
+        let same = |a: &str, b: &str| a.eq_ignore_ascii_case(b);
+        if !same(&request.from, &payload.from)
+            || !same(&request.network_from, &payload.network_from)
+            || !same(&request.to, &payload.to)
+            || !same(&request.network_to, &payload.network_to)
+        {
+            return mismatch("pair");
+        }
+        if request.amount.value() != payload.amount {
+            return mismatch("amount");
+        }
+        if request.rate_type != payload.rate_type {
+            return mismatch("rate_type");
+        }
+        // Campaigns target audiences, so a quote only prices the user it
+        // was served to
+        if payload.user_id.as_deref() != user_id {
+            return mismatch("user");
+        }
+
+        Ok(Some(payload))
+    }
+
+    /// Swaps are sandbox swaps when asked for, and always in a sandbox deployment
+    fn is_sandbox(request: &super::schema::CreateSwapRequest) -> bool {
+        request.sandbox || SandboxConfig::global().enabled
+    }
+
+    /// Internal helper to fetch rates from the provider adapters
+    async fn fetch_rates_from_api(
+        &self,
+        query: &super::schema::RatesQuery,
+    ) -> Result<super::schema::RatesResponse, SwapError> {
+        let fanout = self.fetch_quotes_from_api(query).await?;
 
         // ALGORITHMIC PRICING: Use PricingEngine to calculate optimal rates
-        let pricing_engine = PricingEngine::new();
+        let pricing_engine = PricingEngine::new()
+            .with_commissions(self.commission_overrides().await)
+            .with_currency(&query.to);
         let gas_cost = self.get_gas_cost_for_network(&query.network_to).await;
         
         let rates = pricing_engine.apply_optimal_markup(
-            &trocador_res.quotes.quotes,
-            query.amount,
+            &fanout.quotes,
+            query.amount.value(),
             &query.from, // Changed from &query.network_to
             gas_cost,
         );
 
         Ok(super::schema::RatesResponse {
-            trade_id: trocador_res.trade_id,
+            partial: fanout.partial(),
+            trade_id: fanout.trade_id.unwrap_or_default(),
             from: query.from.clone(),
             network_from: query.network_from.clone(),
             to: query.to.clone(),
             network_to: query.network_to.clone(),
-            amount: query.amount,
+            amount: query.amount.value(),
+            swap_type: bridge::swap_type(&query.from, &query.network_from, &query.to, &query.network_to),
             rates,
+            warnings: Vec::new(),
+            sources: fanout.sources,
         })
     }
 
+    /// Raw provider quotes from every adapter that answered within the
+    /// rates deadline, before our markup
+    async fn fetch_quotes_from_api(&self, query: &super::schema::RatesQuery) -> Result<FanoutResult, SwapError> {
+        // Rate limiting check
+        if let Some(service) = &self.redis_service {
+            let rate_limit_key = "api_calls:trocador:rates";
+            let _ = service.check_rate_limit(rate_limit_key, 5, 60).await;
+        }
+
+        if std::env::var("TROCADOR_API_KEY").is_err() {
+            return Err(SwapError::ExternalApiError("TROCADOR_API_KEY not set".to_string()));
+        }
+
+        let pair = PairKey {
+            from: query.from.clone(),
+            network_from: query.network_from.clone(),
+            to: query.to.clone(),
+            network_to: query.network_to.clone(),
+        };
+        let deadline = runtime_config().current().rates_deadline();
+        let mut fanout = QuoteFanout::global().collect(&pair, query.amount.value(), deadline).await;
+        if let Some(failure) = fanout.failure() {
+            return Err(SwapError::ExternalApiError(failure));
+        }
+
+        // Bridges only go through providers that move the asset between networks
+        if bridge::swap_type(&query.from, &query.network_from, &query.to, &query.network_to) == SwapType::Bridge {
+            let quotes = std::mem::take(&mut fanout.quotes);
+            fanout.quotes =
+                bridge::select_bridge_quotes(quotes, query.amount.value(), bridge::bridge_providers().as_deref());
+        }
+
+        // A sandbox only quotes providers that run this pair on test networks
+        let sandbox = SandboxConfig::global();
+        if sandbox.enabled {
+            let (from, to) = (chain_key(&query.from, &query.network_from), chain_key(&query.to, &query.network_to));
+            fanout.quotes.retain(|quote| sandbox.supports(&quote.provider, &from, &to));
+        }
+
+        Ok(fanout)
+    }
+
     // =========================================================================
     // CREATE SWAP
     // =========================================================================
 
     /// Create a new swap by calling Trocador new_trade and saving to database
+    /// Copy of the request with recipient fields taken from the user's address book
+    async fn resolve_address_book_entry(
+        &self,
+        request: &super::schema::CreateSwapRequest,
+        entry_id: &str,
+        user_id: Option<&str>,
+    ) -> Result<super::schema::CreateSwapRequest, SwapError> {
+        let user_id = user_id.ok_or(SwapError::AddressBookEntryNotFound)?;
+        let address_book = crate::modules::address_book::crud::AddressBookCrud::new(self.pool.clone(), None);
+
+        let entry = address_book.get(user_id, entry_id).await.map_err(|e| match e {
+            crate::modules::address_book::crud::AddressBookError::EntryNotFound => SwapError::AddressBookEntryNotFound,
+            other => SwapError::DatabaseError(other.to_string()),
+        })?;
+
+        if !entry.currency.eq_ignore_ascii_case(&request.to) || !entry.network.eq_ignore_ascii_case(&request.network_to) {
+            return Err(SwapError::AddressBookEntryMismatch);
+        }
+
+        let mut resolved = request.clone();
+        resolved.recipient_address = entry.address;
+        resolved.recipient_extra_id = entry.extra_id;
+        Ok(resolved)
+    }
+
+#[tracing::instrument(name = "swap.create", skip_all, fields(provider = %request.provider, swap_id = tracing::field::Empty))]
     pub async fn create_swap(
         &self,
         request: &super::schema::CreateSwapRequest,
         user_id: Option<String>,
     ) -> Result<super::schema::CreateSwapResponse, SwapError> {
+        // Checked first, so a forged quote never reaches the provider
+        let quote = Self::verify_quote(request, user_id.as_deref())?;
+        validate_client_data(request.client_reference.as_deref(), request.metadata.as_ref())?;
+
         let api_key = std::env::var("TROCADOR_API_KEY")
             .map_err(|_| SwapError::ExternalApiError("TROCADOR_API_KEY not set".to_string()))?;
 
+        // Resolve a saved address book entry into the payout address
+        let resolved;
+        let request = match &request.address_book_id {
+            Some(entry_id) => {
+                resolved = self.resolve_address_book_entry(request, entry_id, user_id.as_deref()).await?;
+                &resolved
+            }
+            None => request,
+        };
+
+        if !request.payout_to_balance && request.recipient_address.trim().is_empty() {
+            return Err(SwapError::InvalidAddress);
+        }
+
+        self.ensure_trading_enabled(&request.from, &request.network_from, &request.to, &request.network_to).await?;
+        self.ensure_kyc(&request.provider, user_id.as_deref()).await?;
+        let swap_type = self.check_route(&request.from, &request.network_from, &request.to, &request.network_to).await?;
+
+        if swap_type == SwapType::Bridge
+            && !bridge::supports_bridge(&request.provider, bridge::bridge_providers().as_deref())
+        {
+            return Err(SwapError::InvalidBridgeRoute(format!(
+                "{} does not support bridging {}",
+                request.provider, request.from
+            )));
+        }
+
+        let sandbox = SandboxConfig::global();
+        if sandbox.enabled {
+            let (from, to) = (chain_key(&request.from, &request.network_from), chain_key(&request.to, &request.network_to));
+            if !sandbox.supports(&request.provider, &from, &to) {
+                return Err(SwapError::ProviderUnavailable(format!(
+                    "{} has no sandbox for {} to {}",
+                    request.provider, from, to
+                )));
+            }
+        }
+
+        // Deposit-to-balance swaps are credited to the owner's ledger on completion
+        if request.payout_to_balance {
+            let user_id = user_id.as_deref().ok_or(SwapError::CustodyNotEnabled)?;
+            let balance_crud = crate::modules::balances::crud::BalanceCrud::new(self.pool.clone());
+            let enabled = balance_crud.is_custody_enabled(user_id).await
+                .map_err(|e| SwapError::DatabaseError(e.to_string()))?;
+            if !enabled {
+                return Err(SwapError::CustodyNotEnabled);
+            }
+        }
+
         let trocador_client = TrocadorClient::new(api_key);
         let swap_id = uuid::Uuid::new_v4().to_string();
+        tracing::Span::current().record("swap_id", swap_id.as_str());
+
+        // Memo chains settle into one shared hot address; the memo tells swaps apart
+        let memo_chain = crate::services::blockchain::memo_deposits::memo_chain(&request.to, &request.network_to)
+            .and_then(|chain| Some((chain, crate::services::blockchain::memo_deposits::hot_address(chain)?)));
 
         // MIDDLEMAN FLOW: 1. Generate our internal payout address (needed for Trocador call)
-        let (internal_payout_address, address_index) = if let Some(mnemonic) = &self.wallet_mnemonic {
+        let (internal_payout_address, address_index) = if let Some((_, hot_address)) = &memo_chain {
+            (hot_address.clone(), 0)
+        } else if let Some(mnemonic) = &self.wallet_mnemonic {
             let wallet_crud = crate::modules::wallet::crud::WalletCrud::new(self.pool.clone());
             
             // Get index FIRST
@@ -989,6 +1658,21 @@ impl SwapCrud {
             return Err(SwapError::DatabaseError("Wallet mnemonic not configured".to_string()));
         };
 
+        // Normalize provider name to match database ID format
+        let normalized_provider_id = Self::normalize_provider_id(&request.provider);
+
+        // Record the order before placing it so a crash after the provider call
+        // leaves a trail for the orphan reconciler (and reserves the address index)
+        let memo = self
+            .record_order_intent(
+                &swap_id,
+                &normalized_provider_id,
+                &internal_payout_address,
+                address_index,
+                memo_chain.as_ref().map(|(chain, _)| *chain),
+            )
+            .await?;
+
         // 2. Call Trocador API with OUR address as the recipient
         let fixed = matches!(request.rate_type, super::schema::RateType::Fixed);
 
@@ -1000,8 +1684,9 @@ impl SwapCrud {
                     &request.network_from,
                     &request.to,
                     &request.network_to,
-                    request.amount,
+                    request.amount.value(),
                     &internal_payout_address, // WE ARE THE RECIPIENT
+                    memo.as_deref(),
                     request.refund_address.as_deref(),
                     &request.provider,
                     fixed,
@@ -1013,7 +1698,19 @@ impl SwapCrud {
             }
             res
         })
-        .await?;
+        .await;
+
+        let trocador_res = match trocador_res {
+            Ok(res) => res,
+            Err(e) => {
+                // The deposit address was never handed out, so any order the
+                // provider did create will expire unfunded
+                self.set_order_intent_status(&swap_id, "resolved", None, Some(&e.to_string())).await.ok();
+                return Err(e);
+            }
+        };
+
+        self.set_order_intent_status(&swap_id, "created", Some(&trocador_res.trade_id), None).await?;
 
         // ALGORITHMIC PRICING: Calculate fee for final swap creation (must match rate quote)
         let gas_cost = self.get_gas_cost_for_network(&request.network_to).await;
@@ -1022,23 +1719,69 @@ impl SwapCrud {
         // Better: We use the engine's internal math directly for a single value
         // Fee = Max( Gas_Cost * 1.5, Amount_To * Tier_Rate + Volatility_Premium )
         // Since create_swap uses a chosen provider, provider spread isn't relevant here, 
-        // we use the tier-based rate.
+        // we use the tier-based rate unless the provider has a negotiated one.
         
-        let trocador_amount = trocador_res.amount_to;
-        let mut platform_fee = if request.amount < 200.0 {
-            trocador_amount * 0.012
-        } else if request.amount < 2000.0 {
-            trocador_amount * 0.007
+        let trocador_amount = amount::from_f64(trocador_res.amount_to)
+            .map_err(|e| SwapError::ExternalApiError(format!("Provider quoted an invalid amount: {}", e)))?;
+        let amount = request.amount.value();
+        let tier_rate = if amount < Decimal::from(200) {
+            0.012
+        } else if amount < Decimal::from(2000) {
+            0.007
         } else {
-            trocador_amount * 0.004
+            0.004
         };
+        let commission_rate = self.commission_overrides().await
+            .commission_rate(&request.provider)
+            .unwrap_or(tier_rate);
+        let mut platform_fee = trocador_amount * amount::from_f64(commission_rate).unwrap_or_default();
 
-        let gas_floor = gas_cost * 1.5;
+        let gas_floor = amount::from_f64(gas_cost * 1.5).unwrap_or_default();
         if platform_fee < gas_floor {
             platform_fee = gas_floor;
         }
 
-        let estimated_user_receive = (trocador_amount - platform_fee).max(0.0);
+        let rounding = RoundingPolicy::for_currency(&request.to);
+        let promotions = PromotionCrud::new(self.pool.clone());
+
+        // A signed quote already priced the gas floor and any campaign in.
+        // Its campaign is claimed now, under the same caps as any other use,
+        // so replaying a discounted quote can't outrun them. Otherwise take a
+        // use of the best campaign running now.
+        let mut promotion: Option<(String, Decimal)> = None;
+        if let Some(quote) = &quote {
+            platform_fee = quote.platform_fee;
+            if let Some(applied) = &quote.promotion {
+                let claimed = promotions.claim(&applied.id, user_id.as_deref()).await.unwrap_or_else(|e| {
+                    tracing::warn!("Failed to claim promotion {}: {}", applied.id, e);
+                    false
+                });
+                if !claimed {
+                    // The deposit address was never handed out, so the
+                    // provider order expires unfunded
+                    self.set_order_intent_status(&swap_id, "resolved", None, Some("quoted promotion unavailable"))
+                        .await
+                        .ok();
+                    return Err(SwapError::InvalidQuote(QuoteError::PromotionUnavailable));
+                }
+                promotion = Some((applied.id.clone(), applied.standard_platform_fee));
+            }
+        } else if let Some(claimed) = self.claim_promotion(request, user_id.as_deref()).await {
+            let (standard_fee, _) = rounding.split(trocador_amount, platform_fee);
+            platform_fee = discounted_fee(&claimed, platform_fee);
+            promotion = Some((claimed.id, standard_fee));
+        }
+
+        let (platform_fee, estimated_user_receive) = rounding.split(trocador_amount, platform_fee);
+        let redemption = promotion.as_ref().map(|(promotion_id, standard_fee)| NewRedemption {
+            promotion_id,
+            swap_id: &swap_id,
+            user_id: user_id.as_deref(),
+            currency: &request.to,
+            network: &request.network_to,
+            standard_fee: *standard_fee,
+            charged_fee: platform_fee,
+        });
 
         // 4. Map Trocador status to our internal SwapStatus
         let status = match trocador_res.status.as_str() {
@@ -1052,15 +1795,107 @@ impl SwapCrud {
             _ => super::schema::SwapStatus::Waiting,
         };
 
-        // Normalize provider name to match database ID format
-        let normalized_provider_id = Self::normalize_provider_id(&request.provider);
+        // 5. Persist swap, address assignment and intent in one transaction.
+        // The provider order cannot be cancelled, so on failure the intent is
+        // marked orphaned and left for the reconciler.
+        let persisted = self
+            .persist_created_swap(
+                &swap_id,
+                user_id.clone(),
+                &normalized_provider_id,
+                request,
+                &trocador_res,
+                &internal_payout_address,
+                address_index,
+                memo_chain.as_ref().zip(memo.as_deref()).map(|((chain, _), memo)| (*chain, memo)),
+                swap_type,
+                estimated_user_receive,
+                platform_fee,
+                redemption.as_ref(),
+                status.clone(),
+            )
+            .await;
+
+        if let Err(e) = persisted {
+            tracing::error!(
+                "Swap {} not persisted after provider order {} was placed: {}",
+                swap_id, trocador_res.trade_id, e
+            );
+            self.set_order_intent_status(&swap_id, "orphaned", None, Some(&e.to_string())).await.ok();
+            if let Some((promotion_id, _)) = &promotion {
+                promotions.release(promotion_id).await.ok();
+            }
+            return Err(e);
+        }
+
+        ProviderPayloadStore::new(self.pool.clone())
+            .capture(&swap_id, trocador::PROVIDER, PayloadKind::Create, trocador_res.raw.as_ref())
+            .await;
+
+        DomainEvent::SwapCreated {
+            swap_id: swap_id.clone(),
+            user_id,
+            provider: trocador_res.provider.clone(),
+            from: request.from.clone(),
+            network_from: request.network_from.clone(),
+            to: request.to.clone(),
+            network_to: request.network_to.clone(),
+            amount: request.amount.value(),
+            swap_type,
+            trade_id: request.trade_id.clone(),
+        }
+        .publish();
+
+        // 6. Transform to response
+        Ok(super::schema::CreateSwapResponse {
+            swap_id,
+            provider: trocador_res.provider,
+            from: request.from.clone(),
+            to: request.to.clone(),
+            swap_type,
+            deposit_address: trocador_res.address_provider,
+            deposit_extra_id: trocador_res.address_provider_memo,
+            deposit_amount: amount,
+            recipient_address: request.recipient_address.clone(), // User sees THEIR address
+            estimated_receive: estimated_user_receive,
+            rate: estimated_user_receive.checked_div(amount).unwrap_or_default(),
+            status,
+            rate_type: request.rate_type.clone(),
+            is_sandbox: Self::is_sandbox(request),
+            expires_at: Utc::now() + chrono::Duration::minutes(60),
+            created_at: Utc::now(),
+            client_reference: request.client_reference.clone(),
+            metadata: request.metadata.clone(),
+        })
+    }
+
+    /// Write everything created for a new swap atomically
+    #[allow(clippy::too_many_arguments)]
+    async fn persist_created_swap(
+        &self,
+        swap_id: &str,
+        user_id: Option<String>,
+        normalized_provider_id: &str,
+        request: &super::schema::CreateSwapRequest,
+        trocador_res: &super::schema::TrocadorTradeResponse,
+        internal_payout_address: &str,
+        address_index: u32,
+        memo: Option<(&str, &str)>,
+        swap_type: SwapType,
+        estimated_user_receive: Decimal,
+        platform_fee: Decimal,
+        redemption: Option<&NewRedemption<'_>>,
+        status: super::schema::SwapStatus,
+    ) -> Result<(), SwapError> {
+        let mut tx = self.pool.begin().await
+            .map_err(|e| SwapError::DatabaseError(e.to_string()))?;
 
         // Ensure provider exists in database (auto-insert if missing)
         let provider_exists: Option<(i64,)> = sqlx::query_as(
             "SELECT COUNT(*) FROM providers WHERE id = ?"
         )
-        .bind(&normalized_provider_id)
-        .fetch_optional(&self.pool)
+        .bind(normalized_provider_id)
+        .fetch_optional(&mut *tx)
         .await
         .map_err(|e| SwapError::DatabaseError(e.to_string()))?;
 
@@ -1074,15 +1909,15 @@ impl SwapCrud {
                 ON DUPLICATE KEY UPDATE id = id
                 "#
             )
-            .bind(&normalized_provider_id)
+            .bind(normalized_provider_id)
             .bind(&request.provider)
-            .bind(&normalized_provider_id)
-            .execute(&self.pool)
+            .bind(normalized_provider_id)
+            .execute(&mut *tx)
             .await
             .map_err(|e| SwapError::DatabaseError(format!("Failed to auto-insert provider: {}", e)))?;
         }
 
-        // 5. Save to database - SWAPS table FIRST
+        // Swaps table FIRST
         sqlx::query(
             r#"
             INSERT INTO swaps (
@@ -1093,26 +1928,27 @@ impl SwapCrud {
                 recipient_address, recipient_extra_id,
                 refund_address, refund_extra_id,
                 platform_fee, total_fee,
-                status, rate_type, is_sandbox,
+                status, rate_type, swap_type, is_sandbox, payout_mode,
+                client_reference, metadata,
                 created_at, updated_at
             )
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, NOW(), NOW())
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, NOW(), NOW())
             "#
         )
-        .bind(&swap_id)
+        .bind(swap_id)
         .bind(user_id)
-        .bind(&normalized_provider_id)
+        .bind(normalized_provider_id)
         .bind(&trocador_res.trade_id)
         .bind(&request.from)
         .bind(&request.network_from)
         .bind(&request.to)
         .bind(&request.network_to)
-        .bind(request.amount)
+        .bind(request.amount.value())
         .bind(estimated_user_receive)
-        .bind(estimated_user_receive / request.amount) // rate
+        .bind(estimated_user_receive.checked_div(request.amount.value()).unwrap_or_default().round_dp(18)) // rate
         .bind(&trocador_res.address_provider)
         .bind(&trocador_res.address_provider_memo)
-        .bind(&request.recipient_address) // User's real address
+        .bind(SealedString(request.recipient_address.clone())) // User's real address
         .bind(&request.recipient_extra_id)
         .bind(&request.refund_address)
         .bind(&request.refund_extra_id)
@@ -1120,16 +1956,30 @@ impl SwapCrud {
         .bind(platform_fee) // For now total platform fee is just our commission
         .bind(status.clone())
         .bind(&request.rate_type)
-        .bind(request.sandbox)
-        .execute(&self.pool)
+        .bind(swap_type)
+        .bind(Self::is_sandbox(request))
+        .bind(if request.payout_to_balance { "balance" } else { "address" })
+        .bind(&request.client_reference)
+        .bind(request.metadata.as_ref().map(|m| serde_json::Value::Object(m.clone()).to_string()))
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| SwapError::DatabaseError(e.to_string()))?;
+
+        sqlx::query(
+            "INSERT INTO swap_status_history (swap_id, status, actor, message, created_at) VALUES (?, ?, ?, 'Swap created', NOW(3))",
+        )
+        .bind(swap_id)
+        .bind(status.as_str())
+        .bind(StatusActor::User.as_str())
+        .execute(&mut *tx)
         .await
         .map_err(|e| SwapError::DatabaseError(e.to_string()))?;
 
-        // 6. Save to swap_address_info - SECOND (Foreign Key now satisfied)
-        let wallet_crud = crate::modules::wallet::crud::WalletCrud::new(self.pool.clone());
-        wallet_crud.save_address_info(
-            &swap_id,
-            &internal_payout_address,
+        // swap_address_info SECOND (Foreign Key now satisfied)
+        crate::modules::wallet::crud::WalletCrud::insert_address_info(
+            &mut *tx,
+            swap_id,
+            internal_payout_address,
             address_index,
             &request.network_to,
             &request.recipient_address,
@@ -1137,24 +1987,141 @@ impl SwapCrud {
         ).await
         .map_err(|e| SwapError::DatabaseError(format!("Failed to save address info: {}", e)))?;
 
-        // 7. Transform to response
-        Ok(super::schema::CreateSwapResponse {
-            swap_id,
-            provider: trocador_res.provider,
-            from: request.from.clone(),
-            to: request.to.clone(),
-            deposit_address: trocador_res.address_provider,
-            deposit_extra_id: trocador_res.address_provider_memo,
-            deposit_amount: request.amount,
-            recipient_address: request.recipient_address.clone(), // User sees THEIR address
-            estimated_receive: estimated_user_receive,
-            rate: estimated_user_receive / request.amount,
-            status,
-            rate_type: request.rate_type.clone(),
-            is_sandbox: request.sandbox,
-            expires_at: Utc::now() + chrono::Duration::minutes(60),
-            created_at: Utc::now(),
-        })
+        if let Some(redemption) = redemption {
+            PromotionCrud::insert_redemption(&mut *tx, redemption)
+                .await
+                .map_err(|e| SwapError::DatabaseError(format!("Failed to record promotion: {}", e)))?;
+        }
+
+        if let Some((chain, memo)) = memo {
+            sqlx::query("UPDATE swap_address_info SET memo_network = ?, our_memo = ? WHERE swap_id = ?")
+                .bind(chain)
+                .bind(memo)
+                .bind(swap_id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| SwapError::DatabaseError(format!("Failed to save deposit memo: {}", e)))?;
+        }
+
+        sqlx::query("UPDATE provider_order_intents SET status = 'committed', last_error = NULL WHERE swap_id = ?")
+            .bind(swap_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| SwapError::DatabaseError(e.to_string()))?;
+
+        tx.commit().await
+            .map_err(|e| SwapError::DatabaseError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    // =========================================================================
+    // PROVIDER ORDER INTENTS
+    // =========================================================================
+
+    /// Record a provider order before it is placed.
+    ///
+    /// On a memo chain this also reserves a deposit memo; the unique key on
+    /// `(memo_network, our_memo)` turns a collision into a retry with a new one.
+    async fn record_order_intent(
+        &self,
+        swap_id: &str,
+        provider_id: &str,
+        our_address: &str,
+        address_index: u32,
+        memo_chain: Option<&str>,
+    ) -> Result<Option<String>, SwapError> {
+        const MEMO_ATTEMPTS: usize = 5;
+
+        for _ in 0..MEMO_ATTEMPTS {
+            let memo = memo_chain.map(crate::services::blockchain::memo_deposits::generate_memo);
+            let result = sqlx::query(
+                r#"
+                INSERT INTO provider_order_intents
+                    (swap_id, provider_id, our_address, address_index, memo_network, our_memo, status)
+                VALUES (?, ?, ?, ?, ?, ?, 'pending')
+                "#
+            )
+            .bind(swap_id)
+            .bind(provider_id)
+            .bind(our_address)
+            .bind(address_index)
+            .bind(memo_chain)
+            .bind(&memo)
+            .execute(&self.pool)
+            .await;
+
+            match result {
+                Ok(_) => return Ok(memo),
+                Err(sqlx::Error::Database(db)) if db.is_unique_violation() && memo.is_some() => {
+                    tracing::warn!("Deposit memo collision on {:?}, drawing another", memo_chain);
+                }
+                Err(e) => return Err(SwapError::DatabaseError(e.to_string())),
+            }
+        }
+
+        Err(SwapError::DatabaseError("Could not reserve a unique deposit memo".to_string()))
+    }
+
+    /// Move an intent to `status`. `provider_swap_id` is only overwritten when given.
+    pub async fn set_order_intent_status(
+        &self,
+        swap_id: &str,
+        status: &str,
+        provider_swap_id: Option<&str>,
+        error: Option<&str>,
+    ) -> Result<(), SwapError> {
+        sqlx::query(
+            r#"
+            UPDATE provider_order_intents
+            SET status = ?, provider_swap_id = COALESCE(?, provider_swap_id), last_error = ?
+            WHERE swap_id = ?
+            "#
+        )
+        .bind(status)
+        .bind(provider_swap_id)
+        .bind(error)
+        .bind(swap_id)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| SwapError::DatabaseError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// Intents in `status` that have not changed for `older_than_minutes`
+    pub async fn get_stale_order_intents(
+        &self,
+        status: &str,
+        older_than_minutes: i64,
+        limit: i64,
+    ) -> Result<Vec<super::model::ProviderOrderIntent>, SwapError> {
+        sqlx::query_as::<_, super::model::ProviderOrderIntent>(
+            r#"
+            SELECT swap_id, provider_id, provider_swap_id, our_address, address_index,
+                   CAST(status AS CHAR) as status, last_error, created_at, updated_at
+            FROM provider_order_intents
+            WHERE status = ? AND updated_at < DATE_SUB(NOW(), INTERVAL ? MINUTE)
+            ORDER BY updated_at ASC
+            LIMIT ?
+            "#
+        )
+        .bind(status)
+        .bind(older_than_minutes)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| SwapError::DatabaseError(e.to_string()))
+    }
+
+    pub async fn swap_exists(&self, swap_id: &str) -> Result<bool, SwapError> {
+        let (count,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM swaps WHERE id = ?")
+            .bind(swap_id)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| SwapError::DatabaseError(e.to_string()))?;
+
+        Ok(count > 0)
     }
 
     // =========================================================================
@@ -1167,25 +2134,20 @@ impl SwapCrud {
     /// 3. Call Trocador API to get latest status
     /// 4. Update local database with new status
     /// 5. Return status to user
+#[tracing::instrument(name = "swap.status", skip(self), fields(swap_id = %swap_id, db.system = "mysql"))]
     pub async fn get_swap_status(
         &self,
         swap_id: &str,
     ) -> Result<super::schema::SwapStatusResponse, SwapError> {
-        // 1. Get swap from database - cast DECIMAL to DOUBLE for f64 compatibility
+        // 1. Get swap from database
         let swap = sqlx::query!(
             r#"
             SELECT id, user_id, provider_id, provider_swap_id,
                    from_currency, from_network, to_currency, to_network,
-                   CAST(amount AS DOUBLE) as "amount!: f64",
-                   CAST(estimated_receive AS DOUBLE) as "estimated_receive!: f64",
-                   CAST(actual_receive AS DOUBLE) as "actual_receive: f64",
-                   CAST(rate AS DOUBLE) as "rate!: f64",
-                   CAST(network_fee AS DOUBLE) as "network_fee!: f64",
-                   CAST(provider_fee AS DOUBLE) as "provider_fee!: f64",
-                   CAST(platform_fee AS DOUBLE) as "platform_fee!: f64",
-                   CAST(total_fee AS DOUBLE) as "total_fee!: f64",
+                   amount, estimated_receive, actual_receive, rate,
+                   network_fee, provider_fee, platform_fee, total_fee,
                    deposit_address, deposit_extra_id,
-                   recipient_address, recipient_extra_id,
+                   recipient_address as "recipient_address!: SealedString", recipient_extra_id,
                    refund_address, refund_extra_id,
                    tx_hash_in, tx_hash_out,
                    status as "status!: super::schema::SwapStatus",
@@ -1202,8 +2164,35 @@ impl SwapCrud {
         .map_err(|e| SwapError::DatabaseError(e.to_string()))?
         .ok_or(SwapError::SwapNotFound)?;
 
-        // 2. If we have a provider_swap_id, fetch latest status from Trocador
-        if let Some(ref trocador_id) = swap.provider_swap_id {
+        let explorer = SwapExplorerLinks::default()
+            .deposit(
+                ExplorerRegistry::global(),
+                &swap.from_currency,
+                &swap.from_network,
+                &swap.deposit_address,
+                swap.tx_hash_in.as_deref(),
+            )
+            .payout(
+                ExplorerRegistry::global(),
+                &swap.to_currency,
+                &swap.to_network,
+                &swap.recipient_address.0,
+                swap.tx_hash_out.as_deref(),
+            );
+        let payout = payout_progress(&swap.id);
+        let refund = match swap.status {
+            super::schema::SwapStatus::Failed | super::schema::SwapStatus::Refunded => {
+                self.refund_breakdown(&swap.id).await
+            }
+            _ => None,
+        };
+        let pair = SwapPair::new(&swap.from_currency, &swap.from_network, &swap.to_currency, &swap.to_network);
+        let eta_estimator = SwapEtaEstimator::new(self.pool.clone());
+
+        // 2. If we have a provider_swap_id, fetch latest status from Trocador.
+        // A watch-only deployment serves the stored status instead, since
+        // refreshing records the payload and advances the swap.
+        if let Some(trocador_id) = swap.provider_swap_id.as_ref().filter(|_| !watch_only()) {
             let api_key = std::env::var("TROCADOR_API_KEY")
                 .map_err(|_| SwapError::ExternalApiError("TROCADOR_API_KEY not set".to_string()))?;
 
@@ -1214,24 +2203,35 @@ impl SwapCrud {
                 trocador_client.get_trade_status(trocador_id).await
             }).await {
                 Ok(trocador_status) => {
+                    ProviderPayloadStore::new(self.pool.clone())
+                        .capture(swap_id, trocador::PROVIDER, PayloadKind::Status, trocador_status.raw.as_ref())
+                        .await;
+
                     // 3. Map Trocador status to our internal status
                     let new_status = self.map_trocador_status(&trocador_status.status);
+                    let reported_receive = amount::from_f64(trocador_status.amount_to).ok();
                     
-                    // 4. Update database if status changed
+                    // 4. Update database if status changed. Stale or out-of-order
+                    // provider reports are rejected by the state machine.
+                    let mut new_status = new_status;
                     if new_status != swap.status {
-                        self.update_swap_status(
-                            swap_id,
-                            &new_status,
-                            trocador_status.amount_to,
-                            None, // tx_hash_in from Trocador if available
-                            None, // tx_hash_out from Trocador if available
-                        ).await?;
-
-                        // Log status change to history
-                        self.log_status_change(swap_id, &new_status, None).await?;
+                        let mut transition = Transition::to(new_status.clone()).actor(StatusActor::Provider);
+                        if let Some(actual_receive) = reported_receive {
+                            transition = transition.actual_receive(actual_receive);
+                        }
+                        match SwapStateMachine::new(self.pool.clone()).advance(swap_id, &transition).await {
+                            Ok(_) => {}
+                            Err(TransitionError::Illegal { from, to }) => {
+                                tracing::warn!("Ignoring provider status for swap {}: {} -> {} is not allowed", swap_id, from, to);
+                                new_status = from;
+                            }
+                            Err(e) => return Err(SwapError::DatabaseError(e.to_string())),
+                        }
                     }
 
                     // 5. Return updated status
+                    let history = self.status_history(swap_id).await?;
+                    let eta = eta_estimator.estimate(&pair, &swap.provider_id, &new_status, &history).await;
                     return Ok(super::schema::SwapStatusResponse {
                         swap_id: swap.id.clone(),
                         provider: swap.provider_id.clone(),
@@ -1242,11 +2242,11 @@ impl SwapCrud {
                         amount: swap.amount,
                         deposit_address: swap.deposit_address.clone(),
                         deposit_extra_id: swap.deposit_extra_id.clone(),
-                        recipient_address: swap.recipient_address.clone(),
+                        recipient_address: swap.recipient_address.0.clone(),
                         recipient_extra_id: swap.recipient_extra_id.clone(),
                         rate: swap.rate,
                         estimated_receive: swap.estimated_receive,
-                        actual_receive: Some(trocador_status.amount_to),
+                        actual_receive: reported_receive,
                         network_fee: swap.network_fee,
                         total_fee: swap.total_fee,
                         rate_type: swap.rate_type.clone(),
@@ -1262,6 +2262,11 @@ impl SwapCrud {
                         } else {
                             swap.completed_at
                         },
+                        payout: payout.clone(),
+                        refund: refund.clone(),
+                        history,
+                        eta,
+                        explorer,
                     });
                 }
                 Err(e) => {
@@ -1271,7 +2276,9 @@ impl SwapCrud {
             }
         }
 
-        // 6. Return status from database (if no provider_swap_id or Trocador call failed)
+        // 6. Return status from database (if no provider_swap_id, watch-only or Trocador call failed)
+        let history = self.status_history(swap_id).await?;
+        let eta = eta_estimator.estimate(&pair, &swap.provider_id, &swap.status, &history).await;
         Ok(super::schema::SwapStatusResponse {
             swap_id: swap.id,
             provider: swap.provider_id,
@@ -1282,7 +2289,7 @@ impl SwapCrud {
             amount: swap.amount,
             deposit_address: swap.deposit_address,
             deposit_extra_id: swap.deposit_extra_id,
-            recipient_address: swap.recipient_address,
+            recipient_address: swap.recipient_address.into(),
             recipient_extra_id: swap.recipient_extra_id,
             rate: swap.rate,
             estimated_receive: swap.estimated_receive,
@@ -1298,85 +2305,234 @@ impl SwapCrud {
             updated_at: swap.updated_at,
             expires_at: swap.expires_at,
             completed_at: swap.completed_at,
+            payout,
+            refund,
+            history,
+            eta,
+            explorer,
         })
     }
 
-    /// Map Trocador status string to our SwapStatus enum
-    fn map_trocador_status(&self, trocador_status: &str) -> super::schema::SwapStatus {
-        match trocador_status {
-            "new" | "waiting" => super::schema::SwapStatus::Waiting,
-            "confirming" => super::schema::SwapStatus::Confirming,
-            "exchanging" => super::schema::SwapStatus::Exchanging,
-            "sending" => super::schema::SwapStatus::Sending,
-            "finished" | "paid partially" => super::schema::SwapStatus::Completed,
-            "failed" | "halted" => super::schema::SwapStatus::Failed,
-            "refunded" => super::schema::SwapStatus::Refunded,
-            "expired" => super::schema::SwapStatus::Expired,
-            _ => super::schema::SwapStatus::Waiting,
-        }
+    /// Swap state, status history and the raw provider responses kept for
+    /// it, for dispute resolution
+    pub async fn debug_info(&self, swap_id: &str) -> Result<super::schema::SwapDebugResponse, SwapError> {
+        let swap: Option<SwapDebugRow> = sqlx::query_as(
+            r#"
+            SELECT id, provider_id, provider_swap_id, CAST(status AS CHAR) AS status, error, created_at, updated_at
+            FROM swaps WHERE id = ?
+            "#,
+        )
+        .bind(swap_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| SwapError::DatabaseError(e.to_string()))?;
+        let (id, provider, provider_swap_id, status, error, created_at, updated_at) =
+            swap.ok_or(SwapError::SwapNotFound)?;
+
+        let payloads = ProviderPayloadStore::new(self.pool.clone())
+            .list(swap_id)
+            .await
+            .map_err(|e| SwapError::DatabaseError(e.to_string()))?;
+
+        Ok(super::schema::SwapDebugResponse {
+            swap_id: id,
+            status,
+            provider,
+            provider_swap_id,
+            error,
+            created_at,
+            updated_at,
+            history: self.status_history(swap_id).await?,
+            payloads: payloads.into_iter().map(Into::into).collect(),
+        })
     }
 
-    /// Update swap status in database
-    async fn update_swap_status(
+    /// Receipt of a completed swap, with its verification hash but no
+    /// token, and the id of the user who owns the swap (None for swaps
+    /// created without an account)
+    pub async fn get_swap_receipt(
         &self,
         swap_id: &str,
-        status: &super::schema::SwapStatus,
-        actual_receive: f64,
-        tx_hash_in: Option<String>,
-        tx_hash_out: Option<String>,
-    ) -> Result<(), SwapError> {
-        let completed_at = if *status == super::schema::SwapStatus::Completed {
-            Some(Utc::now())
-        } else {
-            None
-        };
+    ) -> Result<(Option<String>, super::schema::SwapReceipt), SwapError> {
+        use sqlx::Row;
 
-        sqlx::query(
+        let row = sqlx::query(
             r#"
-            UPDATE swaps
-            SET status = ?,
-                actual_receive = ?,
-                tx_hash_in = COALESCE(?, tx_hash_in),
-                tx_hash_out = COALESCE(?, tx_hash_out),
-                completed_at = COALESCE(?, completed_at),
-                updated_at = NOW()
-            WHERE id = ?
-            "#
+            SELECT s.id, s.user_id, s.provider_id,
+                   s.from_currency, s.from_network, s.to_currency, s.to_network,
+                   s.amount,
+                   COALESCE(sai.payout_amount, s.actual_receive, s.estimated_receive) AS received_amount,
+                   s.rate, s.network_fee, s.provider_fee, s.platform_fee, s.total_fee,
+                   s.recipient_address, s.tx_hash_in,
+                   COALESCE(sai.payout_tx_hash, s.tx_hash_out) AS payout_tx_hash,
+                   CAST(s.status AS CHAR) AS status, s.is_sandbox,
+                   s.created_at, COALESCE(s.completed_at, s.updated_at) AS completed_at
+            FROM swaps s
+            LEFT JOIN swap_address_info sai ON sai.swap_id = s.id
+            WHERE s.id = ?
+            "#,
         )
-        .bind(status)
-        .bind(actual_receive)
-        .bind(tx_hash_in)
-        .bind(tx_hash_out)
-        .bind(completed_at)
         .bind(swap_id)
-        .execute(&self.pool)
+        .fetch_optional(&self.pool)
         .await
-        .map_err(|e| SwapError::DatabaseError(e.to_string()))?;
+        .map_err(|e| SwapError::DatabaseError(e.to_string()))?
+        .ok_or(SwapError::SwapNotFound)?;
 
-        Ok(())
+        let status: String = row.try_get("status").map_err(|e| SwapError::DatabaseError(e.to_string()))?;
+        if status != "completed" {
+            return Err(SwapError::ReceiptNotAvailable);
+        }
+
+        let get = |e: sqlx::Error| SwapError::DatabaseError(e.to_string());
+        let mut receipt = super::schema::SwapReceipt {
+            swap_id: row.try_get("id").map_err(get)?,
+            provider: row.try_get("provider_id").map_err(get)?,
+            from: row.try_get("from_currency").map_err(get)?,
+            network_from: row.try_get("from_network").map_err(get)?,
+            to: row.try_get("to_currency").map_err(get)?,
+            network_to: row.try_get("to_network").map_err(get)?,
+            deposit_amount: row.try_get("amount").map_err(get)?,
+            received_amount: row.try_get("received_amount").map_err(get)?,
+            rate: row.try_get("rate").map_err(get)?,
+            network_fee: row.try_get("network_fee").map_err(get)?,
+            provider_fee: row.try_get("provider_fee").map_err(get)?,
+            platform_fee: row.try_get("platform_fee").map_err(get)?,
+            total_fee: row.try_get("total_fee").map_err(get)?,
+            recipient_address: row.try_get::<SealedString, _>("recipient_address").map_err(get)?.into(),
+            deposit_tx_hash: row.try_get("tx_hash_in").map_err(get)?,
+            payout_tx_hash: row.try_get("payout_tx_hash").map_err(get)?,
+            is_sandbox: row.try_get::<i8, _>("is_sandbox").map_err(get)? != 0,
+            created_at: row.try_get("created_at").map_err(get)?,
+            completed_at: row.try_get("completed_at").map_err(get)?,
+            issued_at: Utc::now(),
+            verification_hash: String::new(),
+            receipt_token: None,
+        };
+        receipt.verification_hash = crate::services::receipt::verification_hash(&receipt);
+
+        Ok((row.try_get("user_id").map_err(get)?, receipt))
     }
 
-    /// Log status change to swap_status_history table
-    async fn log_status_change(
+    /// Sign `challenge` with the key of the address our wallet receives the
+    /// swap's funds at, proving we hold it
+    pub async fn address_proof(
         &self,
         swap_id: &str,
-        status: &super::schema::SwapStatus,
-        message: Option<String>,
-    ) -> Result<(), SwapError> {
-        sqlx::query(
+        challenge: &str,
+    ) -> Result<super::schema::AddressProofResponse, SwapError> {
+        use crate::services::wallet::ownership;
+
+        ownership::validate_challenge(challenge).map_err(SwapError::InvalidChallenge)?;
+
+        let row: Option<(String, Option<String>, Option<u32>)> = sqlx::query_as(
             r#"
-            INSERT INTO swap_status_history (swap_id, status, message, created_at)
-            VALUES (?, ?, ?, NOW())
-            "#
+            SELECT s.id, sai.our_address, sai.address_index
+            FROM swaps s
+            LEFT JOIN swap_address_info sai ON sai.swap_id = s.id
+            WHERE s.id = ?
+            "#,
         )
         .bind(swap_id)
-        .bind(status)
-        .bind(message)
-        .execute(&self.pool)
+        .fetch_optional(&self.pool)
         .await
         .map_err(|e| SwapError::DatabaseError(e.to_string()))?;
+        let (Some(address), Some(index)) = row.map(|(_, address, index)| (address, index)).ok_or(SwapError::SwapNotFound)?
+        else {
+            return Err(SwapError::AddressNotHeld);
+        };
+        let mnemonic = self.wallet_mnemonic.as_deref().ok_or(SwapError::AddressNotHeld)?;
+        if ownership::proof_scheme(&address).is_none() {
+            return Err(SwapError::AddressNotHeld);
+        }
 
-        Ok(())
+        let issued_at = Utc::now();
+        let message = ownership::proof_statement(&address, challenge, issued_at);
+        let signing = crate::services::wallet::signing::SigningService::from_config(mnemonic);
+        let (scheme, signature) = ownership::sign_ownership(&signing, &address, index, &message)
+            .await
+            .map_err(|e| {
+                tracing::error!("Address proof for swap {} failed: {}", swap_id, e);
+                SwapError::ExternalApiError("Could not sign the address proof".to_string())
+            })?;
+
+        Ok(super::schema::AddressProofResponse {
+            swap_id: swap_id.to_string(),
+            address,
+            scheme,
+            message,
+            signature,
+            issued_at,
+        })
+    }
+
+    /// Fee breakdown of a failed swap's refund: the calculator's estimate,
+    /// with the amount, fee and hash of the refund transaction once one
+    /// exists. None when it can't be worked out; the status still loads.
+    async fn refund_breakdown(&self, swap_id: &str) -> Option<super::schema::RefundBreakdown> {
+        let config = RefundConfig::from_env().unwrap_or_default();
+        let calculation = match uuid::Uuid::parse_str(swap_id) {
+            Ok(id) => RefundCalculator::new(self.pool.clone(), config).calculate_refund(id).await,
+            Err(_) => return None,
+        };
+        let calculation = match calculation {
+            Ok(calculation) => calculation,
+            Err(e) => {
+                tracing::warn!("Could not calculate refund for swap {}: {}", swap_id, e);
+                return None;
+            }
+        };
+
+        let sent: Option<(String, Option<String>, Option<String>, String)> = sqlx::query_as(
+            r#"
+            SELECT CAST(refund_amount AS CHAR), CAST(total_fee AS CHAR), tx_hash, CAST(status AS CHAR)
+            FROM refunds
+            WHERE swap_id = ?
+            ORDER BY created_at DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(swap_id)
+        .fetch_optional(&self.pool)
+        .await
+        .unwrap_or_else(|e| {
+            tracing::warn!("Could not load refund for swap {}: {}", swap_id, e);
+            None
+        });
+
+        let mut breakdown = super::schema::RefundBreakdown {
+            original_amount: calculation.deposit_amount,
+            currency: calculation.currency,
+            swap_fee: calculation.swap_fee,
+            platform_fee: calculation.platform_fee,
+            platform_fee_waived: calculation.platform_fee_waived,
+            network_fee: calculation.gas_cost_estimate,
+            refund_amount: calculation.refund_amount.max(Decimal::ZERO),
+            status: "estimated".to_string(),
+            tx_hash: None,
+        };
+        if let Some((refund_amount, network_fee, tx_hash, status)) = sent {
+            if let Ok(refund_amount) = amount::parse(&refund_amount) {
+                breakdown.refund_amount = refund_amount;
+            }
+            if let Some(fee) = network_fee.and_then(|fee| amount::parse(&fee).ok()) {
+                breakdown.network_fee = fee;
+            }
+            breakdown.status = status.to_lowercase();
+            breakdown.tx_hash = tx_hash;
+        }
+        Some(breakdown)
+    }
+
+    async fn status_history(&self, swap_id: &str) -> Result<Vec<super::schema::StatusHistoryEntry>, SwapError> {
+        SwapStateMachine::new(self.pool.clone())
+            .history(swap_id)
+            .await
+            .map_err(|e| SwapError::DatabaseError(e.to_string()))
+    }
+
+    /// Map Trocador status string to our SwapStatus enum
+    fn map_trocador_status(&self, trocador_status: &str) -> super::schema::SwapStatus {
+        super::schema::SwapStatus::from_provider_status(trocador_status)
     }
 
     // =========================================================================
@@ -1401,13 +2557,29 @@ impl SwapCrud {
             return Err(SwapError::InvalidAddress);
         }
 
-        // 2. Get API key
+        // 2. Addresses our published format rules rule out never reach the
+        // provider. The rules are mainnet formats, so a sandbox leaves test
+        // network addresses to the sandbox API.
+        let rule = address_rules::rule_for(&request.ticker, Some(&request.network))
+            .filter(|_| !SandboxConfig::global().enabled);
+        if let Some(rule) = rule {
+            if !rule.matches(request.address.trim()) {
+                return Ok(super::schema::ValidateAddressResponse {
+                    valid: false,
+                    ticker: request.ticker.clone(),
+                    network: request.network.clone(),
+                    address: request.address.clone(),
+                });
+            }
+        }
+
+        // 3. Get API key
         let api_key = std::env::var("TROCADOR_API_KEY")
             .map_err(|_| SwapError::ExternalApiError("TROCADOR_API_KEY not set".to_string()))?;
 
         let trocador_client = TrocadorClient::new(api_key);
 
-        // 3. Call Trocador API with retry logic
+        // 4. Call Trocador API with retry logic
         let is_valid = self.call_trocador_with_retry(|| async {
             trocador_client
                 .validate_address(&request.ticker, &request.network, &request.address)
@@ -1415,7 +2587,7 @@ impl SwapCrud {
         })
         .await?;
 
-        // 4. Return response
+        // 5. Return response
         Ok(super::schema::ValidateAddressResponse {
             valid: is_valid,
             ticker: request.ticker.clone(),
@@ -1482,6 +2654,7 @@ impl SwapCrud {
     // =========================================================================
 
     /// Get user's swap history with keyset pagination for optimal performance
+#[tracing::instrument(name = "swap.history", skip(self, query), fields(db.system = "mysql"))]
     pub async fn get_swap_history(
         &self,
         user_id: &str,
@@ -1519,15 +2692,13 @@ impl SwapCrud {
                 id, user_id, provider_id,
                 CAST(status AS CHAR) as status,
                 from_currency, from_network, to_currency, to_network,
-                CAST(amount AS DOUBLE) as amount,
-                CAST(estimated_receive AS DOUBLE) as estimated_receive,
-                CAST(actual_receive AS DOUBLE) as actual_receive,
-                CAST(rate AS DOUBLE) as rate,
-                CAST(platform_fee AS DOUBLE) as platform_fee,
-                CAST(total_fee AS DOUBLE) as total_fee,
+                amount, estimated_receive, actual_receive, rate,
+                platform_fee, total_fee,
                 deposit_address, recipient_address,
                 CAST(rate_type AS CHAR) as rate_type,
+                CAST(swap_type AS CHAR) as swap_type,
                 is_sandbox,
+                client_reference, CAST(metadata AS CHAR) as metadata,
                 created_at, completed_at
             FROM swaps
             WHERE user_id = ?"
@@ -1559,6 +2730,14 @@ impl SwapCrud {
             sql.push_str(" AND provider_id = ?");
             bind_values.push(provider.clone());
         }
+        if let Some(swap_type) = query.swap_type {
+            sql.push_str(" AND swap_type = ?");
+            bind_values.push(swap_type.as_str().to_string());
+        }
+        if let Some(ref reference) = query.client_reference {
+            sql.push_str(" AND client_reference = ?");
+            bind_values.push(reference.clone());
+        }
         if let Some(dt) = date_from {
             sql.push_str(" AND created_at >= ?");
             bind_values.push(dt.to_rfc3339());
@@ -1624,6 +2803,11 @@ impl SwapCrud {
                 "fixed" => super::schema::RateType::Fixed,
                 _ => super::schema::RateType::Floating,
             };
+
+            let swap_type = match row.get::<String, _>("swap_type").as_str() {
+                "bridge" => SwapType::Bridge,
+                _ => SwapType::Exchange,
+            };
             
             super::schema::SwapSummary {
                 id: row.get("id"),
@@ -1639,12 +2823,19 @@ impl SwapCrud {
                 platform_fee: row.get("platform_fee"),
                 total_fee: row.get("total_fee"),
                 deposit_address: row.get("deposit_address"),
-                recipient_address: row.get("recipient_address"),
+                recipient_address: row.get::<SealedString, _>("recipient_address").into(),
                 provider: row.get("provider_id"),
                 rate_type,
+                swap_type,
                 is_sandbox: row.get::<i8, _>("is_sandbox") != 0,
                 created_at: row.get("created_at"),
                 completed_at: row.try_get("completed_at").ok(),
+                client_reference: row.try_get("client_reference").ok().flatten(),
+                metadata: row
+                    .try_get::<Option<String>, _>("metadata")
+                    .ok()
+                    .flatten()
+                    .and_then(|json| serde_json::from_str(&json).ok()),
             }
         }).collect();
         
@@ -1677,6 +2868,8 @@ impl SwapCrud {
                 from_currency: query.from_currency,
                 to_currency: query.to_currency,
                 provider: query.provider,
+                swap_type: query.swap_type,
+                client_reference: query.client_reference,
                 date_from: query.date_from,
                 date_to: query.date_to,
             },
@@ -1693,6 +2886,9 @@ impl SwapCrud {
         query: &super::schema::EstimateQuery,
     ) -> Result<super::schema::EstimateResponse, SwapError> {
         use chrono::Utc;
+
+        self.ensure_trading_enabled(&query.from, &query.network_from, &query.to, &query.network_to).await?;
+        self.check_route(&query.from, &query.network_from, &query.to, &query.network_to).await?;
         
         // 1. Generate cache keys (exact + bucketed)
         let exact_key = format!(
@@ -1701,10 +2897,10 @@ impl SwapCrud {
             query.to.to_lowercase(),
             query.network_from,
             query.network_to,
-            query.amount
+            query.amount.value()
         );
         
-        let bucketed_amount = Self::bucket_amount(query.amount);
+        let bucketed_amount = Self::bucket_amount(query.amount.value());
         let bucketed_key = format!(
             "estimate:v2:{}:{}:{}:{}:{:.8}:bucket",
             query.from.to_lowercase(),
@@ -1775,6 +2971,37 @@ impl SwapCrud {
         self.fetch_estimate_from_api(query).await
     }
     
+    /// Fee decomposition for a pair and amount, straight from live quotes
+    pub async fn get_estimate_detailed(
+        &self,
+        query: &super::schema::EstimateQuery,
+    ) -> Result<super::schema::DetailedEstimateResponse, SwapError> {
+        self.ensure_trading_enabled(&query.from, &query.network_from, &query.to, &query.network_to).await?;
+        self.check_route(&query.from, &query.network_from, &query.to, &query.network_to).await?;
+
+        let rates_query = super::schema::RatesQuery {
+            from: query.from.clone(),
+            network_from: query.network_from.clone(),
+            to: query.to.clone(),
+            network_to: query.network_to.clone(),
+            amount: query.amount,
+            rate_type: None,
+            provider: None,
+        };
+
+        let fanout = self.fetch_quotes_from_api(&rates_query).await?;
+        let gas_cost = self.get_gas_cost_for_network(&query.network_to).await;
+
+        let mut response = PricingEngine::new()
+            .with_commissions(self.commission_overrides().await)
+            .with_currency(&query.to)
+            .detailed_breakdown(&fanout.quotes, query, gas_cost)
+            .ok_or(SwapError::PairNotAvailable)?;
+        response.gas.fee_targets = self.fee_targets(&query.network_to).await;
+
+        Ok(response)
+    }
+    
     /// Fetch estimate from Trocador API and cache result
     async fn fetch_estimate_from_api(
         &self,
@@ -1804,7 +3031,7 @@ impl SwapCrud {
         
         // 2. Calculate provider spread (volatility indicator)
         let amounts: Vec<f64> = rates_response.rates.iter()
-            .map(|r| r.estimated_amount)
+            .map(|r| r.estimated_amount.to_f64().unwrap_or(0.0))
             .collect();
         let max_amount = amounts.iter().fold(0.0f64, |a, &b| a.max(b));
         let min_amount = amounts.iter().fold(f64::MAX, |a, &b| a.min(b));
@@ -1815,17 +3042,10 @@ impl SwapCrud {
         };
         
         // 3. Estimate USD value (for slippage calculation)
-        let usd_price = match query.from.to_lowercase().as_str() {
-            "btc" => 60000.0,
-            "eth" => 3000.0,
-            "xmr" => 150.0,
-            "usdt" | "usdc" | "dai" => 1.0,
-            _ => 1.0,
-        };
-        let amount_usd = query.amount * usd_price;
+        let amount_usd = query.amount.value().to_f64().unwrap_or(0.0) * approx_usd_price(&query.from);
         
         // 4. Build estimate response using pricing engine
-        let pricing_engine = PricingEngine::new();
+        let pricing_engine = PricingEngine::new().with_currency(&query.to);
         let compute_time_ms = start_time.elapsed().as_millis() as i64;
         
         let response = pricing_engine.build_estimate_response(
@@ -1849,7 +3069,7 @@ impl SwapCrud {
                 query.to.to_lowercase(),
                 query.network_from,
                 query.network_to,
-                query.amount
+                query.amount.value()
             );
             let exact_entry = super::schema::EstimateCacheEntry {
                 response: response.clone(),
@@ -1860,7 +3080,7 @@ impl SwapCrud {
             let _ = service.set_json(&exact_key, &exact_entry, 10).await;
             
             // Bucketed key cache (60s TTL)
-            let bucketed_amount = Self::bucket_amount(query.amount);
+            let bucketed_amount = Self::bucket_amount(query.amount.value());
             let bucketed_key = format!(
                 "estimate:v2:{}:{}:{}:{}:{:.8}:bucket",
                 query.from.to_lowercase(),
@@ -1882,15 +3102,15 @@ impl SwapCrud {
     }
     
     /// Bucket amount to reduce cache fragmentation
-    fn bucket_amount(amount: f64) -> f64 {
-        let bucket_size = if amount < 0.01 {
-            0.001
-        } else if amount < 1.0 {
-            0.01
-        } else if amount < 10.0 {
-            0.1
+    fn bucket_amount(amount: Decimal) -> Decimal {
+        let bucket_size = if amount < Decimal::new(1, 2) {
+            Decimal::new(1, 3)
+        } else if amount < Decimal::ONE {
+            Decimal::new(1, 2)
+        } else if amount < Decimal::TEN {
+            Decimal::new(1, 1)
         } else {
-            1.0
+            Decimal::ONE
         };
         (amount / bucket_size).floor() * bucket_size
     }
@@ -1926,3 +3146,23 @@ impl SwapCrud {
         now + threshold as i64 >= entry.expires_at
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_client_data() {
+        assert!(validate_client_data(None, None).is_ok());
+        assert!(validate_client_data(Some("partner-order-1"), None).is_ok());
+        assert!(validate_client_data(Some(""), None).is_err());
+        assert!(validate_client_data(Some(&"x".repeat(MAX_CLIENT_REFERENCE_LEN + 1)), None).is_err());
+        assert!(validate_client_data(Some("line\nbreak"), None).is_err());
+
+        let mut metadata = serde_json::Map::new();
+        metadata.insert("tier".to_string(), serde_json::json!("gold"));
+        assert!(validate_client_data(None, Some(&metadata)).is_ok());
+        metadata.insert("blob".to_string(), serde_json::json!("x".repeat(MAX_METADATA_BYTES)));
+        assert!(matches!(validate_client_data(None, Some(&metadata)), Err(SwapError::InvalidClientData(_))));
+    }
+}
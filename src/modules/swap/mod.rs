@@ -3,5 +3,8 @@ pub mod model;
 pub mod crud;
 pub mod controller;
 pub mod routes;
+pub mod bridge;
+pub mod search;
+pub mod address_rules;
 
 pub use routes::swap_routes;
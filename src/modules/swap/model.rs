@@ -1,8 +1,11 @@
 use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
 use sqlx::FromRow;
 use serde::{Deserialize, Serialize};
 
 use super::schema::{RateType, SwapStatus};
+use crate::services::amount::Decimal;
+use crate::services::pii::SealedString;
 
 // =============================================================================
 // PROVIDER
@@ -82,6 +85,8 @@ pub struct TradingPairView {
 pub struct Swap {
     pub id: String,
     pub user_id: Option<String>,
+    /// Integrator's own id for the swap
+    pub client_reference: Option<String>,
     pub provider_id: String,
     pub provider_swap_id: Option<String>, // This stores Trocador's trade_id
 
@@ -92,20 +97,29 @@ pub struct Swap {
     pub to_network: String,
 
     // Amounts
-    pub amount: f64,
-    pub estimated_receive: f64,
-    pub actual_receive: Option<f64>,
-    pub rate: f64,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub amount: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub estimated_receive: Decimal,
+    #[serde(default, with = "rust_decimal::serde::float_option")]
+    pub actual_receive: Option<Decimal>,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub rate: Decimal,
 
     // Fees
-    pub network_fee: f64,
-    pub provider_fee: f64,
-    pub platform_fee: f64,
-    pub total_fee: f64,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub network_fee: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub provider_fee: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub platform_fee: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub total_fee: Decimal,
 
     // Addresses
     pub deposit_address: String,
     pub deposit_extra_id: Option<String>,
+    #[sqlx(try_from = "SealedString")]
     pub recipient_address: String,
     pub recipient_extra_id: Option<String>,
     pub refund_address: Option<String>,
@@ -128,6 +142,25 @@ pub struct Swap {
     pub updated_at: DateTime<Utc>,
 }
 
+// =============================================================================
+// PROVIDER ORDER INTENT
+// =============================================================================
+
+/// Provider order placed during swap creation, tracked until its swap row
+/// is committed or the orphaned order is reconciled
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct ProviderOrderIntent {
+    pub swap_id: String,
+    pub provider_id: String,
+    pub provider_swap_id: Option<String>,
+    pub our_address: String,
+    pub address_index: u32,
+    pub status: String,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
 // =============================================================================
 // SWAP STATUS HISTORY
 // =============================================================================
@@ -137,10 +170,106 @@ pub struct SwapStatusHistory {
     pub id: i64,
     pub swap_id: String,
     pub status: SwapStatus,
+    /// Who made the transition, see `StatusActor`
+    pub actor: String,
     pub message: Option<String>,
     pub created_at: DateTime<Utc>,
 }
 
+// =============================================================================
+// PROVIDER PAYLOAD (raw provider responses, for disputes)
+// =============================================================================
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, sqlx::Type, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case")]
+pub enum PayloadKind {
+    /// Response to creating the trade
+    Create,
+    /// Response to a status poll
+    Status,
+}
+
+impl PayloadKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PayloadKind::Create => "create",
+            PayloadKind::Status => "status",
+        }
+    }
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct ProviderPayload {
+    pub id: i64,
+    pub swap_id: String,
+    pub provider: String,
+    pub kind: PayloadKind,
+    /// JSON text, read through `UNCOMPRESS()`
+    pub payload: String,
+    pub payload_hash: String,
+    /// Size of the JSON as received, before any truncation
+    pub original_bytes: u32,
+    pub truncated: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+// =============================================================================
+// SWAP SLA BREACH (swaps stuck in one status past its SLA)
+// =============================================================================
+
+/// How far a stuck swap has been escalated, in the order the steps run
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, sqlx::Type, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(rename_all = "lowercase")]
+pub enum SlaEscalation {
+    /// The provider was asked for the swap's status again
+    Reping,
+    /// Admins were told on the ops channel
+    Notified,
+    /// A support ticket was opened
+    Ticketed,
+}
+
+impl SlaEscalation {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SlaEscalation::Reping => "reping",
+            SlaEscalation::Notified => "notified",
+            SlaEscalation::Ticketed => "ticketed",
+        }
+    }
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct SwapSlaBreach {
+    pub id: i64,
+    pub swap_id: String,
+    pub status: SwapStatus,
+    pub entered_at: DateTime<Utc>,
+    pub sla_minutes: u32,
+    pub escalation: SlaEscalation,
+    pub ticket_reference: Option<String>,
+    pub detected_at: DateTime<Utc>,
+    pub escalated_at: DateTime<Utc>,
+    pub resolved_at: Option<DateTime<Utc>>,
+}
+
+/// An open breach with the swap's provider, for the stuck-swap queue
+#[derive(Debug, Clone, FromRow)]
+pub struct StuckSwap {
+    pub id: i64,
+    pub swap_id: String,
+    pub status: SwapStatus,
+    pub provider_id: String,
+    pub entered_at: DateTime<Utc>,
+    pub sla_minutes: u32,
+    pub escalation: SlaEscalation,
+    pub ticket_reference: Option<String>,
+    pub detected_at: DateTime<Utc>,
+    pub escalated_at: DateTime<Utc>,
+}
+
 // =============================================================================
 // PROVIDER CURRENCY (which currencies each provider supports)
 // =============================================================================
@@ -1,18 +1,29 @@
-use axum::{routing::{get, post}, Router};
+use axum::Router;
 use std::sync::Arc;
 
 use crate::AppState;
-use super::controller::{get_currencies, get_providers, get_rates, create_swap, get_swap_status, validate_address, get_swap_history, get_estimate, get_pairs};
+use crate::manifest::DeclaredRoutes;
+use crate::modules::orders::order_routes;
+use crate::modules::schedules::schedule_routes;
+use super::controller::{get_currencies, get_providers, get_rates, create_swap, get_swap_status, validate_address, get_swap_history, get_estimate, get_estimate_detailed, get_pairs, get_pair_matrix, get_quote_key, get_address_spec, get_swap_receipt, create_address_proof};
 
 pub fn swap_routes() -> Router<Arc<AppState>> {
     Router::new()
-        .route("/currencies", get(get_currencies))
-        .route("/providers", get(get_providers))
-        .route("/pairs", get(get_pairs))
-        .route("/rates", get(get_rates))
-        .route("/estimate", get(get_estimate))
-        .route("/create", post(create_swap))
-        .route("/history", get(get_swap_history))
-        .route("/{id}", get(get_swap_status))
-        .route("/validate-address", post(validate_address))
+        .declared("listCurrencies", get_currencies)
+        .declared("getAddressSpec", get_address_spec)
+        .declared("listProviders", get_providers)
+        .declared("listPairs", get_pairs)
+        .declared("getPairMatrix", get_pair_matrix)
+        .declared("getRates", get_rates)
+        .declared("getQuoteKey", get_quote_key)
+        .declared("getEstimate", get_estimate)
+        .declared("getDetailedEstimate", get_estimate_detailed)
+        .declared("createSwap", create_swap)
+        .declared("getSwapHistory", get_swap_history)
+        .merge(schedule_routes())
+        .merge(order_routes())
+        .declared("getSwapStatus", get_swap_status)
+        .declared("getSwapReceipt", get_swap_receipt)
+        .declared("createAddressProof", create_address_proof)
+        .declared("validateAddress", validate_address)
 }
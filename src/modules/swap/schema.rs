@@ -1,20 +1,29 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
 
+use super::model::{PayloadKind, ProviderPayload, SlaEscalation, StuckSwap};
+use crate::services::amount::{Decimal, WireAmount};
+use crate::services::explorer::{chain_key, ExplorerRegistry};
+use crate::services::network_status::NetworkWarning;
+use crate::services::quote_signing::SignedQuote;
+use crate::services::wallet::btc_fees::FeeTarget;
+
 // =============================================================================
 // PROVIDERS
 // =============================================================================
 
 // Request query parameters for /swap/providers
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Clone, JsonSchema)]
 pub struct ProvidersQuery {
     pub rating: Option<String>,         // Filter by KYC rating (A, B, C, D)
     pub markup_enabled: Option<bool>,   // Filter by markup support
     pub sort: Option<String>,           // Sort by: name, rating, eta
+    pub fields: Option<String>,         // Comma-separated fields to return (e.g. "name,rating")
 }
 
 // Response DTO matching Trocador's /exchanges format EXACTLY
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct ProviderResponse {
     pub name: String,
     pub rating: String,           // Maps from kyc_rating (A/B/C/D)
@@ -51,17 +60,18 @@ impl From<crate::modules::swap::model::Provider> for ProviderResponse {
 // =============================================================================
 
 // Request query parameters for /swap/currencies
-#[derive(Debug, Deserialize, Default, Clone)]
+#[derive(Debug, Deserialize, Default, Clone, JsonSchema)]
 pub struct CurrenciesQuery {
     pub ticker: Option<String>,         // Filter by ticker (e.g., "btc")
     pub network: Option<String>,        // Filter by network (e.g., "Mainnet")
     pub memo: Option<bool>,             // Filter by memo required
     pub page: Option<usize>,            // Pagination: Page number (1-based)
     pub limit: Option<usize>,           // Pagination: Items per page
+    pub fields: Option<String>,         // Comma-separated fields to return (e.g. "ticker,network")
 }
 
 // Response DTO matching Trocador's /coins format EXACTLY
-#[derive(Debug, Serialize, Clone)]
+#[derive(Debug, Serialize, Clone, JsonSchema)]
 pub struct CurrencyResponse {
     pub name: String,
     pub ticker: String,       // Maps from symbol
@@ -102,7 +112,7 @@ impl From<crate::modules::swap::model::Currency> for CurrencyResponse {
 // PAIRS
 // =============================================================================
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Clone, JsonSchema)]
 pub struct PairsQuery {
     // Filtering
     pub base_currency: Option<String>,
@@ -127,7 +137,7 @@ pub struct PairsQuery {
 fn default_page() -> u32 { 0 }
 fn default_pairs_size() -> u32 { 20 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct PairResponse {
     pub name: String,  // e.g., "BTC/USDT"
     pub base_currency: String,
@@ -140,13 +150,13 @@ pub struct PairResponse {
     pub last_updated: DateTime<Utc>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct PairsResponse {
     pub pairs: Vec<PairResponse>,
     pub pagination: PairsPaginationInfo,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct PairsPaginationInfo {
     pub page: u32,
     pub size: u32,
@@ -156,22 +166,66 @@ pub struct PairsPaginationInfo {
     pub has_prev: bool,
 }
 
+/// Filters for GET /swap/pairs/matrix; every one is optional
+#[derive(Debug, Default, Deserialize, Clone, JsonSchema)]
+pub struct PairMatrixQuery {
+    pub from: Option<String>,
+    pub network_from: Option<String>,
+    pub to: Option<String>,
+    pub network_to: Option<String>,
+    /// Only pairs some provider offers at this rate type
+    pub rate_type: Option<RateType>,
+}
+
+/// Pairs providers currently take, with their limits
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct PairMatrixResponse {
+    pub pairs: Vec<PairLiquidityResponse>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct PairLiquidityResponse {
+    pub from: String,
+    pub network_from: String,
+    pub to: String,
+    pub network_to: String,
+    /// Lowest amount any provider accepts, in `from`
+    pub min_amount: Option<f64>,
+    /// Highest amount any provider accepts, in `from`
+    pub max_amount: Option<f64>,
+    pub fixed_rate: bool,
+    pub floating_rate: bool,
+    /// When the oldest provider entry was last probed
+    pub refreshed_at: DateTime<Utc>,
+    pub providers: Vec<ProviderLiquidityResponse>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ProviderLiquidityResponse {
+    pub provider: String,
+    pub min_amount: Option<f64>,
+    pub max_amount: Option<f64>,
+    pub fixed_rate: bool,
+    pub floating_rate: bool,
+}
+
 // =============================================================================
 // RATES
 // =============================================================================
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct RatesQuery {
     pub from: String,
     pub network_from: String,
     pub to: String,
     pub network_to: String,
-    pub amount: f64,
+    /// Number or decimal string, at most the `from` currency's decimals
+    pub amount: WireAmount,
     pub rate_type: Option<RateType>,
     pub provider: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, sqlx::Type)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, sqlx::Type, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 #[sqlx(rename_all = "lowercase")]
 pub enum RateType {
@@ -185,34 +239,122 @@ impl Default for RateType {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// Same-ticker cross-network requests (USDT ERC20 -> USDT TRC20) are bridges;
+/// everything else is a regular exchange
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, sqlx::Type, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case")]
+pub enum SwapType {
+    #[default]
+    Exchange,
+    Bridge,
+}
+
+impl SwapType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SwapType::Exchange => "exchange",
+            SwapType::Bridge => "bridge",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct RateResponse {
     pub provider: String,
     pub provider_name: String,
-    pub rate: f64,
-    pub estimated_amount: f64,
-    pub min_amount: f64,
-    pub max_amount: f64,
-    pub network_fee: f64,
-    pub provider_fee: f64,
-    pub platform_fee: f64,
-    pub total_fee: f64,
+    #[serde(with = "rust_decimal::serde::float")]
+    #[schemars(with = "f64")]
+    pub rate: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    #[schemars(with = "f64")]
+    pub estimated_amount: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    #[schemars(with = "f64")]
+    pub min_amount: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    #[schemars(with = "f64")]
+    pub max_amount: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    #[schemars(with = "f64")]
+    pub network_fee: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    #[schemars(with = "f64")]
+    pub provider_fee: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    #[schemars(with = "f64")]
+    pub platform_fee: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    #[schemars(with = "f64")]
+    pub total_fee: Decimal,
     pub rate_type: RateType,
     pub kyc_required: bool,
     pub kyc_rating: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub eta_minutes: Option<u32>,
+    /// Signed commitment to this rate and its fees; pass it to /swap/create
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quote: Option<SignedQuote>,
+    /// Campaign that waived some or all of `platform_fee`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub promotion: Option<AppliedPromotion>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct AppliedPromotion {
+    pub id: String,
+    pub name: String,
+    /// Fraction of the platform fee waived; 1.0 is fee-free
+    pub fee_discount: f64,
+    /// The platform fee without the campaign
+    #[serde(with = "rust_decimal::serde::float")]
+    #[schemars(with = "f64")]
+    pub standard_platform_fee: Decimal,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct RatesResponse {
     pub trade_id: String, // Trocador trade ID
     pub from: String,
     pub network_from: String,
     pub to: String,
     pub network_to: String,
-    pub amount: f64,
+    #[serde(with = "rust_decimal::serde::float")]
+    #[schemars(with = "f64")]
+    pub amount: Decimal,
+    #[serde(default)]
+    pub swap_type: SwapType,
     pub rates: Vec<RateResponse>,
+    /// Networks on either leg that are degraded or down
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<NetworkWarning>,
+    /// True when some provider adapters missed the deadline or failed, so
+    /// `rates` may be missing providers; ask again for the rest
+    #[serde(default)]
+    pub partial: bool,
+    /// How each provider adapter did on this request
+    #[serde(default)]
+    pub sources: Vec<RateSourceTiming>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RateSourceStatus {
+    Ok,
+    /// No answer before the deadline
+    Timeout,
+    Error,
+}
+
+/// One provider adapter's part in a rates request
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RateSourceTiming {
+    pub source: String,
+    pub status: RateSourceStatus,
+    /// Time until the adapter answered, or the deadline when it didn't
+    pub elapsed_ms: u64,
+    /// Quotes the adapter returned, before filtering
+    pub quotes: usize,
 }
 
 // Trocador's internal rate response
@@ -225,6 +367,15 @@ pub struct TrocadorQuote {
     pub kycrating: Option<String>,
     pub waste: Option<String>, // String in Trocador JSON
     pub eta: Option<f64>,
+    /// "True" when the quoted rate is fixed
+    #[serde(default)]
+    pub fixed: Option<String>,
+}
+
+impl TrocadorQuote {
+    pub fn is_fixed(&self) -> bool {
+        self.fixed.as_deref().is_some_and(|f| f.eq_ignore_ascii_case("true"))
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -252,7 +403,7 @@ pub struct TrocadorRatesResponse {
 
 use validator::Validate;
 
-#[derive(Debug, Deserialize, Validate, Clone)]
+#[derive(Debug, Deserialize, Validate, Clone, JsonSchema)]
 pub struct EstimateQuery {
     #[validate(length(min = 1, max = 20))]
     pub from: String,
@@ -260,8 +411,8 @@ pub struct EstimateQuery {
     #[validate(length(min = 1, max = 20))]
     pub to: String,
     
-    #[validate(range(min = 0.0, max = 1000000.0))]
-    pub amount: f64,
+    #[validate(custom(function = "validate_estimate_amount"))]
+    pub amount: WireAmount,
     
     #[validate(length(min = 1, max = 50))]
     pub network_from: String,
@@ -270,26 +421,51 @@ pub struct EstimateQuery {
     pub network_to: String,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+fn validate_estimate_amount(amount: &WireAmount) -> Result<(), validator::ValidationError> {
+    if amount.value() < Decimal::ZERO || amount.value() > Decimal::from(1_000_000) {
+        return Err(validator::ValidationError::new("range"));
+    }
+    Ok(())
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub struct EstimateResponse {
     // Request echo
     pub from: String,
     pub to: String,
-    pub amount: f64,
+    #[serde(with = "rust_decimal::serde::float")]
+    #[schemars(with = "f64")]
+    pub amount: Decimal,
     pub network_from: String,
     pub network_to: String,
     
     // Best rate summary
-    pub best_rate: f64,
-    pub estimated_receive: f64,
-    pub estimated_receive_min: f64,  // After slippage
-    pub estimated_receive_max: f64,  // Best case
+    #[serde(with = "rust_decimal::serde::float")]
+    #[schemars(with = "f64")]
+    pub best_rate: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    #[schemars(with = "f64")]
+    pub estimated_receive: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    #[schemars(with = "f64")]
+    pub estimated_receive_min: Decimal,  // After slippage
+    #[serde(with = "rust_decimal::serde::float")]
+    #[schemars(with = "f64")]
+    pub estimated_receive_max: Decimal,  // Best case
     
     // Fee breakdown
-    pub network_fee: f64,
-    pub provider_fee: f64,
-    pub platform_fee: f64,
-    pub total_fee: f64,
+    #[serde(with = "rust_decimal::serde::float")]
+    #[schemars(with = "f64")]
+    pub network_fee: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    #[schemars(with = "f64")]
+    pub provider_fee: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    #[schemars(with = "f64")]
+    pub platform_fee: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    #[schemars(with = "f64")]
+    pub total_fee: Decimal,
     
     // Slippage info
     pub slippage_percentage: f64,
@@ -317,22 +493,127 @@ pub struct EstimateCacheEntry {
     pub compute_time_ms: i64, // How long it took to compute (delta for PER)
 }
 
+// =============================================================================
+// DETAILED ESTIMATE - Full fee decomposition for a pair and amount
+// =============================================================================
+
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct CommissionBreakdown {
+    /// Volume tier the amount falls into (small, medium, whale)
+    pub tier: String,
+    pub tier_rate_percentage: f64,
+    /// Added when providers disagree on price by more than the volatility threshold
+    pub volatility_premium_percentage: f64,
+    pub commission_rate_percentage: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct GasFloorBreakdown {
+    /// Estimated payout transaction cost on the destination network
+    pub network_fee_estimate: f64,
+    pub safety_buffer: f64,
+    /// Smallest platform fee charged: network_fee_estimate * safety_buffer
+    pub gas_floor: f64,
+    /// Payout fee at each confirmation target, for Bitcoin destinations
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fee_targets: Vec<FeeTargetOption>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct FeeTargetOption {
+    pub target: FeeTarget,
+    /// Blocks the payout should confirm within
+    pub blocks: u32,
+    pub sat_per_vbyte: f64,
+    pub network_fee_estimate: f64,
+    /// Payouts are built at this target
+    pub payout_target: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct ProviderFeeBreakdown {
+    pub provider: String,
+    /// What the provider sends before our fee
+    #[serde(with = "rust_decimal::serde::float")]
+    #[schemars(with = "f64")]
+    pub provider_amount: Decimal,
+    /// Provider's own spread, already deducted from provider_amount
+    #[serde(with = "rust_decimal::serde::float")]
+    #[schemars(with = "f64")]
+    pub provider_fee: Decimal,
+    /// provider_amount * commission rate
+    #[serde(with = "rust_decimal::serde::float")]
+    #[schemars(with = "f64")]
+    pub commission_fee: Decimal,
+    /// Negotiated rate for this provider, used instead of the tier rate
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commission_override_percentage: Option<f64>,
+    /// The commission was below the gas floor and was raised to it
+    pub gas_floor_applied: bool,
+    #[serde(with = "rust_decimal::serde::float")]
+    #[schemars(with = "f64")]
+    pub platform_fee: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    #[schemars(with = "f64")]
+    pub total_fee: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    #[schemars(with = "f64")]
+    pub estimated_receive: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    #[schemars(with = "f64")]
+    pub rate: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    #[schemars(with = "f64")]
+    pub min_amount: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    #[schemars(with = "f64")]
+    pub max_amount: Decimal,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct DetailedEstimateResponse {
+    pub from: String,
+    pub to: String,
+    #[serde(with = "rust_decimal::serde::float")]
+    #[schemars(with = "f64")]
+    pub amount: Decimal,
+    pub network_from: String,
+    pub network_to: String,
+
+    /// USD size used to pick the commission tier
+    pub amount_usd: f64,
+    pub provider_spread_percentage: f64,
+    pub commission: CommissionBreakdown,
+    pub gas: GasFloorBreakdown,
+    pub slippage_percentage: f64,
+
+    pub best_provider: String,
+    /// Best rate first
+    pub providers: Vec<ProviderFeeBreakdown>,
+}
+
 // =============================================================================
 // CREATE SWAP
 // =============================================================================
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
 pub struct CreateSwapRequest {
     pub trade_id: Option<String>, // ID from new_rate
     pub from: String,
     pub network_from: String,
     pub to: String,
     pub network_to: String,
-    pub amount: f64,
+    /// Number or decimal string, at most the `from` currency's decimals
+    pub amount: WireAmount,
     pub provider: String,
+    /// May be omitted when `address_book_id` or `payout_to_balance` is set
+    #[serde(default)]
     pub recipient_address: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub recipient_extra_id: Option<String>,
+    /// Saved address to pay out to instead of a raw recipient_address
+    #[serde(default)]
+    pub address_book_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub refund_address: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -341,26 +622,51 @@ pub struct CreateSwapRequest {
     pub rate_type: RateType,
     #[serde(default)]
     pub sandbox: bool,
+    /// Credit proceeds to the user's custodial balance instead of recipient_address
+    #[serde(default)]
+    pub payout_to_balance: bool,
+    /// Signed quote from /swap/rates; must match this request, and its
+    /// platform fee is the one charged
+    #[serde(default)]
+    pub quote: Option<SignedQuote>,
+    /// Integrator's own id for this swap (up to 128 printable characters),
+    /// echoed in history and webhooks
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_reference: Option<String>,
+    /// Free-form JSON object stored with the swap, up to 4 KB serialized
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<serde_json::Map<String, serde_json::Value>>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct CreateSwapResponse {
     pub swap_id: String,
     pub provider: String,
     pub from: String,
     pub to: String,
+    pub swap_type: SwapType,
     pub deposit_address: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub deposit_extra_id: Option<String>,
-    pub deposit_amount: f64,
+    #[serde(with = "rust_decimal::serde::float")]
+    #[schemars(with = "f64")]
+    pub deposit_amount: Decimal,
     pub recipient_address: String,
-    pub estimated_receive: f64,
-    pub rate: f64,
+    #[serde(with = "rust_decimal::serde::float")]
+    #[schemars(with = "f64")]
+    pub estimated_receive: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    #[schemars(with = "f64")]
+    pub rate: Decimal,
     pub status: SwapStatus,
     pub rate_type: RateType,
     pub is_sandbox: bool,
     pub expires_at: DateTime<Utc>,
     pub created_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_reference: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<serde_json::Map<String, serde_json::Value>>,
 }
 
 // Trocador's internal trade response
@@ -383,13 +689,16 @@ pub struct TrocadorTradeResponse {
     pub refund_address_memo: Option<String>,
     pub id_provider: Option<String>,
     pub date: Option<String>,
+    /// The response as received, kept for `provider_payloads`
+    #[serde(skip)]
+    pub raw: Option<serde_json::Value>,
 }
 
 // =============================================================================
 // SWAP STATUS
 // =============================================================================
 
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, sqlx::Type)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, sqlx::Type, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 #[sqlx(rename_all = "lowercase")]
 pub enum SwapStatus {
@@ -397,19 +706,75 @@ pub enum SwapStatus {
     Confirming,
     Exchanging,
     Sending,
+    /// Our payout address holds the provider's output; payout pending
+    #[serde(rename = "funds_received")]
+    #[sqlx(rename = "funds_received")]
+    FundsReceived,
     Completed,
     Failed,
     Refunded,
     Expired,
 }
 
+impl SwapStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SwapStatus::Waiting => "waiting",
+            SwapStatus::Confirming => "confirming",
+            SwapStatus::Exchanging => "exchanging",
+            SwapStatus::Sending => "sending",
+            SwapStatus::FundsReceived => "funds_received",
+            SwapStatus::Completed => "completed",
+            SwapStatus::Failed => "failed",
+            SwapStatus::Refunded => "refunded",
+            SwapStatus::Expired => "expired",
+        }
+    }
+
+    /// Map a Trocador trade status onto ours; unknown values stay `Waiting`
+    pub fn from_provider_status(status: &str) -> Self {
+        match status {
+            "new" | "waiting" => SwapStatus::Waiting,
+            "confirming" => SwapStatus::Confirming,
+            "exchanging" => SwapStatus::Exchanging,
+            "sending" => SwapStatus::Sending,
+            "finished" | "paid partially" => SwapStatus::Completed,
+            "failed" | "halted" => SwapStatus::Failed,
+            "refunded" => SwapStatus::Refunded,
+            "expired" => SwapStatus::Expired,
+            _ => SwapStatus::Waiting,
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Some(match value {
+            "waiting" => SwapStatus::Waiting,
+            "confirming" => SwapStatus::Confirming,
+            "exchanging" => SwapStatus::Exchanging,
+            "sending" => SwapStatus::Sending,
+            "funds_received" => SwapStatus::FundsReceived,
+            "completed" => SwapStatus::Completed,
+            "failed" => SwapStatus::Failed,
+            "refunded" => SwapStatus::Refunded,
+            "expired" => SwapStatus::Expired,
+            _ => return None,
+        })
+    }
+}
+
+impl std::fmt::Display for SwapStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 impl Default for SwapStatus {
     fn default() -> Self {
         SwapStatus::Waiting
     }
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct SwapStatusResponse {
     pub swap_id: String,
     pub provider: String,
@@ -417,19 +782,31 @@ pub struct SwapStatusResponse {
     pub status: SwapStatus,
     pub from: String,
     pub to: String,
-    pub amount: f64,
+    #[serde(with = "rust_decimal::serde::float")]
+    #[schemars(with = "f64")]
+    pub amount: Decimal,
     pub deposit_address: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub deposit_extra_id: Option<String>,
     pub recipient_address: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub recipient_extra_id: Option<String>,
-    pub rate: f64,
-    pub estimated_receive: f64,
+    #[serde(with = "rust_decimal::serde::float")]
+    #[schemars(with = "f64")]
+    pub rate: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    #[schemars(with = "f64")]
+    pub estimated_receive: Decimal,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub actual_receive: Option<f64>,
-    pub network_fee: f64,
-    pub total_fee: f64,
+    #[serde(with = "rust_decimal::serde::float_option")]
+    #[schemars(with = "Option<f64>")]
+    pub actual_receive: Option<Decimal>,
+    #[serde(with = "rust_decimal::serde::float")]
+    #[schemars(with = "f64")]
+    pub network_fee: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    #[schemars(with = "f64")]
+    pub total_fee: Decimal,
     pub rate_type: RateType,
     pub is_sandbox: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -444,13 +821,153 @@ pub struct SwapStatusResponse {
     pub expires_at: Option<DateTime<Utc>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub completed_at: Option<DateTime<Utc>>,
+    /// Our payout of a funded swap, while it is queued or being sent
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payout: Option<PayoutProgress>,
+    /// What a failed or refunded swap's deposit comes back as, fee by fee
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub refund: Option<RefundBreakdown>,
+    /// Every status the swap has been in, oldest first
+    pub history: Vec<StatusHistoryEntry>,
+    /// When an in-flight swap is likely to complete, from how long recent
+    /// swaps of the pair took from the current stage
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub eta: Option<SwapEta>,
+    #[serde(flatten)]
+    pub explorer: SwapExplorerLinks,
+}
+
+/// Predicted completion window of an in-flight swap
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+pub struct SwapEta {
+    /// The stage the estimate starts from
+    pub stage: SwapStatus,
+    /// A quarter of similar swaps had completed by then
+    pub earliest_at: DateTime<Utc>,
+    /// Half of similar swaps had completed by then
+    pub expected_at: DateTime<Utc>,
+    /// Three quarters of similar swaps had completed by then
+    pub latest_at: DateTime<Utc>,
+    /// Minutes until `expected_at`, rounded up
+    pub remaining_minutes: i64,
+    /// Completed swaps the estimate is based on
+    pub samples: usize,
+    /// `pair_provider` when based on swaps of this pair with the same
+    /// provider, `pair` when on the pair across providers
+    pub basis: String,
+}
+
+/// Where a funded swap's payout stands in our payout queue
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct PayoutProgress {
+    /// `queued` or `sending`
+    pub state: String,
+    /// `standard` or `high`
+    pub lane: String,
+    /// 1 = sent next on this network
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub queue_position: Option<usize>,
+    /// Payouts waiting on this network, including this one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub queue_length: Option<usize>,
+}
+
+/// How a refund gets from the deposit to the amount sent back
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct RefundBreakdown {
+    /// The deposit being refunded
+    #[serde(with = "rust_decimal::serde::float")]
+    #[schemars(with = "f64")]
+    pub original_amount: Decimal,
+    pub currency: String,
+    /// Fee recorded on the swap
+    #[serde(with = "rust_decimal::serde::float")]
+    #[schemars(with = "f64")]
+    pub swap_fee: Decimal,
+    /// Platform fee on the swap; deducted unless `platform_fee_waived`
+    #[serde(with = "rust_decimal::serde::float")]
+    #[schemars(with = "f64")]
+    pub platform_fee: Decimal,
+    pub platform_fee_waived: bool,
+    /// Network fee of the refund transaction; an estimate until it is sent
+    #[serde(with = "rust_decimal::serde::float")]
+    #[schemars(with = "f64")]
+    pub network_fee: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    #[schemars(with = "f64")]
+    pub refund_amount: Decimal,
+    /// `estimated` until a refund is queued, then the refund's own status
+    pub status: String,
+    /// Set once the refund transaction is broadcast
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tx_hash: Option<String>,
+}
+
+/// One status transition
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+pub struct StatusHistoryEntry {
+    pub status: SwapStatus,
+    /// `user`, `provider`, `listener`, `monitor`, `payout`, `admin` or `system`
+    pub actor: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    pub at: DateTime<Utc>,
+    /// Time spent in this status; absent for the current one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration_secs: Option<f64>,
+}
+
+/// Block explorer links for the swap's addresses and transactions.
+/// Deposit side resolves on the `from` network, payout side on `to`.
+#[derive(Debug, Default, Serialize, JsonSchema)]
+pub struct SwapExplorerLinks {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deposit_address_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recipient_address_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tx_in_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tx_out_url: Option<String>,
+}
+
+impl SwapExplorerLinks {
+    /// Links for the deposit address and incoming transaction
+    pub fn deposit(
+        mut self,
+        registry: &ExplorerRegistry,
+        currency: &str,
+        network: &str,
+        address: &str,
+        tx_hash: Option<&str>,
+    ) -> Self {
+        let chain = chain_key(currency, network);
+        self.deposit_address_url = registry.address_url(&chain, address);
+        self.tx_in_url = tx_hash.and_then(|tx| registry.tx_url(&chain, tx));
+        self
+    }
+
+    /// Links for the recipient address and outgoing transaction
+    pub fn payout(
+        mut self,
+        registry: &ExplorerRegistry,
+        currency: &str,
+        network: &str,
+        address: &str,
+        tx_hash: Option<&str>,
+    ) -> Self {
+        let chain = chain_key(currency, network);
+        self.recipient_address_url = registry.address_url(&chain, address);
+        self.tx_out_url = tx_hash.and_then(|tx| registry.tx_url(&chain, tx));
+        self
+    }
 }
 
 // =============================================================================
 // SWAP HISTORY (Keyset Pagination)
 // =============================================================================
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct HistoryQuery {
     // Keyset pagination
     pub cursor: Option<String>,
@@ -462,12 +979,18 @@ pub struct HistoryQuery {
     pub from_currency: Option<String>,
     pub to_currency: Option<String>,
     pub provider: Option<String>,
+    pub swap_type: Option<SwapType>,
+    /// Exact match on the integrator's client_reference
+    pub client_reference: Option<String>,
     pub date_from: Option<String>,  // ISO 8601
     pub date_to: Option<String>,    // ISO 8601
     
     // Sorting (optional)
     pub sort_by: Option<String>,
     pub sort_order: Option<String>,
+
+    // Comma-separated swap fields to return (e.g. "id,status,created_at")
+    pub fields: Option<String>,
 }
 
 fn default_limit() -> u32 { 20 }
@@ -483,7 +1006,7 @@ pub struct HistoryCursor {
     pub to_currency: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct SwapSummary {
     pub id: String,
     pub status: SwapStatus,
@@ -491,31 +1014,48 @@ pub struct SwapSummary {
     pub from_network: String,
     pub to_currency: String,
     pub to_network: String,
-    pub amount: f64,
-    pub estimated_receive: f64,
+    #[serde(with = "rust_decimal::serde::float")]
+    #[schemars(with = "f64")]
+    pub amount: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    #[schemars(with = "f64")]
+    pub estimated_receive: Decimal,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub actual_receive: Option<f64>,
-    pub rate: f64,
-    pub platform_fee: f64,
-    pub total_fee: f64,
+    #[serde(with = "rust_decimal::serde::float_option")]
+    #[schemars(with = "Option<f64>")]
+    pub actual_receive: Option<Decimal>,
+    #[serde(with = "rust_decimal::serde::float")]
+    #[schemars(with = "f64")]
+    pub rate: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    #[schemars(with = "f64")]
+    pub platform_fee: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    #[schemars(with = "f64")]
+    pub total_fee: Decimal,
     pub deposit_address: String,
     pub recipient_address: String,
     pub provider: String,
     pub rate_type: RateType,
+    pub swap_type: SwapType,
     pub is_sandbox: bool,
     pub created_at: DateTime<Utc>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub completed_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_reference: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<serde_json::Map<String, serde_json::Value>>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct HistoryResponse {
     pub swaps: Vec<SwapSummary>,
     pub pagination: PaginationInfo,
     pub filters_applied: FiltersApplied,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct PaginationInfo {
     pub limit: u32,
     pub has_more: bool,
@@ -523,7 +1063,7 @@ pub struct PaginationInfo {
     pub next_cursor: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct FiltersApplied {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub status: Option<String>,
@@ -534,6 +1074,10 @@ pub struct FiltersApplied {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub provider: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub swap_type: Option<SwapType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_reference: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub date_from: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub date_to: Option<String>,
@@ -543,14 +1087,53 @@ pub struct FiltersApplied {
 // ADDRESS VALIDATION
 // =============================================================================
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 pub struct ValidateAddressRequest {
     pub ticker: String,
     pub network: String,
     pub address: String,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct AddressSpecQuery {
+    /// Network the address is on (e.g. ERC20, TRC20); the coin's own chain
+    /// when omitted
+    pub network: Option<String>,
+    /// Hint language (en, es, fr, de, pt); Accept-Language when omitted
+    pub lang: Option<String>,
+}
+
+/// Address format of one currency, as /swap/validate-address checks it
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct AddressSpecResponse {
+    pub ticker: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub network: Option<String>,
+    /// Regular expression valid in both Rust and JavaScript
+    pub pattern: String,
+    /// Every valid address starts with one of these; empty when any start is allowed
+    pub prefixes: Vec<String>,
+    pub min_length: usize,
+    pub max_length: usize,
+    pub case_sensitive: bool,
+    pub example: String,
+    pub hint: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<MemoSpec>,
+    /// Language of the hints
+    pub lang: String,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct MemoSpec {
+    /// What the chain calls it (Destination Tag, Memo, ...)
+    pub name: String,
+    pub pattern: String,
+    pub max_length: usize,
+    pub hint: String,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct ValidateAddressResponse {
     pub valid: bool,
     pub ticker: String,
@@ -558,11 +1141,292 @@ pub struct ValidateAddressResponse {
     pub address: String,
 }
 
+// =============================================================================
+// ADMIN SEARCH
+// =============================================================================
+
+/// At least one criterion is required; several narrow the results.
+/// Addresses and hashes may be truncated: `0x1234abcd...9f8e` or `0x1234abcd*`.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct SwapSearchQuery {
+    /// Deposit, payout (our) or refund address
+    pub address: Option<String>,
+    /// Deposit, provider payout or our payout transaction hash
+    pub tx_hash: Option<String>,
+    /// Account email of the swap's owner
+    pub email: Option<String>,
+    /// 1-50 (default 20)
+    pub limit: Option<u32>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct SwapSearchResult {
+    pub id: String,
+    pub status: SwapStatus,
+    /// Fields that matched, e.g. `deposit_address`, `payout_tx_hash`
+    pub matched: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_email: Option<String>,
+    pub provider: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provider_swap_id: Option<String>,
+    pub from_currency: String,
+    pub from_network: String,
+    pub to_currency: String,
+    pub to_network: String,
+    #[serde(with = "rust_decimal::serde::float")]
+    #[schemars(with = "f64")]
+    pub amount: Decimal,
+    pub deposit_address: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deposit_extra_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub refund_address: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tx_hash_in: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tx_hash_out: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payout_tx_hash: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct SwapSearchResponse {
+    pub results: Vec<SwapSearchResult>,
+    /// True when a value was matched by prefix rather than exactly
+    pub partial: bool,
+}
+
+// =============================================================================
+// SWAP DEBUG - Our record of a swap next to the provider's own responses
+// =============================================================================
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ProviderPayloadResponse {
+    pub id: i64,
+    pub provider: String,
+    pub kind: PayloadKind,
+    pub captured_at: DateTime<Utc>,
+    /// Size of the response as received
+    pub original_bytes: u32,
+    /// When true, `payload` is `{"truncated", "original_bytes", "prefix"}`
+    /// holding the start of the response as text
+    pub truncated: bool,
+    pub payload: serde_json::Value,
+}
+
+impl From<ProviderPayload> for ProviderPayloadResponse {
+    fn from(p: ProviderPayload) -> Self {
+        let payload = serde_json::from_str(&p.payload).unwrap_or(serde_json::Value::String(p.payload));
+        Self {
+            id: p.id,
+            provider: p.provider,
+            kind: p.kind,
+            captured_at: p.created_at,
+            original_bytes: p.original_bytes,
+            truncated: p.truncated,
+            payload,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct SwapDebugResponse {
+    pub swap_id: String,
+    pub status: SwapStatus,
+    pub provider: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provider_swap_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub history: Vec<StatusHistoryEntry>,
+    /// Create and status responses from the provider, oldest first.
+    /// Repeated status responses are stored once.
+    pub payloads: Vec<ProviderPayloadResponse>,
+}
+
+// =============================================================================
+// STUCK SWAPS - Swaps past their status SLA, for the admin queue
+// =============================================================================
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct StuckSwapsQuery {
+    /// Only swaps stuck in this status
+    pub status: Option<SwapStatus>,
+    /// 1-500 (default 100)
+    pub limit: Option<u32>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct StuckSwapResponse {
+    pub swap_id: String,
+    pub status: SwapStatus,
+    pub provider: String,
+    /// When the swap entered its current status
+    pub entered_at: DateTime<Utc>,
+    pub minutes_in_status: i64,
+    /// SLA for the status when the breach was detected
+    pub sla_minutes: u32,
+    /// Last escalation step taken
+    pub escalation: SlaEscalation,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ticket_reference: Option<String>,
+    pub detected_at: DateTime<Utc>,
+    pub escalated_at: DateTime<Utc>,
+}
+
+impl From<StuckSwap> for StuckSwapResponse {
+    fn from(s: StuckSwap) -> Self {
+        Self {
+            minutes_in_status: (Utc::now() - s.entered_at).num_minutes(),
+            swap_id: s.swap_id,
+            status: s.status,
+            provider: s.provider_id,
+            entered_at: s.entered_at,
+            sla_minutes: s.sla_minutes,
+            escalation: s.escalation,
+            ticket_reference: s.ticket_reference,
+            detected_at: s.detected_at,
+            escalated_at: s.escalated_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct StuckSwapsResponse {
+    /// Longest-stuck first
+    pub swaps: Vec<StuckSwapResponse>,
+}
+
+// =============================================================================
+// QUOTE KEY - Public key white-label frontends verify quotes with
+// =============================================================================
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct QuoteKeyResponse {
+    pub algorithm: String,
+    /// Matches `key_id` on every quote signed with this key
+    pub key_id: String,
+    /// Hex Ed25519 public key
+    pub public_key: String,
+    /// How long a quote can be redeemed after it is issued
+    pub ttl_seconds: i64,
+}
+
+// =============================================================================
+// RECEIPT - What a completed swap cost and delivered
+// =============================================================================
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ReceiptFormat {
+    #[default]
+    Json,
+    Pdf,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ReceiptQuery {
+    /// Receipt token from an earlier receipt; lets the receipt be fetched
+    /// without signing in
+    pub token: Option<String>,
+    #[serde(default)]
+    pub format: ReceiptFormat,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct SwapReceipt {
+    pub swap_id: String,
+    pub provider: String,
+    pub from: String,
+    pub network_from: String,
+    pub to: String,
+    pub network_to: String,
+    #[serde(with = "rust_decimal::serde::float")]
+    #[schemars(with = "f64")]
+    pub deposit_amount: Decimal,
+    /// What was delivered to `recipient_address`
+    #[serde(with = "rust_decimal::serde::float")]
+    #[schemars(with = "f64")]
+    pub received_amount: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    #[schemars(with = "f64")]
+    pub rate: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    #[schemars(with = "f64")]
+    pub network_fee: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    #[schemars(with = "f64")]
+    pub provider_fee: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    #[schemars(with = "f64")]
+    pub platform_fee: Decimal,
+    #[serde(with = "rust_decimal::serde::float")]
+    #[schemars(with = "f64")]
+    pub total_fee: Decimal,
+    pub recipient_address: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deposit_tx_hash: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payout_tx_hash: Option<String>,
+    pub is_sandbox: bool,
+    pub created_at: DateTime<Utc>,
+    pub completed_at: DateTime<Utc>,
+    pub issued_at: DateTime<Utc>,
+    /// Hex SHA-256 of the receipt without `issued_at` and `receipt_token`;
+    /// the same for every copy of this swap's receipt
+    pub verification_hash: String,
+    /// Fetches this receipt without signing in:
+    /// GET /swap/{id}/receipt?token=...
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub receipt_token: Option<String>,
+}
+
+// =============================================================================
+// ADDRESS PROOF - Signature showing our wallet holds a swap's address
+// =============================================================================
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct AddressProofRequest {
+    /// Nonce chosen by the verifier, 8-128 printable ASCII characters
+    pub challenge: String,
+}
+
+/// How the address's chain signs messages
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum AddressProofScheme {
+    /// Ethereum `personal_sign`; signature is `0x{r}{s}{v}`
+    Eip191,
+    /// Bitcoin `signmessage`; signature is base64
+    Bip137,
+    /// Solana; Ed25519 over the message bytes, signature is base58
+    Ed25519,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct AddressProofResponse {
+    pub swap_id: String,
+    /// Address our wallet receives the swap's funds at
+    pub address: String,
+    pub scheme: AddressProofScheme,
+    /// Exact text that was signed, including the challenge
+    pub message: String,
+    pub signature: String,
+    pub issued_at: DateTime<Utc>,
+}
+
 // =============================================================================
 // ERROR RESPONSE
 // =============================================================================
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct SwapErrorResponse {
     pub error: String,
     #[serde(skip_serializing_if = "Option::is_none")]
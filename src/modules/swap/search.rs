@@ -0,0 +1,410 @@
+//! Support lookups: find swaps from whatever the user still has, usually a
+//! transaction hash from their wallet or an explorer, a deposit address, or
+//! their account email.
+//!
+//! Every lookup is an indexed equality or prefix match on one column.
+//! Recipient addresses are encrypted at rest without a blind index, so they
+//! cannot be searched.
+
+use axum::http::StatusCode;
+use chrono::{DateTime, Utc};
+use sqlx::{MySql, Pool, Row};
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::schema::{SwapSearchQuery, SwapSearchResponse, SwapSearchResult, SwapStatus};
+use crate::services::pii::{email_index, SealedString};
+
+const DEFAULT_LIMIT: u32 = 20;
+const MAX_LIMIT: u32 = 50;
+
+/// Shortest prefix accepted for a partial match, so a stray `0x` can't scan
+/// half the table
+const MIN_PREFIX_LEN: usize = 6;
+
+// =============================================================================
+// SEARCH ERROR
+// =============================================================================
+
+#[derive(Debug)]
+pub enum SwapSearchError {
+    MissingCriteria,
+    InvalidTerm(String),
+    DatabaseError(String),
+}
+
+impl std::fmt::Display for SwapSearchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SwapSearchError::MissingCriteria => write!(f, "Provide at least one of address, tx_hash or email"),
+            SwapSearchError::InvalidTerm(msg) => write!(f, "Invalid search term: {}", msg),
+            SwapSearchError::DatabaseError(e) => write!(f, "Database error: {}", e),
+        }
+    }
+}
+
+impl SwapSearchError {
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            SwapSearchError::MissingCriteria | SwapSearchError::InvalidTerm(_) => StatusCode::BAD_REQUEST,
+            SwapSearchError::DatabaseError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+impl From<sqlx::Error> for SwapSearchError {
+    fn from(err: sqlx::Error) -> Self {
+        SwapSearchError::DatabaseError(err.to_string())
+    }
+}
+
+// =============================================================================
+// SEARCH TERMS
+// =============================================================================
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SearchTerm {
+    /// Candidate spellings of a full value, matched with `IN`
+    Exact(Vec<String>),
+    /// `LIKE` pattern for a truncated value, anchored at the start
+    Partial(String),
+}
+
+impl SearchTerm {
+    /// Parse an address or hash as pasted by support. Whitespace is dropped;
+    /// `abc...xyz` / `abc…xyz` (explorer truncation) and `abc*` match by
+    /// prefix. Hex hashes are also tried with `0x` added or removed.
+    pub fn parse(raw: &str) -> Result<Self, SwapSearchError> {
+        let value: String = raw.chars().filter(|c| !c.is_whitespace()).collect();
+        if value.is_empty() {
+            return Err(SwapSearchError::InvalidTerm("empty value".to_string()));
+        }
+
+        let truncated = value
+            .split_once("...")
+            .or_else(|| value.split_once('…'))
+            .map(|(prefix, suffix)| (prefix.to_string(), suffix.trim_end_matches('*').to_string()))
+            .or_else(|| value.strip_suffix('*').map(|prefix| (prefix.to_string(), String::new())));
+
+        match truncated {
+            Some((prefix, suffix)) => {
+                if prefix.chars().count() < MIN_PREFIX_LEN {
+                    return Err(SwapSearchError::InvalidTerm(format!(
+                        "partial values need at least {} leading characters",
+                        MIN_PREFIX_LEN
+                    )));
+                }
+                Ok(SearchTerm::Partial(format!("{}%{}", escape_like(&prefix), escape_like(&suffix))))
+            }
+            None => Ok(SearchTerm::Exact(spellings(&value))),
+        }
+    }
+
+    /// Prefix retry for an exact value that matched nothing, e.g. the first
+    /// few characters of a hash pasted without a marker
+    pub fn fallback(&self) -> Option<SearchTerm> {
+        match self {
+            SearchTerm::Exact(values) => {
+                let value = values.first()?;
+                (value.chars().count() >= MIN_PREFIX_LEN)
+                    .then(|| SearchTerm::Partial(format!("{}%", escape_like(value))))
+            }
+            SearchTerm::Partial(_) => None,
+        }
+    }
+
+    fn is_partial(&self) -> bool {
+        matches!(self, SearchTerm::Partial(_))
+    }
+}
+
+fn spellings(value: &str) -> Vec<String> {
+    let mut values = vec![value.to_string()];
+    if let Some(bare) = value.strip_prefix("0x").or_else(|| value.strip_prefix("0X")) {
+        if !bare.is_empty() && bare.chars().all(|c| c.is_ascii_hexdigit()) {
+            values.push(bare.to_string());
+        }
+    } else if value.len() == 64 && value.chars().all(|c| c.is_ascii_hexdigit()) {
+        values.push(format!("0x{}", value));
+    }
+    values
+}
+
+fn escape_like(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '\\' | '%' | '_') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// One searchable column and the name reported when it matches
+struct Lookup {
+    table: &'static str,
+    key: &'static str,
+    column: &'static str,
+    field: &'static str,
+}
+
+const ADDRESS_LOOKUPS: &[Lookup] = &[
+    Lookup { table: "swaps", key: "id", column: "deposit_address", field: "deposit_address" },
+    Lookup { table: "swap_address_info", key: "swap_id", column: "our_address", field: "our_address" },
+    Lookup { table: "swaps", key: "id", column: "refund_address", field: "refund_address" },
+];
+
+const TX_HASH_LOOKUPS: &[Lookup] = &[
+    Lookup { table: "swaps", key: "id", column: "tx_hash_in", field: "tx_hash_in" },
+    Lookup { table: "swaps", key: "id", column: "tx_hash_out", field: "tx_hash_out" },
+    Lookup { table: "swap_address_info", key: "swap_id", column: "payout_tx_hash", field: "payout_tx_hash" },
+];
+
+/// Swap id -> fields that matched
+type Matches = BTreeMap<String, BTreeSet<&'static str>>;
+
+/// Swaps matching every criterion, with the union of their matched fields
+fn intersect(criteria: Vec<Matches>) -> Matches {
+    let mut criteria = criteria.into_iter();
+    let Some(mut result) = criteria.next() else {
+        return Matches::new();
+    };
+    for matches in criteria {
+        result.retain(|id, _| matches.contains_key(id));
+        for (id, fields) in result.iter_mut() {
+            fields.extend(&matches[id]);
+        }
+    }
+    result
+}
+
+// =============================================================================
+// SWAP SEARCH
+// =============================================================================
+
+pub struct SwapSearch {
+    pool: Pool<MySql>,
+}
+
+impl SwapSearch {
+    pub fn new(pool: Pool<MySql>) -> Self {
+        Self { pool }
+    }
+
+    pub async fn search(&self, query: &SwapSearchQuery) -> Result<SwapSearchResponse, SwapSearchError> {
+        let provided = |v: &Option<String>| v.as_deref().map(str::trim).filter(|v| !v.is_empty()).map(str::to_string);
+        let (address, tx_hash, email) = (provided(&query.address), provided(&query.tx_hash), provided(&query.email));
+        if address.is_none() && tx_hash.is_none() && email.is_none() {
+            return Err(SwapSearchError::MissingCriteria);
+        }
+        let limit = query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+
+        let mut criteria = Vec::new();
+        let mut partial = false;
+        for (value, lookups) in [(address, ADDRESS_LOOKUPS), (tx_hash, TX_HASH_LOOKUPS)] {
+            let Some(value) = value else { continue };
+            let term = SearchTerm::parse(&value)?;
+
+            let mut matches = self.lookup(lookups, &term).await?;
+            partial |= term.is_partial();
+            if matches.is_empty() {
+                if let Some(fallback) = term.fallback() {
+                    matches = self.lookup(lookups, &fallback).await?;
+                    partial = true;
+                }
+            }
+            criteria.push(matches);
+        }
+        if let Some(email) = email {
+            criteria.push(self.by_email(&email).await?);
+        }
+
+        // Criteria are collected whole and cut to the limit only once
+        // intersected, so a swap further down one list isn't lost
+        let matches = intersect(criteria);
+        let results = self.load(&matches, limit).await?;
+        Ok(SwapSearchResponse { results, partial })
+    }
+
+    async fn lookup(&self, lookups: &[Lookup], term: &SearchTerm) -> Result<Matches, SwapSearchError> {
+        let mut matches = Matches::new();
+
+        for lookup in lookups {
+            let ids: Vec<String> = match term {
+                SearchTerm::Exact(values) => {
+                    let placeholders = vec!["?"; values.len()].join(", ");
+                    let sql = format!(
+                        "SELECT {} FROM {} WHERE {} IN ({})",
+                        lookup.key, lookup.table, lookup.column, placeholders
+                    );
+                    let mut q = sqlx::query_scalar(&sql);
+                    for value in values {
+                        q = q.bind(value);
+                    }
+                    q.fetch_all(&self.pool).await?
+                }
+                SearchTerm::Partial(pattern) => {
+                    let sql = format!(
+                        "SELECT {} FROM {} WHERE {} LIKE ?",
+                        lookup.key, lookup.table, lookup.column
+                    );
+                    sqlx::query_scalar(&sql).bind(pattern).fetch_all(&self.pool).await?
+                }
+            };
+
+            for id in ids {
+                matches.entry(id).or_default().insert(lookup.field);
+            }
+        }
+
+        Ok(matches)
+    }
+
+    async fn by_email(&self, email: &str) -> Result<Matches, SwapSearchError> {
+        if !email.contains('@') {
+            return Err(SwapSearchError::InvalidTerm("email must contain @".to_string()));
+        }
+
+        let ids: Vec<String> = sqlx::query_scalar(
+            r#"
+            SELECT s.id FROM swaps s
+            JOIN users u ON u.id = s.user_id
+            WHERE u.email_hash = ? OR u.email = ?
+            "#,
+        )
+        .bind(email_index(email))
+        .bind(email.to_lowercase())
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(ids.into_iter().map(|id| (id, BTreeSet::from(["email"]))).collect())
+    }
+
+    async fn load(&self, matches: &Matches, limit: u32) -> Result<Vec<SwapSearchResult>, SwapSearchError> {
+        if matches.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let placeholders = vec!["?"; matches.len()].join(", ");
+        let sql = format!(
+            r#"
+            SELECT s.id, s.status, s.user_id, u.email as user_email, s.provider_id, s.provider_swap_id,
+                   s.from_currency, s.from_network, s.to_currency, s.to_network,
+                   s.amount, s.deposit_address, s.deposit_extra_id,
+                   s.refund_address, s.tx_hash_in, s.tx_hash_out, sai.payout_tx_hash,
+                   s.created_at, s.updated_at
+            FROM swaps s
+            LEFT JOIN users u ON u.id = s.user_id
+            LEFT JOIN swap_address_info sai ON sai.swap_id = s.id
+            WHERE s.id IN ({})
+            ORDER BY s.created_at DESC
+            LIMIT ?
+            "#,
+            placeholders
+        );
+
+        let mut q = sqlx::query(&sql);
+        for id in matches.keys() {
+            q = q.bind(id);
+        }
+        let rows = q.bind(limit).fetch_all(&self.pool).await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let id: String = row.get("id");
+                let matched = matches
+                    .get(&id)
+                    .map(|fields| fields.iter().map(|f| f.to_string()).collect())
+                    .unwrap_or_default();
+                SwapSearchResult {
+                    status: row.get::<SwapStatus, _>("status"),
+                    matched,
+                    user_id: row.get("user_id"),
+                    user_email: row.get::<Option<SealedString>, _>("user_email").map(Into::into),
+                    provider: row.get("provider_id"),
+                    provider_swap_id: row.get("provider_swap_id"),
+                    from_currency: row.get("from_currency"),
+                    from_network: row.get("from_network"),
+                    to_currency: row.get("to_currency"),
+                    to_network: row.get("to_network"),
+                    amount: row.get("amount"),
+                    deposit_address: row.get("deposit_address"),
+                    deposit_extra_id: row.get("deposit_extra_id"),
+                    refund_address: row.get("refund_address"),
+                    tx_hash_in: row.get("tx_hash_in"),
+                    tx_hash_out: row.get("tx_hash_out"),
+                    payout_tx_hash: row.get("payout_tx_hash"),
+                    created_at: row.get::<DateTime<Utc>, _>("created_at"),
+                    updated_at: row.get::<DateTime<Utc>, _>("updated_at"),
+                    id,
+                }
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HASH: &str = "5c504ed432cb51138bcf09aa5e8a410dd4a1e204ef84bfed1be16dfba1b22060";
+
+    #[test]
+    fn test_exact_hash_spellings() {
+        let with_prefix = format!("0x{}", HASH);
+        assert_eq!(
+            SearchTerm::parse(&format!(" {} \n", with_prefix)).unwrap(),
+            SearchTerm::Exact(vec![with_prefix.clone(), HASH.to_string()])
+        );
+        assert_eq!(
+            SearchTerm::parse(HASH).unwrap(),
+            SearchTerm::Exact(vec![HASH.to_string(), with_prefix])
+        );
+
+        // Non-hex values are left alone
+        let address = "bc1qxy2kgdygjrsqtzq2n0yrf2493p83kkfjhx0wlh";
+        assert_eq!(SearchTerm::parse(address).unwrap(), SearchTerm::Exact(vec![address.to_string()]));
+    }
+
+    #[test]
+    fn test_truncated_values() {
+        assert_eq!(
+            SearchTerm::parse("0x5c504ed4...1b22060").unwrap(),
+            SearchTerm::Partial("0x5c504ed4%1b22060".to_string())
+        );
+        assert_eq!(
+            SearchTerm::parse("0x5c504ed4…1b22060").unwrap(),
+            SearchTerm::Partial("0x5c504ed4%1b22060".to_string())
+        );
+        assert_eq!(SearchTerm::parse("TXyz_12*").unwrap(), SearchTerm::Partial("TXyz\\_12%".to_string()));
+
+        assert!(matches!(SearchTerm::parse("0x5c..."), Err(SwapSearchError::InvalidTerm(_))));
+        assert!(matches!(SearchTerm::parse("  "), Err(SwapSearchError::InvalidTerm(_))));
+    }
+
+    #[test]
+    fn test_prefix_fallback() {
+        let term = SearchTerm::parse("0x5c504ed4").unwrap();
+        assert_eq!(term.fallback(), Some(SearchTerm::Partial("0x5c504ed4%".to_string())));
+        assert_eq!(SearchTerm::parse("abc").unwrap().fallback(), None);
+        assert_eq!(SearchTerm::parse("0x5c504ed4*").unwrap().fallback(), None);
+    }
+
+    #[test]
+    fn test_criteria_are_intersected() {
+        let mut by_address = Matches::new();
+        by_address.entry("a".to_string()).or_default().insert("deposit_address");
+        by_address.entry("b".to_string()).or_default().insert("deposit_address");
+        let mut by_hash = Matches::new();
+        by_hash.entry("b".to_string()).or_default().insert("tx_hash_in");
+        by_hash.entry("c".to_string()).or_default().insert("tx_hash_in");
+
+        let result = intersect(vec![by_address.clone(), by_hash]);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result["b"], BTreeSet::from(["deposit_address", "tx_hash_in"]));
+
+        assert_eq!(intersect(vec![by_address.clone()]), by_address);
+        assert!(intersect(vec![]).is_empty());
+    }
+}
@@ -0,0 +1,9 @@
+//! API v2 handlers. Only endpoints whose v2 shape differs from v1 live
+//! here; each is declared in the route manifest with
+//! `.version(ApiVersion::V2)` and every other /v2 path is served by its v1
+//! handler. Handlers reuse the crud and services of the module they
+//! replace, so both versions read and write the same data.
+
+pub mod routes;
+
+pub use routes::v2_routes;
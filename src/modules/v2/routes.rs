@@ -0,0 +1,11 @@
+use axum::Router;
+use std::sync::Arc;
+
+use crate::AppState;
+
+/// Mounted under /v2. Empty until the first endpoint moves over; handlers
+/// are mounted by their v2 declaration through `DeclaredRoutes`, as in the
+/// v1 route modules.
+pub fn v2_routes() -> Router<Arc<AppState>> {
+    Router::new()
+}
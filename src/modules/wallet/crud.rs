@@ -1,5 +1,11 @@
 use sqlx::{MySql, Pool};
-use crate::modules::wallet::model::SwapAddressInfo;
+use crate::modules::wallet::model::{PayoutConfirmation, SwapAddressInfo, TrackedPayout};
+use crate::services::explorer::chain_key;
+use crate::services::gas::{GasHistory, PayoutGas};
+use crate::services::pii::SealedString;
+use crate::services::pricing::PayoutSplit;
+use crate::services::token::registry::CanonicalToken;
+use crate::services::token::TokenRegistry;
 
 #[derive(Clone)]
 pub struct WalletCrud {
@@ -12,9 +18,17 @@ impl WalletCrud {
     }
 
     /// Get the next available address index by finding the maximum index used
+    /// Indexes reserved by provider order intents count as used, so an
+    /// orphaned provider order never shares a payout address with a new swap
     pub async fn get_next_index(&self) -> Result<u32, sqlx::Error> {
         let result: (Option<u32>,) = sqlx::query_as(
-            "SELECT MAX(address_index) FROM swap_address_info"
+            r#"
+            SELECT MAX(address_index) FROM (
+                SELECT address_index FROM swap_address_info
+                UNION ALL
+                SELECT address_index FROM provider_order_intents
+            ) used
+            "#
         )
         .fetch_one(&self.pool)
         .await?;
@@ -32,6 +46,32 @@ impl WalletCrud {
         user_recipient_address: &str,
         user_recipient_extra_id: Option<&str>,
     ) -> Result<(), sqlx::Error> {
+        Self::insert_address_info(
+            &self.pool,
+            swap_id,
+            our_address,
+            address_index,
+            network,
+            user_recipient_address,
+            user_recipient_extra_id,
+        )
+        .await
+    }
+
+    /// Same as `save_address_info` but on a caller-supplied executor, so the
+    /// insert can share a transaction with the swap row
+    pub async fn insert_address_info<'e, E>(
+        executor: E,
+        swap_id: &str,
+        our_address: &str,
+        address_index: u32,
+        network: &str,
+        user_recipient_address: &str,
+        user_recipient_extra_id: Option<&str>,
+    ) -> Result<(), sqlx::Error>
+    where
+        E: sqlx::Executor<'e, Database = MySql>,
+    {
         let coin_type = match network.to_lowercase().as_str() {
             "bitcoin" => 0,
             "ethereum" | "polygon" | "bsc" | "arbitrum" | "optimism" | "erc20" | "bep20" => 60,
@@ -53,9 +93,9 @@ impl WalletCrud {
         .bind(address_index)
         .bind(1) // Default blockchain_id for now
         .bind(coin_type)
-        .bind(user_recipient_address)
+        .bind(SealedString(user_recipient_address.to_string()))
         .bind(user_recipient_extra_id)
-        .execute(&self.pool)
+        .execute(executor)
         .await?;
 
         Ok(())
@@ -71,13 +111,35 @@ impl WalletCrud {
         .await
     }
 
-    /// Update payout status with actual amounts
+    /// The ERC-20 token a swap pays out, or `None` for the chain's native coin
+    pub async fn payout_token(&self, swap_id: &str) -> Result<Option<CanonicalToken>, String> {
+        let asset: Option<(String, String)> = sqlx::query_as("SELECT to_currency, to_network FROM swaps WHERE id = ?")
+            .bind(swap_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| e.to_string())?;
+        let Some((currency, network)) = asset else {
+            return Ok(None);
+        };
+
+        TokenRegistry::new(self.pool.clone())
+            .canonical_token(&currency, &chain_key(&currency, &network))
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    /// Record the gas a broadcast payout spent in gas_history
+    pub async fn record_payout_gas(&self, gas: &PayoutGas) -> Result<(), sqlx::Error> {
+        GasHistory::new(self.pool.clone()).record_payout(gas).await
+    }
+
+    /// Update payout status with the amounts actually paid out. The
+    /// transaction tracker takes it from there to confirmation.
     pub async fn mark_payout_completed(
         &self,
         swap_id: &str,
         tx_hash: &str,
-        actual_received: f64,
-        commission_taken: f64,
+        split: &PayoutSplit,
     ) -> Result<(), sqlx::Error> {
         sqlx::query(
             r#"
@@ -85,15 +147,89 @@ impl WalletCrud {
             SET status = 'success', 
                 payout_tx_hash = ?,
                 payout_amount = ?,
+                commission_taken = ?,
                 commission_rate = ?,
                 broadcast_at = NOW(),
-                confirmed_at = NOW()
+                confirmation_status = 'pending'
             WHERE swap_id = ?
             "#
         )
         .bind(tx_hash)
-        .bind(actual_received)
-        .bind(if actual_received > 0.0 { commission_taken / actual_received } else { 0.0 })
+        .bind(split.payout)
+        .bind(split.platform_fee)
+        .bind(split.commission_rate())
+        .bind(swap_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Payouts not yet final, least recently checked first
+    pub async fn tracked_payouts(&self, limit: u32) -> Result<Vec<TrackedPayout>, sqlx::Error> {
+        sqlx::query_as::<_, TrackedPayout>(
+            r#"
+            SELECT sai.swap_id, sai.coin_type, sai.payout_tx_hash,
+                   CAST(sai.confirmation_status AS CHAR) as confirmation_status,
+                   s.to_currency, sai.broadcast_at
+            FROM swap_address_info sai
+            JOIN swaps s ON s.id = sai.swap_id
+            WHERE sai.confirmation_status IN ('pending', 'confirmed')
+              AND sai.payout_tx_hash IS NOT NULL
+            ORDER BY sai.last_tracked_at IS NOT NULL, sai.last_tracked_at
+            LIMIT ?
+            "#
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Move a payout from `from` to `to`. Returns false when another worker
+    /// moved it first. Dropped and reverted payouts are marked failed.
+    pub async fn record_payout_confirmation(
+        &self,
+        swap_id: &str,
+        from: PayoutConfirmation,
+        to: PayoutConfirmation,
+        confirmations: Option<u64>,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            UPDATE swap_address_info
+            SET confirmation_status = ?,
+                confirmations = COALESCE(?, confirmations),
+                confirmed_at = CASE ?
+                    WHEN 'pending' THEN NULL
+                    WHEN 'confirmed' THEN COALESCE(confirmed_at, NOW())
+                    WHEN 'finalized' THEN COALESCE(confirmed_at, NOW())
+                    ELSE confirmed_at
+                END,
+                finalized_at = IF(? = 'finalized', NOW(), finalized_at),
+                status = IF(? IN ('dropped', 'failed'), 'failed', status),
+                last_tracked_at = NOW()
+            WHERE swap_id = ? AND confirmation_status = ?
+            "#
+        )
+        .bind(to.as_str())
+        .bind(confirmations)
+        .bind(to.as_str())
+        .bind(to.as_str())
+        .bind(to.as_str())
+        .bind(swap_id)
+        .bind(from.as_str())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Note a check that left the payout where it was
+    pub async fn touch_payout_tracking(&self, swap_id: &str, confirmations: Option<u64>) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "UPDATE swap_address_info SET confirmations = COALESCE(?, confirmations), last_tracked_at = NOW() WHERE swap_id = ?"
+        )
+        .bind(confirmations)
         .bind(swap_id)
         .execute(&self.pool)
         .await?;
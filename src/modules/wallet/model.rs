@@ -1,6 +1,9 @@
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
 
+use crate::services::amount::Decimal;
+use crate::services::pii::SealedString;
+
 // =============================================================================
 // DATABASE MODELS
 // =============================================================================
@@ -12,16 +15,33 @@ pub struct SwapAddressInfo {
     pub address_index: u32,
     pub blockchain_id: i32,
     pub coin_type: i32,
+    #[sqlx(try_from = "SealedString")]
     pub recipient_address: String,
     pub recipient_extra_id: Option<String>,
     pub commission_rate: f64,
     pub payout_tx_hash: Option<String>,
-    pub payout_amount: Option<f64>,
+    #[serde(default, with = "rust_decimal::serde::float_option")]
+    pub payout_amount: Option<Decimal>,
     pub status: String,
     pub created_at: DateTime<Utc>,
     pub signed_at: Option<DateTime<Utc>>,
     pub broadcast_at: Option<DateTime<Utc>>,
     pub confirmed_at: Option<DateTime<Utc>>,
+    /// Unset until a payout is broadcast
+    pub confirmation_status: Option<PayoutConfirmation>,
+    pub confirmations: Option<u32>,
+    pub finalized_at: Option<DateTime<Utc>>,
+}
+
+/// A broadcast payout the transaction tracker is following
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct TrackedPayout {
+    pub swap_id: String,
+    pub coin_type: i32,
+    pub payout_tx_hash: String,
+    pub confirmation_status: PayoutConfirmation,
+    pub to_currency: String,
+    pub broadcast_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -63,6 +83,39 @@ pub enum PayoutStatus {
     Failed,
 }
 
+/// How far a broadcast payout has got on chain
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(rename_all = "lowercase")]
+pub enum PayoutConfirmation {
+    /// Broadcast, not yet in a block
+    Pending,
+    /// In a block, not yet deep enough to be final
+    Confirmed,
+    Finalized,
+    /// The network no longer knows the transaction
+    Dropped,
+    /// Mined, but the transaction reverted
+    Failed,
+}
+
+impl PayoutConfirmation {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PayoutConfirmation::Pending => "pending",
+            PayoutConfirmation::Confirmed => "confirmed",
+            PayoutConfirmation::Finalized => "finalized",
+            PayoutConfirmation::Dropped => "dropped",
+            PayoutConfirmation::Failed => "failed",
+        }
+    }
+
+    /// Whether the tracker is done with the payout
+    pub fn is_terminal(&self) -> bool {
+        !matches!(self, PayoutConfirmation::Pending | PayoutConfirmation::Confirmed)
+    }
+}
+
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, sqlx::Type)]
 #[sqlx(rename_all = "lowercase")]
 pub enum TxStatus {
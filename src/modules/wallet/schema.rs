@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use super::model::PayoutStatus;
+use crate::services::amount::Decimal;
 
 // =============================================================================
 // REQUESTS
@@ -33,8 +34,13 @@ pub struct WalletAddressResponse {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PayoutResponse {
     pub tx_hash: String,
-    pub amount: f64,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub amount: Decimal,
     pub status: PayoutStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tx_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address_url: Option<String>,
 }
 
 // =============================================================================
@@ -45,14 +51,16 @@ pub struct PayoutResponse {
 pub struct SwapExecution {
     pub swap_id: String,
     pub user_recipient_address: String,
-    pub amount_to_send: f64,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub amount_to_send: Decimal,
     pub chain: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TransactionData {
     pub to: String,
-    pub amount: f64,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub amount: Decimal,
     pub token: String,
     pub chain: String,
     #[serde(default)]
@@ -62,11 +70,21 @@ pub struct TransactionData {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EvmTransaction {
     pub to_address: String,
-    pub amount: f64,
+    /// In the native unit (ETH, not wei). Serialized as a decimal string.
+    pub amount: Decimal,
     pub token: String,
     pub chain_id: u32,
     pub nonce: u64,
     pub gas_price: u64,
+    #[serde(default = "default_evm_gas_limit")]
+    pub gas_limit: u64,
+    /// Hex call data; empty for a plain value transfer
+    #[serde(default)]
+    pub data: String,
+}
+
+fn default_evm_gas_limit() -> u64 {
+    21_000
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -99,6 +117,7 @@ pub struct SolanaTransaction {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PayoutRecord {
-    pub received_amount: f64,
+    #[serde(with = "rust_decimal::serde::float")]
+    pub received_amount: Decimal,
     pub tier: String,
 }
@@ -0,0 +1,217 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    Json,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+use validator::Validate;
+
+use super::crud::{callback_secret, CallbackCrud, WebhookApiError, WebhookCrud};
+use super::schema::{
+    CallbackErrorResponse, DeliveriesQuery, DeliveriesResponse, DeliveryAttemptResponse, EventTypeInfo,
+    EventTypesResponse, ProviderCallbackRequest, ProviderCallbackResponse, WebhookErrorResponse,
+};
+use crate::modules::auth::interface::{scope::{HistoryRead, SwapCreate}, Scoped};
+use crate::services::webhook::catalog::{EVENT_TYPES, LATEST_VERSION, SUPPORTED_VERSIONS};
+use crate::services::webhook::idempotency::{Claim, ConsumeError, Consumed, IdempotencyStore};
+use crate::services::webhook::{verify_signature, AttemptOutcome, RetryConfig, WebhookDispatcher, WebhookEvent};
+use crate::AppState;
+
+/// Callbacks signed further than this from our clock are refused
+const CALLBACK_TOLERANCE_SECS: i64 = 300;
+
+/// Tests and redeliveries one webhook may be sent per hour
+const MANUAL_SENDS_PER_HOUR: i64 = 30;
+
+const DEFAULT_DELIVERIES: u32 = 50;
+const MAX_DELIVERIES: u32 = 200;
+
+fn to_error(e: WebhookApiError) -> (StatusCode, Json<WebhookErrorResponse>) {
+    if e.status_code().is_server_error() {
+        tracing::error!("Webhook request failed: {}", e);
+    }
+    (e.status_code(), Json(WebhookErrorResponse::new(e.to_string())))
+}
+
+// =============================================================================
+// GET /webhooks/event-types - Published event types and their payload schemas
+// =============================================================================
+
+pub async fn list_event_types() -> Json<EventTypesResponse> {
+    let event_types = EVENT_TYPES
+        .iter()
+        .map(|t| EventTypeInfo {
+            name: t.name.to_string(),
+            version: t.version,
+            replaces: t.event.as_str().to_string(),
+            description: t.description.to_string(),
+            schema: t.schema(),
+        })
+        .collect();
+
+    Json(EventTypesResponse {
+        latest_version: LATEST_VERSION,
+        supported_versions: SUPPORTED_VERSIONS.to_vec(),
+        event_types,
+        unversioned: WebhookEvent::ALL.iter().map(|e| e.as_str().to_string()).collect(),
+    })
+}
+
+// =============================================================================
+// GET /webhooks/{id}/deliveries - Payloads sent to a webhook, newest first
+// =============================================================================
+
+pub async fn list_deliveries(
+    State(state): State<Arc<AppState>>,
+    user: Scoped<HistoryRead>,
+    Path(webhook_id): Path<String>,
+    Query(query): Query<DeliveriesQuery>,
+) -> Result<Json<DeliveriesResponse>, (StatusCode, Json<WebhookErrorResponse>)> {
+    let crud = WebhookCrud::new(state.db.clone());
+
+    let webhook = crud.get_owned(&user.0.id, &webhook_id).await.map_err(to_error)?;
+    let limit = query.limit.unwrap_or(DEFAULT_DELIVERIES).clamp(1, MAX_DELIVERIES);
+    let deliveries = crud.list_deliveries(&webhook.id.to_string(), limit).await.map_err(to_error)?;
+
+    Ok(Json(DeliveriesResponse { deliveries }))
+}
+
+// =============================================================================
+// POST /webhooks/{id}/test                       - Send a signed sample event
+// POST /webhooks/{id}/redeliver/{delivery_id}    - Replay a stored delivery
+// =============================================================================
+
+/// Both send while the request waits and answer with what the endpoint
+/// returned; a failing endpoint is still a 200 here. They bypass the
+/// webhook's circuit breaker, so they are capped per webhook instead.
+pub async fn test_webhook(
+    State(state): State<Arc<AppState>>,
+    user: Scoped<SwapCreate>,
+    Path(webhook_id): Path<String>,
+) -> Result<Json<DeliveryAttemptResponse>, (StatusCode, Json<WebhookErrorResponse>)> {
+    let crud = WebhookCrud::new(state.db.clone());
+
+    let webhook = crud.get_owned(&user.0.id, &webhook_id).await.map_err(to_error)?;
+    check_manual_sends(&crud, &webhook.id.to_string()).await.map_err(to_error)?;
+
+    let outcome = WebhookDispatcher::new(state.db.clone(), RetryConfig::default())
+        .send_test(&webhook, &user.0.id)
+        .await
+        .map_err(|e| to_error(e.into()))?;
+
+    Ok(Json(attempt_response(outcome)))
+}
+
+pub async fn redeliver(
+    State(state): State<Arc<AppState>>,
+    user: Scoped<SwapCreate>,
+    Path((webhook_id, delivery_id)): Path<(String, String)>,
+) -> Result<Json<DeliveryAttemptResponse>, (StatusCode, Json<WebhookErrorResponse>)> {
+    let crud = WebhookCrud::new(state.db.clone());
+
+    let webhook = crud.get_owned(&user.0.id, &webhook_id).await.map_err(to_error)?;
+    let delivery_id = Uuid::parse_str(&delivery_id).map_err(|_| to_error(WebhookApiError::DeliveryNotFound))?;
+    check_manual_sends(&crud, &webhook.id.to_string()).await.map_err(to_error)?;
+
+    let outcome = WebhookDispatcher::new(state.db.clone(), RetryConfig::default())
+        .redeliver(&webhook, delivery_id, &user.0.id)
+        .await
+        .map_err(|e| to_error(e.into()))?
+        .ok_or_else(|| to_error(WebhookApiError::DeliveryNotFound))?;
+
+    Ok(Json(attempt_response(outcome)))
+}
+
+async fn check_manual_sends(crud: &WebhookCrud, webhook_id: &str) -> Result<(), WebhookApiError> {
+    if crud.manual_attempts_last_hour(webhook_id).await? >= MANUAL_SENDS_PER_HOUR {
+        return Err(WebhookApiError::RateLimited);
+    }
+    Ok(())
+}
+
+fn attempt_response(outcome: AttemptOutcome) -> DeliveryAttemptResponse {
+    DeliveryAttemptResponse {
+        delivery_id: outcome.delivery_id.to_string(),
+        event_type: outcome.event_type,
+        succeeded: outcome.succeeded,
+        response_status: outcome.response_status,
+        response_time_ms: outcome.response_time_ms,
+        error: outcome.error_message,
+    }
+}
+
+// =============================================================================
+// POST /webhooks/providers/{provider} - Status callbacks from exchange providers
+// =============================================================================
+
+/// Signed like our outgoing webhooks: `X-Callback-Signature` is
+/// `sha256=<hex HMAC>` of `<X-Callback-Timestamp>.<body>` under the
+/// provider's secret. Each `event_id` is applied once; redeliveries get a
+/// 200 with `duplicate` set so the provider stops retrying.
+pub async fn provider_callback(
+    State(state): State<Arc<AppState>>,
+    Path(provider): Path<String>,
+    headers: HeaderMap,
+    body: String,
+) -> Result<Json<ProviderCallbackResponse>, (StatusCode, Json<CallbackErrorResponse>)> {
+    let provider = provider.trim().to_lowercase();
+    let secret = callback_secret(&provider)
+        .ok_or_else(|| callback_error(StatusCode::NOT_FOUND, "Unknown provider"))?;
+
+    let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+    let timestamp = header("x-callback-timestamp").and_then(|v| v.trim().parse::<i64>().ok());
+    let (Some(signature), Some(timestamp)) = (header("x-callback-signature"), timestamp) else {
+        return Err(callback_error(StatusCode::UNAUTHORIZED, "Missing callback signature"));
+    };
+    verify_signature(secret, signature.trim(), timestamp, &body, CALLBACK_TOLERANCE_SECS)
+        .map_err(|e| callback_error(StatusCode::UNAUTHORIZED, e.to_string()))?;
+
+    let callback: ProviderCallbackRequest = serde_json::from_str(&body)
+        .map_err(|e| callback_error(StatusCode::BAD_REQUEST, format!("Invalid callback: {}", e)))?;
+    callback.validate().map_err(|e| callback_error(StatusCode::BAD_REQUEST, e.to_string()))?;
+
+    let crud = CallbackCrud::new(state.db.clone());
+    let consumed = IdempotencyStore::new(state.redis.clone())
+        .consume(&format!("provider:{}", provider), &callback.event_id, || crud.apply(&provider, &callback))
+        .await;
+
+    match consumed {
+        Ok(Consumed::Handled(outcome)) => Ok(Json(ProviderCallbackResponse {
+            event_id: callback.event_id,
+            duplicate: false,
+            swap_id: Some(outcome.swap_id),
+            status: Some(outcome.status),
+            changed: outcome.changed,
+        })),
+        Ok(Consumed::Duplicate(Claim::InFlight)) => {
+            Err(callback_error(StatusCode::CONFLICT, "Event is being processed, retry later"))
+        }
+        Ok(Consumed::Duplicate(_)) => {
+            tracing::debug!("Ignoring duplicate {} callback {}", provider, callback.event_id);
+            Ok(Json(ProviderCallbackResponse {
+                event_id: callback.event_id,
+                duplicate: true,
+                swap_id: None,
+                status: None,
+                changed: false,
+            }))
+        }
+        Err(ConsumeError::Handler(e)) => {
+            if e.status_code().is_server_error() {
+                tracing::error!("Failed to apply {} callback {}: {}", provider, callback.event_id, e);
+            }
+            Err(callback_error(e.status_code(), e.to_string()))
+        }
+        // Without the store a redelivery can't be told apart, so the
+        // provider retries once it is back
+        Err(ConsumeError::Store(e)) => {
+            tracing::error!("Callback dedup store unavailable: {}", e);
+            Err(callback_error(StatusCode::SERVICE_UNAVAILABLE, "Temporarily unable to accept callbacks"))
+        }
+    }
+}
+
+fn callback_error(status: StatusCode, error: impl Into<String>) -> (StatusCode, Json<CallbackErrorResponse>) {
+    (status, Json(CallbackErrorResponse::new(error)))
+}
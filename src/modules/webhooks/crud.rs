@@ -0,0 +1,333 @@
+use axum::http::StatusCode;
+use chrono::{DateTime, Utc};
+use sqlx::{MySql, Pool};
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use super::schema::{DeliveryInfo, ProviderCallbackRequest};
+use crate::modules::swap::schema::SwapStatus;
+use crate::services::amount::Decimal;
+use crate::services::swap_state::{StatusActor, SwapStateMachine, Transition, TransitionError};
+use crate::services::webhook::{Webhook, WebhookError};
+
+/// Id, swap id, url, secret, events JSON, payload version, enabled flag and
+/// rate limit of a webhook
+type WebhookRow = (String, String, String, String, String, Option<u32>, Option<bool>, i32);
+
+/// One delivery as listed to the integrator: id, event, test flag, delivery
+/// and retry state, response and creation time
+type DeliveryRow = (
+    String, String, bool, Option<DateTime<Utc>>, i32, Option<DateTime<Utc>>, Option<bool>, Option<i32>, Option<i32>,
+    Option<String>, DateTime<Utc>,
+);
+
+// =============================================================================
+// ERRORS
+// =============================================================================
+
+#[derive(Debug)]
+pub enum CallbackError {
+    SwapNotFound,
+    UnknownStatus(String),
+    /// Another writer kept moving the swap; the provider's retry will land
+    Contended,
+    DatabaseError(String),
+}
+
+impl std::fmt::Display for CallbackError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CallbackError::SwapNotFound => write!(f, "No swap with this provider swap id"),
+            CallbackError::UnknownStatus(status) => write!(f, "Unknown provider status: {}", status),
+            CallbackError::Contended => write!(f, "Swap is being updated, retry later"),
+            CallbackError::DatabaseError(e) => write!(f, "Database error: {}", e),
+        }
+    }
+}
+
+impl CallbackError {
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            CallbackError::SwapNotFound => StatusCode::NOT_FOUND,
+            CallbackError::UnknownStatus(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            CallbackError::Contended => StatusCode::CONFLICT,
+            CallbackError::DatabaseError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+impl From<sqlx::Error> for CallbackError {
+    fn from(err: sqlx::Error) -> Self {
+        CallbackError::DatabaseError(err.to_string())
+    }
+}
+
+#[derive(Debug)]
+pub enum WebhookApiError {
+    /// No such webhook on one of the caller's swaps
+    WebhookNotFound,
+    DeliveryNotFound,
+    /// Too many tests and redeliveries for the webhook this hour
+    RateLimited,
+    DeliveryError(String),
+    DatabaseError(String),
+}
+
+impl std::fmt::Display for WebhookApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WebhookApiError::WebhookNotFound => write!(f, "Webhook not found"),
+            WebhookApiError::DeliveryNotFound => write!(f, "Delivery not found"),
+            WebhookApiError::RateLimited => write!(f, "Too many tests and redeliveries, retry later"),
+            WebhookApiError::DeliveryError(e) => write!(f, "Delivery error: {}", e),
+            WebhookApiError::DatabaseError(e) => write!(f, "Database error: {}", e),
+        }
+    }
+}
+
+impl WebhookApiError {
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            WebhookApiError::WebhookNotFound | WebhookApiError::DeliveryNotFound => StatusCode::NOT_FOUND,
+            WebhookApiError::RateLimited => StatusCode::TOO_MANY_REQUESTS,
+            WebhookApiError::DeliveryError(_) | WebhookApiError::DatabaseError(_) => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+        }
+    }
+}
+
+impl From<sqlx::Error> for WebhookApiError {
+    fn from(err: sqlx::Error) -> Self {
+        WebhookApiError::DatabaseError(err.to_string())
+    }
+}
+
+impl From<WebhookError> for WebhookApiError {
+    fn from(err: WebhookError) -> Self {
+        match err {
+            WebhookError::Database(e) => WebhookApiError::DatabaseError(e.to_string()),
+            e => WebhookApiError::DeliveryError(e.to_string()),
+        }
+    }
+}
+
+// =============================================================================
+// SECRETS
+// =============================================================================
+
+/// Shared secret each provider signs its callbacks with, from
+/// PROVIDER_CALLBACK_SECRETS (`changenow=secret,fixedfloat=secret`).
+/// Providers without one cannot call in.
+pub fn callback_secret(provider: &str) -> Option<&'static str> {
+    static SECRETS: OnceLock<HashMap<String, String>> = OnceLock::new();
+    SECRETS
+        .get_or_init(|| parse_secrets(&std::env::var("PROVIDER_CALLBACK_SECRETS").unwrap_or_default()))
+        .get(&provider.trim().to_lowercase())
+        .map(String::as_str)
+}
+
+fn parse_secrets(value: &str) -> HashMap<String, String> {
+    value
+        .split(',')
+        .filter_map(|entry| entry.split_once('='))
+        .map(|(provider, secret)| (provider.trim().to_lowercase(), secret.trim().to_string()))
+        .filter(|(provider, secret)| !provider.is_empty() && !secret.is_empty())
+        .collect()
+}
+
+// =============================================================================
+// PROVIDER CALLBACKS
+// =============================================================================
+
+/// What a callback did to its swap
+#[derive(Debug, Clone)]
+pub struct CallbackOutcome {
+    pub swap_id: String,
+    pub status: SwapStatus,
+    pub changed: bool,
+}
+
+pub struct CallbackCrud {
+    db: Pool<MySql>,
+}
+
+impl CallbackCrud {
+    pub fn new(db: Pool<MySql>) -> Self {
+        Self { db }
+    }
+
+    /// Move the provider's swap to the reported status through the state
+    /// machine. A status the swap has already passed is acknowledged
+    /// without writing anything.
+    pub async fn apply(&self, provider: &str, callback: &ProviderCallbackRequest) -> Result<CallbackOutcome, CallbackError> {
+        let status = provider_status(&callback.status)
+            .ok_or_else(|| CallbackError::UnknownStatus(callback.status.clone()))?;
+
+        let swap_id: Option<String> =
+            sqlx::query_scalar("SELECT id FROM swaps WHERE provider_id = ? AND provider_swap_id = ?")
+                .bind(provider)
+                .bind(&callback.provider_swap_id)
+                .fetch_optional(&self.db)
+                .await?;
+        let swap_id = swap_id.ok_or(CallbackError::SwapNotFound)?;
+
+        let mut transition = Transition::to(status)
+            .actor(StatusActor::Provider)
+            .message(format!("{} callback {}", provider, callback.event_id));
+        if let Some(amount) = callback.amount_to.map(|a| a.value()).filter(|a| *a > Decimal::ZERO) {
+            transition = transition.actual_receive(amount);
+        }
+        if let Some(hash) = callback.tx_hash_out.as_deref().map(str::trim).filter(|h| !h.is_empty()) {
+            transition = transition.tx_hash_out(hash);
+        }
+
+        match SwapStateMachine::new(self.db.clone()).advance(&swap_id, &transition).await {
+            Ok(applied) => Ok(CallbackOutcome { swap_id, status: applied.to, changed: applied.changed }),
+            Err(TransitionError::Illegal { from, to }) => {
+                tracing::info!("Ignoring {} callback for swap {}: {} -> {} is not allowed", provider, swap_id, from, to);
+                Ok(CallbackOutcome { swap_id, status: from, changed: false })
+            }
+            Err(TransitionError::VersionConflict { .. }) => Err(CallbackError::Contended),
+            Err(TransitionError::SwapNotFound) => Err(CallbackError::SwapNotFound),
+            Err(TransitionError::Database(e)) => Err(CallbackError::DatabaseError(e)),
+        }
+    }
+}
+
+// =============================================================================
+// WEBHOOKS
+// =============================================================================
+
+pub struct WebhookCrud {
+    db: Pool<MySql>,
+}
+
+impl WebhookCrud {
+    pub fn new(db: Pool<MySql>) -> Self {
+        Self { db }
+    }
+
+    /// The webhook, if it is registered on one of the user's swaps.
+    /// Disabled webhooks are returned too so integrators can test them
+    /// before turning them on.
+    pub async fn get_owned(&self, user_id: &str, webhook_id: &str) -> Result<Webhook, WebhookApiError> {
+        let row: Option<WebhookRow> = sqlx::query_as(
+            r#"
+            SELECT w.id, w.swap_id, w.url, w.secret_key, CAST(w.events AS CHAR), w.payload_version,
+                   w.enabled, COALESCE(w.rate_limit_per_second, 10)
+            FROM webhooks w
+            JOIN swaps s ON s.id = w.swap_id
+            WHERE w.id = ? AND s.user_id = ?
+            "#,
+        )
+        .bind(webhook_id)
+        .bind(user_id)
+        .fetch_optional(&self.db)
+        .await?;
+
+        let (id, swap_id, url, secret_key, events, payload_version, enabled, rate_limit_per_second) =
+            row.ok_or(WebhookApiError::WebhookNotFound)?;
+        let now = Utc::now();
+        Ok(Webhook {
+            id: uuid::Uuid::parse_str(&id).map_err(|_| WebhookApiError::WebhookNotFound)?,
+            swap_id: uuid::Uuid::parse_str(&swap_id).map_err(|_| WebhookApiError::WebhookNotFound)?,
+            url,
+            secret_key,
+            events: serde_json::from_str(&events).unwrap_or_default(),
+            payload_version,
+            enabled: enabled.unwrap_or(false),
+            rate_limit_per_second,
+            created_at: now,
+            updated_at: now,
+        })
+    }
+
+    /// Tests and redeliveries sent to the webhook in the last hour
+    pub async fn manual_attempts_last_hour(&self, webhook_id: &str) -> Result<i64, WebhookApiError> {
+        let count: i64 = sqlx::query_scalar(
+            r#"
+            SELECT COUNT(*) FROM webhook_delivery_attempts
+            WHERE webhook_id = ? AND source IN ('test', 'redelivery')
+              AND created_at >= NOW() - INTERVAL 1 HOUR
+            "#,
+        )
+        .bind(webhook_id)
+        .fetch_one(&self.db)
+        .await?;
+        Ok(count)
+    }
+
+    pub async fn list_deliveries(&self, webhook_id: &str, limit: u32) -> Result<Vec<DeliveryInfo>, WebhookApiError> {
+        let rows: Vec<DeliveryRow> = sqlx::query_as(
+            r#"
+            SELECT id, event_type, is_test, delivered_at, attempt_number, next_retry_at,
+                   is_dlq, response_status, response_time_ms, error_message,
+                   COALESCE(created_at, NOW())
+            FROM webhook_deliveries
+            WHERE webhook_id = ?
+            ORDER BY created_at DESC, id
+            LIMIT ?
+            "#,
+        )
+        .bind(webhook_id)
+        .bind(limit)
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(
+                |(id, event_type, is_test, delivered_at, retries, next_retry_at, is_dlq, response_status, response_time_ms, error, created_at)| {
+                    let dead_lettered = is_dlq.unwrap_or(false);
+                    DeliveryInfo {
+                        id,
+                        event_type,
+                        is_test,
+                        delivered: delivered_at.is_some(),
+                        delivered_at,
+                        retries,
+                        // Nothing is pending once it went through
+                        next_retry_at: next_retry_at.filter(|_| delivered_at.is_none() && !dead_lettered),
+                        dead_lettered,
+                        response_status,
+                        response_time_ms,
+                        error,
+                        created_at,
+                    }
+                },
+            )
+            .collect())
+    }
+}
+
+/// Our status for a provider's. Unlike `SwapStatus::from_provider_status`,
+/// statuses we don't know are refused rather than read as waiting.
+fn provider_status(status: &str) -> Option<SwapStatus> {
+    let status = status.trim().to_lowercase();
+    match status.as_str() {
+        "new" | "waiting" | "confirming" | "exchanging" | "sending" | "finished" | "paid partially" | "failed"
+        | "halted" | "refunded" | "expired" => Some(SwapStatus::from_provider_status(&status)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_secrets() {
+        let secrets = parse_secrets(" ChangeNOW = abc ,fixedfloat=def,broken,empty=");
+        assert_eq!(secrets.get("changenow").map(String::as_str), Some("abc"));
+        assert_eq!(secrets.get("fixedfloat").map(String::as_str), Some("def"));
+        assert_eq!(secrets.len(), 2);
+    }
+
+    #[test]
+    fn test_unknown_provider_status_is_refused() {
+        assert_eq!(provider_status("Finished"), Some(SwapStatus::Completed));
+        assert_eq!(provider_status("halted"), Some(SwapStatus::Failed));
+        assert_eq!(provider_status("on hold"), None);
+    }
+}
@@ -0,0 +1,15 @@
+use axum::Router;
+use std::sync::Arc;
+
+use crate::AppState;
+use crate::manifest::DeclaredRoutes;
+use super::controller::{list_deliveries, list_event_types, provider_callback, redeliver, test_webhook};
+
+pub fn webhook_routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .declared("listWebhookEventTypes", list_event_types)
+        .declared("listWebhookDeliveries", list_deliveries)
+        .declared("testWebhook", test_webhook)
+        .declared("redeliverWebhook", redeliver)
+        .declared("providerCallback", provider_callback)
+}
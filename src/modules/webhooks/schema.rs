@@ -0,0 +1,143 @@
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use crate::modules::swap::schema::SwapStatus;
+use crate::services::amount::WireAmount;
+
+// =============================================================================
+// RESPONSES
+// =============================================================================
+
+/// Webhook event types a subscription can receive
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct EventTypesResponse {
+    pub latest_version: u32,
+    /// Payload versions a subscription can ask for
+    pub supported_versions: Vec<u32>,
+    /// Typed events delivered to versioned subscriptions
+    pub event_types: Vec<EventTypeInfo>,
+    /// Event names delivered to subscriptions without a payload version,
+    /// whose `data` is the raw swap event
+    pub unversioned: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct EventTypeInfo {
+    /// e.g. `swap.v1.completed`, sent as the payload's `type`
+    pub name: String,
+    pub version: u32,
+    /// The unversioned event it stands for
+    pub replaces: String,
+    pub description: String,
+    /// JSON Schema of the payload's `data`
+    pub schema: serde_json::Value,
+}
+
+// =============================================================================
+// DELIVERIES
+// =============================================================================
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct DeliveriesQuery {
+    /// Newest first; defaults to 50, at most 200
+    pub limit: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DeliveriesResponse {
+    pub deliveries: Vec<DeliveryInfo>,
+}
+
+/// A payload sent to the webhook and its latest response
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DeliveryInfo {
+    pub id: String,
+    /// The payload's `type`
+    pub event_type: String,
+    /// Sent from `POST /webhooks/{id}/test`
+    pub is_test: bool,
+    pub delivered: bool,
+    pub delivered_at: Option<DateTime<Utc>>,
+    /// Scheduled retries made so far
+    pub retries: i32,
+    pub next_retry_at: Option<DateTime<Utc>>,
+    /// Retries were exhausted; only a redelivery sends it again
+    pub dead_lettered: bool,
+    /// None when no response arrived
+    pub response_status: Option<i32>,
+    pub response_time_ms: Option<i32>,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Result of a test or redelivery, sent while the request waited
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DeliveryAttemptResponse {
+    pub delivery_id: String,
+    pub event_type: String,
+    /// The endpoint answered with a 2xx
+    pub succeeded: bool,
+    /// None when no response arrived
+    pub response_status: Option<i32>,
+    pub response_time_ms: i32,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct WebhookErrorResponse {
+    pub error: String,
+}
+
+impl WebhookErrorResponse {
+    pub fn new(error: impl Into<String>) -> Self {
+        Self { error: error.into() }
+    }
+}
+
+// =============================================================================
+// PROVIDER CALLBACKS
+// =============================================================================
+
+/// Status update pushed by an exchange provider
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, JsonSchema)]
+pub struct ProviderCallbackRequest {
+    /// Unique per event; redeliveries repeat it
+    #[validate(length(min = 1, max = 128))]
+    pub event_id: String,
+    /// The provider's id for the trade
+    #[validate(length(min = 1, max = 255))]
+    pub provider_swap_id: String,
+    /// Provider status, e.g. `exchanging`, `finished`
+    #[validate(length(min = 1, max = 32))]
+    pub status: String,
+    pub amount_to: Option<WireAmount>,
+    pub tx_hash_out: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ProviderCallbackResponse {
+    pub event_id: String,
+    /// The event was delivered before and was not applied again
+    pub duplicate: bool,
+    /// Set when this delivery was applied
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub swap_id: Option<String>,
+    /// Swap status after the event
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<SwapStatus>,
+    /// False when the swap was already past the reported status
+    pub changed: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CallbackErrorResponse {
+    pub error: String,
+}
+
+impl CallbackErrorResponse {
+    pub fn new(error: impl Into<String>) -> Self {
+        Self { error: error.into() }
+    }
+}
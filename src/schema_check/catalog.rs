@@ -0,0 +1,59 @@
+//! Column definitions of the current database, read from INFORMATION_SCHEMA
+
+use sqlx::{MySql, Pool, Row};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColumnInfo {
+    /// Base type, lower case: `varchar`, `bigint`, `enum`, ...
+    pub data_type: String,
+    /// Full definition, e.g. `int unsigned` or `enum('a','b')`
+    pub column_type: String,
+    pub nullable: bool,
+}
+
+impl ColumnInfo {
+    pub fn new(data_type: &str, column_type: &str, nullable: bool) -> Self {
+        Self {
+            data_type: data_type.to_lowercase(),
+            column_type: column_type.to_lowercase(),
+            nullable,
+        }
+    }
+
+    pub fn is_unsigned(&self) -> bool {
+        self.column_type.contains("unsigned")
+    }
+}
+
+/// Table name -> column name -> definition
+pub type Catalog = BTreeMap<String, BTreeMap<String, ColumnInfo>>;
+
+pub async fn load(pool: &Pool<MySql>) -> Result<Catalog, sqlx::Error> {
+    let rows = sqlx::query(
+        r#"
+        SELECT CAST(TABLE_NAME AS CHAR) as table_name, CAST(COLUMN_NAME AS CHAR) as column_name,
+               CAST(DATA_TYPE AS CHAR) as data_type, CAST(COLUMN_TYPE AS CHAR) as column_type,
+               CAST(IS_NULLABLE AS CHAR) as is_nullable
+        FROM INFORMATION_SCHEMA.COLUMNS
+        WHERE TABLE_SCHEMA = DATABASE()
+        "#,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut catalog = Catalog::new();
+    for row in rows {
+        let table: String = row.get("table_name");
+        let column: String = row.get("column_name");
+        let data_type: String = row.get("data_type");
+        let column_type: String = row.get("column_type");
+        let is_nullable: String = row.get("is_nullable");
+
+        catalog
+            .entry(table)
+            .or_default()
+            .insert(column, ColumnInfo::new(&data_type, &column_type, is_nullable == "YES"));
+    }
+    Ok(catalog)
+}
@@ -0,0 +1,174 @@
+//! Throwaway databases for running the migration set, and comparison of a
+//! deployed database's `_sqlx_migrations` history with the local set.
+
+use sqlx::mysql::{MySqlConnectOptions, MySqlPoolOptions};
+use sqlx::{ConnectOptions, Executor, MySqlPool, Row};
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use super::{SchemaIssue, MIGRATOR};
+
+/// An empty database created next to the one `database_url` names. The
+/// caller must call [`ScratchDatabase::drop_database`] when done.
+pub struct ScratchDatabase {
+    name: String,
+    admin: MySqlPool,
+    pool: MySqlPool,
+}
+
+impl ScratchDatabase {
+    pub async fn create(database_url: &str) -> Result<Self, String> {
+        let options = MySqlConnectOptions::from_str(database_url)
+            .map_err(|e| format!("Invalid database URL: {}", e))?
+            .disable_statement_logging();
+
+        let admin = MySqlPoolOptions::new()
+            .max_connections(1)
+            .connect_with(options.clone())
+            .await
+            .map_err(|e| format!("Failed to connect: {}", e))?;
+
+        let simple = uuid::Uuid::new_v4().simple().to_string();
+        let name = format!("schema_check_{}", &simple[..12]);
+        admin
+            .execute(format!("CREATE DATABASE `{}`", name).as_str())
+            .await
+            .map_err(|e| format!("Failed to create scratch database {}: {}", name, e))?;
+
+        let pool = match MySqlPoolOptions::new()
+            .max_connections(2)
+            .connect_with(options.database(&name))
+            .await
+        {
+            Ok(pool) => pool,
+            Err(e) => {
+                let _ = admin.execute(format!("DROP DATABASE IF EXISTS `{}`", name).as_str()).await;
+                return Err(format!("Failed to connect to scratch database {}: {}", name, e));
+            }
+        };
+
+        Ok(Self { name, admin, pool })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn pool(&self) -> &MySqlPool {
+        &self.pool
+    }
+
+    pub async fn drop_database(self) {
+        self.pool.close().await;
+        if let Err(e) = self.admin.execute(format!("DROP DATABASE IF EXISTS `{}`", self.name).as_str()).await {
+            tracing::warn!("Failed to drop scratch database {}: {}", self.name, e);
+        }
+        self.admin.close().await;
+    }
+}
+
+/// One row of `_sqlx_migrations`
+#[derive(Debug, Clone)]
+pub struct AppliedMigration {
+    pub version: i64,
+    pub checksum: Vec<u8>,
+    pub success: bool,
+}
+
+/// Compare the history recorded in a deployed database with the local
+/// migration set. Pending migrations are expected before a deploy; an edited
+/// or failed migration, or one that exists only in the database, needs a
+/// human before `migrate!` will run.
+pub fn compare_history(applied: &[AppliedMigration]) -> Vec<SchemaIssue> {
+    let local: BTreeMap<i64, (&str, &[u8])> = MIGRATOR
+        .iter()
+        .filter(|m| !m.migration_type.is_down_migration())
+        .map(|m| (m.version, (m.description.as_ref(), m.checksum.as_ref())))
+        .collect();
+
+    let mut issues = Vec::new();
+    let applied_versions: BTreeMap<i64, &AppliedMigration> = applied.iter().map(|m| (m.version, m)).collect();
+
+    for (version, (description, checksum)) in &local {
+        let subject = format!("{}_{}", version, description.replace(' ', "_"));
+        match applied_versions.get(version) {
+            None => issues.push(SchemaIssue::warning(subject, "pending")),
+            Some(m) if !m.success => {
+                issues.push(SchemaIssue::error(subject, "failed part-way; the database is dirty"))
+            }
+            Some(m) if m.checksum.as_slice() != *checksum => {
+                issues.push(SchemaIssue::error(subject, "edited after it was applied; checksum differs"))
+            }
+            Some(_) => {}
+        }
+    }
+
+    for version in applied_versions.keys().filter(|v| !local.contains_key(v)) {
+        issues.push(SchemaIssue::error(
+            version.to_string(),
+            "applied to the database but missing from ./migrations",
+        ));
+    }
+
+    issues
+}
+
+/// Read `_sqlx_migrations` from `pool` and compare it with the local set
+pub async fn history_drift(pool: &MySqlPool) -> Result<Vec<SchemaIssue>, sqlx::Error> {
+    let rows = sqlx::query("SELECT version, checksum, success FROM _sqlx_migrations ORDER BY version")
+        .fetch_all(pool)
+        .await?;
+
+    let applied: Vec<AppliedMigration> = rows
+        .iter()
+        .map(|row| AppliedMigration {
+            version: row.get("version"),
+            checksum: row.get("checksum"),
+            success: row.get("success"),
+        })
+        .collect();
+
+    Ok(compare_history(&applied))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema_check::Severity;
+
+    fn applied_all() -> Vec<AppliedMigration> {
+        MIGRATOR
+            .iter()
+            .filter(|m| !m.migration_type.is_down_migration())
+            .map(|m| AppliedMigration { version: m.version, checksum: m.checksum.to_vec(), success: true })
+            .collect()
+    }
+
+    #[test]
+    fn test_up_to_date_history() {
+        assert!(compare_history(&applied_all()).is_empty());
+    }
+
+    #[test]
+    fn test_pending_is_a_warning() {
+        let mut applied = applied_all();
+        applied.pop();
+
+        let issues = compare_history(&applied);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].severity, Severity::Warning);
+    }
+
+    #[test]
+    fn test_edited_failed_and_unknown_are_errors() {
+        let mut applied = applied_all();
+        applied[0].checksum = vec![0; 48];
+        applied[1].success = false;
+        applied.push(AppliedMigration { version: 1, checksum: vec![], success: true });
+
+        let issues = compare_history(&applied);
+        assert_eq!(issues.len(), 3);
+        assert!(issues.iter().all(|i| i.severity == Severity::Error));
+        assert!(issues.iter().any(|i| i.subject == "1"));
+    }
+}
@@ -0,0 +1,166 @@
+//! Pre-deploy schema verification.
+//!
+//! [`verify`] applies the migration set to a fresh, throwaway database and
+//! checks the resulting tables against the code that reads them: the
+//! `FromRow` model structs listed in [`models`] and the offline metadata
+//! `cargo sqlx prepare` writes for `sqlx::query!` macros ([`queries`]).
+//! [`migrations::history_drift`] compares a deployed database's migration
+//! history with the local set. The `schema_check` binary runs both.
+
+pub mod catalog;
+pub mod migrations;
+pub mod models;
+pub mod queries;
+
+use sqlx::migrate::Migrator;
+use std::fmt;
+use std::path::Path;
+
+/// The migration set compiled into this build
+pub static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// Suspicious but not known to break anything, e.g. a non-`Option`
+    /// field over a nullable column that is never NULL in practice
+    Warning,
+    /// Will fail at runtime or at the next `cargo sqlx prepare`
+    Error,
+}
+
+impl Severity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaIssue {
+    pub severity: Severity,
+    /// What the issue is about, e.g. `swaps.status` or a migration version
+    pub subject: String,
+    pub message: String,
+}
+
+impl SchemaIssue {
+    pub fn error(subject: impl Into<String>, message: impl Into<String>) -> Self {
+        Self { severity: Severity::Error, subject: subject.into(), message: message.into() }
+    }
+
+    pub fn warning(subject: impl Into<String>, message: impl Into<String>) -> Self {
+        Self { severity: Severity::Warning, subject: subject.into(), message: message.into() }
+    }
+}
+
+impl fmt::Display for SchemaIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}: {}", self.severity.as_str(), self.subject, self.message)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct SchemaReport {
+    pub migrations_applied: usize,
+    pub tables: usize,
+    pub models_checked: usize,
+    /// `None` when there was no offline query data to check
+    pub queries_checked: Option<usize>,
+    pub issues: Vec<SchemaIssue>,
+}
+
+impl SchemaReport {
+    pub fn has_errors(&self) -> bool {
+        self.issues.iter().any(|i| i.severity == Severity::Error)
+    }
+
+    pub fn count(&self, severity: Severity) -> usize {
+        self.issues.iter().filter(|i| i.severity == severity).count()
+    }
+}
+
+impl fmt::Display for SchemaReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for issue in &self.issues {
+            writeln!(f, "{}", issue)?;
+        }
+        let queries = match self.queries_checked {
+            Some(n) => n.to_string(),
+            None => "no offline data".to_string(),
+        };
+        write!(
+            f,
+            "{} migrations, {} tables, {} models, queries: {} -> {} errors, {} warnings",
+            self.migrations_applied,
+            self.tables,
+            self.models_checked,
+            queries,
+            self.count(Severity::Error),
+            self.count(Severity::Warning),
+        )
+    }
+}
+
+/// Apply every migration to a scratch database on the server of
+/// `database_url`, then check models and (when `sqlx_dir` exists) offline
+/// query metadata against the result. The scratch database is dropped
+/// afterwards, including when a migration fails.
+pub async fn verify(database_url: &str, sqlx_dir: Option<&Path>) -> Result<SchemaReport, String> {
+    let scratch = migrations::ScratchDatabase::create(database_url).await?;
+    let result = verify_on(scratch.pool(), sqlx_dir).await;
+    scratch.drop_database().await;
+    result
+}
+
+async fn verify_on(pool: &sqlx::MySqlPool, sqlx_dir: Option<&Path>) -> Result<SchemaReport, String> {
+    let mut report = SchemaReport::default();
+
+    if let Err(e) = MIGRATOR.run(pool).await {
+        report.issues.push(SchemaIssue::error("migrations", format!("failed on a fresh database: {}", e)));
+        return Ok(report);
+    }
+    report.migrations_applied = MIGRATOR.iter().filter(|m| !m.migration_type.is_down_migration()).count();
+
+    let catalog = catalog::load(pool).await.map_err(|e| format!("Failed to read schema: {}", e))?;
+    report.tables = catalog.len();
+
+    let specs = models::model_specs();
+    report.models_checked = specs.len();
+    report.issues.extend(models::check(&catalog, &specs));
+
+    if let Some(dir) = sqlx_dir.filter(|d| d.is_dir()) {
+        let offline = queries::load_dir(dir)?;
+        report.queries_checked = Some(offline.len());
+        report.issues.extend(queries::check(pool, &offline).await);
+    }
+
+    report.issues.sort_by(|a, b| b.severity.cmp(&a.severity).then_with(|| a.subject.cmp(&b.subject)));
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_counts_and_errors() {
+        let mut report = SchemaReport::default();
+        assert!(!report.has_errors());
+
+        report.issues.push(SchemaIssue::warning("providers.markup_enabled", "nullable column"));
+        assert!(!report.has_errors());
+
+        report.issues.push(SchemaIssue::error("swaps.status", "missing column"));
+        assert!(report.has_errors());
+        assert_eq!(report.count(Severity::Error), 1);
+        assert_eq!(report.count(Severity::Warning), 1);
+        assert!(report.to_string().contains("error: swaps.status: missing column"));
+    }
+
+    #[test]
+    fn test_migrations_are_embedded() {
+        assert!(MIGRATOR.iter().count() > 0);
+    }
+}
@@ -0,0 +1,561 @@
+//! Model structs versus table definitions. Each `FromRow` model that maps
+//! onto one table is listed in [`model_specs`] with its fields. The listing
+//! is checked against the struct at compile time (every field, exact types),
+//! so a field added without updating it fails the build, and [`check`]
+//! compares it with the migrated schema.
+
+use chrono::{DateTime, NaiveDate, Utc};
+
+use super::catalog::{Catalog, ColumnInfo};
+use super::SchemaIssue;
+use crate::modules::address_book::model::AddressBookEntry;
+use crate::modules::auth::model::{
+    BackupCode, EmailVerification, OAuthIdentity, OAuthLoginState, PasswordReset, RefreshToken, User,
+};
+use crate::modules::backups::model::BackupRun;
+use crate::modules::balances::model::{
+    BalanceAccount, LedgerEntry, LedgerEntryType, WithdrawalRequest, WithdrawalStatus,
+};
+use crate::modules::commissions::model::{ProviderCommission, RevenueEntry, RevenueEntryType};
+use crate::modules::email::model::{OutboxEmail, OutboxStatus};
+use crate::modules::exports::model::{ExportJob, ExportKind, ExportStatus};
+use crate::modules::gift_cards::model::{GiftCardPurchase, GiftCardPurchaseStatus};
+use crate::modules::halts::model::{HaltScope, HaltSource, TradingHalt};
+use crate::modules::jobs::model::{Job, JobKind, JobStatus};
+use crate::modules::kyc::model::{DocumentSide, DocumentType, KycApplicant, KycDocument, KycLevel, KycStatus};
+use crate::modules::moderation::model::{
+    AccountStanding, AccountStatus, AccountStatusEvent, AddressAccount, AddressReputation, ReputationStatus, RiskSignal,
+    RiskSignalKind, StatusSource,
+};
+use crate::modules::monitor::model::PollingState;
+use crate::modules::orders::model::{ConditionalOrder, OrderStatus};
+use crate::modules::payout_guard::model::{PayoutGuardTrip, TripCause};
+use crate::modules::reconciliation::model::{
+    DiscrepancyKind, DiscrepancyStatus, ProviderDiscrepancy, ReconciliationRun,
+};
+use crate::modules::recovery::model::{
+    MemoDeposit, MemoDepositStatus, OrphanedFunds, OrphanedFundsKind, OrphanedFundsStatus, WalletAuditRun,
+    WrongNetworkCase, WrongNetworkStatus,
+};
+use crate::modules::schedules::model::{ScheduleFrequency, ScheduleRun, ScheduleStatus, SwapSchedule};
+use crate::modules::swap::model::{
+    Currency, PayloadKind, Provider, ProviderCurrency, ProviderOrderIntent, ProviderPayload, RateCache, SlaEscalation,
+    Swap, SwapSlaBreach, SwapStatusHistory, TradingPair,
+};
+use crate::modules::swap::schema::{RateType, SwapStatus};
+use crate::modules::wallet::model::{PayoutConfirmation, SwapAddressInfo};
+use crate::services::amount::Decimal;
+use crate::services::fx::{CryptoPrice, FxRate};
+use crate::services::oauth::OAuthProvider;
+
+/// What a field decodes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnKind {
+    /// Strings and string-backed enums; compressed BLOB columns are read
+    /// through `CAST(UNCOMPRESS(..) AS CHAR)`
+    Text,
+    Int,
+    UnsignedInt,
+    Bool,
+    /// `f64`; DECIMAL columns are read through `CAST(.. AS DOUBLE)`
+    Float,
+    /// `Decimal`, decoded from DECIMAL columns as-is
+    Decimal,
+    Timestamp,
+    Date,
+}
+
+impl ColumnKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ColumnKind::Text => "text",
+            ColumnKind::Int => "signed integer",
+            ColumnKind::UnsignedInt => "unsigned integer",
+            ColumnKind::Bool => "bool",
+            ColumnKind::Float => "float",
+            ColumnKind::Decimal => "decimal",
+            ColumnKind::Timestamp => "timestamp",
+            ColumnKind::Date => "date",
+        }
+    }
+
+    pub fn accepts(&self, column: &ColumnInfo) -> bool {
+        let is_integer = matches!(
+            column.data_type.as_str(),
+            "tinyint" | "smallint" | "mediumint" | "int" | "bigint"
+        );
+        match self {
+            ColumnKind::Text => matches!(
+                column.data_type.as_str(),
+                "char" | "varchar" | "tinytext" | "text" | "mediumtext" | "longtext" | "enum" | "set" | "json"
+                    | "blob" | "mediumblob" | "longblob"
+            ),
+            ColumnKind::Int => is_integer && !column.is_unsigned(),
+            ColumnKind::UnsignedInt => is_integer && column.is_unsigned(),
+            ColumnKind::Bool => column.data_type == "tinyint",
+            ColumnKind::Float => matches!(column.data_type.as_str(), "float" | "double" | "decimal"),
+            ColumnKind::Decimal => column.data_type == "decimal",
+            ColumnKind::Timestamp => matches!(column.data_type.as_str(), "timestamp" | "datetime"),
+            ColumnKind::Date => column.data_type == "date",
+        }
+    }
+}
+
+/// Rust field types a model may use, and the columns they decode from
+pub trait ColumnField {
+    const KIND: ColumnKind;
+    const NULLABLE: bool = false;
+}
+
+impl<T: ColumnField> ColumnField for Option<T> {
+    const KIND: ColumnKind = T::KIND;
+    const NULLABLE: bool = true;
+}
+
+macro_rules! column_fields {
+    ($kind:ident: $($ty:ty),* $(,)?) => {
+        $( impl ColumnField for $ty { const KIND: ColumnKind = ColumnKind::$kind; } )*
+    };
+}
+
+column_fields!(Text: String);
+column_fields!(Int: i32, i64);
+column_fields!(UnsignedInt: u8, u32, u64);
+column_fields!(Bool: bool);
+column_fields!(Float: f64);
+column_fields!(Decimal: Decimal);
+column_fields!(Timestamp: DateTime<Utc>);
+column_fields!(Date: NaiveDate);
+column_fields!(
+    Text: OAuthProvider, LedgerEntryType, WithdrawalStatus, ExportKind, ExportStatus, GiftCardPurchaseStatus,
+    HaltScope, HaltSource, OrderStatus, DiscrepancyKind, DiscrepancyStatus, MemoDepositStatus,
+    WrongNetworkStatus, ScheduleFrequency, ScheduleStatus, RateType, SwapStatus, RevenueEntryType,
+    JobKind, JobStatus, OutboxStatus, AccountStatus, StatusSource, RiskSignalKind, PayoutConfirmation,
+    ReputationStatus, KycLevel, KycStatus, DocumentType, DocumentSide, PayloadKind,
+    SlaEscalation, TripCause, OrphanedFundsKind, OrphanedFundsStatus,
+);
+
+#[derive(Debug, Clone)]
+pub struct FieldSpec {
+    pub name: &'static str,
+    pub kind: ColumnKind,
+    pub nullable: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct ModelSpec {
+    pub model: &'static str,
+    pub table: &'static str,
+    pub fields: Vec<FieldSpec>,
+}
+
+macro_rules! model_specs {
+    ($( $model:ident => $table:literal { $( $field:ident: $ty:ty ),* $(,)? } )*) => {
+        $(
+            // Destructuring without `..` stops compiling when the struct
+            // gains or loses a field, and each binding is type-checked
+            const _: () = {
+                #[allow(dead_code)]
+                fn exhaustive(model: &$model) {
+                    let $model { $( $field ),* } = model;
+                    $( let _: &$ty = $field; )*
+                }
+            };
+        )*
+
+        /// Models read straight from one table. Projections over joins or
+        /// `CAST`s (e.g. `TradingPairView`) are not listed.
+        pub fn model_specs() -> Vec<ModelSpec> {
+            vec![$(
+                ModelSpec {
+                    model: stringify!($model),
+                    table: $table,
+                    fields: vec![$(
+                        FieldSpec {
+                            name: stringify!($field),
+                            kind: <$ty as ColumnField>::KIND,
+                            nullable: <$ty as ColumnField>::NULLABLE,
+                        }
+                    ),*],
+                }
+            ),*]
+        }
+    };
+}
+
+model_specs! {
+    AddressBookEntry => "address_book_entries" {
+        id: String, user_id: String, label: String, currency: String, network: String, address: String,
+        extra_id: Option<String>, created_at: DateTime<Utc>, updated_at: DateTime<Utc>,
+    }
+    User => "users" {
+        id: String, email: String, password_hash: String, email_verified: bool, two_factor_enabled: bool,
+        two_factor_secret: Option<String>, created_at: DateTime<Utc>, updated_at: DateTime<Utc>,
+        token_version: u32,
+    }
+    AccountStanding => "users" {
+        account_status: AccountStatus, status_reason: Option<String>, suspended_until: Option<DateTime<Utc>>,
+    }
+    AccountStatusEvent => "account_status_events" {
+        id: i64, user_id: String, status: AccountStatus, reason: Option<String>,
+        suspended_until: Option<DateTime<Utc>>, source: StatusSource, actor_id: Option<String>,
+        created_at: DateTime<Utc>,
+    }
+    RiskSignal => "risk_signals" {
+        id: i64, user_id: String, kind: RiskSignalKind, weight: u32, swap_id: Option<String>,
+        detail: Option<String>, reported_by: Option<String>, created_at: DateTime<Utc>,
+    }
+    AddressReputation => "address_reputation" {
+        address_hash: String, address: String, network: String, account_count: u32, swap_count: u32,
+        restricted_accounts: u32, score: u32, status: ReputationStatus, flagged_at: Option<DateTime<Utc>>,
+        review_note: Option<String>, reviewed_by: Option<String>, reviewed_at: Option<DateTime<Utc>>,
+        first_seen_at: DateTime<Utc>, last_seen_at: DateTime<Utc>,
+    }
+    AddressAccount => "address_reputation_accounts" {
+        address_hash: String, user_id: String, swap_count: u32, escalated_at: Option<DateTime<Utc>>,
+        first_seen_at: DateTime<Utc>, last_seen_at: DateTime<Utc>,
+    }
+    KycApplicant => "kyc_applicants" {
+        user_id: String, provider: String, applicant_id: String, level: KycLevel, requested_level: KycLevel,
+        status: KycStatus, reject_reason: Option<String>, submitted_at: Option<DateTime<Utc>>,
+        reviewed_at: Option<DateTime<Utc>>, created_at: DateTime<Utc>, updated_at: DateTime<Utc>,
+    }
+    KycDocument => "kyc_documents" {
+        id: String, user_id: String, document_type: DocumentType, side: Option<DocumentSide>, country: String,
+        provider_document_id: Option<String>, created_at: DateTime<Utc>,
+    }
+    FxRate => "fx_rates" {
+        currency: String, rate_date: NaiveDate, per_usd: Decimal, source: String, created_at: DateTime<Utc>,
+        updated_at: DateTime<Utc>,
+    }
+    CryptoPrice => "crypto_prices" {
+        ticker: String, price_date: NaiveDate, usd_price: Decimal, source: String, created_at: DateTime<Utc>,
+        updated_at: DateTime<Utc>,
+    }
+    RefreshToken => "refresh_tokens" {
+        id: String, user_id: String, token_hash: String, expires_at: DateTime<Utc>, revoked: bool,
+        created_at: DateTime<Utc>,
+    }
+    EmailVerification => "email_verifications" {
+        id: String, user_id: String, token: String, expires_at: DateTime<Utc>, created_at: DateTime<Utc>,
+    }
+    PasswordReset => "password_resets" {
+        id: String, user_id: String, token_hash: String, expires_at: DateTime<Utc>, used: bool,
+        requested_ip: Option<String>, user_agent: Option<String>, created_at: DateTime<Utc>,
+    }
+    BackupCode => "backup_codes" {
+        id: String, user_id: String, code_hash: String, used: bool, created_at: DateTime<Utc>,
+    }
+    OAuthIdentity => "oauth_identities" {
+        id: String, user_id: String, provider: OAuthProvider, subject: String, email: Option<String>,
+        created_at: DateTime<Utc>, last_login_at: Option<DateTime<Utc>>,
+    }
+    OAuthLoginState => "oauth_login_states" {
+        provider: OAuthProvider, code_verifier: String, redirect_uri: String, nonce: String,
+        link_user_id: Option<String>,
+    }
+    BalanceAccount => "balance_accounts" {
+        id: String, user_id: String, currency: String, network: String, balance: Decimal,
+        created_at: DateTime<Utc>, updated_at: DateTime<Utc>,
+    }
+    LedgerEntry => "ledger_entries" {
+        id: i64, account_id: String, entry_type: LedgerEntryType, amount: Decimal, balance_after: Decimal,
+        reference_type: String, reference_id: String, memo: Option<String>, created_at: DateTime<Utc>,
+    }
+    WithdrawalRequest => "withdrawal_requests" {
+        id: String, user_id: String, account_id: String, currency: String, network: String, amount: Decimal,
+        destination_address: String, destination_extra_id: Option<String>, status: WithdrawalStatus,
+        reviewed_by: Option<String>, reviewed_at: Option<DateTime<Utc>>, rejection_reason: Option<String>,
+        tx_hash: Option<String>, error: Option<String>, created_at: DateTime<Utc>, updated_at: DateTime<Utc>,
+    }
+    ExportJob => "export_jobs" {
+        id: String, user_id: String, kind: ExportKind, status: ExportStatus,
+        range_from: Option<DateTime<Utc>>, range_to: Option<DateTime<Utc>>, object_key: Option<String>,
+        row_count: Option<u32>, error: Option<String>, attempts: u32, created_at: DateTime<Utc>,
+        started_at: Option<DateTime<Utc>>, completed_at: Option<DateTime<Utc>>,
+        expires_at: Option<DateTime<Utc>>,
+    }
+    Job => "jobs" {
+        id: String, user_id: Option<String>, kind: JobKind, status: JobStatus, progress: u8,
+        error: Option<String>, created_at: DateTime<Utc>, started_at: Option<DateTime<Utc>>,
+        finished_at: Option<DateTime<Utc>>,
+    }
+    OutboxEmail => "email_outbox" {
+        id: String, recipient: String, recipient_hash: String, subject: String, body: String,
+        status: OutboxStatus, attempts: u32, next_attempt_at: DateTime<Utc>, last_error: Option<String>,
+        created_at: DateTime<Utc>, claimed_at: Option<DateTime<Utc>>, sent_at: Option<DateTime<Utc>>,
+    }
+    GiftCardPurchase => "gift_card_purchases" {
+        id: String, user_id: String, provider: String, card_id: String, card_name: String,
+        country: Option<String>, amount: Decimal, funding_swap_id: Option<String>,
+        provider_order_id: Option<String>, payment_currency: String, payment_network: String,
+        payment_amount: Decimal, payment_address: Option<String>, payment_extra_id: Option<String>,
+        funded_at: Option<DateTime<Utc>>, payment_tx_hash: Option<String>, status: GiftCardPurchaseStatus, delivery_email: String, encrypted_code: Option<String>,
+        delivered_at: Option<DateTime<Utc>>, error: Option<String>, created_at: DateTime<Utc>,
+        updated_at: DateTime<Utc>,
+    }
+    TradingHalt => "trading_halts" {
+        id: String, scope: HaltScope, currency: String, network: String, to_currency: String,
+        to_network: String, reason: Option<String>, source: HaltSource, created_by: Option<String>,
+        created_at: DateTime<Utc>,
+    }
+    ProviderCommission => "provider_commissions" {
+        provider_id: String, commission_rate: Option<f64>, revenue_share_rate: f64, updated_by: Option<String>,
+        created_at: DateTime<Utc>, updated_at: DateTime<Utc>,
+    }
+    RevenueEntry => "revenue_entries" {
+        id: i64, swap_id: String, provider_id: String, entry_type: RevenueEntryType, currency: String,
+        network: String, amount: Decimal, rate: f64, created_at: DateTime<Utc>,
+    }
+    PollingState => "polling_states" {
+        swap_id: String, last_polled_at: Option<DateTime<Utc>>, next_poll_at: DateTime<Utc>, poll_count: i32,
+        last_status: String, created_at: DateTime<Utc>, updated_at: DateTime<Utc>,
+    }
+    ConditionalOrder => "conditional_orders" {
+        id: String, user_id: String, from_currency: String, from_network: String, to_currency: String,
+        to_network: String, amount: Decimal, provider: Option<String>, recipient_address: String,
+        recipient_extra_id: Option<String>, refund_address: Option<String>, refund_extra_id: Option<String>,
+        payout_to_balance: bool, target_rate: Decimal, status: OrderStatus, expires_at: DateTime<Utc>,
+        last_checked_rate: Option<Decimal>, last_checked_at: Option<DateTime<Utc>>, triggered_rate: Option<Decimal>,
+        triggered_at: Option<DateTime<Utc>>, swap_id: Option<String>, error: Option<String>,
+        created_at: DateTime<Utc>, updated_at: DateTime<Utc>,
+    }
+    ReconciliationRun => "provider_reconciliation_runs" {
+        id: String, started_at: DateTime<Utc>, finished_at: Option<DateTime<Utc>>, swaps_checked: u32,
+        discrepancies: u32, errors: u32,
+    }
+    BackupRun => "backup_runs" {
+        id: String, key_fingerprint: String, object_key: Option<String>, size_bytes: Option<u64>,
+        sha256: Option<String>, manifest: Option<String>, started_at: DateTime<Utc>,
+        finished_at: Option<DateTime<Utc>>, verified_at: Option<DateTime<Utc>>, error: Option<String>,
+    }
+    PayoutGuardTrip => "payout_guard_trips" {
+        id: String, cause: TripCause, reason: String, tripped_by: Option<String>, tripped_at: DateTime<Utc>,
+        rearmed_at: Option<DateTime<Utc>>, rearmed_by: Option<String>, rearm_note: Option<String>,
+    }
+    ProviderDiscrepancy => "provider_discrepancies" {
+        id: String, run_id: String, swap_id: String, provider_id: String, provider_swap_id: String,
+        kind: DiscrepancyKind, our_value: String, provider_value: String, status: DiscrepancyStatus,
+        note: Option<String>, reviewed_by: Option<String>, reviewed_at: Option<DateTime<Utc>>,
+        created_at: DateTime<Utc>, updated_at: DateTime<Utc>,
+    }
+    WrongNetworkCase => "wrong_network_cases" {
+        id: String, swap_id: String, address: String, address_index: u32, expected_network: String,
+        detected_network: String, amount: f64, status: WrongNetworkStatus, recovery_address: Option<String>,
+        recovery_tx_hash: Option<String>, recovered_amount: Option<f64>, error: Option<String>,
+        dismiss_reason: Option<String>, resolved_by: Option<String>, resolved_at: Option<DateTime<Utc>>,
+        created_at: DateTime<Utc>, updated_at: DateTime<Utc>,
+    }
+    MemoDeposit => "memo_deposits" {
+        id: i64, network: String, tx_hash: String, memo: Option<String>, amount: f64, swap_id: Option<String>,
+        status: MemoDepositStatus, reason: Option<String>, note: Option<String>, resolved_by: Option<String>,
+        resolved_at: Option<DateTime<Utc>>, detected_at: DateTime<Utc>,
+    }
+    WalletAuditRun => "wallet_audit_runs" {
+        id: String, started_at: DateTime<Utc>, finished_at: Option<DateTime<Utc>>, indices_checked: u32,
+        balance_checks: u32, findings: u32, errors: u32, unaudited_indices: u32, unaudited_chains: String,
+    }
+    OrphanedFunds => "orphaned_funds" {
+        id: String, run_id: String, address_index: u32, address: String, chain: String, currency: String,
+        token_contract: Option<String>, swap_id: String, swap_status: Option<String>, kind: OrphanedFundsKind, amount: f64, status: OrphanedFundsStatus,
+        recovery_address: Option<String>, recovery_tx_hash: Option<String>, recovered_amount: Option<f64>,
+        error: Option<String>, dismiss_reason: Option<String>, resolved_by: Option<String>,
+        resolved_at: Option<DateTime<Utc>>, created_at: DateTime<Utc>, updated_at: DateTime<Utc>,
+    }
+    SwapSchedule => "swap_schedules" {
+        id: String, user_id: String, from_currency: String, from_network: String, to_currency: String,
+        to_network: String, amount: Decimal, provider: String, recipient_address: String,
+        recipient_extra_id: Option<String>, refund_address: Option<String>, refund_extra_id: Option<String>,
+        payout_to_balance: bool, frequency: ScheduleFrequency, status: ScheduleStatus,
+        next_run_at: DateTime<Utc>, last_run_at: Option<DateTime<Utc>>, last_swap_id: Option<String>,
+        consecutive_failures: i32, last_error: Option<String>, created_at: DateTime<Utc>,
+        updated_at: DateTime<Utc>,
+    }
+    ScheduleRun => "swap_schedule_runs" {
+        id: i64, schedule_id: String, swap_id: Option<String>, succeeded: bool, error: Option<String>,
+        created_at: DateTime<Utc>,
+    }
+    Provider => "providers" {
+        id: String, name: String, slug: String, is_active: bool, kyc_rating: String,
+        insurance_percentage: Option<f64>, eta_minutes: Option<i32>, markup_enabled: bool,
+        api_url: Option<String>, logo_url: Option<String>, website_url: Option<String>,
+        last_synced_at: Option<DateTime<Utc>>, created_at: DateTime<Utc>, updated_at: DateTime<Utc>,
+    }
+    Currency => "currencies" {
+        id: i64, symbol: String, name: String, network: String, is_active: bool, logo_url: Option<String>,
+        contract_address: Option<String>, decimals: i32, requires_extra_id: bool,
+        extra_id_name: Option<String>, min_amount: Option<f64>, max_amount: Option<f64>,
+        last_synced_at: Option<DateTime<Utc>>, created_at: DateTime<Utc>, updated_at: DateTime<Utc>,
+    }
+    TradingPair => "trading_pairs" {
+        id: i64, from_currency_id: i64, to_currency_id: i64, is_active: bool, created_at: DateTime<Utc>,
+        updated_at: DateTime<Utc>,
+    }
+    Swap => "swaps" {
+        id: String, user_id: Option<String>, client_reference: Option<String>, provider_id: String, provider_swap_id: Option<String>,
+        from_currency: String, from_network: String, to_currency: String, to_network: String, amount: Decimal,
+        estimated_receive: Decimal, actual_receive: Option<Decimal>, rate: Decimal, network_fee: Decimal,
+        provider_fee: Decimal, platform_fee: Decimal, total_fee: Decimal, deposit_address: String, deposit_extra_id: Option<String>,
+        recipient_address: String, recipient_extra_id: Option<String>, refund_address: Option<String>,
+        refund_extra_id: Option<String>, tx_hash_in: Option<String>, tx_hash_out: Option<String>,
+        status: SwapStatus, rate_type: RateType, is_sandbox: bool, error: Option<String>,
+        expires_at: Option<DateTime<Utc>>, completed_at: Option<DateTime<Utc>>, created_at: DateTime<Utc>,
+        updated_at: DateTime<Utc>,
+    }
+    ProviderOrderIntent => "provider_order_intents" {
+        swap_id: String, provider_id: String, provider_swap_id: Option<String>, our_address: String,
+        address_index: u32, status: String, last_error: Option<String>, created_at: DateTime<Utc>,
+        updated_at: DateTime<Utc>,
+    }
+    SwapStatusHistory => "swap_status_history" {
+        id: i64, swap_id: String, status: SwapStatus, actor: String, message: Option<String>,
+        created_at: DateTime<Utc>,
+    }
+    ProviderPayload => "provider_payloads" {
+        id: i64, swap_id: String, provider: String, kind: PayloadKind, payload: String, payload_hash: String,
+        original_bytes: u32, truncated: bool, created_at: DateTime<Utc>,
+    }
+    SwapSlaBreach => "swap_sla_breaches" {
+        id: i64, swap_id: String, status: SwapStatus, entered_at: DateTime<Utc>, sla_minutes: u32,
+        escalation: SlaEscalation, ticket_reference: Option<String>, detected_at: DateTime<Utc>,
+        escalated_at: DateTime<Utc>, resolved_at: Option<DateTime<Utc>>,
+    }
+    ProviderCurrency => "provider_currencies" {
+        id: i64, provider_id: String, currency_id: i64, is_active: bool, created_at: DateTime<Utc>,
+        updated_at: DateTime<Utc>,
+    }
+    RateCache => "rate_cache" {
+        id: i64, provider_id: String, from_currency: String, to_currency: String, amount: f64, rate: f64,
+        estimated_amount: f64, min_amount: f64, max_amount: f64, network_fee: f64, rate_type: RateType,
+        expires_at: DateTime<Utc>, created_at: DateTime<Utc>,
+    }
+    SwapAddressInfo => "swap_address_info" {
+        swap_id: String, our_address: String, address_index: u32, blockchain_id: i32, coin_type: i32,
+        recipient_address: String, recipient_extra_id: Option<String>, commission_rate: f64,
+        payout_tx_hash: Option<String>, payout_amount: Option<Decimal>, status: String, created_at: DateTime<Utc>,
+        signed_at: Option<DateTime<Utc>>, broadcast_at: Option<DateTime<Utc>>,
+        confirmed_at: Option<DateTime<Utc>>, confirmation_status: Option<PayoutConfirmation>,
+        confirmations: Option<u32>, finalized_at: Option<DateTime<Utc>>,
+    }
+}
+
+/// Fields without a column or with a type their column can't decode into
+/// are errors; a non-`Option` field over a nullable column is a warning.
+pub fn check(catalog: &Catalog, specs: &[ModelSpec]) -> Vec<SchemaIssue> {
+    let mut issues = Vec::new();
+
+    for spec in specs {
+        let Some(columns) = catalog.get(spec.table) else {
+            issues.push(SchemaIssue::error(spec.table, format!("table read by {} does not exist", spec.model)));
+            continue;
+        };
+
+        for field in &spec.fields {
+            let subject = format!("{}.{}", spec.table, field.name);
+            let Some(column) = columns.get(field.name) else {
+                issues.push(SchemaIssue::error(subject, format!("no column for {}.{}", spec.model, field.name)));
+                continue;
+            };
+
+            if !field.kind.accepts(column) {
+                issues.push(SchemaIssue::error(
+                    subject,
+                    format!(
+                        "{}.{} is a {} but the column is {}",
+                        spec.model,
+                        field.name,
+                        field.kind.as_str(),
+                        column.column_type
+                    ),
+                ));
+            } else if column.nullable && !field.nullable {
+                issues.push(SchemaIssue::warning(
+                    subject,
+                    format!("column is nullable but {}.{} is not an Option; a NULL fails to decode", spec.model, field.name),
+                ));
+            }
+        }
+    }
+
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema_check::Severity;
+
+    fn catalog() -> Catalog {
+        let mut columns = std::collections::BTreeMap::new();
+        columns.insert("id".to_string(), ColumnInfo::new("varchar", "varchar(36)", false));
+        columns.insert("attempts".to_string(), ColumnInfo::new("int", "int unsigned", false));
+        columns.insert("enabled".to_string(), ColumnInfo::new("tinyint", "tinyint(1)", true));
+        columns.insert("amount".to_string(), ColumnInfo::new("decimal", "decimal(20,8)", false));
+
+        let mut catalog = Catalog::new();
+        catalog.insert("jobs".to_string(), columns);
+        catalog
+    }
+
+    fn field(name: &'static str, kind: ColumnKind, nullable: bool) -> FieldSpec {
+        FieldSpec { name, kind, nullable }
+    }
+
+    #[test]
+    fn test_matching_model_has_no_issues() {
+        let spec = ModelSpec {
+            model: "Job",
+            table: "jobs",
+            fields: vec![
+                field("id", ColumnKind::Text, false),
+                field("attempts", ColumnKind::UnsignedInt, false),
+                field("enabled", ColumnKind::Bool, true),
+                field("amount", ColumnKind::Float, false),
+            ],
+        };
+        assert!(check(&catalog(), &[spec]).is_empty());
+    }
+
+    #[test]
+    fn test_drift_is_reported() {
+        let spec = ModelSpec {
+            model: "Job",
+            table: "jobs",
+            fields: vec![
+                field("attempts", ColumnKind::Int, false),
+                field("enabled", ColumnKind::Bool, false),
+                field("renamed", ColumnKind::Text, false),
+            ],
+        };
+        let issues = check(&catalog(), &[spec]);
+        assert_eq!(issues.len(), 3);
+
+        assert_eq!(issues[0].subject, "jobs.attempts");
+        assert_eq!(issues[0].severity, Severity::Error);
+        assert!(issues[0].message.contains("int unsigned"));
+
+        assert_eq!(issues[1].severity, Severity::Warning);
+        assert_eq!(issues[2].subject, "jobs.renamed");
+        assert_eq!(issues[2].severity, Severity::Error);
+    }
+
+    #[test]
+    fn test_missing_table() {
+        let spec = ModelSpec { model: "Gone", table: "gone", fields: vec![] };
+        let issues = check(&catalog(), &[spec]);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].severity, Severity::Error);
+    }
+
+    #[test]
+    fn test_specs_cover_option_fields() {
+        let specs = model_specs();
+        let swap = specs.iter().find(|s| s.model == "Swap").unwrap();
+        let user_id = swap.fields.iter().find(|f| f.name == "user_id").unwrap();
+        assert!(user_id.nullable);
+        assert_eq!(user_id.kind, ColumnKind::Text);
+
+        let status = swap.fields.iter().find(|f| f.name == "status").unwrap();
+        assert!(!status.nullable);
+    }
+}
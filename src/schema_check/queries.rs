@@ -0,0 +1,298 @@
+//! Offline `sqlx::query!` metadata versus the migrated schema.
+//!
+//! `cargo sqlx prepare` records each checked query's result columns and
+//! parameters in `.sqlx/query-<hash>.json`, and offline builds trust those
+//! files. Re-describing every query against the migrated schema catches
+//! metadata that a migration has silently invalidated.
+
+use serde::Deserialize;
+use sqlx::{Either, Executor, MySqlPool, TypeInfo};
+use std::path::Path;
+
+use super::SchemaIssue;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryColumn {
+    pub name: String,
+    pub type_name: String,
+    /// `None` when sqlx could not tell
+    pub nullable: Option<bool>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryShape {
+    pub columns: Vec<QueryColumn>,
+    /// `None` when the driver does not report parameters
+    pub parameters: Option<usize>,
+}
+
+#[derive(Debug, Clone)]
+pub struct OfflineQuery {
+    /// File name, e.g. `query-3f2a...json`
+    pub file: String,
+    pub query: String,
+    pub shape: QueryShape,
+}
+
+#[derive(Deserialize)]
+struct OfflineFile {
+    query: String,
+    describe: OfflineDescribe,
+}
+
+#[derive(Deserialize)]
+struct OfflineDescribe {
+    columns: Vec<OfflineColumn>,
+    parameters: serde_json::Value,
+    nullable: Vec<Option<bool>>,
+}
+
+#[derive(Deserialize)]
+struct OfflineColumn {
+    name: String,
+    type_info: OfflineTypeInfo,
+}
+
+#[derive(Deserialize)]
+struct OfflineTypeInfo {
+    r#type: String,
+}
+
+/// Parse one `.sqlx/query-*.json` file
+pub fn parse(file: &str, json: &str) -> Result<OfflineQuery, String> {
+    let parsed: OfflineFile = serde_json::from_str(json).map_err(|e| format!("{}: {}", file, e))?;
+
+    let columns = parsed
+        .describe
+        .columns
+        .into_iter()
+        .enumerate()
+        .map(|(i, column)| QueryColumn {
+            name: column.name,
+            type_name: column.type_info.r#type,
+            nullable: parsed.describe.nullable.get(i).copied().flatten(),
+        })
+        .collect();
+
+    // `{"Right": 2}` is a parameter count, `{"Left": [...]}` a type list
+    let parameters = match &parsed.describe.parameters {
+        serde_json::Value::Object(map) => match (map.get("Right"), map.get("Left")) {
+            (Some(n), _) => n.as_u64().map(|n| n as usize),
+            (_, Some(types)) => types.as_array().map(|t| t.len()),
+            _ => None,
+        },
+        _ => None,
+    };
+
+    Ok(OfflineQuery {
+        file: file.to_string(),
+        query: parsed.query,
+        shape: QueryShape { columns, parameters },
+    })
+}
+
+/// Every `query-*.json` file in `dir`, sorted by name
+pub fn load_dir(dir: &Path) -> Result<Vec<OfflineQuery>, String> {
+    let entries = std::fs::read_dir(dir).map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?;
+
+    let mut files: Vec<_> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with("query-") && n.ends_with(".json"))
+        })
+        .collect();
+    files.sort();
+
+    files
+        .iter()
+        .map(|path| {
+            let name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+            let json =
+                std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+            parse(name, &json)
+        })
+        .collect()
+}
+
+/// Coarse grouping of MySQL type names. sqlx reports wire types offline
+/// (`VarString`, `LongLong`) and may report either form live, so only a
+/// change of family is treated as drift.
+pub fn type_family(type_name: &str) -> Option<&'static str> {
+    let base = type_name.split_whitespace().next().unwrap_or_default().to_uppercase();
+    match base.as_str() {
+        "NULL" => None,
+        "VARCHAR" | "CHAR" | "TEXT" | "TINYTEXT" | "MEDIUMTEXT" | "LONGTEXT" | "BLOB" | "TINYBLOB"
+        | "MEDIUMBLOB" | "LONGBLOB" | "BINARY" | "VARBINARY" | "ENUM" | "SET" | "JSON" | "VARSTRING"
+        | "STRING" => Some("text"),
+        "TINY" | "SHORT" | "LONG" | "LONGLONG" | "INT24" | "YEAR" | "BIT" | "TINYINT" | "SMALLINT"
+        | "MEDIUMINT" | "INT" | "BIGINT" | "BOOLEAN" => Some("integer"),
+        "FLOAT" | "DOUBLE" | "DECIMAL" | "NEWDECIMAL" => Some("numeric"),
+        "TIMESTAMP" | "DATETIME" | "DATE" | "TIME" => Some("temporal"),
+        _ => Some("other"),
+    }
+}
+
+/// Differences between the recorded shape of a query and its live shape.
+/// Anything the generated code would decode differently is an error; a
+/// column that became NOT NULL only makes an `Option` unnecessary.
+pub fn compare(subject: &str, offline: &QueryShape, live: &QueryShape) -> Vec<SchemaIssue> {
+    let mut issues = Vec::new();
+
+    if offline.columns.len() != live.columns.len() {
+        issues.push(SchemaIssue::error(
+            subject,
+            format!("returns {} columns, offline data has {}", live.columns.len(), offline.columns.len()),
+        ));
+        return issues;
+    }
+
+    for (recorded, current) in offline.columns.iter().zip(&live.columns) {
+        if recorded.name != current.name {
+            issues.push(SchemaIssue::error(
+                subject,
+                format!("column `{}` is now `{}`", recorded.name, current.name),
+            ));
+            continue;
+        }
+
+        let (was, is) = (type_family(&recorded.type_name), type_family(&current.type_name));
+        if was.is_some() && is.is_some() && was != is {
+            issues.push(SchemaIssue::error(
+                subject,
+                format!("column `{}` changed from {} to {}", recorded.name, recorded.type_name, current.type_name),
+            ));
+        }
+
+        match (recorded.nullable, current.nullable) {
+            (Some(false), Some(true)) => issues.push(SchemaIssue::error(
+                subject,
+                format!("column `{}` is now nullable but offline data says NOT NULL", recorded.name),
+            )),
+            (Some(true), Some(false)) => issues.push(SchemaIssue::warning(
+                subject,
+                format!("column `{}` is now NOT NULL but offline data says nullable", recorded.name),
+            )),
+            _ => {}
+        }
+    }
+
+    if let (Some(recorded), Some(current)) = (offline.parameters, live.parameters) {
+        if recorded != current {
+            issues.push(SchemaIssue::error(
+                subject,
+                format!("takes {} parameters, offline data has {}", current, recorded),
+            ));
+        }
+    }
+
+    issues
+}
+
+async fn describe(pool: &MySqlPool, query: &str) -> Result<QueryShape, sqlx::Error> {
+    let described = pool.describe(query).await?;
+
+    let columns = described
+        .columns()
+        .iter()
+        .enumerate()
+        .map(|(i, column)| QueryColumn {
+            name: sqlx::Column::name(column).to_string(),
+            type_name: sqlx::Column::type_info(column).name().to_string(),
+            nullable: described.nullable(i),
+        })
+        .collect();
+
+    let parameters = match described.parameters() {
+        Some(Either::Left(types)) => Some(types.len()),
+        Some(Either::Right(count)) => Some(count),
+        None => None,
+    };
+
+    Ok(QueryShape { columns, parameters })
+}
+
+/// Describe each offline query against `pool` and compare. A query that no
+/// longer prepares (missing table or column) is an error.
+pub async fn check(pool: &MySqlPool, offline: &[OfflineQuery]) -> Vec<SchemaIssue> {
+    let mut issues = Vec::new();
+
+    for query in offline {
+        match describe(pool, &query.query).await {
+            Ok(live) => issues.extend(compare(&query.file, &query.shape, &live)),
+            Err(e) => issues.push(SchemaIssue::error(&query.file, format!("no longer prepares: {}", e))),
+        }
+    }
+
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema_check::Severity;
+
+    const OFFLINE: &str = r#"{
+        "db_name": "MySQL",
+        "query": "SELECT id, error FROM swaps WHERE id = ?",
+        "describe": {
+            "columns": [
+                { "ordinal": 0, "name": "id", "type_info": { "type": "VarString", "flags": "NOT_NULL", "max_size": 144 } },
+                { "ordinal": 1, "name": "error", "type_info": { "type": "Blob", "flags": "", "max_size": 262140 } }
+            ],
+            "parameters": { "Right": 1 },
+            "nullable": [false, true]
+        },
+        "hash": "abc"
+    }"#;
+
+    fn column(name: &str, type_name: &str, nullable: bool) -> QueryColumn {
+        QueryColumn { name: name.to_string(), type_name: type_name.to_string(), nullable: Some(nullable) }
+    }
+
+    #[test]
+    fn test_parse_offline_file() {
+        let query = parse("query-abc.json", OFFLINE).unwrap();
+        assert_eq!(query.query, "SELECT id, error FROM swaps WHERE id = ?");
+        assert_eq!(query.shape.parameters, Some(1));
+        assert_eq!(query.shape.columns, vec![column("id", "VarString", false), column("error", "Blob", true)]);
+    }
+
+    #[test]
+    fn test_type_families() {
+        assert_eq!(type_family("VarString"), type_family("VARCHAR"));
+        assert_eq!(type_family("LongLong"), type_family("BIGINT UNSIGNED"));
+        assert_eq!(type_family("NewDecimal"), Some("numeric"));
+        assert_eq!(type_family("Datetime"), Some("temporal"));
+        assert_eq!(type_family("Null"), None);
+    }
+
+    #[test]
+    fn test_matching_shapes() {
+        let offline = parse("query-abc.json", OFFLINE).unwrap().shape;
+        let live = QueryShape {
+            columns: vec![column("id", "VARCHAR", false), column("error", "TEXT", true)],
+            parameters: Some(1),
+        };
+        assert!(compare("q", &offline, &live).is_empty());
+    }
+
+    #[test]
+    fn test_drift_is_reported() {
+        let offline = parse("query-abc.json", OFFLINE).unwrap().shape;
+
+        let live = QueryShape {
+            columns: vec![column("id", "BIGINT", true), column("error", "TEXT", false)],
+            parameters: Some(2),
+        };
+        let issues = compare("q", &offline, &live);
+        let severities: Vec<_> = issues.iter().map(|i| i.severity).collect();
+        assert_eq!(severities, vec![Severity::Error, Severity::Error, Severity::Warning, Severity::Error]);
+
+        let renamed = QueryShape { columns: vec![column("id", "VARCHAR", false)], parameters: Some(1) };
+        let issues = compare("q", &offline, &renamed);
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].message.contains("returns 1 columns"));
+    }
+}
@@ -0,0 +1,266 @@
+//! Recipient address reputation. Fraud rings tend to cash out many
+//! throwaway accounts to the same few addresses, so every swap's payout
+//! address is counted against the accounts that used it. An address shared
+//! by enough accounts, or by accounts already suspended or banned, is
+//! flagged for review; past a higher score each account that used it gets
+//! an address-reuse signal in the risk engine.
+//!
+//! Addresses are keyed by the PII blind index of their normalized form, so
+//! lookups work while the address itself is stored encrypted and the key
+//! can't be matched against a list of candidate addresses.
+
+use sha2::{Digest, Sha256};
+use sqlx::{MySql, Pool};
+use std::sync::OnceLock;
+
+use crate::modules::moderation::crud::{ModerationCrud, ModerationError};
+use crate::modules::moderation::model::{AddressReputation, ReputationStatus, RiskSignalKind};
+use crate::services::encryption::FieldCipher;
+use crate::services::pii::{pii_cipher, SealedString};
+use crate::services::risk::{RiskEngine, SignalContext};
+
+/// Scoring rules, from ADDRESS_REUSE_* settings
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AddressReusePolicy {
+    /// Points per account beyond the first that paid out to the address
+    pub account_points: u32,
+    /// Points per suspended or banned account that used it
+    pub restricted_account_points: u32,
+    /// Score at which the address is flagged for review
+    pub review_score: u32,
+    /// Score at which the accounts that used it are reported to the risk engine
+    pub escalate_score: u32,
+}
+
+impl Default for AddressReusePolicy {
+    fn default() -> Self {
+        Self { account_points: 20, restricted_account_points: 50, review_score: 40, escalate_score: 80 }
+    }
+}
+
+impl AddressReusePolicy {
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let review_score = setting("ADDRESS_REUSE_REVIEW_SCORE").unwrap_or(defaults.review_score);
+        Self {
+            account_points: setting("ADDRESS_REUSE_ACCOUNT_POINTS").unwrap_or(defaults.account_points),
+            restricted_account_points: setting("ADDRESS_REUSE_RESTRICTED_POINTS")
+                .unwrap_or(defaults.restricted_account_points),
+            review_score,
+            escalate_score: setting("ADDRESS_REUSE_ESCALATE_SCORE")
+                .unwrap_or(defaults.escalate_score)
+                .max(review_score),
+        }
+    }
+
+    /// Policy read from the environment once per process
+    pub fn global() -> &'static AddressReusePolicy {
+        static POLICY: OnceLock<AddressReusePolicy> = OnceLock::new();
+        POLICY.get_or_init(Self::from_env)
+    }
+
+    /// Score of an address used by `accounts` accounts, `restricted` of
+    /// which are suspended or banned
+    pub fn score(&self, accounts: u32, restricted: u32) -> u32 {
+        self.account_points
+            .saturating_mul(accounts.saturating_sub(1))
+            .saturating_add(self.restricted_account_points.saturating_mul(restricted))
+    }
+
+    /// Whether the accounts behind an address in `status` with `score`
+    /// should be reported. Cleared addresses never are; confirmed ones
+    /// always are.
+    pub fn should_escalate(&self, status: ReputationStatus, score: u32) -> bool {
+        match status {
+            ReputationStatus::Cleared => false,
+            ReputationStatus::Confirmed => true,
+            ReputationStatus::Normal | ReputationStatus::Flagged => score >= self.escalate_score,
+        }
+    }
+}
+
+/// Positive integer setting; anything else falls back to the default
+fn setting(name: &str) -> Option<u32> {
+    std::env::var(name)
+        .ok()
+        .and_then(|v| v.trim().parse::<u32>().ok())
+        .filter(|v| *v > 0)
+}
+
+/// Canonical form of an address. Hex and bech32 addresses are case
+/// insensitive, so they are lowercased; base58 and the rest are not.
+pub fn normalize_address(address: &str) -> String {
+    let address = address.trim();
+    let lower = address.to_ascii_lowercase();
+    let case_insensitive = lower.starts_with("0x")
+        || ["bc1", "tb1", "bcrt1", "ltc1", "tltc1"].iter().any(|hrp| lower.starts_with(hrp));
+    if case_insensitive {
+        lower
+    } else {
+        address.to_string()
+    }
+}
+
+/// Lookup key of an address on a network
+pub fn address_hash(network: &str, address: &str) -> String {
+    address_hash_with(pii_cipher(), network, address)
+}
+
+/// Blind index of `network:address` under `cipher`. Without one the
+/// address is stored in the clear anyway and a plain SHA-256 is used; the
+/// PII rotation job rekeys those rows once encryption is turned on.
+pub fn address_hash_with(cipher: Option<&FieldCipher>, network: &str, address: &str) -> String {
+    let key = format!("{}:{}", network.trim().to_lowercase(), normalize_address(address));
+    match cipher {
+        Some(cipher) => cipher.blind_index(&key),
+        None => hex::encode(Sha256::digest(key.as_bytes())),
+    }
+}
+
+pub struct AddressReputationService {
+    db: Pool<MySql>,
+    crud: ModerationCrud,
+    policy: AddressReusePolicy,
+}
+
+impl AddressReputationService {
+    pub fn new(db: Pool<MySql>) -> Self {
+        Self { crud: ModerationCrud::new(db.clone()), db, policy: AddressReusePolicy::global().clone() }
+    }
+
+    pub fn with_policy(mut self, policy: AddressReusePolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Count a new swap against its payout address, rescore the address
+    /// and escalate if it now calls for it. Swaps paying out to an
+    /// internal balance have no address and are skipped.
+    pub async fn record_swap(&self, swap_id: &str) -> Result<Option<AddressReputation>, ModerationError> {
+        let row: Option<(Option<String>, String, SealedString)> =
+            sqlx::query_as("SELECT user_id, to_network, recipient_address FROM swaps WHERE id = ?")
+                .bind(swap_id)
+                .fetch_optional(&self.db)
+                .await?;
+        let Some((user_id, network, SealedString(address))) = row else {
+            return Ok(None);
+        };
+        if address.trim().is_empty() {
+            return Ok(None);
+        }
+
+        let hash = address_hash(&network, &address);
+        let normalized = normalize_address(&address);
+        let mut reputation = self.crud.record_address_use(&hash, &normalized, &network, user_id.as_deref()).await?;
+
+        let score = self.policy.score(reputation.account_count, reputation.restricted_accounts);
+        let flag = score >= self.policy.review_score;
+        if score != reputation.score || (flag && reputation.status == ReputationStatus::Normal) {
+            let was = reputation.status;
+            self.crud.set_address_score(&hash, score, flag).await?;
+            reputation = self.crud.get_address(&hash).await?;
+            if was == ReputationStatus::Normal && reputation.status == ReputationStatus::Flagged {
+                tracing::warn!(
+                    "Payout address {} on {} flagged: {} accounts, score {}",
+                    hash,
+                    network,
+                    reputation.account_count,
+                    score
+                );
+            }
+        }
+
+        if self.policy.should_escalate(reputation.status, reputation.score) {
+            self.escalate(&hash).await?;
+        }
+
+        Ok(Some(reputation))
+    }
+
+    /// Report every account that used the address and was not reported
+    /// for it yet. Returns how many were reported.
+    pub async fn escalate(&self, address_hash: &str) -> Result<usize, ModerationError> {
+        let reputation = self.crud.get_address(address_hash).await?;
+        let engine = RiskEngine::new(self.db.clone());
+        let detail = format!(
+            "Payout address shared by {} accounts ({} restricted), score {}",
+            reputation.account_count, reputation.restricted_accounts, reputation.score
+        );
+
+        let mut reported = 0;
+        for account in self.crud.address_accounts(address_hash).await? {
+            if account.escalated_at.is_some() || !self.crud.claim_escalation(address_hash, &account.user_id).await? {
+                continue;
+            }
+            let context = SignalContext { detail: Some(&detail), ..Default::default() };
+            let assessment = engine.report(&account.user_id, RiskSignalKind::AddressReuse, &context).await?;
+            tracing::warn!(
+                "Account {} reported for reusing payout address {} (risk score {})",
+                account.user_id,
+                address_hash,
+                assessment.score
+            );
+            reported += 1;
+        }
+
+        Ok(reported)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_address() {
+        assert_eq!(
+            normalize_address(" 0xAbC0000000000000000000000000000000000001 "),
+            "0xabc0000000000000000000000000000000000001"
+        );
+        assert_eq!(
+            normalize_address("BC1QXY2KGDYGJRSQTZQ2N0YRF2493P83KKFJHX0WLH"),
+            "bc1qxy2kgdygjrsqtzq2n0yrf2493p83kkfjhx0wlh"
+        );
+        // Base58 is case sensitive
+        assert_eq!(normalize_address("1BoatSLRHtKNngkdXEeobR76b53LETtpyT"), "1BoatSLRHtKNngkdXEeobR76b53LETtpyT");
+    }
+
+    #[test]
+    fn test_address_hash_is_per_network() {
+        let address = "0xabc0000000000000000000000000000000000001";
+        let cipher = FieldCipher::new(&[7u8; 32]).unwrap();
+        for cipher in [None, Some(&cipher)] {
+            let hash = |network, address| address_hash_with(cipher, network, address);
+            assert_eq!(hash("ethereum", address), hash("Ethereum", "0xABC0000000000000000000000000000000000001"));
+            assert_ne!(hash("ethereum", address), hash("bsc", address));
+            assert_eq!(hash("ethereum", address).len(), 64);
+        }
+    }
+
+    #[test]
+    fn test_address_hash_is_keyed() {
+        let address = "0xabc0000000000000000000000000000000000001";
+        let a = FieldCipher::new(&[1u8; 32]).unwrap();
+        let b = FieldCipher::new(&[2u8; 32]).unwrap();
+        let plain = address_hash_with(None, "ethereum", address);
+        assert_ne!(address_hash_with(Some(&a), "ethereum", address), plain);
+        assert_ne!(address_hash_with(Some(&a), "ethereum", address), address_hash_with(Some(&b), "ethereum", address));
+    }
+
+    #[test]
+    fn test_score_and_escalation() {
+        let policy = AddressReusePolicy::default();
+        // One account reusing its own address is normal
+        assert_eq!(policy.score(1, 0), 0);
+        assert!(policy.score(3, 0) >= policy.review_score);
+        assert!(policy.score(4, 0) < policy.escalate_score);
+        assert!(policy.score(5, 0) >= policy.escalate_score);
+        // A banned account on the address weighs more than a stranger
+        assert!(policy.score(2, 1) >= policy.review_score);
+
+        assert!(policy.should_escalate(ReputationStatus::Flagged, policy.escalate_score));
+        assert!(!policy.should_escalate(ReputationStatus::Flagged, policy.escalate_score - 1));
+        assert!(!policy.should_escalate(ReputationStatus::Cleared, u32::MAX));
+        assert!(policy.should_escalate(ReputationStatus::Confirmed, 0));
+    }
+}
@@ -0,0 +1,273 @@
+//! Exact amounts. Money is held as `Decimal` from the moment it leaves a
+//! provider API or the database until it is turned into integer chain units
+//! for a transaction, so fee splits and sat/wei/lamport conversions never
+//! pick up binary floating point error (0.29 BTC is 29_000_000 sats, not
+//! 28_999_999).
+//!
+//! Some provider APIs still speak `f64`; `from_f64` is the shim at that
+//! edge. Request amounts arrive as `WireAmount`, which also takes decimal
+//! strings so clients can send amounts a float cannot hold.
+
+use rust_decimal::prelude::ToPrimitive;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+
+pub use rust_decimal::Decimal;
+
+pub const BTC_DECIMALS: u32 = 8;
+pub const EVM_NATIVE_DECIMALS: u32 = 18;
+pub const SOL_DECIMALS: u32 = 9;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AmountError {
+    NotFinite(f64),
+    Negative(Decimal),
+    Overflow(String),
+    Invalid(String),
+    /// More decimal places than the currency has
+    TooPrecise { amount: Decimal, decimals: u32 },
+}
+
+impl fmt::Display for AmountError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AmountError::NotFinite(v) => write!(f, "Amount is not a finite number: {}", v),
+            AmountError::Negative(v) => write!(f, "Amount is negative: {}", v),
+            AmountError::Overflow(v) => write!(f, "Amount out of range: {}", v),
+            AmountError::Invalid(v) => write!(f, "Invalid amount: {}", v),
+            AmountError::TooPrecise { amount, decimals } => {
+                write!(f, "Amount {} has more than {} decimal places", amount, decimals)
+            }
+        }
+    }
+}
+
+impl std::error::Error for AmountError {}
+
+/// Decimal for a float received from a provider API or legacy code. Goes
+/// through the shortest decimal representation of the float, which is the
+/// number the sender meant, rather than its exact binary expansion.
+pub fn from_f64(value: f64) -> Result<Decimal, AmountError> {
+    if !value.is_finite() {
+        return Err(AmountError::NotFinite(value));
+    }
+    Decimal::from_str(&value.to_string()).map_err(|_| AmountError::Overflow(value.to_string()))
+}
+
+pub fn parse(value: &str) -> Result<Decimal, AmountError> {
+    Decimal::from_str(value.trim()).map_err(|e| AmountError::Invalid(format!("{}: {}", value, e)))
+}
+
+/// Integer chain units (sats, wei, lamports, token base units). Digits past
+/// `decimals` are truncated, so a conversion never sends more than `amount`.
+pub fn to_minor_units(amount: Decimal, decimals: u32) -> Result<u128, AmountError> {
+    if amount.is_sign_negative() && !amount.is_zero() {
+        return Err(AmountError::Negative(amount));
+    }
+    let scale = 10u128
+        .checked_pow(decimals)
+        .and_then(|s| Decimal::from_str(&s.to_string()).ok())
+        .ok_or_else(|| AmountError::Overflow(format!("10^{}", decimals)))?;
+
+    amount
+        .checked_mul(scale)
+        .and_then(|units| units.trunc().to_u128())
+        .ok_or_else(|| AmountError::Overflow(format!("{} at {} decimals", amount, decimals)))
+}
+
+pub fn from_minor_units(units: u128, decimals: u32) -> Result<Decimal, AmountError> {
+    i128::try_from(units)
+        .ok()
+        .and_then(|units| Decimal::try_from_i128_with_scale(units, decimals).ok())
+        .map(|d| d.normalize())
+        .ok_or_else(|| AmountError::Overflow(format!("{} units at {} decimals", units, decimals)))
+}
+
+/// An amount in a request: a JSON number or a decimal string such as
+/// `"0.1"` or `"1.000000000000000001"`. Numbers go through `from_f64`;
+/// strings are exact.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct WireAmount(pub Decimal);
+
+impl WireAmount {
+    pub fn value(&self) -> Decimal {
+        self.0
+    }
+
+    /// The amount as `decimals`-place minor units and back. Refuses
+    /// negative amounts and digits the currency cannot represent rather
+    /// than truncating them.
+    pub fn normalize(&self, decimals: u32) -> Result<Self, AmountError> {
+        let amount = self.0.normalize();
+        if amount.scale() > decimals {
+            return Err(AmountError::TooPrecise { amount, decimals });
+        }
+        let units = to_minor_units(amount, decimals)?;
+        from_minor_units(units, decimals).map(Self)
+    }
+}
+
+impl From<f64> for WireAmount {
+    /// For amounts built in code; non-finite values become zero
+    fn from(value: f64) -> Self {
+        Self(from_f64(value).unwrap_or_default())
+    }
+}
+
+impl From<Decimal> for WireAmount {
+    fn from(value: Decimal) -> Self {
+        Self(value)
+    }
+}
+
+impl fmt::Display for WireAmount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Serialize for WireAmount {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for WireAmount {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct Visitor;
+
+        impl de::Visitor<'_> for Visitor {
+            type Value = WireAmount;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a number or a decimal string")
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<WireAmount, E> {
+                parse(value).map(WireAmount).map_err(E::custom)
+            }
+
+            fn visit_f64<E: de::Error>(self, value: f64) -> Result<WireAmount, E> {
+                from_f64(value).map(WireAmount).map_err(E::custom)
+            }
+
+            fn visit_u64<E: de::Error>(self, value: u64) -> Result<WireAmount, E> {
+                Ok(WireAmount(Decimal::from(value)))
+            }
+
+            fn visit_i64<E: de::Error>(self, value: i64) -> Result<WireAmount, E> {
+                Ok(WireAmount(Decimal::from(value)))
+            }
+        }
+
+        deserializer.deserialize_any(Visitor)
+    }
+}
+
+impl schemars::JsonSchema for WireAmount {
+    fn inline_schema() -> bool {
+        true
+    }
+
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        "WireAmount".into()
+    }
+
+    fn json_schema(_: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        schemars::json_schema!({
+            "type": ["string", "number"],
+            "description": "Decimal string (exact) or number"
+        })
+    }
+}
+
+/// `#[serde(with = "amount::float_list")]` for a `Vec<Decimal>` sent as JSON
+/// numbers, the list form of `rust_decimal::serde::float`
+pub mod float_list {
+    use super::Decimal;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    #[derive(Serialize, Deserialize)]
+    struct Float(#[serde(with = "rust_decimal::serde::float")] Decimal);
+
+    pub fn serialize<S: Serializer>(values: &[Decimal], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(values.iter().copied().map(Float))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Decimal>, D::Error> {
+        Ok(Vec::<Float>::deserialize(deserializer)?.into_iter().map(|f| f.0).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_f64_uses_shortest_representation() {
+        assert_eq!(from_f64(0.29).unwrap(), parse("0.29").unwrap());
+        assert_eq!(from_f64(0.1).unwrap() + from_f64(0.2).unwrap(), parse("0.3").unwrap());
+        assert_eq!(from_f64(1e-8).unwrap(), parse("0.00000001").unwrap());
+        assert!(matches!(from_f64(f64::NAN), Err(AmountError::NotFinite(_))));
+    }
+
+    #[test]
+    fn test_minor_units_are_exact() {
+        // f64 gives 28999999 here
+        assert_eq!((0.29f64 * 100_000_000.0) as u64, 28_999_999);
+        assert_eq!(to_minor_units(from_f64(0.29).unwrap(), BTC_DECIMALS).unwrap(), 29_000_000);
+
+        assert_eq!(
+            to_minor_units(parse("1.000000000000000001").unwrap(), EVM_NATIVE_DECIMALS).unwrap(),
+            1_000_000_000_000_000_001
+        );
+        assert_eq!(to_minor_units(parse("0.0000000019").unwrap(), SOL_DECIMALS).unwrap(), 1);
+        assert!(matches!(to_minor_units(parse("-1").unwrap(), 8), Err(AmountError::Negative(_))));
+    }
+
+    #[test]
+    fn test_minor_units_round_trip() {
+        for units in [0u128, 1, 546, 29_000_000, 2_100_000_000_000_000] {
+            let amount = from_minor_units(units, BTC_DECIMALS).unwrap();
+            assert_eq!(to_minor_units(amount, BTC_DECIMALS).unwrap(), units);
+        }
+        assert_eq!(from_minor_units(1_500_000_000_000_000_000, EVM_NATIVE_DECIMALS).unwrap(), parse("1.5").unwrap());
+    }
+
+    #[test]
+    fn test_wire_amount_accepts_numbers_and_strings() {
+        let from_str: WireAmount = serde_json::from_str("\"1.000000000000000001\"").unwrap();
+        assert_eq!(from_str.value(), parse("1.000000000000000001").unwrap());
+        let from_number: WireAmount = serde_json::from_str("0.29").unwrap();
+        assert_eq!(from_number.value(), parse("0.29").unwrap());
+        assert_eq!(serde_json::from_str::<WireAmount>("2").unwrap().value(), Decimal::from(2));
+        assert!(serde_json::from_str::<WireAmount>("\"abc\"").is_err());
+    }
+
+    #[test]
+    fn test_wire_amount_normalize() {
+        let amount = WireAmount(parse("0.12345678").unwrap());
+        assert_eq!(amount.normalize(BTC_DECIMALS).unwrap(), amount);
+        // Trailing zeros are not precision
+        assert!(WireAmount(parse("0.100000000000").unwrap()).normalize(2).is_ok());
+        assert!(matches!(
+            WireAmount(parse("0.123456789").unwrap()).normalize(BTC_DECIMALS),
+            Err(AmountError::TooPrecise { decimals: 8, .. })
+        ));
+        assert!(matches!(WireAmount(parse("-1").unwrap()).normalize(8), Err(AmountError::Negative(_))));
+    }
+
+    #[test]
+    fn test_float_list_round_trips_as_numbers() {
+        #[derive(Serialize, Deserialize)]
+        struct Card {
+            #[serde(with = "float_list")]
+            denominations: Vec<Decimal>,
+        }
+
+        let card: Card = serde_json::from_str(r#"{"denominations":[25,0.29]}"#).unwrap();
+        assert_eq!(card.denominations, vec![Decimal::from(25), parse("0.29").unwrap()]);
+        assert_eq!(serde_json::to_string(&card).unwrap(), r#"{"denominations":[25.0,0.29]}"#);
+    }
+}
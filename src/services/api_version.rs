@@ -0,0 +1,346 @@
+//! API versioning. Every route is mounted under a version prefix
+//! (`/v1/swap/rates`). A request without one is negotiated: the
+//! `Api-Version` header (`2` or `v2`), then a vendor media type in `Accept`
+//! (`application/vnd.exchange.v2+json`), then API_DEFAULT_VERSION.
+//! Unprefixed paths keep working as deprecated aliases and say so in
+//! `Deprecation` and `Link` headers.
+//!
+//! A version only mounts the handlers that changed in it. Routes declared
+//! for a version in the manifest are served by that version's router; any
+//! other path falls through to the newest earlier version that has it, so
+//! /v2 runs alongside /v1 on the same state and services while endpoints
+//! move over one at a time.
+
+use axum::{
+    body::Body,
+    extract::FromRequestParts,
+    http::{header, request::Parts, HeaderMap, HeaderValue, Request, StatusCode, Uri},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Serialize;
+use std::{convert::Infallible, future::Future, pin::Pin, sync::Arc};
+use tower::{Layer, Service};
+
+use crate::manifest::route_access;
+
+/// Header naming the version a client wants, and the one it was served
+pub const API_VERSION_HEADER: &str = "api-version";
+
+/// `Accept: application/vnd.exchange.v2+json` asks for v2
+const MEDIA_TYPE_PREFIX: &str = "application/vnd.exchange.v";
+const MEDIA_TYPE_SUFFIX: &str = "+json";
+
+/// Served as they are, outside any version, so load balancers and monitors
+/// never depend on negotiation
+const UNVERSIONED_PATHS: &[&str] = &["/", "/health"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ApiVersion {
+    V1,
+    /// In development; only handlers declared for it differ from v1
+    V2,
+}
+
+impl ApiVersion {
+    pub const ALL: [ApiVersion; 2] = [ApiVersion::V1, ApiVersion::V2];
+
+    pub fn number(&self) -> u16 {
+        match self {
+            ApiVersion::V1 => 1,
+            ApiVersion::V2 => 2,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApiVersion::V1 => "v1",
+            ApiVersion::V2 => "v2",
+        }
+    }
+
+    /// Path prefix the version is mounted under
+    pub fn prefix(&self) -> &'static str {
+        match self {
+            ApiVersion::V1 => "/v1",
+            ApiVersion::V2 => "/v2",
+        }
+    }
+
+    /// `2`, `v2` or `V2`
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        let number = value.strip_prefix(['v', 'V']).unwrap_or(value);
+        Self::ALL.into_iter().find(|v| number == v.number().to_string())
+    }
+}
+
+/// Split a leading version segment off `path`:
+/// `/v1/swap/rates` -> `(Some(V1), "/swap/rates")`
+pub fn split_version(path: &str) -> (Option<ApiVersion>, &str) {
+    for version in ApiVersion::ALL {
+        if let Some(rest) = path.strip_prefix(version.prefix()) {
+            if rest.is_empty() {
+                return (Some(version), "/");
+            }
+            if rest.starts_with('/') {
+                return (Some(version), rest);
+            }
+        }
+    }
+    (None, path)
+}
+
+/// Whether `path` is served outside any version, like `/health`
+pub fn is_unversioned(path: &str) -> bool {
+    UNVERSIONED_PATHS.contains(&path)
+}
+
+/// `path` without its version prefix, for policies written against
+/// version-independent paths
+pub fn unversioned(path: &str) -> &str {
+    split_version(path).1
+}
+
+// =============================================================================
+// NEGOTIATION
+// =============================================================================
+
+/// Version a request is served under, set by [`ApiVersionLayer`]. Requests
+/// that never went through the layer count as unprefixed v1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RequestedVersion {
+    pub version: ApiVersion,
+    /// The path named the version, rather than headers or the default
+    pub prefixed: bool,
+}
+
+impl Default for RequestedVersion {
+    fn default() -> Self {
+        Self { version: ApiVersion::V1, prefixed: false }
+    }
+}
+
+impl RequestedVersion {
+    /// Prefix of the paths the client calls; empty for unprefixed aliases
+    pub fn base_path(&self) -> &'static str {
+        if self.prefixed {
+            self.version.prefix()
+        } else {
+            ""
+        }
+    }
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for RequestedVersion {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(parts.extensions.get::<RequestedVersion>().copied().unwrap_or_default())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionPolicy {
+    /// Version of unprefixed requests that don't ask for one
+    pub default: ApiVersion,
+}
+
+impl Default for VersionPolicy {
+    fn default() -> Self {
+        Self { default: ApiVersion::V1 }
+    }
+}
+
+impl VersionPolicy {
+    pub fn from_env() -> Result<Self, String> {
+        let mut policy = Self::default();
+        if let Ok(val) = std::env::var("API_DEFAULT_VERSION") {
+            policy.default =
+                ApiVersion::parse(&val).ok_or_else(|| format!("Invalid API_DEFAULT_VERSION: `{}`", val.trim()))?;
+        }
+        Ok(policy)
+    }
+
+    /// Version asked for in `headers`, or the default. Naming a version we
+    /// don't serve is an error rather than a silent fallback.
+    pub fn negotiate(&self, headers: &HeaderMap) -> Result<ApiVersion, String> {
+        if let Some(value) = headers.get(API_VERSION_HEADER) {
+            let value = value.to_str().unwrap_or_default();
+            return ApiVersion::parse(value).ok_or_else(|| format!("Unsupported API version `{}`", value.trim()));
+        }
+
+        let accept = headers.get_all(header::ACCEPT).iter().filter_map(|v| v.to_str().ok());
+        for media_type in accept.flat_map(|v| v.split(',')) {
+            let media_type = media_type.split(';').next().unwrap_or_default().trim();
+            let Some(rest) = media_type.strip_prefix(MEDIA_TYPE_PREFIX) else {
+                continue;
+            };
+            let number = rest.strip_suffix(MEDIA_TYPE_SUFFIX).unwrap_or(rest);
+            return ApiVersion::parse(number).ok_or_else(|| format!("Unsupported API version in `{}`", media_type));
+        }
+
+        Ok(self.default)
+    }
+}
+
+/// Version whose handler serves `path` for a `version` request: the
+/// version itself when it declares the route, otherwise the newest earlier
+/// one. Paths the manifest doesn't list stay on v1, which mounts everything.
+pub fn handler_version(version: ApiVersion, method: &str, path: &str) -> ApiVersion {
+    let versioned = format!("{}{}", version.prefix(), path);
+    route_access(method, &versioned).map(|route| route.api_version()).unwrap_or(ApiVersion::V1)
+}
+
+#[derive(Serialize)]
+struct ApiVersionErrorResponse {
+    error: String,
+    supported: Vec<&'static str>,
+}
+
+fn unsupported(error: String) -> Response {
+    let supported = ApiVersion::ALL.iter().map(|v| v.as_str()).collect();
+    (StatusCode::BAD_REQUEST, Json(ApiVersionErrorResponse { error, supported })).into_response()
+}
+
+// =============================================================================
+// LAYER
+// =============================================================================
+
+/// Negotiates the version and rewrites the path to the router that serves
+/// it. Runs before routing, so it wraps the whole router.
+#[derive(Clone)]
+pub struct ApiVersionLayer {
+    policy: Arc<VersionPolicy>,
+}
+
+impl ApiVersionLayer {
+    pub fn new(policy: VersionPolicy) -> Self {
+        Self { policy: Arc::new(policy) }
+    }
+}
+
+impl<S> Layer<S> for ApiVersionLayer {
+    type Service = ApiVersionService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ApiVersionService { inner, policy: self.policy.clone() }
+    }
+}
+
+#[derive(Clone)]
+pub struct ApiVersionService<S> {
+    inner: S,
+    policy: Arc<VersionPolicy>,
+}
+
+impl<S> Service<Request<Body>> for ApiVersionService<S>
+where
+    S: Service<Request<Body>, Response = Response> + Clone + Send + 'static,
+    S::Future: Send,
+{
+    type Response = Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut std::task::Context<'_>) -> std::task::Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: Request<Body>) -> Self::Future {
+        let mut inner = self.inner.clone();
+        let path = request.uri().path().to_string();
+        if is_unversioned(&path) {
+            return Box::pin(async move { inner.call(request).await });
+        }
+
+        let (prefix, rest) = split_version(&path);
+        let requested = match prefix {
+            Some(version) => RequestedVersion { version, prefixed: true },
+            None => match self.policy.negotiate(request.headers()) {
+                Ok(version) => RequestedVersion { version, prefixed: false },
+                Err(error) => return Box::pin(async move { Ok(unsupported(error)) }),
+            },
+        };
+
+        let handler = handler_version(requested.version, request.method().as_str(), rest);
+        let mut routed = format!("{}{}", handler.prefix(), rest);
+        if let Some(query) = request.uri().query() {
+            routed.push('?');
+            routed.push_str(query);
+        }
+        if let Ok(uri) = routed.parse::<Uri>() {
+            *request.uri_mut() = uri;
+        }
+        request.extensions_mut().insert(requested);
+
+        // Where the unprefixed alias now lives, for the Link header
+        let successor = (!requested.prefixed).then(|| format!("{}{}", requested.version.prefix(), rest));
+
+        Box::pin(async move {
+            let mut response = inner.call(request).await?;
+            let headers = response.headers_mut();
+            headers.insert(API_VERSION_HEADER, HeaderValue::from(requested.version.number()));
+            if let Some(successor) = successor {
+                // The answer depends on headers the URL doesn't show
+                headers.append(header::VARY, HeaderValue::from_static("api-version, accept"));
+                headers.insert("deprecation", HeaderValue::from_static("true"));
+                if let Ok(link) = HeaderValue::from_str(&format!("<{}>; rel=\"successor-version\"", successor)) {
+                    headers.insert(header::LINK, link);
+                }
+            }
+            Ok(response)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(pairs: &[(&'static str, &'static str)]) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for (name, value) in pairs {
+            map.append(*name, HeaderValue::from_static(value));
+        }
+        map
+    }
+
+    #[test]
+    fn test_split_version() {
+        assert_eq!(split_version("/v1/swap/rates"), (Some(ApiVersion::V1), "/swap/rates"));
+        assert_eq!(split_version("/v2"), (Some(ApiVersion::V2), "/"));
+        assert_eq!(split_version("/swap/rates"), (None, "/swap/rates"));
+        assert_eq!(split_version("/v10/swap"), (None, "/v10/swap"));
+        assert_eq!(split_version("/v3/swap"), (None, "/v3/swap"));
+        assert_eq!(unversioned("/v1/auth/login"), "/auth/login");
+    }
+
+    #[test]
+    fn test_negotiation() {
+        let policy = VersionPolicy::default();
+        assert_eq!(policy.negotiate(&headers(&[])), Ok(ApiVersion::V1));
+        assert_eq!(policy.negotiate(&headers(&[("api-version", "2")])), Ok(ApiVersion::V2));
+        assert_eq!(policy.negotiate(&headers(&[("api-version", "v1")])), Ok(ApiVersion::V1));
+        assert!(policy.negotiate(&headers(&[("api-version", "7")])).is_err());
+
+        let accept = headers(&[("accept", "text/html, application/vnd.exchange.v2+json; q=0.9")]);
+        assert_eq!(policy.negotiate(&accept), Ok(ApiVersion::V2));
+        assert!(policy.negotiate(&headers(&[("accept", "application/vnd.exchange.v9+json")])).is_err());
+        assert_eq!(policy.negotiate(&headers(&[("accept", "application/json")])), Ok(ApiVersion::V1));
+
+        // The header beats Accept
+        let both = headers(&[("api-version", "1"), ("accept", "application/vnd.exchange.v2+json")]);
+        assert_eq!(policy.negotiate(&both), Ok(ApiVersion::V1));
+
+        let v2_default = VersionPolicy { default: ApiVersion::V2 };
+        assert_eq!(v2_default.negotiate(&headers(&[])), Ok(ApiVersion::V2));
+    }
+
+    #[test]
+    fn test_base_path() {
+        assert_eq!(RequestedVersion::default().base_path(), "");
+        assert_eq!(RequestedVersion { version: ApiVersion::V2, prefixed: true }.base_path(), "/v2");
+    }
+}
@@ -0,0 +1,549 @@
+//! Encrypted backups of the tables the exchange cannot rebuild from anywhere
+//! else: swaps and their history, the custodial ledger and the wallet
+//! address indexes that map deposit addresses back to HD key indexes.
+//!
+//! Each run reads every table inside one REPEATABLE READ transaction, so the
+//! snapshot is consistent across tables, writes it in the format described
+//! in [`snapshot`], seals it with `BACKUP_ENCRYPTION_KEY` and uploads it to
+//! object storage. The run only counts once the uploaded object has been
+//! downloaded, decrypted and matched against its manifest. Runs are tracked
+//! as system jobs; a failed run is published as a `backup_failed` ops event.
+//!
+//! `cargo run --bin backup` takes, verifies and restores snapshots by hand.
+
+pub mod snapshot;
+
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+use sqlx::{Connection, MySql, MySqlConnection, Pool, Row};
+use std::sync::Arc;
+
+use crate::modules::backups::crud::BackupCrud;
+use crate::modules::backups::model::BackupRun;
+use crate::modules::jobs::crud::{percent, JobCrud};
+use crate::services::events::OpsEvent;
+use crate::services::leader::LeaderElection;
+use crate::services::reconciliation::provider::until_next_run;
+use crate::services::storage::{ObjectStorage, S3Storage, StorageError};
+
+pub use snapshot::{BackupCipher, Manifest, SnapshotReader, SnapshotWriter, TableDigest};
+use snapshot::{describe_mismatch, verify_snapshot, SnapshotEntry};
+
+/// Backed-up tables, in snapshot order
+pub const BACKUP_TABLES: &[&str] = &[
+    "swaps",
+    "swap_status_history",
+    "swap_address_info",
+    "hd_derivation_paths",
+    "balance_accounts",
+    "ledger_entries",
+];
+
+const DEFAULT_HOUR_UTC: u32 = 2;
+const DEFAULT_PREFIX: &str = "backups/";
+const PAGE_SIZE: usize = 2000;
+const INSERT_BATCH: usize = 200;
+const LEADER_ROLE: &str = "backup";
+/// Column types dumped with HEX() so arbitrary bytes survive the JSON text
+const BINARY_TYPES: &[&str] = &["binary", "varbinary", "tinyblob", "blob", "mediumblob", "longblob", "bit"];
+
+#[derive(Debug, thiserror::Error)]
+pub enum BackupError {
+    #[error("Backups are not configured: {0}")]
+    NotConfigured(String),
+
+    #[error("Backup {0} is still running")]
+    AlreadyRunning(String),
+
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+
+    #[error(transparent)]
+    Storage(#[from] StorageError),
+
+    #[error("Job tracking failed: {0}")]
+    Job(String),
+
+    #[error("Snapshot error: {0}")]
+    Snapshot(String),
+
+    #[error("Verification failed: {0}")]
+    Verification(String),
+
+    #[error("Restore refused: {0}")]
+    Restore(String),
+}
+
+impl BackupError {
+    pub fn status_code(&self) -> axum::http::StatusCode {
+        use axum::http::StatusCode;
+        match self {
+            BackupError::NotConfigured(_) => StatusCode::SERVICE_UNAVAILABLE,
+            BackupError::AlreadyRunning(_) => StatusCode::CONFLICT,
+            BackupError::Restore(_) => StatusCode::BAD_REQUEST,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+// =============================================================================
+// CONFIG
+// =============================================================================
+
+#[derive(Debug, Clone)]
+pub struct BackupConfig {
+    /// None leaves backups off
+    pub cipher: Option<BackupCipher>,
+    /// UTC hour of the nightly run
+    pub hour: u32,
+    /// Object key prefix in the S3 bucket
+    pub prefix: String,
+}
+
+impl BackupConfig {
+    /// BACKUP_ENCRYPTION_KEY (64 hex characters; backups are off without
+    /// it), BACKUP_HOUR_UTC (default 2) and BACKUP_S3_PREFIX (default
+    /// `backups/`)
+    pub fn from_env() -> Result<Self, String> {
+        let cipher = match std::env::var("BACKUP_ENCRYPTION_KEY") {
+            Ok(key) if !key.trim().is_empty() => Some(BackupCipher::from_hex(&key)?),
+            _ => None,
+        };
+
+        let hour = match std::env::var("BACKUP_HOUR_UTC") {
+            Ok(v) => v
+                .trim()
+                .parse::<u32>()
+                .ok()
+                .filter(|h| *h < 24)
+                .ok_or_else(|| format!("BACKUP_HOUR_UTC must be 0-23, got '{}'", v))?,
+            Err(_) => DEFAULT_HOUR_UTC,
+        };
+
+        let prefix = std::env::var("BACKUP_S3_PREFIX")
+            .ok()
+            .filter(|p| !p.trim().is_empty())
+            .map(|p| format!("{}/", p.trim().trim_end_matches('/')))
+            .unwrap_or_else(|| DEFAULT_PREFIX.to_string());
+
+        Ok(Self { cipher, hour, prefix })
+    }
+}
+
+/// Object key for a run: `{prefix}YYYY/MM/DD/{run_id}.ndjson.gz.enc`
+pub fn object_key(prefix: &str, at: DateTime<Utc>, run_id: &str) -> String {
+    format!("{}{}/{}.ndjson.gz.enc", prefix, at.format("%Y/%m/%d"), run_id)
+}
+
+// =============================================================================
+// SERVICE
+// =============================================================================
+
+/// Takes, uploads and verifies snapshots
+pub struct BackupService {
+    db: Pool<MySql>,
+    storage: Arc<dyn ObjectStorage>,
+    cipher: BackupCipher,
+    prefix: String,
+}
+
+impl BackupService {
+    pub fn new(db: Pool<MySql>, storage: Arc<dyn ObjectStorage>, cipher: BackupCipher, prefix: &str) -> Self {
+        Self { db, storage, cipher, prefix: prefix.to_string() }
+    }
+
+    /// Service from [`BackupConfig`] and the S3 settings
+    pub fn from_env(db: Pool<MySql>) -> Result<Self, BackupError> {
+        let config = BackupConfig::from_env().map_err(BackupError::NotConfigured)?;
+        let cipher = config
+            .cipher
+            .ok_or_else(|| BackupError::NotConfigured("BACKUP_ENCRYPTION_KEY is not set".to_string()))?;
+        let storage = S3Storage::from_env().map_err(|e| BackupError::NotConfigured(e.to_string()))?;
+        Ok(Self::new(db, Arc::new(storage), cipher, &config.prefix))
+    }
+
+    pub fn key_fingerprint(&self) -> &str {
+        self.cipher.fingerprint()
+    }
+
+    /// Take a backup now and return its verified run
+    pub async fn run(&self) -> Result<BackupRun, BackupError> {
+        let run_id = self.start().await?;
+        self.complete(&run_id).await
+    }
+
+    /// Record a new run, unless one is still in progress
+    pub async fn start(&self) -> Result<String, BackupError> {
+        let crud = BackupCrud::new(self.db.clone());
+        if let Some(run) = crud.running().await? {
+            return Err(BackupError::AlreadyRunning(run.id));
+        }
+        Ok(crud.start_run(self.cipher.fingerprint()).await?)
+    }
+
+    /// Take, upload and verify the snapshot for a started run. Failures are
+    /// recorded on the run and its job and published for operators.
+    pub async fn complete(&self, run_id: &str) -> Result<BackupRun, BackupError> {
+        let crud = BackupCrud::new(self.db.clone());
+        let jobs = JobCrud::new(self.db.clone());
+        jobs.start(run_id).await.map_err(|e| BackupError::Job(e.to_string()))?;
+
+        match self.take(&crud, &jobs, run_id).await {
+            Ok(()) => {
+                jobs.succeed(run_id).await.map_err(|e| BackupError::Job(e.to_string()))?;
+                crud.get_run(run_id)
+                    .await?
+                    .ok_or_else(|| BackupError::Job(format!("Backup run {} disappeared", run_id)))
+            }
+            Err(e) => {
+                let error = e.to_string();
+                tracing::error!("Backup {} failed: {}", run_id, error);
+                if let Err(record_error) = crud.mark_failed(run_id, &error).await {
+                    tracing::warn!("Failed to record backup {} as failed: {}", run_id, record_error);
+                }
+                if let Err(job_error) = jobs.fail(run_id, &error).await {
+                    tracing::warn!("Failed to record backup job {} as failed: {}", run_id, job_error);
+                }
+                OpsEvent::BackupFailed { run_id: run_id.to_string(), error }.publish();
+                Err(e)
+            }
+        }
+    }
+
+    async fn take(&self, crud: &BackupCrud, jobs: &JobCrud, run_id: &str) -> Result<(), BackupError> {
+        let started = Utc::now();
+        // Upload and verification count as the last two steps
+        let steps = BACKUP_TABLES.len() as u64 + 2;
+        let mut writer = SnapshotWriter::new(started).map_err(BackupError::Snapshot)?;
+
+        // Under REPEATABLE READ every read in the transaction sees the same
+        // snapshot, so ledger entries always match the swaps they reference
+        let mut tx = self.db.begin().await?;
+        for (done, table) in BACKUP_TABLES.iter().enumerate() {
+            let rows = dump_table(&mut tx, &mut writer, table).await?;
+            tracing::debug!("Backup {}: {} rows from {}", run_id, rows, table);
+            if let Err(e) = jobs.set_progress(run_id, percent(done as u64 + 1, steps)).await {
+                tracing::debug!("Failed to report progress of backup {}: {}", run_id, e);
+            }
+        }
+        tx.rollback().await?;
+
+        let (compressed, manifest) = writer.finish().map_err(BackupError::Snapshot)?;
+        let sealed = self.cipher.seal(&compressed).map_err(BackupError::Snapshot)?;
+        let size_bytes = sealed.len() as u64;
+        let sha256 = hex::encode(Sha256::digest(&sealed));
+        let key = object_key(&self.prefix, started, run_id);
+
+        self.storage.put_object(&key, sealed, "application/octet-stream").await?;
+        let manifest_json = serde_json::to_string(&manifest).map_err(|e| BackupError::Snapshot(e.to_string()))?;
+        crud.record_upload(run_id, &key, size_bytes, &sha256, &manifest_json).await?;
+        if let Err(e) = jobs.set_progress(run_id, percent(steps - 1, steps)).await {
+            tracing::debug!("Failed to report progress of backup {}: {}", run_id, e);
+        }
+
+        self.verify_object(&key, Some(&manifest)).await?;
+        crud.mark_verified(run_id).await?;
+        tracing::info!("Backup {} verified: {} ({} bytes)", run_id, key, size_bytes);
+        Ok(())
+    }
+
+    /// Download and decrypt a snapshot
+    pub async fn fetch(&self, object_key: &str) -> Result<Vec<u8>, BackupError> {
+        let sealed = self.storage.get_object(object_key).await?;
+        self.cipher.open(&sealed).map_err(BackupError::Verification)
+    }
+
+    /// Download a snapshot, decrypt it and check every table against its
+    /// manifest and, when given, against `expected`
+    pub async fn verify_object(&self, object_key: &str, expected: Option<&Manifest>) -> Result<Manifest, BackupError> {
+        let compressed = self.fetch(object_key).await?;
+        let manifest = verify_snapshot(&compressed).map_err(BackupError::Verification)?;
+        if let Some(expected) = expected {
+            if &manifest != expected {
+                return Err(BackupError::Verification(format!(
+                    "{} differs from the manifest recorded for the run",
+                    describe_mismatch(expected, &manifest)
+                )));
+            }
+        }
+        Ok(manifest)
+    }
+}
+
+// =============================================================================
+// DUMP AND RESTORE
+// =============================================================================
+
+struct TableColumn {
+    name: String,
+    hex: bool,
+    primary: bool,
+}
+
+/// Insertable columns of `table`, in definition order
+async fn table_columns(conn: &mut MySqlConnection, table: &str) -> Result<Vec<TableColumn>, BackupError> {
+    let rows = sqlx::query(
+        r#"
+        SELECT CAST(COLUMN_NAME AS CHAR) as column_name, CAST(DATA_TYPE AS CHAR) as data_type,
+               CAST(COLUMN_KEY AS CHAR) as column_key, CAST(EXTRA AS CHAR) as extra
+        FROM INFORMATION_SCHEMA.COLUMNS
+        WHERE TABLE_SCHEMA = DATABASE() AND TABLE_NAME = ?
+        ORDER BY ORDINAL_POSITION
+        "#,
+    )
+    .bind(table)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let columns = rows
+        .iter()
+        .map(|row| {
+            let data_type: String = row.get("data_type");
+            let column_key: String = row.get("column_key");
+            let extra: String = row.get("extra");
+            (
+                TableColumn {
+                    name: row.get("column_name"),
+                    hex: BINARY_TYPES.contains(&data_type.to_lowercase().as_str()),
+                    primary: column_key == "PRI",
+                },
+                extra.to_uppercase().contains("GENERATED"),
+            )
+        })
+        // Generated columns are recomputed on restore and can't be inserted
+        .filter(|(_, generated)| !generated)
+        .map(|(column, _)| column)
+        .collect();
+    Ok(columns)
+}
+
+fn quote_ident(name: &str) -> String {
+    format!("`{}`", name.replace('`', "``"))
+}
+
+/// Write every row of `table`, paging by primary key
+async fn dump_table(
+    conn: &mut MySqlConnection,
+    writer: &mut SnapshotWriter,
+    table: &str,
+) -> Result<u64, BackupError> {
+    let columns = table_columns(conn, table).await?;
+    if columns.is_empty() {
+        return Err(BackupError::Snapshot(format!("Table {} does not exist", table)));
+    }
+    let keys: Vec<usize> = columns.iter().enumerate().filter(|(_, c)| c.primary).map(|(i, _)| i).collect();
+    let &[key] = keys.as_slice() else {
+        return Err(BackupError::Snapshot(format!("Table {} needs a single-column primary key", table)));
+    };
+    if columns[key].hex {
+        return Err(BackupError::Snapshot(format!("Table {} has a binary primary key", table)));
+    }
+
+    let names: Vec<String> = columns.iter().map(|c| c.name.clone()).collect();
+    let hex: Vec<String> = columns.iter().filter(|c| c.hex).map(|c| c.name.clone()).collect();
+    writer.begin_table(table, &names, &hex).map_err(BackupError::Snapshot)?;
+
+    let select = columns
+        .iter()
+        .map(|c| {
+            if c.hex {
+                format!("HEX({})", quote_ident(&c.name))
+            } else {
+                format!("CAST({} AS CHAR)", quote_ident(&c.name))
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    let key_column = quote_ident(&columns[key].name);
+    let first_page = format!("SELECT {} FROM {} ORDER BY {} LIMIT {}", select, quote_ident(table), key_column, PAGE_SIZE);
+    let next_page = format!(
+        "SELECT {} FROM {} WHERE {} > ? ORDER BY {} LIMIT {}",
+        select,
+        quote_ident(table),
+        key_column,
+        key_column,
+        PAGE_SIZE
+    );
+
+    let mut cursor: Option<String> = None;
+    let mut rows = 0u64;
+    loop {
+        let page = match &cursor {
+            None => sqlx::query(&first_page).fetch_all(&mut *conn).await?,
+            Some(after) => sqlx::query(&next_page).bind(after).fetch_all(&mut *conn).await?,
+        };
+        for row in &page {
+            let values = (0..names.len())
+                .map(|i| row.try_get::<Option<String>, _>(i))
+                .collect::<Result<Vec<_>, _>>()?;
+            writer.write_row(&values).map_err(BackupError::Snapshot)?;
+        }
+        rows += page.len() as u64;
+
+        match page.last() {
+            Some(last) if page.len() == PAGE_SIZE => cursor = last.try_get::<Option<String>, _>(key)?,
+            _ => break,
+        }
+    }
+    Ok(rows)
+}
+
+/// Table being restored and its pending rows
+struct RestoreTable {
+    insert_prefix: String,
+    placeholders: String,
+    rows: Vec<Vec<Option<String>>>,
+}
+
+impl RestoreTable {
+    async fn flush(&mut self, conn: &mut MySqlConnection) -> Result<(), BackupError> {
+        if self.rows.is_empty() {
+            return Ok(());
+        }
+        let values = vec![self.placeholders.as_str(); self.rows.len()].join(", ");
+        let sql = format!("{} VALUES {}", self.insert_prefix, values);
+        let mut query = sqlx::query(&sql);
+        for row in self.rows.drain(..) {
+            for value in row {
+                query = query.bind(value);
+            }
+        }
+        query.execute(&mut *conn).await?;
+        Ok(())
+    }
+}
+
+/// Load a decrypted snapshot into the database behind `pool`. The tables
+/// must exist (run the migrations first) and be empty. Everything goes in
+/// one transaction that is committed only if every table matches the
+/// manifest, so a bad snapshot leaves the database untouched.
+pub async fn restore(pool: &Pool<MySql>, compressed: &[u8]) -> Result<Manifest, BackupError> {
+    let mut reader = SnapshotReader::new(compressed).map_err(BackupError::Verification)?;
+
+    // Foreign key checks are switched off for the session, since parents
+    // such as users are not in the snapshot; the connection is detached so
+    // it never goes back to the pool that way
+    let mut conn = pool.acquire().await?.detach();
+    let mut tx = conn.begin().await?;
+    sqlx::query("SET FOREIGN_KEY_CHECKS = 0").execute(&mut *tx).await?;
+
+    let mut current: Option<RestoreTable> = None;
+    while let Some(entry) = reader.next_entry().map_err(BackupError::Verification)? {
+        match entry {
+            SnapshotEntry::Table { name, columns, hex } => {
+                if let Some(mut previous) = current.take() {
+                    previous.flush(&mut tx).await?;
+                }
+                current = Some(restore_target(&mut tx, &name, &columns, &hex).await?);
+            }
+            SnapshotEntry::Row(values) => {
+                let table = current.as_mut().ok_or_else(|| BackupError::Verification("Row before any table".to_string()))?;
+                table.rows.push(values);
+                if table.rows.len() >= INSERT_BATCH {
+                    table.flush(&mut tx).await?;
+                }
+            }
+        }
+    }
+    if let Some(mut last) = current.take() {
+        last.flush(&mut tx).await?;
+    }
+    let manifest = reader.finish().map_err(BackupError::Verification)?;
+
+    tx.commit().await?;
+    conn.close().await?;
+    Ok(manifest)
+}
+
+/// Check `table` can take the snapshot's rows and build its INSERT
+async fn restore_target(
+    conn: &mut MySqlConnection,
+    table: &str,
+    columns: &[String],
+    hex: &[String],
+) -> Result<RestoreTable, BackupError> {
+    let existing = table_columns(conn, table).await?;
+    if existing.is_empty() {
+        return Err(BackupError::Restore(format!("table {} does not exist; run the migrations first", table)));
+    }
+    if let Some(missing) = columns.iter().find(|c| !existing.iter().any(|e| &e.name == *c)) {
+        return Err(BackupError::Restore(format!("table {} has no column {}", table, missing)));
+    }
+    let has_rows: i64 = sqlx::query_scalar(&format!("SELECT EXISTS(SELECT 1 FROM {})", quote_ident(table)))
+        .fetch_one(&mut *conn)
+        .await?;
+    if has_rows != 0 {
+        return Err(BackupError::Restore(format!("table {} is not empty", table)));
+    }
+
+    let names = columns.iter().map(|c| quote_ident(c)).collect::<Vec<_>>().join(", ");
+    let placeholders = columns
+        .iter()
+        .map(|c| if hex.contains(c) { "UNHEX(?)" } else { "?" })
+        .collect::<Vec<_>>()
+        .join(", ");
+    Ok(RestoreTable {
+        insert_prefix: format!("INSERT INTO {} ({})", quote_ident(table), names),
+        placeholders: format!("({})", placeholders),
+        rows: Vec::new(),
+    })
+}
+
+// =============================================================================
+// WORKER
+// =============================================================================
+
+/// Takes the nightly backup. With leader election only one process of a
+/// deployment does.
+pub struct BackupWorker {
+    service: BackupService,
+    hour: u32,
+    election: Option<Arc<LeaderElection>>,
+}
+
+impl BackupWorker {
+    pub fn new(service: BackupService, hour: u32) -> Self {
+        Self { service, hour, election: None }
+    }
+
+    pub fn with_leader_election(mut self, election: Arc<LeaderElection>) -> Self {
+        self.election = Some(election);
+        self
+    }
+
+    pub async fn run(&self) {
+        loop {
+            tokio::time::sleep(until_next_run(Utc::now(), self.hour)).await;
+
+            if let Some(election) = &self.election {
+                match election.try_lead(LEADER_ROLE).await {
+                    Ok(true) => {}
+                    Ok(false) => continue,
+                    Err(e) => {
+                        tracing::warn!("Skipping backup, leader election failed: {}", e);
+                        continue;
+                    }
+                }
+            }
+
+            match self.service.run().await {
+                Ok(run) => tracing::info!("Nightly backup {} verified", run.id),
+                Err(BackupError::AlreadyRunning(id)) => tracing::info!("Nightly backup skipped: {} is still running", id),
+                // Already logged and published by the service
+                Err(_) => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_object_key() {
+        let at = Utc.with_ymd_and_hms(2026, 4, 17, 2, 0, 0).unwrap();
+        assert_eq!(object_key("backups/", at, "run-1"), "backups/2026/04/17/run-1.ndjson.gz.enc");
+    }
+}
@@ -0,0 +1,431 @@
+//! On-disk format of a backup. A snapshot is newline-delimited JSON,
+//! gzip-compressed, then sealed with AES-256-GCM:
+//!
+//! ```text
+//! {"format":1,"created_at":"2026-04-17T02:00:00Z"}
+//! {"table":"swaps","columns":["id","amount",...],"hex":[]}
+//! ["3f2c...","0.05",...]
+//! ...
+//! {"manifest":{"swaps":{"rows":1200,"sha256":"..."},...}}
+//! ```
+//!
+//! Every value is MySQL's own text form (binary columns in hex), so a
+//! restore hands MySQL back exactly what it produced and no precision is
+//! lost on decimals. The trailing manifest holds each table's row count and
+//! a SHA-256 over its row lines; reading a snapshot recomputes both.
+
+use aes_gcm::{
+    aead::{Aead, KeyInit, Payload},
+    Aes256Gcm, Nonce,
+};
+use chrono::{DateTime, Utc};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use rand::RngCore;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::io::{BufRead, BufReader, Lines, Write};
+
+const FORMAT_VERSION: u32 = 1;
+/// Leading bytes of a sealed snapshot, bumped with the envelope layout
+const MAGIC: &[u8; 5] = b"EXBK1";
+const FINGERPRINT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+
+/// Row count and digest of one table in a snapshot
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct TableDigest {
+    pub rows: u64,
+    /// Hex SHA-256 over the table's row lines, newline included
+    pub sha256: String,
+}
+
+/// Per-table digests, keyed by table name
+pub type Manifest = BTreeMap<String, TableDigest>;
+
+// =============================================================================
+// ENCRYPTION
+// =============================================================================
+
+/// AES-256-GCM key for snapshots. Sealed output is
+/// `EXBK1 || fingerprint || nonce || ciphertext || tag`; the magic and
+/// fingerprint are authenticated as associated data, so a snapshot opened
+/// with the wrong key says so instead of failing as corrupt.
+#[derive(Clone)]
+pub struct BackupCipher {
+    cipher: Aes256Gcm,
+    fingerprint: String,
+}
+
+impl std::fmt::Debug for BackupCipher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BackupCipher").field("fingerprint", &self.fingerprint).finish()
+    }
+}
+
+impl BackupCipher {
+    pub fn new(key: &[u8]) -> Result<Self, String> {
+        if key.len() != 32 {
+            return Err(format!("Backup encryption key must be 32 bytes, got {}", key.len()));
+        }
+        let cipher = Aes256Gcm::new_from_slice(key).map_err(|e| e.to_string())?;
+        let fingerprint = hex::encode(Sha256::digest(key))[..FINGERPRINT_LEN].to_string();
+        Ok(Self { cipher, fingerprint })
+    }
+
+    /// Key given as 64 hex characters
+    pub fn from_hex(hex_key: &str) -> Result<Self, String> {
+        let key = hex::decode(hex_key.trim()).map_err(|e| format!("Invalid BACKUP_ENCRYPTION_KEY: {}", e))?;
+        Self::new(&key)
+    }
+
+    /// First 16 hex characters of SHA-256 over the key
+    pub fn fingerprint(&self) -> &str {
+        &self.fingerprint
+    }
+
+    pub fn seal(&self, plaintext: &[u8]) -> Result<Vec<u8>, String> {
+        let mut nonce_bytes = [0u8; NONCE_LEN];
+        rand::rng().fill_bytes(&mut nonce_bytes);
+
+        let header = self.header();
+        let ciphertext = self
+            .cipher
+            .encrypt(&Nonce::from(nonce_bytes), Payload { msg: plaintext, aad: &header })
+            .map_err(|e| format!("Encryption failed: {}", e))?;
+
+        let mut out = Vec::with_capacity(header.len() + NONCE_LEN + ciphertext.len());
+        out.extend_from_slice(&header);
+        out.extend_from_slice(&nonce_bytes);
+        out.extend_from_slice(&ciphertext);
+        Ok(out)
+    }
+
+    pub fn open(&self, sealed: &[u8]) -> Result<Vec<u8>, String> {
+        let header_len = MAGIC.len() + FINGERPRINT_LEN;
+        if sealed.len() <= header_len + NONCE_LEN || !sealed.starts_with(MAGIC) {
+            return Err("Not a backup snapshot".to_string());
+        }
+        let (header, rest) = sealed.split_at(header_len);
+        let fingerprint = String::from_utf8_lossy(&header[MAGIC.len()..]);
+        if fingerprint != self.fingerprint {
+            return Err(format!(
+                "Snapshot was encrypted with key {}, but the loaded key is {}",
+                fingerprint, self.fingerprint
+            ));
+        }
+
+        let (nonce_bytes, ciphertext) =
+            rest.split_first_chunk::<NONCE_LEN>().ok_or_else(|| "Not a backup snapshot".to_string())?;
+        self.cipher
+            .decrypt(&Nonce::from(*nonce_bytes), Payload { msg: ciphertext, aad: header })
+            .map_err(|_| "Decryption failed: the snapshot is corrupt or was tampered with".to_string())
+    }
+
+    fn header(&self) -> Vec<u8> {
+        let mut header = MAGIC.to_vec();
+        header.extend_from_slice(self.fingerprint.as_bytes());
+        header
+    }
+}
+
+// =============================================================================
+// WRITING
+// =============================================================================
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Header {
+    format: u32,
+    created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct TableHeader {
+    table: String,
+    columns: Vec<String>,
+    /// Columns stored as hex, to be passed through UNHEX on restore
+    #[serde(default)]
+    hex: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Trailer {
+    manifest: Manifest,
+}
+
+/// Digest of the table currently being written or read
+struct OpenTable {
+    name: String,
+    hasher: Sha256,
+    rows: u64,
+}
+
+impl OpenTable {
+    fn new(name: &str) -> Self {
+        Self { name: name.to_string(), hasher: Sha256::new(), rows: 0 }
+    }
+
+    fn add(&mut self, line: &[u8]) {
+        self.hasher.update(line);
+        self.hasher.update(b"\n");
+        self.rows += 1;
+    }
+
+    fn close(self, manifest: &mut Manifest) {
+        let digest = TableDigest { rows: self.rows, sha256: hex::encode(self.hasher.finalize()) };
+        manifest.insert(self.name, digest);
+    }
+}
+
+/// Streams tables into a compressed snapshot
+pub struct SnapshotWriter {
+    encoder: GzEncoder<Vec<u8>>,
+    manifest: Manifest,
+    table: Option<OpenTable>,
+    columns: usize,
+}
+
+impl SnapshotWriter {
+    pub fn new(created_at: DateTime<Utc>) -> Result<Self, String> {
+        let mut writer = Self {
+            encoder: GzEncoder::new(Vec::new(), Compression::default()),
+            manifest: Manifest::new(),
+            table: None,
+            columns: 0,
+        };
+        writer.write_line(&serde_json::to_vec(&Header { format: FORMAT_VERSION, created_at }).map_err(|e| e.to_string())?)?;
+        Ok(writer)
+    }
+
+    /// Start a table; rows that follow belong to it
+    pub fn begin_table(&mut self, table: &str, columns: &[String], hex: &[String]) -> Result<(), String> {
+        if self.manifest.contains_key(table) || self.table.as_ref().is_some_and(|t| t.name == table) {
+            return Err(format!("Table {} written twice", table));
+        }
+        self.close_table();
+        let header = TableHeader { table: table.to_string(), columns: columns.to_vec(), hex: hex.to_vec() };
+        self.write_line(&serde_json::to_vec(&header).map_err(|e| e.to_string())?)?;
+        self.table = Some(OpenTable::new(table));
+        self.columns = columns.len();
+        Ok(())
+    }
+
+    pub fn write_row(&mut self, values: &[Option<String>]) -> Result<(), String> {
+        if values.len() != self.columns {
+            return Err(format!("Row has {} values for {} columns", values.len(), self.columns));
+        }
+        let line = serde_json::to_vec(values).map_err(|e| e.to_string())?;
+        let table = self.table.as_mut().ok_or("Row written before any table")?;
+        table.add(&line);
+        self.write_line(&line)
+    }
+
+    /// Write the manifest and return the compressed snapshot with it
+    pub fn finish(mut self) -> Result<(Vec<u8>, Manifest), String> {
+        self.close_table();
+        let trailer = Trailer { manifest: self.manifest };
+        let line = serde_json::to_vec(&trailer).map_err(|e| e.to_string())?;
+        self.encoder.write_all(&line).and_then(|_| self.encoder.write_all(b"\n")).map_err(|e| e.to_string())?;
+        let compressed = self.encoder.finish().map_err(|e| e.to_string())?;
+        Ok((compressed, trailer.manifest))
+    }
+
+    fn close_table(&mut self) {
+        if let Some(table) = self.table.take() {
+            table.close(&mut self.manifest);
+        }
+    }
+
+    fn write_line(&mut self, line: &[u8]) -> Result<(), String> {
+        self.encoder.write_all(line).and_then(|_| self.encoder.write_all(b"\n")).map_err(|e| e.to_string())
+    }
+}
+
+// =============================================================================
+// READING
+// =============================================================================
+
+/// One item of a snapshot, in file order
+#[derive(Debug, Clone, PartialEq)]
+pub enum SnapshotEntry {
+    Table { name: String, columns: Vec<String>, hex: Vec<String> },
+    Row(Vec<Option<String>>),
+}
+
+/// Streams a compressed snapshot back, recomputing its manifest
+pub struct SnapshotReader<'a> {
+    lines: Lines<BufReader<GzDecoder<&'a [u8]>>>,
+    created_at: DateTime<Utc>,
+    computed: Manifest,
+    recorded: Option<Manifest>,
+    table: Option<OpenTable>,
+    columns: usize,
+}
+
+impl<'a> SnapshotReader<'a> {
+    pub fn new(compressed: &'a [u8]) -> Result<Self, String> {
+        let mut lines = BufReader::new(GzDecoder::new(compressed)).lines();
+        let first = lines
+            .next()
+            .ok_or("Snapshot is empty")?
+            .map_err(|e| format!("Snapshot is not valid gzip: {}", e))?;
+        let header: Header = serde_json::from_str(&first).map_err(|e| format!("Invalid snapshot header: {}", e))?;
+        if header.format != FORMAT_VERSION {
+            return Err(format!("Unsupported snapshot format {}", header.format));
+        }
+
+        Ok(Self {
+            lines,
+            created_at: header.created_at,
+            computed: Manifest::new(),
+            recorded: None,
+            table: None,
+            columns: 0,
+        })
+    }
+
+    pub fn created_at(&self) -> DateTime<Utc> {
+        self.created_at
+    }
+
+    /// Next table or row; None once the manifest has been read
+    pub fn next_entry(&mut self) -> Result<Option<SnapshotEntry>, String> {
+        if self.recorded.is_some() {
+            return Ok(None);
+        }
+        let line = self
+            .lines
+            .next()
+            .ok_or("Snapshot is truncated: no manifest")?
+            .map_err(|e| format!("Failed to read snapshot: {}", e))?;
+
+        if line.starts_with('[') {
+            let values: Vec<Option<String>> =
+                serde_json::from_str(&line).map_err(|e| format!("Invalid snapshot row: {}", e))?;
+            if values.len() != self.columns {
+                return Err(format!("Row has {} values for {} columns", values.len(), self.columns));
+            }
+            self.table.as_mut().ok_or("Row before any table")?.add(line.as_bytes());
+            return Ok(Some(SnapshotEntry::Row(values)));
+        }
+
+        if let Ok(header) = serde_json::from_str::<TableHeader>(&line) {
+            if let Some(table) = self.table.take() {
+                table.close(&mut self.computed);
+            }
+            self.table = Some(OpenTable::new(&header.table));
+            self.columns = header.columns.len();
+            return Ok(Some(SnapshotEntry::Table { name: header.table, columns: header.columns, hex: header.hex }));
+        }
+
+        let trailer: Trailer = serde_json::from_str(&line).map_err(|e| format!("Invalid snapshot line: {}", e))?;
+        if let Some(table) = self.table.take() {
+            table.close(&mut self.computed);
+        }
+        self.recorded = Some(trailer.manifest);
+        Ok(None)
+    }
+
+    /// Read whatever is left and check it against the recorded manifest
+    pub fn finish(mut self) -> Result<Manifest, String> {
+        while self.next_entry()?.is_some() {}
+        let recorded = self.recorded.take().unwrap_or_default();
+        if recorded != self.computed {
+            return Err(format!("{} does not match its manifest", describe_mismatch(&recorded, &self.computed)));
+        }
+        Ok(recorded)
+    }
+}
+
+/// Decompress a whole snapshot and check every table against the manifest
+pub fn verify_snapshot(compressed: &[u8]) -> Result<Manifest, String> {
+    SnapshotReader::new(compressed)?.finish()
+}
+
+/// First table whose digest differs between two manifests, for errors
+pub fn describe_mismatch(expected: &Manifest, actual: &Manifest) -> String {
+    let tables = expected.keys().chain(actual.keys());
+    for table in tables {
+        match (expected.get(table), actual.get(table)) {
+            (Some(e), Some(a)) if e.rows != a.rows => {
+                return format!("Table {} ({} rows, expected {})", table, a.rows, e.rows);
+            }
+            (Some(e), Some(a)) if e != a => return format!("Table {} (digest differs)", table),
+            (Some(_), None) => return format!("Table {} (missing)", table),
+            (None, Some(_)) => return format!("Table {} (unexpected)", table),
+            _ => {}
+        }
+    }
+    "Snapshot".to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn columns(names: &[&str]) -> Vec<String> {
+        names.iter().map(|c| c.to_string()).collect()
+    }
+
+    fn snapshot() -> (Vec<u8>, Manifest) {
+        let mut writer = SnapshotWriter::new(Utc::now()).unwrap();
+        writer.begin_table("swaps", &columns(&["id", "amount"]), &[]).unwrap();
+        writer.write_row(&[Some("swap-1".to_string()), Some("0.050000000000000000".to_string())]).unwrap();
+        writer.write_row(&[Some("swap-2".to_string()), None]).unwrap();
+        writer.begin_table("hd_derivation_paths", &columns(&["chain"]), &[]).unwrap();
+        writer.finish().unwrap()
+    }
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        let (compressed, manifest) = snapshot();
+        assert_eq!(manifest["swaps"].rows, 2);
+        assert_eq!(manifest["hd_derivation_paths"].rows, 0);
+
+        let mut reader = SnapshotReader::new(&compressed).unwrap();
+        assert_eq!(
+            reader.next_entry().unwrap(),
+            Some(SnapshotEntry::Table { name: "swaps".to_string(), columns: columns(&["id", "amount"]), hex: vec![] })
+        );
+        assert_eq!(
+            reader.next_entry().unwrap(),
+            Some(SnapshotEntry::Row(vec![Some("swap-1".to_string()), Some("0.050000000000000000".to_string())]))
+        );
+        assert_eq!(reader.finish().unwrap(), manifest);
+    }
+
+    #[test]
+    fn test_sealed_snapshot_rejects_tampering_and_wrong_key() {
+        let cipher = BackupCipher::new(&[7u8; 32]).unwrap();
+        let (compressed, manifest) = snapshot();
+        let sealed = cipher.seal(&compressed).unwrap();
+        assert_eq!(verify_snapshot(&cipher.open(&sealed).unwrap()).unwrap(), manifest);
+
+        let mut tampered = sealed.clone();
+        let last = tampered.len() - 1;
+        tampered[last] ^= 1;
+        assert!(cipher.open(&tampered).unwrap_err().contains("tampered"));
+
+        let other = BackupCipher::new(&[8u8; 32]).unwrap();
+        assert!(other.open(&sealed).unwrap_err().contains(cipher.fingerprint()));
+        assert!(BackupCipher::new(&[0u8; 16]).is_err());
+    }
+
+    #[test]
+    fn test_manifest_mismatch_is_reported() {
+        let (_, manifest) = snapshot();
+        let mut altered = manifest.clone();
+        altered.get_mut("swaps").unwrap().rows = 3;
+        assert_eq!(describe_mismatch(&altered, &manifest), "Table swaps (2 rows, expected 3)");
+
+        // A snapshot cut off before its manifest never verifies
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder
+            .write_all(b"{\"format\":1,\"created_at\":\"2026-04-17T02:00:00Z\"}\n{\"table\":\"swaps\",\"columns\":[\"id\"]}\n[\"swap-1\"]\n")
+            .unwrap();
+        assert!(verify_snapshot(&encoder.finish().unwrap()).unwrap_err().contains("truncated"));
+    }
+}
@@ -0,0 +1,89 @@
+//! Recovery of deposits missed while the listener was down. Each EVM chain
+//! keeps the head block of the last completed check; on startup the blocks
+//! since then are re-scanned for swaps that were open at the time.
+
+use chrono::{DateTime, Utc};
+
+/// Blocks re-scanned on startup, counted back from the current head
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CatchUpRange {
+    pub from_block: u64,
+    pub to_block: u64,
+    /// Blocks mined since the cursor, including any not re-scanned
+    pub gap_blocks: u64,
+    /// The gap exceeded the limit and only the most recent blocks are scanned
+    pub truncated: bool,
+}
+
+impl CatchUpRange {
+    pub fn blocks(&self) -> u64 {
+        self.to_block - self.from_block + 1
+    }
+}
+
+/// Range to re-scan after `cursor`, bounded to `max_blocks`.
+/// `None` when the chain has not advanced since the cursor.
+pub fn catch_up_range(cursor: u64, head: u64, max_blocks: u64) -> Option<CatchUpRange> {
+    if head <= cursor || max_blocks == 0 {
+        return None;
+    }
+    let gap_blocks = head - cursor;
+    let scanned = gap_blocks.min(max_blocks);
+
+    Some(CatchUpRange {
+        from_block: head - scanned + 1,
+        to_block: head,
+        gap_blocks,
+        truncated: scanned < gap_blocks,
+    })
+}
+
+/// What a startup catch-up found on one chain
+#[derive(Debug, Clone)]
+pub struct CatchUpReport {
+    pub chain: String,
+    pub range: CatchUpRange,
+    /// When the cursor was last written, i.e. roughly when the listener stopped
+    pub last_seen: DateTime<Utc>,
+    pub swaps_checked: u32,
+    /// Swaps whose received total went up during the catch-up
+    pub deposits_recovered: u32,
+}
+
+impl CatchUpReport {
+    pub fn downtime_secs(&self) -> i64 {
+        (Utc::now() - self.last_seen).num_seconds().max(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_range_covers_gap_since_cursor() {
+        let range = catch_up_range(1_000, 1_500, 10_000).unwrap();
+        assert_eq!(range.from_block, 1_001);
+        assert_eq!(range.to_block, 1_500);
+        assert_eq!(range.gap_blocks, 500);
+        assert_eq!(range.blocks(), 500);
+        assert!(!range.truncated);
+    }
+
+    #[test]
+    fn test_long_gap_keeps_most_recent_blocks() {
+        let range = catch_up_range(1_000, 101_000, 50_000).unwrap();
+        assert_eq!(range.from_block, 51_001);
+        assert_eq!(range.blocks(), 50_000);
+        assert_eq!(range.gap_blocks, 100_000);
+        assert!(range.truncated);
+    }
+
+    #[test]
+    fn test_no_range_without_progress_or_when_disabled() {
+        assert!(catch_up_range(1_500, 1_500, 10_000).is_none());
+        // A node behind our cursor (e.g. after failover) is not a gap
+        assert!(catch_up_range(1_500, 1_400, 10_000).is_none());
+        assert!(catch_up_range(1_000, 1_500, 0).is_none());
+    }
+}
@@ -0,0 +1,70 @@
+//! Deposit accounting for the blockchain listener. A deposit address may
+//! receive several transactions; the on-chain balance is the running total.
+
+use crate::services::amount::Decimal;
+
+/// Share of the expected amount that counts as fully paid
+pub const FUNDED_THRESHOLD: Decimal = Decimal::from_parts(95, 0, 0, false, 2);
+/// Balance changes below this are noise, not a new deposit
+pub const DUST_THRESHOLD: Decimal = Decimal::from_parts(1, 0, 0, false, 4);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DepositOutcome {
+    /// Cumulative deposits cover the swap
+    Funded { total: Decimal },
+    /// A new partial deposit arrived; the user may still top up
+    TopUp { delta: Decimal, total: Decimal },
+    /// Nothing new since the last check
+    Waiting,
+}
+
+/// Compare the current balance with what was already recorded
+pub fn evaluate_deposit(previously_received: Decimal, balance: Decimal, expected: Decimal) -> DepositOutcome {
+    if balance >= expected * FUNDED_THRESHOLD && balance > DUST_THRESHOLD {
+        return DepositOutcome::Funded { total: balance };
+    }
+
+    let delta = balance - previously_received;
+    if delta > DUST_THRESHOLD {
+        DepositOutcome::TopUp { delta, total: balance }
+    } else {
+        DepositOutcome::Waiting
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::amount;
+
+    fn dec(s: &str) -> Decimal {
+        amount::parse(s).unwrap()
+    }
+
+    #[test]
+    fn test_full_payment_is_funded() {
+        assert_eq!(evaluate_deposit(Decimal::ZERO, dec("1"), dec("1")), DepositOutcome::Funded { total: dec("1") });
+        assert_eq!(evaluate_deposit(Decimal::ZERO, dec("0.96"), dec("1")), DepositOutcome::Funded { total: dec("0.96") });
+        assert_eq!(evaluate_deposit(Decimal::ZERO, dec("0.95"), dec("1")), DepositOutcome::Funded { total: dec("0.95") });
+    }
+
+    #[test]
+    fn test_partial_payment_opens_top_up() {
+        assert_eq!(
+            evaluate_deposit(Decimal::ZERO, dec("0.5"), dec("1")),
+            DepositOutcome::TopUp { delta: dec("0.5"), total: dec("0.5") }
+        );
+    }
+
+    #[test]
+    fn test_second_deposit_crosses_threshold() {
+        assert_eq!(evaluate_deposit(dec("0.5"), dec("0.5"), dec("1")), DepositOutcome::Waiting);
+        assert_eq!(evaluate_deposit(dec("0.5"), dec("1"), dec("1")), DepositOutcome::Funded { total: dec("1") });
+    }
+
+    #[test]
+    fn test_dust_is_ignored() {
+        assert_eq!(evaluate_deposit(dec("0.5"), dec("0.50005"), dec("1")), DepositOutcome::Waiting);
+        assert_eq!(evaluate_deposit(Decimal::ZERO, Decimal::ZERO, Decimal::ZERO), DepositOutcome::Waiting);
+    }
+}
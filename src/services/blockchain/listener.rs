@@ -2,7 +2,24 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::time::{interval, Duration};
 use sqlx::{MySql, Pool};
-use crate::services::wallet::rpc::{BlockchainProvider, HttpRpcClient};
+use crate::modules::recovery::crud::RecoveryCrud;
+use crate::modules::swap::schema::SwapStatus;
+use crate::services::amount::{self, Decimal};
+use crate::services::blockchain::catch_up::{catch_up_range, CatchUpReport};
+use crate::services::blockchain::deposits::{evaluate_deposit, DepositOutcome, DUST_THRESHOLD};
+use crate::services::blockchain::memo_deposits::{hot_address, IncomingTransfer, MemoIndex, MemoLedger, MemoMatch};
+use crate::services::blockchain::memo_ledgers::{HorizonClient, XrpLedgerClient};
+use crate::services::blockchain::shards::{Shard, ShardLag};
+use crate::services::blockchain::token_deposits::{flag_reason, tally_token_deposits};
+use crate::services::leader::LeaderElection;
+use crate::services::metrics::collectors::ListenerMetricsCollector;
+use crate::services::metrics::MetricsRegistry;
+use crate::services::refund::late_deposits::{record_late_deposit, LateDeposit, LATE_DEPOSIT_INITIATOR};
+use crate::services::sandbox::SandboxConfig;
+use crate::services::simulation::simulated;
+use crate::services::swap_state::{StatusActor, SwapStateMachine, Transition, TransitionError};
+use crate::services::token::registry::{TokenRegistry, TransferVerdict};
+use crate::services::wallet::rpc::{BlockchainProvider, HttpRpcClient, TransferLog};
 
 /// Blockchain event listener that monitors addresses for incoming funds
 /// This is the optimal approach - detects funds immediately without polling Trocador
@@ -10,128 +27,215 @@ pub struct BlockchainListener {
     db: Pool<MySql>,
     providers: HashMap<String, Arc<dyn BlockchainProvider>>,
     check_interval: Duration,
+    /// How long an underpaid swap stays open after each partial deposit
+    top_up_window: Duration,
+    /// Scan other EVM chains for misdirected deposits every N checks (0 = off)
+    wrong_network_scan_ticks: u32,
+    /// Check expired swaps for deposits that arrived too late every N checks (0 = off)
+    late_deposit_scan_ticks: u32,
+    /// Canonical contracts that token deposits are credited from
+    tokens: Arc<TokenRegistry>,
+    /// How far back token transfer logs are read for each check
+    token_lookback_blocks: u64,
+    /// Shared hot address and history client per memo chain
+    memo_ledgers: HashMap<String, (String, Arc<dyn MemoLedger>)>,
+    /// Most blocks re-scanned per chain on startup (0 = no catch-up)
+    catch_up_max_blocks: u64,
+    /// Deposit addresses this listener scans; cursors are kept per shard
+    shard: Shard,
+    /// When set, the shard is only scanned while this process leads it
+    election: Option<Arc<LeaderElection>>,
+    metrics: Option<ListenerMetricsCollector>,
+}
+
+/// Top-ups never keep a swap open longer than this after creation
+const MAX_TOP_UP_HOURS: i64 = 24;
+
+/// RPC URL variable for each EVM chain the listener can watch
+pub const EVM_RPC_ENV_VARS: &[(&str, &str)] = &[
+    ("ethereum", "ETH_RPC_URL"),
+    ("polygon", "POLYGON_RPC_URL"),
+    ("bsc", "BSC_RPC_URL"),
+    ("arbitrum", "ARBITRUM_RPC_URL"),
+    ("optimism", "OPTIMISM_RPC_URL"),
+    ("avalanche", "AVALANCHE_RPC_URL"),
+    ("base", "BASE_RPC_URL"),
+    ("fantom", "FANTOM_RPC_URL"),
+    ("gnosis", "GNOSIS_RPC_URL"),
+    ("cronos", "CRONOS_RPC_URL"),
+    ("moonbeam", "MOONBEAM_RPC_URL"),
+    ("moonriver", "MOONRIVER_RPC_URL"),
+    ("celo", "CELO_RPC_URL"),
+    ("aurora", "AURORA_RPC_URL"),
+    ("harmony", "HARMONY_RPC_URL"),
+    ("metis", "METIS_RPC_URL"),
+    ("zksync", "ZKSYNC_RPC_URL"),
+    ("linea", "LINEA_RPC_URL"),
+    ("scroll", "SCROLL_RPC_URL"),
+    ("mantle", "MANTLE_RPC_URL"),
+    ("blast", "BLAST_RPC_URL"),
+    ("mode", "MODE_RPC_URL"),
+    ("manta", "MANTA_RPC_URL"),
+];
+
+/// Configured RPC URL for a canonical EVM chain name. A sandbox deployment
+/// only uses test network RPCs, so mainnets without one go unwatched.
+pub fn evm_rpc_url(chain: &str) -> Option<String> {
+    let sandbox = SandboxConfig::global();
+    if sandbox.enabled {
+        return sandbox.test_network(chain)?.rpc_url.clone();
+    }
+    let (_, var) = EVM_RPC_ENV_VARS.iter().find(|(name, _)| *name == chain)?;
+    std::env::var(var).ok().filter(|url| !url.trim().is_empty())
+}
+
+/// History clients for memo chains with both a hot address and a node URL set
+fn memo_ledgers_from_env() -> HashMap<String, (String, Arc<dyn MemoLedger>)> {
+    let configured = |chain: &str, var: &str| -> Option<(String, String)> {
+        let url = std::env::var(var).ok().filter(|u| !u.trim().is_empty())?;
+        Some((hot_address(chain)?, url))
+    };
+
+    let mut ledgers: HashMap<String, (String, Arc<dyn MemoLedger>)> = HashMap::new();
+    if let Some((address, url)) = configured("xrp", "RIPPLE_PRIMARY_RPC") {
+        ledgers.insert("xrp".to_string(), (address, Arc::new(XrpLedgerClient::new(url))));
+    }
+    if let Some((address, url)) = configured("xlm", "STELLAR_HORIZON_URL") {
+        ledgers.insert("xlm".to_string(), (address, Arc::new(HorizonClient::new(url))));
+    }
+    ledgers
+}
+
+/// Canonical chain name for a common network alias, e.g. `bep20` -> `bsc`
+pub fn chain_alias(network: &str) -> Option<&'static str> {
+    match network {
+        // Ethereum aliases
+        "erc20" | "eth" | "mainnet" => Some("ethereum"),
+
+        // Polygon aliases
+        "matic" | "pos" => Some("polygon"),
+
+        // BSC aliases
+        "bnb" | "bep20" | "binance" | "smartchain" => Some("bsc"),
+
+        // Arbitrum aliases
+        "arb" | "arbitrum one" | "arbitrumone" => Some("arbitrum"),
+
+        // Optimism aliases
+        "op" | "optimistic" => Some("optimism"),
+
+        // Avalanche aliases
+        "avax" | "avalanche c-chain" | "cchain" => Some("avalanche"),
+
+        // Base aliases
+        "base mainnet" | "coinbase" => Some("base"),
+
+        // Fantom aliases
+        "ftm" | "opera" => Some("fantom"),
+
+        // Gnosis aliases
+        "xdai" | "gno" => Some("gnosis"),
+
+        // Cronos aliases
+        "cro" => Some("cronos"),
+
+        // Moonbeam aliases
+        "glmr" => Some("moonbeam"),
+
+        // Moonriver aliases
+        "movr" => Some("moonriver"),
+
+        // Celo aliases
+        "celo mainnet" => Some("celo"),
+
+        // Aurora aliases
+        "aurora mainnet" | "near" => Some("aurora"),
+
+        // Harmony aliases
+        "one" | "harmony one" => Some("harmony"),
+
+        // Metis aliases
+        "metis andromeda" => Some("metis"),
+
+        // zkSync aliases
+        "zksync era" | "zks" => Some("zksync"),
+
+        // Linea aliases
+        "linea mainnet" => Some("linea"),
+
+        // Scroll aliases
+        "scroll mainnet" => Some("scroll"),
+
+        // Mantle aliases
+        "mnt" => Some("mantle"),
+
+        // Blast aliases
+        "blast mainnet" => Some("blast"),
+
+        // Mode aliases
+        "mode mainnet" => Some("mode"),
+
+        // Manta aliases
+        "manta pacific" | "manta mainnet" => Some("manta"),
+
+        _ => None,
+    }
+}
+
+fn default_token_lookback_blocks() -> u64 {
+    std::env::var("TOKEN_DEPOSIT_LOOKBACK_BLOCKS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(10_000)
+}
+
+fn default_catch_up_max_blocks() -> u64 {
+    std::env::var("CATCH_UP_MAX_BLOCKS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(50_000)
+}
+
+fn default_wrong_network_scan_ticks() -> u32 {
+    std::env::var("WRONG_NETWORK_SCAN_TICKS")
+        .ok()
+        .and_then(|v| v.parse::<u32>().ok())
+        .unwrap_or(10)
+}
+
+fn default_late_deposit_scan_ticks() -> u32 {
+    std::env::var("LATE_DEPOSIT_SCAN_TICKS")
+        .ok()
+        .and_then(|v| v.parse::<u32>().ok())
+        .unwrap_or(10)
+}
+
+/// How long after creation an expired swap is still checked for deposits
+fn late_deposit_lookback_days() -> u32 {
+    std::env::var("LATE_DEPOSIT_LOOKBACK_DAYS")
+        .ok()
+        .and_then(|v| v.parse::<u32>().ok())
+        .unwrap_or(7)
+}
+
+fn default_top_up_window() -> Duration {
+    let minutes = std::env::var("UNDERPAYMENT_TOP_UP_MINUTES")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(60);
+    Duration::from_secs(minutes * 60)
 }
 
 impl BlockchainListener {
     /// Create a new blockchain listener with RPC providers for each chain
     pub fn new(db: Pool<MySql>) -> Self {
-        let mut providers: HashMap<String, Arc<dyn BlockchainProvider>> = HashMap::new();
-        
-        // Initialize RPC clients for each supported EVM chain
-        // Ethereum
-        if let Ok(rpc) = std::env::var("ETH_RPC_URL") {
-            providers.insert("ethereum".to_string(), Arc::new(HttpRpcClient::new(rpc)));
-        }
-        
-        // Polygon
-        if let Ok(rpc) = std::env::var("POLYGON_RPC_URL") {
-            providers.insert("polygon".to_string(), Arc::new(HttpRpcClient::new(rpc)));
-        }
-        
-        // Binance Smart Chain
-        if let Ok(rpc) = std::env::var("BSC_RPC_URL") {
-            providers.insert("bsc".to_string(), Arc::new(HttpRpcClient::new(rpc)));
-        }
-        
-        // Arbitrum
-        if let Ok(rpc) = std::env::var("ARBITRUM_RPC_URL") {
-            providers.insert("arbitrum".to_string(), Arc::new(HttpRpcClient::new(rpc)));
-        }
-        
-        // Optimism
-        if let Ok(rpc) = std::env::var("OPTIMISM_RPC_URL") {
-            providers.insert("optimism".to_string(), Arc::new(HttpRpcClient::new(rpc)));
-        }
-        
-        // Avalanche
-        if let Ok(rpc) = std::env::var("AVALANCHE_RPC_URL") {
-            providers.insert("avalanche".to_string(), Arc::new(HttpRpcClient::new(rpc)));
-        }
-        
-        // Base
-        if let Ok(rpc) = std::env::var("BASE_RPC_URL") {
-            providers.insert("base".to_string(), Arc::new(HttpRpcClient::new(rpc)));
-        }
-        
-        // Fantom
-        if let Ok(rpc) = std::env::var("FANTOM_RPC_URL") {
-            providers.insert("fantom".to_string(), Arc::new(HttpRpcClient::new(rpc)));
-        }
-        
-        // Gnosis
-        if let Ok(rpc) = std::env::var("GNOSIS_RPC_URL") {
-            providers.insert("gnosis".to_string(), Arc::new(HttpRpcClient::new(rpc)));
-        }
-        
-        // Cronos
-        if let Ok(rpc) = std::env::var("CRONOS_RPC_URL") {
-            providers.insert("cronos".to_string(), Arc::new(HttpRpcClient::new(rpc)));
-        }
-        
-        // Moonbeam
-        if let Ok(rpc) = std::env::var("MOONBEAM_RPC_URL") {
-            providers.insert("moonbeam".to_string(), Arc::new(HttpRpcClient::new(rpc)));
-        }
-        
-        // Moonriver
-        if let Ok(rpc) = std::env::var("MOONRIVER_RPC_URL") {
-            providers.insert("moonriver".to_string(), Arc::new(HttpRpcClient::new(rpc)));
-        }
-        
-        // Celo
-        if let Ok(rpc) = std::env::var("CELO_RPC_URL") {
-            providers.insert("celo".to_string(), Arc::new(HttpRpcClient::new(rpc)));
-        }
-        
-        // Aurora
-        if let Ok(rpc) = std::env::var("AURORA_RPC_URL") {
-            providers.insert("aurora".to_string(), Arc::new(HttpRpcClient::new(rpc)));
-        }
-        
-        // Harmony
-        if let Ok(rpc) = std::env::var("HARMONY_RPC_URL") {
-            providers.insert("harmony".to_string(), Arc::new(HttpRpcClient::new(rpc)));
-        }
-        
-        // Metis
-        if let Ok(rpc) = std::env::var("METIS_RPC_URL") {
-            providers.insert("metis".to_string(), Arc::new(HttpRpcClient::new(rpc)));
-        }
-        
-        // zkSync Era
-        if let Ok(rpc) = std::env::var("ZKSYNC_RPC_URL") {
-            providers.insert("zksync".to_string(), Arc::new(HttpRpcClient::new(rpc)));
-        }
-        
-        // Linea
-        if let Ok(rpc) = std::env::var("LINEA_RPC_URL") {
-            providers.insert("linea".to_string(), Arc::new(HttpRpcClient::new(rpc)));
-        }
-        
-        // Scroll
-        if let Ok(rpc) = std::env::var("SCROLL_RPC_URL") {
-            providers.insert("scroll".to_string(), Arc::new(HttpRpcClient::new(rpc)));
-        }
-        
-        // Mantle
-        if let Ok(rpc) = std::env::var("MANTLE_RPC_URL") {
-            providers.insert("mantle".to_string(), Arc::new(HttpRpcClient::new(rpc)));
-        }
-        
-        // Blast
-        if let Ok(rpc) = std::env::var("BLAST_RPC_URL") {
-            providers.insert("blast".to_string(), Arc::new(HttpRpcClient::new(rpc)));
-        }
-        
-        // Mode
-        if let Ok(rpc) = std::env::var("MODE_RPC_URL") {
-            providers.insert("mode".to_string(), Arc::new(HttpRpcClient::new(rpc)));
-        }
-        
-        // Manta Pacific
-        if let Ok(rpc) = std::env::var("MANTA_RPC_URL") {
-            providers.insert("manta".to_string(), Arc::new(HttpRpcClient::new(rpc)));
-        }
+        let providers: HashMap<String, Arc<dyn BlockchainProvider>> = EVM_RPC_ENV_VARS
+            .iter()
+            .filter_map(|(chain, _)| {
+                let rpc = evm_rpc_url(chain)?;
+                Some((chain.to_string(), simulated(Arc::new(HttpRpcClient::new(rpc)))))
+            })
+            .collect();
         
         if providers.is_empty() {
             tracing::warn!("⚠️  No RPC providers configured! Blockchain listener will not work.");
@@ -143,211 +247,965 @@ impl BlockchainListener {
             );
         }
         
+        let tokens = Arc::new(TokenRegistry::new(db.clone()));
         Self {
             db,
             providers,
             check_interval: Duration::from_secs(30), // Check every 30 seconds
+            top_up_window: default_top_up_window(),
+            wrong_network_scan_ticks: default_wrong_network_scan_ticks(),
+            late_deposit_scan_ticks: default_late_deposit_scan_ticks(),
+            tokens,
+            token_lookback_blocks: default_token_lookback_blocks(),
+            memo_ledgers: memo_ledgers_from_env(),
+            catch_up_max_blocks: default_catch_up_max_blocks(),
+            shard: Shard::ALL,
+            election: None,
+            metrics: None,
         }
     }
     
+    /// Create a listener with explicit providers keyed by network name.
+    /// Used by tests to point the listener at a mock RPC server.
+    pub fn with_providers(db: Pool<MySql>, providers: HashMap<String, Arc<dyn BlockchainProvider>>) -> Self {
+        let tokens = Arc::new(TokenRegistry::new(db.clone()));
+        Self {
+            db,
+            providers,
+            check_interval: Duration::from_secs(30),
+            top_up_window: default_top_up_window(),
+            wrong_network_scan_ticks: default_wrong_network_scan_ticks(),
+            late_deposit_scan_ticks: default_late_deposit_scan_ticks(),
+            tokens,
+            token_lookback_blocks: default_token_lookback_blocks(),
+            memo_ledgers: HashMap::new(),
+            catch_up_max_blocks: default_catch_up_max_blocks(),
+            shard: Shard::ALL,
+            election: None,
+            metrics: None,
+        }
+    }
+
+    pub fn with_check_interval(mut self, check_interval: Duration) -> Self {
+        self.check_interval = check_interval;
+        self
+    }
+
+    pub fn with_token_lookback_blocks(mut self, blocks: u64) -> Self {
+        self.token_lookback_blocks = blocks;
+        self
+    }
+
+    pub fn with_top_up_window(mut self, top_up_window: Duration) -> Self {
+        self.top_up_window = top_up_window;
+        self
+    }
+
+    pub fn with_catch_up_max_blocks(mut self, blocks: u64) -> Self {
+        self.catch_up_max_blocks = blocks;
+        self
+    }
+
+    pub fn with_wrong_network_scan_ticks(mut self, ticks: u32) -> Self {
+        self.wrong_network_scan_ticks = ticks;
+        self
+    }
+
+    pub fn with_late_deposit_scan_ticks(mut self, ticks: u32) -> Self {
+        self.late_deposit_scan_ticks = ticks;
+        self
+    }
+
+    /// Scan only the deposit addresses in `shard`
+    pub fn with_shard(mut self, shard: Shard) -> Self {
+        self.shard = shard;
+        self
+    }
+
+    /// Scan the shard only while this process holds its lease, so replicas
+    /// running the same shards don't credit deposits twice
+    pub fn with_leader_election(mut self, election: Arc<LeaderElection>) -> Self {
+        self.election = Some(election);
+        self
+    }
+
+    pub fn with_metrics(mut self, metrics: Arc<MetricsRegistry>) -> Self {
+        self.metrics = Some(ListenerMetricsCollector::new(metrics));
+        self
+    }
+
+    /// Watch a memo chain's shared hot address with the given history client
+    pub fn with_memo_ledger(mut self, chain: &str, address: &str, ledger: Arc<dyn MemoLedger>) -> Self {
+        self.memo_ledgers.insert(chain.to_string(), (address.to_string(), ledger));
+        self
+    }
+
+    /// Run a single check over all pending swaps
+    pub async fn run_once(&self) -> Result<(), String> {
+        self.check_pending_swaps().await?;
+        self.check_memo_deposits().await
+    }
+    
     /// Main monitoring loop - runs continuously in background
     pub async fn run(&self) {
-        tracing::info!("🚀 Blockchain listener started");
+        let shard = self.shard.label();
+        tracing::info!("🚀 Blockchain listener started (shard {})", shard);
+        // A led shard catches up when it takes the lease instead, since the
+        // previous leader may have stopped at any point
+        if self.election.is_none() {
+            self.catch_up().await;
+        }
+
         let mut tick = interval(self.check_interval);
+        let mut ticks: u64 = 0;
+        let mut leading = false;
         
         loop {
             tick.tick().await;
+
+            if let Some(election) = &self.election {
+                // Fails closed: two leaders could record the same deposit twice
+                let led = election.try_lead(&self.shard.role()).await.unwrap_or_else(|e| {
+                    tracing::error!("Leader election failed for listener shard {}: {}", shard, e);
+                    false
+                });
+                if led != leading {
+                    tracing::info!("Listener shard {} {}", shard, if led { "acquired" } else { "lost" });
+                    if let Some(metrics) = &self.metrics {
+                        metrics.set_leader(&shard, led);
+                    }
+                }
+                if !led {
+                    leading = false;
+                    continue;
+                }
+                if !leading {
+                    leading = true;
+                    self.catch_up().await;
+                }
+            }
+            ticks += 1;
             
-            if let Err(e) = self.check_pending_swaps().await {
-                tracing::error!("Blockchain listener error: {}", e);
+            // Heads read before the check, so a saved cursor never claims
+            // blocks the check did not see
+            let heads = self.chain_heads().await;
+            self.record_lag(&heads).await;
+            match self.check_pending_swaps().await {
+                Ok(()) => self.save_chain_cursors(&heads).await,
+                Err(e) => tracing::error!("Blockchain listener error (shard {}): {}", shard, e),
+            }
+
+            // Memo chains share one hot address, so only the first shard reads them
+            if self.shard.index == 0 {
+                if let Err(e) = self.check_memo_deposits().await {
+                    tracing::error!("Memo deposit check error: {}", e);
+                }
+            }
+
+            // One balance call per chain per swap, so this runs less often
+            if self.wrong_network_scan_ticks > 0 && ticks.is_multiple_of(self.wrong_network_scan_ticks as u64) {
+                if let Err(e) = self.scan_wrong_networks().await {
+                    tracing::error!("Wrong-network scan error: {}", e);
+                }
+            }
+
+            if self.late_deposit_scan_ticks > 0 && ticks.is_multiple_of(self.late_deposit_scan_ticks as u64) {
+                if let Err(e) = self.scan_late_deposits().await {
+                    tracing::error!("Late deposit scan error: {}", e);
+                }
             }
         }
     }
     
     /// Check all pending swaps for incoming funds on blockchain
     async fn check_pending_swaps(&self) -> Result<(), String> {
-        // Get swaps that are in progress and waiting for funds
-        let pending: Vec<(String, String, String, f64, f64)> = sqlx::query_as(
+        // Get swaps that are in progress and waiting for funds. Underpaid
+        // swaps stay in the set while their top-up window is open.
+        let pending: Vec<(String, String, String, String, Decimal, Decimal, Decimal)> = sqlx::query_as(
             r#"
             SELECT 
                 s.id,
                 sa.our_address,
+                s.to_currency,
                 s.to_network,
                 s.estimated_receive,
-                s.platform_fee
+                s.platform_fee,
+                COALESCE(sa.actual_received, 0)
             FROM swaps s
             JOIN swap_address_info sa ON s.id = sa.swap_id
             WHERE s.status IN ('sending', 'exchanging', 'confirming')
             AND sa.status = 'pending'
-            AND s.created_at > DATE_SUB(NOW(), INTERVAL 24 HOUR)
+            AND sa.our_memo IS NULL
+            AND (s.created_at > DATE_SUB(NOW(), INTERVAL 24 HOUR) OR s.expires_at > NOW())
+            AND MOD(CRC32(LOWER(sa.our_address)), ?) = ?
             ORDER BY s.created_at DESC
             LIMIT 100
             "#
         )
+        .bind(self.shard.count)
+        .bind(self.shard.index)
         .fetch_all(&self.db)
         .await
         .map_err(|e| format!("Database error: {}", e))?;
         
+        if let Some(metrics) = &self.metrics {
+            metrics.set_pending_swaps(&self.shard.label(), pending.len());
+        }
         if !pending.is_empty() {
             tracing::debug!("Checking {} pending swaps for blockchain funds", pending.len());
         }
         
-        for (swap_id, our_address, network, estimated_receive, platform_fee) in pending {
+        for (swap_id, our_address, currency, network, estimated_receive, platform_fee, received) in pending {
             // Expected amount is what user gets + our commission
             let expected_amount = estimated_receive + platform_fee;
             
             // Get the appropriate RPC provider for this network
-            let provider = match self.get_provider_for_network(&network) {
-                Some(p) => p,
+            let (chain, provider) = match self.resolve_chain(&network) {
+                Some(chain) => {
+                    let provider = self.providers[&chain].clone();
+                    (chain, provider)
+                }
                 None => {
                     tracing::warn!("No RPC provider configured for network: {}", network);
                     continue;
                 }
             };
             
-            // Check blockchain balance
-            match provider.get_balance(&our_address).await {
-                Ok(balance) if balance >= expected_amount * 0.95 => {
-                    // Funds detected! (95% threshold to account for small discrepancies)
+            // Check blockchain balance; it is the sum of every deposit so far
+            let balance = match self
+                .deposited_amount(&swap_id, &chain, &currency, &our_address, provider.as_ref(), self.token_lookback_blocks)
+                .await
+            {
+                Ok(balance) => balance,
+                Err(e) => {
+                    tracing::error!(
+                        "RPC error checking balance for swap {} on {}: {}",
+                        swap_id, network, e
+                    );
+                    continue;
+                }
+            };
+
+            self.apply_deposit(&swap_id, &network, received, balance, expected_amount).await;
+        }
+        
+        Ok(())
+    }
+
+    /// Current head block of every configured chain that answers
+    async fn chain_heads(&self) -> Vec<(String, u64)> {
+        let mut heads = Vec::new();
+        for (chain, provider) in &self.providers {
+            match provider.get_block_number().await {
+                Ok(head) => heads.push((chain.clone(), head)),
+                Err(e) => tracing::debug!("Could not read {} head block: {}", chain, e),
+            }
+        }
+        heads
+    }
+
+    async fn save_chain_cursors(&self, heads: &[(String, u64)]) {
+        for (chain, head) in heads {
+            if let Err(e) = self.save_chain_cursor(chain, *head).await {
+                tracing::error!("{}", e);
+            }
+        }
+    }
+
+    async fn save_chain_cursor(&self, chain: &str, head: u64) -> Result<(), String> {
+        sqlx::query(
+            r#"
+            INSERT INTO chain_scan_cursors (network, shard_count, shard_index, last_block) VALUES (?, ?, ?, ?)
+            ON DUPLICATE KEY UPDATE last_block = GREATEST(last_block, VALUES(last_block)), updated_at = NOW()
+            "#
+        )
+        .bind(chain)
+        .bind(self.shard.count)
+        .bind(self.shard.index)
+        .bind(head)
+        .execute(&self.db)
+        .await
+        .map_err(|e| format!("Failed to save {} scan cursor: {}", chain, e))?;
+        Ok(())
+    }
+
+    /// How far this shard's cursors trail the given chain heads
+    pub async fn shard_lag(&self, heads: &[(String, u64)]) -> Result<Vec<ShardLag>, String> {
+        let cursors: Vec<(String, u64, chrono::DateTime<chrono::Utc>)> = sqlx::query_as(
+            "SELECT network, last_block, updated_at FROM chain_scan_cursors WHERE shard_count = ? AND shard_index = ?"
+        )
+        .bind(self.shard.count)
+        .bind(self.shard.index)
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+        Ok(heads
+            .iter()
+            .filter_map(|(chain, head)| {
+                let (_, cursor_block, scanned_at) = cursors.iter().find(|(network, ..)| network == chain)?;
+                Some(ShardLag {
+                    chain: chain.clone(),
+                    head_block: *head,
+                    cursor_block: *cursor_block,
+                    scanned_at: *scanned_at,
+                })
+            })
+            .collect())
+    }
+
+    async fn record_lag(&self, heads: &[(String, u64)]) {
+        let Some(metrics) = &self.metrics else { return };
+        let lags = match self.shard_lag(heads).await {
+            Ok(lags) => lags,
+            Err(e) => {
+                tracing::debug!("Could not read listener shard {} lag: {}", self.shard.label(), e);
+                return;
+            }
+        };
+
+        let shard = self.shard.label();
+        for lag in &lags {
+            metrics.set_lag_blocks(&shard, &lag.chain, lag.blocks());
+        }
+        if let Some(secs) = lags.iter().map(ShardLag::seconds).max() {
+            metrics.set_lag_seconds(&shard, secs);
+        }
+    }
+
+    /// Re-check deposits for the blocks mined while the listener was down,
+    /// from each chain's saved cursor to its current head. Balance polling
+    /// only sees the present, and token logs are normally read a fixed
+    /// window back, so transfers during a long outage would otherwise be
+    /// missed. Gaps longer than `catch_up_max_blocks` are cut to the most
+    /// recent blocks and reported as truncated.
+    pub async fn catch_up(&self) -> Vec<CatchUpReport> {
+        let mut reports = Vec::new();
+        if self.catch_up_max_blocks == 0 {
+            return reports;
+        }
+
+        for (chain, provider) in &self.providers {
+            match self.catch_up_chain(chain, provider.as_ref()).await {
+                Ok(Some(report)) => {
+                    if report.range.truncated {
+                        tracing::warn!(
+                            "⚠️  {} catch-up truncated: {} blocks behind after {}s down, scanned the last {} (from block {})",
+                            chain, report.range.gap_blocks, report.downtime_secs(), report.range.blocks(), report.range.from_block
+                        );
+                    }
                     tracing::info!(
-                        "✅ Blockchain funds detected for swap {}: {} {} (expected {})",
-                        swap_id, balance, network, expected_amount
+                        "🔁 {} catch-up recovered {} deposits across {} open swaps (blocks {}..={}, {}s down)",
+                        chain, report.deposits_recovered, report.swaps_checked,
+                        report.range.from_block, report.range.to_block, report.downtime_secs()
                     );
-                    
-                    // Trigger payout
-                    if let Err(e) = self.trigger_payout(&swap_id, balance).await {
-                        tracing::error!("Failed to trigger payout for {}: {}", swap_id, e);
+                    reports.push(report);
+                }
+                Ok(None) => {}
+                Err(e) => tracing::error!("Catch-up scan failed on {}: {}", chain, e),
+            }
+        }
+
+        reports
+    }
+
+    async fn catch_up_chain(&self, chain: &str, provider: &dyn BlockchainProvider) -> Result<Option<CatchUpReport>, String> {
+        let head = provider.get_block_number().await.map_err(|e| e.to_string())?;
+
+        // A shard without its own cursor yet (the split changed) starts from
+        // the furthest-behind cursor any split left on the chain
+        let cursor: Option<(u64, chrono::DateTime<chrono::Utc>)> = sqlx::query_as(
+            r#"
+            SELECT last_block, updated_at FROM chain_scan_cursors
+            WHERE network = ?
+            ORDER BY (shard_count = ? AND shard_index = ?) DESC, last_block ASC
+            LIMIT 1
+            "#
+        )
+        .bind(chain)
+        .bind(self.shard.count)
+        .bind(self.shard.index)
+        .fetch_optional(&self.db)
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+        // First start on this chain: nothing is known to be missing
+        let Some((last_block, last_seen)) = cursor else {
+            self.save_chain_cursor(chain, head).await?;
+            return Ok(None);
+        };
+        let Some(range) = catch_up_range(last_block, head, self.catch_up_max_blocks) else {
+            return Ok(None);
+        };
+
+        // Swaps that were still accepting deposits when the listener stopped,
+        // including those whose expiry passed during the outage
+        let open: Vec<(String, String, String, String, Decimal, Decimal, Decimal)> = sqlx::query_as(
+            r#"
+            SELECT
+                s.id,
+                sa.our_address,
+                s.to_currency,
+                s.to_network,
+                s.estimated_receive,
+                s.platform_fee,
+                COALESCE(sa.actual_received, 0)
+            FROM swaps s
+            JOIN swap_address_info sa ON s.id = sa.swap_id
+            WHERE s.status IN ('sending', 'exchanging', 'confirming')
+            AND sa.status = 'pending'
+            AND sa.our_memo IS NULL
+            AND (s.created_at > DATE_SUB(?, INTERVAL 24 HOUR) OR s.expires_at > ?)
+            AND MOD(CRC32(LOWER(sa.our_address)), ?) = ?
+            ORDER BY s.created_at DESC
+            LIMIT 500
+            "#
+        )
+        .bind(last_seen)
+        .bind(last_seen)
+        .bind(self.shard.count)
+        .bind(self.shard.index)
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+        let lookback = self.token_lookback_blocks.max(range.blocks());
+        let mut report = CatchUpReport {
+            chain: chain.to_string(),
+            range,
+            last_seen,
+            swaps_checked: 0,
+            deposits_recovered: 0,
+        };
+
+        for (swap_id, our_address, currency, network, estimated_receive, platform_fee, received) in open {
+            if self.resolve_chain(&network).as_deref() != Some(chain) {
+                continue;
+            }
+            report.swaps_checked += 1;
+
+            let balance = match self.deposited_amount(&swap_id, chain, &currency, &our_address, provider, lookback).await {
+                Ok(balance) => balance,
+                Err(e) => {
+                    tracing::error!("RPC error during catch-up for swap {} on {}: {}", swap_id, chain, e);
+                    continue;
+                }
+            };
+            if balance - received > DUST_THRESHOLD {
+                report.deposits_recovered += 1;
+            }
+
+            self.apply_deposit(&swap_id, &network, received, balance, estimated_receive + platform_fee).await;
+        }
+
+        self.save_chain_cursor(chain, head).await?;
+        self.record_catch_up(&report).await;
+        Ok(Some(report))
+    }
+
+    async fn record_catch_up(&self, report: &CatchUpReport) {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO chain_catch_up_runs
+                (network, from_block, to_block, gap_blocks, truncated, downtime_secs, swaps_checked, deposits_recovered)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            "#
+        )
+        .bind(&report.chain)
+        .bind(report.range.from_block)
+        .bind(report.range.to_block)
+        .bind(report.range.gap_blocks)
+        .bind(report.range.truncated)
+        .bind(report.downtime_secs())
+        .bind(report.swaps_checked)
+        .bind(report.deposits_recovered)
+        .execute(&self.db)
+        .await;
+
+        if let Err(e) = result {
+            tracing::error!("Failed to record {} catch-up run: {}", report.chain, e);
+        }
+    }
+
+    /// Act on the running total received for a swap: trigger the payout once
+    /// funded, or record a partial deposit and hold the swap open for a top-up
+    async fn apply_deposit(&self, swap_id: &str, network: &str, received: Decimal, balance: Decimal, expected_amount: Decimal) {
+        match evaluate_deposit(received, balance, expected_amount) {
+            DepositOutcome::Funded { total } => {
+                // Funds detected! (95% threshold to account for small discrepancies)
+                tracing::info!(
+                    "✅ Blockchain funds detected for swap {}: {} {} (expected {})",
+                    swap_id, total, network, expected_amount
+                );
+                
+                if total - received > DUST_THRESHOLD {
+                    if let Err(e) = self.record_deposit(swap_id, total - received, total).await {
+                        tracing::error!("Failed to record deposit for {}: {}", swap_id, e);
                     }
                 }
-                Ok(balance) if balance > 0.0001 => {
-                    // Some funds detected but not enough yet
-                    tracing::debug!(
-                        "⏳ Partial funds for swap {}: {} / {} {}",
-                        swap_id, balance, expected_amount, network
-                    );
-                    
-                    // Update last balance check timestamp
-                    self.update_balance_check(&swap_id).await.ok();
+
+                // Trigger payout
+                if let Err(e) = self.trigger_payout(swap_id, total).await {
+                    tracing::error!("Failed to trigger payout for {}: {}", swap_id, e);
                 }
-                Ok(_) => {
-                    // No funds yet, keep waiting
-                    tracing::trace!("Waiting for funds: swap {} on {}", swap_id, network);
+            }
+            DepositOutcome::TopUp { delta, total } => {
+                // Underpaid so far; give the user time to send the rest
+                tracing::info!(
+                    "⏳ Partial deposit for swap {}: +{} (total {} / {} {}), top-up window extended",
+                    swap_id, delta, total, expected_amount, network
+                );
+
+                if let Err(e) = self.record_deposit(swap_id, delta, total).await {
+                    tracing::error!("Failed to record deposit for {}: {}", swap_id, e);
+                }
+                if let Err(e) = self.extend_top_up_window(swap_id).await {
+                    tracing::error!("Failed to extend top-up window for {}: {}", swap_id, e);
                 }
+            }
+            DepositOutcome::Waiting if balance > DUST_THRESHOLD => {
+                // Partial funds already recorded, still waiting for the rest
+                self.update_balance_check(swap_id).await.ok();
+            }
+            DepositOutcome::Waiting => {
+                // No funds yet, keep waiting
+                tracing::trace!("Waiting for funds: swap {} on {}", swap_id, network);
+            }
+        }
+    }
+
+    /// Read new payments into each memo chain's hot address and credit them
+    /// to the swap named by the memo. Payments that don't name exactly one
+    /// open swap are quarantined for an operator.
+    pub async fn check_memo_deposits(&self) -> Result<(), String> {
+        for (chain, (address, ledger)) in &self.memo_ledgers {
+            let cursor: Option<(String,)> = sqlx::query_as("SELECT cursor_value FROM memo_scan_cursors WHERE network = ?")
+                .bind(chain)
+                .fetch_optional(&self.db)
+                .await
+                .map_err(|e| format!("Database error: {}", e))?;
+
+            let page = match ledger.incoming_transfers(address, cursor.as_ref().map(|(c,)| c.as_str())).await {
+                Ok(page) => page,
                 Err(e) => {
-                    tracing::error!(
-                        "RPC error checking balance for swap {} on {}: {}",
-                        swap_id, network, e
-                    );
+                    tracing::error!("Failed to read {} hot address history: {}", chain, e);
+                    continue;
+                }
+            };
+
+            if !page.transfers.is_empty() {
+                let index = self.memo_index(chain).await?;
+                for transfer in &page.transfers {
+                    self.process_memo_transfer(chain, transfer, &index).await?;
                 }
             }
+
+            if let Some(next) = page.next_cursor {
+                sqlx::query(
+                    r#"
+                    INSERT INTO memo_scan_cursors (network, cursor_value) VALUES (?, ?)
+                    ON DUPLICATE KEY UPDATE cursor_value = VALUES(cursor_value)
+                    "#
+                )
+                .bind(chain)
+                .bind(next)
+                .execute(&self.db)
+                .await
+                .map_err(|e| format!("Failed to save memo cursor: {}", e))?;
+            }
         }
-        
+
+        Ok(())
+    }
+
+    /// Memos of recent swaps on a chain, split by whether the swap still
+    /// accepts deposits
+    async fn memo_index(&self, chain: &str) -> Result<MemoIndex, String> {
+        let rows: Vec<(String, String, i64)> = sqlx::query_as(
+            r#"
+            SELECT sa.our_memo, s.id,
+                   (s.status IN ('sending', 'exchanging', 'confirming') AND sa.status = 'pending') AS open
+            FROM swap_address_info sa
+            JOIN swaps s ON s.id = sa.swap_id
+            WHERE sa.memo_network = ? AND sa.our_memo IS NOT NULL
+            AND s.created_at > DATE_SUB(NOW(), INTERVAL 30 DAY)
+            "#
+        )
+        .bind(chain)
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+        let mut index = MemoIndex::default();
+        for (memo, swap_id, open) in rows {
+            index.insert(&memo, &swap_id, open != 0);
+        }
+        Ok(index)
+    }
+
+    async fn process_memo_transfer(&self, chain: &str, transfer: &IncomingTransfer, index: &MemoIndex) -> Result<(), String> {
+        let matched = index.resolve(transfer.memo.as_deref());
+        let (swap_id, status, reason) = match &matched {
+            MemoMatch::Matched(swap_id) => (Some(swap_id.as_str()), "credited", None),
+            MemoMatch::Quarantined(reason) => (None, "quarantined", Some(reason.as_str())),
+        };
+
+        // One row per transaction; a transfer seen again after a restart is skipped
+        let inserted = sqlx::query(
+            r#"
+            INSERT IGNORE INTO memo_deposits (network, tx_hash, memo, amount, swap_id, status, reason)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            "#
+        )
+        .bind(chain)
+        .bind(&transfer.tx_hash)
+        .bind(&transfer.memo)
+        .bind(transfer.amount)
+        .bind(swap_id)
+        .bind(status)
+        .bind(reason)
+        .execute(&self.db)
+        .await
+        .map_err(|e| format!("Failed to record memo deposit: {}", e))?;
+        if inserted.rows_affected() == 0 {
+            return Ok(());
+        }
+
+        let swap_id = match matched {
+            MemoMatch::Matched(swap_id) => swap_id,
+            MemoMatch::Quarantined(reason) => {
+                tracing::warn!(
+                    "🚩 Quarantined {} deposit {} of {} (memo {:?}): {}",
+                    chain, transfer.tx_hash, transfer.amount, transfer.memo, reason.as_str()
+                );
+                return Ok(());
+            }
+        };
+
+        let amount = amount::from_f64(transfer.amount).map_err(|e| e.to_string())?;
+        self.credit_deposit(&swap_id, chain, amount).await
+    }
+
+    /// Add a deposit that can't be read from an address balance (a memo
+    /// chain payment, or one released from quarantine) to a swap's total
+    pub async fn credit_deposit(&self, swap_id: &str, network: &str, amount: Decimal) -> Result<(), String> {
+        let (expected_amount, received): (Decimal, Decimal) = sqlx::query_as(
+            r#"
+            SELECT s.estimated_receive + s.platform_fee, COALESCE(sa.actual_received, 0)
+            FROM swaps s
+            JOIN swap_address_info sa ON s.id = sa.swap_id
+            WHERE s.id = ?
+            "#
+        )
+        .bind(swap_id)
+        .fetch_one(&self.db)
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+        self.apply_deposit(swap_id, network, received, received + amount, expected_amount).await;
+        Ok(())
+    }
+
+    /// Amount received at a deposit address. Native coins use the address
+    /// balance; tokens with a canonical contract use the sum of its transfer
+    /// logs, so look-alike tokens sent to the address are never credited.
+    async fn deposited_amount(
+        &self,
+        swap_id: &str,
+        chain: &str,
+        currency: &str,
+        address: &str,
+        provider: &dyn BlockchainProvider,
+        lookback_blocks: u64,
+    ) -> Result<Decimal, String> {
+        let token = self.tokens.canonical_token(currency, chain).await.map_err(|e| e.to_string())?;
+        let Some(token) = token else {
+            return provider.get_balance(address).await.map_err(|e| e.to_string());
+        };
+
+        let latest = provider.get_block_number().await.map_err(|e| e.to_string())?;
+        let logs = provider
+            .get_transfer_logs(address, latest.saturating_sub(lookback_blocks))
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let canonical = self.tokens.canonical_tokens(chain).await.map_err(|e| e.to_string())?;
+        let tally = tally_token_deposits(logs, &canonical, &token);
+
+        for (log, verdict) in &tally.flagged {
+            if let Err(e) = self.flag_transfer(swap_id, chain, log, verdict).await {
+                tracing::error!("Failed to flag token transfer {} for {}: {}", log.tx_hash, swap_id, e);
+            }
+        }
+
+        amount::from_minor_units(tally.units, token.decimals as u32).map_err(|e| e.to_string())
+    }
+
+    /// Keep a record of an ignored token transfer; warns the first time it is seen
+    async fn flag_transfer(
+        &self,
+        swap_id: &str,
+        chain: &str,
+        log: &TransferLog,
+        verdict: &TransferVerdict,
+    ) -> Result<(), String> {
+        let result = sqlx::query(
+            r#"
+            INSERT IGNORE INTO flagged_token_transfers
+                (swap_id, network, contract_address, from_address, to_address, amount_raw, tx_hash, log_index, block_number, reason)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#
+        )
+        .bind(swap_id)
+        .bind(chain)
+        .bind(&log.contract)
+        .bind(&log.from)
+        .bind(&log.to)
+        .bind(log.amount.to_string())
+        .bind(&log.tx_hash)
+        .bind(log.log_index)
+        .bind(log.block_number)
+        .bind(flag_reason(verdict))
+        .execute(&self.db)
+        .await
+        .map_err(|e| format!("Failed to flag transfer: {}", e))?;
+
+        if result.rows_affected() == 1 {
+            tracing::warn!(
+                "🚩 Ignoring {} token transfer to swap {} on {}: contract {} (tx {})",
+                flag_reason(verdict), swap_id, chain, log.contract, log.tx_hash
+            );
+        }
+        Ok(())
+    }
+
+    /// Record one deposit and the new running total for the address
+    async fn record_deposit(&self, swap_id: &str, amount: Decimal, cumulative: Decimal) -> Result<(), String> {
+        let mut tx = self.db.begin().await.map_err(|e| e.to_string())?;
+
+        sqlx::query("INSERT INTO deposit_receipts (swap_id, amount, cumulative) VALUES (?, ?, ?)")
+            .bind(swap_id)
+            .bind(amount)
+            .bind(cumulative)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("Failed to record deposit: {}", e))?;
+
+        sqlx::query(
+            r#"
+            UPDATE swap_address_info
+            SET actual_received = ?, deposit_count = deposit_count + 1, last_balance_check = NOW()
+            WHERE swap_id = ?
+            "#
+        )
+        .bind(cumulative)
+        .bind(swap_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Failed to update received amount: {}", e))?;
+
+        tx.commit().await.map_err(|e| e.to_string())
+    }
+
+    /// Push expires_at out by the top-up window, capped relative to creation;
+    /// never shortens an existing expiry
+    async fn extend_top_up_window(&self, swap_id: &str) -> Result<(), String> {
+        sqlx::query(
+            r#"
+            UPDATE swaps
+            SET expires_at = GREATEST(
+                COALESCE(expires_at, NOW()),
+                LEAST(DATE_ADD(NOW(), INTERVAL ? SECOND), DATE_ADD(created_at, INTERVAL ? HOUR))
+            )
+            WHERE id = ?
+            "#
+        )
+        .bind(self.top_up_window.as_secs())
+        .bind(MAX_TOP_UP_HOURS)
+        .bind(swap_id)
+        .execute(&self.db)
+        .await
+        .map_err(|e| format!("Failed to extend expiry: {}", e))?;
+
         Ok(())
     }
     
-    /// Get RPC provider for a specific network
-    fn get_provider_for_network(&self, network: &str) -> Option<Arc<dyn BlockchainProvider>> {
+    /// Look for deposits to pending swap addresses on EVM chains other than
+    /// the one each swap expects. The same key controls the address on
+    /// every EVM chain, so such funds are recoverable; each sighting opens
+    /// (or updates) a wrong-network case for an admin to resolve. Returns
+    /// the number of new cases.
+    pub async fn scan_wrong_networks(&self) -> Result<usize, String> {
+        if self.providers.len() < 2 {
+            return Ok(0);
+        }
+
+        let pending: Vec<(String, String, u32, String)> = sqlx::query_as(
+            r#"
+            SELECT s.id, sa.our_address, sa.address_index, s.to_network
+            FROM swaps s
+            JOIN swap_address_info sa ON s.id = sa.swap_id
+            WHERE s.status IN ('sending', 'exchanging', 'confirming')
+            AND sa.status = 'pending'
+            AND sa.our_memo IS NULL
+            AND (s.created_at > DATE_SUB(NOW(), INTERVAL 24 HOUR) OR s.expires_at > NOW())
+            AND MOD(CRC32(LOWER(sa.our_address)), ?) = ?
+            ORDER BY s.created_at DESC
+            LIMIT 100
+            "#
+        )
+        .bind(self.shard.count)
+        .bind(self.shard.index)
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+        let crud = RecoveryCrud::new(self.db.clone());
+        let mut opened = 0;
+
+        for (swap_id, our_address, address_index, network) in pending {
+            // Only EVM swaps share their address across the configured chains
+            let Some(expected) = self.resolve_chain(&network) else { continue };
+
+            for (chain, provider) in &self.providers {
+                if *chain == expected {
+                    continue;
+                }
+
+                let balance = match provider.get_balance(&our_address).await {
+                    Ok(balance) => balance,
+                    Err(e) => {
+                        tracing::debug!("RPC error scanning {} on {}: {}", our_address, chain, e);
+                        continue;
+                    }
+                };
+                if balance <= DUST_THRESHOLD {
+                    continue;
+                }
+
+                match crud.record_detection(&swap_id, &our_address, address_index, &expected, chain, balance).await {
+                    Ok(true) => {
+                        opened += 1;
+                        tracing::warn!(
+                            "⚠️  Wrong-network deposit for swap {}: {} on {} (expected {})",
+                            swap_id, balance, chain, expected
+                        );
+                    }
+                    Ok(false) => {}
+                    Err(e) => tracing::error!("Failed to record wrong-network deposit for {}: {}", swap_id, e),
+                }
+            }
+        }
+
+        Ok(opened)
+    }
+    
+    /// Look for funds that reached an expired swap's address after it
+    /// expired. Each one is recorded as a refund to the swap's refund
+    /// address, which `LateDepositRefunder` sends. Returns the number of
+    /// new refunds.
+    pub async fn scan_late_deposits(&self) -> Result<usize, String> {
+        let expired: Vec<(String, String, String, String, Option<String>)> = sqlx::query_as(
+            r#"
+            SELECT s.id, sa.our_address, s.to_currency, s.to_network, s.refund_address
+            FROM swaps s
+            JOIN swap_address_info sa ON s.id = sa.swap_id
+            WHERE s.status = 'expired'
+            AND sa.status = 'pending'
+            AND sa.our_memo IS NULL
+            AND s.created_at > DATE_SUB(NOW(), INTERVAL ? DAY)
+            AND NOT EXISTS (
+                SELECT 1 FROM refunds r WHERE r.swap_id = s.id AND r.initiated_by = ?
+            )
+            AND MOD(CRC32(LOWER(sa.our_address)), ?) = ?
+            ORDER BY s.created_at DESC
+            LIMIT 100
+            "#
+        )
+        .bind(late_deposit_lookback_days())
+        .bind(LATE_DEPOSIT_INITIATOR)
+        .bind(self.shard.count)
+        .bind(self.shard.index)
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+        let mut recorded = 0;
+        for (swap_id, our_address, currency, network, refund_address) in expired {
+            let Some(chain) = self.resolve_chain(&network) else { continue };
+            let provider = self.providers[&chain].clone();
+
+            let native = match self.tokens.canonical_token(&currency, &chain).await {
+                Ok(token) => token.is_none(),
+                Err(e) => {
+                    tracing::debug!("Token lookup failed for {} on {}: {}", currency, chain, e);
+                    continue;
+                }
+            };
+            let amount = match self
+                .deposited_amount(&swap_id, &chain, &currency, &our_address, provider.as_ref(), self.token_lookback_blocks)
+                .await
+            {
+                Ok(amount) => amount,
+                Err(e) => {
+                    tracing::debug!("RPC error checking late deposit to {} on {}: {}", our_address, chain, e);
+                    continue;
+                }
+            };
+            if amount <= DUST_THRESHOLD {
+                continue;
+            }
+
+            let deposit = LateDeposit { swap_id, network: chain, currency, amount, refund_address, native };
+            match record_late_deposit(&self.db, &deposit).await {
+                Ok(true) => {
+                    recorded += 1;
+                    tracing::warn!(
+                        "⚠️  Late deposit for expired swap {}: {} {} on {}",
+                        deposit.swap_id, deposit.amount, deposit.currency, deposit.network
+                    );
+                }
+                Ok(false) => {}
+                Err(e) => tracing::error!("{}", e),
+            }
+        }
+
+        Ok(recorded)
+    }
+
+    /// Canonical name of a configured chain for a swap network
+    fn resolve_chain(&self, network: &str) -> Option<String> {
         let normalized = network.to_lowercase();
         
         // Try exact match first
-        if let Some(provider) = self.providers.get(&normalized) {
-            return Some(provider.clone());
+        if self.providers.contains_key(&normalized) {
+            return Some(normalized);
         }
         
         // Try common aliases and variations
-        let provider_key = match normalized.as_str() {
-            // Ethereum aliases
-            "erc20" | "eth" | "mainnet" => "ethereum",
-            
-            // Polygon aliases
-            "matic" | "pos" => "polygon",
-            
-            // BSC aliases
-            "bnb" | "bep20" | "binance" | "smartchain" => "bsc",
-            
-            // Arbitrum aliases
-            "arb" | "arbitrum one" | "arbitrumone" => "arbitrum",
-            
-            // Optimism aliases
-            "op" | "optimistic" => "optimism",
-            
-            // Avalanche aliases
-            "avax" | "avalanche c-chain" | "cchain" => "avalanche",
-            
-            // Base aliases
-            "base mainnet" | "coinbase" => "base",
-            
-            // Fantom aliases
-            "ftm" | "opera" => "fantom",
-            
-            // Gnosis aliases
-            "xdai" | "gno" => "gnosis",
-            
-            // Cronos aliases
-            "cro" => "cronos",
-            
-            // Moonbeam aliases
-            "glmr" => "moonbeam",
-            
-            // Moonriver aliases
-            "movr" => "moonriver",
-            
-            // Celo aliases
-            "celo mainnet" => "celo",
-            
-            // Aurora aliases
-            "aurora mainnet" | "near" => "aurora",
-            
-            // Harmony aliases
-            "one" | "harmony one" => "harmony",
-            
-            // Metis aliases
-            "metis andromeda" => "metis",
-            
-            // zkSync aliases
-            "zksync era" | "zks" => "zksync",
-            
-            // Linea aliases
-            "linea mainnet" => "linea",
-            
-            // Scroll aliases
-            "scroll mainnet" => "scroll",
-            
-            // Mantle aliases
-            "mnt" => "mantle",
-            
-            // Blast aliases
-            "blast mainnet" => "blast",
-            
-            // Mode aliases
-            "mode mainnet" => "mode",
-            
-            // Manta aliases
-            "manta pacific" | "manta mainnet" => "manta",
-            
-            _ => {
-                tracing::debug!("No RPC provider found for network: {}", network);
-                return None;
-            }
+        let Some(provider_key) = chain_alias(&normalized) else {
+            tracing::debug!("No RPC provider found for network: {}", network);
+            return None;
         };
         
-        self.providers.get(provider_key).cloned()
+        self.providers.contains_key(provider_key).then(|| provider_key.to_string())
     }
     
     /// Trigger payout by updating swap status
-    async fn trigger_payout(&self, swap_id: &str, actual_balance: f64) -> Result<(), String> {
-        // Update swap status to 'funds_received'
-        sqlx::query(
-            r#"
-            UPDATE swaps 
-            SET status = 'funds_received', updated_at = NOW() 
-            WHERE id = ? AND status IN ('sending', 'exchanging', 'confirming')
-            "#
-        )
-        .bind(swap_id)
-        .execute(&self.db)
-        .await
-        .map_err(|e| format!("Failed to update swap status: {}", e))?;
+    async fn trigger_payout(&self, swap_id: &str, actual_balance: Decimal) -> Result<(), String> {
+        // Update swap status to 'funds_received'; the state machine rejects
+        // swaps another worker already moved past this point
+        let transition = Transition::to(SwapStatus::FundsReceived)
+            .message("Detected by blockchain listener")
+            .actor(StatusActor::Listener);
+        match SwapStateMachine::new(self.db.clone()).advance(swap_id, &transition).await {
+            Ok(_) => {}
+            Err(TransitionError::Illegal { from, .. }) => {
+                tracing::debug!("Swap {} is already {}, not marking funds_received", swap_id, from);
+                return Ok(());
+            }
+            Err(e) => return Err(format!("Failed to update swap status: {}", e)),
+        }
         
         // Update swap_address_info with actual received amount
         sqlx::query(
@@ -0,0 +1,189 @@
+//! Deposit matching for chains where swaps share one hot address and are
+//! told apart by a memo (XRP destination tag, Stellar memo id, Hedera and
+//! Cosmos transaction memos).
+
+use async_trait::async_trait;
+use rand::Rng;
+use std::collections::{HashMap, HashSet};
+
+use crate::services::explorer::chain_key;
+
+/// Memo chains and the variable holding each chain's shared hot address
+pub const MEMO_CHAINS: &[(&str, &str)] = &[
+    ("xrp", "XRP_HOT_ADDRESS"),
+    ("xlm", "XLM_HOT_ADDRESS"),
+    ("hbar", "HBAR_HOT_ADDRESS"),
+    ("atom", "ATOM_HOT_ADDRESS"),
+];
+
+/// Memo chain a currency/network settles on, if any
+pub fn memo_chain(currency: &str, network: &str) -> Option<&'static str> {
+    match chain_key(currency, network).as_str() {
+        "xrp" | "ripple" | "xrpl" => Some("xrp"),
+        "xlm" | "stellar" => Some("xlm"),
+        "hbar" | "hedera" => Some("hbar"),
+        "atom" | "cosmos" | "cosmoshub" => Some("atom"),
+        _ => None,
+    }
+}
+
+/// Configured shared hot address for a memo chain
+pub fn hot_address(chain: &str) -> Option<String> {
+    let (_, var) = MEMO_CHAINS.iter().find(|(name, _)| *name == chain)?;
+    std::env::var(var).ok().filter(|a| !a.trim().is_empty())
+}
+
+/// A fresh numeric memo. XRP destination tags are 32-bit; the other chains
+/// accept any short string, so they get a wider range to make collisions rarer.
+pub fn generate_memo(chain: &str) -> String {
+    let mut rng = rand::rng();
+    if chain == "xrp" {
+        rng.random_range(100_000..=u32::MAX).to_string()
+    } else {
+        rng.random_range(1_000_000_000u64..10_000_000_000_000).to_string()
+    }
+}
+
+/// Wallets pad or space memos differently; numeric memos compare by value
+pub fn normalize_memo(memo: &str) -> Option<String> {
+    let memo = memo.trim();
+    if memo.is_empty() {
+        return None;
+    }
+    if memo.chars().all(|c| c.is_ascii_digit()) {
+        let trimmed = memo.trim_start_matches('0');
+        return Some(if trimmed.is_empty() { "0" } else { trimmed }.to_string());
+    }
+    Some(memo.to_string())
+}
+
+/// A payment into a hot address
+#[derive(Debug, Clone, PartialEq)]
+pub struct IncomingTransfer {
+    pub tx_hash: String,
+    pub memo: Option<String>,
+    /// Whole coins
+    pub amount: f64,
+}
+
+/// Transfers read from an account's history, and where to resume
+#[derive(Debug, Default)]
+pub struct LedgerPage {
+    pub transfers: Vec<IncomingTransfer>,
+    /// Position after the last record read, including records that were
+    /// not incoming payments; `None` when nothing new was read
+    pub next_cursor: Option<String>,
+}
+
+/// Why a transfer could not be credited to a swap
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuarantineReason {
+    /// Sent without a memo
+    MissingMemo,
+    /// Memo belongs to no swap
+    UnknownMemo,
+    /// Memo matches more than one open swap
+    MemoCollision,
+    /// Memo belongs to a swap that is no longer waiting for funds
+    SwapClosed,
+}
+
+impl QuarantineReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            QuarantineReason::MissingMemo => "missing_memo",
+            QuarantineReason::UnknownMemo => "unknown_memo",
+            QuarantineReason::MemoCollision => "memo_collision",
+            QuarantineReason::SwapClosed => "swap_closed",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MemoMatch {
+    Matched(String),
+    Quarantined(QuarantineReason),
+}
+
+/// Memos of recent swaps on one chain, keyed by normalized memo
+#[derive(Debug, Default)]
+pub struct MemoIndex {
+    open: HashMap<String, Vec<String>>,
+    closed: HashSet<String>,
+}
+
+impl MemoIndex {
+    pub fn insert(&mut self, memo: &str, swap_id: &str, open: bool) {
+        let Some(memo) = normalize_memo(memo) else { return };
+        if open {
+            self.open.entry(memo).or_default().push(swap_id.to_string());
+        } else {
+            self.closed.insert(memo);
+        }
+    }
+
+    /// Credit only when the memo names exactly one open swap
+    pub fn resolve(&self, memo: Option<&str>) -> MemoMatch {
+        let Some(memo) = memo.and_then(normalize_memo) else {
+            return MemoMatch::Quarantined(QuarantineReason::MissingMemo);
+        };
+
+        match self.open.get(&memo).map(Vec::as_slice) {
+            Some([swap_id]) => MemoMatch::Matched(swap_id.clone()),
+            Some([_, _, ..]) => MemoMatch::Quarantined(QuarantineReason::MemoCollision),
+            _ if self.closed.contains(&memo) => MemoMatch::Quarantined(QuarantineReason::SwapClosed),
+            _ => MemoMatch::Quarantined(QuarantineReason::UnknownMemo),
+        }
+    }
+}
+
+/// Transaction history of a memo chain account
+#[async_trait]
+pub trait MemoLedger: Send + Sync {
+    /// Successful payments of the native coin into `address` after `cursor`
+    /// (from the start of history when `None`), oldest first. Transfers at
+    /// the cursor may be returned again; callers dedupe by transaction hash.
+    async fn incoming_transfers(&self, address: &str, cursor: Option<&str>) -> Result<LedgerPage, String>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memo_chain_aliases() {
+        assert_eq!(memo_chain("XRP", "Mainnet"), Some("xrp"));
+        assert_eq!(memo_chain("XLM", "stellar"), Some("xlm"));
+        assert_eq!(memo_chain("ATOM", "Mainnet"), Some("atom"));
+        assert_eq!(memo_chain("USDT", "ERC20"), None);
+    }
+
+    #[test]
+    fn test_numeric_memos_compare_by_value() {
+        assert_eq!(normalize_memo(" 000123 "), Some("123".to_string()));
+        assert_eq!(normalize_memo("abc"), Some("abc".to_string()));
+        assert_eq!(normalize_memo("  "), None);
+    }
+
+    #[test]
+    fn test_resolve_matches_single_open_swap() {
+        let mut index = MemoIndex::default();
+        index.insert("1001", "swap-a", true);
+        index.insert("2002", "swap-b", true);
+        index.insert("02002", "swap-c", true);
+        index.insert("3003", "swap-d", false);
+
+        assert_eq!(index.resolve(Some("1001")), MemoMatch::Matched("swap-a".to_string()));
+        assert_eq!(index.resolve(Some("2002")), MemoMatch::Quarantined(QuarantineReason::MemoCollision));
+        assert_eq!(index.resolve(Some("3003")), MemoMatch::Quarantined(QuarantineReason::SwapClosed));
+        assert_eq!(index.resolve(Some("4004")), MemoMatch::Quarantined(QuarantineReason::UnknownMemo));
+        assert_eq!(index.resolve(None), MemoMatch::Quarantined(QuarantineReason::MissingMemo));
+    }
+
+    #[test]
+    fn test_xrp_memo_fits_destination_tag() {
+        for _ in 0..100 {
+            assert!(generate_memo("xrp").parse::<u32>().is_ok());
+        }
+    }
+}
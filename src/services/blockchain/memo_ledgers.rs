@@ -0,0 +1,280 @@
+//! History clients for memo chain hot addresses
+
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::time::Duration;
+
+use super::memo_deposits::{IncomingTransfer, LedgerPage, MemoLedger};
+
+const PAGE_LIMIT: u32 = 200;
+/// Pages read per poll; the rest is picked up on the next check
+const MAX_PAGES: usize = 5;
+const DROPS_PER_XRP: f64 = 1_000_000.0;
+
+fn http_client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build()
+        .unwrap_or_default()
+}
+
+// =============================================================================
+// XRP LEDGER
+// =============================================================================
+
+/// rippled JSON-RPC `account_tx`. The cursor is the last ledger index read;
+/// the next poll starts at that ledger again so no transaction in it is missed.
+pub struct XrpLedgerClient {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl XrpLedgerClient {
+    pub fn new(url: String) -> Self {
+        Self { client: http_client(), url }
+    }
+
+    async fn account_tx(&self, address: &str, ledger_min: i64, marker: Option<&Value>) -> Result<Value, String> {
+        let mut params = json!({
+            "account": address,
+            "ledger_index_min": ledger_min,
+            "ledger_index_max": -1,
+            "forward": true,
+            "limit": PAGE_LIMIT,
+        });
+        if let Some(marker) = marker {
+            params["marker"] = marker.clone();
+        }
+
+        let response: Value = self
+            .client
+            .post(&self.url)
+            .json(&json!({ "method": "account_tx", "params": [params] }))
+            .send()
+            .await
+            .map_err(|e| format!("XRPL request failed: {}", e))?
+            .json()
+            .await
+            .map_err(|e| format!("XRPL response invalid: {}", e))?;
+
+        let result = response.get("result").cloned().unwrap_or(Value::Null);
+        if result.get("status").and_then(Value::as_str) == Some("error") {
+            let error = result.get("error").and_then(Value::as_str).unwrap_or("unknown");
+            return Err(format!("XRPL error: {}", error));
+        }
+        Ok(result)
+    }
+}
+
+/// A validated, successful XRP payment into `address`
+fn parse_xrp_payment(entry: &Value, address: &str) -> Option<IncomingTransfer> {
+    // API v2 moved the transaction under `tx_json` with the hash alongside
+    let tx = entry.get("tx_json").or_else(|| entry.get("tx"))?;
+    let hash = entry.get("hash").or_else(|| tx.get("hash"))?.as_str()?;
+
+    if tx.get("TransactionType")?.as_str()? != "Payment" || tx.get("Destination")?.as_str()? != address {
+        return None;
+    }
+    let meta = entry.get("meta")?;
+    if meta.get("TransactionResult")?.as_str()? != "tesSUCCESS" {
+        return None;
+    }
+
+    // Issued currencies are objects; only native XRP (drops as a string) counts
+    let drops: f64 = meta.get("delivered_amount")?.as_str()?.parse().ok()?;
+    let memo = tx.get("DestinationTag").and_then(Value::as_u64).map(|t| t.to_string());
+
+    Some(IncomingTransfer { tx_hash: hash.to_string(), memo, amount: drops / DROPS_PER_XRP })
+}
+
+fn xrp_ledger_index(entry: &Value) -> Option<i64> {
+    entry
+        .get("ledger_index")
+        .or_else(|| entry.get("tx").and_then(|tx| tx.get("ledger_index")))
+        .and_then(Value::as_i64)
+}
+
+#[async_trait]
+impl MemoLedger for XrpLedgerClient {
+    async fn incoming_transfers(&self, address: &str, cursor: Option<&str>) -> Result<LedgerPage, String> {
+        let ledger_min = cursor.and_then(|c| c.parse::<i64>().ok()).unwrap_or(-1);
+        let mut page = LedgerPage::default();
+        let mut marker: Option<Value> = None;
+
+        for _ in 0..MAX_PAGES {
+            let result = self.account_tx(address, ledger_min, marker.as_ref()).await?;
+            let entries = result.get("transactions").and_then(Value::as_array).cloned().unwrap_or_default();
+
+            for entry in &entries {
+                if entry.get("validated").and_then(Value::as_bool) == Some(false) {
+                    continue;
+                }
+                if let Some(index) = xrp_ledger_index(entry) {
+                    page.next_cursor = Some(index.to_string());
+                }
+                if let Some(transfer) = parse_xrp_payment(entry, address) {
+                    page.transfers.push(transfer);
+                }
+            }
+
+            marker = result.get("marker").cloned();
+            if marker.is_none() {
+                break;
+            }
+        }
+
+        Ok(page)
+    }
+}
+
+// =============================================================================
+// STELLAR (HORIZON)
+// =============================================================================
+
+/// Horizon `/accounts/{id}/payments` joined with transactions for the memo.
+/// The cursor is Horizon's paging token.
+pub struct HorizonClient {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl HorizonClient {
+    pub fn new(url: String) -> Self {
+        Self { client: http_client(), url: url.trim_end_matches('/').to_string() }
+    }
+}
+
+/// A successful native XLM payment into `address`
+fn parse_stellar_payment(record: &Value, address: &str) -> Option<IncomingTransfer> {
+    if record.get("type")?.as_str()? != "payment"
+        || record.get("to")?.as_str()? != address
+        || record.get("asset_type")?.as_str()? != "native"
+        || record.get("transaction_successful").and_then(Value::as_bool) == Some(false)
+    {
+        return None;
+    }
+
+    let amount: f64 = record.get("amount")?.as_str()?.parse().ok()?;
+    let memo = record
+        .get("transaction")
+        .and_then(|tx| tx.get("memo"))
+        .and_then(Value::as_str)
+        .map(str::to_string);
+
+    Some(IncomingTransfer {
+        tx_hash: record.get("transaction_hash")?.as_str()?.to_string(),
+        memo,
+        amount,
+    })
+}
+
+#[async_trait]
+impl MemoLedger for HorizonClient {
+    async fn incoming_transfers(&self, address: &str, cursor: Option<&str>) -> Result<LedgerPage, String> {
+        let mut page = LedgerPage::default();
+        let mut cursor = cursor.map(str::to_string);
+
+        for _ in 0..MAX_PAGES {
+            let mut query = vec![
+                ("join", "transactions".to_string()),
+                ("order", "asc".to_string()),
+                ("limit", PAGE_LIMIT.to_string()),
+            ];
+            if let Some(c) = &cursor {
+                query.push(("cursor", c.clone()));
+            }
+
+            let body: Value = self
+                .client
+                .get(format!("{}/accounts/{}/payments", self.url, address))
+                .query(&query)
+                .send()
+                .await
+                .map_err(|e| format!("Horizon request failed: {}", e))?
+                .json()
+                .await
+                .map_err(|e| format!("Horizon response invalid: {}", e))?;
+
+            let records = body
+                .get("_embedded")
+                .and_then(|e| e.get("records"))
+                .and_then(Value::as_array)
+                .cloned()
+                .unwrap_or_default();
+
+            for record in &records {
+                if let Some(token) = record.get("paging_token").and_then(Value::as_str) {
+                    cursor = Some(token.to_string());
+                    page.next_cursor = Some(token.to_string());
+                }
+                if let Some(transfer) = parse_stellar_payment(record, address) {
+                    page.transfers.push(transfer);
+                }
+            }
+
+            if records.len() < PAGE_LIMIT as usize {
+                break;
+            }
+        }
+
+        Ok(page)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOT: &str = "rHb9CJAWyB4rj91VRWn96DkukG4bwdtyTh";
+
+    #[test]
+    fn test_parse_xrp_payment_reads_destination_tag() {
+        let entry = json!({
+            "validated": true,
+            "meta": { "TransactionResult": "tesSUCCESS", "delivered_amount": "25000000" },
+            "tx": {
+                "TransactionType": "Payment",
+                "Destination": HOT,
+                "DestinationTag": 123456,
+                "hash": "ABC",
+                "ledger_index": 90000000
+            }
+        });
+
+        let transfer = parse_xrp_payment(&entry, HOT).unwrap();
+        assert_eq!(transfer.memo.as_deref(), Some("123456"));
+        assert!((transfer.amount - 25.0).abs() < 1e-9);
+        assert_eq!(xrp_ledger_index(&entry), Some(90000000));
+    }
+
+    #[test]
+    fn test_parse_xrp_payment_skips_issued_currency() {
+        let entry = json!({
+            "meta": {
+                "TransactionResult": "tesSUCCESS",
+                "delivered_amount": { "currency": "USD", "issuer": "rIssuer", "value": "10" }
+            },
+            "tx": { "TransactionType": "Payment", "Destination": HOT, "hash": "DEF" }
+        });
+
+        assert!(parse_xrp_payment(&entry, HOT).is_none());
+    }
+
+    #[test]
+    fn test_parse_stellar_payment_reads_memo() {
+        let record = json!({
+            "type": "payment",
+            "to": "GHOT",
+            "asset_type": "native",
+            "amount": "12.5000000",
+            "transaction_hash": "tx1",
+            "transaction_successful": true,
+            "paging_token": "1",
+            "transaction": { "memo": "987654321", "memo_type": "id" }
+        });
+
+        let transfer = parse_stellar_payment(&record, "GHOT").unwrap();
+        assert_eq!(transfer.memo.as_deref(), Some("987654321"));
+        assert!((transfer.amount - 12.5).abs() < 1e-9);
+    }
+}
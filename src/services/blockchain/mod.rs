@@ -1,3 +1,12 @@
+pub mod catch_up;
+pub mod deposits;
 pub mod listener;
+pub mod memo_deposits;
+pub mod memo_ledgers;
+pub mod shards;
+pub mod token_deposits;
 
+pub use catch_up::{CatchUpRange, CatchUpReport};
+pub use deposits::{evaluate_deposit, DepositOutcome};
 pub use listener::BlockchainListener;
+pub use shards::{Shard, ShardLag};
@@ -0,0 +1,129 @@
+//! Sharding of pending-swap scanning. Each deposit address belongs to one
+//! shard by CRC32 of its lowercase form, the same hash MySQL's `CRC32()`
+//! computes, so a shard can select its swaps in SQL. Every shard keeps its
+//! own scan cursor per chain and is led by one process at a time.
+
+use chrono::{DateTime, Utc};
+
+/// One slice of the deposit addresses, `index` of `count`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Shard {
+    pub index: u32,
+    pub count: u32,
+}
+
+impl Shard {
+    /// Every address; what an unsharded listener scans
+    pub const ALL: Shard = Shard { index: 0, count: 1 };
+
+    pub fn new(index: u32, count: u32) -> Result<Self, String> {
+        if count == 0 || index >= count {
+            return Err(format!("Invalid listener shard {} of {}", index, count));
+        }
+        Ok(Self { index, count })
+    }
+
+    /// All shards of a listener split `count` ways
+    pub fn all(count: u32) -> Vec<Shard> {
+        (0..count.max(1)).map(|index| Shard { index, count: count.max(1) }).collect()
+    }
+
+    pub fn owns(&self, address: &str) -> bool {
+        shard_of(address, self.count) == self.index
+    }
+
+    /// Leader-election role; includes the count so shards of a different
+    /// split never share a lease
+    pub fn role(&self) -> String {
+        format!("listener:shard:{}:{}", self.count, self.index)
+    }
+
+    /// Metrics label
+    pub fn label(&self) -> String {
+        format!("{}/{}", self.index, self.count)
+    }
+}
+
+/// How far one shard's scan cursor trails a chain
+#[derive(Debug, Clone)]
+pub struct ShardLag {
+    pub chain: String,
+    pub head_block: u64,
+    pub cursor_block: u64,
+    /// When the shard last completed a scan of the chain
+    pub scanned_at: DateTime<Utc>,
+}
+
+impl ShardLag {
+    pub fn blocks(&self) -> u64 {
+        self.head_block.saturating_sub(self.cursor_block)
+    }
+
+    pub fn seconds(&self) -> i64 {
+        (Utc::now() - self.scanned_at).num_seconds().max(0)
+    }
+}
+
+/// Shard an address falls in when scanning is split `count` ways
+pub fn shard_of(address: &str, count: u32) -> u32 {
+    crc32(address.to_ascii_lowercase().as_bytes()) % count.max(1)
+}
+
+/// CRC-32 (IEEE), matching MySQL's `CRC32()`
+pub fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for byte in bytes {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+/// Shard count from LISTENER_SHARDS (default 1)
+pub fn shard_count_from_env() -> Result<u32, String> {
+    match std::env::var("LISTENER_SHARDS") {
+        Ok(v) if !v.trim().is_empty() => match v.trim().parse::<u32>() {
+            Ok(count) if (1..=256).contains(&count) => Ok(count),
+            _ => Err(format!("LISTENER_SHARDS must be between 1 and 256, got {:?}", v)),
+        },
+        _ => Ok(1),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crc32_matches_mysql() {
+        // SELECT CRC32('123456789') = 3421780262
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(crc32(b""), 0);
+    }
+
+    #[test]
+    fn test_every_address_has_exactly_one_shard() {
+        let shards = Shard::all(4);
+        for i in 0..200 {
+            let address = format!("0x{:040x}", i * 7919);
+            assert_eq!(shards.iter().filter(|s| s.owns(&address)).count(), 1);
+        }
+        assert!(Shard::ALL.owns("0xAnything"));
+    }
+
+    #[test]
+    fn test_shard_ignores_address_case() {
+        let address = "0x742d35Cc6634C0532925a3b844Bc9e7595f5bE12";
+        assert_eq!(shard_of(address, 8), shard_of(&address.to_lowercase(), 8));
+    }
+
+    #[test]
+    fn test_invalid_shard_rejected() {
+        assert!(Shard::new(2, 2).is_err());
+        assert!(Shard::new(0, 0).is_err());
+        assert_eq!(Shard::new(1, 3).unwrap().role(), "listener:shard:3:1");
+    }
+}
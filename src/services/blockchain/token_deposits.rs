@@ -0,0 +1,80 @@
+use crate::services::token::registry::{classify_contract, CanonicalToken, TransferVerdict};
+use crate::services::wallet::rpc::TransferLog;
+
+/// Token transfers into a deposit address, split by whether they count
+#[derive(Debug, Default)]
+pub struct TokenDepositTally {
+    /// Sum of transfers from the canonical contract, in base units
+    pub units: u128,
+    /// Transfers that were ignored, with the reason
+    pub flagged: Vec<(TransferLog, TransferVerdict)>,
+}
+
+/// Count only transfers emitted by the canonical contract for the expected
+/// token. Anything else sent to the address (look-alike "USDT" contracts,
+/// airdrop spam, a different real token) is returned for flagging instead.
+pub fn tally_token_deposits(
+    logs: Vec<TransferLog>,
+    canonical: &[CanonicalToken],
+    expected: &CanonicalToken,
+) -> TokenDepositTally {
+    let mut tally = TokenDepositTally::default();
+
+    for log in logs {
+        match classify_contract(canonical, &expected.symbol, &log.contract) {
+            TransferVerdict::Expected(_) => tally.units = tally.units.saturating_add(log.amount),
+            verdict => tally.flagged.push((log, verdict)),
+        }
+    }
+    tally
+}
+
+/// Reason stored with a flagged transfer
+pub fn flag_reason(verdict: &TransferVerdict) -> &'static str {
+    match verdict {
+        TransferVerdict::Expected(_) => "expected",
+        TransferVerdict::OtherToken(_) => "unexpected_token",
+        TransferVerdict::NonCanonical => "non_canonical",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usdt() -> CanonicalToken {
+        CanonicalToken {
+            network: "ethereum".to_string(),
+            symbol: "USDT".to_string(),
+            contract_address: "0xdac17f958d2ee523a2206206994597c13d831ec7".to_string(),
+            decimals: 6,
+        }
+    }
+
+    fn transfer(contract: &str, amount: u128, log_index: u64) -> TransferLog {
+        TransferLog {
+            contract: contract.to_string(),
+            from: "0x1111111111111111111111111111111111111111".to_string(),
+            to: "0x742d35cc6634c0532925a3b844bc454e4438f44e".to_string(),
+            amount,
+            tx_hash: format!("0x{:064x}", log_index),
+            log_index,
+            block_number: 1,
+        }
+    }
+
+    #[test]
+    fn test_spoofed_transfers_do_not_count() {
+        let canonical = vec![usdt()];
+        let logs = vec![
+            transfer("0xdac17f958d2ee523a2206206994597c13d831ec7", 150_000_000, 0),
+            transfer("0x00000000000000000000000000000000deadbeef", 1_000_000_000_000, 1),
+        ];
+
+        let tally = tally_token_deposits(logs, &canonical, &usdt());
+
+        assert_eq!(tally.units, 150_000_000);
+        assert_eq!(tally.flagged.len(), 1);
+        assert_eq!(flag_reason(&tally.flagged[0].1), "non_canonical");
+    }
+}
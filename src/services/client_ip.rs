@@ -0,0 +1,380 @@
+use axum::{
+    body::Body,
+    extract::{ConnectInfo, OptionalFromRequestParts},
+    http::{request::Parts, HeaderMap, Request},
+    response::Response,
+};
+use std::{
+    convert::Infallible,
+    future::Future,
+    net::{IpAddr, Ipv6Addr, SocketAddr},
+    pin::Pin,
+    sync::Arc,
+};
+use tower::{Layer, Service};
+
+/// Forwarding chains longer than this are treated as spoofed and ignored
+const MAX_FORWARDED_HOPS: usize = 20;
+
+/// Real client address resolved by `ClientIpLayer`. Handlers take it as
+/// `Option<ClientIp>`; it is absent when the server has no connect info.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClientIp(pub IpAddr);
+
+impl ClientIp {
+    /// Bucket key for rate limiting. IPv6 clients usually control a whole
+    /// /64, so they share one bucket per prefix.
+    pub fn rate_limit_key(&self) -> String {
+        match self.0 {
+            IpAddr::V4(ip) => ip.to_string(),
+            IpAddr::V6(ip) => {
+                let s = ip.segments();
+                let prefix = Ipv6Addr::new(s[0], s[1], s[2], s[3], 0, 0, 0, 0);
+                format!("{}/64", prefix)
+            }
+        }
+    }
+}
+
+impl<S: Send + Sync> OptionalFromRequestParts<S> for ClientIp {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Option<Self>, Self::Rejection> {
+        Ok(parts.extensions.get::<ClientIp>().copied())
+    }
+}
+
+// =============================================================================
+// TRUSTED PROXIES
+// =============================================================================
+
+/// IPv4 or IPv6 network in CIDR notation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IpCidr {
+    network: IpAddr,
+    prefix: u8,
+}
+
+impl IpCidr {
+    /// Parse `10.0.0.0/8`, `2001:db8::/32` or a bare address
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let (addr, prefix) = match value.trim().split_once('/') {
+            Some((addr, prefix)) => (addr, Some(prefix)),
+            None => (value.trim(), None),
+        };
+        let network: IpAddr = addr.parse().map_err(|_| format!("Invalid proxy address '{}'", value))?;
+        let max = if network.is_ipv4() { 32 } else { 128 };
+        let prefix = match prefix {
+            Some(p) => p.parse::<u8>().map_err(|_| format!("Invalid prefix length in '{}'", value))?,
+            None => max,
+        };
+        if prefix > max {
+            return Err(format!("Prefix length too long in '{}'", value));
+        }
+        Ok(Self { network, prefix })
+    }
+
+    pub fn contains(&self, ip: &IpAddr) -> bool {
+        match (self.network, normalize(*ip)) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => {
+                let mask = u32::MAX.checked_shl(32 - self.prefix as u32).unwrap_or(0);
+                u32::from(net) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                let mask = u128::MAX.checked_shl(128 - self.prefix as u32).unwrap_or(0);
+                u128::from(net) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Proxies allowed to report the client address, and how many forwarding
+/// hops to follow back from our own peer
+#[derive(Debug, Clone)]
+pub struct TrustedProxies {
+    networks: Vec<IpCidr>,
+    max_depth: usize,
+}
+
+impl Default for TrustedProxies {
+    /// Trust nobody: the TCP peer is the client
+    fn default() -> Self {
+        Self { networks: Vec::new(), max_depth: 1 }
+    }
+}
+
+impl TrustedProxies {
+    pub fn new(networks: Vec<IpCidr>) -> Self {
+        Self { networks, ..Self::default() }
+    }
+
+    /// Number of forwarded entries to consult, counted from the right
+    pub fn with_max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = depth.clamp(1, MAX_FORWARDED_HOPS);
+        self
+    }
+
+    /// Load TRUSTED_PROXIES (comma separated CIDRs) and TRUSTED_PROXY_DEPTH
+    pub fn from_env() -> Result<Self, String> {
+        let networks = match std::env::var("TRUSTED_PROXIES") {
+            Ok(list) => list
+                .split(',')
+                .filter(|entry| !entry.trim().is_empty())
+                .map(IpCidr::parse)
+                .collect::<Result<Vec<_>, _>>()?,
+            Err(_) => Vec::new(),
+        };
+        let depth = match std::env::var("TRUSTED_PROXY_DEPTH") {
+            Ok(v) => v.trim().parse::<usize>().map_err(|e| format!("Invalid TRUSTED_PROXY_DEPTH: {}", e))?,
+            Err(_) => 1,
+        };
+        Ok(Self::new(networks).with_max_depth(depth))
+    }
+
+    pub fn is_trusted(&self, ip: &IpAddr) -> bool {
+        self.networks.iter().any(|net| net.contains(ip))
+    }
+
+    /// Client address for a request arriving from `peer`. Forwarding
+    /// headers are only honoured when the peer is a trusted proxy; the
+    /// chain is walked right to left and stops at the first untrusted hop.
+    pub fn resolve(&self, peer: IpAddr, headers: &HeaderMap) -> IpAddr {
+        let peer = normalize(peer);
+        if !self.is_trusted(&peer) {
+            return peer;
+        }
+
+        let Some(chain) = forwarded_chain(headers) else {
+            return peer;
+        };
+        if chain.len() > MAX_FORWARDED_HOPS {
+            tracing::debug!("Ignoring forwarding chain of {} hops", chain.len());
+            return peer;
+        }
+
+        let mut client = peer;
+        for (depth, hop) in chain.iter().rev().enumerate() {
+            if depth >= self.max_depth {
+                break;
+            }
+            // Unparseable or obfuscated hop: nothing beyond it can be trusted
+            let Some(ip) = hop else { break };
+            client = *ip;
+            if !self.is_trusted(ip) {
+                break;
+            }
+        }
+        client
+    }
+}
+
+/// Unwrap IPv4-mapped IPv6 addresses (`::ffff:1.2.3.4`) so dual-stack
+/// listeners see the same address as IPv4 ones
+fn normalize(ip: IpAddr) -> IpAddr {
+    match ip {
+        IpAddr::V6(v6) => v6.to_ipv4_mapped().map(IpAddr::V4).unwrap_or(ip),
+        v4 => v4,
+    }
+}
+
+/// Addresses from `Forwarded` (preferred) or `X-Forwarded-For`, client
+/// first. `None` entries are hops that did not parse as an address.
+fn forwarded_chain(headers: &HeaderMap) -> Option<Vec<Option<IpAddr>>> {
+    let forwarded: Vec<&str> = headers
+        .get_all("forwarded")
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .collect();
+    if !forwarded.is_empty() {
+        let chain = forwarded
+            .iter()
+            .flat_map(|value| value.split(','))
+            .map(|element| {
+                element
+                    .split(';')
+                    .filter_map(|pair| pair.trim().split_once('='))
+                    .find(|(key, _)| key.trim().eq_ignore_ascii_case("for"))
+                    .and_then(|(_, node)| parse_node(node))
+            })
+            .collect();
+        return Some(chain);
+    }
+
+    let xff: Vec<&str> = headers
+        .get_all("x-forwarded-for")
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .collect();
+    if xff.is_empty() {
+        return None;
+    }
+    Some(xff.iter().flat_map(|value| value.split(',')).map(parse_node).collect())
+}
+
+/// Parse `1.2.3.4`, `1.2.3.4:80`, `"[2001:db8::1]:443"` or `2001:db8::1`
+fn parse_node(node: &str) -> Option<IpAddr> {
+    let node = node.trim().trim_matches('"');
+    if let Ok(ip) = node.parse::<IpAddr>() {
+        return Some(normalize(ip));
+    }
+    if let Some(rest) = node.strip_prefix('[') {
+        let (ip, _) = rest.split_once(']')?;
+        return ip.parse().ok().map(normalize);
+    }
+    node.parse::<SocketAddr>().ok().map(|addr| normalize(addr.ip()))
+}
+
+// =============================================================================
+// LAYER
+// =============================================================================
+
+/// Resolves the real client address once per request and stores it as a
+/// `ClientIp` extension for rate limiting, logging and handlers
+#[derive(Clone)]
+pub struct ClientIpLayer {
+    proxies: Arc<TrustedProxies>,
+}
+
+impl ClientIpLayer {
+    pub fn new(proxies: TrustedProxies) -> Self {
+        Self { proxies: Arc::new(proxies) }
+    }
+}
+
+impl<S> Layer<S> for ClientIpLayer {
+    type Service = ClientIpService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ClientIpService {
+            inner,
+            proxies: self.proxies.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct ClientIpService<S> {
+    inner: S,
+    proxies: Arc<TrustedProxies>,
+}
+
+impl<S> Service<Request<Body>> for ClientIpService<S>
+where
+    S: Service<Request<Body>, Response = Response> + Clone + Send + 'static,
+    S::Future: Send,
+{
+    type Response = Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut std::task::Context<'_>) -> std::task::Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: Request<Body>) -> Self::Future {
+        let peer = request
+            .extensions()
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip());
+
+        if let Some(peer) = peer {
+            let client = self.proxies.resolve(peer, request.headers());
+            request.extensions_mut().insert(ClientIp(client));
+        }
+
+        let mut inner = self.inner.clone();
+        Box::pin(async move { inner.call(request).await })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    fn ip(value: &str) -> IpAddr {
+        value.parse().unwrap()
+    }
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for (name, value) in pairs {
+            map.append(*name, HeaderValue::from_str(value).unwrap());
+        }
+        map
+    }
+
+    fn proxies(depth: usize) -> TrustedProxies {
+        TrustedProxies::new(vec![IpCidr::parse("10.0.0.0/8").unwrap(), IpCidr::parse("fd00::/8").unwrap()])
+            .with_max_depth(depth)
+    }
+
+    #[test]
+    fn test_cidr_contains() {
+        let net = IpCidr::parse("10.0.0.0/8").unwrap();
+        assert!(net.contains(&ip("10.1.2.3")));
+        assert!(!net.contains(&ip("11.0.0.1")));
+        assert!(net.contains(&ip("::ffff:10.0.0.1")));
+
+        let v6 = IpCidr::parse("2001:db8::/32").unwrap();
+        assert!(v6.contains(&ip("2001:db8:1::1")));
+        assert!(!v6.contains(&ip("2001:db9::1")));
+
+        assert!(IpCidr::parse("0.0.0.0/0").unwrap().contains(&ip("8.8.8.8")));
+        assert!(IpCidr::parse("10.0.0.0/33").is_err());
+    }
+
+    #[test]
+    fn test_untrusted_peer_ignores_headers() {
+        let h = headers(&[("x-forwarded-for", "1.2.3.4")]);
+        assert_eq!(proxies(1).resolve(ip("203.0.113.9"), &h), ip("203.0.113.9"));
+    }
+
+    #[test]
+    fn test_trusted_peer_uses_forwarded_for() {
+        let h = headers(&[("x-forwarded-for", "198.51.100.7")]);
+        assert_eq!(proxies(1).resolve(ip("10.0.0.2"), &h), ip("198.51.100.7"));
+    }
+
+    #[test]
+    fn test_spoofed_left_entries_are_not_trusted() {
+        // Client prepended a fake address; only the entry our proxy added counts
+        let h = headers(&[("x-forwarded-for", "6.6.6.6, 198.51.100.7")]);
+        assert_eq!(proxies(1).resolve(ip("10.0.0.2"), &h), ip("198.51.100.7"));
+    }
+
+    #[test]
+    fn test_depth_limits_trusted_hops() {
+        let h = headers(&[("x-forwarded-for", "198.51.100.7, 10.0.0.5")]);
+        assert_eq!(proxies(1).resolve(ip("10.0.0.2"), &h), ip("10.0.0.5"));
+        assert_eq!(proxies(2).resolve(ip("10.0.0.2"), &h), ip("198.51.100.7"));
+    }
+
+    #[test]
+    fn test_forwarded_header_with_ipv6() {
+        let h = headers(&[("forwarded", r#"for="[2001:db8:cafe::17]:4711";proto=https"#)]);
+        assert_eq!(proxies(1).resolve(ip("fd00::1"), &h), ip("2001:db8:cafe::17"));
+    }
+
+    #[test]
+    fn test_garbage_hop_stops_the_walk() {
+        let h = headers(&[("x-forwarded-for", "198.51.100.7, unknown")]);
+        assert_eq!(proxies(2).resolve(ip("10.0.0.2"), &h), ip("10.0.0.2"));
+    }
+
+    #[test]
+    fn test_overlong_chain_is_ignored() {
+        let chain = vec!["10.0.0.9"; MAX_FORWARDED_HOPS + 1].join(", ");
+        let h = headers(&[("x-forwarded-for", &chain)]);
+        assert_eq!(proxies(3).resolve(ip("10.0.0.2"), &h), ip("10.0.0.2"));
+    }
+
+    #[test]
+    fn test_ipv6_rate_limit_key_uses_prefix() {
+        let a = ClientIp(ip("2001:db8:1:2:aaaa::1"));
+        let b = ClientIp(ip("2001:db8:1:2:bbbb::2"));
+        assert_eq!(a.rate_limit_key(), b.rate_limit_key());
+        assert_eq!(ClientIp(ip("1.2.3.4")).rate_limit_key(), "1.2.3.4");
+    }
+}
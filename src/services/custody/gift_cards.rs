@@ -0,0 +1,120 @@
+use std::sync::Arc;
+use std::time::Duration;
+use sqlx::{MySql, Pool};
+
+use crate::modules::gift_cards::crud::GiftCardCrud;
+use crate::modules::payout_guard::model::PayoutKind;
+use crate::modules::wallet::crud::WalletCrud;
+use crate::services::blockchain::listener::evm_rpc_url;
+use crate::services::payout::{PayoutGuard, PayoutGuardError, PlannedPayout};
+use crate::services::wallet::manager::WalletManager;
+use crate::services::wallet::rpc::{BlockchainProvider, HttpRpcClient};
+
+const BATCH_SIZE: i64 = 20;
+
+/// Pays gift card orders debited from custodial balances to the provider
+pub struct GiftCardPayer {
+    db: Pool<MySql>,
+    master_seed: String,
+    provider: Option<Arc<dyn BlockchainProvider>>,
+}
+
+impl GiftCardPayer {
+    pub fn new(db: Pool<MySql>, master_seed: String) -> Self {
+        Self { db, master_seed, provider: None }
+    }
+
+    /// Send through `provider` instead of the ethereum RPC URL
+    pub fn with_provider(mut self, provider: Arc<dyn BlockchainProvider>) -> Self {
+        self.provider = Some(provider);
+        self
+    }
+
+    /// Start the background processing loop
+    pub async fn run(&self) {
+        let mut interval = tokio::time::interval(Duration::from_secs(30));
+
+        loop {
+            interval.tick().await;
+
+            if let Err(e) = self.process_batch().await {
+                tracing::error!("Gift card payments failed: {}", e);
+            }
+        }
+    }
+
+    /// Pay one batch of funded purchases. Stops at the first one the payout
+    /// guard refuses; the rest stay pending for a later batch.
+    pub async fn process_batch(&self) -> Result<usize, String> {
+        let crud = GiftCardCrud::new(self.db.clone(), None);
+        let unpaid = crud.get_unpaid_purchases(BATCH_SIZE).await
+            .map_err(|e| e.to_string())?;
+
+        let provider = self.provider.clone().unwrap_or_else(|| {
+            let rpc_url = evm_rpc_url("ethereum").unwrap_or_else(|| "http://localhost:8545".to_string());
+            Arc::new(HttpRpcClient::new(rpc_url))
+        });
+        let wallet_manager = WalletManager::new(WalletCrud::new(self.db.clone()), self.master_seed.clone(), provider);
+        let guard = PayoutGuard::new(self.db.clone());
+
+        let mut processed = 0;
+        for purchase in unpaid {
+            let planned = PlannedPayout::new(
+                PayoutKind::GiftCard,
+                &purchase.id,
+                &purchase.payment_network,
+                &purchase.payment_currency,
+                purchase.payment_amount,
+            );
+            let reservation = match guard.check(&planned).await {
+                Ok(reservation) => reservation,
+                Err(e @ PayoutGuardError::Unpriced(_)) => {
+                    tracing::warn!("Holding gift card payment {}: {}", purchase.id, e);
+                    continue;
+                }
+                Err(e) => {
+                    tracing::warn!("Holding gift card payments at {}: {}", purchase.id, e);
+                    break;
+                }
+            };
+
+            // Another worker may have claimed it between the read and now
+            if crud.claim_payment(&purchase.id).await.is_err() {
+                guard.release(&reservation).await;
+                continue;
+            }
+
+            let result = match (&purchase.payment_address, &purchase.payment_extra_id) {
+                (Some(address), None) => {
+                    wallet_manager
+                        .process_withdrawal(
+                            &purchase.payment_currency,
+                            &purchase.payment_network,
+                            address,
+                            purchase.payment_amount,
+                        )
+                        .await
+                }
+                (Some(_), Some(_)) => Err("Payments that need a memo are not supported".to_string()),
+                (None, _) => Err("Provider order has no payment address".to_string()),
+            };
+            guard.record(&reservation, result.as_ref().err().map(String::as_str)).await;
+
+            match result {
+                Ok(tx_hash) => {
+                    tracing::info!("✅ Gift card purchase {} paid: tx_hash={}", purchase.id, tx_hash);
+                    crud.complete_payment(&purchase.id, &tx_hash).await
+                        .map_err(|e| e.to_string())?;
+                }
+                Err(e) => {
+                    tracing::error!("❌ Gift card payment {} failed: {}", purchase.id, e);
+                    crud.fail_payment(&purchase, &e).await
+                        .map_err(|e| e.to_string())?;
+                }
+            }
+            processed += 1;
+        }
+
+        Ok(processed)
+    }
+}
@@ -0,0 +1,5 @@
+pub mod withdrawals;
+pub mod gift_cards;
+
+pub use withdrawals::WithdrawalProcessor;
+pub use gift_cards::GiftCardPayer;
@@ -0,0 +1,105 @@
+use std::sync::Arc;
+use std::time::Duration;
+use sqlx::{MySql, Pool};
+
+use crate::modules::balances::crud::BalanceCrud;
+use crate::modules::payout_guard::model::PayoutKind;
+use crate::modules::wallet::crud::WalletCrud;
+use crate::services::blockchain::listener::evm_rpc_url;
+use crate::services::payout::{PayoutGuard, PayoutGuardError, PlannedPayout};
+use crate::services::wallet::manager::WalletManager;
+use crate::services::wallet::rpc::{BlockchainProvider, HttpRpcClient};
+
+const BATCH_SIZE: i64 = 20;
+
+/// Executes admin-approved balance withdrawals through the wallet payout pipeline
+pub struct WithdrawalProcessor {
+    db: Pool<MySql>,
+    master_seed: String,
+}
+
+impl WithdrawalProcessor {
+    pub fn new(db: Pool<MySql>, master_seed: String) -> Self {
+        Self { db, master_seed }
+    }
+
+    /// Start the background processing loop
+    pub async fn run(&self) {
+        let mut interval = tokio::time::interval(Duration::from_secs(30));
+
+        loop {
+            interval.tick().await;
+
+            if let Err(e) = self.process_batch().await {
+                tracing::error!("Withdrawal processing failed: {}", e);
+            }
+        }
+    }
+
+    /// Process one batch of approved withdrawals. Stops at the first one
+    /// the payout guard refuses; the rest stay approved for a later batch.
+    pub async fn process_batch(&self) -> Result<usize, String> {
+        let crud = BalanceCrud::new(self.db.clone());
+        let approved = crud.get_approved_withdrawals(BATCH_SIZE).await
+            .map_err(|e| e.to_string())?;
+
+        let rpc_url = evm_rpc_url("ethereum").unwrap_or_else(|| "http://localhost:8545".to_string());
+        let provider: Arc<dyn BlockchainProvider> = Arc::new(HttpRpcClient::new(rpc_url));
+        let wallet_manager = WalletManager::new(WalletCrud::new(self.db.clone()), self.master_seed.clone(), provider);
+        let guard = PayoutGuard::new(self.db.clone());
+
+        let mut processed = 0;
+        for withdrawal in approved {
+            let planned = PlannedPayout::new(
+                PayoutKind::Withdrawal,
+                &withdrawal.id,
+                &withdrawal.network,
+                &withdrawal.currency,
+                withdrawal.amount,
+            );
+            let reservation = match guard.check(&planned).await {
+                Ok(reservation) => reservation,
+                Err(e @ PayoutGuardError::Unpriced(_)) => {
+                    tracing::warn!("Holding withdrawal {}: {}", withdrawal.id, e);
+                    continue;
+                }
+                Err(e) => {
+                    tracing::warn!("Holding withdrawals at {}: {}", withdrawal.id, e);
+                    break;
+                }
+            };
+
+            // Another worker may have claimed it between the read and now
+            if crud.claim_withdrawal(&withdrawal.id).await.is_err() {
+                guard.release(&reservation).await;
+                continue;
+            }
+
+            let result = wallet_manager
+                .process_withdrawal(
+                    &withdrawal.currency,
+                    &withdrawal.network,
+                    &withdrawal.destination_address,
+                    withdrawal.amount,
+                )
+                .await;
+            guard.record(&reservation, result.as_ref().err().map(String::as_str)).await;
+
+            match result {
+                Ok(tx_hash) => {
+                    tracing::info!("✅ Withdrawal {} sent: tx_hash={}", withdrawal.id, tx_hash);
+                    crud.complete_withdrawal(&withdrawal.id, &tx_hash).await
+                        .map_err(|e| e.to_string())?;
+                }
+                Err(e) => {
+                    tracing::error!("❌ Withdrawal {} failed: {}", withdrawal.id, e);
+                    crud.fail_withdrawal(&withdrawal.id, &e).await
+                        .map_err(|e| e.to_string())?;
+                }
+            }
+            processed += 1;
+        }
+
+        Ok(processed)
+    }
+}
@@ -0,0 +1,121 @@
+//! Delivery events posted by the mail provider. Postmark and Resend webhook
+//! payloads are understood; permanent bounces and spam complaints put the
+//! address on the suppression list, soft bounces are ignored.
+
+use serde_json::Value;
+
+use crate::modules::email::model::SuppressionReason;
+
+/// An address the provider says we must stop mailing
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeliveryEvent {
+    pub email: String,
+    pub reason: SuppressionReason,
+    pub detail: Option<String>,
+}
+
+/// Postmark bounce types that will not recover on retry
+const POSTMARK_HARD_BOUNCES: &[&str] = &["HardBounce", "BadEmailAddress", "ManuallyDeactivated"];
+
+/// Events in a webhook body: one event object or an array of them
+pub fn parse_events(payload: &Value) -> Vec<DeliveryEvent> {
+    match payload {
+        Value::Array(items) => items.iter().flat_map(parse_event).collect(),
+        other => parse_event(other),
+    }
+}
+
+fn parse_event(event: &Value) -> Vec<DeliveryEvent> {
+    if let Some(record_type) = event.get("RecordType").and_then(Value::as_str) {
+        return parse_postmark(record_type, event).into_iter().collect();
+    }
+    if let Some(kind) = event.get("type").and_then(Value::as_str) {
+        return parse_resend(kind, event);
+    }
+    Vec::new()
+}
+
+fn parse_postmark(record_type: &str, event: &Value) -> Option<DeliveryEvent> {
+    let email = event.get("Email").and_then(Value::as_str)?.to_string();
+    let detail = event.get("Description").and_then(Value::as_str).map(str::to_string);
+    match record_type {
+        "Bounce" => {
+            let bounce_type = event.get("Type").and_then(Value::as_str)?;
+            POSTMARK_HARD_BOUNCES.contains(&bounce_type).then(|| DeliveryEvent {
+                email,
+                reason: SuppressionReason::Bounce,
+                detail: detail.or_else(|| Some(bounce_type.to_string())),
+            })
+        }
+        "SpamComplaint" => Some(DeliveryEvent { email, reason: SuppressionReason::Complaint, detail }),
+        _ => None,
+    }
+}
+
+fn parse_resend(kind: &str, event: &Value) -> Vec<DeliveryEvent> {
+    let data = event.get("data");
+    let (reason, detail) = match kind {
+        "email.bounced" => {
+            let bounce = data.and_then(|d| d.get("bounce"));
+            let bounce_type = bounce.and_then(|b| b.get("type")).and_then(Value::as_str);
+            if bounce_type.is_some_and(|t| t.eq_ignore_ascii_case("transient")) {
+                return Vec::new();
+            }
+            let detail = bounce.and_then(|b| b.get("message")).and_then(Value::as_str).map(str::to_string);
+            (SuppressionReason::Bounce, detail)
+        }
+        "email.complained" => (SuppressionReason::Complaint, None),
+        _ => return Vec::new(),
+    };
+
+    let recipients = match data.and_then(|d| d.get("to")) {
+        Some(Value::Array(to)) => to.iter().filter_map(Value::as_str).collect(),
+        Some(Value::String(to)) => vec![to.as_str()],
+        _ => Vec::new(),
+    };
+    recipients
+        .into_iter()
+        .map(|email| DeliveryEvent { email: email.to_string(), reason, detail: detail.clone() })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_postmark_hard_bounce_and_complaint() {
+        let events = parse_events(&json!([
+            { "RecordType": "Bounce", "Type": "HardBounce", "Email": "gone@example.com", "Description": "Unknown user" },
+            { "RecordType": "Bounce", "Type": "SoftBounce", "Email": "full@example.com" },
+            { "RecordType": "SpamComplaint", "Email": "angry@example.com" },
+            { "RecordType": "Delivery", "Email": "ok@example.com" },
+        ]));
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].email, "gone@example.com");
+        assert_eq!(events[0].reason, SuppressionReason::Bounce);
+        assert_eq!(events[0].detail.as_deref(), Some("Unknown user"));
+        assert_eq!(events[1].reason, SuppressionReason::Complaint);
+    }
+
+    #[test]
+    fn test_resend_bounce_skips_transient() {
+        let permanent = parse_events(&json!({
+            "type": "email.bounced",
+            "data": { "to": ["a@example.com", "b@example.com"], "bounce": { "type": "Permanent", "message": "No such user" } }
+        }));
+        assert_eq!(permanent.len(), 2);
+        assert_eq!(permanent[1].email, "b@example.com");
+
+        let transient = parse_events(&json!({
+            "type": "email.bounced",
+            "data": { "to": ["a@example.com"], "bounce": { "type": "Transient" } }
+        }));
+        assert!(transient.is_empty());
+
+        let complaint = parse_events(&json!({ "type": "email.complained", "data": { "to": ["c@example.com"] } }));
+        assert_eq!(complaint[0].reason, SuppressionReason::Complaint);
+        assert!(parse_events(&json!({ "type": "email.delivered", "data": { "to": ["c@example.com"] } })).is_empty());
+    }
+}
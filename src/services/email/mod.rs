@@ -0,0 +1,189 @@
+//! Outbound email. Application code hands messages to the outbox
+//! ([`queued_email_sender`]); the outbox worker delivers them through the
+//! configured transports with retries, failing over from SMTP to the mail
+//! API, and skips addresses on the suppression list. Mail to an account
+//! with an anti-phishing code carries the code at the top.
+
+pub mod events;
+pub mod outbox;
+pub mod smtp;
+
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::Serialize;
+use std::sync::Arc;
+
+pub use outbox::{queued_email_sender, EmailOutboxWorker, OutboxEmailSender};
+pub use smtp::SmtpEmailSender;
+
+/// Outgoing email
+#[derive(Debug, Clone, Serialize)]
+pub struct EmailMessage {
+    pub to: String,
+    pub subject: String,
+    pub text: String,
+}
+
+/// Body with the recipient's anti-phishing code above it, so they can tell
+/// our mail from imitations
+pub fn with_anti_phishing_code(text: &str, code: Option<&str>) -> String {
+    match code {
+        Some(code) => format!("Your anti-phishing code: {}\n\n{}", code, text),
+        None => text.to_string(),
+    }
+}
+
+#[async_trait]
+pub trait EmailSender: Send + Sync {
+    async fn send(&self, message: &EmailMessage) -> Result<(), String>;
+}
+
+/// Sends mail through an HTTP mail API (Postmark/Resend style JSON endpoint)
+pub struct HttpEmailSender {
+    client: Client,
+    api_url: String,
+    api_key: String,
+    from: String,
+}
+
+impl HttpEmailSender {
+    pub fn new(api_url: String, api_key: String, from: String) -> Self {
+        Self {
+            client: Client::new(),
+            api_url,
+            api_key,
+            from,
+        }
+    }
+}
+
+#[async_trait]
+impl EmailSender for HttpEmailSender {
+    async fn send(&self, message: &EmailMessage) -> Result<(), String> {
+        let body = serde_json::json!({
+            "from": self.from,
+            "to": message.to,
+            "subject": message.subject,
+            "text": message.text,
+        });
+
+        let response = self
+            .client
+            .post(&self.api_url)
+            .bearer_auth(&self.api_key)
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        if !response.status().is_success() {
+            return Err(format!("Mail API returned status: {}", response.status()));
+        }
+
+        Ok(())
+    }
+}
+
+/// Development sender: logs the recipient and subject, never the body
+pub struct LogEmailSender;
+
+#[async_trait]
+impl EmailSender for LogEmailSender {
+    async fn send(&self, message: &EmailMessage) -> Result<(), String> {
+        tracing::info!("Email to {}: {}", message.to, message.subject);
+        Ok(())
+    }
+}
+
+/// Tries each transport in order until one accepts the message
+pub struct FailoverEmailSender {
+    senders: Vec<(&'static str, Arc<dyn EmailSender>)>,
+}
+
+impl FailoverEmailSender {
+    pub fn new(senders: Vec<(&'static str, Arc<dyn EmailSender>)>) -> Self {
+        Self { senders }
+    }
+}
+
+#[async_trait]
+impl EmailSender for FailoverEmailSender {
+    async fn send(&self, message: &EmailMessage) -> Result<(), String> {
+        let mut errors = Vec::new();
+        for (name, sender) in &self.senders {
+            match sender.send(message).await {
+                Ok(()) => return Ok(()),
+                Err(e) => {
+                    tracing::warn!("Email transport {} failed, trying the next: {}", name, e);
+                    errors.push(format!("{}: {}", name, e));
+                }
+            }
+        }
+        Err(errors.join("; "))
+    }
+}
+
+/// Build the delivery transports: SMTP when SMTP_HOST is set, then the mail
+/// API when EMAIL_API_URL is set, failing over in that order. Falls back to
+/// logging when neither is configured. Application code should queue
+/// through [`queued_email_sender`] instead of sending directly.
+pub fn email_sender_from_env() -> Arc<dyn EmailSender> {
+    let from = std::env::var("EMAIL_FROM").unwrap_or_else(|_| "no-reply@localhost".to_string());
+    let mut senders: Vec<(&'static str, Arc<dyn EmailSender>)> = Vec::new();
+
+    match SmtpEmailSender::from_env(&from) {
+        Ok(Some(smtp)) => senders.push(("smtp", Arc::new(smtp))),
+        Ok(None) => {}
+        Err(e) => tracing::warn!("SMTP transport disabled: {}", e),
+    }
+    if let Ok(api_url) = std::env::var("EMAIL_API_URL") {
+        let api_key = std::env::var("EMAIL_API_KEY").unwrap_or_default();
+        senders.push(("api", Arc::new(HttpEmailSender::new(api_url, api_key, from))));
+    }
+
+    match senders.len() {
+        0 => Arc::new(LogEmailSender),
+        1 => senders.remove(0).1,
+        _ => Arc::new(FailoverEmailSender::new(senders)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct CountingSender {
+        calls: AtomicUsize,
+        fail: bool,
+    }
+
+    #[async_trait]
+    impl EmailSender for CountingSender {
+        async fn send(&self, _message: &EmailMessage) -> Result<(), String> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            if self.fail { Err("down".to_string()) } else { Ok(()) }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failover_tries_next_transport() {
+        let smtp = Arc::new(CountingSender { calls: AtomicUsize::new(0), fail: true });
+        let api = Arc::new(CountingSender { calls: AtomicUsize::new(0), fail: false });
+        let sender = FailoverEmailSender::new(vec![("smtp", smtp.clone()), ("api", api.clone())]);
+        let message = EmailMessage { to: "a@example.com".into(), subject: "s".into(), text: "t".into() };
+
+        assert!(sender.send(&message).await.is_ok());
+        assert_eq!(smtp.calls.load(Ordering::SeqCst), 1);
+        assert_eq!(api.calls.load(Ordering::SeqCst), 1);
+
+        let all_down = FailoverEmailSender::new(vec![("smtp", smtp.clone())]);
+        assert_eq!(all_down.send(&message).await.unwrap_err(), "smtp: down");
+    }
+
+    #[test]
+    fn test_anti_phishing_code_leads_the_body() {
+        assert_eq!(with_anti_phishing_code("Swap done", Some("BLUE42")), "Your anti-phishing code: BLUE42\n\nSwap done");
+        assert_eq!(with_anti_phishing_code("Swap done", None), "Swap done");
+    }
+}
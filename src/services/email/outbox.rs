@@ -0,0 +1,152 @@
+use async_trait::async_trait;
+use sqlx::{MySql, Pool};
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::{EmailMessage, EmailSender};
+use crate::modules::email::crud::{EmailCrud, EmailError};
+
+/// Messages claimed per pass
+const BATCH_SIZE: i64 = 20;
+/// Delivery attempts before a message is given up on
+const MAX_ATTEMPTS: u32 = 8;
+/// A sending message untouched this long belongs to a dead worker
+const STALE_AFTER_MINUTES: i64 = 10;
+const BASE_RETRY_SECS: i64 = 60;
+const MAX_RETRY_SECS: i64 = 3600;
+
+/// Delay before retrying a message after its `attempts`th failed attempt:
+/// one minute, doubling, capped at an hour
+pub fn retry_delay(attempts: u32) -> i64 {
+    let exponent = attempts.saturating_sub(1).min(16);
+    (BASE_RETRY_SECS << exponent).min(MAX_RETRY_SECS)
+}
+
+/// Sender for application code: queues the message in `email_outbox` for
+/// [`EmailOutboxWorker`]. Suppressed recipients are refused.
+pub struct OutboxEmailSender {
+    db: Pool<MySql>,
+}
+
+impl OutboxEmailSender {
+    pub fn new(db: Pool<MySql>) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl EmailSender for OutboxEmailSender {
+    async fn send(&self, message: &EmailMessage) -> Result<(), String> {
+        EmailCrud::new(self.db.clone())
+            .enqueue(message)
+            .await
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+}
+
+pub fn queued_email_sender(db: Pool<MySql>) -> Arc<dyn EmailSender> {
+    Arc::new(OutboxEmailSender::new(db))
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct EmailOutboxReport {
+    pub sent: usize,
+    pub retried: usize,
+    pub failed: usize,
+    pub suppressed: usize,
+}
+
+/// Delivers queued email through the configured transports
+pub struct EmailOutboxWorker {
+    db: Pool<MySql>,
+    transport: Arc<dyn EmailSender>,
+    interval: Duration,
+}
+
+impl EmailOutboxWorker {
+    pub fn new(db: Pool<MySql>, transport: Arc<dyn EmailSender>) -> Self {
+        Self {
+            db,
+            transport,
+            interval: Duration::from_secs(5),
+        }
+    }
+
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Start the background delivery loop
+    pub async fn run(&self) {
+        let mut interval = tokio::time::interval(self.interval);
+
+        loop {
+            interval.tick().await;
+
+            match self.process().await {
+                Ok(report) if report != EmailOutboxReport::default() => {
+                    tracing::info!("Email outbox: {:?}", report);
+                }
+                Ok(_) => {}
+                Err(e) => tracing::error!("Email outbox pass failed: {}", e),
+            }
+        }
+    }
+
+    /// Run one pass over the due messages
+    pub async fn process(&self) -> Result<EmailOutboxReport, EmailError> {
+        let crud = EmailCrud::new(self.db.clone());
+        let mut report = EmailOutboxReport::default();
+
+        crud.requeue_stale(STALE_AFTER_MINUTES).await?;
+
+        for email in crud.claim_due(BATCH_SIZE).await? {
+            // A bounce may have arrived since the message was queued
+            if crud.is_suppressed(&email.recipient).await? {
+                crud.mark_suppressed(&email.id).await?;
+                report.suppressed += 1;
+                continue;
+            }
+
+            let message = EmailMessage {
+                to: email.recipient.clone(),
+                subject: email.subject.clone(),
+                text: email.body.clone(),
+            };
+            match self.transport.send(&message).await {
+                Ok(()) => {
+                    crud.mark_sent(&email.id).await?;
+                    report.sent += 1;
+                }
+                Err(e) if email.attempts >= MAX_ATTEMPTS => {
+                    tracing::warn!("Email {} failed after {} attempts: {}", email.id, email.attempts, e);
+                    crud.fail(&email.id, &e).await?;
+                    report.failed += 1;
+                }
+                Err(e) => {
+                    tracing::debug!("Email {} attempt {} failed: {}", email.id, email.attempts, e);
+                    crud.retry_later(&email.id, &e, retry_delay(email.attempts)).await?;
+                    report.retried += 1;
+                }
+            }
+        }
+
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_delay_backs_off_to_an_hour() {
+        assert_eq!(retry_delay(1), 60);
+        assert_eq!(retry_delay(2), 120);
+        assert_eq!(retry_delay(4), 480);
+        assert_eq!(retry_delay(7), 3600);
+        assert_eq!(retry_delay(40), 3600);
+    }
+}
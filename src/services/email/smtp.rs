@@ -0,0 +1,58 @@
+use async_trait::async_trait;
+use lettre::message::header::ContentType;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+
+use super::{EmailMessage, EmailSender};
+
+/// Sends mail through an SMTP relay
+pub struct SmtpEmailSender {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: String,
+}
+
+impl SmtpEmailSender {
+    /// From SMTP_HOST, SMTP_PORT (default 587), SMTP_USERNAME,
+    /// SMTP_PASSWORD and SMTP_TLS (`starttls`, `tls` or `none`, default
+    /// `starttls`). `None` when SMTP_HOST is unset.
+    pub fn from_env(from: &str) -> Result<Option<Self>, String> {
+        let Some(host) = std::env::var("SMTP_HOST").ok().filter(|h| !h.trim().is_empty()) else {
+            return Ok(None);
+        };
+        let host = host.trim();
+        let port = match std::env::var("SMTP_PORT") {
+            Ok(v) if !v.trim().is_empty() => v.trim().parse::<u16>().map_err(|_| format!("Invalid SMTP_PORT: {}", v))?,
+            _ => 587,
+        };
+        let tls = std::env::var("SMTP_TLS").unwrap_or_else(|_| "starttls".to_string());
+
+        let builder = match tls.trim().to_ascii_lowercase().as_str() {
+            "starttls" => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host).map_err(|e| e.to_string())?,
+            "tls" => AsyncSmtpTransport::<Tokio1Executor>::relay(host).map_err(|e| e.to_string())?,
+            "none" => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(host),
+            other => return Err(format!("Invalid SMTP_TLS: {}", other)),
+        };
+        let builder = match (std::env::var("SMTP_USERNAME"), std::env::var("SMTP_PASSWORD")) {
+            (Ok(user), Ok(password)) => builder.credentials(Credentials::new(user, password)),
+            _ => builder,
+        };
+
+        Ok(Some(Self { transport: builder.port(port).build(), from: from.to_string() }))
+    }
+}
+
+#[async_trait]
+impl EmailSender for SmtpEmailSender {
+    async fn send(&self, message: &EmailMessage) -> Result<(), String> {
+        let email = Message::builder()
+            .from(self.from.parse().map_err(|e| format!("Invalid EMAIL_FROM: {}", e))?)
+            .to(message.to.parse().map_err(|e| format!("Invalid recipient: {}", e))?)
+            .subject(&message.subject)
+            .header(ContentType::TEXT_PLAIN)
+            .body(message.text.clone())
+            .map_err(|e| e.to_string())?;
+
+        self.transport.send(email).await.map_err(|e| e.to_string())?;
+        Ok(())
+    }
+}
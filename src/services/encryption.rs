@@ -0,0 +1,289 @@
+use aes_gcm::{
+    aead::{Aead, KeyInit},
+    Aes256Gcm, Nonce,
+};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use sha2::Sha256;
+use std::collections::BTreeMap;
+
+type HmacSha256 = Hmac<Sha256>;
+
+const NONCE_LEN: usize = 12;
+const PREFIX: &str = "enc:v";
+/// Ciphertext written before key versioning has no prefix and was
+/// produced with what is now version 1
+const LEGACY_VERSION: u32 = 1;
+
+/// AES-256-GCM cipher for sensitive values stored in the database
+/// (gift card codes, emails, recipient addresses, provider secrets, etc.)
+///
+/// Ciphertext layout: `enc:v{version}:` + base64(nonce || ciphertext || tag).
+/// Several key versions can be loaded at once so old rows stay readable
+/// while new writes use the active version.
+#[derive(Clone)]
+pub struct FieldCipher {
+    keys: BTreeMap<u32, Aes256Gcm>,
+    active: u32,
+    index_key: Vec<u8>,
+}
+
+impl FieldCipher {
+    /// Build a cipher from a single 32-byte key (version 1)
+    pub fn new(key: &[u8]) -> Result<Self, String> {
+        Self::with_keys(&[(LEGACY_VERSION, key)], LEGACY_VERSION)
+    }
+
+    /// Build a keyring; `active` is the version used for new ciphertext
+    pub fn with_keys(keys: &[(u32, &[u8])], active: u32) -> Result<Self, String> {
+        let mut ring = BTreeMap::new();
+        for (version, key) in keys {
+            if key.len() != 32 {
+                return Err(format!("Encryption key v{} must be 32 bytes, got {}", version, key.len()));
+            }
+            let cipher = Aes256Gcm::new_from_slice(key).map_err(|e| e.to_string())?;
+            ring.insert(*version, cipher);
+        }
+
+        if !ring.contains_key(&active) {
+            return Err(format!("Active encryption key v{} is not loaded", active));
+        }
+
+        // The blind index must survive key rotation, so by default it is
+        // derived from the oldest key rather than the active one
+        let (_, oldest) = keys
+            .iter()
+            .min_by_key(|(version, _)| *version)
+            .ok_or_else(|| "At least one encryption key is required".to_string())?;
+        let index_key = derive_index_key(oldest);
+
+        Ok(Self { keys: ring, active, index_key })
+    }
+
+    /// Use a dedicated key for blind indexes
+    pub fn with_index_key(mut self, key: &[u8]) -> Self {
+        self.index_key = key.to_vec();
+        self
+    }
+
+    /// Load keys from the environment:
+    ///
+    /// - DATA_ENCRYPTION_KEYS: `version:hex` pairs, e.g. `1:ab..,2:cd..`
+    /// - DATA_ENCRYPTION_KEY_VERSION: active version (defaults to the highest)
+    /// - DATA_ENCRYPTION_KEY: single 64-hex key, loaded as version 1 when
+    ///   DATA_ENCRYPTION_KEYS is not set
+    /// - DATA_BLIND_INDEX_KEY: optional hex key for blind indexes
+    pub fn from_env() -> Result<Self, String> {
+        let cipher = match std::env::var("DATA_ENCRYPTION_KEYS") {
+            Ok(spec) if !spec.trim().is_empty() => {
+                let keys = parse_keyring(&spec)?;
+                let active = match std::env::var("DATA_ENCRYPTION_KEY_VERSION") {
+                    Ok(v) => v.trim().parse::<u32>()
+                        .map_err(|e| format!("Invalid DATA_ENCRYPTION_KEY_VERSION: {}", e))?,
+                    Err(_) => keys.iter().map(|(v, _)| *v).max().unwrap_or(LEGACY_VERSION),
+                };
+                let refs: Vec<(u32, &[u8])> = keys.iter().map(|(v, k)| (*v, k.as_slice())).collect();
+                Self::with_keys(&refs, active)?
+            }
+            _ => {
+                let hex_key = std::env::var("DATA_ENCRYPTION_KEY")
+                    .map_err(|_| "DATA_ENCRYPTION_KEY must be set".to_string())?;
+                let key = hex::decode(hex_key.trim()).map_err(|e| format!("Invalid DATA_ENCRYPTION_KEY: {}", e))?;
+                Self::new(&key)?
+            }
+        };
+
+        match std::env::var("DATA_BLIND_INDEX_KEY") {
+            Ok(hex_key) if !hex_key.trim().is_empty() => {
+                let key = hex::decode(hex_key.trim()).map_err(|e| format!("Invalid DATA_BLIND_INDEX_KEY: {}", e))?;
+                Ok(cipher.with_index_key(&key))
+            }
+            _ => Ok(cipher),
+        }
+    }
+
+    pub fn active_version(&self) -> u32 {
+        self.active
+    }
+
+    pub fn encrypt(&self, plaintext: &str) -> Result<String, String> {
+        let cipher = &self.keys[&self.active];
+
+        let mut nonce_bytes = [0u8; NONCE_LEN];
+        rand::rng().fill_bytes(&mut nonce_bytes);
+        let nonce = Nonce::from(nonce_bytes);
+
+        let ciphertext = cipher
+            .encrypt(&nonce, plaintext.as_bytes())
+            .map_err(|e| format!("Encryption failed: {}", e))?;
+
+        let mut out = Vec::with_capacity(NONCE_LEN + ciphertext.len());
+        out.extend_from_slice(&nonce_bytes);
+        out.extend_from_slice(&ciphertext);
+        Ok(format!("{}{}:{}", PREFIX, self.active, STANDARD.encode(out)))
+    }
+
+    pub fn decrypt(&self, encoded: &str) -> Result<String, String> {
+        let (version, body) = split_versioned(encoded).unwrap_or((LEGACY_VERSION, encoded));
+        let cipher = self
+            .keys
+            .get(&version)
+            .ok_or_else(|| format!("Encryption key v{} is not loaded", version))?;
+
+        let data = STANDARD.decode(body).map_err(|e| format!("Invalid ciphertext encoding: {}", e))?;
+        let (nonce_bytes, ciphertext) = match data.split_first_chunk::<NONCE_LEN>() {
+            Some((nonce, rest)) if !rest.is_empty() => (nonce, rest),
+            _ => return Err("Ciphertext too short".to_string()),
+        };
+
+        let plaintext = cipher
+            .decrypt(&Nonce::from(*nonce_bytes), ciphertext)
+            .map_err(|_| "Decryption failed".to_string())?;
+
+        String::from_utf8(plaintext).map_err(|e| e.to_string())
+    }
+
+    /// Key version of a prefixed ciphertext, `None` for plaintext or
+    /// legacy unprefixed values
+    pub fn key_version(value: &str) -> Option<u32> {
+        split_versioned(value).map(|(version, _)| version)
+    }
+
+    pub fn is_encrypted(value: &str) -> bool {
+        Self::key_version(value).is_some()
+    }
+
+    /// True when `value` is not ciphertext under the active key
+    pub fn needs_rotation(&self, value: &str) -> bool {
+        Self::key_version(value) != Some(self.active)
+    }
+
+    /// Decrypt with whichever key produced `encoded` and encrypt again
+    /// under the active key
+    pub fn reencrypt(&self, encoded: &str) -> Result<String, String> {
+        self.encrypt(&self.decrypt(encoded)?)
+    }
+
+    /// Deterministic keyed hash (hex HMAC-SHA256) so encrypted columns can
+    /// still be looked up by equality
+    pub fn blind_index(&self, value: &str) -> String {
+        let mut mac = <HmacSha256 as Mac>::new_from_slice(&self.index_key).expect("HMAC accepts any key length");
+        mac.update(value.as_bytes());
+        hex::encode(mac.finalize().into_bytes())
+    }
+}
+
+fn derive_index_key(key: &[u8]) -> Vec<u8> {
+    let mut mac = <HmacSha256 as Mac>::new_from_slice(key).expect("HMAC accepts any key length");
+    mac.update(b"blind-index");
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// Split `enc:v{n}:{body}` into its version and body
+fn split_versioned(value: &str) -> Option<(u32, &str)> {
+    let rest = value.strip_prefix(PREFIX)?;
+    let (version, body) = rest.split_once(':')?;
+    Some((version.parse().ok()?, body))
+}
+
+fn parse_keyring(spec: &str) -> Result<Vec<(u32, Vec<u8>)>, String> {
+    spec.split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let (version, hex_key) = entry
+                .split_once(':')
+                .ok_or_else(|| format!("Invalid DATA_ENCRYPTION_KEYS entry '{}', expected version:hex", entry))?;
+            let version = version.trim().parse::<u32>()
+                .map_err(|e| format!("Invalid key version '{}': {}", version, e))?;
+            let key = hex::decode(hex_key.trim())
+                .map_err(|e| format!("Invalid key for v{}: {}", version, e))?;
+            Ok((version, key))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encrypt_decrypt_roundtrip() {
+        let cipher = FieldCipher::new(&[7u8; 32]).unwrap();
+        let encrypted = cipher.encrypt("GIFT-1234-ABCD").unwrap();
+        assert_ne!(encrypted, "GIFT-1234-ABCD");
+        assert_eq!(cipher.decrypt(&encrypted).unwrap(), "GIFT-1234-ABCD");
+    }
+
+    #[test]
+    fn test_nonce_is_random() {
+        let cipher = FieldCipher::new(&[7u8; 32]).unwrap();
+        assert_ne!(cipher.encrypt("same").unwrap(), cipher.encrypt("same").unwrap());
+    }
+
+    #[test]
+    fn test_wrong_key_fails() {
+        let a = FieldCipher::new(&[1u8; 32]).unwrap();
+        let b = FieldCipher::new(&[2u8; 32]).unwrap();
+        let encrypted = a.encrypt("secret").unwrap();
+        assert!(b.decrypt(&encrypted).is_err());
+    }
+
+    #[test]
+    fn test_rejects_short_key() {
+        assert!(FieldCipher::new(&[0u8; 16]).is_err());
+    }
+
+    #[test]
+    fn test_decrypts_legacy_unprefixed_ciphertext() {
+        let cipher = FieldCipher::new(&[7u8; 32]).unwrap();
+        let encrypted = cipher.encrypt("legacy").unwrap();
+        let (_, body) = split_versioned(&encrypted).unwrap();
+        assert_eq!(cipher.decrypt(body).unwrap(), "legacy");
+    }
+
+    #[test]
+    fn test_rotation_to_new_key_version() {
+        let old = FieldCipher::new(&[1u8; 32]).unwrap();
+        let encrypted = old.encrypt("user@example.com").unwrap();
+        assert_eq!(FieldCipher::key_version(&encrypted), Some(1));
+
+        let ring = FieldCipher::with_keys(&[(1, &[1u8; 32]), (2, &[2u8; 32])], 2).unwrap();
+        assert!(ring.needs_rotation(&encrypted));
+        assert_eq!(ring.decrypt(&encrypted).unwrap(), "user@example.com");
+
+        let rotated = ring.reencrypt(&encrypted).unwrap();
+        assert_eq!(FieldCipher::key_version(&rotated), Some(2));
+        assert!(!ring.needs_rotation(&rotated));
+        assert_eq!(ring.decrypt(&rotated).unwrap(), "user@example.com");
+    }
+
+    #[test]
+    fn test_active_version_must_be_loaded() {
+        assert!(FieldCipher::with_keys(&[(1, &[1u8; 32])], 2).is_err());
+    }
+
+    #[test]
+    fn test_blind_index_is_stable_across_rotation() {
+        let old = FieldCipher::new(&[1u8; 32]).unwrap();
+        let ring = FieldCipher::with_keys(&[(1, &[1u8; 32]), (2, &[2u8; 32])], 2).unwrap();
+        assert_eq!(old.blind_index("a@b.com"), ring.blind_index("a@b.com"));
+        assert_ne!(ring.blind_index("a@b.com"), ring.blind_index("c@d.com"));
+    }
+
+    #[test]
+    fn test_plaintext_is_not_encrypted() {
+        assert!(!FieldCipher::is_encrypted("0x742d35Cc6634C0532925a3b844Bc454e4438f44e"));
+        assert!(!FieldCipher::is_encrypted("user@example.com"));
+    }
+
+    #[test]
+    fn test_parse_keyring() {
+        let spec = format!("1:{}, 2:{}", "11".repeat(32), "22".repeat(32));
+        let keys = parse_keyring(&spec).unwrap();
+        assert_eq!(keys.len(), 2);
+        assert_eq!(keys[1].0, 2);
+        assert!(parse_keyring("nonsense").is_err());
+    }
+}
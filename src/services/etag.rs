@@ -0,0 +1,104 @@
+//! Conditional GET support for cacheable JSON. The ETag is a hash of the
+//! exact bytes sent, so it changes exactly when the payload does, whether
+//! that is a cache refresh, a catalog sync or a different `fields` selection.
+
+use axum::{
+    body::Body,
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::Response,
+};
+use sha2::{Digest, Sha256};
+
+/// Strong entity tag for a response body (first 128 bits of its SHA-256)
+pub fn entity_tag(body: &[u8]) -> String {
+    let digest = Sha256::digest(body);
+    format!("\"{}\"", hex::encode(&digest[..16]))
+}
+
+/// Whether an `If-None-Match` header matches `etag`. Comparison is weak, as
+/// RFC 9110 requires for If-None-Match, so `W/"x"` matches `"x"`.
+pub fn if_none_match(headers: &HeaderMap, etag: &str) -> bool {
+    let wanted = etag.trim_start_matches("W/");
+    headers
+        .get_all(header::IF_NONE_MATCH)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .any(|candidate| candidate == "*" || candidate.trim_start_matches("W/") == wanted)
+}
+
+/// 200 with the JSON body, or an empty 304 when the client already has it.
+/// Both carry the ETag and `cache_control`.
+pub fn conditional_json(headers: &HeaderMap, body: String, cache_control: &'static str) -> Response {
+    let etag = entity_tag(body.as_bytes());
+
+    let mut response = if if_none_match(headers, &etag) {
+        let mut response = Response::new(Body::empty());
+        *response.status_mut() = StatusCode::NOT_MODIFIED;
+        response
+    } else {
+        let mut response = Response::new(Body::from(body));
+        response
+            .headers_mut()
+            .insert(header::CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        response
+    };
+    response
+        .headers_mut()
+        .insert(header::CACHE_CONTROL, HeaderValue::from_static(cache_control));
+    if let Ok(value) = HeaderValue::from_str(&etag) {
+        response.headers_mut().insert(header::ETAG, value);
+    }
+
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn with_if_none_match(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::IF_NONE_MATCH, HeaderValue::from_str(value).unwrap());
+        headers
+    }
+
+    #[test]
+    fn test_entity_tag_tracks_content() {
+        let a = entity_tag(b"[{\"ticker\":\"btc\"}]");
+        assert_eq!(a, entity_tag(b"[{\"ticker\":\"btc\"}]"));
+        assert_ne!(a, entity_tag(b"[{\"ticker\":\"eth\"}]"));
+        assert!(a.starts_with('"') && a.ends_with('"'));
+        assert_eq!(a.len(), 34);
+    }
+
+    #[test]
+    fn test_if_none_match() {
+        let etag = entity_tag(b"[]");
+
+        assert!(!if_none_match(&HeaderMap::new(), &etag));
+        assert!(if_none_match(&with_if_none_match(&etag), &etag));
+        assert!(if_none_match(&with_if_none_match(&format!("W/{}", etag)), &etag));
+        assert!(if_none_match(&with_if_none_match(&format!("\"old\", {}", etag)), &etag));
+        assert!(if_none_match(&with_if_none_match("*"), &etag));
+        assert!(!if_none_match(&with_if_none_match("\"old\""), &etag));
+    }
+
+    #[test]
+    fn test_conditional_json() {
+        let body = "[1,2,3]".to_string();
+        let etag = entity_tag(body.as_bytes());
+
+        let fresh = conditional_json(&HeaderMap::new(), body.clone(), "public, max-age=60");
+        assert_eq!(fresh.status(), StatusCode::OK);
+        assert_eq!(fresh.headers()[header::ETAG], etag.as_str());
+        assert_eq!(fresh.headers()[header::CACHE_CONTROL], "public, max-age=60");
+        assert_eq!(fresh.headers()[header::CONTENT_TYPE], "application/json");
+
+        let cached = conditional_json(&with_if_none_match(&etag), body, "public, max-age=60");
+        assert_eq!(cached.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(cached.headers()[header::ETAG], etag.as_str());
+        assert!(cached.headers().get(header::CONTENT_TYPE).is_none());
+    }
+}
@@ -0,0 +1,150 @@
+//! Swap lifecycle events. Code that changes a swap publishes what happened;
+//! webhooks, metrics and customer notifications subscribe instead of being
+//! called from every place a swap moves.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::sync::OnceLock;
+use uuid::Uuid;
+
+use super::{EventBus, DEFAULT_CAPACITY};
+use crate::modules::swap::schema::{SwapStatus, SwapType};
+use crate::services::amount::Decimal;
+
+static DOMAIN_EVENTS: OnceLock<EventBus<DomainEnvelope>> = OnceLock::new();
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DomainEvent {
+    /// A provider order was placed and the swap persisted
+    SwapCreated {
+        swap_id: String,
+        user_id: Option<String>,
+        provider: String,
+        from: String,
+        network_from: String,
+        to: String,
+        network_to: String,
+        #[serde(with = "rust_decimal::serde::float")]
+        amount: Decimal,
+        swap_type: SwapType,
+        /// Trade id of the rates quote the swap was created from, if any
+        trade_id: Option<String>,
+    },
+    /// A status write committed through the swap state machine
+    SwapStatusChanged {
+        swap_id: String,
+        from: SwapStatus,
+        to: SwapStatus,
+    },
+    /// The deposit was returned to the user
+    RefundIssued {
+        swap_id: String,
+    },
+    /// Our payout of the received funds was broadcast
+    PayoutSent {
+        swap_id: String,
+        chain: String,
+        currency: String,
+        #[serde(with = "rust_decimal::serde::float")]
+        amount: Decimal,
+        reference: String,
+    },
+    PayoutFailed {
+        swap_id: String,
+        chain: String,
+        currency: String,
+        error: String,
+    },
+    /// The payout transaction is deep enough on chain not to be reorged out
+    PayoutFinalized {
+        swap_id: String,
+        chain: String,
+        tx_hash: String,
+        confirmations: u64,
+    },
+}
+
+impl DomainEvent {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            DomainEvent::SwapCreated { .. } => "swap_created",
+            DomainEvent::SwapStatusChanged { .. } => "swap_status_changed",
+            DomainEvent::RefundIssued { .. } => "refund_issued",
+            DomainEvent::PayoutSent { .. } => "payout_sent",
+            DomainEvent::PayoutFailed { .. } => "payout_failed",
+            DomainEvent::PayoutFinalized { .. } => "payout_finalized",
+        }
+    }
+
+    pub fn swap_id(&self) -> &str {
+        match self {
+            DomainEvent::SwapCreated { swap_id, .. }
+            | DomainEvent::SwapStatusChanged { swap_id, .. }
+            | DomainEvent::RefundIssued { swap_id }
+            | DomainEvent::PayoutSent { swap_id, .. }
+            | DomainEvent::PayoutFailed { swap_id, .. }
+            | DomainEvent::PayoutFinalized { swap_id, .. } => swap_id,
+        }
+    }
+
+    /// Publish on the process-wide bus
+    pub fn publish(self) {
+        domain_events().publish(DomainEnvelope::new(self));
+    }
+}
+
+/// An event with the identity subscribers deduplicate on
+#[derive(Debug, Clone, Serialize)]
+pub struct DomainEnvelope {
+    pub id: Uuid,
+    pub at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub event: DomainEvent,
+}
+
+impl DomainEnvelope {
+    pub fn new(event: DomainEvent) -> Self {
+        Self { id: Uuid::new_v4(), at: Utc::now(), event }
+    }
+}
+
+/// Process-wide bus of swap lifecycle events
+pub fn domain_events() -> &'static EventBus<DomainEnvelope> {
+    DOMAIN_EVENTS.get_or_init(|| EventBus::new(DEFAULT_CAPACITY))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_envelope_serializes_flat_with_type_tag() {
+        let envelope = DomainEnvelope::new(DomainEvent::SwapStatusChanged {
+            swap_id: "swap-1".to_string(),
+            from: SwapStatus::Sending,
+            to: SwapStatus::Completed,
+        });
+
+        let json = serde_json::to_value(&envelope).unwrap();
+        assert_eq!(json["type"], "swap_status_changed");
+        assert_eq!(json["swap_id"], "swap-1");
+        assert_eq!(json["from"], "sending");
+        assert_eq!(json["to"], "completed");
+        assert!(json["id"].is_string());
+        assert_eq!(envelope.event.kind(), "swap_status_changed");
+    }
+
+    #[test]
+    fn test_swap_id_for_every_variant() {
+        let event = DomainEvent::PayoutSent {
+            swap_id: "swap-2".to_string(),
+            chain: "ethereum".to_string(),
+            currency: "eth".to_string(),
+            amount: Decimal::new(5, 1),
+            reference: "0xabc".to_string(),
+        };
+        assert_eq!(event.swap_id(), "swap-2");
+        assert_eq!(DomainEvent::RefundIssued { swap_id: "swap-3".to_string() }.swap_id(), "swap-3");
+    }
+}
@@ -0,0 +1,82 @@
+//! In-process publish/subscribe. Publishers never wait on subscribers: events
+//! go to a bounded broadcast channel, and a subscriber that falls behind skips
+//! what it missed (it is told how many) instead of slowing the publisher.
+
+pub mod domain;
+pub mod ops;
+pub mod stream;
+pub mod subscribers;
+
+use tokio::sync::broadcast;
+
+pub use domain::{domain_events, DomainEnvelope, DomainEvent};
+pub use ops::{ops_events, OpsEvent, OpsNotification};
+pub use stream::{EventStream, EventStreamConfig, StreamSubscriber};
+pub use subscribers::{
+    spawn_subscriber, AddressReputationSubscriber, DomainSubscriber, MetricsSubscriber, NotificationSubscriber,
+    RevenueSubscriber, WebhookSubscriber,
+};
+
+/// Events buffered per subscriber before the slowest one starts losing them
+pub const DEFAULT_CAPACITY: usize = 1024;
+
+pub struct EventBus<E> {
+    sender: broadcast::Sender<E>,
+}
+
+impl<E: Clone + Send + 'static> EventBus<E> {
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity.max(1));
+        Self { sender }
+    }
+
+    /// Deliver to current subscribers; a bus nobody listens to drops the event
+    pub fn publish(&self, event: E) {
+        let _ = self.sender.send(event);
+    }
+
+    /// Events published from now on
+    pub fn subscribe(&self) -> broadcast::Receiver<E> {
+        self.sender.subscribe()
+    }
+
+    pub fn subscriber_count(&self) -> usize {
+        self.sender.receiver_count()
+    }
+}
+
+impl<E: Clone + Send + 'static> Default for EventBus<E> {
+    fn default() -> Self {
+        Self::new(DEFAULT_CAPACITY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_publish_reaches_every_subscriber() {
+        let bus: EventBus<u32> = EventBus::new(8);
+        bus.publish(0); // nobody listening yet
+
+        let mut a = bus.subscribe();
+        let mut b = bus.subscribe();
+        bus.publish(1);
+
+        assert_eq!(a.recv().await.unwrap(), 1);
+        assert_eq!(b.recv().await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_slow_subscriber_skips_missed_events() {
+        let bus: EventBus<u32> = EventBus::new(2);
+        let mut rx = bus.subscribe();
+        for i in 0..5 {
+            bus.publish(i);
+        }
+
+        assert!(matches!(rx.recv().await, Err(broadcast::error::RecvError::Lagged(3))));
+        assert_eq!(rx.recv().await.unwrap(), 3);
+    }
+}
@@ -0,0 +1,155 @@
+//! Operational events for the admin dashboard: things an operator should look
+//! at now rather than discover by polling the admin endpoints.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::sync::OnceLock;
+
+use super::{EventBus, DEFAULT_CAPACITY};
+use crate::services::amount::Decimal;
+
+static OPS_EVENTS: OnceLock<EventBus<OpsNotification>> = OnceLock::new();
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum OpsEvent {
+    /// A payout transaction could not be sent
+    PayoutFailed {
+        swap_id: String,
+        chain: String,
+        currency: String,
+        error: String,
+    },
+    /// Calls to an upstream stopped after repeated failures
+    CircuitBreakerOpened {
+        /// `rpc` or `webhook`
+        component: String,
+        /// Chain for RPC endpoints, webhook id for webhooks
+        target: String,
+        /// Endpoint host, never the full URL (it may carry an API key)
+        endpoint: Option<String>,
+    },
+    /// A hot wallet lacks the native coin to pay network fees
+    GasTankLow {
+        chain: String,
+        detail: String,
+    },
+    /// Nightly reconciliation found a swap disagreeing with the provider
+    ReconciliationDiscrepancy {
+        run_id: String,
+        swap_id: String,
+        kind: String,
+    },
+    /// A swap has stayed in one status past its SLA
+    SwapStuck {
+        swap_id: String,
+        status: String,
+        minutes_in_status: i64,
+        sla_minutes: u32,
+    },
+    /// An admin signed a message with the hot wallet's key
+    HotWalletMessageSigned {
+        admin_id: String,
+        chain: String,
+        address: String,
+        purpose: String,
+    },
+    /// A backup could not be taken, uploaded or verified
+    BackupFailed {
+        run_id: String,
+        error: String,
+    },
+    /// The wallet audit found funds at an allocated address that nothing
+    /// accounts for; they wait in the admin recovery queue
+    OrphanedFundsFound {
+        run_id: String,
+        address_index: u32,
+        chain: String,
+        /// Native coin or canonical token ticker
+        currency: String,
+        #[serde(with = "rust_decimal::serde::float")]
+        amount: Decimal,
+        /// `unswept` or `unmatched_deposit`
+        kind: String,
+    },
+    /// The payout guard halted every payout until an admin re-arms it
+    PayoutGuardTripped {
+        trip_id: String,
+        /// `failure_rate`, `usd_volume`, `outlier_amount` or `manual`
+        cause: String,
+        reason: String,
+    },
+    /// An admin resumed payouts after a guard trip
+    PayoutGuardRearmed {
+        trip_id: String,
+        admin_id: String,
+        note: String,
+    },
+}
+
+impl OpsEvent {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            OpsEvent::PayoutFailed { .. } => "payout_failed",
+            OpsEvent::CircuitBreakerOpened { .. } => "circuit_breaker_opened",
+            OpsEvent::GasTankLow { .. } => "gas_tank_low",
+            OpsEvent::ReconciliationDiscrepancy { .. } => "reconciliation_discrepancy",
+            OpsEvent::SwapStuck { .. } => "swap_stuck",
+            OpsEvent::HotWalletMessageSigned { .. } => "hot_wallet_message_signed",
+            OpsEvent::BackupFailed { .. } => "backup_failed",
+            OpsEvent::OrphanedFundsFound { .. } => "orphaned_funds_found",
+            OpsEvent::PayoutGuardTripped { .. } => "payout_guard_tripped",
+            OpsEvent::PayoutGuardRearmed { .. } => "payout_guard_rearmed",
+        }
+    }
+
+    /// Publish on the process-wide bus
+    pub fn publish(self) {
+        ops_events().publish(OpsNotification { at: Utc::now(), event: self });
+    }
+}
+
+/// An event as pushed to dashboards
+#[derive(Debug, Clone, Serialize)]
+pub struct OpsNotification {
+    pub at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub event: OpsEvent,
+}
+
+/// Process-wide bus for operational events
+pub fn ops_events() -> &'static EventBus<OpsNotification> {
+    OPS_EVENTS.get_or_init(|| EventBus::new(DEFAULT_CAPACITY))
+}
+
+/// Host of an endpoint URL, for events and logs
+pub fn endpoint_host(url: &str) -> Option<String> {
+    reqwest::Url::parse(url).ok()?.host_str().map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_notification_json_is_flat() {
+        let notification = OpsNotification {
+            at: Utc::now(),
+            event: OpsEvent::GasTankLow { chain: "ethereum".to_string(), detail: "0.01 ETH".to_string() },
+        };
+        let json = serde_json::to_value(&notification).unwrap();
+
+        assert_eq!(json["type"], "gas_tank_low");
+        assert_eq!(json["chain"], "ethereum");
+        assert!(json.get("at").is_some());
+    }
+
+    #[test]
+    fn test_endpoint_host_drops_path_and_key() {
+        assert_eq!(
+            endpoint_host("https://eth-mainnet.g.alchemy.com/v2/secret-key").as_deref(),
+            Some("eth-mainnet.g.alchemy.com")
+        );
+        assert_eq!(endpoint_host("not a url"), None);
+    }
+}
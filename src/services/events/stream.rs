@@ -0,0 +1,482 @@
+//! Swap lifecycle events as an append-only Redis Stream, for consumers
+//! outside this process (fraud, analytics). The in-process bus drops what a
+//! slow subscriber misses; the stream keeps every event (trimmed to about
+//! EVENT_STREAM_MAX_LEN entries) so a consumer can replay from any offset,
+//! and several instances of one consumer can share the work through a
+//! Redis consumer group, acknowledging each entry once handled.
+//!
+//! Each entry carries `id` (the envelope id, to deduplicate on), `type`,
+//! `swap_id`, `at` and `event`, the whole envelope as JSON.
+
+use async_trait::async_trait;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use super::domain::DomainEnvelope;
+use super::subscribers::DomainSubscriber;
+use crate::services::redis_cache::RedisService;
+
+pub const DEFAULT_STREAM_KEY: &str = "events:swap_lifecycle";
+pub const DEFAULT_MAX_LEN: u64 = 1_000_000;
+/// Most entries returned by one read
+pub const MAX_READ_COUNT: usize = 1000;
+
+#[derive(Debug, thiserror::Error)]
+pub enum EventStreamError {
+    #[error("Invalid stream offset '{0}'; use start, end or an entry id such as 1700000000000-0")]
+    InvalidOffset(String),
+
+    #[error("Consumer group names are 1-64 letters, digits, '.', '_' or '-'")]
+    InvalidGroup,
+
+    #[error("Consumer group {0} already exists")]
+    GroupExists(String),
+
+    #[error("Event stream unavailable: {0}")]
+    Redis(String),
+}
+
+impl EventStreamError {
+    pub fn status_code(&self) -> axum::http::StatusCode {
+        match self {
+            EventStreamError::InvalidOffset(_) | EventStreamError::InvalidGroup => {
+                axum::http::StatusCode::BAD_REQUEST
+            }
+            EventStreamError::GroupExists(_) => axum::http::StatusCode::CONFLICT,
+            EventStreamError::Redis(_) => axum::http::StatusCode::SERVICE_UNAVAILABLE,
+        }
+    }
+}
+
+impl From<redis::RedisError> for EventStreamError {
+    fn from(e: redis::RedisError) -> Self {
+        EventStreamError::Redis(e.to_string())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventStreamConfig {
+    pub enabled: bool,
+    pub key: String,
+    /// Approximate length the stream is trimmed to on append
+    pub max_len: u64,
+}
+
+impl Default for EventStreamConfig {
+    fn default() -> Self {
+        Self { enabled: true, key: DEFAULT_STREAM_KEY.to_string(), max_len: DEFAULT_MAX_LEN }
+    }
+}
+
+impl EventStreamConfig {
+    pub fn from_env() -> Result<Self, String> {
+        let mut config = Self::default();
+
+        if let Ok(val) = std::env::var("EVENT_STREAM_ENABLED") {
+            config.enabled = val.trim().parse().map_err(|e| format!("Invalid EVENT_STREAM_ENABLED: {}", e))?;
+        }
+        if let Ok(val) = std::env::var("EVENT_STREAM_KEY") {
+            if val.trim().is_empty() {
+                return Err("EVENT_STREAM_KEY must not be empty".to_string());
+            }
+            config.key = val.trim().to_string();
+        }
+        if let Ok(val) = std::env::var("EVENT_STREAM_MAX_LEN") {
+            let max_len: u64 = val.trim().parse().map_err(|e| format!("Invalid EVENT_STREAM_MAX_LEN: {}", e))?;
+            if max_len == 0 {
+                return Err("EVENT_STREAM_MAX_LEN must be at least 1".to_string());
+            }
+            config.max_len = max_len;
+        }
+
+        Ok(config)
+    }
+
+    pub fn global() -> &'static EventStreamConfig {
+        static CONFIG: OnceLock<EventStreamConfig> = OnceLock::new();
+        CONFIG.get_or_init(|| {
+            Self::from_env().unwrap_or_else(|e| {
+                tracing::warn!("Ignoring invalid event stream config: {}", e);
+                Self::default()
+            })
+        })
+    }
+}
+
+/// Where a read or a new consumer group starts
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StreamOffset {
+    /// The oldest entry still in the stream
+    Start,
+    /// Only entries appended from now on
+    End,
+    /// Entries after this entry id
+    After(String),
+}
+
+impl StreamOffset {
+    /// `start`, `end` or an entry id (`<ms>-<seq>`, or just `<ms>`)
+    pub fn parse(value: &str) -> Result<Self, EventStreamError> {
+        let value = value.trim();
+        match value {
+            "start" | "0" => return Ok(StreamOffset::Start),
+            "end" | "$" => return Ok(StreamOffset::End),
+            _ => {}
+        }
+        let (ms, seq) = value.split_once('-').unwrap_or((value, "0"));
+        let numeric = |s: &str| !s.is_empty() && s.len() <= 20 && s.bytes().all(|b| b.is_ascii_digit());
+        if !numeric(ms) || !numeric(seq) {
+            return Err(EventStreamError::InvalidOffset(value.to_string()));
+        }
+        Ok(StreamOffset::After(format!("{}-{}", ms, seq)))
+    }
+
+    /// Id as XGROUP CREATE takes it
+    fn group_id(&self) -> &str {
+        match self {
+            StreamOffset::Start => "0",
+            StreamOffset::End => "$",
+            StreamOffset::After(id) => id,
+        }
+    }
+}
+
+fn validate_group(name: &str) -> Result<(), EventStreamError> {
+    let valid = (1..=64).contains(&name.len())
+        && name.bytes().all(|b| b.is_ascii_alphanumeric() || matches!(b, b'.' | b'_' | b'-'));
+    if valid {
+        Ok(())
+    } else {
+        Err(EventStreamError::InvalidGroup)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct StreamEntry {
+    /// Position in the stream; pass as `after` to read on from here
+    pub offset: String,
+    /// Envelope id, the same on every delivery of the event
+    pub id: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub swap_id: String,
+    pub at: String,
+    /// The whole event envelope
+    pub event: serde_json::Value,
+}
+
+impl StreamEntry {
+    /// Entry from XRANGE/XREADGROUP field-value pairs. Entries trimmed
+    /// while still pending in a group come back without fields.
+    fn from_fields(offset: String, fields: Option<Vec<String>>) -> Self {
+        let fields: HashMap<String, String> = fields
+            .unwrap_or_default()
+            .chunks(2)
+            .filter_map(|pair| match pair {
+                [name, value] => Some((name.clone(), value.clone())),
+                _ => None,
+            })
+            .collect();
+        let field = |name: &str| fields.get(name).cloned().unwrap_or_default();
+
+        Self {
+            id: field("id"),
+            kind: field("type"),
+            swap_id: field("swap_id"),
+            at: field("at"),
+            event: fields
+                .get("event")
+                .and_then(|json| serde_json::from_str(json).ok())
+                .unwrap_or(serde_json::Value::Null),
+            offset,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct StreamGroup {
+    pub name: String,
+    pub consumers: u64,
+    /// Delivered to a consumer but not yet acknowledged
+    pub pending: u64,
+    pub last_delivered: String,
+    /// Entries not yet delivered to the group, when Redis can tell
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lag: Option<u64>,
+}
+
+pub struct EventStream {
+    redis: RedisService,
+    config: EventStreamConfig,
+}
+
+impl EventStream {
+    pub fn new(redis: RedisService, config: EventStreamConfig) -> Self {
+        Self { redis, config }
+    }
+
+    pub fn key(&self) -> &str {
+        &self.config.key
+    }
+
+    async fn connection(&self) -> Result<redis::aio::MultiplexedConnection, EventStreamError> {
+        Ok(self.redis.get_client().get_multiplexed_async_connection().await?)
+    }
+
+    /// Append an event; returns its offset
+    pub async fn append(&self, envelope: &DomainEnvelope) -> Result<String, EventStreamError> {
+        let json = serde_json::to_string(envelope).map_err(|e| EventStreamError::Redis(e.to_string()))?;
+        let mut conn = self.connection().await?;
+
+        let offset: String = redis::cmd("XADD")
+            .arg(&self.config.key)
+            .arg("MAXLEN")
+            .arg("~")
+            .arg(self.config.max_len)
+            .arg("*")
+            .arg("id")
+            .arg(envelope.id.to_string())
+            .arg("type")
+            .arg(envelope.event.kind())
+            .arg("swap_id")
+            .arg(envelope.event.swap_id())
+            .arg("at")
+            .arg(envelope.at.to_rfc3339())
+            .arg("event")
+            .arg(json)
+            .query_async(&mut conn)
+            .await?;
+        Ok(offset)
+    }
+
+    /// Up to `count` entries after `from`, oldest first. Replaying needs no
+    /// consumer group and changes nothing.
+    pub async fn read(&self, from: &StreamOffset, count: usize) -> Result<Vec<StreamEntry>, EventStreamError> {
+        let start = match from {
+            StreamOffset::Start => "-".to_string(),
+            StreamOffset::End => return Ok(Vec::new()),
+            StreamOffset::After(id) => format!("({}", id),
+        };
+        let mut conn = self.connection().await?;
+
+        let entries: Vec<(String, Option<Vec<String>>)> = redis::cmd("XRANGE")
+            .arg(&self.config.key)
+            .arg(start)
+            .arg("+")
+            .arg("COUNT")
+            .arg(count.clamp(1, MAX_READ_COUNT))
+            .query_async(&mut conn)
+            .await?;
+        Ok(entries.into_iter().map(|(offset, fields)| StreamEntry::from_fields(offset, fields)).collect())
+    }
+
+    /// Offset of the newest entry; None while the stream is empty
+    pub async fn last_offset(&self) -> Result<Option<String>, EventStreamError> {
+        let mut conn = self.connection().await?;
+
+        let newest: Vec<(String, Option<Vec<String>>)> = redis::cmd("XREVRANGE")
+            .arg(&self.config.key)
+            .arg("+")
+            .arg("-")
+            .arg("COUNT")
+            .arg(1)
+            .query_async(&mut conn)
+            .await?;
+        Ok(newest.into_iter().next().map(|(offset, _)| offset))
+    }
+
+    /// Create a consumer group that starts reading at `from`. Creates the
+    /// stream too if nothing has been published yet.
+    pub async fn create_group(&self, group: &str, from: &StreamOffset) -> Result<(), EventStreamError> {
+        validate_group(group)?;
+        let mut conn = self.connection().await?;
+
+        let created: Result<(), redis::RedisError> = redis::cmd("XGROUP")
+            .arg("CREATE")
+            .arg(&self.config.key)
+            .arg(group)
+            .arg(from.group_id())
+            .arg("MKSTREAM")
+            .query_async(&mut conn)
+            .await;
+        match created {
+            Ok(()) => Ok(()),
+            Err(e) if e.to_string().contains("BUSYGROUP") => Err(EventStreamError::GroupExists(group.to_string())),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Entries for `consumer` in `group`: new ones, waiting up to `block`
+    /// for any to arrive. Each stays pending until acknowledged.
+    pub async fn read_group(
+        &self,
+        group: &str,
+        consumer: &str,
+        count: usize,
+        block: Duration,
+    ) -> Result<Vec<StreamEntry>, EventStreamError> {
+        self.read_group_from(group, consumer, count, Some(block), ">").await
+    }
+
+    /// Entries delivered to `consumer` but never acknowledged, e.g. after a
+    /// crash; handle these before reading new ones
+    pub async fn read_pending(
+        &self,
+        group: &str,
+        consumer: &str,
+        count: usize,
+    ) -> Result<Vec<StreamEntry>, EventStreamError> {
+        self.read_group_from(group, consumer, count, None, "0").await
+    }
+
+    async fn read_group_from(
+        &self,
+        group: &str,
+        consumer: &str,
+        count: usize,
+        block: Option<Duration>,
+        id: &str,
+    ) -> Result<Vec<StreamEntry>, EventStreamError> {
+        validate_group(group)?;
+        let mut conn = self.connection().await?;
+
+        let mut cmd = redis::cmd("XREADGROUP");
+        cmd.arg("GROUP").arg(group).arg(consumer).arg("COUNT").arg(count.clamp(1, MAX_READ_COUNT));
+        if let Some(block) = block {
+            cmd.arg("BLOCK").arg(block.as_millis() as u64);
+        }
+        cmd.arg("STREAMS").arg(&self.config.key).arg(id);
+
+        type Reply = Option<Vec<(String, Vec<(String, Option<Vec<String>>)>)>>;
+        let reply: Reply = cmd.query_async(&mut conn).await?;
+        Ok(reply
+            .unwrap_or_default()
+            .into_iter()
+            .flat_map(|(_, entries)| entries)
+            .map(|(offset, fields)| StreamEntry::from_fields(offset, fields))
+            .collect())
+    }
+
+    /// Acknowledge handled entries; returns how many were pending
+    pub async fn ack(&self, group: &str, offsets: &[String]) -> Result<u64, EventStreamError> {
+        if offsets.is_empty() {
+            return Ok(0);
+        }
+        validate_group(group)?;
+        let mut conn = self.connection().await?;
+
+        let acked: u64 = redis::cmd("XACK")
+            .arg(&self.config.key)
+            .arg(group)
+            .arg(offsets)
+            .query_async(&mut conn)
+            .await?;
+        Ok(acked)
+    }
+
+    /// Consumer groups on the stream; none when it doesn't exist yet
+    pub async fn groups(&self) -> Result<Vec<StreamGroup>, EventStreamError> {
+        let mut conn = self.connection().await?;
+
+        let groups: Vec<HashMap<String, Option<String>>> =
+            match redis::cmd("XINFO").arg("GROUPS").arg(&self.config.key).query_async(&mut conn).await {
+                Ok(groups) => groups,
+                Err(e) if e.to_string().contains("no such key") => return Ok(Vec::new()),
+                Err(e) => return Err(e.into()),
+            };
+
+        Ok(groups
+            .into_iter()
+            .map(|info| {
+                let text = |name: &str| info.get(name).cloned().flatten().unwrap_or_default();
+                let number = |name: &str| info.get(name).cloned().flatten().and_then(|v| v.parse().ok());
+                StreamGroup {
+                    name: text("name"),
+                    consumers: number("consumers").unwrap_or(0),
+                    pending: number("pending").unwrap_or(0),
+                    last_delivered: text("last-delivered-id"),
+                    lag: number("lag"),
+                }
+            })
+            .collect())
+    }
+}
+
+/// Appends every domain event to the stream
+pub struct StreamSubscriber {
+    stream: EventStream,
+}
+
+impl StreamSubscriber {
+    pub fn new(redis: RedisService, config: EventStreamConfig) -> Self {
+        Self { stream: EventStream::new(redis, config) }
+    }
+}
+
+#[async_trait]
+impl DomainSubscriber for StreamSubscriber {
+    fn name(&self) -> &'static str {
+        "event_stream"
+    }
+
+    async fn handle(&self, envelope: &DomainEnvelope) {
+        if let Err(e) = self.stream.append(envelope).await {
+            tracing::warn!(
+                "Failed to append {} event {} to {}: {}",
+                envelope.event.kind(),
+                envelope.id,
+                self.stream.key(),
+                e
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_offsets() {
+        assert_eq!(StreamOffset::parse("start").unwrap(), StreamOffset::Start);
+        assert_eq!(StreamOffset::parse("$").unwrap(), StreamOffset::End);
+        assert_eq!(
+            StreamOffset::parse("1700000000000-3").unwrap(),
+            StreamOffset::After("1700000000000-3".to_string())
+        );
+        assert_eq!(StreamOffset::parse("1700000000000").unwrap(), StreamOffset::After("1700000000000-0".to_string()));
+        assert!(StreamOffset::parse("1700000000000-").is_err());
+        assert!(StreamOffset::parse("-5").is_err());
+        assert!(StreamOffset::parse("latest").is_err());
+    }
+
+    #[test]
+    fn test_group_names() {
+        assert!(validate_group("fraud-scoring.v2").is_ok());
+        assert!(validate_group("").is_err());
+        assert!(validate_group("has space").is_err());
+        assert!(validate_group(&"g".repeat(65)).is_err());
+    }
+
+    #[test]
+    fn test_entry_from_fields() {
+        let fields = ["id", "e-1", "type", "refund_issued", "swap_id", "swap-1", "at", "2026-01-01T00:00:00Z"]
+            .iter()
+            .map(|s| s.to_string())
+            .chain(["event".to_string(), r#"{"type":"refund_issued","swap_id":"swap-1"}"#.to_string()])
+            .collect();
+        let entry = StreamEntry::from_fields("1-0".to_string(), Some(fields));
+
+        assert_eq!(entry.offset, "1-0");
+        assert_eq!(entry.kind, "refund_issued");
+        assert_eq!(entry.swap_id, "swap-1");
+        assert_eq!(entry.event["swap_id"], "swap-1");
+
+        let trimmed = StreamEntry::from_fields("2-0".to_string(), None);
+        assert_eq!(trimmed.event, serde_json::Value::Null);
+        assert!(trimmed.id.is_empty());
+    }
+}
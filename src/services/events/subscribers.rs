@@ -0,0 +1,637 @@
+//! Consumers of swap lifecycle events. Each runs on its own task with its own
+//! receiver, so a slow webhook endpoint or mail API never holds up the others
+//! or the code that published the event.
+
+use async_trait::async_trait;
+use rust_decimal::prelude::ToPrimitive;
+use sqlx::{MySql, Pool};
+use std::sync::Arc;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+
+use super::domain::{domain_events, DomainEnvelope, DomainEvent};
+use crate::modules::commissions::crud::CommissionCrud;
+use crate::modules::swap::schema::SwapStatus;
+use crate::services::address_reputation::AddressReputationService;
+use crate::services::amount::Decimal;
+use crate::services::email::{EmailMessage, EmailSender};
+use crate::services::metrics::collectors::SwapMetricsCollector;
+use crate::services::metrics::MetricsRegistry;
+use crate::services::pii::SealedString;
+use crate::services::pricing::approx_usd_price;
+use crate::services::swap_state::SwapStateMachine;
+use crate::services::webhook::catalog::{
+    self, EventType, PayoutFinalizedV1, PayoutSentV1, RefundIssuedV1, SwapCompletedV1,
+};
+use crate::services::webhook::{Webhook, WebhookDispatcher, WebhookEvent, WebhookPayload};
+
+/// Id, swap id, url, secret, events JSON, payload version and rate limit of
+/// an enabled webhook
+type WebhookRow = (String, String, String, String, String, Option<u32>, i32);
+
+/// Pair, amounts, addresses, payout hash and completion time of the swap a
+/// versioned payload describes
+type SwapEventRow = (
+    String, String, String, String, Decimal, Option<Decimal>, SealedString, Option<String>, Option<String>,
+    Option<chrono::DateTime<chrono::Utc>>,
+);
+
+#[async_trait]
+pub trait DomainSubscriber: Send + Sync + 'static {
+    fn name(&self) -> &'static str;
+
+    async fn handle(&self, envelope: &DomainEnvelope);
+}
+
+/// Subscribe now and handle events on a background task. Subscribing before
+/// the spawn means nothing published after this call returns is missed.
+pub fn spawn_subscriber<S: DomainSubscriber>(subscriber: S) -> JoinHandle<()> {
+    let mut rx = domain_events().subscribe();
+    tokio::spawn(async move {
+        loop {
+            match rx.recv().await {
+                Ok(envelope) => subscriber.handle(&envelope).await,
+                Err(RecvError::Lagged(missed)) => {
+                    tracing::warn!("{} subscriber fell behind and skipped {} domain events", subscriber.name(), missed);
+                }
+                Err(RecvError::Closed) => break,
+            }
+        }
+    })
+}
+
+// =============================================================================
+// WEBHOOKS
+// =============================================================================
+
+/// The webhook event a domain event is delivered as, if any
+pub fn webhook_event(event: &DomainEvent) -> Option<WebhookEvent> {
+    match event {
+        DomainEvent::SwapCreated { .. } => Some(WebhookEvent::SwapCreated),
+        DomainEvent::SwapStatusChanged { to, .. } => match to {
+            SwapStatus::Waiting => Some(WebhookEvent::SwapPending),
+            SwapStatus::Confirming | SwapStatus::Exchanging | SwapStatus::Sending | SwapStatus::FundsReceived => {
+                Some(WebhookEvent::SwapProcessing)
+            }
+            SwapStatus::Completed => Some(WebhookEvent::SwapCompleted),
+            SwapStatus::Failed => Some(WebhookEvent::SwapFailed),
+            SwapStatus::Expired => Some(WebhookEvent::SwapExpired),
+            // Delivered from RefundIssued so it is sent once
+            SwapStatus::Refunded => None,
+        },
+        DomainEvent::RefundIssued { .. } => Some(WebhookEvent::SwapRefunded),
+        DomainEvent::PayoutSent { .. } => Some(WebhookEvent::PayoutCompleted),
+        DomainEvent::PayoutFailed { .. } => Some(WebhookEvent::PayoutFailed),
+        DomainEvent::PayoutFinalized { .. } => Some(WebhookEvent::PayoutFinalized),
+    }
+}
+
+/// Delivers events to the webhooks registered on the swap they concern
+pub struct WebhookSubscriber {
+    db: Pool<MySql>,
+    dispatcher: Arc<WebhookDispatcher>,
+}
+
+impl WebhookSubscriber {
+    pub fn new(db: Pool<MySql>, dispatcher: Arc<WebhookDispatcher>) -> Self {
+        Self { db, dispatcher }
+    }
+
+    async fn webhooks_for_swap(&self, swap_id: &str) -> Result<Vec<Webhook>, sqlx::Error> {
+        let rows: Vec<WebhookRow> = sqlx::query_as(
+            r#"
+            SELECT id, swap_id, url, secret_key, CAST(events AS CHAR), payload_version,
+                   COALESCE(rate_limit_per_second, 10)
+            FROM webhooks
+            WHERE swap_id = ? AND enabled = true
+            "#,
+        )
+        .bind(swap_id)
+        .fetch_all(&self.db)
+        .await?;
+
+        let now = chrono::Utc::now();
+        Ok(rows
+            .into_iter()
+            .filter_map(|(id, swap_id, url, secret_key, events, payload_version, rate_limit_per_second)| {
+                Some(Webhook {
+                    id: Uuid::parse_str(&id).ok()?,
+                    swap_id: Uuid::parse_str(&swap_id).ok()?,
+                    url,
+                    secret_key,
+                    events: serde_json::from_str(&events).unwrap_or_default(),
+                    payload_version,
+                    enabled: true,
+                    rate_limit_per_second,
+                    created_at: now,
+                    updated_at: now,
+                })
+            })
+            .collect())
+    }
+
+    /// The integrator's client_reference and metadata for the swap
+    async fn client_data(&self, swap_id: &str) -> Result<(Option<String>, Option<serde_json::Value>), sqlx::Error> {
+        let row: Option<(Option<String>, Option<String>)> =
+            sqlx::query_as("SELECT client_reference, CAST(metadata AS CHAR) FROM swaps WHERE id = ?")
+                .bind(swap_id)
+                .fetch_optional(&self.db)
+                .await?;
+        let (reference, metadata) = row.unwrap_or_default();
+        Ok((reference, metadata.and_then(|json| serde_json::from_str(&json).ok())))
+    }
+
+    /// `data` of a published event type, built from the swap as it is now
+    async fn versioned_data(&self, event_type: &EventType, envelope: &DomainEnvelope) -> Result<serde_json::Value, String> {
+        let swap_id = envelope.event.swap_id();
+        let row: Option<SwapEventRow> = sqlx::query_as(
+            r#"
+            SELECT from_currency, from_network, to_currency, to_network, amount, actual_receive,
+                   recipient_address, refund_address, tx_hash_out, completed_at
+            FROM swaps WHERE id = ?
+            "#,
+        )
+        .bind(swap_id)
+        .fetch_optional(&self.db)
+        .await
+        .map_err(|e| e.to_string())?;
+        let Some((from_currency, from_network, to_currency, to_network, amount, received, recipient, refund_address, tx_hash_out, completed_at)) = row
+        else {
+            return Err("swap not found".to_string());
+        };
+        let (client_reference, metadata) = self.client_data(swap_id).await.map_err(|e| e.to_string())?;
+
+        let data = match (&event_type.event, &envelope.event) {
+            (WebhookEvent::SwapCompleted, _) => serde_json::to_value(SwapCompletedV1 {
+                swap_id: swap_id.to_string(),
+                from_currency,
+                from_network,
+                to_currency,
+                to_network,
+                amount_sent: amount,
+                amount_received: received,
+                recipient_address: recipient.0,
+                tx_hash_out,
+                completed_at: completed_at.unwrap_or(envelope.at),
+                client_reference,
+                metadata,
+            }),
+            (WebhookEvent::PayoutCompleted, DomainEvent::PayoutSent { chain, currency, amount, reference, .. }) => {
+                serde_json::to_value(PayoutSentV1 {
+                    swap_id: swap_id.to_string(),
+                    chain: chain.clone(),
+                    currency: currency.clone(),
+                    amount: *amount,
+                    reference: reference.clone(),
+                    sent_at: envelope.at,
+                    client_reference,
+                    metadata,
+                })
+            }
+            (WebhookEvent::PayoutFinalized, DomainEvent::PayoutFinalized { chain, tx_hash, confirmations, .. }) => {
+                serde_json::to_value(PayoutFinalizedV1 {
+                    swap_id: swap_id.to_string(),
+                    chain: chain.clone(),
+                    tx_hash: tx_hash.clone(),
+                    confirmations: *confirmations,
+                    finalized_at: envelope.at,
+                    client_reference,
+                    metadata,
+                })
+            }
+            (WebhookEvent::SwapRefunded, _) => serde_json::to_value(RefundIssuedV1 {
+                swap_id: swap_id.to_string(),
+                currency: from_currency,
+                network: from_network,
+                refund_address,
+                tx_hash: tx_hash_out,
+                refunded_at: envelope.at,
+                client_reference,
+                metadata,
+            }),
+            _ => return Err(format!("no payload for {}", event_type.name)),
+        };
+        data.map_err(|e| e.to_string())
+    }
+}
+
+/// How a webhook receives an event, if it does
+enum Delivery {
+    Unversioned,
+    Versioned(&'static EventType),
+}
+
+/// Versioned subscriptions only receive the event types published for
+/// their version
+fn delivery(webhook: &Webhook, event: &WebhookEvent) -> Option<Delivery> {
+    let version = catalog::negotiate_version(webhook.payload_version);
+    if !catalog::selects(&webhook.events, event, version) {
+        return None;
+    }
+    match version {
+        Some(version) => catalog::event_type(event, version).map(Delivery::Versioned),
+        None => Some(Delivery::Unversioned),
+    }
+}
+
+/// Add the swap's client_reference and metadata so partners can correlate events
+fn with_client_data(mut data: serde_json::Value, reference: Option<String>, metadata: Option<serde_json::Value>) -> serde_json::Value {
+    if let Some(object) = data.as_object_mut() {
+        if let Some(reference) = reference {
+            object.insert("client_reference".to_string(), serde_json::Value::String(reference));
+        }
+        if let Some(metadata) = metadata {
+            object.insert("metadata".to_string(), metadata);
+        }
+    }
+    data
+}
+
+/// A webhook with no event list receives everything
+fn subscribed(webhook: &Webhook, event: &WebhookEvent) -> bool {
+    delivery(webhook, event).is_some()
+}
+
+#[async_trait]
+impl DomainSubscriber for WebhookSubscriber {
+    fn name(&self) -> &'static str {
+        "webhook"
+    }
+
+    async fn handle(&self, envelope: &DomainEnvelope) {
+        let Some(event) = webhook_event(&envelope.event) else {
+            return;
+        };
+        let swap_id = envelope.event.swap_id();
+
+        let webhooks = match self.webhooks_for_swap(swap_id).await {
+            Ok(webhooks) => webhooks,
+            Err(e) => {
+                tracing::error!("Failed to load webhooks for swap {}: {}", swap_id, e);
+                return;
+            }
+        };
+
+        if !webhooks.iter().any(|w| subscribed(w, &event)) {
+            return;
+        }
+
+        let data = match serde_json::to_value(&envelope.event) {
+            Ok(data) => data,
+            Err(e) => {
+                tracing::error!("Failed to serialize {} for swap {}: {}", envelope.event.kind(), swap_id, e);
+                return;
+            }
+        };
+        let data = match self.client_data(swap_id).await {
+            Ok((reference, metadata)) => with_client_data(data, reference, metadata),
+            Err(e) => {
+                tracing::warn!("Failed to load client reference for swap {}: {}", swap_id, e);
+                data
+            }
+        };
+
+        let mut versioned: Option<serde_json::Value> = None;
+        for webhook in &webhooks {
+            let (event_type, data) = match delivery(webhook, &event) {
+                None => continue,
+                Some(Delivery::Unversioned) => (event.as_str(), data.clone()),
+                Some(Delivery::Versioned(event_type)) => {
+                    // Every supported version publishes at most one type per event
+                    if versioned.is_none() {
+                        match self.versioned_data(event_type, envelope).await {
+                            Ok(data) => versioned = Some(data),
+                            Err(e) => {
+                                tracing::error!("Failed to build {} for swap {}: {}", event_type.name, swap_id, e);
+                                continue;
+                            }
+                        }
+                    }
+                    (event_type.name, versioned.clone().unwrap_or_default())
+                }
+            };
+            let payload = WebhookPayload {
+                id: envelope.id.to_string(),
+                event_type: event_type.to_string(),
+                created_at: envelope.at.timestamp(),
+                data,
+            };
+            if let Err(e) = self.dispatcher.dispatch(webhook, payload).await {
+                tracing::warn!("Webhook {} not delivered for swap {}: {}", webhook.id, swap_id, e);
+            }
+        }
+    }
+}
+
+// =============================================================================
+// METRICS
+// =============================================================================
+
+/// Swap counters and durations for Prometheus
+pub struct MetricsSubscriber {
+    db: Pool<MySql>,
+    swaps: SwapMetricsCollector,
+}
+
+impl MetricsSubscriber {
+    pub fn new(db: Pool<MySql>, metrics: Arc<MetricsRegistry>) -> Self {
+        Self { db, swaps: SwapMetricsCollector::new(metrics) }
+    }
+
+    /// Observe how long the swap spent in the status it just left
+    async fn record_stage(&self, swap_id: &str, left: &SwapStatus) {
+        let history = match SwapStateMachine::new(self.db.clone()).history(swap_id).await {
+            Ok(history) => history,
+            Err(e) => {
+                tracing::warn!("Failed to load status history of swap {} for metrics: {}", swap_id, e);
+                return;
+            }
+        };
+
+        // The latest finished stage in that status; swaps created before
+        // history was kept may have none
+        let duration = history
+            .iter()
+            .rev()
+            .find(|entry| &entry.status == left && entry.duration_secs.is_some())
+            .and_then(|entry| entry.duration_secs);
+        if let Some(duration_secs) = duration {
+            self.swaps.record_stage_duration(left.as_str(), duration_secs);
+        }
+    }
+}
+
+#[async_trait]
+impl DomainSubscriber for MetricsSubscriber {
+    fn name(&self) -> &'static str {
+        "metrics"
+    }
+
+    async fn handle(&self, envelope: &DomainEnvelope) {
+        let (swap_id, to) = match &envelope.event {
+            DomainEvent::SwapCreated { from, to, provider, .. } => {
+                self.swaps.record_swap_initiated(&from.to_lowercase(), &to.to_lowercase(), provider);
+                return;
+            }
+            DomainEvent::SwapStatusChanged { swap_id, from, to } => {
+                self.record_stage(swap_id, from).await;
+                if !matches!(to, SwapStatus::Completed | SwapStatus::Failed | SwapStatus::Expired) {
+                    return;
+                }
+                (swap_id, to)
+            }
+            _ => return,
+        };
+
+        let row: Option<(String, String, String, Decimal, i64)> = match sqlx::query_as(
+            r#"
+            SELECT from_currency, to_currency, provider_id, amount,
+                   TIMESTAMPDIFF(SECOND, created_at, NOW())
+            FROM swaps WHERE id = ?
+            "#,
+        )
+        .bind(swap_id)
+        .fetch_optional(&self.db)
+        .await
+        {
+            Ok(row) => row,
+            Err(e) => {
+                tracing::warn!("Failed to load swap {} for metrics: {}", swap_id, e);
+                return;
+            }
+        };
+        let Some((from, to_currency, provider, amount, duration_secs)) = row else {
+            return;
+        };
+        let (from, to_currency) = (from.to_lowercase(), to_currency.to_lowercase());
+
+        match to {
+            SwapStatus::Completed => self.swaps.record_swap_completed(
+                &from,
+                &to_currency,
+                &provider,
+                duration_secs.max(0) as f64,
+                amount.to_f64().unwrap_or(0.0) * approx_usd_price(&from),
+            ),
+            _ => self.swaps.record_swap_failed(&from, &to_currency, &provider, to.as_str()),
+        }
+    }
+}
+
+// =============================================================================
+// CUSTOMER NOTIFICATIONS
+// =============================================================================
+
+/// Emails the swap owner when a swap finishes, fails or is refunded.
+/// Anonymous swaps have nobody to notify.
+pub struct NotificationSubscriber {
+    db: Pool<MySql>,
+    email_sender: Arc<dyn EmailSender>,
+}
+
+impl NotificationSubscriber {
+    pub fn new(db: Pool<MySql>, email_sender: Arc<dyn EmailSender>) -> Self {
+        Self { db, email_sender }
+    }
+}
+
+/// Subject and opening line for events worth an email
+fn notification_text(event: &DomainEvent) -> Option<(&'static str, &'static str)> {
+    match event {
+        DomainEvent::SwapStatusChanged { to: SwapStatus::Completed, .. } => {
+            Some(("Your swap is complete", "Your swap has completed and the funds were sent to your address."))
+        }
+        DomainEvent::SwapStatusChanged { to: SwapStatus::Failed, .. } => {
+            Some(("Your swap failed", "Your swap could not be completed. Contact support if your deposit was sent."))
+        }
+        DomainEvent::RefundIssued { .. } => {
+            Some(("Your swap was refunded", "Your deposit has been returned to your refund address."))
+        }
+        _ => None,
+    }
+}
+
+#[async_trait]
+impl DomainSubscriber for NotificationSubscriber {
+    fn name(&self) -> &'static str {
+        "notification"
+    }
+
+    async fn handle(&self, envelope: &DomainEnvelope) {
+        let Some((subject, intro)) = notification_text(&envelope.event) else {
+            return;
+        };
+        let swap_id = envelope.event.swap_id();
+
+        let row: Option<(SealedString, String, String)> = match sqlx::query_as(
+            r#"
+            SELECT u.email, s.from_currency, s.to_currency
+            FROM swaps s
+            JOIN users u ON u.id = s.user_id
+            WHERE s.id = ?
+            "#,
+        )
+        .bind(swap_id)
+        .fetch_optional(&self.db)
+        .await
+        {
+            Ok(row) => row,
+            Err(e) => {
+                tracing::warn!("Failed to load owner of swap {}: {}", swap_id, e);
+                return;
+            }
+        };
+        let Some((email, from, to)) = row else {
+            return;
+        };
+
+        let message = EmailMessage {
+            to: email.into(),
+            subject: subject.to_string(),
+            text: format!(
+                "{}\n\nPair: {} -> {}\nSwap ID: {}\n",
+                intro,
+                from.to_uppercase(),
+                to.to_uppercase(),
+                swap_id
+            ),
+        };
+
+        if let Err(e) = self.email_sender.send(&message).await {
+            tracing::error!("Failed to send swap notification for {}: {}", swap_id, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status_changed(to: SwapStatus) -> DomainEvent {
+        DomainEvent::SwapStatusChanged { swap_id: "swap-1".to_string(), from: SwapStatus::Sending, to }
+    }
+
+    #[test]
+    fn test_refund_is_delivered_once() {
+        assert_eq!(webhook_event(&status_changed(SwapStatus::Refunded)), None);
+        assert_eq!(
+            webhook_event(&DomainEvent::RefundIssued { swap_id: "swap-1".to_string() }),
+            Some(WebhookEvent::SwapRefunded)
+        );
+        assert_eq!(webhook_event(&status_changed(SwapStatus::Completed)), Some(WebhookEvent::SwapCompleted));
+        assert_eq!(webhook_event(&status_changed(SwapStatus::Exchanging)), Some(WebhookEvent::SwapProcessing));
+    }
+
+    #[test]
+    fn test_webhook_event_filter() {
+        let now = chrono::Utc::now();
+        let mut webhook = Webhook {
+            id: Uuid::new_v4(),
+            swap_id: Uuid::new_v4(),
+            url: "https://example.com/hook".to_string(),
+            secret_key: "secret".to_string(),
+            events: vec![],
+            payload_version: None,
+            enabled: true,
+            rate_limit_per_second: 10,
+            created_at: now,
+            updated_at: now,
+        };
+        assert!(subscribed(&webhook, &WebhookEvent::SwapCompleted));
+
+        webhook.events = vec!["swap.completed".to_string()];
+        assert!(subscribed(&webhook, &WebhookEvent::SwapCompleted));
+        assert!(!subscribed(&webhook, &WebhookEvent::PayoutFailed));
+
+        // Versioned subscriptions only receive published types
+        webhook.events = vec![];
+        webhook.payload_version = Some(1);
+        assert!(subscribed(&webhook, &WebhookEvent::SwapCompleted));
+        assert!(!subscribed(&webhook, &WebhookEvent::SwapCreated));
+    }
+
+    #[test]
+    fn test_client_data_is_added_to_payload() {
+        let data = serde_json::to_value(status_changed(SwapStatus::Completed)).unwrap();
+        let enriched = with_client_data(data.clone(), Some("order-42".to_string()), Some(serde_json::json!({ "tier": "gold" })));
+        assert_eq!(enriched["client_reference"], "order-42");
+        assert_eq!(enriched["metadata"]["tier"], "gold");
+        assert_eq!(enriched["swap_id"], "swap-1");
+
+        assert_eq!(with_client_data(data.clone(), None, None), data);
+    }
+
+    #[test]
+    fn test_only_outcomes_are_emailed() {
+        assert!(notification_text(&status_changed(SwapStatus::Completed)).is_some());
+        assert!(notification_text(&status_changed(SwapStatus::Failed)).is_some());
+        assert!(notification_text(&status_changed(SwapStatus::Exchanging)).is_none());
+        // Refunds are announced from RefundIssued, not the status change
+        assert!(notification_text(&status_changed(SwapStatus::Refunded)).is_none());
+    }
+}
+
+// =============================================================================
+// REVENUE LEDGER
+// =============================================================================
+
+/// Writes what a swap earned to the revenue ledger once it completes
+pub struct RevenueSubscriber {
+    commissions: CommissionCrud,
+}
+
+impl RevenueSubscriber {
+    pub fn new(db: Pool<MySql>) -> Self {
+        Self { commissions: CommissionCrud::new(db) }
+    }
+}
+
+#[async_trait]
+impl DomainSubscriber for RevenueSubscriber {
+    fn name(&self) -> &'static str {
+        "revenue"
+    }
+
+    async fn handle(&self, envelope: &DomainEnvelope) {
+        let DomainEvent::SwapStatusChanged { swap_id, to: SwapStatus::Completed, .. } = &envelope.event else {
+            return;
+        };
+
+        if let Err(e) = self.commissions.record_swap_revenue(swap_id).await {
+            tracing::error!("Failed to record revenue for swap {}: {}", swap_id, e);
+        }
+    }
+}
+
+// =============================================================================
+// ADDRESS REPUTATION
+// =============================================================================
+
+/// Counts each new swap against its payout address
+pub struct AddressReputationSubscriber {
+    reputation: AddressReputationService,
+}
+
+impl AddressReputationSubscriber {
+    pub fn new(db: Pool<MySql>) -> Self {
+        Self { reputation: AddressReputationService::new(db) }
+    }
+}
+
+#[async_trait]
+impl DomainSubscriber for AddressReputationSubscriber {
+    fn name(&self) -> &'static str {
+        "address_reputation"
+    }
+
+    async fn handle(&self, envelope: &DomainEnvelope) {
+        let DomainEvent::SwapCreated { swap_id, .. } = &envelope.event else {
+            return;
+        };
+
+        if let Err(e) = self.reputation.record_swap(swap_id).await {
+            tracing::error!("Failed to record payout address of swap {}: {}", swap_id, e);
+        }
+    }
+}
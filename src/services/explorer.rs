@@ -0,0 +1,200 @@
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// URL templates for one chain's block explorer. `{tx}` and `{address}`
+/// are replaced with the transaction hash and address.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExplorerTemplate {
+    pub tx: String,
+    pub address: String,
+}
+
+const DEFAULT_EXPLORERS: &[(&str, &str, &str)] = &[
+    // Mainnets
+    ("bitcoin", "https://mempool.space/tx/{tx}", "https://mempool.space/address/{address}"),
+    ("litecoin", "https://litecoinspace.org/tx/{tx}", "https://litecoinspace.org/address/{address}"),
+    ("ethereum", "https://etherscan.io/tx/{tx}", "https://etherscan.io/address/{address}"),
+    ("polygon", "https://polygonscan.com/tx/{tx}", "https://polygonscan.com/address/{address}"),
+    ("bsc", "https://bscscan.com/tx/{tx}", "https://bscscan.com/address/{address}"),
+    ("arbitrum", "https://arbiscan.io/tx/{tx}", "https://arbiscan.io/address/{address}"),
+    ("optimism", "https://optimistic.etherscan.io/tx/{tx}", "https://optimistic.etherscan.io/address/{address}"),
+    ("base", "https://basescan.org/tx/{tx}", "https://basescan.org/address/{address}"),
+    ("avalanche", "https://snowtrace.io/tx/{tx}", "https://snowtrace.io/address/{address}"),
+    ("fantom", "https://ftmscan.com/tx/{tx}", "https://ftmscan.com/address/{address}"),
+    ("gnosis", "https://gnosisscan.io/tx/{tx}", "https://gnosisscan.io/address/{address}"),
+    ("solana", "https://solscan.io/tx/{tx}", "https://solscan.io/account/{address}"),
+    ("tron", "https://tronscan.org/#/transaction/{tx}", "https://tronscan.org/#/address/{address}"),
+    // Testnets
+    ("sepolia", "https://sepolia.etherscan.io/tx/{tx}", "https://sepolia.etherscan.io/address/{address}"),
+    ("bitcoin-testnet", "https://mempool.space/testnet/tx/{tx}", "https://mempool.space/testnet/address/{address}"),
+    ("solana-devnet", "https://solscan.io/tx/{tx}?cluster=devnet", "https://solscan.io/account/{address}?cluster=devnet"),
+];
+
+/// Explorer URL templates keyed by canonical chain name (see `chain_key`)
+#[derive(Debug, Clone, Default)]
+pub struct ExplorerRegistry {
+    chains: HashMap<String, ExplorerTemplate>,
+}
+
+impl ExplorerRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registry with the built-in mainnet and testnet explorers
+    pub fn with_defaults() -> Self {
+        DEFAULT_EXPLORERS
+            .iter()
+            .fold(Self::new(), |registry, (chain, tx, address)| registry.register(chain, tx, address))
+    }
+
+    pub fn register(mut self, chain: &str, tx: &str, address: &str) -> Self {
+        self.chains.insert(
+            chain.to_lowercase(),
+            ExplorerTemplate { tx: tx.to_string(), address: address.to_string() },
+        );
+        self
+    }
+
+    /// Defaults plus overrides from the environment, one pair per chain:
+    ///
+    /// - EXPLORER_<CHAIN>_TX_URL, e.g. `https://scan.example/tx/{tx}`
+    /// - EXPLORER_<CHAIN>_ADDRESS_URL, e.g. `https://scan.example/address/{address}`
+    ///
+    /// `<CHAIN>` is the chain name upper-cased with `-` written as `_`
+    /// (EXPLORER_BITCOIN_TESTNET_TX_URL). Either template may be set on its
+    /// own for a known chain; new chains need both.
+    pub fn from_env() -> Self {
+        Self::with_defaults().with_overrides(std::env::vars())
+    }
+
+    fn with_overrides(mut self, vars: impl IntoIterator<Item = (String, String)>) -> Self {
+        for (key, value) in vars {
+            let Some(rest) = key.strip_prefix("EXPLORER_") else { continue };
+            let value = value.trim().to_string();
+            if value.is_empty() {
+                continue;
+            }
+
+            let (chain, is_tx) = if let Some(chain) = rest.strip_suffix("_TX_URL") {
+                (chain, true)
+            } else if let Some(chain) = rest.strip_suffix("_ADDRESS_URL") {
+                (chain, false)
+            } else {
+                continue;
+            };
+
+            let chain = chain.to_lowercase().replace('_', "-");
+            let entry = self.chains.entry(chain).or_insert_with(|| ExplorerTemplate {
+                tx: String::new(),
+                address: String::new(),
+            });
+            if is_tx {
+                entry.tx = value;
+            } else {
+                entry.address = value;
+            }
+        }
+        self
+    }
+
+    /// Process-wide registry, read from the environment once
+    pub fn global() -> &'static ExplorerRegistry {
+        static REGISTRY: OnceLock<ExplorerRegistry> = OnceLock::new();
+        REGISTRY.get_or_init(Self::from_env)
+    }
+
+    pub fn get(&self, chain: &str) -> Option<&ExplorerTemplate> {
+        self.chains.get(&chain.to_lowercase())
+    }
+
+    pub fn tx_url(&self, chain: &str, tx_hash: &str) -> Option<String> {
+        let template = &self.get(chain)?.tx;
+        (!template.is_empty() && !tx_hash.is_empty()).then(|| template.replace("{tx}", tx_hash))
+    }
+
+    pub fn address_url(&self, chain: &str, address: &str) -> Option<String> {
+        let template = &self.get(chain)?.address;
+        (!template.is_empty() && !address.is_empty()).then(|| template.replace("{address}", address))
+    }
+}
+
+/// Canonical chain name for a provider currency/network pair. Providers
+/// name the native network of a coin "Mainnet", so the currency decides
+/// the chain in that case.
+pub fn chain_key(currency: &str, network: &str) -> String {
+    let network = network.trim().to_lowercase();
+    let currency = currency.trim().to_lowercase();
+
+    let chain = match network.as_str() {
+        "" | "mainnet" | "main" => match currency.as_str() {
+            "btc" => "bitcoin",
+            "ltc" => "litecoin",
+            "eth" => "ethereum",
+            "sol" => "solana",
+            "trx" => "tron",
+            "matic" | "pol" => "polygon",
+            "bnb" => "bsc",
+            "avax" => "avalanche",
+            "ftm" => "fantom",
+            other => return other.to_string(),
+        },
+        "erc20" | "eth" => "ethereum",
+        "bep20" | "bnb" | "binance" | "smartchain" => "bsc",
+        "trc20" | "trx" => "tron",
+        "spl" | "sol" => "solana",
+        "matic" | "pos" => "polygon",
+        "arb" | "arbitrum one" | "arbitrumone" => "arbitrum",
+        "op" | "optimistic" => "optimism",
+        "avax" | "avalanche c-chain" | "cchain" => "avalanche",
+        "btc" => "bitcoin",
+        "testnet" if currency == "btc" => "bitcoin-testnet",
+        "devnet" if currency == "sol" => "solana-devnet",
+        other => return other.replace([' ', '_'], "-"),
+    };
+    chain.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chain_key_aliases() {
+        assert_eq!(chain_key("BTC", "Mainnet"), "bitcoin");
+        assert_eq!(chain_key("USDT", "ERC20"), "ethereum");
+        assert_eq!(chain_key("USDT", "TRC20"), "tron");
+        assert_eq!(chain_key("SOL", "devnet"), "solana-devnet");
+        assert_eq!(chain_key("ETH", "Sepolia"), "sepolia");
+    }
+
+    #[test]
+    fn test_default_urls() {
+        let registry = ExplorerRegistry::with_defaults();
+        assert_eq!(
+            registry.tx_url("ethereum", "0xabc").as_deref(),
+            Some("https://etherscan.io/tx/0xabc")
+        );
+        assert_eq!(
+            registry.address_url("solana-devnet", "So1").as_deref(),
+            Some("https://solscan.io/account/So1?cluster=devnet")
+        );
+        assert!(registry.tx_url("unknown-chain", "0xabc").is_none());
+        assert!(registry.tx_url("ethereum", "").is_none());
+    }
+
+    #[test]
+    fn test_env_overrides() {
+        let registry = ExplorerRegistry::with_defaults().with_overrides(vec![
+            ("EXPLORER_ETHEREUM_TX_URL".to_string(), "https://eth.example/t/{tx}".to_string()),
+            ("EXPLORER_MY_TESTNET_TX_URL".to_string(), "https://my.example/tx/{tx}".to_string()),
+            ("EXPLORER_MY_TESTNET_ADDRESS_URL".to_string(), "https://my.example/a/{address}".to_string()),
+            ("EXPLORER_IGNORED".to_string(), "x".to_string()),
+        ]);
+
+        assert_eq!(registry.tx_url("ethereum", "1").as_deref(), Some("https://eth.example/t/1"));
+        // Address template for a known chain is kept when only tx is overridden
+        assert_eq!(registry.address_url("ethereum", "a").as_deref(), Some("https://etherscan.io/address/a"));
+        assert_eq!(registry.address_url("my-testnet", "a").as_deref(), Some("https://my.example/a/a"));
+    }
+}
@@ -0,0 +1,85 @@
+/// Small RFC 4180 writer. Cells that a spreadsheet would run as a formula
+/// are prefixed with a quote so exported addresses or memos can't inject one.
+#[derive(Debug, Default)]
+pub struct CsvWriter {
+    buf: String,
+    rows: u32,
+}
+
+impl CsvWriter {
+    pub fn new(header: &[&str]) -> Self {
+        let mut writer = Self::default();
+        writer.write_line(header.iter().map(|h| h.to_string()));
+        writer
+    }
+
+    /// Append a data row
+    pub fn row<I, S>(&mut self, cells: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.write_line(cells.into_iter().map(Into::into));
+        self.rows += 1;
+    }
+
+    /// Data rows written, excluding the header
+    pub fn rows(&self) -> u32 {
+        self.rows
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.buf.into_bytes()
+    }
+
+    fn write_line(&mut self, cells: impl Iterator<Item = String>) {
+        for (i, cell) in cells.enumerate() {
+            if i > 0 {
+                self.buf.push(',');
+            }
+            self.buf.push_str(&escape(&cell));
+        }
+        self.buf.push_str("\r\n");
+    }
+}
+
+fn escape(cell: &str) -> String {
+    let neutralized = match cell.chars().next() {
+        Some('=' | '+' | '-' | '@' | '\t' | '\r') if cell.parse::<f64>().is_err() => format!("'{}", cell),
+        _ => cell.to_string(),
+    };
+
+    if neutralized.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", neutralized.replace('"', "\"\""))
+    } else {
+        neutralized
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_writes_header_and_rows() {
+        let mut csv = CsvWriter::new(&["id", "amount"]);
+        csv.row(["a", "1.5"]);
+        csv.row(["b", "-2"]);
+        assert_eq!(csv.rows(), 2);
+        assert_eq!(String::from_utf8(csv.into_bytes()).unwrap(), "id,amount\r\na,1.5\r\nb,-2\r\n");
+    }
+
+    #[test]
+    fn test_quotes_special_characters() {
+        assert_eq!(escape("a,b"), "\"a,b\"");
+        assert_eq!(escape("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(escape("line\nbreak"), "\"line\nbreak\"");
+    }
+
+    #[test]
+    fn test_neutralizes_formulas() {
+        assert_eq!(escape("=HYPERLINK(\"x\")"), "\"'=HYPERLINK(\"\"x\"\")\"");
+        assert_eq!(escape("@SUM(A1)"), "'@SUM(A1)");
+        assert_eq!(escape("-0.25"), "-0.25");
+    }
+}
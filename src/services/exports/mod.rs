@@ -0,0 +1,29 @@
+//! Asynchronous CSV exports. `ExportWorker` picks up jobs queued through
+//! `POST /exports`, writes the file to object storage and the API hands out
+//! signed download URLs.
+
+pub mod csv;
+pub mod worker;
+
+use std::time::Duration;
+
+pub use csv::CsvWriter;
+pub use worker::{ExportWorker, ExportWorkerReport};
+
+/// Lifetime of signed download URLs (EXPORT_URL_TTL_SECS, default 15 minutes)
+pub fn url_ttl() -> Duration {
+    let secs = std::env::var("EXPORT_URL_TTL_SECS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(900);
+    Duration::from_secs(secs)
+}
+
+/// Hours a finished export stays in storage (EXPORT_RETENTION_HOURS, default 24)
+pub fn retention_hours() -> i64 {
+    std::env::var("EXPORT_RETENTION_HOURS")
+        .ok()
+        .and_then(|v| v.parse::<i64>().ok())
+        .filter(|h| *h > 0)
+        .unwrap_or(24)
+}
@@ -0,0 +1,269 @@
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, MySql, Pool};
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::csv::CsvWriter;
+use super::retention_hours;
+use crate::modules::exports::crud::{ExportCrud, ExportError};
+use crate::modules::exports::model::{ExportJob, ExportKind};
+use crate::modules::jobs::crud::JobCrud;
+use crate::services::pii::SealedString;
+use crate::services::storage::ObjectStorage;
+
+/// Jobs claimed per pass
+const BATCH_SIZE: i64 = 5;
+/// Rows per file; larger histories need a narrower date range
+const MAX_EXPORT_ROWS: i64 = 100_000;
+/// A processing job untouched this long belongs to a dead worker
+const STALE_AFTER_MINUTES: i64 = 30;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ExportWorkerReport {
+    pub completed: usize,
+    pub failed: usize,
+    pub expired: usize,
+}
+
+/// Builds queued exports, uploads them and removes files past retention
+pub struct ExportWorker {
+    db: Pool<MySql>,
+    storage: Arc<dyn ObjectStorage>,
+    interval: Duration,
+}
+
+impl ExportWorker {
+    pub fn new(db: Pool<MySql>, storage: Arc<dyn ObjectStorage>) -> Self {
+        Self {
+            db,
+            storage,
+            interval: Duration::from_secs(10),
+        }
+    }
+
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Start the background export loop
+    pub async fn run(&self) {
+        let mut interval = tokio::time::interval(self.interval);
+
+        loop {
+            interval.tick().await;
+
+            match self.process().await {
+                Ok(report) if report != ExportWorkerReport::default() => {
+                    tracing::info!("Export worker: {:?}", report);
+                }
+                Ok(_) => {}
+                Err(e) => tracing::error!("Export worker pass failed: {}", e),
+            }
+        }
+    }
+
+    /// Run one pass: build pending exports, then expire old files
+    pub async fn process(&self) -> Result<ExportWorkerReport, ExportError> {
+        let crud = ExportCrud::new(self.db.clone());
+        let mut report = ExportWorkerReport::default();
+
+        crud.requeue_stale(STALE_AFTER_MINUTES).await?;
+
+        for job in crud.claim_pending(BATCH_SIZE).await? {
+            match self.export(&job).await {
+                Ok((key, rows)) => {
+                    crud.complete(&job.id, &key, rows, retention_hours()).await?;
+                    report.completed += 1;
+                }
+                Err(e) => {
+                    tracing::warn!("Export {} failed: {}", job.id, e);
+                    crud.fail(&job.id, &e).await?;
+                    report.failed += 1;
+                }
+            }
+        }
+
+        for job in crud.get_expired(100).await? {
+            if let Some(key) = &job.object_key {
+                if let Err(e) = self.storage.delete_object(key).await {
+                    tracing::warn!("Failed to delete expired export {}: {}", job.id, e);
+                    continue;
+                }
+            }
+            crud.mark_expired(&job.id).await?;
+            report.expired += 1;
+        }
+
+        Ok(report)
+    }
+
+    /// Build the file for `job` and upload it; returns the object key and row count
+    async fn export(&self, job: &ExportJob) -> Result<(String, u32), String> {
+        let csv = match job.kind {
+            ExportKind::Swaps => self.swaps_csv(job).await,
+            ExportKind::Ledger => self.ledger_csv(job).await,
+        }
+        .map_err(|e| format!("Failed to read records: {}", e))?;
+
+        // Records are read; the upload is what's left
+        if let Err(e) = JobCrud::new(self.db.clone()).set_progress(&job.id, 50).await {
+            tracing::debug!("Failed to report progress of export {}: {}", job.id, e);
+        }
+
+        let rows = csv.rows();
+        let key = object_key(job, Utc::now());
+        self.storage
+            .put_object(&key, csv.into_bytes(), "text/csv; charset=utf-8")
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok((key, rows))
+    }
+
+    async fn swaps_csv(&self, job: &ExportJob) -> Result<CsvWriter, sqlx::Error> {
+        let rows = sqlx::query_as::<_, SwapExportRow>(
+            r#"
+            SELECT id, created_at, completed_at, CAST(status AS CHAR) as status, provider_id,
+                   from_currency, from_network, to_currency, to_network,
+                   CAST(amount AS CHAR) as amount,
+                   CAST(estimated_receive AS CHAR) as estimated_receive,
+                   CAST(actual_receive AS CHAR) as actual_receive,
+                   CAST(total_fee AS CHAR) as total_fee,
+                   deposit_address, recipient_address, tx_hash_in, tx_hash_out
+            FROM swaps
+            WHERE user_id = ?
+              AND (? IS NULL OR created_at >= ?)
+              AND (? IS NULL OR created_at < ?)
+            ORDER BY created_at ASC
+            LIMIT ?
+            "#,
+        )
+        .bind(&job.user_id)
+        .bind(job.range_from)
+        .bind(job.range_from)
+        .bind(job.range_to)
+        .bind(job.range_to)
+        .bind(MAX_EXPORT_ROWS)
+        .fetch_all(&self.db)
+        .await?;
+
+        let mut csv = CsvWriter::new(&[
+            "swap_id", "created_at", "completed_at", "status", "provider",
+            "from_currency", "from_network", "to_currency", "to_network",
+            "amount", "estimated_receive", "actual_receive", "total_fee",
+            "deposit_address", "recipient_address", "tx_hash_in", "tx_hash_out",
+        ]);
+        for r in rows {
+            csv.row([
+                r.id,
+                r.created_at.to_rfc3339(),
+                r.completed_at.map(|t| t.to_rfc3339()).unwrap_or_default(),
+                r.status,
+                r.provider_id,
+                r.from_currency,
+                r.from_network,
+                r.to_currency,
+                r.to_network,
+                r.amount,
+                r.estimated_receive,
+                r.actual_receive.unwrap_or_default(),
+                r.total_fee,
+                r.deposit_address,
+                r.recipient_address,
+                r.tx_hash_in.unwrap_or_default(),
+                r.tx_hash_out.unwrap_or_default(),
+            ]);
+        }
+        Ok(csv)
+    }
+
+    async fn ledger_csv(&self, job: &ExportJob) -> Result<CsvWriter, sqlx::Error> {
+        let rows = sqlx::query_as::<_, LedgerExportRow>(
+            r#"
+            SELECT le.id, le.created_at, ba.currency, ba.network,
+                   CAST(le.entry_type AS CHAR) as entry_type,
+                   CAST(le.amount AS CHAR) as amount,
+                   CAST(le.balance_after AS CHAR) as balance_after,
+                   le.reference_type, le.reference_id, le.memo
+            FROM ledger_entries le
+            JOIN balance_accounts ba ON ba.id = le.account_id
+            WHERE ba.user_id = ?
+              AND (? IS NULL OR le.created_at >= ?)
+              AND (? IS NULL OR le.created_at < ?)
+            ORDER BY le.id ASC
+            LIMIT ?
+            "#,
+        )
+        .bind(&job.user_id)
+        .bind(job.range_from)
+        .bind(job.range_from)
+        .bind(job.range_to)
+        .bind(job.range_to)
+        .bind(MAX_EXPORT_ROWS)
+        .fetch_all(&self.db)
+        .await?;
+
+        let mut csv = CsvWriter::new(&[
+            "entry_id", "created_at", "currency", "network", "entry_type",
+            "amount", "balance_after", "reference_type", "reference_id", "memo",
+        ]);
+        for r in rows {
+            csv.row([
+                r.id.to_string(),
+                r.created_at.to_rfc3339(),
+                r.currency,
+                r.network,
+                r.entry_type,
+                r.amount,
+                r.balance_after,
+                r.reference_type,
+                r.reference_id,
+                r.memo.unwrap_or_default(),
+            ]);
+        }
+        Ok(csv)
+    }
+}
+
+/// `exports/{job id}/{kind}-{date}.csv`; the last segment becomes the
+/// download's file name
+fn object_key(job: &ExportJob, now: DateTime<Utc>) -> String {
+    format!("exports/{}/{}-{}.csv", job.id, job.kind.as_str(), now.format("%Y%m%d"))
+}
+
+#[derive(FromRow)]
+struct SwapExportRow {
+    id: String,
+    created_at: DateTime<Utc>,
+    completed_at: Option<DateTime<Utc>>,
+    status: String,
+    provider_id: String,
+    from_currency: String,
+    from_network: String,
+    to_currency: String,
+    to_network: String,
+    amount: String,
+    estimated_receive: String,
+    actual_receive: Option<String>,
+    total_fee: String,
+    deposit_address: String,
+    #[sqlx(try_from = "SealedString")]
+    recipient_address: String,
+    tx_hash_in: Option<String>,
+    tx_hash_out: Option<String>,
+}
+
+#[derive(FromRow)]
+struct LedgerExportRow {
+    id: i64,
+    created_at: DateTime<Utc>,
+    currency: String,
+    network: String,
+    entry_type: String,
+    amount: String,
+    balance_after: String,
+    reference_type: String,
+    reference_id: String,
+    memo: Option<String>,
+}
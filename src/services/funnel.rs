@@ -0,0 +1,424 @@
+//! Quote → create → deposit → complete funnel. Each swap journey is one
+//! `swap_funnel` row stamped as it reaches each stage, so the admin report
+//! and the Prometheus counters show where users drop off per pair and
+//! provider.
+//!
+//! A journey is abandoned at the last stage it reached: a quote no swap was
+//! created from within FUNNEL_QUOTE_TTL_MINUTES, a swap that expired or
+//! failed unfunded, or a funded swap that failed, expired or was refunded.
+//! Every stamp is a guarded update, so replays and several instances never
+//! count a stage twice.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{MySql, Pool};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use crate::modules::swap::schema::SwapStatus;
+use crate::services::events::{DomainEnvelope, DomainEvent, DomainSubscriber};
+use crate::services::liquidity::PairKey;
+use crate::services::metrics::collectors::FunnelMetricsCollector;
+
+/// Quotes marked abandoned per sweep
+const SWEEP_BATCH: i64 = 500;
+
+/// Provider label of journeys that never chose one
+const NO_PROVIDER: &str = "none";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FunnelStage {
+    Quoted,
+    Created,
+    Deposited,
+    Completed,
+}
+
+impl FunnelStage {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FunnelStage::Quoted => "quoted",
+            FunnelStage::Created => "created",
+            FunnelStage::Deposited => "deposited",
+            FunnelStage::Completed => "completed",
+        }
+    }
+
+    /// Stage a swap in `status` has reached, when the status says so
+    pub fn reached_by(status: &SwapStatus) -> Option<FunnelStage> {
+        match status {
+            SwapStatus::Confirming | SwapStatus::Exchanging | SwapStatus::Sending | SwapStatus::FundsReceived => {
+                Some(FunnelStage::Deposited)
+            }
+            SwapStatus::Completed => Some(FunnelStage::Completed),
+            _ => None,
+        }
+    }
+}
+
+/// Statuses that end a journey short of completion
+fn ends_journey(status: &SwapStatus) -> bool {
+    matches!(status, SwapStatus::Failed | SwapStatus::Expired | SwapStatus::Refunded)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FunnelPolicy {
+    /// How long a quote may go unused before it counts as abandoned
+    pub quote_ttl: Duration,
+    pub interval: Duration,
+}
+
+impl Default for FunnelPolicy {
+    fn default() -> Self {
+        Self { quote_ttl: Duration::from_secs(30 * 60), interval: Duration::from_secs(300) }
+    }
+}
+
+impl FunnelPolicy {
+    pub fn from_env() -> Result<Self, String> {
+        let mut policy = Self::default();
+
+        if let Ok(val) = std::env::var("FUNNEL_QUOTE_TTL_MINUTES") {
+            let minutes: u64 = val.trim().parse().map_err(|e| format!("Invalid FUNNEL_QUOTE_TTL_MINUTES: {}", e))?;
+            if minutes == 0 {
+                return Err("FUNNEL_QUOTE_TTL_MINUTES must be at least 1".to_string());
+            }
+            policy.quote_ttl = Duration::from_secs(minutes * 60);
+        }
+        if let Ok(val) = std::env::var("FUNNEL_SWEEP_INTERVAL_SECS") {
+            let secs: u64 = val.trim().parse().map_err(|e| format!("Invalid FUNNEL_SWEEP_INTERVAL_SECS: {}", e))?;
+            if secs == 0 {
+                return Err("FUNNEL_SWEEP_INTERVAL_SECS must be at least 1".to_string());
+            }
+            policy.interval = Duration::from_secs(secs);
+        }
+
+        Ok(policy)
+    }
+
+    pub fn global() -> &'static FunnelPolicy {
+        static POLICY: OnceLock<FunnelPolicy> = OnceLock::new();
+        POLICY.get_or_init(|| {
+            Self::from_env().unwrap_or_else(|e| {
+                tracing::warn!("Ignoring invalid funnel config: {}", e);
+                Self::default()
+            })
+        })
+    }
+}
+
+/// Labels and progress of one journey
+#[derive(Debug, Clone, sqlx::FromRow)]
+struct Journey {
+    id: i64,
+    from_currency: String,
+    to_currency: String,
+    provider: Option<String>,
+    deposited_at: Option<DateTime<Utc>>,
+    completed_at: Option<DateTime<Utc>>,
+    abandoned_stage: Option<String>,
+}
+
+impl Journey {
+    fn provider(&self) -> &str {
+        self.provider.as_deref().unwrap_or(NO_PROVIDER)
+    }
+}
+
+pub struct SwapFunnel {
+    pool: Pool<MySql>,
+    metrics: Option<FunnelMetricsCollector>,
+}
+
+impl SwapFunnel {
+    pub fn new(pool: Pool<MySql>) -> Self {
+        Self { pool, metrics: FunnelMetricsCollector::global() }
+    }
+
+    /// A quote was served. Cached rates are served under the trade id they
+    /// were fetched with, so a journey starts at its first serving.
+    pub async fn record_quote(&self, trade_id: &str, pair: &PairKey) -> Result<(), sqlx::Error> {
+        if trade_id.is_empty() {
+            return Ok(());
+        }
+        let (from, to) = (pair.from.to_lowercase(), pair.to.to_lowercase());
+        let result = sqlx::query(
+            r#"
+            INSERT IGNORE INTO swap_funnel (trade_id, from_currency, from_network, to_currency, to_network, quoted_at)
+            VALUES (?, ?, ?, ?, ?, NOW())
+            "#,
+        )
+        .bind(trade_id)
+        .bind(&from)
+        .bind(&pair.network_from)
+        .bind(&to)
+        .bind(&pair.network_to)
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() > 0 {
+            if let Some(metrics) = &self.metrics {
+                metrics.record_stage(FunnelStage::Quoted.as_str(), &from, &to, NO_PROVIDER, None);
+            }
+        }
+        Ok(())
+    }
+
+    /// A swap was created, from a quote when `trade_id` is one we served
+    #[allow(clippy::too_many_arguments)]
+    pub async fn record_created(
+        &self,
+        swap_id: &str,
+        trade_id: Option<&str>,
+        from: &str,
+        network_from: &str,
+        to: &str,
+        network_to: &str,
+        provider: &str,
+    ) -> Result<(), sqlx::Error> {
+        let (from, to, provider) = (from.to_lowercase(), to.to_lowercase(), provider.to_lowercase());
+
+        // The quote's journey goes on, even if the sweep already gave up on it
+        let mut step_secs = None;
+        if let Some(trade_id) = trade_id.filter(|t| !t.is_empty()) {
+            let result = sqlx::query(
+                r#"
+                UPDATE swap_funnel
+                SET swap_id = ?, provider = ?, created_at = NOW(), abandoned_stage = NULL, abandoned_at = NULL
+                WHERE trade_id = ? AND swap_id IS NULL
+                "#,
+            )
+            .bind(swap_id)
+            .bind(&provider)
+            .bind(trade_id)
+            .execute(&self.pool)
+            .await?;
+            if result.rows_affected() > 0 {
+                let (secs,): (Option<i64>,) = sqlx::query_as(
+                    "SELECT TIMESTAMPDIFF(SECOND, quoted_at, created_at) FROM swap_funnel WHERE swap_id = ?",
+                )
+                .bind(swap_id)
+                .fetch_one(&self.pool)
+                .await?;
+                step_secs = Some(secs.map(|s| s as f64));
+            }
+        }
+
+        if step_secs.is_none() {
+            let result = sqlx::query(
+                r#"
+                INSERT IGNORE INTO swap_funnel (
+                    swap_id, from_currency, from_network, to_currency, to_network, provider, created_at
+                ) VALUES (?, ?, ?, ?, ?, ?, NOW())
+                "#,
+            )
+            .bind(swap_id)
+            .bind(&from)
+            .bind(network_from)
+            .bind(&to)
+            .bind(network_to)
+            .bind(&provider)
+            .execute(&self.pool)
+            .await?;
+            if result.rows_affected() == 0 {
+                return Ok(());
+            }
+        }
+
+        if let Some(metrics) = &self.metrics {
+            metrics.record_stage(FunnelStage::Created.as_str(), &from, &to, &provider, step_secs.flatten());
+        }
+        Ok(())
+    }
+
+    /// A swap moved to `status`
+    pub async fn record_status(&self, swap_id: &str, status: &SwapStatus) -> Result<(), sqlx::Error> {
+        let journey: Option<Journey> = sqlx::query_as(
+            r#"
+            SELECT id, from_currency, to_currency, provider, deposited_at, completed_at,
+                   CAST(abandoned_stage AS CHAR) AS abandoned_stage
+            FROM swap_funnel
+            WHERE swap_id = ?
+            "#,
+        )
+        .bind(swap_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        // Swaps created before the funnel was tracked
+        let Some(journey) = journey else { return Ok(()) };
+
+        if let Some(stage) = FunnelStage::reached_by(status) {
+            if journey.deposited_at.is_none() {
+                self.stamp(&journey, FunnelStage::Deposited, "deposited_at", "created_at").await?;
+            }
+            if stage == FunnelStage::Completed && journey.completed_at.is_none() {
+                self.stamp(&journey, FunnelStage::Completed, "completed_at", "deposited_at").await?;
+            }
+        } else if ends_journey(status) && journey.completed_at.is_none() && journey.abandoned_stage.is_none() {
+            let stage = if journey.deposited_at.is_some() { FunnelStage::Deposited } else { FunnelStage::Created };
+            let result = sqlx::query(
+                r#"
+                UPDATE swap_funnel
+                SET abandoned_stage = ?, abandoned_at = NOW()
+                WHERE id = ? AND completed_at IS NULL AND abandoned_stage IS NULL
+                "#,
+            )
+            .bind(stage.as_str())
+            .bind(journey.id)
+            .execute(&self.pool)
+            .await?;
+            if result.rows_affected() > 0 {
+                if let Some(metrics) = &self.metrics {
+                    metrics.record_abandoned(stage.as_str(), &journey.from_currency, &journey.to_currency, journey.provider());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Set `column` to now unless already set, counting the stage once
+    async fn stamp(
+        &self,
+        journey: &Journey,
+        stage: FunnelStage,
+        column: &'static str,
+        previous: &'static str,
+    ) -> Result<(), sqlx::Error> {
+        let sql = format!("UPDATE swap_funnel SET {column} = NOW() WHERE id = ? AND {column} IS NULL");
+        let result = sqlx::query(&sql).bind(journey.id).execute(&self.pool).await?;
+        if result.rows_affected() == 0 {
+            return Ok(());
+        }
+
+        if let Some(metrics) = &self.metrics {
+            let sql = format!("SELECT TIMESTAMPDIFF(SECOND, {previous}, {column}) FROM swap_funnel WHERE id = ?");
+            let (secs,): (Option<i64>,) = sqlx::query_as(&sql).bind(journey.id).fetch_one(&self.pool).await?;
+            metrics.record_stage(
+                stage.as_str(),
+                &journey.from_currency,
+                &journey.to_currency,
+                journey.provider(),
+                secs.map(|s| s as f64),
+            );
+        }
+        Ok(())
+    }
+
+    /// Mark quotes unused for longer than `ttl` as abandoned; returns how
+    /// many were
+    pub async fn sweep_quotes(&self, ttl: Duration) -> Result<u64, sqlx::Error> {
+        let stale: Vec<(i64, String, String)> = sqlx::query_as(
+            r#"
+            SELECT id, from_currency, to_currency
+            FROM swap_funnel
+            WHERE created_at IS NULL AND abandoned_stage IS NULL
+              AND quoted_at < NOW() - INTERVAL ? SECOND
+            ORDER BY quoted_at
+            LIMIT ?
+            "#,
+        )
+        .bind(ttl.as_secs())
+        .bind(SWEEP_BATCH)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut abandoned = 0;
+        for (id, from, to) in stale {
+            let result = sqlx::query(
+                r#"
+                UPDATE swap_funnel
+                SET abandoned_stage = 'quoted', abandoned_at = NOW()
+                WHERE id = ? AND created_at IS NULL AND abandoned_stage IS NULL
+                "#,
+            )
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+            if result.rows_affected() > 0 {
+                abandoned += 1;
+                if let Some(metrics) = &self.metrics {
+                    metrics.record_abandoned(FunnelStage::Quoted.as_str(), &from, &to, NO_PROVIDER);
+                }
+            }
+        }
+        Ok(abandoned)
+    }
+}
+
+/// Follows created swaps through the funnel
+pub struct FunnelSubscriber {
+    funnel: SwapFunnel,
+}
+
+impl FunnelSubscriber {
+    pub fn new(pool: Pool<MySql>) -> Self {
+        Self { funnel: SwapFunnel::new(pool) }
+    }
+}
+
+#[async_trait]
+impl DomainSubscriber for FunnelSubscriber {
+    fn name(&self) -> &'static str {
+        "funnel"
+    }
+
+    async fn handle(&self, envelope: &DomainEnvelope) {
+        let result = match &envelope.event {
+            DomainEvent::SwapCreated { swap_id, trade_id, provider, from, network_from, to, network_to, .. } => {
+                self.funnel
+                    .record_created(swap_id, trade_id.as_deref(), from, network_from, to, network_to, provider)
+                    .await
+            }
+            DomainEvent::SwapStatusChanged { swap_id, to, .. } => self.funnel.record_status(swap_id, to).await,
+            _ => return,
+        };
+        if let Err(e) = result {
+            tracing::warn!("Failed to record swap {} in the funnel: {}", envelope.event.swap_id(), e);
+        }
+    }
+}
+
+/// Gives up on quotes nobody created a swap from
+pub struct FunnelSweeper {
+    funnel: SwapFunnel,
+    policy: FunnelPolicy,
+}
+
+impl FunnelSweeper {
+    pub fn new(pool: Pool<MySql>, policy: FunnelPolicy) -> Self {
+        Self { funnel: SwapFunnel::new(pool), policy }
+    }
+
+    /// Start the background sweep loop
+    pub async fn run(&self) {
+        let mut interval = tokio::time::interval(self.policy.interval);
+
+        loop {
+            interval.tick().await;
+
+            match self.funnel.sweep_quotes(self.policy.quote_ttl).await {
+                Ok(0) => {}
+                Ok(abandoned) => tracing::debug!("Funnel sweep: {} quotes abandoned", abandoned),
+                Err(e) => tracing::error!("Funnel sweep failed: {}", e),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stage_reached_by_status() {
+        assert_eq!(FunnelStage::reached_by(&SwapStatus::Waiting), None);
+        assert_eq!(FunnelStage::reached_by(&SwapStatus::Confirming), Some(FunnelStage::Deposited));
+        assert_eq!(FunnelStage::reached_by(&SwapStatus::FundsReceived), Some(FunnelStage::Deposited));
+        assert_eq!(FunnelStage::reached_by(&SwapStatus::Completed), Some(FunnelStage::Completed));
+        assert_eq!(FunnelStage::reached_by(&SwapStatus::Refunded), None);
+
+        assert!(ends_journey(&SwapStatus::Expired));
+        assert!(ends_journey(&SwapStatus::Refunded));
+        assert!(!ends_journey(&SwapStatus::Sending));
+    }
+}
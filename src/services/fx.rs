@@ -0,0 +1,698 @@
+//! Fiat exchange rates for reporting. Revenue and promotion reports are
+//! valued in USD; operators who book in another currency set
+//! REPORTING_CURRENCY and the reports convert each amount at the rate of the
+//! day it was earned. Rates are snapshotted daily into `fx_rates` from an
+//! ECB-backed API (Frankfurter by default), and `fx_backfill` fills in
+//! history.
+//!
+//! Crypto amounts are first priced in USD at that day's price. Prices are
+//! snapshotted into `crypto_prices` alongside the fiat rates, from a
+//! CoinGecko-compatible API, for the native coins we swap and every active
+//! token with a `coingecko_id`.
+
+use chrono::{DateTime, Duration as ChronoDuration, NaiveDate, Utc};
+use sqlx::{MySql, Pool};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::OnceLock;
+
+use crate::services::amount::{self, Decimal};
+use crate::services::reconciliation::provider::until_next_run;
+
+/// Currency every rate is quoted against
+pub const BASE_CURRENCY: &str = "USD";
+
+const DEFAULT_API_URL: &str = "https://api.frankfurter.app";
+const SOURCE: &str = "frankfurter";
+/// ECB reference rates are published around 16:00 CET
+const DEFAULT_SNAPSHOT_HOUR_UTC: u32 = 16;
+/// Days requested per backfill call
+const BACKFILL_CHUNK_DAYS: i64 = 366;
+
+const DEFAULT_PRICE_API_URL: &str = "https://api.coingecko.com/api/v3";
+const PRICE_SOURCE: &str = "coingecko";
+/// CoinGecko ids of native coins and the stablecoins every chain carries;
+/// other tokens use `tokens.coingecko_id`
+const DEFAULT_PRICE_IDS: &[(&str, &str)] = &[
+    ("btc", "bitcoin"),
+    ("eth", "ethereum"),
+    ("xmr", "monero"),
+    ("sol", "solana"),
+    ("ltc", "litecoin"),
+    ("bnb", "binancecoin"),
+    ("trx", "tron"),
+    ("usdt", "tether"),
+    ("usdc", "usd-coin"),
+    ("dai", "dai"),
+];
+
+#[derive(Debug, thiserror::Error)]
+pub enum FxError {
+    #[error("Unsupported reporting currency: {0}")]
+    InvalidCurrency(String),
+
+    #[error("FX rate request failed: {0}")]
+    Request(String),
+
+    #[error("Unreadable FX rate response: {0}")]
+    InvalidResponse(String),
+
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+impl FxError {
+    pub fn status_code(&self) -> axum::http::StatusCode {
+        match self {
+            FxError::InvalidCurrency(_) => axum::http::StatusCode::BAD_REQUEST,
+            FxError::Request(_) | FxError::InvalidResponse(_) => axum::http::StatusCode::BAD_GATEWAY,
+            FxError::Database(_) => axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+/// Upper-case ISO 4217 code, or an error for anything that isn't three letters
+pub fn parse_currency(code: &str) -> Result<String, FxError> {
+    let code = code.trim().to_ascii_uppercase();
+    if code.len() != 3 || !code.chars().all(|c| c.is_ascii_alphabetic()) {
+        return Err(FxError::InvalidCurrency(code));
+    }
+    Ok(code)
+}
+
+/// REPORTING_CURRENCY, USD when unset or invalid
+pub fn default_reporting_currency() -> &'static str {
+    static CURRENCY: OnceLock<String> = OnceLock::new();
+    CURRENCY.get_or_init(|| match std::env::var("REPORTING_CURRENCY") {
+        Ok(code) => parse_currency(&code).unwrap_or_else(|e| {
+            tracing::warn!("{}; reporting in {}", e, BASE_CURRENCY);
+            BASE_CURRENCY.to_string()
+        }),
+        Err(_) => BASE_CURRENCY.to_string(),
+    })
+}
+
+/// Currency a report asked for, or the configured default
+pub fn reporting_currency(requested: Option<&str>) -> Result<String, FxError> {
+    match requested {
+        Some(code) => parse_currency(code),
+        None => Ok(default_reporting_currency().to_string()),
+    }
+}
+
+/// One day's rate for one currency
+#[derive(Debug, Clone, PartialEq, sqlx::FromRow)]
+pub struct FxRate {
+    pub currency: String,
+    pub rate_date: NaiveDate,
+    /// Units of `currency` per 1 USD
+    pub per_usd: Decimal,
+    pub source: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// One day's USD price of one crypto asset
+#[derive(Debug, Clone, PartialEq, sqlx::FromRow)]
+pub struct CryptoPrice {
+    pub ticker: String,
+    pub price_date: NaiveDate,
+    pub usd_price: Decimal,
+    pub source: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+// =============================================================================
+// RATE TABLE
+// =============================================================================
+
+/// Rates for one reporting currency, and USD prices of each asset, over a
+/// report's window
+#[derive(Debug, Clone)]
+pub struct FxRateTable {
+    currency: String,
+    rates: BTreeMap<NaiveDate, Decimal>,
+    prices: HashMap<String, BTreeMap<NaiveDate, Decimal>>,
+}
+
+impl FxRateTable {
+    pub fn new(currency: &str, rates: impl IntoIterator<Item = (NaiveDate, Decimal)>) -> Self {
+        Self { currency: currency.to_string(), rates: rates.into_iter().collect(), prices: HashMap::new() }
+    }
+
+    /// Add daily USD prices, as (ticker, date, price)
+    pub fn with_prices(mut self, prices: impl IntoIterator<Item = (String, NaiveDate, Decimal)>) -> Self {
+        for (ticker, date, price) in prices {
+            self.prices.entry(ticker.to_ascii_lowercase()).or_default().insert(date, price);
+        }
+        self
+    }
+
+    pub fn currency(&self) -> &str {
+        &self.currency
+    }
+
+    /// Units of the reporting currency per USD on `date`: the latest snapshot
+    /// on or before it. None before the first snapshot.
+    pub fn rate_on(&self, date: NaiveDate) -> Option<Decimal> {
+        if self.currency == BASE_CURRENCY {
+            return Some(Decimal::ONE);
+        }
+        self.rates.range(..=date).next_back().map(|(_, rate)| *rate)
+    }
+
+    /// USD price of `ticker` on `date`: the latest snapshot on or before it
+    pub fn usd_price_on(&self, ticker: &str, date: NaiveDate) -> Option<Decimal> {
+        let prices = self.prices.get(&ticker.to_ascii_lowercase())?;
+        prices.range(..=date).next_back().map(|(_, price)| *price)
+    }
+
+    /// `amount` of the crypto asset `ticker`, earned on `date`, in the
+    /// reporting currency. None when either the price or the rate is missing.
+    pub fn value(&self, amount: Decimal, ticker: &str, date: NaiveDate) -> Option<Decimal> {
+        Some(amount * self.usd_price_on(ticker, date)? * self.rate_on(date)?)
+    }
+}
+
+/// Reporting-currency amounts are shown to the cent
+pub fn format_value(value: Decimal) -> String {
+    value.round_dp(2).to_string()
+}
+
+// =============================================================================
+// STORE
+// =============================================================================
+
+pub struct FxStore {
+    pool: Pool<MySql>,
+}
+
+impl FxStore {
+    pub fn new(pool: Pool<MySql>) -> Self {
+        Self { pool }
+    }
+
+    /// Insert or correct rates; returns how many rows changed
+    pub async fn upsert(&self, rates: &[(String, NaiveDate, Decimal)], source: &str) -> Result<u64, FxError> {
+        let mut changed = 0;
+        for (currency, date, per_usd) in rates {
+            let result = sqlx::query(
+                r#"
+                INSERT INTO fx_rates (currency, rate_date, per_usd, source)
+                VALUES (?, ?, ?, ?)
+                ON DUPLICATE KEY UPDATE per_usd = VALUES(per_usd), source = VALUES(source)
+                "#,
+            )
+            .bind(currency)
+            .bind(date)
+            .bind(per_usd)
+            .bind(source)
+            .execute(&self.pool)
+            .await?;
+            changed += result.rows_affected().min(1);
+        }
+        Ok(changed)
+    }
+
+    /// Insert or correct crypto prices; returns how many rows changed
+    pub async fn upsert_prices(&self, prices: &[(String, NaiveDate, Decimal)], source: &str) -> Result<u64, FxError> {
+        let mut changed = 0;
+        for (ticker, date, usd_price) in prices {
+            let result = sqlx::query(
+                r#"
+                INSERT INTO crypto_prices (ticker, price_date, usd_price, source)
+                VALUES (?, ?, ?, ?)
+                ON DUPLICATE KEY UPDATE usd_price = VALUES(usd_price), source = VALUES(source)
+                "#,
+            )
+            .bind(ticker)
+            .bind(date)
+            .bind(usd_price)
+            .bind(source)
+            .execute(&self.pool)
+            .await?;
+            changed += result.rows_affected().min(1);
+        }
+        Ok(changed)
+    }
+
+    /// Rates of `currency` and prices of every asset covering `from` to
+    /// `to`, each including the last snapshot before `from` so the window's
+    /// first days are covered
+    pub async fn table(&self, currency: &str, from: NaiveDate, to: NaiveDate) -> Result<FxRateTable, FxError> {
+        let prices: Vec<(String, NaiveDate, Decimal)> = sqlx::query_as(
+            r#"
+            SELECT p.ticker, p.price_date, p.usd_price FROM crypto_prices p
+            WHERE p.price_date BETWEEN
+                COALESCE((SELECT MAX(e.price_date) FROM crypto_prices e WHERE e.ticker = p.ticker AND e.price_date <= ?), ?) AND ?
+            "#,
+        )
+        .bind(from)
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.pool)
+        .await?;
+
+        if currency == BASE_CURRENCY {
+            return Ok(FxRateTable::new(currency, []).with_prices(prices));
+        }
+
+        let rates: Vec<(NaiveDate, Decimal)> = sqlx::query_as(
+            r#"
+            SELECT rate_date, per_usd FROM fx_rates
+            WHERE currency = ? AND rate_date BETWEEN
+                COALESCE((SELECT MAX(rate_date) FROM fx_rates WHERE currency = ? AND rate_date <= ?), ?) AND ?
+            "#,
+        )
+        .bind(currency)
+        .bind(currency)
+        .bind(from)
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(FxRateTable::new(currency, rates).with_prices(prices))
+    }
+
+    /// Most recent stored USD price of `ticker`
+    pub async fn latest_price(&self, ticker: &str) -> Result<Option<CryptoPrice>, FxError> {
+        let price = sqlx::query_as::<_, CryptoPrice>(
+            r#"
+            SELECT ticker, price_date, usd_price, source, created_at, updated_at FROM crypto_prices
+            WHERE ticker = ?
+            ORDER BY price_date DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(ticker.to_ascii_lowercase())
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(price)
+    }
+
+    /// Assets to price: the defaults, then active tokens with a CoinGecko
+    /// id, as (ticker, CoinGecko id)
+    pub async fn price_ids(&self) -> Result<Vec<(String, String)>, FxError> {
+        let tokens: Vec<(String, String)> = sqlx::query_as(
+            "SELECT LOWER(symbol), coingecko_id FROM tokens WHERE is_active = TRUE AND coingecko_id IS NOT NULL",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut ids: Vec<(String, String)> =
+            DEFAULT_PRICE_IDS.iter().map(|(ticker, id)| (ticker.to_string(), id.to_string())).collect();
+        for (ticker, id) in tokens {
+            if !ids.iter().any(|(known, _)| *known == ticker) {
+                ids.push((ticker, id));
+            }
+        }
+        Ok(ids)
+    }
+
+    /// Snapshots of `currency` between two days, oldest first
+    pub async fn list(&self, currency: &str, from: NaiveDate, to: NaiveDate) -> Result<Vec<FxRate>, FxError> {
+        let rates = sqlx::query_as::<_, FxRate>(
+            r#"
+            SELECT currency, rate_date, per_usd, source, created_at, updated_at FROM fx_rates
+            WHERE currency = ? AND rate_date BETWEEN ? AND ?
+            ORDER BY rate_date
+            "#,
+        )
+        .bind(currency)
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rates)
+    }
+}
+
+// =============================================================================
+// RATE SOURCE
+// =============================================================================
+
+#[derive(Debug, serde::Deserialize)]
+struct LatestResponse {
+    date: NaiveDate,
+    rates: HashMap<String, f64>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct RangeResponse {
+    #[serde(default)]
+    rates: BTreeMap<NaiveDate, HashMap<String, f64>>,
+}
+
+/// Client for a Frankfurter-compatible API (ECB reference rates)
+#[derive(Clone)]
+pub struct FxClient {
+    http: reqwest::Client,
+    api_url: String,
+}
+
+impl FxClient {
+    pub fn new(http: reqwest::Client, api_url: &str) -> Self {
+        Self { http, api_url: api_url.trim_end_matches('/').to_string() }
+    }
+
+    /// Most recent published rates for `currencies`
+    pub async fn latest(&self, currencies: &[String]) -> Result<Vec<(String, NaiveDate, Decimal)>, FxError> {
+        let url = format!("{}/latest", self.api_url);
+        let response: LatestResponse = self.get(&url, currencies).await?;
+        rows(response.date, response.rates)
+    }
+
+    /// Every published day between `from` and `to`
+    pub async fn range(
+        &self,
+        currencies: &[String],
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<(String, NaiveDate, Decimal)>, FxError> {
+        let url = format!("{}/{}..{}", self.api_url, from, to);
+        let response: RangeResponse = self.get(&url, currencies).await?;
+        let mut all = Vec::new();
+        for (date, rates) in response.rates {
+            all.extend(rows(date, rates)?);
+        }
+        Ok(all)
+    }
+
+    async fn get<T: serde::de::DeserializeOwned>(&self, url: &str, currencies: &[String]) -> Result<T, FxError> {
+        let response = self
+            .http
+            .get(url)
+            .query(&[("from", BASE_CURRENCY.to_string()), ("to", currencies.join(","))])
+            .send()
+            .await
+            .map_err(|e| FxError::Request(e.to_string()))?;
+        if !response.status().is_success() {
+            return Err(FxError::Request(format!("{} returned {}", url, response.status())));
+        }
+        response.json().await.map_err(|e| FxError::InvalidResponse(e.to_string()))
+    }
+}
+
+fn rows(date: NaiveDate, rates: HashMap<String, f64>) -> Result<Vec<(String, NaiveDate, Decimal)>, FxError> {
+    rates
+        .into_iter()
+        .map(|(currency, rate)| {
+            let per_usd = amount::from_f64(rate)
+                .ok()
+                .filter(|r| *r > Decimal::ZERO)
+                .ok_or_else(|| FxError::InvalidResponse(format!("{} rate {} on {}", currency, rate, date)))?;
+            Ok((currency.to_ascii_uppercase(), date, per_usd))
+        })
+        .collect()
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct MarketChartResponse {
+    #[serde(default)]
+    prices: Vec<(i64, f64)>,
+}
+
+/// Client for a CoinGecko-compatible API (USD prices of crypto assets)
+#[derive(Clone)]
+pub struct PriceClient {
+    http: reqwest::Client,
+    api_url: String,
+    api_key: Option<String>,
+}
+
+impl PriceClient {
+    pub fn new(http: reqwest::Client, api_url: &str, api_key: Option<String>) -> Self {
+        Self { http, api_url: api_url.trim_end_matches('/').to_string(), api_key }
+    }
+
+    /// Current USD price of each (ticker, CoinGecko id), dated today
+    pub async fn latest(&self, ids: &[(String, String)]) -> Result<Vec<(String, NaiveDate, Decimal)>, FxError> {
+        let url = format!("{}/simple/price", self.api_url);
+        let joined = ids.iter().map(|(_, id)| id.as_str()).collect::<Vec<_>>().join(",");
+        let response: HashMap<String, HashMap<String, f64>> =
+            self.get(&url, &[("ids", joined), ("vs_currencies", "usd".to_string())]).await?;
+
+        let today = Utc::now().date_naive();
+        let mut prices = Vec::new();
+        for (ticker, id) in ids {
+            match response.get(id).and_then(|quote| quote.get("usd")) {
+                Some(price) => prices.push((ticker.clone(), today, usd_price(ticker, *price, today)?)),
+                None => tracing::warn!("No USD price for {} ({})", ticker, id),
+            }
+        }
+        Ok(prices)
+    }
+
+    /// Daily closing USD price of one asset between `from` and `to`
+    pub async fn range(
+        &self,
+        ticker: &str,
+        id: &str,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<(String, NaiveDate, Decimal)>, FxError> {
+        let url = format!("{}/coins/{}/market_chart/range", self.api_url, id);
+        let start = from.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc().timestamp();
+        let end = (to + ChronoDuration::days(1)).and_hms_opt(0, 0, 0).unwrap_or_default().and_utc().timestamp();
+        let response: MarketChartResponse = self
+            .get(&url, &[("vs_currency", "usd".to_string()), ("from", start.to_string()), ("to", end.to_string())])
+            .await?;
+
+        daily_closes(&response.prices)
+            .into_iter()
+            .filter(|(date, _)| *date >= from && *date <= to)
+            .map(|(date, price)| Ok((ticker.to_string(), date, usd_price(ticker, price, date)?)))
+            .collect()
+    }
+
+    async fn get<T: serde::de::DeserializeOwned>(&self, url: &str, query: &[(&str, String)]) -> Result<T, FxError> {
+        let mut request = self.http.get(url).query(query);
+        if let Some(key) = &self.api_key {
+            // Paid plans are served from pro-api.coingecko.com
+            let header = if self.api_url.contains("pro-api") { "x-cg-pro-api-key" } else { "x-cg-demo-api-key" };
+            request = request.header(header, key);
+        }
+        let response = request.send().await.map_err(|e| FxError::Request(e.to_string()))?;
+        if !response.status().is_success() {
+            return Err(FxError::Request(format!("{} returned {}", url, response.status())));
+        }
+        response.json().await.map_err(|e| FxError::InvalidResponse(e.to_string()))
+    }
+}
+
+fn usd_price(ticker: &str, price: f64, date: NaiveDate) -> Result<Decimal, FxError> {
+    amount::from_f64(price)
+        .ok()
+        .filter(|p| *p > Decimal::ZERO)
+        .ok_or_else(|| FxError::InvalidResponse(format!("{} price {} on {}", ticker, price, date)))
+}
+
+/// Last price of each UTC day from `[millis, price]` points
+fn daily_closes(points: &[(i64, f64)]) -> BTreeMap<NaiveDate, f64> {
+    let mut closes = BTreeMap::new();
+    let mut sorted: Vec<_> = points.to_vec();
+    sorted.sort_by_key(|(millis, _)| *millis);
+    for (millis, price) in sorted {
+        if let Some(at) = DateTime::from_timestamp_millis(millis) {
+            closes.insert(at.date_naive(), price);
+        }
+    }
+    closes
+}
+
+// =============================================================================
+// SNAPSHOTS
+// =============================================================================
+
+/// Settings from FX_API_URL, FX_CURRENCIES, FX_SNAPSHOT_HOUR,
+/// CRYPTO_PRICE_API_URL and CRYPTO_PRICE_API_KEY
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FxConfig {
+    pub api_url: String,
+    /// Currencies snapshotted daily: FX_CURRENCIES plus the reporting
+    /// currency, without USD
+    pub currencies: Vec<String>,
+    pub snapshot_hour: u32,
+    pub price_api_url: String,
+    pub price_api_key: Option<String>,
+}
+
+impl FxConfig {
+    pub fn from_env() -> Result<Self, FxError> {
+        let api_url = std::env::var("FX_API_URL").unwrap_or_else(|_| DEFAULT_API_URL.to_string());
+
+        let mut currencies = Vec::new();
+        let listed = std::env::var("FX_CURRENCIES").unwrap_or_default();
+        for code in listed.split(',').filter(|c| !c.trim().is_empty()) {
+            currencies.push(parse_currency(code)?);
+        }
+        currencies.push(default_reporting_currency().to_string());
+        currencies.retain(|c| c != BASE_CURRENCY);
+        currencies.sort();
+        currencies.dedup();
+
+        let snapshot_hour = std::env::var("FX_SNAPSHOT_HOUR")
+            .ok()
+            .and_then(|v| v.parse::<u32>().ok())
+            .filter(|h| *h < 24)
+            .unwrap_or(DEFAULT_SNAPSHOT_HOUR_UTC);
+
+        let price_api_url =
+            std::env::var("CRYPTO_PRICE_API_URL").unwrap_or_else(|_| DEFAULT_PRICE_API_URL.to_string());
+        let price_api_key = std::env::var("CRYPTO_PRICE_API_KEY").ok().filter(|k| !k.is_empty());
+
+        Ok(Self { api_url, currencies, snapshot_hour, price_api_url, price_api_key })
+    }
+}
+
+/// Takes the daily rate and price snapshot and backfills history
+pub struct FxSnapshotter {
+    store: FxStore,
+    client: FxClient,
+    prices: PriceClient,
+    config: FxConfig,
+}
+
+impl FxSnapshotter {
+    pub fn new(db: Pool<MySql>, http: reqwest::Client, config: FxConfig) -> Self {
+        let client = FxClient::new(http.clone(), &config.api_url);
+        let prices = PriceClient::new(http, &config.price_api_url, config.price_api_key.clone());
+        Self { store: FxStore::new(db), client, prices, config }
+    }
+
+    pub fn currencies(&self) -> &[String] {
+        &self.config.currencies
+    }
+
+    /// Snapshot on start, then daily at the configured hour
+    pub async fn run(&self) {
+        loop {
+            match self.snapshot().await {
+                Ok(changed) => tracing::info!("FX snapshot: {} rates and prices", changed),
+                Err(e) => tracing::error!("FX snapshot failed: {}", e),
+            }
+            tokio::time::sleep(until_next_run(Utc::now(), self.config.snapshot_hour)).await;
+        }
+    }
+
+    /// Store the latest published rates and crypto prices
+    pub async fn snapshot(&self) -> Result<u64, FxError> {
+        let mut changed = 0;
+        if !self.config.currencies.is_empty() {
+            let rates = self.client.latest(&self.config.currencies).await?;
+            changed += self.store.upsert(&rates, SOURCE).await?;
+        }
+
+        let ids = self.store.price_ids().await?;
+        let prices = self.prices.latest(&ids).await?;
+        changed += self.store.upsert_prices(&prices, PRICE_SOURCE).await?;
+        Ok(changed)
+    }
+
+    /// Store every published rate and daily price from `from` to `to`, a
+    /// year per request
+    pub async fn backfill(&self, from: NaiveDate, to: NaiveDate) -> Result<u64, FxError> {
+        let ids = self.store.price_ids().await?;
+        let mut changed = 0;
+        for (start, end) in chunks(from, to, BACKFILL_CHUNK_DAYS) {
+            if !self.config.currencies.is_empty() {
+                let rates = self.client.range(&self.config.currencies, start, end).await?;
+                changed += self.store.upsert(&rates, SOURCE).await?;
+                tracing::info!("FX backfill {}..{}: {} rates", start, end, rates.len());
+            }
+            for (ticker, id) in &ids {
+                let prices = self.prices.range(ticker, id, start, end).await?;
+                changed += self.store.upsert_prices(&prices, PRICE_SOURCE).await?;
+                tracing::info!("Price backfill {} {}..{}: {} days", ticker, start, end, prices.len());
+            }
+        }
+        Ok(changed)
+    }
+}
+
+/// Split `from..=to` into windows of at most `days` days
+fn chunks(from: NaiveDate, to: NaiveDate, days: i64) -> Vec<(NaiveDate, NaiveDate)> {
+    let mut windows = Vec::new();
+    let mut start = from;
+    while start <= to {
+        let end = (start + ChronoDuration::days(days - 1)).min(to);
+        windows.push((start, end));
+        start = end + ChronoDuration::days(1);
+    }
+    windows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::from_str(s).unwrap()
+    }
+
+    fn dec(s: &str) -> Decimal {
+        Decimal::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn test_rate_on_uses_latest_earlier_snapshot() {
+        let table = FxRateTable::new("EUR", [(date("2026-03-06"), dec("0.92")), (date("2026-03-09"), dec("0.93"))]);
+
+        assert_eq!(table.rate_on(date("2026-03-05")), None);
+        assert_eq!(table.rate_on(date("2026-03-06")), Some(dec("0.92")));
+        // Weekend falls back to Friday's rate
+        assert_eq!(table.rate_on(date("2026-03-08")), Some(dec("0.92")));
+        assert_eq!(table.rate_on(date("2026-03-20")), Some(dec("0.93")));
+
+        assert_eq!(FxRateTable::new("USD", []).rate_on(date("2020-01-01")), Some(Decimal::ONE));
+    }
+
+    #[test]
+    fn test_value_prices_asset_on_the_day_then_converts() {
+        let table = FxRateTable::new("EUR", [(date("2026-03-01"), dec("0.9"))]).with_prices([
+            ("eth".to_string(), date("2026-03-06"), dec("3000")),
+            ("eth".to_string(), date("2026-03-07"), dec("2500")),
+        ]);
+        assert_eq!(table.value(dec("0.5"), "ETH", date("2026-03-06")), Some(dec("1350")));
+        assert_eq!(table.value(dec("0.5"), "ETH", date("2026-03-08")), Some(dec("1125")));
+        // Before the first price, and assets never priced, have no value
+        assert_eq!(table.value(dec("0.5"), "ETH", date("2026-03-05")), None);
+        assert_eq!(table.value(dec("0.5"), "XMR", date("2026-03-07")), None);
+
+        let usd = FxRateTable::new("USD", []).with_prices([("btc".to_string(), date("2026-03-06"), dec("60000"))]);
+        assert_eq!(usd.value(dec("0.01"), "btc", date("2026-03-06")), Some(dec("600")));
+        assert_eq!(format_value(dec("1234.5678")), "1234.57");
+    }
+
+    #[test]
+    fn test_daily_closes_keep_last_price_of_each_day() {
+        // 2026-03-06 00:00, 12:00 and 2026-03-07 06:00 UTC, out of order
+        let points = [(1_772_798_400_000, 2.0), (1_772_755_200_000, 1.0), (1_772_863_200_000, 3.0)];
+        let closes = daily_closes(&points);
+        assert_eq!(closes.get(&date("2026-03-06")), Some(&2.0));
+        assert_eq!(closes.get(&date("2026-03-07")), Some(&3.0));
+        assert_eq!(closes.len(), 2);
+    }
+
+    #[test]
+    fn test_parse_currency_and_chunks() {
+        assert_eq!(parse_currency(" eur ").unwrap(), "EUR");
+        assert!(parse_currency("EURO").is_err());
+        assert!(parse_currency("E1R").is_err());
+
+        let windows = chunks(date("2024-01-01"), date("2024-01-10"), 4);
+        assert_eq!(
+            windows,
+            vec![
+                (date("2024-01-01"), date("2024-01-04")),
+                (date("2024-01-05"), date("2024-01-08")),
+                (date("2024-01-09"), date("2024-01-10")),
+            ]
+        );
+        assert!(chunks(date("2024-01-02"), date("2024-01-01"), 4).is_empty());
+    }
+}
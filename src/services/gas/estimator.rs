@@ -1,14 +1,20 @@
+use super::history::GasHistory;
 use super::types::{GasEstimate, GasError, TxType};
 use crate::config::rpc_config::{get_rpc_config, BlockchainProtocol};
 use crate::services::redis_cache::RedisService;
 use crate::services::wallet::rpc::{BlockchainProvider, HttpRpcClient};
+use crate::services::wallet::btc_fees::{BitcoinFeeOracle, PAYOUT_VBYTES};
+use crate::services::wallet::solana_fees::{ComputeBudget, PriorityFeeConfig};
 use chrono::Utc;
+use sqlx::{MySql, Pool};
 
 /// Gas price estimator with multi-tier caching and EMA smoothing
 pub struct GasEstimator {
     redis_service: Option<RedisService>,
     /// EMA alpha parameter (0.125 per EIP-1559 spec)
     ema_alpha: f64,
+    /// Records fresh estimates and tunes fallbacks from past ones
+    history: Option<GasHistory>,
 }
 
 impl GasEstimator {
@@ -16,9 +22,17 @@ impl GasEstimator {
         Self {
             redis_service,
             ema_alpha: 0.125, // EIP-1559 standard
+            history: None,
         }
     }
 
+    /// Record estimates to gas_history and fall back to recent history
+    /// rather than hardcoded costs
+    pub fn with_history(mut self, db: Pool<MySql>) -> Self {
+        self.history = Some(GasHistory::new(db));
+        self
+    }
+
     /// Get gas estimate for a network and transaction type
     /// Uses multi-tier caching with Probabilistic Early Recomputation (PER)
     pub async fn estimate_gas(
@@ -49,13 +63,14 @@ impl GasEstimator {
                 self.estimate_solana_gas(&network_lower, tx_type).await?
             }
             _ => {
-                // Fallback to hardcoded for unsupported protocols
-                self.get_fallback_estimate(&network_lower, tx_type)
+                // Fallback for unsupported protocols
+                self.fallback_estimate(&network_lower, tx_type).await
             }
         };
 
         // 3. Cache the result
         self.cache_estimate(&estimate).await;
+        self.record_estimate(&estimate);
 
         Ok(estimate)
     }
@@ -74,7 +89,7 @@ impl GasEstimator {
             Ok(price) => price,
             Err(e) => {
                 tracing::warn!("RPC gas price fetch failed for {}: {}, using fallback", network, e);
-                return Ok(self.get_fallback_estimate(network, tx_type));
+                return Ok(self.fallback_estimate(network, tx_type).await);
             }
         };
 
@@ -105,20 +120,25 @@ impl GasEstimator {
         network: &str,
         _tx_type: TxType,
     ) -> Result<GasEstimate, GasError> {
-        // Bitcoin uses fee rate (sat/vByte) × transaction size
-        // For now, use conservative estimate
-        // TODO: Fetch from mempool.space API or blockchair
-        
-        let fee_rate_sat_per_vbyte = 10; // Conservative: 10 sat/vByte
-        let avg_tx_size_vbytes = 250; // Average P2PKH transaction
+        // Bitcoin uses fee rate (sat/vByte) × transaction size, at the
+        // confirmation target payouts are built for
+        let oracle = BitcoinFeeOracle::global();
+        let fee_rate_sat_per_vbyte = match oracle.rate(oracle.payout_target()).await {
+            Ok(rate) => rate.ceil() as u64,
+            Err(e) => {
+                tracing::warn!("Bitcoin fee oracle failed: {}, using conservative rate", e);
+                10 // Conservative: 10 sat/vByte
+            }
+        };
+        let avg_tx_size_vbytes = PAYOUT_VBYTES;
         let total_fee_sats = fee_rate_sat_per_vbyte * avg_tx_size_vbytes;
         let total_cost_btc = total_fee_sats as f64 / 100_000_000.0; // Convert sats to BTC
 
         Ok(GasEstimate {
             network: network.to_string(),
             tx_type: TxType::NativeTransfer,
-            gas_price_wei: fee_rate_sat_per_vbyte as u64,
-            gas_limit: avg_tx_size_vbytes as u64,
+            gas_price_wei: fee_rate_sat_per_vbyte,
+            gas_limit: avg_tx_size_vbytes,
             total_cost_native: total_cost_btc,
             cached: false,
             timestamp: Utc::now(),
@@ -131,11 +151,15 @@ impl GasEstimator {
         network: &str,
         _tx_type: TxType,
     ) -> Result<GasEstimate, GasError> {
-        // Solana uses fixed fee structure: 5,000 lamports base + priority fees
+        // Solana charges 5,000 lamports base + priority fees; estimate with
+        // the priority fee cap payouts are held to
         // 1 SOL = 1,000,000,000 lamports
-        let base_fee_lamports = 5_000;
-        let priority_fee_lamports = 1_000; // Conservative priority fee
-        let total_fee_lamports = base_fee_lamports + priority_fee_lamports;
+        let config = PriorityFeeConfig::global();
+        let total_fee_lamports = ComputeBudget {
+            unit_limit: config.compute_unit_limit,
+            unit_price_micro_lamports: config.max_micro_lamports,
+        }
+        .total_fee_lamports();
         let total_cost_sol = total_fee_lamports as f64 / 1_000_000_000.0;
 
         Ok(GasEstimate {
@@ -204,6 +228,34 @@ impl GasEstimator {
         }
     }
 
+    /// Record a fresh estimate in the background. Fallbacks carry no gas
+    /// price and are not recorded, so history only learns from real prices.
+    fn record_estimate(&self, estimate: &GasEstimate) {
+        let Some(history) = self.history.clone() else { return };
+        if estimate.cached || estimate.gas_price_wei == 0 {
+            return;
+        }
+        let estimate = estimate.clone();
+        tokio::spawn(async move {
+            if let Err(e) = history.record_estimate(&estimate).await {
+                tracing::warn!("Failed to record gas estimate for {}: {}", estimate.network, e);
+            }
+        });
+    }
+
+    /// Fallback priced from recent history when there is enough of it,
+    /// otherwise the hardcoded estimate
+    async fn fallback_estimate(&self, network: &str, tx_type: TxType) -> GasEstimate {
+        let mut estimate = self.get_fallback_estimate(network, tx_type);
+        if let Some(history) = &self.history {
+            if let Some(cost) = history.tuned_fallback(network, tx_type).await {
+                tracing::debug!("Using tuned fallback for {}: {} (default {})", network, cost, estimate.total_cost_native);
+                estimate.total_cost_native = cost;
+            }
+        }
+        estimate
+    }
+
     /// Fallback to hardcoded estimates when RPC fails
     fn get_fallback_estimate(&self, network: &str, tx_type: TxType) -> GasEstimate {
         let total_cost_native = match network {
@@ -231,7 +283,8 @@ impl GasEstimator {
             Ok(estimate) => estimate.total_cost_native,
             Err(e) => {
                 tracing::warn!("Gas estimation failed for {}: {}, using fallback", network, e);
-                self.get_fallback_estimate(network, TxType::NativeTransfer)
+                self.fallback_estimate(&network.to_lowercase(), TxType::NativeTransfer)
+                    .await
                     .total_cost_native
             }
         }
@@ -0,0 +1,330 @@
+//! Gas price history. Fresh estimates and the gas each payout actually spent
+//! are recorded per network; the admin analytics summarise them and the
+//! estimator falls back to recent history instead of hardcoded costs.
+
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use sqlx::{MySql, Pool, Row};
+use std::collections::HashMap;
+use std::sync::{OnceLock, RwLock};
+use std::time::{Duration, Instant};
+
+use super::types::{GasEstimate, TxType};
+use crate::services::amount::Decimal;
+
+/// Window of history used to tune fallback costs
+pub const TUNING_WINDOW_DAYS: i64 = 7;
+
+/// Fewer samples than this and the hardcoded fallback is used instead
+pub const MIN_TUNING_SAMPLES: usize = 10;
+
+/// Fallbacks are priced at the upper quartile so a quiet RPC outage does not
+/// underprice gas on a busy chain
+const TUNING_PERCENTILE: f64 = 0.75;
+
+/// Tuned fallbacks are read on every failed RPC call, so they are cached per
+/// process for this long
+const TUNED_CACHE_TTL: Duration = Duration::from_secs(600);
+
+type TunedCache = RwLock<HashMap<(String, TxType), (Instant, Option<f64>)>>;
+
+static TUNED_CACHE: OnceLock<TunedCache> = OnceLock::new();
+
+fn tuned_cache() -> &'static TunedCache {
+    TUNED_CACHE.get_or_init(|| RwLock::new(HashMap::new()))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GasSampleKind {
+    Estimate,
+    Payout,
+}
+
+impl GasSampleKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GasSampleKind::Estimate => "estimate",
+            GasSampleKind::Payout => "payout",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "estimate" => Some(GasSampleKind::Estimate),
+            "payout" => Some(GasSampleKind::Payout),
+            _ => None,
+        }
+    }
+}
+
+/// Gas spent by a broadcast payout
+#[derive(Debug, Clone)]
+pub struct PayoutGas {
+    pub network: String,
+    pub swap_id: String,
+    pub tx_hash: String,
+    pub tx_type: TxType,
+    /// Wei for EVM, sat/vB for Bitcoin, lamports for Solana
+    pub gas_price: u64,
+    pub gas_limit: u64,
+    /// Gas units (EVM) or vbytes (Bitcoin) the transaction used, when known
+    pub gas_used: Option<u64>,
+    pub fee_native: Decimal,
+}
+
+/// One row of history
+#[derive(Debug, Clone)]
+pub struct GasSample {
+    pub network: String,
+    pub kind: GasSampleKind,
+    pub gas_price: u64,
+    pub fee_native: f64,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Distribution of a set of samples
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Percentiles {
+    pub samples: usize,
+    pub min: f64,
+    pub p50: f64,
+    pub p90: f64,
+    pub p99: f64,
+    pub max: f64,
+    pub mean: f64,
+}
+
+impl Percentiles {
+    /// `None` for an empty set
+    pub fn from_samples(samples: &[f64]) -> Option<Self> {
+        let mut sorted: Vec<f64> = samples.iter().copied().filter(|v| v.is_finite()).collect();
+        if sorted.is_empty() {
+            return None;
+        }
+        sorted.sort_by(|a, b| a.total_cmp(b));
+
+        Some(Self {
+            samples: sorted.len(),
+            min: sorted[0],
+            p50: percentile(&sorted, 0.50),
+            p90: percentile(&sorted, 0.90),
+            p99: percentile(&sorted, 0.99),
+            max: sorted[sorted.len() - 1],
+            mean: sorted.iter().sum::<f64>() / sorted.len() as f64,
+        })
+    }
+}
+
+/// Linear-interpolated percentile of an ascending, non-empty slice
+pub fn percentile(sorted: &[f64], p: f64) -> f64 {
+    if sorted.len() == 1 {
+        return sorted[0];
+    }
+    let rank = p.clamp(0.0, 1.0) * (sorted.len() - 1) as f64;
+    let lower = rank.floor() as usize;
+    let upper = rank.ceil() as usize;
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (rank - lower as f64)
+}
+
+/// Relative change from `previous` to `current`, in percent
+pub fn change_pct(previous: f64, current: f64) -> Option<f64> {
+    if previous <= 0.0 || !previous.is_finite() || !current.is_finite() {
+        return None;
+    }
+    Some((current - previous) / previous * 100.0)
+}
+
+/// Fallback cost from recent fees, or `None` when there are too few samples
+pub fn tuned_cost(fees: &[f64]) -> Option<f64> {
+    let mut sorted: Vec<f64> = fees.iter().copied().filter(|v| v.is_finite() && *v > 0.0).collect();
+    if sorted.len() < MIN_TUNING_SAMPLES {
+        return None;
+    }
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    Some(percentile(&sorted, TUNING_PERCENTILE))
+}
+
+// =============================================================================
+// GAS HISTORY
+// =============================================================================
+
+#[derive(Clone)]
+pub struct GasHistory {
+    db: Pool<MySql>,
+}
+
+impl GasHistory {
+    pub fn new(db: Pool<MySql>) -> Self {
+        Self { db }
+    }
+
+    pub async fn record_estimate(&self, estimate: &GasEstimate) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO gas_history (network, kind, tx_type, gas_price, gas_limit, fee_native)
+            VALUES (?, 'estimate', ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&estimate.network)
+        .bind(estimate.tx_type.as_str())
+        .bind(estimate.gas_price_wei)
+        .bind(estimate.gas_limit)
+        .bind(estimate.total_cost_native)
+        .execute(&self.db)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn record_payout(&self, payout: &PayoutGas) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO gas_history
+                (network, kind, tx_type, gas_price, gas_limit, gas_used, fee_native, swap_id, tx_hash)
+            VALUES (?, 'payout', ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&payout.network)
+        .bind(payout.tx_type.as_str())
+        .bind(payout.gas_price)
+        .bind(payout.gas_limit)
+        .bind(payout.gas_used)
+        .bind(payout.fee_native)
+        .bind(&payout.swap_id)
+        .bind(&payout.tx_hash)
+        .execute(&self.db)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Samples since `since`, optionally for one network, oldest first
+    pub async fn samples(&self, network: Option<&str>, since: DateTime<Utc>) -> Result<Vec<GasSample>, sqlx::Error> {
+        let rows = sqlx::query(
+            r#"
+            SELECT network, CAST(kind AS CHAR) as kind, gas_price, fee_native, created_at
+            FROM gas_history
+            WHERE created_at >= ? AND (? IS NULL OR network = ?)
+            ORDER BY created_at ASC
+            "#,
+        )
+        .bind(since)
+        .bind(network)
+        .bind(network)
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|row| {
+                let kind: String = row.get("kind");
+                Some(GasSample {
+                    network: row.get("network"),
+                    kind: GasSampleKind::parse(&kind)?,
+                    gas_price: row.get("gas_price"),
+                    fee_native: row.get("fee_native"),
+                    created_at: row.get("created_at"),
+                })
+            })
+            .collect())
+    }
+
+    /// Fallback cost for `network` learned from the last week of estimates
+    /// and payouts of the same transaction type
+    pub async fn tuned_fallback(&self, network: &str, tx_type: TxType) -> Option<f64> {
+        let key = (network.to_string(), tx_type);
+        if let Ok(cache) = tuned_cache().read() {
+            if let Some((at, cost)) = cache.get(&key) {
+                if at.elapsed() < TUNED_CACHE_TTL {
+                    return *cost;
+                }
+            }
+        }
+
+        let since = Utc::now() - ChronoDuration::days(TUNING_WINDOW_DAYS);
+        let fees: Vec<f64> = match sqlx::query_scalar(
+            r#"
+            SELECT fee_native FROM gas_history
+            WHERE network = ? AND tx_type = ? AND created_at >= ?
+            "#,
+        )
+        .bind(network)
+        .bind(tx_type.as_str())
+        .bind(since)
+        .fetch_all(&self.db)
+        .await
+        {
+            Ok(fees) => fees,
+            Err(e) => {
+                tracing::warn!("Failed to load gas history for {}: {}", network, e);
+                return None;
+            }
+        };
+
+        let cost = tuned_cost(&fees);
+        if let Ok(mut cache) = tuned_cache().write() {
+            cache.insert(key, (Instant::now(), cost));
+        }
+        cost
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percentiles_interpolate() {
+        let samples: Vec<f64> = (1..=100).map(|v| v as f64).collect();
+        let p = Percentiles::from_samples(&samples).unwrap();
+        assert_eq!(p.samples, 100);
+        assert_eq!(p.min, 1.0);
+        assert_eq!(p.max, 100.0);
+        assert!((p.p50 - 50.5).abs() < 1e-9);
+        assert!((p.p90 - 90.1).abs() < 1e-9);
+        assert!((p.p99 - 99.01).abs() < 1e-9);
+        assert!((p.mean - 50.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_percentiles_of_unsorted_and_single_samples() {
+        let p = Percentiles::from_samples(&[3.0, 1.0, 2.0]).unwrap();
+        assert_eq!(p.p50, 2.0);
+
+        let single = Percentiles::from_samples(&[0.002]).unwrap();
+        assert_eq!(single.p50, 0.002);
+        assert_eq!(single.p99, 0.002);
+
+        assert!(Percentiles::from_samples(&[]).is_none());
+        assert!(Percentiles::from_samples(&[f64::NAN]).is_none());
+    }
+
+    #[test]
+    fn test_change_pct() {
+        assert_eq!(change_pct(2.0, 3.0), Some(50.0));
+        assert_eq!(change_pct(2.0, 1.0), Some(-50.0));
+        assert_eq!(change_pct(0.0, 1.0), None);
+    }
+
+    #[test]
+    fn test_tuned_cost_needs_enough_samples() {
+        let few = vec![0.001; MIN_TUNING_SAMPLES - 1];
+        assert!(tuned_cost(&few).is_none());
+
+        // Zero-cost rows (e.g. failed lookups) do not count as samples
+        let mut with_zeros = vec![0.0; 5];
+        with_zeros.extend(vec![0.001; MIN_TUNING_SAMPLES - 1]);
+        assert!(tuned_cost(&with_zeros).is_none());
+
+        let fees: Vec<f64> = (1..=20).map(|v| v as f64 / 10_000.0).collect();
+        let cost = tuned_cost(&fees).unwrap();
+        assert!((cost - 0.001525).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_sample_kind_round_trip() {
+        for kind in [GasSampleKind::Estimate, GasSampleKind::Payout] {
+            assert_eq!(GasSampleKind::parse(kind.as_str()), Some(kind));
+        }
+        assert_eq!(TxType::TokenTransfer.as_str(), "token_transfer");
+    }
+}
@@ -1,5 +1,9 @@
 pub mod estimator;
+pub mod history;
+pub mod station;
 pub mod types;
 
 pub use estimator::GasEstimator;
+pub use history::{GasHistory, PayoutGas, Percentiles};
+pub use station::{GasStation, GasStationConfig, TopUp, TopUpRequest, HOT_WALLET_INDEX};
 pub use types::{GasEstimate, TxType, GasError};
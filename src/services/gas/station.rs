@@ -0,0 +1,354 @@
+//! Gas station. Deposit addresses that only ever receive ERC-20 tokens hold
+//! no native coin to pay for moving them, so before a token transfer the
+//! address is topped up from the hot wallet with just enough for its gas.
+//! Requests arriving within a short window are funded together as
+//! consecutive-nonce transactions, and every top-up is written to the ledger.
+
+use sqlx::{MySql, Pool};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::oneshot;
+
+use crate::modules::commissions::crud::CommissionCrud;
+use crate::modules::wallet::schema::EvmTransaction;
+use crate::services::amount::{self, AmountError, Decimal};
+use crate::services::events::OpsEvent;
+use crate::services::sandbox::signing_chain_id;
+use crate::services::wallet::derivation;
+use crate::services::wallet::rpc::BlockchainProvider;
+use crate::services::wallet::signer::Signer;
+use crate::services::wallet::signing::SigningService;
+
+/// HD index of the hot wallet that pays for top-ups. Swap addresses count
+/// up from 0, so it sits at the top of the non-hardened range.
+pub const HOT_WALLET_INDEX: u32 = 0x7fff_ffff;
+
+/// Gas of the top-up itself, a plain value transfer
+const TOP_UP_GAS_LIMIT: u64 = 21_000;
+
+#[derive(Debug, Clone)]
+pub struct GasStationConfig {
+    /// Top-ups cover the transfer's gas at the current price times this
+    pub buffer: f64,
+    /// How long the first request waits for others to join its batch (ms)
+    pub batch_window_ms: u64,
+    /// Most top-ups sent from one nonce read
+    pub max_batch: usize,
+    /// Hot wallet balance below which operators are alerted, in native units
+    pub low_balance: Decimal,
+}
+
+impl Default for GasStationConfig {
+    fn default() -> Self {
+        Self {
+            buffer: 1.2,
+            batch_window_ms: 500,
+            max_batch: 20,
+            low_balance: Decimal::new(5, 2),
+        }
+    }
+}
+
+impl GasStationConfig {
+    /// Load from environment variables
+    pub fn from_env() -> Result<Self, String> {
+        let mut config = Self::default();
+
+        if let Ok(val) = std::env::var("GAS_STATION_BUFFER") {
+            config.buffer = val.parse().map_err(|e| format!("Invalid GAS_STATION_BUFFER: {}", e))?;
+        }
+
+        if let Ok(val) = std::env::var("GAS_STATION_BATCH_WINDOW_MS") {
+            config.batch_window_ms = val.parse().map_err(|e| format!("Invalid GAS_STATION_BATCH_WINDOW_MS: {}", e))?;
+        }
+
+        if let Ok(val) = std::env::var("GAS_STATION_MAX_BATCH") {
+            config.max_batch = val.parse().map_err(|e| format!("Invalid GAS_STATION_MAX_BATCH: {}", e))?;
+        }
+
+        if let Ok(val) = std::env::var("GAS_STATION_LOW_BALANCE") {
+            config.low_balance = amount::parse(&val).map_err(|e| format!("Invalid GAS_STATION_LOW_BALANCE: {}", e))?;
+        }
+
+        if config.buffer < 1.0 {
+            return Err("GAS_STATION_BUFFER must be at least 1.0".to_string());
+        }
+        if config.max_batch == 0 {
+            return Err("GAS_STATION_MAX_BATCH must be at least 1".to_string());
+        }
+
+        Ok(config)
+    }
+}
+
+/// An address that is about to send a transaction it may not afford
+#[derive(Debug, Clone)]
+pub struct TopUpRequest {
+    pub network: String,
+    pub address: String,
+    /// Swap the transaction belongs to; its gas cost is booked against it
+    pub swap_id: Option<String>,
+    /// Gas limit of the transaction the address will send
+    pub gas_limit: u64,
+}
+
+/// A broadcast top-up
+#[derive(Debug, Clone, PartialEq)]
+pub struct TopUp {
+    pub address: String,
+    pub amount: Decimal,
+    /// Gas the hot wallet paid to send it
+    pub network_fee: Decimal,
+    pub tx_hash: String,
+}
+
+/// Native coin `balance` lacks to pay `gas_limit` at `gas_price` wei with
+/// `buffer` headroom, or `None` when it already can
+pub fn shortfall(balance: Decimal, gas_price: u64, gas_limit: u64, buffer: f64) -> Result<Option<Decimal>, AmountError> {
+    let cost = amount::from_minor_units(gas_price as u128 * gas_limit as u128, amount::EVM_NATIVE_DECIMALS)?;
+    let needed = (cost * amount::from_f64(buffer)?)
+        .round_dp_with_strategy(amount::EVM_NATIVE_DECIMALS, rust_decimal::RoundingStrategy::AwayFromZero);
+
+    let missing = needed - balance;
+    Ok((missing > Decimal::ZERO).then(|| missing.normalize()))
+}
+
+/// Native ticker of an EVM chain, for ledger entries
+pub(crate) fn native_currency(network: &str) -> &'static str {
+    match network {
+        "bsc" => "bnb",
+        "polygon" => "pol",
+        "avalanche" => "avax",
+        _ => "eth",
+    }
+}
+
+type TopUpResult = Result<Option<TopUp>, String>;
+
+pub struct GasStation {
+    db: Pool<MySql>,
+    provider: Arc<dyn BlockchainProvider>,
+    master_seed: String,
+    signing: SigningService,
+    config: GasStationConfig,
+    pending: Mutex<Vec<(TopUpRequest, oneshot::Sender<TopUpResult>)>>,
+}
+
+impl GasStation {
+    pub fn new(db: Pool<MySql>, provider: Arc<dyn BlockchainProvider>, master_seed: String) -> Self {
+        let signing = SigningService::from_config(&master_seed);
+        Self {
+            db,
+            provider,
+            master_seed,
+            signing,
+            config: GasStationConfig::default(),
+            pending: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn with_config(mut self, config: GasStationConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Replace the signer configured by SIGNER_BACKEND
+    pub fn with_signer(mut self, signer: Arc<dyn Signer>) -> Self {
+        self.signing = SigningService::new(signer);
+        self
+    }
+
+    /// Make sure `request.address` can pay for its next transaction, joining
+    /// any batch that is being collected. Returns the top-up that was sent,
+    /// or `None` when the address already had enough.
+    pub async fn ensure_gas(&self, request: TopUpRequest) -> TopUpResult {
+        let (sender, receiver) = oneshot::channel();
+        let leader = {
+            let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+            pending.push((request, sender));
+            pending.len() == 1
+        };
+
+        // The first request in collects the batch and funds it for everyone
+        if leader {
+            tokio::time::sleep(Duration::from_millis(self.config.batch_window_ms)).await;
+            let batch = std::mem::take(&mut *self.pending.lock().unwrap_or_else(|e| e.into_inner()));
+            let (requests, senders): (Vec<_>, Vec<_>) = batch.into_iter().unzip();
+
+            let results = self.fund(&requests).await;
+            for (sender, result) in senders.into_iter().zip(results) {
+                let _ = sender.send(result);
+            }
+        }
+
+        receiver.await.map_err(|_| "Gas station dropped the top-up request".to_string())?
+    }
+
+    /// Top up every address that needs it, `max_batch` transactions per
+    /// nonce read. Results are in request order.
+    pub async fn fund(&self, requests: &[TopUpRequest]) -> Vec<TopUpResult> {
+        let mut results = Vec::with_capacity(requests.len());
+        for chunk in requests.chunks(self.config.max_batch.max(1)) {
+            match self.fund_chunk(chunk).await {
+                Ok(chunk_results) => results.extend(chunk_results),
+                Err(e) => results.extend(chunk.iter().map(|_| Err(e.clone()))),
+            }
+        }
+        results
+    }
+
+    async fn fund_chunk(&self, requests: &[TopUpRequest]) -> Result<Vec<TopUpResult>, String> {
+        let gas_price = self.provider.get_gas_price().await
+            .map_err(|e| format!("Failed to get gas price: {}", e))?;
+
+        let mut needed = Vec::with_capacity(requests.len());
+        for request in requests {
+            let balance = self.provider.get_balance(&request.address).await
+                .map_err(|e| format!("Failed to get balance of {}: {}", request.address, e))?;
+            needed.push(shortfall(balance, gas_price, request.gas_limit, self.config.buffer).map_err(|e| e.to_string())?);
+        }
+
+        let count = needed.iter().flatten().count();
+        if count == 0 {
+            return Ok(requests.iter().map(|_| Ok(None)).collect());
+        }
+
+        let network_fee = amount::from_minor_units(gas_price as u128 * TOP_UP_GAS_LIMIT as u128, amount::EVM_NATIVE_DECIMALS)
+            .map_err(|e| e.to_string())?;
+        let total = needed.iter().flatten().sum::<Decimal>() + network_fee * Decimal::from(count);
+
+        let hot_wallet = derivation::derive_evm_address(&self.master_seed, HOT_WALLET_INDEX).await?;
+        let hot_balance = self.provider.get_balance(&hot_wallet).await
+            .map_err(|e| format!("Failed to get hot wallet balance: {}", e))?;
+        let network = requests[0].network.clone();
+
+        if hot_balance < total {
+            let detail = format!("Gas station needs {} for {} top-ups, hot wallet holds {}", total, count, hot_balance);
+            OpsEvent::GasTankLow { chain: network, detail: detail.clone() }.publish();
+            return Err(detail);
+        }
+
+        let mut nonce = self.provider.get_transaction_count(&hot_wallet).await
+            .map_err(|e| format!("Failed to get nonce: {}", e))?;
+        let batch_id = uuid::Uuid::new_v4().to_string();
+        let mut spent = Decimal::ZERO;
+        let mut halted: Option<String> = None;
+        let mut results = Vec::with_capacity(requests.len());
+
+        for (request, missing) in requests.iter().zip(needed) {
+            let Some(missing) = missing else {
+                results.push(Ok(None));
+                continue;
+            };
+            // A failed send leaves a nonce gap, so nothing after it would confirm
+            if let Some(e) = &halted {
+                results.push(Err(e.clone()));
+                continue;
+            }
+
+            // Signed for Ethereum (its test network in a sandbox), like the
+            // payouts it funds
+            let tx = EvmTransaction {
+                to_address: request.address.clone(),
+                amount: missing,
+                token: "NATIVE".to_string(),
+                chain_id: signing_chain_id("ethereum"),
+                nonce,
+                gas_price,
+                gas_limit: TOP_UP_GAS_LIMIT,
+                data: String::new(),
+            };
+
+            let sent = match self.signing.sign_evm(HOT_WALLET_INDEX, &tx).await {
+                Ok(signature) => self.provider.send_raw_transaction(&signature).await
+                    .map_err(|e| format!("Failed to broadcast top-up: {}", e)),
+                Err(e) => Err(e),
+            };
+
+            match sent {
+                Ok(tx_hash) => {
+                    nonce += 1;
+                    spent += missing + network_fee;
+                    let top_up = TopUp { address: request.address.clone(), amount: missing, network_fee, tx_hash };
+                    self.record(&batch_id, request, &top_up, gas_price).await;
+                    results.push(Ok(Some(top_up)));
+                }
+                Err(e) => {
+                    tracing::error!("Gas top-up of {} failed: {}", request.address, e);
+                    halted = Some(e.clone());
+                    results.push(Err(e));
+                }
+            }
+        }
+
+        tracing::info!("Gas station batch {} sent {} top-ups costing {}", batch_id, count, spent);
+
+        let remaining = hot_balance - spent;
+        if remaining < self.config.low_balance {
+            OpsEvent::GasTankLow {
+                chain: network,
+                detail: format!("Hot wallet {} has {} left after gas top-ups", hot_wallet, remaining),
+            }
+            .publish();
+        }
+
+        Ok(results)
+    }
+
+    /// The top-up is already broadcast, so failed writes are only logged
+    async fn record(&self, batch_id: &str, request: &TopUpRequest, top_up: &TopUp, gas_price: u64) {
+        let inserted = sqlx::query(
+            r#"
+            INSERT INTO gas_topups (batch_id, network, address, swap_id, amount, network_fee, gas_price, tx_hash)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(batch_id)
+        .bind(&request.network)
+        .bind(&top_up.address)
+        .bind(&request.swap_id)
+        .bind(top_up.amount)
+        .bind(top_up.network_fee)
+        .bind(gas_price)
+        .bind(&top_up.tx_hash)
+        .execute(&self.db)
+        .await;
+        if let Err(e) = inserted {
+            tracing::warn!("Failed to record gas top-up {}: {}", top_up.tx_hash, e);
+        }
+
+        let Some(swap_id) = &request.swap_id else {
+            return;
+        };
+        let cost = top_up.amount + top_up.network_fee;
+        if let Err(e) = CommissionCrud::new(self.db.clone())
+            .record_gas_cost(swap_id, (native_currency(&request.network), &request.network), cost)
+            .await
+        {
+            tracing::warn!("Failed to book gas top-up {} against swap {}: {}", top_up.tx_hash, swap_id, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dec(s: &str) -> Decimal {
+        amount::parse(s).unwrap()
+    }
+
+    #[test]
+    fn test_shortfall_covers_buffered_gas() {
+        // 65,000 gas at 20 gwei = 0.0013 ETH, plus 20%
+        assert_eq!(shortfall(Decimal::ZERO, 20_000_000_000, 65_000, 1.2).unwrap(), Some(dec("0.00156")));
+        assert_eq!(shortfall(dec("0.001"), 20_000_000_000, 65_000, 1.2).unwrap(), Some(dec("0.00056")));
+        assert_eq!(shortfall(dec("0.00156"), 20_000_000_000, 65_000, 1.2).unwrap(), None);
+    }
+
+    #[test]
+    fn test_shortfall_rounds_up_to_wei() {
+        let missing = shortfall(Decimal::ZERO, 1, 1, 1.5).unwrap().unwrap();
+        assert_eq!(amount::to_minor_units(missing, amount::EVM_NATIVE_DECIMALS).unwrap(), 2);
+    }
+}
@@ -9,6 +9,12 @@ pub enum TxType {
     TokenTransfer,
     /// ERC20 approve operation (45,000 gas avg)
     TokenApprove,
+    /// EIP-2612 permit submitted for the token owner (80,000 gas avg)
+    TokenPermit,
+    /// ERC20 transferFrom under an allowance (75,000 gas avg)
+    TokenTransferFrom,
+    /// Permit2 signature transfer (120,000 gas avg)
+    PermitTransfer,
     /// Complex contract interaction (use eth_estimateGas)
     ComplexContract,
 }
@@ -20,9 +26,24 @@ impl TxType {
             TxType::NativeTransfer => 21_000,
             TxType::TokenTransfer => 65_000,
             TxType::TokenApprove => 45_000,
+            TxType::TokenPermit => 80_000,
+            TxType::TokenTransferFrom => 75_000,
+            TxType::PermitTransfer => 120_000,
             TxType::ComplexContract => 150_000, // Conservative estimate
         }
     }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TxType::NativeTransfer => "native_transfer",
+            TxType::TokenTransfer => "token_transfer",
+            TxType::TokenApprove => "token_approve",
+            TxType::TokenPermit => "token_permit",
+            TxType::TokenTransferFrom => "token_transfer_from",
+            TxType::PermitTransfer => "permit_transfer",
+            TxType::ComplexContract => "complex_contract",
+        }
+    }
 }
 
 /// Gas price estimate result
@@ -0,0 +1,575 @@
+//! Jurisdiction policy. Clients are located by IP and refused service from
+//! blocked countries with a 451, either entirely or only for the endpoints
+//! that move funds. Partners on allow-listed networks are never blocked.
+//!
+//! Countries come from a CDN header or an HTTP lookup API; there is no local
+//! MaxMind database reader. A malformed setting is an error rather than
+//! being skipped, since skipping it would quietly serve blocked countries.
+
+use axum::{
+    body::Body,
+    http::{HeaderMap, HeaderName, Method, Request, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Serialize;
+use std::{
+    collections::{HashMap, HashSet},
+    future::Future,
+    net::IpAddr,
+    pin::Pin,
+    sync::{Arc, RwLock},
+    time::{Duration, Instant},
+};
+use tower::{Layer, Service};
+
+use crate::services::api_version::unversioned;
+use crate::services::client_ip::{ClientIp, IpCidr};
+use crate::services::redis_cache::RedisService;
+
+/// Lookups are cached per process for this long, and in Redis for a day
+const MEMORY_CACHE_TTL: Duration = Duration::from_secs(3600);
+const REDIS_CACHE_TTL_SECS: u64 = 86_400;
+/// The per-process cache is dropped wholesale once it grows past this
+const MAX_MEMORY_ENTRIES: usize = 50_000;
+/// A slow geo API must not hold up requests; the client counts as unknown
+const LOOKUP_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Endpoints that create swaps or move funds. Everything else, including
+/// quotes and status reads, is a read.
+const TRADE_ENDPOINTS: &[(&str, &str)] = &[
+    ("POST", "/swap/create"),
+    ("POST", "/swap/schedules"),
+    ("POST", "/swap/orders"),
+    ("POST", "/balances/transfers"),
+    ("POST", "/balances/withdrawals"),
+    ("POST", "/gift-cards/purchase"),
+];
+
+/// Never blocked, so load balancers and monitors keep working
+const EXEMPT_PATHS: &[&str] = &["/", "/health"];
+
+/// Country of the client, set by `GeoBlockLayer` when it could be resolved
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClientCountry(pub String);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GeoFeature {
+    Read,
+    Trade,
+}
+
+impl GeoFeature {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GeoFeature::Read => "read",
+            GeoFeature::Trade => "trade",
+        }
+    }
+}
+
+/// Feature a request falls under, `None` for exempt paths. The path may
+/// carry a version prefix.
+pub fn feature_for(method: &Method, path: &str) -> Option<GeoFeature> {
+    let path = match unversioned(path).trim_end_matches('/') {
+        "" => "/",
+        trimmed => trimmed,
+    };
+    if EXEMPT_PATHS.contains(&path) {
+        return None;
+    }
+    let is_trade = TRADE_ENDPOINTS
+        .iter()
+        .any(|(m, p)| method.as_str() == *m && path == *p);
+    Some(if is_trade { GeoFeature::Trade } else { GeoFeature::Read })
+}
+
+/// ISO 3166-1 alpha-2 code, upper-cased. "XX" is what CDNs and geo APIs
+/// report for unknown locations, so it is treated as no country.
+pub fn normalize_country(value: &str) -> Option<String> {
+    let code = value.trim().to_ascii_uppercase();
+    (code.len() == 2 && code.chars().all(|c| c.is_ascii_alphabetic()) && code != "XX").then_some(code)
+}
+
+/// Countries from `var`, refusing entries that aren't ISO codes
+fn env_country_list(var: &str) -> Result<HashSet<String>, String> {
+    let Ok(value) = std::env::var(var) else {
+        return Ok(HashSet::new());
+    };
+    value
+        .split(',')
+        .filter(|entry| !entry.trim().is_empty())
+        .map(|entry| {
+            normalize_country(entry)
+                .ok_or_else(|| format!("{} entry '{}' is not an ISO 3166-1 alpha-2 code", var, entry.trim()))
+        })
+        .collect()
+}
+
+// =============================================================================
+// POLICY
+// =============================================================================
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GeoDecision {
+    Allowed,
+    Blocked { country: Option<String>, feature: GeoFeature },
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct GeoPolicy {
+    /// Refused all service
+    blocked: HashSet<String>,
+    /// Refused trade endpoints only
+    blocked_trade: HashSet<String>,
+    /// Partner networks exempt from the policy
+    allowlist: Vec<IpCidr>,
+    /// Refuse trading when the country can't be resolved
+    fail_closed: bool,
+    policy_url: Option<String>,
+}
+
+impl GeoPolicy {
+    pub fn new(blocked: HashSet<String>, blocked_trade: HashSet<String>) -> Self {
+        Self { blocked, blocked_trade, ..Self::default() }
+    }
+
+    pub fn with_allowlist(mut self, allowlist: Vec<IpCidr>) -> Self {
+        self.allowlist = allowlist;
+        self
+    }
+
+    pub fn with_fail_closed(mut self, fail_closed: bool) -> Self {
+        self.fail_closed = fail_closed;
+        self
+    }
+
+    /// Load GEO_BLOCKED_COUNTRIES, GEO_BLOCKED_TRADE_COUNTRIES (comma
+    /// separated ISO codes), GEO_ALLOWLIST (comma separated CIDRs),
+    /// GEO_FAIL_CLOSED and GEO_POLICY_URL
+    pub fn from_env() -> Result<Self, String> {
+        let blocked = env_country_list("GEO_BLOCKED_COUNTRIES")?;
+        let blocked_trade = env_country_list("GEO_BLOCKED_TRADE_COUNTRIES")?;
+        let allowlist = match std::env::var("GEO_ALLOWLIST") {
+            Ok(list) => list
+                .split(',')
+                .filter(|entry| !entry.trim().is_empty())
+                .map(|entry| IpCidr::parse(entry).map_err(|e| format!("GEO_ALLOWLIST: {}", e)))
+                .collect::<Result<Vec<_>, _>>()?,
+            Err(_) => Vec::new(),
+        };
+        let fail_closed = std::env::var("GEO_FAIL_CLOSED")
+            .map(|v| v.eq_ignore_ascii_case("true") || v == "1")
+            .unwrap_or(false);
+
+        Ok(Self {
+            blocked,
+            blocked_trade,
+            allowlist,
+            fail_closed,
+            policy_url: std::env::var("GEO_POLICY_URL").ok().filter(|v| !v.trim().is_empty()),
+        })
+    }
+
+    /// Nothing is blocked, so clients need not be located at all
+    pub fn is_enabled(&self) -> bool {
+        !self.blocked.is_empty() || !self.blocked_trade.is_empty()
+    }
+
+    pub fn is_allowlisted(&self, ip: &IpAddr) -> bool {
+        self.allowlist.iter().any(|net| net.contains(ip))
+    }
+
+    pub fn decide(&self, feature: GeoFeature, country: Option<&str>) -> GeoDecision {
+        let blocked = match country {
+            Some(code) => {
+                self.blocked.contains(code) || (feature == GeoFeature::Trade && self.blocked_trade.contains(code))
+            }
+            None => self.fail_closed && feature == GeoFeature::Trade,
+        };
+
+        if blocked {
+            GeoDecision::Blocked { country: country.map(str::to_string), feature }
+        } else {
+            GeoDecision::Allowed
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct GeoBlockedResponse {
+    pub error: String,
+    pub code: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub country: Option<String>,
+    pub feature: GeoFeature,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub policy_url: Option<String>,
+}
+
+fn unavailable_for_legal_reasons(country: Option<String>, feature: GeoFeature, policy_url: Option<String>) -> Response {
+    let error = match (&country, feature) {
+        (Some(code), GeoFeature::Trade) => format!("Swaps and transfers are not available in {}", code),
+        (Some(code), GeoFeature::Read) => format!("This service is not available in {}", code),
+        (None, _) => "Your location could not be verified".to_string(),
+    };
+    let body = GeoBlockedResponse {
+        error,
+        code: "jurisdiction_restricted",
+        country,
+        feature,
+        policy_url,
+    };
+    (StatusCode::UNAVAILABLE_FOR_LEGAL_REASONS, Json(body)).into_response()
+}
+
+// =============================================================================
+// LOCATOR
+// =============================================================================
+
+#[derive(Debug, Clone)]
+pub enum GeoSource {
+    /// Country header set by a CDN in front of every request (e.g.
+    /// `cf-ipcountry`). Only safe when clients cannot reach us directly.
+    Header(HeaderName),
+    /// HTTP lookup; `{ip}` in the URL is replaced by the client address.
+    /// The response is either a bare country code or JSON with a
+    /// `country_code`, `countryCode` or `country` field.
+    Api(String),
+}
+
+impl GeoSource {
+    /// GEO_COUNTRY_HEADER takes precedence over GEOIP_API_URL
+    pub fn from_env() -> Result<Option<Self>, String> {
+        if let Some(header) = std::env::var("GEO_COUNTRY_HEADER").ok().filter(|v| !v.trim().is_empty()) {
+            let name = HeaderName::from_bytes(header.trim().to_ascii_lowercase().as_bytes())
+                .map_err(|_| format!("GEO_COUNTRY_HEADER '{}' is not a valid header name", header.trim()))?;
+            return Ok(Some(GeoSource::Header(name)));
+        }
+        match std::env::var("GEOIP_API_URL").ok().filter(|v| !v.trim().is_empty()) {
+            Some(url) if url.contains("{ip}") => Ok(Some(GeoSource::Api(url.trim().to_string()))),
+            Some(_) => Err("GEOIP_API_URL must contain {ip}".to_string()),
+            None => Ok(None),
+        }
+    }
+}
+
+type CountryCache = RwLock<HashMap<IpAddr, (Instant, Option<String>)>>;
+
+/// Resolves client countries, caching API lookups in memory and Redis
+pub struct GeoLocator {
+    source: Option<GeoSource>,
+    http: reqwest::Client,
+    redis: Option<RedisService>,
+    cache: CountryCache,
+}
+
+impl GeoLocator {
+    pub fn new(source: Option<GeoSource>, http: reqwest::Client) -> Self {
+        Self {
+            source,
+            http,
+            redis: None,
+            cache: RwLock::new(HashMap::new()),
+        }
+    }
+
+    pub fn with_redis(mut self, redis: RedisService) -> Self {
+        self.redis = Some(redis);
+        self
+    }
+
+    /// Locator for the source configured by `GeoSource::from_env`
+    pub fn from_env(http: reqwest::Client) -> Result<Self, String> {
+        Ok(Self::new(GeoSource::from_env()?, http))
+    }
+
+    pub async fn country(&self, ip: Option<IpAddr>, headers: &HeaderMap) -> Option<String> {
+        match self.source.as_ref()? {
+            GeoSource::Header(name) => headers.get(name)?.to_str().ok().and_then(normalize_country),
+            GeoSource::Api(url) => {
+                let ip = ip.filter(is_public)?;
+                self.lookup(url, ip).await
+            }
+        }
+    }
+
+    async fn lookup(&self, url: &str, ip: IpAddr) -> Option<String> {
+        if let Ok(cache) = self.cache.read() {
+            if let Some((at, country)) = cache.get(&ip) {
+                if at.elapsed() < MEMORY_CACHE_TTL {
+                    return country.clone();
+                }
+            }
+        }
+
+        let redis_key = format!("geo:country:{}", ip);
+        let cached = match &self.redis {
+            Some(redis) => redis.get_string(&redis_key).await.ok().flatten(),
+            None => None,
+        };
+
+        let country = match cached {
+            // Unknown addresses are cached as an empty string
+            Some(value) => normalize_country(&value),
+            None => {
+                let country = match self.fetch(url, ip).await {
+                    Ok(country) => country,
+                    Err(e) => {
+                        // Not cached, so the next request retries
+                        tracing::warn!("Geo lookup for {} failed: {}", ip, e);
+                        return None;
+                    }
+                };
+                if let Some(redis) = &self.redis {
+                    let _ = redis
+                        .set_string(&redis_key, country.as_deref().unwrap_or(""), REDIS_CACHE_TTL_SECS)
+                        .await;
+                }
+                country
+            }
+        };
+
+        if let Ok(mut cache) = self.cache.write() {
+            if cache.len() >= MAX_MEMORY_ENTRIES {
+                cache.clear();
+            }
+            cache.insert(ip, (Instant::now(), country.clone()));
+        }
+        country
+    }
+
+    async fn fetch(&self, url: &str, ip: IpAddr) -> Result<Option<String>, String> {
+        let response = self
+            .http
+            .get(url.replace("{ip}", &ip.to_string()))
+            .timeout(LOOKUP_TIMEOUT)
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        if !response.status().is_success() {
+            return Err(format!("status {}", response.status()));
+        }
+        let body = response.text().await.map_err(|e| e.to_string())?;
+        Ok(parse_country(&body))
+    }
+}
+
+/// Country from a geo API body, see `GeoSource::Api`
+pub fn parse_country(body: &str) -> Option<String> {
+    if let Some(code) = normalize_country(body) {
+        return Some(code);
+    }
+    let json: serde_json::Value = serde_json::from_str(body).ok()?;
+    ["country_code", "countryCode", "country"]
+        .iter()
+        .find_map(|key| json.get(*key)?.as_str().and_then(normalize_country))
+}
+
+/// Private, loopback and link-local addresses have no country
+fn is_public(ip: &IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => !(v4.is_private() || v4.is_loopback() || v4.is_link_local() || v4.is_unspecified()),
+        IpAddr::V6(v6) => {
+            let unique_local = (v6.segments()[0] & 0xfe00) == 0xfc00;
+            let link_local = (v6.segments()[0] & 0xffc0) == 0xfe80;
+            !(v6.is_loopback() || v6.is_unspecified() || unique_local || link_local)
+        }
+    }
+}
+
+// =============================================================================
+// LAYER
+// =============================================================================
+
+/// Applies the jurisdiction policy using the address from `ClientIpLayer`,
+/// which must wrap this layer
+#[derive(Clone)]
+pub struct GeoBlockLayer {
+    policy: Arc<GeoPolicy>,
+    locator: Arc<GeoLocator>,
+}
+
+impl GeoBlockLayer {
+    pub fn new(policy: GeoPolicy, locator: GeoLocator) -> Self {
+        Self { policy: Arc::new(policy), locator: Arc::new(locator) }
+    }
+}
+
+impl<S> Layer<S> for GeoBlockLayer {
+    type Service = GeoBlockService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        GeoBlockService {
+            inner,
+            policy: self.policy.clone(),
+            locator: self.locator.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct GeoBlockService<S> {
+    inner: S,
+    policy: Arc<GeoPolicy>,
+    locator: Arc<GeoLocator>,
+}
+
+impl<S> Service<Request<Body>> for GeoBlockService<S>
+where
+    S: Service<Request<Body>, Response = Response> + Clone + Send + 'static,
+    S::Future: Send,
+{
+    type Response = Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut std::task::Context<'_>) -> std::task::Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: Request<Body>) -> Self::Future {
+        let policy = self.policy.clone();
+        let locator = self.locator.clone();
+        let mut inner = self.inner.clone();
+
+        Box::pin(async move {
+            if !policy.is_enabled() {
+                return inner.call(request).await;
+            }
+            let Some(feature) = feature_for(request.method(), request.uri().path()) else {
+                return inner.call(request).await;
+            };
+
+            let ip = request.extensions().get::<ClientIp>().map(|c| c.0);
+            if ip.is_some_and(|ip| policy.is_allowlisted(&ip)) {
+                return inner.call(request).await;
+            }
+
+            let country = locator.country(ip, request.headers()).await;
+            if let GeoDecision::Blocked { country, feature } = policy.decide(feature, country.as_deref()) {
+                tracing::info!(
+                    country = country.as_deref().unwrap_or("unknown"),
+                    feature = feature.as_str(),
+                    path = %request.uri().path(),
+                    "Request refused by jurisdiction policy"
+                );
+                return Ok(unavailable_for_legal_reasons(country, feature, policy.policy_url.clone()));
+            }
+
+            if let Some(country) = country {
+                request.extensions_mut().insert(ClientCountry(country));
+            }
+            inner.call(request).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn country_list(value: &str) -> HashSet<String> {
+        value.split(',').filter_map(normalize_country).collect()
+    }
+
+    fn policy() -> GeoPolicy {
+        GeoPolicy::new(country_list("KP, ir"), country_list("US"))
+    }
+
+    #[test]
+    fn test_feature_for_request() {
+        assert_eq!(feature_for(&Method::POST, "/swap/create"), Some(GeoFeature::Trade));
+        assert_eq!(feature_for(&Method::POST, "/v1/swap/create"), Some(GeoFeature::Trade));
+        assert_eq!(feature_for(&Method::POST, "/balances/withdrawals/"), Some(GeoFeature::Trade));
+        assert_eq!(feature_for(&Method::GET, "/balances/withdrawals"), Some(GeoFeature::Read));
+        assert_eq!(feature_for(&Method::GET, "/swap/rates"), Some(GeoFeature::Read));
+        assert_eq!(feature_for(&Method::GET, "/health"), None);
+        assert_eq!(feature_for(&Method::GET, "/"), None);
+    }
+
+    #[test]
+    fn test_policy_blocks_per_feature() {
+        let policy = policy();
+        assert!(policy.is_enabled());
+
+        assert!(matches!(policy.decide(GeoFeature::Read, Some("KP")), GeoDecision::Blocked { .. }));
+        assert!(matches!(policy.decide(GeoFeature::Trade, Some("IR")), GeoDecision::Blocked { .. }));
+
+        // Trade-only countries can still read
+        assert_eq!(policy.decide(GeoFeature::Read, Some("US")), GeoDecision::Allowed);
+        assert_eq!(
+            policy.decide(GeoFeature::Trade, Some("US")),
+            GeoDecision::Blocked { country: Some("US".to_string()), feature: GeoFeature::Trade }
+        );
+        assert_eq!(policy.decide(GeoFeature::Trade, Some("DE")), GeoDecision::Allowed);
+    }
+
+    #[test]
+    fn test_unknown_country_only_blocked_when_fail_closed() {
+        assert_eq!(policy().decide(GeoFeature::Trade, None), GeoDecision::Allowed);
+
+        let strict = policy().with_fail_closed(true);
+        assert!(matches!(strict.decide(GeoFeature::Trade, None), GeoDecision::Blocked { country: None, .. }));
+        assert_eq!(strict.decide(GeoFeature::Read, None), GeoDecision::Allowed);
+    }
+
+    #[test]
+    fn test_malformed_country_list_is_an_error() {
+        std::env::set_var("GEO_TEST_BLOCKED_OK", "kp, IR,");
+        assert_eq!(env_country_list("GEO_TEST_BLOCKED_OK").unwrap(), country_list("KP,IR"));
+
+        std::env::set_var("GEO_TEST_BLOCKED_BAD", "KP,Iran");
+        let err = env_country_list("GEO_TEST_BLOCKED_BAD").unwrap_err();
+        assert!(err.contains("'Iran'"), "{}", err);
+
+        assert!(env_country_list("GEO_TEST_BLOCKED_UNSET").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_allowlist_and_disabled_policy() {
+        let policy = policy().with_allowlist(vec![IpCidr::parse("203.0.113.0/24").unwrap()]);
+        assert!(policy.is_allowlisted(&"203.0.113.40".parse().unwrap()));
+        assert!(!policy.is_allowlisted(&"198.51.100.1".parse().unwrap()));
+
+        assert!(!GeoPolicy::default().is_enabled());
+    }
+
+    #[test]
+    fn test_parse_country() {
+        assert_eq!(parse_country("us\n"), Some("US".to_string()));
+        assert_eq!(parse_country(r#"{"country_code":"de","city":"Berlin"}"#), Some("DE".to_string()));
+        assert_eq!(parse_country(r#"{"countryCode":"FR"}"#), Some("FR".to_string()));
+        assert_eq!(parse_country(r#"{"country":"Germany"}"#), None);
+        assert_eq!(parse_country("Undefined"), None);
+        assert_eq!(parse_country("XX"), None);
+    }
+
+    #[test]
+    fn test_private_addresses_are_not_looked_up() {
+        assert!(!is_public(&"10.1.2.3".parse().unwrap()));
+        assert!(!is_public(&"127.0.0.1".parse().unwrap()));
+        assert!(!is_public(&"fd00::1".parse().unwrap()));
+        assert!(is_public(&"8.8.8.8".parse().unwrap()));
+        assert!(is_public(&"2001:4860::8888".parse().unwrap()));
+    }
+
+    #[tokio::test]
+    async fn test_header_source() {
+        let locator = GeoLocator::new(Some(GeoSource::Header(HeaderName::from_static("cf-ipcountry"))), reqwest::Client::new());
+        let mut headers = HeaderMap::new();
+        headers.insert("cf-ipcountry", "gb".parse().unwrap());
+        assert_eq!(locator.country(None, &headers).await, Some("GB".to_string()));
+
+        // Cloudflare reports Tor exits as "T1", which is not a country
+        headers.insert("cf-ipcountry", "T1".parse().unwrap());
+        assert_eq!(locator.country(None, &headers).await, None);
+    }
+
+    #[test]
+    fn test_blocked_response() {
+        let response = unavailable_for_legal_reasons(Some("US".to_string()), GeoFeature::Trade, None);
+        assert_eq!(response.status(), StatusCode::UNAVAILABLE_FOR_LEGAL_REASONS);
+    }
+}
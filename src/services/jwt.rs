@@ -1,8 +1,97 @@
 use chrono::{Duration, Utc};
 use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, TokenData, Validation};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
 use uuid::Uuid;
 
+/// Longest lifetime of a downscoped token handed to an integration
+pub const MAX_DOWNSCOPED_TOKEN_SECS: i64 = 24 * 3600;
+
+// =============================================================================
+// SCOPES
+// =============================================================================
+
+/// What an access token may be used for. Routes declare the scope they
+/// need; routes that declare none only accept tokens with every scope.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Scope {
+    /// Create swaps, orders and schedules, and manage them
+    SwapCreate,
+    /// Read swap history, orders, schedules and exports
+    HistoryRead,
+    /// Account settings, address book and linked logins
+    AccountManage,
+}
+
+impl Scope {
+    pub const ALL: [Scope; 3] = [Scope::SwapCreate, Scope::HistoryRead, Scope::AccountManage];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Scope::SwapCreate => "swap:create",
+            Scope::HistoryRead => "history:read",
+            Scope::AccountManage => "account:manage",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|s| s.as_str() == value)
+    }
+}
+
+/// The scopes a token grants
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Scopes(BTreeSet<Scope>);
+
+impl Scopes {
+    pub fn all() -> Self {
+        Self(Scope::ALL.into_iter().collect())
+    }
+
+    /// Space-separated scope names, as clients request them. Unknown names
+    /// and an empty list are errors.
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let scopes = value
+            .split_whitespace()
+            .map(|name| Scope::parse(name).ok_or_else(|| format!("Unknown scope: {}", name)))
+            .collect::<Result<BTreeSet<_>, _>>()?;
+        if scopes.is_empty() {
+            return Err("At least one scope is required".to_string());
+        }
+        Ok(Self(scopes))
+    }
+
+    /// Scopes from a token's claim. Tokens issued before scopes existed
+    /// carry none and keep full access until they expire; names this
+    /// version doesn't know are ignored.
+    fn from_claim(claim: Option<&str>) -> Self {
+        match claim {
+            None => Self::all(),
+            Some(claim) => Self(claim.split_whitespace().filter_map(Scope::parse).collect()),
+        }
+    }
+
+    pub fn contains(&self, scope: Scope) -> bool {
+        self.0.contains(&scope)
+    }
+
+    pub fn is_full(&self) -> bool {
+        Scope::ALL.iter().all(|s| self.contains(*s))
+    }
+
+    pub fn is_subset(&self, other: &Scopes) -> bool {
+        self.0.is_subset(&other.0)
+    }
+
+    pub fn to_claim(&self) -> String {
+        self.0.iter().map(Scope::as_str).collect::<Vec<_>>().join(" ")
+    }
+}
+
+// =============================================================================
+// CLAIMS
+// =============================================================================
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Claims {
     pub sub: String,        // user id
@@ -10,6 +99,22 @@ pub struct Claims {
     pub exp: i64,           // expiration time
     pub iat: i64,           // issued at
     pub jti: String,        // unique token id
+    /// The account's token version when issued. A password reset bumps
+    /// the version, which refuses every token issued before it.
+    #[serde(default)]
+    pub ver: u32,
+    /// Space-separated scopes; see [`Claims::scopes`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+    /// Integration a downscoped token was issued to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client: Option<String>,
+}
+
+impl Claims {
+    pub fn scopes(&self) -> Scopes {
+        Scopes::from_claim(self.scope.as_deref())
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -18,8 +123,21 @@ pub struct RefreshClaims {
     pub exp: i64,
     pub iat: i64,
     pub jti: String,        // unique token id
+    /// The account's token version when issued, as in [`Claims::ver`]
+    #[serde(default)]
+    pub ver: u32,
+    /// Scopes the refreshed access tokens get, so refreshing never widens them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+}
+
+impl RefreshClaims {
+    pub fn scopes(&self) -> Scopes {
+        Scopes::from_claim(self.scope.as_deref())
+    }
 }
 
+#[derive(Clone)]
 pub struct JwtService {
     secret: String,
     access_token_duration: Duration,
@@ -35,9 +153,42 @@ impl JwtService {
         }
     }
 
-    pub fn create_access_token(&self, user_id: &str, email: &str) -> Result<String, jsonwebtoken::errors::Error> {
+    pub fn create_access_token(
+        &self,
+        user_id: &str,
+        email: &str,
+        token_version: u32,
+        scopes: &Scopes,
+    ) -> Result<String, jsonwebtoken::errors::Error> {
+        self.encode_access_token(user_id, email, token_version, scopes, None, self.access_token_duration)
+    }
+
+    /// An access token for a third-party integration, limited to `scopes`
+    /// and living `ttl_secs` (at most [`MAX_DOWNSCOPED_TOKEN_SECS`])
+    pub fn create_downscoped_token(
+        &self,
+        user_id: &str,
+        email: &str,
+        token_version: u32,
+        scopes: &Scopes,
+        client: &str,
+        ttl_secs: i64,
+    ) -> Result<String, jsonwebtoken::errors::Error> {
+        let ttl = Duration::seconds(ttl_secs.clamp(1, MAX_DOWNSCOPED_TOKEN_SECS));
+        self.encode_access_token(user_id, email, token_version, scopes, Some(client), ttl)
+    }
+
+    fn encode_access_token(
+        &self,
+        user_id: &str,
+        email: &str,
+        token_version: u32,
+        scopes: &Scopes,
+        client: Option<&str>,
+        ttl: Duration,
+    ) -> Result<String, jsonwebtoken::errors::Error> {
         let now = Utc::now();
-        let exp = now + self.access_token_duration;
+        let exp = now + ttl;
 
         let claims = Claims {
             sub: user_id.to_string(),
@@ -45,6 +196,9 @@ impl JwtService {
             exp: exp.timestamp(),
             iat: now.timestamp(),
             jti: Uuid::new_v4().to_string(),
+            ver: token_version,
+            scope: Some(scopes.to_claim()),
+            client: client.map(str::to_string),
         };
 
         encode(
@@ -54,7 +208,12 @@ impl JwtService {
         )
     }
 
-    pub fn create_refresh_token(&self, user_id: &str) -> Result<String, jsonwebtoken::errors::Error> {
+    pub fn create_refresh_token(
+        &self,
+        user_id: &str,
+        token_version: u32,
+        scopes: &Scopes,
+    ) -> Result<String, jsonwebtoken::errors::Error> {
         let now = Utc::now();
         let exp = now + self.refresh_token_duration;
 
@@ -63,6 +222,8 @@ impl JwtService {
             exp: exp.timestamp(),
             iat: now.timestamp(),
             jti: Uuid::new_v4().to_string(),
+            ver: token_version,
+            scope: Some(scopes.to_claim()),
         };
 
         encode(
@@ -91,4 +252,42 @@ impl JwtService {
     pub fn get_access_token_duration_secs(&self) -> i64 {
         self.access_token_duration.num_seconds()
     }
+
+    pub fn get_refresh_token_duration_secs(&self) -> i64 {
+        self.refresh_token_duration.num_seconds()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scopes_parse_and_round_trip() {
+        let scopes = Scopes::parse("history:read  swap:create").unwrap();
+        assert!(scopes.contains(Scope::HistoryRead));
+        assert!(!scopes.contains(Scope::AccountManage));
+        assert!(!scopes.is_full());
+        assert!(scopes.is_subset(&Scopes::all()));
+        assert_eq!(scopes.to_claim(), "swap:create history:read");
+        assert_eq!(Scopes::parse(&scopes.to_claim()).unwrap(), scopes);
+
+        assert!(Scopes::parse("swap:create admin").is_err());
+        assert!(Scopes::parse("  ").is_err());
+    }
+
+    #[test]
+    fn test_access_token_carries_scopes() {
+        let jwt = JwtService::new("test-secret".to_string());
+        let scopes = Scopes::parse("history:read").unwrap();
+        let token = jwt.create_downscoped_token("user-1", "a@example.com", 3, &scopes, "tax-tool", 600).unwrap();
+        let claims = jwt.verify_access_token(&token).unwrap().claims;
+        assert_eq!(claims.scopes(), scopes);
+        assert_eq!(claims.client.as_deref(), Some("tax-tool"));
+        assert_eq!(claims.ver, 3);
+
+        // Tokens from before scopes existed keep full access
+        assert!(Scopes::from_claim(None).is_full());
+        assert!(Scopes::from_claim(Some("unknown:scope")).0.is_empty());
+    }
 }
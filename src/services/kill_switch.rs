@@ -0,0 +1,159 @@
+use std::sync::Arc;
+use std::time::Duration;
+use sqlx::{MySql, Pool};
+
+use crate::modules::halts::crud::HaltCrud;
+use crate::services::rpc::{EndpointHealthStatus, RpcManager};
+
+const DEFAULT_HEALTH_THRESHOLD: f64 = 0.3;
+const DEFAULT_CHECK_INTERVAL_SECS: u64 = 30;
+/// Health must climb this far above the threshold before an automatic halt
+/// is lifted, so a chain hovering at the threshold doesn't flap
+const RESUME_MARGIN: f64 = 0.1;
+
+/// What the guard does with a chain given its current RPC health
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChainHealthAction {
+    Halt,
+    Resume,
+    Hold,
+}
+
+impl ChainHealthAction {
+    pub fn for_health(health: f64, threshold: f64) -> Self {
+        if health < threshold {
+            ChainHealthAction::Halt
+        } else if health >= threshold + RESUME_MARGIN {
+            ChainHealthAction::Resume
+        } else {
+            ChainHealthAction::Hold
+        }
+    }
+}
+
+/// Health of a chain: the best score among its usable endpoints, since one
+/// good endpoint is enough to watch deposits and send payouts. `None` when
+/// the chain has no endpoints.
+pub fn chain_health(endpoints: &[EndpointHealthStatus]) -> Option<f64> {
+    if endpoints.is_empty() {
+        return None;
+    }
+    Some(
+        endpoints
+            .iter()
+            .filter(|e| e.is_healthy)
+            .map(|e| e.health_score)
+            .fold(0.0, f64::max),
+    )
+}
+
+/// Halts every pair on a chain when its RPC health drops below the threshold,
+/// and lifts that halt once the chain recovers. Halts set by operators are
+/// never touched.
+pub struct RpcHealthGuard {
+    db: Pool<MySql>,
+    rpc: Arc<RpcManager>,
+    threshold: f64,
+    interval: Duration,
+}
+
+impl RpcHealthGuard {
+    /// Settings from KILL_SWITCH_RPC_HEALTH_THRESHOLD (0.0-1.0, default 0.3)
+    /// and KILL_SWITCH_CHECK_INTERVAL_SECS (default 30)
+    pub fn new(db: Pool<MySql>, rpc: Arc<RpcManager>) -> Self {
+        let threshold = std::env::var("KILL_SWITCH_RPC_HEALTH_THRESHOLD")
+            .ok()
+            .and_then(|v| v.parse::<f64>().ok())
+            .map(|t| t.clamp(0.0, 1.0))
+            .unwrap_or(DEFAULT_HEALTH_THRESHOLD);
+
+        let interval_secs = std::env::var("KILL_SWITCH_CHECK_INTERVAL_SECS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .filter(|s| *s > 0)
+            .unwrap_or(DEFAULT_CHECK_INTERVAL_SECS);
+
+        Self { db, rpc, threshold, interval: Duration::from_secs(interval_secs) }
+    }
+
+    pub fn with_threshold(mut self, threshold: f64) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// Start the background health check loop
+    pub async fn run(&self) {
+        let mut interval = tokio::time::interval(self.interval);
+
+        loop {
+            interval.tick().await;
+
+            if let Err(e) = self.check().await {
+                tracing::error!("RPC health kill-switch check failed: {}", e);
+            }
+        }
+    }
+
+    /// Apply one round of halts and resumes. Returns the chains halted.
+    pub async fn check(&self) -> Result<Vec<String>, String> {
+        let crud = HaltCrud::new(self.db.clone());
+        let mut halted = Vec::new();
+
+        for chain in self.rpc.chains() {
+            let Some(health) = chain_health(&self.rpc.get_health_status(&chain).await) else {
+                continue;
+            };
+
+            match ChainHealthAction::for_health(health, self.threshold) {
+                ChainHealthAction::Halt => {
+                    let reason = format!("RPC health {:.2} below threshold {:.2}", health, self.threshold);
+                    if crud.halt_chain(&chain, &reason).await.map_err(|e| e.to_string())? {
+                        tracing::warn!("🛑 Trading halted on {}: {}", chain, reason);
+                    }
+                    halted.push(chain);
+                }
+                ChainHealthAction::Resume => {
+                    if crud.resume_chain(&chain).await.map_err(|e| e.to_string())? {
+                        tracing::info!("✅ Trading resumed on {} (RPC health {:.2})", chain, health);
+                    }
+                }
+                ChainHealthAction::Hold => {}
+            }
+        }
+
+        Ok(halted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn endpoint(health_score: f64, is_healthy: bool) -> EndpointHealthStatus {
+        EndpointHealthStatus {
+            url: "https://rpc.example".to_string(),
+            state: "Closed".to_string(),
+            health_score,
+            total_requests: 10,
+            success_rate: 1.0,
+            average_latency_ms: 100.0,
+            p95_latency_ms: None,
+            last_block_height: None,
+            is_healthy,
+        }
+    }
+
+    #[test]
+    fn test_chain_health_ignores_unhealthy_endpoints() {
+        assert_eq!(chain_health(&[]), None);
+        assert_eq!(chain_health(&[endpoint(0.9, false), endpoint(0.5, true)]), Some(0.5));
+        assert_eq!(chain_health(&[endpoint(0.9, false)]), Some(0.0));
+    }
+
+    #[test]
+    fn test_health_action_has_resume_margin() {
+        assert_eq!(ChainHealthAction::for_health(0.2, 0.3), ChainHealthAction::Halt);
+        assert_eq!(ChainHealthAction::for_health(0.35, 0.3), ChainHealthAction::Hold);
+        assert_eq!(ChainHealthAction::for_health(0.45, 0.3), ChainHealthAction::Resume);
+    }
+}
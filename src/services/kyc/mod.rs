@@ -0,0 +1,209 @@
+//! Identity verification through an external KYC provider. The provider
+//! holds the applicant and the document images; we keep the verified level
+//! per user and what was submitted. Review outcomes arrive by webhook.
+//!
+//! Swaps routed to providers whose KYC rating calls for it are refused
+//! until the user is verified to the level [`KycPolicy`] asks for.
+
+pub mod sumsub;
+
+use async_trait::async_trait;
+use axum::http::HeaderMap;
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock};
+
+use crate::modules::kyc::model::{DocumentSide, DocumentType, KycLevel};
+
+pub use sumsub::{SumsubConfig, SumsubProvider};
+
+/// Largest document image accepted, before base64 encoding
+pub const DEFAULT_MAX_DOCUMENT_BYTES: usize = 5 * 1024 * 1024;
+
+#[derive(Debug, thiserror::Error)]
+pub enum KycProviderError {
+    #[error("KYC provider is not configured: {0}")]
+    NotConfigured(String),
+
+    #[error("KYC provider request failed: {0}")]
+    Request(String),
+
+    #[error("KYC provider returned {status}: {body}")]
+    Status { status: u16, body: String },
+
+    #[error("Webhook signature is missing or invalid")]
+    InvalidSignature,
+
+    #[error("Unreadable provider response: {0}")]
+    InvalidPayload(String),
+}
+
+/// A document image on its way to the provider
+#[derive(Debug, Clone)]
+pub struct DocumentUpload<'a> {
+    pub document_type: DocumentType,
+    pub side: Option<DocumentSide>,
+    /// ISO 3166-1 alpha-3
+    pub country: &'a str,
+    pub file_name: &'a str,
+    pub content_type: &'a str,
+    pub content: &'a [u8],
+}
+
+/// What a review webhook says about an applicant
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReviewOutcome {
+    /// Submitted, or back in review
+    Pending,
+    Approved,
+    /// `retry` when the user may fix the documents and resubmit
+    Rejected { retry: bool, reason: Option<String> },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReviewEvent {
+    pub applicant_id: String,
+    pub outcome: ReviewOutcome,
+}
+
+#[async_trait]
+pub trait KycProvider: Send + Sync {
+    /// Stored with each applicant and used in the webhook path
+    fn name(&self) -> &'static str;
+
+    /// Create the applicant for `user_id` at `level`; returns the provider's
+    /// applicant id
+    async fn create_applicant(&self, user_id: &str, level: KycLevel) -> Result<String, KycProviderError>;
+
+    /// Move an existing applicant to a higher level
+    async fn change_level(&self, applicant_id: &str, level: KycLevel) -> Result<(), KycProviderError>;
+
+    /// Short-lived token for the provider's own capture SDK, for clients
+    /// that upload documents there rather than through us. None when the
+    /// provider has no SDK.
+    async fn sdk_token(&self, user_id: &str, level: KycLevel) -> Result<Option<String>, KycProviderError>;
+
+    /// Forward a document image; returns the provider's id for it if any
+    async fn upload_document(
+        &self,
+        applicant_id: &str,
+        document: &DocumentUpload<'_>,
+    ) -> Result<Option<String>, KycProviderError>;
+
+    /// Ask the provider to review what was uploaded
+    async fn request_review(&self, applicant_id: &str) -> Result<(), KycProviderError>;
+
+    /// Check a webhook's signature and read it. Ok(None) for events that
+    /// say nothing about a review.
+    fn parse_webhook(&self, headers: &HeaderMap, body: &[u8]) -> Result<Option<ReviewEvent>, KycProviderError>;
+}
+
+static KYC_PROVIDER: OnceLock<Option<Arc<dyn KycProvider>>> = OnceLock::new();
+
+/// Provider named by KYC_PROVIDER, loaded once. None while KYC is not set up.
+pub fn kyc_provider() -> Option<Arc<dyn KycProvider>> {
+    KYC_PROVIDER
+        .get_or_init(|| {
+            let name = std::env::var("KYC_PROVIDER").ok().filter(|v| !v.trim().is_empty())?;
+            let provider: Result<Arc<dyn KycProvider>, KycProviderError> =
+                match name.trim().to_ascii_lowercase().as_str() {
+                    "sumsub" => SumsubConfig::from_env().map(|config| Arc::new(SumsubProvider::new(config)) as _),
+                    other => Err(KycProviderError::NotConfigured(format!("unknown KYC_PROVIDER {}", other))),
+                };
+            provider.inspect_err(|e| tracing::warn!("KYC disabled: {}", e)).ok()
+        })
+        .clone()
+}
+
+/// Which swaps need a verified user, from KYC_* settings
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KycPolicy {
+    /// Level required to swap through providers of each KYC rating (A-D)
+    pub required: HashMap<String, KycLevel>,
+    pub max_document_bytes: usize,
+}
+
+impl KycPolicy {
+    /// KYC_REQUIRED_LEVELS is a list of `rating:level` pairs, e.g.
+    /// `C:basic,D:full`. Unset, no swap needs verification.
+    pub fn from_env() -> Result<Self, String> {
+        let required = match std::env::var("KYC_REQUIRED_LEVELS") {
+            Ok(list) => parse_required_levels(&list)?,
+            Err(_) => HashMap::new(),
+        };
+        let max_document_bytes = std::env::var("KYC_MAX_DOCUMENT_BYTES")
+            .ok()
+            .and_then(|v| v.trim().parse::<usize>().ok())
+            .filter(|v| *v > 0)
+            .unwrap_or(DEFAULT_MAX_DOCUMENT_BYTES);
+
+        Ok(Self { required, max_document_bytes })
+    }
+
+    /// Policy read from the environment once per process
+    pub fn global() -> &'static KycPolicy {
+        static POLICY: OnceLock<KycPolicy> = OnceLock::new();
+        POLICY.get_or_init(|| {
+            Self::from_env().unwrap_or_else(|e| {
+                tracing::warn!("Ignoring invalid KYC policy config: {}", e);
+                Self { max_document_bytes: DEFAULT_MAX_DOCUMENT_BYTES, ..Self::default() }
+            })
+        })
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.required.values().any(|level| *level > KycLevel::None)
+    }
+
+    /// Level needed to swap through a provider with `rating`. A provider we
+    /// have no rating for gets the strictest configured level.
+    pub fn required_level(&self, rating: Option<&str>) -> KycLevel {
+        match rating {
+            Some(rating) => self.required.get(&rating.trim().to_ascii_uppercase()).copied().unwrap_or_default(),
+            None => self.required.values().copied().max().unwrap_or_default(),
+        }
+    }
+}
+
+fn parse_required_levels(list: &str) -> Result<HashMap<String, KycLevel>, String> {
+    list.split(',')
+        .filter(|entry| !entry.trim().is_empty())
+        .map(|entry| {
+            let (rating, level) = entry.split_once(':').ok_or_else(|| format!("expected rating:level, got {}", entry))?;
+            let rating = rating.trim().to_ascii_uppercase();
+            if !matches!(rating.as_str(), "A" | "B" | "C" | "D") {
+                return Err(format!("unknown KYC rating {}", rating));
+            }
+            let level = KycLevel::parse(level).ok_or_else(|| format!("unknown KYC level {}", level.trim()))?;
+            Ok((rating, level))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_required_levels() {
+        let policy = KycPolicy { required: parse_required_levels("c:basic, D:full").unwrap(), ..Default::default() };
+        assert!(policy.is_enabled());
+        assert_eq!(policy.required_level(Some("A")), KycLevel::None);
+        assert_eq!(policy.required_level(Some("C")), KycLevel::Basic);
+        assert_eq!(policy.required_level(Some("d")), KycLevel::Full);
+        // Unrated providers get the strictest level
+        assert_eq!(policy.required_level(None), KycLevel::Full);
+
+        assert!(!KycPolicy::default().is_enabled());
+        assert_eq!(KycPolicy::default().required_level(None), KycLevel::None);
+
+        assert!(parse_required_levels("D").is_err());
+        assert!(parse_required_levels("E:basic").is_err());
+        assert!(parse_required_levels("D:gold").is_err());
+    }
+
+    #[test]
+    fn test_levels_are_ordered() {
+        assert!(KycLevel::Full > KycLevel::Basic);
+        assert!(KycLevel::Basic > KycLevel::None);
+    }
+}
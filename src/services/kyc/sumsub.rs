@@ -0,0 +1,412 @@
+//! SumSub applicant API. Requests are signed with the app secret
+//! (X-App-Access-Sig over timestamp, method, path and body); webhooks carry
+//! an HMAC of the raw body under a separate webhook secret.
+
+use async_trait::async_trait;
+use axum::http::HeaderMap;
+use hmac::{Hmac, Mac};
+use reqwest::{Client, Method, Url};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use sha1::Sha1;
+use sha2::{Sha256, Sha512};
+
+use super::{DocumentUpload, KycProvider, KycProviderError, ReviewEvent, ReviewOutcome};
+use crate::modules::kyc::model::{DocumentSide, DocumentType, KycLevel};
+
+const DEFAULT_BASE_URL: &str = "https://api.sumsub.com";
+const DEFAULT_BASIC_LEVEL: &str = "basic-kyc-level";
+const DEFAULT_FULL_LEVEL: &str = "full-kyc-level";
+/// Lifetime of WebSDK access tokens
+const SDK_TOKEN_TTL_SECS: u32 = 600;
+
+/// Connection settings, from SUMSUB_* settings
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SumsubConfig {
+    pub base_url: String,
+    pub app_token: String,
+    pub secret_key: String,
+    pub webhook_secret: String,
+    /// SumSub level names our levels map to
+    pub basic_level: String,
+    pub full_level: String,
+}
+
+impl SumsubConfig {
+    pub fn from_env() -> Result<Self, KycProviderError> {
+        let required = |name: &str| {
+            std::env::var(name)
+                .ok()
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+                .ok_or_else(|| KycProviderError::NotConfigured(format!("{} is not set", name)))
+        };
+        let optional = |name: &str, default: &str| {
+            std::env::var(name)
+                .ok()
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+                .unwrap_or_else(|| default.to_string())
+        };
+
+        Ok(Self {
+            base_url: optional("SUMSUB_BASE_URL", DEFAULT_BASE_URL).trim_end_matches('/').to_string(),
+            app_token: required("SUMSUB_APP_TOKEN")?,
+            secret_key: required("SUMSUB_SECRET_KEY")?,
+            webhook_secret: required("SUMSUB_WEBHOOK_SECRET")?,
+            basic_level: optional("SUMSUB_LEVEL_BASIC", DEFAULT_BASIC_LEVEL),
+            full_level: optional("SUMSUB_LEVEL_FULL", DEFAULT_FULL_LEVEL),
+        })
+    }
+
+    fn level_name(&self, level: KycLevel) -> &str {
+        match level {
+            KycLevel::Full => &self.full_level,
+            KycLevel::Basic | KycLevel::None => &self.basic_level,
+        }
+    }
+}
+
+pub struct SumsubProvider {
+    client: Client,
+    config: SumsubConfig,
+}
+
+impl SumsubProvider {
+    pub fn new(config: SumsubConfig) -> Self {
+        Self { client: Client::new(), config }
+    }
+
+    fn url(&self, path: &str, query: &[(&str, &str)]) -> Result<Url, KycProviderError> {
+        let mut url = Url::parse(&format!("{}{}", self.config.base_url, path))
+            .map_err(|e| KycProviderError::NotConfigured(format!("invalid SUMSUB_BASE_URL: {}", e)))?;
+        if !query.is_empty() {
+            url.query_pairs_mut().extend_pairs(query);
+        }
+        Ok(url)
+    }
+
+    /// Send a signed request and return the response
+    async fn send(
+        &self,
+        method: Method,
+        url: Url,
+        content_type: Option<&str>,
+        body: Vec<u8>,
+    ) -> Result<reqwest::Response, KycProviderError> {
+        let ts = chrono::Utc::now().timestamp().to_string();
+        let path = match url.query() {
+            Some(query) => format!("{}?{}", url.path(), query),
+            None => url.path().to_string(),
+        };
+        let signature = request_signature(&self.config.secret_key, &ts, method.as_str(), &path, &body);
+
+        let mut request = self
+            .client
+            .request(method, url)
+            .header("X-App-Token", &self.config.app_token)
+            .header("X-App-Access-Ts", ts)
+            .header("X-App-Access-Sig", signature)
+            .header("Accept", "application/json");
+        if let Some(content_type) = content_type {
+            request = request.header("Content-Type", content_type);
+        }
+
+        let response = request.body(body).send().await.map_err(|e| KycProviderError::Request(e.to_string()))?;
+        if !response.status().is_success() {
+            let status = response.status().as_u16();
+            let body = response.text().await.unwrap_or_default();
+            return Err(KycProviderError::Status { status, body });
+        }
+        Ok(response)
+    }
+
+    async fn send_json(&self, method: Method, url: Url, body: Option<&Value>) -> Result<Value, KycProviderError> {
+        let (content_type, bytes) = match body {
+            Some(body) => (Some("application/json"), serde_json::to_vec(body).unwrap_or_default()),
+            None => (None, Vec::new()),
+        };
+        let response = self.send(method, url, content_type, bytes).await?;
+        let text = response.text().await.map_err(|e| KycProviderError::Request(e.to_string()))?;
+        if text.trim().is_empty() {
+            return Ok(Value::Null);
+        }
+        serde_json::from_str(&text).map_err(|e| KycProviderError::InvalidPayload(e.to_string()))
+    }
+}
+
+#[async_trait]
+impl KycProvider for SumsubProvider {
+    fn name(&self) -> &'static str {
+        "sumsub"
+    }
+
+    async fn create_applicant(&self, user_id: &str, level: KycLevel) -> Result<String, KycProviderError> {
+        let url = self.url("/resources/applicants", &[("levelName", self.config.level_name(level))])?;
+        let response = self.send_json(Method::POST, url, Some(&json!({ "externalUserId": user_id }))).await?;
+        response
+            .get("id")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .ok_or_else(|| KycProviderError::InvalidPayload("applicant response has no id".to_string()))
+    }
+
+    async fn change_level(&self, applicant_id: &str, level: KycLevel) -> Result<(), KycProviderError> {
+        let url = self.url(
+            &format!("/resources/applicants/{}/moveToLevel", applicant_id),
+            &[("name", self.config.level_name(level))],
+        )?;
+        self.send_json(Method::POST, url, None).await?;
+        Ok(())
+    }
+
+    async fn sdk_token(&self, user_id: &str, level: KycLevel) -> Result<Option<String>, KycProviderError> {
+        let ttl = SDK_TOKEN_TTL_SECS.to_string();
+        let url = self.url(
+            "/resources/accessTokens",
+            &[("userId", user_id), ("levelName", self.config.level_name(level)), ("ttlInSecs", &ttl)],
+        )?;
+        let response = self.send_json(Method::POST, url, None).await?;
+        Ok(response.get("token").and_then(Value::as_str).map(str::to_string))
+    }
+
+    async fn upload_document(
+        &self,
+        applicant_id: &str,
+        document: &DocumentUpload<'_>,
+    ) -> Result<Option<String>, KycProviderError> {
+        let mut metadata = json!({
+            "idDocType": id_doc_type(document.document_type),
+            "country": document.country,
+        });
+        if let Some(side) = document.side {
+            metadata["idDocSubType"] = json!(match side {
+                DocumentSide::Front => "FRONT_SIDE",
+                DocumentSide::Back => "BACK_SIDE",
+            });
+        }
+
+        let boundary = format!("kyc-{}", uuid::Uuid::new_v4().simple());
+        let body = multipart_body(&boundary, &metadata.to_string(), document);
+        let url = self.url(&format!("/resources/applicants/{}/info/idDoc", applicant_id), &[])?;
+        let content_type = format!("multipart/form-data; boundary={}", boundary);
+        let response = self.send(Method::POST, url, Some(&content_type), body).await?;
+
+        Ok(response.headers().get("X-Image-Id").and_then(|v| v.to_str().ok()).map(str::to_string))
+    }
+
+    async fn request_review(&self, applicant_id: &str) -> Result<(), KycProviderError> {
+        let url = self.url(&format!("/resources/applicants/{}/status/pending", applicant_id), &[])?;
+        self.send_json(Method::POST, url, None).await?;
+        Ok(())
+    }
+
+    fn parse_webhook(&self, headers: &HeaderMap, body: &[u8]) -> Result<Option<ReviewEvent>, KycProviderError> {
+        let digest = headers
+            .get("X-Payload-Digest")
+            .and_then(|v| v.to_str().ok())
+            .ok_or(KycProviderError::InvalidSignature)?;
+        let algorithm = headers.get("X-Payload-Digest-Alg").and_then(|v| v.to_str().ok()).unwrap_or("HMAC_SHA1_HEX");
+        verify_payload_digest(&self.config.webhook_secret, algorithm, digest, body)?;
+
+        let payload: WebhookPayload =
+            serde_json::from_slice(body).map_err(|e| KycProviderError::InvalidPayload(e.to_string()))?;
+        Ok(payload.into_event())
+    }
+}
+
+/// X-App-Access-Sig: hex HMAC-SHA256 of timestamp, method, path with query
+/// and body, concatenated
+fn request_signature(secret: &str, ts: &str, method: &str, path: &str, body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC can take key of any size");
+    mac.update(ts.as_bytes());
+    mac.update(method.to_ascii_uppercase().as_bytes());
+    mac.update(path.as_bytes());
+    mac.update(body);
+    hex::encode(mac.finalize().into_bytes())
+}
+
+/// Check X-Payload-Digest in constant time
+fn verify_payload_digest(secret: &str, algorithm: &str, digest: &str, body: &[u8]) -> Result<(), KycProviderError> {
+    let expected = hex::decode(digest.trim()).map_err(|_| KycProviderError::InvalidSignature)?;
+    let verified = match algorithm {
+        "HMAC_SHA1_HEX" => verify_mac::<Hmac<Sha1>>(secret, body, &expected),
+        "HMAC_SHA256_HEX" => verify_mac::<Hmac<Sha256>>(secret, body, &expected),
+        "HMAC_SHA512_HEX" => verify_mac::<Hmac<Sha512>>(secret, body, &expected),
+        _ => false,
+    };
+    if verified {
+        Ok(())
+    } else {
+        Err(KycProviderError::InvalidSignature)
+    }
+}
+
+fn verify_mac<M: Mac + hmac::digest::KeyInit>(secret: &str, body: &[u8], expected: &[u8]) -> bool {
+    let Ok(mut mac) = <M as hmac::digest::KeyInit>::new_from_slice(secret.as_bytes()) else {
+        return false;
+    };
+    mac.update(body);
+    mac.verify_slice(expected).is_ok()
+}
+
+fn id_doc_type(document_type: DocumentType) -> &'static str {
+    match document_type {
+        DocumentType::Passport => "PASSPORT",
+        DocumentType::IdCard => "ID_CARD",
+        DocumentType::DrivingLicense => "DRIVERS",
+        DocumentType::ResidencePermit => "RESIDENCE_PERMIT",
+        DocumentType::Selfie => "SELFIE",
+        DocumentType::ProofOfAddress => "UTILITY_BILL",
+    }
+}
+
+/// multipart/form-data with the JSON `metadata` part and the `content` file
+fn multipart_body(boundary: &str, metadata: &str, document: &DocumentUpload<'_>) -> Vec<u8> {
+    let file_name: String = document.file_name.chars().filter(|c| !matches!(c, '"' | '\r' | '\n')).collect();
+    let mut body = Vec::with_capacity(document.content.len() + metadata.len() + 512);
+    body.extend_from_slice(
+        format!(
+            "--{b}\r\nContent-Disposition: form-data; name=\"metadata\"\r\n\r\n{metadata}\r\n\
+             --{b}\r\nContent-Disposition: form-data; name=\"content\"; filename=\"{file_name}\"\r\n\
+             Content-Type: {content_type}\r\n\r\n",
+            b = boundary,
+            metadata = metadata,
+            file_name = file_name,
+            content_type = document.content_type,
+        )
+        .as_bytes(),
+    );
+    body.extend_from_slice(document.content);
+    body.extend_from_slice(format!("\r\n--{}--\r\n", boundary).as_bytes());
+    body
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct WebhookPayload {
+    #[serde(rename = "type")]
+    kind: String,
+    applicant_id: String,
+    review_result: Option<ReviewResult>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ReviewResult {
+    review_answer: Option<String>,
+    review_reject_type: Option<String>,
+    /// Comment meant for the applicant
+    moderation_comment: Option<String>,
+    #[serde(default)]
+    reject_labels: Vec<String>,
+}
+
+impl WebhookPayload {
+    fn into_event(self) -> Option<ReviewEvent> {
+        let outcome = match self.kind.as_str() {
+            "applicantPending" | "applicantOnHold" => ReviewOutcome::Pending,
+            "applicantReviewed" => {
+                let result = self.review_result?;
+                match result.review_answer.as_deref()? {
+                    "GREEN" => ReviewOutcome::Approved,
+                    "RED" => ReviewOutcome::Rejected {
+                        retry: result.review_reject_type.as_deref() == Some("RETRY"),
+                        reason: result
+                            .moderation_comment
+                            .filter(|c| !c.trim().is_empty())
+                            .or_else(|| (!result.reject_labels.is_empty()).then(|| result.reject_labels.join(", "))),
+                    },
+                    _ => return None,
+                }
+            }
+            _ => return None,
+        };
+        Some(ReviewEvent { applicant_id: self.applicant_id, outcome })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn provider() -> SumsubProvider {
+        SumsubProvider::new(SumsubConfig {
+            base_url: DEFAULT_BASE_URL.to_string(),
+            app_token: "token".to_string(),
+            secret_key: "secret".to_string(),
+            webhook_secret: "webhook-secret".to_string(),
+            basic_level: DEFAULT_BASIC_LEVEL.to_string(),
+            full_level: DEFAULT_FULL_LEVEL.to_string(),
+        })
+    }
+
+    fn signed(body: &[u8], secret: &str) -> HeaderMap {
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+        mac.update(body);
+        let mut headers = HeaderMap::new();
+        headers.insert("X-Payload-Digest", hex::encode(mac.finalize().into_bytes()).parse().unwrap());
+        headers.insert("X-Payload-Digest-Alg", "HMAC_SHA256_HEX".parse().unwrap());
+        headers
+    }
+
+    #[test]
+    fn test_request_signature_covers_method_path_and_body() {
+        let a = request_signature("secret", "1700000000", "post", "/resources/applicants?levelName=basic", b"{}");
+        assert_eq!(a, request_signature("secret", "1700000000", "POST", "/resources/applicants?levelName=basic", b"{}"));
+        assert_ne!(a, request_signature("secret", "1700000000", "POST", "/resources/applicants?levelName=full", b"{}"));
+        assert_ne!(a, request_signature("secret", "1700000001", "POST", "/resources/applicants?levelName=basic", b"{}"));
+        assert_eq!(a.len(), 64);
+    }
+
+    #[test]
+    fn test_webhook_review_outcomes() {
+        let provider = provider();
+        let approved = br#"{"type":"applicantReviewed","applicantId":"app-1","reviewResult":{"reviewAnswer":"GREEN"}}"#;
+        let event = provider.parse_webhook(&signed(approved, "webhook-secret"), approved).unwrap().unwrap();
+        assert_eq!(event, ReviewEvent { applicant_id: "app-1".to_string(), outcome: ReviewOutcome::Approved });
+
+        let retry = br#"{"type":"applicantReviewed","applicantId":"app-1","reviewResult":{"reviewAnswer":"RED","reviewRejectType":"RETRY","rejectLabels":["BAD_PROOF_OF_IDENTITY"]}}"#;
+        let event = provider.parse_webhook(&signed(retry, "webhook-secret"), retry).unwrap().unwrap();
+        assert_eq!(
+            event.outcome,
+            ReviewOutcome::Rejected { retry: true, reason: Some("BAD_PROOF_OF_IDENTITY".to_string()) }
+        );
+
+        let created = br#"{"type":"applicantCreated","applicantId":"app-1"}"#;
+        assert!(provider.parse_webhook(&signed(created, "webhook-secret"), created).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_webhook_signature_is_checked() {
+        let provider = provider();
+        let body = br#"{"type":"applicantReviewed","applicantId":"app-1","reviewResult":{"reviewAnswer":"GREEN"}}"#;
+        assert!(matches!(
+            provider.parse_webhook(&signed(body, "wrong-secret"), body),
+            Err(KycProviderError::InvalidSignature)
+        ));
+        assert!(matches!(provider.parse_webhook(&HeaderMap::new(), body), Err(KycProviderError::InvalidSignature)));
+
+        // Valid digest, body altered afterwards
+        let headers = signed(body, "webhook-secret");
+        let tampered = br#"{"type":"applicantReviewed","applicantId":"app-2","reviewResult":{"reviewAnswer":"GREEN"}}"#;
+        assert!(provider.parse_webhook(&headers, tampered).is_err());
+    }
+
+    #[test]
+    fn test_multipart_body() {
+        let document = DocumentUpload {
+            document_type: DocumentType::Passport,
+            side: None,
+            country: "DEU",
+            file_name: "pass\"port.jpg",
+            content_type: "image/jpeg",
+            content: b"\xff\xd8image",
+        };
+        let body = multipart_body("b", r#"{"idDocType":"PASSPORT"}"#, &document);
+        let text = String::from_utf8_lossy(&body);
+        assert!(text.starts_with("--b\r\nContent-Disposition: form-data; name=\"metadata\"\r\n\r\n{"));
+        assert!(text.contains("filename=\"passport.jpg\""));
+        assert!(text.ends_with("\r\n--b--\r\n"));
+    }
+}
@@ -0,0 +1,56 @@
+//! Leader election over Redis leases. A role (e.g. one listener shard) is
+//! led by whichever process holds its lease; the leader renews it every
+//! time it acts, and a crashed leader's lease lapses after its TTL so
+//! another process can take over.
+
+use std::time::Duration;
+
+use crate::services::redis_cache::RedisService;
+
+const KEY_PREFIX: &str = "leader:";
+
+pub struct LeaderElection {
+    redis: RedisService,
+    /// Unique per process, so a restarted process never renews a lease it
+    /// held in a previous life
+    holder: String,
+    ttl: Duration,
+}
+
+impl LeaderElection {
+    pub fn new(redis: RedisService, ttl: Duration) -> Self {
+        Self {
+            redis,
+            holder: uuid::Uuid::new_v4().to_string(),
+            ttl,
+        }
+    }
+
+    /// Lease TTL from LEADER_LEASE_SECS (default 120). It has to outlast
+    /// the longest gap between renewals, or two processes may lead at once.
+    pub fn from_env(redis: RedisService) -> Self {
+        let secs = std::env::var("LEADER_LEASE_SECS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .filter(|secs| *secs > 0)
+            .unwrap_or(120);
+        Self::new(redis, Duration::from_secs(secs))
+    }
+
+    pub fn holder(&self) -> &str {
+        &self.holder
+    }
+
+    /// Take or renew the lease on `role`. True while this process leads it.
+    pub async fn try_lead(&self, role: &str) -> Result<bool, String> {
+        self.redis
+            .acquire_lease(&format!("{}{}", KEY_PREFIX, role), &self.holder, self.ttl.as_millis() as u64)
+            .await
+    }
+
+    /// Step down from `role` so another process can take it without
+    /// waiting for the lease to lapse
+    pub async fn resign(&self, role: &str) -> Result<(), String> {
+        self.redis.release_lease(&format!("{}{}", KEY_PREFIX, role), &self.holder).await
+    }
+}
@@ -0,0 +1,429 @@
+//! Pair liquidity matrix: which trading pairs providers will actually take,
+//! between what amounts and at which rate types. `PairLiquidityRefresher`
+//! probes a batch of the stalest pairs through a provider adapter on every
+//! pass and stores one row per provider in `pair_liquidity`;
+//! GET /swap/pairs/matrix serves the rows folded into one entry per pair,
+//! so frontends don't have to probe rates to find tradable pairs.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{MySql, Pool};
+
+use crate::modules::swap::crud::SwapCrud;
+use crate::modules::swap::schema::{PairLiquidityResponse, ProviderLiquidityResponse};
+use crate::services::amount;
+use crate::services::redis_cache::RedisService;
+use crate::services::trocador::TrocadorClient;
+
+/// Redis key the assembled matrix is cached under
+pub const MATRIX_CACHE_KEY: &str = "pairs:matrix";
+pub const MATRIX_CACHE_TTL_SECS: u64 = 60;
+
+const DEFAULT_REFRESH_INTERVAL: Duration = Duration::from_secs(300);
+const DEFAULT_BATCH_SIZE: u32 = 25;
+/// Held for a pass so only one replica probes providers at a time
+const REFRESH_LOCK_KEY: &str = "pairs:matrix:refresh";
+/// Probed when the source currency has no known minimum
+const FALLBACK_PROBE_AMOUNT: f64 = 1.0;
+
+/// One leg pair as the provider adapters name it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PairKey {
+    pub from: String,
+    pub network_from: String,
+    pub to: String,
+    pub network_to: String,
+}
+
+/// What one provider reported for one pair
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProviderLiquidity {
+    pub provider: String,
+    pub min_amount: Option<f64>,
+    pub max_amount: Option<f64>,
+    pub fixed_rate: bool,
+    pub floating_rate: bool,
+}
+
+/// Adapter for the providers behind the matrix
+#[async_trait]
+pub trait LiquiditySource: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// Every provider quoting `pair`, asked at `probe_amount` of the source
+    /// currency
+    async fn liquidity(&self, pair: &PairKey, probe_amount: f64) -> Result<Vec<ProviderLiquidity>, String>;
+}
+
+// =============================================================================
+// TROCADOR
+// =============================================================================
+
+pub struct TrocadorLiquiditySource {
+    client: TrocadorClient,
+}
+
+impl TrocadorLiquiditySource {
+    pub fn new(api_key: String) -> Self {
+        Self { client: TrocadorClient::new(api_key) }
+    }
+
+    pub fn from_env() -> Self {
+        Self::new(std::env::var("TROCADOR_API_KEY").unwrap_or_default())
+    }
+}
+
+#[async_trait]
+impl LiquiditySource for TrocadorLiquiditySource {
+    fn name(&self) -> &'static str {
+        "trocador"
+    }
+
+    async fn liquidity(&self, pair: &PairKey, probe_amount: f64) -> Result<Vec<ProviderLiquidity>, String> {
+        let probe_amount = amount::from_f64(probe_amount).map_err(|e| e.to_string())?;
+        let rates = self
+            .client
+            .get_rates(&pair.from, &pair.network_from, &pair.to, &pair.network_to, probe_amount)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let reported = rates.quotes.quotes.iter().map(|quote| ProviderLiquidity {
+            provider: SwapCrud::normalize_provider_id(&quote.provider),
+            min_amount: quote.min_amount,
+            max_amount: quote.max_amount,
+            fixed_rate: quote.is_fixed(),
+            floating_rate: !quote.is_fixed(),
+        });
+        Ok(merge_by_provider(reported))
+    }
+}
+
+/// One entry per provider; a provider quoting both rate types shows up twice
+fn merge_by_provider(reported: impl IntoIterator<Item = ProviderLiquidity>) -> Vec<ProviderLiquidity> {
+    let mut merged: BTreeMap<String, ProviderLiquidity> = BTreeMap::new();
+    for mut entry in reported {
+        entry.min_amount = entry.min_amount.filter(|a| a.is_finite() && *a > 0.0);
+        entry.max_amount = entry.max_amount.filter(|a| a.is_finite() && *a > 0.0);
+        match merged.get_mut(&entry.provider) {
+            Some(existing) => {
+                existing.min_amount = lowest(existing.min_amount, entry.min_amount);
+                existing.max_amount = highest(existing.max_amount, entry.max_amount);
+                existing.fixed_rate |= entry.fixed_rate;
+                existing.floating_rate |= entry.floating_rate;
+            }
+            None => {
+                merged.insert(entry.provider.clone(), entry);
+            }
+        }
+    }
+    merged.into_values().collect()
+}
+
+fn lowest(a: Option<f64>, b: Option<f64>) -> Option<f64> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+fn highest(a: Option<f64>, b: Option<f64>) -> Option<f64> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.max(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+// =============================================================================
+// MATRIX
+// =============================================================================
+
+/// A stored provider row with its pair, as read for the matrix
+#[derive(Debug, Clone)]
+pub struct LiquidityRow {
+    pub pair: PairKey,
+    pub liquidity: ProviderLiquidity,
+    pub refreshed_at: DateTime<Utc>,
+}
+
+/// Fold provider rows into one entry per pair. A pair is open from the
+/// lowest provider minimum to the highest provider maximum, and offers a
+/// rate type when any provider does. Rows must be sorted by pair.
+pub fn build_matrix(rows: Vec<LiquidityRow>) -> Vec<PairLiquidityResponse> {
+    let mut matrix: Vec<PairLiquidityResponse> = Vec::new();
+    let mut current: Option<PairKey> = None;
+
+    for row in rows {
+        let provider = ProviderLiquidityResponse {
+            provider: row.liquidity.provider,
+            min_amount: row.liquidity.min_amount,
+            max_amount: row.liquidity.max_amount,
+            fixed_rate: row.liquidity.fixed_rate,
+            floating_rate: row.liquidity.floating_rate,
+        };
+
+        match matrix.last_mut() {
+            Some(entry) if current.as_ref() == Some(&row.pair) => {
+                entry.min_amount = lowest(entry.min_amount, provider.min_amount);
+                entry.max_amount = highest(entry.max_amount, provider.max_amount);
+                entry.fixed_rate |= provider.fixed_rate;
+                entry.floating_rate |= provider.floating_rate;
+                entry.refreshed_at = entry.refreshed_at.min(row.refreshed_at);
+                entry.providers.push(provider);
+            }
+            _ => {
+                matrix.push(PairLiquidityResponse {
+                    from: row.pair.from.clone(),
+                    network_from: row.pair.network_from.clone(),
+                    to: row.pair.to.clone(),
+                    network_to: row.pair.network_to.clone(),
+                    min_amount: provider.min_amount,
+                    max_amount: provider.max_amount,
+                    fixed_rate: provider.fixed_rate,
+                    floating_rate: provider.floating_rate,
+                    refreshed_at: row.refreshed_at,
+                    providers: vec![provider],
+                });
+                current = Some(row.pair);
+            }
+        }
+    }
+
+    matrix
+}
+
+// =============================================================================
+// REFRESHER
+// =============================================================================
+
+/// A trading pair due for a probe
+struct StalePair {
+    id: i64,
+    key: PairKey,
+    probe_amount: f64,
+}
+
+pub struct PairLiquidityRefresher {
+    db: Pool<MySql>,
+    redis: Option<RedisService>,
+    source: Arc<dyn LiquiditySource>,
+    interval: Duration,
+    batch_size: u32,
+}
+
+impl PairLiquidityRefresher {
+    pub fn new(db: Pool<MySql>, source: Arc<dyn LiquiditySource>) -> Self {
+        let (interval, batch_size) = refresh_settings();
+        Self { db, redis: None, source, interval, batch_size }
+    }
+
+    /// Share passes between replicas and drop the cached matrix after each
+    pub fn with_redis(mut self, redis: RedisService) -> Self {
+        self.redis = Some(redis);
+        self
+    }
+
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    pub fn with_batch_size(mut self, batch_size: u32) -> Self {
+        self.batch_size = batch_size;
+        self
+    }
+
+    pub async fn run(&self) {
+        let mut interval = tokio::time::interval(self.interval);
+        loop {
+            interval.tick().await;
+            match self.refresh().await {
+                Ok(0) => {}
+                Ok(probed) => tracing::debug!("Refreshed liquidity for {} pairs from {}", probed, self.source.name()),
+                Err(e) => tracing::error!("Pair liquidity refresh failed: {}", e),
+            }
+        }
+    }
+
+    /// Probe the stalest pairs. Returns how many were probed; 0 when
+    /// another replica has this pass.
+    pub async fn refresh(&self) -> Result<usize, String> {
+        if let Some(redis) = &self.redis {
+            // Without Redis every replica refreshes, which is only wasteful
+            let lock_secs = self.interval.as_secs().saturating_sub(1).max(1);
+            if let Ok(false) = redis.try_lock(REFRESH_LOCK_KEY, lock_secs).await {
+                return Ok(0);
+            }
+        }
+
+        let pairs = self.stalest_pairs().await?;
+        for pair in &pairs {
+            match self.source.liquidity(&pair.key, pair.probe_amount).await {
+                Ok(liquidity) => self.store(pair.id, &liquidity).await?,
+                Err(e) => {
+                    // Keep the last good rows; the pair goes to the back of
+                    // the queue so one failing pair can't stall the rest
+                    tracing::warn!(
+                        "Liquidity probe for {}-{}/{}-{} failed: {}",
+                        pair.key.from, pair.key.network_from, pair.key.to, pair.key.network_to, e
+                    );
+                    self.mark_probed(pair.id).await?;
+                }
+            }
+        }
+
+        if let Some(redis) = &self.redis {
+            if !pairs.is_empty() {
+                let _ = redis.delete_prefix(MATRIX_CACHE_KEY).await;
+            }
+        }
+        Ok(pairs.len())
+    }
+
+    async fn stalest_pairs(&self) -> Result<Vec<StalePair>, String> {
+        let rows: Vec<(i64, String, String, String, String, Option<f64>)> = sqlx::query_as(
+            r#"
+            SELECT tp.id, c1.symbol, c1.network, c2.symbol, c2.network, c1.min_amount
+            FROM trading_pairs tp
+            INNER JOIN currencies c1 ON tp.from_currency_id = c1.id
+            INNER JOIN currencies c2 ON tp.to_currency_id = c2.id
+            WHERE tp.is_active = TRUE AND c1.is_active = TRUE AND c2.is_active = TRUE
+            ORDER BY tp.liquidity_refreshed_at IS NOT NULL, tp.liquidity_refreshed_at, tp.id
+            LIMIT ?
+            "#,
+        )
+        .bind(self.batch_size)
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| format!("Failed to load pairs to refresh: {}", e))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(id, from, network_from, to, network_to, min_amount)| StalePair {
+                id,
+                key: PairKey { from, network_from, to, network_to },
+                // Comfortably above the minimum, so providers quote it
+                probe_amount: min_amount.filter(|m| m.is_finite() && *m > 0.0).map(|m| m * 2.0).unwrap_or(FALLBACK_PROBE_AMOUNT),
+            })
+            .collect())
+    }
+
+    /// Replace a pair's provider rows with what was just reported
+    async fn store(&self, pair_id: i64, liquidity: &[ProviderLiquidity]) -> Result<(), String> {
+        let save = async {
+            let mut tx = self.db.begin().await?;
+            sqlx::query("DELETE FROM pair_liquidity WHERE trading_pair_id = ?")
+                .bind(pair_id)
+                .execute(&mut *tx)
+                .await?;
+            for entry in liquidity {
+                sqlx::query(
+                    r#"
+                    INSERT INTO pair_liquidity
+                        (trading_pair_id, provider_id, min_amount, max_amount, fixed_rate, floating_rate, refreshed_at)
+                    VALUES (?, ?, ?, ?, ?, ?, NOW())
+                    "#,
+                )
+                .bind(pair_id)
+                .bind(&entry.provider)
+                .bind(entry.min_amount)
+                .bind(entry.max_amount)
+                .bind(entry.fixed_rate)
+                .bind(entry.floating_rate)
+                .execute(&mut *tx)
+                .await?;
+            }
+            sqlx::query("UPDATE trading_pairs SET liquidity_refreshed_at = NOW() WHERE id = ?")
+                .bind(pair_id)
+                .execute(&mut *tx)
+                .await?;
+            tx.commit().await
+        };
+        save.await.map_err(|e: sqlx::Error| format!("Failed to store liquidity for pair {}: {}", pair_id, e))
+    }
+
+    async fn mark_probed(&self, pair_id: i64) -> Result<(), String> {
+        sqlx::query("UPDATE trading_pairs SET liquidity_refreshed_at = NOW() WHERE id = ?")
+            .bind(pair_id)
+            .execute(&self.db)
+            .await
+            .map(|_| ())
+            .map_err(|e| format!("Failed to mark pair {} probed: {}", pair_id, e))
+    }
+}
+
+/// PAIR_MATRIX_REFRESH_SECS (default 300) and PAIR_MATRIX_BATCH_SIZE
+/// (default 25)
+fn refresh_settings() -> (Duration, u32) {
+    let var = |name: &str| std::env::var(name).ok().and_then(|v| v.trim().parse::<u64>().ok()).filter(|v| *v > 0);
+    let interval = var("PAIR_MATRIX_REFRESH_SECS").map(Duration::from_secs).unwrap_or(DEFAULT_REFRESH_INTERVAL);
+    let batch_size = var("PAIR_MATRIX_BATCH_SIZE").map(|v| v.min(u32::MAX as u64) as u32).unwrap_or(DEFAULT_BATCH_SIZE);
+    (interval, batch_size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pair(from: &str, to: &str) -> PairKey {
+        PairKey {
+            from: from.to_string(),
+            network_from: "Mainnet".to_string(),
+            to: to.to_string(),
+            network_to: "Mainnet".to_string(),
+        }
+    }
+
+    fn liquidity(provider: &str, min: Option<f64>, max: Option<f64>, fixed: bool) -> ProviderLiquidity {
+        ProviderLiquidity {
+            provider: provider.to_string(),
+            min_amount: min,
+            max_amount: max,
+            fixed_rate: fixed,
+            floating_rate: !fixed,
+        }
+    }
+
+    #[test]
+    fn test_merge_by_provider() {
+        let merged = merge_by_provider([
+            liquidity("changenow", Some(0.01), Some(5.0), false),
+            liquidity("changenow", Some(0.02), Some(8.0), true),
+            liquidity("fixedfloat", Some(0.0), None, false),
+        ]);
+        assert_eq!(merged.len(), 2);
+        assert_eq!(merged[0].min_amount, Some(0.01));
+        assert_eq!(merged[0].max_amount, Some(8.0));
+        assert!(merged[0].fixed_rate && merged[0].floating_rate);
+        // A zero bound means the provider did not report one
+        assert_eq!(merged[1].min_amount, None);
+    }
+
+    #[test]
+    fn test_build_matrix_folds_providers_per_pair() {
+        let now = Utc::now();
+        let earlier = now - chrono::Duration::minutes(5);
+        let rows = vec![
+            LiquidityRow { pair: pair("BTC", "XMR"), liquidity: liquidity("changenow", Some(0.001), Some(2.0), false), refreshed_at: now },
+            LiquidityRow { pair: pair("BTC", "XMR"), liquidity: liquidity("fixedfloat", Some(0.0005), Some(1.0), true), refreshed_at: earlier },
+            LiquidityRow { pair: pair("ETH", "XMR"), liquidity: liquidity("changenow", None, None, false), refreshed_at: now },
+        ];
+
+        let matrix = build_matrix(rows);
+        assert_eq!(matrix.len(), 2);
+
+        let btc = &matrix[0];
+        assert_eq!(btc.providers.len(), 2);
+        assert_eq!(btc.min_amount, Some(0.0005));
+        assert_eq!(btc.max_amount, Some(2.0));
+        assert!(btc.fixed_rate && btc.floating_rate);
+        assert_eq!(btc.refreshed_at, earlier);
+
+        let eth = &matrix[1];
+        assert_eq!((eth.min_amount, eth.max_amount), (None, None));
+        assert!(!eth.fixed_rate);
+    }
+}
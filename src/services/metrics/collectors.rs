@@ -63,6 +63,53 @@ impl SwapMetricsCollector {
             .with_label_values(&[status])
             .set(count as f64);
     }
+    
+    /// Time spent in `stage` (a swap status) before the next transition
+    pub fn record_stage_duration(&self, stage: &str, duration_secs: f64) {
+        self.metrics
+            .swap_stage_duration_seconds
+            .with_label_values(&[stage])
+            .observe(duration_secs);
+    }
+}
+
+/// Collector for the quote-to-completion funnel
+pub struct FunnelMetricsCollector {
+    metrics: Arc<MetricsRegistry>,
+}
+
+impl FunnelMetricsCollector {
+    pub fn new(metrics: Arc<MetricsRegistry>) -> Self {
+        Self { metrics }
+    }
+    
+    /// Collector on the global registry, when metrics are enabled
+    pub fn global() -> Option<Self> {
+        MetricsRegistry::global().map(|metrics| Self::new(metrics.clone()))
+    }
+    
+    /// A journey reached `stage`, `step_secs` after the stage before it
+    pub fn record_stage(&self, stage: &str, base: &str, quote: &str, provider: &str, step_secs: Option<f64>) {
+        self.metrics
+            .swap_funnel_stage_total
+            .with_label_values(&[stage, base, quote, provider])
+            .inc();
+        
+        if let Some(secs) = step_secs {
+            self.metrics
+                .swap_funnel_step_seconds
+                .with_label_values(&[stage])
+                .observe(secs.max(0.0));
+        }
+    }
+    
+    /// A journey went no further than `stage`
+    pub fn record_abandoned(&self, stage: &str, base: &str, quote: &str, provider: &str) {
+        self.metrics
+            .swap_funnel_abandoned_total
+            .with_label_values(&[stage, base, quote, provider])
+            .inc();
+    }
 }
 
 /// Collector for payout metrics
@@ -111,6 +158,40 @@ impl PayoutMetricsCollector {
             .with_label_values(&[chain, currency, reason])
             .inc();
     }
+
+    /// Completed payout where the gas cost is not known to the caller
+    pub fn record_payout_executed(&self, chain: &str, currency: &str, duration_secs: f64) {
+        self.metrics
+            .payout_completed_total
+            .with_label_values(&[chain, currency])
+            .inc();
+
+        self.metrics
+            .payout_duration_seconds
+            .with_label_values(&[chain, currency])
+            .observe(duration_secs);
+    }
+
+    pub fn set_queue_depth(&self, chain: &str, depth: usize) {
+        self.metrics
+            .payout_queue_depth
+            .with_label_values(&[chain])
+            .set(depth as f64);
+    }
+
+    pub fn set_in_flight(&self, chain: &str, count: usize) {
+        self.metrics
+            .payout_in_flight
+            .with_label_values(&[chain])
+            .set(count as f64);
+    }
+
+    pub fn record_queue_wait(&self, chain: &str, wait_secs: f64) {
+        self.metrics
+            .payout_queue_wait_seconds
+            .with_label_values(&[chain])
+            .observe(wait_secs);
+    }
 }
 
 /// Collector for RPC metrics
@@ -123,6 +204,11 @@ impl RpcMetricsCollector {
         Self { metrics }
     }
     
+    /// Collector on the global registry, when metrics are enabled
+    pub fn global() -> Option<Self> {
+        MetricsRegistry::global().map(|metrics| Self::new(metrics.clone()))
+    }
+    
     pub fn set_health_score(&self, chain: &str, endpoint: &str, score: f64) {
         self.metrics
             .rpc_endpoint_health_score
@@ -162,6 +248,86 @@ impl RpcMetricsCollector {
             .with_label_values(&[chain, endpoint])
             .set(lag as f64);
     }
+    
+    /// A request to an endpoint in `region` (`untagged` when it has none);
+    /// `locality` is `same_region`, `cross_region` or `untagged`
+    pub fn record_region_request(&self, chain: &str, region: &str, locality: &str) {
+        self.metrics
+            .rpc_region_requests_total
+            .with_label_values(&[chain, region, locality])
+            .inc();
+    }
+}
+
+/// Collector for blockchain listener shard metrics
+pub struct ListenerMetricsCollector {
+    metrics: Arc<MetricsRegistry>,
+}
+
+impl ListenerMetricsCollector {
+    pub fn new(metrics: Arc<MetricsRegistry>) -> Self {
+        Self { metrics }
+    }
+    
+    pub fn set_lag_blocks(&self, shard: &str, chain: &str, blocks: u64) {
+        self.metrics
+            .listener_shard_lag_blocks
+            .with_label_values(&[shard, chain])
+            .set(blocks as f64);
+    }
+    
+    pub fn set_lag_seconds(&self, shard: &str, secs: i64) {
+        self.metrics
+            .listener_shard_lag_seconds
+            .with_label_values(&[shard])
+            .set(secs as f64);
+    }
+    
+    pub fn set_pending_swaps(&self, shard: &str, count: usize) {
+        self.metrics
+            .listener_shard_pending_swaps
+            .with_label_values(&[shard])
+            .set(count as f64);
+    }
+    
+    pub fn set_leader(&self, shard: &str, leading: bool) {
+        self.metrics
+            .listener_shard_leader
+            .with_label_values(&[shard])
+            .set(if leading { 1.0 } else { 0.0 });
+    }
+}
+
+/// Collector for swap provider API metrics
+pub struct ProviderMetricsCollector {
+    metrics: Arc<MetricsRegistry>,
+}
+
+impl ProviderMetricsCollector {
+    pub fn new(metrics: Arc<MetricsRegistry>) -> Self {
+        Self { metrics }
+    }
+    
+    /// Collector on the global registry, when metrics are enabled
+    pub fn global() -> Option<Self> {
+        MetricsRegistry::global().map(|metrics| Self::new(metrics.clone()))
+    }
+    
+    /// One call to `provider`, successful or not; `error` is the failure
+    /// kind of a failed call
+    pub fn record_request(&self, provider: &str, operation: &str, duration_secs: f64, error: Option<&str>) {
+        self.metrics
+            .provider_request_duration_seconds
+            .with_label_values(&[provider, operation])
+            .observe(duration_secs);
+        
+        if let Some(kind) = error {
+            self.metrics
+                .provider_errors_total
+                .with_label_values(&[provider, operation, kind])
+                .inc();
+        }
+    }
 }
 
 /// Collector for cache metrics
@@ -247,6 +413,13 @@ impl DatabaseMetricsCollector {
         self.metrics.db_connections_idle.set(idle as f64);
         self.metrics.db_connections_max.set(max as f64);
     }
+    
+    pub fn record_retention(&self, artifact: &str, action: &str, rows: u64) {
+        self.metrics
+            .retention_rows_total
+            .with_label_values(&[artifact, action])
+            .inc_by(rows as f64);
+    }
 }
 
 /// Collector for business metrics
@@ -0,0 +1,362 @@
+use axum::{
+    body::Body,
+    extract::MatchedPath,
+    http::Request,
+    response::Response,
+};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::{future::Future, pin::Pin, sync::Arc, time::{Duration, Instant}};
+use tower::{Layer, Service};
+use tracing::{field::{Field, Visit}, span, Subscriber};
+use tracing_subscriber::{layer::Context, registry::LookupSpan};
+
+use super::MetricsRegistry;
+use crate::services::api_version::unversioned;
+
+/// Budget for routes without an entry of their own
+const DEFAULT_BUDGET: Duration = Duration::from_millis(1000);
+/// Slowest operations listed in a violation warning
+const BREAKDOWN_LIMIT: usize = 5;
+
+/// Expected latency per route template, matching the targets the
+/// integration tests hold these endpoints to
+const DEFAULT_BUDGETS: &[(&str, u64)] = &[
+    ("/", 10),
+    ("/health", 10),
+    ("/swap/currencies", 50),
+    ("/swap/providers", 50),
+    ("/swap/pairs", 100),
+    ("/swap/estimate", 500),
+    ("/swap/rates", 2000),
+    ("/swap/{id}", 1000),
+    ("/swap/history", 200),
+];
+
+/// Latency budgets keyed by axum route template (`/swap/{id}`)
+#[derive(Debug, Clone)]
+pub struct LatencyBudgets {
+    routes: HashMap<String, Duration>,
+    default: Duration,
+}
+
+impl Default for LatencyBudgets {
+    fn default() -> Self {
+        Self {
+            routes: DEFAULT_BUDGETS
+                .iter()
+                .map(|(route, ms)| (route.to_string(), Duration::from_millis(*ms)))
+                .collect(),
+            default: DEFAULT_BUDGET,
+        }
+    }
+}
+
+impl LatencyBudgets {
+    pub fn with_route(mut self, route: &str, budget: Duration) -> Self {
+        self.routes.insert(route.to_string(), budget);
+        self
+    }
+
+    pub fn with_default(mut self, budget: Duration) -> Self {
+        self.default = budget;
+        self
+    }
+
+    /// Defaults plus overrides:
+    ///
+    /// - LATENCY_BUDGETS: `route=ms` pairs, e.g. `/swap/providers=50,/swap/rates=3000`
+    /// - LATENCY_BUDGET_DEFAULT_MS: budget for routes not listed
+    pub fn from_env() -> Result<Self, String> {
+        let mut budgets = Self::default();
+
+        if let Ok(spec) = std::env::var("LATENCY_BUDGETS") {
+            for entry in spec.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+                let (route, ms) = entry
+                    .rsplit_once('=')
+                    .ok_or_else(|| format!("Invalid LATENCY_BUDGETS entry '{}', expected route=ms", entry))?;
+                let ms = ms.trim().parse::<u64>()
+                    .map_err(|e| format!("Invalid budget for {}: {}", route, e))?;
+                budgets = budgets.with_route(route.trim(), Duration::from_millis(ms));
+            }
+        }
+
+        if let Ok(ms) = std::env::var("LATENCY_BUDGET_DEFAULT_MS") {
+            let ms = ms.trim().parse::<u64>()
+                .map_err(|e| format!("Invalid LATENCY_BUDGET_DEFAULT_MS: {}", e))?;
+            budgets = budgets.with_default(Duration::from_millis(ms));
+        }
+
+        Ok(budgets)
+    }
+
+    /// Budget of a route template, with or without its version prefix
+    pub fn budget_for(&self, route: &str) -> Duration {
+        self.routes.get(unversioned(route)).copied().unwrap_or(self.default)
+    }
+}
+
+// =============================================================================
+// PER-REQUEST BREAKDOWN
+// =============================================================================
+
+/// Time spent in one instrumented operation while serving a request
+#[derive(Debug, Clone, PartialEq)]
+pub struct Segment {
+    pub name: String,
+    pub elapsed: Duration,
+}
+
+tokio::task_local! {
+    static BREAKDOWN: RefCell<Vec<Segment>>;
+}
+
+/// Attribute time to the request being served on this task. A no-op
+/// outside `LatencyBudgetLayer` (background workers, spawned tasks).
+pub fn record_segment(name: impl Into<String>, elapsed: Duration) {
+    let _ = BREAKDOWN.try_with(|segments| {
+        segments.borrow_mut().push(Segment { name: name.into(), elapsed });
+    });
+}
+
+/// Total time and call count per operation, slowest first
+fn summarize(segments: &[Segment]) -> Vec<(String, Duration, usize)> {
+    let mut totals: HashMap<&str, (Duration, usize)> = HashMap::new();
+    for segment in segments {
+        let entry = totals.entry(segment.name.as_str()).or_default();
+        entry.0 += segment.elapsed;
+        entry.1 += 1;
+    }
+
+    let mut summary: Vec<(String, Duration, usize)> = totals
+        .into_iter()
+        .map(|(name, (elapsed, count))| (name.to_string(), elapsed, count))
+        .collect();
+    summary.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    summary.truncate(BREAKDOWN_LIMIT);
+    summary
+}
+
+fn format_breakdown(segments: &[Segment]) -> String {
+    let summary = summarize(segments);
+    if summary.is_empty() {
+        return "no instrumented operations".to_string();
+    }
+    summary
+        .iter()
+        .map(|(name, elapsed, count)| format!("{} x{} {}ms", name, count, elapsed.as_millis()))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Span name prefixes whose durations feed the breakdown
+const TRACKED_SPANS: &[&str] = &["db.", "rpc.", "provider.", "redis."];
+/// Span fields that make a segment name more specific
+const DETAIL_FIELDS: &[&str] = &["db.operation", "rpc.method", "provider.operation", "redis.op"];
+
+struct SpanTiming {
+    name: String,
+    started: Instant,
+}
+
+#[derive(Default)]
+struct DetailVisitor(Option<String>);
+
+impl Visit for DetailVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if self.0.is_none() && DETAIL_FIELDS.contains(&field.name()) {
+            self.0 = Some(value.to_string());
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if self.0.is_none() && DETAIL_FIELDS.contains(&field.name()) {
+            self.0 = Some(format!("{:?}", value).trim_matches('"').to_string());
+        }
+    }
+}
+
+/// Tracing layer that times DB, RPC, provider and cache spans and adds
+/// them to the breakdown of the request they ran under
+pub struct BreakdownLayer;
+
+impl<S> tracing_subscriber::Layer<S> for BreakdownLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &span::Attributes<'_>, id: &span::Id, ctx: Context<'_, S>) {
+        let name = attrs.metadata().name();
+        if !TRACKED_SPANS.iter().any(|prefix| name.starts_with(prefix)) {
+            return;
+        }
+
+        let mut detail = DetailVisitor::default();
+        attrs.record(&mut detail);
+        let name = match detail.0 {
+            Some(detail) => format!("{} {}", name, detail),
+            None => name.to_string(),
+        };
+
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().insert(SpanTiming { name, started: Instant::now() });
+        }
+    }
+
+    fn on_close(&self, id: span::Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(&id) {
+            if let Some(timing) = span.extensions_mut().remove::<SpanTiming>() {
+                record_segment(timing.name, timing.started.elapsed());
+            }
+        }
+    }
+}
+
+// =============================================================================
+// LAYER
+// =============================================================================
+
+/// Compares each request's latency with its route budget. Violations are
+/// logged at warn level with the slowest operations and counted in
+/// `exchange_http_latency_budget_exceeded_total` when metrics are attached.
+#[derive(Clone)]
+pub struct LatencyBudgetLayer {
+    budgets: Arc<LatencyBudgets>,
+    metrics: Option<Arc<MetricsRegistry>>,
+}
+
+impl LatencyBudgetLayer {
+    pub fn new(budgets: LatencyBudgets) -> Self {
+        Self { budgets: Arc::new(budgets), metrics: None }
+    }
+
+    pub fn with_metrics(mut self, metrics: Arc<MetricsRegistry>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+}
+
+impl<S> Layer<S> for LatencyBudgetLayer {
+    type Service = LatencyBudgetService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        LatencyBudgetService {
+            inner,
+            budgets: self.budgets.clone(),
+            metrics: self.metrics.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct LatencyBudgetService<S> {
+    inner: S,
+    budgets: Arc<LatencyBudgets>,
+    metrics: Option<Arc<MetricsRegistry>>,
+}
+
+impl<S> Service<Request<Body>> for LatencyBudgetService<S>
+where
+    S: Service<Request<Body>, Response = Response> + Clone + Send + 'static,
+    S::Future: Send,
+{
+    type Response = Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut std::task::Context<'_>) -> std::task::Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        let budgets = self.budgets.clone();
+        let metrics = self.metrics.clone();
+        let mut inner = self.inner.clone();
+
+        // Unmatched requests (404s) have no template and no budget
+        let route = request.extensions().get::<MatchedPath>().map(|p| p.as_str().to_string());
+        let method = request.method().to_string();
+
+        Box::pin(BREAKDOWN.scope(RefCell::new(Vec::new()), async move {
+            let started = Instant::now();
+            let response = inner.call(request).await?;
+            let elapsed = started.elapsed();
+
+            if let Some(route) = route {
+                let budget = budgets.budget_for(&route);
+                if elapsed > budget {
+                    let segments = BREAKDOWN.with(|s| s.take());
+                    tracing::warn!(
+                        route = %route,
+                        method = %method,
+                        elapsed_ms = elapsed.as_millis() as u64,
+                        budget_ms = budget.as_millis() as u64,
+                        "Latency budget exceeded: {} {} took {}ms (budget {}ms); slowest: {}",
+                        method,
+                        route,
+                        elapsed.as_millis(),
+                        budget.as_millis(),
+                        format_breakdown(&segments),
+                    );
+                    if let Some(metrics) = &metrics {
+                        metrics
+                            .http_latency_budget_exceeded_total
+                            .with_label_values(&[&method, &route])
+                            .inc();
+                    }
+                }
+            }
+
+            Ok(response)
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_budget_lookup_uses_route_template() {
+        let budgets = LatencyBudgets::default();
+        assert_eq!(budgets.budget_for("/swap/providers"), Duration::from_millis(50));
+        assert_eq!(budgets.budget_for("/swap/{id}"), Duration::from_millis(1000));
+        assert_eq!(budgets.budget_for("/v1/swap/{id}"), Duration::from_millis(1000));
+        assert_eq!(budgets.budget_for("/unlisted"), DEFAULT_BUDGET);
+
+        let custom = budgets.with_route("/swap/rates", Duration::from_millis(3000)).with_default(Duration::from_millis(250));
+        assert_eq!(custom.budget_for("/swap/rates"), Duration::from_millis(3000));
+        assert_eq!(custom.budget_for("/unlisted"), Duration::from_millis(250));
+    }
+
+    #[test]
+    fn test_breakdown_groups_and_orders_by_time() {
+        let segments = vec![
+            Segment { name: "redis.command GET".to_string(), elapsed: Duration::from_millis(2) },
+            Segment { name: "db.query".to_string(), elapsed: Duration::from_millis(30) },
+            Segment { name: "redis.command GET".to_string(), elapsed: Duration::from_millis(3) },
+            Segment { name: "provider.request coins".to_string(), elapsed: Duration::from_millis(40) },
+        ];
+
+        let summary = summarize(&segments);
+        assert_eq!(summary[0].0, "provider.request coins");
+        assert_eq!(summary[2], ("redis.command GET".to_string(), Duration::from_millis(5), 2));
+        assert_eq!(
+            format_breakdown(&segments),
+            "provider.request coins x1 40ms, db.query x1 30ms, redis.command GET x2 5ms"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_segments_are_scoped_to_request_task() {
+        record_segment("outside", Duration::from_millis(1));
+
+        let collected = BREAKDOWN
+            .scope(RefCell::new(Vec::new()), async {
+                record_segment("db.query", Duration::from_millis(4));
+                BREAKDOWN.with(|s| s.take())
+            })
+            .await;
+
+        assert_eq!(collected, vec![Segment { name: "db.query".to_string(), elapsed: Duration::from_millis(4) }]);
+    }
+}
@@ -1,6 +1,8 @@
 pub mod registry;
 pub mod middleware;
 pub mod collectors;
+pub mod latency_budget;
 
 pub use registry::MetricsRegistry;
 pub use middleware::metrics_middleware;
+pub use latency_budget::{LatencyBudgetLayer, LatencyBudgets};
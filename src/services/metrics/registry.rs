@@ -2,7 +2,9 @@ use prometheus::{
     Registry, CounterVec, HistogramVec, HistogramOpts, Gauge, GaugeVec, Opts,
     Encoder, TextEncoder,
 };
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
+
+static GLOBAL_REGISTRY: OnceLock<Arc<MetricsRegistry>> = OnceLock::new();
 
 /// Central metrics registry for the exchange platform
 pub struct MetricsRegistry {
@@ -12,6 +14,7 @@ pub struct MetricsRegistry {
     pub http_requests_total: CounterVec,
     pub http_request_duration_seconds: HistogramVec,
     pub http_response_size_bytes: HistogramVec,
+    pub http_latency_budget_exceeded_total: CounterVec,
     
     // Swap Metrics
     pub swap_initiated_total: CounterVec,
@@ -20,6 +23,10 @@ pub struct MetricsRegistry {
     pub swap_processing_duration_seconds: HistogramVec,
     pub swap_amount_usd: HistogramVec,
     pub swap_active_count: GaugeVec,
+    pub swap_stage_duration_seconds: HistogramVec,
+    pub swap_funnel_stage_total: CounterVec,
+    pub swap_funnel_abandoned_total: CounterVec,
+    pub swap_funnel_step_seconds: HistogramVec,
     
     // Payout Metrics
     pub payout_initiated_total: CounterVec,
@@ -27,6 +34,11 @@ pub struct MetricsRegistry {
     pub payout_failed_total: CounterVec,
     pub payout_duration_seconds: HistogramVec,
     pub payout_gas_cost: HistogramVec,
+    pub payout_queue_depth: GaugeVec,
+    pub payout_in_flight: GaugeVec,
+    pub payout_queue_wait_seconds: HistogramVec,
+    pub solana_transactions_total: CounterVec,
+    pub solana_priority_fee_micro_lamports: HistogramVec,
     
     // RPC Metrics
     pub rpc_endpoint_health_score: GaugeVec,
@@ -34,6 +46,17 @@ pub struct MetricsRegistry {
     pub rpc_request_duration_seconds: HistogramVec,
     pub rpc_circuit_breaker_state: GaugeVec,
     pub rpc_block_height_lag: GaugeVec,
+    pub rpc_region_requests_total: CounterVec,
+    
+    // Provider Metrics
+    pub provider_request_duration_seconds: HistogramVec,
+    pub provider_errors_total: CounterVec,
+    
+    // Listener Metrics
+    pub listener_shard_lag_blocks: GaugeVec,
+    pub listener_shard_lag_seconds: GaugeVec,
+    pub listener_shard_pending_swaps: GaugeVec,
+    pub listener_shard_leader: GaugeVec,
     
     // Cache Metrics
     pub cache_operations_total: CounterVec,
@@ -48,6 +71,7 @@ pub struct MetricsRegistry {
     pub db_connections_active: Gauge,
     pub db_connections_idle: Gauge,
     pub db_connections_max: Gauge,
+    pub retention_rows_total: CounterVec,
     
     // Business Metrics
     pub revenue_total_usd: CounterVec,
@@ -84,6 +108,13 @@ impl MetricsRegistry {
         )?;
         registry.register(Box::new(http_response_size_bytes.clone()))?;
         
+        let http_latency_budget_exceeded_total = CounterVec::new(
+            Opts::new("exchange_http_latency_budget_exceeded_total", "Requests slower than their route latency budget")
+                .namespace("exchange"),
+            &["method", "endpoint"],
+        )?;
+        registry.register(Box::new(http_latency_budget_exceeded_total.clone()))?;
+        
         // Swap Metrics
         let swap_initiated_total = CounterVec::new(
             Opts::new("exchange_swap_initiated_total", "Total swaps initiated")
@@ -129,6 +160,36 @@ impl MetricsRegistry {
         )?;
         registry.register(Box::new(swap_active_count.clone()))?;
         
+        let swap_stage_duration_seconds = HistogramVec::new(
+            HistogramOpts::new("exchange_swap_stage_duration_seconds", "Time a swap spent in a status before moving on")
+                .namespace("exchange")
+                .buckets(vec![5.0, 15.0, 30.0, 60.0, 120.0, 300.0, 600.0, 1800.0, 3600.0, 7200.0]),
+            &["stage"],
+        )?;
+        registry.register(Box::new(swap_stage_duration_seconds.clone()))?;
+        
+        let swap_funnel_stage_total = CounterVec::new(
+            Opts::new("exchange_swap_funnel_stage_total", "Swap journeys reaching a funnel stage (quoted, created, deposited, completed)")
+                .namespace("exchange"),
+            &["stage", "base_currency", "quote_currency", "provider"],
+        )?;
+        registry.register(Box::new(swap_funnel_stage_total.clone()))?;
+        
+        let swap_funnel_abandoned_total = CounterVec::new(
+            Opts::new("exchange_swap_funnel_abandoned_total", "Swap journeys that stopped at a funnel stage")
+                .namespace("exchange"),
+            &["stage", "base_currency", "quote_currency", "provider"],
+        )?;
+        registry.register(Box::new(swap_funnel_abandoned_total.clone()))?;
+        
+        let swap_funnel_step_seconds = HistogramVec::new(
+            HistogramOpts::new("exchange_swap_funnel_step_seconds", "Time from the previous funnel stage to this one")
+                .namespace("exchange")
+                .buckets(vec![5.0, 15.0, 30.0, 60.0, 120.0, 300.0, 600.0, 1800.0, 3600.0, 7200.0]),
+            &["stage"],
+        )?;
+        registry.register(Box::new(swap_funnel_step_seconds.clone()))?;
+        
         // Payout Metrics
         let payout_initiated_total = CounterVec::new(
             Opts::new("exchange_payout_initiated_total", "Total payouts initiated")
@@ -167,6 +228,43 @@ impl MetricsRegistry {
         )?;
         registry.register(Box::new(payout_gas_cost.clone()))?;
         
+        let payout_queue_depth = GaugeVec::new(
+            Opts::new("exchange_payout_queue_depth", "Payouts waiting in the executor queue")
+                .namespace("exchange"),
+            &["chain"],
+        )?;
+        registry.register(Box::new(payout_queue_depth.clone()))?;
+        
+        let payout_in_flight = GaugeVec::new(
+            Opts::new("exchange_payout_in_flight", "Payouts currently being executed")
+                .namespace("exchange"),
+            &["chain"],
+        )?;
+        registry.register(Box::new(payout_in_flight.clone()))?;
+        
+        let payout_queue_wait_seconds = HistogramVec::new(
+            HistogramOpts::new("exchange_payout_queue_wait_seconds", "Time a payout spent queued before execution")
+                .namespace("exchange")
+                .buckets(vec![0.1, 0.5, 1.0, 5.0, 15.0, 30.0, 60.0, 300.0, 900.0]),
+            &["chain"],
+        )?;
+        registry.register(Box::new(payout_queue_wait_seconds.clone()))?;
+        
+        let solana_transactions_total = CounterVec::new(
+            Opts::new("exchange_solana_transactions_total", "Broadcast Solana payouts by whether they landed")
+                .namespace("exchange"),
+            &["outcome", "priority"],
+        )?;
+        registry.register(Box::new(solana_transactions_total.clone()))?;
+        
+        let solana_priority_fee_micro_lamports = HistogramVec::new(
+            HistogramOpts::new("exchange_solana_priority_fee_micro_lamports", "Priority fee paid per compute unit on Solana payouts")
+                .namespace("exchange")
+                .buckets(vec![0.0, 100.0, 1_000.0, 10_000.0, 50_000.0, 100_000.0, 500_000.0, 1_000_000.0]),
+            &[],
+        )?;
+        registry.register(Box::new(solana_priority_fee_micro_lamports.clone()))?;
+        
         // RPC Metrics
         let rpc_endpoint_health_score = GaugeVec::new(
             Opts::new("exchange_rpc_endpoint_health_score", "RPC endpoint health score (0.0-1.0)")
@@ -204,6 +302,58 @@ impl MetricsRegistry {
         )?;
         registry.register(Box::new(rpc_block_height_lag.clone()))?;
         
+        let rpc_region_requests_total = CounterVec::new(
+            Opts::new("exchange_rpc_region_requests_total", "RPC requests by endpoint region and locality")
+                .namespace("exchange"),
+            &["chain", "region", "locality"],
+        )?;
+        registry.register(Box::new(rpc_region_requests_total.clone()))?;
+        
+        // Provider Metrics
+        let provider_request_duration_seconds = HistogramVec::new(
+            HistogramOpts::new("exchange_provider_request_duration_seconds", "Swap provider API call duration")
+                .namespace("exchange")
+                .buckets(vec![0.05, 0.1, 0.25, 0.5, 1.0, 2.0, 3.0, 5.0, 10.0, 20.0, 30.0]),
+            &["provider", "operation"],
+        )?;
+        registry.register(Box::new(provider_request_duration_seconds.clone()))?;
+        
+        let provider_errors_total = CounterVec::new(
+            Opts::new("exchange_provider_errors_total", "Failed swap provider API calls")
+                .namespace("exchange"),
+            &["provider", "operation", "kind"],
+        )?;
+        registry.register(Box::new(provider_errors_total.clone()))?;
+        
+        // Listener Metrics
+        let listener_shard_lag_blocks = GaugeVec::new(
+            Opts::new("exchange_listener_shard_lag_blocks", "Blocks mined since the shard's last completed scan")
+                .namespace("exchange"),
+            &["shard", "chain"],
+        )?;
+        registry.register(Box::new(listener_shard_lag_blocks.clone()))?;
+        
+        let listener_shard_lag_seconds = GaugeVec::new(
+            Opts::new("exchange_listener_shard_lag_seconds", "Seconds since the shard's last completed scan")
+                .namespace("exchange"),
+            &["shard"],
+        )?;
+        registry.register(Box::new(listener_shard_lag_seconds.clone()))?;
+        
+        let listener_shard_pending_swaps = GaugeVec::new(
+            Opts::new("exchange_listener_shard_pending_swaps", "Pending swaps scanned by the shard")
+                .namespace("exchange"),
+            &["shard"],
+        )?;
+        registry.register(Box::new(listener_shard_pending_swaps.clone()))?;
+        
+        let listener_shard_leader = GaugeVec::new(
+            Opts::new("exchange_listener_shard_leader", "Whether this process leads the shard (0/1)")
+                .namespace("exchange"),
+            &["shard"],
+        )?;
+        registry.register(Box::new(listener_shard_leader.clone()))?;
+        
         // Cache Metrics
         let cache_operations_total = CounterVec::new(
             Opts::new("exchange_cache_operations_total", "Total cache operations")
@@ -275,6 +425,13 @@ impl MetricsRegistry {
         )?;
         registry.register(Box::new(db_connections_max.clone()))?;
         
+        let retention_rows_total = CounterVec::new(
+            Opts::new("exchange_retention_rows_total", "Rows anonymized or purged by retention sweeps")
+                .namespace("exchange"),
+            &["artifact", "action"],
+        )?;
+        registry.register(Box::new(retention_rows_total.clone()))?;
+        
         // Business Metrics
         let revenue_total_usd = CounterVec::new(
             Opts::new("exchange_revenue_total_usd", "Total revenue in USD")
@@ -310,22 +467,39 @@ impl MetricsRegistry {
             http_requests_total,
             http_request_duration_seconds,
             http_response_size_bytes,
+            http_latency_budget_exceeded_total,
             swap_initiated_total,
             swap_completed_total,
             swap_failed_total,
             swap_processing_duration_seconds,
             swap_amount_usd,
             swap_active_count,
+            swap_stage_duration_seconds,
+            swap_funnel_stage_total,
+            swap_funnel_abandoned_total,
+            swap_funnel_step_seconds,
             payout_initiated_total,
             payout_completed_total,
             payout_failed_total,
             payout_duration_seconds,
             payout_gas_cost,
+            payout_queue_depth,
+            payout_in_flight,
+            payout_queue_wait_seconds,
+            solana_transactions_total,
+            solana_priority_fee_micro_lamports,
             rpc_endpoint_health_score,
             rpc_requests_total,
             rpc_request_duration_seconds,
             rpc_circuit_breaker_state,
             rpc_block_height_lag,
+            rpc_region_requests_total,
+            provider_request_duration_seconds,
+            provider_errors_total,
+            listener_shard_lag_blocks,
+            listener_shard_lag_seconds,
+            listener_shard_pending_swaps,
+            listener_shard_leader,
             cache_operations_total,
             cache_hit_ratio,
             cache_size_bytes,
@@ -336,6 +510,7 @@ impl MetricsRegistry {
             db_connections_active,
             db_connections_idle,
             db_connections_max,
+            retention_rows_total,
             revenue_total_usd,
             tvl_usd,
             user_swaps_total,
@@ -352,6 +527,18 @@ impl MetricsRegistry {
         Ok(String::from_utf8(buffer)?)
     }
     
+    /// Make this registry the one reported by [`MetricsRegistry::global`],
+    /// for code with no registry handed to it. Only the first call has an
+    /// effect.
+    pub fn install_global(self: &Arc<Self>) {
+        let _ = GLOBAL_REGISTRY.set(self.clone());
+    }
+    
+    /// The process's registry, when metrics are enabled
+    pub fn global() -> Option<&'static Arc<MetricsRegistry>> {
+        GLOBAL_REGISTRY.get()
+    }
+    
     /// Get the underlying registry
     pub fn registry(&self) -> &Registry {
         &self.registry
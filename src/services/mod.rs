@@ -1,9 +1,12 @@
 pub mod hashing;
 pub mod jwt;
+pub mod client_ip;
+pub mod geo;
 pub mod rate_limit;
 pub mod rate_limiter;
 pub mod redis_cache;
 pub mod security;
+pub mod session;
 pub mod wallet;
 pub mod trocador;
 pub mod monitor;
@@ -15,3 +18,47 @@ pub mod metrics;
 pub mod webhook;
 pub mod refund;
 pub mod token;
+pub mod encryption;
+pub mod pii;
+pub mod email;
+pub mod custody;
+pub mod schedule;
+pub mod orders;
+pub mod totp;
+pub mod oauth;
+pub mod payout;
+pub mod reconciliation;
+pub mod swap_state;
+pub mod storage;
+pub mod exports;
+pub mod explorer;
+pub mod telemetry;
+pub mod kill_switch;
+pub mod events;
+pub mod projection;
+pub mod warmup;
+pub mod etag;
+pub mod retention;
+pub mod amount;
+pub mod leader;
+pub mod quote_signing;
+pub mod network_status;
+pub mod status_page;
+pub mod watch_only;
+pub mod sandbox;
+pub mod simulation;
+pub mod usage;
+pub mod receipt;
+pub mod runtime_config;
+pub mod liquidity;
+pub mod risk;
+pub mod address_reputation;
+pub mod kyc;
+pub mod fx;
+pub mod provider_payloads;
+pub mod swap_sla;
+pub mod swap_eta;
+pub mod preflight;
+pub mod funnel;
+pub mod backup;
+pub mod api_version;
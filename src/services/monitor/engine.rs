@@ -1,37 +1,71 @@
-use std::time::Duration;
+use std::sync::Arc;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use sqlx::{MySql, Pool};
+use crate::modules::balances::crud::BalanceCrud;
 use crate::modules::monitor::crud::MonitorCrud;
-use crate::services::trocador::TrocadorClient;
+use crate::modules::payout_guard::model::PayoutKind;
+use crate::modules::swap::model::PayloadKind;
+use crate::modules::swap::schema::SwapStatus;
+use crate::modules::wallet::crud::WalletCrud;
+use crate::services::amount::Decimal;
+use crate::services::blockchain::listener::evm_rpc_url;
+use crate::services::gas::GasStation;
+use crate::services::token::permit::PermitRelayer;
+use crate::services::payout::{
+    PayoutBatcher, PayoutExecutor, PayoutGuard, PayoutHandler, PayoutJob, PayoutPriority, PlannedPayout,
+};
+use crate::services::swap_state::{StatusActor, SwapStateMachine, Transition};
+use crate::services::provider_payloads::ProviderPayloadStore;
+use crate::services::trocador::{self, TrocadorClient};
 use crate::services::wallet::manager::WalletManager;
-use crate::services::wallet::rpc::HttpRpcClient;
+use crate::services::wallet::rpc::{BlockchainProvider, HttpRpcClient};
+use crate::services::wallet::sequencer::{ChainSequencer, SolanaBlockhashSource};
+use crate::services::wallet::solana_rpc::SolanaProvider;
 use crate::services::redis_cache::RedisService;
 use crate::modules::monitor::model::PollingState;
-
-use crate::services::monitor::strategy::PollingStrategy;
+use crate::services::runtime_config::runtime_config;
+use crate::services::simulation::simulated;
 
 pub struct MonitorEngine {
     db: Pool<MySql>,
     redis: RedisService,
     master_seed: String,
-    strategy: PollingStrategy,
+    payout_executor: Option<PayoutExecutor>,
 }
 
 impl MonitorEngine {
     pub fn new(db: Pool<MySql>, redis: RedisService, master_seed: String) -> Self {
-        // Initialize strategy with default costs:
-        // Cp = 1.0 (one poll)
-        // Cd = 0.05 (20 seconds of delay equals cost of one poll)
-        let strategy = PollingStrategy::new(1.0, 0.05);
-        Self { db, redis, master_seed, strategy }
+        Self { db, redis, master_seed, payout_executor: None }
+    }
+
+    /// Queue payouts on a bounded executor pool instead of running them inline
+    pub fn with_payout_executor(mut self, executor: PayoutExecutor) -> Self {
+        self.payout_executor = Some(executor);
+        self
     }
 
-    /// Start the background polling loop
+    /// Start the background polling loop. The tick follows the
+    /// `monitor.tick_secs` runtime setting.
     pub async fn run(&self) {
-        let mut interval = tokio::time::interval(Duration::from_secs(10));
+        let mut config = runtime_config().subscribe();
+        let mut tick = config.borrow_and_update().monitor_tick();
+        let mut interval = tokio::time::interval(tick);
         let monitor_crud = MonitorCrud::new(self.db.clone());
 
         loop {
-            interval.tick().await;
+            tokio::select! {
+                _ = interval.tick() => {}
+                Ok(()) = config.changed() => {
+                    let next = config.borrow_and_update().monitor_tick();
+                    if next != tick {
+                        tracing::info!("Swap monitor tick changed to {:?}", next);
+                        tick = next;
+                        interval = tokio::time::interval(tick);
+                    }
+                    continue;
+                }
+            }
             
             if let Ok(polls) = monitor_crud.get_due_polls().await {
                 for poll in polls {
@@ -64,38 +98,11 @@ impl MonitorEngine {
             tracing::info!("Swap {} already has funds detected by blockchain listener, executing payout", state.swap_id);
             
             // Blockchain listener detected funds, now execute payout
-            let wallet_crud = crate::modules::wallet::crud::WalletCrud::new(self.db.clone());
-            let rpc_url = std::env::var("ETH_RPC_URL").unwrap_or_else(|_| "http://localhost:8545".to_string());
-            let provider: std::sync::Arc<dyn crate::services::wallet::rpc::BlockchainProvider> = 
-                std::sync::Arc::new(HttpRpcClient::new(rpc_url));
-            let wallet_manager = WalletManager::new(wallet_crud, self.master_seed.clone(), provider);
-            
-            match wallet_manager.process_payout(crate::modules::wallet::schema::PayoutRequest {
-                swap_id: state.swap_id.clone(),
-            }).await {
-                Ok(payout) => {
-                    tracing::info!(
-                        "✅ Payout successful for swap {}: tx_hash={}, amount={}",
-                        state.swap_id, payout.tx_hash, payout.amount
-                    );
-                    
-                    sqlx::query!("UPDATE swaps SET status = 'completed', updated_at = NOW() WHERE id = ?", state.swap_id)
-                        .execute(&self.db).await.ok();
-                    
-                    let monitor_crud = MonitorCrud::new(self.db.clone());
-                    let _ = monitor_crud.update_poll_result(&state.swap_id, "completed", 86400).await;
-                    
-                    return Ok(());
-                }
-                Err(e) => {
-                    tracing::error!("❌ Payout failed for swap {}: {}", state.swap_id, e);
-                    
-                    let monitor_crud = MonitorCrud::new(self.db.clone());
-                    let _ = monitor_crud.update_poll_result(&state.swap_id, "payout_failed", 300).await;
-                    
-                    return Ok(());
-                }
-            }
+            let rpc_url = evm_rpc_url("ethereum").unwrap_or_else(|| "http://localhost:8545".to_string());
+            let provider = simulated(Arc::new(HttpRpcClient::new(rpc_url)));
+            self.dispatch_payout(&state.swap_id, provider).await;
+
+            return Ok(());
         }
 
         let provider_swap_id = swap.provider_swap_id
@@ -107,6 +114,9 @@ impl MonitorEngine {
         
         let trocador_trade = client.get_trade_status(&provider_swap_id).await
             .map_err(|e| e.to_string())?;
+        ProviderPayloadStore::new(self.db.clone())
+            .capture(&state.swap_id, trocador::PROVIDER, PayloadKind::Status, trocador_trade.raw.as_ref())
+            .await;
 
         // 5. THE BRIDGE: Check blockchain and trigger payout if funds confirmed
         let final_status: String;
@@ -138,7 +148,7 @@ impl MonitorEngine {
             };
             
             // Check blockchain balance (fallback verification)
-            let rpc_url = std::env::var("ETH_RPC_URL").unwrap_or_else(|_| "http://localhost:8545".to_string());
+            let rpc_url = evm_rpc_url("ethereum").unwrap_or_else(|| "http://localhost:8545".to_string());
             let provider: std::sync::Arc<dyn crate::services::wallet::rpc::BlockchainProvider> = 
                 std::sync::Arc::new(HttpRpcClient::new(rpc_url));
             
@@ -151,32 +161,16 @@ impl MonitorEngine {
                     );
                     
                     // Update status to funds_received (in case listener missed it)
-                    sqlx::query!("UPDATE swaps SET status = 'funds_received', updated_at = NOW() WHERE id = ?", state.swap_id)
-                        .execute(&self.db).await.ok();
+                    let transition = Transition::to(SwapStatus::FundsReceived)
+                        .message("Detected by monitor fallback")
+                        .actor(StatusActor::Monitor);
+                    if let Err(e) = SwapStateMachine::new(self.db.clone()).advance(&state.swap_id, &transition).await {
+                        tracing::warn!("Could not mark swap {} as funds_received: {}", state.swap_id, e);
+                    }
                     
                     // Now safe to trigger payout
-                    let wallet_manager = WalletManager::new(wallet_crud, self.master_seed.clone(), provider);
-                    
-                    match wallet_manager.process_payout(crate::modules::wallet::schema::PayoutRequest {
-                        swap_id: state.swap_id.clone(),
-                    }).await {
-                        Ok(payout) => {
-                            tracing::info!(
-                                "✅ Payout successful for swap {}: tx_hash={}, amount={}",
-                                state.swap_id, payout.tx_hash, payout.amount
-                            );
-                            final_status = "completed".to_string();
-                            next_poll_secs = 3600 * 24; // Stop polling (once a day for cleanup)
-                            
-                            sqlx::query!("UPDATE swaps SET status = 'completed', updated_at = NOW() WHERE id = ?", state.swap_id)
-                                .execute(&self.db).await.ok();
-                        }
-                        Err(e) => {
-                            tracing::error!("❌ Payout failed for swap {}: {}", state.swap_id, e);
-                            final_status = "payout_failed".to_string();
-                            next_poll_secs = 300; // Retry in 5 minutes
-                        }
-                    }
+                    self.dispatch_payout(&state.swap_id, provider).await;
+                    return Ok(());
                 }
                 Ok(balance) => {
                     // Trocador says finished but funds not on chain yet
@@ -196,15 +190,21 @@ impl MonitorEngine {
         } else {
             final_status = trocador_trade.status.clone();
             // Update internal swap status if changed (e.g. 'confirming' -> 'sending')
-            if trocador_trade.status != swap.status {
-                sqlx::query!("UPDATE swaps SET status = ?, updated_at = NOW() WHERE id = ?", trocador_trade.status, state.swap_id)
-                    .execute(&self.db).await.ok();
+            let mapped = SwapStatus::from_provider_status(&trocador_trade.status);
+            if mapped.as_str() != swap.status {
+                let transition = Transition::to(mapped).actor(StatusActor::Provider);
+                if let Err(e) = SwapStateMachine::new(self.db.clone()).advance(&state.swap_id, &transition).await {
+                    tracing::warn!("Provider status update rejected for swap {}: {}", state.swap_id, e);
+                }
             }
             
             // 6. OPTIMAL POLLING LOGIC
+            // Costs come from the runtime settings: by default one poll
+            // costs as much as 20 seconds of delay
+            let strategy = runtime_config().current().polling_strategy();
             let elapsed = chrono::Utc::now() - swap.created_at;
             let elapsed_secs = elapsed.num_seconds().max(0) as u64;
-            next_poll_secs = self.strategy.calculate_next_interval(elapsed_secs).as_secs();
+            next_poll_secs = strategy.calculate_next_interval(elapsed_secs).as_secs();
         }
 
         // 7. Update Monitoring State
@@ -213,4 +213,181 @@ impl MonitorEngine {
 
         Ok(())
     }
-}
\ No newline at end of file
+
+    /// Pay out a funded swap, through the executor pool when one is configured
+    async fn dispatch_payout(&self, swap_id: &str, provider: Arc<dyn BlockchainProvider>) {
+        let Some(executor) = &self.payout_executor else {
+            let wallet_manager = WalletManager::new(WalletCrud::new(self.db.clone()), self.master_seed.clone(), provider);
+            let _ = execute_payout(&self.db, &wallet_manager, swap_id).await;
+            return;
+        };
+
+        let monitor_crud = MonitorCrud::new(self.db.clone());
+        let job = match payout_job(&self.db, swap_id).await {
+            Ok(job) => job,
+            Err(e) => {
+                tracing::error!("Failed to queue payout for swap {}: {}", swap_id, e);
+                let _ = monitor_crud.update_poll_result(swap_id, "payout_failed", 300).await;
+                return;
+            }
+        };
+
+        // Record the queued state first so a fast payout's "completed" is not overwritten
+        let _ = monitor_crud.update_poll_result(swap_id, "payout_queued", 60).await;
+
+        if let Err(e) = executor.submit(job) {
+            // Back off and let a later poll resubmit once the queue drains
+            tracing::warn!("Deferring payout for swap {}: {}", swap_id, e);
+            let _ = monitor_crud.update_poll_result(swap_id, "payout_deferred", 30).await;
+        }
+    }
+}
+
+/// Executes queued swap payouts for a [`PayoutExecutor`]
+pub struct SwapPayoutHandler {
+    db: Pool<MySql>,
+    master_seed: String,
+    gas_station: Option<Arc<GasStation>>,
+    permit_relayer: Option<Arc<PermitRelayer>>,
+    payout_batcher: Option<Arc<PayoutBatcher>>,
+    solana: Option<(Arc<dyn SolanaProvider>, Arc<ChainSequencer>)>,
+}
+
+impl SwapPayoutHandler {
+    pub fn new(db: Pool<MySql>, master_seed: String) -> Self {
+        Self { db, master_seed, gas_station: None, permit_relayer: None, payout_batcher: None, solana: None }
+    }
+
+    /// Pay out Solana swaps, sharing one blockhash cache between payouts
+    pub fn with_solana_provider(mut self, provider: Arc<dyn SolanaProvider>) -> Self {
+        let sequencer = ChainSequencer::new(Arc::new(SolanaBlockhashSource::new(provider.clone())));
+        self.solana = Some((provider, Arc::new(sequencer)));
+        self
+    }
+
+    /// Top up token-only deposit addresses before their payouts
+    pub fn with_gas_station(mut self, station: Arc<GasStation>) -> Self {
+        self.gas_station = Some(station);
+        self
+    }
+
+    /// Move permit-capable tokens with signed permits the hot wallet submits
+    pub fn with_permit_relayer(mut self, relayer: Arc<PermitRelayer>) -> Self {
+        self.permit_relayer = Some(relayer);
+        self
+    }
+
+    /// Send native payouts on chains with a disperse contract in batches
+    pub fn with_payout_batcher(mut self, batcher: Arc<PayoutBatcher>) -> Self {
+        self.payout_batcher = Some(batcher);
+        self
+    }
+}
+
+#[async_trait]
+impl PayoutHandler for SwapPayoutHandler {
+    async fn execute(&self, job: &PayoutJob) -> Result<String, String> {
+        let rpc_url = evm_rpc_url("ethereum").unwrap_or_else(|| "http://localhost:8545".to_string());
+        let provider = simulated(Arc::new(HttpRpcClient::new(rpc_url)));
+        let mut wallet_manager = WalletManager::new(WalletCrud::new(self.db.clone()), self.master_seed.clone(), provider);
+        if let Some(station) = &self.gas_station {
+            wallet_manager = wallet_manager.with_gas_station(station.clone());
+        }
+        if let Some(relayer) = &self.permit_relayer {
+            wallet_manager = wallet_manager.with_permit_relayer(relayer.clone());
+        }
+        if let Some(batcher) = &self.payout_batcher {
+            wallet_manager = wallet_manager.with_payout_batcher(batcher.clone());
+        }
+        if let Some((provider, sequencer)) = &self.solana {
+            wallet_manager = wallet_manager
+                .with_solana_sequencer(sequencer.clone())
+                .with_solana_provider(provider.clone());
+        }
+
+        execute_payout(&self.db, &wallet_manager, &job.swap_id).await
+    }
+}
+
+/// Build the queue entry for a funded swap. The amount is what reached our
+/// address (the most the payout can send), or the estimate before that is
+/// known. The lane comes from the swap owner's payout tier; anonymous swaps
+/// are standard.
+async fn payout_job(db: &Pool<MySql>, swap_id: &str) -> Result<PayoutJob, String> {
+    let (network, currency, amount, created_at, tier): (String, String, Decimal, DateTime<Utc>, String) = sqlx::query_as(
+        r#"
+        SELECT s.to_network, s.to_currency, COALESCE(sa.actual_received, s.estimated_receive), s.created_at,
+               COALESCE(CAST(u.payout_tier AS CHAR), 'standard')
+        FROM swaps s
+        LEFT JOIN swap_address_info sa ON sa.swap_id = s.id
+        LEFT JOIN users u ON u.id = s.user_id
+        WHERE s.id = ?
+        "#,
+    )
+    .bind(swap_id)
+    .fetch_optional(db)
+    .await
+    .map_err(|e| e.to_string())?
+    .ok_or_else(|| "Swap not found".to_string())?;
+
+    Ok(PayoutJob::new(swap_id, &network, &currency, amount, created_at).with_priority(PayoutPriority::from_tier(&tier)))
+}
+
+/// Settle a funded swap and record the outcome on the swap and its poll state
+async fn execute_payout(db: &Pool<MySql>, wallet_manager: &WalletManager, swap_id: &str) -> Result<String, String> {
+    let monitor_crud = MonitorCrud::new(db.clone());
+
+    match settle_swap(db, wallet_manager, swap_id).await {
+        Ok(reference) => {
+            tracing::info!("✅ Payout successful for swap {}: {}", swap_id, reference);
+
+            let transition = Transition::to(SwapStatus::Completed)
+                .tx_hash_out(reference.clone())
+                .actor(StatusActor::Payout);
+            if let Err(e) = SwapStateMachine::new(db.clone()).advance(swap_id, &transition).await {
+                tracing::error!("Payout for swap {} succeeded but status update failed: {}", swap_id, e);
+            }
+
+            let _ = monitor_crud.update_poll_result(swap_id, "completed", 86400).await;
+            Ok(reference)
+        }
+        Err(e) => {
+            tracing::error!("❌ Payout failed for swap {}: {}", swap_id, e);
+
+            let _ = monitor_crud.update_poll_result(swap_id, "payout_failed", 300).await;
+            Err(e)
+        }
+    }
+}
+
+/// Settle a funded swap. Deposit-to-balance swaps are credited to the
+/// owner's custodial ledger; everything else is paid out on-chain once the
+/// payout guard clears it.
+async fn settle_swap(db: &Pool<MySql>, wallet_manager: &WalletManager, swap_id: &str) -> Result<String, String> {
+    let payout_mode: Option<(String,)> =
+        sqlx::query_as("SELECT CAST(payout_mode AS CHAR) FROM swaps WHERE id = ?")
+            .bind(swap_id)
+            .fetch_optional(db)
+            .await
+            .map_err(|e| e.to_string())?;
+
+    if payout_mode.map(|m| m.0 == "balance").unwrap_or(false) {
+        let balance_crud = BalanceCrud::new(db.clone());
+        let credited = balance_crud.credit_swap_proceeds(swap_id).await
+            .map_err(|e| e.to_string())?;
+        return Ok(if credited { "credited to balance" } else { "balance already credited" }.to_string());
+    }
+
+    let job = payout_job(db, swap_id).await?;
+    let planned = PlannedPayout::new(PayoutKind::Swap, swap_id, &job.chain, &job.currency, job.amount);
+    let guard = PayoutGuard::new(db.clone());
+    let reservation = guard.check(&planned).await.map_err(|e| e.to_string())?;
+
+    let result = wallet_manager.process_payout(crate::modules::wallet::schema::PayoutRequest {
+        swap_id: swap_id.to_string(),
+    }).await;
+    guard.record(&reservation, result.as_ref().err().map(String::as_str)).await;
+    let payout = result?;
+
+    Ok(format!("tx_hash={}, amount={}", payout.tx_hash, payout.amount))
+}
@@ -1,5 +1,5 @@
 pub mod engine;
 pub mod strategy;
 
-pub use engine::MonitorEngine;
+pub use engine::{MonitorEngine, SwapPayoutHandler};
 pub use strategy::PollingStrategy;
@@ -0,0 +1,246 @@
+//! Per-chain health as shown to users: a chain is operational, degraded or
+//! down depending on its RPC endpoints' health scores and circuit breakers,
+//! and on whether trading on it has been halted. Backs /status/networks and
+//! the warnings attached to rate quotes.
+
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::modules::halts::crud::HaltSet;
+use crate::services::kill_switch::chain_health;
+use crate::services::rpc::{EndpointHealthStatus, RpcManager};
+
+const DEFAULT_DEGRADED_THRESHOLD: f64 = 0.6;
+
+/// Ordered from best to worst, so the worst of several is their `max`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum NetworkSeverity {
+    Operational,
+    Degraded,
+    Down,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct NetworkHealth {
+    pub network: String,
+    pub severity: NetworkSeverity,
+    /// Best score among usable endpoints, 0 to 1. Absent when the chain has
+    /// no monitored endpoints.
+    pub health_score: Option<f64>,
+    pub healthy_endpoints: usize,
+    pub total_endpoints: usize,
+    /// Endpoints whose circuit breaker is open or half-open
+    pub open_circuits: usize,
+    /// Trading on the chain is halted, by an operator or the RPC kill-switch
+    pub halted: bool,
+    /// User-facing explanation; absent when operational
+    pub message: Option<String>,
+}
+
+impl NetworkHealth {
+    /// Assess one chain from its endpoints. `halted` is the halt reason when
+    /// trading on the chain is halted (an empty string for none given).
+    pub fn assess(
+        network: &str,
+        endpoints: &[EndpointHealthStatus],
+        halted: Option<&str>,
+        degraded_threshold: f64,
+    ) -> Self {
+        let health_score = chain_health(endpoints);
+        let healthy_endpoints = endpoints.iter().filter(|e| e.is_healthy).count();
+        let open_circuits = endpoints.iter().filter(|e| e.state != "Closed").count();
+
+        let severity = if halted.is_some() || (!endpoints.is_empty() && healthy_endpoints == 0) {
+            NetworkSeverity::Down
+        } else if health_score.is_some_and(|score| score < degraded_threshold) || open_circuits > 0 {
+            NetworkSeverity::Degraded
+        } else {
+            NetworkSeverity::Operational
+        };
+
+        let message = match (severity, halted) {
+            (NetworkSeverity::Operational, _) => None,
+            (NetworkSeverity::Degraded, _) => Some(format!(
+                "The {} network is degraded; deposits and payouts may be delayed",
+                network
+            )),
+            (NetworkSeverity::Down, Some(reason)) if !reason.trim().is_empty() => Some(format!(
+                "Swaps on the {} network are paused: {}",
+                network,
+                reason.trim()
+            )),
+            (NetworkSeverity::Down, _) => Some(format!(
+                "The {} network is unavailable; swaps on it are paused",
+                network
+            )),
+        };
+
+        Self {
+            network: network.to_string(),
+            severity,
+            health_score,
+            healthy_endpoints,
+            total_endpoints: endpoints.len(),
+            open_circuits,
+            halted: halted.is_some(),
+            message,
+        }
+    }
+}
+
+/// A non-operational network attached to a rate quote
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct NetworkWarning {
+    pub network: String,
+    pub severity: NetworkSeverity,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct NetworkStatusReport {
+    /// Worst severity across all networks
+    pub overall: NetworkSeverity,
+    /// Whether RPC endpoints are monitored. When false only halted networks
+    /// are listed.
+    pub rpc_monitoring: bool,
+    pub networks: Vec<NetworkHealth>,
+    pub checked_at: DateTime<Utc>,
+}
+
+impl NetworkStatusReport {
+    /// Health of every monitored chain plus every chain with a chain-wide
+    /// halt, sorted by network name
+    pub async fn collect(rpc: Option<&RpcManager>, halts: &HaltSet) -> Self {
+        let threshold = degraded_threshold();
+        let halt_reason = |chain: &str| {
+            halts
+                .chain_halt(chain)
+                .map(|halt| halt.reason.as_deref().unwrap_or_default())
+        };
+
+        let mut networks = Vec::new();
+        if let Some(rpc) = rpc {
+            for chain in rpc.chains() {
+                let endpoints = rpc.get_health_status(&chain).await;
+                networks.push(NetworkHealth::assess(&chain, &endpoints, halt_reason(&chain), threshold));
+            }
+        }
+        for halt in halts.chain_halts() {
+            if !networks.iter().any(|n| n.network == halt.network) {
+                networks.push(NetworkHealth::assess(&halt.network, &[], halt_reason(&halt.network), threshold));
+            }
+        }
+        networks.sort_by(|a, b| a.network.cmp(&b.network));
+
+        Self {
+            overall: networks
+                .iter()
+                .map(|n| n.severity)
+                .max()
+                .unwrap_or(NetworkSeverity::Operational),
+            rpc_monitoring: rpc.is_some(),
+            networks,
+            checked_at: Utc::now(),
+        }
+    }
+
+    /// Warnings for the given chains, skipping operational ones and
+    /// naming each chain once
+    pub fn warnings_for(&self, chains: &[String]) -> Vec<NetworkWarning> {
+        let mut warnings: Vec<NetworkWarning> = Vec::new();
+        for chain in chains {
+            if warnings.iter().any(|w| &w.network == chain) {
+                continue;
+            }
+            if let Some(health) = self.networks.iter().find(|n| &n.network == chain) {
+                if let Some(message) = &health.message {
+                    warnings.push(NetworkWarning {
+                        network: health.network.clone(),
+                        severity: health.severity,
+                        message: message.clone(),
+                    });
+                }
+            }
+        }
+        warnings
+    }
+}
+
+/// Chains scoring below this are reported as degraded
+/// (NETWORK_DEGRADED_THRESHOLD, default 0.6)
+pub fn degraded_threshold() -> f64 {
+    std::env::var("NETWORK_DEGRADED_THRESHOLD")
+        .ok()
+        .and_then(|v| v.trim().parse::<f64>().ok())
+        .filter(|t| (0.0..=1.0).contains(t))
+        .unwrap_or(DEFAULT_DEGRADED_THRESHOLD)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn endpoint(state: &str, health_score: f64, is_healthy: bool) -> EndpointHealthStatus {
+        EndpointHealthStatus {
+            url: "https://rpc.example.com".to_string(),
+            state: state.to_string(),
+            health_score,
+            total_requests: 10,
+            success_rate: 1.0,
+            average_latency_ms: 100.0,
+            p95_latency_ms: Some(150),
+            last_block_height: None,
+            is_healthy,
+        }
+    }
+
+    #[test]
+    fn test_healthy_chain_is_operational() {
+        let health = NetworkHealth::assess("ethereum", &[endpoint("Closed", 0.95, true)], None, 0.6);
+        assert_eq!(health.severity, NetworkSeverity::Operational);
+        assert!(health.message.is_none());
+    }
+
+    #[test]
+    fn test_open_circuit_or_low_score_is_degraded() {
+        let endpoints = [endpoint("Closed", 0.95, true), endpoint("Open", 0.2, false)];
+        let health = NetworkHealth::assess("ethereum", &endpoints, None, 0.6);
+        assert_eq!(health.severity, NetworkSeverity::Degraded);
+        assert_eq!(health.open_circuits, 1);
+        assert_eq!(health.healthy_endpoints, 1);
+
+        let health = NetworkHealth::assess("tron", &[endpoint("Closed", 0.5, true)], None, 0.6);
+        assert_eq!(health.severity, NetworkSeverity::Degraded);
+    }
+
+    #[test]
+    fn test_no_healthy_endpoint_or_halt_is_down() {
+        let health = NetworkHealth::assess("bitcoin", &[endpoint("Open", 0.1, false)], None, 0.6);
+        assert_eq!(health.severity, NetworkSeverity::Down);
+
+        let health = NetworkHealth::assess("bitcoin", &[], Some("Node upgrade"), 0.6);
+        assert_eq!(health.severity, NetworkSeverity::Down);
+        assert!(health.halted);
+        assert!(health.message.unwrap().contains("Node upgrade"));
+    }
+
+    #[test]
+    fn test_warnings_skip_operational_chains() {
+        let report = NetworkStatusReport {
+            overall: NetworkSeverity::Degraded,
+            rpc_monitoring: true,
+            networks: vec![
+                NetworkHealth::assess("bitcoin", &[endpoint("Closed", 0.95, true)], None, 0.6),
+                NetworkHealth::assess("ethereum", &[endpoint("HalfOpen", 0.5, true)], None, 0.6),
+            ],
+            checked_at: Utc::now(),
+        };
+
+        let warnings = report.warnings_for(&["bitcoin".to_string(), "ethereum".to_string(), "ethereum".to_string()]);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].network, "ethereum");
+        assert_eq!(warnings[0].severity, NetworkSeverity::Degraded);
+    }
+}
@@ -0,0 +1,416 @@
+//! OAuth2 / OpenID Connect login with Google and Apple (authorization code
+//! flow with PKCE). Identity comes from the provider's signed ID token, which
+//! is checked against the provider's published keys.
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::Utc;
+use jsonwebtoken::{decode, decode_header, encode, jwk::JwkSet, Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::{OnceLock, RwLock};
+use std::time::{Duration, Instant};
+
+/// Provider signing keys rotate rarely; refetched on an unknown `kid` anyway
+const JWKS_TTL: Duration = Duration::from_secs(3600);
+/// Lifetime of the client secret JWT sent to Apple's token endpoint
+const APPLE_CLIENT_SECRET_SECS: i64 = 300;
+const VERIFIER_LEN: usize = 64;
+
+static JWKS_CACHE: OnceLock<RwLock<HashMap<OAuthProvider, (Instant, JwkSet)>>> = OnceLock::new();
+
+// =============================================================================
+// PROVIDERS
+// =============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, sqlx::Type, schemars::JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(rename_all = "snake_case")]
+pub enum OAuthProvider {
+    Google,
+    Apple,
+}
+
+impl OAuthProvider {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "google" => Some(OAuthProvider::Google),
+            "apple" => Some(OAuthProvider::Apple),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OAuthProvider::Google => "google",
+            OAuthProvider::Apple => "apple",
+        }
+    }
+
+    fn authorize_endpoint(&self) -> &'static str {
+        match self {
+            OAuthProvider::Google => "https://accounts.google.com/o/oauth2/v2/auth",
+            OAuthProvider::Apple => "https://appleid.apple.com/auth/authorize",
+        }
+    }
+
+    fn token_endpoint(&self) -> &'static str {
+        match self {
+            OAuthProvider::Google => "https://oauth2.googleapis.com/token",
+            OAuthProvider::Apple => "https://appleid.apple.com/auth/token",
+        }
+    }
+
+    fn jwks_uri(&self) -> &'static str {
+        match self {
+            OAuthProvider::Google => "https://www.googleapis.com/oauth2/v3/certs",
+            OAuthProvider::Apple => "https://appleid.apple.com/auth/keys",
+        }
+    }
+
+    fn issuers(&self) -> &'static [&'static str] {
+        match self {
+            OAuthProvider::Google => &["https://accounts.google.com", "accounts.google.com"],
+            OAuthProvider::Apple => &["https://appleid.apple.com"],
+        }
+    }
+}
+
+// =============================================================================
+// ERRORS
+// =============================================================================
+
+#[derive(Debug)]
+pub enum OAuthError {
+    NotConfigured(OAuthProvider),
+    RedirectUriNotAllowed,
+    ProviderError(String),
+    InvalidIdToken(String),
+}
+
+impl std::fmt::Display for OAuthError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OAuthError::NotConfigured(p) => write!(f, "{} login is not configured", p.as_str()),
+            OAuthError::RedirectUriNotAllowed => write!(f, "Redirect URI is not allowed"),
+            OAuthError::ProviderError(e) => write!(f, "OAuth provider error: {}", e),
+            OAuthError::InvalidIdToken(e) => write!(f, "Invalid ID token: {}", e),
+        }
+    }
+}
+
+// =============================================================================
+// PKCE
+// =============================================================================
+
+/// Random string from the RFC 7636 unreserved set, used for the PKCE
+/// verifier, `state` and `nonce`
+pub fn random_token(len: usize) -> String {
+    const CHARSET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-._~";
+    let mut rng = rand::rng();
+    (0..len).map(|_| CHARSET[rng.random_range(0..CHARSET.len())] as char).collect()
+}
+
+pub fn generate_code_verifier() -> String {
+    random_token(VERIFIER_LEN)
+}
+
+/// S256 code challenge for a verifier
+pub fn code_challenge(verifier: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()))
+}
+
+/// Tokens are stored hashed so a database read can't replay a login
+pub fn hash_state(value: &str) -> String {
+    hex::encode(Sha256::digest(value.as_bytes()))
+}
+
+// =============================================================================
+// ID TOKEN
+// =============================================================================
+
+/// Claims read from a provider ID token
+#[derive(Debug, Clone, Deserialize)]
+pub struct IdTokenClaims {
+    pub sub: String,
+    pub email: Option<String>,
+    /// A bool from Google, the string "true"/"false" from Apple
+    #[serde(default)]
+    email_verified: Option<serde_json::Value>,
+    pub nonce: Option<String>,
+}
+
+impl IdTokenClaims {
+    pub fn email_verified(&self) -> bool {
+        match &self.email_verified {
+            Some(serde_json::Value::Bool(b)) => *b,
+            Some(serde_json::Value::String(s)) => s == "true",
+            _ => false,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    id_token: Option<String>,
+    error: Option<String>,
+    error_description: Option<String>,
+}
+
+#[derive(Serialize)]
+struct AppleClientSecretClaims<'a> {
+    iss: &'a str,
+    iat: i64,
+    exp: i64,
+    aud: &'static str,
+    sub: &'a str,
+}
+
+// =============================================================================
+// CLIENT
+// =============================================================================
+
+/// Credentials for one provider
+#[derive(Clone)]
+enum ProviderCredentials {
+    Google { client_id: String, client_secret: String },
+    /// Apple's client secret is a short-lived ES256 JWT signed with a key
+    /// from the developer account
+    Apple { client_id: String, team_id: String, key_id: String, private_key: String },
+}
+
+impl ProviderCredentials {
+    fn client_id(&self) -> &str {
+        match self {
+            ProviderCredentials::Google { client_id, .. } | ProviderCredentials::Apple { client_id, .. } => client_id,
+        }
+    }
+}
+
+pub struct OAuthClient {
+    http: reqwest::Client,
+    credentials: HashMap<OAuthProvider, ProviderCredentials>,
+    redirect_uris: Vec<String>,
+}
+
+impl OAuthClient {
+    /// Providers are enabled by GOOGLE_OAUTH_CLIENT_ID/_SECRET and
+    /// APPLE_OAUTH_CLIENT_ID, APPLE_TEAM_ID, APPLE_KEY_ID, APPLE_PRIVATE_KEY.
+    /// OAUTH_REDIRECT_URIS lists the callback URLs clients may use.
+    pub fn from_env() -> Self {
+        let var = |name: &str| std::env::var(name).ok().filter(|v| !v.trim().is_empty());
+        let mut credentials = HashMap::new();
+
+        if let (Some(client_id), Some(client_secret)) = (var("GOOGLE_OAUTH_CLIENT_ID"), var("GOOGLE_OAUTH_CLIENT_SECRET")) {
+            credentials.insert(OAuthProvider::Google, ProviderCredentials::Google { client_id, client_secret });
+        }
+        if let (Some(client_id), Some(team_id), Some(key_id), Some(private_key)) = (
+            var("APPLE_OAUTH_CLIENT_ID"),
+            var("APPLE_TEAM_ID"),
+            var("APPLE_KEY_ID"),
+            var("APPLE_PRIVATE_KEY"),
+        ) {
+            // Keys pasted into a single env line keep their newlines escaped
+            let private_key = private_key.replace("\\n", "\n");
+            credentials.insert(OAuthProvider::Apple, ProviderCredentials::Apple { client_id, team_id, key_id, private_key });
+        }
+
+        let redirect_uris = var("OAUTH_REDIRECT_URIS")
+            .map(|v| v.split(',').map(|u| u.trim().to_string()).filter(|u| !u.is_empty()).collect())
+            .unwrap_or_default();
+
+        Self {
+            http: reqwest::Client::builder()
+                .timeout(Duration::from_secs(10))
+                .build()
+                .unwrap_or_default(),
+            credentials,
+            redirect_uris,
+        }
+    }
+
+    fn credentials(&self, provider: OAuthProvider) -> Result<&ProviderCredentials, OAuthError> {
+        self.credentials.get(&provider).ok_or(OAuthError::NotConfigured(provider))
+    }
+
+    pub fn check_redirect_uri(&self, redirect_uri: &str) -> Result<(), OAuthError> {
+        if self.redirect_uris.iter().any(|u| u == redirect_uri) {
+            Ok(())
+        } else {
+            Err(OAuthError::RedirectUriNotAllowed)
+        }
+    }
+
+    /// URL the user is sent to for consent
+    pub fn authorization_url(
+        &self,
+        provider: OAuthProvider,
+        redirect_uri: &str,
+        state: &str,
+        verifier: &str,
+        nonce: &str,
+    ) -> Result<String, OAuthError> {
+        let credentials = self.credentials(provider)?;
+        let challenge = code_challenge(verifier);
+        let mut params = vec![
+            ("response_type", "code"),
+            ("client_id", credentials.client_id()),
+            ("redirect_uri", redirect_uri),
+            ("scope", "openid email"),
+            ("state", state),
+            ("nonce", nonce),
+            ("code_challenge", challenge.as_str()),
+            ("code_challenge_method", "S256"),
+        ];
+        // Apple only returns the email scope to a form POST
+        if provider == OAuthProvider::Apple {
+            params.push(("response_mode", "form_post"));
+        }
+
+        reqwest::Url::parse_with_params(provider.authorize_endpoint(), &params)
+            .map(String::from)
+            .map_err(|e| OAuthError::ProviderError(e.to_string()))
+    }
+
+    /// Redeem an authorization code and return the verified ID token claims
+    pub async fn exchange_code(
+        &self,
+        provider: OAuthProvider,
+        code: &str,
+        verifier: &str,
+        redirect_uri: &str,
+    ) -> Result<IdTokenClaims, OAuthError> {
+        let credentials = self.credentials(provider)?;
+        let client_secret = match credentials {
+            ProviderCredentials::Google { client_secret, .. } => client_secret.clone(),
+            ProviderCredentials::Apple { client_id, team_id, key_id, private_key } => {
+                apple_client_secret(client_id, team_id, key_id, private_key)?
+            }
+        };
+
+        let response: TokenResponse = self
+            .http
+            .post(provider.token_endpoint())
+            .form(&[
+                ("grant_type", "authorization_code"),
+                ("code", code),
+                ("redirect_uri", redirect_uri),
+                ("client_id", credentials.client_id()),
+                ("client_secret", client_secret.as_str()),
+                ("code_verifier", verifier),
+            ])
+            .send()
+            .await
+            .map_err(|e| OAuthError::ProviderError(e.to_string()))?
+            .json()
+            .await
+            .map_err(|e| OAuthError::ProviderError(e.to_string()))?;
+
+        if let Some(error) = response.error {
+            let detail = response.error_description.map(|d| format!(": {}", d)).unwrap_or_default();
+            return Err(OAuthError::ProviderError(format!("{}{}", error, detail)));
+        }
+        let id_token = response
+            .id_token
+            .ok_or_else(|| OAuthError::ProviderError("token response has no id_token".to_string()))?;
+
+        self.verify_id_token(provider, &id_token).await
+    }
+
+    async fn verify_id_token(&self, provider: OAuthProvider, id_token: &str) -> Result<IdTokenClaims, OAuthError> {
+        let header = decode_header(id_token).map_err(|e| OAuthError::InvalidIdToken(e.to_string()))?;
+        let kid = header.kid.ok_or_else(|| OAuthError::InvalidIdToken("missing kid".to_string()))?;
+
+        let jwk = match cached_jwks(provider).and_then(|set| set.find(&kid).cloned()) {
+            Some(jwk) => jwk,
+            None => {
+                let set = self.fetch_jwks(provider).await?;
+                set.find(&kid)
+                    .cloned()
+                    .ok_or_else(|| OAuthError::InvalidIdToken(format!("unknown signing key {}", kid)))?
+            }
+        };
+        let key = DecodingKey::from_jwk(&jwk).map_err(|e| OAuthError::InvalidIdToken(e.to_string()))?;
+
+        let mut validation = Validation::new(Algorithm::RS256);
+        validation.set_audience(&[self.credentials(provider)?.client_id()]);
+        validation.set_issuer(provider.issuers());
+
+        decode::<IdTokenClaims>(id_token, &key, &validation)
+            .map(|data| data.claims)
+            .map_err(|e| OAuthError::InvalidIdToken(e.to_string()))
+    }
+
+    async fn fetch_jwks(&self, provider: OAuthProvider) -> Result<JwkSet, OAuthError> {
+        let set: JwkSet = self
+            .http
+            .get(provider.jwks_uri())
+            .send()
+            .await
+            .map_err(|e| OAuthError::ProviderError(e.to_string()))?
+            .json()
+            .await
+            .map_err(|e| OAuthError::ProviderError(e.to_string()))?;
+
+        if let Ok(mut cache) = JWKS_CACHE.get_or_init(Default::default).write() {
+            cache.insert(provider, (Instant::now(), set.clone()));
+        }
+        Ok(set)
+    }
+}
+
+fn cached_jwks(provider: OAuthProvider) -> Option<JwkSet> {
+    let cache = JWKS_CACHE.get_or_init(Default::default).read().ok()?;
+    let (fetched_at, set) = cache.get(&provider)?;
+    (fetched_at.elapsed() < JWKS_TTL).then(|| set.clone())
+}
+
+fn apple_client_secret(client_id: &str, team_id: &str, key_id: &str, private_key: &str) -> Result<String, OAuthError> {
+    let now = Utc::now().timestamp();
+    let claims = AppleClientSecretClaims {
+        iss: team_id,
+        iat: now,
+        exp: now + APPLE_CLIENT_SECRET_SECS,
+        aud: "https://appleid.apple.com",
+        sub: client_id,
+    };
+    let mut header = Header::new(Algorithm::ES256);
+    header.kid = Some(key_id.to_string());
+
+    let key = EncodingKey::from_ec_pem(private_key.as_bytes())
+        .map_err(|e| OAuthError::ProviderError(format!("invalid APPLE_PRIVATE_KEY: {}", e)))?;
+    encode(&header, &claims, &key).map_err(|e| OAuthError::ProviderError(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_code_challenge_rfc7636_vector() {
+        assert_eq!(
+            code_challenge("dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk"),
+            "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSZw-cM"
+        );
+    }
+
+    #[test]
+    fn test_code_verifier_is_unreserved() {
+        let verifier = generate_code_verifier();
+        assert_eq!(verifier.len(), VERIFIER_LEN);
+        assert!(verifier.chars().all(|c| c.is_ascii_alphanumeric() || "-._~".contains(c)));
+    }
+
+    #[test]
+    fn test_email_verified_accepts_apple_strings() {
+        let claims: IdTokenClaims = serde_json::from_value(serde_json::json!({
+            "sub": "001", "email": "a@b.c", "email_verified": "true"
+        }))
+        .unwrap();
+        assert!(claims.email_verified());
+
+        let claims: IdTokenClaims = serde_json::from_value(serde_json::json!({ "sub": "002" })).unwrap();
+        assert!(!claims.email_verified());
+    }
+}
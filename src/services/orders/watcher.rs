@@ -0,0 +1,205 @@
+use std::sync::Arc;
+use std::time::Duration;
+use sqlx::{MySql, Pool};
+
+use crate::modules::orders::crud::OrderCrud;
+use crate::modules::orders::model::ConditionalOrder;
+use crate::modules::swap::crud::SwapCrud;
+use crate::modules::swap::schema::{CreateSwapRequest, RateResponse, RateType, RatesQuery};
+use crate::services::email::{queued_email_sender, EmailMessage, EmailSender};
+use crate::services::redis_cache::RedisService;
+
+const BATCH_SIZE: i64 = 100;
+
+/// Watches quotes for pending conditional orders and creates the swap
+/// once the target rate is reached
+pub struct OrderWatcher {
+    db: Pool<MySql>,
+    redis: RedisService,
+    wallet_mnemonic: String,
+    email_sender: Arc<dyn EmailSender>,
+}
+
+impl OrderWatcher {
+    pub fn new(db: Pool<MySql>, redis: RedisService, wallet_mnemonic: String) -> Self {
+        let email_sender = queued_email_sender(db.clone());
+        Self {
+            db,
+            redis,
+            wallet_mnemonic,
+            email_sender,
+        }
+    }
+
+    pub fn with_email_sender(mut self, sender: Arc<dyn EmailSender>) -> Self {
+        self.email_sender = sender;
+        self
+    }
+
+    /// Start the background watch loop
+    pub async fn run(&self) {
+        let mut interval = tokio::time::interval(Duration::from_secs(30));
+
+        loop {
+            interval.tick().await;
+
+            if let Err(e) = self.check_pending().await {
+                tracing::error!("Order watcher failed: {}", e);
+            }
+        }
+    }
+
+    /// Check every pending order once. Returns the number triggered.
+    pub async fn check_pending(&self) -> Result<usize, String> {
+        let crud = OrderCrud::new(self.db.clone());
+
+        let expired = crud.expire_stale().await.map_err(|e| e.to_string())?;
+        if expired > 0 {
+            tracing::info!("Expired {} conditional orders", expired);
+        }
+
+        // Rates are cached per pair+amount for 15s, so orders on the same pair share quotes
+        let swap_crud = SwapCrud::new(
+            self.db.clone(),
+            Some(self.redis.clone()),
+            Some(self.wallet_mnemonic.clone()),
+        );
+
+        let mut triggered = 0;
+        for order in crud.get_pending(BATCH_SIZE).await.map_err(|e| e.to_string())? {
+            let query = RatesQuery {
+                from: order.from_currency.clone(),
+                network_from: order.from_network.clone(),
+                to: order.to_currency.clone(),
+                network_to: order.to_network.clone(),
+                amount: order.amount.into(),
+                rate_type: None,
+                provider: order.provider.clone(),
+            };
+
+            let rates = match swap_crud.get_rates_optimized(&query, Some(&order.user_id)).await {
+                Ok(rates) => rates,
+                Err(e) => {
+                    tracing::warn!("Failed to quote order {}: {}", order.id, e);
+                    continue;
+                }
+            };
+
+            let Some(best) = best_rate(&rates.rates, order.provider.as_deref()) else {
+                continue;
+            };
+
+            let rate = best.rate;
+            let _ = crud.record_check(&order.id, rate).await;
+
+            if !order.is_triggered_by(rate) {
+                continue;
+            }
+
+            if !crud.claim_trigger(&order.id, rate).await.map_err(|e| e.to_string())? {
+                continue;
+            }
+
+            self.execute(&crud, &swap_crud, &order, &rates.trade_id, best).await;
+            triggered += 1;
+        }
+
+        Ok(triggered)
+    }
+
+    async fn execute(
+        &self,
+        crud: &OrderCrud,
+        swap_crud: &SwapCrud,
+        order: &ConditionalOrder,
+        trade_id: &str,
+        quote: &RateResponse,
+    ) {
+        let request = CreateSwapRequest {
+            trade_id: Some(trade_id.to_string()),
+            from: order.from_currency.clone(),
+            network_from: order.from_network.clone(),
+            to: order.to_currency.clone(),
+            network_to: order.to_network.clone(),
+            amount: order.amount.into(),
+            provider: quote.provider.clone(),
+            recipient_address: order.recipient_address.clone(),
+            recipient_extra_id: order.recipient_extra_id.clone(),
+            address_book_id: None,
+            refund_address: order.refund_address.clone(),
+            refund_extra_id: order.refund_extra_id.clone(),
+            rate_type: RateType::Floating,
+            sandbox: false,
+            payout_to_balance: order.payout_to_balance,
+            quote: quote.quote.clone(),
+            client_reference: None,
+            metadata: None,
+        };
+
+        match swap_crud.create_swap(&request, Some(order.user_id.clone())).await {
+            Ok(swap) => {
+                tracing::info!(
+                    "Order {} triggered at rate {} (target {}), swap {}",
+                    order.id, quote.rate, order.target_rate.normalize(), swap.swap_id
+                );
+                if let Err(e) = crud.attach_swap(&order.id, &swap.swap_id).await {
+                    tracing::error!("Failed to link swap to order {}: {}", order.id, e);
+                }
+
+                let mut text = format!(
+                    "Your {} -> {} order reached its target rate ({} >= {}).\n\nSend exactly {} {} to:\n{}\n",
+                    order.from_currency.to_uppercase(),
+                    order.to_currency.to_uppercase(),
+                    quote.rate,
+                    order.target_rate.normalize(),
+                    swap.deposit_amount,
+                    order.from_currency.to_uppercase(),
+                    swap.deposit_address
+                );
+                if let Some(extra_id) = &swap.deposit_extra_id {
+                    text.push_str(&format!("Memo / destination tag: {}\n", extra_id));
+                }
+                text.push_str(&format!("\nSwap ID: {}\n", swap.swap_id));
+                self.notify(crud, order, "Your swap order was triggered", &text).await;
+            }
+            Err(e) => {
+                let error = e.to_string();
+                tracing::warn!("Order {} triggered but swap creation failed: {}", order.id, error);
+                let _ = crud.mark_failed(&order.id, &error).await;
+
+                let text = format!(
+                    "Your {} -> {} order reached its target rate, but we could not create the swap.\n\nReason: {}\n",
+                    order.from_currency.to_uppercase(),
+                    order.to_currency.to_uppercase(),
+                    error
+                );
+                self.notify(crud, order, "Your swap order failed", &text).await;
+            }
+        }
+    }
+
+    async fn notify(&self, crud: &OrderCrud, order: &ConditionalOrder, subject: &str, text: &str) {
+        let email = match crud.get_user_email(&order.user_id).await {
+            Ok(Some(email)) => email,
+            _ => return,
+        };
+
+        let message = EmailMessage {
+            to: email,
+            subject: subject.to_string(),
+            text: text.to_string(),
+        };
+
+        if let Err(e) = self.email_sender.send(&message).await {
+            tracing::error!("Failed to send order notification for {}: {}", order.id, e);
+        }
+    }
+}
+
+/// Best quote for an order, optionally restricted to one provider
+pub fn best_rate<'a>(rates: &'a [RateResponse], provider: Option<&str>) -> Option<&'a RateResponse> {
+    rates
+        .iter()
+        .filter(|r| provider.map(|p| r.provider.eq_ignore_ascii_case(p)).unwrap_or(true))
+        .max_by(|a, b| a.rate.cmp(&b.rate))
+}
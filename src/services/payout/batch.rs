@@ -0,0 +1,362 @@
+//! Payout batching. Native EVM payouts on chains with a Disperse-style
+//! contract configured are collected for a short window and sent from the
+//! hot wallet as one `disperseEther` call instead of one transfer each.
+//! Every recipient's amount and share of the gas is written to the ledger.
+//! The hot wallet fronts batched payouts, so each ledger row also names the
+//! deposit address still holding that swap's funds.
+//!
+//! Each payout in a batch waits out the window while holding its executor
+//! slot, so batches can only grow as large as the chain's payout
+//! concurrency allows.
+
+use sqlx::{MySql, Pool};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::oneshot;
+
+use crate::modules::wallet::schema::EvmTransaction;
+use crate::services::amount::{self, Decimal};
+use crate::services::events::OpsEvent;
+use crate::services::gas::HOT_WALLET_INDEX;
+use crate::services::sandbox::signing_chain_id;
+use crate::services::wallet::derivation;
+use crate::services::wallet::rpc::BlockchainProvider;
+use crate::services::wallet::signer::Signer;
+use crate::services::wallet::signing::{disperse_ether_data, SigningService};
+
+/// Gas of the `disperseEther` call itself
+const DISPERSE_BASE_GAS: u64 = 30_000;
+/// Gas per recipient, enough for a value transfer to a fresh account
+const DISPERSE_GAS_PER_RECIPIENT: u64 = 36_000;
+
+#[derive(Debug, Clone)]
+pub struct PayoutBatchConfig {
+    /// Disperse contract per chain, keyed by lowercase network name. Chains
+    /// without one pay out one transfer per swap.
+    pub contracts: HashMap<String, String>,
+    /// How long the first payout waits for others to join its batch (ms)
+    pub window_ms: u64,
+    /// Most recipients in one batch transaction
+    pub max_batch: usize,
+}
+
+impl Default for PayoutBatchConfig {
+    fn default() -> Self {
+        Self {
+            contracts: HashMap::new(),
+            window_ms: 10_000,
+            max_batch: 50,
+        }
+    }
+}
+
+impl PayoutBatchConfig {
+    /// Load from environment variables
+    pub fn from_env() -> Result<Self, String> {
+        let mut config = Self::default();
+
+        if let Ok(val) = std::env::var("PAYOUT_BATCH_CONTRACTS") {
+            config.contracts = parse_batch_contracts(&val)?;
+        }
+
+        if let Ok(val) = std::env::var("PAYOUT_BATCH_WINDOW_SECS") {
+            let secs: u64 = val.parse().map_err(|e| format!("Invalid PAYOUT_BATCH_WINDOW_SECS: {}", e))?;
+            config.window_ms = secs * 1000;
+        }
+
+        if let Ok(val) = std::env::var("PAYOUT_BATCH_MAX") {
+            config.max_batch = val.parse().map_err(|e| format!("Invalid PAYOUT_BATCH_MAX: {}", e))?;
+        }
+
+        if config.max_batch < 2 {
+            return Err("PAYOUT_BATCH_MAX must be at least 2".to_string());
+        }
+
+        Ok(config)
+    }
+
+    pub fn contract_for(&self, network: &str) -> Option<&str> {
+        self.contracts.get(&network.to_lowercase()).map(String::as_str)
+    }
+}
+
+/// Parse `ethereum=0x...,polygon=0x...` into per-chain contracts
+fn parse_batch_contracts(value: &str) -> Result<HashMap<String, String>, String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let (chain, contract) = entry
+                .split_once('=')
+                .ok_or_else(|| format!("Invalid PAYOUT_BATCH_CONTRACTS entry: {}", entry))?;
+            let contract = contract.trim();
+            let hex_part = contract.trim_start_matches("0x");
+            if !contract.starts_with("0x") || hex_part.len() != 40 || hex::decode(hex_part).is_err() {
+                return Err(format!("Invalid PAYOUT_BATCH_CONTRACTS address for {}: {}", chain.trim(), contract));
+            }
+            Ok((chain.trim().to_lowercase(), contract.to_string()))
+        })
+        .collect()
+}
+
+/// Gas limit of a batch paying `recipients` addresses
+pub fn batch_gas_limit(recipients: usize) -> u64 {
+    DISPERSE_BASE_GAS + DISPERSE_GAS_PER_RECIPIENT * recipients as u64
+}
+
+/// A swap payout waiting to join a batch
+#[derive(Debug, Clone)]
+pub struct BatchedPayout {
+    pub network: String,
+    pub swap_id: String,
+    /// Swap address the deposit stays on; the hot wallet pays in its place
+    pub deposit_address: String,
+    pub recipient: String,
+    /// Native coin the recipient receives
+    pub amount: Decimal,
+}
+
+/// A payout's place in a broadcast batch
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchReceipt {
+    pub batch_id: String,
+    pub tx_hash: String,
+    pub recipients: usize,
+    pub gas_price: u64,
+    pub gas_limit: u64,
+    /// This payout's even share of the batch's gas
+    pub network_fee: Decimal,
+}
+
+type BatchResult = Result<BatchReceipt, String>;
+/// A payout waiting for its batch, and where to send the outcome
+type QueuedPayout = (BatchedPayout, oneshot::Sender<BatchResult>);
+
+pub struct PayoutBatcher {
+    db: Pool<MySql>,
+    provider: Arc<dyn BlockchainProvider>,
+    master_seed: String,
+    signing: SigningService,
+    config: PayoutBatchConfig,
+    /// Payouts collected per network while a batch window is open
+    pending: Mutex<HashMap<String, Vec<QueuedPayout>>>,
+}
+
+impl PayoutBatcher {
+    pub fn new(db: Pool<MySql>, provider: Arc<dyn BlockchainProvider>, master_seed: String) -> Self {
+        let signing = SigningService::from_config(&master_seed);
+        Self {
+            db,
+            provider,
+            master_seed,
+            signing,
+            config: PayoutBatchConfig::default(),
+            pending: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn with_config(mut self, config: PayoutBatchConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Replace the signer configured by SIGNER_BACKEND
+    pub fn with_signer(mut self, signer: Arc<dyn Signer>) -> Self {
+        self.signing = SigningService::new(signer);
+        self
+    }
+
+    /// Whether payouts on `network` are batched
+    pub fn batches(&self, network: &str) -> bool {
+        self.config.contract_for(network).is_some()
+    }
+
+    /// Pay `payout` as part of the batch being collected for its network,
+    /// opening one if none is. Resolves once the batch is broadcast.
+    pub async fn submit(&self, payout: BatchedPayout) -> BatchResult {
+        let network = payout.network.to_lowercase();
+        if !self.batches(&network) {
+            return Err(format!("Payout batching is not configured for {}", network));
+        }
+
+        let (sender, receiver) = oneshot::channel();
+        let leader = {
+            let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+            let queue = pending.entry(network.clone()).or_default();
+            queue.push((payout, sender));
+            queue.len() == 1
+        };
+
+        // The first payout in collects the batch and sends it for everyone
+        if leader {
+            tokio::time::sleep(Duration::from_millis(self.config.window_ms)).await;
+            let batch = self
+                .pending
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .remove(&network)
+                .unwrap_or_default();
+            let (payouts, senders): (Vec<_>, Vec<_>) = batch.into_iter().unzip();
+
+            let results = self.send(&network, &payouts).await;
+            for (sender, result) in senders.into_iter().zip(results) {
+                let _ = sender.send(result);
+            }
+        }
+
+        receiver.await.map_err(|_| "Payout batcher dropped the payout".to_string())?
+    }
+
+    /// Send `payouts` in transactions of at most `max_batch` recipients,
+    /// reading the nonce once. Results are in payout order.
+    pub async fn send(&self, network: &str, payouts: &[BatchedPayout]) -> Vec<BatchResult> {
+        let Some(contract) = self.config.contract_for(network) else {
+            let e = format!("Payout batching is not configured for {}", network);
+            return payouts.iter().map(|_| Err(e.clone())).collect();
+        };
+
+        let mut results = Vec::with_capacity(payouts.len());
+        let mut nonce = None;
+        for chunk in payouts.chunks(self.config.max_batch.max(1)) {
+            match self.send_chunk(network, contract, chunk, &mut nonce).await {
+                Ok(receipt) => results.extend(chunk.iter().map(|_| Ok(receipt.clone()))),
+                Err(e) => {
+                    tracing::error!("Payout batch of {} on {} failed: {}", chunk.len(), network, e);
+                    results.extend(chunk.iter().map(|_| Err(e.clone())));
+                }
+            }
+        }
+        results
+    }
+
+    async fn send_chunk(
+        &self,
+        network: &str,
+        contract: &str,
+        payouts: &[BatchedPayout],
+        nonce: &mut Option<u64>,
+    ) -> BatchResult {
+        let gas_price = self.provider.get_gas_price().await
+            .map_err(|e| format!("Failed to get gas price: {}", e))?;
+        let gas_limit = batch_gas_limit(payouts.len());
+        let max_fee = amount::from_minor_units(gas_price as u128 * gas_limit as u128, amount::EVM_NATIVE_DECIMALS)
+            .map_err(|e| e.to_string())?;
+        let total: Decimal = payouts.iter().map(|p| p.amount).sum();
+
+        let hot_wallet = derivation::derive_evm_address(&self.master_seed, HOT_WALLET_INDEX).await?;
+        let hot_balance = self.provider.get_balance(&hot_wallet).await
+            .map_err(|e| format!("Failed to get hot wallet balance: {}", e))?;
+        if hot_balance < total + max_fee {
+            let detail = format!(
+                "Payout batch needs {} for {} payouts, hot wallet holds {}",
+                total + max_fee, payouts.len(), hot_balance
+            );
+            OpsEvent::GasTankLow { chain: network.to_string(), detail: detail.clone() }.publish();
+            return Err(detail);
+        }
+
+        let values = payouts
+            .iter()
+            .map(|p| {
+                amount::to_minor_units(p.amount, amount::EVM_NATIVE_DECIMALS)
+                    .map(|wei| (p.recipient.as_str(), wei))
+                    .map_err(|e| e.to_string())
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let tx_nonce = match nonce {
+            Some(n) => *n,
+            None => self.provider.get_transaction_count(&hot_wallet).await
+                .map_err(|e| format!("Failed to get nonce: {}", e))?,
+        };
+
+        // Signed for Ethereum (its test network in a sandbox), like the
+        // single payouts it replaces
+        let tx = EvmTransaction {
+            to_address: contract.to_string(),
+            amount: total,
+            token: "NATIVE".to_string(),
+            chain_id: signing_chain_id("ethereum"),
+            nonce: tx_nonce,
+            gas_price,
+            gas_limit,
+            data: disperse_ether_data(&values)?,
+        };
+
+        let signature = self.signing.sign_evm(HOT_WALLET_INDEX, &tx).await?;
+        let tx_hash = self.provider.send_raw_transaction(&signature).await
+            .map_err(|e| format!("Failed to broadcast payout batch: {}", e))?;
+        *nonce = Some(tx_nonce + 1);
+
+        let receipt = BatchReceipt {
+            batch_id: uuid::Uuid::new_v4().to_string(),
+            tx_hash,
+            recipients: payouts.len(),
+            gas_price,
+            gas_limit,
+            network_fee: (max_fee / Decimal::from(payouts.len())).round_dp(amount::EVM_NATIVE_DECIMALS),
+        };
+        tracing::info!(
+            "Payout batch {} paid {} recipients {} on {} in {}",
+            receipt.batch_id, payouts.len(), total, network, receipt.tx_hash
+        );
+        self.record(network, contract, payouts, &receipt).await;
+
+        Ok(receipt)
+    }
+
+    /// The batch is already broadcast, so failed writes are only logged
+    async fn record(&self, network: &str, contract: &str, payouts: &[BatchedPayout], receipt: &BatchReceipt) {
+        for payout in payouts {
+            let inserted = sqlx::query(
+                r#"
+                INSERT INTO payout_batch_items
+                    (batch_id, network, contract_address, swap_id, deposit_address, recipient,
+                     amount, network_fee, gas_price, tx_hash)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(&receipt.batch_id)
+            .bind(network)
+            .bind(contract)
+            .bind(&payout.swap_id)
+            .bind(&payout.deposit_address)
+            .bind(&payout.recipient)
+            .bind(payout.amount)
+            .bind(receipt.network_fee)
+            .bind(receipt.gas_price)
+            .bind(&receipt.tx_hash)
+            .execute(&self.db)
+            .await;
+            if let Err(e) = inserted {
+                tracing::warn!("Failed to record batched payout for swap {}: {}", payout.swap_id, e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_batch_contracts() {
+        let contracts =
+            parse_batch_contracts("Ethereum=0xD152f549545093347A162Dce210e7293f1452150, ").unwrap();
+        assert_eq!(
+            contracts.get("ethereum").map(String::as_str),
+            Some("0xD152f549545093347A162Dce210e7293f1452150")
+        );
+
+        assert!(parse_batch_contracts("ethereum").is_err());
+        assert!(parse_batch_contracts("ethereum=0x1234").is_err());
+    }
+
+    #[test]
+    fn test_batch_gas_limit_grows_per_recipient() {
+        assert_eq!(batch_gas_limit(1), 66_000);
+        assert_eq!(batch_gas_limit(10) - batch_gas_limit(9), DISPERSE_GAS_PER_RECIPIENT);
+    }
+}
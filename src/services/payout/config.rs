@@ -0,0 +1,143 @@
+use std::collections::HashMap;
+
+#[derive(Debug, Clone)]
+pub struct PayoutExecutorConfig {
+    /// Concurrent payouts per chain when the chain has no override
+    pub default_concurrency: usize,
+    /// Per-chain overrides keyed by lowercase network name
+    pub chain_concurrency: HashMap<String, usize>,
+    /// Extra payouts per chain that only high-priority jobs may use, on top
+    /// of the chain's limit (0 = high priority only jumps the queue)
+    pub priority_concurrency: usize,
+    /// Submissions are rejected once a chain has this many queued jobs
+    pub max_queue_depth: usize,
+    /// How often the dispatcher re-checks the queue when nothing wakes it (ms)
+    pub dispatch_interval_ms: u64,
+
+    // Priority weights
+    pub priority_weight_age: f64,
+    pub priority_weight_amount: f64,
+}
+
+impl Default for PayoutExecutorConfig {
+    fn default() -> Self {
+        Self {
+            default_concurrency: 2,
+            chain_concurrency: HashMap::new(),
+            priority_concurrency: 1,
+            max_queue_depth: 500,
+            dispatch_interval_ms: 1000,
+
+            priority_weight_age: 0.6,
+            priority_weight_amount: 0.4,
+        }
+    }
+}
+
+impl PayoutExecutorConfig {
+    /// Load from environment variables
+    pub fn from_env() -> Result<Self, String> {
+        let mut config = Self::default();
+
+        if let Ok(val) = std::env::var("PAYOUT_DEFAULT_CONCURRENCY") {
+            config.default_concurrency = val.parse().map_err(|e| format!("Invalid PAYOUT_DEFAULT_CONCURRENCY: {}", e))?;
+        }
+
+        if let Ok(val) = std::env::var("PAYOUT_CHAIN_CONCURRENCY") {
+            config.chain_concurrency = parse_chain_concurrency(&val)?;
+        }
+
+        if let Ok(val) = std::env::var("PAYOUT_PRIORITY_CONCURRENCY") {
+            config.priority_concurrency = val.parse().map_err(|e| format!("Invalid PAYOUT_PRIORITY_CONCURRENCY: {}", e))?;
+        }
+
+        if let Ok(val) = std::env::var("PAYOUT_MAX_QUEUE_DEPTH") {
+            config.max_queue_depth = val.parse().map_err(|e| format!("Invalid PAYOUT_MAX_QUEUE_DEPTH: {}", e))?;
+        }
+
+        if config.default_concurrency == 0 || config.chain_concurrency.values().any(|&n| n == 0) {
+            return Err("Payout concurrency must be at least 1".to_string());
+        }
+
+        let weight_sum = config.priority_weight_age + config.priority_weight_amount;
+        if (weight_sum - 1.0).abs() > 0.01 {
+            return Err(format!("Priority weights must sum to 1.0, got {}", weight_sum));
+        }
+
+        Ok(config)
+    }
+
+    /// Maximum number of payouts allowed to run at once on `chain`
+    pub fn concurrency_for(&self, chain: &str) -> usize {
+        self.chain_concurrency
+            .get(&chain.to_lowercase())
+            .copied()
+            .unwrap_or(self.default_concurrency)
+            .max(1)
+    }
+
+    /// Calculate priority score for payout processing order.
+    /// Older swaps and larger payouts go first; both factors are capped so
+    /// neither can starve the other.
+    pub fn priority_score(&self, age_hours: f64, amount: f64) -> f64 {
+        let age_factor = (age_hours * 10.0).clamp(0.0, 10.0);
+        let amount_factor = (amount.max(0.0) / 100.0).min(10.0);
+
+        self.priority_weight_age * age_factor + self.priority_weight_amount * amount_factor
+    }
+}
+
+/// Parse `ethereum=2,bitcoin=1` into per-chain limits
+fn parse_chain_concurrency(value: &str) -> Result<HashMap<String, usize>, String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let (chain, limit) = entry
+                .split_once('=')
+                .ok_or_else(|| format!("Invalid PAYOUT_CHAIN_CONCURRENCY entry: {}", entry))?;
+            let limit = limit
+                .trim()
+                .parse()
+                .map_err(|e| format!("Invalid PAYOUT_CHAIN_CONCURRENCY limit for {}: {}", chain, e))?;
+            Ok((chain.trim().to_lowercase(), limit))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_chain_concurrency() {
+        let limits = parse_chain_concurrency("Ethereum=3, bitcoin=1,").unwrap();
+        assert_eq!(limits.get("ethereum"), Some(&3));
+        assert_eq!(limits.get("bitcoin"), Some(&1));
+
+        assert!(parse_chain_concurrency("ethereum").is_err());
+        assert!(parse_chain_concurrency("ethereum=many").is_err());
+    }
+
+    #[test]
+    fn test_concurrency_for_falls_back_to_default() {
+        let mut config = PayoutExecutorConfig::default();
+        config.chain_concurrency.insert("bitcoin".to_string(), 1);
+
+        assert_eq!(config.concurrency_for("BITCOIN"), 1);
+        assert_eq!(config.concurrency_for("solana"), config.default_concurrency);
+    }
+
+    #[test]
+    fn test_priority_score() {
+        let config = PayoutExecutorConfig::default();
+
+        // 0.6 * (0.5h * 10) + 0.4 * (250 / 100) = 3.0 + 1.0
+        assert!((config.priority_score(0.5, 250.0) - 4.0).abs() < 0.001);
+
+        // Both factors cap at 10
+        assert!((config.priority_score(48.0, 1_000_000.0) - 10.0).abs() < 0.001);
+        assert!(config.priority_score(0.2, 10.0) > config.priority_score(0.1, 10.0));
+    }
+}
@@ -0,0 +1,486 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use async_trait::async_trait;
+use chrono::Utc;
+use tokio::sync::{Notify, OwnedSemaphorePermit, Semaphore};
+
+use super::config::PayoutExecutorConfig;
+use super::guard::PayoutGuard;
+use super::queue::{PayoutJob, PayoutPriority, PayoutQueue, QueuePosition};
+use crate::services::events::{DomainEvent, OpsEvent};
+use crate::services::metrics::collectors::PayoutMetricsCollector;
+use crate::services::metrics::MetricsRegistry;
+
+/// Performs the actual payout for a dequeued job
+#[async_trait]
+pub trait PayoutHandler: Send + Sync {
+    /// Returns a reference for the payout (e.g. tx hash) or an error message
+    async fn execute(&self, job: &PayoutJob) -> Result<String, String>;
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PayoutQueueError {
+    /// The chain's queue is at capacity; the caller should retry later
+    QueueFull { chain: String, depth: usize },
+}
+
+impl std::fmt::Display for PayoutQueueError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PayoutQueueError::QueueFull { chain, depth } => {
+                write!(f, "Payout queue for {} is full ({} jobs)", chain, depth)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PayoutQueueError {}
+
+static GLOBAL_EXECUTOR: OnceLock<PayoutExecutor> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubmitOutcome {
+    Queued,
+    /// The swap is already queued or being paid out
+    AlreadyQueued,
+}
+
+/// Where a swap's payout is in the executor
+#[derive(Debug, Clone, PartialEq)]
+pub enum PayoutQueueStatus {
+    Queued(QueuePosition),
+    InFlight { chain: String, priority: PayoutPriority },
+}
+
+#[derive(Default)]
+struct ExecutorState {
+    queue: PayoutQueue,
+    /// Running swap ids and their lane, per chain
+    in_flight: HashMap<String, HashMap<String, PayoutPriority>>,
+    limits: HashMap<String, Arc<Semaphore>>,
+    /// Slots only high-priority jobs may take, per chain
+    priority_limits: HashMap<String, Arc<Semaphore>>,
+}
+
+impl ExecutorState {
+    fn in_flight_count(&self, chain: &str) -> usize {
+        self.in_flight.get(chain).map(HashMap::len).unwrap_or(0)
+    }
+
+    fn is_in_flight(&self, swap_id: &str) -> bool {
+        self.in_flight.values().any(|ids| ids.contains_key(swap_id))
+    }
+}
+
+/// Bounded, prioritized payout pool. Jobs are queued per chain and at most
+/// `concurrency_for(chain)` run at once so a busy chain cannot exhaust its
+/// RPC rate limit or starve other chains. High-priority jobs are dispatched
+/// first and may also use `priority_concurrency` slots reserved for them.
+/// While the payout guard is tripped nothing is started; jobs stay queued.
+#[derive(Clone)]
+pub struct PayoutExecutor {
+    config: Arc<PayoutExecutorConfig>,
+    handler: Arc<dyn PayoutHandler>,
+    state: Arc<Mutex<ExecutorState>>,
+    notify: Arc<Notify>,
+    metrics: Option<Arc<PayoutMetricsCollector>>,
+    guard: Option<PayoutGuard>,
+    held: Arc<AtomicBool>,
+}
+
+impl PayoutExecutor {
+    pub fn new(handler: Arc<dyn PayoutHandler>, config: PayoutExecutorConfig) -> Self {
+        Self {
+            config: Arc::new(config),
+            handler,
+            state: Arc::new(Mutex::new(ExecutorState::default())),
+            notify: Arc::new(Notify::new()),
+            metrics: None,
+            guard: None,
+            held: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Hold queued jobs while `guard` is tripped
+    pub fn with_guard(mut self, guard: PayoutGuard) -> Self {
+        self.guard = Some(guard);
+        self
+    }
+
+    /// Whether dispatch is paused by the payout guard
+    pub fn is_held(&self) -> bool {
+        self.held.load(Ordering::Relaxed)
+    }
+
+    pub fn with_metrics(mut self, metrics: Arc<MetricsRegistry>) -> Self {
+        self.metrics = Some(Arc::new(PayoutMetricsCollector::new(metrics)));
+        self
+    }
+
+    /// Make this executor the one reported by [`PayoutExecutor::global`].
+    /// Only the first call has an effect.
+    pub fn install_global(&self) {
+        let _ = GLOBAL_EXECUTOR.set(self.clone());
+    }
+
+    /// The process's payout executor, for reporting queue status to users
+    pub fn global() -> Option<&'static PayoutExecutor> {
+        GLOBAL_EXECUTOR.get()
+    }
+
+    /// Queue a payout. Rejects the job when the chain's queue is full so the
+    /// caller can back off instead of piling up work.
+    pub fn submit(&self, job: PayoutJob) -> Result<SubmitOutcome, PayoutQueueError> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+
+        if state.queue.contains(&job.swap_id) || state.is_in_flight(&job.swap_id) {
+            return Ok(SubmitOutcome::AlreadyQueued);
+        }
+
+        let depth = state.queue.depth(&job.chain);
+        if depth >= self.config.max_queue_depth {
+            return Err(PayoutQueueError::QueueFull { chain: job.chain, depth });
+        }
+
+        let chain = job.chain.clone();
+        state.queue.push(job);
+        if let Some(metrics) = &self.metrics {
+            metrics.set_queue_depth(&chain, depth + 1);
+        }
+        drop(state);
+
+        self.notify.notify_one();
+        Ok(SubmitOutcome::Queued)
+    }
+
+    pub fn queue_depth(&self, chain: &str) -> usize {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.queue.depth(&chain.to_lowercase())
+    }
+
+    pub fn in_flight(&self, chain: &str) -> usize {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.in_flight_count(&chain.to_lowercase())
+    }
+
+    /// Queue position or running state of a swap's payout; `None` when the
+    /// executor does not hold it
+    pub fn status(&self, swap_id: &str) -> Option<PayoutQueueStatus> {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+
+        if let Some(position) = state.queue.position(swap_id, &self.config, Utc::now()) {
+            return Some(PayoutQueueStatus::Queued(position));
+        }
+        state.in_flight.iter().find_map(|(chain, ids)| {
+            ids.get(swap_id).map(|priority| PayoutQueueStatus::InFlight { chain: chain.clone(), priority: *priority })
+        })
+    }
+
+    /// Start the background dispatch loop
+    pub async fn run(&self) {
+        let interval = Duration::from_millis(self.config.dispatch_interval_ms);
+
+        loop {
+            if let Some(guard) = &self.guard {
+                let halted = guard.is_halted().await;
+                if halted != self.held.swap(halted, Ordering::Relaxed) {
+                    if halted {
+                        tracing::warn!("Payout guard is tripped; holding queued payouts");
+                    } else {
+                        tracing::info!("Payout guard re-armed; resuming queued payouts");
+                    }
+                }
+            }
+            self.dispatch();
+
+            tokio::select! {
+                _ = self.notify.notified() => {}
+                _ = tokio::time::sleep(interval) => {}
+            }
+        }
+    }
+
+    /// Start as many queued jobs as the per-chain limits allow.
+    /// Returns the number of jobs started.
+    pub fn dispatch(&self) -> usize {
+        if self.is_held() {
+            return 0;
+        }
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Utc::now();
+        let mut started = Vec::new();
+
+        for chain in state.queue.chains() {
+            let limit = state
+                .limits
+                .entry(chain.clone())
+                .or_insert_with(|| Arc::new(Semaphore::new(self.config.concurrency_for(&chain))))
+                .clone();
+
+            let reserved = state
+                .priority_limits
+                .entry(chain.clone())
+                .or_insert_with(|| Arc::new(Semaphore::new(self.config.priority_concurrency)))
+                .clone();
+
+            // High-priority jobs take their reserved slots first...
+            while state.queue.lane_depth(&chain, PayoutPriority::High) > 0 {
+                let Ok(permit) = reserved.clone().try_acquire_owned() else {
+                    break;
+                };
+                let Some(job) = state.queue.pop_lane(&chain, PayoutPriority::High, &self.config, now) else {
+                    break;
+                };
+                state.in_flight.entry(chain.clone()).or_default().insert(job.swap_id.clone(), job.priority);
+                started.push((job, permit));
+            }
+
+            // ...then everyone shares the chain's limit, high priority still first
+            while state.queue.depth(&chain) > 0 {
+                let Ok(permit) = limit.clone().try_acquire_owned() else {
+                    break;
+                };
+                let Some(job) = state.queue.pop(&chain, &self.config, now) else {
+                    break;
+                };
+                state.in_flight.entry(chain.clone()).or_default().insert(job.swap_id.clone(), job.priority);
+                started.push((job, permit));
+            }
+
+            if let Some(metrics) = &self.metrics {
+                metrics.set_queue_depth(&chain, state.queue.depth(&chain));
+                metrics.set_in_flight(&chain, state.in_flight_count(&chain));
+            }
+        }
+        drop(state);
+
+        let count = started.len();
+        for (job, permit) in started {
+            let executor = self.clone();
+            tokio::spawn(async move {
+                executor.execute(job, permit).await;
+            });
+        }
+        count
+    }
+
+    async fn execute(&self, job: PayoutJob, permit: OwnedSemaphorePermit) {
+        let waited = (Utc::now() - job.enqueued_at).num_milliseconds().max(0) as f64 / 1000.0;
+        if let Some(metrics) = &self.metrics {
+            metrics.record_queue_wait(&job.chain, waited);
+            metrics.record_payout_initiated(&job.chain, &job.currency);
+        }
+
+        let started = Instant::now();
+        let result = self.handler.execute(&job).await;
+        let elapsed = started.elapsed().as_secs_f64();
+
+        match &result {
+            Ok(reference) => {
+                tracing::info!(
+                    "Payout for swap {} on {} finished in {:.2}s after {:.2}s queued: {}",
+                    job.swap_id, job.chain, elapsed, waited, reference
+                );
+                if let Some(metrics) = &self.metrics {
+                    metrics.record_payout_executed(&job.chain, &job.currency, elapsed);
+                }
+                DomainEvent::PayoutSent {
+                    swap_id: job.swap_id.clone(),
+                    chain: job.chain.clone(),
+                    currency: job.currency.clone(),
+                    amount: job.amount,
+                    reference: reference.clone(),
+                }
+                .publish();
+            }
+            Err(e) => {
+                tracing::error!("Payout for swap {} on {} failed: {}", job.swap_id, job.chain, e);
+                if let Some(metrics) = &self.metrics {
+                    metrics.record_payout_failed(&job.chain, &job.currency, "execution_error");
+                }
+                // Nodes reject sends the wallet can't pay network fees for
+                if e.to_lowercase().contains("insufficient funds") {
+                    OpsEvent::GasTankLow { chain: job.chain.clone(), detail: e.clone() }.publish();
+                }
+                OpsEvent::PayoutFailed {
+                    swap_id: job.swap_id.clone(),
+                    chain: job.chain.clone(),
+                    currency: job.currency.clone(),
+                    error: e.clone(),
+                }
+                .publish();
+                DomainEvent::PayoutFailed {
+                    swap_id: job.swap_id.clone(),
+                    chain: job.chain.clone(),
+                    currency: job.currency.clone(),
+                    error: e.clone(),
+                }
+                .publish();
+            }
+        }
+
+        drop(permit);
+        {
+            let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+            if let Some(ids) = state.in_flight.get_mut(&job.chain) {
+                ids.remove(&job.swap_id);
+            }
+            if let Some(metrics) = &self.metrics {
+                metrics.set_in_flight(&job.chain, state.in_flight_count(&job.chain));
+            }
+        }
+        self.notify.notify_one();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::amount::Decimal;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Records peak concurrency and completion order
+    struct RecordingHandler {
+        running: AtomicUsize,
+        peak: AtomicUsize,
+        completed: Mutex<Vec<String>>,
+        delay: Duration,
+    }
+
+    impl RecordingHandler {
+        fn new(delay_ms: u64) -> Arc<Self> {
+            Arc::new(Self {
+                running: AtomicUsize::new(0),
+                peak: AtomicUsize::new(0),
+                completed: Mutex::new(Vec::new()),
+                delay: Duration::from_millis(delay_ms),
+            })
+        }
+    }
+
+    #[async_trait]
+    impl PayoutHandler for RecordingHandler {
+        async fn execute(&self, job: &PayoutJob) -> Result<String, String> {
+            let running = self.running.fetch_add(1, Ordering::SeqCst) + 1;
+            self.peak.fetch_max(running, Ordering::SeqCst);
+            tokio::time::sleep(self.delay).await;
+            self.running.fetch_sub(1, Ordering::SeqCst);
+            self.completed.lock().unwrap().push(job.swap_id.clone());
+            Ok(format!("tx-{}", job.swap_id))
+        }
+    }
+
+    fn job(swap_id: &str, chain: &str, amount: i64) -> PayoutJob {
+        PayoutJob::new(swap_id, chain, "eth", Decimal::from(amount), Utc::now())
+    }
+
+    async fn wait_for(handler: &RecordingHandler, count: usize) {
+        for _ in 0..200 {
+            if handler.completed.lock().unwrap().len() >= count {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("payouts did not complete in time");
+    }
+
+    #[tokio::test]
+    async fn test_respects_per_chain_concurrency() {
+        let handler = RecordingHandler::new(30);
+        let mut config = PayoutExecutorConfig::default();
+        config.default_concurrency = 2;
+        config.dispatch_interval_ms = 10;
+        let executor = PayoutExecutor::new(handler.clone(), config);
+
+        for i in 0..6 {
+            executor.submit(job(&format!("swap-{}", i), "ethereum", 1)).unwrap();
+        }
+
+        let runner = executor.clone();
+        let task = tokio::spawn(async move { runner.run().await });
+        wait_for(&handler, 6).await;
+        task.abort();
+
+        assert_eq!(handler.peak.load(Ordering::SeqCst), 2);
+        assert_eq!(executor.queue_depth("ethereum"), 0);
+        assert_eq!(executor.in_flight("ethereum"), 0);
+    }
+
+    #[tokio::test]
+    async fn test_rejects_when_queue_is_full() {
+        let handler = RecordingHandler::new(0);
+        let mut config = PayoutExecutorConfig::default();
+        config.max_queue_depth = 2;
+        let executor = PayoutExecutor::new(handler, config);
+
+        assert_eq!(executor.submit(job("a", "bitcoin", 1)), Ok(SubmitOutcome::Queued));
+        assert_eq!(executor.submit(job("a", "bitcoin", 1)), Ok(SubmitOutcome::AlreadyQueued));
+        assert_eq!(executor.submit(job("b", "bitcoin", 1)), Ok(SubmitOutcome::Queued));
+        assert_eq!(
+            executor.submit(job("c", "bitcoin", 1)),
+            Err(PayoutQueueError::QueueFull { chain: "bitcoin".to_string(), depth: 2 })
+        );
+
+        // Other chains have their own queue
+        assert_eq!(executor.submit(job("d", "solana", 1)), Ok(SubmitOutcome::Queued));
+    }
+
+    #[tokio::test]
+    async fn test_dispatches_highest_priority_first() {
+        let handler = RecordingHandler::new(5);
+        let mut config = PayoutExecutorConfig::default();
+        config.default_concurrency = 1;
+        config.dispatch_interval_ms = 10;
+        let executor = PayoutExecutor::new(handler.clone(), config);
+
+        executor.submit(job("small", "ethereum", 1)).unwrap();
+        executor.submit(job("large", "ethereum", 800)).unwrap();
+        executor.submit(job("medium", "ethereum", 300)).unwrap();
+
+        let runner = executor.clone();
+        let task = tokio::spawn(async move { runner.run().await });
+        wait_for(&handler, 3).await;
+        task.abort();
+
+        assert_eq!(*handler.completed.lock().unwrap(), vec!["large", "medium", "small"]);
+    }
+
+    #[tokio::test]
+    async fn test_high_priority_uses_reserved_slot_and_reports_position() {
+        let handler = RecordingHandler::new(50);
+        let mut config = PayoutExecutorConfig::default();
+        config.default_concurrency = 1;
+        config.priority_concurrency = 1;
+        let executor = PayoutExecutor::new(handler.clone(), config);
+
+        executor.submit(job("standard-1", "ethereum", 500)).unwrap();
+        executor.submit(job("standard-2", "ethereum", 1)).unwrap();
+        executor.submit(job("vip", "ethereum", 1).with_priority(PayoutPriority::High)).unwrap();
+
+        match executor.status("standard-2") {
+            Some(PayoutQueueStatus::Queued(position)) => {
+                assert_eq!(position.position, 3);
+                assert_eq!(position.queued, 3);
+            }
+            other => panic!("expected queued, got {:?}", other),
+        }
+
+        // The shared slot and the reserved slot both start
+        assert_eq!(executor.dispatch(), 2);
+        assert_eq!(executor.in_flight("ethereum"), 2);
+        assert_eq!(
+            executor.status("vip"),
+            Some(PayoutQueueStatus::InFlight { chain: "ethereum".to_string(), priority: PayoutPriority::High })
+        );
+        match executor.status("standard-2") {
+            Some(PayoutQueueStatus::Queued(position)) => assert_eq!(position.position, 1),
+            other => panic!("expected queued, got {:?}", other),
+        }
+
+        wait_for(&handler, 2).await;
+        assert!(handler.completed.lock().unwrap().contains(&"vip".to_string()));
+        assert!(executor.status("missing").is_none());
+    }
+}
@@ -0,0 +1,567 @@
+//! Fail-closed circuit breaker over every outgoing payout. Swap payouts,
+//! balance withdrawals, gift card payments, late deposit refunds and admin
+//! recovery sweeps call
+//! [`PayoutGuard::check`] before signing and [`PayoutGuard::record`] after. The guard trips, and
+//! halts all payouts, when within its window too many sends fail, when a
+//! payout would take the hour's USD volume past the cap, or when a payout
+//! is far larger than recent ones of its kind: any of these can mean the
+//! hot wallet key is being misused.
+//!
+//! Payouts are valued at the latest `crypto_prices` snapshot; an asset with
+//! no recent price is refused. A cleared payout is reserved against the
+//! hourly cap in the same transaction as the check, so concurrent payouts
+//! can't each slip under it.
+//!
+//! A trip stays until an admin re-arms the guard; attempts made before the
+//! re-arm no longer count. If the guard cannot read its state it refuses
+//! the payout rather than let it through.
+
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use rust_decimal::prelude::ToPrimitive;
+use serde::Serialize;
+use sqlx::{MySql, Pool};
+use std::sync::OnceLock;
+
+use crate::modules::payout_guard::crud::PayoutGuardCrud;
+use crate::modules::payout_guard::model::{AmountBaseline, AttemptStats, PayoutGuardTrip, PayoutKind, TripCause};
+use crate::services::amount::Decimal;
+use crate::services::events::ops::OpsEvent;
+use crate::services::fx::FxStore;
+
+/// Shortest re-arm note or manual trip reason accepted
+const MIN_NOTE_LEN: usize = 10;
+const MAX_NOTE_LEN: usize = 1000;
+
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+pub struct PayoutGuardPolicy {
+    /// Minutes of attempts the failure rate is taken over
+    pub window_minutes: u32,
+    /// Share of failed sends in the window that trips the guard
+    pub max_failure_rate: f64,
+    /// Attempts needed in the window before the failure rate counts
+    pub min_attempts: u32,
+    /// USD that may go out in an hour; 0 turns the cap off
+    pub max_usd_per_hour: f64,
+    /// A payout more than this many standard deviations above the mean of
+    /// recent ones of its kind is an outlier; 0 turns the check off
+    pub outlier_stddevs: f64,
+    /// Payouts below this value are never outliers
+    pub outlier_min_usd: f64,
+    /// Days of successful payouts the amount distribution is taken over
+    pub baseline_days: u32,
+    /// Payouts needed in the baseline before outliers are checked
+    pub baseline_min_samples: u32,
+    /// Days a `crypto_prices` snapshot is trusted for; older or missing
+    /// prices refuse the payout
+    pub max_price_age_days: u32,
+}
+
+impl Default for PayoutGuardPolicy {
+    fn default() -> Self {
+        Self {
+            window_minutes: 60,
+            max_failure_rate: 0.5,
+            min_attempts: 5,
+            max_usd_per_hour: 100_000.0,
+            outlier_stddevs: 6.0,
+            outlier_min_usd: 1_000.0,
+            baseline_days: 7,
+            baseline_min_samples: 20,
+            max_price_age_days: 2,
+        }
+    }
+}
+
+impl PayoutGuardPolicy {
+    pub fn from_env() -> Result<Self, String> {
+        let mut policy = Self::default();
+
+        fn parse<T: std::str::FromStr>(name: &str, default: T) -> Result<T, String>
+        where
+            T::Err: std::fmt::Display,
+        {
+            match std::env::var(name) {
+                Ok(val) => val.trim().parse().map_err(|e| format!("Invalid {}: {}", name, e)),
+                Err(_) => Ok(default),
+            }
+        }
+        policy.window_minutes = parse("PAYOUT_GUARD_WINDOW_MINUTES", policy.window_minutes)?;
+        policy.max_failure_rate = parse("PAYOUT_GUARD_MAX_FAILURE_RATE", policy.max_failure_rate)?;
+        policy.min_attempts = parse("PAYOUT_GUARD_MIN_ATTEMPTS", policy.min_attempts)?;
+        policy.max_usd_per_hour = parse("PAYOUT_GUARD_MAX_USD_PER_HOUR", policy.max_usd_per_hour)?;
+        policy.outlier_stddevs = parse("PAYOUT_GUARD_OUTLIER_STDDEVS", policy.outlier_stddevs)?;
+        policy.outlier_min_usd = parse("PAYOUT_GUARD_OUTLIER_MIN_USD", policy.outlier_min_usd)?;
+        policy.baseline_days = parse("PAYOUT_GUARD_BASELINE_DAYS", policy.baseline_days)?;
+        policy.baseline_min_samples = parse("PAYOUT_GUARD_BASELINE_MIN_SAMPLES", policy.baseline_min_samples)?;
+        policy.max_price_age_days = parse("PAYOUT_GUARD_MAX_PRICE_AGE_DAYS", policy.max_price_age_days)?;
+
+        if policy.window_minutes == 0 {
+            return Err("PAYOUT_GUARD_WINDOW_MINUTES must be at least 1".to_string());
+        }
+        if !(policy.max_failure_rate > 0.0 && policy.max_failure_rate <= 1.0) {
+            return Err("PAYOUT_GUARD_MAX_FAILURE_RATE must be above 0 and at most 1".to_string());
+        }
+        if policy.min_attempts == 0 {
+            return Err("PAYOUT_GUARD_MIN_ATTEMPTS must be at least 1".to_string());
+        }
+        if policy.max_usd_per_hour < 0.0 || policy.outlier_stddevs < 0.0 || policy.outlier_min_usd < 0.0 {
+            return Err("PAYOUT_GUARD USD limits and PAYOUT_GUARD_OUTLIER_STDDEVS cannot be negative".to_string());
+        }
+        if policy.baseline_days == 0 {
+            return Err("PAYOUT_GUARD_BASELINE_DAYS must be at least 1".to_string());
+        }
+        if policy.max_price_age_days == 0 {
+            return Err("PAYOUT_GUARD_MAX_PRICE_AGE_DAYS must be at least 1".to_string());
+        }
+
+        Ok(policy)
+    }
+
+    /// Process-wide policy, read from the environment once. An invalid
+    /// configuration panics rather than guard payouts with limits nobody
+    /// set; preflight reports it before startup gets this far.
+    pub fn global() -> &'static PayoutGuardPolicy {
+        static POLICY: OnceLock<PayoutGuardPolicy> = OnceLock::new();
+        POLICY.get_or_init(|| Self::from_env().expect("Invalid payout guard configuration"))
+    }
+
+    /// Why sending `usd_value` must trip the guard, given the last hour's
+    /// attempts and the recent distribution of payouts of the same kind
+    pub fn assess_payout(
+        &self,
+        usd_value: f64,
+        last_hour: &AttemptStats,
+        baseline: &AmountBaseline,
+    ) -> Option<(TripCause, String)> {
+        if self.max_usd_per_hour > 0.0 && last_hour.usd_sent + usd_value > self.max_usd_per_hour {
+            return Some((
+                TripCause::UsdVolume,
+                format!(
+                    "Payout of ${:.2} would bring the last hour's volume to ${:.2}, over the ${:.2} cap",
+                    usd_value,
+                    last_hour.usd_sent + usd_value,
+                    self.max_usd_per_hour
+                ),
+            ));
+        }
+
+        if self.outlier_stddevs > 0.0
+            && usd_value >= self.outlier_min_usd
+            && baseline.samples >= i64::from(self.baseline_min_samples)
+        {
+            let limit = baseline.mean_usd + self.outlier_stddevs * baseline.stddev_usd;
+            if usd_value > limit {
+                return Some((
+                    TripCause::OutlierAmount,
+                    format!(
+                        "Payout of ${:.2} is above ${:.2}, {} standard deviations over the mean of the last {} (${:.2})",
+                        usd_value, limit, self.outlier_stddevs, baseline.samples, baseline.mean_usd
+                    ),
+                ));
+            }
+        }
+
+        None
+    }
+
+    /// Why the attempts in the window must trip the guard
+    pub fn assess_failures(&self, window: &AttemptStats) -> Option<(TripCause, String)> {
+        if window.attempts < i64::from(self.min_attempts) {
+            return None;
+        }
+        let rate = window.failure_rate();
+        (rate >= self.max_failure_rate).then(|| {
+            (
+                TripCause::FailureRate,
+                format!(
+                    "{} of the last {} payouts failed within {} minutes ({:.0}%, limit {:.0}%)",
+                    window.failures,
+                    window.attempts,
+                    self.window_minutes,
+                    rate * 100.0,
+                    self.max_failure_rate * 100.0
+                ),
+            )
+        })
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum PayoutGuardError {
+    #[error("Payouts are halted by the payout guard: {0}")]
+    Halted(String),
+
+    /// The guard could not read its state, so it refuses
+    #[error("Payout guard unavailable, refusing to pay out: {0}")]
+    Unavailable(String),
+
+    /// The asset has no recent USD price, so the payout can't be valued
+    #[error("Payout can't be valued, refusing to pay out: {0}")]
+    Unpriced(String),
+
+    #[error("The payout guard is not tripped")]
+    NotTripped,
+
+    #[error("The payout guard is already tripped")]
+    AlreadyTripped,
+
+    #[error("Note must be between {} and {} characters", MIN_NOTE_LEN, MAX_NOTE_LEN)]
+    InvalidNote,
+}
+
+impl PayoutGuardError {
+    pub fn status_code(&self) -> axum::http::StatusCode {
+        match self {
+            PayoutGuardError::Halted(_) => axum::http::StatusCode::SERVICE_UNAVAILABLE,
+            PayoutGuardError::Unavailable(_) => axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+            PayoutGuardError::Unpriced(_) => axum::http::StatusCode::SERVICE_UNAVAILABLE,
+            PayoutGuardError::NotTripped | PayoutGuardError::AlreadyTripped => axum::http::StatusCode::CONFLICT,
+            PayoutGuardError::InvalidNote => axum::http::StatusCode::BAD_REQUEST,
+        }
+    }
+}
+
+impl From<sqlx::Error> for PayoutGuardError {
+    fn from(e: sqlx::Error) -> Self {
+        PayoutGuardError::Unavailable(e.to_string())
+    }
+}
+
+/// A payout about to be sent
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlannedPayout {
+    pub kind: PayoutKind,
+    /// Swap, withdrawal or refund id, or the recovered case or finding
+    pub reference: String,
+    pub chain: String,
+    pub currency: String,
+    pub amount: Decimal,
+}
+
+impl PlannedPayout {
+    pub fn new(kind: PayoutKind, reference: &str, chain: &str, currency: &str, amount: Decimal) -> Self {
+        Self {
+            kind,
+            reference: reference.to_string(),
+            chain: chain.to_lowercase(),
+            currency: currency.to_lowercase(),
+            amount,
+        }
+    }
+
+    /// Value at `usd_price`. A value too large to represent is infinite, so
+    /// it trips the hourly cap rather than slipping under it.
+    pub fn usd_value(&self, usd_price: Decimal) -> f64 {
+        self.amount
+            .checked_mul(usd_price)
+            .and_then(|value| value.to_f64())
+            .unwrap_or(f64::INFINITY)
+    }
+}
+
+/// A payout cleared by [`PayoutGuard::check`] and counted against the
+/// hourly cap until it is recorded or released
+#[derive(Debug, Clone, PartialEq)]
+pub struct PayoutReservation {
+    pub planned: PlannedPayout,
+    pub usd_value: f64,
+    attempt_id: u64,
+}
+
+/// Checks payouts against the [`PayoutGuardPolicy`]. State lives in the
+/// database, so every instance sees the same trips.
+#[derive(Clone)]
+pub struct PayoutGuard {
+    db: Pool<MySql>,
+    policy: PayoutGuardPolicy,
+}
+
+impl PayoutGuard {
+    pub fn new(db: Pool<MySql>) -> Self {
+        Self { db, policy: PayoutGuardPolicy::global().clone() }
+    }
+
+    pub fn with_policy(mut self, policy: PayoutGuardPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    pub fn policy(&self) -> &PayoutGuardPolicy {
+        &self.policy
+    }
+
+    fn crud(&self) -> PayoutGuardCrud {
+        PayoutGuardCrud::new(self.db.clone())
+    }
+
+    /// Start of a window reaching `minutes` back, cut at the last re-arm
+    async fn window_start(&self, minutes: i64) -> Result<DateTime<Utc>, sqlx::Error> {
+        let start = Utc::now() - chrono::Duration::minutes(minutes);
+        Ok(match self.crud().last_rearmed_at().await? {
+            Some(rearmed) if rearmed > start => rearmed,
+            _ => start,
+        })
+    }
+
+    pub async fn active_trip(&self) -> Result<Option<PayoutGuardTrip>, PayoutGuardError> {
+        Ok(self.crud().active_trip().await?)
+    }
+
+    /// Whether payouts are halted. Also true when the state can't be read.
+    pub async fn is_halted(&self) -> bool {
+        match self.crud().active_trip().await {
+            Ok(trip) => trip.is_some(),
+            Err(e) => {
+                tracing::error!("Payout guard state unreadable, holding payouts: {}", e);
+                true
+            }
+        }
+    }
+
+    /// Attempts in the failure-rate window
+    pub async fn window_stats(&self) -> Result<AttemptStats, PayoutGuardError> {
+        let since = self.window_start(i64::from(self.policy.window_minutes)).await?;
+        Ok(self.crud().attempt_stats(since).await?)
+    }
+
+    /// Attempts in the last hour, for the USD cap
+    pub async fn hourly_stats(&self) -> Result<AttemptStats, PayoutGuardError> {
+        let since = self.window_start(60).await?;
+        Ok(self.crud().attempt_stats(since).await?)
+    }
+
+    /// Latest USD price of `currency`, if it is recent enough to trust
+    async fn usd_price(&self, currency: &str) -> Result<Decimal, PayoutGuardError> {
+        let price = FxStore::new(self.db.clone())
+            .latest_price(currency)
+            .await
+            .map_err(|e| PayoutGuardError::Unavailable(e.to_string()))?;
+        let oldest = Utc::now().date_naive() - chrono::Duration::days(i64::from(self.policy.max_price_age_days));
+        match price {
+            Some(price) if price.price_date >= oldest => Ok(price.usd_price),
+            Some(price) => Err(PayoutGuardError::Unpriced(format!(
+                "latest {} price is from {}",
+                currency, price.price_date
+            ))),
+            None => Err(PayoutGuardError::Unpriced(format!("no {} price stored", currency))),
+        }
+    }
+
+    /// Clear `planned` to be sent and reserve it against the hourly cap.
+    /// Trips the guard when it breaks a limit and refuses while the guard is
+    /// tripped, its state is unreadable or the asset has no recent price.
+    pub async fn check(&self, planned: &PlannedPayout) -> Result<PayoutReservation, PayoutGuardError> {
+        if let Some(trip) = self.active_trip().await? {
+            return Err(PayoutGuardError::Halted(trip.reason));
+        }
+
+        let usd_value = planned.usd_value(self.usd_price(&planned.currency).await?);
+        let hour_start = self.window_start(60).await?;
+        let baseline_since = Utc::now() - chrono::Duration::days(i64::from(self.policy.baseline_days));
+        let baseline = self.crud().amount_baseline(planned.kind, baseline_since).await?;
+
+        // The cap check and the reservation share one transaction under the
+        // guard lock, so concurrent payouts see each other's reservations
+        let mut tx = self.db.begin().await?;
+        PayoutGuardCrud::lock(&mut *tx).await?;
+        let last_hour = PayoutGuardCrud::attempt_stats_in(&mut *tx, hour_start).await?;
+
+        if let Some((cause, reason)) = self.policy.assess_payout(usd_value, &last_hour, &baseline) {
+            tx.rollback().await?;
+            let reason = format!("{} {} on {}: {}", planned.kind.as_str(), planned.reference, planned.chain, reason);
+            self.trip(cause, &reason, None).await?;
+            return Err(PayoutGuardError::Halted(reason));
+        }
+
+        let attempt_id = PayoutGuardCrud::reserve_attempt(
+            &mut *tx,
+            planned.kind,
+            &planned.reference,
+            &planned.chain,
+            &planned.currency,
+            planned.amount,
+            usd_value,
+        )
+        .await?;
+        tx.commit().await?;
+
+        Ok(PayoutReservation { planned: planned.clone(), usd_value, attempt_id })
+    }
+
+    /// Drop the reservation of a cleared payout that won't be sent
+    pub async fn release(&self, reservation: &PayoutReservation) {
+        if let Err(e) = self.crud().release_attempt(reservation.attempt_id).await {
+            tracing::error!(
+                "Failed to release {} payout {} with the payout guard: {}",
+                reservation.planned.kind.as_str(),
+                reservation.planned.reference,
+                e
+            );
+        }
+    }
+
+    /// Record how a send cleared by [`check`](Self::check) went, with the
+    /// error when it failed, and trip the guard if failures pile up
+    pub async fn record(&self, reservation: &PayoutReservation, error: Option<&str>) {
+        let planned = &reservation.planned;
+        if let Err(e) = self.crud().finish_attempt(reservation.attempt_id, error).await {
+            tracing::error!(
+                "Failed to record {} payout {} with the payout guard: {}",
+                planned.kind.as_str(),
+                planned.reference,
+                e
+            );
+            return;
+        }
+        if error.is_none() {
+            return;
+        }
+
+        let result = match self.window_stats().await {
+            Ok(window) => match self.policy.assess_failures(&window) {
+                Some((cause, reason)) => self.trip(cause, &reason, None).await.map(drop),
+                None => Ok(()),
+            },
+            Err(e) => Err(e),
+        };
+        if let Err(e) = result {
+            tracing::error!("Payout guard failure check failed: {}", e);
+        }
+    }
+
+    /// Halt all payouts. Returns the new trip's id, or `None` when the
+    /// guard was already tripped.
+    pub async fn trip(
+        &self,
+        cause: TripCause,
+        reason: &str,
+        admin_id: Option<&str>,
+    ) -> Result<Option<String>, PayoutGuardError> {
+        let Some(trip_id) = self.crud().trip(cause, reason, admin_id).await? else {
+            return Ok(None);
+        };
+
+        tracing::error!(
+            "🚨 PAYOUT GUARD TRIPPED ({}): {}. All payouts are halted until an admin re-arms the guard.",
+            cause.as_str(),
+            reason
+        );
+        OpsEvent::PayoutGuardTripped {
+            trip_id: trip_id.clone(),
+            cause: cause.as_str().to_string(),
+            reason: reason.to_string(),
+        }
+        .publish();
+        Ok(Some(trip_id))
+    }
+
+    /// Halt all payouts by hand, e.g. while a suspected leak is looked into
+    pub async fn trip_manually(&self, admin_id: &str, reason: &str) -> Result<String, PayoutGuardError> {
+        let reason = valid_note(reason)?;
+        self.trip(TripCause::Manual, reason, Some(admin_id)).await?.ok_or(PayoutGuardError::AlreadyTripped)
+    }
+
+    /// Resume payouts after an operator has looked into the trip
+    pub async fn rearm(&self, admin_id: &str, note: &str) -> Result<PayoutGuardTrip, PayoutGuardError> {
+        let note = valid_note(note)?;
+
+        let crud = self.crud();
+        let trip = crud.active_trip().await?.ok_or(PayoutGuardError::NotTripped)?;
+        if crud.rearm(admin_id, note).await? == 0 {
+            return Err(PayoutGuardError::NotTripped);
+        }
+
+        tracing::warn!("Payout guard re-armed by {} after {} trip: {}", admin_id, trip.cause.as_str(), note);
+        OpsEvent::PayoutGuardRearmed {
+            trip_id: trip.id.clone(),
+            admin_id: admin_id.to_string(),
+            note: note.to_string(),
+        }
+        .publish();
+        Ok(trip)
+    }
+}
+
+fn valid_note(note: &str) -> Result<&str, PayoutGuardError> {
+    let note = note.trim();
+    let len = note.chars().count();
+    if !(MIN_NOTE_LEN..=MAX_NOTE_LEN).contains(&len) {
+        return Err(PayoutGuardError::InvalidNote);
+    }
+    Ok(note)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(attempts: i64, failures: i64, usd_sent: f64) -> AttemptStats {
+        AttemptStats { attempts, failures, usd_sent }
+    }
+
+    fn baseline(samples: i64, mean_usd: f64, stddev_usd: f64) -> AmountBaseline {
+        AmountBaseline { samples, mean_usd, stddev_usd }
+    }
+
+    #[test]
+    fn test_failure_rate_needs_enough_attempts() {
+        let policy = PayoutGuardPolicy::default();
+
+        assert_eq!(policy.assess_failures(&stats(4, 4, 0.0)), None);
+        assert_eq!(policy.assess_failures(&stats(10, 4, 0.0)), None);
+
+        let (cause, reason) = policy.assess_failures(&stats(10, 5, 0.0)).unwrap();
+        assert_eq!(cause, TripCause::FailureRate);
+        assert!(reason.contains("5 of the last 10"));
+    }
+
+    #[test]
+    fn test_hourly_cap_counts_the_planned_payout() {
+        let policy = PayoutGuardPolicy { max_usd_per_hour: 10_000.0, ..Default::default() };
+
+        assert_eq!(policy.assess_payout(2_000.0, &stats(3, 0, 8_000.0), &AmountBaseline::default()), None);
+        let (cause, _) = policy.assess_payout(2_001.0, &stats(3, 0, 8_000.0), &AmountBaseline::default()).unwrap();
+        assert_eq!(cause, TripCause::UsdVolume);
+
+        let uncapped = PayoutGuardPolicy { max_usd_per_hour: 0.0, outlier_stddevs: 0.0, ..Default::default() };
+        assert_eq!(uncapped.assess_payout(1e9, &stats(0, 0, 1e9), &AmountBaseline::default()), None);
+    }
+
+    #[test]
+    fn test_outliers_need_a_baseline_and_a_minimum_value() {
+        let policy = PayoutGuardPolicy::default();
+        let recent = baseline(50, 200.0, 100.0);
+
+        // Mean + 6 stddevs = 800, but small payouts never count
+        assert_eq!(policy.assess_payout(900.0, &AttemptStats::default(), &recent), None);
+        let (cause, _) = policy.assess_payout(1_500.0, &AttemptStats::default(), &recent).unwrap();
+        assert_eq!(cause, TripCause::OutlierAmount);
+
+        // Too few samples to judge
+        assert_eq!(policy.assess_payout(1_500.0, &AttemptStats::default(), &baseline(5, 200.0, 100.0)), None);
+        assert_eq!(policy.assess_payout(1_500.0, &AttemptStats::default(), &baseline(50, 1_000.0, 200.0)), None);
+    }
+
+    #[test]
+    fn test_planned_payout_is_valued_in_usd() {
+        let planned = PlannedPayout::new(PayoutKind::Swap, "swap-1", "Ethereum", "ETH", Decimal::TWO);
+
+        assert_eq!(planned.chain, "ethereum");
+        assert_eq!(planned.currency, "eth");
+        assert_eq!(planned.usd_value(Decimal::new(250_050, 2)), 5_001.0);
+
+        // Too large to value: infinite, so it can only trip the cap
+        let huge = PlannedPayout::new(PayoutKind::Swap, "swap-2", "ethereum", "eth", Decimal::MAX);
+        assert_eq!(huge.usd_value(Decimal::TWO), f64::INFINITY);
+    }
+
+    #[test]
+    fn test_halted_is_unavailable_to_callers() {
+        assert_eq!(
+            PayoutGuardError::Halted("x".to_string()).status_code(),
+            axum::http::StatusCode::SERVICE_UNAVAILABLE
+        );
+        assert_eq!(PayoutGuardError::NotTripped.status_code(), axum::http::StatusCode::CONFLICT);
+    }
+}
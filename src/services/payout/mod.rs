@@ -0,0 +1,16 @@
+mod batch;
+mod config;
+mod executor;
+mod guard;
+mod queue;
+mod tracker;
+
+pub use batch::{batch_gas_limit, BatchReceipt, BatchedPayout, PayoutBatchConfig, PayoutBatcher};
+pub use config::PayoutExecutorConfig;
+pub use executor::{PayoutExecutor, PayoutHandler, PayoutQueueError, PayoutQueueStatus, SubmitOutcome};
+pub use guard::{PayoutGuard, PayoutGuardError, PayoutGuardPolicy, PayoutReservation, PlannedPayout};
+pub use queue::{PayoutJob, PayoutPriority, PayoutQueue, QueuePosition};
+pub use tracker::{
+    next_confirmation, BitcoinTxStatus, ChainTxStatus, EvmTxStatus, SolanaTxStatus, TrackerReport, TxStatusSource,
+    TxTracker, TxTrackerConfig,
+};
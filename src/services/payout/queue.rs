@@ -0,0 +1,270 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use chrono::{DateTime, Utc};
+use rust_decimal::prelude::ToPrimitive;
+
+use super::config::PayoutExecutorConfig;
+use crate::services::amount::Decimal;
+
+/// Payout lane. High-priority jobs are dispatched ahead of standard ones
+/// and have concurrency reserved for them on every chain.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PayoutPriority {
+    #[default]
+    Standard,
+    High,
+}
+
+impl PayoutPriority {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PayoutPriority::Standard => "standard",
+            PayoutPriority::High => "high",
+        }
+    }
+
+    /// Lane for a user's payout tier (`users.payout_tier`)
+    pub fn from_tier(tier: &str) -> Self {
+        match tier.trim().to_lowercase().as_str() {
+            "vip" | "partner" => PayoutPriority::High,
+            _ => PayoutPriority::Standard,
+        }
+    }
+}
+
+/// A funded swap waiting for its on-chain payout
+#[derive(Debug, Clone)]
+pub struct PayoutJob {
+    pub swap_id: String,
+    /// Lowercase network the payout is sent on; concurrency is limited per chain
+    pub chain: String,
+    pub currency: String,
+    /// Expected payout in `currency`
+    pub amount: Decimal,
+    pub priority: PayoutPriority,
+    pub swap_created_at: DateTime<Utc>,
+    pub enqueued_at: DateTime<Utc>,
+}
+
+impl PayoutJob {
+    pub fn new(
+        swap_id: impl Into<String>,
+        chain: &str,
+        currency: &str,
+        amount: Decimal,
+        swap_created_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            swap_id: swap_id.into(),
+            chain: chain.to_lowercase(),
+            currency: currency.to_lowercase(),
+            amount,
+            priority: PayoutPriority::Standard,
+            swap_created_at,
+            enqueued_at: Utc::now(),
+        }
+    }
+
+    pub fn with_priority(mut self, priority: PayoutPriority) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    pub fn age_hours(&self, now: DateTime<Utc>) -> f64 {
+        (now - self.swap_created_at).num_seconds().max(0) as f64 / 3600.0
+    }
+}
+
+/// A queued job's place in its chain's dispatch order
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueuePosition {
+    pub chain: String,
+    pub priority: PayoutPriority,
+    /// 1 = dispatched next
+    pub position: usize,
+    /// Jobs queued on the chain, including this one
+    pub queued: usize,
+}
+
+/// Pending payouts grouped by chain. Priority is evaluated at pop time
+/// because the age factor keeps growing while a job waits.
+#[derive(Debug, Default)]
+pub struct PayoutQueue {
+    chains: HashMap<String, Vec<PayoutJob>>,
+}
+
+/// Dispatch order: lane first, then age/amount score. Ties go to the job
+/// that was queued first. `Greater` means `a` goes before `b`.
+fn dispatch_order(config: &PayoutExecutorConfig, now: DateTime<Utc>, a: &PayoutJob, b: &PayoutJob) -> Ordering {
+    let score_a = config.priority_score(a.age_hours(now), a.amount.to_f64().unwrap_or(0.0));
+    let score_b = config.priority_score(b.age_hours(now), b.amount.to_f64().unwrap_or(0.0));
+    a.priority
+        .cmp(&b.priority)
+        .then_with(|| score_a.total_cmp(&score_b))
+        .then_with(|| b.enqueued_at.cmp(&a.enqueued_at))
+}
+
+impl PayoutQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, job: PayoutJob) {
+        self.chains.entry(job.chain.clone()).or_default().push(job);
+    }
+
+    pub fn contains(&self, swap_id: &str) -> bool {
+        self.chains.values().flatten().any(|job| job.swap_id == swap_id)
+    }
+
+    pub fn depth(&self, chain: &str) -> usize {
+        self.chains.get(chain).map(Vec::len).unwrap_or(0)
+    }
+
+    /// Jobs queued on `chain` in one lane
+    pub fn lane_depth(&self, chain: &str, priority: PayoutPriority) -> usize {
+        self.chains
+            .get(chain)
+            .map(|jobs| jobs.iter().filter(|job| job.priority == priority).count())
+            .unwrap_or(0)
+    }
+
+    pub fn total_depth(&self) -> usize {
+        self.chains.values().map(Vec::len).sum()
+    }
+
+    /// Chains with at least one queued job
+    pub fn chains(&self) -> Vec<String> {
+        self.chains
+            .iter()
+            .filter(|(_, jobs)| !jobs.is_empty())
+            .map(|(chain, _)| chain.clone())
+            .collect()
+    }
+
+    /// Remove the job dispatched next on `chain`: high-priority jobs first,
+    /// then by age and amount
+    pub fn pop(&mut self, chain: &str, config: &PayoutExecutorConfig, now: DateTime<Utc>) -> Option<PayoutJob> {
+        self.pop_where(chain, config, now, |_| true)
+    }
+
+    /// Remove the next job of one lane only
+    pub fn pop_lane(
+        &mut self,
+        chain: &str,
+        priority: PayoutPriority,
+        config: &PayoutExecutorConfig,
+        now: DateTime<Utc>,
+    ) -> Option<PayoutJob> {
+        self.pop_where(chain, config, now, |job| job.priority == priority)
+    }
+
+    fn pop_where(
+        &mut self,
+        chain: &str,
+        config: &PayoutExecutorConfig,
+        now: DateTime<Utc>,
+        eligible: impl Fn(&PayoutJob) -> bool,
+    ) -> Option<PayoutJob> {
+        let jobs = self.chains.get_mut(chain)?;
+
+        let index = jobs
+            .iter()
+            .enumerate()
+            .filter(|(_, job)| eligible(job))
+            .max_by(|(_, a), (_, b)| dispatch_order(config, now, a, b))
+            .map(|(index, _)| index)?;
+
+        Some(jobs.swap_remove(index))
+    }
+
+    /// Where a queued swap stands if nothing else is submitted before it runs
+    pub fn position(&self, swap_id: &str, config: &PayoutExecutorConfig, now: DateTime<Utc>) -> Option<QueuePosition> {
+        let (chain, jobs) = self
+            .chains
+            .iter()
+            .find(|(_, jobs)| jobs.iter().any(|job| job.swap_id == swap_id))?;
+        let job = jobs.iter().find(|job| job.swap_id == swap_id)?;
+
+        let ahead = jobs
+            .iter()
+            .filter(|other| other.swap_id != swap_id && dispatch_order(config, now, other, job) == Ordering::Greater)
+            .count();
+
+        Some(QueuePosition {
+            chain: chain.clone(),
+            priority: job.priority,
+            position: ahead + 1,
+            queued: jobs.len(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn job(swap_id: &str, chain: &str, amount: i64, age_minutes: i64) -> PayoutJob {
+        PayoutJob::new(swap_id, chain, "eth", Decimal::from(amount), Utc::now() - chrono::Duration::minutes(age_minutes))
+    }
+
+    #[test]
+    fn test_pop_prefers_older_and_larger_swaps() {
+        let config = PayoutExecutorConfig::default();
+        let mut queue = PayoutQueue::new();
+        queue.push(job("new-small", "ethereum", 1, 1));
+        queue.push(job("old-small", "ethereum", 1, 50));
+        queue.push(job("new-large", "ethereum", 900, 1));
+
+        let now = Utc::now();
+        assert_eq!(queue.pop("ethereum", &config, now).unwrap().swap_id, "old-small");
+        assert_eq!(queue.pop("ethereum", &config, now).unwrap().swap_id, "new-large");
+        assert_eq!(queue.pop("ethereum", &config, now).unwrap().swap_id, "new-small");
+        assert!(queue.pop("ethereum", &config, now).is_none());
+    }
+
+    #[test]
+    fn test_high_priority_lane_goes_first() {
+        let config = PayoutExecutorConfig::default();
+        let mut queue = PayoutQueue::new();
+        queue.push(job("old-large", "ethereum", 900, 50));
+        queue.push(job("vip", "ethereum", 1, 1).with_priority(PayoutPriority::High));
+        queue.push(job("new-small", "ethereum", 1, 1));
+
+        let now = Utc::now();
+        let vip = queue.position("vip", &config, now).unwrap();
+        assert_eq!((vip.position, vip.queued, vip.priority), (1, 3, PayoutPriority::High));
+        assert_eq!(queue.position("new-small", &config, now).unwrap().position, 3);
+        assert_eq!(queue.lane_depth("ethereum", PayoutPriority::High), 1);
+
+        assert!(queue.pop_lane("ethereum", PayoutPriority::High, &config, now).is_some());
+        assert!(queue.pop_lane("ethereum", PayoutPriority::High, &config, now).is_none());
+        assert_eq!(queue.pop("ethereum", &config, now).unwrap().swap_id, "old-large");
+        assert!(queue.position("missing", &config, now).is_none());
+    }
+
+    #[test]
+    fn test_priority_from_tier() {
+        assert_eq!(PayoutPriority::from_tier("VIP"), PayoutPriority::High);
+        assert_eq!(PayoutPriority::from_tier("partner"), PayoutPriority::High);
+        assert_eq!(PayoutPriority::from_tier("standard"), PayoutPriority::Standard);
+        assert_eq!(PayoutPriority::from_tier(""), PayoutPriority::Standard);
+    }
+
+    #[test]
+    fn test_queue_is_partitioned_by_chain() {
+        let config = PayoutExecutorConfig::default();
+        let mut queue = PayoutQueue::new();
+        queue.push(job("a", "Ethereum", 1, 1));
+        queue.push(job("b", "bitcoin", 1, 1));
+        queue.push(job("c", "bitcoin", 1, 1));
+
+        assert_eq!(queue.depth("ethereum"), 1);
+        assert_eq!(queue.depth("bitcoin"), 2);
+        assert_eq!(queue.total_depth(), 3);
+        assert!(queue.contains("c"));
+
+        queue.pop("ethereum", &config, Utc::now());
+        assert_eq!(queue.chains(), vec!["bitcoin".to_string()]);
+    }
+}
@@ -0,0 +1,445 @@
+//! Payout transaction tracker. A payout is recorded as sent once it is
+//! broadcast; `TxTracker` follows the transaction from there, moving the
+//! payout pending -> confirmed -> finalized as it gets deeper. A
+//! transaction the network forgets is marked dropped and one that reverts
+//! failed; both fail the payout and alert operators rather than being
+//! rebroadcast, since the funds may still move. Finality is announced to
+//! webhooks as `payout.finalized`.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use sqlx::{MySql, Pool};
+
+use crate::modules::wallet::crud::WalletCrud;
+use crate::modules::wallet::model::{PayoutConfirmation, TrackedPayout};
+use crate::services::events::{DomainEvent, OpsEvent};
+use crate::services::metrics::collectors::PayoutMetricsCollector;
+use crate::services::metrics::MetricsRegistry;
+use crate::services::wallet::bitcoin_rpc::BitcoinProvider;
+use crate::services::wallet::manager::payout_chain;
+use crate::services::wallet::rpc::{BlockchainProvider, RpcError};
+use crate::services::wallet::solana_rpc::{SolanaProvider, SolanaSignatureStatus};
+
+const BATCH_SIZE: u32 = 100;
+/// Solana's finalized commitment waits for 31 confirmed blocks on top
+const SOLANA_FINALITY: u64 = 32;
+/// Blockhashes expire after about a minute, so a Solana transaction unseen
+/// for longer than this can no longer land
+const SOLANA_DROP_AFTER: Duration = Duration::from_secs(5 * 60);
+
+/// Tracker settings, from TX_TRACKER_* settings
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TxTrackerConfig {
+    pub interval: Duration,
+    /// Confirmations after which an EVM payout is final
+    pub evm_confirmations: u64,
+    pub bitcoin_confirmations: u64,
+    /// How long a broadcast transaction may stay unknown to the node
+    /// before it counts as dropped
+    pub drop_after: Duration,
+}
+
+impl Default for TxTrackerConfig {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(30),
+            evm_confirmations: 12,
+            bitcoin_confirmations: 6,
+            drop_after: Duration::from_secs(3600),
+        }
+    }
+}
+
+impl TxTrackerConfig {
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let var = |name: &str| {
+            std::env::var(name)
+                .ok()
+                .and_then(|v| v.trim().parse::<u64>().ok())
+                .filter(|v| *v > 0)
+        };
+
+        Self {
+            interval: var("TX_TRACKER_INTERVAL_SECS").map(Duration::from_secs).unwrap_or(defaults.interval),
+            evm_confirmations: var("TX_TRACKER_EVM_CONFIRMATIONS").unwrap_or(defaults.evm_confirmations),
+            bitcoin_confirmations: var("TX_TRACKER_BITCOIN_CONFIRMATIONS").unwrap_or(defaults.bitcoin_confirmations),
+            drop_after: var("TX_TRACKER_DROP_AFTER_SECS").map(Duration::from_secs).unwrap_or(defaults.drop_after),
+        }
+    }
+}
+
+// =============================================================================
+// CHAIN STATUS
+// =============================================================================
+
+/// What a chain reports about a transaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChainTxStatus {
+    /// The node does not know the transaction
+    NotFound,
+    /// Known, not in a block yet
+    Pending,
+    /// In a block `confirmations` deep
+    Included { confirmations: u64 },
+    /// Mined, but the transaction failed
+    Reverted,
+}
+
+/// Reads transaction status from one chain
+#[async_trait]
+pub trait TxStatusSource: Send + Sync {
+    async fn status(&self, tx_hash: &str) -> Result<ChainTxStatus, RpcError>;
+
+    /// Confirmations after which a transaction is final
+    fn finality(&self) -> u64;
+
+    /// How long a transaction may go unseen before it counts as dropped,
+    /// given the configured default
+    fn drop_after(&self, default: Duration) -> Duration {
+        default
+    }
+}
+
+/// EVM receipts, with depth from the current block
+pub struct EvmTxStatus {
+    provider: Arc<dyn BlockchainProvider>,
+    finality: u64,
+}
+
+impl EvmTxStatus {
+    pub fn new(provider: Arc<dyn BlockchainProvider>, finality: u64) -> Self {
+        Self { provider, finality }
+    }
+}
+
+#[async_trait]
+impl TxStatusSource for EvmTxStatus {
+    async fn status(&self, tx_hash: &str) -> Result<ChainTxStatus, RpcError> {
+        // A pending transaction has no receipt either; the drop window
+        // covers the time a sane gas price needs to get mined
+        let Some(receipt) = self.provider.get_transaction_receipt(tx_hash).await? else {
+            return Ok(ChainTxStatus::NotFound);
+        };
+        if !receipt.success {
+            return Ok(ChainTxStatus::Reverted);
+        }
+        let head = self.provider.get_block_number().await?;
+        Ok(ChainTxStatus::Included { confirmations: head.saturating_sub(receipt.block_number) + 1 })
+    }
+
+    fn finality(&self) -> u64 {
+        self.finality
+    }
+}
+
+pub struct BitcoinTxStatus {
+    provider: Arc<dyn BitcoinProvider>,
+    finality: u64,
+}
+
+impl BitcoinTxStatus {
+    pub fn new(provider: Arc<dyn BitcoinProvider>, finality: u64) -> Self {
+        Self { provider, finality }
+    }
+}
+
+#[async_trait]
+impl TxStatusSource for BitcoinTxStatus {
+    async fn status(&self, tx_hash: &str) -> Result<ChainTxStatus, RpcError> {
+        Ok(match self.provider.get_transaction_confirmations(tx_hash).await? {
+            None => ChainTxStatus::NotFound,
+            Some(0) => ChainTxStatus::Pending,
+            Some(confirmations) => ChainTxStatus::Included { confirmations: confirmations as u64 },
+        })
+    }
+
+    fn finality(&self) -> u64 {
+        self.finality
+    }
+}
+
+/// Solana signature statuses. Confirmed counts as one confirmation and
+/// finalized as final; Solana reports no depth in between.
+pub struct SolanaTxStatus {
+    provider: Arc<dyn SolanaProvider>,
+}
+
+impl SolanaTxStatus {
+    pub fn new(provider: Arc<dyn SolanaProvider>) -> Self {
+        Self { provider }
+    }
+}
+
+#[async_trait]
+impl TxStatusSource for SolanaTxStatus {
+    async fn status(&self, tx_hash: &str) -> Result<ChainTxStatus, RpcError> {
+        Ok(match self.provider.get_signature_status(tx_hash).await? {
+            None => ChainTxStatus::NotFound,
+            Some(SolanaSignatureStatus::Processed) => ChainTxStatus::Pending,
+            Some(SolanaSignatureStatus::Landed) => ChainTxStatus::Included { confirmations: 1 },
+            Some(SolanaSignatureStatus::Finalized) => ChainTxStatus::Included { confirmations: SOLANA_FINALITY },
+            Some(SolanaSignatureStatus::Failed(_)) => ChainTxStatus::Reverted,
+        })
+    }
+
+    fn finality(&self) -> u64 {
+        SOLANA_FINALITY
+    }
+
+    fn drop_after(&self, default: Duration) -> Duration {
+        default.min(SOLANA_DROP_AFTER)
+    }
+}
+
+/// Where a payout at `current` moves given what the chain reports, if it
+/// moves at all. `unseen_for` is the time since broadcast.
+pub fn next_confirmation(
+    current: PayoutConfirmation,
+    status: ChainTxStatus,
+    finality: u64,
+    unseen_for: Duration,
+    drop_after: Duration,
+) -> Option<PayoutConfirmation> {
+    let next = match status {
+        ChainTxStatus::Reverted => PayoutConfirmation::Failed,
+        ChainTxStatus::Included { confirmations } if confirmations >= finality => PayoutConfirmation::Finalized,
+        ChainTxStatus::Included { .. } => PayoutConfirmation::Confirmed,
+        ChainTxStatus::NotFound if current == PayoutConfirmation::Pending && unseen_for >= drop_after => {
+            PayoutConfirmation::Dropped
+        }
+        // Reorged out of its block: back to waiting for inclusion
+        ChainTxStatus::NotFound | ChainTxStatus::Pending => PayoutConfirmation::Pending,
+    };
+    (next != current).then_some(next)
+}
+
+// =============================================================================
+// TRACKER
+// =============================================================================
+
+/// Outcome of one tracking pass
+#[derive(Debug, Default, Clone, Copy)]
+pub struct TrackerReport {
+    pub checked: usize,
+    pub confirmed: usize,
+    pub finalized: usize,
+    /// Dropped or reverted
+    pub failed: usize,
+}
+
+/// Follows broadcast payouts until they are final
+pub struct TxTracker {
+    crud: WalletCrud,
+    /// Status sources keyed by payout chain
+    sources: HashMap<&'static str, Arc<dyn TxStatusSource>>,
+    config: TxTrackerConfig,
+}
+
+impl TxTracker {
+    pub fn new(db: Pool<MySql>) -> Self {
+        Self { crud: WalletCrud::new(db), sources: HashMap::new(), config: TxTrackerConfig::from_env() }
+    }
+
+    pub fn with_config(mut self, config: TxTrackerConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    pub fn config(&self) -> &TxTrackerConfig {
+        &self.config
+    }
+
+    /// Track payouts on `chain` (`ethereum`, `bitcoin` or `solana`) through
+    /// `source`. Payouts on chains without one are left pending.
+    pub fn with_source(mut self, chain: &'static str, source: Arc<dyn TxStatusSource>) -> Self {
+        self.sources.insert(chain, source);
+        self
+    }
+
+    pub async fn run(&self) {
+        let mut interval = tokio::time::interval(self.config.interval);
+        loop {
+            interval.tick().await;
+            match self.process().await {
+                Ok(report) if report.confirmed + report.finalized + report.failed > 0 => {
+                    tracing::info!(
+                        "Payout tracking: {} checked, {} confirmed, {} finalized, {} failed",
+                        report.checked,
+                        report.confirmed,
+                        report.finalized,
+                        report.failed
+                    );
+                }
+                Ok(_) => {}
+                Err(e) => tracing::error!("Payout tracking pass failed: {}", e),
+            }
+        }
+    }
+
+    /// Check the least recently checked payouts that are not final yet
+    pub async fn process(&self) -> Result<TrackerReport, String> {
+        let payouts = self
+            .crud
+            .tracked_payouts(BATCH_SIZE)
+            .await
+            .map_err(|e| format!("Database error: {}", e))?;
+
+        let mut report = TrackerReport::default();
+        // Batched payouts share one transaction
+        let mut seen: HashMap<String, ChainTxStatus> = HashMap::new();
+        for payout in payouts {
+            let chain = payout_chain(payout.coin_type);
+            let status = match seen.get(&payout.payout_tx_hash) {
+                Some(status) => Some(*status),
+                None => self.status(chain, &payout.payout_tx_hash).await,
+            };
+            let Some(status) = status else {
+                // Rotated to the back so unreadable payouts don't starve the rest
+                self.crud
+                    .touch_payout_tracking(&payout.swap_id, None)
+                    .await
+                    .map_err(|e| format!("Database error: {}", e))?;
+                continue;
+            };
+            seen.insert(payout.payout_tx_hash.clone(), status);
+            report.checked += 1;
+
+            let source = &self.sources[chain];
+            let unseen_for = payout
+                .broadcast_at
+                .and_then(|at| (Utc::now() - at).to_std().ok())
+                .unwrap_or_default();
+            let confirmations = match status {
+                ChainTxStatus::Included { confirmations } => Some(confirmations),
+                _ => None,
+            };
+            let next = next_confirmation(
+                payout.confirmation_status,
+                status,
+                source.finality(),
+                unseen_for,
+                source.drop_after(self.config.drop_after),
+            );
+
+            let Some(next) = next else {
+                self.crud
+                    .touch_payout_tracking(&payout.swap_id, confirmations)
+                    .await
+                    .map_err(|e| format!("Database error: {}", e))?;
+                continue;
+            };
+            let moved = self
+                .crud
+                .record_payout_confirmation(&payout.swap_id, payout.confirmation_status, next, confirmations)
+                .await
+                .map_err(|e| format!("Database error: {}", e))?;
+            if !moved {
+                continue;
+            }
+
+            match next {
+                PayoutConfirmation::Confirmed => report.confirmed += 1,
+                PayoutConfirmation::Finalized => report.finalized += 1,
+                PayoutConfirmation::Dropped | PayoutConfirmation::Failed => report.failed += 1,
+                PayoutConfirmation::Pending => {}
+            }
+            announce(&payout, chain, next, confirmations);
+        }
+
+        Ok(report)
+    }
+
+    /// What `chain` reports about `tx_hash`; None when it can't be read
+    async fn status(&self, chain: &str, tx_hash: &str) -> Option<ChainTxStatus> {
+        let Some(source) = self.sources.get(chain) else {
+            tracing::debug!("No transaction status source for {}; {} left pending", chain, tx_hash);
+            return None;
+        };
+        match source.status(tx_hash).await {
+            Ok(status) => Some(status),
+            Err(e) => {
+                tracing::warn!("Status lookup for {} transaction {} failed: {}", chain, tx_hash, e);
+                None
+            }
+        }
+    }
+}
+
+/// Tell subscribers and operators about a payout that moved to `next`
+fn announce(payout: &TrackedPayout, chain: &str, next: PayoutConfirmation, confirmations: Option<u64>) {
+    let error = match next {
+        PayoutConfirmation::Finalized => {
+            tracing::info!("Payout for swap {} finalized: {}", payout.swap_id, payout.payout_tx_hash);
+            DomainEvent::PayoutFinalized {
+                swap_id: payout.swap_id.clone(),
+                chain: chain.to_string(),
+                tx_hash: payout.payout_tx_hash.clone(),
+                confirmations: confirmations.unwrap_or_default(),
+            }
+            .publish();
+            return;
+        }
+        PayoutConfirmation::Dropped => format!("Payout transaction {} was dropped by the network", payout.payout_tx_hash),
+        PayoutConfirmation::Failed => format!("Payout transaction {} reverted on chain", payout.payout_tx_hash),
+        PayoutConfirmation::Pending | PayoutConfirmation::Confirmed => return,
+    };
+
+    tracing::error!("Payout for swap {} failed: {}", payout.swap_id, error);
+    if let Some(metrics) = MetricsRegistry::global() {
+        PayoutMetricsCollector::new(metrics.clone()).record_payout_failed(chain, &payout.to_currency, next.as_str());
+    }
+    OpsEvent::PayoutFailed {
+        swap_id: payout.swap_id.clone(),
+        chain: chain.to_string(),
+        currency: payout.to_currency.clone(),
+        error: error.clone(),
+    }
+    .publish();
+    DomainEvent::PayoutFailed {
+        swap_id: payout.swap_id.clone(),
+        chain: chain.to_string(),
+        currency: payout.to_currency.clone(),
+        error,
+    }
+    .publish();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOUR: Duration = Duration::from_secs(3600);
+
+    fn next(current: PayoutConfirmation, status: ChainTxStatus, unseen_for: Duration) -> Option<PayoutConfirmation> {
+        next_confirmation(current, status, 12, unseen_for, HOUR)
+    }
+
+    #[test]
+    fn test_payout_moves_through_confirmation_to_finality() {
+        use PayoutConfirmation::*;
+        let minute = Duration::from_secs(60);
+        assert_eq!(next(Pending, ChainTxStatus::Pending, minute), None);
+        assert_eq!(next(Pending, ChainTxStatus::Included { confirmations: 1 }, minute), Some(Confirmed));
+        assert_eq!(next(Confirmed, ChainTxStatus::Included { confirmations: 11 }, minute), None);
+        assert_eq!(next(Confirmed, ChainTxStatus::Included { confirmations: 12 }, minute), Some(Finalized));
+        // Straight to final when first seen deep enough
+        assert_eq!(next(Pending, ChainTxStatus::Included { confirmations: 40 }, minute), Some(Finalized));
+        assert_eq!(next(Confirmed, ChainTxStatus::Reverted, minute), Some(Failed));
+    }
+
+    #[test]
+    fn test_unseen_transaction_is_dropped_after_the_window() {
+        use PayoutConfirmation::*;
+        assert_eq!(next(Pending, ChainTxStatus::NotFound, HOUR / 2), None);
+        assert_eq!(next(Pending, ChainTxStatus::NotFound, HOUR), Some(Dropped));
+        // In the mempool is not dropped, however long it waits
+        assert_eq!(next(Pending, ChainTxStatus::Pending, 2 * HOUR), None);
+        // Reorged out of its block
+        assert_eq!(next(Confirmed, ChainTxStatus::NotFound, 2 * HOUR), Some(Pending));
+    }
+}
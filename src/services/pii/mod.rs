@@ -0,0 +1,9 @@
+//! Field-level encryption for personal data (user emails, recipient
+//! addresses). Encryption is on when a data encryption key is configured;
+//! without one values are stored and read as plaintext.
+
+pub mod rotation;
+pub mod sealed;
+
+pub use rotation::{PiiColumn, PiiRotationJob, RotationReport, PII_COLUMNS};
+pub use sealed::{email_index, open, open_column, pii_cipher, seal, SealedString};
@@ -0,0 +1,215 @@
+use std::time::Duration;
+use sqlx::{MySql, Pool};
+
+use crate::services::address_reputation::address_hash_with;
+use crate::services::encryption::FieldCipher;
+
+const BATCH_SIZE: i64 = 200;
+/// Batches per column per pass, so a large backlog cannot starve the pool
+const MAX_BATCHES: usize = 50;
+
+/// A column holding personal data
+#[derive(Debug, Clone, Copy)]
+pub struct PiiColumn {
+    pub table: &'static str,
+    pub key: &'static str,
+    pub column: &'static str,
+    /// Blind index kept next to the column for equality lookups
+    pub index_column: Option<&'static str>,
+}
+
+pub const PII_COLUMNS: &[PiiColumn] = &[
+    PiiColumn { table: "users", key: "id", column: "email", index_column: Some("email_hash") },
+    PiiColumn { table: "users", key: "id", column: "anti_phishing_code", index_column: None },
+    PiiColumn { table: "swaps", key: "id", column: "recipient_address", index_column: None },
+    PiiColumn { table: "swap_address_info", key: "swap_id", column: "recipient_address", index_column: None },
+    PiiColumn { table: "email_outbox", key: "id", column: "recipient", index_column: None },
+    PiiColumn { table: "email_outbox", key: "id", column: "body", index_column: None },
+    PiiColumn { table: "address_reputation", key: "address_hash", column: "address", index_column: None },
+];
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct RotationReport {
+    /// Plaintext or old-key values rewritten under the active key
+    pub rewritten: usize,
+    /// Values that could not be decrypted with any loaded key
+    pub failed: usize,
+}
+
+/// Re-encrypts PII columns under the active key version. Also encrypts
+/// rows written before encryption was enabled and backfills blind indexes.
+pub struct PiiRotationJob {
+    db: Pool<MySql>,
+    cipher: FieldCipher,
+    interval: Duration,
+}
+
+impl PiiRotationJob {
+    pub fn new(db: Pool<MySql>, cipher: FieldCipher) -> Self {
+        Self {
+            db,
+            cipher,
+            interval: Duration::from_secs(3600),
+        }
+    }
+
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Start the background rotation loop
+    pub async fn run(&self) {
+        let mut interval = tokio::time::interval(self.interval);
+
+        loop {
+            interval.tick().await;
+
+            match self.rotate().await {
+                Ok(report) if report != RotationReport::default() => {
+                    tracing::info!("PII key rotation (v{}): {:?}", self.cipher.active_version(), report);
+                }
+                Ok(_) => {}
+                Err(e) => tracing::error!("PII key rotation failed: {}", e),
+            }
+        }
+    }
+
+    /// Run one pass over every PII column
+    pub async fn rotate(&self) -> Result<RotationReport, sqlx::Error> {
+        let mut report = RotationReport::default();
+        self.rekey_addresses(&mut report).await?;
+        for column in PII_COLUMNS {
+            self.rotate_column(column, &mut report).await?;
+        }
+        Ok(report)
+    }
+
+    /// Address reputation rows recorded before encryption was turned on are
+    /// keyed by a plain hash of the address. Seal each address and move its
+    /// row to the blind index in one update, before the column pass would
+    /// seal it under the old key.
+    async fn rekey_addresses(&self, report: &mut RotationReport) -> Result<(), sqlx::Error> {
+        for _ in 0..MAX_BATCHES {
+            let rows: Vec<(String, String, String)> = sqlx::query_as(
+                "SELECT address_hash, address, network FROM address_reputation WHERE address NOT LIKE 'enc:%' LIMIT ?",
+            )
+            .bind(BATCH_SIZE)
+            .fetch_all(&self.db)
+            .await?;
+
+            let mut progressed = false;
+            for (key, address, network) in &rows {
+                let rekeyed = address_hash_with(Some(&self.cipher), network, address);
+                let sealed = self.cipher.encrypt(address)
+                    .map_err(sqlx::Error::Protocol)?;
+
+                // Account links follow through ON UPDATE CASCADE
+                let result = sqlx::query(
+                    "UPDATE address_reputation SET address_hash = ?, address = ? WHERE address_hash = ? AND address = ?",
+                )
+                .bind(&rekeyed)
+                .bind(&sealed)
+                .bind(key)
+                .bind(address)
+                .execute(&self.db)
+                .await;
+
+                match result {
+                    Ok(result) if result.rows_affected() > 0 => {
+                        report.rewritten += 1;
+                        progressed = true;
+                    }
+                    Ok(_) => {}
+                    // Already recorded again under its blind index
+                    Err(e) => {
+                        tracing::warn!("Cannot rekey address reputation {}: {}", key, e);
+                        report.failed += 1;
+                    }
+                }
+            }
+
+            if !progressed || (rows.len() as i64) < BATCH_SIZE {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn rotate_column(&self, spec: &PiiColumn, report: &mut RotationReport) -> Result<(), sqlx::Error> {
+        let active_prefix = format!("enc:v{}:%", self.cipher.active_version());
+        let mut filter = format!("{} NOT LIKE ?", spec.column);
+        if let Some(index) = spec.index_column {
+            filter = format!("({} OR {} IS NULL)", filter, index);
+        }
+
+        let select = format!(
+            "SELECT {key}, {col} FROM {table} WHERE {filter} LIMIT ?",
+            key = spec.key,
+            col = spec.column,
+            table = spec.table,
+            filter = filter,
+        );
+        let update = match spec.index_column {
+            Some(index) => format!(
+                "UPDATE {table} SET {col} = ?, {index} = ? WHERE {key} = ? AND {col} = ?",
+                table = spec.table,
+                col = spec.column,
+                index = index,
+                key = spec.key,
+            ),
+            None => format!(
+                "UPDATE {table} SET {col} = ? WHERE {key} = ? AND {col} = ?",
+                table = spec.table,
+                col = spec.column,
+                key = spec.key,
+            ),
+        };
+
+        for _ in 0..MAX_BATCHES {
+            let rows: Vec<(String, String)> = sqlx::query_as(&select)
+                .bind(&active_prefix)
+                .bind(BATCH_SIZE)
+                .fetch_all(&self.db)
+                .await?;
+
+            let mut progressed = false;
+            for (key, current) in &rows {
+                let plaintext = if FieldCipher::is_encrypted(current) {
+                    match self.cipher.decrypt(current) {
+                        Ok(value) => value,
+                        Err(e) => {
+                            tracing::warn!("Cannot rotate {}.{} for {}: {}", spec.table, spec.column, key, e);
+                            report.failed += 1;
+                            continue;
+                        }
+                    }
+                } else {
+                    current.clone()
+                };
+
+                let sealed = self.cipher.encrypt(&plaintext)
+                    .map_err(sqlx::Error::Protocol)?;
+
+                // Conditioned on the value we read so a concurrent write wins
+                let mut query = sqlx::query(&update).bind(&sealed);
+                if spec.index_column.is_some() {
+                    query = query.bind(self.cipher.blind_index(&plaintext.trim().to_lowercase()));
+                }
+                let result = query.bind(key).bind(current).execute(&self.db).await?;
+
+                if result.rows_affected() > 0 {
+                    report.rewritten += 1;
+                    progressed = true;
+                }
+            }
+
+            if !progressed || (rows.len() as i64) < BATCH_SIZE {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,104 @@
+use std::sync::OnceLock;
+use sqlx::{
+    decode::Decode,
+    encode::{Encode, IsNull},
+    error::BoxDynError,
+    mysql::{MySql, MySqlTypeInfo, MySqlValueRef},
+    Type,
+};
+
+use crate::services::encryption::FieldCipher;
+
+static PII_CIPHER: OnceLock<Option<FieldCipher>> = OnceLock::new();
+
+/// Process-wide cipher for PII columns, loaded once from the environment
+pub fn pii_cipher() -> Option<&'static FieldCipher> {
+    PII_CIPHER
+        .get_or_init(|| match FieldCipher::from_env() {
+            Ok(cipher) => Some(cipher),
+            Err(e) => {
+                tracing::warn!("PII encryption disabled: {}", e);
+                None
+            }
+        })
+        .as_ref()
+}
+
+/// Value to write into a PII column: ciphertext under the active key, or
+/// the plaintext when no key is configured
+pub fn seal(value: &str) -> Result<String, String> {
+    match pii_cipher() {
+        Some(cipher) => cipher.encrypt(value),
+        None => Ok(value.to_string()),
+    }
+}
+
+/// Plaintext of a PII column. Values written before encryption was
+/// enabled pass through unchanged.
+pub fn open(value: String) -> Result<String, String> {
+    if !FieldCipher::is_encrypted(&value) {
+        return Ok(value);
+    }
+    pii_cipher()
+        .ok_or_else(|| "Encrypted column read without a data encryption key".to_string())?
+        .decrypt(&value)
+}
+
+/// `open` for CRUD read paths that map rows by hand
+pub fn open_column(value: String) -> Result<String, sqlx::Error> {
+    open(value).map_err(|e| sqlx::Error::Decode(e.into()))
+}
+
+/// Blind index for email lookups, `None` while encryption is off
+pub fn email_index(email: &str) -> Option<String> {
+    pii_cipher().map(|cipher| cipher.blind_index(&email.trim().to_lowercase()))
+}
+
+/// String column that is encrypted at rest. Decodes to plaintext, so
+/// models can use it with `#[sqlx(try_from = "SealedString")]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SealedString(pub String);
+
+impl From<SealedString> for String {
+    fn from(value: SealedString) -> Self {
+        value.0
+    }
+}
+
+impl Type<MySql> for SealedString {
+    fn type_info() -> MySqlTypeInfo {
+        <String as Type<MySql>>::type_info()
+    }
+
+    fn compatible(ty: &MySqlTypeInfo) -> bool {
+        <String as Type<MySql>>::compatible(ty)
+    }
+}
+
+impl<'r> Decode<'r, MySql> for SealedString {
+    fn decode(value: MySqlValueRef<'r>) -> Result<Self, BoxDynError> {
+        let raw = <String as Decode<MySql>>::decode(value)?;
+        Ok(SealedString(open(raw)?))
+    }
+}
+
+impl Encode<'_, MySql> for SealedString {
+    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> Result<IsNull, BoxDynError> {
+        let sealed = seal(&self.0)?;
+        <String as Encode<MySql>>::encode_by_ref(&sealed, buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plaintext_passes_through_open() {
+        assert_eq!(open("user@example.com".to_string()).unwrap(), "user@example.com");
+        assert_eq!(
+            open("bc1qxy2kgdygjrsqtzq2n0yrf2493p83kkfjhx0wlh".to_string()).unwrap(),
+            "bc1qxy2kgdygjrsqtzq2n0yrf2493p83kkfjhx0wlh"
+        );
+    }
+}
@@ -0,0 +1,577 @@
+//! Startup configuration check. A misconfigured environment used to fail
+//! deep inside a worker (a missing Solana RPC surfaced during the first
+//! Solana payout), so before anything starts every setting is checked at
+//! once and startup stops with one readable report:
+//!
+//! - required variables, and variables a feature needs once another one
+//!   turns it on (e.g. SMTP_USERNAME without SMTP_PASSWORD)
+//! - the wallet mnemonic is a valid BIP39 phrase
+//! - every worker's settings parse
+//! - each chain we pay out on has an RPC, and configured RPCs answer with
+//!   the chain id we expect
+//!
+//! `cargo run --bin check_config` prints the same report without starting
+//! the server.
+
+use bip39::{Language, Mnemonic};
+use serde_json::json;
+use std::fmt;
+use std::time::Duration;
+use tokio::task::JoinSet;
+
+use crate::services::api_version::VersionPolicy;
+use crate::services::backup::BackupConfig;
+use crate::services::blockchain::listener::{evm_rpc_url, EVM_RPC_ENV_VARS};
+use crate::services::blockchain::shards::shard_count_from_env;
+use crate::services::client_ip::TrustedProxies;
+use crate::services::encryption::FieldCipher;
+use crate::services::events::ops::endpoint_host;
+use crate::services::events::stream::EventStreamConfig;
+use crate::services::funnel::FunnelPolicy;
+use crate::services::gas::GasStationConfig;
+use crate::services::geo::{GeoPolicy, GeoSource};
+use crate::services::metrics::LatencyBudgets;
+use crate::services::payout::{PayoutBatchConfig, PayoutExecutorConfig, PayoutGuardPolicy};
+use crate::services::provider_payloads::PayloadPolicy;
+use crate::services::quote_signing::QuoteSigner;
+use crate::services::reconciliation::WalletAuditConfig;
+use crate::services::retention::RetentionPolicy;
+use crate::services::sandbox::{signing_chain_id, SandboxConfig};
+use crate::services::swap_sla::SlaPolicy;
+use crate::services::swap_eta::EtaPolicy;
+use crate::services::token::PermitPolicy;
+use crate::services::wallet::message_signing::MessageSigningPolicy;
+use crate::services::wallet::paths::DerivationPaths;
+use crate::services::wallet::signer::SignerBackend;
+use crate::services::warmup::WarmupConfig;
+use crate::services::watch_only::watch_only;
+
+/// Set in every deployment
+const REQUIRED: &[&str] = &["DATABASE_URL", "JWT_SECRET", "TROCADOR_API_KEY", "WALLET_MNEMONIC"];
+
+/// Features a variable turns on, and what else they then need
+const FEATURE_REQUIREMENTS: &[(&str, &str, &[&str])] = &[
+    ("remote signer", "REMOTE_SIGNER_TOKEN", &["REMOTE_SIGNER_URL"]),
+    ("SMTP", "SMTP_USERNAME", &["SMTP_HOST", "SMTP_PASSWORD"]),
+    ("SMTP", "SMTP_PASSWORD", &["SMTP_HOST", "SMTP_USERNAME"]),
+    ("email API", "EMAIL_API_URL", &["EMAIL_API_KEY"]),
+    ("object storage", "S3_BUCKET", &["S3_ACCESS_KEY_ID", "S3_SECRET_ACCESS_KEY"]),
+    ("support desk", "SUPPORT_DESK_TOKEN", &["SUPPORT_DESK_URL"]),
+    ("XRP memo deposits", "XRP_HOT_ADDRESS", &["RIPPLE_PRIMARY_RPC"]),
+    ("XLM memo deposits", "XLM_HOT_ADDRESS", &["STELLAR_HORIZON_URL"]),
+];
+
+/// README asks for at least this many characters
+const MIN_JWT_SECRET_LEN: usize = 32;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Ok,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    fn label(&self) -> &'static str {
+        match self {
+            Severity::Ok => "ok",
+            Severity::Warning => "WARN",
+            Severity::Error => "ERROR",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PreflightCheck {
+    /// What was checked, e.g. `mnemonic` or `rpc ethereum`
+    pub subject: String,
+    pub severity: Severity,
+    pub detail: String,
+}
+
+impl PreflightCheck {
+    fn new(subject: impl Into<String>, severity: Severity, detail: impl Into<String>) -> Self {
+        Self { subject: subject.into(), severity, detail: detail.into() }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PreflightReport {
+    pub checks: Vec<PreflightCheck>,
+}
+
+impl PreflightReport {
+    pub fn errors(&self) -> impl Iterator<Item = &PreflightCheck> {
+        self.checks.iter().filter(|c| c.severity == Severity::Error)
+    }
+
+    pub fn warnings(&self) -> impl Iterator<Item = &PreflightCheck> {
+        self.checks.iter().filter(|c| c.severity == Severity::Warning)
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.errors().next().is_some()
+    }
+}
+
+/// Errors first, then warnings, then what passed, one check per line
+impl fmt::Display for PreflightReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Configuration check: {} errors, {} warnings, {} passed",
+            self.errors().count(),
+            self.warnings().count(),
+            self.checks.iter().filter(|c| c.severity == Severity::Ok).count()
+        )?;
+
+        let mut checks: Vec<&PreflightCheck> = self.checks.iter().collect();
+        checks.sort_by_key(|c| std::cmp::Reverse(c.severity));
+        let width = checks.iter().map(|c| c.subject.len()).max().unwrap_or(0);
+        for check in checks {
+            writeln!(f, "  {:<5}  {:<width$}  {}", check.severity.label(), check.subject, check.detail, width = width)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PreflightConfig {
+    pub enabled: bool,
+    pub ping_rpcs: bool,
+    /// Upper bound for each RPC ping
+    pub rpc_timeout: Duration,
+    /// Chains swaps pay out on, each of which needs an RPC
+    pub payout_chains: Vec<String>,
+}
+
+impl Default for PreflightConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            ping_rpcs: true,
+            rpc_timeout: Duration::from_secs(5),
+            payout_chains: vec!["ethereum".to_string(), "solana".to_string()],
+        }
+    }
+}
+
+impl PreflightConfig {
+    pub fn from_env() -> Result<Self, String> {
+        let mut config = Self::default();
+
+        if let Ok(val) = std::env::var("PREFLIGHT") {
+            config.enabled = val.parse().map_err(|e| format!("Invalid PREFLIGHT: {}", e))?;
+        }
+        if let Ok(val) = std::env::var("PREFLIGHT_PING_RPCS") {
+            config.ping_rpcs = val.parse().map_err(|e| format!("Invalid PREFLIGHT_PING_RPCS: {}", e))?;
+        }
+        if let Ok(val) = std::env::var("PREFLIGHT_RPC_TIMEOUT_SECS") {
+            let secs: u64 = val.parse().map_err(|e| format!("Invalid PREFLIGHT_RPC_TIMEOUT_SECS: {}", e))?;
+            config.rpc_timeout = Duration::from_secs(secs.max(1));
+        }
+        if let Ok(val) = std::env::var("PREFLIGHT_PAYOUT_CHAINS") {
+            config.payout_chains = val
+                .split(',')
+                .map(|chain| chain.trim().to_lowercase())
+                .filter(|chain| !chain.is_empty())
+                .collect();
+        }
+
+        Ok(config)
+    }
+}
+
+pub struct Preflight {
+    config: PreflightConfig,
+    client: reqwest::Client,
+}
+
+impl Preflight {
+    pub fn new(config: PreflightConfig) -> Self {
+        Self { config, client: reqwest::Client::new() }
+    }
+
+    /// Run every check against the process environment
+    pub async fn run(&self) -> PreflightReport {
+        let mut report = PreflightReport::default();
+        if !self.config.enabled {
+            return report;
+        }
+
+        report.checks.extend(env_checks(process_env));
+        report.checks.extend(settings_checks());
+
+        // Chain lookups below read the sandbox configuration, which panics
+        // when invalid; settings_checks has already reported it
+        if SandboxConfig::from_env().is_err() {
+            return report;
+        }
+        report.checks.extend(self.payout_chain_checks());
+        if self.config.ping_rpcs {
+            report.checks.extend(self.rpc_checks().await);
+        }
+
+        report
+    }
+
+    /// Each chain we pay out on needs an RPC; watch-only deployments never
+    /// pay out
+    fn payout_chain_checks(&self) -> Vec<PreflightCheck> {
+        if watch_only() {
+            return Vec::new();
+        }
+
+        self.config
+            .payout_chains
+            .iter()
+            .map(|chain| {
+                let subject = format!("payouts {}", chain);
+                match payout_rpc(chain) {
+                    Ok(Some(_)) => PreflightCheck::new(subject, Severity::Ok, "RPC configured"),
+                    Ok(None) => PreflightCheck::new(
+                        subject,
+                        Severity::Error,
+                        format!("{} is not set; payouts on {} would fail", payout_rpc_var(chain), chain),
+                    ),
+                    Err(e) => PreflightCheck::new(subject, Severity::Error, e),
+                }
+            })
+            .collect()
+    }
+
+    /// Ping every configured RPC. EVM endpoints must answer with the chain
+    /// id we sign for. An unreachable payout RPC is an error, any other a
+    /// warning.
+    async fn rpc_checks(&self) -> Vec<PreflightCheck> {
+        let mut endpoints: Vec<(String, String)> = EVM_RPC_ENV_VARS
+            .iter()
+            .filter_map(|(chain, _)| evm_rpc_url(chain).map(|url| (chain.to_string(), url)))
+            .collect();
+        if let Some(url) = process_env("SOLANA_PRIMARY_RPC") {
+            endpoints.push(("solana".to_string(), url));
+        }
+
+        let mut pings = JoinSet::new();
+        for (index, (chain, url)) in endpoints.into_iter().enumerate() {
+            let client = self.client.clone();
+            let timeout = self.config.rpc_timeout;
+            let payout = self.config.payout_chains.contains(&chain) && !watch_only();
+            pings.spawn(async move {
+                let result = match tokio::time::timeout(timeout, ping_rpc(&client, &chain, &url)).await {
+                    Ok(result) => result,
+                    Err(_) => Err(RpcProblem::Unreachable(format!("no answer within {:?}", timeout))),
+                };
+                let host = endpoint_host(&url).unwrap_or_else(|| "invalid URL".to_string());
+                let subject = format!("rpc {}", chain);
+                let check = match result {
+                    Ok(detail) => PreflightCheck::new(subject, Severity::Ok, format!("{} ({})", detail, host)),
+                    Err(RpcProblem::WrongChain(detail)) => {
+                        PreflightCheck::new(subject, Severity::Error, format!("{} ({})", detail, host))
+                    }
+                    Err(RpcProblem::Unreachable(detail)) => PreflightCheck::new(
+                        subject,
+                        if payout { Severity::Error } else { Severity::Warning },
+                        format!("{} ({})", detail, host),
+                    ),
+                };
+                (index, check)
+            });
+        }
+
+        let mut checks = Vec::new();
+        while let Some(joined) = pings.join_next().await {
+            match joined {
+                Ok(check) => checks.push(check),
+                Err(e) => tracing::warn!("RPC ping panicked: {}", e),
+            }
+        }
+        checks.sort_by_key(|(index, _)| *index);
+        checks.into_iter().map(|(_, check)| check).collect()
+    }
+}
+
+fn process_env(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|v| !v.trim().is_empty())
+}
+
+/// Checks that only read variables: required ones, feature requirements,
+/// the database URL, the JWT secret and the mnemonic
+pub fn env_checks(env: impl Fn(&str) -> Option<String>) -> Vec<PreflightCheck> {
+    let mut checks = Vec::new();
+
+    let missing: Vec<&str> = REQUIRED.iter().copied().filter(|name| env(name).is_none()).collect();
+    if missing.is_empty() {
+        checks.push(PreflightCheck::new("required", Severity::Ok, "all set"));
+    } else {
+        checks.push(PreflightCheck::new("required", Severity::Error, format!("{} not set", missing.join(", "))));
+    }
+
+    for (feature, trigger, needs) in FEATURE_REQUIREMENTS {
+        if env(trigger).is_none() {
+            continue;
+        }
+        let missing: Vec<&str> = needs.iter().copied().filter(|name| env(name).is_none()).collect();
+        if !missing.is_empty() {
+            checks.push(PreflightCheck::new(
+                *feature,
+                Severity::Error,
+                format!("{} is set but {} is not", trigger, missing.join(", ")),
+            ));
+        }
+    }
+    if env("QUOTE_REQUIRE_SIGNED").is_some_and(|v| matches!(v.trim().to_ascii_lowercase().as_str(), "1" | "true" | "yes"))
+        && env("QUOTE_SIGNING_KEY").is_none()
+    {
+        checks.push(PreflightCheck::new(
+            "quote signing",
+            Severity::Error,
+            "QUOTE_REQUIRE_SIGNED is on but QUOTE_SIGNING_KEY is not set",
+        ));
+    }
+
+    // Blocked countries with no way to locate clients would block nobody
+    if (env("GEO_BLOCKED_COUNTRIES").is_some() || env("GEO_BLOCKED_TRADE_COUNTRIES").is_some())
+        && env("GEO_COUNTRY_HEADER").is_none()
+        && env("GEOIP_API_URL").is_none()
+    {
+        checks.push(PreflightCheck::new(
+            "geo policy",
+            Severity::Error,
+            "blocked countries are set but neither GEO_COUNTRY_HEADER nor GEOIP_API_URL is",
+        ));
+    }
+
+    if let Some(url) = env("DATABASE_URL") {
+        let scheme = url.split_once(':').map(|(scheme, _)| scheme).unwrap_or_default();
+        if !matches!(scheme.to_ascii_lowercase().as_str(), "mysql" | "mariadb") {
+            checks.push(PreflightCheck::new(
+                "database",
+                Severity::Error,
+                format!("DATABASE_URL scheme '{}' is not supported; expected mysql://", scheme),
+            ));
+        }
+    }
+
+    if let Some(secret) = env("JWT_SECRET") {
+        if secret.len() < MIN_JWT_SECRET_LEN {
+            checks.push(PreflightCheck::new(
+                "jwt secret",
+                Severity::Warning,
+                format!("JWT_SECRET is {} characters; use at least {}", secret.len(), MIN_JWT_SECRET_LEN),
+            ));
+        }
+    }
+
+    // Never echo the phrase itself
+    if let Some(phrase) = env("WALLET_MNEMONIC") {
+        match Mnemonic::parse_in_normalized(Language::English, phrase.trim()) {
+            Ok(mnemonic) => checks.push(PreflightCheck::new(
+                "mnemonic",
+                Severity::Ok,
+                format!("valid {}-word BIP39 phrase", mnemonic.word_count()),
+            )),
+            Err(e) => checks.push(PreflightCheck::new(
+                "mnemonic",
+                Severity::Error,
+                format!("WALLET_MNEMONIC is not a valid BIP39 phrase: {}", e),
+            )),
+        }
+    }
+
+    checks
+}
+
+/// Parse every worker's settings, so all mistakes show up together instead
+/// of one `expect` at a time
+fn settings_checks() -> Vec<PreflightCheck> {
+    let mut results: Vec<(&str, Result<(), String>)> = vec![
+        ("sandbox", SandboxConfig::from_env().map(drop)),
+        ("signer", SignerBackend::from_env().map(drop)),
+        ("derivation paths", DerivationPaths::from_env().map(drop)),
+        ("listener shards", shard_count_from_env().map(drop)),
+        ("payout executor", PayoutExecutorConfig::from_env().map(drop)),
+        ("gas station", GasStationConfig::from_env().map(drop)),
+        ("token permits", PermitPolicy::from_env().map(drop)),
+        ("payout batching", PayoutBatchConfig::from_env().map(drop)),
+        ("payout guard", PayoutGuardPolicy::from_env().map(drop)),
+        ("auth retention", RetentionPolicy::from_env().map(drop)),
+        ("provider payloads", PayloadPolicy::from_env().map(drop)),
+        ("swap SLA", SlaPolicy::from_env().map(drop)),
+        ("swap ETA", EtaPolicy::from_env().map(drop)),
+        ("swap funnel", FunnelPolicy::from_env().map(drop)),
+        ("backups", BackupConfig::from_env().map(drop)),
+        ("wallet audit", WalletAuditConfig::from_env().map(drop)),
+        ("event stream", EventStreamConfig::from_env().map(drop)),
+        ("wallet message signing", MessageSigningPolicy::from_env().map(drop)),
+        ("startup warm-up", WarmupConfig::from_env().map(drop)),
+        ("geo policy", GeoPolicy::from_env().map(drop)),
+        ("geo lookup", GeoSource::from_env().map(drop)),
+        ("API versioning", VersionPolicy::from_env().map(drop)),
+        ("trusted proxies", TrustedProxies::from_env().map(drop)),
+        ("latency budgets", LatencyBudgets::from_env().map(drop)),
+    ];
+    if process_env("DATA_ENCRYPTION_KEYS").is_some() || process_env("DATA_ENCRYPTION_KEY").is_some() {
+        results.push(("PII encryption", FieldCipher::from_env().map(drop)));
+    }
+    if process_env("QUOTE_SIGNING_KEY").is_some() {
+        results.push(("quote signing", QuoteSigner::from_env().map(drop)));
+    }
+
+    let failed: Vec<PreflightCheck> = results
+        .iter()
+        .filter_map(|(subject, result)| result.as_ref().err().map(|e| PreflightCheck::new(*subject, Severity::Error, e)))
+        .collect();
+    if failed.is_empty() {
+        return vec![PreflightCheck::new("settings", Severity::Ok, format!("{} groups parse", results.len()))];
+    }
+    failed
+}
+
+/// Variable holding a payout chain's RPC
+fn payout_rpc_var(chain: &str) -> &'static str {
+    if chain == "solana" {
+        return "SOLANA_PRIMARY_RPC";
+    }
+    EVM_RPC_ENV_VARS.iter().find(|(name, _)| *name == chain).map(|(_, var)| *var).unwrap_or("an RPC")
+}
+
+/// The RPC a payout chain would use; Err for chains payouts can't run on
+fn payout_rpc(chain: &str) -> Result<Option<String>, String> {
+    if chain == "solana" {
+        return Ok(process_env("SOLANA_PRIMARY_RPC"));
+    }
+    if EVM_RPC_ENV_VARS.iter().any(|(name, _)| *name == chain) {
+        return Ok(evm_rpc_url(chain));
+    }
+    Err(format!("no payout RPC is wired up for {}; remove it from PREFLIGHT_PAYOUT_CHAINS", chain))
+}
+
+enum RpcProblem {
+    /// Answered, but for another network
+    WrongChain(String),
+    Unreachable(String),
+}
+
+async fn ping_rpc(client: &reqwest::Client, chain: &str, url: &str) -> Result<String, RpcProblem> {
+    let method = if chain == "solana" { "getHealth" } else { "eth_chainId" };
+    let body = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": [] });
+    let response = client
+        .post(url)
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| RpcProblem::Unreachable(format!("request failed: {}", e.without_url())))?;
+    if !response.status().is_success() {
+        return Err(RpcProblem::Unreachable(format!("HTTP {}", response.status())));
+    }
+    let reply: serde_json::Value =
+        response.json().await.map_err(|e| RpcProblem::Unreachable(format!("invalid reply: {}", e)))?;
+    if let Some(error) = reply.get("error") {
+        return Err(RpcProblem::Unreachable(format!("{} returned {}", method, error)));
+    }
+
+    if chain == "solana" {
+        return Ok("healthy".to_string());
+    }
+    let chain_id = reply["result"]
+        .as_str()
+        .and_then(|hex| u64::from_str_radix(hex.trim_start_matches("0x"), 16).ok())
+        .ok_or_else(|| RpcProblem::Unreachable("eth_chainId returned no chain id".to_string()))?;
+    let expected = signing_chain_id(chain) as u64;
+    if chain_id != expected {
+        return Err(RpcProblem::WrongChain(format!("answers chain id {}, expected {}", chain_id, expected)));
+    }
+    Ok(format!("chain id {}", chain_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    const TEST_MNEMONIC: &str =
+        "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+    fn checks(vars: &[(&str, &str)]) -> Vec<PreflightCheck> {
+        let vars: HashMap<String, String> = vars.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+        env_checks(|name| vars.get(name).cloned())
+    }
+
+    fn base() -> Vec<(&'static str, &'static str)> {
+        vec![
+            ("DATABASE_URL", "mysql://root@localhost/exchange"),
+            ("JWT_SECRET", "0123456789abcdef0123456789abcdef"),
+            ("TROCADOR_API_KEY", "key"),
+            ("WALLET_MNEMONIC", TEST_MNEMONIC),
+        ]
+    }
+
+    #[test]
+    fn test_complete_environment_passes() {
+        let checks = checks(&base());
+        assert!(checks.iter().all(|c| c.severity == Severity::Ok), "{:?}", checks);
+        assert!(checks.iter().any(|c| c.subject == "mnemonic" && c.detail.contains("12-word")));
+    }
+
+    #[test]
+    fn test_missing_and_half_configured_features() {
+        let mut vars = base();
+        vars.retain(|(name, _)| *name != "TROCADOR_API_KEY");
+        vars.push(("SMTP_USERNAME", "mailer"));
+        vars.push(("SMTP_HOST", "smtp.example.com"));
+        vars.push(("SUPPORT_DESK_TOKEN", "secret"));
+        let checks = checks(&vars);
+
+        let error = |subject: &str| checks.iter().find(|c| c.subject == subject && c.severity == Severity::Error);
+        assert_eq!(error("required").unwrap().detail, "TROCADOR_API_KEY not set");
+        assert_eq!(error("SMTP").unwrap().detail, "SMTP_USERNAME is set but SMTP_PASSWORD is not");
+        assert!(error("support desk").is_some());
+        assert!(error("object storage").is_none());
+    }
+
+    #[test]
+    fn test_geo_blocking_needs_a_country_source() {
+        let mut vars = base();
+        vars.push(("GEO_BLOCKED_COUNTRIES", "KP,IR"));
+        let geo = |vars: &[(&str, &str)]| {
+            checks(vars).into_iter().find(|c| c.subject == "geo policy" && c.severity == Severity::Error)
+        };
+        assert!(geo(&vars).is_some());
+
+        vars.push(("GEO_COUNTRY_HEADER", "cf-ipcountry"));
+        assert!(geo(&vars).is_none());
+    }
+
+    #[test]
+    fn test_bad_mnemonic_is_reported_without_the_phrase() {
+        let mut vars: Vec<(&str, &str)> = base();
+        vars.retain(|(name, _)| *name != "WALLET_MNEMONIC");
+        let phrase = TEST_MNEMONIC.replace("about", "abandon");
+        vars.push(("WALLET_MNEMONIC", &phrase));
+        let checks = checks(&vars);
+
+        let mnemonic = checks.iter().find(|c| c.subject == "mnemonic").unwrap();
+        assert_eq!(mnemonic.severity, Severity::Error);
+        assert!(!mnemonic.detail.contains("abandon"));
+    }
+
+    #[test]
+    fn test_report_lists_errors_first() {
+        let report = PreflightReport {
+            checks: vec![
+                PreflightCheck::new("mnemonic", Severity::Ok, "valid"),
+                PreflightCheck::new("jwt secret", Severity::Warning, "short"),
+                PreflightCheck::new("payouts solana", Severity::Error, "SOLANA_PRIMARY_RPC is not set"),
+            ],
+        };
+        let text = report.to_string();
+        let lines: Vec<&str> = text.lines().collect();
+
+        assert!(report.has_errors());
+        assert_eq!(lines[0], "Configuration check: 1 errors, 1 warnings, 1 passed");
+        assert!(lines[1].contains("ERROR") && lines[1].contains("payouts solana"));
+        assert!(lines[2].contains("WARN"));
+        assert!(lines[3].contains("ok"));
+    }
+}
@@ -1,29 +1,70 @@
-use crate::modules::swap::schema::{TrocadorQuote, RateResponse, RateType, EstimateQuery, EstimateResponse};
-use super::strategy::{PricingStrategy, PricingContext, AdaptivePricingStrategy};
+use crate::modules::swap::schema::{
+    TrocadorQuote, RateResponse, RateType, EstimateQuery, EstimateResponse, AppliedPromotion,
+    DetailedEstimateResponse, CommissionBreakdown, GasFloorBreakdown, ProviderFeeBreakdown,
+};
+use super::promotions::{discounted_fee, ActivePromotions, Audience, PromotionQuote};
+use super::rounding::RoundingPolicy;
+use super::strategy::{PricingStrategy, PricingContext, AdaptivePricingStrategy, FeeDecomposition};
+use crate::modules::commissions::crud::CommissionOverrides;
+use crate::services::amount::{self, Decimal};
+use rust_decimal::prelude::ToPrimitive;
+use std::sync::Arc;
+
+/// Rough USD price of a ticker, good enough for tiering and metrics
+pub fn approx_usd_price(ticker: &str) -> f64 {
+    match ticker.to_lowercase().as_str() {
+        "btc" => 60000.0,
+        "eth" => 3000.0,
+        "xmr" => 150.0,
+        "usdt" | "usdc" | "dai" => 1.0,
+        _ => 1.0, // Default to 1.0 for others (safe side)
+    }
+}
 
 pub struct PricingEngine {
     strategy: Box<dyn PricingStrategy>,
+    commissions: Arc<CommissionOverrides>,
+    rounding: RoundingPolicy,
 }
 
 impl PricingEngine {
     pub fn new() -> Self {
         Self {
             strategy: Box::new(AdaptivePricingStrategy::default()),
+            commissions: Arc::new(CommissionOverrides::default()),
+            rounding: RoundingPolicy::default(),
         }
     }
 
-    /// Takes raw provider quotes and applies the optimal markup algorithm
-    pub fn apply_optimal_markup(
-        &self,
-        quotes: &[TrocadorQuote],
-        amount_from: f64,
-        ticker_from: &str, // Changed from _network_to to ticker_from
-        gas_cost_native: f64, // Fetched from RpcClient
-    ) -> Vec<RateResponse> {
-        if quotes.is_empty() {
-            return vec![];
-        }
+    /// Charge negotiated per-provider rates instead of the strategy's rate
+    pub fn with_commissions(mut self, commissions: Arc<CommissionOverrides>) -> Self {
+        self.commissions = commissions;
+        self
+    }
+
+    /// Round fees and receive amounts to the decimals of the currency the
+    /// provider quotes in (the swap's `to` ticker)
+    pub fn with_currency(mut self, ticker_to: &str) -> Self {
+        self.rounding = RoundingPolicy::for_currency(ticker_to);
+        self
+    }
+
+    pub fn rounding(&self) -> RoundingPolicy {
+        self.rounding
+    }
+
+    /// The provider's override when it has one, else the strategy's rate
+    fn commission_rate_for(&self, provider: &str, strategy_rate: f64) -> f64 {
+        self.commissions.commission_rate(provider).unwrap_or(strategy_rate)
+    }
 
+    /// Provider spread (volatility index) and USD size of a trade
+    fn pricing_context(
+        quotes: &[TrocadorQuote],
+        amount_from: Decimal,
+        ticker_from: &str,
+        gas_cost_native: f64,
+    ) -> PricingContext {
         // 1. Calculate Provider Spread (Volatility Index)
         let amounts: Vec<f64> = quotes.iter()
             .map(|q| q.amount_to.parse::<f64>().unwrap_or(0.0))
@@ -35,64 +76,211 @@ impl PricingEngine {
         let spread = if max_amount > 0.0 { (max_amount - min_amount) / max_amount } else { 0.0 };
 
         // 2. USD Price Estimation (Heuristic for tiering)
-        let usd_price = match ticker_from.to_lowercase().as_str() {
-            "btc" => 60000.0,
-            "eth" => 3000.0,
-            "xmr" => 150.0,
-            "usdt" | "usdc" | "dai" => 1.0,
-            _ => 1.0, // Default to 1.0 for others (safe side)
-        };
-        let amount_usd = amount_from * usd_price;
+        let usd_price = approx_usd_price(ticker_from);
 
         // 3. Prepare Context
-        let ctx = PricingContext {
-            amount_usd,
+        PricingContext {
+            amount_usd: amount_from.to_f64().unwrap_or(0.0) * usd_price,
             network_gas_cost_native: gas_cost_native,
             provider_spread_percentage: spread,
-        };
+        }
+    }
+
+    /// Commission on the provider amount, bumped up to the gas floor when it
+    /// doesn't cover it
+    fn platform_fee(amount_to: Decimal, commission_rate: f64, gas_floor: f64) -> Decimal {
+        (amount_to * Self::decimal(commission_rate)).max(Self::decimal(gas_floor))
+    }
+
+    /// Strategy outputs and provider quote fields are floats
+    fn decimal(value: f64) -> Decimal {
+        amount::from_f64(value).unwrap_or_default()
+    }
+
+    /// Amount received per unit sent
+    fn unit_rate(receive: Decimal, amount_from: Decimal) -> Decimal {
+        receive.checked_div(amount_from).unwrap_or_default()
+    }
+
+    /// A provider's quoted amount and its own spread
+    fn quoted_amounts(quote: &TrocadorQuote) -> (Decimal, Decimal) {
+        let amount_to = amount::parse(&quote.amount_to).unwrap_or_default();
+        let waste = quote.waste.as_deref().map(amount::parse).and_then(Result::ok).unwrap_or_default();
+        (amount_to, waste)
+    }
+
+    /// Takes raw provider quotes and applies the optimal markup algorithm
+    pub fn apply_optimal_markup(
+        &self,
+        quotes: &[TrocadorQuote],
+        amount_from: Decimal,
+        ticker_from: &str, // Changed from _network_to to ticker_from
+        gas_cost_native: f64, // Fetched from RpcClient
+    ) -> Vec<RateResponse> {
+        if quotes.is_empty() {
+            return vec![];
+        }
+
+        // 1-3. Provider spread, USD size and gas cost
+        let ctx = Self::pricing_context(quotes, amount_from, ticker_from, gas_cost_native);
 
         // 4. Get Optimal Rates from Strategy
         let (commission_rate, gas_floor) = self.strategy.calculate_fees(&ctx);
 
         // 4. Transform and Sort
         let mut results: Vec<RateResponse> = quotes.iter().map(|quote| {
-            let amount_to = quote.amount_to.parse::<f64>().unwrap_or(0.0);
-            let waste = quote.waste.as_deref().unwrap_or("0.0").parse::<f64>().unwrap_or(0.0);
+            let (amount_to, waste) = Self::quoted_amounts(quote);
             
             // MATH: User_Receive = Max(0, Amount_To * (1 - Rate) - Gas_Floor)
-            let mut platform_fee = amount_to * commission_rate;
-            
-            // If platform fee doesn't cover gas floor, bump it up
-            if platform_fee < gas_floor {
-                platform_fee = gas_floor;
-            }
-
-            let final_user_receive = (amount_to - platform_fee).max(0.0);
+            let rate = self.commission_rate_for(&quote.provider, commission_rate);
+            let (platform_fee, final_user_receive) =
+                self.rounding.split(amount_to, Self::platform_fee(amount_to, rate, gas_floor));
             
             RateResponse {
                 provider: quote.provider.clone(),
                 provider_name: quote.provider.clone(),
-                rate: if amount_from > 0.0 { final_user_receive / amount_from } else { 0.0 },
+                rate: Self::unit_rate(final_user_receive, amount_from),
                 estimated_amount: final_user_receive,
-                min_amount: quote.min_amount.unwrap_or(0.0),
-                max_amount: quote.max_amount.unwrap_or(0.0),
-                network_fee: 0.0,
+                min_amount: Self::decimal(quote.min_amount.unwrap_or(0.0)),
+                max_amount: Self::decimal(quote.max_amount.unwrap_or(0.0)),
+                network_fee: Decimal::ZERO,
                 provider_fee: waste,
                 platform_fee,
-                total_fee: waste + platform_fee,
+                total_fee: self.rounding.total(&[waste, platform_fee]),
                 rate_type: RateType::Floating, // Default
                 kyc_required: quote.kycrating.as_deref().unwrap_or("D") != "A",
                 kyc_rating: quote.kycrating.clone(),
                 eta_minutes: quote.eta.map(|e| e as u32).or(Some(15)),
+                quote: None,
+                promotion: None,
             }
         }).collect();
 
         // Sort by best rate for user
-        results.sort_by(|a, b| b.estimated_amount.partial_cmp(&a.estimated_amount).unwrap_or(std::cmp::Ordering::Equal));
+        results.sort_by_key(|r| std::cmp::Reverse(r.estimated_amount));
         
         results
     }
     
+    /// Waive platform fees under the best campaign matching each rate for
+    /// this audience, then re-sort. `pair` is (from, network_from, to,
+    /// network_to). Rates keep the provider's amount; only our share of it
+    /// changes.
+    pub fn apply_promotions(
+        &self,
+        rates: &mut [RateResponse],
+        pair: (&str, &str, &str, &str),
+        amount_from: Decimal,
+        promotions: &ActivePromotions,
+        audience: &Audience,
+    ) {
+        if promotions.is_empty() {
+            return;
+        }
+
+        let (from, network_from, to, network_to) = pair;
+        let now = chrono::Utc::now();
+        for rate in rates.iter_mut() {
+            let quote = PromotionQuote { from, network_from, to, network_to, provider: &rate.provider };
+            let Some(promotion) = promotions.best(&quote, audience, now) else {
+                continue;
+            };
+
+            let standard_fee = rate.platform_fee;
+            let (platform_fee, estimated_amount) = self
+                .rounding
+                .split(rate.estimated_amount + standard_fee, discounted_fee(promotion, standard_fee));
+            rate.platform_fee = platform_fee;
+            rate.estimated_amount = estimated_amount;
+            rate.rate = Self::unit_rate(estimated_amount, amount_from);
+            rate.total_fee = self.rounding.total(&[rate.provider_fee, platform_fee]);
+            rate.promotion = Some(AppliedPromotion {
+                id: promotion.id.clone(),
+                name: promotion.name.clone(),
+                fee_discount: promotion.fee_discount,
+                standard_platform_fee: standard_fee,
+            });
+        }
+
+        rates.sort_by_key(|r| std::cmp::Reverse(r.estimated_amount));
+    }
+
+    /// Every step `apply_optimal_markup` takes for a trade, per provider.
+    /// Returns `None` when no provider quoted the pair.
+    pub fn detailed_breakdown(
+        &self,
+        quotes: &[TrocadorQuote],
+        query: &EstimateQuery,
+        gas_cost_native: f64,
+    ) -> Option<DetailedEstimateResponse> {
+        if quotes.is_empty() {
+            return None;
+        }
+
+        let ctx = Self::pricing_context(quotes, query.amount.value(), &query.from, gas_cost_native);
+        let FeeDecomposition {
+            tier,
+            tier_rate,
+            volatility_premium,
+            commission_rate,
+            network_gas_cost_native,
+            gas_safety_buffer,
+            gas_floor_native,
+        } = self.strategy.decompose_fees(&ctx);
+
+        let mut providers: Vec<ProviderFeeBreakdown> = quotes.iter().map(|quote| {
+            let (provider_amount, provider_fee) = Self::quoted_amounts(quote);
+            let commission_override = self.commissions.commission_rate(&quote.provider);
+            let rate = commission_override.unwrap_or(commission_rate);
+            let commission_fee = provider_amount * Self::decimal(rate);
+            let (platform_fee, estimated_receive) = self
+                .rounding
+                .split(provider_amount, Self::platform_fee(provider_amount, rate, gas_floor_native));
+
+            ProviderFeeBreakdown {
+                provider: quote.provider.clone(),
+                provider_amount,
+                provider_fee,
+                commission_fee,
+                commission_override_percentage: commission_override.map(|r| r * 100.0),
+                gas_floor_applied: commission_fee < Self::decimal(gas_floor_native),
+                platform_fee,
+                total_fee: self.rounding.total(&[provider_fee, platform_fee]),
+                estimated_receive,
+                rate: Self::unit_rate(estimated_receive, query.amount.value()),
+                min_amount: Self::decimal(quote.min_amount.unwrap_or(0.0)),
+                max_amount: Self::decimal(quote.max_amount.unwrap_or(0.0)),
+            }
+        }).collect();
+
+        providers.sort_by_key(|p| std::cmp::Reverse(p.estimated_receive));
+
+        Some(DetailedEstimateResponse {
+            from: query.from.clone(),
+            to: query.to.clone(),
+            amount: query.amount.value(),
+            network_from: query.network_from.clone(),
+            network_to: query.network_to.clone(),
+            amount_usd: ctx.amount_usd,
+            provider_spread_percentage: ctx.provider_spread_percentage * 100.0,
+            commission: CommissionBreakdown {
+                tier: tier.to_string(),
+                tier_rate_percentage: tier_rate * 100.0,
+                volatility_premium_percentage: volatility_premium * 100.0,
+                commission_rate_percentage: commission_rate * 100.0,
+            },
+            gas: GasFloorBreakdown {
+                network_fee_estimate: network_gas_cost_native,
+                safety_buffer: gas_safety_buffer,
+                gas_floor: gas_floor_native,
+                fee_targets: Vec::new(),
+            },
+            slippage_percentage: self.strategy.estimate_slippage(ctx.amount_usd, ctx.provider_spread_percentage) * 100.0,
+            best_provider: providers[0].provider.clone(),
+            providers,
+        })
+    }
+
     /// Generate warnings based on trade conditions
     pub fn generate_warnings(
         &self,
@@ -151,7 +339,7 @@ impl PricingEngine {
         
         // Calculate slippage
         let slippage_pct = self.strategy.estimate_slippage(amount_usd, provider_spread);
-        let slippage_amount = best_rate.estimated_amount * slippage_pct;
+        let slippage_amount = best_rate.estimated_amount * Self::decimal(slippage_pct);
         
         // Generate warnings
         let warnings = self.generate_warnings(
@@ -164,13 +352,13 @@ impl PricingEngine {
         EstimateResponse {
             from: query.from.clone(),
             to: query.to.clone(),
-            amount: query.amount,
+            amount: query.amount.value(),
             network_from: query.network_from.clone(),
             network_to: query.network_to.clone(),
             best_rate: best_rate.rate,
             estimated_receive: best_rate.estimated_amount,
-            estimated_receive_min: (best_rate.estimated_amount - slippage_amount).max(0.0),
-            estimated_receive_max: best_rate.estimated_amount + (slippage_amount * 0.5),
+            estimated_receive_min: self.rounding.credit((best_rate.estimated_amount - slippage_amount).max(Decimal::ZERO)),
+            estimated_receive_max: self.rounding.credit(best_rate.estimated_amount + slippage_amount / Decimal::TWO),
             network_fee: best_rate.network_fee,
             provider_fee: best_rate.provider_fee,
             platform_fee: best_rate.platform_fee,
@@ -0,0 +1,291 @@
+//! Rate quotes gathered from every provider adapter at once. All adapters
+//! are asked concurrently under one deadline (`rates.deadline_ms`); quotes
+//! from those that answered in time are served and the rest are reported
+//! as timed out, so one slow provider can't hold up GET /swap/rates.
+
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use tokio::task::JoinSet;
+
+use crate::modules::swap::schema::{RateSourceStatus, RateSourceTiming, TrocadorQuote};
+use crate::services::amount::Decimal;
+use crate::services::liquidity::PairKey;
+use crate::services::trocador::{TrocadorClient, TrocadorError};
+
+/// Retries of a rate-limited Trocador request, 500ms apart and growing
+const TROCADOR_RATE_LIMIT_RETRIES: u64 = 2;
+
+/// What one adapter quoted
+#[derive(Debug, Default)]
+pub struct SourceQuotes {
+    /// Id swaps created from these quotes must reference, when the adapter
+    /// issues one
+    pub trade_id: Option<String>,
+    pub quotes: Vec<TrocadorQuote>,
+}
+
+#[async_trait]
+pub trait QuoteSource: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// Every quote the adapter has for `amount` of the source currency
+    async fn quotes(&self, pair: &PairKey, amount: Decimal) -> Result<SourceQuotes, String>;
+}
+
+// =============================================================================
+// TROCADOR
+// =============================================================================
+
+pub struct TrocadorQuoteSource {
+    client: TrocadorClient,
+}
+
+impl TrocadorQuoteSource {
+    pub fn new(api_key: String) -> Self {
+        Self { client: TrocadorClient::new(api_key) }
+    }
+}
+
+fn is_rate_limited(e: &TrocadorError) -> bool {
+    let message = e.to_string();
+    message.contains("Rate limit")
+        || message.contains("rate limit")
+        || message.contains("429")
+        || message.contains("Too Many Requests")
+}
+
+#[async_trait]
+impl QuoteSource for TrocadorQuoteSource {
+    fn name(&self) -> &'static str {
+        "trocador"
+    }
+
+    async fn quotes(&self, pair: &PairKey, amount: Decimal) -> Result<SourceQuotes, String> {
+        let mut retries = 0;
+        loop {
+            match self.client.get_rates(&pair.from, &pair.network_from, &pair.to, &pair.network_to, amount).await {
+                Ok(rates) => return Ok(SourceQuotes { trade_id: Some(rates.trade_id), quotes: rates.quotes.quotes }),
+                Err(e) if is_rate_limited(&e) && retries < TROCADOR_RATE_LIMIT_RETRIES => {
+                    retries += 1;
+                    tokio::time::sleep(Duration::from_millis(retries * 500)).await;
+                }
+                Err(e) => return Err(e.to_string()),
+            }
+        }
+    }
+}
+
+// =============================================================================
+// FAN-OUT
+// =============================================================================
+
+/// Quotes from the adapters that answered in time
+#[derive(Debug, Default)]
+pub struct FanoutResult {
+    /// First trade id issued, in adapter order
+    pub trade_id: Option<String>,
+    pub quotes: Vec<TrocadorQuote>,
+    /// One entry per adapter, in adapter order
+    pub sources: Vec<RateSourceTiming>,
+    errors: Vec<String>,
+}
+
+impl FanoutResult {
+    /// Some adapter missed the deadline or failed
+    pub fn partial(&self) -> bool {
+        self.sources.iter().any(|s| s.status != RateSourceStatus::Ok)
+    }
+
+    /// Why no adapter answered; None when at least one did
+    pub fn failure(&self) -> Option<String> {
+        if self.sources.iter().any(|s| s.status == RateSourceStatus::Ok) {
+            return None;
+        }
+        if self.errors.is_empty() {
+            return Some("No rate provider answered in time".to_string());
+        }
+        Some(self.errors.join("; "))
+    }
+}
+
+pub struct QuoteFanout {
+    sources: Vec<Arc<dyn QuoteSource>>,
+}
+
+impl QuoteFanout {
+    pub fn new() -> Self {
+        Self { sources: Vec::new() }
+    }
+
+    pub fn with_source(mut self, source: Arc<dyn QuoteSource>) -> Self {
+        self.sources.push(source);
+        self
+    }
+
+    /// Adapters configured in the environment
+    pub fn from_env() -> Self {
+        let api_key = std::env::var("TROCADOR_API_KEY").unwrap_or_default();
+        Self::new().with_source(Arc::new(TrocadorQuoteSource::new(api_key)))
+    }
+
+    pub fn global() -> &'static QuoteFanout {
+        static FANOUT: OnceLock<QuoteFanout> = OnceLock::new();
+        FANOUT.get_or_init(Self::from_env)
+    }
+
+    /// Ask every adapter at once and keep what arrives within `deadline`
+    pub async fn collect(&self, pair: &PairKey, amount: Decimal, deadline: Duration) -> FanoutResult {
+        let mut requests = JoinSet::new();
+        for (index, source) in self.sources.iter().enumerate() {
+            let source = source.clone();
+            let pair = pair.clone();
+            requests.spawn(async move {
+                let started = Instant::now();
+                let answer = tokio::time::timeout(deadline, source.quotes(&pair, amount)).await;
+                (index, source.name(), started.elapsed(), answer)
+            });
+        }
+
+        let mut answers = Vec::with_capacity(self.sources.len());
+        while let Some(joined) = requests.join_next().await {
+            match joined {
+                Ok(answer) => answers.push(answer),
+                Err(e) => tracing::warn!("Rate source panicked: {}", e),
+            }
+        }
+        answers.sort_by_key(|(index, ..)| *index);
+
+        let mut result = FanoutResult::default();
+        for (_, name, elapsed, answer) in answers {
+            let (status, quotes) = match answer {
+                Ok(Ok(answer)) => {
+                    let count = answer.quotes.len();
+                    if result.trade_id.is_none() {
+                        result.trade_id = answer.trade_id;
+                    }
+                    result.quotes.extend(answer.quotes);
+                    (RateSourceStatus::Ok, count)
+                }
+                Ok(Err(e)) => {
+                    tracing::warn!("Rate source {} failed: {}", name, e);
+                    result.errors.push(e);
+                    (RateSourceStatus::Error, 0)
+                }
+                Err(_) => {
+                    tracing::warn!("Rate source {} missed the {}ms deadline", name, deadline.as_millis());
+                    (RateSourceStatus::Timeout, 0)
+                }
+            };
+            result.sources.push(RateSourceTiming {
+                source: name.to_string(),
+                status,
+                elapsed_ms: elapsed.min(deadline).as_millis() as u64,
+                quotes,
+            });
+        }
+        result
+    }
+}
+
+impl Default for QuoteFanout {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct DelayedSource {
+        name: &'static str,
+        delay: Duration,
+        answer: Result<usize, ()>,
+    }
+
+    #[async_trait]
+    impl QuoteSource for DelayedSource {
+        fn name(&self) -> &'static str {
+            self.name
+        }
+
+        async fn quotes(&self, _pair: &PairKey, _amount: Decimal) -> Result<SourceQuotes, String> {
+            tokio::time::sleep(self.delay).await;
+            let count = self.answer.map_err(|_| format!("{} is down", self.name))?;
+            let quotes = (0..count)
+                .map(|i| {
+                    serde_json::from_value(serde_json::json!({
+                        "provider": format!("{}-{}", self.name, i),
+                        "amount_to": "1.0",
+                    }))
+                    .unwrap()
+                })
+                .collect();
+            Ok(SourceQuotes { trade_id: Some(format!("{}-trade", self.name)), quotes })
+        }
+    }
+
+    fn source(name: &'static str, delay_ms: u64, answer: Result<usize, ()>) -> Arc<dyn QuoteSource> {
+        Arc::new(DelayedSource { name, delay: Duration::from_millis(delay_ms), answer })
+    }
+
+    fn pair() -> PairKey {
+        PairKey {
+            from: "btc".to_string(),
+            network_from: "Mainnet".to_string(),
+            to: "eth".to_string(),
+            network_to: "ERC20".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_slow_source_is_left_out() {
+        let fanout = QuoteFanout::new()
+            .with_source(source("slow", 2_000, Ok(3)))
+            .with_source(source("fast", 10, Ok(2)));
+
+        let started = Instant::now();
+        let result = fanout.collect(&pair(), Decimal::ONE, Duration::from_millis(200)).await;
+
+        assert!(started.elapsed() < Duration::from_secs(1));
+        assert!(result.partial());
+        assert_eq!(result.failure(), None);
+        assert_eq!(result.quotes.len(), 2);
+        assert_eq!(result.trade_id.as_deref(), Some("fast-trade"));
+        assert_eq!(result.sources[0].source, "slow");
+        assert_eq!(result.sources[0].status, RateSourceStatus::Timeout);
+        assert_eq!(result.sources[0].elapsed_ms, 200);
+        assert_eq!(result.sources[1].status, RateSourceStatus::Ok);
+        assert_eq!(result.sources[1].quotes, 2);
+    }
+
+    #[tokio::test]
+    async fn test_sources_are_asked_concurrently() {
+        let fanout = QuoteFanout::new()
+            .with_source(source("a", 300, Ok(1)))
+            .with_source(source("b", 300, Ok(1)));
+
+        let started = Instant::now();
+        let result = fanout.collect(&pair(), Decimal::ONE, Duration::from_millis(1000)).await;
+
+        assert!(started.elapsed() < Duration::from_millis(600));
+        assert!(!result.partial());
+        assert_eq!(result.quotes.len(), 2);
+        assert_eq!(result.trade_id.as_deref(), Some("a-trade"));
+    }
+
+    #[tokio::test]
+    async fn test_failure_when_nothing_answered() {
+        let fanout = QuoteFanout::new()
+            .with_source(source("down", 10, Err(())))
+            .with_source(source("slow", 2_000, Ok(1)));
+
+        let result = fanout.collect(&pair(), Decimal::ONE, Duration::from_millis(100)).await;
+
+        assert!(result.quotes.is_empty());
+        assert_eq!(result.failure().as_deref(), Some("down is down"));
+        assert_eq!(result.sources[0].status, RateSourceStatus::Error);
+    }
+}
@@ -1,5 +1,12 @@
 pub mod strategy;
 pub mod engine;
+pub mod payout;
+pub mod promotions;
+pub mod rounding;
+pub mod fanout;
 
-pub use engine::PricingEngine;
+pub use engine::{approx_usd_price, PricingEngine};
+pub use payout::PayoutSplit;
+pub use promotions::{ActivePromotions, Audience, PromotionQuote};
+pub use rounding::RoundingPolicy;
 pub use strategy::*;
@@ -0,0 +1,87 @@
+use rust_decimal::prelude::ToPrimitive;
+
+use crate::services::amount::{self, AmountError, Decimal};
+
+/// How a received amount is divided at payout. Always satisfies
+/// `received = payout + platform_fee + network_fee` when `payout > 0`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PayoutSplit {
+    pub received: Decimal,
+    pub platform_fee: Decimal,
+    pub network_fee: Decimal,
+    pub payout: Decimal,
+}
+
+impl PayoutSplit {
+    /// Split `received` using a strategy's `(commission_rate, gas_floor)`.
+    /// The payout is truncated to the chain's `decimals`; the remainder that
+    /// cannot be sent is added to the platform fee rather than lost.
+    pub fn compute(
+        received: Decimal,
+        network_fee: Decimal,
+        commission_rate: f64,
+        gas_floor: f64,
+        decimals: u32,
+    ) -> Result<Self, AmountError> {
+        let rate = amount::from_f64(commission_rate)?;
+        let floor = amount::from_f64(gas_floor)?;
+        let fee = (received * rate).max(floor);
+
+        let remaining = received - fee - network_fee;
+        if remaining <= Decimal::ZERO {
+            return Ok(Self { received, platform_fee: fee, network_fee, payout: Decimal::ZERO });
+        }
+
+        let payout = remaining.trunc_with_scale(decimals).normalize();
+        Ok(Self {
+            received,
+            platform_fee: fee + (remaining - payout),
+            network_fee,
+            payout,
+        })
+    }
+
+    /// Platform fee as a fraction of what was received
+    pub fn commission_rate(&self) -> f64 {
+        if self.received > Decimal::ZERO {
+            (self.platform_fee / self.received).to_f64().unwrap_or(0.0)
+        } else {
+            0.0
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dec(s: &str) -> Decimal {
+        amount::parse(s).unwrap()
+    }
+
+    #[test]
+    fn test_split_adds_up_exactly() {
+        let split = PayoutSplit::compute(dec("0.29"), dec("0.0000025"), 0.005, 0.00001, amount::BTC_DECIMALS).unwrap();
+
+        assert_eq!(split.platform_fee, dec("0.00145"));
+        assert_eq!(split.payout, dec("0.2885475"));
+        assert_eq!(split.payout + split.platform_fee + split.network_fee, split.received);
+    }
+
+    #[test]
+    fn test_truncation_dust_goes_to_fee() {
+        let split = PayoutSplit::compute(dec("1"), dec("0.000021"), 0.003, 0.0, 4).unwrap();
+
+        assert_eq!(split.payout, dec("0.9969"));
+        assert_eq!(split.platform_fee, dec("0.003079"));
+        assert_eq!(split.payout + split.platform_fee + split.network_fee, split.received);
+    }
+
+    #[test]
+    fn test_fees_exceeding_received() {
+        let split = PayoutSplit::compute(dec("0.00001"), dec("0.00002"), 0.01, 0.00005, 8).unwrap();
+
+        assert_eq!(split.payout, Decimal::ZERO);
+        assert_eq!(split.platform_fee, dec("0.00005"));
+    }
+}
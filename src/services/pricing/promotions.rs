@@ -0,0 +1,266 @@
+//! Promotional campaigns ("zero fees this weekend on BTC→ETH"). Campaigns
+//! waive a fraction of the platform fee on quotes matching their pair,
+//! provider, date range and user segment. Only the platform fee is
+//! discounted; provider and network fees are untouched.
+//!
+//! When several campaigns match a quote one applies, chosen by priority,
+//! then the larger discount, then the more specific match, then the one
+//! ending first. Campaigns never stack.
+
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+
+use crate::modules::promotions::model::{Promotion, UserSegment};
+use crate::modules::swap::crud::SwapCrud;
+use crate::services::amount::{self, Decimal};
+
+/// The trade a quote is for
+#[derive(Debug, Clone, Copy)]
+pub struct PromotionQuote<'a> {
+    pub from: &'a str,
+    pub network_from: &'a str,
+    pub to: &'a str,
+    pub network_to: &'a str,
+    pub provider: &'a str,
+}
+
+/// Who is asking for a quote, as far as campaigns care
+#[derive(Debug, Clone, Default)]
+pub struct Audience {
+    /// `None` for requests without an account
+    pub user_id: Option<String>,
+    /// `users.payout_tier`
+    pub tier: Option<String>,
+    pub completed_swaps: u64,
+    /// Campaign id to the swaps this user has created under it
+    pub redemptions: HashMap<String, u32>,
+}
+
+impl Audience {
+    pub fn anonymous() -> Self {
+        Self::default()
+    }
+
+    pub fn in_segment(&self, segment: UserSegment) -> bool {
+        let tier = |name: &str| self.tier.as_deref().is_some_and(|t| t.eq_ignore_ascii_case(name));
+        match segment {
+            UserSegment::All => true,
+            UserSegment::Anonymous => self.user_id.is_none(),
+            UserSegment::Registered => self.user_id.is_some(),
+            UserSegment::NewUsers => self.user_id.is_some() && self.completed_swaps == 0,
+            UserSegment::Vip => tier("vip"),
+            UserSegment::Partner => tier("partner"),
+        }
+    }
+
+    /// Per-user caps need an account to count against, so capped campaigns
+    /// are never offered anonymously
+    fn under_user_cap(&self, promotion: &Promotion) -> bool {
+        match promotion.max_uses_per_user {
+            None => true,
+            Some(cap) => {
+                self.user_id.is_some() && self.redemptions.get(&promotion.id).copied().unwrap_or(0) < cap
+            }
+        }
+    }
+}
+
+/// Campaigns that may apply to quotes right now
+#[derive(Debug, Default)]
+pub struct ActivePromotions {
+    promotions: Vec<Promotion>,
+}
+
+impl ActivePromotions {
+    pub fn new(promotions: Vec<Promotion>) -> Self {
+        Self { promotions }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.promotions.is_empty()
+    }
+
+    /// The campaign that applies to a quote at `now`, if any
+    pub fn best(&self, quote: &PromotionQuote<'_>, audience: &Audience, now: DateTime<Utc>) -> Option<&Promotion> {
+        self.promotions
+            .iter()
+            .filter(|p| matches(p, quote, now) && audience.in_segment(p.segment) && audience.under_user_cap(p))
+            .max_by(|a, b| {
+                a.priority
+                    .cmp(&b.priority)
+                    .then(a.fee_discount.total_cmp(&b.fee_discount))
+                    .then(specificity(a).cmp(&specificity(b)))
+                    .then(b.ends_at.cmp(&a.ends_at))
+                    .then(b.id.cmp(&a.id))
+            })
+    }
+}
+
+/// Whether a campaign is running at `now`, has uses left and covers the
+/// quote's pair and provider
+fn matches(promotion: &Promotion, quote: &PromotionQuote<'_>, now: DateTime<Utc>) -> bool {
+    let field = |expected: &Option<String>, actual: &str| {
+        expected.as_deref().is_none_or(|e| e.eq_ignore_ascii_case(actual))
+    };
+
+    promotion.is_active
+        && promotion.starts_at <= now
+        && now < promotion.ends_at
+        && promotion.max_uses.is_none_or(|max| promotion.uses < max)
+        && field(&promotion.from_currency, quote.from)
+        && field(&promotion.from_network, quote.network_from)
+        && field(&promotion.to_currency, quote.to)
+        && field(&promotion.to_network, quote.network_to)
+        && promotion
+            .provider_id
+            .as_deref()
+            .is_none_or(|id| id == SwapCrud::normalize_provider_id(quote.provider))
+}
+
+/// How many of the pair and provider fields a campaign pins down
+fn specificity(promotion: &Promotion) -> usize {
+    [
+        &promotion.from_currency,
+        &promotion.from_network,
+        &promotion.to_currency,
+        &promotion.to_network,
+        &promotion.provider_id,
+    ]
+    .iter()
+    .filter(|f| f.is_some())
+    .count()
+}
+
+/// The platform fee left after a campaign's discount
+pub fn discounted_fee(promotion: &Promotion, platform_fee: Decimal) -> Decimal {
+    let discount = amount::from_f64(promotion.fee_discount.clamp(0.0, 1.0)).unwrap_or_default();
+    platform_fee * (Decimal::ONE - discount)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn promotion(id: &str) -> Promotion {
+        Promotion {
+            id: id.to_string(),
+            name: id.to_string(),
+            description: None,
+            from_currency: None,
+            from_network: None,
+            to_currency: None,
+            to_network: None,
+            provider_id: None,
+            segment: UserSegment::All,
+            fee_discount: 1.0,
+            priority: 0,
+            starts_at: Utc::now() - Duration::days(1),
+            ends_at: Utc::now() + Duration::days(1),
+            max_uses: None,
+            max_uses_per_user: None,
+            uses: 0,
+            is_active: true,
+            created_by: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn btc_eth() -> PromotionQuote<'static> {
+        PromotionQuote {
+            from: "btc",
+            network_from: "bitcoin",
+            to: "eth",
+            network_to: "ethereum",
+            provider: "ChangeNOW",
+        }
+    }
+
+    fn signed_in() -> Audience {
+        Audience {
+            user_id: Some("user-1".to_string()),
+            ..Audience::default()
+        }
+    }
+
+    #[test]
+    fn test_matches_pair_provider_and_window() {
+        let mut weekend = promotion("weekend");
+        weekend.from_currency = Some("BTC".to_string());
+        weekend.to_currency = Some("eth".to_string());
+        weekend.provider_id = Some("changenow".to_string());
+        let promotions = ActivePromotions::new(vec![weekend.clone()]);
+        let now = Utc::now();
+
+        assert!(promotions.best(&btc_eth(), &Audience::anonymous(), now).is_some());
+        let eth_btc = PromotionQuote { from: "eth", to: "btc", ..btc_eth() };
+        assert!(promotions.best(&eth_btc, &Audience::anonymous(), now).is_none());
+        let other_provider = PromotionQuote { provider: "FixedFloat", ..btc_eth() };
+        assert!(promotions.best(&other_provider, &Audience::anonymous(), now).is_none());
+        assert!(promotions.best(&btc_eth(), &Audience::anonymous(), weekend.ends_at).is_none());
+
+        weekend.max_uses = Some(10);
+        weekend.uses = 10;
+        let exhausted = ActivePromotions::new(vec![weekend]);
+        assert!(exhausted.best(&btc_eth(), &Audience::anonymous(), now).is_none());
+    }
+
+    #[test]
+    fn test_segments_and_user_caps() {
+        let mut new_users = promotion("welcome");
+        new_users.segment = UserSegment::NewUsers;
+        new_users.max_uses_per_user = Some(1);
+        let promotions = ActivePromotions::new(vec![new_users]);
+        let now = Utc::now();
+
+        assert!(promotions.best(&btc_eth(), &Audience::anonymous(), now).is_none());
+        assert!(promotions.best(&btc_eth(), &signed_in(), now).is_some());
+
+        let returning = Audience { completed_swaps: 3, ..signed_in() };
+        assert!(promotions.best(&btc_eth(), &returning, now).is_none());
+
+        let mut redeemed = signed_in();
+        redeemed.redemptions.insert("welcome".to_string(), 1);
+        assert!(promotions.best(&btc_eth(), &redeemed, now).is_none());
+
+        let vip = Audience { tier: Some("vip".to_string()), ..signed_in() };
+        assert!(vip.in_segment(UserSegment::Vip));
+        assert!(!vip.in_segment(UserSegment::Partner));
+    }
+
+    #[test]
+    fn test_precedence() {
+        let mut half = promotion("half");
+        half.fee_discount = 0.5;
+        half.priority = 10;
+        let free = promotion("free");
+        let mut free_pair = promotion("free-pair");
+        free_pair.from_currency = Some("btc".to_string());
+        let now = Utc::now();
+
+        // Priority beats a larger discount
+        let promotions = ActivePromotions::new(vec![free.clone(), half.clone()]);
+        assert_eq!(promotions.best(&btc_eth(), &Audience::anonymous(), now).unwrap().id, "half");
+
+        // Equal priority: larger discount, then the more specific campaign
+        half.priority = 0;
+        let promotions = ActivePromotions::new(vec![half, free.clone(), free_pair]);
+        assert_eq!(promotions.best(&btc_eth(), &Audience::anonymous(), now).unwrap().id, "free-pair");
+
+        // Otherwise the one ending first
+        let mut ending = promotion("ending");
+        ending.ends_at = now + Duration::hours(1);
+        let promotions = ActivePromotions::new(vec![free, ending]);
+        assert_eq!(promotions.best(&btc_eth(), &Audience::anonymous(), now).unwrap().id, "ending");
+    }
+
+    #[test]
+    fn test_discounted_fee() {
+        let mut campaign = promotion("p");
+        let fee = amount::parse("0.002").unwrap();
+        assert!(discounted_fee(&campaign, fee).is_zero());
+        campaign.fee_discount = 0.25;
+        assert_eq!(discounted_fee(&campaign, fee), amount::parse("0.0015").unwrap());
+    }
+}
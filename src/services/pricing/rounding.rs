@@ -0,0 +1,124 @@
+//! Per-currency rounding. Fees are rounded up and user credit (receive
+//! amounts, refunds) down to the currency's decimal places, and the credit
+//! is taken from what is left after the rounded fee, so a quote's parts add
+//! up to the satoshi instead of drifting by float error.
+
+use crate::services::amount::Decimal;
+use rust_decimal::RoundingStrategy;
+
+/// Places used for tickers without an entry in `CURRENCY_DECIMALS`
+pub const DEFAULT_DECIMALS: u32 = 8;
+
+/// Decimal places amounts are quoted and stored in. EVM natives are quoted
+/// to 8 places like the swaps table, not to the 18 of their base unit.
+const CURRENCY_DECIMALS: &[(&str, u32)] = &[
+    ("btc", 8),
+    ("ltc", 8),
+    ("bch", 8),
+    ("doge", 8),
+    ("eth", 8),
+    ("bnb", 8),
+    ("matic", 8),
+    ("pol", 8),
+    ("sol", 9),
+    ("xmr", 12),
+    ("xrp", 6),
+    ("usdt", 6),
+    ("usdc", 6),
+    ("dai", 6),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RoundingPolicy {
+    pub decimals: u32,
+}
+
+impl Default for RoundingPolicy {
+    fn default() -> Self {
+        Self { decimals: DEFAULT_DECIMALS }
+    }
+}
+
+impl RoundingPolicy {
+    pub fn new(decimals: u32) -> Self {
+        Self { decimals }
+    }
+
+    pub fn for_currency(ticker: &str) -> Self {
+        let ticker = ticker.trim().to_lowercase();
+        CURRENCY_DECIMALS
+            .iter()
+            .find(|(t, _)| *t == ticker)
+            .map(|(_, decimals)| Self::new(*decimals))
+            .unwrap_or_default()
+    }
+
+    /// Round an amount owed to the user down
+    pub fn credit(&self, value: Decimal) -> Decimal {
+        value.round_dp_with_strategy(self.decimals, RoundingStrategy::ToZero).normalize()
+    }
+
+    /// Round a fee we charge up
+    pub fn fee(&self, value: Decimal) -> Decimal {
+        value.round_dp_with_strategy(self.decimals, RoundingStrategy::AwayFromZero).normalize()
+    }
+
+    /// `(fee, credit)` for a fee taken out of `gross`: the fee rounded up,
+    /// the credit what is left of `gross` rounded down and never negative
+    pub fn split(&self, gross: Decimal, fee: Decimal) -> (Decimal, Decimal) {
+        let fee = self.fee(fee);
+        let credit = self.credit((gross - fee).max(Decimal::ZERO));
+        (fee, credit)
+    }
+
+    /// Sum of fees, rounded up
+    pub fn total(&self, fees: &[Decimal]) -> Decimal {
+        self.fee(fees.iter().sum())
+    }
+
+    /// An amount at exactly the currency's decimal places, for display
+    pub fn format(&self, value: Decimal) -> String {
+        let mut rounded = self.credit(value);
+        rounded.rescale(self.decimals);
+        rounded.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::amount;
+
+    fn dec(s: &str) -> Decimal {
+        amount::parse(s).unwrap()
+    }
+
+    #[test]
+    fn test_fee_rounds_up_and_credit_down() {
+        let btc = RoundingPolicy::for_currency("BTC");
+        assert_eq!(btc.fee(dec("0.000000001")), dec("0.00000001"));
+        assert_eq!(btc.credit(dec("0.123456789")), dec("0.12345678"));
+        assert_eq!(RoundingPolicy::for_currency("usdt").fee(dec("1.0000001")), dec("1.000001"));
+        assert_eq!(RoundingPolicy::for_currency("unknown"), RoundingPolicy::default());
+    }
+
+    #[test]
+    fn test_split_adds_up() {
+        let btc = RoundingPolicy::for_currency("btc");
+        let gross = dec("0.29");
+        let (fee, credit) = btc.split(gross, gross * dec("0.0123456"));
+        assert_eq!(fee, dec("0.00358023"));
+        assert_eq!(fee + credit, gross);
+
+        let (fee, credit) = btc.split(dec("0.0001"), dec("0.0002"));
+        assert_eq!((fee, credit), (dec("0.0002"), Decimal::ZERO));
+    }
+
+    #[test]
+    fn test_totals_and_format_are_exact() {
+        let eth = RoundingPolicy::for_currency("eth");
+        assert_eq!(eth.split(dec("0.3"), dec("0.1")), (dec("0.1"), dec("0.2")));
+        assert_eq!(eth.total(&[dec("0.1"), dec("0.2")]), dec("0.3"));
+        assert_eq!(eth.format(dec("1.5")), "1.50000000");
+    }
+}
@@ -7,11 +7,45 @@ pub struct PricingContext {
     pub provider_spread_percentage: f64,
 }
 
+/// How a strategy arrived at its commission rate and gas floor
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeeDecomposition {
+    /// Volume tier the trade fell into
+    pub tier: &'static str,
+    pub tier_rate: f64,
+    /// Added on top of the tier rate when providers disagree on price
+    pub volatility_premium: f64,
+    /// tier_rate + volatility_premium
+    pub commission_rate: f64,
+    pub network_gas_cost_native: f64,
+    pub gas_safety_buffer: f64,
+    /// Minimum platform fee, in the destination asset
+    pub gas_floor_native: f64,
+}
+
 #[async_trait]
 pub trait PricingStrategy: Send + Sync {
     /// Calculate the optimal commission rate and fixed fee
     /// Returns (percentage_rate, absolute_premium_in_native)
     fn calculate_fees(&self, ctx: &PricingContext) -> (f64, f64);
+
+    /// Same result as `calculate_fees`, broken into its components
+    fn decompose_fees(&self, ctx: &PricingContext) -> FeeDecomposition {
+        let (rate, gas_floor) = self.calculate_fees(ctx);
+        FeeDecomposition {
+            tier: "flat",
+            tier_rate: rate,
+            volatility_premium: 0.0,
+            commission_rate: rate,
+            network_gas_cost_native: ctx.network_gas_cost_native,
+            gas_safety_buffer: if ctx.network_gas_cost_native > 0.0 {
+                gas_floor / ctx.network_gas_cost_native
+            } else {
+                1.0
+            },
+            gas_floor_native: gas_floor,
+        }
+    }
     
     /// Estimate slippage percentage for a trade
     fn estimate_slippage(&self, amount_usd: f64, provider_spread: f64) -> f64;
@@ -34,13 +68,13 @@ impl Default for AdaptivePricingStrategy {
 }
 
 impl AdaptivePricingStrategy {
-    fn get_tier_rate(&self, amount_usd: f64) -> f64 {
+    fn get_tier(&self, amount_usd: f64) -> (&'static str, f64) {
         if amount_usd < 200.0 {
-            0.012 // 1.2% for small trades
+            ("small", 0.012) // 1.2% for small trades
         } else if amount_usd < 2000.0 {
-            0.007 // 0.7% for medium trades
+            ("medium", 0.007) // 0.7% for medium trades
         } else {
-            0.004 // 0.4% for whales
+            ("whale", 0.004) // 0.4% for whales
         }
     }
 }
@@ -48,19 +82,34 @@ impl AdaptivePricingStrategy {
 #[async_trait]
 impl PricingStrategy for AdaptivePricingStrategy {
     fn calculate_fees(&self, ctx: &PricingContext) -> (f64, f64) {
+        let fees = self.decompose_fees(ctx);
+        (fees.commission_rate, fees.gas_floor_native)
+    }
+
+    fn decompose_fees(&self, ctx: &PricingContext) -> FeeDecomposition {
         // 1. Determine base rate from volume tiers
-        let mut rate = self.get_tier_rate(ctx.amount_usd);
+        let (tier, tier_rate) = self.get_tier(ctx.amount_usd);
 
         // 2. Add Volatility Premium if providers are erratic
-        if ctx.provider_spread_percentage > self.volatility_threshold {
-            rate += self.volatility_premium;
-        }
+        let volatility_premium = if ctx.provider_spread_percentage > self.volatility_threshold {
+            self.volatility_premium
+        } else {
+            0.0
+        };
 
         // 3. Ensure Gas Floor protection
         // We calculate the required "Minimum Premium" to cover gas with a safety buffer
         let gas_floor_native = ctx.network_gas_cost_native * self.gas_safety_buffer;
 
-        (rate, gas_floor_native)
+        FeeDecomposition {
+            tier,
+            tier_rate,
+            volatility_premium,
+            commission_rate: tier_rate + volatility_premium,
+            network_gas_cost_native: ctx.network_gas_cost_native,
+            gas_safety_buffer: self.gas_safety_buffer,
+            gas_floor_native,
+        }
     }
     
     fn estimate_slippage(&self, amount_usd: f64, provider_spread: f64) -> f64 {
@@ -85,3 +134,36 @@ impl PricingStrategy for AdaptivePricingStrategy {
         base + volume + volatility
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ctx(amount_usd: f64, spread: f64) -> PricingContext {
+        PricingContext {
+            amount_usd,
+            network_gas_cost_native: 0.002,
+            provider_spread_percentage: spread,
+        }
+    }
+
+    #[test]
+    fn test_decomposition_matches_calculate_fees() {
+        let strategy = AdaptivePricingStrategy::default();
+        for c in [ctx(50.0, 0.0), ctx(500.0, 0.03), ctx(50_000.0, 0.01)] {
+            let fees = strategy.decompose_fees(&c);
+            assert_eq!(strategy.calculate_fees(&c), (fees.commission_rate, fees.gas_floor_native));
+        }
+    }
+
+    #[test]
+    fn test_decomposition_components() {
+        let fees = AdaptivePricingStrategy::default().decompose_fees(&ctx(500.0, 0.03));
+
+        assert_eq!(fees.tier, "medium");
+        assert_eq!(fees.tier_rate, 0.007);
+        assert_eq!(fees.volatility_premium, 0.005);
+        assert!((fees.commission_rate - 0.012).abs() < 1e-12);
+        assert!((fees.gas_floor_native - 0.003).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,134 @@
+//! Response shaping for the `fields` query parameter. Handlers serialize as
+//! usual and then keep only the requested top-level fields of each record,
+//! so bandwidth-constrained clients can skip what they don't display.
+//!
+//! Requested names are checked against the record type's JSON schema, so a
+//! typo is a 400 instead of a silently empty object.
+
+use schemars::JsonSchema;
+use serde_json::Value;
+use std::collections::BTreeSet;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldSelection {
+    fields: BTreeSet<String>,
+}
+
+impl FieldSelection {
+    /// Parse a comma-separated `fields` value for records of type `T`.
+    /// `None` (keep everything) when the parameter is absent or blank.
+    pub fn parse<T: JsonSchema>(raw: Option<&str>) -> Result<Option<Self>, String> {
+        let fields: BTreeSet<String> = raw
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|f| !f.is_empty())
+            .map(str::to_string)
+            .collect();
+        if fields.is_empty() {
+            return Ok(None);
+        }
+
+        let known = field_names::<T>();
+        let unknown: Vec<&str> = fields.iter().filter(|f| !known.contains(*f)).map(String::as_str).collect();
+        if !unknown.is_empty() {
+            return Err(format!(
+                "Unknown fields: {}. Available: {}",
+                unknown.join(", "),
+                known.into_iter().collect::<Vec<_>>().join(", ")
+            ));
+        }
+
+        Ok(Some(Self { fields }))
+    }
+
+    pub fn contains(&self, field: &str) -> bool {
+        self.fields.contains(field)
+    }
+
+    /// Keep only the selected keys of an object, or of every object in an array
+    pub fn apply(&self, value: Value) -> Value {
+        match value {
+            Value::Object(mut object) => {
+                object.retain(|key, _| self.fields.contains(key));
+                Value::Object(object)
+            }
+            Value::Array(items) => Value::Array(items.into_iter().map(|item| self.apply(item)).collect()),
+            other => other,
+        }
+    }
+
+    /// Apply to the records under `key` of a wrapper object (e.g. the
+    /// `swaps` of a history page), leaving pagination and the like intact
+    pub fn apply_at(&self, mut value: Value, key: &str) -> Value {
+        if let Some(records) = value.get_mut(key) {
+            *records = self.apply(records.take());
+        }
+        value
+    }
+}
+
+/// Top-level property names of `T`'s JSON schema, including flattened ones
+fn field_names<T: JsonSchema>() -> BTreeSet<String> {
+    let schema = schemars::schema_for!(T).to_value();
+    let properties = schema
+        .get("properties")
+        .or_else(|| schema.pointer("/items/properties"))
+        .and_then(Value::as_object);
+
+    properties.map(|p| p.keys().cloned().collect()).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Serialize;
+    use serde_json::json;
+
+    #[derive(Serialize, JsonSchema)]
+    struct Coin {
+        ticker: String,
+        network: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        image: Option<String>,
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(FieldSelection::parse::<Coin>(None).unwrap(), None);
+        assert_eq!(FieldSelection::parse::<Coin>(Some(" , ")).unwrap(), None);
+
+        let selection = FieldSelection::parse::<Coin>(Some("ticker, image")).unwrap().unwrap();
+        assert!(selection.contains("ticker"));
+        assert!(selection.contains("image"));
+        assert!(!selection.contains("network"));
+
+        let err = FieldSelection::parse::<Coin>(Some("ticker,price")).unwrap_err();
+        assert!(err.contains("Unknown fields: price"));
+        assert!(err.contains("image, network, ticker"));
+    }
+
+    #[test]
+    fn test_apply_to_records() {
+        let selection = FieldSelection::parse::<Coin>(Some("ticker,image")).unwrap().unwrap();
+        let coins = vec![
+            Coin { ticker: "btc".into(), network: "Mainnet".into(), image: None },
+            Coin { ticker: "eth".into(), network: "ERC20".into(), image: Some("eth.png".into()) },
+        ];
+
+        let projected = selection.apply(serde_json::to_value(&coins).unwrap());
+        assert_eq!(projected, json!([{ "ticker": "btc" }, { "ticker": "eth", "image": "eth.png" }]));
+    }
+
+    #[test]
+    fn test_apply_at_keeps_wrapper() {
+        let selection = FieldSelection::parse::<Coin>(Some("ticker")).unwrap().unwrap();
+        let page = json!({
+            "swaps": [{ "ticker": "btc", "network": "Mainnet" }],
+            "pagination": { "has_more": false }
+        });
+
+        let projected = selection.apply_at(page, "swaps");
+        assert_eq!(projected, json!({ "swaps": [{ "ticker": "btc" }], "pagination": { "has_more": false } }));
+    }
+}
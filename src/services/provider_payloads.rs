@@ -0,0 +1,275 @@
+//! Raw provider responses, kept per swap for dispute resolution. Payloads
+//! are stored as JSON compressed with MySQL's `COMPRESS()`; anything larger
+//! than the configured limit is cut to a prefix inside a JSON envelope, so
+//! every stored payload still parses. Status payloads are only stored when
+//! they differ from the swap's previous one, and rows past the retention
+//! window are purged in the background.
+
+use chrono::Utc;
+use sha2::{Digest, Sha256};
+use sqlx::{MySql, Pool};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use crate::modules::swap::model::{PayloadKind, ProviderPayload};
+
+/// Largest payload stored whole
+pub const DEFAULT_MAX_BYTES: usize = 64 * 1024;
+/// Limit on PROVIDER_PAYLOAD_MAX_BYTES, well inside a MEDIUMBLOB
+pub const MAX_BYTES_LIMIT: usize = 1024 * 1024;
+pub const DEFAULT_RETENTION_DAYS: u32 = 180;
+
+const BATCH_SIZE: i64 = 500;
+/// Batches per pass, so a large backlog cannot starve the pool
+const MAX_BATCHES: usize = 50;
+
+const PAYLOAD_COLUMNS: &str = r#"
+    id, swap_id, provider, CAST(kind AS CHAR) as kind, CAST(UNCOMPRESS(payload) AS CHAR) as payload, payload_hash,
+    original_bytes, truncated, created_at
+"#;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PayloadPolicy {
+    /// Payloads above this many bytes of JSON are truncated
+    pub max_bytes: usize,
+    pub retention: Duration,
+    pub interval: Duration,
+}
+
+impl Default for PayloadPolicy {
+    fn default() -> Self {
+        Self {
+            max_bytes: DEFAULT_MAX_BYTES,
+            retention: Duration::from_secs(DEFAULT_RETENTION_DAYS as u64 * 86400),
+            interval: Duration::from_secs(3600),
+        }
+    }
+}
+
+impl PayloadPolicy {
+    pub fn from_env() -> Result<Self, String> {
+        let mut policy = Self::default();
+
+        if let Ok(val) = std::env::var("PROVIDER_PAYLOAD_MAX_BYTES") {
+            let bytes: usize = val.trim().parse().map_err(|e| format!("Invalid PROVIDER_PAYLOAD_MAX_BYTES: {}", e))?;
+            if bytes == 0 || bytes > MAX_BYTES_LIMIT {
+                return Err(format!("PROVIDER_PAYLOAD_MAX_BYTES must be between 1 and {}", MAX_BYTES_LIMIT));
+            }
+            policy.max_bytes = bytes;
+        }
+        if let Ok(val) = std::env::var("PROVIDER_PAYLOAD_RETENTION_DAYS") {
+            let days: u64 =
+                val.trim().parse().map_err(|e| format!("Invalid PROVIDER_PAYLOAD_RETENTION_DAYS: {}", e))?;
+            if days == 0 {
+                return Err("PROVIDER_PAYLOAD_RETENTION_DAYS must be at least 1".to_string());
+            }
+            policy.retention = Duration::from_secs(days * 86400);
+        }
+
+        Ok(policy)
+    }
+
+    pub fn global() -> &'static PayloadPolicy {
+        static POLICY: OnceLock<PayloadPolicy> = OnceLock::new();
+        POLICY.get_or_init(|| {
+            Self::from_env().unwrap_or_else(|e| {
+                tracing::warn!("Ignoring invalid provider payload config: {}", e);
+                Self::default()
+            })
+        })
+    }
+}
+
+/// A payload ready to store
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PreparedPayload {
+    /// JSON to store; an envelope holding a prefix when truncated
+    pub json: String,
+    /// SHA-256 of the JSON as received
+    pub hash: String,
+    pub original_bytes: usize,
+    pub truncated: bool,
+}
+
+/// Serialize `payload`, cutting it to `max_bytes` when it is larger. A
+/// truncated payload is stored as
+/// `{"truncated": true, "original_bytes": N, "prefix": "<first bytes>"}`.
+pub fn prepare(payload: &serde_json::Value, max_bytes: usize) -> PreparedPayload {
+    let json = payload.to_string();
+    let hash = hex::encode(Sha256::digest(json.as_bytes()));
+    let original_bytes = json.len();
+    if original_bytes <= max_bytes {
+        return PreparedPayload { json, hash, original_bytes, truncated: false };
+    }
+
+    let mut end = max_bytes;
+    while !json.is_char_boundary(end) {
+        end -= 1;
+    }
+    let envelope = serde_json::json!({
+        "truncated": true,
+        "original_bytes": original_bytes,
+        "prefix": &json[..end],
+    });
+    PreparedPayload { json: envelope.to_string(), hash, original_bytes, truncated: true }
+}
+
+pub struct ProviderPayloadStore {
+    pool: Pool<MySql>,
+}
+
+impl ProviderPayloadStore {
+    pub fn new(pool: Pool<MySql>) -> Self {
+        Self { pool }
+    }
+
+    /// Store a payload for the swap. Returns false when it was a status
+    /// payload identical to the swap's previous one.
+    pub async fn record(
+        &self,
+        swap_id: &str,
+        provider: &str,
+        kind: PayloadKind,
+        payload: &serde_json::Value,
+    ) -> Result<bool, sqlx::Error> {
+        let prepared = prepare(payload, PayloadPolicy::global().max_bytes);
+
+        if kind == PayloadKind::Status {
+            let previous: Option<(String,)> = sqlx::query_as(
+                "SELECT payload_hash FROM provider_payloads WHERE swap_id = ? AND kind = ? ORDER BY id DESC LIMIT 1",
+            )
+            .bind(swap_id)
+            .bind(kind.as_str())
+            .fetch_optional(&self.pool)
+            .await?;
+            if previous.is_some_and(|(hash,)| hash == prepared.hash) {
+                return Ok(false);
+            }
+        }
+
+        sqlx::query(
+            r#"
+            INSERT INTO provider_payloads (swap_id, provider, kind, payload, payload_hash, original_bytes, truncated)
+            VALUES (?, ?, ?, COMPRESS(?), ?, ?, ?)
+            "#,
+        )
+        .bind(swap_id)
+        .bind(provider)
+        .bind(kind.as_str())
+        .bind(&prepared.json)
+        .bind(&prepared.hash)
+        .bind(prepared.original_bytes as u32)
+        .bind(prepared.truncated)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(true)
+    }
+
+    /// Store the payload if there is one, logging rather than failing; a
+    /// missed capture must never fail the swap call that produced it
+    pub async fn capture(&self, swap_id: &str, provider: &str, kind: PayloadKind, payload: Option<&serde_json::Value>) {
+        let Some(payload) = payload else {
+            return;
+        };
+        if let Err(e) = self.record(swap_id, provider, kind, payload).await {
+            tracing::warn!("Failed to store {} {} payload for swap {}: {}", provider, kind.as_str(), swap_id, e);
+        }
+    }
+
+    /// Payloads stored for the swap, oldest first
+    pub async fn list(&self, swap_id: &str) -> Result<Vec<ProviderPayload>, sqlx::Error> {
+        sqlx::query_as::<_, ProviderPayload>(&format!(
+            "SELECT {} FROM provider_payloads WHERE swap_id = ? ORDER BY id ASC LIMIT 200",
+            PAYLOAD_COLUMNS
+        ))
+        .bind(swap_id)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Delete payloads older than `retention`
+    pub async fn purge_expired(&self, retention: Duration) -> Result<u64, sqlx::Error> {
+        let cutoff = Utc::now() - chrono::Duration::from_std(retention).unwrap_or_else(|_| chrono::Duration::days(36500));
+
+        let mut total = 0;
+        for _ in 0..MAX_BATCHES {
+            let affected = sqlx::query("DELETE FROM provider_payloads WHERE created_at < ? LIMIT ?")
+                .bind(cutoff)
+                .bind(BATCH_SIZE)
+                .execute(&self.pool)
+                .await?
+                .rows_affected();
+            total += affected;
+            if affected < BATCH_SIZE as u64 {
+                break;
+            }
+        }
+        Ok(total)
+    }
+}
+
+/// Purges provider payloads past PROVIDER_PAYLOAD_RETENTION_DAYS
+pub struct ProviderPayloadSweeper {
+    store: ProviderPayloadStore,
+    policy: PayloadPolicy,
+}
+
+impl ProviderPayloadSweeper {
+    pub fn new(db: Pool<MySql>, policy: PayloadPolicy) -> Self {
+        Self { store: ProviderPayloadStore::new(db), policy }
+    }
+
+    /// Start the background sweep loop
+    pub async fn run(&self) {
+        let mut interval = tokio::time::interval(self.policy.interval);
+
+        loop {
+            interval.tick().await;
+
+            match self.store.purge_expired(self.policy.retention).await {
+                Ok(0) => {}
+                Ok(purged) => tracing::info!("Purged {} expired provider payloads", purged),
+                Err(e) => tracing::error!("Provider payload sweep failed: {}", e),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_small_payload_is_kept_whole() {
+        let payload = serde_json::json!({ "trade_id": "abc", "status": "waiting" });
+        let prepared = prepare(&payload, 1024);
+
+        assert!(!prepared.truncated);
+        assert_eq!(prepared.original_bytes, prepared.json.len());
+        assert_eq!(serde_json::from_str::<serde_json::Value>(&prepared.json).unwrap(), payload);
+        assert_eq!(prepared.hash.len(), 64);
+    }
+
+    #[test]
+    fn test_large_payload_is_truncated_to_valid_json() {
+        let payload = serde_json::json!({ "memo": "é".repeat(100) });
+        let prepared = prepare(&payload, 31);
+
+        assert!(prepared.truncated);
+        assert_eq!(prepared.original_bytes, payload.to_string().len());
+        let envelope: serde_json::Value = serde_json::from_str(&prepared.json).unwrap();
+        assert_eq!(envelope["truncated"], true);
+        assert_eq!(envelope["original_bytes"], prepared.original_bytes);
+        let prefix = envelope["prefix"].as_str().unwrap();
+        assert!(prefix.len() <= 31);
+        assert!(payload.to_string().starts_with(prefix));
+    }
+
+    #[test]
+    fn test_hash_is_of_the_payload_as_received() {
+        let payload = serde_json::json!({ "status": "finished" });
+        assert_eq!(prepare(&payload, 4).hash, prepare(&payload, 1024).hash);
+        assert_ne!(prepare(&payload, 1024).hash, prepare(&serde_json::json!({ "status": "failed" }), 1024).hash);
+    }
+}
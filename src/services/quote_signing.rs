@@ -0,0 +1,335 @@
+//! Signed swap quotes. Each rate returned by /swap/rates carries an Ed25519
+//! signature over its pair, amount, rate, fees, the user it was quoted to,
+//! any campaign it was discounted under and its expiry, so white-label
+//! frontends can prove a quote came from us (the public key is served at
+//! /swap/quote-key) and /swap/create can reject quotes whose fee fields
+//! were edited client-side or that are redeemed by someone else.
+
+use std::sync::OnceLock;
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::Utc;
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::modules::swap::schema::{AppliedPromotion, RateResponse, RateType};
+use crate::services::amount::Decimal;
+
+static QUOTE_SIGNER: OnceLock<Option<QuoteSigner>> = OnceLock::new();
+
+/// Process-wide quote signer, loaded once from the environment
+pub fn quote_signer() -> Option<&'static QuoteSigner> {
+    QUOTE_SIGNER
+        .get_or_init(|| match QuoteSigner::from_env() {
+            Ok(signer) => Some(signer),
+            Err(e) => {
+                tracing::warn!("Quote signing disabled: {}", e);
+                None
+            }
+        })
+        .as_ref()
+}
+
+/// Whether /swap/create refuses swaps without a signed quote
+/// (QUOTE_REQUIRE_SIGNED, default false)
+pub fn signed_quotes_required() -> bool {
+    std::env::var("QUOTE_REQUIRE_SIGNED")
+        .map(|v| matches!(v.trim().to_ascii_lowercase().as_str(), "1" | "true" | "yes"))
+        .unwrap_or(false)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QuoteError {
+    Malformed(String),
+    BadSignature,
+    Expired,
+    /// The quote is genuine but for a different swap than the one requested
+    Mismatch(&'static str),
+    /// The campaign the quote was discounted under has reached its caps
+    PromotionUnavailable,
+    Disabled,
+}
+
+impl std::fmt::Display for QuoteError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            QuoteError::Malformed(e) => write!(f, "Malformed quote: {}", e),
+            QuoteError::BadSignature => write!(f, "Quote signature does not verify"),
+            QuoteError::Expired => write!(f, "Quote has expired"),
+            QuoteError::Mismatch(field) => write!(f, "Quote does not match the request ({})", field),
+            QuoteError::PromotionUnavailable => {
+                write!(f, "The promotion this quote was discounted under is no longer available")
+            }
+            QuoteError::Disabled => write!(f, "Quote signing is not enabled"),
+        }
+    }
+}
+
+/// Everything a quote commits to. Serialized as JSON in field order, with
+/// amounts as normalized decimal strings; the signature covers those exact
+/// bytes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct QuotePayload {
+    pub trade_id: String,
+    pub provider: String,
+    pub from: String,
+    pub network_from: String,
+    pub to: String,
+    pub network_to: String,
+    #[serde(with = "rust_decimal::serde::str")]
+    #[schemars(with = "String")]
+    pub amount: Decimal,
+    #[serde(with = "rust_decimal::serde::str")]
+    #[schemars(with = "String")]
+    pub rate: Decimal,
+    #[serde(with = "rust_decimal::serde::str")]
+    #[schemars(with = "String")]
+    pub estimated_amount: Decimal,
+    #[serde(with = "rust_decimal::serde::str")]
+    #[schemars(with = "String")]
+    pub network_fee: Decimal,
+    #[serde(with = "rust_decimal::serde::str")]
+    #[schemars(with = "String")]
+    pub provider_fee: Decimal,
+    #[serde(with = "rust_decimal::serde::str")]
+    #[schemars(with = "String")]
+    pub platform_fee: Decimal,
+    #[serde(with = "rust_decimal::serde::str")]
+    #[schemars(with = "String")]
+    pub total_fee: Decimal,
+    pub rate_type: RateType,
+    /// User the quote was served to; `None` for anonymous quotes, which
+    /// only anonymous swaps can redeem
+    pub user_id: Option<String>,
+    /// Campaign the fees were discounted under. Its id is what
+    /// /swap/create claims a use of.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub promotion: Option<AppliedPromotion>,
+    /// Unix seconds
+    pub expires_at: i64,
+}
+
+/// A quote as handed to clients and sent back to /swap/create
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct SignedQuote {
+    /// Base64url (no padding) of the payload JSON
+    pub payload: String,
+    /// Base64url (no padding) Ed25519 signature over the decoded payload
+    pub signature: String,
+    pub key_id: String,
+}
+
+pub struct QuoteSigner {
+    key: SigningKey,
+    key_id: String,
+    ttl_secs: i64,
+}
+
+impl QuoteSigner {
+    pub fn new(seed: [u8; 32], ttl_secs: i64) -> Self {
+        let key = SigningKey::from_bytes(&seed);
+        let key_id = key_id(&key.verifying_key());
+        Self { key, key_id, ttl_secs }
+    }
+
+    /// QUOTE_SIGNING_KEY: hex Ed25519 seed (32 bytes).
+    /// QUOTE_TTL_SECS: how long a quote can be redeemed (default 120).
+    pub fn from_env() -> Result<Self, String> {
+        let hex_key = std::env::var("QUOTE_SIGNING_KEY").map_err(|_| "QUOTE_SIGNING_KEY not set".to_string())?;
+        let seed: [u8; 32] = hex::decode(hex_key.trim())
+            .map_err(|e| format!("Invalid QUOTE_SIGNING_KEY: {}", e))?
+            .try_into()
+            .map_err(|_| "QUOTE_SIGNING_KEY must be 32 bytes".to_string())?;
+
+        let ttl_secs = match std::env::var("QUOTE_TTL_SECS") {
+            Ok(v) => v
+                .trim()
+                .parse::<i64>()
+                .ok()
+                .filter(|secs| *secs > 0)
+                .ok_or_else(|| format!("Invalid QUOTE_TTL_SECS: {}", v))?,
+            Err(_) => 120,
+        };
+
+        Ok(Self::new(seed, ttl_secs))
+    }
+
+    pub fn key_id(&self) -> &str {
+        &self.key_id
+    }
+
+    pub fn public_key_hex(&self) -> String {
+        hex::encode(self.key.verifying_key().as_bytes())
+    }
+
+    pub fn ttl_secs(&self) -> i64 {
+        self.ttl_secs
+    }
+
+    pub fn sign(&self, payload: &QuotePayload) -> Result<SignedQuote, String> {
+        let bytes = serde_json::to_vec(payload).map_err(|e| e.to_string())?;
+        let signature = self.key.sign(&bytes);
+        Ok(SignedQuote {
+            payload: URL_SAFE_NO_PAD.encode(&bytes),
+            signature: URL_SAFE_NO_PAD.encode(signature.to_bytes()),
+            key_id: self.key_id.clone(),
+        })
+    }
+
+    /// Signed quote for one provider's rate, served to `user_id`, valid for
+    /// the signer's TTL
+    pub fn sign_rate(
+        &self,
+        trade_id: &str,
+        pair: (&str, &str, &str, &str),
+        amount: Decimal,
+        rate: &RateResponse,
+        user_id: Option<&str>,
+    ) -> Result<SignedQuote, String> {
+        let (from, network_from, to, network_to) = pair;
+        self.sign(&QuotePayload {
+            trade_id: trade_id.to_string(),
+            provider: rate.provider.clone(),
+            from: from.to_string(),
+            network_from: network_from.to_string(),
+            to: to.to_string(),
+            network_to: network_to.to_string(),
+            amount: amount.normalize(),
+            rate: rate.rate.normalize(),
+            estimated_amount: rate.estimated_amount.normalize(),
+            network_fee: rate.network_fee.normalize(),
+            provider_fee: rate.provider_fee.normalize(),
+            platform_fee: rate.platform_fee.normalize(),
+            total_fee: rate.total_fee.normalize(),
+            rate_type: rate.rate_type.clone(),
+            user_id: user_id.map(str::to_string),
+            promotion: rate.promotion.clone(),
+            expires_at: Utc::now().timestamp() + self.ttl_secs,
+        })
+    }
+
+    /// The payload of a quote this server signed and that has not expired
+    pub fn verify(&self, quote: &SignedQuote) -> Result<QuotePayload, QuoteError> {
+        let malformed = |e: &dyn std::fmt::Display| QuoteError::Malformed(e.to_string());
+
+        let bytes = URL_SAFE_NO_PAD.decode(&quote.payload).map_err(|e| malformed(&e))?;
+        let signature = URL_SAFE_NO_PAD.decode(&quote.signature).map_err(|e| malformed(&e))?;
+        let signature = Signature::from_slice(&signature).map_err(|e| malformed(&e))?;
+        self.key
+            .verifying_key()
+            .verify(&bytes, &signature)
+            .map_err(|_| QuoteError::BadSignature)?;
+
+        let payload: QuotePayload = serde_json::from_slice(&bytes).map_err(|e| malformed(&e))?;
+        if payload.expires_at < Utc::now().timestamp() {
+            return Err(QuoteError::Expired);
+        }
+        Ok(payload)
+    }
+}
+
+/// First 8 bytes of SHA-256 of the public key, hex
+fn key_id(key: &VerifyingKey) -> String {
+    hex::encode(&Sha256::digest(key.as_bytes())[..8])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn payload() -> QuotePayload {
+        QuotePayload {
+            trade_id: "trade-1".to_string(),
+            provider: "changenow".to_string(),
+            from: "btc".to_string(),
+            network_from: "Mainnet".to_string(),
+            to: "eth".to_string(),
+            network_to: "ERC20".to_string(),
+            amount: Decimal::new(1, 1),
+            rate: Decimal::new(152, 1),
+            estimated_amount: Decimal::new(152, 2),
+            network_fee: Decimal::ZERO,
+            provider_fee: Decimal::new(1, 2),
+            platform_fee: Decimal::new(184, 4),
+            total_fee: Decimal::new(284, 4),
+            rate_type: RateType::Floating,
+            user_id: Some("user-1".to_string()),
+            promotion: None,
+            expires_at: Utc::now().timestamp() + 60,
+        }
+    }
+
+    #[test]
+    fn test_signed_quote_verifies() {
+        let signer = QuoteSigner::new([7u8; 32], 120);
+        let quote = signer.sign(&payload()).unwrap();
+        assert_eq!(quote.key_id, signer.key_id());
+        assert_eq!(signer.verify(&quote).unwrap(), payload());
+    }
+
+    #[test]
+    fn test_amounts_are_signed_as_decimal_strings() {
+        let signer = QuoteSigner::new([7u8; 32], 120);
+        let mut exact = payload();
+        exact.amount = "1.000000000000000001".parse().unwrap();
+        let quote = signer.sign(&exact).unwrap();
+
+        let json: serde_json::Value = serde_json::from_slice(&URL_SAFE_NO_PAD.decode(&quote.payload).unwrap()).unwrap();
+        assert_eq!(json["amount"], "1.000000000000000001");
+        assert_eq!(json["platform_fee"], "0.0184");
+        assert_eq!(signer.verify(&quote).unwrap().amount, exact.amount);
+    }
+
+    #[test]
+    fn test_edited_fee_is_rejected() {
+        let signer = QuoteSigner::new([7u8; 32], 120);
+        let quote = signer.sign(&payload()).unwrap();
+
+        let mut edited = payload();
+        edited.platform_fee = Decimal::ZERO;
+        let forged = SignedQuote {
+            payload: URL_SAFE_NO_PAD.encode(serde_json::to_vec(&edited).unwrap()),
+            ..quote
+        };
+        assert_eq!(signer.verify(&forged), Err(QuoteError::BadSignature));
+    }
+
+    #[test]
+    fn test_quote_is_bound_to_its_user_and_promotion() {
+        let signer = QuoteSigner::new([7u8; 32], 120);
+        let quote = signer.sign(&payload()).unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&URL_SAFE_NO_PAD.decode(&quote.payload).unwrap()).unwrap();
+        assert_eq!(json["user_id"], "user-1");
+
+        // Handing the quote to someone else, or attaching a campaign, breaks it
+        let mut stolen = payload();
+        stolen.user_id = Some("user-2".to_string());
+        let mut discounted = payload();
+        discounted.promotion = Some(AppliedPromotion {
+            id: "promo-1".to_string(),
+            name: "Launch".to_string(),
+            fee_discount: 1.0,
+            standard_platform_fee: Decimal::new(184, 4),
+        });
+        for edited in [stolen, discounted] {
+            let forged = SignedQuote {
+                payload: URL_SAFE_NO_PAD.encode(serde_json::to_vec(&edited).unwrap()),
+                ..quote.clone()
+            };
+            assert_eq!(signer.verify(&forged), Err(QuoteError::BadSignature));
+        }
+    }
+
+    #[test]
+    fn test_other_key_and_expiry_rejected() {
+        let signer = QuoteSigner::new([7u8; 32], 120);
+        let other = QuoteSigner::new([8u8; 32], 120);
+        assert_eq!(signer.verify(&other.sign(&payload()).unwrap()), Err(QuoteError::BadSignature));
+
+        let mut stale = payload();
+        stale.expires_at = Utc::now().timestamp() - 1;
+        assert_eq!(signer.verify(&signer.sign(&stale).unwrap()), Err(QuoteError::Expired));
+    }
+}
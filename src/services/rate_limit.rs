@@ -1,34 +1,210 @@
 use axum::{
     body::Body,
-    http::{Request, StatusCode},
+    extract::ConnectInfo,
+    http::{header, HeaderValue, Request, StatusCode},
     response::{IntoResponse, Response},
 };
 use governor::{
-    clock::DefaultClock,
-    state::{InMemoryState, NotKeyed},
+    clock::{Clock, DefaultClock},
+    state::keyed::DefaultKeyedStateStore,
     Quota, RateLimiter,
 };
-use std::{num::NonZeroU32, sync::Arc, future::Future, pin::Pin};
+use std::{net::SocketAddr, num::NonZeroU32, sync::{Arc, RwLock}, future::Future, pin::Pin, time::Duration};
+use tokio::sync::watch;
 use tower::{Layer, Service};
 
-pub type GlobalRateLimiter = Arc<RateLimiter<NotKeyed, InMemoryState, DefaultClock>>;
+use crate::services::client_ip::ClientIp;
+use crate::services::redis_cache::RedisService;
+use crate::services::runtime_config::RuntimeConfig;
 
-pub fn create_rate_limiter(burst: u32) -> GlobalRateLimiter {
+pub type KeyedRateLimiter = Arc<RateLimiter<String, DefaultKeyedStateStore<String>, DefaultClock>>;
+
+/// Keys tracked by the in-memory limiter before idle ones are pruned
+const MAX_IN_MEMORY_KEYS: usize = 10_000;
+/// Redis must answer within this budget or the request falls back to memory
+const REDIS_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// Bucket shape shared by the Redis and in-memory backends
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitQuota {
+    pub burst: u32,
+    /// Time to earn back one token
+    pub replenish_every: Duration,
+}
+
+/// Default quota of the public HTTP API, per client address; the live
+/// value comes from the `rate_limit.*` runtime settings
+pub const HTTP_QUOTA: RateLimitQuota = RateLimitQuota {
+    burst: 10,
+    replenish_every: Duration::from_secs(60),
+};
+
+impl RateLimitQuota {
+    /// `burst` requests, then 1 per minute after
+    pub fn per_minute_with_burst(burst: u32) -> Self {
+        Self {
+            burst: burst.max(1),
+            replenish_every: Duration::from_secs(60),
+        }
+    }
+}
+
+pub fn create_rate_limiter(burst: u32) -> KeyedRateLimiter {
     // 1 token per minute refill, with burst capacity
     // Effectively limits to `burst` requests, then 1 per minute after
-    let quota = Quota::per_minute(NonZeroU32::new(1).unwrap())
-        .allow_burst(NonZeroU32::new(burst).unwrap());
-    Arc::new(RateLimiter::direct(quota))
+    create_quota_limiter(RateLimitQuota::per_minute_with_burst(burst))
+}
+
+/// In-memory limiter for any bucket shape
+pub fn create_quota_limiter(quota: RateLimitQuota) -> KeyedRateLimiter {
+    let period = quota.replenish_every.max(Duration::from_millis(1));
+    let quota = Quota::with_period(period)
+        .expect("period is non-zero")
+        .allow_burst(NonZeroU32::new(quota.burst.max(1)).unwrap());
+    Arc::new(RateLimiter::keyed(quota))
+}
+
+// =============================================================================
+// REDIS TOKEN BUCKET
+// =============================================================================
+
+/// Refill, take and persist a bucket in one round trip. Uses the Redis
+/// clock so every replica sees the same time. Buckets expire once they
+/// would be full again, since a missing key already means "full".
+const TOKEN_BUCKET_SCRIPT: &str = r#"
+local capacity = tonumber(ARGV[1])
+local interval_ms = tonumber(ARGV[2])
+local requested = tonumber(ARGV[3])
+
+local time = redis.call('TIME')
+local now = tonumber(time[1]) * 1000 + math.floor(tonumber(time[2]) / 1000)
+
+local state = redis.call('HMGET', KEYS[1], 'tokens', 'ts')
+local tokens = tonumber(state[1])
+local ts = tonumber(state[2])
+if tokens == nil or ts == nil then
+    tokens = capacity
+    ts = now
+end
+
+local refill = math.floor(math.max(0, now - ts) / interval_ms)
+if refill > 0 then
+    tokens = math.min(capacity, tokens + refill)
+    if tokens == capacity then
+        ts = now
+    else
+        ts = ts + refill * interval_ms
+    end
+end
+
+local allowed = 0
+local retry_after_ms = 0
+if tokens >= requested then
+    tokens = tokens - requested
+    allowed = 1
+else
+    retry_after_ms = interval_ms - (now - ts)
+end
+
+redis.call('HSET', KEYS[1], 'tokens', tokens, 'ts', ts)
+redis.call('PEXPIRE', KEYS[1], math.max(interval_ms, (capacity - tokens) * interval_ms))
+
+return {allowed, tokens, retry_after_ms}
+"#;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RateLimitDecision {
+    Allowed { remaining: u32 },
+    Limited { retry_after: Duration },
+}
+
+/// Token bucket stored in Redis so every replica shares the same limits
+#[derive(Clone)]
+pub struct RedisTokenBucket {
+    redis: RedisService,
+    quota: RateLimitQuota,
+    key_prefix: String,
+    script: Arc<redis::Script>,
+}
+
+impl RedisTokenBucket {
+    pub fn new(redis: RedisService, quota: RateLimitQuota) -> Self {
+        Self {
+            redis,
+            quota,
+            key_prefix: "rate_limit:http".to_string(),
+            script: Arc::new(redis::Script::new(TOKEN_BUCKET_SCRIPT)),
+        }
+    }
+
+    pub fn with_key_prefix(mut self, prefix: &str) -> Self {
+        self.key_prefix = prefix.to_string();
+        self
+    }
+
+    /// Take one token for `key`
+    pub async fn check(&self, key: &str) -> Result<RateLimitDecision, String> {
+        self.check_quota(key, self.quota).await
+    }
+
+    /// Take one token for `key` from a bucket of shape `quota`, for limits
+    /// that changed since the bucket was built
+    pub async fn check_quota(&self, key: &str, quota: RateLimitQuota) -> Result<RateLimitDecision, String> {
+        let mut conn = self.redis.get_client()
+            .get_multiplexed_async_connection()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let (allowed, remaining, retry_after_ms): (i64, i64, i64) = self.script
+            .key(format!("{}:{}", self.key_prefix, key))
+            .arg(quota.burst)
+            .arg(quota.replenish_every.as_millis() as u64)
+            .arg(1)
+            .invoke_async(&mut conn)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(if allowed == 1 {
+            RateLimitDecision::Allowed { remaining: remaining.max(0) as u32 }
+        } else {
+            RateLimitDecision::Limited { retry_after: Duration::from_millis(retry_after_ms.max(0) as u64) }
+        })
+    }
 }
 
+// =============================================================================
+// LAYER
+// =============================================================================
+
+/// In-memory limiter and the quota it was built for. Shared by every clone
+/// of the service so a quota change rebuilds it once.
+type SharedLimiter = Arc<RwLock<(Option<RateLimitQuota>, KeyedRateLimiter)>>;
+
+/// Limits requests per client. Uses the Redis bucket when configured and
+/// reachable, otherwise the in-memory limiter of this instance.
 #[derive(Clone)]
 pub struct RateLimitLayer {
-    limiter: GlobalRateLimiter,
+    limiter: SharedLimiter,
+    redis: Option<RedisTokenBucket>,
+    config: Option<watch::Receiver<Arc<RuntimeConfig>>>,
 }
 
 impl RateLimitLayer {
-    pub fn new(limiter: GlobalRateLimiter) -> Self {
-        Self { limiter }
+    pub fn new(limiter: KeyedRateLimiter) -> Self {
+        Self { limiter: Arc::new(RwLock::new((None, limiter))), redis: None, config: None }
+    }
+
+    pub fn with_redis(mut self, bucket: RedisTokenBucket) -> Self {
+        self.redis = Some(bucket);
+        self
+    }
+
+    /// Follow the `rate_limit.*` runtime settings. The in-memory limiter is
+    /// rebuilt when they change, which starts every client on a full bucket;
+    /// Redis buckets keep their tokens and take the new shape.
+    pub fn with_runtime_config(mut self, config: watch::Receiver<Arc<RuntimeConfig>>) -> Self {
+        self.config = Some(config);
+        self
     }
 }
 
@@ -39,6 +215,8 @@ impl<S> Layer<S> for RateLimitLayer {
         RateLimitService {
             inner,
             limiter: self.limiter.clone(),
+            redis: self.redis.clone(),
+            config: self.config.clone(),
         }
     }
 }
@@ -46,7 +224,71 @@ impl<S> Layer<S> for RateLimitLayer {
 #[derive(Clone)]
 pub struct RateLimitService<S> {
     inner: S,
-    limiter: GlobalRateLimiter,
+    limiter: SharedLimiter,
+    redis: Option<RedisTokenBucket>,
+    config: Option<watch::Receiver<Arc<RuntimeConfig>>>,
+}
+
+/// The limiter for the configured quota, rebuilding it if the quota
+/// changed. `None` quota means the limits are fixed.
+fn current_limits(
+    limiter: &SharedLimiter,
+    config: Option<&watch::Receiver<Arc<RuntimeConfig>>>,
+) -> (KeyedRateLimiter, Option<RateLimitQuota>) {
+    let Some(config) = config else {
+        return (limiter.read().unwrap().1.clone(), None);
+    };
+    let quota = config.borrow().rate_limit_quota();
+    {
+        let current = limiter.read().unwrap();
+        if current.0 == Some(quota) {
+            return (current.1.clone(), Some(quota));
+        }
+    }
+
+    let mut current = limiter.write().unwrap();
+    if current.0 != Some(quota) {
+        if current.0.is_some() {
+            tracing::info!("HTTP rate limit changed to {} requests, 1 per {:?}", quota.burst, quota.replenish_every);
+        }
+        *current = (Some(quota), create_quota_limiter(quota));
+    }
+    (current.1.clone(), Some(quota))
+}
+
+/// Client address resolved by `ClientIpLayer`, else the TCP peer when the
+/// server exposes connect info, otherwise a single shared bucket
+fn client_key(request: &Request<Body>) -> String {
+    if let Some(client) = request.extensions().get::<ClientIp>() {
+        return client.rate_limit_key();
+    }
+    request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| ClientIp(addr.ip()).rate_limit_key())
+        .unwrap_or_else(|| "global".to_string())
+}
+
+fn check_in_memory(limiter: &KeyedRateLimiter, key: &String) -> RateLimitDecision {
+    if limiter.len() > MAX_IN_MEMORY_KEYS {
+        limiter.retain_recent();
+    }
+
+    match limiter.check_key(key) {
+        Ok(()) => RateLimitDecision::Allowed { remaining: 0 },
+        Err(not_until) => RateLimitDecision::Limited {
+            retry_after: not_until.wait_time_from(DefaultClock::default().now()),
+        },
+    }
+}
+
+fn too_many_requests(retry_after: Duration) -> Response {
+    let mut response = StatusCode::TOO_MANY_REQUESTS.into_response();
+    let secs = retry_after.as_secs_f64().ceil().max(1.0) as u64;
+    if let Ok(value) = HeaderValue::from_str(&secs.to_string()) {
+        response.headers_mut().insert(header::RETRY_AFTER, value);
+    }
+    response
 }
 
 impl<S> Service<Request<Body>> for RateLimitService<S>
@@ -63,14 +305,83 @@ where
     }
 
     fn call(&mut self, request: Request<Body>) -> Self::Future {
-        let limiter = self.limiter.clone();
+        let (limiter, quota) = current_limits(&self.limiter, self.config.as_ref());
+        let redis = self.redis.clone();
         let mut inner = self.inner.clone();
 
         Box::pin(async move {
-            if limiter.check().is_err() {
-                return Ok(StatusCode::TOO_MANY_REQUESTS.into_response());
+            let key = client_key(&request);
+
+            let decision = match &redis {
+                Some(bucket) => match tokio::time::timeout(
+                    REDIS_TIMEOUT,
+                    bucket.check_quota(&key, quota.unwrap_or(bucket.quota)),
+                )
+                .await
+                {
+                    Ok(Ok(decision)) => decision,
+                    Ok(Err(e)) => {
+                        tracing::warn!("Redis rate limiter unavailable, using in-memory limits: {}", e);
+                        check_in_memory(&limiter, &key)
+                    }
+                    Err(_) => {
+                        tracing::warn!("Redis rate limiter timed out, using in-memory limits");
+                        check_in_memory(&limiter, &key)
+                    }
+                },
+                None => check_in_memory(&limiter, &key),
+            };
+
+            if let RateLimitDecision::Limited { retry_after } = decision {
+                return Ok(too_many_requests(retry_after));
             }
             inner.call(request).await
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_in_memory_limits_per_key() {
+        let limiter = create_rate_limiter(2);
+        let a = "10.0.0.1".to_string();
+        let b = "10.0.0.2".to_string();
+
+        assert!(matches!(check_in_memory(&limiter, &a), RateLimitDecision::Allowed { .. }));
+        assert!(matches!(check_in_memory(&limiter, &a), RateLimitDecision::Allowed { .. }));
+        assert!(matches!(check_in_memory(&limiter, &a), RateLimitDecision::Limited { .. }));
+
+        // Other clients have their own bucket
+        assert!(matches!(check_in_memory(&limiter, &b), RateLimitDecision::Allowed { .. }));
+    }
+
+    #[test]
+    fn test_limiter_rebuilt_when_quota_changes() {
+        let (tx, rx) = watch::channel(Arc::new(RuntimeConfig::default()));
+        let limiter: SharedLimiter = Arc::new(RwLock::new((None, create_rate_limiter(1))));
+
+        let (first, quota) = current_limits(&limiter, Some(&rx));
+        assert_eq!(quota, Some(HTTP_QUOTA));
+        let (same, _) = current_limits(&limiter, Some(&rx));
+        assert!(Arc::ptr_eq(&first, &same));
+
+        let overrides = [("rate_limit.burst".to_string(), "3".to_string())].into_iter().collect();
+        tx.send_replace(Arc::new(RuntimeConfig::resolve(&[(
+            crate::services::runtime_config::SettingSource::Database,
+            &overrides,
+        )])));
+        let (rebuilt, quota) = current_limits(&limiter, Some(&rx));
+        assert_eq!(quota.unwrap().burst, 3);
+        assert!(!Arc::ptr_eq(&first, &rebuilt));
+    }
+
+    #[test]
+    fn test_too_many_requests_sets_retry_after() {
+        let response = too_many_requests(Duration::from_millis(1500));
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers().get(header::RETRY_AFTER).unwrap(), "2");
+    }
+}
@@ -0,0 +1,202 @@
+//! Customer receipts for completed swaps. A receipt carries a verification
+//! hash over its contents, so support can check a receipt a customer sends
+//! in against the swap record, and a receipt token: an HMAC over the swap id
+//! and an expiry that lets the receipt be fetched without signing in
+//! (for sharing with an accountant, say). PDF receipts are rendered by an
+//! external service that takes the receipt JSON and returns the document.
+
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+
+use crate::modules::swap::schema::SwapReceipt;
+
+type HmacSha256 = Hmac<Sha256>;
+
+const DEFAULT_TOKEN_TTL_DAYS: i64 = 365;
+const RENDER_TIMEOUT: Duration = Duration::from_secs(20);
+
+static RECEIPT_SIGNER: OnceLock<Option<ReceiptSigner>> = OnceLock::new();
+static PDF_RENDERER: OnceLock<Option<PdfRenderer>> = OnceLock::new();
+
+/// Process-wide receipt token signer, loaded once from the environment
+pub fn receipt_signer() -> Option<&'static ReceiptSigner> {
+    RECEIPT_SIGNER
+        .get_or_init(|| match ReceiptSigner::from_env() {
+            Ok(signer) => Some(signer),
+            Err(e) => {
+                tracing::warn!("Receipt tokens disabled: {}", e);
+                None
+            }
+        })
+        .as_ref()
+}
+
+/// Process-wide PDF renderer, if RECEIPT_PDF_RENDERER_URL is set
+pub fn pdf_renderer() -> Option<&'static PdfRenderer> {
+    PDF_RENDERER.get_or_init(PdfRenderer::from_env).as_ref()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReceiptTokenError {
+    Malformed,
+    BadSignature,
+    Expired,
+}
+
+impl std::fmt::Display for ReceiptTokenError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReceiptTokenError::Malformed => write!(f, "Malformed receipt token"),
+            ReceiptTokenError::BadSignature => write!(f, "Receipt token is not valid for this swap"),
+            ReceiptTokenError::Expired => write!(f, "Receipt token has expired"),
+        }
+    }
+}
+
+/// Issues and checks `<expires>.<signature>` tokens bound to one swap id
+pub struct ReceiptSigner {
+    key: Vec<u8>,
+    ttl_days: i64,
+}
+
+impl ReceiptSigner {
+    pub fn new(key: &[u8], ttl_days: i64) -> Self {
+        Self { key: key.to_vec(), ttl_days }
+    }
+
+    /// RECEIPT_SIGNING_KEY: hex HMAC key (at least 32 bytes).
+    /// RECEIPT_TOKEN_TTL_DAYS: how long a token works (default 365).
+    pub fn from_env() -> Result<Self, String> {
+        let hex_key = std::env::var("RECEIPT_SIGNING_KEY").map_err(|_| "RECEIPT_SIGNING_KEY not set".to_string())?;
+        let key = hex::decode(hex_key.trim()).map_err(|e| format!("Invalid RECEIPT_SIGNING_KEY: {}", e))?;
+        if key.len() < 32 {
+            return Err("RECEIPT_SIGNING_KEY must be at least 32 bytes".to_string());
+        }
+
+        let ttl_days = match std::env::var("RECEIPT_TOKEN_TTL_DAYS") {
+            Ok(v) => v
+                .trim()
+                .parse::<i64>()
+                .ok()
+                .filter(|days| *days > 0)
+                .ok_or_else(|| format!("Invalid RECEIPT_TOKEN_TTL_DAYS: {}", v))?,
+            Err(_) => DEFAULT_TOKEN_TTL_DAYS,
+        };
+
+        Ok(Self::new(&key, ttl_days))
+    }
+
+    fn mac(&self, swap_id: &str, expires_at: i64) -> HmacSha256 {
+        let mut mac = HmacSha256::new_from_slice(&self.key).expect("HMAC accepts any key length");
+        mac.update(format!("receipt:{}:{}", swap_id, expires_at).as_bytes());
+        mac
+    }
+
+    fn signature(&self, swap_id: &str, expires_at: i64) -> Vec<u8> {
+        self.mac(swap_id, expires_at).finalize().into_bytes().to_vec()
+    }
+
+    pub fn issue(&self, swap_id: &str) -> String {
+        let expires_at = Utc::now().timestamp() + self.ttl_days * 24 * 3600;
+        format!("{}.{}", expires_at, URL_SAFE_NO_PAD.encode(self.signature(swap_id, expires_at)))
+    }
+
+    pub fn verify(&self, swap_id: &str, token: &str) -> Result<(), ReceiptTokenError> {
+        let (expires_at, signature) = token.trim().split_once('.').ok_or(ReceiptTokenError::Malformed)?;
+        let expires_at = expires_at.parse::<i64>().map_err(|_| ReceiptTokenError::Malformed)?;
+        let signature = URL_SAFE_NO_PAD.decode(signature).map_err(|_| ReceiptTokenError::Malformed)?;
+
+        self.mac(swap_id, expires_at)
+            .verify_slice(&signature).map_err(|_| ReceiptTokenError::BadSignature)?;
+
+        if expires_at < Utc::now().timestamp() {
+            return Err(ReceiptTokenError::Expired);
+        }
+        Ok(())
+    }
+}
+
+/// SHA-256 (hex) of the receipt's contents. Excludes when it was issued and
+/// the token, so every copy of one swap's receipt has the same hash.
+pub fn verification_hash(receipt: &SwapReceipt) -> String {
+    let mut value = serde_json::to_value(receipt).unwrap_or_default();
+    if let Some(fields) = value.as_object_mut() {
+        for field in ["issued_at", "receipt_token", "verification_hash"] {
+            fields.remove(field);
+        }
+    }
+    hex::encode(Sha256::digest(value.to_string().as_bytes()))
+}
+
+/// Renders receipts through RECEIPT_PDF_RENDERER_URL, which is POSTed the
+/// receipt JSON and answers with `application/pdf`
+pub struct PdfRenderer {
+    url: String,
+    http: reqwest::Client,
+}
+
+impl PdfRenderer {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            http: reqwest::Client::builder().timeout(RENDER_TIMEOUT).build().unwrap_or_default(),
+        }
+    }
+
+    pub fn from_env() -> Option<Self> {
+        std::env::var("RECEIPT_PDF_RENDERER_URL")
+            .ok()
+            .map(|url| url.trim().to_string())
+            .filter(|url| !url.is_empty())
+            .map(Self::new)
+    }
+
+    pub async fn render(&self, receipt: &SwapReceipt) -> Result<Vec<u8>, String> {
+        let response = self
+            .http
+            .post(&self.url)
+            .header(reqwest::header::ACCEPT, "application/pdf")
+            .json(receipt)
+            .send()
+            .await
+            .map_err(|e| format!("Receipt renderer unreachable: {}", e))?;
+        if !response.status().is_success() {
+            return Err(format!("Receipt renderer returned {}", response.status()));
+        }
+        let bytes = response.bytes().await.map_err(|e| format!("Receipt renderer response: {}", e))?;
+        if !bytes.starts_with(b"%PDF") {
+            return Err("Receipt renderer did not return a PDF".to_string());
+        }
+        Ok(bytes.to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_bound_to_swap() {
+        let signer = ReceiptSigner::new(&[7u8; 32], 30);
+        let token = signer.issue("swap-1");
+        assert!(signer.verify("swap-1", &token).is_ok());
+        assert_eq!(signer.verify("swap-2", &token), Err(ReceiptTokenError::BadSignature));
+        assert_eq!(signer.verify("swap-1", "garbage"), Err(ReceiptTokenError::Malformed));
+
+        let other = ReceiptSigner::new(&[8u8; 32], 30);
+        assert_eq!(other.verify("swap-1", &token), Err(ReceiptTokenError::BadSignature));
+    }
+
+    #[test]
+    fn test_expired_token_rejected() {
+        let signer = ReceiptSigner::new(&[7u8; 32], 30);
+        let expires_at = Utc::now().timestamp() - 1;
+        let token = format!("{}.{}", expires_at, URL_SAFE_NO_PAD.encode(signer.signature("swap-1", expires_at)));
+        assert_eq!(signer.verify("swap-1", &token), Err(ReceiptTokenError::Expired));
+    }
+}
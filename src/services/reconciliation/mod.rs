@@ -0,0 +1,7 @@
+pub mod orphans;
+pub mod provider;
+pub mod wallet_audit;
+
+pub use orphans::{OrphanAction, OrphanOrderReconciler, ReconciliationReport};
+pub use provider::{ProviderReconciler, ProviderReconciliationReport};
+pub use wallet_audit::{WalletAuditConfig, WalletAuditor};
@@ -0,0 +1,201 @@
+use std::time::Duration;
+use sqlx::{MySql, Pool};
+
+use crate::modules::swap::crud::SwapCrud;
+use crate::modules::swap::model::ProviderOrderIntent;
+use crate::services::trocador::TrocadorClient;
+
+const BATCH_SIZE: i64 = 100;
+/// Swap creation finishes in seconds; anything untouched this long was interrupted
+const GRACE_PERIOD_MINUTES: i64 = 10;
+
+/// What to do with an orphaned provider order given its provider status
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrphanAction {
+    /// Never funded (or already closed); safe to stop tracking
+    Resolve,
+    /// Funds are moving through an order with no swap; needs an operator
+    Review,
+    /// Still open and unfunded; check again next run
+    Wait,
+}
+
+impl OrphanAction {
+    pub fn for_provider_status(status: &str) -> Self {
+        match status {
+            "expired" | "failed" | "halted" | "refunded" => OrphanAction::Resolve,
+            "confirming" | "exchanging" | "sending" | "finished" | "paid partially" => OrphanAction::Review,
+            _ => OrphanAction::Wait,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ReconciliationReport {
+    /// Intents whose swap row turned out to be committed
+    pub committed: usize,
+    pub orphaned: usize,
+    pub resolved: usize,
+    pub needs_review: usize,
+}
+
+/// Finds provider orders left behind by interrupted swap creation and
+/// settles them against the swaps table and the provider's view
+pub struct OrphanOrderReconciler {
+    db: Pool<MySql>,
+    trocador: Option<TrocadorClient>,
+}
+
+impl OrphanOrderReconciler {
+    pub fn new(db: Pool<MySql>) -> Self {
+        let trocador = std::env::var("TROCADOR_API_KEY").ok().map(TrocadorClient::new);
+        Self { db, trocador }
+    }
+
+    /// Start the background reconciliation loop
+    pub async fn run(&self) {
+        let mut interval = tokio::time::interval(Duration::from_secs(300));
+
+        loop {
+            interval.tick().await;
+
+            match self.reconcile().await {
+                Ok(report) if report != ReconciliationReport::default() => {
+                    tracing::info!("Provider order reconciliation: {:?}", report);
+                }
+                Ok(_) => {}
+                Err(e) => tracing::error!("Provider order reconciliation failed: {}", e),
+            }
+        }
+    }
+
+    /// Run one reconciliation pass
+    pub async fn reconcile(&self) -> Result<ReconciliationReport, String> {
+        let crud = SwapCrud::new(self.db.clone(), None, None);
+        let mut report = ReconciliationReport::default();
+
+        // Crashed before the provider answered: the deposit address was never
+        // shown, so whatever the provider did will expire unfunded
+        for intent in crud
+            .get_stale_order_intents("pending", GRACE_PERIOD_MINUTES, BATCH_SIZE)
+            .await
+            .map_err(|e| e.to_string())?
+        {
+            self.settle_interrupted(&crud, &intent, "resolved", "Provider call did not complete", &mut report)
+                .await?;
+        }
+
+        // Crashed between the provider order and the swap commit
+        for intent in crud
+            .get_stale_order_intents("created", GRACE_PERIOD_MINUTES, BATCH_SIZE)
+            .await
+            .map_err(|e| e.to_string())?
+        {
+            self.settle_interrupted(&crud, &intent, "orphaned", "Swap was never committed", &mut report)
+                .await?;
+        }
+
+        let Some(trocador) = &self.trocador else {
+            return Ok(report);
+        };
+
+        for intent in crud
+            .get_stale_order_intents("orphaned", GRACE_PERIOD_MINUTES, BATCH_SIZE)
+            .await
+            .map_err(|e| e.to_string())?
+        {
+            let Some(trade_id) = intent.provider_swap_id.as_deref() else {
+                crud.set_order_intent_status(&intent.swap_id, "resolved", None, Some("No provider order was placed"))
+                    .await
+                    .map_err(|e| e.to_string())?;
+                report.resolved += 1;
+                continue;
+            };
+
+            let trade = match trocador.get_trade_status(trade_id).await {
+                Ok(trade) => trade,
+                Err(e) => {
+                    tracing::warn!("Could not check orphaned provider order {}: {}", trade_id, e);
+                    continue;
+                }
+            };
+
+            match OrphanAction::for_provider_status(&trade.status) {
+                OrphanAction::Resolve => {
+                    crud.set_order_intent_status(
+                        &intent.swap_id,
+                        "resolved",
+                        None,
+                        Some(&format!("Provider order closed as {}", trade.status)),
+                    )
+                    .await
+                    .map_err(|e| e.to_string())?;
+                    report.resolved += 1;
+                }
+                OrphanAction::Review => {
+                    tracing::error!(
+                        "Orphaned provider order {} ({}) is {}; payout address {} (index {}) needs manual review",
+                        trade_id, intent.provider_id, trade.status, intent.our_address, intent.address_index
+                    );
+                    // Touch the row so it is re-checked after the next grace period, not every pass
+                    crud.set_order_intent_status(
+                        &intent.swap_id,
+                        "orphaned",
+                        None,
+                        Some(&format!("Needs review: provider order is {}", trade.status)),
+                    )
+                    .await
+                    .map_err(|e| e.to_string())?;
+                    report.needs_review += 1;
+                }
+                OrphanAction::Wait => {}
+            }
+        }
+
+        Ok(report)
+    }
+
+    async fn settle_interrupted(
+        &self,
+        crud: &SwapCrud,
+        intent: &ProviderOrderIntent,
+        status_if_missing: &str,
+        reason: &str,
+        report: &mut ReconciliationReport,
+    ) -> Result<(), String> {
+        if crud.swap_exists(&intent.swap_id).await.map_err(|e| e.to_string())? {
+            crud.set_order_intent_status(&intent.swap_id, "committed", None, None)
+                .await
+                .map_err(|e| e.to_string())?;
+            report.committed += 1;
+            return Ok(());
+        }
+
+        tracing::warn!("Provider order intent for swap {} abandoned: {}", intent.swap_id, reason);
+        crud.set_order_intent_status(&intent.swap_id, status_if_missing, None, Some(reason))
+            .await
+            .map_err(|e| e.to_string())?;
+
+        if status_if_missing == "orphaned" {
+            report.orphaned += 1;
+        } else {
+            report.resolved += 1;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_orphan_action_for_provider_status() {
+        assert_eq!(OrphanAction::for_provider_status("expired"), OrphanAction::Resolve);
+        assert_eq!(OrphanAction::for_provider_status("refunded"), OrphanAction::Resolve);
+        assert_eq!(OrphanAction::for_provider_status("confirming"), OrphanAction::Review);
+        assert_eq!(OrphanAction::for_provider_status("finished"), OrphanAction::Review);
+        assert_eq!(OrphanAction::for_provider_status("waiting"), OrphanAction::Wait);
+        assert_eq!(OrphanAction::for_provider_status("new"), OrphanAction::Wait);
+    }
+}
@@ -0,0 +1,309 @@
+use chrono::{DateTime, Duration as ChronoDuration, Timelike, Utc};
+use sqlx::{MySql, Pool};
+use std::time::Duration;
+
+use crate::modules::jobs::crud::{percent, JobCrud};
+use crate::modules::reconciliation::crud::ReconciliationCrud;
+use crate::modules::reconciliation::model::{DiscrepancyKind, ReconcilableSwap};
+use crate::modules::swap::schema::{SwapStatus, TrocadorTradeResponse};
+use crate::services::amount;
+use crate::services::events::OpsEvent;
+use crate::services::pricing::RoundingPolicy;
+use crate::services::trocador::{TrocadorClient, TrocadorError};
+
+const BATCH_SIZE: i64 = 100;
+/// Swaps younger than this are still moving; comparing them only finds noise
+const SETTLE_MINUTES: i64 = 60;
+/// Pause between provider calls so a nightly run doesn't trip rate limits
+const REQUEST_SPACING: Duration = Duration::from_millis(250);
+
+const DEFAULT_RUN_HOUR_UTC: u32 = 3;
+const DEFAULT_LOOKBACK_HOURS: i64 = 48;
+const DEFAULT_AMOUNT_TOLERANCE: f64 = 0.01;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ProviderReconciliationReport {
+    pub checked: u32,
+    pub discrepancies: u32,
+    pub errors: u32,
+}
+
+fn is_terminal(status: &SwapStatus) -> bool {
+    matches!(
+        status,
+        SwapStatus::Completed | SwapStatus::Failed | SwapStatus::Refunded | SwapStatus::Expired
+    )
+}
+
+/// Compare one swap against the provider's order. Status drift is reported
+/// when the two sides disagree and at least one has settled; the deposit
+/// amount is compared once the provider has finished.
+pub fn diff_order(
+    swap: &ReconcilableSwap,
+    trade: &TrocadorTradeResponse,
+    amount_tolerance: f64,
+) -> Vec<(DiscrepancyKind, String, String)> {
+    let mut found = Vec::new();
+
+    // Our payout leg runs after the provider finishes, so funds_received
+    // agrees with a finished provider order
+    let ours = match SwapStatus::parse(&swap.status) {
+        Some(SwapStatus::FundsReceived) => Some(SwapStatus::Completed),
+        other => other,
+    };
+    let theirs = SwapStatus::from_provider_status(&trade.status);
+    let status_matches = ours.as_ref() == Some(&theirs);
+    let settled = ours.as_ref().is_some_and(is_terminal) || is_terminal(&theirs);
+    if !status_matches && settled {
+        found.push((DiscrepancyKind::StatusMismatch, swap.status.clone(), trade.status.clone()));
+    }
+
+    if trade.status == "finished" && swap.amount > 0.0 {
+        let drift = (trade.amount_from - swap.amount).abs() / swap.amount;
+        if drift > amount_tolerance {
+            let rounding = RoundingPolicy::for_currency(&trade.ticker_from);
+            let format = |value: f64| {
+                amount::from_f64(value).map(|v| rounding.format(v)).unwrap_or_else(|_| value.to_string())
+            };
+            found.push((DiscrepancyKind::AmountMismatch, format(swap.amount), format(trade.amount_from)));
+        }
+    }
+
+    found
+}
+
+/// Time until the next `hour`:00 UTC
+pub fn until_next_run(now: DateTime<Utc>, hour: u32) -> Duration {
+    let today = now
+        .with_hour(hour)
+        .and_then(|t| t.with_minute(0))
+        .and_then(|t| t.with_second(0))
+        .and_then(|t| t.with_nanosecond(0))
+        .unwrap_or(now);
+    let next = if today > now { today } else { today + ChronoDuration::days(1) };
+    (next - now).to_std().unwrap_or_default()
+}
+
+/// Nightly job comparing recent swaps with the provider's records, so drift
+/// such as a provider refunding directly ends up in the admin review queue
+pub struct ProviderReconciler {
+    db: Pool<MySql>,
+    trocador: Option<TrocadorClient>,
+    run_hour: u32,
+    lookback: ChronoDuration,
+    amount_tolerance: f64,
+}
+
+impl ProviderReconciler {
+    /// Settings from PROVIDER_RECONCILIATION_HOUR (UTC, default 3),
+    /// PROVIDER_RECONCILIATION_LOOKBACK_HOURS (default 48) and
+    /// PROVIDER_RECONCILIATION_AMOUNT_TOLERANCE (fraction, default 0.01)
+    pub fn new(db: Pool<MySql>) -> Self {
+        let trocador = std::env::var("TROCADOR_API_KEY").ok().map(TrocadorClient::new);
+
+        let run_hour = std::env::var("PROVIDER_RECONCILIATION_HOUR")
+            .ok()
+            .and_then(|v| v.parse::<u32>().ok())
+            .filter(|h| *h < 24)
+            .unwrap_or(DEFAULT_RUN_HOUR_UTC);
+
+        let lookback_hours = std::env::var("PROVIDER_RECONCILIATION_LOOKBACK_HOURS")
+            .ok()
+            .and_then(|v| v.parse::<i64>().ok())
+            .filter(|h| *h > 0)
+            .unwrap_or(DEFAULT_LOOKBACK_HOURS);
+
+        let amount_tolerance = std::env::var("PROVIDER_RECONCILIATION_AMOUNT_TOLERANCE")
+            .ok()
+            .and_then(|v| v.parse::<f64>().ok())
+            .filter(|t| *t >= 0.0)
+            .unwrap_or(DEFAULT_AMOUNT_TOLERANCE);
+
+        Self {
+            db,
+            trocador,
+            run_hour,
+            lookback: ChronoDuration::hours(lookback_hours),
+            amount_tolerance,
+        }
+    }
+
+    /// Start the nightly reconciliation loop
+    pub async fn run(&self) {
+        if self.trocador.is_none() {
+            tracing::warn!("Provider reconciliation disabled: TROCADOR_API_KEY not set");
+            return;
+        }
+
+        loop {
+            tokio::time::sleep(until_next_run(Utc::now(), self.run_hour)).await;
+
+            match self.reconcile().await {
+                Ok(report) => tracing::info!("Provider reconciliation finished: {:?}", report),
+                Err(e) => tracing::error!("Provider reconciliation failed: {}", e),
+            }
+        }
+    }
+
+    /// Run one reconciliation pass over the lookback window. The run is
+    /// tracked as a job under its run id.
+    pub async fn reconcile(&self) -> Result<ProviderReconciliationReport, String> {
+        let trocador = self.trocador.as_ref().ok_or("TROCADOR_API_KEY not set")?;
+        let crud = ReconciliationCrud::new(self.db.clone());
+        let jobs = JobCrud::new(self.db.clone());
+        let run_id = crud.start_run().await.map_err(|e| e.to_string())?;
+        jobs.start(&run_id).await.map_err(|e| e.to_string())?;
+
+        match self.reconcile_run(trocador, &crud, &jobs, &run_id).await {
+            Ok(report) => {
+                jobs.succeed(&run_id).await.map_err(|e| e.to_string())?;
+                Ok(report)
+            }
+            Err(e) => {
+                if let Err(job_error) = jobs.fail(&run_id, &e).await {
+                    tracing::warn!("Failed to record reconciliation run {} as failed: {}", run_id, job_error);
+                }
+                Err(e)
+            }
+        }
+    }
+
+    async fn reconcile_run(
+        &self,
+        trocador: &TrocadorClient,
+        crud: &ReconciliationCrud,
+        jobs: &JobCrud,
+        run_id: &str,
+    ) -> Result<ProviderReconciliationReport, String> {
+        let until = Utc::now() - ChronoDuration::minutes(SETTLE_MINUTES);
+        let since = until - self.lookback;
+        let total = crud.count_swaps_to_reconcile(since, until).await.map_err(|e| e.to_string())?;
+        let mut report = ProviderReconciliationReport::default();
+        let mut cursor = String::new();
+
+        loop {
+            let swaps = crud
+                .swaps_to_reconcile(since, until, &cursor, BATCH_SIZE)
+                .await
+                .map_err(|e| e.to_string())?;
+            let Some(last) = swaps.last() else { break };
+            cursor = last.id.clone();
+
+            for swap in &swaps {
+                report.checked += 1;
+
+                let found = match trocador.get_trade_status(&swap.provider_swap_id).await {
+                    Ok(trade) => diff_order(swap, &trade, self.amount_tolerance),
+                    Err(TrocadorError::NotFound(_)) => vec![(
+                        DiscrepancyKind::MissingAtProvider,
+                        swap.status.clone(),
+                        "not_found".to_string(),
+                    )],
+                    Err(e) => {
+                        tracing::warn!("Could not reconcile swap {} with provider: {}", swap.id, e);
+                        report.errors += 1;
+                        continue;
+                    }
+                };
+
+                for (kind, ours, theirs) in found {
+                    tracing::warn!(
+                        "Provider drift on swap {} ({}): ours={} provider={}",
+                        swap.id, kind.as_str(), ours, theirs
+                    );
+                    crud.record_discrepancy(run_id, swap, kind, &ours, &theirs)
+                        .await
+                        .map_err(|e| e.to_string())?;
+                    report.discrepancies += 1;
+                    OpsEvent::ReconciliationDiscrepancy {
+                        run_id: run_id.to_string(),
+                        swap_id: swap.id.clone(),
+                        kind: kind.as_str().to_string(),
+                    }
+                    .publish();
+                }
+
+                tokio::time::sleep(REQUEST_SPACING).await;
+            }
+
+            if let Err(e) = jobs.set_progress(run_id, percent(report.checked as u64, total)).await {
+                tracing::debug!("Failed to report progress of reconciliation run {}: {}", run_id, e);
+            }
+        }
+
+        crud.finish_run(run_id, report.checked, report.discrepancies, report.errors)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn swap(status: &str, amount: f64) -> ReconcilableSwap {
+        ReconcilableSwap {
+            id: "swap-1".to_string(),
+            provider_id: "changenow".to_string(),
+            provider_swap_id: "trade-1".to_string(),
+            status: status.to_string(),
+            amount,
+        }
+    }
+
+    fn trade(status: &str, amount_from: f64) -> TrocadorTradeResponse {
+        TrocadorTradeResponse {
+            trade_id: "trade-1".to_string(),
+            status: status.to_string(),
+            ticker_from: "btc".to_string(),
+            network_from: "Mainnet".to_string(),
+            ticker_to: "eth".to_string(),
+            network_to: "ERC20".to_string(),
+            amount_from,
+            amount_to: 15.0,
+            provider: "ChangeNOW".to_string(),
+            address_provider: "bc1qprovider".to_string(),
+            address_provider_memo: None,
+            address_user: "0xuser".to_string(),
+            address_user_memo: None,
+            refund_address: None,
+            refund_address_memo: None,
+            id_provider: None,
+            date: None,
+            raw: None,
+        }
+    }
+
+    #[test]
+    fn test_provider_refund_is_status_drift() {
+        let found = diff_order(&swap("exchanging", 1.0), &trade("refunded", 1.0), 0.01);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].0, DiscrepancyKind::StatusMismatch);
+        assert_eq!(found[0].2, "refunded");
+    }
+
+    #[test]
+    fn test_in_flight_and_agreeing_orders_are_clean() {
+        assert!(diff_order(&swap("waiting", 1.0), &trade("confirming", 1.0), 0.01).is_empty());
+        assert!(diff_order(&swap("funds_received", 1.0), &trade("finished", 1.0), 0.01).is_empty());
+        assert!(diff_order(&swap("completed", 1.0), &trade("finished", 1.005), 0.01).is_empty());
+    }
+
+    #[test]
+    fn test_finished_order_with_different_deposit() {
+        let found = diff_order(&swap("completed", 1.0), &trade("finished", 0.8), 0.01);
+        assert_eq!(found, vec![(DiscrepancyKind::AmountMismatch, "1.00000000".to_string(), "0.80000000".to_string())]);
+    }
+
+    #[test]
+    fn test_until_next_run() {
+        let before = Utc.with_ymd_and_hms(2026, 3, 14, 1, 30, 0).unwrap();
+        assert_eq!(until_next_run(before, 3), Duration::from_secs(90 * 60));
+
+        let after = Utc.with_ymd_and_hms(2026, 3, 14, 3, 0, 0).unwrap();
+        assert_eq!(until_next_run(after, 3), Duration::from_secs(24 * 3600));
+    }
+}
@@ -0,0 +1,530 @@
+//! Nightly audit of the HD wallet against the chain. Every allocated
+//! derivation index is checked on each configured EVM chain (the same key
+//! controls the address on all of them): its native balance and its balance
+//! of every canonical token there (USDT, USDC, ...) are compared with what
+//! the swap behind it says should be there. Bitcoin and Solana indices are
+//! checked for their native balance when a node for the chain is configured.
+//! Addresses of finished swaps should be empty once swept; anything left
+//! over, or sent to an address whose swap never took a deposit, is queued
+//! for admins as orphaned funds.
+//!
+//! An index whose chain has no node configured can't be checked there. Each
+//! run counts those indices and lists their chains rather than pass over
+//! them silently.
+//!
+//! Funds another workflow already owns are left alone: in-flight swaps,
+//! payouts still owed, queued refunds (late deposits included) and open
+//! wrong-network cases.
+
+use bitcoin::address::NetworkUnchecked;
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use sqlx::{MySql, Pool};
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::modules::jobs::crud::{percent, JobCrud};
+use crate::modules::recovery::crud::RecoveryCrud;
+use crate::modules::recovery::model::{AllocatedIndex, FundsAsset, OrphanedFundsKind, WalletAuditRun};
+use crate::modules::swap::schema::{AddressProofScheme, SwapStatus};
+use crate::services::amount::{self, Decimal};
+use crate::services::blockchain::deposits::DUST_THRESHOLD;
+use crate::services::blockchain::listener::{chain_alias, evm_rpc_url, EVM_RPC_ENV_VARS};
+use crate::services::events::OpsEvent;
+use crate::services::gas::station::native_currency;
+use crate::services::leader::LeaderElection;
+use crate::services::reconciliation::provider::until_next_run;
+use crate::services::token::registry::{CanonicalToken, TokenRegistry};
+use crate::services::wallet::bitcoin_rpc::BitcoinProvider;
+use crate::services::wallet::ownership::proof_scheme;
+use crate::services::wallet::rpc::{BlockchainProvider, HttpRpcClient, RpcError};
+use crate::services::wallet::solana_rpc::{SolanaProvider, SolanaRpcClient};
+
+const BATCH_SIZE: i64 = 200;
+/// Pause between balance calls so a nightly run doesn't trip RPC rate limits
+const REQUEST_SPACING: Duration = Duration::from_millis(50);
+const LEADER_ROLE: &str = "wallet-audit";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WalletAuditConfig {
+    /// UTC hour the nightly run starts
+    pub hour: u32,
+    /// Swaps changed more recently than this are skipped; their sweep or
+    /// late-deposit refund may still be on its way
+    pub settle_hours: u32,
+}
+
+impl Default for WalletAuditConfig {
+    fn default() -> Self {
+        Self { hour: 4, settle_hours: 6 }
+    }
+}
+
+impl WalletAuditConfig {
+    pub fn from_env() -> Result<Self, String> {
+        let mut config = Self::default();
+
+        if let Ok(val) = std::env::var("WALLET_AUDIT_HOUR") {
+            config.hour = val.trim().parse().map_err(|e| format!("Invalid WALLET_AUDIT_HOUR: {}", e))?;
+            if config.hour > 23 {
+                return Err("WALLET_AUDIT_HOUR must be 0-23".to_string());
+            }
+        }
+        if let Ok(val) = std::env::var("WALLET_AUDIT_SETTLE_HOURS") {
+            config.settle_hours =
+                val.trim().parse().map_err(|e| format!("Invalid WALLET_AUDIT_SETTLE_HOURS: {}", e))?;
+        }
+
+        Ok(config)
+    }
+}
+
+/// What should be at an allocated address
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Expected {
+    /// Funds may legitimately be there; another workflow owns them
+    MayHoldFunds,
+    /// Nothing should be there; a balance is orphaned funds of this kind
+    Empty(OrphanedFundsKind),
+}
+
+/// Expected state of `index` on a chain. `own_chain` is whether the chain
+/// is the one the swap settles on.
+pub fn expected_state(index: &AllocatedIndex, own_chain: bool) -> Expected {
+    if index.refund_open {
+        return Expected::MayHoldFunds;
+    }
+    let Some(status) = index.swap_status.as_deref() else {
+        // An order intent that never became a swap: the address was never shown
+        return Expected::Empty(OrphanedFundsKind::UnmatchedDeposit);
+    };
+
+    match SwapStatus::parse(status) {
+        Some(SwapStatus::Completed) if index.payout_status.as_deref() != Some("success") => Expected::MayHoldFunds,
+        Some(SwapStatus::Completed | SwapStatus::Refunded) if own_chain => {
+            Expected::Empty(OrphanedFundsKind::Unswept)
+        }
+        Some(SwapStatus::Completed | SwapStatus::Refunded | SwapStatus::Failed | SwapStatus::Expired) => {
+            Expected::Empty(OrphanedFundsKind::UnmatchedDeposit)
+        }
+        _ => Expected::MayHoldFunds,
+    }
+}
+
+/// Chain family of an allocated address, from its shape
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddressFamily {
+    Evm,
+    Bitcoin,
+    Solana,
+}
+
+impl AddressFamily {
+    pub fn of(address: &str) -> Option<Self> {
+        match proof_scheme(address) {
+            Some(AddressProofScheme::Eip191) => Some(AddressFamily::Evm),
+            Some(AddressProofScheme::Bip137) => Some(AddressFamily::Bitcoin),
+            Some(AddressProofScheme::Ed25519) => Some(AddressFamily::Solana),
+            // P2SH and segwit addresses
+            None => address
+                .parse::<bitcoin::Address<NetworkUnchecked>>()
+                .is_ok()
+                .then_some(AddressFamily::Bitcoin),
+        }
+    }
+}
+
+/// Audits every allocated derivation index. With leader election only one
+/// process of a deployment runs the nightly pass.
+pub struct WalletAuditor {
+    db: Pool<MySql>,
+    providers: HashMap<String, Arc<dyn BlockchainProvider>>,
+    bitcoin: Option<Arc<dyn BitcoinProvider>>,
+    solana: Option<Arc<dyn SolanaProvider>>,
+    config: WalletAuditConfig,
+    election: Option<Arc<LeaderElection>>,
+    spacing: Duration,
+}
+
+impl WalletAuditor {
+    /// Auditor over every EVM chain with an RPC URL configured, and Solana
+    /// when `SOLANA_PRIMARY_RPC` is set
+    pub fn new(db: Pool<MySql>, config: WalletAuditConfig) -> Self {
+        let providers = EVM_RPC_ENV_VARS
+            .iter()
+            .filter_map(|(chain, _)| {
+                let rpc = evm_rpc_url(chain)?;
+                Some((chain.to_string(), Arc::new(HttpRpcClient::new(rpc)) as Arc<dyn BlockchainProvider>))
+            })
+            .collect();
+        let solana = std::env::var("SOLANA_PRIMARY_RPC")
+            .ok()
+            .filter(|url| !url.trim().is_empty())
+            .map(|url| Arc::new(SolanaRpcClient::new(url.trim().to_string())) as Arc<dyn SolanaProvider>);
+        Self { db, providers, bitcoin: None, solana, config, election: None, spacing: REQUEST_SPACING }
+    }
+
+    /// Read EVM balances from `providers` instead of the configured chains
+    pub fn with_providers(mut self, providers: HashMap<String, Arc<dyn BlockchainProvider>>) -> Self {
+        self.providers = providers;
+        self.spacing = Duration::ZERO;
+        self
+    }
+
+    /// Check Bitcoin indices against `provider`; without one they are
+    /// reported as unaudited
+    pub fn with_bitcoin_provider(mut self, provider: Arc<dyn BitcoinProvider>) -> Self {
+        self.bitcoin = Some(provider);
+        self
+    }
+
+    pub fn with_solana_provider(mut self, provider: Arc<dyn SolanaProvider>) -> Self {
+        self.solana = Some(provider);
+        self
+    }
+
+    pub fn with_leader_election(mut self, election: Arc<LeaderElection>) -> Self {
+        self.election = Some(election);
+        self
+    }
+
+    /// Start the nightly audit loop
+    pub async fn run(&self) {
+        if self.providers.is_empty() && self.bitcoin.is_none() && self.solana.is_none() {
+            tracing::warn!("Wallet audit disabled: no EVM, Bitcoin or Solana nodes configured");
+            return;
+        }
+
+        loop {
+            tokio::time::sleep(until_next_run(Utc::now(), self.config.hour)).await;
+
+            if let Some(election) = &self.election {
+                match election.try_lead(LEADER_ROLE).await {
+                    Ok(true) => {}
+                    Ok(false) => continue,
+                    Err(e) => {
+                        tracing::warn!("Skipping wallet audit, leader election failed: {}", e);
+                        continue;
+                    }
+                }
+            }
+
+            match self.audit().await {
+                Ok(run) => tracing::info!(
+                    "Wallet audit {} finished: {} indices, {} balance checks, {} findings, {} errors, {} unaudited",
+                    run.id, run.indices_checked, run.balance_checks, run.findings, run.errors, run.unaudited_indices
+                ),
+                Err(e) => tracing::error!("Wallet audit failed: {}", e),
+            }
+        }
+    }
+
+    /// Run one audit pass
+    pub async fn audit(&self) -> Result<WalletAuditRun, String> {
+        let run_id = self.start().await?;
+        self.complete(&run_id).await
+    }
+
+    /// Record a new run and its job
+    pub async fn start(&self) -> Result<String, String> {
+        RecoveryCrud::new(self.db.clone()).start_audit_run().await.map_err(|e| e.to_string())
+    }
+
+    /// Audit every index for a started run. The run is tracked as a job
+    /// under its run id.
+    pub async fn complete(&self, run_id: &str) -> Result<WalletAuditRun, String> {
+        let crud = RecoveryCrud::new(self.db.clone());
+        let jobs = JobCrud::new(self.db.clone());
+        jobs.start(run_id).await.map_err(|e| e.to_string())?;
+
+        match self.audit_run(&crud, &jobs, run_id).await {
+            Ok(run) => {
+                jobs.succeed(run_id).await.map_err(|e| e.to_string())?;
+                Ok(run)
+            }
+            Err(e) => {
+                if let Err(job_error) = jobs.fail(run_id, &e).await {
+                    tracing::warn!("Failed to record wallet audit {} as failed: {}", run_id, job_error);
+                }
+                Err(e)
+            }
+        }
+    }
+
+    async fn audit_run(&self, crud: &RecoveryCrud, jobs: &JobCrud, run_id: &str) -> Result<WalletAuditRun, String> {
+        let mut run = crud
+            .get_audit_run(run_id)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("Wallet audit run {} not found", run_id))?;
+        let tokens = self.canonical_tokens().await?;
+        let total = crud.count_allocated_indices().await.map_err(|e| e.to_string())?;
+        let settled_before = Utc::now() - ChronoDuration::hours(self.config.settle_hours as i64);
+        let mut cursor: (Option<u32>, String) = (None, String::new());
+        let mut unaudited = BTreeSet::new();
+
+        loop {
+            let indices = crud
+                .allocated_indices(cursor.0, &cursor.1, BATCH_SIZE)
+                .await
+                .map_err(|e| e.to_string())?;
+            let Some(last) = indices.last() else { break };
+            cursor = (Some(last.address_index), last.swap_id.clone());
+
+            for index in &indices {
+                run.indices_checked += 1;
+                if !settled(index.updated_at, settled_before) {
+                    continue;
+                }
+                if let Some(chain) = self.audit_index(crud, &mut run, &tokens, index).await? {
+                    run.unaudited_indices += 1;
+                    unaudited.insert(chain);
+                }
+            }
+
+            if let Err(e) = jobs.set_progress(run_id, percent(run.indices_checked as u64, total)).await {
+                tracing::debug!("Failed to report progress of wallet audit {}: {}", run_id, e);
+            }
+        }
+
+        run.unaudited_chains = unaudited.into_iter().collect::<Vec<_>>().join(",");
+        if run.unaudited_indices > 0 {
+            tracing::warn!(
+                "⚠️  Wallet audit {}: {} indices not audited, no node configured for {}",
+                run.id, run.unaudited_indices, run.unaudited_chains
+            );
+        }
+
+        crud.finish_audit_run(&run).await.map_err(|e| e.to_string())?;
+        Ok(run)
+    }
+
+    /// Canonical tokens of every audited EVM chain
+    async fn canonical_tokens(&self) -> Result<HashMap<String, Vec<CanonicalToken>>, String> {
+        let registry = TokenRegistry::new(self.db.clone());
+        let mut tokens = HashMap::new();
+        for chain in self.providers.keys() {
+            let canonical = registry.canonical_tokens(chain).await.map_err(|e| e.to_string())?;
+            tokens.insert(chain.clone(), canonical);
+        }
+        Ok(tokens)
+    }
+
+    /// Check one index on every chain it can hold funds on. Returns the
+    /// chain it could not be checked on, if any.
+    async fn audit_index(
+        &self,
+        crud: &RecoveryCrud,
+        run: &mut WalletAuditRun,
+        tokens: &HashMap<String, Vec<CanonicalToken>>,
+        index: &AllocatedIndex,
+    ) -> Result<Option<String>, String> {
+        let (chain, currency, provider) = match AddressFamily::of(&index.address) {
+            Some(AddressFamily::Evm) => return self.audit_evm_index(crud, run, tokens, index).await,
+            Some(AddressFamily::Bitcoin) => ("bitcoin", "btc", self.bitcoin.as_ref().map(NativeSource::Bitcoin)),
+            Some(AddressFamily::Solana) => ("solana", "sol", self.solana.as_ref().map(NativeSource::Solana)),
+            None => return Ok(Some("unknown".to_string())),
+        };
+        let Some(provider) = provider else {
+            return Ok(Some(chain.to_string()));
+        };
+
+        // The address exists on its own chain only
+        let Expected::Empty(kind) = expected_state(index, true) else {
+            return Ok(None);
+        };
+        let balance = provider.get_balance(&index.address).await;
+        self.check_balance(crud, run, index, &FundsAsset::native(chain, currency), kind, true, balance)
+            .await?;
+        Ok(None)
+    }
+
+    /// Native and canonical token balances on every configured EVM chain.
+    /// Returns the swap's own chain when no RPC URL is configured for it.
+    async fn audit_evm_index(
+        &self,
+        crud: &RecoveryCrud,
+        run: &mut WalletAuditRun,
+        tokens: &HashMap<String, Vec<CanonicalToken>>,
+        index: &AllocatedIndex,
+    ) -> Result<Option<String>, String> {
+        let own_chain = index.network.as_deref().and_then(evm_chain);
+
+        for (chain, provider) in &self.providers {
+            let is_own_chain = own_chain == Some(chain.as_str());
+            let Expected::Empty(kind) = expected_state(index, is_own_chain) else {
+                continue;
+            };
+
+            let native = FundsAsset::native(chain, native_currency(chain));
+            let balance = provider.get_balance(&index.address).await;
+            self.check_balance(crud, run, index, &native, kind, is_own_chain, balance).await?;
+
+            for token in tokens.get(chain).into_iter().flatten() {
+                let asset = FundsAsset::token(chain, &token.symbol, &token.contract_address);
+                let balance = provider
+                    .get_token_balance(&token.contract_address, &index.address)
+                    .await
+                    .and_then(|raw| {
+                        amount::from_minor_units(raw, token.decimals as u32).map_err(|e| RpcError::Parse(e.to_string()))
+                    });
+                self.check_balance(crud, run, index, &asset, kind, is_own_chain, balance).await?;
+            }
+        }
+
+        Ok(own_chain.filter(|chain| !self.providers.contains_key(*chain)).map(str::to_string))
+    }
+
+    /// Queue `balance` of `asset` at the index when it is more than dust
+    #[allow(clippy::too_many_arguments)]
+    async fn check_balance(
+        &self,
+        crud: &RecoveryCrud,
+        run: &mut WalletAuditRun,
+        index: &AllocatedIndex,
+        asset: &FundsAsset,
+        kind: OrphanedFundsKind,
+        is_own_chain: bool,
+        balance: Result<Decimal, RpcError>,
+    ) -> Result<(), String> {
+        run.balance_checks += 1;
+        let balance = match balance {
+            Ok(balance) => balance,
+            Err(e) => {
+                tracing::debug!("RPC error auditing {} {} on {}: {}", index.address, asset.currency, asset.chain, e);
+                run.errors += 1;
+                return Ok(());
+            }
+        };
+        tokio::time::sleep(self.spacing).await;
+        if balance <= DUST_THRESHOLD {
+            return Ok(());
+        }
+
+        // Funds on another chain for a swap that is still being looked at
+        // through the wrong-network queue
+        if !is_own_chain
+            && crud.has_open_wrong_network_case(&index.swap_id, &asset.chain).await.map_err(|e| e.to_string())?
+        {
+            return Ok(());
+        }
+
+        run.findings += 1;
+        let new = crud
+            .record_orphaned_funds(&run.id, index, asset, kind, balance)
+            .await
+            .map_err(|e| e.to_string())?;
+        if new {
+            tracing::warn!(
+                "⚠️  Orphaned funds at index {} ({}): {} {} on {} ({}, swap {})",
+                index.address_index, index.address, balance, asset.currency, asset.chain, kind.as_str(), index.swap_id
+            );
+            OpsEvent::OrphanedFundsFound {
+                run_id: run.id.clone(),
+                address_index: index.address_index,
+                chain: asset.chain.clone(),
+                currency: asset.currency.clone(),
+                amount: balance,
+                kind: kind.as_str().to_string(),
+            }
+            .publish();
+        }
+
+        Ok(())
+    }
+}
+
+/// Native balance reader of a non-EVM chain
+enum NativeSource<'a> {
+    Bitcoin(&'a Arc<dyn BitcoinProvider>),
+    Solana(&'a Arc<dyn SolanaProvider>),
+}
+
+impl NativeSource<'_> {
+    async fn get_balance(&self, address: &str) -> Result<Decimal, RpcError> {
+        match self {
+            NativeSource::Bitcoin(provider) => provider.get_balance(address).await,
+            NativeSource::Solana(provider) => provider.get_balance(address).await,
+        }
+    }
+}
+
+/// Canonical EVM chain name of a swap network, whether or not it is configured
+fn evm_chain(network: &str) -> Option<&'static str> {
+    let normalized = network.to_lowercase();
+    let known = |chain: &str| EVM_RPC_ENV_VARS.iter().map(|(name, _)| *name).find(|name| *name == chain);
+    known(&normalized).or_else(|| chain_alias(&normalized).and_then(known))
+}
+
+/// Whether the swap behind an index last changed before `settled_before`
+fn settled(updated_at: Option<DateTime<Utc>>, settled_before: DateTime<Utc>) -> bool {
+    updated_at.is_none_or(|at| at < settled_before)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn index(swap_status: Option<&str>, payout_status: Option<&str>) -> AllocatedIndex {
+        AllocatedIndex {
+            address_index: 7,
+            address: "0x742d35Cc6634C0532925a3b844Bc454e4438f44e".to_string(),
+            swap_id: "swap-1".to_string(),
+            swap_status: swap_status.map(str::to_string),
+            network: Some("ERC20".to_string()),
+            payout_status: payout_status.map(str::to_string),
+            updated_at: None,
+            refund_open: false,
+        }
+    }
+
+    #[test]
+    fn test_finished_swaps_should_be_empty() {
+        assert_eq!(
+            expected_state(&index(Some("completed"), Some("success")), true),
+            Expected::Empty(OrphanedFundsKind::Unswept)
+        );
+        assert_eq!(
+            expected_state(&index(Some("completed"), Some("success")), false),
+            Expected::Empty(OrphanedFundsKind::UnmatchedDeposit)
+        );
+        assert_eq!(
+            expected_state(&index(Some("expired"), Some("pending")), true),
+            Expected::Empty(OrphanedFundsKind::UnmatchedDeposit)
+        );
+        assert_eq!(expected_state(&index(None, None), true), Expected::Empty(OrphanedFundsKind::UnmatchedDeposit));
+    }
+
+    #[test]
+    fn test_funds_owned_elsewhere_are_expected() {
+        assert_eq!(expected_state(&index(Some("exchanging"), Some("pending")), true), Expected::MayHoldFunds);
+        assert_eq!(expected_state(&index(Some("funds_received"), Some("pending")), false), Expected::MayHoldFunds);
+        // Payout still owed
+        assert_eq!(expected_state(&index(Some("completed"), Some("failed")), true), Expected::MayHoldFunds);
+
+        let refunding = AllocatedIndex { refund_open: true, ..index(Some("expired"), Some("pending")) };
+        assert_eq!(expected_state(&refunding, true), Expected::MayHoldFunds);
+    }
+
+    #[test]
+    fn test_addresses_are_audited_on_their_own_chain_family() {
+        assert_eq!(AddressFamily::of("0x742d35Cc6634C0532925a3b844Bc454e4438f44e"), Some(AddressFamily::Evm));
+        assert_eq!(AddressFamily::of("1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN2"), Some(AddressFamily::Bitcoin));
+        assert_eq!(AddressFamily::of("3J98t1WpEZ73CNmQviecrnyiWrnqRhWNLy"), Some(AddressFamily::Bitcoin));
+        assert_eq!(AddressFamily::of("bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq"), Some(AddressFamily::Bitcoin));
+        assert_eq!(AddressFamily::of("11111111111111111111111111111111"), Some(AddressFamily::Solana));
+        assert_eq!(AddressFamily::of("not-an-address"), None);
+
+        assert_eq!(evm_chain("ERC20"), Some("ethereum"));
+        assert_eq!(evm_chain("polygon"), Some("polygon"));
+        assert_eq!(evm_chain("bitcoin"), None);
+    }
+
+    #[test]
+    fn test_recently_changed_swaps_are_not_settled() {
+        let cutoff = Utc::now();
+        assert!(settled(None, cutoff));
+        assert!(settled(Some(cutoff - ChronoDuration::minutes(1)), cutoff));
+        assert!(!settled(Some(cutoff + ChronoDuration::minutes(1)), cutoff));
+    }
+}
@@ -16,6 +16,7 @@ impl RedisService {
         self.client.clone()
     }
 
+    #[tracing::instrument(name = "redis.command", level = "debug", skip_all, fields(redis.op = "SETEX"))]
     pub async fn set_json<T: Serialize>(&self, key: &str, value: &T, ttl_seconds: u64) -> Result<(), String> {
         let json = serde_json::to_string(value).map_err(|e| e.to_string())?;
         
@@ -28,6 +29,7 @@ impl RedisService {
             .map_err(|e: redis::RedisError| e.to_string())
     }
 
+    #[tracing::instrument(name = "redis.command", level = "debug", skip_all, fields(redis.op = "GET"))]
     pub async fn get_json<T: DeserializeOwned>(&self, key: &str) -> Result<Option<T>, String> {
         let mut conn = self.client.get_multiplexed_async_connection()
             .await
@@ -69,6 +71,7 @@ impl RedisService {
     }
 
     // Distributed Lock: Set key only if it doesn't exist
+    #[tracing::instrument(name = "redis.command", level = "debug", skip_all, fields(redis.op = "SET NX"))]
     pub async fn try_lock(&self, key: &str, ttl_seconds: u64) -> Result<bool, String> {
         let mut conn = self.client.get_multiplexed_async_connection()
             .await
@@ -89,6 +92,62 @@ impl RedisService {
         Ok(result.is_some())
     }
 
+    /// Take a lease held by `holder`, or extend it if `holder` already has
+    /// it. Returns whether `holder` holds the lease afterwards.
+    #[tracing::instrument(name = "redis.command", level = "debug", skip_all, fields(redis.op = "EVAL"))]
+    pub async fn acquire_lease(&self, key: &str, holder: &str, ttl_ms: u64) -> Result<bool, String> {
+        let mut conn = self.client.get_multiplexed_async_connection()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let script = redis::Script::new(
+            r#"
+            if redis.call('GET', KEYS[1]) == ARGV[1] then
+                return redis.call('PEXPIRE', KEYS[1], ARGV[2])
+            end
+            if redis.call('SET', KEYS[1], ARGV[1], 'NX', 'PX', ARGV[2]) then
+                return 1
+            end
+            return 0
+            "#,
+        );
+        let held: i64 = script
+            .key(key)
+            .arg(holder)
+            .arg(ttl_ms)
+            .invoke_async(&mut conn)
+            .await
+            .map_err(|e: redis::RedisError| e.to_string())?;
+
+        Ok(held == 1)
+    }
+
+    /// Give up a lease, only if `holder` still has it
+    #[tracing::instrument(name = "redis.command", level = "debug", skip_all, fields(redis.op = "EVAL"))]
+    pub async fn release_lease(&self, key: &str, holder: &str) -> Result<(), String> {
+        let mut conn = self.client.get_multiplexed_async_connection()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let script = redis::Script::new(
+            r#"
+            if redis.call('GET', KEYS[1]) == ARGV[1] then
+                return redis.call('DEL', KEYS[1])
+            end
+            return 0
+            "#,
+        );
+        let _: i64 = script
+            .key(key)
+            .arg(holder)
+            .invoke_async(&mut conn)
+            .await
+            .map_err(|e: redis::RedisError| e.to_string())?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "redis.command", level = "debug", skip_all, fields(redis.op = "SETEX"))]
     pub async fn set_string(&self, key: &str, value: &str, ttl_seconds: u64) -> Result<(), String> {
         let mut conn = self.client.get_multiplexed_async_connection()
             .await
@@ -99,6 +158,7 @@ impl RedisService {
             .map_err(|e: redis::RedisError| e.to_string())
     }
 
+    #[tracing::instrument(name = "redis.command", level = "debug", skip_all, fields(redis.op = "GET"))]
     pub async fn get_string(&self, key: &str) -> Result<Option<String>, String> {
         let mut conn = self.client.get_multiplexed_async_connection()
             .await
@@ -112,6 +172,41 @@ impl RedisService {
     }
 
     // Cache with deduplication
+    /// Delete every key starting with `prefix`. SCAN rather than KEYS so a
+    /// large keyspace doesn't block the server.
+    #[tracing::instrument(name = "redis.command", level = "debug", skip_all, fields(redis.op = "SCAN+DEL"))]
+    pub async fn delete_prefix(&self, prefix: &str) -> Result<u64, String> {
+        let mut conn = self.client.get_multiplexed_async_connection()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let pattern = format!("{}*", prefix);
+        let mut cursor: u64 = 0;
+        let mut deleted = 0;
+        loop {
+            let (next, keys): (u64, Vec<String>) = redis::cmd("SCAN")
+                .arg(cursor)
+                .arg("MATCH")
+                .arg(&pattern)
+                .arg("COUNT")
+                .arg(500)
+                .query_async(&mut conn)
+                .await
+                .map_err(|e: redis::RedisError| e.to_string())?;
+
+            if !keys.is_empty() {
+                deleted += conn.del::<_, u64>(&keys)
+                    .await
+                    .map_err(|e: redis::RedisError| e.to_string())?;
+            }
+
+            if next == 0 {
+                return Ok(deleted);
+            }
+            cursor = next;
+        }
+    }
+
     pub async fn get_or_set_json<T, F, Fut>(&self, key: &str, ttl_seconds: u64, fetch_fn: F) -> Result<T, String>
     where
         T: Serialize + DeserializeOwned,
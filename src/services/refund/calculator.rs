@@ -4,6 +4,7 @@ use uuid::Uuid;
 use sqlx::{MySqlPool, Row};
 use std::str::FromStr;
 
+use crate::services::pricing::RoundingPolicy;
 use crate::services::refund::{RefundCalculation, RefundConfig, RefundError};
 
 pub struct RefundCalculator {
@@ -47,8 +48,11 @@ impl RefundCalculator {
         // Estimate gas cost for refund transaction
         let gas_cost_estimate = self.estimate_gas_cost(&from_currency).await?;
         
-        // Calculate refund amount
-        let refund_amount = deposit_amount - platform_fee - total_fee - gas_cost_estimate;
+        // Calculate refund amount: fees rounded up, the refund down
+        let rounding = RoundingPolicy::for_currency(&from_currency);
+        let platform_fee_charged = if self.config.waive_platform_fee { Decimal::ZERO } else { platform_fee };
+        let fees_paid = rounding.fee(platform_fee_charged + total_fee + gas_cost_estimate);
+        let refund_amount = rounding.credit(deposit_amount - fees_paid);
         
         // Check if economical
         let min_threshold = self.get_min_threshold(&from_currency);
@@ -63,7 +67,11 @@ impl RefundCalculator {
         Ok(RefundCalculation {
             refund_amount,
             deposit_amount,
-            fees_paid: platform_fee + total_fee,
+            currency: from_currency,
+            fees_paid,
+            swap_fee: total_fee,
+            platform_fee,
+            platform_fee_waived: self.config.waive_platform_fee,
             gas_cost_estimate,
             is_economical,
             reason,
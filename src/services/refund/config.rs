@@ -25,6 +25,9 @@ pub struct RefundConfig {
     pub min_refund_threshold_eth: f64,
     pub min_refund_threshold_usd: f64,
     
+    // Don't charge the platform fee on swaps that end up refunded
+    pub waive_platform_fee: bool,
+    
     // Processing
     pub worker_pool_size: usize,
     pub batch_size: usize,
@@ -56,6 +59,8 @@ impl Default for RefundConfig {
             min_refund_threshold_eth: 0.001,
             min_refund_threshold_usd: 1.0,
             
+            waive_platform_fee: false,
+            
             worker_pool_size: 10,
             batch_size: 100,
             check_interval: 60,
@@ -80,6 +85,10 @@ impl RefundConfig {
             config.max_retry_attempts = val.parse().map_err(|e| format!("Invalid REFUND_MAX_RETRY_ATTEMPTS: {}", e))?;
         }
         
+        if let Ok(val) = std::env::var("REFUND_WAIVE_PLATFORM_FEE") {
+            config.waive_platform_fee = val.parse().map_err(|e| format!("Invalid REFUND_WAIVE_PLATFORM_FEE: {}", e))?;
+        }
+        
         if let Ok(val) = std::env::var("REFUND_CHECK_INTERVAL") {
             config.check_interval = val.parse().map_err(|e| format!("Invalid REFUND_CHECK_INTERVAL: {}", e))?;
         }
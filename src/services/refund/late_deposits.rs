@@ -0,0 +1,386 @@
+//! Deposits that reach a swap's address after it expired. The listener
+//! records each one as a refund; `LateDepositRefunder` sends the recorded
+//! amount of native EVM deposits back to the swap's refund address, less
+//! gas, and moves the swap to refunded so the user is notified. Token
+//! deposits and swaps without a usable refund address are left as MANUAL
+//! refunds for an admin.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use sqlx::{MySql, Pool};
+use uuid::Uuid;
+
+use crate::modules::payout_guard::model::PayoutKind;
+use crate::modules::recovery::crud::{evm_chain_id, is_evm_address};
+use crate::modules::swap::schema::SwapStatus;
+use crate::modules::wallet::crud::WalletCrud;
+use crate::services::amount::Decimal;
+use crate::services::blockchain::listener::evm_rpc_url;
+use crate::services::payout::{PayoutGuard, PayoutGuardError, PlannedPayout};
+use crate::services::sandbox::signing_chain_id;
+use crate::services::swap_state::{StatusActor, SwapStateMachine, Transition};
+use crate::services::wallet::manager::WalletManager;
+use crate::services::wallet::rpc::{BlockchainProvider, HttpRpcClient};
+
+/// `refunds.initiated_by` of late deposit refunds
+pub const LATE_DEPOSIT_INITIATOR: &str = "LATE_DEPOSIT";
+
+const BATCH_SIZE: i64 = 20;
+const DEFAULT_INTERVAL: Duration = Duration::from_secs(60);
+/// First retry delay; doubles with each failed attempt
+const RETRY_BASE_SECS: i64 = 60;
+const MAX_RETRY_SECS: i64 = 6 * 3600;
+
+/// A deposit seen on an expired swap's address
+#[derive(Debug, Clone)]
+pub struct LateDeposit {
+    pub swap_id: String,
+    /// Canonical chain the funds are on
+    pub network: String,
+    pub currency: String,
+    pub amount: Decimal,
+    pub refund_address: Option<String>,
+    /// Native coin rather than a token
+    pub native: bool,
+}
+
+/// Why a late deposit cannot be refunded automatically, if it can't
+pub fn manual_reason(deposit: &LateDeposit) -> Option<String> {
+    if !deposit.native {
+        return Some(format!("{} token deposits are refunded manually", deposit.currency.to_uppercase()));
+    }
+    match deposit.refund_address.as_deref().map(str::trim) {
+        None | Some("") => Some("Swap has no refund address".to_string()),
+        Some(address) if !is_evm_address(address) => {
+            Some(format!("Refund address {} is not an EVM address", address))
+        }
+        Some(_) => None,
+    }
+}
+
+/// Delay before retrying a refund that failed `attempts` times
+pub fn retry_delay_secs(attempts: u32) -> i64 {
+    let exponent = attempts.saturating_sub(1).min(16);
+    (RETRY_BASE_SECS << exponent).min(MAX_RETRY_SECS)
+}
+
+/// Record a refund for a late deposit. A swap gets at most one, so repeat
+/// sightings are ignored; returns whether a refund was created.
+pub async fn record_late_deposit(db: &Pool<MySql>, deposit: &LateDeposit) -> Result<bool, String> {
+    let reason = manual_reason(deposit);
+    let status = if reason.is_some() { "MANUAL" } else { "PENDING" };
+
+    let result = sqlx::query(
+        r#"
+        INSERT IGNORE INTO refunds (
+            id, swap_id, idempotency_key, refund_address, refund_amount,
+            refund_currency, refund_network, status, initiated_by, failure_reason, next_retry_at
+        ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, NOW())
+        "#,
+    )
+    .bind(Uuid::new_v4().to_string())
+    .bind(&deposit.swap_id)
+    .bind(format!("late-deposit:{}", deposit.swap_id))
+    .bind(deposit.refund_address.as_deref().unwrap_or(""))
+    .bind(deposit.amount)
+    .bind(&deposit.currency)
+    .bind(&deposit.network)
+    .bind(status)
+    .bind(LATE_DEPOSIT_INITIATOR)
+    .bind(&reason)
+    .execute(db)
+    .await
+    .map_err(|e| format!("Failed to record late deposit for {}: {}", deposit.swap_id, e))?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Outcome of one refunder pass
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct LateDepositReport {
+    pub sent: usize,
+    pub failed: usize,
+}
+
+/// A due refund with the key index of the address holding the funds
+#[derive(Debug, sqlx::FromRow)]
+struct DueRefund {
+    id: String,
+    swap_id: String,
+    refund_address: String,
+    refund_amount: Decimal,
+    refund_currency: String,
+    refund_network: String,
+    attempt_number: i32,
+    max_attempts: i32,
+    address_index: u32,
+}
+
+/// Sends pending late deposit refunds from the swap's deposit address
+pub struct LateDepositRefunder {
+    db: Pool<MySql>,
+    master_seed: String,
+    interval: Duration,
+    /// Per-network overrides of the RPC from `evm_rpc_url`
+    providers: HashMap<String, Arc<dyn BlockchainProvider>>,
+}
+
+impl LateDepositRefunder {
+    pub fn new(db: Pool<MySql>, master_seed: String) -> Self {
+        Self { db, master_seed, interval: DEFAULT_INTERVAL, providers: HashMap::new() }
+    }
+
+    pub fn with_provider(mut self, network: &str, provider: Arc<dyn BlockchainProvider>) -> Self {
+        self.providers.insert(network.to_lowercase(), provider);
+        self
+    }
+
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    pub async fn run(&self) {
+        let mut interval = tokio::time::interval(self.interval);
+        loop {
+            interval.tick().await;
+            match self.process().await {
+                Ok(report) if report.sent + report.failed > 0 => {
+                    tracing::info!("Late deposit refunds: {} sent, {} failed", report.sent, report.failed);
+                }
+                Ok(_) => {}
+                Err(e) => tracing::error!("Late deposit refund pass failed: {}", e),
+            }
+        }
+    }
+
+    /// Send every refund that is due, stopping at the first one the payout
+    /// guard refuses
+    pub async fn process(&self) -> Result<LateDepositReport, String> {
+        let due: Vec<DueRefund> = sqlx::query_as(
+            r#"
+            SELECT r.id, r.swap_id, r.refund_address,
+                   r.refund_amount, r.refund_currency, r.refund_network, r.attempt_number, r.max_attempts, sa.address_index
+            FROM refunds r
+            JOIN swap_address_info sa ON sa.swap_id = r.swap_id
+            WHERE r.initiated_by = ?
+            AND r.status IN ('PENDING', 'FAILED')
+            AND r.attempt_number < r.max_attempts
+            AND (r.next_retry_at IS NULL OR r.next_retry_at <= NOW())
+            ORDER BY r.created_at
+            LIMIT ?
+            "#,
+        )
+        .bind(LATE_DEPOSIT_INITIATOR)
+        .bind(BATCH_SIZE)
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| format!("Database error: {}", e))?;
+
+        let guard = PayoutGuard::new(self.db.clone());
+        let mut report = LateDepositReport::default();
+        for refund in due {
+            let planned = PlannedPayout::new(
+                PayoutKind::Refund,
+                &refund.id,
+                &refund.refund_network,
+                &refund.refund_currency,
+                refund.refund_amount,
+            );
+            let reservation = match guard.check(&planned).await {
+                Ok(reservation) => reservation,
+                Err(e @ PayoutGuardError::Unpriced(_)) => {
+                    tracing::warn!("Holding late deposit refund {}: {}", refund.id, e);
+                    continue;
+                }
+                Err(e) => {
+                    tracing::warn!("Holding late deposit refunds at {}: {}", refund.id, e);
+                    break;
+                }
+            };
+            let claimed = match self.claim(&refund).await {
+                Ok(claimed) => claimed,
+                Err(e) => {
+                    guard.release(&reservation).await;
+                    return Err(e);
+                }
+            };
+            if !claimed {
+                guard.release(&reservation).await;
+                continue;
+            }
+            let result = self.send(&refund).await;
+            guard.record(&reservation, result.as_ref().err().map(String::as_str)).await;
+            match result {
+                Ok((tx_hash, sent)) => {
+                    self.complete(&refund, &tx_hash, sent).await?;
+                    report.sent += 1;
+                }
+                Err(e) => {
+                    tracing::warn!("Late deposit refund {} for swap {} failed: {}", refund.id, refund.swap_id, e);
+                    self.fail(&refund, &e).await?;
+                    report.failed += 1;
+                }
+            }
+        }
+        Ok(report)
+    }
+
+    /// Take the refund unless another worker already has
+    async fn claim(&self, refund: &DueRefund) -> Result<bool, String> {
+        let result = sqlx::query(
+            r#"
+            UPDATE refunds
+            SET status = 'PROCESSING', attempt_number = attempt_number + 1
+            WHERE id = ? AND status IN ('PENDING', 'FAILED')
+            "#,
+        )
+        .bind(&refund.id)
+        .execute(&self.db)
+        .await
+        .map_err(|e| format!("Failed to claim refund {}: {}", refund.id, e))?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Send the recorded amount, less gas, from the deposit address to the
+    /// refund address. Later deposits to the address are not swept along.
+    async fn send(&self, refund: &DueRefund) -> Result<(String, Decimal), String> {
+        let network = &refund.refund_network;
+        // Signed for the test network in a sandbox deployment
+        let chain_id = evm_chain_id(network)
+            .map(|_| signing_chain_id(network))
+            .ok_or_else(|| format!("No chain id for {}", network))?;
+
+        let provider = match self.providers.get(network) {
+            Some(provider) => provider.clone(),
+            None => {
+                let rpc_url = evm_rpc_url(network).ok_or_else(|| format!("No RPC configured for {}", network))?;
+                Arc::new(HttpRpcClient::new(rpc_url)) as Arc<dyn BlockchainProvider>
+            }
+        };
+        let wallet_manager = WalletManager::new(WalletCrud::new(self.db.clone()), self.master_seed.clone(), provider);
+        wallet_manager
+            .process_refund_payout(refund.address_index, chain_id, &refund.refund_address, refund.refund_amount)
+            .await
+    }
+
+    /// Record the sent refund and move the swap to refunded, which notifies the user
+    async fn complete(&self, refund: &DueRefund, tx_hash: &str, sent: Decimal) -> Result<(), String> {
+        sqlx::query(
+            r#"
+            UPDATE refunds
+            SET status = 'COMPLETED', tx_status = 'SUBMITTED', tx_hash = ?, total_fee = ?,
+                last_error = NULL, completed_at = NOW()
+            WHERE id = ?
+            "#,
+        )
+        .bind(tx_hash)
+        .bind(refund.refund_amount - sent)
+        .bind(&refund.id)
+        .execute(&self.db)
+        .await
+        .map_err(|e| format!("Failed to complete refund {}: {}", refund.id, e))?;
+        self.history(refund, "PROCESSING", "COMPLETED", Some(tx_hash), None).await;
+
+        tracing::info!(
+            "↩️  Late deposit for swap {} refunded: {} on {} to {} (tx {})",
+            refund.swap_id, sent, refund.refund_network, refund.refund_address, tx_hash
+        );
+
+        let transition = Transition::to(SwapStatus::Refunded)
+            .actor(StatusActor::System)
+            .tx_hash_out(tx_hash)
+            .message(format!("Deposit received after expiry refunded on {}", refund.refund_network));
+        if let Err(e) = SwapStateMachine::new(self.db.clone()).advance(&refund.swap_id, &transition).await {
+            tracing::error!("Refund {} sent but swap {} not marked refunded: {}", refund.id, refund.swap_id, e);
+        }
+        Ok(())
+    }
+
+    /// Schedule a retry, or hand the refund to an admin once attempts run out
+    async fn fail(&self, refund: &DueRefund, error: &str) -> Result<(), String> {
+        let attempts = refund.attempt_number + 1;
+        let exhausted = attempts >= refund.max_attempts;
+        let status = if exhausted { "MANUAL" } else { "FAILED" };
+
+        sqlx::query(
+            r#"
+            UPDATE refunds
+            SET status = ?, last_error = ?, failure_reason = IF(?, ?, failure_reason),
+                next_retry_at = DATE_ADD(NOW(), INTERVAL ? SECOND)
+            WHERE id = ?
+            "#,
+        )
+        .bind(status)
+        .bind(error)
+        .bind(exhausted)
+        .bind("Refund attempts exhausted")
+        .bind(retry_delay_secs(attempts.max(0) as u32))
+        .bind(&refund.id)
+        .execute(&self.db)
+        .await
+        .map_err(|e| format!("Failed to record refund failure {}: {}", refund.id, e))?;
+        self.history(refund, "PROCESSING", status, None, Some(error)).await;
+        Ok(())
+    }
+
+    async fn history(&self, refund: &DueRefund, from: &str, to: &str, tx_hash: Option<&str>, error: Option<&str>) {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO refund_history (refund_id, from_status, to_status, tx_hash, error_message, triggered_by)
+            VALUES (?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&refund.id)
+        .bind(from)
+        .bind(to)
+        .bind(tx_hash)
+        .bind(error)
+        .bind(LATE_DEPOSIT_INITIATOR)
+        .execute(&self.db)
+        .await;
+        if let Err(e) = result {
+            tracing::error!("Failed to write refund history for {}: {}", refund.id, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn deposit() -> LateDeposit {
+        LateDeposit {
+            swap_id: "swap-1".to_string(),
+            network: "ethereum".to_string(),
+            currency: "eth".to_string(),
+            amount: Decimal::new(5, 1),
+            refund_address: Some("0x742d35Cc6634C0532925a3b844Bc454e4438f44e".to_string()),
+            native: true,
+        }
+    }
+
+    #[test]
+    fn test_manual_reason() {
+        assert_eq!(manual_reason(&deposit()), None);
+
+        let token = LateDeposit { native: false, currency: "usdt".to_string(), ..deposit() };
+        assert!(manual_reason(&token).unwrap().contains("USDT"));
+
+        let no_address = LateDeposit { refund_address: Some("  ".to_string()), ..deposit() };
+        assert_eq!(manual_reason(&no_address).as_deref(), Some("Swap has no refund address"));
+
+        let bitcoin = LateDeposit { refund_address: Some("bc1qxy2kgdygjrsqtzq2n0yrf2493p83kkfjhx0wlh".to_string()), ..deposit() };
+        assert!(manual_reason(&bitcoin).is_some());
+    }
+
+    #[test]
+    fn test_retry_delay_doubles_up_to_cap() {
+        assert_eq!(retry_delay_secs(1), 60);
+        assert_eq!(retry_delay_secs(2), 120);
+        assert_eq!(retry_delay_secs(3), 240);
+        assert_eq!(retry_delay_secs(40), MAX_RETRY_SECS);
+    }
+}
@@ -1,6 +1,7 @@
 mod types;
 mod config;
 mod calculator;
+pub mod late_deposits;
 
 pub use types::{
     SwapStatus, TimeoutStage, RefundStatus, Refund, RefundCalculation,
@@ -8,3 +9,4 @@ pub use types::{
 };
 pub use config::RefundConfig;
 pub use calculator::RefundCalculator;
+pub use late_deposits::{LateDeposit, LateDepositRefunder};
@@ -138,12 +138,22 @@ pub struct Refund {
     pub updated_at: DateTime<Utc>,
 }
 
-/// Refund calculation result
-#[derive(Debug, Clone)]
+/// Refund calculation result. Every deduction between the deposit and the
+/// refund is itemised so it can be shown to the user.
+#[derive(Debug, Clone, Serialize)]
 pub struct RefundCalculation {
     pub refund_amount: Decimal,
     pub deposit_amount: Decimal,
+    /// Currency of the deposit, and so of the refund
+    pub currency: String,
+    /// Total deducted: swap fee, platform fee (unless waived) and network fee
     pub fees_paid: Decimal,
+    /// The swap's recorded total fee
+    pub swap_fee: Decimal,
+    /// Platform fee on the swap; not deducted when waived
+    pub platform_fee: Decimal,
+    pub platform_fee_waived: bool,
+    /// Network fee of the refund transaction itself
     pub gas_cost_estimate: Decimal,
     pub is_economical: bool,
     pub reason: String,
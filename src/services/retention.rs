@@ -0,0 +1,342 @@
+use chrono::Utc;
+use sqlx::{MySql, Pool};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::services::metrics::collectors::DatabaseMetricsCollector;
+use crate::services::metrics::MetricsRegistry;
+
+const BATCH_SIZE: i64 = 500;
+/// Batches per artifact per pass, so a large backlog cannot starve the pool
+const MAX_BATCHES: usize = 50;
+/// Shortest retention window accepted. Purges only ever touch rows that
+/// expired (or were used/revoked) at least this long ago, so a typo in the
+/// configuration cannot delete anything a user could still redeem.
+pub const MIN_RETENTION: Duration = Duration::from_secs(3600);
+/// Secrets of dead artifacts are overwritten with this prefix plus the row id
+const SCRUBBED_PREFIX: &str = "scrubbed:";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum AuthArtifact {
+    EmailVerification,
+    PasswordReset,
+    RefreshToken,
+    OAuthLoginState,
+}
+
+impl AuthArtifact {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuthArtifact::EmailVerification => "email_verification",
+            AuthArtifact::PasswordReset => "password_reset",
+            AuthArtifact::RefreshToken => "refresh_token",
+            AuthArtifact::OAuthLoginState => "oauth_login_state",
+        }
+    }
+}
+
+/// How one artifact table is swept
+struct ArtifactRule {
+    artifact: AuthArtifact,
+    table: &'static str,
+    key: &'static str,
+    /// Column holding the token (or its hash); scrubbed as soon as the row
+    /// can no longer be redeemed. `None` when the secret is the key itself.
+    secret: Option<&'static str>,
+    /// Rows that can no longer be redeemed
+    dead: &'static str,
+    /// Rows past retention; every `?` is bound to the cutoff
+    expired_before: &'static str,
+}
+
+const RULES: &[ArtifactRule] = &[
+    ArtifactRule {
+        artifact: AuthArtifact::EmailVerification,
+        table: "email_verifications",
+        key: "id",
+        secret: Some("token"),
+        dead: "expires_at < NOW()",
+        expired_before: "expires_at < ?",
+    },
+    ArtifactRule {
+        artifact: AuthArtifact::PasswordReset,
+        table: "password_resets",
+        key: "id",
+        secret: Some("token_hash"),
+        dead: "used = TRUE OR expires_at < NOW()",
+        expired_before: "expires_at < ? OR (used = TRUE AND created_at < ?)",
+    },
+    ArtifactRule {
+        artifact: AuthArtifact::RefreshToken,
+        table: "refresh_tokens",
+        key: "id",
+        secret: Some("token_hash"),
+        dead: "revoked = TRUE OR expires_at < NOW()",
+        expired_before: "expires_at < ? OR (revoked = TRUE AND created_at < ?)",
+    },
+    ArtifactRule {
+        artifact: AuthArtifact::OAuthLoginState,
+        table: "oauth_login_states",
+        key: "state_hash",
+        secret: None,
+        dead: "expires_at < NOW()",
+        expired_before: "expires_at < ?",
+    },
+];
+
+/// How long dead auth artifacts are kept (for support and abuse
+/// investigations) before they are deleted
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetentionPolicy {
+    pub email_verifications: Duration,
+    pub password_resets: Duration,
+    pub refresh_tokens: Duration,
+    pub oauth_login_states: Duration,
+    pub interval: Duration,
+}
+
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        Self {
+            email_verifications: Duration::from_secs(7 * 86400),
+            password_resets: Duration::from_secs(30 * 86400),
+            refresh_tokens: Duration::from_secs(30 * 86400),
+            oauth_login_states: Duration::from_secs(86400),
+            interval: Duration::from_secs(3600),
+        }
+    }
+}
+
+impl RetentionPolicy {
+    pub fn from_env() -> Result<Self, String> {
+        let mut policy = Self::default();
+
+        let hours = |name: &str| -> Result<Option<Duration>, String> {
+            match std::env::var(name) {
+                Ok(val) => {
+                    let hours: u64 = val.parse().map_err(|e| format!("Invalid {}: {}", name, e))?;
+                    Ok(Some(Duration::from_secs(hours * 3600)))
+                }
+                Err(_) => Ok(None),
+            }
+        };
+
+        if let Some(d) = hours("AUTH_RETENTION_EMAIL_VERIFICATIONS_HOURS")? {
+            policy.email_verifications = d;
+        }
+        if let Some(d) = hours("AUTH_RETENTION_PASSWORD_RESETS_HOURS")? {
+            policy.password_resets = d;
+        }
+        if let Some(d) = hours("AUTH_RETENTION_REFRESH_TOKENS_HOURS")? {
+            policy.refresh_tokens = d;
+        }
+        if let Some(d) = hours("AUTH_RETENTION_OAUTH_STATES_HOURS")? {
+            policy.oauth_login_states = d;
+        }
+        if let Ok(val) = std::env::var("AUTH_RETENTION_INTERVAL_SECS") {
+            let secs: u64 = val.parse().map_err(|e| format!("Invalid AUTH_RETENTION_INTERVAL_SECS: {}", e))?;
+            policy.interval = Duration::from_secs(secs.max(60));
+        }
+
+        policy.validate()?;
+        Ok(policy)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        for artifact in RULES.iter().map(|r| r.artifact) {
+            if self.retention(artifact) < MIN_RETENTION {
+                return Err(format!(
+                    "Retention for {} must be at least {}h",
+                    artifact.as_str(),
+                    MIN_RETENTION.as_secs() / 3600
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn retention(&self, artifact: AuthArtifact) -> Duration {
+        match artifact {
+            AuthArtifact::EmailVerification => self.email_verifications,
+            AuthArtifact::PasswordReset => self.password_resets,
+            AuthArtifact::RefreshToken => self.refresh_tokens,
+            AuthArtifact::OAuthLoginState => self.oauth_login_states,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SweepCounts {
+    /// Rows whose secret was overwritten
+    pub anonymized: u64,
+    /// Rows deleted
+    pub purged: u64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RetentionReport {
+    pub counts: Vec<(AuthArtifact, SweepCounts)>,
+}
+
+impl RetentionReport {
+    pub fn get(&self, artifact: AuthArtifact) -> SweepCounts {
+        self.counts
+            .iter()
+            .find(|(a, _)| *a == artifact)
+            .map(|(_, c)| *c)
+            .unwrap_or_default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counts.iter().all(|(_, c)| *c == SweepCounts::default())
+    }
+}
+
+/// Sweeps expired email verifications, password resets, refresh tokens and
+/// OAuth login states. Tokens stop being stored as soon as they can no
+/// longer be redeemed; the rows themselves go once the retention window
+/// has passed.
+pub struct AuthRetentionSweeper {
+    db: Pool<MySql>,
+    policy: RetentionPolicy,
+    metrics: Option<DatabaseMetricsCollector>,
+}
+
+impl AuthRetentionSweeper {
+    pub fn new(db: Pool<MySql>, policy: RetentionPolicy) -> Self {
+        Self { db, policy, metrics: None }
+    }
+
+    pub fn with_metrics(mut self, metrics: Arc<MetricsRegistry>) -> Self {
+        self.metrics = Some(DatabaseMetricsCollector::new(metrics));
+        self
+    }
+
+    /// Start the background sweep loop
+    pub async fn run(&self) {
+        let mut interval = tokio::time::interval(self.policy.interval);
+
+        loop {
+            interval.tick().await;
+
+            match self.sweep().await {
+                Ok(report) if !report.is_empty() => tracing::info!("Auth retention sweep: {:?}", report),
+                Ok(_) => {}
+                Err(e) => tracing::error!("Auth retention sweep failed: {}", e),
+            }
+        }
+    }
+
+    /// Run one pass over every artifact table
+    pub async fn sweep(&self) -> Result<RetentionReport, sqlx::Error> {
+        let mut report = RetentionReport::default();
+
+        for rule in RULES {
+            let counts = SweepCounts {
+                anonymized: self.anonymize(rule).await?,
+                purged: self.purge(rule).await?,
+            };
+
+            if let Some(metrics) = &self.metrics {
+                metrics.record_retention(rule.artifact.as_str(), "anonymized", counts.anonymized);
+                metrics.record_retention(rule.artifact.as_str(), "purged", counts.purged);
+            }
+            report.counts.push((rule.artifact, counts));
+        }
+
+        Ok(report)
+    }
+
+    async fn anonymize(&self, rule: &ArtifactRule) -> Result<u64, sqlx::Error> {
+        let Some(secret) = rule.secret else {
+            return Ok(0);
+        };
+
+        let sql = format!(
+            "UPDATE {table} SET {secret} = CONCAT('{prefix}', {key}) \
+             WHERE ({dead}) AND {secret} NOT LIKE '{prefix}%' LIMIT ?",
+            table = rule.table,
+            secret = secret,
+            prefix = SCRUBBED_PREFIX,
+            key = rule.key,
+            dead = rule.dead,
+        );
+
+        let mut total = 0;
+        for _ in 0..MAX_BATCHES {
+            let affected = sqlx::query(&sql).bind(BATCH_SIZE).execute(&self.db).await?.rows_affected();
+            total += affected;
+            if affected < BATCH_SIZE as u64 {
+                break;
+            }
+        }
+        Ok(total)
+    }
+
+    async fn purge(&self, rule: &ArtifactRule) -> Result<u64, sqlx::Error> {
+        // Never closer to now than MIN_RETENTION, whatever the policy says
+        let retention = self.policy.retention(rule.artifact).max(MIN_RETENTION);
+        let cutoff = Utc::now() - chrono::Duration::from_std(retention).unwrap_or_else(|_| chrono::Duration::days(36500));
+        let sql = format!("DELETE FROM {} WHERE {} LIMIT ?", rule.table, rule.expired_before);
+        let placeholders = rule.expired_before.matches('?').count();
+
+        let mut total = 0;
+        for _ in 0..MAX_BATCHES {
+            let mut query = sqlx::query(&sql);
+            for _ in 0..placeholders {
+                query = query.bind(cutoff);
+            }
+            let affected = query.bind(BATCH_SIZE).execute(&self.db).await?.rows_affected();
+            total += affected;
+            if affected < BATCH_SIZE as u64 {
+                break;
+            }
+        }
+        Ok(total)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_policy_is_valid() {
+        assert!(RetentionPolicy::default().validate().is_ok());
+    }
+
+    #[test]
+    fn test_policy_rejects_short_retention() {
+        let policy = RetentionPolicy {
+            refresh_tokens: Duration::from_secs(60),
+            ..RetentionPolicy::default()
+        };
+        let err = policy.validate().unwrap_err();
+        assert!(err.contains("refresh_token"));
+    }
+
+    #[test]
+    fn test_every_artifact_has_one_rule() {
+        let mut artifacts: Vec<AuthArtifact> = RULES.iter().map(|r| r.artifact).collect();
+        artifacts.sort();
+        artifacts.dedup();
+        assert_eq!(artifacts.len(), RULES.len());
+
+        for rule in RULES {
+            // Purges must be bounded by the cutoff, not by NOW()
+            assert!(rule.expired_before.contains('?'), "{} purge is not bounded by the cutoff", rule.table);
+            assert!(!rule.expired_before.contains("NOW()"));
+        }
+    }
+
+    #[test]
+    fn test_report_lookup() {
+        let report = RetentionReport {
+            counts: vec![(AuthArtifact::RefreshToken, SweepCounts { anonymized: 2, purged: 1 })],
+        };
+        assert_eq!(report.get(AuthArtifact::RefreshToken).purged, 1);
+        assert_eq!(report.get(AuthArtifact::PasswordReset), SweepCounts::default());
+        assert!(!report.is_empty());
+        assert!(RetentionReport::default().is_empty());
+    }
+}
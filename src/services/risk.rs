@@ -0,0 +1,224 @@
+//! Risk engine. Chargebacks, fraud reports, stolen-funds traces and
+//! payouts to widely shared addresses are recorded against the account they concern, each with a weight. When the
+//! weights recorded inside the scoring window add up past a threshold the
+//! account is suspended, or banned past a higher one, automatically. The
+//! engine only ever tightens a restriction; lifting one is an operator's
+//! call.
+
+use chrono::{Duration, Utc};
+use sqlx::{MySql, Pool};
+use std::sync::OnceLock;
+
+use crate::modules::moderation::crud::{ModerationCrud, ModerationError, NewRiskSignal, StatusChange};
+use crate::modules::moderation::model::{AccountStatus, RiskSignal, RiskSignalKind, StatusSource};
+
+/// Weights and thresholds, from RISK_* settings
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RiskPolicy {
+    pub chargeback_weight: u32,
+    pub fraud_report_weight: u32,
+    pub stolen_funds_weight: u32,
+    pub address_reuse_weight: u32,
+    /// Signals older than this no longer count
+    pub window: Duration,
+    pub suspend_score: u32,
+    pub suspend_for: Duration,
+    pub ban_score: u32,
+}
+
+impl Default for RiskPolicy {
+    fn default() -> Self {
+        Self {
+            chargeback_weight: 40,
+            fraud_report_weight: 30,
+            stolen_funds_weight: 100,
+            address_reuse_weight: 50,
+            window: Duration::days(90),
+            suspend_score: 80,
+            suspend_for: Duration::days(7),
+            ban_score: 200,
+        }
+    }
+}
+
+impl RiskPolicy {
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            chargeback_weight: setting("RISK_CHARGEBACK_WEIGHT").unwrap_or(defaults.chargeback_weight),
+            fraud_report_weight: setting("RISK_FRAUD_REPORT_WEIGHT").unwrap_or(defaults.fraud_report_weight),
+            stolen_funds_weight: setting("RISK_STOLEN_FUNDS_WEIGHT").unwrap_or(defaults.stolen_funds_weight),
+            address_reuse_weight: setting("RISK_ADDRESS_REUSE_WEIGHT").unwrap_or(defaults.address_reuse_weight),
+            window: setting("RISK_WINDOW_DAYS").map(|d| Duration::days(d.into())).unwrap_or(defaults.window),
+            suspend_score: setting("RISK_SUSPEND_SCORE").unwrap_or(defaults.suspend_score),
+            suspend_for: setting("RISK_SUSPEND_HOURS")
+                .map(|h| Duration::hours(h.into()))
+                .unwrap_or(defaults.suspend_for),
+            ban_score: setting("RISK_BAN_SCORE").unwrap_or(defaults.ban_score),
+        }
+    }
+
+    /// Policy read from the environment once per process
+    pub fn global() -> &'static RiskPolicy {
+        static POLICY: OnceLock<RiskPolicy> = OnceLock::new();
+        POLICY.get_or_init(Self::from_env)
+    }
+
+    pub fn weight(&self, kind: RiskSignalKind) -> u32 {
+        match kind {
+            RiskSignalKind::Chargeback => self.chargeback_weight,
+            RiskSignalKind::FraudReport => self.fraud_report_weight,
+            RiskSignalKind::StolenFunds => self.stolen_funds_weight,
+            RiskSignalKind::AddressReuse => self.address_reuse_weight,
+        }
+    }
+
+    /// What an account with `score` inside the window should be held to
+    pub fn status_for(&self, score: u32) -> AccountStatus {
+        if score >= self.ban_score {
+            AccountStatus::Banned
+        } else if score >= self.suspend_score {
+            AccountStatus::Suspended
+        } else {
+            AccountStatus::Active
+        }
+    }
+}
+
+/// Positive integer setting; anything else falls back to the default
+fn setting(name: &str) -> Option<u32> {
+    std::env::var(name)
+        .ok()
+        .and_then(|v| v.trim().parse::<u32>().ok())
+        .filter(|v| *v > 0)
+}
+
+/// A recorded signal and what it led to
+#[derive(Debug, Clone)]
+pub struct RiskAssessment {
+    pub signal: RiskSignal,
+    pub score: u32,
+    /// Status the account was moved to, if any
+    pub applied: Option<AccountStatus>,
+}
+
+/// What a signal is about
+#[derive(Debug, Clone, Default)]
+pub struct SignalContext<'a> {
+    pub swap_id: Option<&'a str>,
+    pub detail: Option<&'a str>,
+    /// Admin who reported it; unset for automated sources
+    pub reported_by: Option<&'a str>,
+}
+
+pub struct RiskEngine {
+    crud: ModerationCrud,
+    policy: RiskPolicy,
+}
+
+impl RiskEngine {
+    pub fn new(db: Pool<MySql>) -> Self {
+        Self { crud: ModerationCrud::new(db), policy: RiskPolicy::global().clone() }
+    }
+
+    pub fn with_policy(mut self, policy: RiskPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    pub fn policy(&self) -> &RiskPolicy {
+        &self.policy
+    }
+
+    /// Record a signal against `user_id` and suspend or ban the account if
+    /// its score now calls for it
+    pub async fn report(
+        &self,
+        user_id: &str,
+        kind: RiskSignalKind,
+        context: &SignalContext<'_>,
+    ) -> Result<RiskAssessment, ModerationError> {
+        let signal = self
+            .crud
+            .record_signal(
+                user_id,
+                &NewRiskSignal {
+                    kind,
+                    weight: self.policy.weight(kind),
+                    swap_id: context.swap_id,
+                    detail: context.detail,
+                    reported_by: context.reported_by,
+                },
+            )
+            .await?;
+
+        let score = self.score(user_id).await?;
+        let status = self.policy.status_for(score);
+        if status == AccountStatus::Active {
+            return Ok(RiskAssessment { signal, score, applied: None });
+        }
+
+        let change = StatusChange {
+            status,
+            reason: Some(automatic_reason(status)),
+            until: (status == AccountStatus::Suspended).then(|| Utc::now() + self.policy.suspend_for),
+            source: StatusSource::Risk,
+            actor_id: None,
+        };
+        let applied = self.crud.escalate(user_id, &change).await?.then_some(status);
+        if let Some(status) = applied {
+            tracing::warn!(
+                "Account {} {} by the risk engine: score {} after a {} signal",
+                user_id,
+                status.as_str(),
+                score,
+                kind.as_str()
+            );
+        }
+
+        Ok(RiskAssessment { signal, score, applied })
+    }
+
+    /// Summed weight of the account's signals inside the window
+    pub async fn score(&self, user_id: &str) -> Result<u32, ModerationError> {
+        self.crud.risk_score(user_id, Utc::now() - self.policy.window).await
+    }
+}
+
+/// Reason shown to the user. Which reports were filed stays internal.
+fn automatic_reason(status: AccountStatus) -> &'static str {
+    match status {
+        AccountStatus::Banned => "Banned automatically after risk reports",
+        _ => "Suspended automatically after risk reports",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_for_score() {
+        let policy = RiskPolicy::default();
+        assert_eq!(policy.status_for(0), AccountStatus::Active);
+        assert_eq!(policy.status_for(policy.suspend_score - 1), AccountStatus::Active);
+        assert_eq!(policy.status_for(policy.suspend_score), AccountStatus::Suspended);
+        assert_eq!(policy.status_for(policy.ban_score), AccountStatus::Banned);
+    }
+
+    #[test]
+    fn test_default_weights() {
+        let policy = RiskPolicy::default();
+        let chargeback = policy.weight(RiskSignalKind::Chargeback);
+        // One chargeback can be a dispute; a second suspends
+        assert_eq!(policy.status_for(chargeback), AccountStatus::Active);
+        assert_eq!(policy.status_for(2 * chargeback), AccountStatus::Suspended);
+
+        let stolen_funds = policy.weight(RiskSignalKind::StolenFunds);
+        assert_eq!(policy.status_for(stolen_funds), AccountStatus::Suspended);
+        assert_eq!(
+            policy.status_for(2 * chargeback + stolen_funds + policy.weight(RiskSignalKind::FraudReport)),
+            AccountStatus::Banned
+        );
+    }
+}
@@ -25,6 +25,45 @@ pub struct RpcEndpoint {
     #[serde(default = "default_timeout")]
     pub timeout_ms: u64,
     pub auth: Option<RpcAuth>,
+    /// Region the endpoint serves from (e.g. `eu-west-1`); endpoints in the
+    /// instance's own region are preferred
+    #[serde(default)]
+    pub region: Option<String>,
+}
+
+/// Where an endpoint runs relative to this instance, in order of preference
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum EndpointLocality {
+    SameRegion,
+    /// The endpoint or the instance has no region, e.g. an anycast provider
+    Untagged,
+    CrossRegion,
+}
+
+impl EndpointLocality {
+    pub fn of(local_region: Option<&str>, endpoint_region: Option<&str>) -> Self {
+        match (local_region, endpoint_region) {
+            (Some(local), Some(region)) if local.eq_ignore_ascii_case(region.trim()) => EndpointLocality::SameRegion,
+            (Some(_), Some(_)) => EndpointLocality::CrossRegion,
+            _ => EndpointLocality::Untagged,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EndpointLocality::SameRegion => "same_region",
+            EndpointLocality::Untagged => "untagged",
+            EndpointLocality::CrossRegion => "cross_region",
+        }
+    }
+}
+
+/// This instance's region from RPC_REGION, lowercased
+pub fn local_region() -> Option<String> {
+    std::env::var("RPC_REGION")
+        .ok()
+        .map(|r| r.trim().to_lowercase())
+        .filter(|r| !r.is_empty())
 }
 
 fn default_priority() -> u8 {
@@ -126,6 +165,16 @@ fn substitute_env_vars(content: &str) -> String {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_endpoint_locality() {
+        assert_eq!(EndpointLocality::of(Some("eu-west-1"), Some("EU-West-1")), EndpointLocality::SameRegion);
+        assert_eq!(EndpointLocality::of(Some("eu-west-1"), Some("us-east-1")), EndpointLocality::CrossRegion);
+        assert_eq!(EndpointLocality::of(Some("eu-west-1"), None), EndpointLocality::Untagged);
+        assert_eq!(EndpointLocality::of(None, Some("us-east-1")), EndpointLocality::Untagged);
+        assert!(EndpointLocality::SameRegion < EndpointLocality::Untagged);
+        assert!(EndpointLocality::Untagged < EndpointLocality::CrossRegion);
+    }
+
     #[test]
     fn test_env_var_substitution() {
         std::env::set_var("TEST_VAR", "test_value");
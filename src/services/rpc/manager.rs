@@ -1,12 +1,15 @@
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 use serde_json::{json, Value};
 use serde::de::DeserializeOwned;
 
-use super::config::{RpcConfig, RpcEndpoint, LoadBalancingStrategy, RpcAuth};
+use super::config::{local_region, EndpointLocality, RpcConfig, RpcEndpoint, LoadBalancingStrategy, RpcAuth};
+use super::circuit_breaker::CircuitState;
 use super::health::{EndpointHealth, EndpointHealthStatus};
+use crate::services::events::{ops::endpoint_host, OpsEvent};
+use crate::services::metrics::collectors::RpcMetricsCollector;
 
 #[derive(Debug, thiserror::Error)]
 pub enum RpcError {
@@ -26,11 +29,15 @@ pub enum RpcError {
     CircuitBreakerOpen,
 }
 
+static GLOBAL_MANAGER: OnceLock<Arc<RpcManager>> = OnceLock::new();
+
 pub struct RpcManager {
     configs: HashMap<String, RpcConfig>,
     health_tracker: Arc<RwLock<HashMap<String, EndpointHealth>>>,
     client: reqwest::Client,
     round_robin_indices: Arc<RwLock<HashMap<String, usize>>>,
+    /// This instance's region; same-region endpoints are tried first
+    region: Option<String>,
 }
 
 impl RpcManager {
@@ -60,9 +67,36 @@ impl RpcManager {
                 .build()
                 .unwrap_or_default(),
             round_robin_indices: Arc::new(RwLock::new(HashMap::new())),
+            region: local_region(),
         }
     }
 
+    /// Prefer endpoints tagged with `region` instead of RPC_REGION's
+    pub fn with_region(mut self, region: Option<&str>) -> Self {
+        self.region = region.map(|r| r.trim().to_lowercase()).filter(|r| !r.is_empty());
+        self
+    }
+
+    pub fn region(&self) -> Option<&str> {
+        self.region.as_deref()
+    }
+
+    /// Where `endpoint` runs relative to this instance
+    pub fn locality(&self, endpoint: &RpcEndpoint) -> EndpointLocality {
+        EndpointLocality::of(self.region.as_deref(), endpoint.region.as_deref())
+    }
+
+    /// Make this manager the one reported by [`RpcManager::global`].
+    /// Only the first call has an effect.
+    pub fn install_global(self: &Arc<Self>) {
+        let _ = GLOBAL_MANAGER.set(self.clone());
+    }
+
+    /// The process's RPC manager, when RPC endpoints are configured
+    pub fn global() -> Option<&'static Arc<RpcManager>> {
+        GLOBAL_MANAGER.get()
+    }
+
     /// Select best endpoint based on health scores and strategy
     pub async fn select_endpoint(&self, chain: &str) -> Result<String, RpcError> {
         let config = self.configs.get(chain)
@@ -83,7 +117,15 @@ impl RpcManager {
             return Err(RpcError::NoHealthyEndpoints);
         }
         
-        // Sort by priority first
+        // Stay in the nearest region that has a usable endpoint, falling
+        // back to untagged and then other regions' endpoints
+        let nearest = candidates.iter().map(|ep| self.locality(ep)).min().unwrap_or(EndpointLocality::Untagged);
+        if nearest != EndpointLocality::SameRegion && self.region.is_some() {
+            tracing::debug!("No same-region RPC endpoint available for {}, using {}", chain, nearest.as_str());
+        }
+        candidates.retain(|ep| self.locality(ep) == nearest);
+        
+        // Then by priority
         candidates.sort_by_key(|ep| ep.priority);
         
         // Filter to only highest priority endpoints
@@ -161,6 +203,7 @@ impl RpcManager {
     }
 
     /// Execute RPC call with automatic failover
+    #[tracing::instrument(name = "rpc.call", skip(self, params), fields(chain = %chain, rpc.method = %method))]
     pub async fn call<T: DeserializeOwned>(
         &self,
         chain: &str,
@@ -185,6 +228,7 @@ impl RpcManager {
             let start = Instant::now();
             let result = self.execute_rpc_call(&url, method, params.clone(), endpoint).await;
             let latency = start.elapsed();
+            self.record_request_metrics(chain, endpoint, method, result.is_ok(), latency);
             
             match result {
                 Ok(response) => {
@@ -261,29 +305,51 @@ impl RpcManager {
         
         if let Some(h) = health.get_mut(url) {
             let latency_ms = latency.as_millis() as u64;
+            let was_open = h.circuit_breaker.state == CircuitState::Open;
             
             if success {
                 h.record_success(latency_ms, block_height);
             } else {
                 h.record_failure(latency_ms);
             }
+
+            if !was_open && h.circuit_breaker.state == CircuitState::Open {
+                OpsEvent::CircuitBreakerOpened {
+                    component: "rpc".to_string(),
+                    target: self.chain_of(url).unwrap_or_default(),
+                    endpoint: endpoint_host(url),
+                }
+                .publish();
+            }
         }
     }
 
+    /// Export a call, with the endpoint's region so cross-region traffic shows
+    fn record_request_metrics(&self, chain: &str, endpoint: &RpcEndpoint, method: &str, success: bool, latency: Duration) {
+        let Some(metrics) = RpcMetricsCollector::global() else {
+            return;
+        };
+        let host = endpoint_host(&endpoint.url).unwrap_or_default();
+        let status = if success { "success" } else { "error" };
+        metrics.record_rpc_request(chain, &host, method, status, latency.as_secs_f64());
+        metrics.record_region_request(
+            chain,
+            endpoint.region.as_deref().unwrap_or("untagged"),
+            self.locality(endpoint).as_str(),
+        );
+    }
+
+    fn chain_of(&self, url: &str) -> Option<String> {
+        self.configs
+            .iter()
+            .find(|(_, config)| config.endpoints.iter().any(|e| e.url == url))
+            .map(|(chain, _)| chain.clone())
+    }
+
     /// Background health check loop
     pub async fn health_check_loop(self: Arc<Self>) {
         loop {
-            for (chain, config) in &self.configs {
-                for endpoint in &config.endpoints {
-                    let result = self.check_endpoint_health(&endpoint.url, chain).await;
-                    
-                    let latency = result.latency;
-                    let success = result.success;
-                    let block_height = result.block_height;
-                    
-                    self.record_result(&endpoint.url, latency, success, block_height).await;
-                }
-            }
+            self.check_all_endpoints().await;
             
             // Sleep based on shortest health check interval
             let min_interval = self.configs.values()
@@ -295,6 +361,20 @@ impl RpcManager {
         }
     }
 
+    /// One health check pass over every endpoint, recording the results.
+    /// Returns (chain, url, healthy) per endpoint.
+    pub async fn check_all_endpoints(&self) -> Vec<(String, String, bool)> {
+        let mut results = Vec::new();
+        for (chain, config) in &self.configs {
+            for endpoint in &config.endpoints {
+                let result = self.check_endpoint_health(&endpoint.url, chain).await;
+                self.record_result(&endpoint.url, result.latency, result.success, result.block_height).await;
+                results.push((chain.clone(), endpoint.url.clone(), result.success));
+            }
+        }
+        results
+    }
+
     /// Check endpoint health
     async fn check_endpoint_health(&self, url: &str, chain: &str) -> HealthCheckResult {
         let start = Instant::now();
@@ -340,6 +420,11 @@ impl RpcManager {
         }
     }
 
+    /// Chains with endpoints configured
+    pub fn chains(&self) -> Vec<String> {
+        self.configs.keys().cloned().collect()
+    }
+
     /// Get current health status for all endpoints
     pub async fn get_health_status(&self, chain: &str) -> Vec<EndpointHealthStatus> {
         let config = match self.configs.get(chain) {
@@ -0,0 +1,578 @@
+//! Settings that can change without a restart. Every known setting has a
+//! built-in default, overridden in turn by its environment variable, the
+//! settings file named by RUNTIME_CONFIG_FILE and the `runtime_settings`
+//! table. `ConfigReloader` re-reads the file and table on an interval and
+//! publishes a new snapshot whenever an effective value changes; services
+//! hold a receiver from `runtime_config().subscribe()` or read
+//! `runtime_config().current()` and apply changes live.
+//!
+//! The settings file is a JSON object, flat (`{"rate_limit.burst": 20}`) or
+//! nested (`{"rate_limit": {"burst": 20}}`). Values that are unknown or fail
+//! validation are ignored, keeping the layer below, and are listed by
+//! GET /admin/config.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::{MySql, Pool};
+use tokio::sync::watch;
+
+use crate::services::monitor::strategy::PollingStrategy;
+use crate::services::rate_limit::RateLimitQuota;
+
+const DEFAULT_RELOAD_INTERVAL: Duration = Duration::from_secs(30);
+
+static SERVICE: OnceLock<ConfigService> = OnceLock::new();
+
+pub const RATE_LIMIT_BURST: &str = "rate_limit.burst";
+pub const RATE_LIMIT_REPLENISH_SECS: &str = "rate_limit.replenish_secs";
+pub const MONITOR_TICK_SECS: &str = "monitor.tick_secs";
+pub const MONITOR_COST_PER_POLL: &str = "monitor.cost_per_poll";
+pub const MONITOR_COST_PER_DELAY_SEC: &str = "monitor.cost_per_delay_sec";
+pub const RATES_DEADLINE_MS: &str = "rates.deadline_ms";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SettingKind {
+    /// Whole number of at least 1
+    PositiveInteger,
+    /// Number above 0
+    PositiveNumber,
+}
+
+impl SettingKind {
+    fn check(&self, value: &str) -> Result<(), String> {
+        let valid = match self {
+            SettingKind::PositiveInteger => value.parse::<u32>().is_ok_and(|n| n >= 1),
+            SettingKind::PositiveNumber => value.parse::<f64>().is_ok_and(|n| n.is_finite() && n > 0.0),
+        };
+        if valid {
+            return Ok(());
+        }
+        Err(match self {
+            SettingKind::PositiveInteger => format!("'{}' is not a whole number of at least 1", value),
+            SettingKind::PositiveNumber => format!("'{}' is not a number above 0", value),
+        })
+    }
+}
+
+/// A setting services read live
+#[derive(Debug, Clone, Copy)]
+pub struct SettingDef {
+    pub key: &'static str,
+    /// Environment variable overriding the default
+    pub env: &'static str,
+    pub default: &'static str,
+    pub description: &'static str,
+    kind: SettingKind,
+}
+
+pub const SETTINGS: &[SettingDef] = &[
+    SettingDef {
+        key: RATE_LIMIT_BURST,
+        env: "RATE_LIMIT_BURST",
+        default: "10",
+        description: "Requests a client can make before the HTTP rate limit applies",
+        kind: SettingKind::PositiveInteger,
+    },
+    SettingDef {
+        key: RATE_LIMIT_REPLENISH_SECS,
+        env: "RATE_LIMIT_REPLENISH_SECS",
+        default: "60",
+        description: "Seconds for a rate-limited client to earn back one request",
+        kind: SettingKind::PositiveInteger,
+    },
+    SettingDef {
+        key: MONITOR_TICK_SECS,
+        env: "MONITOR_TICK_SECS",
+        default: "10",
+        description: "Seconds between swap monitor passes over due polls",
+        kind: SettingKind::PositiveInteger,
+    },
+    SettingDef {
+        key: MONITOR_COST_PER_POLL,
+        env: "MONITOR_COST_PER_POLL",
+        default: "1.0",
+        description: "Relative cost of one provider status poll",
+        kind: SettingKind::PositiveNumber,
+    },
+    SettingDef {
+        key: MONITOR_COST_PER_DELAY_SEC,
+        env: "MONITOR_COST_PER_DELAY_SEC",
+        default: "0.05",
+        description: "Relative cost of noticing a swap's completion one second late",
+        kind: SettingKind::PositiveNumber,
+    },
+    SettingDef {
+        key: RATES_DEADLINE_MS,
+        env: "RATES_DEADLINE_MS",
+        default: "1500",
+        description: "Milliseconds GET /swap/rates waits for provider adapters before serving what arrived",
+        kind: SettingKind::PositiveInteger,
+    },
+];
+
+fn setting(key: &str) -> Option<&'static SettingDef> {
+    SETTINGS.iter().find(|def| def.key == key)
+}
+
+/// Where an effective value came from, lowest precedence first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SettingSource {
+    Default,
+    Env,
+    File,
+    Database,
+}
+
+// =============================================================================
+// SNAPSHOT
+// =============================================================================
+
+#[derive(Debug, Clone, PartialEq)]
+struct EffectiveValue {
+    value: String,
+    source: SettingSource,
+}
+
+/// Effective settings at one point in time
+#[derive(Debug, Clone)]
+pub struct RuntimeConfig {
+    values: BTreeMap<&'static str, EffectiveValue>,
+    rejected: Vec<RejectedSetting>,
+    loaded_at: DateTime<Utc>,
+}
+
+impl Default for RuntimeConfig {
+    fn default() -> Self {
+        Self::resolve(&[])
+    }
+}
+
+impl RuntimeConfig {
+    /// Apply `layers` in order over the defaults
+    pub fn resolve(layers: &[(SettingSource, &HashMap<String, String>)]) -> Self {
+        let mut values: BTreeMap<&'static str, EffectiveValue> = SETTINGS
+            .iter()
+            .map(|def| (def.key, EffectiveValue { value: def.default.to_string(), source: SettingSource::Default }))
+            .collect();
+        let mut rejected = Vec::new();
+
+        for (source, layer) in layers {
+            let mut keys: Vec<&String> = layer.keys().collect();
+            keys.sort();
+            for key in keys {
+                let value = layer[key].trim();
+                let Some(def) = setting(key) else {
+                    rejected.push(RejectedSetting::new(key, *source, "unknown setting".to_string()));
+                    continue;
+                };
+                match def.kind.check(value) {
+                    Ok(()) => {
+                        values.insert(def.key, EffectiveValue { value: value.to_string(), source: *source });
+                    }
+                    Err(reason) => rejected.push(RejectedSetting::new(key, *source, reason)),
+                }
+            }
+        }
+
+        Self { values, rejected, loaded_at: Utc::now() }
+    }
+
+    /// Whether both snapshots have the same effective values
+    pub fn same_values(&self, other: &RuntimeConfig) -> bool {
+        self.values == other.values && self.rejected == other.rejected
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.values.get(key).map(|v| v.value.as_str())
+    }
+
+    pub fn source(&self, key: &str) -> Option<SettingSource> {
+        self.values.get(key).map(|v| v.source)
+    }
+
+    /// Values are validated on load, so only an unregistered key falls
+    /// through to 0
+    fn integer(&self, key: &str) -> u32 {
+        self.get(key).and_then(|v| v.parse().ok()).unwrap_or(0)
+    }
+
+    fn number(&self, key: &str) -> f64 {
+        self.get(key).and_then(|v| v.parse().ok()).unwrap_or(0.0)
+    }
+
+    /// Quota of the public HTTP API, per client address
+    pub fn rate_limit_quota(&self) -> RateLimitQuota {
+        RateLimitQuota {
+            burst: self.integer(RATE_LIMIT_BURST),
+            replenish_every: Duration::from_secs(self.integer(RATE_LIMIT_REPLENISH_SECS) as u64),
+        }
+    }
+
+    pub fn monitor_tick(&self) -> Duration {
+        Duration::from_secs(self.integer(MONITOR_TICK_SECS) as u64)
+    }
+
+    /// How long a rates request waits for provider adapters
+    pub fn rates_deadline(&self) -> Duration {
+        Duration::from_millis(self.integer(RATES_DEADLINE_MS) as u64)
+    }
+
+    pub fn polling_strategy(&self) -> PollingStrategy {
+        PollingStrategy::new(self.number(MONITOR_COST_PER_POLL), self.number(MONITOR_COST_PER_DELAY_SEC))
+    }
+
+    pub fn report(&self, file: Option<String>) -> EffectiveConfigResponse {
+        EffectiveConfigResponse {
+            settings: SETTINGS
+                .iter()
+                .map(|def| {
+                    let effective = &self.values[def.key];
+                    EffectiveSettingResponse {
+                        key: def.key.to_string(),
+                        value: effective.value.clone(),
+                        source: effective.source,
+                        default: def.default.to_string(),
+                        env: def.env.to_string(),
+                        description: def.description.to_string(),
+                    }
+                })
+                .collect(),
+            rejected: self.rejected.clone(),
+            file,
+            loaded_at: self.loaded_at,
+        }
+    }
+}
+
+// =============================================================================
+// REPORT
+// =============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct EffectiveConfigResponse {
+    pub settings: Vec<EffectiveSettingResponse>,
+    /// File and database values that were ignored
+    pub rejected: Vec<RejectedSetting>,
+    /// RUNTIME_CONFIG_FILE, when set
+    pub file: Option<String>,
+    pub loaded_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct EffectiveSettingResponse {
+    pub key: String,
+    pub value: String,
+    pub source: SettingSource,
+    pub default: String,
+    pub env: String,
+    pub description: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct RejectedSetting {
+    pub key: String,
+    pub source: SettingSource,
+    pub reason: String,
+}
+
+impl RejectedSetting {
+    fn new(key: &str, source: SettingSource, reason: String) -> Self {
+        Self { key: key.to_string(), source, reason }
+    }
+}
+
+// =============================================================================
+// LAYERS
+// =============================================================================
+
+/// Environment variables of the known settings, by setting key
+fn env_layer() -> HashMap<String, String> {
+    SETTINGS
+        .iter()
+        .filter_map(|def| {
+            let value = std::env::var(def.env).ok().filter(|v| !v.trim().is_empty())?;
+            Some((def.key.to_string(), value))
+        })
+        .collect()
+}
+
+/// Settings file contents, nested objects flattened into dotted keys
+pub fn parse_settings_file(contents: &str) -> Result<HashMap<String, String>, String> {
+    let root: Value = serde_json::from_str(contents).map_err(|e| format!("Invalid settings file: {}", e))?;
+    let Value::Object(root) = root else {
+        return Err("Invalid settings file: expected a JSON object".to_string());
+    };
+
+    fn flatten(prefix: &str, object: &serde_json::Map<String, Value>, out: &mut HashMap<String, String>) -> Result<(), String> {
+        for (name, value) in object {
+            let key = if prefix.is_empty() { name.clone() } else { format!("{}.{}", prefix, name) };
+            match value {
+                Value::Object(nested) => flatten(&key, nested, out)?,
+                Value::String(s) => {
+                    out.insert(key, s.clone());
+                }
+                Value::Number(n) => {
+                    out.insert(key, n.to_string());
+                }
+                Value::Bool(b) => {
+                    out.insert(key, b.to_string());
+                }
+                _ => return Err(format!("Invalid settings file: {} must be a string, number or boolean", key)),
+            }
+        }
+        Ok(())
+    }
+
+    let mut values = HashMap::new();
+    flatten("", &root, &mut values)?;
+    Ok(values)
+}
+
+async fn database_layer(db: &Pool<MySql>) -> Result<HashMap<String, String>, String> {
+    let rows: Vec<(String, String)> = sqlx::query_as("SELECT setting_key, setting_value FROM runtime_settings")
+        .fetch_all(db)
+        .await
+        .map_err(|e| format!("Failed to read runtime settings: {}", e))?;
+    Ok(rows.into_iter().collect())
+}
+
+// =============================================================================
+// SERVICE
+// =============================================================================
+
+/// Holds the current snapshot and notifies subscribers of changes
+pub struct ConfigService {
+    file: Option<PathBuf>,
+    /// Database values from the last successful read, kept so a file-only
+    /// reload doesn't drop them
+    database: std::sync::Mutex<HashMap<String, String>>,
+    tx: watch::Sender<Arc<RuntimeConfig>>,
+}
+
+impl ConfigService {
+    pub fn new(file: Option<PathBuf>) -> Self {
+        let (tx, _) = watch::channel(Arc::new(RuntimeConfig::default()));
+        Self { file, database: std::sync::Mutex::new(HashMap::new()), tx }
+    }
+
+    /// Environment and RUNTIME_CONFIG_FILE; database settings arrive with
+    /// the first reload. An unreadable file is logged and skipped.
+    pub fn from_env() -> Self {
+        let file = std::env::var("RUNTIME_CONFIG_FILE")
+            .ok()
+            .filter(|v| !v.trim().is_empty())
+            .map(|v| PathBuf::from(v.trim()));
+        let service = Self::new(file);
+        let file_values = service.read_file_blocking().unwrap_or_else(|e| {
+            tracing::warn!("{}", e);
+            HashMap::new()
+        });
+        service.publish(&file_values);
+        service
+    }
+
+    pub fn file(&self) -> Option<String> {
+        self.file.as_ref().map(|path| path.display().to_string())
+    }
+
+    pub fn current(&self) -> Arc<RuntimeConfig> {
+        self.tx.borrow().clone()
+    }
+
+    /// Receiver marked changed whenever an effective value changes
+    pub fn subscribe(&self) -> watch::Receiver<Arc<RuntimeConfig>> {
+        self.tx.subscribe()
+    }
+
+    fn read_file_blocking(&self) -> Result<HashMap<String, String>, String> {
+        let Some(path) = &self.file else {
+            return Ok(HashMap::new());
+        };
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read settings file {}: {}", path.display(), e))?;
+        parse_settings_file(&contents)
+    }
+
+    async fn read_file(&self) -> Result<HashMap<String, String>, String> {
+        let Some(path) = &self.file else {
+            return Ok(HashMap::new());
+        };
+        let contents = tokio::fs::read_to_string(path)
+            .await
+            .map_err(|e| format!("Failed to read settings file {}: {}", path.display(), e))?;
+        parse_settings_file(&contents)
+    }
+
+    /// Re-read the environment, file and database. A layer that can't be
+    /// read keeps its previous values. Returns whether anything changed.
+    pub async fn reload(&self, db: &Pool<MySql>) -> Result<bool, String> {
+        let file_values = self.read_file().await?;
+        match database_layer(db).await {
+            Ok(values) => *self.database.lock().unwrap() = values,
+            Err(e) => {
+                self.publish(&file_values);
+                return Err(e);
+            }
+        }
+        Ok(self.publish(&file_values))
+    }
+
+    /// Resolve the layers and notify subscribers if the result differs
+    fn publish(&self, file_values: &HashMap<String, String>) -> bool {
+        let env = env_layer();
+        let database = self.database.lock().unwrap().clone();
+        let next = RuntimeConfig::resolve(&[
+            (SettingSource::Env, &env),
+            (SettingSource::File, file_values),
+            (SettingSource::Database, &database),
+        ]);
+        self.tx.send_if_modified(|current| {
+            if current.same_values(&next) {
+                return false;
+            }
+            *current = Arc::new(next);
+            true
+        })
+    }
+}
+
+/// Process-wide settings, loaded from the environment and file on first use
+pub fn runtime_config() -> &'static ConfigService {
+    SERVICE.get_or_init(ConfigService::from_env)
+}
+
+// =============================================================================
+// RELOADER
+// =============================================================================
+
+/// Re-reads the settings file and `runtime_settings` table so edits apply
+/// without a restart
+pub struct ConfigReloader {
+    db: Pool<MySql>,
+    interval: Duration,
+}
+
+impl ConfigReloader {
+    pub fn new(db: Pool<MySql>) -> Self {
+        Self { db, interval: reload_interval() }
+    }
+
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    pub async fn run(&self) {
+        let mut interval = tokio::time::interval(self.interval);
+        loop {
+            interval.tick().await;
+            self.reload().await;
+        }
+    }
+
+    /// One pass; returns whether the effective settings changed
+    pub async fn reload(&self) -> bool {
+        let config = runtime_config();
+        match config.reload(&self.db).await {
+            Ok(true) => {
+                let current = config.current();
+                let summary: Vec<String> = SETTINGS
+                    .iter()
+                    .filter_map(|def| Some(format!("{}={}", def.key, current.get(def.key)?)))
+                    .collect();
+                tracing::info!("Runtime settings changed: {}", summary.join(", "));
+                for rejected in &current.rejected {
+                    tracing::warn!("Ignoring runtime setting {} from {:?}: {}", rejected.key, rejected.source, rejected.reason);
+                }
+                true
+            }
+            Ok(false) => false,
+            Err(e) => {
+                tracing::warn!("Runtime settings reload failed: {}", e);
+                false
+            }
+        }
+    }
+}
+
+/// RUNTIME_CONFIG_RELOAD_SECS, default 30
+fn reload_interval() -> Duration {
+    std::env::var("RUNTIME_CONFIG_RELOAD_SECS")
+        .ok()
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .filter(|secs| *secs > 0)
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_RELOAD_INTERVAL)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::rate_limit::HTTP_QUOTA;
+
+    fn layer(entries: &[(&str, &str)]) -> HashMap<String, String> {
+        entries.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn test_defaults_are_valid_and_match_built_in_quota() {
+        for def in SETTINGS {
+            assert!(def.kind.check(def.default).is_ok(), "{}", def.key);
+        }
+        let config = RuntimeConfig::default();
+        assert_eq!(config.rate_limit_quota(), HTTP_QUOTA);
+        assert_eq!(config.monitor_tick(), Duration::from_secs(10));
+        assert_eq!(config.source(RATE_LIMIT_BURST), Some(SettingSource::Default));
+    }
+
+    #[test]
+    fn test_later_layers_win_and_invalid_values_are_ignored() {
+        let env = layer(&[(RATE_LIMIT_BURST, "20"), (MONITOR_TICK_SECS, "5")]);
+        let file = layer(&[(RATE_LIMIT_BURST, "30"), (MONITOR_TICK_SECS, "0")]);
+        let database = layer(&[(RATE_LIMIT_BURST, "40"), ("pricing.margin", "0.01")]);
+        let config = RuntimeConfig::resolve(&[
+            (SettingSource::Env, &env),
+            (SettingSource::File, &file),
+            (SettingSource::Database, &database),
+        ]);
+
+        assert_eq!(config.rate_limit_quota().burst, 40);
+        assert_eq!(config.source(RATE_LIMIT_BURST), Some(SettingSource::Database));
+        // The file's 0 is rejected, so the environment's value stands
+        assert_eq!(config.monitor_tick(), Duration::from_secs(5));
+        assert_eq!(config.source(MONITOR_TICK_SECS), Some(SettingSource::Env));
+
+        let rejected: Vec<&str> = config.rejected.iter().map(|r| r.key.as_str()).collect();
+        assert_eq!(rejected, vec![MONITOR_TICK_SECS, "pricing.margin"]);
+    }
+
+    #[test]
+    fn test_parse_settings_file_flattens_nested_objects() {
+        let values = parse_settings_file(r#"{"rate_limit": {"burst": 25}, "monitor.cost_per_poll": "2.5"}"#).unwrap();
+        assert_eq!(values[RATE_LIMIT_BURST], "25");
+        assert_eq!(values[MONITOR_COST_PER_POLL], "2.5");
+
+        assert!(parse_settings_file("[1, 2]").is_err());
+        assert!(parse_settings_file(r#"{"rate_limit": {"burst": [1]}}"#).is_err());
+    }
+
+    #[test]
+    fn test_publish_notifies_only_on_change() {
+        let service = ConfigService::new(None);
+        let mut rx = service.subscribe();
+        assert!(!service.publish(&HashMap::new()));
+        assert!(!rx.has_changed().unwrap());
+
+        assert!(service.publish(&layer(&[(RATE_LIMIT_REPLENISH_SECS, "30")])));
+        assert!(rx.has_changed().unwrap());
+        assert_eq!(rx.borrow_and_update().rate_limit_quota().replenish_every, Duration::from_secs(30));
+    }
+}
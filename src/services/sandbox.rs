@@ -0,0 +1,247 @@
+//! Sandbox deployments run the whole swap flow on test networks: quotes and
+//! orders go to a Trocador-compatible sandbox API, deposits are watched and
+//! payouts are sent through test network RPCs, and EVM transactions are
+//! signed for the test network's chain id. Only providers configured with
+//! sandbox support are quoted, and every swap is recorded as a sandbox swap,
+//! so staging can be exercised end to end without real funds.
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use crate::modules::recovery::crud::evm_chain_id;
+use crate::services::blockchain::listener::EVM_RPC_ENV_VARS;
+
+/// Test network used for a mainnet when SANDBOX_NETWORKS does not name one
+const DEFAULT_TEST_NETWORKS: &[(&str, &str, Option<u32>)] = &[
+    ("ethereum", "sepolia", Some(11_155_111)),
+    ("polygon", "amoy", Some(80_002)),
+    ("bsc", "bsc-testnet", Some(97)),
+    ("arbitrum", "arbitrum-sepolia", Some(421_614)),
+    ("optimism", "optimism-sepolia", Some(11_155_420)),
+    ("base", "base-sepolia", Some(84_532)),
+    ("bitcoin", "testnet3", None),
+    ("solana", "devnet", None),
+];
+
+/// P2PKH version bytes for Bitcoin mainnet and testnet
+const BITCOIN_MAINNET_P2PKH: u8 = 0x00;
+const BITCOIN_TESTNET_P2PKH: u8 = 0x6f;
+
+static CONFIG: OnceLock<SandboxConfig> = OnceLock::new();
+
+/// The test network standing in for one mainnet
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestNetwork {
+    /// e.g. `sepolia`, `testnet3`, `devnet`
+    pub name: String,
+    /// EIP-155 chain id, for EVM networks
+    pub chain_id: Option<u32>,
+    pub rpc_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SandboxConfig {
+    pub enabled: bool,
+    /// Trocador-compatible API that quotes and places test network orders
+    pub api_url: Option<String>,
+    /// Key for `api_url`; TROCADOR_API_KEY is used when unset
+    pub api_key: Option<String>,
+    /// Provider id to the mainnet networks it serves on their test networks
+    pub providers: HashMap<String, Vec<String>>,
+    /// Mainnet network name to its test network
+    pub networks: HashMap<String, TestNetwork>,
+}
+
+impl SandboxConfig {
+    /// Read from SANDBOX_MODE, SANDBOX_API_URL, SANDBOX_API_KEY,
+    /// SANDBOX_PROVIDERS (`changenow=ethereum|bitcoin,...`),
+    /// SANDBOX_NETWORKS (`ethereum=sepolia:11155111,...`, overriding the
+    /// built-in test networks) and SANDBOX_RPC_URLS (`ethereum=https://...`)
+    pub fn from_env() -> Result<Self, String> {
+        let var = |name: &str| std::env::var(name).ok().filter(|v| !v.trim().is_empty());
+        let enabled = var("SANDBOX_MODE")
+            .map(|v| matches!(v.trim().to_ascii_lowercase().as_str(), "1" | "true" | "yes"))
+            .unwrap_or(false);
+        if !enabled {
+            return Ok(Self::default());
+        }
+
+        let mut networks: HashMap<String, TestNetwork> = DEFAULT_TEST_NETWORKS
+            .iter()
+            .map(|(mainnet, name, chain_id)| {
+                (mainnet.to_string(), TestNetwork { name: name.to_string(), chain_id: *chain_id, rpc_url: None })
+            })
+            .collect();
+        for (mainnet, value) in pairs(var("SANDBOX_NETWORKS").as_deref(), "SANDBOX_NETWORKS")? {
+            let (name, chain_id) = match value.split_once(':') {
+                Some((name, id)) => {
+                    let id = id.trim().parse::<u32>().map_err(|_| format!("Invalid SANDBOX_NETWORKS chain id: {}", id))?;
+                    (name.trim().to_string(), Some(id))
+                }
+                None => (value, None),
+            };
+            networks.insert(mainnet, TestNetwork { name, chain_id, rpc_url: None });
+        }
+        for (mainnet, url) in pairs(var("SANDBOX_RPC_URLS").as_deref(), "SANDBOX_RPC_URLS")? {
+            let network = networks
+                .get_mut(&mainnet)
+                .ok_or_else(|| format!("Invalid SANDBOX_RPC_URLS: no test network for {}", mainnet))?;
+            network.rpc_url = Some(url);
+        }
+
+        let mut providers = HashMap::new();
+        for (provider, served) in pairs(var("SANDBOX_PROVIDERS").as_deref(), "SANDBOX_PROVIDERS")? {
+            let served: Vec<String> = served
+                .split('|')
+                .map(|n| n.trim().to_lowercase())
+                .filter(|n| !n.is_empty())
+                .collect();
+            if let Some(unknown) = served.iter().find(|n| !networks.contains_key(*n)) {
+                return Err(format!("Invalid SANDBOX_PROVIDERS: no test network for {}", unknown));
+            }
+            providers.insert(provider, served);
+        }
+
+        let config = Self {
+            enabled,
+            api_url: var("SANDBOX_API_URL").map(|url| url.trim().trim_end_matches('/').to_string()),
+            api_key: var("SANDBOX_API_KEY"),
+            providers,
+            networks,
+        };
+        config.validate()?;
+        Ok(config)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.api_url.is_none() {
+            return Err("SANDBOX_API_URL is required in sandbox mode".to_string());
+        }
+        if self.providers.is_empty() {
+            return Err("SANDBOX_PROVIDERS is required in sandbox mode".to_string());
+        }
+        let evm = |mainnet: &str| EVM_RPC_ENV_VARS.iter().any(|(chain, _)| *chain == mainnet);
+        if let Some((mainnet, network)) = self.networks.iter().find(|(m, n)| evm(m) && n.chain_id.is_none()) {
+            return Err(format!("Invalid SANDBOX_NETWORKS: {} ({}) needs a chain id", network.name, mainnet));
+        }
+        Ok(())
+    }
+
+    /// Process-wide settings, read once. Invalid settings panic rather than
+    /// let a staging deployment fall through to mainnet.
+    pub fn global() -> &'static SandboxConfig {
+        CONFIG.get_or_init(|| Self::from_env().expect("Invalid sandbox configuration"))
+    }
+
+    /// Whether `provider` can run a swap between these mainnet networks on
+    /// their test networks
+    pub fn supports(&self, provider: &str, network_from: &str, network_to: &str) -> bool {
+        let provider = provider.trim().to_lowercase();
+        self.providers.get(&provider).is_some_and(|served| {
+            [network_from, network_to]
+                .iter()
+                .all(|n| served.iter().any(|s| s.eq_ignore_ascii_case(n)))
+        })
+    }
+
+    pub fn test_network(&self, network: &str) -> Option<&TestNetwork> {
+        if !self.enabled {
+            return None;
+        }
+        self.networks.get(&network.to_lowercase())
+    }
+}
+
+/// `key=value` pairs separated by commas, keys lower-cased
+fn pairs(value: Option<&str>, name: &str) -> Result<Vec<(String, String)>, String> {
+    let Some(value) = value else {
+        return Ok(Vec::new());
+    };
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let (key, value) = entry.split_once('=').ok_or_else(|| format!("Invalid {}: {}", name, entry))?;
+            Ok((key.trim().to_lowercase(), value.trim().to_string()))
+        })
+        .collect()
+}
+
+/// EIP-155 chain id for transactions on `chain`: its test network's in a
+/// sandbox deployment, mainnet's otherwise
+pub fn signing_chain_id(chain: &str) -> u32 {
+    SandboxConfig::global()
+        .test_network(chain)
+        .and_then(|n| n.chain_id)
+        .or_else(|| evm_chain_id(chain))
+        .unwrap_or(1)
+}
+
+/// Version byte of the P2PKH addresses we derive for Bitcoin
+pub fn bitcoin_p2pkh_version() -> u8 {
+    if SandboxConfig::global().test_network("bitcoin").is_some() {
+        BITCOIN_TESTNET_P2PKH
+    } else {
+        BITCOIN_MAINNET_P2PKH
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> SandboxConfig {
+        let mut providers = HashMap::new();
+        providers.insert("changenow".to_string(), vec!["ethereum".to_string(), "bitcoin".to_string()]);
+        let networks = DEFAULT_TEST_NETWORKS
+            .iter()
+            .map(|(m, n, id)| (m.to_string(), TestNetwork { name: n.to_string(), chain_id: *id, rpc_url: None }))
+            .collect();
+        SandboxConfig {
+            enabled: true,
+            api_url: Some("https://sandbox.example".to_string()),
+            api_key: None,
+            providers,
+            networks,
+        }
+    }
+
+    #[test]
+    fn test_supports_only_configured_provider_networks() {
+        let config = config();
+        assert!(config.supports("ChangeNOW", "Ethereum", "bitcoin"));
+        assert!(!config.supports("changenow", "ethereum", "solana"));
+        assert!(!config.supports("fixedfloat", "ethereum", "bitcoin"));
+    }
+
+    #[test]
+    fn test_validation() {
+        assert!(config().validate().is_ok());
+
+        let mut missing_url = config();
+        missing_url.api_url = None;
+        assert!(missing_url.validate().is_err());
+
+        let mut evm_without_chain_id = config();
+        evm_without_chain_id
+            .networks
+            .insert("polygon".to_string(), TestNetwork { name: "mumbai".to_string(), chain_id: None, rpc_url: None });
+        assert!(evm_without_chain_id.validate().is_err());
+    }
+
+    #[test]
+    fn test_disabled_config_has_no_test_networks() {
+        let mut config = config();
+        assert_eq!(config.test_network("ethereum").unwrap().chain_id, Some(11_155_111));
+        config.enabled = false;
+        assert!(config.test_network("ethereum").is_none());
+    }
+
+    #[test]
+    fn test_pairs() {
+        let parsed = pairs(Some("Ethereum=sepolia:11155111, bitcoin=testnet3"), "X").unwrap();
+        assert_eq!(parsed[0], ("ethereum".to_string(), "sepolia:11155111".to_string()));
+        assert!(pairs(Some("ethereum"), "X").is_err());
+    }
+}
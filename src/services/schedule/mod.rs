@@ -0,0 +1,3 @@
+pub mod worker;
+
+pub use worker::ScheduleWorker;
@@ -0,0 +1,172 @@
+use std::sync::Arc;
+use std::time::Duration;
+use chrono::Utc;
+use sqlx::{MySql, Pool};
+
+use crate::modules::schedules::crud::ScheduleCrud;
+use crate::modules::schedules::model::SwapSchedule;
+use crate::modules::swap::crud::SwapCrud;
+use crate::modules::swap::schema::{CreateSwapRequest, CreateSwapResponse, RateType};
+use crate::services::email::{queued_email_sender, EmailMessage, EmailSender};
+use crate::services::redis_cache::RedisService;
+
+const BATCH_SIZE: i64 = 50;
+
+/// Executes due recurring swaps through the normal swap creation flow
+pub struct ScheduleWorker {
+    db: Pool<MySql>,
+    redis: RedisService,
+    wallet_mnemonic: String,
+    email_sender: Arc<dyn EmailSender>,
+}
+
+impl ScheduleWorker {
+    pub fn new(db: Pool<MySql>, redis: RedisService, wallet_mnemonic: String) -> Self {
+        let email_sender = queued_email_sender(db.clone());
+        Self {
+            db,
+            redis,
+            wallet_mnemonic,
+            email_sender,
+        }
+    }
+
+    pub fn with_email_sender(mut self, sender: Arc<dyn EmailSender>) -> Self {
+        self.email_sender = sender;
+        self
+    }
+
+    /// Start the background scheduling loop
+    pub async fn run(&self) {
+        let mut interval = tokio::time::interval(Duration::from_secs(60));
+
+        loop {
+            interval.tick().await;
+
+            if let Err(e) = self.process_due().await {
+                tracing::error!("Schedule worker failed: {}", e);
+            }
+        }
+    }
+
+    /// Execute every schedule whose next run is due. Returns the number executed.
+    pub async fn process_due(&self) -> Result<usize, String> {
+        let crud = ScheduleCrud::new(self.db.clone());
+        let due = crud.get_due(BATCH_SIZE).await.map_err(|e| e.to_string())?;
+
+        let mut executed = 0;
+        for schedule in due {
+            // Schedule from now rather than the missed slot so downtime doesn't cause a burst
+            let next_run_at = schedule.frequency.next_after(schedule.next_run_at.max(Utc::now()));
+
+            if !crud.claim_run(&schedule, next_run_at).await.map_err(|e| e.to_string())? {
+                continue;
+            }
+
+            self.execute(&crud, &schedule).await;
+            executed += 1;
+        }
+
+        Ok(executed)
+    }
+
+    async fn execute(&self, crud: &ScheduleCrud, schedule: &SwapSchedule) {
+        let swap_crud = SwapCrud::new(
+            self.db.clone(),
+            Some(self.redis.clone()),
+            Some(self.wallet_mnemonic.clone()),
+        );
+
+        match swap_crud.create_swap(&to_swap_request(schedule), Some(schedule.user_id.clone())).await {
+            Ok(swap) => {
+                tracing::info!("Schedule {} created swap {}", schedule.id, swap.swap_id);
+                if let Err(e) = crud.record_success(&schedule.id, &swap.swap_id).await {
+                    tracing::error!("Failed to record run for schedule {}: {}", schedule.id, e);
+                }
+                self.notify(schedule, "Your recurring swap is ready for deposit", &deposit_instructions(schedule, &swap))
+                    .await;
+            }
+            Err(e) => {
+                let error = e.to_string();
+                tracing::warn!("Schedule {} failed: {}", schedule.id, error);
+
+                let paused = crud.record_failure(&schedule.id, &error).await.unwrap_or_else(|e| {
+                    tracing::error!("Failed to record failure for schedule {}: {}", schedule.id, e);
+                    false
+                });
+
+                let mut text = format!(
+                    "We could not create your recurring {} -> {} swap of {} {}.\n\nReason: {}\n",
+                    schedule.from_currency.to_uppercase(),
+                    schedule.to_currency.to_uppercase(),
+                    schedule.amount.normalize(),
+                    schedule.from_currency.to_uppercase(),
+                    error
+                );
+                if paused {
+                    text.push_str("\nThe schedule has been paused after repeated failures. Resume it once the issue is resolved.\n");
+                }
+                self.notify(schedule, "Your recurring swap failed", &text).await;
+            }
+        }
+    }
+
+    async fn notify(&self, schedule: &SwapSchedule, subject: &str, text: &str) {
+        let crud = ScheduleCrud::new(self.db.clone());
+        let email = match crud.get_user_email(&schedule.user_id).await {
+            Ok(Some(email)) => email,
+            _ => return,
+        };
+
+        let message = EmailMessage {
+            to: email,
+            subject: subject.to_string(),
+            text: text.to_string(),
+        };
+
+        if let Err(e) = self.email_sender.send(&message).await {
+            tracing::error!("Failed to send schedule notification for {}: {}", schedule.id, e);
+        }
+    }
+}
+
+/// Build a floating-rate swap request from a schedule template
+pub fn to_swap_request(schedule: &SwapSchedule) -> CreateSwapRequest {
+    CreateSwapRequest {
+        trade_id: None,
+        from: schedule.from_currency.clone(),
+        network_from: schedule.from_network.clone(),
+        to: schedule.to_currency.clone(),
+        network_to: schedule.to_network.clone(),
+        amount: schedule.amount.into(),
+        provider: schedule.provider.clone(),
+        recipient_address: schedule.recipient_address.clone(),
+        recipient_extra_id: schedule.recipient_extra_id.clone(),
+        address_book_id: None,
+        refund_address: schedule.refund_address.clone(),
+        refund_extra_id: schedule.refund_extra_id.clone(),
+        // Fixed quotes expire long before the next run, so schedules always float
+        rate_type: RateType::Floating,
+        sandbox: false,
+        payout_to_balance: schedule.payout_to_balance,
+        quote: None,
+        client_reference: None,
+        metadata: None,
+    }
+}
+
+fn deposit_instructions(schedule: &SwapSchedule, swap: &CreateSwapResponse) -> String {
+    let mut text = format!(
+        "Your recurring {} -> {} swap has been created.\n\nSend exactly {} {} to:\n{}\n",
+        schedule.from_currency.to_uppercase(),
+        schedule.to_currency.to_uppercase(),
+        swap.deposit_amount,
+        schedule.from_currency.to_uppercase(),
+        swap.deposit_address
+    );
+    if let Some(extra_id) = &swap.deposit_extra_id {
+        text.push_str(&format!("Memo / destination tag: {}\n", extra_id));
+    }
+    text.push_str(&format!("\nSwap ID: {}\n", swap.swap_id));
+    text
+}
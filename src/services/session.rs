@@ -0,0 +1,339 @@
+//! Cookie sessions for browser frontends. The access and refresh tokens are
+//! kept in httpOnly cookies instead of JS-readable storage; state-changing
+//! requests that ride on those cookies must echo a CSRF token (double-submit).
+//! Bearer tokens keep working alongside, and are never CSRF-checked since a
+//! browser won't attach them on its own.
+
+use axum::{
+    body::Body,
+    http::{header, HeaderMap, HeaderValue, Method, Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Serialize;
+use std::sync::OnceLock;
+
+use crate::services::oauth::random_token;
+
+pub const CSRF_HEADER: &str = "x-csrf-token";
+/// The refresh cookie is only sent to the session endpoints, under the
+/// version prefix the client logged in with
+const REFRESH_COOKIE_PATH: &str = "/auth/session";
+const CSRF_TOKEN_LEN: usize = 43;
+
+static CONFIG: OnceLock<SessionConfig> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SameSite {
+    Strict,
+    Lax,
+    None,
+}
+
+impl SameSite {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "strict" => Some(SameSite::Strict),
+            "lax" => Some(SameSite::Lax),
+            "none" => Some(SameSite::None),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SameSite::Strict => "Strict",
+            SameSite::Lax => "Lax",
+            SameSite::None => "None",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SessionConfig {
+    pub enabled: bool,
+    pub access_cookie: String,
+    pub refresh_cookie: String,
+    pub csrf_cookie: String,
+    pub same_site: SameSite,
+    pub secure: bool,
+    pub domain: Option<String>,
+    /// Origins allowed to send credentialed cross-origin requests
+    pub allowed_origins: Vec<String>,
+}
+
+impl Default for SessionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            access_cookie: "session".to_string(),
+            refresh_cookie: "session_refresh".to_string(),
+            csrf_cookie: "csrf_token".to_string(),
+            same_site: SameSite::Lax,
+            secure: true,
+            domain: None,
+            allowed_origins: Vec::new(),
+        }
+    }
+}
+
+impl SessionConfig {
+    /// Read from AUTH_COOKIE_SESSIONS, SESSION_COOKIE_NAME,
+    /// SESSION_COOKIE_SAMESITE, SESSION_COOKIE_SECURE, SESSION_COOKIE_DOMAIN
+    /// and SESSION_ALLOWED_ORIGINS
+    pub fn from_env() -> Self {
+        let var = |name: &str| std::env::var(name).ok().filter(|v| !v.trim().is_empty());
+        let flag = |name: &str, default: bool| {
+            var(name).map(|v| matches!(v.to_ascii_lowercase().as_str(), "1" | "true" | "yes")).unwrap_or(default)
+        };
+        let defaults = Self::default();
+
+        let access_cookie = var("SESSION_COOKIE_NAME").unwrap_or(defaults.access_cookie);
+        let same_site = var("SESSION_COOKIE_SAMESITE")
+            .and_then(|v| SameSite::parse(&v))
+            .unwrap_or(defaults.same_site);
+
+        Self {
+            enabled: flag("AUTH_COOKIE_SESSIONS", false),
+            refresh_cookie: format!("{}_refresh", access_cookie),
+            access_cookie,
+            csrf_cookie: defaults.csrf_cookie,
+            same_site,
+            // Browsers reject SameSite=None without Secure
+            secure: flag("SESSION_COOKIE_SECURE", true) || same_site == SameSite::None,
+            domain: var("SESSION_COOKIE_DOMAIN"),
+            allowed_origins: var("SESSION_ALLOWED_ORIGINS")
+                .map(|v| v.split(',').map(|o| o.trim().to_string()).filter(|o| !o.is_empty()).collect())
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Process-wide settings, read once
+    pub fn global() -> &'static SessionConfig {
+        CONFIG.get_or_init(Self::from_env)
+    }
+
+    fn cookie(&self, name: &str, value: &str, path: &str, max_age: i64, http_only: bool) -> String {
+        let mut cookie = format!(
+            "{}={}; Path={}; Max-Age={}; SameSite={}",
+            name, value, path, max_age, self.same_site.as_str()
+        );
+        if let Some(domain) = &self.domain {
+            cookie.push_str(&format!("; Domain={}", domain));
+        }
+        if self.secure {
+            cookie.push_str("; Secure");
+        }
+        if http_only {
+            cookie.push_str("; HttpOnly");
+        }
+        cookie
+    }
+
+    /// `Set-Cookie` values starting a session. The CSRF cookie is readable by
+    /// the frontend, which echoes it in the `X-CSRF-Token` header.
+    /// `base_path` is the API version prefix the session endpoints were
+    /// called under, empty for unprefixed paths.
+    pub fn session_cookies(
+        &self,
+        base_path: &str,
+        access_token: &str,
+        access_max_age: i64,
+        refresh_token: &str,
+        refresh_max_age: i64,
+        csrf_token: &str,
+    ) -> Vec<String> {
+        vec![
+            self.cookie(&self.access_cookie, access_token, "/", access_max_age, true),
+            self.cookie(&self.refresh_cookie, refresh_token, &refresh_path(base_path), refresh_max_age, true),
+            self.cookie(&self.csrf_cookie, csrf_token, "/", refresh_max_age, false),
+        ]
+    }
+
+    /// `Set-Cookie` values ending a session
+    pub fn clear_cookies(&self, base_path: &str) -> Vec<String> {
+        vec![
+            self.cookie(&self.access_cookie, "", "/", 0, true),
+            self.cookie(&self.refresh_cookie, "", &refresh_path(base_path), 0, true),
+            self.cookie(&self.csrf_cookie, "", "/", 0, false),
+        ]
+    }
+
+    /// The request carries credentials a browser attaches by itself
+    pub fn has_session_cookie(&self, headers: &HeaderMap) -> bool {
+        cookie_value(headers, &self.access_cookie).is_some() || cookie_value(headers, &self.refresh_cookie).is_some()
+    }
+}
+
+pub fn generate_csrf_token() -> String {
+    random_token(CSRF_TOKEN_LEN)
+}
+
+/// Value of a cookie in the request's `Cookie` headers
+pub fn cookie_value<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(';'))
+        .filter_map(|pair| pair.trim().split_once('='))
+        .find(|(key, value)| *key == name && !value.is_empty())
+        .map(|(_, value)| value)
+}
+
+/// Access token from `Authorization: Bearer`, or from the session cookie when
+/// cookie sessions are enabled
+pub fn access_token(headers: &HeaderMap) -> Option<&str> {
+    // An Authorization header always wins, even a malformed one, so the
+    // CSRF check and authentication agree on which credential was used
+    if let Some(authorization) = headers.get(header::AUTHORIZATION) {
+        return authorization.to_str().ok()?.strip_prefix("Bearer ");
+    }
+
+    let config = SessionConfig::global();
+    if config.enabled {
+        cookie_value(headers, &config.access_cookie)
+    } else {
+        None
+    }
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Whether a request passes the double-submit check
+pub fn csrf_ok(config: &SessionConfig, method: &Method, headers: &HeaderMap) -> bool {
+    let safe = matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS);
+    if safe || !config.enabled || headers.contains_key(header::AUTHORIZATION) || !config.has_session_cookie(headers) {
+        return true;
+    }
+
+    let cookie = cookie_value(headers, &config.csrf_cookie);
+    let header = headers.get(CSRF_HEADER).and_then(|v| v.to_str().ok());
+    match (cookie, header) {
+        (Some(cookie), Some(header)) => constant_time_eq(cookie.as_bytes(), header.as_bytes()),
+        _ => false,
+    }
+}
+
+/// Browsers attach cookies to cross-site WebSocket handshakes and CORS does
+/// not cover them, so a cookie-authenticated upgrade must come from our own
+/// host or an allowed origin. Requests without an Origin are not browsers.
+pub fn websocket_origin_ok(config: &SessionConfig, headers: &HeaderMap) -> bool {
+    if headers.contains_key(header::AUTHORIZATION) {
+        return true;
+    }
+    let Some(origin) = headers.get(header::ORIGIN).and_then(|v| v.to_str().ok()) else {
+        return true;
+    };
+    if config.allowed_origins.iter().any(|o| o == origin) {
+        return true;
+    }
+
+    let host = headers.get(header::HOST).and_then(|v| v.to_str().ok());
+    let origin_host = origin.split_once("://").map(|(_, rest)| rest);
+    matches!((origin_host, host), (Some(o), Some(h)) if o.eq_ignore_ascii_case(h))
+}
+
+#[derive(Serialize)]
+struct CsrfErrorResponse {
+    error: &'static str,
+}
+
+/// Reject state-changing cookie-authenticated requests without a matching
+/// CSRF token
+pub async fn csrf_protection(request: Request<Body>, next: Next) -> Response {
+    if !csrf_ok(SessionConfig::global(), request.method(), request.headers()) {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(CsrfErrorResponse { error: "Missing or invalid CSRF token" }),
+        )
+            .into_response();
+    }
+    next.run(request).await
+}
+
+fn refresh_path(base_path: &str) -> String {
+    format!("{}{}", base_path, REFRESH_COOKIE_PATH)
+}
+
+/// Attach `Set-Cookie` headers to a response
+pub fn with_cookies(mut response: Response, cookies: Vec<String>) -> Response {
+    for cookie in cookies {
+        if let Ok(value) = HeaderValue::from_str(&cookie) {
+            response.headers_mut().append(header::SET_COOKIE, value);
+        }
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn enabled() -> SessionConfig {
+        SessionConfig { enabled: true, ..SessionConfig::default() }
+    }
+
+    fn headers(pairs: &[(&str, &str)]) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for (k, v) in pairs {
+            map.append(axum::http::HeaderName::from_bytes(k.as_bytes()).unwrap(), HeaderValue::from_str(v).unwrap());
+        }
+        map
+    }
+
+    #[test]
+    fn test_cookie_value() {
+        let h = headers(&[("cookie", "a=1; session=abc; csrf_token=xyz")]);
+        assert_eq!(cookie_value(&h, "session"), Some("abc"));
+        assert_eq!(cookie_value(&h, "csrf_token"), Some("xyz"));
+        assert_eq!(cookie_value(&h, "missing"), None);
+    }
+
+    #[test]
+    fn test_csrf_required_only_for_cookie_writes() {
+        let config = enabled();
+        let cookie = ("cookie", "session=abc; csrf_token=tok");
+
+        assert!(!csrf_ok(&config, &Method::POST, &headers(&[cookie])));
+        assert!(!csrf_ok(&config, &Method::POST, &headers(&[cookie, (CSRF_HEADER, "other")])));
+        assert!(csrf_ok(&config, &Method::POST, &headers(&[cookie, (CSRF_HEADER, "tok")])));
+
+        // Reads, bearer clients and cookie-less requests are not checked
+        assert!(csrf_ok(&config, &Method::GET, &headers(&[cookie])));
+        assert!(csrf_ok(&config, &Method::POST, &headers(&[cookie, ("authorization", "Bearer t")])));
+        assert!(csrf_ok(&config, &Method::POST, &headers(&[])));
+    }
+
+    #[test]
+    fn test_websocket_origin() {
+        let config = SessionConfig { allowed_origins: vec!["https://app.example.com".to_string()], ..enabled() };
+        let host = ("host", "api.example.com");
+
+        assert!(websocket_origin_ok(&config, &headers(&[host])));
+        assert!(websocket_origin_ok(&config, &headers(&[host, ("origin", "https://api.example.com")])));
+        assert!(websocket_origin_ok(&config, &headers(&[host, ("origin", "https://app.example.com")])));
+        assert!(!websocket_origin_ok(&config, &headers(&[host, ("origin", "https://evil.example")])));
+        assert!(websocket_origin_ok(&config, &headers(&[host, ("origin", "https://evil.example"), ("authorization", "Bearer t")])));
+    }
+
+    #[test]
+    fn test_session_cookie_attributes() {
+        let config = SessionConfig { same_site: SameSite::Strict, ..enabled() };
+        let cookies = config.session_cookies("", "acc", 900, "ref", 604800, "tok");
+
+        assert_eq!(cookies[0], "session=acc; Path=/; Max-Age=900; SameSite=Strict; Secure; HttpOnly");
+        assert!(cookies[1].contains("Path=/auth/session"));
+        assert!(!cookies[2].contains("HttpOnly"));
+
+        let versioned = config.session_cookies("/v1", "acc", 900, "ref", 604800, "tok");
+        assert!(versioned[1].contains("Path=/v1/auth/session;"));
+        assert!(config.clear_cookies("/v1")[1].contains("Path=/v1/auth/session;"));
+    }
+}
@@ -0,0 +1,277 @@
+//! Simulated deposits for staging QA. Builds with the `deposit-simulation`
+//! feature and DEPOSIT_SIMULATION_ENABLED set expose
+//! POST /_test/simulate-deposit/{swap_id}, which credits a fake balance to
+//! the swap's deposit address. The listener and payout pipeline read chains
+//! through `SimulatedProvider`, which adds those balances to what the RPC
+//! reports and fakes the broadcast of transactions spending them, so a swap
+//! runs to completion without real funds.
+//!
+//! Balances are held in memory: the endpoint, listener and payout workers
+//! must run in the same process, as they do in the single-binary staging
+//! deployment.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use crate::services::amount::Decimal;
+use crate::services::wallet::rpc::{BlockchainProvider, RpcError, TransactionReceipt, TransferLog};
+
+static LEDGER: OnceLock<SimulatedLedger> = OnceLock::new();
+
+/// Sender recorded on simulated token transfers
+const SIMULATED_SENDER: &str = "0x0000000000000000000000000000000000051a7e";
+
+/// Whether deposits can be simulated: the feature is compiled in and
+/// DEPOSIT_SIMULATION_ENABLED is set
+pub fn simulation_enabled() -> bool {
+    cfg!(feature = "deposit-simulation")
+        && std::env::var("DEPOSIT_SIMULATION_ENABLED")
+            .map(|v| matches!(v.trim().to_ascii_lowercase().as_str(), "1" | "true" | "yes"))
+            .unwrap_or(false)
+}
+
+/// `provider` with simulated balances layered on when simulation is
+/// enabled, unchanged otherwise
+pub fn simulated(provider: Arc<dyn BlockchainProvider>) -> Arc<dyn BlockchainProvider> {
+    if simulation_enabled() {
+        Arc::new(SimulatedProvider::new(provider))
+    } else {
+        provider
+    }
+}
+
+/// Fake funds held by one address
+#[derive(Debug, Clone, Default)]
+struct SimulatedBalance {
+    native: Decimal,
+    /// Token deposits, reported as transfer logs
+    transfers: Vec<TransferLog>,
+    /// Set once a payout spent the funds
+    spent: bool,
+}
+
+/// Fake balances by lower-cased address
+#[derive(Default)]
+pub struct SimulatedLedger {
+    balances: Mutex<HashMap<String, SimulatedBalance>>,
+    /// Hashes of faked broadcasts, reported as mined
+    broadcasts: Mutex<HashSet<String>>,
+}
+
+impl SimulatedLedger {
+    /// The process-wide ledger
+    pub fn global() -> &'static SimulatedLedger {
+        LEDGER.get_or_init(SimulatedLedger::default)
+    }
+
+    /// Add a native coin deposit
+    pub fn credit_native(&self, address: &str, amount: Decimal) {
+        let mut balances = self.balances.lock().unwrap_or_else(|e| e.into_inner());
+        let balance = balances.entry(address.to_lowercase()).or_default();
+        balance.native += amount;
+        balance.spent = false;
+    }
+
+    /// Add a token deposit of `units` base units from `contract`; returns
+    /// the fake transaction hash
+    pub fn credit_token(&self, address: &str, contract: &str, units: u128, block_number: u64) -> String {
+        let tx_hash = format!("0x{:0>64}", Uuid::new_v4().simple());
+        let mut balances = self.balances.lock().unwrap_or_else(|e| e.into_inner());
+        let balance = balances.entry(address.to_lowercase()).or_default();
+        balance.transfers.push(TransferLog {
+            contract: contract.to_lowercase(),
+            from: SIMULATED_SENDER.to_string(),
+            to: address.to_lowercase(),
+            amount: units,
+            tx_hash: tx_hash.clone(),
+            log_index: balance.transfers.len() as u64,
+            block_number,
+        });
+        balance.spent = false;
+        tx_hash
+    }
+
+    fn get(&self, address: &str) -> Option<SimulatedBalance> {
+        let balances = self.balances.lock().unwrap_or_else(|e| e.into_inner());
+        balances.get(&address.to_lowercase()).cloned()
+    }
+
+    fn holds(&self, address: &str) -> bool {
+        self.get(address).is_some_and(|b| !b.spent)
+    }
+
+    /// Payouts sweep the deposit, so the whole fake balance is spent
+    fn spend(&self, address: &str) {
+        let mut balances = self.balances.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(balance) = balances.get_mut(&address.to_lowercase()) {
+            balance.spent = true;
+        }
+    }
+
+    fn record_broadcast(&self, tx_hash: &str) {
+        self.broadcasts.lock().unwrap_or_else(|e| e.into_inner()).insert(tx_hash.to_lowercase());
+    }
+
+    fn broadcast(&self, tx_hash: &str) -> bool {
+        self.broadcasts.lock().unwrap_or_else(|e| e.into_inner()).contains(&tx_hash.to_lowercase())
+    }
+}
+
+/// Adds simulated balances to an RPC provider. A transaction is taken to
+/// be sent by the address whose nonce was read last, which holds for the
+/// wallet manager: it reads the sender's nonce, signs and broadcasts, with
+/// a fresh provider per payout.
+pub struct SimulatedProvider {
+    inner: Arc<dyn BlockchainProvider>,
+    ledger: &'static SimulatedLedger,
+    sender: Mutex<Option<String>>,
+}
+
+impl SimulatedProvider {
+    pub fn new(inner: Arc<dyn BlockchainProvider>) -> Self {
+        Self::with_ledger(inner, SimulatedLedger::global())
+    }
+
+    pub fn with_ledger(inner: Arc<dyn BlockchainProvider>, ledger: &'static SimulatedLedger) -> Self {
+        Self { inner, ledger, sender: Mutex::new(None) }
+    }
+}
+
+#[async_trait]
+impl BlockchainProvider for SimulatedProvider {
+    async fn get_transaction_count(&self, address: &str) -> Result<u64, RpcError> {
+        let simulated = self.ledger.holds(address);
+        *self.sender.lock().unwrap_or_else(|e| e.into_inner()) = simulated.then(|| address.to_lowercase());
+        match self.inner.get_transaction_count(address).await {
+            Err(_) if simulated => Ok(0),
+            result => result,
+        }
+    }
+
+    async fn get_gas_price(&self) -> Result<u64, RpcError> {
+        self.inner.get_gas_price().await
+    }
+
+    async fn send_raw_transaction(&self, signed_hex: &str) -> Result<String, RpcError> {
+        let sender = self.sender.lock().unwrap_or_else(|e| e.into_inner()).take();
+        match sender {
+            Some(sender) => {
+                self.ledger.spend(&sender);
+                let tx_hash = format!("0x{:0>64}", Uuid::new_v4().simple());
+                self.ledger.record_broadcast(&tx_hash);
+                tracing::info!("Simulated broadcast from {}: {}", sender, tx_hash);
+                Ok(tx_hash)
+            }
+            None => self.inner.send_raw_transaction(signed_hex).await,
+        }
+    }
+
+    async fn get_balance(&self, address: &str) -> Result<Decimal, RpcError> {
+        match self.ledger.get(address) {
+            Some(balance) if !balance.spent => {
+                Ok(self.inner.get_balance(address).await.unwrap_or_default() + balance.native)
+            }
+            _ => self.inner.get_balance(address).await,
+        }
+    }
+
+    async fn get_block_number(&self) -> Result<u64, RpcError> {
+        self.inner.get_block_number().await
+    }
+
+    async fn get_transfer_logs(&self, to_address: &str, from_block: u64) -> Result<Vec<TransferLog>, RpcError> {
+        let Some(balance) = self.ledger.get(to_address) else {
+            return self.inner.get_transfer_logs(to_address, from_block).await;
+        };
+        let mut logs = self.inner.get_transfer_logs(to_address, from_block).await.unwrap_or_default();
+        logs.extend(balance.transfers.into_iter().filter(|log| log.block_number >= from_block));
+        Ok(logs)
+    }
+
+    async fn get_token_balance(&self, contract: &str, owner: &str) -> Result<u128, RpcError> {
+        let Some(balance) = self.ledger.get(owner).filter(|b| !b.spent) else {
+            return self.inner.get_token_balance(contract, owner).await;
+        };
+        let simulated: u128 = balance
+            .transfers
+            .iter()
+            .filter(|log| log.contract.eq_ignore_ascii_case(contract))
+            .map(|log| log.amount)
+            .sum();
+        Ok(self.inner.get_token_balance(contract, owner).await.unwrap_or(0).saturating_add(simulated))
+    }
+
+    /// Faked broadcasts count as mined in the genesis block, so they are
+    /// final as soon as they are checked
+    async fn get_transaction_receipt(&self, tx_hash: &str) -> Result<Option<TransactionReceipt>, RpcError> {
+        if self.ledger.broadcast(tx_hash) {
+            return Ok(Some(TransactionReceipt { block_number: 0, success: true }));
+        }
+        self.inner.get_transaction_receipt(tx_hash).await
+    }
+
+    async fn call_contract(&self, from: Option<&str>, to: &str, data: &str) -> Result<String, RpcError> {
+        self.inner.call_contract(from, to, data).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An RPC that is down
+    struct Offline;
+
+    #[async_trait]
+    impl BlockchainProvider for Offline {
+        async fn get_transaction_count(&self, _: &str) -> Result<u64, RpcError> {
+            Err(RpcError::Network("offline".to_string()))
+        }
+        async fn get_gas_price(&self) -> Result<u64, RpcError> {
+            Ok(1)
+        }
+        async fn send_raw_transaction(&self, _: &str) -> Result<String, RpcError> {
+            Err(RpcError::Network("offline".to_string()))
+        }
+        async fn get_balance(&self, _: &str) -> Result<Decimal, RpcError> {
+            Err(RpcError::Network("offline".to_string()))
+        }
+    }
+
+    fn provider() -> SimulatedProvider {
+        let ledger: &'static SimulatedLedger = Box::leak(Box::default());
+        SimulatedProvider::with_ledger(Arc::new(Offline), ledger)
+    }
+
+    #[tokio::test]
+    async fn test_native_deposit_is_reported_and_spent_by_payout() {
+        let provider = provider();
+        provider.ledger.credit_native("0xABC", Decimal::new(5, 1));
+        assert_eq!(provider.get_balance("0xabc").await.unwrap(), Decimal::new(5, 1));
+        assert!(provider.get_balance("0xdef").await.is_err());
+
+        assert_eq!(provider.get_transaction_count("0xabc").await.unwrap(), 0);
+        let tx_hash = provider.send_raw_transaction("0x00").await.unwrap();
+        assert!(tx_hash.starts_with("0x"));
+        assert!(provider.get_transaction_receipt(&tx_hash).await.unwrap().is_some_and(|r| r.success));
+        assert!(provider.get_balance("0xabc").await.is_err());
+
+        // Other senders still go to the chain
+        assert!(provider.get_transaction_count("0xdef").await.is_err());
+        assert!(provider.send_raw_transaction("0x00").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_token_deposit_is_a_transfer_log() {
+        let provider = provider();
+        provider.ledger.credit_token("0xabc", "0xToken", 2_000_000, 100);
+        let logs = provider.get_transfer_logs("0xabc", 50).await.unwrap();
+        assert_eq!(logs.len(), 1);
+        assert_eq!(logs[0].contract, "0xtoken");
+        assert!(provider.get_transfer_logs("0xabc", 101).await.unwrap().is_empty());
+        assert_eq!(provider.get_token_balance("0xtoken", "0xabc").await.unwrap(), 2_000_000);
+    }
+}
@@ -0,0 +1,180 @@
+//! Data for a public status page. Each chain's severity is sampled once a
+//! minute into `network_status_samples`; uptime is the share of samples in
+//! which a chain was not down. Only severities, uptime percentages and the
+//! average swap completion time are published — endpoint URLs, scores and
+//! halt reasons stay internal.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, DurationRound, Utc};
+use sqlx::{MySql, Pool};
+
+use crate::modules::halts::crud::{HaltCrud, HaltSet};
+use crate::modules::status::schema::{PublicNetworkStatus, PublicStatusResponse, UptimeSummary};
+use crate::services::network_status::{NetworkSeverity, NetworkStatusReport};
+use crate::services::rpc::RpcManager;
+
+/// Samples older than this are pruned; also the longest uptime window
+const RETENTION_DAYS: i64 = 30;
+
+/// Current per-chain health, as /status/networks reports it
+pub async fn current_report(db: &Pool<MySql>) -> NetworkStatusReport {
+    // RPC health is still worth reporting when the halt table can't be read
+    let halts = HaltCrud::new(db.clone()).active_halts().await.unwrap_or_else(|e| {
+        tracing::warn!("Network status without halts: {}", e);
+        Arc::new(HaltSet::default())
+    });
+
+    NetworkStatusReport::collect(RpcManager::global().map(|rpc| rpc.as_ref()), &halts).await
+}
+
+/// Percentage of `up` in `total`, rounded to two decimals; `None` without samples
+pub fn uptime_percent(up: i64, total: i64) -> Option<f64> {
+    if total <= 0 {
+        return None;
+    }
+    Some((up.clamp(0, total) as f64 * 10_000.0 / total as f64).round() / 100.0)
+}
+
+// =============================================================================
+// SAMPLER
+// =============================================================================
+
+/// Records every chain's severity once per minute. Samples are keyed by
+/// chain and minute, so replicas sampling the same minute write one row.
+pub struct StatusSampler {
+    db: Pool<MySql>,
+    interval: Duration,
+}
+
+impl StatusSampler {
+    pub fn new(db: Pool<MySql>) -> Self {
+        Self {
+            db,
+            interval: Duration::from_secs(60),
+        }
+    }
+
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Start the background sampling loop
+    pub async fn run(&self) {
+        let mut interval = tokio::time::interval(self.interval);
+
+        loop {
+            interval.tick().await;
+
+            if let Err(e) = self.sample().await {
+                tracing::error!("Status sampling failed: {}", e);
+            }
+        }
+    }
+
+    /// Record the current severities; returns the chains sampled
+    pub async fn sample(&self) -> Result<usize, sqlx::Error> {
+        let report = current_report(&self.db).await;
+        let minute = Utc::now().duration_trunc(chrono::Duration::minutes(1)).unwrap_or_else(|_| Utc::now());
+
+        for network in &report.networks {
+            sqlx::query(
+                "INSERT IGNORE INTO network_status_samples (network, sampled_at, severity) VALUES (?, ?, ?)",
+            )
+            .bind(&network.network)
+            .bind(minute)
+            .bind(severity_str(network.severity))
+            .execute(&self.db)
+            .await?;
+        }
+
+        sqlx::query("DELETE FROM network_status_samples WHERE sampled_at < DATE_SUB(NOW(), INTERVAL ? DAY)")
+            .bind(RETENTION_DAYS)
+            .execute(&self.db)
+            .await?;
+
+        Ok(report.networks.len())
+    }
+}
+
+fn severity_str(severity: NetworkSeverity) -> &'static str {
+    match severity {
+        NetworkSeverity::Operational => "operational",
+        NetworkSeverity::Degraded => "degraded",
+        NetworkSeverity::Down => "down",
+    }
+}
+
+// =============================================================================
+// PUBLIC STATUS
+// =============================================================================
+
+/// Build the public status document
+pub async fn public_status(db: &Pool<MySql>) -> Result<PublicStatusResponse, sqlx::Error> {
+    let report = current_report(db).await;
+    let now = Utc::now();
+
+    let uptime = UptimeSummary {
+        last_24h: uptime_since(db, now - chrono::Duration::hours(24)).await?,
+        last_7d: uptime_since(db, now - chrono::Duration::days(7)).await?,
+        last_30d: uptime_since(db, now - chrono::Duration::days(RETENTION_DAYS)).await?,
+    };
+
+    let (average,): (Option<f64>,) = sqlx::query_as(
+        r#"
+        SELECT CAST(AVG(TIMESTAMPDIFF(SECOND, created_at, completed_at)) AS DOUBLE)
+        FROM swaps
+        WHERE status = 'completed' AND is_sandbox = FALSE
+          AND completed_at >= DATE_SUB(NOW(), INTERVAL 24 HOUR)
+        "#,
+    )
+    .fetch_one(db)
+    .await?;
+
+    Ok(PublicStatusResponse {
+        status: report.overall,
+        uptime,
+        average_completion_secs_24h: average.map(|secs| secs.max(0.0).round() as u64),
+        networks: report
+            .networks
+            .iter()
+            .map(|n| PublicNetworkStatus {
+                network: n.network.clone(),
+                status: n.severity,
+                operational: n.severity != NetworkSeverity::Down,
+            })
+            .collect(),
+        updated_at: now,
+    })
+}
+
+/// Share of chain-minutes since `since` in which the chain was not down
+async fn uptime_since(db: &Pool<MySql>, since: DateTime<Utc>) -> Result<Option<f64>, sqlx::Error> {
+    let (total, up): (i64, Option<i64>) = sqlx::query_as(
+        r#"
+        SELECT COUNT(*), CAST(SUM(severity <> 'down') AS SIGNED)
+        FROM network_status_samples
+        WHERE sampled_at >= ?
+        "#,
+    )
+    .bind(since)
+    .fetch_one(db)
+    .await?;
+
+    Ok(uptime_percent(up.unwrap_or(0), total))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_uptime_percent() {
+        assert_eq!(uptime_percent(0, 0), None);
+        assert_eq!(uptime_percent(1440, 1440), Some(100.0));
+        assert_eq!(uptime_percent(1439, 1440), Some(99.93));
+        assert_eq!(uptime_percent(0, 10), Some(0.0));
+    }
+}
@@ -0,0 +1,34 @@
+//! Object storage for generated files (CSV exports, data archives, backups).
+//! `S3Storage` talks to any S3-compatible endpoint (AWS, MinIO, R2) and
+//! hands out presigned download URLs so files never stream through the API.
+
+pub mod s3;
+
+use async_trait::async_trait;
+use std::time::Duration;
+
+pub use s3::{S3Config, S3Storage};
+
+#[derive(Debug, thiserror::Error)]
+pub enum StorageError {
+    #[error("Object storage is not configured: {0}")]
+    NotConfigured(String),
+
+    #[error("Storage request failed: {0}")]
+    Request(String),
+
+    #[error("Storage returned {status}: {body}")]
+    Status { status: u16, body: String },
+}
+
+#[async_trait]
+pub trait ObjectStorage: Send + Sync {
+    async fn put_object(&self, key: &str, body: Vec<u8>, content_type: &str) -> Result<(), StorageError>;
+
+    async fn get_object(&self, key: &str) -> Result<Vec<u8>, StorageError>;
+
+    async fn delete_object(&self, key: &str) -> Result<(), StorageError>;
+
+    /// Time-limited GET URL that needs no further credentials
+    fn presigned_get_url(&self, key: &str, expires_in: Duration) -> Result<String, StorageError>;
+}
@@ -0,0 +1,345 @@
+//! Completion-time estimates for in-flight swaps. For each stage, the time
+//! from first entering it to completion is read from the status history of
+//! recently completed swaps of the same pair. A swap's estimate only counts
+//! the swaps that were still unfinished after as long as this one has spent
+//! in its stage, so the window narrows as stages complete and moves out
+//! while a swap runs slow.
+//!
+//! Swaps with the same provider are used when there are enough of them,
+//! otherwise the pair across all providers. Distributions are cached in
+//! process for SWAP_ETA_CACHE_SECS.
+
+use chrono::{DateTime, Utc};
+use sqlx::{MySql, Pool};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use crate::modules::swap::schema::{StatusHistoryEntry, SwapEta, SwapStatus};
+
+/// Most recent completed swaps read per distribution
+const MAX_SWAPS: i64 = 2000;
+
+/// Share of similar swaps completed by the earliest, expected and latest time
+const EARLIEST_QUANTILE: f64 = 0.25;
+const EXPECTED_QUANTILE: f64 = 0.5;
+const LATEST_QUANTILE: f64 = 0.75;
+
+/// Stages a swap gets an estimate in. Before the deposit arrives the wait is
+/// up to the user.
+const ESTIMATED_STAGES: [SwapStatus; 4] =
+    [SwapStatus::Confirming, SwapStatus::Exchanging, SwapStatus::Sending, SwapStatus::FundsReceived];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EtaPolicy {
+    /// Only swaps completed within this many days count
+    pub lookback_days: u32,
+    /// Fewest comparable swaps an estimate is given from; 0 turns estimates off
+    pub min_samples: usize,
+    /// How long a pair's distribution is reused before it is read again
+    pub cache_ttl: Duration,
+}
+
+impl Default for EtaPolicy {
+    fn default() -> Self {
+        Self { lookback_days: 30, min_samples: 10, cache_ttl: Duration::from_secs(300) }
+    }
+}
+
+impl EtaPolicy {
+    pub fn from_env() -> Result<Self, String> {
+        let mut policy = Self::default();
+
+        if let Ok(val) = std::env::var("SWAP_ETA_LOOKBACK_DAYS") {
+            policy.lookback_days = val.trim().parse().map_err(|e| format!("Invalid SWAP_ETA_LOOKBACK_DAYS: {}", e))?;
+            if policy.lookback_days == 0 {
+                return Err("SWAP_ETA_LOOKBACK_DAYS must be at least 1".to_string());
+            }
+        }
+        if let Ok(val) = std::env::var("SWAP_ETA_MIN_SAMPLES") {
+            policy.min_samples = val.trim().parse().map_err(|e| format!("Invalid SWAP_ETA_MIN_SAMPLES: {}", e))?;
+        }
+        if let Ok(val) = std::env::var("SWAP_ETA_CACHE_SECS") {
+            let secs: u64 = val.trim().parse().map_err(|e| format!("Invalid SWAP_ETA_CACHE_SECS: {}", e))?;
+            policy.cache_ttl = Duration::from_secs(secs);
+        }
+
+        Ok(policy)
+    }
+
+    pub fn global() -> &'static EtaPolicy {
+        static POLICY: OnceLock<EtaPolicy> = OnceLock::new();
+        POLICY.get_or_init(|| {
+            Self::from_env().unwrap_or_else(|e| {
+                tracing::warn!("Ignoring invalid swap ETA config: {}", e);
+                Self::default()
+            })
+        })
+    }
+}
+
+// =============================================================================
+// DISTRIBUTIONS
+// =============================================================================
+
+/// Currencies and networks a swap goes between. Lowercased so swaps share
+/// a cache entry whatever case they were created with.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SwapPair {
+    pub from_currency: String,
+    pub from_network: String,
+    pub to_currency: String,
+    pub to_network: String,
+}
+
+impl SwapPair {
+    pub fn new(from_currency: &str, from_network: &str, to_currency: &str, to_network: &str) -> Self {
+        Self {
+            from_currency: from_currency.to_lowercase(),
+            from_network: from_network.to_lowercase(),
+            to_currency: to_currency.to_lowercase(),
+            to_network: to_network.to_lowercase(),
+        }
+    }
+}
+
+/// Time left in an in-flight swap, in seconds from now
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RemainingTime {
+    pub earliest_secs: i64,
+    pub expected_secs: i64,
+    pub latest_secs: i64,
+    /// Completed swaps that were still unfinished at this point
+    pub samples: usize,
+}
+
+/// Seconds from first entering each stage to completion, per completed swap
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CompletionTimes {
+    /// Keyed by status, sorted ascending
+    stages: HashMap<String, Vec<i64>>,
+}
+
+impl CompletionTimes {
+    /// Build from `(status, seconds to completion)` pairs, one per swap and stage
+    pub fn from_samples(samples: impl IntoIterator<Item = (String, i64)>) -> Self {
+        let mut stages: HashMap<String, Vec<i64>> = HashMap::new();
+        for (status, secs) in samples {
+            stages.entry(status).or_default().push(secs.max(0));
+        }
+        for times in stages.values_mut() {
+            times.sort_unstable();
+        }
+        Self { stages }
+    }
+
+    /// Completed swaps that went through `stage`
+    pub fn samples(&self, stage: &SwapStatus) -> usize {
+        self.stages.get(stage.as_str()).map_or(0, Vec::len)
+    }
+
+    /// Time left for a swap `elapsed_secs` into `stage`, from the swaps that
+    /// took longer than that. None with fewer than `min_samples` of them.
+    pub fn remaining(&self, stage: &SwapStatus, elapsed_secs: i64, min_samples: usize) -> Option<RemainingTime> {
+        let times = self.stages.get(stage.as_str())?;
+        let slower = &times[times.partition_point(|&secs| secs <= elapsed_secs)..];
+        if slower.is_empty() || slower.len() < min_samples {
+            return None;
+        }
+
+        let quantile = |q: f64| slower[((slower.len() - 1) as f64 * q).round() as usize] - elapsed_secs;
+        Some(RemainingTime {
+            earliest_secs: quantile(EARLIEST_QUANTILE),
+            expected_secs: quantile(EXPECTED_QUANTILE),
+            latest_secs: quantile(LATEST_QUANTILE),
+            samples: slower.len(),
+        })
+    }
+}
+
+/// When the swap entered `stage`: its latest history entry, when that is
+/// for the stage
+pub fn stage_entered_at(history: &[StatusHistoryEntry], stage: &SwapStatus) -> Option<DateTime<Utc>> {
+    history.last().filter(|entry| &entry.status == stage).map(|entry| entry.at)
+}
+
+// =============================================================================
+// ESTIMATOR
+// =============================================================================
+
+type CacheKey = (SwapPair, Option<String>);
+/// Completion times per pair and provider, and when they were read
+type CompletionCache = Mutex<HashMap<CacheKey, (Instant, Arc<CompletionTimes>)>>;
+
+fn cache() -> &'static CompletionCache {
+    static CACHE: OnceLock<CompletionCache> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+pub struct SwapEtaEstimator {
+    pool: Pool<MySql>,
+    policy: EtaPolicy,
+}
+
+impl SwapEtaEstimator {
+    pub fn new(pool: Pool<MySql>) -> Self {
+        Self { pool, policy: EtaPolicy::global().clone() }
+    }
+
+    pub fn with_policy(mut self, policy: EtaPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Completion window for a swap in `status`, or None when it is not in
+    /// flight or too few similar swaps have completed. Errors reading the
+    /// history are logged; a status response never fails over its estimate.
+    pub async fn estimate(
+        &self,
+        pair: &SwapPair,
+        provider: &str,
+        status: &SwapStatus,
+        history: &[StatusHistoryEntry],
+    ) -> Option<SwapEta> {
+        if self.policy.min_samples == 0 || !ESTIMATED_STAGES.contains(status) {
+            return None;
+        }
+        let now = Utc::now();
+        let entered_at = stage_entered_at(history, status).unwrap_or(now);
+        let elapsed = (now - entered_at).num_seconds().max(0);
+
+        for (provider, basis) in [(Some(provider), "pair_provider"), (None, "pair")] {
+            let times = match self.completion_times(pair, provider).await {
+                Ok(times) => times,
+                Err(e) => {
+                    tracing::warn!("Could not load completion times for swap ETA: {}", e);
+                    return None;
+                }
+            };
+            if let Some(remaining) = times.remaining(status, elapsed, self.policy.min_samples) {
+                return Some(SwapEta {
+                    stage: status.clone(),
+                    earliest_at: now + chrono::Duration::seconds(remaining.earliest_secs),
+                    expected_at: now + chrono::Duration::seconds(remaining.expected_secs),
+                    latest_at: now + chrono::Duration::seconds(remaining.latest_secs),
+                    remaining_minutes: (remaining.expected_secs + 59) / 60,
+                    samples: remaining.samples,
+                    basis: basis.to_string(),
+                });
+            }
+        }
+        None
+    }
+
+    /// The pair's distribution, restricted to `provider` when given
+    pub async fn completion_times(
+        &self,
+        pair: &SwapPair,
+        provider: Option<&str>,
+    ) -> Result<Arc<CompletionTimes>, sqlx::Error> {
+        let key = (pair.clone(), provider.map(str::to_string));
+        if let Some((loaded_at, times)) = cache().lock().unwrap().get(&key) {
+            if loaded_at.elapsed() < self.policy.cache_ttl {
+                return Ok(times.clone());
+            }
+        }
+
+        let times = Arc::new(self.load(pair, provider).await?);
+        let mut cache = cache().lock().unwrap();
+        cache.retain(|_, (loaded_at, _)| loaded_at.elapsed() < self.policy.cache_ttl);
+        cache.insert(key, (Instant::now(), times.clone()));
+        Ok(times)
+    }
+
+    async fn load(&self, pair: &SwapPair, provider: Option<&str>) -> Result<CompletionTimes, sqlx::Error> {
+        let filter = if provider.is_some() { "AND provider_id = ?" } else { "" };
+        let sql = format!(
+            r#"
+            SELECT CAST(h.status AS CHAR) AS status,
+                   CAST(TIMESTAMPDIFF(SECOND, MIN(h.created_at), s.completed_at) AS SIGNED) AS secs
+            FROM (
+                SELECT id, completed_at FROM swaps
+                WHERE from_currency = ? AND to_currency = ? AND status = 'completed' AND completed_at > ?
+                  AND from_network = ? AND to_network = ? {}
+                ORDER BY completed_at DESC
+                LIMIT ?
+            ) s
+            JOIN swap_status_history h ON h.swap_id = s.id
+            GROUP BY s.id, s.completed_at, h.status
+            "#,
+            filter
+        );
+
+        let since = Utc::now() - chrono::Duration::days(self.policy.lookback_days as i64);
+        let mut query = sqlx::query_as::<_, (String, i64)>(&sql)
+            .bind(&pair.from_currency)
+            .bind(&pair.to_currency)
+            .bind(since)
+            .bind(&pair.from_network)
+            .bind(&pair.to_network);
+        if let Some(provider) = provider {
+            query = query.bind(provider);
+        }
+        let rows = query.bind(MAX_SWAPS).fetch_all(&self.pool).await?;
+        Ok(CompletionTimes::from_samples(rows))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn times(stage: &SwapStatus, secs: impl IntoIterator<Item = i64>) -> CompletionTimes {
+        CompletionTimes::from_samples(secs.into_iter().map(|s| (stage.as_str().to_string(), s)))
+    }
+
+    #[test]
+    fn test_remaining_quantiles() {
+        // 60s..=600s in 60s steps
+        let times = times(&SwapStatus::Exchanging, (1..=10).rev().map(|m| m * 60));
+
+        let remaining = times.remaining(&SwapStatus::Exchanging, 0, 10).unwrap();
+        assert_eq!(remaining.samples, 10);
+        assert_eq!(remaining.earliest_secs, 180);
+        assert_eq!(remaining.expected_secs, 360);
+        assert_eq!(remaining.latest_secs, 480);
+        assert!(times.remaining(&SwapStatus::Sending, 0, 1).is_none());
+    }
+
+    #[test]
+    fn test_remaining_conditions_on_elapsed_time() {
+        let times = times(&SwapStatus::Confirming, (1..=10).map(|m| m * 60));
+
+        // 5 minutes in: only the five slower swaps count, measured from now
+        let remaining = times.remaining(&SwapStatus::Confirming, 300, 5).unwrap();
+        assert_eq!(remaining.samples, 5);
+        assert_eq!(remaining.earliest_secs, 120);
+        assert_eq!(remaining.expected_secs, 180);
+        assert_eq!(remaining.latest_secs, 240);
+
+        // Too few swaps were ever this slow
+        assert!(times.remaining(&SwapStatus::Confirming, 300, 6).is_none());
+        assert!(times.remaining(&SwapStatus::Confirming, 600, 0).is_none());
+    }
+
+    #[test]
+    fn test_stage_entered_at_uses_latest_entry() {
+        let entry = |status: SwapStatus, minutes: i64| StatusHistoryEntry {
+            status,
+            actor: "system".to_string(),
+            message: None,
+            at: DateTime::<Utc>::UNIX_EPOCH + chrono::Duration::minutes(minutes),
+            duration_secs: None,
+        };
+        let history = vec![entry(SwapStatus::Waiting, 0), entry(SwapStatus::Confirming, 5)];
+
+        assert_eq!(stage_entered_at(&history, &SwapStatus::Confirming), Some(history[1].at));
+        assert_eq!(stage_entered_at(&history, &SwapStatus::Exchanging), None);
+        assert_eq!(stage_entered_at(&[], &SwapStatus::Confirming), None);
+    }
+
+    #[test]
+    fn test_pair_is_case_insensitive() {
+        assert_eq!(SwapPair::new("BTC", "Bitcoin", "ETH", "ERC20"), SwapPair::new("btc", "bitcoin", "eth", "erc20"));
+    }
+}
@@ -0,0 +1,553 @@
+//! SLA timers for in-flight swaps. Each in-flight status has a limit
+//! (SWAP_SLA_<STATUS>_MINUTES); a swap that stays in a status past its limit
+//! is recorded as a breach and escalated in steps, one every
+//! SWAP_SLA_ESCALATION_MINUTES: the provider is re-polled, admins are told
+//! on the ops channel, then a support ticket is opened when a desk is
+//! configured. A breach is resolved once the swap leaves the status.
+//!
+//! Each step is claimed with a conditional update before it runs, so
+//! several instances evaluating at once act on a breach only once.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{MySql, Pool};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+use crate::modules::monitor::crud::MonitorCrud;
+use crate::modules::swap::model::{SlaEscalation, StuckSwap};
+use crate::modules::swap::schema::SwapStatus;
+use crate::services::events::ops::OpsEvent;
+
+/// Most open breaches escalated per pass
+const MAX_CANDIDATES: i64 = 500;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlaPolicy {
+    /// Minutes a swap may stay in each status; 0 turns the timer off
+    pub confirming_minutes: u32,
+    pub exchanging_minutes: u32,
+    pub sending_minutes: u32,
+    pub funds_received_minutes: u32,
+    /// Minutes between escalation steps once a limit is passed
+    pub escalation_minutes: u32,
+    pub interval: Duration,
+}
+
+impl Default for SlaPolicy {
+    fn default() -> Self {
+        Self {
+            confirming_minutes: 60,
+            exchanging_minutes: 120,
+            sending_minutes: 60,
+            funds_received_minutes: 30,
+            escalation_minutes: 30,
+            interval: Duration::from_secs(300),
+        }
+    }
+}
+
+impl SlaPolicy {
+    pub fn from_env() -> Result<Self, String> {
+        let mut policy = Self::default();
+
+        let minutes = |name: &str, default: u32| -> Result<u32, String> {
+            match std::env::var(name) {
+                Ok(val) => val.trim().parse().map_err(|e| format!("Invalid {}: {}", name, e)),
+                Err(_) => Ok(default),
+            }
+        };
+        policy.confirming_minutes = minutes("SWAP_SLA_CONFIRMING_MINUTES", policy.confirming_minutes)?;
+        policy.exchanging_minutes = minutes("SWAP_SLA_EXCHANGING_MINUTES", policy.exchanging_minutes)?;
+        policy.sending_minutes = minutes("SWAP_SLA_SENDING_MINUTES", policy.sending_minutes)?;
+        policy.funds_received_minutes = minutes("SWAP_SLA_FUNDS_RECEIVED_MINUTES", policy.funds_received_minutes)?;
+        policy.escalation_minutes = minutes("SWAP_SLA_ESCALATION_MINUTES", policy.escalation_minutes)?;
+        if policy.escalation_minutes == 0 {
+            return Err("SWAP_SLA_ESCALATION_MINUTES must be at least 1".to_string());
+        }
+        if let Ok(val) = std::env::var("SWAP_SLA_INTERVAL_SECS") {
+            let secs: u64 = val.trim().parse().map_err(|e| format!("Invalid SWAP_SLA_INTERVAL_SECS: {}", e))?;
+            if secs == 0 {
+                return Err("SWAP_SLA_INTERVAL_SECS must be at least 1".to_string());
+            }
+            policy.interval = Duration::from_secs(secs);
+        }
+
+        Ok(policy)
+    }
+
+    pub fn global() -> &'static SlaPolicy {
+        static POLICY: OnceLock<SlaPolicy> = OnceLock::new();
+        POLICY.get_or_init(|| {
+            Self::from_env().unwrap_or_else(|e| {
+                tracing::warn!("Ignoring invalid swap SLA config: {}", e);
+                Self::default()
+            })
+        })
+    }
+
+    /// SLA for the status in minutes; None for statuses without a timer
+    pub fn limit(&self, status: &SwapStatus) -> Option<u32> {
+        let minutes = match status {
+            SwapStatus::Confirming => self.confirming_minutes,
+            SwapStatus::Exchanging => self.exchanging_minutes,
+            SwapStatus::Sending => self.sending_minutes,
+            SwapStatus::FundsReceived => self.funds_received_minutes,
+            _ => 0,
+        };
+        (minutes > 0).then_some(minutes)
+    }
+
+    /// Statuses with a timer, with their limits
+    pub fn timed_statuses(&self) -> Vec<(SwapStatus, u32)> {
+        [SwapStatus::Confirming, SwapStatus::Exchanging, SwapStatus::Sending, SwapStatus::FundsReceived]
+            .into_iter()
+            .filter_map(|status| self.limit(&status).map(|limit| (status, limit)))
+            .collect()
+    }
+
+    /// Escalation a swap `minutes` into a status with an SLA of `limit` has
+    /// earned: the re-poll at the limit, then one step per escalation period
+    pub fn escalation_due(&self, limit: u32, minutes: i64) -> Option<SlaEscalation> {
+        let over = minutes - limit as i64;
+        if over < 0 {
+            return None;
+        }
+        Some(match over / self.escalation_minutes.max(1) as i64 {
+            0 => SlaEscalation::Reping,
+            1 => SlaEscalation::Notified,
+            _ => SlaEscalation::Ticketed,
+        })
+    }
+}
+
+// =============================================================================
+// SUPPORT DESK
+// =============================================================================
+
+/// What a support ticket for a stuck swap says
+#[derive(Debug, Clone, Serialize)]
+pub struct StuckSwapTicket {
+    pub swap_id: String,
+    pub status: String,
+    pub provider_id: String,
+    pub entered_at: DateTime<Utc>,
+    pub minutes_in_status: i64,
+    pub sla_minutes: u32,
+}
+
+#[async_trait]
+pub trait SupportDesk: Send + Sync {
+    /// Open a ticket, returning the desk's reference for it when it gives one
+    async fn open_ticket(&self, ticket: &StuckSwapTicket) -> Result<Option<String>, String>;
+}
+
+/// Opens tickets by POSTing the ticket as JSON to SUPPORT_DESK_URL. An `id`
+/// in the JSON reply is kept as the ticket reference.
+pub struct WebhookSupportDesk {
+    client: reqwest::Client,
+    url: String,
+    token: Option<String>,
+}
+
+impl WebhookSupportDesk {
+    pub fn new(url: String, token: Option<String>) -> Self {
+        Self {
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(10))
+                .build()
+                .unwrap_or_default(),
+            url,
+            token,
+        }
+    }
+
+    /// None when SUPPORT_DESK_URL is unset
+    pub fn from_env() -> Option<Self> {
+        let url = std::env::var("SUPPORT_DESK_URL").ok().filter(|url| !url.trim().is_empty())?;
+        let token = std::env::var("SUPPORT_DESK_TOKEN").ok().filter(|token| !token.is_empty());
+        Some(Self::new(url, token))
+    }
+}
+
+#[async_trait]
+impl SupportDesk for WebhookSupportDesk {
+    async fn open_ticket(&self, ticket: &StuckSwapTicket) -> Result<Option<String>, String> {
+        let mut request = self.client.post(&self.url).json(ticket);
+        if let Some(token) = &self.token {
+            request = request.bearer_auth(token);
+        }
+        let response = request.send().await.map_err(|e| format!("Support desk unreachable: {}", e))?;
+        if !response.status().is_success() {
+            return Err(format!("Support desk returned {}", response.status()));
+        }
+
+        let reply: serde_json::Value = response.json().await.unwrap_or_default();
+        Ok(match &reply["id"] {
+            serde_json::Value::String(id) => Some(id.clone()),
+            serde_json::Value::Number(id) => Some(id.to_string()),
+            _ => None,
+        })
+    }
+}
+
+// =============================================================================
+// BREACH STORE
+// =============================================================================
+
+/// A swap in a timed status, with when it entered it
+#[derive(Debug, sqlx::FromRow)]
+struct SlaCandidate {
+    swap_id: String,
+    status: SwapStatus,
+    provider_id: String,
+    entered_at: DateTime<Utc>,
+    /// Escalation already reached for this stay in the status
+    escalation: Option<SlaEscalation>,
+    breach_id: Option<i64>,
+}
+
+const STUCK_COLUMNS: &str = r#"
+    b.id, b.swap_id, b.status, s.provider_id, b.entered_at, b.sla_minutes, CAST(b.escalation AS CHAR) as escalation,
+    b.ticket_reference, b.detected_at, b.escalated_at
+"#;
+
+pub struct SlaBreachStore {
+    pool: Pool<MySql>,
+}
+
+impl SlaBreachStore {
+    pub fn new(pool: Pool<MySql>) -> Self {
+        Self { pool }
+    }
+
+    /// Swaps that have been in a timed status for at least `min_minutes`
+    async fn candidates(&self, policy: &SlaPolicy) -> Result<Vec<SlaCandidate>, sqlx::Error> {
+        let timed = policy.timed_statuses();
+        let Some(min_minutes) = timed.iter().map(|(_, limit)| *limit).min() else {
+            return Ok(Vec::new());
+        };
+        let cutoff = Utc::now() - chrono::Duration::minutes(min_minutes as i64);
+        let placeholders = vec!["?"; timed.len()].join(", ");
+
+        // A swap's latest history row for its current status is when it
+        // entered it; swaps from before the history table fall back to the
+        // last update
+        let sql = format!(
+            r#"
+            SELECT c.swap_id, c.status, c.provider_id, c.entered_at,
+                   CAST(b.escalation AS CHAR) as escalation, b.id as breach_id
+            FROM (
+                SELECT s.id as swap_id, CAST(s.status AS CHAR) as status, s.provider_id,
+                       COALESCE(
+                           (SELECT MAX(h.created_at) FROM swap_status_history h
+                            WHERE h.swap_id = s.id AND CAST(h.status AS CHAR) = CAST(s.status AS CHAR)),
+                           s.updated_at
+                       ) as entered_at
+                FROM swaps s
+                WHERE CAST(s.status AS CHAR) IN ({}) AND s.created_at < ?
+            ) c
+            LEFT JOIN swap_sla_breaches b
+                ON b.swap_id = c.swap_id AND b.status = c.status AND b.entered_at = c.entered_at
+            WHERE c.entered_at < ? AND (b.id IS NULL OR (b.resolved_at IS NULL AND b.escalation <> 'ticketed'))
+            ORDER BY c.entered_at ASC
+            LIMIT ?
+            "#,
+            placeholders
+        );
+
+        let mut query = sqlx::query_as::<_, SlaCandidate>(&sql);
+        for (status, _) in &timed {
+            query = query.bind(status.as_str());
+        }
+        query.bind(cutoff).bind(cutoff).bind(MAX_CANDIDATES).fetch_all(&self.pool).await
+    }
+
+    /// Record a new breach at the re-poll step. Returns its id, or None when
+    /// another instance recorded it first.
+    async fn open(&self, candidate: &SlaCandidate, sla_minutes: u32) -> Result<Option<i64>, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            INSERT IGNORE INTO swap_sla_breaches (swap_id, status, entered_at, sla_minutes, escalation)
+            VALUES (?, ?, ?, ?, 'reping')
+            "#,
+        )
+        .bind(&candidate.swap_id)
+        .bind(candidate.status.as_str())
+        .bind(candidate.entered_at)
+        .bind(sla_minutes)
+        .execute(&self.pool)
+        .await?;
+
+        Ok((result.rows_affected() == 1).then(|| result.last_insert_id() as i64))
+    }
+
+    /// Move an open breach from `from` to `to`; false when another instance
+    /// moved it first
+    async fn advance(&self, id: i64, from: SlaEscalation, to: SlaEscalation) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            UPDATE swap_sla_breaches SET escalation = ?, escalated_at = NOW()
+            WHERE id = ? AND escalation = ? AND resolved_at IS NULL
+            "#,
+        )
+        .bind(to.as_str())
+        .bind(id)
+        .bind(from.as_str())
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn set_ticket_reference(&self, id: i64, reference: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE swap_sla_breaches SET ticket_reference = ? WHERE id = ?")
+            .bind(reference)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Resolve open breaches whose swap has left the status, or re-entered it
+    /// since
+    pub async fn resolve_left(&self) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            UPDATE swap_sla_breaches b
+            JOIN swaps s ON s.id = b.swap_id
+            SET b.resolved_at = NOW()
+            WHERE b.resolved_at IS NULL
+              AND (CAST(s.status AS CHAR) <> b.status OR EXISTS (
+                  SELECT 1 FROM swap_status_history h
+                  WHERE h.swap_id = b.swap_id AND h.created_at > b.entered_at
+              ))
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Open breaches, longest-stuck first
+    pub async fn list_open(&self, status: Option<&SwapStatus>, limit: u32) -> Result<Vec<StuckSwap>, sqlx::Error> {
+        let filter = if status.is_some() { "AND b.status = ?" } else { "" };
+        let sql = format!(
+            r#"
+            SELECT {} FROM swap_sla_breaches b
+            JOIN swaps s ON s.id = b.swap_id
+            WHERE b.resolved_at IS NULL {}
+            ORDER BY b.entered_at ASC
+            LIMIT ?
+            "#,
+            STUCK_COLUMNS, filter
+        );
+
+        let mut query = sqlx::query_as::<_, StuckSwap>(&sql);
+        if let Some(status) = status {
+            query = query.bind(status.as_str());
+        }
+        query.bind(limit).fetch_all(&self.pool).await
+    }
+}
+
+// =============================================================================
+// MONITOR
+// =============================================================================
+
+/// What one evaluation pass did
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SlaReport {
+    /// New breaches recorded
+    pub breached: usize,
+    /// Escalation steps taken past the re-poll
+    pub escalated: usize,
+    /// Breaches closed because the swap moved on
+    pub resolved: u64,
+}
+
+pub struct SwapSlaMonitor {
+    store: SlaBreachStore,
+    monitor: MonitorCrud,
+    policy: SlaPolicy,
+    desk: Option<Arc<dyn SupportDesk>>,
+}
+
+impl SwapSlaMonitor {
+    pub fn new(db: Pool<MySql>, policy: SlaPolicy) -> Self {
+        Self {
+            store: SlaBreachStore::new(db.clone()),
+            monitor: MonitorCrud::new(db),
+            policy,
+            desk: WebhookSupportDesk::from_env().map(|desk| Arc::new(desk) as Arc<dyn SupportDesk>),
+        }
+    }
+
+    /// Open tickets with `desk` instead of the configured one
+    pub fn with_support_desk(mut self, desk: Option<Arc<dyn SupportDesk>>) -> Self {
+        self.desk = desk;
+        self
+    }
+
+    /// Resolve breaches that cleared, then record and escalate the rest
+    pub async fn evaluate(&self) -> Result<SlaReport, sqlx::Error> {
+        let mut report = SlaReport { resolved: self.store.resolve_left().await?, ..Default::default() };
+        let now = Utc::now();
+
+        for candidate in self.store.candidates(&self.policy).await? {
+            let Some(limit) = self.policy.limit(&candidate.status) else {
+                continue;
+            };
+            let minutes = (now - candidate.entered_at).num_minutes();
+            let Some(mut due) = self.policy.escalation_due(limit, minutes) else {
+                continue;
+            };
+            // Without a desk, admins are the last step
+            if self.desk.is_none() {
+                due = due.min(SlaEscalation::Notified);
+            }
+
+            let (id, mut reached) = match (candidate.breach_id, candidate.escalation) {
+                (Some(id), Some(escalation)) => (id, escalation),
+                _ => {
+                    let Some(id) = self.store.open(&candidate, limit).await? else {
+                        continue;
+                    };
+                    report.breached += 1;
+                    tracing::warn!(
+                        "Swap {} has been {} for {} minutes (SLA {})",
+                        candidate.swap_id,
+                        candidate.status.as_str(),
+                        minutes,
+                        limit
+                    );
+                    self.reping(&candidate).await;
+                    (id, SlaEscalation::Reping)
+                }
+            };
+
+            while reached < due {
+                let next = match reached {
+                    SlaEscalation::Reping => SlaEscalation::Notified,
+                    _ => SlaEscalation::Ticketed,
+                };
+                if !self.store.advance(id, reached, next).await? {
+                    break;
+                }
+                match next {
+                    SlaEscalation::Notified => self.notify(&candidate, minutes, limit),
+                    _ => {
+                        if !self.ticket(id, &candidate, minutes, limit).await? {
+                            break;
+                        }
+                    }
+                }
+                report.escalated += 1;
+                reached = next;
+            }
+        }
+
+        Ok(report)
+    }
+
+    async fn reping(&self, candidate: &SlaCandidate) {
+        if let Err(e) = self.monitor.request_poll(&candidate.swap_id, candidate.status.as_str()).await {
+            tracing::error!("Failed to re-poll stuck swap {}: {}", candidate.swap_id, e);
+        }
+    }
+
+    fn notify(&self, candidate: &SlaCandidate, minutes: i64, limit: u32) {
+        OpsEvent::SwapStuck {
+            swap_id: candidate.swap_id.clone(),
+            status: candidate.status.as_str().to_string(),
+            minutes_in_status: minutes,
+            sla_minutes: limit,
+        }
+        .publish();
+    }
+
+    /// Open the ticket. A failed attempt puts the breach back at
+    /// `notified`, so the next pass tries again; returns false then.
+    async fn ticket(&self, id: i64, candidate: &SlaCandidate, minutes: i64, limit: u32) -> Result<bool, sqlx::Error> {
+        let Some(desk) = &self.desk else {
+            return Ok(false);
+        };
+        let ticket = StuckSwapTicket {
+            swap_id: candidate.swap_id.clone(),
+            status: candidate.status.as_str().to_string(),
+            provider_id: candidate.provider_id.clone(),
+            entered_at: candidate.entered_at,
+            minutes_in_status: minutes,
+            sla_minutes: limit,
+        };
+
+        match desk.open_ticket(&ticket).await {
+            Ok(reference) => {
+                if let Some(reference) = reference {
+                    self.store.set_ticket_reference(id, &reference).await?;
+                }
+                Ok(true)
+            }
+            Err(e) => {
+                tracing::error!("Failed to open a ticket for stuck swap {}: {}", candidate.swap_id, e);
+                self.store.advance(id, SlaEscalation::Ticketed, SlaEscalation::Notified).await?;
+                Ok(false)
+            }
+        }
+    }
+
+    /// Start the background evaluation loop
+    pub async fn run(&self) {
+        let mut interval = tokio::time::interval(self.policy.interval);
+
+        loop {
+            interval.tick().await;
+
+            match self.evaluate().await {
+                Ok(report) if report == SlaReport::default() => {}
+                Ok(report) => tracing::info!(
+                    "Swap SLA pass: {} new breaches, {} escalations, {} resolved",
+                    report.breached,
+                    report.escalated,
+                    report.resolved
+                ),
+                Err(e) => tracing::error!("Swap SLA evaluation failed: {}", e),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_limits_per_status() {
+        let policy = SlaPolicy { funds_received_minutes: 0, ..SlaPolicy::default() };
+
+        assert_eq!(policy.limit(&SwapStatus::Exchanging), Some(120));
+        assert_eq!(policy.limit(&SwapStatus::Confirming), Some(60));
+        assert_eq!(policy.limit(&SwapStatus::FundsReceived), None);
+        assert_eq!(policy.limit(&SwapStatus::Waiting), None);
+        assert_eq!(policy.limit(&SwapStatus::Completed), None);
+        assert_eq!(policy.timed_statuses().len(), 3);
+    }
+
+    #[test]
+    fn test_escalation_steps() {
+        let policy = SlaPolicy::default();
+
+        assert_eq!(policy.escalation_due(120, 119), None);
+        assert_eq!(policy.escalation_due(120, 120), Some(SlaEscalation::Reping));
+        assert_eq!(policy.escalation_due(120, 149), Some(SlaEscalation::Reping));
+        assert_eq!(policy.escalation_due(120, 150), Some(SlaEscalation::Notified));
+        assert_eq!(policy.escalation_due(120, 180), Some(SlaEscalation::Ticketed));
+        assert_eq!(policy.escalation_due(120, 10_000), Some(SlaEscalation::Ticketed));
+    }
+
+    #[test]
+    fn test_escalations_are_ordered() {
+        assert!(SlaEscalation::Reping < SlaEscalation::Notified);
+        assert!(SlaEscalation::Notified < SlaEscalation::Ticketed);
+    }
+}
@@ -0,0 +1,381 @@
+use chrono::{DateTime, Utc};
+use sqlx::{MySql, Pool};
+
+use crate::modules::swap::schema::{StatusHistoryEntry, SwapStatus};
+use crate::services::amount::Decimal;
+use crate::services::events::DomainEvent;
+
+/// Attempts made by `advance` before giving up on a contended swap
+const MAX_CAS_ATTEMPTS: usize = 3;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TransitionError {
+    /// The state machine does not allow `from -> to`
+    Illegal { from: SwapStatus, to: SwapStatus },
+    /// Another writer changed the swap since `expected` was read
+    VersionConflict { swap_id: String, expected: u32 },
+    SwapNotFound,
+    Database(String),
+}
+
+impl std::fmt::Display for TransitionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TransitionError::Illegal { from, to } => {
+                write!(f, "Illegal swap status transition: {} -> {}", from, to)
+            }
+            TransitionError::VersionConflict { swap_id, expected } => {
+                write!(f, "Swap {} was modified concurrently (expected version {})", swap_id, expected)
+            }
+            TransitionError::SwapNotFound => write!(f, "Swap not found"),
+            TransitionError::Database(e) => write!(f, "Database error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for TransitionError {}
+
+impl From<sqlx::Error> for TransitionError {
+    fn from(err: sqlx::Error) -> Self {
+        TransitionError::Database(err.to_string())
+    }
+}
+
+/// Who moved a swap to a status, recorded in swap_status_history
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StatusActor {
+    /// The swap was created by a user or API client
+    User,
+    /// Reported by the exchange provider
+    Provider,
+    /// Our blockchain listener saw the deposit
+    Listener,
+    /// The monitor's fallback balance check
+    Monitor,
+    /// Our payout pipeline
+    Payout,
+    Admin,
+    #[default]
+    System,
+}
+
+impl StatusActor {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StatusActor::User => "user",
+            StatusActor::Provider => "provider",
+            StatusActor::Listener => "listener",
+            StatusActor::Monitor => "monitor",
+            StatusActor::Payout => "payout",
+            StatusActor::Admin => "admin",
+            StatusActor::System => "system",
+        }
+    }
+}
+
+/// A requested status change plus the columns written with it
+#[derive(Debug, Clone)]
+pub struct Transition {
+    pub to: SwapStatus,
+    pub actual_receive: Option<Decimal>,
+    pub tx_hash_in: Option<String>,
+    pub tx_hash_out: Option<String>,
+    pub error: Option<String>,
+    /// Recorded in swap_status_history
+    pub message: Option<String>,
+    pub actor: StatusActor,
+}
+
+impl Transition {
+    pub fn to(status: SwapStatus) -> Self {
+        Self {
+            to: status,
+            actual_receive: None,
+            tx_hash_in: None,
+            tx_hash_out: None,
+            error: None,
+            message: None,
+            actor: StatusActor::System,
+        }
+    }
+
+    pub fn actual_receive(mut self, amount: Decimal) -> Self {
+        self.actual_receive = Some(amount);
+        self
+    }
+
+    pub fn tx_hash_out(mut self, hash: impl Into<String>) -> Self {
+        self.tx_hash_out = Some(hash.into());
+        self
+    }
+
+    pub fn error(mut self, error: impl Into<String>) -> Self {
+        self.error = Some(error.into());
+        self
+    }
+
+    pub fn message(mut self, message: impl Into<String>) -> Self {
+        self.message = Some(message.into());
+        self
+    }
+
+    pub fn actor(mut self, actor: StatusActor) -> Self {
+        self.actor = actor;
+        self
+    }
+}
+
+/// Outcome of an applied transition
+#[derive(Debug, Clone, PartialEq)]
+pub struct Applied {
+    pub from: SwapStatus,
+    pub to: SwapStatus,
+    pub version: u32,
+    /// False when the swap was already in the target status and nothing was written
+    pub changed: bool,
+}
+
+/// Single owner of swap status writes. Validates every transition and
+/// writes it with compare-and-set on `swaps.version`, so the listener,
+/// monitor and admin actions cannot clobber each other.
+#[derive(Clone)]
+pub struct SwapStateMachine {
+    db: Pool<MySql>,
+}
+
+impl SwapStateMachine {
+    pub fn new(db: Pool<MySql>) -> Self {
+        Self { db }
+    }
+
+    /// Whether `from -> to` is a legal move. Statuses only move forward;
+    /// providers may skip intermediate steps.
+    pub fn can_transition(from: &SwapStatus, to: &SwapStatus) -> bool {
+        use SwapStatus::*;
+
+        match from {
+            Waiting => matches!(to, Confirming | Exchanging | Sending | FundsReceived | Completed | Failed | Refunded | Expired),
+            Confirming => matches!(to, Exchanging | Sending | FundsReceived | Completed | Failed | Refunded),
+            Exchanging => matches!(to, Sending | FundsReceived | Completed | Failed | Refunded),
+            Sending => matches!(to, FundsReceived | Completed | Failed | Refunded),
+            FundsReceived => matches!(to, Completed | Failed | Refunded),
+            Failed | Expired => matches!(to, Refunded),
+            Completed | Refunded => false,
+        }
+    }
+
+    pub fn validate(from: &SwapStatus, to: &SwapStatus) -> Result<(), TransitionError> {
+        if Self::can_transition(from, to) {
+            Ok(())
+        } else {
+            Err(TransitionError::Illegal { from: from.clone(), to: to.clone() })
+        }
+    }
+
+    /// Current status and version of a swap
+    pub async fn current(&self, swap_id: &str) -> Result<(SwapStatus, u32), TransitionError> {
+        let row: Option<(String, u32)> =
+            sqlx::query_as("SELECT CAST(status AS CHAR), version FROM swaps WHERE id = ?")
+                .bind(swap_id)
+                .fetch_optional(&self.db)
+                .await?;
+
+        let (status, version) = row.ok_or(TransitionError::SwapNotFound)?;
+        let status = SwapStatus::parse(&status)
+            .ok_or_else(|| TransitionError::Database(format!("Unknown swap status: {}", status)))?;
+        Ok((status, version))
+    }
+
+    /// Apply `transition` only if the swap is still at `expected_version`.
+    /// Use this when the caller made its decision from a specific read.
+    pub async fn apply(
+        &self,
+        swap_id: &str,
+        expected_version: u32,
+        transition: &Transition,
+    ) -> Result<Applied, TransitionError> {
+        let (from, version) = self.current(swap_id).await?;
+        if version != expected_version {
+            return Err(TransitionError::VersionConflict { swap_id: swap_id.to_string(), expected: expected_version });
+        }
+        self.write(swap_id, from, version, transition).await
+    }
+
+    /// Apply `transition` against whatever the swap currently is, re-reading
+    /// and re-validating when another writer gets there first
+    #[tracing::instrument(name = "db.swap_transition", skip(self, transition), fields(swap_id = %swap_id, db.system = "mysql", to = %transition.to))]
+    pub async fn advance(&self, swap_id: &str, transition: &Transition) -> Result<Applied, TransitionError> {
+        let mut last_conflict = None;
+
+        for _ in 0..MAX_CAS_ATTEMPTS {
+            let (from, version) = self.current(swap_id).await?;
+            match self.write(swap_id, from, version, transition).await {
+                Err(conflict @ TransitionError::VersionConflict { .. }) => last_conflict = Some(conflict),
+                other => return other,
+            }
+        }
+
+        Err(last_conflict.unwrap_or(TransitionError::SwapNotFound))
+    }
+
+    async fn write(
+        &self,
+        swap_id: &str,
+        from: SwapStatus,
+        version: u32,
+        transition: &Transition,
+    ) -> Result<Applied, TransitionError> {
+        if from == transition.to {
+            return Ok(Applied { from, to: transition.to.clone(), version, changed: false });
+        }
+        Self::validate(&from, &transition.to)?;
+
+        let completed_at = (transition.to == SwapStatus::Completed).then(Utc::now);
+
+        let mut tx = self.db.begin().await?;
+
+        let result = sqlx::query(
+            r#"
+            UPDATE swaps
+            SET status = ?,
+                version = version + 1,
+                actual_receive = COALESCE(?, actual_receive),
+                tx_hash_in = COALESCE(?, tx_hash_in),
+                tx_hash_out = COALESCE(?, tx_hash_out),
+                error = COALESCE(?, error),
+                completed_at = COALESCE(?, completed_at),
+                updated_at = NOW()
+            WHERE id = ? AND version = ?
+            "#,
+        )
+        .bind(transition.to.as_str())
+        .bind(transition.actual_receive)
+        .bind(&transition.tx_hash_in)
+        .bind(&transition.tx_hash_out)
+        .bind(&transition.error)
+        .bind(completed_at)
+        .bind(swap_id)
+        .bind(version)
+        .execute(&mut *tx)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(TransitionError::VersionConflict { swap_id: swap_id.to_string(), expected: version });
+        }
+
+        sqlx::query(
+            "INSERT INTO swap_status_history (swap_id, status, actor, message, created_at) VALUES (?, ?, ?, ?, NOW(3))",
+        )
+        .bind(swap_id)
+        .bind(transition.to.as_str())
+        .bind(transition.actor.as_str())
+        .bind(&transition.message)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        tracing::debug!("Swap {} moved {} -> {} (v{})", swap_id, from, transition.to, version + 1);
+
+        DomainEvent::SwapStatusChanged {
+            swap_id: swap_id.to_string(),
+            from: from.clone(),
+            to: transition.to.clone(),
+        }
+        .publish();
+        if transition.to == SwapStatus::Refunded {
+            DomainEvent::RefundIssued { swap_id: swap_id.to_string() }.publish();
+        }
+
+        Ok(Applied { from, to: transition.to.clone(), version: version + 1, changed: true })
+    }
+
+    /// Every recorded transition of a swap, oldest first, with the time
+    /// spent in each status
+    pub async fn history(&self, swap_id: &str) -> Result<Vec<StatusHistoryEntry>, TransitionError> {
+        let rows: Vec<(String, String, Option<String>, DateTime<Utc>)> = sqlx::query_as(
+            r#"
+            SELECT CAST(status AS CHAR), actor, message, created_at
+            FROM swap_status_history
+            WHERE swap_id = ?
+            ORDER BY created_at, id
+            "#,
+        )
+        .bind(swap_id)
+        .fetch_all(&self.db)
+        .await?;
+
+        let entries = rows
+            .into_iter()
+            .filter_map(|(status, actor, message, at)| {
+                let status = SwapStatus::parse(&status)?;
+                Some(StatusHistoryEntry { status, actor, message, at, duration_secs: None })
+            })
+            .collect();
+        Ok(with_durations(entries))
+    }
+}
+
+/// Fill in how long each status lasted: until the next entry. The last
+/// entry is the current status and has no duration yet.
+pub fn with_durations(mut entries: Vec<StatusHistoryEntry>) -> Vec<StatusHistoryEntry> {
+    for i in 1..entries.len() {
+        let elapsed = entries[i].at - entries[i - 1].at;
+        entries[i - 1].duration_secs = Some(elapsed.num_milliseconds().max(0) as f64 / 1000.0);
+    }
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use SwapStatus::*;
+
+    #[test]
+    fn test_forward_transitions_are_legal() {
+        assert!(SwapStateMachine::can_transition(&Waiting, &Confirming));
+        assert!(SwapStateMachine::can_transition(&Confirming, &Sending));
+        assert!(SwapStateMachine::can_transition(&Sending, &FundsReceived));
+        assert!(SwapStateMachine::can_transition(&FundsReceived, &Completed));
+        assert!(SwapStateMachine::can_transition(&Failed, &Refunded));
+        // Providers may skip steps
+        assert!(SwapStateMachine::can_transition(&Waiting, &Completed));
+    }
+
+    #[test]
+    fn test_backward_and_terminal_transitions_are_illegal() {
+        assert!(!SwapStateMachine::can_transition(&Sending, &Confirming));
+        assert!(!SwapStateMachine::can_transition(&FundsReceived, &Waiting));
+        assert!(!SwapStateMachine::can_transition(&Completed, &Failed));
+        assert!(!SwapStateMachine::can_transition(&Refunded, &Waiting));
+        assert!(!SwapStateMachine::can_transition(&Expired, &Completed));
+    }
+
+    #[test]
+    fn test_stage_durations() {
+        let start = Utc::now();
+        let entry = |status: SwapStatus, offset_ms: i64| StatusHistoryEntry {
+            status,
+            actor: "system".to_string(),
+            message: None,
+            at: start + chrono::Duration::milliseconds(offset_ms),
+            duration_secs: None,
+        };
+
+        let entries = with_durations(vec![entry(Waiting, 0), entry(Confirming, 90_500), entry(Completed, 150_500)]);
+        assert_eq!(entries[0].duration_secs, Some(90.5));
+        assert_eq!(entries[1].duration_secs, Some(60.0));
+        assert_eq!(entries[2].duration_secs, None);
+
+        assert!(with_durations(vec![]).is_empty());
+    }
+
+    #[test]
+    fn test_validate_returns_typed_error() {
+        assert_eq!(
+            SwapStateMachine::validate(&Completed, &Sending),
+            Err(TransitionError::Illegal { from: Completed, to: Sending })
+        );
+        assert!(SwapStateMachine::validate(&Waiting, &Expired).is_ok());
+    }
+}
@@ -3,9 +3,11 @@ pub mod erc20_client;
 pub mod registry;
 pub mod approval_manager;
 pub mod gas_estimator;
+pub mod permit;
 
 pub use types::*;
 pub use erc20_client::Erc20Client;
 pub use registry::TokenRegistry;
 pub use approval_manager::ApprovalManager;
 pub use gas_estimator::TokenGasEstimator;
+pub use permit::{PermitPolicy, PermitRelayer};
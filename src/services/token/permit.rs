@@ -0,0 +1,734 @@
+//! Token payouts without a gas top-up. A deposit address that only received
+//! an ERC-20 token holds no native coin, so moving the token means funding
+//! its gas from the hot wallet first and waiting for that to land. Where the
+//! token allows it, the deposit address signs an authorization off-chain
+//! instead and the hot wallet submits it, paying the gas itself:
+//!
+//! - EIP-2612: `permit()` on the token grants the hot wallet an allowance,
+//!   then the hot wallet calls `transferFrom()`.
+//! - Permit2: when the owner has approved Uniswap's Permit2 contract, one
+//!   `permitTransferFrom()` moves the tokens.
+//!
+//! Support is read from each token contract and cached. Anything unexpected
+//! (no `DOMAIN_SEPARATOR`, a non-standard permit such as DAI's, a permit that
+//! reverts in simulation, RPC trouble) returns `None` and the caller falls
+//! back to the gas station and a plain transfer.
+
+use sha3::{Digest, Keccak256};
+use sqlx::{MySql, Pool};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use crate::modules::commissions::crud::CommissionCrud;
+use crate::modules::wallet::schema::EvmTransaction;
+use crate::services::amount::{self, Decimal};
+use crate::services::gas::station::native_currency;
+use crate::services::gas::{TxType, HOT_WALLET_INDEX};
+use crate::services::sandbox::signing_chain_id;
+use crate::services::wallet::derivation;
+use crate::services::wallet::rpc::{BlockchainProvider, RpcError};
+use crate::services::wallet::signer::Signer;
+use crate::services::wallet::signing::{erc20_transfer_from_data, SigningService};
+
+/// Uniswap's Permit2, deployed at the same address on every EVM chain
+pub const PERMIT2_ADDRESS: &str = "0x000000000022d473030f116ddee9f6b43ac78ba3";
+
+const EIP2612_PERMIT_TYPE: &str =
+    "Permit(address owner,address spender,uint256 value,uint256 nonce,uint256 deadline)";
+const PERMIT2_DOMAIN_TYPE: &str = "EIP712Domain(string name,uint256 chainId,address verifyingContract)";
+const PERMIT2_TOKEN_PERMISSIONS_TYPE: &str = "TokenPermissions(address token,uint256 amount)";
+const PERMIT2_TRANSFER_FROM_TYPE: &str = "PermitTransferFrom(TokenPermissions permitted,address spender,uint256 nonce,uint256 deadline)TokenPermissions(address token,uint256 amount)";
+
+// DOMAIN_SEPARATOR() = 0x3644e515
+const DOMAIN_SEPARATOR_SELECTOR: &str = "0x3644e515";
+// PERMIT_TYPEHASH() = 0x30adf81f
+const PERMIT_TYPEHASH_SELECTOR: &str = "0x30adf81f";
+// nonces(address) = 0x7ecebe00
+const NONCES_SELECTOR: &str = "0x7ecebe00";
+// allowance(address,address) = 0xdd62ed3e
+const ALLOWANCE_SELECTOR: &str = "0xdd62ed3e";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PermitPolicy {
+    /// Off sends every token payout through the gas station
+    pub enabled: bool,
+    /// How long a signed permit stays valid
+    pub deadline: Duration,
+    /// How long a token's detected support is trusted before it is read again
+    pub cache_ttl: Duration,
+}
+
+impl Default for PermitPolicy {
+    fn default() -> Self {
+        Self { enabled: true, deadline: Duration::from_secs(1800), cache_ttl: Duration::from_secs(6 * 3600) }
+    }
+}
+
+impl PermitPolicy {
+    pub fn from_env() -> Result<Self, String> {
+        let mut policy = Self::default();
+
+        if let Ok(val) = std::env::var("TOKEN_PERMITS_ENABLED") {
+            policy.enabled = val.trim().parse().map_err(|e| format!("Invalid TOKEN_PERMITS_ENABLED: {}", e))?;
+        }
+        if let Ok(val) = std::env::var("TOKEN_PERMIT_DEADLINE_SECS") {
+            let secs: u64 = val.trim().parse().map_err(|e| format!("Invalid TOKEN_PERMIT_DEADLINE_SECS: {}", e))?;
+            if secs < 60 {
+                return Err("TOKEN_PERMIT_DEADLINE_SECS must be at least 60".to_string());
+            }
+            policy.deadline = Duration::from_secs(secs);
+        }
+        if let Ok(val) = std::env::var("TOKEN_PERMIT_CACHE_SECS") {
+            let secs: u64 = val.trim().parse().map_err(|e| format!("Invalid TOKEN_PERMIT_CACHE_SECS: {}", e))?;
+            policy.cache_ttl = Duration::from_secs(secs);
+        }
+
+        Ok(policy)
+    }
+
+    pub fn global() -> &'static PermitPolicy {
+        static POLICY: OnceLock<PermitPolicy> = OnceLock::new();
+        POLICY.get_or_init(|| {
+            Self::from_env().unwrap_or_else(|e| {
+                tracing::warn!("Ignoring invalid token permit config: {}", e);
+                Self::default()
+            })
+        })
+    }
+}
+
+/// What a token contract itself supports
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PermitSupport {
+    /// Standard EIP-2612 `permit`, signed against the token's own domain
+    Eip2612 { domain_separator: [u8; 32] },
+    /// No usable `permit`; Permit2 may still work for owners that approved it
+    Unsupported,
+}
+
+/// How a relayed transfer was authorized
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PermitMethod {
+    Eip2612,
+    Permit2,
+}
+
+impl PermitMethod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PermitMethod::Eip2612 => "eip2612",
+            PermitMethod::Permit2 => "permit2",
+        }
+    }
+}
+
+/// Tokens to move out of a deposit address
+#[derive(Debug, Clone)]
+pub struct PermitTransferRequest {
+    /// Chain the gas cost is booked on
+    pub network: String,
+    pub swap_id: String,
+    pub contract: String,
+    pub owner_index: u32,
+    pub owner: String,
+    pub recipient: String,
+    /// Raw token units
+    pub amount: u128,
+}
+
+/// A transaction the hot wallet sent
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RelayedTx {
+    pub tx_hash: String,
+    pub tx_type: TxType,
+    pub gas_limit: u64,
+}
+
+/// A broadcast relayed transfer
+#[derive(Debug, Clone)]
+pub struct PermitTransfer {
+    pub method: PermitMethod,
+    /// In nonce order; the last one moves the tokens
+    pub txs: Vec<RelayedTx>,
+    pub gas_price: u64,
+}
+
+impl PermitTransfer {
+    /// Hash of the transaction that moves the tokens
+    pub fn tx_hash(&self) -> &str {
+        self.txs.last().map(|tx| tx.tx_hash.as_str()).unwrap_or_default()
+    }
+}
+
+/// Calls the hot wallet will send, in order
+struct Plan {
+    method: PermitMethod,
+    calls: Vec<(String, String, TxType)>,
+}
+
+/// Submits permits signed by deposit addresses from the hot wallet. Share
+/// one relayer between payouts so token support is detected once and the
+/// hot wallet's nonces are read one transfer at a time.
+pub struct PermitRelayer {
+    db: Pool<MySql>,
+    provider: Arc<dyn BlockchainProvider>,
+    master_seed: String,
+    signing: SigningService,
+    policy: PermitPolicy,
+    support: Mutex<HashMap<String, (PermitSupport, Instant)>>,
+    sending: tokio::sync::Mutex<()>,
+}
+
+impl PermitRelayer {
+    pub fn new(db: Pool<MySql>, provider: Arc<dyn BlockchainProvider>, master_seed: String) -> Self {
+        let signing = SigningService::from_config(&master_seed);
+        Self {
+            db,
+            provider,
+            master_seed,
+            signing,
+            policy: PermitPolicy::global().clone(),
+            support: Mutex::new(HashMap::new()),
+            sending: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    pub fn with_policy(mut self, policy: PermitPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Replace the signer configured by SIGNER_BACKEND
+    pub fn with_signer(mut self, signer: Arc<dyn Signer>) -> Self {
+        self.signing = SigningService::new(signer);
+        self
+    }
+
+    /// Move `request.amount` to the recipient with the hot wallet paying
+    /// the gas. `None` when no permit route applies and nothing was sent;
+    /// an error only once something was broadcast.
+    pub async fn transfer(&self, request: &PermitTransferRequest) -> Result<Option<PermitTransfer>, String> {
+        if !self.policy.enabled {
+            return Ok(None);
+        }
+        let hot_wallet = derivation::derive_evm_address(&self.master_seed, HOT_WALLET_INDEX).await?;
+
+        let plan = match self.plan(request, &hot_wallet).await {
+            Ok(Some(plan)) => plan,
+            Ok(None) => return Ok(None),
+            Err(e) => {
+                tracing::warn!("Swap {}: permit check for {} failed, falling back: {}", request.swap_id, request.contract, e);
+                return Ok(None);
+            }
+        };
+
+        self.send(request, &hot_wallet, plan).await
+    }
+
+    /// Support of `contract`, from the cache while it is fresh
+    pub async fn support(&self, contract: &str, probe_owner: &str) -> Result<PermitSupport, RpcError> {
+        let key = contract.to_lowercase();
+        let cached = self.support.lock().unwrap_or_else(|e| e.into_inner()).get(&key).copied();
+        if let Some((support, at)) = cached {
+            if at.elapsed() < self.policy.cache_ttl {
+                return Ok(support);
+            }
+        }
+
+        let support = detect_support(self.provider.as_ref(), contract, probe_owner).await?;
+        self.remember(&key, support);
+        Ok(support)
+    }
+
+    fn remember(&self, contract: &str, support: PermitSupport) {
+        self.support
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(contract.to_lowercase(), (support, Instant::now()));
+    }
+
+    async fn plan(&self, request: &PermitTransferRequest, hot_wallet: &str) -> Result<Option<Plan>, RpcError> {
+        let transfer_from = erc20_transfer_from_data(&request.owner, &request.recipient, request.amount)
+            .map_err(RpcError::Parse)?;
+        let deadline = chrono::Utc::now().timestamp() as u64 + self.policy.deadline.as_secs();
+
+        // An earlier attempt got its permit on chain but not the transfer
+        if self.allowance(&request.contract, &request.owner, hot_wallet).await? >= request.amount {
+            return Ok(Some(Plan {
+                method: PermitMethod::Eip2612,
+                calls: vec![(request.contract.clone(), transfer_from, TxType::TokenTransferFrom)],
+            }));
+        }
+
+        if let PermitSupport::Eip2612 { domain_separator } = self.support(&request.contract, hot_wallet).await? {
+            let nonce = read_uint(self.provider.as_ref(), &request.contract, &with_address(NONCES_SELECTOR, &request.owner)?).await?;
+            let struct_hash = eip2612_struct_hash(&request.owner, hot_wallet, request.amount, nonce, deadline)
+                .map_err(RpcError::Parse)?;
+            let signature = self.signing
+                .sign_evm_typed_data(request.owner_index, &domain_separator, &struct_hash)
+                .await
+                .map_err(RpcError::Rpc)?;
+            let permit = eip2612_permit_data(&request.owner, hot_wallet, request.amount, deadline, &signature)
+                .map_err(RpcError::Parse)?;
+
+            // A permit only sets an allowance, so it simulates whatever the balance
+            match self.provider.call_contract(Some(hot_wallet), &request.contract, &permit).await {
+                Ok(_) => {
+                    return Ok(Some(Plan {
+                        method: PermitMethod::Eip2612,
+                        calls: vec![
+                            (request.contract.clone(), permit, TxType::TokenPermit),
+                            (request.contract.clone(), transfer_from, TxType::TokenTransferFrom),
+                        ],
+                    }));
+                }
+                Err(RpcError::Rpc(e)) => {
+                    tracing::warn!("Permit for {} reverted in simulation, not using it again: {}", request.contract, e);
+                    self.remember(&request.contract, PermitSupport::Unsupported);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        if self.allowance(&request.contract, &request.owner, PERMIT2_ADDRESS).await? >= request.amount {
+            let nonce = permit2_nonce(&request.swap_id);
+            let domain_separator = permit2_domain_separator(signing_chain_id("ethereum"));
+            let struct_hash = permit2_struct_hash(&request.contract, request.amount, hot_wallet, nonce, deadline)
+                .map_err(RpcError::Parse)?;
+            let signature = self.signing
+                .sign_evm_typed_data(request.owner_index, &domain_separator, &struct_hash)
+                .await
+                .map_err(RpcError::Rpc)?;
+            let data = permit2_transfer_data(
+                &request.contract,
+                request.amount,
+                nonce,
+                deadline,
+                &request.recipient,
+                &request.owner,
+                &signature,
+            )
+            .map_err(RpcError::Parse)?;
+
+            match self.provider.call_contract(Some(hot_wallet), PERMIT2_ADDRESS, &data).await {
+                Ok(_) => {
+                    return Ok(Some(Plan {
+                        method: PermitMethod::Permit2,
+                        calls: vec![(PERMIT2_ADDRESS.to_string(), data, TxType::PermitTransfer)],
+                    }));
+                }
+                Err(RpcError::Rpc(e)) => {
+                    tracing::warn!("Permit2 transfer of {} reverted in simulation: {}", request.contract, e);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(None)
+    }
+
+    async fn allowance(&self, contract: &str, owner: &str, spender: &str) -> Result<u128, RpcError> {
+        let spender = address_word(spender).map_err(RpcError::Parse)?;
+        let data = format!("{}{}", with_address(ALLOWANCE_SELECTOR, owner)?, hex::encode(spender));
+        read_uint(self.provider.as_ref(), contract, &data).await
+    }
+
+    async fn send(
+        &self,
+        request: &PermitTransferRequest,
+        hot_wallet: &str,
+        plan: Plan,
+    ) -> Result<Option<PermitTransfer>, String> {
+        let _sending = self.sending.lock().await;
+
+        let gas_price = self.provider.get_gas_price().await
+            .map_err(|e| format!("Failed to get gas price: {}", e))?;
+        let gas_limit: u64 = plan.calls.iter().map(|(_, _, tx_type)| tx_type.evm_gas_limit()).sum();
+        let cost = amount::from_minor_units(gas_price as u128 * gas_limit as u128, amount::EVM_NATIVE_DECIMALS)
+            .map_err(|e| e.to_string())?;
+
+        // The gas station raises the low-balance alert when it is asked instead
+        let balance = self.provider.get_balance(hot_wallet).await
+            .map_err(|e| format!("Failed to get hot wallet balance: {}", e))?;
+        if balance < cost {
+            tracing::warn!("Swap {}: hot wallet can't pay {} for a relayed transfer", request.swap_id, cost);
+            return Ok(None);
+        }
+
+        let mut nonce = self.provider.get_transaction_count(hot_wallet).await
+            .map_err(|e| format!("Failed to get nonce: {}", e))?;
+        let mut txs: Vec<RelayedTx> = Vec::with_capacity(plan.calls.len());
+
+        for (to, data, tx_type) in plan.calls {
+            // Signed for Ethereum (its test network in a sandbox), like the
+            // payouts it replaces
+            let tx = EvmTransaction {
+                to_address: to,
+                amount: Decimal::ZERO,
+                token: "NATIVE".to_string(),
+                chain_id: signing_chain_id("ethereum"),
+                nonce,
+                gas_price,
+                gas_limit: tx_type.evm_gas_limit(),
+                data,
+            };
+
+            let sent = match self.signing.sign_evm(HOT_WALLET_INDEX, &tx).await {
+                Ok(signature) => self.provider.send_raw_transaction(&signature).await.map_err(|e| e.to_string()),
+                Err(e) => Err(e),
+            };
+
+            match sent {
+                Ok(tx_hash) => {
+                    nonce += 1;
+                    txs.push(RelayedTx { tx_hash, tx_type, gas_limit: tx.gas_limit });
+                }
+                // Nothing on chain yet: the gas station route is still open
+                Err(e) if txs.is_empty() => {
+                    tracing::warn!("Swap {}: relayed {} failed to broadcast, falling back: {}", request.swap_id, tx_type.as_str(), e);
+                    return Ok(None);
+                }
+                Err(e) => {
+                    self.book(request, &txs, gas_price).await;
+                    return Err(format!(
+                        "Permit {} sent but {} failed to broadcast: {}",
+                        txs[0].tx_hash,
+                        tx_type.as_str(),
+                        e
+                    ));
+                }
+            }
+        }
+
+        self.book(request, &txs, gas_price).await;
+        tracing::info!(
+            "Swap {}: {} moved with {} via {} hot wallet transaction(s)",
+            request.swap_id,
+            request.contract,
+            plan.method.as_str(),
+            txs.len()
+        );
+
+        Ok(Some(PermitTransfer { method: plan.method, txs, gas_price }))
+    }
+
+    /// Gas the hot wallet paid goes against the swap, like a top-up. The
+    /// transactions are already broadcast, so a failed write is only logged.
+    async fn book(&self, request: &PermitTransferRequest, txs: &[RelayedTx], gas_price: u64) {
+        let gas: u128 = txs.iter().map(|tx| tx.gas_limit as u128).sum();
+        let cost = match amount::from_minor_units(gas_price as u128 * gas, amount::EVM_NATIVE_DECIMALS) {
+            Ok(cost) => cost,
+            Err(e) => {
+                tracing::warn!("Failed to price relayed gas for swap {}: {}", request.swap_id, e);
+                return;
+            }
+        };
+        if let Err(e) = CommissionCrud::new(self.db.clone())
+            .record_gas_cost(&request.swap_id, (native_currency(&request.network), &request.network), cost)
+            .await
+        {
+            tracing::warn!("Failed to book relayed gas against swap {}: {}", request.swap_id, e);
+        }
+    }
+}
+
+// =============================================================================
+// DETECTION
+// =============================================================================
+
+/// Read what `contract` supports. `probe_owner` is any address, asked for
+/// its permit nonce to check `nonces` exists. Reverts mean unsupported;
+/// network errors are returned so they aren't cached as such.
+pub async fn detect_support(
+    provider: &dyn BlockchainProvider,
+    contract: &str,
+    probe_owner: &str,
+) -> Result<PermitSupport, RpcError> {
+    let domain_separator = match provider.call_contract(None, contract, DOMAIN_SEPARATOR_SELECTOR).await {
+        Ok(result) => match parse_word(&result) {
+            Some(word) if word != [0u8; 32] => word,
+            _ => return Ok(PermitSupport::Unsupported),
+        },
+        Err(RpcError::Rpc(_)) => return Ok(PermitSupport::Unsupported),
+        Err(e) => return Err(e),
+    };
+
+    match provider.call_contract(None, contract, &with_address(NONCES_SELECTOR, probe_owner)?).await {
+        Ok(result) if parse_word(&result).is_some() => {}
+        Ok(_) | Err(RpcError::Rpc(_)) => return Ok(PermitSupport::Unsupported),
+        Err(e) => return Err(e),
+    }
+
+    // Exposing the typehash is optional, but one that differs (DAI's
+    // `allowed` flag) means a permit we don't build
+    match provider.call_contract(None, contract, PERMIT_TYPEHASH_SELECTOR).await {
+        Ok(result) => match parse_word(&result) {
+            Some(word) if word != type_hash(EIP2612_PERMIT_TYPE) => return Ok(PermitSupport::Unsupported),
+            _ => {}
+        },
+        Err(RpcError::Rpc(_)) => {}
+        Err(e) => return Err(e),
+    }
+
+    Ok(PermitSupport::Eip2612 { domain_separator })
+}
+
+async fn read_uint(provider: &dyn BlockchainProvider, contract: &str, data: &str) -> Result<u128, RpcError> {
+    let result = provider.call_contract(None, contract, data).await?;
+    let word = parse_word(&result).ok_or_else(|| RpcError::Parse(format!("Expected one word from {}", contract)))?;
+    // Saturate beyond u128, like balances; unlimited allowances are 2^256 - 1
+    if word[..16].iter().any(|b| *b != 0) {
+        return Ok(u128::MAX);
+    }
+    Ok(u128::from_be_bytes(word[16..].try_into().unwrap_or_default()))
+}
+
+/// First 32 bytes of an `eth_call` result
+fn parse_word(result: &str) -> Option<[u8; 32]> {
+    let bytes = hex::decode(result.trim_start_matches("0x")).ok()?;
+    bytes.get(..32)?.try_into().ok()
+}
+
+fn with_address(selector: &str, address: &str) -> Result<String, RpcError> {
+    address_word(address)
+        .map(|word| format!("{}{}", selector, hex::encode(word)))
+        .map_err(RpcError::Parse)
+}
+
+// =============================================================================
+// ENCODING
+// =============================================================================
+
+fn keccak(data: &[u8]) -> [u8; 32] {
+    Keccak256::digest(data).into()
+}
+
+fn type_hash(encoded_type: &str) -> [u8; 32] {
+    keccak(encoded_type.as_bytes())
+}
+
+fn address_word(address: &str) -> Result<[u8; 32], String> {
+    let digits = address.trim_start_matches("0x");
+    let bytes = hex::decode(digits).map_err(|_| format!("Invalid EVM address: 0x{}", digits))?;
+    if bytes.len() != 20 {
+        return Err(format!("Invalid EVM address: 0x{}", digits));
+    }
+    let mut word = [0u8; 32];
+    word[12..].copy_from_slice(&bytes);
+    Ok(word)
+}
+
+fn uint_word(value: u128) -> [u8; 32] {
+    let mut word = [0u8; 32];
+    word[16..].copy_from_slice(&value.to_be_bytes());
+    word
+}
+
+/// `0x{r}{s}{v}` split into its parts
+fn split_signature(signature: &str) -> Result<([u8; 32], [u8; 32], u8), String> {
+    let bytes = hex::decode(signature.trim_start_matches("0x")).map_err(|e| format!("Invalid signature: {}", e))?;
+    if bytes.len() != 65 {
+        return Err(format!("Invalid signature length: {} bytes", bytes.len()));
+    }
+    let mut r = [0u8; 32];
+    let mut s = [0u8; 32];
+    r.copy_from_slice(&bytes[..32]);
+    s.copy_from_slice(&bytes[32..64]);
+    Ok((r, s, bytes[64]))
+}
+
+/// EIP-712 hash of an EIP-2612 `Permit`
+pub fn eip2612_struct_hash(
+    owner: &str,
+    spender: &str,
+    value: u128,
+    nonce: u128,
+    deadline: u64,
+) -> Result<[u8; 32], String> {
+    let mut encoded = type_hash(EIP2612_PERMIT_TYPE).to_vec();
+    encoded.extend(address_word(owner)?);
+    encoded.extend(address_word(spender)?);
+    encoded.extend(uint_word(value));
+    encoded.extend(uint_word(nonce));
+    encoded.extend(uint_word(deadline as u128));
+    Ok(keccak(&encoded))
+}
+
+/// Call data for `permit(owner, spender, value, deadline, v, r, s)`, as hex
+pub fn eip2612_permit_data(
+    owner: &str,
+    spender: &str,
+    value: u128,
+    deadline: u64,
+    signature: &str,
+) -> Result<String, String> {
+    let (r, s, v) = split_signature(signature)?;
+    // permit(address,address,uint256,uint256,uint8,bytes32,bytes32) = 0xd505accf
+    let mut data = String::from("0xd505accf");
+    data.push_str(&hex::encode(address_word(owner)?));
+    data.push_str(&hex::encode(address_word(spender)?));
+    data.push_str(&format!("{:064x}", value));
+    data.push_str(&format!("{:064x}", deadline));
+    data.push_str(&format!("{:064x}", v));
+    data.push_str(&hex::encode(r));
+    data.push_str(&hex::encode(s));
+    Ok(data)
+}
+
+/// Permit2's EIP-712 domain on `chain_id`
+pub fn permit2_domain_separator(chain_id: u32) -> [u8; 32] {
+    let mut encoded = type_hash(PERMIT2_DOMAIN_TYPE).to_vec();
+    encoded.extend(keccak(b"Permit2"));
+    encoded.extend(uint_word(chain_id as u128));
+    encoded.extend(address_word(PERMIT2_ADDRESS).unwrap_or_default());
+    keccak(&encoded)
+}
+
+/// EIP-712 hash of a Permit2 `PermitTransferFrom` letting `spender` move
+/// `amount` of `token`
+pub fn permit2_struct_hash(
+    token: &str,
+    amount: u128,
+    spender: &str,
+    nonce: u128,
+    deadline: u64,
+) -> Result<[u8; 32], String> {
+    let mut permitted = type_hash(PERMIT2_TOKEN_PERMISSIONS_TYPE).to_vec();
+    permitted.extend(address_word(token)?);
+    permitted.extend(uint_word(amount));
+
+    let mut encoded = type_hash(PERMIT2_TRANSFER_FROM_TYPE).to_vec();
+    encoded.extend(keccak(&permitted));
+    encoded.extend(address_word(spender)?);
+    encoded.extend(uint_word(nonce));
+    encoded.extend(uint_word(deadline as u128));
+    Ok(keccak(&encoded))
+}
+
+/// Permit2 nonces are unordered; one derived from the swap makes a second
+/// transfer for the same swap revert
+pub fn permit2_nonce(swap_id: &str) -> u128 {
+    let hash = keccak(format!("permit2:{}", swap_id).as_bytes());
+    u128::from_be_bytes(hash[..16].try_into().unwrap_or_default())
+}
+
+/// Call data for Permit2 `permitTransferFrom(permit, transferDetails,
+/// owner, signature)` sending the whole permitted amount, as hex
+pub fn permit2_transfer_data(
+    token: &str,
+    amount: u128,
+    nonce: u128,
+    deadline: u64,
+    recipient: &str,
+    owner: &str,
+    signature: &str,
+) -> Result<String, String> {
+    let signature = hex::decode(signature.trim_start_matches("0x")).map_err(|e| format!("Invalid signature: {}", e))?;
+    if signature.len() != 65 {
+        return Err(format!("Invalid signature length: {} bytes", signature.len()));
+    }
+
+    // permitTransferFrom(((address,uint256),uint256,uint256),(address,uint256),address,bytes) = 0x30f28b7a
+    let mut data = String::from("0x30f28b7a");
+    // The structs are static and inline; the signature follows the 8 head words
+    data.push_str(&hex::encode(address_word(token)?));
+    data.push_str(&format!("{:064x}", amount));
+    data.push_str(&format!("{:064x}", nonce));
+    data.push_str(&format!("{:064x}", deadline));
+    data.push_str(&hex::encode(address_word(recipient)?));
+    data.push_str(&format!("{:064x}", amount));
+    data.push_str(&hex::encode(address_word(owner)?));
+    data.push_str(&format!("{:064x}", 8 * 32));
+
+    data.push_str(&format!("{:064x}", signature.len()));
+    let mut padded = signature;
+    padded.resize(96, 0);
+    data.push_str(&hex::encode(padded));
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OWNER: &str = "0x742d35Cc6634C0532925a3b844Bc454e4438f44e";
+    const SPENDER: &str = "0x1111111111111111111111111111111111111111";
+    const TOKEN: &str = "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48";
+
+    #[test]
+    fn test_type_hashes() {
+        assert_eq!(
+            hex::encode(type_hash(EIP2612_PERMIT_TYPE)),
+            "6e71edae12b1b97f4d1f60370fef10105fa2faae0126114a169c64845d6126c9"
+        );
+        assert_eq!(
+            hex::encode(type_hash(PERMIT2_TOKEN_PERMISSIONS_TYPE)),
+            "618358ac3db8dc274f0cd8829da7e234bd48cd73c4a740aede1adec9846d06a1"
+        );
+        assert_eq!(
+            hex::encode(type_hash(PERMIT2_TRANSFER_FROM_TYPE)),
+            "939c21a48a8dbe3a9a2404a1d46691e4d39f6583d6ec6b35714604c986d80106"
+        );
+    }
+
+    #[test]
+    fn test_eip2612_permit_data_layout() {
+        let signature = format!("0x{}{}1c", "aa".repeat(32), "bb".repeat(32));
+        let data = eip2612_permit_data(OWNER, SPENDER, 1_000_000, 1_700_000_000, &signature).unwrap();
+
+        assert!(data.starts_with("0xd505accf"));
+        assert_eq!(data.len(), 2 + 8 + 7 * 64);
+        let words: Vec<&str> = (0..7).map(|i| &data[10 + i * 64..10 + (i + 1) * 64]).collect();
+        assert_eq!(words[0], format!("{:0>64}", &OWNER[2..].to_lowercase()));
+        assert_eq!(words[2], format!("{:064x}", 1_000_000));
+        assert_eq!(words[4], format!("{:064x}", 0x1c));
+        assert_eq!(words[5], "aa".repeat(32));
+        assert_eq!(words[6], "bb".repeat(32));
+
+        assert!(eip2612_permit_data(OWNER, SPENDER, 1, 1, "0x1234").is_err());
+        assert!(eip2612_permit_data("0x1234", SPENDER, 1, 1, &signature).is_err());
+    }
+
+    #[test]
+    fn test_permit2_transfer_data_layout() {
+        let signature = format!("0x{}{}1b", "aa".repeat(32), "bb".repeat(32));
+        let data = permit2_transfer_data(TOKEN, 500, 7, 1_700_000_000, SPENDER, OWNER, &signature).unwrap();
+
+        assert!(data.starts_with("0x30f28b7a"));
+        // 8 head words, the length and the signature padded to 3 words
+        assert_eq!(data.len(), 2 + 8 + 12 * 64);
+        let word = |i: usize| &data[10 + i * 64..10 + (i + 1) * 64];
+        assert_eq!(word(1), format!("{:064x}", 500));
+        assert_eq!(word(5), format!("{:064x}", 500));
+        assert_eq!(word(7), format!("{:064x}", 0x100));
+        assert_eq!(word(8), format!("{:064x}", 65));
+        assert!(word(11).starts_with("1b"));
+    }
+
+    #[test]
+    fn test_struct_hashes_cover_every_field() {
+        let base = eip2612_struct_hash(OWNER, SPENDER, 100, 0, 1_000).unwrap();
+        assert_ne!(base, eip2612_struct_hash(OWNER, SPENDER, 101, 0, 1_000).unwrap());
+        assert_ne!(base, eip2612_struct_hash(OWNER, SPENDER, 100, 1, 1_000).unwrap());
+        assert_ne!(base, eip2612_struct_hash(OWNER, TOKEN, 100, 0, 1_000).unwrap());
+
+        let base = permit2_struct_hash(TOKEN, 100, SPENDER, 1, 1_000).unwrap();
+        assert_ne!(base, permit2_struct_hash(TOKEN, 100, OWNER, 1, 1_000).unwrap());
+        assert_ne!(base, permit2_struct_hash(TOKEN, 100, SPENDER, 1, 1_001).unwrap());
+        assert_ne!(permit2_domain_separator(1), permit2_domain_separator(11155111));
+    }
+
+    #[test]
+    fn test_permit2_nonce_is_stable_per_swap() {
+        assert_eq!(permit2_nonce("swap-a"), permit2_nonce("swap-a"));
+        assert_ne!(permit2_nonce("swap-a"), permit2_nonce("swap-b"));
+    }
+
+    #[test]
+    fn test_parse_word() {
+        assert_eq!(parse_word(&format!("0x{}", "11".repeat(32))), Some([0x11; 32]));
+        assert_eq!(parse_word("0x"), None);
+        assert_eq!(parse_word("0xzz"), None);
+    }
+}
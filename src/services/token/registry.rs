@@ -7,9 +7,60 @@ use rust_decimal::Decimal;
 
 use crate::services::token::{Token, TokenType, TokenError};
 
+/// Issuer contracts for the stablecoins deposits are most often spoofed as.
+/// Verified rows in `tokens` extend this list.
+const CANONICAL_CONTRACTS: &[(&str, &str, &str, u8)] = &[
+    // (network, symbol, contract, decimals)
+    ("ethereum", "USDT", "0xdAC17F958D2ee523a2206206994597C13D831ec7", 6),
+    ("ethereum", "USDC", "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48", 6),
+    ("ethereum", "DAI", "0x6B175474E89094C44Da98b954EedeAC495271d0F", 18),
+    ("bsc", "USDT", "0x55d398326f99059fF775485246999027B3197955", 18),
+    ("bsc", "USDC", "0x8AC76a51cc950d9822D68b83fE1Ad97B32Cd580d", 18),
+    ("polygon", "USDT", "0xc2132D05D31c914a87C6611C10748AEb04B58e8F", 6),
+    ("polygon", "USDC", "0x3c499c542cEF5E3811e1192ce70d8cC03d5c3359", 6),
+    ("arbitrum", "USDT", "0xFd086bC7CD5C481DCC9C85ebE478A1C0b69FCbb9", 6),
+    ("arbitrum", "USDC", "0xaf88d065e77c8cC2239327C5EDb3A432268e5831", 6),
+    ("optimism", "USDT", "0x94b008aA00579c1307B0EF2c499aD98a8ce58e58", 6),
+    ("optimism", "USDC", "0x0b2C639c533813f4Aa9D7837cAf62653d097Ff85", 6),
+    ("avalanche", "USDT", "0x9702230A8Ea53601f5cD2dc00fDBc13d4dF4A8c7", 6),
+    ("avalanche", "USDC", "0xB97EF9Ef8734C71904D8002F8b6Bc66Dd9c48a6E", 6),
+    ("base", "USDC", "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913", 6),
+];
+
+/// A token contract trusted for a symbol on one chain
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CanonicalToken {
+    pub network: String,
+    pub symbol: String,
+    /// Lower-cased
+    pub contract_address: String,
+    pub decimals: u8,
+}
+
+/// How a transfer's emitting contract relates to the token a deposit expects
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TransferVerdict {
+    /// Canonical contract for the expected symbol
+    Expected(CanonicalToken),
+    /// Canonical contract, but for a different token than the swap expects
+    OtherToken(CanonicalToken),
+    /// Not in the registry: a look-alike or spam token
+    NonCanonical,
+}
+
+/// Classify `contract` against a chain's canonical list
+pub fn classify_contract(canonical: &[CanonicalToken], expected_symbol: &str, contract: &str) -> TransferVerdict {
+    match canonical.iter().find(|t| t.contract_address.eq_ignore_ascii_case(contract)) {
+        Some(token) if token.symbol.eq_ignore_ascii_case(expected_symbol) => TransferVerdict::Expected(token.clone()),
+        Some(token) => TransferVerdict::OtherToken(token.clone()),
+        None => TransferVerdict::NonCanonical,
+    }
+}
+
 pub struct TokenRegistry {
     pool: MySqlPool,
     cache: Arc<RwLock<HashMap<String, Token>>>,  // key: "network:contract_address" or "network:symbol"
+    canonical: Arc<RwLock<HashMap<String, Vec<CanonicalToken>>>>,  // key: network
 }
 
 impl TokenRegistry {
@@ -17,8 +68,77 @@ impl TokenRegistry {
         Self {
             pool,
             cache: Arc::new(RwLock::new(HashMap::new())),
+            canonical: Arc::new(RwLock::new(HashMap::new())),
         }
     }
+
+    /// Canonical contracts on `network`: the built-in list plus verified,
+    /// active registry rows. A verified row overrides a built-in entry for
+    /// the same symbol.
+    pub async fn canonical_tokens(&self, network: &str) -> Result<Vec<CanonicalToken>, TokenError> {
+        let network = network.to_lowercase();
+        {
+            let cache = self.canonical.read().await;
+            if let Some(tokens) = cache.get(&network) {
+                return Ok(tokens.clone());
+            }
+        }
+
+        let verified: Vec<(String, String, i32)> = sqlx::query_as(
+            r#"
+            SELECT symbol, contract_address, decimals
+            FROM tokens
+            WHERE network = ? AND contract_address IS NOT NULL AND is_active = TRUE AND is_verified = TRUE
+            "#,
+        )
+        .bind(&network)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut tokens: Vec<CanonicalToken> = verified
+            .into_iter()
+            .map(|(symbol, contract, decimals)| CanonicalToken {
+                network: network.clone(),
+                symbol: symbol.to_uppercase(),
+                contract_address: contract.to_lowercase(),
+                decimals: decimals as u8,
+            })
+            .collect();
+
+        for (chain, symbol, contract, decimals) in CANONICAL_CONTRACTS {
+            if *chain == network && !tokens.iter().any(|t| t.symbol == *symbol) {
+                tokens.push(CanonicalToken {
+                    network: network.clone(),
+                    symbol: symbol.to_string(),
+                    contract_address: contract.to_lowercase(),
+                    decimals: *decimals,
+                });
+            }
+        }
+
+        self.canonical.write().await.insert(network, tokens.clone());
+        Ok(tokens)
+    }
+
+    /// Canonical contract for `symbol` on `network`, if the symbol is a token there
+    pub async fn canonical_token(&self, symbol: &str, network: &str) -> Result<Option<CanonicalToken>, TokenError> {
+        Ok(self
+            .canonical_tokens(network)
+            .await?
+            .into_iter()
+            .find(|t| t.symbol.eq_ignore_ascii_case(symbol)))
+    }
+
+    /// Check a transfer's emitting contract against the registry
+    pub async fn verify_transfer(
+        &self,
+        network: &str,
+        expected_symbol: &str,
+        contract: &str,
+    ) -> Result<TransferVerdict, TokenError> {
+        let canonical = self.canonical_tokens(network).await?;
+        Ok(classify_contract(&canonical, expected_symbol, contract))
+    }
     
     /// Get token by contract address and network
     pub async fn get_token(&self, contract_address: &str, network: &str) -> Result<Token, TokenError> {
@@ -272,12 +392,14 @@ impl TokenRegistry {
     async fn clear_cache_for_network(&self, network: &str) {
         let mut cache = self.cache.write().await;
         cache.retain(|key, _| !key.starts_with(&format!("{}:", network)));
+        self.canonical.write().await.remove(&network.to_lowercase());
     }
     
     /// Clear entire cache
     pub async fn clear_cache(&self) {
         let mut cache = self.cache.write().await;
         cache.clear();
+        self.canonical.write().await.clear();
     }
 }
 
@@ -303,4 +425,26 @@ mod tests {
         let key = format!("{}:symbol:{}", network, symbol.to_uppercase());
         assert_eq!(key, "ethereum:symbol:USDT");
     }
+
+    #[test]
+    fn test_classify_contract() {
+        let canonical: Vec<CanonicalToken> = CANONICAL_CONTRACTS
+            .iter()
+            .filter(|(network, ..)| *network == "ethereum")
+            .map(|(network, symbol, contract, decimals)| CanonicalToken {
+                network: network.to_string(),
+                symbol: symbol.to_string(),
+                contract_address: contract.to_lowercase(),
+                decimals: *decimals,
+            })
+            .collect();
+
+        let usdt = "0xDAC17F958D2EE523A2206206994597C13D831EC7";
+        assert!(matches!(classify_contract(&canonical, "usdt", usdt), TransferVerdict::Expected(t) if t.decimals == 6));
+        assert!(matches!(classify_contract(&canonical, "USDC", usdt), TransferVerdict::OtherToken(t) if t.symbol == "USDT"));
+        assert_eq!(
+            classify_contract(&canonical, "USDT", "0x0000000000000000000000000000000000000bad"),
+            TransferVerdict::NonCanonical
+        );
+    }
 }
@@ -0,0 +1,148 @@
+use hmac::{Hmac, Mac};
+use sha1::Sha1;
+use sqlx::{MySql, Pool};
+use subtle::ConstantTimeEq;
+
+type HmacSha1 = Hmac<Sha1>;
+
+/// RFC 6238 defaults used by authenticator apps
+const STEP_SECS: u64 = 30;
+const DIGITS: u32 = 6;
+/// Accept codes from one step either side to absorb clock drift
+const ALLOWED_SKEW_STEPS: i64 = 1;
+
+/// Decode an unpadded RFC 4648 base32 secret (case-insensitive, spaces ignored)
+pub fn decode_base32(secret: &str) -> Option<Vec<u8>> {
+    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+    let mut bits: u64 = 0;
+    let mut bit_count = 0;
+    let mut out = Vec::new();
+
+    for c in secret.bytes().filter(|c| !c.is_ascii_whitespace() && *c != b'=') {
+        let value = ALPHABET.iter().position(|a| *a == c.to_ascii_uppercase())? as u64;
+        bits = (bits << 5) | value;
+        bit_count += 5;
+        if bit_count >= 8 {
+            bit_count -= 8;
+            out.push((bits >> bit_count) as u8);
+            bits &= (1 << bit_count) - 1;
+        }
+    }
+
+    if out.is_empty() { None } else { Some(out) }
+}
+
+/// HOTP value (RFC 4226) for a counter
+pub fn hotp(key: &[u8], counter: u64) -> u32 {
+    let mut mac = HmacSha1::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(&counter.to_be_bytes());
+    let hash = mac.finalize().into_bytes();
+
+    let offset = (hash[hash.len() - 1] & 0x0f) as usize;
+    let binary = ((hash[offset] as u32 & 0x7f) << 24)
+        | ((hash[offset + 1] as u32) << 16)
+        | ((hash[offset + 2] as u32) << 8)
+        | (hash[offset + 3] as u32);
+
+    binary % 10u32.pow(DIGITS)
+}
+
+/// The time step a 6-digit TOTP code matches at `unix_time`. Every candidate
+/// step is computed and compared in constant time, so response timing says
+/// nothing about how close a guess was.
+pub fn matching_step_at(secret_b32: &str, code: &str, unix_time: u64) -> Option<u64> {
+    let code = code.trim();
+    if code.len() != DIGITS as usize || !code.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let key = decode_base32(secret_b32)?;
+
+    let step = (unix_time / STEP_SECS) as i64;
+    let mut matched = None;
+    for counter in (-ALLOWED_SKEW_STEPS..=ALLOWED_SKEW_STEPS).filter_map(|skew| u64::try_from(step + skew).ok()) {
+        let expected = format!("{:0width$}", hotp(&key, counter), width = DIGITS as usize);
+        if bool::from(expected.as_bytes().ct_eq(code.as_bytes())) {
+            matched = Some(counter);
+        }
+    }
+    matched
+}
+
+/// Verify a 6-digit TOTP code against a base32 secret at `unix_time`
+pub fn verify_totp_at(secret_b32: &str, code: &str, unix_time: u64) -> bool {
+    matching_step_at(secret_b32, code, unix_time).is_some()
+}
+
+fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Verify a TOTP code against the current time
+pub fn verify_totp(secret_b32: &str, code: &str) -> bool {
+    verify_totp_at(secret_b32, code, unix_now())
+}
+
+/// Verify a user's code and spend it. The step it matched is recorded, and a
+/// code for that step or an earlier one is refused from then on, so each code
+/// gates one action.
+pub async fn redeem_totp(pool: &Pool<MySql>, user_id: &str, secret_b32: &str, code: &str) -> Result<bool, sqlx::Error> {
+    let Some(step) = matching_step_at(secret_b32, code, unix_now()) else {
+        return Ok(false);
+    };
+
+    let spent = sqlx::query(
+        r#"
+        UPDATE users SET totp_last_step = ?
+        WHERE id = ? AND (totp_last_step IS NULL OR totp_last_step < ?)
+        "#,
+    )
+    .bind(step)
+    .bind(user_id)
+    .bind(step)
+    .execute(pool)
+    .await?;
+
+    Ok(spent.rows_affected() == 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // RFC 6238 appendix B secret "12345678901234567890"
+    const RFC_SECRET: &str = "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ";
+
+    #[test]
+    fn decodes_base32() {
+        assert_eq!(decode_base32(RFC_SECRET).unwrap(), b"12345678901234567890");
+        assert_eq!(decode_base32("gezd gnbv"), decode_base32("GEZDGNBV"));
+        assert!(decode_base32("not base32!").is_none());
+    }
+
+    #[test]
+    fn matches_rfc6238_vectors() {
+        // 8-digit RFC values truncated to the 6 digits apps display
+        assert!(verify_totp_at(RFC_SECRET, "287082", 59));
+        assert!(verify_totp_at(RFC_SECRET, "081804", 1111111109));
+        assert!(verify_totp_at(RFC_SECRET, "050471", 1111111111));
+    }
+
+    #[test]
+    fn rejects_wrong_or_stale_codes() {
+        assert!(!verify_totp_at(RFC_SECRET, "287083", 59));
+        assert!(!verify_totp_at(RFC_SECRET, "287082", 59 + 120));
+        assert!(!verify_totp_at(RFC_SECRET, "28708", 59));
+    }
+
+    #[test]
+    fn reports_the_matched_step() {
+        assert_eq!(matching_step_at(RFC_SECRET, "287082", 59), Some(1));
+        // The previous step's code, still inside the skew window
+        assert_eq!(matching_step_at(RFC_SECRET, "287082", 59 + 30), Some(1));
+        assert_eq!(matching_step_at(RFC_SECRET, "287083", 59), None);
+    }
+}
@@ -1,6 +1,15 @@
 use reqwest::Client;
+use std::future::Future;
+use std::time::Instant;
 
+use crate::modules::gift_cards::schema::{TrocadorGiftCard, TrocadorGiftCardOrder};
 use crate::modules::swap::schema::{TrocadorCurrency, TrocadorProvider, TrocadorTradeResponse};
+use crate::services::amount::Decimal;
+use crate::services::metrics::collectors::ProviderMetricsCollector;
+use crate::services::sandbox::SandboxConfig;
+
+/// `provider` label of the provider metrics
+pub const PROVIDER: &str = "trocador";
 
 /// Trocador API client
 /// Handles all communication with Trocador.app API
@@ -15,6 +24,8 @@ pub enum TrocadorError {
     HttpError(String),
     ParseError(String),
     ApiError(String),
+    /// The provider has no record of the requested order
+    NotFound(String),
 }
 
 impl std::fmt::Display for TrocadorError {
@@ -23,22 +34,70 @@ impl std::fmt::Display for TrocadorError {
             TrocadorError::HttpError(e) => write!(f, "HTTP error: {}", e),
             TrocadorError::ParseError(e) => write!(f, "Parse error: {}", e),
             TrocadorError::ApiError(e) => write!(f, "API error: {}", e),
+            TrocadorError::NotFound(id) => write!(f, "Trade not found: {}", id),
         }
     }
 }
 
 impl std::error::Error for TrocadorError {}
 
+impl TrocadorError {
+    /// `kind` label of the provider error counter
+    pub fn kind(&self) -> &'static str {
+        match self {
+            TrocadorError::HttpError(_) => "http",
+            TrocadorError::ParseError(_) => "parse",
+            TrocadorError::ApiError(_) => "api",
+            TrocadorError::NotFound(_) => "not_found",
+        }
+    }
+}
+
+/// Time a provider call and record its latency and any failure under
+/// `operation`
+async fn observed<T>(
+    operation: &str,
+    call: impl Future<Output = Result<T, TrocadorError>>,
+) -> Result<T, TrocadorError> {
+    let started = Instant::now();
+    let result = call.await;
+    if let Some(collector) = ProviderMetricsCollector::global() {
+        let error = result.as_ref().err().map(TrocadorError::kind);
+        collector.record_request(PROVIDER, operation, started.elapsed().as_secs_f64(), error);
+    }
+    result
+}
+
+/// Decode a trade, keeping the response as received for
+/// `provider_payloads`
+fn parse_trade(raw: serde_json::Value) -> Result<TrocadorTradeResponse, TrocadorError> {
+    let mut trade: TrocadorTradeResponse =
+        serde_json::from_value(raw.clone()).map_err(|e| TrocadorError::ParseError(e.to_string()))?;
+    trade.raw = Some(raw);
+    Ok(trade)
+}
+
 impl TrocadorClient {
+    /// Client for the production API, or for the sandbox API in a sandbox
+    /// deployment
     pub fn new(api_key: String) -> Self {
-        Self {
-            client: Client::new(),
-            api_key,
-            base_url: "https://api.trocador.app".to_string(),
+        let sandbox = SandboxConfig::global();
+        match &sandbox.api_url {
+            Some(url) if sandbox.enabled => Self {
+                client: Client::new(),
+                api_key: sandbox.api_key.clone().unwrap_or(api_key),
+                base_url: url.clone(),
+            },
+            _ => Self {
+                client: Client::new(),
+                api_key,
+                base_url: "https://api.trocador.app".to_string(),
+            },
         }
     }
 
     /// Fetch all currencies from Trocador /coins endpoint
+    #[tracing::instrument(name = "provider.request", skip_all, fields(provider = "trocador", provider.operation = "coins"))]
     pub async fn get_currencies(&self) -> Result<Vec<TrocadorCurrency>, TrocadorError> {
         let url = format!("{}/coins", self.base_url);
 
@@ -66,6 +125,7 @@ impl TrocadorClient {
     }
 
     /// Fetch all providers from Trocador /exchanges endpoint
+    #[tracing::instrument(name = "provider.request", skip_all, fields(provider = "trocador", provider.operation = "exchanges"))]
     pub async fn get_providers(&self) -> Result<Vec<TrocadorProvider>, TrocadorError> {
         let url = format!("{}/exchanges", self.base_url);
 
@@ -101,23 +161,176 @@ impl TrocadorClient {
     }
 
     /// Get rates from Trocador (new_rate)
+    #[tracing::instrument(name = "provider.request", skip_all, fields(provider = "trocador", provider.operation = "new_rate"))]
     pub async fn get_rates(
         &self,
         ticker_from: &str,
         network_from: &str,
         ticker_to: &str,
         network_to: &str,
-        amount: f64,
+        amount: Decimal,
     ) -> Result<crate::modules::swap::schema::TrocadorRatesResponse, TrocadorError> {
-        let url = format!("{}/new_rate", self.base_url);
+        observed("rates", async {
+            let url = format!("{}/new_rate", self.base_url);
+        
+            let params = [
+                ("ticker_from", ticker_from.to_string()),
+                ("network_from", network_from.to_string()),
+                ("ticker_to", ticker_to.to_string()),
+                ("network_to", network_to.to_string()),
+                ("amount_from", amount.to_string()),
+                ("best_only", "false".to_string()),
+            ];
+
+            let response = self
+                .client
+                .get(&url)
+                .header("API-Key", &self.api_key)
+                .query(&params)
+                .send()
+                .await
+                .map_err(|e| TrocadorError::HttpError(e.to_string()))?;
+
+            if !response.status().is_success() {
+                 let error_text = response.text().await.unwrap_or_default();
+                 return Err(TrocadorError::ApiError(format!(
+                    "API returned error: {}",
+                    error_text
+                )));
+            }
+
+            let rates_response: crate::modules::swap::schema::TrocadorRatesResponse = response
+                .json()
+                .await
+                .map_err(|e| TrocadorError::ParseError(e.to_string()))?;
+
+            Ok(rates_response)
+        })
+        .await
+    }
+
+    /// Create a new trade on Trocador (new_trade)
+    #[tracing::instrument(name = "provider.request", skip_all, fields(provider = "trocador", provider.operation = "new_trade"))]
+    pub async fn create_trade(
+        &self,
+        trade_id: Option<&str>,
+        ticker_from: &str,
+        network_from: &str,
+        ticker_to: &str,
+        network_to: &str,
+        amount: Decimal,
+        address: &str,
+        address_memo: Option<&str>,
+        refund: Option<&str>,
+        provider: &str,
+        fixed: bool,
+    ) -> Result<TrocadorTradeResponse, TrocadorError> {
+        observed("create", async {
+            let url = format!("{}/new_trade", self.base_url);
+
+            let mut params = vec![
+                ("ticker_from", ticker_from.to_string()),
+                ("network_from", network_from.to_string()),
+                ("ticker_to", ticker_to.to_string()),
+                ("network_to", network_to.to_string()),
+                ("amount_from", amount.to_string()),
+                ("address", address.to_string()),
+                ("provider", provider.to_string()),
+                ("fixed", fixed.to_string()),
+            ];
+
+            if let Some(id) = trade_id {
+                params.push(("id", id.to_string()));
+            }
+
+            if let Some(memo) = address_memo {
+                params.push(("address_memo", memo.to_string()));
+            }
+
+            if let Some(r) = refund {
+                params.push(("refund", r.to_string()));
+            }
+
+            let response = self
+                .client
+                .get(&url)
+                .header("API-Key", &self.api_key)
+                .query(&params)
+                .send()
+                .await
+                .map_err(|e| TrocadorError::HttpError(e.to_string()))?;
+
+            if !response.status().is_success() {
+                let error_text = response.text().await.unwrap_or_default();
+                return Err(TrocadorError::ApiError(format!(
+                    "API returned error: {}",
+                    error_text
+                )));
+            }
+
+            let raw: serde_json::Value = response
+                .json()
+                .await
+                .map_err(|e| TrocadorError::ParseError(e.to_string()))?;
+
+            parse_trade(raw)
+        })
+        .await
+    }
+
+    /// Get trade status from Trocador (trade)
+    #[tracing::instrument(name = "provider.request", skip_all, fields(provider = "trocador", provider.operation = "trade"))]
+    pub async fn get_trade_status(&self, trade_id: &str) -> Result<TrocadorTradeResponse, TrocadorError> {
+        observed("status", async {
+            let url = format!("{}/trade", self.base_url);
+        
+            let params = [("id", trade_id.to_string())];
+
+            let response = self
+                .client
+                .get(&url)
+                .header("API-Key", &self.api_key)
+                .query(&params)
+                .send()
+                .await
+                .map_err(|e| TrocadorError::HttpError(e.to_string()))?;
+
+            if response.status() == reqwest::StatusCode::NOT_FOUND {
+                return Err(TrocadorError::NotFound(trade_id.to_string()));
+            }
+
+            if !response.status().is_success() {
+                let error_text = response.text().await.unwrap_or_default();
+                return Err(TrocadorError::ApiError(format!(
+                    "API returned error: {}",
+                    error_text
+                )));
+            }
+
+            let raw: serde_json::Value = response
+                .json()
+                .await
+                .map_err(|e| TrocadorError::ParseError(e.to_string()))?;
+
+            parse_trade(raw)
+        })
+        .await
+    }
+
+    /// Validate address for a specific coin and network
+    #[tracing::instrument(name = "provider.request", skip_all, fields(provider = "trocador", provider.operation = "validateaddress"))]
+    pub async fn validate_address(
+        &self,
+        ticker: &str,
+        network: &str,
+        address: &str,
+    ) -> Result<bool, TrocadorError> {
+        let url = format!("{}/validateaddress", self.base_url);
         
         let params = [
-            ("ticker_from", ticker_from.to_string()),
-            ("network_from", network_from.to_string()),
-            ("ticker_to", ticker_to.to_string()),
-            ("network_to", network_to.to_string()),
-            ("amount_from", amount.to_string()),
-            ("best_only", "false".to_string()),
+            ("ticker", ticker.to_string()),
+            ("network", network.to_string()),
+            ("address", address.to_string()),
         ];
 
         let response = self
@@ -130,55 +343,33 @@ impl TrocadorClient {
             .map_err(|e| TrocadorError::HttpError(e.to_string()))?;
 
         if !response.status().is_success() {
-             let error_text = response.text().await.unwrap_or_default();
-             return Err(TrocadorError::ApiError(format!(
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(TrocadorError::ApiError(format!(
                 "API returned error: {}",
                 error_text
             )));
         }
 
-        let rates_response: crate::modules::swap::schema::TrocadorRatesResponse = response
+        // Parse response: {"result": true} or {"result": false}
+        let response_json: serde_json::Value = response
             .json()
             .await
             .map_err(|e| TrocadorError::ParseError(e.to_string()))?;
 
-        Ok(rates_response)
-    }
-
-    /// Create a new trade on Trocador (new_trade)
-    pub async fn create_trade(
-        &self,
-        trade_id: Option<&str>,
-        ticker_from: &str,
-        network_from: &str,
-        ticker_to: &str,
-        network_to: &str,
-        amount: f64,
-        address: &str,
-        refund: Option<&str>,
-        provider: &str,
-        fixed: bool,
-    ) -> Result<TrocadorTradeResponse, TrocadorError> {
-        let url = format!("{}/new_trade", self.base_url);
+        let is_valid = response_json
+            .get("result")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
 
-        let mut params = vec![
-            ("ticker_from", ticker_from.to_string()),
-            ("network_from", network_from.to_string()),
-            ("ticker_to", ticker_to.to_string()),
-            ("network_to", network_to.to_string()),
-            ("amount_from", amount.to_string()),
-            ("address", address.to_string()),
-            ("provider", provider.to_string()),
-            ("fixed", fixed.to_string()),
-        ];
+        Ok(is_valid)
+    }
 
-        if let Some(id) = trade_id {
-            params.push(("id", id.to_string()));
-        }
+    /// Fetch gift card catalog (giftcards)
+    #[tracing::instrument(name = "provider.request", skip_all, fields(provider = "trocador", provider.operation = "giftcards"))]
+    pub async fn get_giftcards(&self, country: Option<&str>) -> Result<Vec<TrocadorGiftCard>, TrocadorError> {
+        let url = format!("{}/giftcards", self.base_url);
 
-        if let Some(r) = refund {
-            params.push(("refund", r.to_string()));
-        }
+        let params = [("country", country.unwrap_or_default().to_string())];
 
         let response = self
             .client
@@ -197,19 +388,33 @@ impl TrocadorClient {
             )));
         }
 
-        let trade_response: TrocadorTradeResponse = response
+        let cards: Vec<TrocadorGiftCard> = response
             .json()
             .await
             .map_err(|e| TrocadorError::ParseError(e.to_string()))?;
 
-        Ok(trade_response)
+        Ok(cards)
     }
 
-    /// Get trade status from Trocador (trade)
-    pub async fn get_trade_status(&self, trade_id: &str) -> Result<TrocadorTradeResponse, TrocadorError> {
-        let url = format!("{}/trade", self.base_url);
-        
-        let params = [("id", trade_id.to_string())];
+    /// Create a gift card order (order_giftcard)
+    #[tracing::instrument(name = "provider.request", skip_all, fields(provider = "trocador", provider.operation = "order_giftcard"))]
+    pub async fn order_giftcard(
+        &self,
+        product_id: &str,
+        ticker_from: &str,
+        network_from: &str,
+        amount: Decimal,
+        email: &str,
+    ) -> Result<TrocadorGiftCardOrder, TrocadorError> {
+        let url = format!("{}/order_giftcard", self.base_url);
+
+        let params = [
+            ("product_id", product_id.to_string()),
+            ("ticker_from", ticker_from.to_string()),
+            ("network_from", network_from.to_string()),
+            ("amount", amount.to_string()),
+            ("email", email.to_string()),
+        ];
 
         let response = self
             .client
@@ -228,28 +433,20 @@ impl TrocadorClient {
             )));
         }
 
-        let trade_response: TrocadorTradeResponse = response
+        let order: TrocadorGiftCardOrder = response
             .json()
             .await
             .map_err(|e| TrocadorError::ParseError(e.to_string()))?;
 
-        Ok(trade_response)
+        Ok(order)
     }
 
-    /// Validate address for a specific coin and network
-    pub async fn validate_address(
-        &self,
-        ticker: &str,
-        network: &str,
-        address: &str,
-    ) -> Result<bool, TrocadorError> {
-        let url = format!("{}/validateaddress", self.base_url);
-        
-        let params = [
-            ("ticker", ticker.to_string()),
-            ("network", network.to_string()),
-            ("address", address.to_string()),
-        ];
+    /// Get raw trade payload (trade). Gift card orders carry redeem data in `details`.
+    #[tracing::instrument(name = "provider.request", skip_all, fields(provider = "trocador", provider.operation = "trade"))]
+    pub async fn get_trade_raw(&self, trade_id: &str) -> Result<serde_json::Value, TrocadorError> {
+        let url = format!("{}/trade", self.base_url);
+
+        let params = [("id", trade_id.to_string())];
 
         let response = self
             .client
@@ -268,17 +465,9 @@ impl TrocadorClient {
             )));
         }
 
-        // Parse response: {"result": true} or {"result": false}
-        let response_json: serde_json::Value = response
+        response
             .json()
             .await
-            .map_err(|e| TrocadorError::ParseError(e.to_string()))?;
-
-        let is_valid = response_json
-            .get("result")
-            .and_then(|v| v.as_bool())
-            .unwrap_or(false);
-
-        Ok(is_valid)
+            .map_err(|e| TrocadorError::ParseError(e.to_string()))
     }
 }
@@ -0,0 +1,302 @@
+//! Per-user API usage. Authenticated requests are counted in Redis per user
+//! and UTC day (requests, errors and rate-limit hits); `UsageFlusher` copies
+//! the day totals into `api_usage_daily` so GET /account/usage can show a
+//! history beyond what Redis keeps.
+//!
+//! Integrators authenticate with their account's bearer tokens, so usage is
+//! keyed by the user the token belongs to.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+    response::Response,
+};
+use chrono::{NaiveDate, Utc};
+use sqlx::{MySql, Pool};
+use tower::{Layer, Service};
+
+use crate::services::jwt::JwtService;
+use crate::services::redis_cache::RedisService;
+use crate::services::session::access_token;
+
+/// Counters outlive the day by this long so the flusher can finish it
+const COUNTER_TTL_SECS: i64 = 3 * 24 * 3600;
+const DEFAULT_FLUSH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// One user's counters for one day
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct UsageCounts {
+    pub requests: u64,
+    /// Responses with a 4xx or 5xx status other than 429
+    pub errors: u64,
+    pub rate_limited: u64,
+    /// Unix time of the latest 429, if any
+    pub last_limited_at: Option<i64>,
+}
+
+impl UsageCounts {
+    fn from_hash(hash: &HashMap<String, i64>) -> Self {
+        let count = |field: &str| hash.get(field).copied().unwrap_or(0).max(0) as u64;
+        Self {
+            requests: count("requests"),
+            errors: count("errors"),
+            rate_limited: count("rate_limited"),
+            last_limited_at: hash.get("last_limited").copied(),
+        }
+    }
+}
+
+/// Redis counters shared by every replica
+#[derive(Clone)]
+pub struct UsageCounters {
+    redis: RedisService,
+}
+
+impl UsageCounters {
+    pub fn new(redis: RedisService) -> Self {
+        Self { redis }
+    }
+
+    fn counts_key(day: NaiveDate, user_id: &str) -> String {
+        format!("usage:{}:{}", day, user_id)
+    }
+
+    fn users_key(day: NaiveDate) -> String {
+        format!("usage:users:{}", day)
+    }
+
+    /// Count one response for `user_id` today
+    pub async fn record(&self, user_id: &str, status: StatusCode) -> Result<(), String> {
+        let now = Utc::now();
+        let day = now.date_naive();
+        let key = Self::counts_key(day, user_id);
+        let users = Self::users_key(day);
+
+        let mut pipe = redis::pipe();
+        pipe.atomic().hincr(&key, "requests", 1).ignore();
+        if status == StatusCode::TOO_MANY_REQUESTS {
+            pipe.hincr(&key, "rate_limited", 1).ignore();
+            pipe.hset(&key, "last_limited", now.timestamp()).ignore();
+        } else if status.is_client_error() || status.is_server_error() {
+            pipe.hincr(&key, "errors", 1).ignore();
+        }
+        pipe.expire(&key, COUNTER_TTL_SECS).ignore();
+        pipe.sadd(&users, user_id).ignore();
+        pipe.expire(&users, COUNTER_TTL_SECS).ignore();
+
+        let mut conn = self.redis.get_client().get_multiplexed_async_connection().await.map_err(|e| e.to_string())?;
+        pipe.query_async::<()>(&mut conn).await.map_err(|e| e.to_string())
+    }
+
+    /// Live counters for one user and day
+    pub async fn read(&self, user_id: &str, day: NaiveDate) -> Result<UsageCounts, String> {
+        let mut conn = self.redis.get_client().get_multiplexed_async_connection().await.map_err(|e| e.to_string())?;
+        let hash: HashMap<String, i64> = redis::cmd("HGETALL")
+            .arg(Self::counts_key(day, user_id))
+            .query_async(&mut conn)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(UsageCounts::from_hash(&hash))
+    }
+
+    /// Every user with traffic on `day` and their counters
+    pub async fn read_day(&self, day: NaiveDate) -> Result<Vec<(String, UsageCounts)>, String> {
+        let mut conn = self.redis.get_client().get_multiplexed_async_connection().await.map_err(|e| e.to_string())?;
+        let users: Vec<String> = redis::cmd("SMEMBERS")
+            .arg(Self::users_key(day))
+            .query_async(&mut conn)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let mut counts = Vec::with_capacity(users.len());
+        for user_id in users {
+            let hash: HashMap<String, i64> = redis::cmd("HGETALL")
+                .arg(Self::counts_key(day, &user_id))
+                .query_async(&mut conn)
+                .await
+                .map_err(|e| e.to_string())?;
+            counts.push((user_id, UsageCounts::from_hash(&hash)));
+        }
+        Ok(counts)
+    }
+}
+
+// =============================================================================
+// LAYER
+// =============================================================================
+
+/// Counts every response to a request carrying a valid access token.
+/// Sits outside the rate limiter so its 429s are counted too; counting runs
+/// in the background and never delays or fails the response.
+#[derive(Clone)]
+pub struct UsageLayer {
+    counters: UsageCounters,
+    jwt: Arc<JwtService>,
+}
+
+impl UsageLayer {
+    pub fn new(counters: UsageCounters, jwt: JwtService) -> Self {
+        Self { counters, jwt: Arc::new(jwt) }
+    }
+}
+
+impl<S> Layer<S> for UsageLayer {
+    type Service = UsageService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        UsageService {
+            inner,
+            counters: self.counters.clone(),
+            jwt: self.jwt.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct UsageService<S> {
+    inner: S,
+    counters: UsageCounters,
+    jwt: Arc<JwtService>,
+}
+
+impl<S> Service<Request<Body>> for UsageService<S>
+where
+    S: Service<Request<Body>, Response = Response> + Clone + Send + 'static,
+    S::Future: Send,
+{
+    type Response = Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut std::task::Context<'_>) -> std::task::Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        let user_id = access_token(request.headers())
+            .and_then(|token| self.jwt.verify_access_token(token).ok())
+            .map(|claims| claims.claims.sub);
+        let counters = self.counters.clone();
+        let mut inner = self.inner.clone();
+
+        Box::pin(async move {
+            let response = inner.call(request).await?;
+            if let Some(user_id) = user_id {
+                let status = response.status();
+                tokio::spawn(async move {
+                    if let Err(e) = counters.record(&user_id, status).await {
+                        tracing::debug!("Failed to record API usage: {}", e);
+                    }
+                });
+            }
+            Ok(response)
+        })
+    }
+}
+
+// =============================================================================
+// FLUSHER
+// =============================================================================
+
+/// Copies Redis day totals into `api_usage_daily`. Rows are overwritten
+/// with the running totals, so replicas flushing the same day agree; a
+/// total never goes down, in case Redis lost its counters mid-day.
+pub struct UsageFlusher {
+    db: Pool<MySql>,
+    counters: UsageCounters,
+    interval: Duration,
+}
+
+impl UsageFlusher {
+    pub fn new(db: Pool<MySql>, counters: UsageCounters) -> Self {
+        Self { db, counters, interval: flush_interval() }
+    }
+
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    pub async fn run(&self) {
+        let mut interval = tokio::time::interval(self.interval);
+        loop {
+            interval.tick().await;
+            if let Err(e) = self.flush().await {
+                tracing::error!("API usage flush failed: {}", e);
+            }
+        }
+    }
+
+    /// Flush today and yesterday, whose last minutes may not have been
+    /// flushed yet. Returns the rows written.
+    pub async fn flush(&self) -> Result<usize, String> {
+        let today = Utc::now().date_naive();
+        let mut written = 0;
+        for day in [today.pred_opt(), Some(today)].into_iter().flatten() {
+            for (user_id, counts) in self.counters.read_day(day).await? {
+                self.write(&user_id, day, &counts).await?;
+                written += 1;
+            }
+        }
+        Ok(written)
+    }
+
+    async fn write(&self, user_id: &str, day: NaiveDate, counts: &UsageCounts) -> Result<(), String> {
+        sqlx::query(
+            r#"
+            INSERT INTO api_usage_daily (user_id, day, requests, errors, rate_limited)
+            VALUES (?, ?, ?, ?, ?)
+            ON DUPLICATE KEY UPDATE
+                requests = GREATEST(requests, VALUES(requests)),
+                errors = GREATEST(errors, VALUES(errors)),
+                rate_limited = GREATEST(rate_limited, VALUES(rate_limited)),
+                updated_at = NOW()
+            "#,
+        )
+        .bind(user_id)
+        .bind(day)
+        .bind(counts.requests)
+        .bind(counts.errors)
+        .bind(counts.rate_limited)
+        .execute(&self.db)
+        .await
+        .map_err(|e| format!("Failed to write API usage for {}: {}", user_id, e))?;
+        Ok(())
+    }
+}
+
+/// USAGE_FLUSH_INTERVAL_SECS, default 60
+fn flush_interval() -> Duration {
+    std::env::var("USAGE_FLUSH_INTERVAL_SECS")
+        .ok()
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .filter(|secs| *secs > 0)
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_FLUSH_INTERVAL)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counts_from_hash() {
+        let hash: HashMap<String, i64> = [("requests", 12), ("errors", 3), ("last_limited", 1_700_000_000)]
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v))
+            .collect();
+        let counts = UsageCounts::from_hash(&hash);
+        assert_eq!(counts.requests, 12);
+        assert_eq!(counts.errors, 3);
+        assert_eq!(counts.rate_limited, 0);
+        assert_eq!(counts.last_limited_at, Some(1_700_000_000));
+
+        assert_eq!(UsageCounts::from_hash(&HashMap::new()), UsageCounts::default());
+    }
+}
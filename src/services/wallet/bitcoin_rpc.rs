@@ -9,6 +9,7 @@ use std::str::FromStr;
 use std::time::Duration;
 
 use super::rpc::RpcError;
+use crate::services::amount::{self, Decimal};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BitcoinUtxo {
@@ -26,9 +27,12 @@ pub struct BitcoinFeeEstimate {
 #[async_trait]
 pub trait BitcoinProvider: Send + Sync {
     async fn get_utxos(&self, address: &str) -> Result<Vec<BitcoinUtxo>, RpcError>;
-    async fn get_balance(&self, address: &str) -> Result<f64, RpcError>;
+    async fn get_balance(&self, address: &str) -> Result<Decimal, RpcError>;
     async fn estimate_fee(&self, blocks: u32) -> Result<f64, RpcError>;
     async fn broadcast_transaction(&self, tx_hex: &str) -> Result<String, RpcError>;
+    /// Confirmations of `txid`: 0 while in the mempool, `None` when the
+    /// node does not know it
+    async fn get_transaction_confirmations(&self, txid: &str) -> Result<Option<u32>, RpcError>;
 }
 
 pub struct BitcoinRpcClient {
@@ -116,9 +120,13 @@ impl BitcoinProvider for BitcoinRpcClient {
         Ok(utxos)
     }
 
-    async fn get_balance(&self, address: &str) -> Result<f64, RpcError> {
+    async fn get_balance(&self, address: &str) -> Result<Decimal, RpcError> {
         let utxos = self.get_utxos(address).await?;
-        Ok(utxos.iter().map(|u| u.amount).sum())
+        let mut sats = 0u128;
+        for utxo in &utxos {
+            sats += utxo_sats(utxo).map_err(RpcError::Parse)? as u128;
+        }
+        amount::from_minor_units(sats, amount::BTC_DECIMALS).map_err(|e| RpcError::Parse(e.to_string()))
     }
 
     async fn estimate_fee(&self, blocks: u32) -> Result<f64, RpcError> {
@@ -132,13 +140,33 @@ impl BitcoinProvider for BitcoinRpcClient {
         self.call_rpc("sendrawtransaction", json!([tx_hex]))
             .await
     }
+
+    async fn get_transaction_confirmations(&self, txid: &str) -> Result<Option<u32>, RpcError> {
+        // Needs -txindex for transactions the node's wallet did not send
+        let result: serde_json::Value = match self.call_rpc("getrawtransaction", json!([txid, true])).await {
+            Err(RpcError::Rpc(message)) if message.contains("No such") => return Ok(None),
+            other => other?,
+        };
+        // Mempool transactions carry no confirmations field
+        Ok(Some(result.get("confirmations").and_then(|c| c.as_u64()).unwrap_or(0) as u32))
+    }
+}
+
+/// Value of a UTXO in satoshis. `listunspent` reports BTC as a JSON float.
+fn utxo_sats(utxo: &BitcoinUtxo) -> Result<u64, String> {
+    amount::from_f64(utxo.amount)
+        .and_then(|btc| amount::to_minor_units(btc, amount::BTC_DECIMALS))
+        .ok()
+        .and_then(|sats| u64::try_from(sats).ok())
+        .ok_or_else(|| format!("Invalid UTXO amount {}:{}: {}", utxo.txid, utxo.vout, utxo.amount))
 }
 
-/// Build a Bitcoin transaction from UTXOs
+/// Build a Bitcoin transaction from UTXOs, paying `fee_rate` sat per
+/// 1000 vbytes
 pub fn build_bitcoin_transaction(
     utxos: Vec<BitcoinUtxo>,
     to_address: &str,
-    amount: f64,
+    amount: Decimal,
     fee_rate: f64,
     change_address: &str,
 ) -> Result<Transaction, String> {
@@ -154,8 +182,10 @@ pub fn build_bitcoin_transaction(
         .require_network(network)
         .map_err(|e| format!("Address network mismatch: {}", e))?;
 
-    // Convert BTC to satoshis
-    let amount_sats = (amount * 100_000_000.0) as u64;
+    let amount_sats = amount::to_minor_units(amount, amount::BTC_DECIMALS)
+        .ok()
+        .and_then(|sats| u64::try_from(sats).ok())
+        .ok_or_else(|| format!("Invalid payout amount: {} BTC", amount))?;
     
     // Select UTXOs
     let mut selected_utxos = Vec::new();
@@ -163,7 +193,7 @@ pub fn build_bitcoin_transaction(
     
     for utxo in utxos {
         selected_utxos.push(utxo.clone());
-        total_input += (utxo.amount * 100_000_000.0) as u64;
+        total_input += utxo_sats(&utxo)?;
         
         // Estimate tx size: inputs * 148 + outputs * 34 + 10
         let estimated_size = selected_utxos.len() * 148 + 2 * 34 + 10;
@@ -0,0 +1,456 @@
+//! Bitcoin fee oracle. One `estimatesmartfee` call is a single point of
+//! failure and a single opinion, so fee rates are gathered from several
+//! sources at once: mempool.space, an Esplora instance (Blockstream) and
+//! the node itself. Per confirmation target the rates are compared, any
+//! that sit far from the median are dropped as outliers, and the rest are
+//! averaged.
+//!
+//! When no source answers, the last agreed rates are reused for a while;
+//! after that payouts wait rather than guess.
+
+use async_trait::async_trait;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+use tokio::task::JoinSet;
+
+use super::bitcoin_rpc::BitcoinProvider;
+use super::rpc::RpcError;
+
+/// Rough size of a one-input payout with change, used to price it
+pub const PAYOUT_VBYTES: u64 = 250;
+
+/// Rates further than this factor from the median are outliers
+const OUTLIER_FACTOR: f64 = 2.0;
+/// Below the default relay fee nothing propagates
+const MIN_SAT_PER_VBYTE: f64 = 1.0;
+const DEFAULT_MAX_SAT_PER_VBYTE: f64 = 500.0;
+/// How long the last agreed rates stand in when every source fails
+const LAST_GOOD_MAX_AGE: Duration = Duration::from_secs(30 * 60);
+const SOURCE_TIMEOUT: Duration = Duration::from_secs(5);
+
+const DEFAULT_MEMPOOL_URL: &str = "https://mempool.space/api";
+const DEFAULT_ESPLORA_URL: &str = "https://blockstream.info/api";
+
+// =============================================================================
+// TARGETS AND RATES
+// =============================================================================
+
+/// How soon a transaction should confirm
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, Hash, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FeeTarget {
+    /// Next block
+    Fast,
+    /// Within about an hour
+    #[default]
+    Medium,
+    /// Within about a day
+    Slow,
+}
+
+impl FeeTarget {
+    pub const ALL: [FeeTarget; 3] = [FeeTarget::Fast, FeeTarget::Medium, FeeTarget::Slow];
+
+    /// Confirmation target in blocks
+    pub fn blocks(&self) -> u32 {
+        match self {
+            FeeTarget::Fast => 1,
+            FeeTarget::Medium => 6,
+            FeeTarget::Slow => 144,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FeeTarget::Fast => "fast",
+            FeeTarget::Medium => "medium",
+            FeeTarget::Slow => "slow",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|target| target.as_str().eq_ignore_ascii_case(value.trim()))
+    }
+}
+
+/// Fee rates in sat/vB per target
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FeeRates {
+    pub fast: f64,
+    pub medium: f64,
+    pub slow: f64,
+}
+
+impl FeeRates {
+    pub fn get(&self, target: FeeTarget) -> f64 {
+        match target {
+            FeeTarget::Fast => self.fast,
+            FeeTarget::Medium => self.medium,
+            FeeTarget::Slow => self.slow,
+        }
+    }
+
+    /// Within [1, `max`] sat/vB, and never cheaper for a sooner target
+    fn bounded(self, max: f64) -> Self {
+        let clamp = |rate: f64| rate.clamp(MIN_SAT_PER_VBYTE, max.max(MIN_SAT_PER_VBYTE));
+        let slow = clamp(self.slow);
+        let medium = clamp(self.medium).max(slow);
+        Self { fast: clamp(self.fast).max(medium), medium, slow }
+    }
+
+    /// Fee of a payout at `target`, in satoshis
+    pub fn payout_fee_sats(&self, target: FeeTarget) -> u64 {
+        (self.get(target) * PAYOUT_VBYTES as f64).ceil() as u64
+    }
+}
+
+/// Agreed rate from several sources' answers: the mean of those within
+/// `OUTLIER_FACTOR` of the median
+fn consensus(samples: &[f64]) -> Option<f64> {
+    let mut samples: Vec<f64> = samples.iter().copied().filter(|r| r.is_finite() && *r > 0.0).collect();
+    if samples.is_empty() {
+        return None;
+    }
+    samples.sort_by(|a, b| a.total_cmp(b));
+
+    let mid = samples.len() / 2;
+    let median = if samples.len().is_multiple_of(2) { (samples[mid - 1] + samples[mid]) / 2.0 } else { samples[mid] };
+    let kept: Vec<f64> = samples
+        .into_iter()
+        .filter(|r| *r >= median / OUTLIER_FACTOR && *r <= median * OUTLIER_FACTOR)
+        .collect();
+
+    Some(kept.iter().sum::<f64>() / kept.len() as f64)
+}
+
+// =============================================================================
+// SOURCES
+// =============================================================================
+
+#[async_trait]
+pub trait FeeSource: Send + Sync {
+    fn name(&self) -> &'static str;
+    async fn rates(&self) -> Result<FeeRates, RpcError>;
+}
+
+fn http_client() -> reqwest::Client {
+    reqwest::Client::builder().timeout(SOURCE_TIMEOUT).build().unwrap_or_default()
+}
+
+async fn get_json(client: &reqwest::Client, url: String) -> Result<Value, RpcError> {
+    let response = client.get(url).send().await.map_err(|e| RpcError::Network(e.to_string()))?;
+    if !response.status().is_success() {
+        return Err(RpcError::Rpc(format!("Fee API returned {}", response.status())));
+    }
+    response.json().await.map_err(|e| RpcError::Parse(e.to_string()))
+}
+
+/// mempool.space `/v1/fees/recommended`
+pub struct MempoolSpaceSource {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl MempoolSpaceSource {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self { client: http_client(), url: url.into().trim_end_matches('/').to_string() }
+    }
+}
+
+#[async_trait]
+impl FeeSource for MempoolSpaceSource {
+    fn name(&self) -> &'static str {
+        "mempool"
+    }
+
+    async fn rates(&self) -> Result<FeeRates, RpcError> {
+        let body = get_json(&self.client, format!("{}/v1/fees/recommended", self.url)).await?;
+        let rate = |field: &str| {
+            body.get(field)
+                .and_then(Value::as_f64)
+                .ok_or_else(|| RpcError::Parse(format!("Missing {}", field)))
+        };
+
+        Ok(FeeRates { fast: rate("fastestFee")?, medium: rate("hourFee")?, slow: rate("economyFee")? })
+    }
+}
+
+/// Esplora `/fee-estimates`: sat/vB keyed by confirmation target
+pub struct EsploraSource {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl EsploraSource {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self { client: http_client(), url: url.into().trim_end_matches('/').to_string() }
+    }
+}
+
+#[async_trait]
+impl FeeSource for EsploraSource {
+    fn name(&self) -> &'static str {
+        "esplora"
+    }
+
+    async fn rates(&self) -> Result<FeeRates, RpcError> {
+        let body = get_json(&self.client, format!("{}/fee-estimates", self.url)).await?;
+        let estimates: HashMap<String, f64> = serde_json::from_value(body).map_err(|e| RpcError::Parse(e.to_string()))?;
+        let estimates: Vec<(u32, f64)> =
+            estimates.into_iter().filter_map(|(blocks, rate)| Some((blocks.parse().ok()?, rate))).collect();
+
+        // The nearest target at or below ours: confirming sooner costs
+        // more, so it never underpays
+        let rate = |target: FeeTarget| {
+            estimates
+                .iter()
+                .filter(|(blocks, _)| *blocks <= target.blocks())
+                .max_by_key(|(blocks, _)| *blocks)
+                .or_else(|| estimates.iter().min_by_key(|(blocks, _)| *blocks))
+                .map(|(_, rate)| *rate)
+                .ok_or_else(|| RpcError::Parse("No fee estimates".to_string()))
+        };
+
+        Ok(FeeRates { fast: rate(FeeTarget::Fast)?, medium: rate(FeeTarget::Medium)?, slow: rate(FeeTarget::Slow)? })
+    }
+}
+
+/// The node's `estimatesmartfee`, reported in BTC/kvB
+pub struct NodeFeeSource {
+    provider: Arc<dyn BitcoinProvider>,
+}
+
+impl NodeFeeSource {
+    pub fn new(provider: Arc<dyn BitcoinProvider>) -> Self {
+        Self { provider }
+    }
+
+    async fn rate(&self, target: FeeTarget) -> Result<f64, RpcError> {
+        let btc_per_kvb = self.provider.estimate_fee(target.blocks()).await?;
+        if !btc_per_kvb.is_finite() || btc_per_kvb <= 0.0 {
+            return Err(RpcError::Parse(format!("Invalid fee rate {}", btc_per_kvb)));
+        }
+        // 1e8 sat per BTC, 1e3 vB per kvB
+        Ok(btc_per_kvb * 100_000.0)
+    }
+}
+
+#[async_trait]
+impl FeeSource for NodeFeeSource {
+    fn name(&self) -> &'static str {
+        "node"
+    }
+
+    async fn rates(&self) -> Result<FeeRates, RpcError> {
+        let (fast, medium, slow) =
+            tokio::try_join!(self.rate(FeeTarget::Fast), self.rate(FeeTarget::Medium), self.rate(FeeTarget::Slow))?;
+        Ok(FeeRates { fast, medium, slow })
+    }
+}
+
+// =============================================================================
+// ORACLE
+// =============================================================================
+
+/// Fee oracle settings, from BTC_FEE_* settings
+#[derive(Debug, Clone, PartialEq)]
+pub struct BitcoinFeeConfig {
+    /// Unset to leave mempool.space out
+    pub mempool_url: Option<String>,
+    /// Unset to leave Esplora out
+    pub esplora_url: Option<String>,
+    pub max_sat_per_vbyte: f64,
+    /// Target payouts are built for
+    pub payout_target: FeeTarget,
+}
+
+impl Default for BitcoinFeeConfig {
+    fn default() -> Self {
+        Self {
+            mempool_url: Some(DEFAULT_MEMPOOL_URL.to_string()),
+            esplora_url: Some(DEFAULT_ESPLORA_URL.to_string()),
+            max_sat_per_vbyte: DEFAULT_MAX_SAT_PER_VBYTE,
+            payout_target: FeeTarget::default(),
+        }
+    }
+}
+
+impl BitcoinFeeConfig {
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        // Set but empty turns a source off
+        let url = |name: &str, default: Option<String>| match std::env::var(name) {
+            Ok(v) if v.trim().is_empty() => None,
+            Ok(v) => Some(v.trim().to_string()),
+            Err(_) => default,
+        };
+
+        Self {
+            mempool_url: url("BTC_FEE_MEMPOOL_URL", defaults.mempool_url),
+            esplora_url: url("BTC_FEE_ESPLORA_URL", defaults.esplora_url),
+            max_sat_per_vbyte: std::env::var("BTC_FEE_MAX_SAT_PER_VBYTE")
+                .ok()
+                .and_then(|v| v.trim().parse::<f64>().ok())
+                .filter(|v| v.is_finite() && *v >= MIN_SAT_PER_VBYTE)
+                .unwrap_or(defaults.max_sat_per_vbyte),
+            payout_target: std::env::var("BTC_PAYOUT_FEE_TARGET")
+                .ok()
+                .and_then(|v| FeeTarget::parse(&v))
+                .unwrap_or(defaults.payout_target),
+        }
+    }
+
+    /// Settings read from the environment once per process
+    pub fn global() -> &'static BitcoinFeeConfig {
+        static CONFIG: OnceLock<BitcoinFeeConfig> = OnceLock::new();
+        CONFIG.get_or_init(Self::from_env)
+    }
+}
+
+/// Fee rates agreed between every configured source
+pub struct BitcoinFeeOracle {
+    sources: Vec<Arc<dyn FeeSource>>,
+    max_sat_per_vbyte: f64,
+    payout_target: FeeTarget,
+    last_good: Mutex<Option<(Instant, FeeRates)>>,
+}
+
+impl BitcoinFeeOracle {
+    /// Oracle with no sources; add them with `with_source`
+    pub fn new(config: &BitcoinFeeConfig) -> Self {
+        Self {
+            sources: Vec::new(),
+            max_sat_per_vbyte: config.max_sat_per_vbyte,
+            payout_target: config.payout_target,
+            last_good: Mutex::new(None),
+        }
+    }
+
+    /// Oracle over the public fee APIs in `config`
+    pub fn from_config(config: &BitcoinFeeConfig) -> Self {
+        let mut oracle = Self::new(config);
+        if let Some(url) = &config.mempool_url {
+            oracle = oracle.with_source(Arc::new(MempoolSpaceSource::new(url.clone())));
+        }
+        if let Some(url) = &config.esplora_url {
+            oracle = oracle.with_source(Arc::new(EsploraSource::new(url.clone())));
+        }
+        oracle
+    }
+
+    /// Oracle over the public fee APIs, shared by the process. Without a
+    /// node of its own it serves previews rather than payouts.
+    pub fn global() -> &'static Arc<BitcoinFeeOracle> {
+        static ORACLE: OnceLock<Arc<BitcoinFeeOracle>> = OnceLock::new();
+        ORACLE.get_or_init(|| Arc::new(Self::from_config(BitcoinFeeConfig::global())))
+    }
+
+    pub fn with_source(mut self, source: Arc<dyn FeeSource>) -> Self {
+        self.sources.push(source);
+        self
+    }
+
+    pub fn payout_target(&self) -> FeeTarget {
+        self.payout_target
+    }
+
+    /// Rates from every source that answers, outliers dropped
+    pub async fn rates(&self) -> Result<FeeRates, RpcError> {
+        let mut requests = JoinSet::new();
+        for source in &self.sources {
+            let source = source.clone();
+            requests.spawn(async move { (source.name(), source.rates().await) });
+        }
+
+        let mut answers = Vec::new();
+        while let Some(joined) = requests.join_next().await {
+            match joined {
+                Ok((_, Ok(rates))) => answers.push(rates),
+                Ok((name, Err(e))) => tracing::warn!("Bitcoin fee source {} failed: {}", name, e),
+                Err(e) => tracing::warn!("Bitcoin fee source panicked: {}", e),
+            }
+        }
+
+        let agreed = |target: FeeTarget| consensus(&answers.iter().map(|r| r.get(target)).collect::<Vec<_>>());
+        if let (Some(fast), Some(medium), Some(slow)) =
+            (agreed(FeeTarget::Fast), agreed(FeeTarget::Medium), agreed(FeeTarget::Slow))
+        {
+            let rates = FeeRates { fast, medium, slow }.bounded(self.max_sat_per_vbyte);
+            *self.last_good.lock().await = Some((Instant::now(), rates));
+            return Ok(rates);
+        }
+
+        match *self.last_good.lock().await {
+            Some((at, rates)) if at.elapsed() <= LAST_GOOD_MAX_AGE => {
+                tracing::warn!("No Bitcoin fee source answered; reusing rates from {}s ago", at.elapsed().as_secs());
+                Ok(rates)
+            }
+            _ => Err(RpcError::Network("No Bitcoin fee source answered".to_string())),
+        }
+    }
+
+    /// Rate for `target` in sat/vB
+    pub async fn rate(&self, target: FeeTarget) -> Result<f64, RpcError> {
+        Ok(self.rates().await?.get(target))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FixedSource(Result<FeeRates, ()>);
+
+    #[async_trait]
+    impl FeeSource for FixedSource {
+        fn name(&self) -> &'static str {
+            "fixed"
+        }
+
+        async fn rates(&self) -> Result<FeeRates, RpcError> {
+            self.0.map_err(|_| RpcError::Network("down".to_string()))
+        }
+    }
+
+    fn rates(fast: f64, medium: f64, slow: f64) -> FeeRates {
+        FeeRates { fast, medium, slow }
+    }
+
+    #[test]
+    fn test_consensus_drops_outliers() {
+        assert_eq!(consensus(&[]), None);
+        assert_eq!(consensus(&[12.0]), Some(12.0));
+        assert_eq!(consensus(&[10.0, 12.0, 100.0]), Some(11.0));
+        assert_eq!(consensus(&[10.0, 20.0]), Some(15.0));
+        assert_eq!(consensus(&[0.0, f64::NAN, 8.0]), Some(8.0));
+    }
+
+    #[test]
+    fn test_rates_are_bounded_and_ordered() {
+        let bounded = rates(3.0, 5.0, 0.2).bounded(4.0);
+        assert_eq!(bounded, rates(4.0, 4.0, 1.0));
+        assert_eq!(rates(20.0, 10.0, 2.0).payout_fee_sats(FeeTarget::Medium), 2_500);
+        assert_eq!(FeeTarget::parse(" Fast "), Some(FeeTarget::Fast));
+    }
+
+    #[tokio::test]
+    async fn test_oracle_falls_back_to_last_good_rates() {
+        let config = BitcoinFeeConfig { mempool_url: None, esplora_url: None, ..Default::default() };
+        let oracle = BitcoinFeeOracle::new(&config)
+            .with_source(Arc::new(FixedSource(Ok(rates(30.0, 12.0, 2.0)))))
+            .with_source(Arc::new(FixedSource(Ok(rates(28.0, 10.0, 2.0)))))
+            .with_source(Arc::new(FixedSource(Ok(rates(400.0, 11.0, 2.0)))))
+            .with_source(Arc::new(FixedSource(Err(()))));
+        assert_eq!(oracle.rates().await.unwrap(), rates(29.0, 11.0, 2.0));
+
+        let down = BitcoinFeeOracle::new(&config).with_source(Arc::new(FixedSource(Err(()))));
+        assert!(down.rates().await.is_err());
+        *down.last_good.lock().await = Some((Instant::now(), rates(9.0, 5.0, 1.0)));
+        assert_eq!(down.rate(FeeTarget::Medium).await.unwrap(), 5.0);
+    }
+}
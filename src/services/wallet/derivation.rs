@@ -14,13 +14,16 @@ use monero::{Address, PrivateKey as MoneroPrivateKey, PublicKey as MoneroPublicK
 use tiny_keccak::{Hasher, Keccak};
 use curve25519_dalek::scalar::Scalar;
 
+use super::paths::{DerivationPaths, PathChain};
+use crate::services::sandbox::bitcoin_p2pkh_version;
+
 // =============================================================================
 // HD WALLET DERIVATION
 // Implements BIP39/BIP44 hierarchical deterministic wallet derivation
 // =============================================================================
 
 /// Derive Bitcoin private key from seed phrase and index
-/// Path: m/44'/0'/0'/0/[index]
+/// Path: the configured Bitcoin path, m/44'/0'/0'/0/[index] by default
 pub async fn derive_btc_key(seed_phrase: &str, index: u32) -> Result<String, String> {
     if !is_valid_seed_phrase(seed_phrase) {
         return Err("Invalid seed phrase".to_string());
@@ -30,7 +33,7 @@ pub async fn derive_btc_key(seed_phrase: &str, index: u32) -> Result<String, Str
         .map_err(|e| format!("Invalid mnemonic: {}", e))?;
     let seed = mnemonic.to_seed("");
 
-    let path_str = format!("m/44'/0'/0'/0/{}", index);
+    let path_str = DerivationPaths::global().path(PathChain::Bitcoin, index);
     let derivation_path = DerivationPath::from_str(&path_str)
         .map_err(|e| format!("Invalid derivation path: {}", e))?;
 
@@ -66,10 +69,16 @@ pub async fn derive_solana_key(seed_phrase: &str, index: u32) -> Result<Vec<u8>,
     Ok(derived_seed.to_vec())
 }
 
-/// Derive EVM private key from seed phrase
-/// Path: m/44'/60'/0'/0/0 (Ethereum)
+/// Derive EVM private key from seed phrase at index 0 of the EVM path
 /// Returns hex string of private key
 pub async fn derive_evm_key(seed_phrase: &str) -> Result<String, String> {
+    derive_evm_key_at(seed_phrase, 0).await
+}
+
+/// Derive EVM private key from seed phrase and index
+/// Path: the configured EVM path, m/44'/60'/0'/0/[index] by default
+/// Returns hex string of private key
+pub async fn derive_evm_key_at(seed_phrase: &str, index: u32) -> Result<String, String> {
     if !is_valid_seed_phrase(seed_phrase) {
         return Err("Invalid seed phrase".to_string());
     }
@@ -78,8 +87,8 @@ pub async fn derive_evm_key(seed_phrase: &str) -> Result<String, String> {
         .map_err(|e| format!("Invalid mnemonic: {}", e))?;
     let seed = mnemonic.to_seed("");
 
-    // Derive key using BIP44 path: m/44'/60'/0'/0/0
-    let derivation_path = DerivationPath::from_str("m/44'/60'/0'/0/0")
+    let path_str = DerivationPaths::global().path(PathChain::Evm, index);
+    let derivation_path = DerivationPath::from_str(&path_str)
         .map_err(|e| format!("Invalid derivation path: {}", e))?;
 
     let key = coins_bip32::xkeys::XPriv::root_from_seed(&seed, None)
@@ -95,7 +104,7 @@ pub async fn derive_evm_key(seed_phrase: &str) -> Result<String, String> {
 }
 
 /// Derive EVM address from seed phrase and index
-/// Path: m/44'/60'/0'/0/[index]
+/// Path: the configured EVM path, m/44'/60'/0'/0/[index] by default
 pub async fn derive_evm_address(seed_phrase: &str, index: u32) -> Result<String, String> {
     if !is_valid_seed_phrase(seed_phrase) {
         return Err("Invalid seed phrase".to_string());
@@ -105,7 +114,7 @@ pub async fn derive_evm_address(seed_phrase: &str, index: u32) -> Result<String,
         .map_err(|e| format!("Invalid mnemonic: {}", e))?;
     let seed = mnemonic.to_seed("");
 
-    let path_str = format!("m/44'/60'/0'/0/{}", index);
+    let path_str = DerivationPaths::global().path(PathChain::Evm, index);
     let derivation_path = DerivationPath::from_str(&path_str)
         .map_err(|e| format!("Invalid derivation path: {}", e))?;
 
@@ -135,7 +144,8 @@ pub async fn derive_evm_address(seed_phrase: &str, index: u32) -> Result<String,
 }
 
 /// Derive Bitcoin address from seed phrase and index
-/// Path: m/44'/0'/0'/0/[index] (Legacy P2PKH for simplicity in this env)
+/// Path: the configured Bitcoin path, m/44'/0'/0'/0/[index] by default
+/// (Legacy P2PKH for simplicity in this env)
 pub async fn derive_btc_address(seed_phrase: &str, index: u32) -> Result<String, String> {
     if !is_valid_seed_phrase(seed_phrase) {
         return Err("Invalid seed phrase".to_string());
@@ -145,7 +155,7 @@ pub async fn derive_btc_address(seed_phrase: &str, index: u32) -> Result<String,
         .map_err(|e| format!("Invalid mnemonic: {}", e))?;
     let seed = mnemonic.to_seed("");
 
-    let path_str = format!("m/44'/0'/0'/0/{}", index);
+    let path_str = DerivationPaths::global().path(PathChain::Bitcoin, index);
     let derivation_path = DerivationPath::from_str(&path_str)
         .map_err(|e| format!("Invalid derivation path: {}", e))?;
 
@@ -175,9 +185,9 @@ pub async fn derive_btc_address(seed_phrase: &str, index: u32) -> Result<String,
     ripemd_hasher.update(&sha256_hash);
     let ripemd_hash = ripemd_hasher.finalize();
 
-    // Version byte (0x00 for Mainnet) + Hash
+    // Version byte (0x00 for mainnet, 0x6f for testnet in a sandbox) + Hash
     let mut payload = Vec::with_capacity(21);
-    payload.push(0x00);
+    payload.push(bitcoin_p2pkh_version());
     payload.extend_from_slice(&ripemd_hash);
 
     // Checksum: SHA256(SHA256(payload))
@@ -358,7 +368,7 @@ pub async fn sign_message_with_seed(
         .map_err(|e| format!("Invalid mnemonic: {}", e))?;
     let seed = mnemonic.to_seed("");
     
-    let path_str = format!("m/44'/60'/0'/0/{}", index);
+    let path_str = DerivationPaths::global().path(PathChain::Evm, index);
     let derivation_path = DerivationPath::from_str(&path_str)
         .map_err(|e| format!("Invalid derivation path: {}", e))?;
 
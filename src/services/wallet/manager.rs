@@ -3,18 +3,36 @@ use base64::Engine;
 use crate::modules::wallet::crud::WalletCrud;
 use crate::modules::wallet::schema::{GenerateAddressRequest, WalletAddressResponse, PayoutRequest, PayoutResponse};
 use super::derivation;
-use super::signing::SigningService;
+use super::signing::{erc20_transfer_data, SigningService};
 use super::rpc::BlockchainProvider;
 use super::bitcoin_rpc::{BitcoinProvider, build_bitcoin_transaction};
-use super::solana_rpc::{SolanaProvider, build_solana_transaction, sign_solana_transaction};
-use crate::services::pricing::{PricingContext, PricingStrategy, AdaptivePricingStrategy};
+use super::btc_fees::{self, BitcoinFeeConfig, BitcoinFeeOracle, NodeFeeSource};
+use super::signer::Signer;
+use super::sequencer::{ChainSequencer, SolanaBlockhashSource, TxPrerequisite};
+use super::solana_rpc::{SolanaProvider, build_solana_transaction, apply_solana_signature};
+use super::solana_fees::{self, PriorityFeeEstimator};
+use crate::services::pricing::{PricingContext, PricingStrategy, AdaptivePricingStrategy, PayoutSplit};
+use crate::services::amount::{self, Decimal};
+use rust_decimal::prelude::ToPrimitive;
+use crate::services::explorer::ExplorerRegistry;
+use crate::services::gas::{GasStation, PayoutGas, TopUpRequest, TxType};
+use crate::services::payout::{BatchedPayout, PayoutBatcher};
+use crate::services::sandbox::signing_chain_id;
+use crate::services::token::permit::{PermitRelayer, PermitTransferRequest};
+use crate::services::token::registry::CanonicalToken;
 
 pub struct WalletManager {
     crud: WalletCrud,
     master_seed: String,
     evm_provider: Arc<dyn BlockchainProvider>,
     bitcoin_provider: Option<Arc<dyn BitcoinProvider>>,
+    bitcoin_fee_oracle: Option<Arc<BitcoinFeeOracle>>,
     solana_provider: Option<Arc<dyn SolanaProvider>>,
+    solana_sequencer: Option<Arc<ChainSequencer>>,
+    gas_station: Option<Arc<GasStation>>,
+    permit_relayer: Option<Arc<PermitRelayer>>,
+    payout_batcher: Option<Arc<PayoutBatcher>>,
+    signing: SigningService,
 }
 
 impl WalletManager {
@@ -23,25 +41,84 @@ impl WalletManager {
         master_seed: String,
         evm_provider: Arc<dyn BlockchainProvider>,
     ) -> Self {
+        let signing = SigningService::from_config(&master_seed);
         Self {
             crud,
             master_seed,
             evm_provider,
             bitcoin_provider: None,
+            bitcoin_fee_oracle: None,
             solana_provider: None,
+            solana_sequencer: None,
+            gas_station: None,
+            permit_relayer: None,
+            payout_batcher: None,
+            signing,
         }
     }
 
+    /// Bitcoin payouts through `provider`, priced by the public fee APIs
+    /// and the node together unless a fee oracle is set
     pub fn with_bitcoin_provider(mut self, provider: Arc<dyn BitcoinProvider>) -> Self {
+        if self.bitcoin_fee_oracle.is_none() {
+            let oracle = BitcoinFeeOracle::from_config(BitcoinFeeConfig::global())
+                .with_source(Arc::new(NodeFeeSource::new(provider.clone())));
+            self.bitcoin_fee_oracle = Some(Arc::new(oracle));
+        }
         self.bitcoin_provider = Some(provider);
         self
     }
 
+    /// Price Bitcoin payouts with `oracle`
+    pub fn with_bitcoin_fee_oracle(mut self, oracle: Arc<BitcoinFeeOracle>) -> Self {
+        self.bitcoin_fee_oracle = Some(oracle);
+        self
+    }
+
+    /// Solana payouts through `provider`, with blockhashes cached by this
+    /// manager unless a shared sequencer is set
     pub fn with_solana_provider(mut self, provider: Arc<dyn SolanaProvider>) -> Self {
+        if self.solana_sequencer.is_none() {
+            let source = Arc::new(SolanaBlockhashSource::new(provider.clone()));
+            self.solana_sequencer = Some(Arc::new(ChainSequencer::new(source)));
+        }
         self.solana_provider = Some(provider);
         self
     }
 
+    /// Share one blockhash cache between managers
+    pub fn with_solana_sequencer(mut self, sequencer: Arc<ChainSequencer>) -> Self {
+        self.solana_sequencer = Some(sequencer);
+        self
+    }
+
+    /// Fund gas for token payouts from the hot wallet. Share one station
+    /// between managers so concurrent payouts are topped up in one batch.
+    pub fn with_gas_station(mut self, station: Arc<GasStation>) -> Self {
+        self.gas_station = Some(station);
+        self
+    }
+
+    /// Move tokens that support EIP-2612 or Permit2 with a signed permit
+    /// the hot wallet submits, instead of funding the deposit address's gas
+    pub fn with_permit_relayer(mut self, relayer: Arc<PermitRelayer>) -> Self {
+        self.permit_relayer = Some(relayer);
+        self
+    }
+
+    /// Batch native payouts on chains with a disperse contract configured.
+    /// Share one batcher between managers so their payouts meet in a batch.
+    pub fn with_payout_batcher(mut self, batcher: Arc<PayoutBatcher>) -> Self {
+        self.payout_batcher = Some(batcher);
+        self
+    }
+
+    /// Replace the signer configured by SIGNER_BACKEND
+    pub fn with_signer(mut self, signer: Arc<dyn Signer>) -> Self {
+        self.signing = SigningService::new(signer);
+        self
+    }
+
     /// High-level orchestrator to generate a new swap address
     pub async fn get_or_generate_address(
         &self,
@@ -82,6 +159,7 @@ impl WalletManager {
     }
 
     /// Orchestrate a payout to the user with idempotency and blockchain verification
+#[tracing::instrument(name = "wallet.payout", skip_all, fields(swap_id = %req.swap_id, chain = tracing::field::Empty))]
     pub async fn process_payout(
         &self,
         req: PayoutRequest,
@@ -92,20 +170,21 @@ impl WalletManager {
             .ok_or_else(|| "No address info found for swap".to_string())?;
 
         // 2. IDEMPOTENCY CHECK: If already has tx_hash or status is success, return early
-        if let Some(tx_hash) = info.payout_tx_hash {
-            return Ok(PayoutResponse {
-                tx_hash,
-                amount: info.payout_amount.unwrap_or(0.0),
-                status: crate::modules::wallet::model::PayoutStatus::Success,
-            });
+        if let Some(tx_hash) = info.payout_tx_hash.clone() {
+            let paid = info.payout_amount.unwrap_or_default();
+            return Ok(payout_response(&info, tx_hash, paid));
         }
 
         // 3. Determine chain type from coin_type
         // coin_type: 0 = Bitcoin, 60 = Ethereum/EVM, 501 = Solana
+        tracing::Span::current().record("chain", payout_chain(info.coin_type));
         match info.coin_type {
             0 => self.process_bitcoin_payout(&info, &req.swap_id).await,
             501 => self.process_solana_payout(&info, &req.swap_id).await,
-            _ => self.process_evm_payout(&info, &req.swap_id).await,
+            _ => match self.crud.payout_token(&req.swap_id).await? {
+                Some(token) => self.process_token_payout(&info, &req.swap_id, &token).await,
+                None => self.process_evm_payout(&info, &req.swap_id).await,
+            },
         }
     }
 
@@ -124,78 +203,466 @@ impl WalletManager {
             swap_id, info.our_address, actual_balance
         );
         
-        if actual_balance < 0.0001 {
+        if actual_balance < Decimal::new(1, 4) {
             return Err(format!(
                 "Insufficient balance on blockchain: {} (address: {})",
                 actual_balance, info.our_address
             ));
         }
 
-        let sender_address = derivation::derive_evm_address(&self.master_seed, info.address_index).await?;
-        let private_key = derivation::derive_evm_key(&self.master_seed).await?;
-
-        let nonce = self.evm_provider.get_transaction_count(&sender_address).await
-            .map_err(|e| format!("Failed to get nonce: {}", e))?;
-            
         let gas_price = self.evm_provider.get_gas_price().await
             .map_err(|e| format!("Failed to get gas price: {}", e))?;
 
-        // Calculate fees
-        let pricing_strategy = AdaptivePricingStrategy::default();
-        let raw_received = actual_balance;
-        
-        let gas_limit = 21000.0;
-        let estimated_gas_native = (gas_price as f64 * gas_limit) / 1_000_000_000_000_000_000.0;
+        // Calculate fees. Batched payouts are charged the same network fee as
+        // a single transfer.
+        let gas_limit = 21000u64;
+        let estimated_gas = evm_gas_cost(gas_price, gas_limit)?;
 
-        let ctx = PricingContext {
-            amount_usd: raw_received,
-            network_gas_cost_native: estimated_gas_native,
-            provider_spread_percentage: 0.0,
-        };
+        let split = payout_split(actual_balance, estimated_gas, amount::EVM_NATIVE_DECIMALS)?;
 
-        let (commission_rate, gas_floor) = pricing_strategy.calculate_fees(&ctx);
-        
-        let mut platform_fee = raw_received * commission_rate;
-        if platform_fee < gas_floor {
-            platform_fee = gas_floor;
-        }
-
-        let final_payout: f64 = (raw_received - platform_fee - estimated_gas_native).max(0.0);
-
-        if final_payout <= 0.0 {
+        if split.payout <= Decimal::ZERO {
             return Err(format!(
                 "Payout amount too small to cover fees: received={}, fee={}, gas={}",
-                raw_received, platform_fee, estimated_gas_native
+                split.received, split.platform_fee, split.network_fee
             ));
         }
 
         tracing::info!(
             "Swap {}: EVM payout calculation - Received: {}, Commission: {}, Gas: {}, Final: {}",
-            swap_id, raw_received, platform_fee, estimated_gas_native, final_payout
+            swap_id, split.received, split.platform_fee, split.network_fee, split.payout
         );
 
+        if let Some(batcher) = self.payout_batcher.as_ref().filter(|b| b.batches(payout_chain(info.coin_type))) {
+            return self.process_batched_payout(batcher, info, swap_id, &split).await;
+        }
+
+        let sender_address = derivation::derive_evm_address(&self.master_seed, info.address_index).await?;
+        let nonce = self.evm_provider.get_transaction_count(&sender_address).await
+            .map_err(|e| format!("Failed to get nonce: {}", e))?;
+
         let tx = crate::modules::wallet::schema::EvmTransaction {
             to_address: info.recipient_address.clone(),
-            amount: final_payout,
-            token: "ETH".to_string(), 
-            chain_id: 1, 
+            amount: split.payout,
+            token: "ETH".to_string(),
+            // Sepolia's chain id in a sandbox deployment
+            chain_id: signing_chain_id("ethereum"),
             nonce,
             gas_price,
+            gas_limit,
+            data: String::new(),
         };
 
-        let signature = SigningService::sign_evm_transaction(&private_key, &tx)?;
+        let signature = self.signing.sign_evm(info.address_index, &tx).await?;
 
         let tx_hash = self.evm_provider.send_raw_transaction(&signature).await
             .map_err(|e| format!("Failed to broadcast: {}", e))?;
 
-        self.crud.mark_payout_completed(swap_id, &tx_hash, raw_received, platform_fee).await
+        // A plain value transfer always uses exactly its 21000 gas limit
+        self.record_payout_gas(PayoutGas {
+            network: payout_chain(info.coin_type).to_string(),
+            swap_id: swap_id.to_string(),
+            tx_hash: tx_hash.clone(),
+            tx_type: TxType::NativeTransfer,
+            gas_price,
+            gas_limit,
+            gas_used: Some(gas_limit),
+            fee_native: split.network_fee,
+        }).await;
+
+        self.crud.mark_payout_completed(swap_id, &tx_hash, &split).await
             .map_err(|e: sqlx::Error| e.to_string())?;
 
-        Ok(PayoutResponse {
-            tx_hash,
-            amount: final_payout,
-            status: crate::modules::wallet::model::PayoutStatus::Success,
-        })
+        Ok(payout_response(info, tx_hash, split.payout))
+    }
+
+    /// Pay a native EVM payout as part of a disperse batch sent from the
+    /// hot wallet. The swap's deposit stays on its address.
+    async fn process_batched_payout(
+        &self,
+        batcher: &PayoutBatcher,
+        info: &crate::modules::wallet::model::SwapAddressInfo,
+        swap_id: &str,
+        split: &PayoutSplit,
+    ) -> Result<PayoutResponse, String> {
+        let network = payout_chain(info.coin_type);
+        let receipt = batcher.submit(BatchedPayout {
+            network: network.to_string(),
+            swap_id: swap_id.to_string(),
+            deposit_address: info.our_address.clone(),
+            recipient: info.recipient_address.clone(),
+            amount: split.payout,
+        }).await?;
+
+        self.record_payout_gas(PayoutGas {
+            network: network.to_string(),
+            swap_id: swap_id.to_string(),
+            tx_hash: receipt.tx_hash.clone(),
+            tx_type: TxType::ComplexContract,
+            gas_price: receipt.gas_price,
+            gas_limit: receipt.gas_limit,
+            gas_used: None,
+            fee_native: receipt.network_fee,
+        }).await;
+
+        self.crud.mark_payout_completed(swap_id, &receipt.tx_hash, split).await
+            .map_err(|e: sqlx::Error| e.to_string())?;
+
+        Ok(payout_response(info, receipt.tx_hash, split.payout))
+    }
+
+    /// Process an ERC-20 payout. The deposit address received only the
+    /// token, so where the token supports permits the hot wallet moves it
+    /// with one the address signs; otherwise the gas station funds the
+    /// address's gas first when one is configured. Either way the gas is
+    /// booked in the ledger rather than taken from the payout.
+    async fn process_token_payout(
+        &self,
+        info: &crate::modules::wallet::model::SwapAddressInfo,
+        swap_id: &str,
+        token: &CanonicalToken,
+    ) -> Result<PayoutResponse, String> {
+        let decimals = token.decimals as u32;
+        let raw_balance = self.evm_provider.get_token_balance(&token.contract_address, &info.our_address).await
+            .map_err(|e| format!("Failed to get token balance: {}", e))?;
+        let balance = amount::from_minor_units(raw_balance, decimals).map_err(|e| e.to_string())?;
+
+        tracing::info!(
+            "Swap {}: {} balance check - Address: {}, Balance: {}",
+            swap_id, token.symbol, info.our_address, balance
+        );
+
+        if balance <= Decimal::ZERO {
+            return Err(format!(
+                "Insufficient {} balance on blockchain: {} (address: {})",
+                token.symbol, balance, info.our_address
+            ));
+        }
+
+        let split = payout_split(balance, Decimal::ZERO, decimals)?;
+        if split.payout <= Decimal::ZERO {
+            return Err(format!(
+                "Payout amount too small to cover fees: received={}, fee={}",
+                split.received, split.platform_fee
+            ));
+        }
+        let payout_units = amount::to_minor_units(split.payout, decimals).map_err(|e| e.to_string())?;
+
+        tracing::info!(
+            "Swap {}: {} payout calculation - Received: {}, Commission: {}, Final: {}",
+            swap_id, token.symbol, split.received, split.platform_fee, split.payout
+        );
+
+        if let Some(relayer) = &self.permit_relayer {
+            let request = PermitTransferRequest {
+                network: token.network.clone(),
+                swap_id: swap_id.to_string(),
+                contract: token.contract_address.clone(),
+                owner_index: info.address_index,
+                owner: info.our_address.clone(),
+                recipient: info.recipient_address.clone(),
+                amount: payout_units,
+            };
+            if let Some(relayed) = relayer.transfer(&request).await? {
+                for tx in &relayed.txs {
+                    self.record_payout_gas(PayoutGas {
+                        network: payout_chain(info.coin_type).to_string(),
+                        swap_id: swap_id.to_string(),
+                        tx_hash: tx.tx_hash.clone(),
+                        tx_type: tx.tx_type,
+                        gas_price: relayed.gas_price,
+                        gas_limit: tx.gas_limit,
+                        gas_used: None,
+                        fee_native: evm_gas_cost(relayed.gas_price, tx.gas_limit)?,
+                    }).await;
+                }
+
+                let tx_hash = relayed.tx_hash().to_string();
+                self.crud.mark_payout_completed(swap_id, &tx_hash, &split).await
+                    .map_err(|e: sqlx::Error| e.to_string())?;
+
+                return Ok(payout_response(info, tx_hash, split.payout));
+            }
+        }
+
+        let gas_limit = TxType::TokenTransfer.evm_gas_limit();
+        if let Some(station) = &self.gas_station {
+            station.ensure_gas(TopUpRequest {
+                network: token.network.clone(),
+                address: info.our_address.clone(),
+                swap_id: Some(swap_id.to_string()),
+                gas_limit,
+            }).await?;
+        }
+
+        let nonce = self.evm_provider.get_transaction_count(&info.our_address).await
+            .map_err(|e| format!("Failed to get nonce: {}", e))?;
+
+        let gas_price = self.evm_provider.get_gas_price().await
+            .map_err(|e| format!("Failed to get gas price: {}", e))?;
+
+        let tx = crate::modules::wallet::schema::EvmTransaction {
+            to_address: token.contract_address.clone(),
+            amount: Decimal::ZERO,
+            token: token.symbol.clone(),
+            chain_id: signing_chain_id("ethereum"),
+            nonce,
+            gas_price,
+            gas_limit,
+            data: erc20_transfer_data(&info.recipient_address, payout_units)?,
+        };
+
+        let signature = self.signing.sign_evm(info.address_index, &tx).await?;
+
+        let tx_hash = self.evm_provider.send_raw_transaction(&signature).await
+            .map_err(|e| format!("Failed to broadcast: {}", e))?;
+
+        // Token transfers rarely use their whole limit; the receipt is not awaited
+        self.record_payout_gas(PayoutGas {
+            network: payout_chain(info.coin_type).to_string(),
+            swap_id: swap_id.to_string(),
+            tx_hash: tx_hash.clone(),
+            tx_type: TxType::TokenTransfer,
+            gas_price,
+            gas_limit,
+            gas_used: None,
+            fee_native: evm_gas_cost(gas_price, gas_limit)?,
+        }).await;
+
+        self.crud.mark_payout_completed(swap_id, &tx_hash, &split).await
+            .map_err(|e: sqlx::Error| e.to_string())?;
+
+        Ok(payout_response(info, tx_hash, split.payout))
+    }
+
+    /// Send a custodial balance withdrawal from the EVM hot wallet.
+    /// Funds for credited balances are held there, so no per-swap fee is taken.
+    pub async fn process_withdrawal(
+        &self,
+        currency: &str,
+        network: &str,
+        to_address: &str,
+        value: Decimal,
+    ) -> Result<String, String> {
+        let is_evm = matches!(
+            network.to_lowercase().as_str(),
+            "ethereum" | "polygon" | "bsc" | "arbitrum" | "optimism" | "erc20" | "bep20"
+        ) || (network.eq_ignore_ascii_case("mainnet") && currency.eq_ignore_ascii_case("eth"));
+
+        if !is_evm {
+            return Err(format!("Withdrawals are not supported for {} on {}", currency, network));
+        }
+
+        let sender_address = derivation::derive_evm_address(&self.master_seed, 0).await?;
+
+        let balance = self.evm_provider.get_balance(&sender_address).await
+            .map_err(|e| format!("Failed to get hot wallet balance: {}", e))?;
+
+        let gas_price = self.evm_provider.get_gas_price().await
+            .map_err(|e| format!("Failed to get gas price: {}", e))?;
+        let estimated_gas = evm_gas_cost(gas_price, 21000)?;
+
+        if balance < value + estimated_gas {
+            return Err(format!(
+                "Hot wallet balance too low: balance={}, required={}",
+                balance, value + estimated_gas
+            ));
+        }
+
+        let nonce = self.evm_provider.get_transaction_count(&sender_address).await
+            .map_err(|e| format!("Failed to get nonce: {}", e))?;
+
+        let tx = crate::modules::wallet::schema::EvmTransaction {
+            to_address: to_address.to_string(),
+            amount: value,
+            token: "ETH".to_string(),
+            chain_id: signing_chain_id("ethereum"),
+            nonce,
+            gas_price,
+            gas_limit: 21000,
+            data: String::new(),
+        };
+
+        let signature = self.signing.sign_evm(0, &tx).await?;
+
+        self.evm_provider.send_raw_transaction(&signature).await
+            .map_err(|e| format!("Failed to broadcast: {}", e))
+    }
+
+    /// What sweeping the native balance of a swap's deposit address would
+    /// send on the chain `evm_provider` points at: the balance less gas, and
+    /// the gas price that was priced at. Recovery sweeps return funds sent
+    /// on the wrong EVM network or left behind, so no platform fee is taken.
+    pub async fn plan_recovery_sweep(&self, address_index: u32) -> Result<(Decimal, u64), String> {
+        let sender_address = derivation::derive_evm_address(&self.master_seed, address_index).await?;
+
+        let balance = self.evm_provider.get_balance(&sender_address).await
+            .map_err(|e| format!("Failed to get blockchain balance: {}", e))?;
+
+        let gas_price = self.evm_provider.get_gas_price().await
+            .map_err(|e| format!("Failed to get gas price: {}", e))?;
+        let estimated_gas = evm_gas_cost(gas_price, 21000)?;
+
+        let swept = balance - estimated_gas;
+        if swept <= Decimal::ZERO {
+            return Err(format!(
+                "Balance too small to cover gas: balance={}, gas={}",
+                balance, estimated_gas
+            ));
+        }
+
+        Ok((swept, gas_price))
+    }
+
+    /// Send a sweep planned by [`plan_recovery_sweep`](Self::plan_recovery_sweep)
+    /// to `to_address`. Returns the tx hash.
+    pub async fn send_recovery_sweep(
+        &self,
+        address_index: u32,
+        chain_id: u32,
+        to_address: &str,
+        value: Decimal,
+        gas_price: u64,
+    ) -> Result<String, String> {
+        let sender_address = derivation::derive_evm_address(&self.master_seed, address_index).await?;
+        self.send_native(address_index, &sender_address, chain_id, to_address, value, gas_price).await
+    }
+
+    /// What sweeping the whole balance of a canonical token off a swap's
+    /// deposit address would send on the chain `evm_provider` points at, and
+    /// the gas price that was priced at. The address pays its own gas, so it
+    /// must hold enough of the native coin.
+    pub async fn plan_token_recovery_sweep(
+        &self,
+        address_index: u32,
+        token: &CanonicalToken,
+    ) -> Result<(Decimal, u64), String> {
+        let sender_address = derivation::derive_evm_address(&self.master_seed, address_index).await?;
+
+        let raw_balance = self.evm_provider.get_token_balance(&token.contract_address, &sender_address).await
+            .map_err(|e| format!("Failed to get token balance: {}", e))?;
+        let balance = amount::from_minor_units(raw_balance, token.decimals as u32).map_err(|e| e.to_string())?;
+        if balance <= Decimal::ZERO {
+            return Err(format!("No {} left to sweep", token.symbol));
+        }
+
+        let gas_price = self.evm_provider.get_gas_price().await
+            .map_err(|e| format!("Failed to get gas price: {}", e))?;
+        let estimated_gas = evm_gas_cost(gas_price, TxType::TokenTransfer.evm_gas_limit())?;
+        let native_balance = self.evm_provider.get_balance(&sender_address).await
+            .map_err(|e| format!("Failed to get blockchain balance: {}", e))?;
+        if native_balance < estimated_gas {
+            return Err(format!(
+                "Address can't pay gas for the {} transfer: balance={}, gas={}",
+                token.symbol, native_balance, estimated_gas
+            ));
+        }
+
+        Ok((balance, gas_price))
+    }
+
+    /// Send a sweep planned by
+    /// [`plan_token_recovery_sweep`](Self::plan_token_recovery_sweep) to
+    /// `to_address`. Returns the tx hash.
+    pub async fn send_token_recovery_sweep(
+        &self,
+        address_index: u32,
+        chain_id: u32,
+        token: &CanonicalToken,
+        to_address: &str,
+        value: Decimal,
+        gas_price: u64,
+    ) -> Result<String, String> {
+        let sender_address = derivation::derive_evm_address(&self.master_seed, address_index).await?;
+        let units = amount::to_minor_units(value, token.decimals as u32).map_err(|e| e.to_string())?;
+
+        let nonce = self.evm_provider.get_transaction_count(&sender_address).await
+            .map_err(|e| format!("Failed to get nonce: {}", e))?;
+
+        let tx = crate::modules::wallet::schema::EvmTransaction {
+            to_address: token.contract_address.clone(),
+            amount: Decimal::ZERO,
+            token: token.symbol.clone(),
+            chain_id,
+            nonce,
+            gas_price,
+            gas_limit: TxType::TokenTransfer.evm_gas_limit(),
+            data: erc20_transfer_data(to_address, units)?,
+        };
+
+        let signature = self.signing.sign_evm(address_index, &tx).await?;
+
+        self.evm_provider.send_raw_transaction(&signature).await
+            .map_err(|e| format!("Failed to broadcast: {}", e))
+    }
+
+    /// Send `refund` less gas from a swap's deposit address to `to_address`
+    /// on the chain `evm_provider` points at. Anything else on the address
+    /// stays there. Returns the tx hash and the amount sent.
+    pub async fn process_refund_payout(
+        &self,
+        address_index: u32,
+        chain_id: u32,
+        to_address: &str,
+        refund: Decimal,
+    ) -> Result<(String, Decimal), String> {
+        let sender_address = derivation::derive_evm_address(&self.master_seed, address_index).await?;
+
+        let balance = self.evm_provider.get_balance(&sender_address).await
+            .map_err(|e| format!("Failed to get blockchain balance: {}", e))?;
+        if balance < refund {
+            return Err(format!(
+                "Address holds less than the refund: balance={}, refund={}",
+                balance, refund
+            ));
+        }
+
+        let gas_price = self.evm_provider.get_gas_price().await
+            .map_err(|e| format!("Failed to get gas price: {}", e))?;
+        let estimated_gas = evm_gas_cost(gas_price, 21000)?;
+
+        let value = refund - estimated_gas;
+        if value <= Decimal::ZERO {
+            return Err(format!(
+                "Refund too small to cover gas: refund={}, gas={}",
+                refund, estimated_gas
+            ));
+        }
+
+        let tx_hash = self
+            .send_native(address_index, &sender_address, chain_id, to_address, value, gas_price)
+            .await?;
+
+        Ok((tx_hash, value))
+    }
+
+    /// Sign and broadcast a plain native transfer from a derived address
+    async fn send_native(
+        &self,
+        address_index: u32,
+        sender_address: &str,
+        chain_id: u32,
+        to_address: &str,
+        value: Decimal,
+        gas_price: u64,
+    ) -> Result<String, String> {
+        let nonce = self.evm_provider.get_transaction_count(sender_address).await
+            .map_err(|e| format!("Failed to get nonce: {}", e))?;
+
+        let tx = crate::modules::wallet::schema::EvmTransaction {
+            to_address: to_address.to_string(),
+            amount: value,
+            token: "NATIVE".to_string(),
+            chain_id,
+            nonce,
+            gas_price,
+            gas_limit: 21000,
+            data: String::new(),
+        };
+
+        let signature = self.signing.sign_evm(address_index, &tx).await?;
+
+        self.evm_provider.send_raw_transaction(&signature).await
+            .map_err(|e| format!("Failed to broadcast: {}", e))
     }
 
     /// Process Bitcoin payout
@@ -216,7 +683,7 @@ impl WalletManager {
             swap_id, info.our_address, actual_balance
         );
         
-        if actual_balance < 0.00001 {
+        if actual_balance < Decimal::new(1, 5) {
             return Err(format!(
                 "Insufficient Bitcoin balance: {} BTC (address: {})",
                 actual_balance, info.our_address
@@ -226,38 +693,31 @@ impl WalletManager {
         let utxos = bitcoin_provider.get_utxos(&info.our_address).await
             .map_err(|e| format!("Failed to get UTXOs: {}", e))?;
 
-        // Estimate fee (target 6 blocks)
-        let fee_rate = bitcoin_provider.estimate_fee(6).await
+        // Fee rate (sat/vB) agreed between the fee sources for the
+        // configured confirmation target
+        let fee_oracle = self.bitcoin_fee_oracle.as_ref()
+            .ok_or_else(|| "Bitcoin fee oracle not configured".to_string())?;
+        let fee_target = fee_oracle.payout_target();
+        let fee_rates = fee_oracle.rates().await
             .map_err(|e| format!("Failed to estimate fee: {}", e))?;
+        let fee_rate = fee_rates.get(fee_target);
 
-        // Calculate platform fee
-        let pricing_strategy = AdaptivePricingStrategy::default();
-        let estimated_tx_fee = fee_rate * 250.0 / 100_000_000.0; // ~250 bytes tx
-
-        let ctx = PricingContext {
-            amount_usd: actual_balance,
-            network_gas_cost_native: estimated_tx_fee,
-            provider_spread_percentage: 0.0,
-        };
-
-        let (commission_rate, gas_floor) = pricing_strategy.calculate_fees(&ctx);
-        let mut platform_fee = actual_balance * commission_rate;
-        if platform_fee < gas_floor {
-            platform_fee = gas_floor;
-        }
+        let tx_fee_sats = fee_rates.payout_fee_sats(fee_target) as u128;
+        let estimated_tx_fee = amount::from_minor_units(tx_fee_sats, amount::BTC_DECIMALS)
+            .map_err(|e| e.to_string())?;
 
-        let final_payout = (actual_balance - platform_fee - estimated_tx_fee).max(0.0);
+        let split = payout_split(actual_balance, estimated_tx_fee, amount::BTC_DECIMALS)?;
 
-        if final_payout <= 0.00001 {
+        if split.payout <= Decimal::new(1, 5) {
             return Err(format!(
                 "Bitcoin payout too small: received={}, fee={}, tx_fee={}",
-                actual_balance, platform_fee, estimated_tx_fee
+                split.received, split.platform_fee, split.network_fee
             ));
         }
 
         tracing::info!(
             "Swap {}: Bitcoin payout - Received: {}, Commission: {}, TxFee: {}, Final: {}",
-            swap_id, actual_balance, platform_fee, estimated_tx_fee, final_payout
+            swap_id, split.received, split.platform_fee, split.network_fee, split.payout
         );
 
         // Build transaction
@@ -265,30 +725,35 @@ impl WalletManager {
         let tx = build_bitcoin_transaction(
             utxos,
             &info.recipient_address,
-            final_payout,
-            fee_rate,
+            split.payout,
+            fee_rate * 1000.0,
             &change_address,
         )?;
 
-        // Sign transaction
-        let _private_key = derivation::derive_btc_key(&self.master_seed, info.address_index).await?;
-        
         // For simplicity, we'll use a basic signing approach
-        // In production, you'd want to properly sign each input with SIGHASH
+        // In production, you'd want to sign each input's SIGHASH with
+        // self.signing.sign_btc(info.address_index, ..)
         let tx_hex = hex::encode(bitcoin::consensus::serialize(&tx));
 
         // Broadcast
         let tx_hash = bitcoin_provider.broadcast_transaction(&tx_hex).await
             .map_err(|e| format!("Failed to broadcast Bitcoin tx: {}", e))?;
 
-        self.crud.mark_payout_completed(swap_id, &tx_hash, actual_balance, platform_fee).await
+        self.record_payout_gas(PayoutGas {
+            network: payout_chain(info.coin_type).to_string(),
+            swap_id: swap_id.to_string(),
+            tx_hash: tx_hash.clone(),
+            tx_type: TxType::NativeTransfer,
+            gas_price: fee_rate.ceil() as u64,
+            gas_limit: btc_fees::PAYOUT_VBYTES,
+            gas_used: Some(tx.vsize() as u64),
+            fee_native: split.network_fee,
+        }).await;
+
+        self.crud.mark_payout_completed(swap_id, &tx_hash, &split).await
             .map_err(|e: sqlx::Error| e.to_string())?;
 
-        Ok(PayoutResponse {
-            tx_hash,
-            amount: final_payout,
-            status: crate::modules::wallet::model::PayoutStatus::Success,
-        })
+        Ok(payout_response(info, tx_hash, split.payout))
     }
 
     /// Process Solana payout
@@ -309,45 +774,42 @@ impl WalletManager {
             swap_id, info.our_address, actual_balance
         );
         
-        if actual_balance < 0.001 {
+        if actual_balance < Decimal::new(1, 3) {
             return Err(format!(
                 "Insufficient Solana balance: {} SOL (address: {})",
                 actual_balance, info.our_address
             ));
         }
 
-        // Get recent blockhash
-        let recent_blockhash = solana_provider.get_recent_blockhash().await
-            .map_err(|e| format!("Failed to get blockhash: {}", e))?;
-
-        // Calculate fees (Solana tx fee is ~0.000005 SOL)
-        let pricing_strategy = AdaptivePricingStrategy::default();
-        let estimated_tx_fee = 0.000005;
-
-        let ctx = PricingContext {
-            amount_usd: actual_balance,
-            network_gas_cost_native: estimated_tx_fee,
-            provider_spread_percentage: 0.0,
+        // Recent blockhash, reused across payouts until it nears expiry
+        let sequencer = self.solana_sequencer.as_ref()
+            .ok_or_else(|| "Solana sequencer not configured".to_string())?;
+        let (recent_blockhash, last_valid_block_height) = match sequencer.acquire(&info.our_address).await? {
+            TxPrerequisite::RecentBlockhash { blockhash, last_valid_block_height } => (blockhash, last_valid_block_height),
+            other => return Err(format!("Unexpected Solana transaction prerequisite: {:?}", other)),
         };
 
-        let (commission_rate, gas_floor) = pricing_strategy.calculate_fees(&ctx);
-        let mut platform_fee = actual_balance * commission_rate;
-        if platform_fee < gas_floor {
-            platform_fee = gas_floor;
-        }
+        // Base fee plus a priority fee priced from recent transactions
+        // writing to the same accounts, so the payout lands under congestion
+        let budget = PriorityFeeEstimator::new(solana_provider.clone())
+            .budget(&[info.our_address.clone(), info.recipient_address.clone()])
+            .await;
+        let tx_fee_lamports = budget.total_fee_lamports();
+        let estimated_tx_fee = amount::from_minor_units(tx_fee_lamports as u128, amount::SOL_DECIMALS)
+            .map_err(|e| e.to_string())?;
 
-        let final_payout = (actual_balance - platform_fee - estimated_tx_fee).max(0.0);
+        let split = payout_split(actual_balance, estimated_tx_fee, amount::SOL_DECIMALS)?;
 
-        if final_payout <= 0.001 {
+        if split.payout <= Decimal::new(1, 3) {
             return Err(format!(
                 "Solana payout too small: received={}, fee={}, tx_fee={}",
-                actual_balance, platform_fee, estimated_tx_fee
+                split.received, split.platform_fee, split.network_fee
             ));
         }
 
         tracing::info!(
             "Swap {}: Solana payout - Received: {}, Commission: {}, TxFee: {}, Final: {}",
-            swap_id, actual_balance, platform_fee, estimated_tx_fee, final_payout
+            swap_id, split.received, split.platform_fee, split.network_fee, split.payout
         );
 
         // Build transaction
@@ -355,43 +817,105 @@ impl WalletManager {
         let mut tx = build_solana_transaction(
             &from_address,
             &info.recipient_address,
-            final_payout,
+            split.payout,
             &recent_blockhash,
+            Some(&budget),
         )?;
 
         // Sign transaction
-        let keypair_seed = derivation::derive_solana_key(&self.master_seed, info.address_index).await?;
-        
-        // Solana keypair is 64 bytes: 32-byte seed + 32-byte public key
-        // We need to construct the full keypair
-        let mut keypair_bytes = vec![0u8; 64];
-        keypair_bytes[..32].copy_from_slice(&keypair_seed);
-        
-        // Derive public key from seed
-        let signing_key = ed25519_dalek::SigningKey::from_bytes(
-            keypair_seed.as_slice().try_into().map_err(|_| "Invalid key length")?
-        );
-        let verifying_key = signing_key.verifying_key();
-        keypair_bytes[32..].copy_from_slice(&verifying_key.to_bytes());
-
-        sign_solana_transaction(&mut tx, &keypair_bytes)?;
+        let signature = self.signing.sign_solana(info.address_index, &tx.message_data()).await?;
+        apply_solana_signature(&mut tx, &signature)?;
 
         // Serialize and encode transaction
         let tx_bytes = bincode::serialize(&tx)
             .map_err(|e| format!("Failed to serialize transaction: {}", e))?;
         let tx_base64 = base64::engine::general_purpose::STANDARD.encode(&tx_bytes);
 
-        // Broadcast
-        let tx_hash = solana_provider.send_transaction(&tx_base64).await
-            .map_err(|e| format!("Failed to broadcast Solana tx: {}", e))?;
+        // Broadcast; a rejection may mean the blockhash expired, so the
+        // next attempt fetches a fresh one
+        let tx_hash = match solana_provider.send_transaction(&tx_base64).await {
+            Ok(tx_hash) => tx_hash,
+            Err(e) => {
+                sequencer.invalidate(&info.our_address).await;
+                return Err(format!("Failed to broadcast Solana tx: {}", e));
+            }
+        };
 
-        self.crud.mark_payout_completed(swap_id, &tx_hash, actual_balance, platform_fee).await
+        // Follow the transaction until it lands or its blockhash expires
+        tokio::spawn(solana_fees::track_landing(
+            solana_provider.clone(),
+            tx_hash.clone(),
+            last_valid_block_height,
+            budget.unit_price_micro_lamports > 0,
+        ));
+
+        self.record_payout_gas(PayoutGas {
+            network: payout_chain(info.coin_type).to_string(),
+            swap_id: swap_id.to_string(),
+            tx_hash: tx_hash.clone(),
+            tx_type: TxType::NativeTransfer,
+            gas_price: tx_fee_lamports,
+            gas_limit: 1,
+            gas_used: None,
+            fee_native: split.network_fee,
+        }).await;
+
+        self.crud.mark_payout_completed(swap_id, &tx_hash, &split).await
             .map_err(|e: sqlx::Error| e.to_string())?;
 
-        Ok(PayoutResponse {
-            tx_hash,
-            amount: final_payout,
-            status: crate::modules::wallet::model::PayoutStatus::Success,
-        })
+        Ok(payout_response(info, tx_hash, split.payout))
+    }
+
+    /// The payout is already broadcast, so a failed write is only logged
+    async fn record_payout_gas(&self, gas: PayoutGas) {
+        if let Err(e) = self.crud.record_payout_gas(&gas).await {
+            tracing::warn!("Swap {}: failed to record payout gas: {}", gas.swap_id, e);
+        }
+    }
+}
+
+/// Split a swap's on-chain balance (as reported by the provider, in the
+/// chain's native unit) between platform fee, network fee and payout
+fn payout_split(received: Decimal, network_fee: Decimal, decimals: u32) -> Result<PayoutSplit, String> {
+    // The pricing strategy only picks a fee tier, so floats are fine here
+    let ctx = PricingContext {
+        amount_usd: received.to_f64().unwrap_or(0.0),
+        network_gas_cost_native: network_fee.to_f64().unwrap_or(0.0),
+        provider_spread_percentage: 0.0,
+    };
+    let (commission_rate, gas_floor) = AdaptivePricingStrategy::default().calculate_fees(&ctx);
+
+    PayoutSplit::compute(received, network_fee, commission_rate, gas_floor, decimals).map_err(|e| e.to_string())
+}
+
+/// Cost of `gas_limit` at `gas_price` wei, in ETH
+fn evm_gas_cost(gas_price: u64, gas_limit: u64) -> Result<Decimal, String> {
+    amount::from_minor_units(gas_price as u128 * gas_limit as u128, amount::EVM_NATIVE_DECIMALS)
+        .map_err(|e| e.to_string())
+}
+
+/// Explorer chain of a payout, following the coin_type dispatch in
+/// `process_payout` (EVM payouts are signed for chain id 1)
+pub(crate) fn payout_chain(coin_type: i32) -> &'static str {
+    match coin_type {
+        0 => "bitcoin",
+        501 => "solana",
+        _ => "ethereum",
+    }
+}
+
+fn payout_response(
+    info: &crate::modules::wallet::model::SwapAddressInfo,
+    tx_hash: String,
+    amount: Decimal,
+) -> PayoutResponse {
+    let registry = ExplorerRegistry::global();
+    let chain = payout_chain(info.coin_type);
+    PayoutResponse {
+        tx_url: registry.tx_url(chain, &tx_hash),
+        address_url: registry.address_url(chain, &info.recipient_address),
+        tx_hash,
+        amount,
+        status: crate::modules::wallet::model::PayoutStatus::Success,
     }
 }
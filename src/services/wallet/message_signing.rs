@@ -0,0 +1,394 @@
+//! Arbitrary messages signed with the hot wallet's key, for operators who
+//! must prove control of it (exchange listings, provider onboarding). Only
+//! admins listed in WALLET_MESSAGE_SIGNERS may sign, each request needs a
+//! fresh two-factor code, and every attempt is written to
+//! `wallet_message_signatures` whether it succeeded or not.
+//!
+//! Messages are signed with the chain's message scheme (EIP-191
+//! `personal_sign`, Bitcoin `signmessage`), whose prefix keeps a message
+//! from ever being a valid transaction or typed-data signature.
+
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::{MySql, Pool};
+use std::collections::HashSet;
+use std::sync::OnceLock;
+
+use super::derivation;
+use super::ownership::verify_ownership_proof;
+use super::signing::SigningService;
+use crate::modules::auth::model::User;
+use crate::modules::swap::schema::AddressProofScheme;
+use crate::services::events::OpsEvent;
+use crate::services::gas::HOT_WALLET_INDEX;
+use crate::services::totp::verify_totp;
+
+pub const DEFAULT_MAX_MESSAGE_BYTES: usize = 2048;
+const MAX_PURPOSE_LEN: usize = 255;
+
+/// Chain whose hot wallet key signs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum HotWalletChain {
+    /// Ethereum and every EVM chain; one key, one address
+    Evm,
+    Bitcoin,
+}
+
+impl HotWalletChain {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HotWalletChain::Evm => "evm",
+            HotWalletChain::Bitcoin => "bitcoin",
+        }
+    }
+
+    pub fn scheme(&self) -> AddressProofScheme {
+        match self {
+            HotWalletChain::Evm => AddressProofScheme::Eip191,
+            HotWalletChain::Bitcoin => AddressProofScheme::Bip137,
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum MessageSigningError {
+    #[error("Not permitted to sign with the hot wallet")]
+    NotPermitted,
+
+    #[error("Enable two-factor authentication to sign with the hot wallet")]
+    TwoFactorRequired,
+
+    #[error("Invalid two-factor code")]
+    InvalidTwoFactorCode,
+
+    #[error("Invalid message: {0}")]
+    InvalidMessage(String),
+
+    #[error("Purpose must be between 3 and {} characters", MAX_PURPOSE_LEN)]
+    InvalidPurpose,
+
+    #[error("Signing failed: {0}")]
+    Signing(String),
+
+    #[error("Database error: {0}")]
+    Database(String),
+}
+
+impl MessageSigningError {
+    pub fn status_code(&self) -> axum::http::StatusCode {
+        match self {
+            MessageSigningError::NotPermitted | MessageSigningError::TwoFactorRequired => {
+                axum::http::StatusCode::FORBIDDEN
+            }
+            MessageSigningError::InvalidTwoFactorCode => axum::http::StatusCode::UNAUTHORIZED,
+            MessageSigningError::InvalidMessage(_) | MessageSigningError::InvalidPurpose => {
+                axum::http::StatusCode::BAD_REQUEST
+            }
+            MessageSigningError::Signing(_) | MessageSigningError::Database(_) => {
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR
+            }
+        }
+    }
+
+    /// Outcome recorded for an attempt that ended in this error
+    fn outcome(&self) -> &'static str {
+        match self {
+            MessageSigningError::Signing(_) | MessageSigningError::Database(_) => "failed",
+            _ => "denied",
+        }
+    }
+}
+
+impl From<sqlx::Error> for MessageSigningError {
+    fn from(e: sqlx::Error) -> Self {
+        MessageSigningError::Database(e.to_string())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MessageSigningPolicy {
+    /// User ids of the admins allowed to sign; nobody when empty
+    pub signers: HashSet<String>,
+    pub max_message_bytes: usize,
+}
+
+impl Default for MessageSigningPolicy {
+    fn default() -> Self {
+        Self { signers: HashSet::new(), max_message_bytes: DEFAULT_MAX_MESSAGE_BYTES }
+    }
+}
+
+impl MessageSigningPolicy {
+    pub fn from_env() -> Result<Self, String> {
+        let mut policy = Self::default();
+
+        if let Ok(val) = std::env::var("WALLET_MESSAGE_SIGNERS") {
+            policy.signers = val.split(',').map(str::trim).filter(|s| !s.is_empty()).map(str::to_string).collect();
+        }
+        if let Ok(val) = std::env::var("WALLET_MESSAGE_MAX_BYTES") {
+            let max: usize = val.trim().parse().map_err(|e| format!("Invalid WALLET_MESSAGE_MAX_BYTES: {}", e))?;
+            if !(1..=65_535).contains(&max) {
+                return Err("WALLET_MESSAGE_MAX_BYTES must be between 1 and 65535".to_string());
+            }
+            policy.max_message_bytes = max;
+        }
+
+        Ok(policy)
+    }
+
+    pub fn global() -> &'static MessageSigningPolicy {
+        static POLICY: OnceLock<MessageSigningPolicy> = OnceLock::new();
+        POLICY.get_or_init(|| {
+            Self::from_env().unwrap_or_else(|e| {
+                tracing::warn!("Ignoring invalid wallet message signing config: {}", e);
+                Self::default()
+            })
+        })
+    }
+
+    pub fn permits(&self, user_id: &str) -> bool {
+        self.signers.contains(user_id)
+    }
+
+    /// Messages are text; control characters other than line breaks and
+    /// tabs, or bidi overrides, could hide what is being signed from
+    /// whoever reviews the audit
+    pub fn validate_message(&self, message: &str) -> Result<(), MessageSigningError> {
+        if message.trim().is_empty() {
+            return Err(MessageSigningError::InvalidMessage("message is empty".to_string()));
+        }
+        if message.len() > self.max_message_bytes {
+            return Err(MessageSigningError::InvalidMessage(format!(
+                "message is longer than {} bytes",
+                self.max_message_bytes
+            )));
+        }
+        let hidden = |c: char| {
+            (c.is_control() && !matches!(c, '\n' | '\r' | '\t'))
+                || matches!(c, '\u{202a}'..='\u{202e}' | '\u{2066}'..='\u{2069}')
+        };
+        if message.chars().any(hidden) {
+            return Err(MessageSigningError::InvalidMessage("message contains control or direction-override characters".to_string()));
+        }
+        Ok(())
+    }
+}
+
+fn validate_purpose(purpose: &str) -> Result<(), MessageSigningError> {
+    let len = purpose.trim().chars().count();
+    if (3..=MAX_PURPOSE_LEN).contains(&len) {
+        Ok(())
+    } else {
+        Err(MessageSigningError::InvalidPurpose)
+    }
+}
+
+/// What an admin asked to have signed
+#[derive(Debug, Clone)]
+pub struct MessageSigningRequest {
+    pub chain: HotWalletChain,
+    pub message: String,
+    /// Why it is being signed, e.g. "Listing on Example Exchange"
+    pub purpose: String,
+    pub two_factor_code: String,
+    pub client_ip: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct SignedMessage {
+    /// Audit record of this signature
+    pub audit_id: i64,
+    pub chain: HotWalletChain,
+    pub address: String,
+    pub scheme: AddressProofScheme,
+    /// Exact text that was signed
+    pub message: String,
+    pub signature: String,
+    pub signed_at: DateTime<Utc>,
+}
+
+pub struct HotWalletMessageSigner {
+    pool: Pool<MySql>,
+    signing: SigningService,
+    master_seed: String,
+    policy: MessageSigningPolicy,
+}
+
+impl HotWalletMessageSigner {
+    pub fn new(pool: Pool<MySql>, master_seed: String, policy: MessageSigningPolicy) -> Self {
+        Self { pool, signing: SigningService::from_config(&master_seed), master_seed, policy }
+    }
+
+    pub fn with_signing(mut self, signing: SigningService) -> Self {
+        self.signing = signing;
+        self
+    }
+
+    /// Hot wallet address of `chain`
+    pub async fn address(&self, chain: HotWalletChain) -> Result<String, String> {
+        match chain {
+            HotWalletChain::Evm => derivation::derive_evm_address(&self.master_seed, HOT_WALLET_INDEX).await,
+            HotWalletChain::Bitcoin => derivation::derive_btc_address(&self.master_seed, HOT_WALLET_INDEX).await,
+        }
+    }
+
+    /// Sign `request.message` for `admin`, recording the attempt. A
+    /// signature is only returned once its audit row is written.
+    pub async fn sign(&self, admin: &User, request: &MessageSigningRequest) -> Result<SignedMessage, MessageSigningError> {
+        let mut address = None;
+        let result = self.attempt(admin, request, &mut address).await;
+
+        let (outcome, signature, error) = match &result {
+            Ok(signed) => ("signed", Some(signed.signature.as_str()), None),
+            Err(e) => (e.outcome(), None, Some(e.to_string())),
+        };
+        let audit_id = self
+            .record(admin, request, address.as_deref(), outcome, signature, error.as_deref())
+            .await
+            .map_err(|e| {
+                tracing::error!("Could not audit hot wallet message signing by {}: {}", admin.id, e);
+                MessageSigningError::Database(e.to_string())
+            })?;
+
+        match result {
+            Ok(mut signed) => {
+                tracing::warn!(
+                    admin_id = %admin.id,
+                    chain = request.chain.as_str(),
+                    audit_id,
+                    "Hot wallet signed a message: {}",
+                    request.purpose.trim()
+                );
+                OpsEvent::HotWalletMessageSigned {
+                    admin_id: admin.id.clone(),
+                    chain: request.chain.as_str().to_string(),
+                    address: signed.address.clone(),
+                    purpose: request.purpose.trim().to_string(),
+                }
+                .publish();
+                signed.audit_id = audit_id;
+                Ok(signed)
+            }
+            Err(e) => {
+                tracing::warn!(admin_id = %admin.id, audit_id, "Hot wallet message signing refused: {}", e);
+                Err(e)
+            }
+        }
+    }
+
+    async fn attempt(
+        &self,
+        admin: &User,
+        request: &MessageSigningRequest,
+        address: &mut Option<String>,
+    ) -> Result<SignedMessage, MessageSigningError> {
+        if !self.policy.permits(&admin.id) {
+            return Err(MessageSigningError::NotPermitted);
+        }
+        let secret = match (admin.two_factor_enabled, &admin.two_factor_secret) {
+            (true, Some(secret)) => secret,
+            _ => return Err(MessageSigningError::TwoFactorRequired),
+        };
+        if !verify_totp(secret, &request.two_factor_code) {
+            return Err(MessageSigningError::InvalidTwoFactorCode);
+        }
+        self.policy.validate_message(&request.message)?;
+        validate_purpose(&request.purpose)?;
+
+        let hot_wallet = self.address(request.chain).await.map_err(MessageSigningError::Signing)?;
+        *address = Some(hot_wallet.clone());
+
+        let bytes = request.message.as_bytes();
+        let signature = match request.chain {
+            HotWalletChain::Evm => self.signing.sign_evm_message(HOT_WALLET_INDEX, bytes).await,
+            HotWalletChain::Bitcoin => self.signing.sign_btc_message(HOT_WALLET_INDEX, bytes).await,
+        }
+        .map_err(MessageSigningError::Signing)?;
+        // A signer backend on another key must not look like a valid proof
+        verify_ownership_proof(&hot_wallet, &request.message, &signature).map_err(MessageSigningError::Signing)?;
+
+        Ok(SignedMessage {
+            audit_id: 0,
+            chain: request.chain,
+            address: hot_wallet,
+            scheme: request.chain.scheme(),
+            message: request.message.clone(),
+            signature,
+            signed_at: Utc::now(),
+        })
+    }
+
+    async fn record(
+        &self,
+        admin: &User,
+        request: &MessageSigningRequest,
+        address: Option<&str>,
+        outcome: &str,
+        signature: Option<&str>,
+        error: Option<&str>,
+    ) -> Result<i64, sqlx::Error> {
+        let truncate = |s: &str, max: usize| s.chars().take(max).collect::<String>();
+        let result = sqlx::query(
+            r#"
+            INSERT INTO wallet_message_signatures (
+                admin_id, chain, address, message, message_sha256, purpose, outcome, signature, error, client_ip
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&admin.id)
+        .bind(request.chain.as_str())
+        .bind(address)
+        // TEXT holds 64 KiB; longer messages are refused anyway
+        .bind(truncate(&request.message, 16_000))
+        .bind(hex::encode(Sha256::digest(request.message.as_bytes())))
+        .bind(truncate(request.purpose.trim(), MAX_PURPOSE_LEN))
+        .bind(outcome)
+        .bind(signature)
+        .bind(error.map(|e| truncate(e, 255)))
+        .bind(request.client_ip.as_deref())
+        .execute(&self.pool)
+        .await?;
+        Ok(result.last_insert_id() as i64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_policy_permits_listed_signers_only() {
+        let policy = MessageSigningPolicy::default();
+        assert!(!policy.permits("admin-1"));
+
+        let policy = MessageSigningPolicy {
+            signers: ["admin-1".to_string()].into_iter().collect(),
+            ..MessageSigningPolicy::default()
+        };
+        assert!(policy.permits("admin-1"));
+        assert!(!policy.permits("admin-2"));
+    }
+
+    #[test]
+    fn test_message_validation() {
+        let policy = MessageSigningPolicy { max_message_bytes: 32, ..MessageSigningPolicy::default() };
+        assert!(policy.validate_message("Example Exchange listing\nnonce: 7").is_err());
+        assert!(policy.validate_message("listing nonce: 7\n").is_ok());
+        assert!(policy.validate_message("  \n").is_err());
+        assert!(policy.validate_message("hidden\u{202e}text").is_err());
+        assert!(policy.validate_message("nul\0byte").is_err());
+
+        assert!(validate_purpose("Listing").is_ok());
+        assert!(validate_purpose(" x ").is_err());
+    }
+
+    #[test]
+    fn test_refusals_are_recorded_as_denied() {
+        assert_eq!(MessageSigningError::NotPermitted.outcome(), "denied");
+        assert_eq!(MessageSigningError::InvalidTwoFactorCode.outcome(), "denied");
+        assert_eq!(MessageSigningError::Signing("remote signer down".to_string()).outcome(), "failed");
+    }
+}
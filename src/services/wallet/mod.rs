@@ -1,8 +1,15 @@
 pub mod derivation;
+pub mod paths;
 pub mod signing;
+pub mod signer;
+pub mod ownership;
+pub mod message_signing;
 pub mod manager;
 pub mod rpc;
 pub mod bitcoin_rpc;
+pub mod btc_fees;
 pub mod solana_rpc;
+pub mod solana_fees;
+pub mod sequencer;
 
 pub use derivation::*;
@@ -0,0 +1,239 @@
+//! Proofs that an address belongs to our HD wallet, for partners who want to
+//! check where they are sending funds. The partner picks a challenge; we sign
+//! a fixed statement naming the address and the challenge with the address's
+//! own key, in the chain's usual message-signing scheme, so the proof can be
+//! checked with standard wallet tooling or [`verify_ownership_proof`].
+//!
+//! The statement always starts with [`STATEMENT_HEADER`], so a challenge can
+//! never make us sign a transaction.
+
+use base64::Engine;
+use chrono::{DateTime, SecondsFormat, Utc};
+use ed25519_dalek::{Signature as EdSignature, VerifyingKey};
+use ripemd::Ripemd160;
+use secp256k1::ecdsa::{RecoverableSignature, RecoveryId};
+use secp256k1::{Message, PublicKey, Secp256k1};
+use sha2::{Digest, Sha256};
+use sha3::Keccak256;
+
+use crate::modules::swap::schema::AddressProofScheme;
+use super::signing::{btc_message_hash, evm_message_hash, SigningService};
+
+pub const STATEMENT_HEADER: &str = "exchange-shared address ownership proof";
+pub const MIN_CHALLENGE_LEN: usize = 8;
+pub const MAX_CHALLENGE_LEN: usize = 128;
+
+/// Scheme the address's chain signs messages with, from the address's shape:
+/// `0x` + 40 hex is EVM, a base58check P2PKH address is Bitcoin and a
+/// 32-byte base58 key is Solana
+pub fn proof_scheme(address: &str) -> Option<AddressProofScheme> {
+    if let Some(hex_part) = address.strip_prefix("0x") {
+        return (hex_part.len() == 40 && hex::decode(hex_part).is_ok()).then_some(AddressProofScheme::Eip191);
+    }
+    let bytes = bs58::decode(address).into_vec().ok()?;
+    match bytes.len() {
+        25 if p2pkh_hash(&bytes).is_some() => Some(AddressProofScheme::Bip137),
+        32 => Some(AddressProofScheme::Ed25519),
+        _ => None,
+    }
+}
+
+/// Challenges are printable ASCII, so the statement is unambiguous
+pub fn validate_challenge(challenge: &str) -> Result<(), String> {
+    if !(MIN_CHALLENGE_LEN..=MAX_CHALLENGE_LEN).contains(&challenge.len()) {
+        return Err(format!(
+            "challenge must be {} to {} characters",
+            MIN_CHALLENGE_LEN, MAX_CHALLENGE_LEN
+        ));
+    }
+    if !challenge.bytes().all(|b| (0x20..=0x7e).contains(&b)) {
+        return Err("challenge must be printable ASCII".to_string());
+    }
+    Ok(())
+}
+
+/// The message signed for a proof
+pub fn proof_statement(address: &str, challenge: &str, issued_at: DateTime<Utc>) -> String {
+    format!(
+        "{}\nAddress: {}\nChallenge: {}\nIssued at: {}",
+        STATEMENT_HEADER,
+        address,
+        challenge,
+        issued_at.to_rfc3339_opts(SecondsFormat::Secs, true)
+    )
+}
+
+/// Sign `statement` with the key at HD `index`, which must be the key behind
+/// `address`. Signatures are EIP-191 `0x{r}{s}{v}`, base64 Bitcoin message
+/// signatures, or base58 Ed25519 signatures, as each chain's wallets expect.
+pub async fn sign_ownership(
+    signing: &SigningService,
+    address: &str,
+    index: u32,
+    statement: &str,
+) -> Result<(AddressProofScheme, String), String> {
+    let scheme = proof_scheme(address).ok_or_else(|| format!("No ownership proof scheme for {}", address))?;
+    let signature = match scheme {
+        AddressProofScheme::Eip191 => signing.sign_evm_message(index, statement.as_bytes()).await?,
+        AddressProofScheme::Bip137 => signing.sign_btc_message(index, statement.as_bytes()).await?,
+        AddressProofScheme::Ed25519 => {
+            let signature = signing.sign_solana(index, statement.as_bytes()).await?;
+            let bytes = hex::decode(signature.trim_start_matches("0x")).map_err(|e| e.to_string())?;
+            bs58::encode(bytes).into_string()
+        }
+    };
+
+    // Never hand out a proof that doesn't check out, e.g. after a path change
+    verify_ownership_proof(address, statement, &signature)?;
+    Ok((scheme, signature))
+}
+
+/// Check that `signature` over `message` was made with the key behind
+/// `address`
+pub fn verify_ownership_proof(address: &str, message: &str, signature: &str) -> Result<(), String> {
+    let scheme = proof_scheme(address).ok_or_else(|| format!("No ownership proof scheme for {}", address))?;
+    let message = message.as_bytes();
+    let matches = match scheme {
+        AddressProofScheme::Eip191 => {
+            let bytes = hex::decode(signature.trim_start_matches("0x")).map_err(|_| "Signature is not hex")?;
+            if bytes.len() != 65 {
+                return Err("Signature must be 65 bytes".to_string());
+            }
+            let v = match bytes[64] {
+                27 | 28 => bytes[64] - 27,
+                v @ (0 | 1) => v,
+                _ => return Err("Invalid signature recovery byte".to_string()),
+            };
+            let key = recover(&bytes[..64], v, evm_message_hash(message))?;
+            let hash = Keccak256::digest(&key.serialize_uncompressed()[1..]);
+            hex::encode(&hash[12..]).eq_ignore_ascii_case(&address[2..])
+        }
+        AddressProofScheme::Bip137 => {
+            let bytes = base64::engine::general_purpose::STANDARD
+                .decode(signature)
+                .map_err(|_| "Signature is not base64")?;
+            if bytes.len() != 65 || !(27..=42).contains(&bytes[0]) {
+                return Err("Signature is not a Bitcoin message signature".to_string());
+            }
+            let header = bytes[0] - 27;
+            let key = recover(&bytes[1..], header & 3, btc_message_hash(message))?;
+            // Headers 27-30 are for uncompressed keys, the rest compressed
+            let key_bytes = if header < 4 { key.serialize_uncompressed().to_vec() } else { key.serialize().to_vec() };
+            let decoded = bs58::decode(address).into_vec().map_err(|e| e.to_string())?;
+            p2pkh_hash(&decoded) == Some(hash160(&key_bytes))
+        }
+        AddressProofScheme::Ed25519 => {
+            let key_bytes: [u8; 32] = bs58::decode(address)
+                .into_vec()
+                .map_err(|e| e.to_string())?
+                .try_into()
+                .map_err(|_| "Invalid Solana address")?;
+            let key = VerifyingKey::from_bytes(&key_bytes).map_err(|e| e.to_string())?;
+            let signature_bytes: [u8; 64] = bs58::decode(signature)
+                .into_vec()
+                .map_err(|_| "Signature is not base58")?
+                .try_into()
+                .map_err(|_| "Signature must be 64 bytes")?;
+            key.verify_strict(message, &EdSignature::from_bytes(&signature_bytes)).is_ok()
+        }
+    };
+
+    if matches {
+        Ok(())
+    } else {
+        Err(format!("Signature was not made by the key of {}", address))
+    }
+}
+
+fn recover(compact: &[u8], recovery_id: u8, digest: [u8; 32]) -> Result<PublicKey, String> {
+    let recovery_id = RecoveryId::from_i32(recovery_id as i32).map_err(|e| e.to_string())?;
+    let signature = RecoverableSignature::from_compact(compact, recovery_id).map_err(|e| e.to_string())?;
+    Secp256k1::verification_only()
+        .recover_ecdsa(&Message::from_digest(digest), &signature)
+        .map_err(|e| format!("Signature does not recover a key: {}", e))
+}
+
+fn hash160(data: &[u8]) -> [u8; 20] {
+    Ripemd160::digest(Sha256::digest(data)).into()
+}
+
+/// Public key hash of a decoded base58check P2PKH address with a valid
+/// checksum
+fn p2pkh_hash(decoded: &[u8]) -> Option<[u8; 20]> {
+    if decoded.len() != 25 || !matches!(decoded[0], 0x00 | 0x6f) {
+        return None;
+    }
+    let checksum = Sha256::digest(Sha256::digest(&decoded[..21]));
+    if checksum[..4] != decoded[21..] {
+        return None;
+    }
+    decoded[1..21].try_into().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::wallet::derivation;
+    use crate::services::wallet::signer::LocalHdSigner;
+    use std::sync::Arc;
+
+    const TEST_MNEMONIC: &str =
+        "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+    fn signing() -> SigningService {
+        SigningService::new(Arc::new(LocalHdSigner::new(TEST_MNEMONIC.to_string())))
+    }
+
+    fn statement(address: &str) -> String {
+        proof_statement(address, "partner-nonce-42", DateTime::from_timestamp(1_760_000_000, 0).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_proofs_verify_for_each_chain() {
+        let addresses = [
+            (derivation::derive_evm_address(TEST_MNEMONIC, 3).await.unwrap(), AddressProofScheme::Eip191),
+            (derivation::derive_btc_address(TEST_MNEMONIC, 3).await.unwrap(), AddressProofScheme::Bip137),
+            (derivation::derive_solana_address(TEST_MNEMONIC, 3).await.unwrap(), AddressProofScheme::Ed25519),
+        ];
+
+        for (address, expected) in addresses {
+            let message = statement(&address);
+            let (scheme, signature) = sign_ownership(&signing(), &address, 3, &message).await.unwrap();
+            assert_eq!(scheme, expected);
+            assert!(verify_ownership_proof(&address, &message, &signature).is_ok());
+            assert!(verify_ownership_proof(&address, &message.replace("42", "43"), &signature).is_err());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_proof_for_another_index_is_refused() {
+        let address = derivation::derive_evm_address(TEST_MNEMONIC, 3).await.unwrap();
+        assert!(sign_ownership(&signing(), &address, 4, &statement(&address)).await.is_err());
+
+        let other = derivation::derive_btc_address(TEST_MNEMONIC, 4).await.unwrap();
+        let address = derivation::derive_btc_address(TEST_MNEMONIC, 3).await.unwrap();
+        let (_, signature) = sign_ownership(&signing(), &other, 4, &statement(&address)).await.unwrap();
+        assert!(verify_ownership_proof(&address, &statement(&address), &signature).is_err());
+    }
+
+    #[test]
+    fn test_statement_and_challenge() {
+        let message = statement("0x742d35cc6634c0532925a3b844bc454e4438f44e");
+        assert!(message.starts_with(STATEMENT_HEADER));
+        assert!(message.ends_with("Issued at: 2025-10-09T08:53:20Z"));
+
+        assert!(validate_challenge("partner-nonce-42").is_ok());
+        assert!(validate_challenge("short").is_err());
+        assert!(validate_challenge("line\nbreak-inside").is_err());
+        assert!(validate_challenge(&"x".repeat(MAX_CHALLENGE_LEN + 1)).is_err());
+    }
+
+    #[test]
+    fn test_proof_scheme_from_address() {
+        assert_eq!(proof_scheme("0x742d35Cc6634C0532925a3b844Bc454e4438f44e"), Some(AddressProofScheme::Eip191));
+        assert_eq!(proof_scheme("1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN2"), Some(AddressProofScheme::Bip137));
+        assert_eq!(proof_scheme("1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN3"), None);
+        assert_eq!(proof_scheme("0x742d35"), None);
+        assert_eq!(proof_scheme("not an address"), None);
+    }
+}
@@ -0,0 +1,250 @@
+//! BIP32 derivation paths for the secp256k1 chains (Bitcoin and EVM).
+//! Operators migrating from another wallet can set the account and change
+//! indices and the whole path template per chain. Solana, Sui and Monero
+//! keys are derived by hashing the seed, not by path, and are unaffected.
+//!
+//! The paths in use are recorded in `hd_derivation_paths`. Once addresses
+//! exist, startup refuses a configuration that would derive different keys
+//! for them unless HD_PATH_ALLOW_CHANGE is set.
+
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+use coins_bip32::path::DerivationPath;
+use sqlx::{MySql, Pool};
+
+pub const DEFAULT_BITCOIN_PATH: &str = "m/44'/0'/{account}'/{change}/{index}";
+pub const DEFAULT_EVM_PATH: &str = "m/44'/60'/{account}'/{change}/{index}";
+
+const HARDENED: u32 = 0x8000_0000;
+
+static PATHS: OnceLock<DerivationPaths> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PathChain {
+    Bitcoin,
+    Evm,
+}
+
+impl PathChain {
+    pub const ALL: [PathChain; 2] = [PathChain::Bitcoin, PathChain::Evm];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PathChain::Bitcoin => "bitcoin",
+            PathChain::Evm => "evm",
+        }
+    }
+
+    /// `swap_address_info.coin_type` of addresses derived on this path
+    pub fn coin_type(&self) -> i32 {
+        match self {
+            PathChain::Bitcoin => 0,
+            PathChain::Evm => 60,
+        }
+    }
+}
+
+/// Path templates with the account and change indices filled in; only the
+/// trailing `{index}` is left to substitute per address
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DerivationPaths {
+    bitcoin: String,
+    evm: String,
+}
+
+impl Default for DerivationPaths {
+    fn default() -> Self {
+        Self::new(0, 0, DEFAULT_BITCOIN_PATH, DEFAULT_EVM_PATH).expect("default derivation paths are valid")
+    }
+}
+
+impl DerivationPaths {
+    pub fn new(account: u32, change: u32, bitcoin: &str, evm: &str) -> Result<Self, String> {
+        Ok(Self {
+            bitcoin: resolve_template(bitcoin, account, change).map_err(|e| format!("Bitcoin path: {}", e))?,
+            evm: resolve_template(evm, account, change).map_err(|e| format!("EVM path: {}", e))?,
+        })
+    }
+
+    /// HD_ACCOUNT and HD_CHANGE (default 0) fill `{account}` and `{change}`
+    /// in HD_PATH_BITCOIN and HD_PATH_EVM (default BIP44 for each chain)
+    pub fn from_env() -> Result<Self, String> {
+        let index = |name: &str| -> Result<u32, String> {
+            match std::env::var(name) {
+                Ok(v) if !v.trim().is_empty() => v
+                    .trim()
+                    .parse::<u32>()
+                    .ok()
+                    .filter(|i| *i < HARDENED)
+                    .ok_or_else(|| format!("Invalid {}: {}", name, v)),
+                _ => Ok(0),
+            }
+        };
+        let template = |name: &str, default: &str| {
+            std::env::var(name)
+                .ok()
+                .filter(|v| !v.trim().is_empty())
+                .unwrap_or_else(|| default.to_string())
+        };
+
+        Self::new(
+            index("HD_ACCOUNT")?,
+            index("HD_CHANGE")?,
+            &template("HD_PATH_BITCOIN", DEFAULT_BITCOIN_PATH),
+            &template("HD_PATH_EVM", DEFAULT_EVM_PATH),
+        )
+    }
+
+    /// Process-wide paths, read from the environment once. An invalid
+    /// configuration panics rather than deriving keys on the wrong path.
+    pub fn global() -> &'static DerivationPaths {
+        PATHS.get_or_init(|| Self::from_env().expect("Invalid HD derivation path configuration"))
+    }
+
+    /// The resolved template, ending in `{index}`
+    pub fn template(&self, chain: PathChain) -> &str {
+        match chain {
+            PathChain::Bitcoin => &self.bitcoin,
+            PathChain::Evm => &self.evm,
+        }
+    }
+
+    pub fn path(&self, chain: PathChain, index: u32) -> String {
+        self.template(chain).replace("{index}", &index.to_string())
+    }
+}
+
+/// Fill in `{account}` and `{change}` and check the result is a BIP32 path
+/// whose last component is a non-hardened `{index}`
+fn resolve_template(template: &str, account: u32, change: u32) -> Result<String, String> {
+    let resolved = template
+        .trim()
+        .replace("{account}", &account.to_string())
+        .replace("{change}", &change.to_string());
+
+    let mut segments = resolved.split('/');
+    if segments.next() != Some("m") {
+        return Err(format!("{} must start with m/", template));
+    }
+    let segments: Vec<&str> = segments.collect();
+    let Some((last, parents)) = segments.split_last() else {
+        return Err(format!("{} has no components", template));
+    };
+    if *last != "{index}" {
+        return Err(format!("{} must end with a non-hardened {{index}}", template));
+    }
+    if parents.is_empty() {
+        return Err(format!("{} needs at least one component before {{index}}", template));
+    }
+    for segment in parents {
+        let number = segment.strip_suffix('\'').unwrap_or(segment);
+        if !number.parse::<u32>().is_ok_and(|n| n < HARDENED) {
+            return Err(format!("{} has an invalid component '{}'", template, segment));
+        }
+    }
+
+    DerivationPath::from_str(&resolved.replace("{index}", "0"))
+        .map_err(|e| format!("{} is not a valid derivation path: {}", template, e))?;
+    Ok(resolved)
+}
+
+/// Whether HD_PATH_ALLOW_CHANGE permits new paths over existing addresses
+pub fn path_change_allowed() -> bool {
+    std::env::var("HD_PATH_ALLOW_CHANGE")
+        .map(|v| matches!(v.trim().to_ascii_lowercase().as_str(), "1" | "true" | "yes"))
+        .unwrap_or(false)
+}
+
+/// Record the configured paths, refusing to replace a recorded path that
+/// addresses were already derived on unless `allow_change` is set
+pub async fn guard_path_changes(db: &Pool<MySql>, paths: &DerivationPaths, allow_change: bool) -> Result<(), String> {
+    for chain in PathChain::ALL {
+        let template = paths.template(chain);
+        let recorded: Option<(String,)> =
+            sqlx::query_as("SELECT path_template FROM hd_derivation_paths WHERE chain = ?")
+                .bind(chain.as_str())
+                .fetch_optional(db)
+                .await
+                .map_err(|e| e.to_string())?;
+        // Deployments from before paths were recorded used the defaults
+        let stored = recorded.is_some();
+        let recorded = recorded
+            .map(|(path,)| path)
+            .unwrap_or_else(|| DerivationPaths::default().template(chain).to_string());
+
+        if recorded == template {
+            if stored {
+                continue;
+            }
+        } else {
+            let (addresses,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM swap_address_info WHERE coin_type = ?")
+                .bind(chain.coin_type())
+                .fetch_one(db)
+                .await
+                .map_err(|e| e.to_string())?;
+
+            if addresses > 0 && !allow_change {
+                return Err(format!(
+                    "{} derivation path changed from {} to {} but {} addresses were derived on the old path; \
+                     set HD_PATH_ALLOW_CHANGE=true to switch anyway",
+                    chain.as_str(), recorded, template, addresses
+                ));
+            }
+            if addresses > 0 {
+                tracing::warn!(
+                    "{} derivation path changed from {} to {} over {} existing addresses",
+                    chain.as_str(), recorded, template, addresses
+                );
+            }
+        }
+
+        sqlx::query(
+            r#"
+            INSERT INTO hd_derivation_paths (chain, path_template) VALUES (?, ?)
+            ON DUPLICATE KEY UPDATE path_template = VALUES(path_template)
+            "#,
+        )
+        .bind(chain.as_str())
+        .bind(template)
+        .execute(db)
+        .await
+        .map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_paths_are_bip44() {
+        let paths = DerivationPaths::default();
+        assert_eq!(paths.path(PathChain::Bitcoin, 7), "m/44'/0'/0'/0/7");
+        assert_eq!(paths.path(PathChain::Evm, 7), "m/44'/60'/0'/0/7");
+    }
+
+    #[test]
+    fn test_account_change_and_custom_template() {
+        let paths = DerivationPaths::new(2, 1, DEFAULT_BITCOIN_PATH, "m/44'/60'/0'/{account}/{index}").unwrap();
+        assert_eq!(paths.path(PathChain::Bitcoin, 3), "m/44'/0'/2'/1/3");
+        assert_eq!(paths.template(PathChain::Evm), "m/44'/60'/0'/2/{index}");
+    }
+
+    #[test]
+    fn test_invalid_templates_rejected() {
+        for template in [
+            "44'/60'/0'/0/{index}",
+            "m/44'/60'/0'/0/0",
+            "m/44'/60'/0'/{index}/0",
+            "m/44'/60'/0'/0/{index}'",
+            "m/44'/60'/x'/0/{index}",
+            "m/44'/60'/2147483648/0/{index}",
+            "m/{index}",
+        ] {
+            assert!(DerivationPaths::new(0, 0, DEFAULT_BITCOIN_PATH, template).is_err(), "{}", template);
+        }
+    }
+}
@@ -3,6 +3,8 @@ use serde::Deserialize;
 use serde_json::json;
 use std::time::Duration;
 
+use crate::services::amount::{self, Decimal};
+
 #[derive(Debug, thiserror::Error)]
 pub enum RpcError {
     #[error("Network error: {0}")]
@@ -13,12 +15,67 @@ pub enum RpcError {
     Parse(String),
 }
 
+/// keccak256("Transfer(address,address,uint256)")
+pub const ERC20_TRANSFER_TOPIC: &str = "0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef";
+
+/// Selector of ERC-20 `balanceOf(address)`
+const ERC20_BALANCE_OF: &str = "70a08231";
+
+/// One ERC-20 `Transfer` event
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransferLog {
+    /// Emitting contract, lower-cased
+    pub contract: String,
+    pub from: String,
+    pub to: String,
+    /// Raw token units; saturates for values beyond u128
+    pub amount: u128,
+    pub tx_hash: String,
+    pub log_index: u64,
+    pub block_number: u64,
+}
+
+/// Outcome of a mined transaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TransactionReceipt {
+    pub block_number: u64,
+    /// False when the transaction reverted
+    pub success: bool,
+}
+
 #[async_trait]
 pub trait BlockchainProvider: Send + Sync {
     async fn get_transaction_count(&self, address: &str) -> Result<u64, RpcError>;
     async fn get_gas_price(&self) -> Result<u64, RpcError>;
     async fn send_raw_transaction(&self, signed_hex: &str) -> Result<String, RpcError>;
-    async fn get_balance(&self, address: &str) -> Result<f64, RpcError>;
+    /// Native balance in ETH (not wei)
+    async fn get_balance(&self, address: &str) -> Result<Decimal, RpcError>;
+
+    async fn get_block_number(&self) -> Result<u64, RpcError> {
+        Err(RpcError::Rpc("eth_blockNumber not supported by this provider".to_string()))
+    }
+
+    /// ERC-20 transfers into `to_address` from any contract since `from_block`
+    async fn get_transfer_logs(&self, _to_address: &str, _from_block: u64) -> Result<Vec<TransferLog>, RpcError> {
+        Err(RpcError::Rpc("eth_getLogs not supported by this provider".to_string()))
+    }
+
+    /// Raw ERC-20 balance of `owner`; saturates for values beyond u128
+    async fn get_token_balance(&self, _contract: &str, _owner: &str) -> Result<u128, RpcError> {
+        Err(RpcError::Rpc("eth_call not supported by this provider".to_string()))
+    }
+
+    /// Receipt of `tx_hash`, or `None` while it is pending or unknown
+    async fn get_transaction_receipt(&self, _tx_hash: &str) -> Result<Option<TransactionReceipt>, RpcError> {
+        Err(RpcError::Rpc("eth_getTransactionReceipt not supported by this provider".to_string()))
+    }
+
+    /// Run `data` against contract `to` at the latest block without sending
+    /// it, as `from` when given, and return the hex result. A revert is an
+    /// `RpcError::Rpc`.
+    async fn call_contract(&self, _from: Option<&str>, _to: &str, _data: &str) -> Result<String, RpcError> {
+        Err(RpcError::Rpc("eth_call not supported by this provider".to_string()))
+    }
 }
 
 pub struct HttpRpcClient {
@@ -38,6 +95,19 @@ impl HttpRpcClient {
     }
 
     async fn call_rpc<T: for<'de> Deserialize<'de>>(&self, method: &str, params: serde_json::Value) -> Result<T, RpcError> {
+        self.call_rpc_nullable(method, params)
+            .await?
+            .ok_or_else(|| RpcError::Parse("Missing result".to_string()))
+    }
+
+    /// Like `call_rpc`, for methods answering `null` when there is nothing
+    /// to return
+    #[tracing::instrument(name = "rpc.call", skip(self, params), fields(chain = "evm", rpc.method = %method))]
+    async fn call_rpc_nullable<T: for<'de> Deserialize<'de>>(
+        &self,
+        method: &str,
+        params: serde_json::Value,
+    ) -> Result<Option<T>, RpcError> {
         let payload = json!({
             "jsonrpc": "2.0",
             "method": method,
@@ -59,7 +129,88 @@ impl HttpRpcClient {
             return Err(RpcError::Rpc(err.message));
         }
 
-        rpc_response.result.ok_or_else(|| RpcError::Parse("Missing result".to_string()))
+        Ok(rpc_response.result)
+    }
+}
+
+fn parse_quantity(hex: &str) -> Result<u64, RpcError> {
+    u64::from_str_radix(hex.trim_start_matches("0x"), 16)
+        .map_err(|e| RpcError::Parse(format!("Invalid quantity hex: {}", e)))
+}
+
+/// A 32-byte ABI word as an integer, saturating beyond u128
+fn parse_uint256(hex: &str) -> Result<u128, RpcError> {
+    let digits = hex.trim_start_matches("0x").trim_start_matches('0');
+    if digits.is_empty() {
+        return Ok(0);
+    }
+    if digits.len() > 32 {
+        return Ok(u128::MAX);
+    }
+    u128::from_str_radix(digits, 16).map_err(|e| RpcError::Parse(format!("Invalid uint256 hex: {}", e)))
+}
+
+/// Left-pad an address to a 32-byte log topic
+fn address_topic(address: &str) -> String {
+    format!("0x{:0>64}", address.trim_start_matches("0x").to_lowercase())
+}
+
+/// Last 20 bytes of a 32-byte topic as an address
+fn topic_address(topic: &str) -> String {
+    let hex = topic.trim_start_matches("0x");
+    format!("0x{}", &hex[hex.len().saturating_sub(40)..]).to_lowercase()
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawLog {
+    address: String,
+    topics: Vec<String>,
+    data: String,
+    transaction_hash: String,
+    log_index: String,
+    block_number: String,
+}
+
+impl RawLog {
+    fn into_transfer(self) -> Option<TransferLog> {
+        if self.topics.len() != 3 || !self.topics[0].eq_ignore_ascii_case(ERC20_TRANSFER_TOPIC) {
+            return None;
+        }
+        let data = self.data.trim_start_matches("0x").trim_start_matches('0');
+        let amount = if data.is_empty() {
+            0
+        } else {
+            u128::from_str_radix(data, 16).unwrap_or(u128::MAX)
+        };
+
+        Some(TransferLog {
+            contract: self.address.to_lowercase(),
+            from: topic_address(&self.topics[1]),
+            to: topic_address(&self.topics[2]),
+            amount,
+            tx_hash: self.transaction_hash,
+            log_index: parse_quantity(&self.log_index).ok()?,
+            block_number: parse_quantity(&self.block_number).ok()?,
+        })
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawReceipt {
+    block_number: Option<String>,
+    /// Absent before Byzantium, when failures could not be told apart
+    status: Option<String>,
+}
+
+impl RawReceipt {
+    fn into_receipt(self) -> Option<TransactionReceipt> {
+        Some(TransactionReceipt {
+            // Pending-block receipts from some nodes carry no block yet
+            block_number: parse_quantity(self.block_number.as_deref()?).ok()?,
+            success: self.status.as_deref().is_none_or(|s| parse_quantity(s).is_ok_and(|v| v == 1)),
+        })
     }
 }
 
@@ -92,10 +243,103 @@ impl BlockchainProvider for HttpRpcClient {
         self.call_rpc("eth_sendRawTransaction", json!([signed_hex])).await
     }
 
-    async fn get_balance(&self, address: &str) -> Result<f64, RpcError> {
+    async fn get_balance(&self, address: &str) -> Result<Decimal, RpcError> {
         let hex_balance: String = self.call_rpc("eth_getBalance", json!([address, "latest"])).await?;
         let wei = u128::from_str_radix(hex_balance.trim_start_matches("0x"), 16)
             .map_err(|e| RpcError::Parse(format!("Invalid balance hex: {}", e)))?;
-        Ok(wei as f64 / 1_000_000_000_000_000_000.0)
+        amount::from_minor_units(wei, amount::EVM_NATIVE_DECIMALS).map_err(|e| RpcError::Parse(e.to_string()))
+    }
+
+    async fn get_block_number(&self) -> Result<u64, RpcError> {
+        let hex_block: String = self.call_rpc("eth_blockNumber", json!([])).await?;
+        parse_quantity(&hex_block)
+    }
+
+    async fn get_transfer_logs(&self, to_address: &str, from_block: u64) -> Result<Vec<TransferLog>, RpcError> {
+        // No contract filter: transfers from unknown contracts are needed to flag spoofed tokens
+        let filter = json!({
+            "fromBlock": format!("0x{:x}", from_block),
+            "toBlock": "latest",
+            "topics": [ERC20_TRANSFER_TOPIC, null, address_topic(to_address)],
+        });
+        let logs: Vec<RawLog> = self.call_rpc("eth_getLogs", json!([filter])).await?;
+        Ok(logs.into_iter().filter_map(RawLog::into_transfer).collect())
+    }
+
+    async fn get_token_balance(&self, contract: &str, owner: &str) -> Result<u128, RpcError> {
+        let call = json!({
+            "to": contract,
+            "data": format!("0x{}{}", ERC20_BALANCE_OF, &address_topic(owner)[2..]),
+        });
+        let result: String = self.call_rpc("eth_call", json!([call, "latest"])).await?;
+        parse_uint256(&result)
+    }
+
+    async fn get_transaction_receipt(&self, tx_hash: &str) -> Result<Option<TransactionReceipt>, RpcError> {
+        let receipt: Option<RawReceipt> = self.call_rpc_nullable("eth_getTransactionReceipt", json!([tx_hash])).await?;
+        Ok(receipt.and_then(RawReceipt::into_receipt))
+    }
+
+    async fn call_contract(&self, from: Option<&str>, to: &str, data: &str) -> Result<String, RpcError> {
+        let mut call = json!({ "to": to, "data": data });
+        if let Some(from) = from {
+            call["from"] = json!(from);
+        }
+        self.call_rpc("eth_call", json!([call, "latest"])).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transfer_log_parsing() {
+        let raw: RawLog = serde_json::from_value(json!({
+            "address": "0xdAC17F958D2ee523a2206206994597C13D831ec7",
+            "topics": [
+                ERC20_TRANSFER_TOPIC,
+                "0x0000000000000000000000001111111111111111111111111111111111111111",
+                "0x000000000000000000000000742d35cc6634c0532925a3b844bc454e4438f44e"
+            ],
+            "data": "0x00000000000000000000000000000000000000000000000000000000000f4240",
+            "transactionHash": "0xabc",
+            "logIndex": "0x2",
+            "blockNumber": "0x10"
+        }))
+        .unwrap();
+
+        let log = raw.into_transfer().unwrap();
+        assert_eq!(log.contract, "0xdac17f958d2ee523a2206206994597c13d831ec7");
+        assert_eq!(log.to, "0x742d35cc6634c0532925a3b844bc454e4438f44e");
+        assert_eq!(log.amount, 1_000_000);
+        assert_eq!(log.log_index, 2);
+        assert_eq!(log.block_number, 16);
+    }
+
+    #[test]
+    fn test_receipt_parsing() {
+        let mined: RawReceipt = serde_json::from_value(json!({"blockNumber": "0x10", "status": "0x1"})).unwrap();
+        assert_eq!(mined.into_receipt(), Some(TransactionReceipt { block_number: 16, success: true }));
+
+        let reverted: RawReceipt = serde_json::from_value(json!({"blockNumber": "0x10", "status": "0x0"})).unwrap();
+        assert!(!reverted.into_receipt().unwrap().success);
+
+        let pending: RawReceipt = serde_json::from_value(json!({"blockNumber": null})).unwrap();
+        assert_eq!(pending.into_receipt(), None);
+    }
+
+    #[test]
+    fn test_parse_uint256() {
+        assert_eq!(parse_uint256("0x").unwrap(), 0);
+        assert_eq!(parse_uint256("0x00000000000000000000000000000000000000000000000000000000000f4240").unwrap(), 1_000_000);
+        assert_eq!(parse_uint256(&format!("0x{}", "f".repeat(64))).unwrap(), u128::MAX);
+        assert!(parse_uint256("0xzz").is_err());
+    }
+
+    #[test]
+    fn test_address_topic_round_trip() {
+        let address = "0x742d35Cc6634C0532925a3b844Bc454e4438f44e";
+        assert_eq!(topic_address(&address_topic(address)), address.to_lowercase());
     }
 }
@@ -0,0 +1,424 @@
+//! Transaction prerequisites of non-EVM chains. A Solana transaction must
+//! reference a recent blockhash, a Cosmos SDK transaction the signer's
+//! account number and sequence, and an XRP Ledger transaction the account
+//! sequence plus the last ledger it may land in. Each goes stale in its own
+//! way, so payout builders take them from a `ChainSequencer` rather than
+//! querying the node themselves.
+//!
+//! The sequencer caches one prerequisite per account, or one per chain for
+//! chain-wide ones. Blockhashes are shared until they near expiry; sequences are handed out in order, so concurrent
+//! payouts from one account don't collide, and re-read from the node once
+//! the entry ages out. A failed broadcast should `invalidate` the account so
+//! the next transaction starts from the node's view again.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use tokio::sync::Mutex;
+
+use super::rpc::RpcError;
+use super::solana_rpc::SolanaProvider;
+
+/// Solana blockhashes are valid for 150 slots, about a minute
+const SOLANA_BLOCKHASH_MAX_AGE: Duration = Duration::from_secs(30);
+/// Kept below the LastLedgerSequence window so a cached entry never hands
+/// out a ledger bound that has already passed
+const XRP_SEQUENCE_MAX_AGE: Duration = Duration::from_secs(30);
+const COSMOS_SEQUENCE_MAX_AGE: Duration = Duration::from_secs(60);
+/// Ledgers (3-5 seconds each) an XRP transaction may take to validate
+const XRP_LAST_LEDGER_OFFSET: u32 = 20;
+
+/// What a transaction must reference to be accepted
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TxPrerequisite {
+    /// Solana: usable until the chain passes `last_valid_block_height`
+    RecentBlockhash { blockhash: String, last_valid_block_height: u64 },
+    /// Cosmos SDK: the signer's account number and next sequence
+    CosmosAccount { account_number: u64, sequence: u64 },
+    /// XRP Ledger: the account's next sequence and the last ledger the
+    /// transaction may be validated in
+    XrpSequence { sequence: u32, last_ledger_sequence: u32 },
+}
+
+impl TxPrerequisite {
+    /// The prerequisite of the account's following transaction. Blockhashes
+    /// are shared, sequences move on by one.
+    fn next(&self) -> Self {
+        match self {
+            TxPrerequisite::RecentBlockhash { .. } => self.clone(),
+            TxPrerequisite::CosmosAccount { account_number, sequence } => {
+                TxPrerequisite::CosmosAccount { account_number: *account_number, sequence: sequence + 1 }
+            }
+            TxPrerequisite::XrpSequence { sequence, last_ledger_sequence } => {
+                TxPrerequisite::XrpSequence { sequence: sequence + 1, last_ledger_sequence: *last_ledger_sequence }
+            }
+        }
+    }
+}
+
+/// Reads a prerequisite from the chain
+#[async_trait]
+pub trait SequenceSource: Send + Sync {
+    fn chain(&self) -> &'static str;
+
+    /// How long a fetched prerequisite may be reused
+    fn max_age(&self) -> Duration;
+
+    /// Whether prerequisites differ per account. Chain-wide ones, like a
+    /// blockhash, are cached once for every account.
+    fn per_account(&self) -> bool {
+        true
+    }
+
+    async fn fetch(&self, account: &str) -> Result<TxPrerequisite, RpcError>;
+}
+
+struct CachedPrerequisite {
+    prerequisite: TxPrerequisite,
+    fetched_at: Instant,
+}
+
+/// Caches and hands out one chain's transaction prerequisites
+pub struct ChainSequencer {
+    source: Arc<dyn SequenceSource>,
+    max_age: Duration,
+    cache: Mutex<HashMap<String, CachedPrerequisite>>,
+}
+
+impl ChainSequencer {
+    pub fn new(source: Arc<dyn SequenceSource>) -> Self {
+        let max_age = source.max_age();
+        Self { source, max_age, cache: Mutex::new(HashMap::new()) }
+    }
+
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = max_age;
+        self
+    }
+
+    pub fn chain(&self) -> &'static str {
+        self.source.chain()
+    }
+
+    fn cache_key<'a>(&self, account: &'a str) -> &'a str {
+        if self.source.per_account() {
+            account
+        } else {
+            ""
+        }
+    }
+
+    /// The prerequisite for `account`'s next transaction. Callers on one
+    /// account are served one at a time so each gets its own sequence.
+    pub async fn acquire(&self, account: &str) -> Result<TxPrerequisite, String> {
+        let key = self.cache_key(account);
+        let mut cache = self.cache.lock().await;
+        let fresh = cache
+            .get(key)
+            .filter(|cached| cached.fetched_at.elapsed() < self.max_age)
+            .map(|cached| cached.prerequisite.clone());
+
+        let prerequisite = match fresh {
+            Some(prerequisite) => prerequisite,
+            None => {
+                let fetched = self.source.fetch(account).await.map_err(|e| {
+                    format!("Failed to fetch {} transaction prerequisites for {}: {}", self.chain(), account, e)
+                })?;
+                cache.insert(
+                    key.to_string(),
+                    CachedPrerequisite { prerequisite: fetched.clone(), fetched_at: Instant::now() },
+                );
+                fetched
+            }
+        };
+
+        if let Some(cached) = cache.get_mut(key) {
+            cached.prerequisite = prerequisite.next();
+        }
+        Ok(prerequisite)
+    }
+
+    /// Forget `account`'s cached prerequisite, e.g. after a rejected
+    /// broadcast, so the next `acquire` reads it from the chain
+    pub async fn invalidate(&self, account: &str) {
+        self.cache.lock().await.remove(self.cache_key(account));
+    }
+}
+
+// =============================================================================
+// SOLANA
+// =============================================================================
+
+/// Latest finalized blockhash; the account is ignored
+pub struct SolanaBlockhashSource {
+    provider: Arc<dyn SolanaProvider>,
+}
+
+impl SolanaBlockhashSource {
+    pub fn new(provider: Arc<dyn SolanaProvider>) -> Self {
+        Self { provider }
+    }
+}
+
+#[async_trait]
+impl SequenceSource for SolanaBlockhashSource {
+    fn chain(&self) -> &'static str {
+        "solana"
+    }
+
+    fn max_age(&self) -> Duration {
+        SOLANA_BLOCKHASH_MAX_AGE
+    }
+
+    fn per_account(&self) -> bool {
+        false
+    }
+
+    async fn fetch(&self, _account: &str) -> Result<TxPrerequisite, RpcError> {
+        let latest = self.provider.get_latest_blockhash().await?;
+        Ok(TxPrerequisite::RecentBlockhash {
+            blockhash: latest.blockhash,
+            last_valid_block_height: latest.last_valid_block_height,
+        })
+    }
+}
+
+// =============================================================================
+// XRP LEDGER
+// =============================================================================
+
+/// rippled JSON-RPC `account_info` against the current open ledger, so
+/// transactions of ours already queued are counted
+pub struct XrpSequenceSource {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl XrpSequenceSource {
+    pub fn new(url: String) -> Self {
+        Self {
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(10))
+                .build()
+                .unwrap_or_default(),
+            url,
+        }
+    }
+}
+
+/// Account sequence and LastLedgerSequence from an `account_info` result
+fn parse_xrp_account_info(result: &Value) -> Result<TxPrerequisite, RpcError> {
+    if result.get("status").and_then(Value::as_str) == Some("error") {
+        let error = result.get("error").and_then(Value::as_str).unwrap_or("unknown");
+        return Err(RpcError::Rpc(format!("XRPL error: {}", error)));
+    }
+    let sequence = result
+        .pointer("/account_data/Sequence")
+        .and_then(Value::as_u64)
+        .and_then(|s| u32::try_from(s).ok())
+        .ok_or_else(|| RpcError::Parse("Missing account sequence".to_string()))?;
+    let ledger = result
+        .get("ledger_current_index")
+        .and_then(Value::as_u64)
+        .and_then(|l| u32::try_from(l).ok())
+        .ok_or_else(|| RpcError::Parse("Missing current ledger index".to_string()))?;
+
+    Ok(TxPrerequisite::XrpSequence { sequence, last_ledger_sequence: ledger + XRP_LAST_LEDGER_OFFSET })
+}
+
+#[async_trait]
+impl SequenceSource for XrpSequenceSource {
+    fn chain(&self) -> &'static str {
+        "xrp"
+    }
+
+    fn max_age(&self) -> Duration {
+        XRP_SEQUENCE_MAX_AGE
+    }
+
+    async fn fetch(&self, account: &str) -> Result<TxPrerequisite, RpcError> {
+        let params = json!({ "account": account, "ledger_index": "current" });
+        let response: Value = self
+            .client
+            .post(&self.url)
+            .json(&json!({ "method": "account_info", "params": [params] }))
+            .send()
+            .await
+            .map_err(|e| RpcError::Network(e.to_string()))?
+            .json()
+            .await
+            .map_err(|e| RpcError::Parse(e.to_string()))?;
+
+        parse_xrp_account_info(response.get("result").unwrap_or(&Value::Null))
+    }
+}
+
+// =============================================================================
+// COSMOS SDK
+// =============================================================================
+
+/// Cosmos SDK REST `/cosmos/auth/v1beta1/accounts/{address}`
+pub struct CosmosSequenceSource {
+    client: reqwest::Client,
+    rest_url: String,
+}
+
+impl CosmosSequenceSource {
+    pub fn new(rest_url: String) -> Self {
+        Self {
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(10))
+                .build()
+                .unwrap_or_default(),
+            rest_url: rest_url.trim_end_matches('/').to_string(),
+        }
+    }
+}
+
+/// Account number and sequence of a base or vesting account. Both are
+/// encoded as strings.
+fn parse_cosmos_account(response: &Value) -> Result<TxPrerequisite, RpcError> {
+    let account = response
+        .get("account")
+        .ok_or_else(|| RpcError::Parse("Missing account".to_string()))?;
+    let base = account
+        .pointer("/base_vesting_account/base_account")
+        .or_else(|| account.get("base_account"))
+        .unwrap_or(account);
+
+    let number = |field: &str| -> Result<u64, RpcError> {
+        let value = base.get(field).ok_or_else(|| RpcError::Parse(format!("Missing {}", field)))?;
+        value
+            .as_str()
+            .and_then(|s| s.parse().ok())
+            .or_else(|| value.as_u64())
+            .ok_or_else(|| RpcError::Parse(format!("Invalid {}: {}", field, value)))
+    };
+
+    Ok(TxPrerequisite::CosmosAccount { account_number: number("account_number")?, sequence: number("sequence")? })
+}
+
+#[async_trait]
+impl SequenceSource for CosmosSequenceSource {
+    fn chain(&self) -> &'static str {
+        "cosmos"
+    }
+
+    fn max_age(&self) -> Duration {
+        COSMOS_SEQUENCE_MAX_AGE
+    }
+
+    async fn fetch(&self, account: &str) -> Result<TxPrerequisite, RpcError> {
+        let response = self
+            .client
+            .get(format!("{}/cosmos/auth/v1beta1/accounts/{}", self.rest_url, account))
+            .send()
+            .await
+            .map_err(|e| RpcError::Network(e.to_string()))?;
+        if !response.status().is_success() {
+            return Err(RpcError::Rpc(format!("Account query returned {}", response.status())));
+        }
+        let body: Value = response.json().await.map_err(|e| RpcError::Parse(e.to_string()))?;
+        parse_cosmos_account(&body)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    /// Counts fetches and returns sequence 100 + fetch number
+    struct CountingSource {
+        fetches: AtomicU64,
+    }
+
+    #[async_trait]
+    impl SequenceSource for CountingSource {
+        fn chain(&self) -> &'static str {
+            "test"
+        }
+
+        fn max_age(&self) -> Duration {
+            Duration::from_secs(60)
+        }
+
+        async fn fetch(&self, _account: &str) -> Result<TxPrerequisite, RpcError> {
+            let n = self.fetches.fetch_add(1, Ordering::SeqCst);
+            Ok(TxPrerequisite::CosmosAccount { account_number: 7, sequence: 100 + n * 10 })
+        }
+    }
+
+    fn sequence(prerequisite: TxPrerequisite) -> u64 {
+        match prerequisite {
+            TxPrerequisite::CosmosAccount { sequence, .. } => sequence,
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sequences_are_handed_out_in_order_until_invalidated() {
+        let source = Arc::new(CountingSource { fetches: AtomicU64::new(0) });
+        let sequencer = ChainSequencer::new(source.clone());
+
+        assert_eq!(sequence(sequencer.acquire("a").await.unwrap()), 100);
+        assert_eq!(sequence(sequencer.acquire("a").await.unwrap()), 101);
+        // Each account has its own entry
+        assert_eq!(sequence(sequencer.acquire("b").await.unwrap()), 110);
+        assert_eq!(source.fetches.load(Ordering::SeqCst), 2);
+
+        sequencer.invalidate("a").await;
+        assert_eq!(sequence(sequencer.acquire("a").await.unwrap()), 120);
+    }
+
+    #[tokio::test]
+    async fn test_stale_entries_are_refetched() {
+        let source = Arc::new(CountingSource { fetches: AtomicU64::new(0) });
+        let sequencer = ChainSequencer::new(source.clone()).with_max_age(Duration::ZERO);
+
+        sequencer.acquire("a").await.unwrap();
+        assert_eq!(sequence(sequencer.acquire("a").await.unwrap()), 110);
+        assert_eq!(source.fetches.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_blockhash_is_shared() {
+        let blockhash = TxPrerequisite::RecentBlockhash { blockhash: "abc".to_string(), last_valid_block_height: 9 };
+        assert_eq!(blockhash.next(), blockhash);
+    }
+
+    #[test]
+    fn test_parse_xrp_account_info() {
+        let result = json!({
+            "account_data": { "Account": "rTest", "Sequence": 42 },
+            "ledger_current_index": 1000,
+            "status": "success"
+        });
+        assert_eq!(
+            parse_xrp_account_info(&result).unwrap(),
+            TxPrerequisite::XrpSequence { sequence: 42, last_ledger_sequence: 1020 }
+        );
+        assert!(parse_xrp_account_info(&json!({ "status": "error", "error": "actNotFound" })).is_err());
+    }
+
+    #[test]
+    fn test_parse_cosmos_base_and_vesting_accounts() {
+        let base = json!({ "account": { "@type": "/cosmos.auth.v1beta1.BaseAccount", "account_number": "12", "sequence": "3" } });
+        assert_eq!(
+            parse_cosmos_account(&base).unwrap(),
+            TxPrerequisite::CosmosAccount { account_number: 12, sequence: 3 }
+        );
+
+        let vesting = json!({
+            "account": { "base_vesting_account": { "base_account": { "account_number": "5", "sequence": "0" } } }
+        });
+        assert_eq!(
+            parse_cosmos_account(&vesting).unwrap(),
+            TxPrerequisite::CosmosAccount { account_number: 5, sequence: 0 }
+        );
+        assert!(parse_cosmos_account(&json!({})).is_err());
+    }
+}
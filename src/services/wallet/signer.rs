@@ -0,0 +1,364 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+use crate::modules::wallet::schema::EvmTransaction;
+use super::derivation;
+use super::signing::SigningService;
+
+/// Holds or reaches the keys behind our HD wallet. Keys are addressed by
+/// derivation index only, so an implementation never has to hand private
+/// key material back to the API process.
+///
+/// Signatures use the same encodings as the `SigningService` primitives:
+/// EVM `0x{r}{s}{v}`, Solana `0x{ed25519}`, Bitcoin DER hex; signed messages
+/// are EIP-191 or EIP-712 `0x{r}{s}{v}` and base64 Bitcoin message
+/// signatures.
+///
+/// Backends: `LocalHdSigner` (in-process, default) and `RemoteSignerClient`
+/// (JSON-RPC signer service). An HSM backend only needs to implement this
+/// trait.
+#[async_trait]
+pub trait Signer: Send + Sync {
+    /// Backend name for logs
+    fn name(&self) -> &'static str;
+
+    async fn sign_evm_transaction(&self, index: u32, tx: &EvmTransaction) -> Result<String, String>;
+
+    async fn sign_solana_message(&self, index: u32, message: &[u8]) -> Result<String, String>;
+
+    async fn sign_btc_sighash(&self, index: u32, sighash: &[u8]) -> Result<String, String>;
+
+    /// EIP-191 `personal_sign` over `message`
+    async fn sign_evm_message(&self, index: u32, message: &[u8]) -> Result<String, String>;
+
+    /// EIP-712 signature over a struct, given the hash of its domain and of
+    /// the struct itself
+    async fn sign_evm_typed_data(
+        &self,
+        index: u32,
+        domain_separator: &[u8; 32],
+        struct_hash: &[u8; 32],
+    ) -> Result<String, String>;
+
+    /// Bitcoin `signmessage` over `message`, for the P2PKH address at `index`
+    async fn sign_btc_message(&self, index: u32, message: &[u8]) -> Result<String, String>;
+}
+
+// =============================================================================
+// LOCAL HD SIGNER
+// =============================================================================
+
+/// Derives keys from the master mnemonic inside this process
+pub struct LocalHdSigner {
+    master_seed: String,
+}
+
+impl LocalHdSigner {
+    pub fn new(master_seed: String) -> Self {
+        Self { master_seed }
+    }
+}
+
+#[async_trait]
+impl Signer for LocalHdSigner {
+    fn name(&self) -> &'static str {
+        "local"
+    }
+
+    async fn sign_evm_transaction(&self, index: u32, tx: &EvmTransaction) -> Result<String, String> {
+        let private_key = derivation::derive_evm_key_at(&self.master_seed, index).await?;
+        SigningService::sign_evm_transaction(&private_key, tx)
+    }
+
+    async fn sign_solana_message(&self, index: u32, message: &[u8]) -> Result<String, String> {
+        let private_key = derivation::derive_solana_key(&self.master_seed, index).await?;
+        SigningService::sign_solana_transaction(&hex::encode(private_key), &hex::encode(message))
+    }
+
+    async fn sign_btc_sighash(&self, index: u32, sighash: &[u8]) -> Result<String, String> {
+        let private_key = derivation::derive_btc_key(&self.master_seed, index).await?;
+        SigningService::sign_btc_transaction(&private_key, &hex::encode(sighash))
+    }
+
+    async fn sign_evm_message(&self, index: u32, message: &[u8]) -> Result<String, String> {
+        let private_key = derivation::derive_evm_key_at(&self.master_seed, index).await?;
+        SigningService::sign_evm_personal_message(&private_key, message)
+    }
+
+    async fn sign_evm_typed_data(
+        &self,
+        index: u32,
+        domain_separator: &[u8; 32],
+        struct_hash: &[u8; 32],
+    ) -> Result<String, String> {
+        let private_key = derivation::derive_evm_key_at(&self.master_seed, index).await?;
+        SigningService::sign_evm_typed_data_hash(&private_key, domain_separator, struct_hash)
+    }
+
+    async fn sign_btc_message(&self, index: u32, message: &[u8]) -> Result<String, String> {
+        let private_key = derivation::derive_btc_key(&self.master_seed, index).await?;
+        SigningService::sign_btc_signed_message(&private_key, message)
+    }
+}
+
+// =============================================================================
+// REMOTE SIGNER
+// =============================================================================
+
+#[derive(Debug, Deserialize)]
+struct SignerRpcResponse {
+    result: Option<SignerRpcResult>,
+    error: Option<SignerRpcError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SignerRpcResult {
+    signature: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct SignerRpcError {
+    message: String,
+}
+
+/// Client for an external signing service speaking JSON-RPC 2.0.
+///
+/// Methods and params:
+/// - `sign_evm_transaction`: `{index, tx}`
+/// - `sign_solana_message`: `{index, message}` (hex)
+/// - `sign_btc_sighash`: `{index, sighash}` (hex)
+/// - `sign_evm_message`: `{index, message}` (hex)
+/// - `sign_evm_typed_data`: `{index, domain_separator, struct_hash}` (hex)
+/// - `sign_btc_message`: `{index, message}` (hex)
+///
+/// Every call returns `{"signature": "..."}` in the encoding documented on
+/// `Signer`.
+pub struct RemoteSignerClient {
+    client: reqwest::Client,
+    url: String,
+    auth_token: Option<String>,
+}
+
+impl RemoteSignerClient {
+    pub fn new(url: String) -> Self {
+        Self {
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(10))
+                .build()
+                .unwrap_or_default(),
+            url,
+            auth_token: None,
+        }
+    }
+
+    pub fn with_auth_token(mut self, token: String) -> Self {
+        self.auth_token = Some(token);
+        self
+    }
+
+    async fn call(&self, method: &str, params: serde_json::Value) -> Result<String, String> {
+        let payload = json!({
+            "jsonrpc": "2.0",
+            "method": method,
+            "params": params,
+            "id": 1
+        });
+
+        let mut request = self.client.post(&self.url).json(&payload);
+        if let Some(token) = &self.auth_token {
+            request = request.bearer_auth(token);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| format!("Remote signer unreachable: {}", e))?;
+
+        if !response.status().is_success() {
+            return Err(format!("Remote signer returned HTTP {}", response.status()));
+        }
+
+        let rpc_response: SignerRpcResponse = response
+            .json()
+            .await
+            .map_err(|e| format!("Invalid remote signer response: {}", e))?;
+
+        if let Some(err) = rpc_response.error {
+            return Err(format!("Remote signer refused {}: {}", method, err.message));
+        }
+
+        rpc_response
+            .result
+            .map(|r| r.signature)
+            .ok_or_else(|| "Remote signer returned no signature".to_string())
+    }
+}
+
+#[async_trait]
+impl Signer for RemoteSignerClient {
+    fn name(&self) -> &'static str {
+        "remote"
+    }
+
+    async fn sign_evm_transaction(&self, index: u32, tx: &EvmTransaction) -> Result<String, String> {
+        self.call("sign_evm_transaction", json!({ "index": index, "tx": tx })).await
+    }
+
+    async fn sign_solana_message(&self, index: u32, message: &[u8]) -> Result<String, String> {
+        self.call("sign_solana_message", json!({ "index": index, "message": hex::encode(message) })).await
+    }
+
+    async fn sign_btc_sighash(&self, index: u32, sighash: &[u8]) -> Result<String, String> {
+        self.call("sign_btc_sighash", json!({ "index": index, "sighash": hex::encode(sighash) })).await
+    }
+
+    async fn sign_evm_message(&self, index: u32, message: &[u8]) -> Result<String, String> {
+        self.call("sign_evm_message", json!({ "index": index, "message": hex::encode(message) })).await
+    }
+
+    async fn sign_evm_typed_data(
+        &self,
+        index: u32,
+        domain_separator: &[u8; 32],
+        struct_hash: &[u8; 32],
+    ) -> Result<String, String> {
+        let params = json!({
+            "index": index,
+            "domain_separator": hex::encode(domain_separator),
+            "struct_hash": hex::encode(struct_hash),
+        });
+        self.call("sign_evm_typed_data", params).await
+    }
+
+    async fn sign_btc_message(&self, index: u32, message: &[u8]) -> Result<String, String> {
+        self.call("sign_btc_message", json!({ "index": index, "message": hex::encode(message) })).await
+    }
+}
+
+// =============================================================================
+// CONFIGURATION
+// =============================================================================
+
+/// Signer selected by the environment:
+///
+/// - SIGNER_BACKEND: `local` (default) or `remote`
+/// - REMOTE_SIGNER_URL: JSON-RPC endpoint, required for `remote`
+/// - REMOTE_SIGNER_TOKEN: optional bearer token sent to the signer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignerBackend {
+    Local,
+    Remote { url: String, auth_token: Option<String> },
+}
+
+impl SignerBackend {
+    pub fn from_env() -> Result<Self, String> {
+        let backend = std::env::var("SIGNER_BACKEND").unwrap_or_else(|_| "local".to_string());
+        match backend.trim().to_lowercase().as_str() {
+            "" | "local" => Ok(Self::Local),
+            "remote" => {
+                let url = std::env::var("REMOTE_SIGNER_URL")
+                    .ok()
+                    .filter(|u| !u.trim().is_empty())
+                    .ok_or_else(|| "REMOTE_SIGNER_URL must be set when SIGNER_BACKEND=remote".to_string())?;
+                let auth_token = std::env::var("REMOTE_SIGNER_TOKEN")
+                    .ok()
+                    .filter(|t| !t.trim().is_empty());
+                Ok(Self::Remote { url, auth_token })
+            }
+            other => Err(format!("Unknown SIGNER_BACKEND '{}'", other)),
+        }
+    }
+
+    /// Process-wide backend, read from the environment once. A broken
+    /// remote configuration falls back to local signing with an error log
+    /// rather than taking payouts down.
+    pub fn configured() -> &'static SignerBackend {
+        static BACKEND: OnceLock<SignerBackend> = OnceLock::new();
+        BACKEND.get_or_init(|| {
+            Self::from_env().unwrap_or_else(|e| {
+                tracing::error!("Invalid signer configuration, using local signer: {}", e);
+                Self::Local
+            })
+        })
+    }
+
+    pub fn build(&self, master_seed: &str) -> Arc<dyn Signer> {
+        match self {
+            Self::Local => Arc::new(LocalHdSigner::new(master_seed.to_string())),
+            Self::Remote { url, auth_token } => {
+                let client = RemoteSignerClient::new(url.clone());
+                Arc::new(match auth_token {
+                    Some(token) => client.with_auth_token(token.clone()),
+                    None => client,
+                })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::amount::Decimal;
+
+    const TEST_MNEMONIC: &str =
+        "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+    fn sample_tx() -> EvmTransaction {
+        EvmTransaction {
+            to_address: "0x742d35Cc6634C0532925a3b844Bc454e4438f44e".to_string(),
+            amount: Decimal::new(5, 1),
+            token: "ETH".to_string(),
+            chain_id: 1,
+            nonce: 7,
+            gas_price: 20_000_000_000,
+            gas_limit: 21000,
+            data: String::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_local_signer_matches_static_signing() {
+        let signer = LocalHdSigner::new(TEST_MNEMONIC.to_string());
+        let key = derivation::derive_evm_key_at(TEST_MNEMONIC, 3).await.unwrap();
+
+        let expected = SigningService::sign_evm_transaction(&key, &sample_tx()).unwrap();
+        assert_eq!(signer.sign_evm_transaction(3, &sample_tx()).await.unwrap(), expected);
+    }
+
+    #[tokio::test]
+    async fn test_local_signer_uses_requested_index() {
+        let signer = LocalHdSigner::new(TEST_MNEMONIC.to_string());
+        let a = signer.sign_evm_transaction(0, &sample_tx()).await.unwrap();
+        let b = signer.sign_evm_transaction(1, &sample_tx()).await.unwrap();
+        assert_ne!(a, b);
+
+        let index_zero = derivation::derive_evm_key(TEST_MNEMONIC).await.unwrap();
+        assert_eq!(a, SigningService::sign_evm_transaction(&index_zero, &sample_tx()).unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_local_solana_signature_verifies() {
+        use ed25519_dalek::{Signature, SigningKey, Verifier};
+
+        let signer = LocalHdSigner::new(TEST_MNEMONIC.to_string());
+        let signature = signer.sign_solana_message(2, b"message").await.unwrap();
+
+        let seed = derivation::derive_solana_key(TEST_MNEMONIC, 2).await.unwrap();
+        let key = SigningKey::from_bytes(seed.as_slice().try_into().unwrap());
+        let bytes = hex::decode(signature.trim_start_matches("0x")).unwrap();
+        let signature = Signature::from_slice(&bytes).unwrap();
+        assert!(key.verifying_key().verify(b"message", &signature).is_ok());
+    }
+
+    #[test]
+    fn test_backend_build_names() {
+        assert_eq!(SignerBackend::Local.build(TEST_MNEMONIC).name(), "local");
+
+        let remote = SignerBackend::Remote { url: "http://signer:8545".to_string(), auth_token: None };
+        assert_eq!(remote.build(TEST_MNEMONIC).name(), "remote");
+    }
+}
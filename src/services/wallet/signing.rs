@@ -1,14 +1,70 @@
 use std::str::FromStr;
+use std::sync::Arc;
 use secp256k1::{Secp256k1, SecretKey, Message};
 use ed25519_dalek::{SigningKey, Signer};
+use sha2::Sha256;
 use sha3::{Keccak256, Digest};
 use hex;
+use base64::Engine;
 
 use crate::modules::wallet::schema::EvmTransaction;
+use crate::services::amount::{self, Decimal};
+use super::signer::{Signer as KeySigner, SignerBackend};
 
-pub struct SigningService;
+/// Signs payouts through a `Signer` backend, so callers only pass the HD
+/// index of the paying address and never see key material.
+///
+/// The associated `sign_*_transaction(private_key_hex, ..)` functions are
+/// the raw signing primitives used by `LocalHdSigner`.
+#[derive(Clone)]
+pub struct SigningService {
+    signer: Arc<dyn KeySigner>,
+}
 
 impl SigningService {
+    pub fn new(signer: Arc<dyn KeySigner>) -> Self {
+        Self { signer }
+    }
+
+    /// Backend chosen by SIGNER_BACKEND, deriving locally from `master_seed`
+    /// unless a remote signer is configured
+    pub fn from_config(master_seed: &str) -> Self {
+        Self::new(SignerBackend::configured().build(master_seed))
+    }
+
+    pub fn backend(&self) -> &'static str {
+        self.signer.name()
+    }
+
+    pub async fn sign_evm(&self, index: u32, tx: &EvmTransaction) -> Result<String, String> {
+        self.signer.sign_evm_transaction(index, tx).await
+    }
+
+    pub async fn sign_solana(&self, index: u32, message: &[u8]) -> Result<String, String> {
+        self.signer.sign_solana_message(index, message).await
+    }
+
+    pub async fn sign_btc(&self, index: u32, sighash: &[u8]) -> Result<String, String> {
+        self.signer.sign_btc_sighash(index, sighash).await
+    }
+
+    pub async fn sign_evm_message(&self, index: u32, message: &[u8]) -> Result<String, String> {
+        self.signer.sign_evm_message(index, message).await
+    }
+
+    pub async fn sign_btc_message(&self, index: u32, message: &[u8]) -> Result<String, String> {
+        self.signer.sign_btc_message(index, message).await
+    }
+
+    pub async fn sign_evm_typed_data(
+        &self,
+        index: u32,
+        domain_separator: &[u8; 32],
+        struct_hash: &[u8; 32],
+    ) -> Result<String, String> {
+        self.signer.sign_evm_typed_data(index, domain_separator, struct_hash).await
+    }
+
     /// Sign an EVM transaction (Ethereum, Polygon, Arbitrum, etc.)
     /// Implements EIP-155 signing with RLP encoding
     pub fn sign_evm_transaction(
@@ -26,10 +82,10 @@ impl SigningService {
         let mut rlp_fields: Vec<Vec<u8>> = Vec::new();
         rlp_fields.push(encode_u64(tx.nonce));
         rlp_fields.push(encode_u64(tx.gas_price));
-        rlp_fields.push(encode_u64(21000)); // Default gas limit for transfer
+        rlp_fields.push(encode_u64(tx.gas_limit));
         rlp_fields.push(hex::decode(tx.to_address.trim_start_matches("0x")).map_err(|e| e.to_string())?);
-        rlp_fields.push(encode_f64_to_wei(tx.amount));
-        rlp_fields.push(Vec::new()); // Empty data
+        rlp_fields.push(encode_wei(tx.amount)?);
+        rlp_fields.push(hex::decode(tx.data.trim_start_matches("0x")).map_err(|e| format!("Invalid call data: {}", e))?);
         rlp_fields.push(encode_u64(tx.chain_id as u64));
         rlp_fields.push(Vec::new()); // r = 0 for signing hash
         rlp_fields.push(Vec::new()); // s = 0 for signing hash
@@ -88,6 +144,148 @@ impl SigningService {
         
         Ok(hex::encode(sig.serialize_der()))
     }
+
+    /// Sign a message the way `personal_sign` does (EIP-191), as
+    /// `0x{r}{s}{v}` with v = 27 or 28
+    pub fn sign_evm_personal_message(private_key_hex: &str, message: &[u8]) -> Result<String, String> {
+        let secp = Secp256k1::new();
+        let secret_key = SecretKey::from_str(private_key_hex.trim_start_matches("0x"))
+            .map_err(|e| format!("Invalid private key: {}", e))?;
+
+        let digest = Message::from_digest(evm_message_hash(message));
+        let (rec_id, sig_bytes) = secp.sign_ecdsa_recoverable(&digest, &secret_key).serialize_compact();
+
+        Ok(format!("0x{}{:02x}", hex::encode(sig_bytes), 27 + rec_id.to_i32() as u8))
+    }
+
+    /// Sign EIP-712 typed data the way `eth_signTypedData_v4` does, as
+    /// `0x{r}{s}{v}` with v = 27 or 28
+    pub fn sign_evm_typed_data_hash(
+        private_key_hex: &str,
+        domain_separator: &[u8; 32],
+        struct_hash: &[u8; 32],
+    ) -> Result<String, String> {
+        let secp = Secp256k1::new();
+        let secret_key = SecretKey::from_str(private_key_hex.trim_start_matches("0x"))
+            .map_err(|e| format!("Invalid private key: {}", e))?;
+
+        let digest = Message::from_digest(eip712_digest(domain_separator, struct_hash));
+        let (rec_id, sig_bytes) = secp.sign_ecdsa_recoverable(&digest, &secret_key).serialize_compact();
+
+        Ok(format!("0x{}{:02x}", hex::encode(sig_bytes), 27 + rec_id.to_i32() as u8))
+    }
+
+    /// Sign a message the way Bitcoin Core's `signmessage` does, as base64
+    /// of the 65-byte compact signature (BIP-137 header for a compressed
+    /// P2PKH key)
+    pub fn sign_btc_signed_message(private_key_hex: &str, message: &[u8]) -> Result<String, String> {
+        let secp = Secp256k1::new();
+        let secret_key = SecretKey::from_str(private_key_hex.trim_start_matches("0x"))
+            .map_err(|e| format!("Invalid private key: {}", e))?;
+
+        let digest = Message::from_digest(btc_message_hash(message));
+        let (rec_id, sig_bytes) = secp.sign_ecdsa_recoverable(&digest, &secret_key).serialize_compact();
+
+        let mut signature = Vec::with_capacity(65);
+        signature.push(31 + rec_id.to_i32() as u8);
+        signature.extend_from_slice(&sig_bytes);
+        Ok(base64::engine::general_purpose::STANDARD.encode(signature))
+    }
+}
+
+/// Digest `personal_sign` signs: Keccak-256 of the EIP-191 prefix and the
+/// message
+pub fn evm_message_hash(message: &[u8]) -> [u8; 32] {
+    let mut hasher = Keccak256::new();
+    hasher.update(format!("\x19Ethereum Signed Message:\n{}", message.len()).as_bytes());
+    hasher.update(message);
+    hasher.finalize().into()
+}
+
+/// Digest EIP-712 signatures sign: Keccak-256 of `0x1901`, the domain
+/// separator and the struct hash
+pub fn eip712_digest(domain_separator: &[u8; 32], struct_hash: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Keccak256::new();
+    hasher.update([0x19, 0x01]);
+    hasher.update(domain_separator);
+    hasher.update(struct_hash);
+    hasher.finalize().into()
+}
+
+/// Digest Bitcoin message signatures sign: double SHA-256 of the
+/// length-prefixed magic string and message
+pub fn btc_message_hash(message: &[u8]) -> [u8; 32] {
+    const MAGIC: &[u8] = b"Bitcoin Signed Message:\n";
+    let mut data = Vec::with_capacity(MAGIC.len() + message.len() + 10);
+    push_compact_size(&mut data, MAGIC.len());
+    data.extend_from_slice(MAGIC);
+    push_compact_size(&mut data, message.len());
+    data.extend_from_slice(message);
+    Sha256::digest(Sha256::digest(&data)).into()
+}
+
+/// Bitcoin's variable-length integer encoding
+fn push_compact_size(out: &mut Vec<u8>, n: usize) {
+    match n {
+        0..=0xfc => out.push(n as u8),
+        0xfd..=0xffff => {
+            out.push(0xfd);
+            out.extend_from_slice(&(n as u16).to_le_bytes());
+        }
+        _ => {
+            out.push(0xfe);
+            out.extend_from_slice(&(n as u32).to_le_bytes());
+        }
+    }
+}
+
+/// Call data for ERC-20 `transfer(recipient, amount)`, as hex
+pub fn erc20_transfer_data(recipient: &str, amount: u128) -> Result<String, String> {
+    let recipient = recipient.trim_start_matches("0x");
+    if recipient.len() != 40 || hex::decode(recipient).is_err() {
+        return Err(format!("Invalid EVM address: 0x{}", recipient));
+    }
+    // transfer(address,uint256) = 0xa9059cbb
+    Ok(format!("0xa9059cbb{:0>64}{:064x}", recipient.to_lowercase(), amount))
+}
+
+/// Call data for ERC-20 `transferFrom(owner, recipient, amount)`, as hex
+pub fn erc20_transfer_from_data(owner: &str, recipient: &str, amount: u128) -> Result<String, String> {
+    let mut words = String::new();
+    for address in [owner, recipient] {
+        let address = address.trim_start_matches("0x");
+        if address.len() != 40 || hex::decode(address).is_err() {
+            return Err(format!("Invalid EVM address: 0x{}", address));
+        }
+        words.push_str(&format!("{:0>64}", address.to_lowercase()));
+    }
+    // transferFrom(address,address,uint256) = 0x23b872dd
+    Ok(format!("0x23b872dd{}{:064x}", words, amount))
+}
+
+/// Call data for `disperseEther(recipients, values)` on a Disperse-style
+/// contract, which forwards each value (in wei) to its recipient, as hex
+pub fn disperse_ether_data(payments: &[(&str, u128)]) -> Result<String, String> {
+    let count = payments.len();
+    // Two dynamic arrays: their offsets, then each as length + elements
+    let mut data = String::from("0xe63d38ed");
+    data.push_str(&format!("{:064x}", 0x40));
+    data.push_str(&format!("{:064x}", 0x40 + 0x20 * (count + 1)));
+
+    data.push_str(&format!("{:064x}", count));
+    for (recipient, _) in payments {
+        let recipient = recipient.trim_start_matches("0x");
+        if recipient.len() != 40 || hex::decode(recipient).is_err() {
+            return Err(format!("Invalid EVM address: 0x{}", recipient));
+        }
+        data.push_str(&format!("{:0>64}", recipient.to_lowercase()));
+    }
+
+    data.push_str(&format!("{:064x}", count));
+    for (_, value) in payments {
+        data.push_str(&format!("{:064x}", value));
+    }
+    Ok(data)
 }
 
 // =============================================================================
@@ -103,12 +301,12 @@ fn encode_u64(val: u64) -> Vec<u8> {
     bytes[start..].to_vec()
 }
 
-fn encode_f64_to_wei(amount: f64) -> Vec<u8> {
+fn encode_wei(amount: Decimal) -> Result<Vec<u8>, String> {
     // 1 ETH = 10^18 Wei
-    let wei = (amount * 1_000_000_000_000_000_000.0) as u128;
+    let wei = amount::to_minor_units(amount, amount::EVM_NATIVE_DECIMALS).map_err(|e| e.to_string())?;
     let bytes = wei.to_be_bytes();
     let start = bytes.iter().position(|&b| b != 0).unwrap_or(16);
-    bytes[start..].to_vec()
+    Ok(bytes[start..].to_vec())
 }
 
 fn encode_list(elements: &[Vec<u8>]) -> Vec<u8> {
@@ -0,0 +1,277 @@
+//! Solana priority fees. A transfer paying only the base fee is the first
+//! to be dropped when leaders are congested, so payouts carry compute
+//! budget instructions: a tight compute unit limit and a price per unit
+//! estimated from what recent transactions touching the same accounts
+//! paid, kept between configured caps.
+//!
+//! After broadcast a payout is followed until it lands or its blockhash
+//! expires, and the outcome is counted so dropped transactions show up on
+//! dashboards.
+
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+use solana_sdk::compute_budget::ComputeBudgetInstruction;
+use solana_sdk::instruction::Instruction;
+
+use super::solana_rpc::{SolanaProvider, SolanaSignatureStatus};
+use crate::services::metrics::MetricsRegistry;
+
+/// Lamports per signature, charged on top of any priority fee
+pub const BASE_FEE_LAMPORTS: u64 = 5000;
+
+/// A system transfer uses 150 compute units and each compute budget
+/// instruction another 150; the rest is headroom
+const DEFAULT_COMPUTE_UNIT_LIMIT: u32 = 1_000;
+const DEFAULT_FEE_PERCENTILE: u8 = 75;
+/// 1 lamport per compute unit, i.e. 1000 lamports at the default limit
+const DEFAULT_MAX_MICRO_LAMPORTS: u64 = 1_000_000;
+
+const LANDING_POLL_INTERVAL: Duration = Duration::from_secs(2);
+/// Blockhashes expire after about a minute; past this the outcome is
+/// counted as dropped even if the expiry height could not be read
+const LANDING_MAX_WAIT: Duration = Duration::from_secs(120);
+
+/// Compute budget of one transaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ComputeBudget {
+    pub unit_limit: u32,
+    /// Priority fee per compute unit; 0 pays the base fee only
+    pub unit_price_micro_lamports: u64,
+}
+
+impl ComputeBudget {
+    /// Instructions to put ahead of the transaction's own
+    pub fn instructions(&self) -> Vec<Instruction> {
+        let mut instructions = vec![ComputeBudgetInstruction::set_compute_unit_limit(self.unit_limit)];
+        if self.unit_price_micro_lamports > 0 {
+            instructions.push(ComputeBudgetInstruction::set_compute_unit_price(self.unit_price_micro_lamports));
+        }
+        instructions
+    }
+
+    /// Priority fee in lamports, rounded up as the runtime does
+    pub fn priority_fee_lamports(&self) -> u64 {
+        let micro_lamports = self.unit_price_micro_lamports as u128 * self.unit_limit as u128;
+        micro_lamports.div_ceil(1_000_000).min(u64::MAX as u128) as u64
+    }
+
+    /// Base plus priority fee of a single-signature transaction
+    pub fn total_fee_lamports(&self) -> u64 {
+        BASE_FEE_LAMPORTS.saturating_add(self.priority_fee_lamports())
+    }
+}
+
+/// Priority fee settings, from SOLANA_* settings
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PriorityFeeConfig {
+    pub compute_unit_limit: u32,
+    /// Percentile of recent fees to pay, 0-100
+    pub percentile: u8,
+    pub min_micro_lamports: u64,
+    /// 0 turns priority fees off
+    pub max_micro_lamports: u64,
+}
+
+impl Default for PriorityFeeConfig {
+    fn default() -> Self {
+        Self {
+            compute_unit_limit: DEFAULT_COMPUTE_UNIT_LIMIT,
+            percentile: DEFAULT_FEE_PERCENTILE,
+            min_micro_lamports: 0,
+            max_micro_lamports: DEFAULT_MAX_MICRO_LAMPORTS,
+        }
+    }
+}
+
+impl PriorityFeeConfig {
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let var = |name: &str| std::env::var(name).ok().and_then(|v| v.trim().parse::<u64>().ok());
+        let max_micro_lamports = var("SOLANA_PRIORITY_FEE_MAX_MICROLAMPORTS").unwrap_or(defaults.max_micro_lamports);
+
+        Self {
+            compute_unit_limit: var("SOLANA_COMPUTE_UNIT_LIMIT")
+                .and_then(|v| u32::try_from(v).ok())
+                .filter(|v| *v > 0)
+                .unwrap_or(defaults.compute_unit_limit),
+            percentile: var("SOLANA_PRIORITY_FEE_PERCENTILE")
+                .filter(|v| *v <= 100)
+                .map(|v| v as u8)
+                .unwrap_or(defaults.percentile),
+            min_micro_lamports: var("SOLANA_PRIORITY_FEE_MIN_MICROLAMPORTS")
+                .unwrap_or(defaults.min_micro_lamports)
+                .min(max_micro_lamports),
+            max_micro_lamports,
+        }
+    }
+
+    /// Settings read from the environment once per process
+    pub fn global() -> &'static PriorityFeeConfig {
+        static CONFIG: OnceLock<PriorityFeeConfig> = OnceLock::new();
+        CONFIG.get_or_init(Self::from_env)
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.max_micro_lamports > 0
+    }
+
+    /// Price per compute unit to pay given recent fees: the configured
+    /// percentile of the samples, within the caps
+    pub fn unit_price(&self, recent_fees: &[u64]) -> u64 {
+        if !self.enabled() {
+            return 0;
+        }
+        let mut fees = recent_fees.to_vec();
+        fees.sort_unstable();
+        let estimate = match fees.len() {
+            0 => 0,
+            n => fees[((n - 1) * self.percentile as usize).div_ceil(100)],
+        };
+        estimate.clamp(self.min_micro_lamports, self.max_micro_lamports)
+    }
+}
+
+/// Compute budgets for payouts, priced from recent fees
+pub struct PriorityFeeEstimator {
+    provider: Arc<dyn SolanaProvider>,
+    config: PriorityFeeConfig,
+}
+
+impl PriorityFeeEstimator {
+    pub fn new(provider: Arc<dyn SolanaProvider>) -> Self {
+        Self { provider, config: PriorityFeeConfig::global().clone() }
+    }
+
+    pub fn with_config(mut self, config: PriorityFeeConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Budget for a transaction writing to `accounts`. Without fee data
+    /// the cap is paid, since failing to land costs more than the fee.
+    pub async fn budget(&self, accounts: &[String]) -> ComputeBudget {
+        let unit_price = if !self.config.enabled() {
+            0
+        } else {
+            match self.provider.get_recent_prioritization_fees(accounts).await {
+                Ok(fees) => self.config.unit_price(&fees),
+                Err(e) => {
+                    tracing::warn!("Solana priority fee lookup failed, paying the cap: {}", e);
+                    self.config.max_micro_lamports
+                }
+            }
+        };
+
+        if let Some(metrics) = MetricsRegistry::global() {
+            metrics.solana_priority_fee_micro_lamports.with_label_values(&[]).observe(unit_price as f64);
+        }
+
+        ComputeBudget { unit_limit: self.config.compute_unit_limit, unit_price_micro_lamports: unit_price }
+    }
+}
+
+// =============================================================================
+// LANDING
+// =============================================================================
+
+/// What became of a broadcast transaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LandingOutcome {
+    Landed,
+    /// Included, but the transaction failed
+    Failed,
+    /// Its blockhash expired before any leader included it
+    Dropped,
+}
+
+impl LandingOutcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LandingOutcome::Landed => "landed",
+            LandingOutcome::Failed => "failed",
+            LandingOutcome::Dropped => "dropped",
+        }
+    }
+}
+
+/// Follow `signature` until it is confirmed, fails, or the chain passes
+/// `last_valid_block_height`, and count the outcome. Errors reading the
+/// node are retried until the wait runs out.
+pub async fn track_landing(
+    provider: Arc<dyn SolanaProvider>,
+    signature: String,
+    last_valid_block_height: u64,
+    priority: bool,
+) -> LandingOutcome {
+    let started = tokio::time::Instant::now();
+
+    let outcome = loop {
+        match provider.get_signature_status(&signature).await {
+            Ok(Some(SolanaSignatureStatus::Landed | SolanaSignatureStatus::Finalized)) => break LandingOutcome::Landed,
+            Ok(Some(SolanaSignatureStatus::Failed(err))) => {
+                tracing::warn!("Solana transaction {} failed on chain: {}", signature, err);
+                break LandingOutcome::Failed;
+            }
+            Ok(Some(SolanaSignatureStatus::Processed)) => {}
+            // Unknown to the node: dropped once its blockhash has expired
+            Ok(None) => {
+                if provider.get_block_height().await.is_ok_and(|height| height > last_valid_block_height) {
+                    break LandingOutcome::Dropped;
+                }
+            }
+            Err(e) => tracing::debug!("Solana status lookup for {} failed: {}", signature, e),
+        }
+
+        if started.elapsed() >= LANDING_MAX_WAIT {
+            break LandingOutcome::Dropped;
+        }
+        tokio::time::sleep(LANDING_POLL_INTERVAL).await;
+    };
+
+    if outcome == LandingOutcome::Dropped {
+        tracing::warn!("Solana transaction {} was dropped before landing", signature);
+    }
+    if let Some(metrics) = MetricsRegistry::global() {
+        metrics
+            .solana_transactions_total
+            .with_label_values(&[outcome.as_str(), if priority { "true" } else { "false" }])
+            .inc();
+    }
+
+    outcome
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unit_price_takes_percentile_within_caps() {
+        let config = PriorityFeeConfig {
+            compute_unit_limit: 1_000,
+            percentile: 75,
+            min_micro_lamports: 100,
+            max_micro_lamports: 50_000,
+        };
+        assert_eq!(config.unit_price(&[]), 100);
+        assert_eq!(config.unit_price(&[0, 0, 0, 0, 0]), 100);
+        assert_eq!(config.unit_price(&[5_000, 1_000, 3_000, 2_000, 4_000]), 4_000);
+        assert_eq!(config.unit_price(&[1_000_000, 2_000_000]), 50_000);
+
+        let disabled = PriorityFeeConfig { max_micro_lamports: 0, ..config };
+        assert_eq!(disabled.unit_price(&[5_000]), 0);
+    }
+
+    #[test]
+    fn test_fee_is_rounded_up_to_whole_lamports() {
+        let budget = ComputeBudget { unit_limit: 1_000, unit_price_micro_lamports: 1_500 };
+        assert_eq!(budget.priority_fee_lamports(), 2);
+        assert_eq!(budget.total_fee_lamports(), BASE_FEE_LAMPORTS + 2);
+        assert_eq!(budget.instructions().len(), 2);
+
+        let base_only = ComputeBudget { unit_limit: 1_000, unit_price_micro_lamports: 0 };
+        assert_eq!(base_only.total_fee_lamports(), BASE_FEE_LAMPORTS);
+        assert_eq!(base_only.instructions().len(), 1);
+    }
+}
@@ -11,6 +11,8 @@ use std::str::FromStr;
 use std::time::Duration;
 
 use super::rpc::RpcError;
+use super::solana_fees::ComputeBudget;
+use crate::services::amount::{self, Decimal};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SolanaBalance {
@@ -23,12 +25,33 @@ pub struct SolanaRecentBlockhash {
     pub last_valid_block_height: u64,
 }
 
+/// Where a broadcast transaction stands
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SolanaSignatureStatus {
+    /// Included in a block, not yet confirmed
+    Processed,
+    /// Confirmed by a supermajority of the cluster
+    Landed,
+    /// Rooted; can no longer be rolled back
+    Finalized,
+    /// Included in a block but the transaction failed
+    Failed(String),
+}
+
 #[async_trait]
 pub trait SolanaProvider: Send + Sync {
-    async fn get_balance(&self, address: &str) -> Result<f64, RpcError>;
+    async fn get_balance(&self, address: &str) -> Result<Decimal, RpcError>;
     async fn get_recent_blockhash(&self) -> Result<String, RpcError>;
+    /// Latest finalized blockhash and the block height it expires after
+    async fn get_latest_blockhash(&self) -> Result<SolanaRecentBlockhash, RpcError>;
     async fn send_transaction(&self, tx_base64: &str) -> Result<String, RpcError>;
     async fn get_minimum_balance_for_rent_exemption(&self) -> Result<u64, RpcError>;
+    /// Priority fees (micro-lamports per compute unit) paid in recent
+    /// slots by transactions writing to `accounts`
+    async fn get_recent_prioritization_fees(&self, accounts: &[String]) -> Result<Vec<u64>, RpcError>;
+    async fn get_block_height(&self) -> Result<u64, RpcError>;
+    /// None while the node hasn't seen the transaction
+    async fn get_signature_status(&self, signature: &str) -> Result<Option<SolanaSignatureStatus>, RpcError>;
 }
 
 pub struct SolanaRpcClient {
@@ -47,6 +70,7 @@ impl SolanaRpcClient {
         }
     }
 
+#[tracing::instrument(name = "rpc.call", skip(self, params), fields(chain = "solana", rpc.method = %method))]
     async fn call_rpc<T: for<'de> Deserialize<'de>>(
         &self,
         method: &str,
@@ -106,28 +130,62 @@ struct BlockhashResult {
 #[derive(Deserialize)]
 struct BlockhashValue {
     blockhash: String,
-    #[allow(dead_code)]
     #[serde(rename = "lastValidBlockHeight")]
     last_valid_block_height: u64,
 }
 
+#[derive(Deserialize)]
+struct PrioritizationFee {
+    #[serde(rename = "prioritizationFee")]
+    prioritization_fee: u64,
+}
+
+#[derive(Deserialize)]
+struct SignatureStatusesResult {
+    value: Vec<Option<SignatureStatusValue>>,
+}
+
+#[derive(Deserialize)]
+struct SignatureStatusValue {
+    err: Option<serde_json::Value>,
+    #[serde(rename = "confirmationStatus")]
+    confirmation_status: Option<String>,
+}
+
+impl From<SignatureStatusValue> for SolanaSignatureStatus {
+    fn from(value: SignatureStatusValue) -> Self {
+        match (value.err, value.confirmation_status.as_deref()) {
+            (Some(err), _) if !err.is_null() => SolanaSignatureStatus::Failed(err.to_string()),
+            (_, Some("finalized")) => SolanaSignatureStatus::Finalized,
+            (_, Some("confirmed")) => SolanaSignatureStatus::Landed,
+            _ => SolanaSignatureStatus::Processed,
+        }
+    }
+}
+
 #[async_trait]
 impl SolanaProvider for SolanaRpcClient {
-    async fn get_balance(&self, address: &str) -> Result<f64, RpcError> {
+    async fn get_balance(&self, address: &str) -> Result<Decimal, RpcError> {
         let result: BalanceResult = self
             .call_rpc("getBalance", json!([address, {"commitment": "confirmed"}]))
             .await?;
         
-        // Convert lamports to SOL (1 SOL = 1e9 lamports)
-        Ok(result.value as f64 / 1_000_000_000.0)
+        amount::from_minor_units(result.value as u128, amount::SOL_DECIMALS).map_err(|e| RpcError::Parse(e.to_string()))
     }
 
     async fn get_recent_blockhash(&self) -> Result<String, RpcError> {
+        Ok(self.get_latest_blockhash().await?.blockhash)
+    }
+
+    async fn get_latest_blockhash(&self) -> Result<SolanaRecentBlockhash, RpcError> {
         let result: BlockhashResult = self
             .call_rpc("getLatestBlockhash", json!([{"commitment": "finalized"}]))
             .await?;
-        
-        Ok(result.value.blockhash)
+
+        Ok(SolanaRecentBlockhash {
+            blockhash: result.value.blockhash,
+            last_valid_block_height: result.value.last_valid_block_height,
+        })
     }
 
     async fn send_transaction(&self, tx_base64: &str) -> Result<String, RpcError> {
@@ -148,14 +206,36 @@ impl SolanaProvider for SolanaRpcClient {
         
         Ok(result)
     }
+
+    async fn get_recent_prioritization_fees(&self, accounts: &[String]) -> Result<Vec<u64>, RpcError> {
+        let result: Vec<PrioritizationFee> = self
+            .call_rpc("getRecentPrioritizationFees", json!([accounts]))
+            .await?;
+
+        Ok(result.into_iter().map(|f| f.prioritization_fee).collect())
+    }
+
+    async fn get_block_height(&self) -> Result<u64, RpcError> {
+        self.call_rpc("getBlockHeight", json!([{"commitment": "confirmed"}])).await
+    }
+
+    async fn get_signature_status(&self, signature: &str) -> Result<Option<SolanaSignatureStatus>, RpcError> {
+        let result: SignatureStatusesResult = self
+            .call_rpc("getSignatureStatuses", json!([[signature], {"searchTransactionHistory": true}]))
+            .await?;
+
+        Ok(result.value.into_iter().next().flatten().map(Into::into))
+    }
 }
 
-/// Build a Solana transfer transaction
+/// Build a Solana transfer transaction, preceded by `budget`'s compute
+/// budget instructions when one is given
 pub fn build_solana_transaction(
     from_pubkey: &str,
     to_pubkey: &str,
-    amount_sol: f64,
+    amount_sol: Decimal,
     recent_blockhash: &str,
+    budget: Option<&ComputeBudget>,
 ) -> Result<Transaction, String> {
     let from = Pubkey::from_str(from_pubkey)
         .map_err(|e| format!("Invalid from pubkey: {}", e))?;
@@ -163,17 +243,20 @@ pub fn build_solana_transaction(
     let to = Pubkey::from_str(to_pubkey)
         .map_err(|e| format!("Invalid to pubkey: {}", e))?;
     
-    let _blockhash = Hash::from_str(recent_blockhash)
+    let blockhash = Hash::from_str(recent_blockhash)
         .map_err(|e| format!("Invalid blockhash: {}", e))?;
 
-    // Convert SOL to lamports
-    let lamports = (amount_sol * 1_000_000_000.0) as u64;
+    let lamports = amount::to_minor_units(amount_sol, amount::SOL_DECIMALS)
+        .ok()
+        .and_then(|lamports| u64::try_from(lamports).ok())
+        .ok_or_else(|| format!("Invalid payout amount: {} SOL", amount_sol))?;
 
-    // Create transfer instruction using solana_sdk directly
-    let instruction = solana_sdk::system_instruction::transfer(&from, &to, lamports);
+    // Compute budget first, then the transfer itself
+    let mut instructions = budget.map(ComputeBudget::instructions).unwrap_or_default();
+    instructions.push(solana_sdk::system_instruction::transfer(&from, &to, lamports));
 
-    // Create message
-    let message = Message::new(&[instruction], Some(&from));
+    // Create message; the blockhash is part of what gets signed
+    let message = Message::new_with_blockhash(&instructions, Some(&from), &blockhash);
 
     // Create unsigned transaction
     Ok(Transaction::new_unsigned(message))
@@ -191,3 +274,22 @@ pub fn sign_solana_transaction(
     
     Ok(())
 }
+
+/// Attach a detached fee-payer signature (`0x`-prefixed hex, as returned by
+/// a `Signer`) to a transaction
+pub fn apply_solana_signature(
+    transaction: &mut Transaction,
+    signature_hex: &str,
+) -> Result<(), String> {
+    let bytes = hex::decode(signature_hex.trim_start_matches("0x"))
+        .map_err(|e| format!("Invalid signature encoding: {}", e))?;
+    let signature = solana_sdk::signature::Signature::try_from(bytes.as_slice())
+        .map_err(|e| format!("Invalid signature: {}", e))?;
+
+    match transaction.signatures.first_mut() {
+        Some(slot) => *slot = signature,
+        None => transaction.signatures.push(signature),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,292 @@
+//! Startup warm-up. The first requests after a deploy used to pay for
+//! opening MySQL connections, preparing statements, building lazily
+//! initialized statics and a cold currency cache that has to go to
+//! Trocador. Running those once before the listener binds moves that cost
+//! off the request path.
+//!
+//! Every step is best effort: a failure is logged and reported, never fatal.
+
+use sqlx::{Executor, MySql, Pool};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::modules::swap::crud::SwapCrud;
+use crate::modules::swap::schema::{CurrenciesQuery, ProvidersQuery};
+use crate::services::redis_cache::RedisService;
+use crate::services::rpc::RpcManager;
+
+/// Statements prepared on every warmed connection. sqlx caches prepared
+/// statements per connection keyed on the SQL text, so these must match
+/// the queries in the request path character for character.
+const HOT_QUERIES: &[&str] = &[
+    "SELECT * FROM users WHERE id = ?",
+    "SELECT * FROM users WHERE email_hash = ? OR email = ? LIMIT 1",
+    "SELECT is_admin FROM users WHERE id = ?",
+    crate::modules::moderation::crud::STANDING_QUERY,
+];
+
+#[derive(Debug, Clone)]
+pub struct WarmupConfig {
+    pub enabled: bool,
+    /// MySQL connections to open up front (capped by the pool size)
+    pub db_connections: u32,
+    pub prime_caches: bool,
+    pub ping_rpcs: bool,
+    /// Upper bound for each step
+    pub step_timeout: Duration,
+}
+
+impl Default for WarmupConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            db_connections: 5,
+            prime_caches: true,
+            ping_rpcs: true,
+            step_timeout: Duration::from_secs(15),
+        }
+    }
+}
+
+impl WarmupConfig {
+    pub fn from_env() -> Result<Self, String> {
+        let mut config = Self::default();
+
+        if let Ok(val) = std::env::var("STARTUP_WARMUP") {
+            config.enabled = val.parse().map_err(|e| format!("Invalid STARTUP_WARMUP: {}", e))?;
+        }
+
+        if let Ok(val) = std::env::var("STARTUP_WARMUP_DB_CONNECTIONS") {
+            config.db_connections = val.parse().map_err(|e| format!("Invalid STARTUP_WARMUP_DB_CONNECTIONS: {}", e))?;
+        }
+
+        if let Ok(val) = std::env::var("STARTUP_WARMUP_PRIME_CACHES") {
+            config.prime_caches = val.parse().map_err(|e| format!("Invalid STARTUP_WARMUP_PRIME_CACHES: {}", e))?;
+        }
+
+        if let Ok(val) = std::env::var("STARTUP_WARMUP_PING_RPCS") {
+            config.ping_rpcs = val.parse().map_err(|e| format!("Invalid STARTUP_WARMUP_PING_RPCS: {}", e))?;
+        }
+
+        if let Ok(val) = std::env::var("STARTUP_WARMUP_TIMEOUT_SECS") {
+            let secs: u64 = val.parse().map_err(|e| format!("Invalid STARTUP_WARMUP_TIMEOUT_SECS: {}", e))?;
+            config.step_timeout = Duration::from_secs(secs);
+        }
+
+        Ok(config)
+    }
+
+    /// Nothing enabled; for callers that opt in step by step
+    pub fn disabled() -> Self {
+        Self {
+            enabled: false,
+            db_connections: 0,
+            prime_caches: false,
+            ping_rpcs: false,
+            ..Self::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct WarmupStep {
+    pub name: &'static str,
+    pub elapsed: Duration,
+    pub result: Result<String, String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct WarmupReport {
+    pub steps: Vec<WarmupStep>,
+}
+
+impl WarmupReport {
+    pub fn step(&self, name: &str) -> Option<&WarmupStep> {
+        self.steps.iter().find(|s| s.name == name)
+    }
+
+    pub fn failures(&self) -> impl Iterator<Item = &WarmupStep> {
+        self.steps.iter().filter(|s| s.result.is_err())
+    }
+
+    pub fn total(&self) -> Duration {
+        self.steps.iter().map(|s| s.elapsed).sum()
+    }
+}
+
+pub struct Warmup {
+    db: Pool<MySql>,
+    redis: RedisService,
+    wallet_mnemonic: String,
+    rpc_manager: Option<Arc<RpcManager>>,
+    config: WarmupConfig,
+}
+
+impl Warmup {
+    pub fn new(db: Pool<MySql>, redis: RedisService, wallet_mnemonic: String) -> Self {
+        Self {
+            db,
+            redis,
+            wallet_mnemonic,
+            rpc_manager: None,
+            config: WarmupConfig::default(),
+        }
+    }
+
+    pub fn with_config(mut self, config: WarmupConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    pub fn with_rpc_manager(mut self, rpc_manager: Arc<RpcManager>) -> Self {
+        self.rpc_manager = Some(rpc_manager);
+        self
+    }
+
+    /// Run every enabled step in order and log a summary
+    pub async fn run(&self) -> WarmupReport {
+        let mut report = WarmupReport::default();
+        if !self.config.enabled {
+            tracing::info!("Startup warm-up disabled");
+            return report;
+        }
+
+        if self.config.db_connections > 0 {
+            report.steps.push(self.timed("database", self.warm_database()).await);
+        }
+        report.steps.push(self.timed("redis", self.warm_redis()).await);
+        report.steps.push(self.timed("statics", async { Ok(init_statics()) }).await);
+        if self.config.prime_caches {
+            report.steps.push(self.timed("caches", self.prime_caches()).await);
+        }
+        if self.config.ping_rpcs {
+            if let Some(rpc_manager) = &self.rpc_manager {
+                report.steps.push(self.timed("rpc", ping_rpcs(rpc_manager)).await);
+            }
+        }
+
+        for step in &report.steps {
+            match &step.result {
+                Ok(detail) => tracing::info!("Warm-up {} done in {:?}: {}", step.name, step.elapsed, detail),
+                Err(e) => tracing::warn!("Warm-up {} failed after {:?}: {}", step.name, step.elapsed, e),
+            }
+        }
+        tracing::info!("Startup warm-up finished in {:?}", report.total());
+
+        report
+    }
+
+    async fn timed<F>(&self, name: &'static str, step: F) -> WarmupStep
+    where
+        F: std::future::Future<Output = Result<String, String>>,
+    {
+        let start = Instant::now();
+        let result = match tokio::time::timeout(self.config.step_timeout, step).await {
+            Ok(result) => result,
+            Err(_) => Err(format!("timed out after {:?}", self.config.step_timeout)),
+        };
+        WarmupStep { name, elapsed: start.elapsed(), result }
+    }
+
+    /// Open connections and prepare the hot statements on each. Holding them
+    /// all at once forces the pool to open that many rather than reuse one.
+    async fn warm_database(&self) -> Result<String, String> {
+        let wanted = self.config.db_connections.min(self.db.options().get_max_connections());
+
+        let mut connections = Vec::with_capacity(wanted as usize);
+        for _ in 0..wanted {
+            connections.push(self.db.acquire().await.map_err(|e| e.to_string())?);
+        }
+
+        for conn in connections.iter_mut() {
+            for sql in HOT_QUERIES {
+                (&mut **conn).prepare(sql).await.map_err(|e| format!("preparing `{}`: {}", sql, e))?;
+            }
+        }
+
+        Ok(format!("{} connections, {} statements each", connections.len(), HOT_QUERIES.len()))
+    }
+
+    async fn warm_redis(&self) -> Result<String, String> {
+        let mut conn = self
+            .redis
+            .get_client()
+            .get_multiplexed_async_connection()
+            .await
+            .map_err(|e| e.to_string())?;
+        let pong: String = redis::cmd("PING").query_async(&mut conn).await.map_err(|e| e.to_string())?;
+        Ok(pong)
+    }
+
+    /// Fill the unfiltered currency and provider caches that back the
+    /// default `GET /swap/currencies` and `GET /swap/providers` responses
+    async fn prime_caches(&self) -> Result<String, String> {
+        let crud = SwapCrud::new(self.db.clone(), Some(self.redis.clone()), Some(self.wallet_mnemonic.clone()));
+
+        crud.get_currencies_optimized(CurrenciesQuery::default())
+            .await
+            .map_err(|e| format!("currencies: {}", e))?;
+
+        let providers = ProvidersQuery { rating: None, markup_enabled: None, sort: None, fields: None };
+        crud.get_providers_optimized(providers)
+            .await
+            .map_err(|e| format!("providers: {}", e))?;
+
+        Ok("currencies, providers".to_string())
+    }
+}
+
+/// Force the process-wide lazily initialized values that are otherwise
+/// built inside the first request that needs them
+fn init_statics() -> String {
+    crate::modules::graphql::controller::api_schema();
+    crate::services::session::SessionConfig::global();
+    crate::services::explorer::ExplorerRegistry::global();
+    crate::services::wallet::signer::SignerBackend::configured();
+    let pii = crate::services::pii::pii_cipher().is_some();
+
+    format!("graphql schema, session, explorers, signer, pii cipher ({})", if pii { "enabled" } else { "disabled" })
+}
+
+async fn ping_rpcs(rpc_manager: &RpcManager) -> Result<String, String> {
+    let results = rpc_manager.check_all_endpoints().await;
+    let healthy = results.iter().filter(|(_, _, ok)| *ok).count();
+    let unhealthy: Vec<String> = results
+        .iter()
+        .filter(|(_, _, ok)| !ok)
+        .map(|(chain, _, _)| chain.clone())
+        .collect();
+
+    if unhealthy.is_empty() {
+        Ok(format!("{}/{} endpoints healthy", healthy, results.len()))
+    } else {
+        Err(format!("{}/{} endpoints healthy; failing on {}", healthy, results.len(), unhealthy.join(", ")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_failures_and_total() {
+        let report = WarmupReport {
+            steps: vec![
+                WarmupStep { name: "redis", elapsed: Duration::from_millis(5), result: Ok("PONG".into()) },
+                WarmupStep { name: "rpc", elapsed: Duration::from_millis(20), result: Err("down".into()) },
+            ],
+        };
+
+        assert_eq!(report.total(), Duration::from_millis(25));
+        assert_eq!(report.failures().map(|s| s.name).collect::<Vec<_>>(), vec!["rpc"]);
+        assert!(report.step("redis").unwrap().result.is_ok());
+        assert!(report.step("caches").is_none());
+    }
+
+    #[test]
+    fn test_disabled_config_runs_nothing() {
+        let config = WarmupConfig::disabled();
+        assert!(!config.enabled && !config.prime_caches && !config.ping_rpcs);
+        assert_eq!(config.db_connections, 0);
+    }
+}
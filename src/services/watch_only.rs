@@ -0,0 +1,103 @@
+//! Watch-only deployments for auditors. With WATCH_ONLY_MODE set the service
+//! can run against production data without changing it: every mutating
+//! request is refused with 403, and no background task that writes is
+//! started: not the listeners, the event subscribers that record
+//! deliveries, emails or revenue, the payout and order workers, the
+//! reconcilers, sweepers and PII key rotation, nor the RPC kill-switch.
+//! Balances, swap status, reports and the read-only GraphQL API keep
+//! working. Reads serve what is stored: swap status and gift card
+//! purchases are not refreshed from the provider, which would advance them,
+//! record provider payloads and send emails, and quotes are not recorded in
+//! the funnel.
+//!
+//! What still writes: signing in and out, which stores refresh tokens, and
+//! Redis, where rate-limit buckets, usage counters and the caches the
+//! startup warm-up fills are kept.
+
+use std::sync::OnceLock;
+
+use axum::{
+    body::Body,
+    http::{Method, Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Serialize;
+
+use crate::services::api_version::unversioned;
+
+static ENABLED: OnceLock<bool> = OnceLock::new();
+
+/// Writes auditors still need: signing in and out. GraphQL is query-only,
+/// so POST /graphql never mutates.
+const ALLOWED_WRITES: &[(Method, &str)] = &[
+    (Method::POST, "/auth/login"),
+    (Method::POST, "/auth/session"),
+    (Method::POST, "/auth/session/refresh"),
+    (Method::DELETE, "/auth/session"),
+    (Method::POST, "/graphql"),
+];
+
+/// Whether WATCH_ONLY_MODE is set; read once
+pub fn watch_only() -> bool {
+    *ENABLED.get_or_init(|| {
+        std::env::var("WATCH_ONLY_MODE")
+            .map(|v| matches!(v.trim().to_ascii_lowercase().as_str(), "1" | "true" | "yes"))
+            .unwrap_or(false)
+    })
+}
+
+/// Whether a watch-only deployment serves this request; the path may carry
+/// a version prefix
+pub fn permits(method: &Method, path: &str) -> bool {
+    if matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS) {
+        return true;
+    }
+    let path = unversioned(path);
+    let path = path.strip_suffix('/').filter(|p| !p.is_empty()).unwrap_or(path);
+    ALLOWED_WRITES.iter().any(|(m, p)| m == method && *p == path)
+}
+
+#[derive(Serialize)]
+struct WatchOnlyErrorResponse {
+    error: &'static str,
+}
+
+/// Refuse mutating requests while the deployment is watch-only
+pub async fn watch_only_guard(request: Request<Body>, next: Next) -> Response {
+    if watch_only() && !permits(request.method(), request.uri().path()) {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(WatchOnlyErrorResponse { error: "This deployment is watch-only" }),
+        )
+            .into_response();
+    }
+    next.run(request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reads_are_permitted() {
+        assert!(permits(&Method::GET, "/swap/abc"));
+        assert!(permits(&Method::GET, "/admin/revenue"));
+        assert!(permits(&Method::HEAD, "/balances"));
+        assert!(permits(&Method::POST, "/graphql"));
+        assert!(permits(&Method::POST, "/graphql/"));
+    }
+
+    #[test]
+    fn test_writes_are_refused_except_sign_in() {
+        assert!(permits(&Method::POST, "/auth/login"));
+        assert!(permits(&Method::POST, "/v1/auth/login"));
+        assert!(!permits(&Method::POST, "/v1/swap/create"));
+        assert!(permits(&Method::DELETE, "/auth/session"));
+        assert!(!permits(&Method::POST, "/auth/register"));
+        assert!(!permits(&Method::POST, "/swap/create"));
+        assert!(!permits(&Method::PATCH, "/admin/halts/1"));
+        assert!(!permits(&Method::DELETE, "/address-book/1"));
+    }
+}
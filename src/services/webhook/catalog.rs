@@ -0,0 +1,213 @@
+//! Published webhook event catalog. Subscriptions created with a payload
+//! version receive the typed events listed here instead of the raw domain
+//! event, under versioned names such as `swap.v1.completed`. A version's
+//! payloads only ever gain optional fields; anything else is a new version.
+
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::types::WebhookEvent;
+use crate::services::amount::Decimal;
+
+/// Newest payload version
+pub const LATEST_VERSION: u32 = 1;
+/// Payload versions still delivered, oldest first
+pub const SUPPORTED_VERSIONS: &[u32] = &[1];
+
+// =============================================================================
+// PAYLOADS
+// =============================================================================
+
+/// `swap.v1.completed`: the swap's payout reached the recipient
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SwapCompletedV1 {
+    pub swap_id: String,
+    pub from_currency: String,
+    pub from_network: String,
+    pub to_currency: String,
+    pub to_network: String,
+    /// Amount the user sent, in `from_currency`
+    #[serde(with = "rust_decimal::serde::float")]
+    #[schemars(with = "f64")]
+    pub amount_sent: Decimal,
+    /// Amount paid out, in `to_currency`; absent when the provider did not report it
+    #[serde(with = "rust_decimal::serde::float_option")]
+    #[schemars(with = "Option<f64>")]
+    pub amount_received: Option<Decimal>,
+    pub recipient_address: String,
+    pub tx_hash_out: Option<String>,
+    pub completed_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_reference: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Value>,
+}
+
+/// `payout.v1.sent`: our payout of the received funds was broadcast
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct PayoutSentV1 {
+    pub swap_id: String,
+    pub chain: String,
+    pub currency: String,
+    #[serde(with = "rust_decimal::serde::float")]
+    #[schemars(with = "f64")]
+    pub amount: Decimal,
+    /// Transaction hash, or the batch reference for batched payouts
+    pub reference: String,
+    pub sent_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_reference: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Value>,
+}
+
+/// `payout.v1.finalized`: the payout transaction can no longer be reorged out
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct PayoutFinalizedV1 {
+    pub swap_id: String,
+    pub chain: String,
+    pub tx_hash: String,
+    /// Confirmations when finality was observed
+    pub confirmations: u64,
+    pub finalized_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_reference: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Value>,
+}
+
+/// `refund.v1.issued`: the deposit was returned to the user
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RefundIssuedV1 {
+    pub swap_id: String,
+    /// Currency and network of the original deposit
+    pub currency: String,
+    pub network: String,
+    pub refund_address: Option<String>,
+    pub tx_hash: Option<String>,
+    pub refunded_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_reference: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Value>,
+}
+
+// =============================================================================
+// CATALOG
+// =============================================================================
+
+/// One published event type
+pub struct EventType {
+    /// e.g. `swap.v1.completed`
+    pub name: &'static str,
+    pub version: u32,
+    /// The unversioned event it replaces
+    pub event: WebhookEvent,
+    pub description: &'static str,
+    schema: fn() -> Value,
+}
+
+impl EventType {
+    /// JSON Schema of the event's `data`
+    pub fn schema(&self) -> Value {
+        (self.schema)()
+    }
+}
+
+fn schema<T: JsonSchema>() -> Value {
+    schemars::schema_for!(T).to_value()
+}
+
+pub const EVENT_TYPES: &[EventType] = &[
+    EventType {
+        name: "swap.v1.completed",
+        version: 1,
+        event: WebhookEvent::SwapCompleted,
+        description: "The swap finished and the payout reached the recipient",
+        schema: schema::<SwapCompletedV1>,
+    },
+    EventType {
+        name: "payout.v1.sent",
+        version: 1,
+        event: WebhookEvent::PayoutCompleted,
+        description: "Our payout of the received funds was broadcast",
+        schema: schema::<PayoutSentV1>,
+    },
+    EventType {
+        name: "payout.v1.finalized",
+        version: 1,
+        event: WebhookEvent::PayoutFinalized,
+        description: "Our payout transaction reached finality on chain",
+        schema: schema::<PayoutFinalizedV1>,
+    },
+    EventType {
+        name: "refund.v1.issued",
+        version: 1,
+        event: WebhookEvent::SwapRefunded,
+        description: "The deposit was returned to the refund address",
+        schema: schema::<RefundIssuedV1>,
+    },
+];
+
+/// The published type `event` is delivered as at `version`, if any
+pub fn event_type(event: &WebhookEvent, version: u32) -> Option<&'static EventType> {
+    EVENT_TYPES.iter().find(|t| t.version == version && &t.event == event)
+}
+
+/// Version delivered to a subscription that asked for `requested`: the
+/// newest supported version not above it. None means unversioned payloads,
+/// either because none was asked for or the request predates every
+/// supported version.
+pub fn negotiate_version(requested: Option<u32>) -> Option<u32> {
+    let requested = requested?;
+    SUPPORTED_VERSIONS.iter().rev().copied().find(|v| *v <= requested)
+}
+
+/// Whether a subscription's event list selects `event` at `version`. Lists
+/// may name unversioned (`swap.completed`) or versioned (`swap.v1.completed`)
+/// types; an empty list selects everything.
+pub fn selects(events: &[String], event: &WebhookEvent, version: Option<u32>) -> bool {
+    if events.is_empty() {
+        return true;
+    }
+    let versioned = version.and_then(|v| event_type(event, v)).map(|t| t.name);
+    events.iter().any(|e| e == event.as_str() || Some(e.as_str()) == versioned)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_negotiate_version() {
+        assert_eq!(negotiate_version(None), None);
+        assert_eq!(negotiate_version(Some(0)), None);
+        assert_eq!(negotiate_version(Some(1)), Some(1));
+        // A subscription asking for a version we do not publish yet gets ours
+        assert_eq!(negotiate_version(Some(7)), Some(LATEST_VERSION));
+    }
+
+    #[test]
+    fn test_catalog_names_and_schemas() {
+        for event_type in EVENT_TYPES {
+            let (_, rest) = event_type.name.split_once('.').unwrap();
+            assert!(rest.starts_with(&format!("v{}.", event_type.version)));
+            assert!(SUPPORTED_VERSIONS.contains(&event_type.version));
+            assert!(event_type.schema().get("properties").is_some());
+        }
+        assert_eq!(event_type(&WebhookEvent::SwapCompleted, 1).unwrap().name, "swap.v1.completed");
+        assert!(event_type(&WebhookEvent::SwapCreated, 1).is_none());
+    }
+
+    #[test]
+    fn test_selects() {
+        let events = vec!["swap.v1.completed".to_string(), "swap.failed".to_string()];
+        assert!(selects(&events, &WebhookEvent::SwapCompleted, Some(1)));
+        assert!(!selects(&events, &WebhookEvent::SwapCompleted, None));
+        assert!(selects(&events, &WebhookEvent::SwapFailed, None));
+        assert!(!selects(&events, &WebhookEvent::PayoutCompleted, Some(1)));
+        assert!(selects(&[], &WebhookEvent::PayoutCompleted, Some(1)));
+    }
+}
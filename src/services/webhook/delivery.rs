@@ -31,6 +31,19 @@ impl WebhookDeliveryClient {
         url: &str,
         secret_key: &str,
         payload: &WebhookPayload,
+    ) -> Result<DeliveryResult, WebhookError> {
+        self.deliver_at(url, secret_key, payload, payload.created_at).await
+    }
+    
+    /// Deliver webhook signed at `timestamp` rather than the payload's
+    /// creation time, so a replayed payload passes the receiver's freshness
+    /// check
+    pub async fn deliver_at(
+        &self,
+        url: &str,
+        secret_key: &str,
+        payload: &WebhookPayload,
+        timestamp: i64,
     ) -> Result<DeliveryResult, WebhookError> {
         let start = Instant::now();
         
@@ -38,7 +51,6 @@ impl WebhookDeliveryClient {
         let payload_json = serde_json::to_string(payload)?;
         
         // Generate signature
-        let timestamp = payload.created_at;
         let signature = generate_signature(secret_key, timestamp, &payload_json);
         
         // Build request
@@ -5,11 +5,40 @@ use tokio::sync::RwLock;
 use chrono::Utc;
 use uuid::Uuid;
 
+use crate::modules::swap::schema::SwapStatus;
+use crate::services::amount::Decimal;
+use crate::services::webhook::catalog::{self, SwapCompletedV1};
 use crate::services::webhook::{
-    Webhook, WebhookPayload, WebhookError,
-    WebhookDeliveryClient, RetryConfig, WebhookCircuitBreaker,
-    TokenBucketRateLimiter, IdempotencyStatus,
+    Webhook, WebhookPayload, WebhookError, WebhookEvent, AttemptSource,
+    WebhookDeliveryClient, DeliveryResult, RetryConfig, WebhookCircuitBreaker,
+    TokenBucketRateLimiter, IdempotencyStatus, CircuitState,
 };
+use crate::services::events::{ops::endpoint_host, DomainEvent, OpsEvent};
+
+/// Outcome of a test or redelivery, sent while the caller waits
+#[derive(Debug, Clone)]
+pub struct AttemptOutcome {
+    pub delivery_id: Uuid,
+    pub event_type: String,
+    pub succeeded: bool,
+    /// None when no response arrived
+    pub response_status: Option<i32>,
+    pub response_time_ms: i32,
+    pub error_message: Option<String>,
+}
+
+impl AttemptOutcome {
+    fn new(delivery_id: Uuid, event_type: String, result: &DeliveryResult) -> Self {
+        Self {
+            delivery_id,
+            event_type,
+            succeeded: result.is_success(),
+            response_status: result.response_status,
+            response_time_ms: result.duration.as_millis() as i32,
+            error_message: result.error_message.clone(),
+        }
+    }
+}
 
 /// Webhook dispatcher manages webhook delivery with retry logic
 pub struct WebhookDispatcher {
@@ -101,20 +130,31 @@ impl WebhookDispatcher {
             webhook.swap_id,
             &payload,
             &idempotency_key,
+            false,
         ).await?;
         
         // Attempt delivery
         let result = self.client.deliver(&webhook.url, &webhook.secret_key, &payload).await?;
+        self.record_attempt(delivery_id, webhook.id, AttemptSource::Event, None, &result).await?;
         
         // Update circuit breaker
         {
             let mut breakers = self.circuit_breakers.write().await;
             if let Some(breaker) = breakers.get_mut(&webhook.id) {
+                let was_open = breaker.state == CircuitState::Open;
                 if result.is_success() {
                     breaker.record_success();
                 } else {
                     breaker.record_failure();
                 }
+                if !was_open && breaker.state == CircuitState::Open {
+                    OpsEvent::CircuitBreakerOpened {
+                        component: "webhook".to_string(),
+                        target: webhook.id.to_string(),
+                        endpoint: endpoint_host(&webhook.url),
+                    }
+                    .publish();
+                }
             }
         }
         
@@ -129,22 +169,69 @@ impl WebhookDispatcher {
         } else if result.is_retryable() {
             // Schedule retry
             let next_retry = self.calculate_next_retry(0);
-            self.schedule_retry(
-                delivery_id,
-                result.error_message.as_deref(),
-                next_retry,
-            ).await?;
+            self.schedule_retry(delivery_id, &result, next_retry).await?;
         } else {
             // Move to DLQ
-            self.move_to_dlq(
-                delivery_id,
-                result.error_message.as_deref(),
-            ).await?;
+            self.move_to_dlq(delivery_id, &result).await?;
         }
         
         Ok(())
     }
     
+    /// Send a signed sample event to the webhook while the caller waits.
+    /// It skips the circuit breaker and is never retried; the delivery is
+    /// stored with `is_test` set so it can be redelivered like any other.
+    pub async fn send_test(
+        &self,
+        webhook: &Webhook,
+        requested_by: &str,
+    ) -> Result<AttemptOutcome, WebhookError> {
+        let payload = test_payload(webhook)?;
+        let idempotency_key = self.generate_idempotency_key(
+            &webhook.swap_id,
+            &payload.id,
+            payload.created_at,
+        );
+        let delivery_id = self.create_delivery_record(
+            webhook.id,
+            webhook.swap_id,
+            &payload,
+            &idempotency_key,
+            true,
+        ).await?;
+        
+        let result = self.client.deliver(&webhook.url, &webhook.secret_key, &payload).await?;
+        self.record_attempt(delivery_id, webhook.id, AttemptSource::Test, Some(requested_by), &result).await?;
+        self.record_manual_result(delivery_id, &result).await?;
+        
+        Ok(AttemptOutcome::new(delivery_id, payload.event_type, &result))
+    }
+    
+    /// Resend a stored delivery of this webhook with its original payload
+    /// and id, signed with the current time. A success marks the delivery
+    /// delivered and takes it out of the retry queue and the DLQ. None when
+    /// the webhook has no such delivery.
+    pub async fn redeliver(
+        &self,
+        webhook: &Webhook,
+        delivery_id: Uuid,
+        requested_by: &str,
+    ) -> Result<Option<AttemptOutcome>, WebhookError> {
+        let delivery = match self.get_delivery(delivery_id).await? {
+            Some(d) if d.webhook_id == webhook.id => d,
+            _ => return Ok(None),
+        };
+        let payload: WebhookPayload = serde_json::from_value(delivery.payload)?;
+        
+        let result = self.client
+            .deliver_at(&webhook.url, &webhook.secret_key, &payload, Utc::now().timestamp())
+            .await?;
+        self.record_attempt(delivery_id, webhook.id, AttemptSource::Redelivery, Some(requested_by), &result).await?;
+        self.record_manual_result(delivery_id, &result).await?;
+        
+        Ok(Some(AttemptOutcome::new(delivery_id, delivery.event_type, &result)))
+    }
+    
     /// Process retry queue
     pub async fn process_retries(&self) -> Result<usize, WebhookError> {
         let pending = self.get_pending_retries().await?;
@@ -168,6 +255,7 @@ impl WebhookDispatcher {
             
             // Attempt delivery
             let result = self.client.deliver(&webhook.url, &webhook.secret_key, &payload).await?;
+            self.record_attempt(delivery_id, webhook_id, AttemptSource::Retry, None, &result).await?;
             
             // Update based on result
             if result.is_success() {
@@ -180,17 +268,10 @@ impl WebhookDispatcher {
             } else if attempt_number < self.retry_config.max_attempts as i32 {
                 // Schedule next retry
                 let next_retry = self.calculate_next_retry(attempt_number as u32 + 1);
-                self.schedule_retry(
-                    delivery_id,
-                    result.error_message.as_deref(),
-                    next_retry,
-                ).await?;
+                self.schedule_retry(delivery_id, &result, next_retry).await?;
             } else {
                 // Exhausted retries, move to DLQ
-                self.move_to_dlq(
-                    delivery_id,
-                    result.error_message.as_deref(),
-                ).await?;
+                self.move_to_dlq(delivery_id, &result).await?;
             }
             
             processed += 1;
@@ -232,6 +313,7 @@ impl WebhookDispatcher {
         swap_id: Uuid,
         payload: &WebhookPayload,
         idempotency_key: &str,
+        is_test: bool,
     ) -> Result<Uuid, WebhookError> {
         let payload_json = serde_json::to_value(payload)?;
         let signature = ""; // Will be generated during delivery
@@ -241,9 +323,9 @@ impl WebhookDispatcher {
             r#"
             INSERT INTO webhook_deliveries (
                 id, webhook_id, swap_id, event_type, idempotency_key,
-                payload, signature, attempt_number, max_attempts
+                payload, signature, attempt_number, max_attempts, is_test
             )
-            VALUES (?, ?, ?, ?, ?, ?, ?, 0, ?)
+            VALUES (?, ?, ?, ?, ?, ?, ?, 0, ?, ?)
             "#,
             id.to_string(),
             webhook_id.to_string(),
@@ -252,7 +334,8 @@ impl WebhookDispatcher {
             idempotency_key,
             payload_json,
             signature,
-            self.retry_config.max_attempts as i32
+            self.retry_config.max_attempts as i32,
+            is_test
         )
         .execute(&self.pool)
         .await?;
@@ -291,7 +374,7 @@ impl WebhookDispatcher {
     async fn schedule_retry(
         &self,
         delivery_id: Uuid,
-        error_message: Option<&str>,
+        result: &DeliveryResult,
         next_retry: chrono::DateTime<Utc>,
     ) -> Result<(), WebhookError> {
         sqlx::query!(
@@ -299,12 +382,18 @@ impl WebhookDispatcher {
             UPDATE webhook_deliveries
             SET attempt_number = attempt_number + 1,
                 next_retry_at = ?,
+                response_status = ?,
+                response_body = ?,
+                response_time_ms = ?,
                 error_message = ?,
                 updated_at = NOW()
             WHERE id = ?
             "#,
             next_retry,
-            error_message,
+            result.response_status,
+            result.response_body.as_deref(),
+            result.duration.as_millis() as i32,
+            result.error_message.as_deref(),
             delivery_id.to_string()
         )
         .execute(&self.pool)
@@ -316,17 +405,23 @@ impl WebhookDispatcher {
     async fn move_to_dlq(
         &self,
         delivery_id: Uuid,
-        error_message: Option<&str>,
+        result: &DeliveryResult,
     ) -> Result<(), WebhookError> {
         sqlx::query!(
             r#"
             UPDATE webhook_deliveries
             SET is_dlq = true,
+                response_status = ?,
+                response_body = ?,
+                response_time_ms = ?,
                 error_message = ?,
                 updated_at = NOW()
             WHERE id = ?
             "#,
-            error_message,
+            result.response_status,
+            result.response_body.as_deref(),
+            result.duration.as_millis() as i32,
+            result.error_message.as_deref(),
             delivery_id.to_string()
         )
         .execute(&self.pool)
@@ -335,6 +430,73 @@ impl WebhookDispatcher {
         Ok(())
     }
     
+    /// Latest response of a test or redelivery. A failure leaves the retry
+    /// schedule alone; a success ends it.
+    async fn record_manual_result(
+        &self,
+        delivery_id: Uuid,
+        result: &DeliveryResult,
+    ) -> Result<(), WebhookError> {
+        let succeeded = result.is_success();
+        sqlx::query(
+            r#"
+            UPDATE webhook_deliveries
+            SET delivered_at = IF(?, NOW(), delivered_at),
+                next_retry_at = IF(?, NULL, next_retry_at),
+                is_dlq = IF(?, false, is_dlq),
+                response_status = ?,
+                response_body = ?,
+                response_time_ms = ?,
+                error_message = ?,
+                updated_at = NOW()
+            WHERE id = ?
+            "#,
+        )
+        .bind(succeeded)
+        .bind(succeeded)
+        .bind(succeeded)
+        .bind(result.response_status)
+        .bind(result.response_body.as_deref())
+        .bind(result.duration.as_millis() as i32)
+        .bind(result.error_message.as_deref())
+        .bind(delivery_id.to_string())
+        .execute(&self.pool)
+        .await?;
+        
+        Ok(())
+    }
+    
+    async fn record_attempt(
+        &self,
+        delivery_id: Uuid,
+        webhook_id: Uuid,
+        source: AttemptSource,
+        requested_by: Option<&str>,
+        result: &DeliveryResult,
+    ) -> Result<(), WebhookError> {
+        sqlx::query(
+            r#"
+            INSERT INTO webhook_delivery_attempts (
+                delivery_id, webhook_id, source, requested_by,
+                response_status, response_time_ms, succeeded, error_message
+            )
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(delivery_id.to_string())
+        .bind(webhook_id.to_string())
+        .bind(source.as_str())
+        .bind(requested_by)
+        .bind(result.response_status)
+        .bind(result.duration.as_millis() as i32)
+        .bind(result.is_success())
+        .bind(result.error_message.as_deref())
+        .execute(&self.pool)
+        .await?;
+        
+        Ok(())
+    }
+    
     fn calculate_next_retry(&self, attempt: u32) -> chrono::DateTime<Utc> {
         let delay = self.retry_config.calculate_delay(attempt);
         Utc::now() + chrono::Duration::from_std(delay).unwrap()
@@ -387,6 +549,8 @@ impl WebhookDispatcher {
                 url: r.url,
                 secret_key: r.secret_key,
                 events,
+                // Retries resend the stored payload, whatever its version
+                payload_version: None,
                 enabled: r.enabled.map(|e| e != 0).unwrap_or(false),
                 rate_limit_per_second: r.rate_limit_per_second.unwrap_or(10),
                 created_at: r.created_at.unwrap_or_else(Utc::now),
@@ -418,6 +582,52 @@ impl WebhookDispatcher {
     }
 }
 
+/// Sample `swap.completed` for the webhook's swap, in the shape its
+/// subscription receives: the typed event for versioned subscriptions, the
+/// raw status change otherwise. The id starts with `test_` so receivers can
+/// tell it apart.
+pub fn test_payload(webhook: &Webhook) -> Result<WebhookPayload, WebhookError> {
+    let now = Utc::now();
+    let swap_id = webhook.swap_id.to_string();
+    let versioned = catalog::negotiate_version(webhook.payload_version)
+        .and_then(|version| catalog::event_type(&WebhookEvent::SwapCompleted, version));
+    
+    let (event_type, data) = match versioned {
+        Some(event_type) => (
+            event_type.name.to_string(),
+            serde_json::to_value(SwapCompletedV1 {
+                swap_id,
+                from_currency: "btc".to_string(),
+                from_network: "bitcoin".to_string(),
+                to_currency: "eth".to_string(),
+                to_network: "ethereum".to_string(),
+                amount_sent: Decimal::new(1, 2),
+                amount_received: Some(Decimal::new(25, 2)),
+                recipient_address: "0x742d35Cc6634C0532925a3b844Bc454e4438f44e".to_string(),
+                tx_hash_out: None,
+                completed_at: now,
+                client_reference: None,
+                metadata: None,
+            })?,
+        ),
+        None => (
+            WebhookEvent::SwapCompleted.as_str().to_string(),
+            serde_json::to_value(DomainEvent::SwapStatusChanged {
+                swap_id,
+                from: SwapStatus::Sending,
+                to: SwapStatus::Completed,
+            })?,
+        ),
+    };
+    
+    Ok(WebhookPayload {
+        id: format!("test_{}", Uuid::new_v4()),
+        event_type,
+        created_at: now.timestamp(),
+        data,
+    })
+}
+
 #[derive(Debug)]
 #[allow(dead_code)]
 struct DeliveryRecord {
@@ -428,3 +638,39 @@ struct DeliveryRecord {
     payload: serde_json::Value,
     attempt_number: i32,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn webhook(payload_version: Option<u32>) -> Webhook {
+        Webhook {
+            id: Uuid::new_v4(),
+            swap_id: Uuid::new_v4(),
+            url: "https://example.com/webhook".to_string(),
+            secret_key: "secret".to_string(),
+            events: vec![],
+            payload_version,
+            enabled: true,
+            rate_limit_per_second: 10,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_payload_matches_the_subscription_version() {
+        let unversioned = webhook(None);
+        let payload = test_payload(&unversioned).unwrap();
+        assert!(payload.id.starts_with("test_"));
+        assert_eq!(payload.event_type, "swap.completed");
+        assert_eq!(payload.data["type"], "swap_status_changed");
+        assert_eq!(payload.data["swap_id"], unversioned.swap_id.to_string());
+
+        let versioned = webhook(Some(1));
+        let payload = test_payload(&versioned).unwrap();
+        assert_eq!(payload.event_type, "swap.v1.completed");
+        let data: SwapCompletedV1 = serde_json::from_value(payload.data).unwrap();
+        assert_eq!(data.swap_id, versioned.swap_id.to_string());
+    }
+}
@@ -0,0 +1,142 @@
+//! Dedup for inbound webhooks. Senders retry on timeouts and errors and
+//! sometimes deliver the same event twice on their own, so every event is
+//! claimed in Redis under its sender and event id before it is handled.
+//! A handled event stays recorded for the dedup TTL; a failed one is
+//! released so the sender's retry is handled afresh.
+
+use std::future::Future;
+
+use crate::services::redis_cache::RedisService;
+
+/// How long a handled event is remembered. Providers stop retrying well
+/// within a week.
+pub const DEFAULT_DEDUP_TTL_SECS: u64 = 7 * 24 * 3600;
+
+/// How long a claim survives a handler that never finishes, e.g. because
+/// the process died mid-event
+const CLAIM_TTL_SECS: u64 = 120;
+
+const CLAIMED: &str = "processing";
+const HANDLED: &str = "handled";
+
+/// Result of claiming an event
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Claim {
+    /// First delivery; the caller handles it
+    New,
+    /// Another delivery of the event is being handled right now
+    InFlight,
+    /// The event was already handled
+    Handled,
+}
+
+/// Outcome of [`IdempotencyStore::consume`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum Consumed<T> {
+    Handled(T),
+    /// Not handled because it is a duplicate of an in-flight or handled event
+    Duplicate(Claim),
+}
+
+#[derive(Debug)]
+pub enum ConsumeError<E> {
+    /// The handler failed; the claim was released so a retry runs it again
+    Handler(E),
+    /// Redis could not be reached, so the event was not handled
+    Store(String),
+}
+
+#[derive(Clone)]
+pub struct IdempotencyStore {
+    redis: RedisService,
+    ttl_secs: u64,
+}
+
+impl IdempotencyStore {
+    pub fn new(redis: RedisService) -> Self {
+        Self { redis, ttl_secs: dedup_ttl_secs() }
+    }
+
+    pub fn with_ttl(mut self, ttl_secs: u64) -> Self {
+        self.ttl_secs = ttl_secs;
+        self
+    }
+
+    fn key(source: &str, event_id: &str) -> String {
+        format!("inbound_event:{}:{}", source, event_id)
+    }
+
+    /// Claim `event_id` from `source` for handling
+    pub async fn claim(&self, source: &str, event_id: &str) -> Result<Claim, String> {
+        let key = Self::key(source, event_id);
+        let mut conn = self.redis.get_client().get_multiplexed_async_connection().await.map_err(|e| e.to_string())?;
+
+        let set: Option<String> = redis::cmd("SET")
+            .arg(&key)
+            .arg(CLAIMED)
+            .arg("NX")
+            .arg("EX")
+            .arg(CLAIM_TTL_SECS)
+            .query_async(&mut conn)
+            .await
+            .map_err(|e| e.to_string())?;
+        if set.is_some() {
+            return Ok(Claim::New);
+        }
+
+        let current: Option<String> = redis::cmd("GET").arg(&key).query_async(&mut conn).await.map_err(|e| e.to_string())?;
+        Ok(match current.as_deref() {
+            Some(HANDLED) => Claim::Handled,
+            _ => Claim::InFlight,
+        })
+    }
+
+    /// Record a claimed event as handled for the dedup TTL
+    pub async fn complete(&self, source: &str, event_id: &str) -> Result<(), String> {
+        self.redis.set_string(&Self::key(source, event_id), HANDLED, self.ttl_secs).await
+    }
+
+    /// Drop a claim whose handling failed. A completed event is left alone.
+    pub async fn release(&self, source: &str, event_id: &str) -> Result<(), String> {
+        self.redis.release_lease(&Self::key(source, event_id), CLAIMED).await
+    }
+
+    /// Run `handler` unless the event is a duplicate. The event counts as
+    /// handled only once `handler` succeeds.
+    pub async fn consume<T, E, F, Fut>(&self, source: &str, event_id: &str, handler: F) -> Result<Consumed<T>, ConsumeError<E>>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        match self.claim(source, event_id).await.map_err(ConsumeError::Store)? {
+            Claim::New => {}
+            duplicate => return Ok(Consumed::Duplicate(duplicate)),
+        }
+
+        match handler().await {
+            Ok(value) => {
+                // The event took effect; failing to record that only risks
+                // handling a later duplicate, which the handler tolerates
+                if let Err(e) = self.complete(source, event_id).await {
+                    tracing::warn!("Failed to record inbound event {}:{} as handled: {}", source, event_id, e);
+                }
+                Ok(Consumed::Handled(value))
+            }
+            Err(e) => {
+                if let Err(release_error) = self.release(source, event_id).await {
+                    tracing::warn!("Failed to release inbound event {}:{}: {}", source, event_id, release_error);
+                }
+                Err(ConsumeError::Handler(e))
+            }
+        }
+    }
+}
+
+/// INBOUND_EVENT_DEDUP_TTL_SECS, default one week
+fn dedup_ttl_secs() -> u64 {
+    std::env::var("INBOUND_EVENT_DEDUP_TTL_SECS")
+        .ok()
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .filter(|secs| *secs > 0)
+        .unwrap_or(DEFAULT_DEDUP_TTL_SECS)
+}
@@ -5,6 +5,8 @@ pub mod circuit_breaker;
 pub mod rate_limiter;
 pub mod dispatcher;
 pub mod delivery;
+pub mod catalog;
+pub mod idempotency;
 
 pub use types::*;
 pub use signature::*;
@@ -12,12 +12,28 @@ pub enum WebhookEvent {
     SwapCompleted,
     SwapFailed,
     SwapExpired,
+    SwapRefunded,
     PayoutInitiated,
     PayoutCompleted,
     PayoutFailed,
+    PayoutFinalized,
 }
 
 impl WebhookEvent {
+    pub const ALL: &'static [WebhookEvent] = &[
+        Self::SwapCreated,
+        Self::SwapPending,
+        Self::SwapProcessing,
+        Self::SwapCompleted,
+        Self::SwapFailed,
+        Self::SwapExpired,
+        Self::SwapRefunded,
+        Self::PayoutInitiated,
+        Self::PayoutCompleted,
+        Self::PayoutFailed,
+        Self::PayoutFinalized,
+    ];
+
     pub fn as_str(&self) -> &str {
         match self {
             Self::SwapCreated => "swap.created",
@@ -26,9 +42,11 @@ impl WebhookEvent {
             Self::SwapCompleted => "swap.completed",
             Self::SwapFailed => "swap.failed",
             Self::SwapExpired => "swap.expired",
+            Self::SwapRefunded => "swap.refunded",
             Self::PayoutInitiated => "payout.initiated",
             Self::PayoutCompleted => "payout.completed",
             Self::PayoutFailed => "payout.failed",
+            Self::PayoutFinalized => "payout.finalized",
         }
     }
 }
@@ -41,6 +59,9 @@ pub struct Webhook {
     pub url: String,
     pub secret_key: String,
     pub events: Vec<String>,
+    /// Payload version the subscription asked for; None receives the
+    /// unversioned payloads
+    pub payload_version: Option<u32>,
     pub enabled: bool,
     pub rate_limit_per_second: i32,
     pub created_at: DateTime<Utc>,
@@ -80,6 +101,30 @@ pub struct WebhookDelivery {
     pub updated_at: DateTime<Utc>,
 }
 
+/// Why a request was sent to a webhook endpoint
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttemptSource {
+    /// First delivery of an event
+    Event,
+    /// Scheduled retry after a failed delivery
+    Retry,
+    /// Sample event fired by the integrator
+    Test,
+    /// Stored delivery replayed on the integrator's request
+    Redelivery,
+}
+
+impl AttemptSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Event => "event",
+            Self::Retry => "retry",
+            Self::Test => "test",
+            Self::Redelivery => "redelivery",
+        }
+    }
+}
+
 /// Webhook delivery status
 #[derive(Debug, Clone, PartialEq)]
 pub enum DeliveryStatus {
@@ -0,0 +1,100 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use sqlx::{MySql, Pool};
+
+use super::mock_rpc::{MockRpcServer, RpcChain};
+use crate::modules::wallet::crud::WalletCrud;
+use crate::services::blockchain::BlockchainListener;
+use crate::services::refund::LateDepositRefunder;
+use crate::services::wallet::bitcoin_rpc::{BitcoinProvider, BitcoinRpcClient};
+use crate::services::wallet::btc_fees::{BitcoinFeeConfig, BitcoinFeeOracle, NodeFeeSource};
+use crate::services::wallet::manager::WalletManager;
+use crate::services::wallet::rpc::{BlockchainProvider, HttpRpcClient};
+use crate::services::wallet::solana_rpc::{SolanaProvider, SolanaRpcClient};
+
+pub const TEST_MNEMONIC: &str =
+    "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+/// Wires the real RPC clients to a `MockRpcServer` so wallet, payout and
+/// listener code runs end to end without touching a live node.
+pub struct MockChainContext {
+    pub rpc: MockRpcServer,
+    master_seed: String,
+    evm_networks: Vec<String>,
+}
+
+impl MockChainContext {
+    /// Mock server with fixtures mounted for every chain
+    pub async fn new() -> Self {
+        Self {
+            rpc: MockRpcServer::with_fixtures().await,
+            master_seed: TEST_MNEMONIC.to_string(),
+            evm_networks: vec!["ethereum".to_string()],
+        }
+    }
+
+    pub fn with_master_seed(mut self, master_seed: &str) -> Self {
+        self.master_seed = master_seed.to_string();
+        self
+    }
+
+    /// EVM networks the listener should watch; all share the mock EVM endpoint
+    pub fn with_evm_networks(mut self, networks: &[&str]) -> Self {
+        self.evm_networks = networks.iter().map(|n| n.to_lowercase()).collect();
+        self
+    }
+
+    pub fn evm_provider(&self) -> Arc<dyn BlockchainProvider> {
+        Arc::new(HttpRpcClient::new(self.rpc.url(RpcChain::Evm)))
+    }
+
+    pub fn bitcoin_provider(&self) -> Arc<dyn BitcoinProvider> {
+        Arc::new(BitcoinRpcClient::new(self.rpc.url(RpcChain::Bitcoin)))
+    }
+
+    /// Fee oracle over the mock mempool.space, Esplora and node
+    pub fn bitcoin_fee_oracle(&self) -> Arc<BitcoinFeeOracle> {
+        let config = BitcoinFeeConfig {
+            mempool_url: Some(self.rpc.mempool_url()),
+            esplora_url: Some(self.rpc.esplora_url()),
+            ..Default::default()
+        };
+        Arc::new(
+            BitcoinFeeOracle::from_config(&config).with_source(Arc::new(NodeFeeSource::new(self.bitcoin_provider()))),
+        )
+    }
+
+    pub fn solana_provider(&self) -> Arc<dyn SolanaProvider> {
+        Arc::new(SolanaRpcClient::new(self.rpc.url(RpcChain::Solana)))
+    }
+
+    /// Wallet manager with EVM, Bitcoin and Solana providers on the mock server
+    pub fn wallet_manager(&self, db: Pool<MySql>) -> WalletManager {
+        WalletManager::new(WalletCrud::new(db), self.master_seed.clone(), self.evm_provider())
+            .with_bitcoin_fee_oracle(self.bitcoin_fee_oracle())
+            .with_bitcoin_provider(self.bitcoin_provider())
+            .with_solana_provider(self.solana_provider())
+    }
+
+    /// Blockchain listener polling the mock server every 100ms
+    pub fn listener(&self, db: Pool<MySql>) -> BlockchainListener {
+        let providers: HashMap<String, Arc<dyn BlockchainProvider>> = self
+            .evm_networks
+            .iter()
+            .map(|network| (network.clone(), self.evm_provider()))
+            .collect();
+
+        BlockchainListener::with_providers(db, providers)
+            .with_check_interval(Duration::from_millis(100))
+    }
+
+    /// Late deposit refunder sending through the mock server on every watched EVM network
+    pub fn late_deposit_refunder(&self, db: Pool<MySql>) -> LateDepositRefunder {
+        let mut refunder = LateDepositRefunder::new(db, self.master_seed.clone());
+        for network in &self.evm_networks {
+            refunder = refunder.with_provider(network, self.evm_provider());
+        }
+        refunder
+    }
+}
@@ -0,0 +1,54 @@
+//! Canned chain responses served by `MockRpcServer` when no override is mounted.
+//! Values are realistic enough for the signing and fee code paths to succeed.
+
+// =============================================================================
+// EVM
+// =============================================================================
+
+/// 20 gwei
+pub const EVM_GAS_PRICE_WEI: u64 = 20_000_000_000;
+pub const EVM_NONCE: u64 = 5;
+/// 1 ETH
+pub const EVM_BALANCE_WEI: u128 = 1_000_000_000_000_000_000;
+pub const EVM_TX_HASH: &str = "0x8f2d1c9b6e3a47f5b0d4c2e1a9f8b7c6d5e4f3a2b1c0d9e8f7a6b5c4d3e2f1a0";
+pub const EVM_BLOCK_NUMBER: u64 = 19_000_000;
+/// Block `EVM_TX_HASH` was mined in, 3 confirmations deep
+pub const EVM_TX_BLOCK_NUMBER: u64 = EVM_BLOCK_NUMBER - 2;
+
+// =============================================================================
+// BITCOIN
+// =============================================================================
+
+/// BTC per kB
+pub const BTC_FEE_RATE: f64 = 0.0001;
+pub const BTC_UTXO_TXID: &str = "4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b";
+pub const BTC_UTXO_AMOUNT: f64 = 0.05;
+pub const BTC_UTXO_CONFIRMATIONS: u32 = 6;
+pub const BTC_TXID: &str = "f4184fc596403b9d638783cf57adfe4c75c605f6356fbc91338530e9831e9e16";
+/// Confirmations of `BTC_TXID`
+pub const BTC_TX_CONFIRMATIONS: u32 = 2;
+/// mempool.space fastest, hour and economy rates, sat/vB
+pub const BTC_MEMPOOL_FEES: (f64, f64, f64) = (24.0, 10.0, 2.0);
+/// Esplora rates for 1, 6 and 144 blocks, sat/vB
+pub const BTC_ESPLORA_FEES: (f64, f64, f64) = (22.0, 10.0, 2.0);
+
+// =============================================================================
+// SOLANA
+// =============================================================================
+
+/// 2 SOL
+pub const SOL_BALANCE_LAMPORTS: u64 = 2_000_000_000;
+pub const SOL_BLOCKHASH: &str = "EkSnNWid2cvwEVnVx9aBqawnmiCNiDgp3gUdkDPTKN1N";
+pub const SOL_LAST_VALID_BLOCK_HEIGHT: u64 = 250_000_000;
+pub const SOL_RENT_EXEMPT_MINIMUM: u64 = 890_880;
+pub const SOL_SIGNATURE: &str =
+    "5VERv8NMvzbJMEkV8xnrLkEaWRtSz9CosKDYjCJjBRnbJLgp8uirBgmQpjKhoR4tjF3ZpRzrFmBV6UjKdiSZkQUW";
+/// Micro-lamports per compute unit paid in recent slots
+pub const SOL_PRIORITY_FEES: [u64; 4] = [0, 1_000, 5_000, 20_000];
+/// Below the fixture blockhash's last valid height, so it has not expired
+pub const SOL_BLOCK_HEIGHT: u64 = 249_999_900;
+
+/// Hex-encode a value the way EVM nodes return quantities
+pub fn evm_quantity(value: u128) -> String {
+    format!("0x{:x}", value)
+}
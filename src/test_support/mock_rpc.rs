@@ -0,0 +1,254 @@
+use serde_json::{json, Value};
+use wiremock::matchers::{body_partial_json, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+use super::fixtures;
+
+/// Override mocks take precedence over the canned fixtures (wiremock picks
+/// the lowest priority value first).
+const OVERRIDE_PRIORITY: u8 = 1;
+const FIXTURE_PRIORITY: u8 = 10;
+
+/// Which node family a JSON-RPC call targets. Each family is served on its
+/// own path so one server can stand in for every chain at once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RpcChain {
+    Evm,
+    Bitcoin,
+    Solana,
+}
+
+impl RpcChain {
+    fn path(&self) -> &'static str {
+        match self {
+            RpcChain::Evm => "/evm",
+            RpcChain::Bitcoin => "/btc",
+            RpcChain::Solana => "/sol",
+        }
+    }
+}
+
+/// In-process JSON-RPC server answering EVM, Bitcoin Core and Solana calls
+/// from fixtures. Tests override individual methods to script failures or
+/// per-address balances.
+pub struct MockRpcServer {
+    server: MockServer,
+}
+
+impl MockRpcServer {
+    /// Start an empty server with no mounted methods
+    pub async fn start() -> Self {
+        Self {
+            server: MockServer::start().await,
+        }
+    }
+
+    /// Start a server answering every method the wallet and listener use
+    pub async fn with_fixtures() -> Self {
+        let rpc = Self::start().await;
+        rpc.mount_evm_fixtures().await;
+        rpc.mount_bitcoin_fixtures().await;
+        rpc.mount_solana_fixtures().await;
+        rpc.mount_fee_api_fixtures().await;
+        rpc
+    }
+
+    /// Drop every override and recorded request, keeping the fixtures
+    pub async fn reset(&self) {
+        self.server.reset().await;
+        self.mount_evm_fixtures().await;
+        self.mount_bitcoin_fixtures().await;
+        self.mount_solana_fixtures().await;
+        self.mount_fee_api_fixtures().await;
+    }
+
+    pub fn url(&self, chain: RpcChain) -> String {
+        format!("{}{}", self.server.uri(), chain.path())
+    }
+
+    /// Base URL of the mock mempool.space fee API
+    pub fn mempool_url(&self) -> String {
+        format!("{}/mempool", self.server.uri())
+    }
+
+    /// Base URL of the mock Esplora fee API
+    pub fn esplora_url(&self) -> String {
+        format!("{}/esplora", self.server.uri())
+    }
+
+    /// Respond to `rpc_method` with `result`, overriding any fixture
+    pub async fn mock_result(&self, chain: RpcChain, rpc_method: &str, result: Value) {
+        self.mount(chain, json!({ "method": rpc_method }), success(result), OVERRIDE_PRIORITY)
+            .await;
+    }
+
+    /// Respond to `rpc_method` with a JSON-RPC error object
+    pub async fn mock_error(&self, chain: RpcChain, rpc_method: &str, message: &str) {
+        let body = json!({
+            "jsonrpc": "2.0",
+            "error": { "code": -32000, "message": message },
+            "id": 1
+        });
+        self.mount(chain, json!({ "method": rpc_method }), body, OVERRIDE_PRIORITY)
+            .await;
+    }
+
+    /// Balance for a single EVM address; other addresses keep the fixture
+    pub async fn mock_evm_balance(&self, address: &str, wei: u128) {
+        self.mount(
+            RpcChain::Evm,
+            json!({ "method": "eth_getBalance", "params": [address] }),
+            success(json!(fixtures::evm_quantity(wei))),
+            OVERRIDE_PRIORITY,
+        )
+        .await;
+    }
+
+    /// Number of calls received for `rpc_method` on `chain`
+    pub async fn calls(&self, chain: RpcChain, rpc_method: &str) -> usize {
+        self.requests(chain, rpc_method).await.len()
+    }
+
+    /// Params of every call received for `rpc_method` on `chain`, oldest first
+    pub async fn requests(&self, chain: RpcChain, rpc_method: &str) -> Vec<Value> {
+        self.server
+            .received_requests()
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|request| request.url.path() == chain.path())
+            .filter_map(|request| serde_json::from_slice::<Value>(&request.body).ok())
+            .filter(|body| body["method"] == rpc_method)
+            .map(|body| body["params"].clone())
+            .collect()
+    }
+
+    async fn mount_evm_fixtures(&self) {
+        let fixtures = [
+            ("eth_gasPrice", json!(fixtures::evm_quantity(fixtures::EVM_GAS_PRICE_WEI as u128))),
+            ("eth_getTransactionCount", json!(fixtures::evm_quantity(fixtures::EVM_NONCE as u128))),
+            ("eth_getBalance", json!(fixtures::evm_quantity(fixtures::EVM_BALANCE_WEI))),
+            ("eth_sendRawTransaction", json!(fixtures::EVM_TX_HASH)),
+            ("eth_blockNumber", json!(fixtures::evm_quantity(fixtures::EVM_BLOCK_NUMBER as u128))),
+            (
+                "eth_getTransactionReceipt",
+                json!({
+                    "transactionHash": fixtures::EVM_TX_HASH,
+                    "blockNumber": fixtures::evm_quantity(fixtures::EVM_TX_BLOCK_NUMBER as u128),
+                    "status": "0x1"
+                }),
+            ),
+            // No token transfers unless a test mounts some
+            ("eth_getLogs", json!([])),
+        ];
+        self.mount_fixtures(RpcChain::Evm, fixtures).await;
+    }
+
+    async fn mount_bitcoin_fixtures(&self) {
+        let fixtures = [
+            (
+                "listunspent",
+                json!([{
+                    "txid": fixtures::BTC_UTXO_TXID,
+                    "vout": 0,
+                    "amount": fixtures::BTC_UTXO_AMOUNT,
+                    "confirmations": fixtures::BTC_UTXO_CONFIRMATIONS
+                }]),
+            ),
+            ("estimatesmartfee", json!({ "feerate": fixtures::BTC_FEE_RATE, "blocks": 6 })),
+            ("sendrawtransaction", json!(fixtures::BTC_TXID)),
+            (
+                "getrawtransaction",
+                json!({ "txid": fixtures::BTC_TXID, "confirmations": fixtures::BTC_TX_CONFIRMATIONS }),
+            ),
+        ];
+        self.mount_fixtures(RpcChain::Bitcoin, fixtures).await;
+    }
+
+    async fn mount_solana_fixtures(&self) {
+        let fixtures = [
+            (
+                "getBalance",
+                json!({ "context": { "slot": 1 }, "value": fixtures::SOL_BALANCE_LAMPORTS }),
+            ),
+            (
+                "getLatestBlockhash",
+                json!({
+                    "context": { "slot": 1 },
+                    "value": {
+                        "blockhash": fixtures::SOL_BLOCKHASH,
+                        "lastValidBlockHeight": fixtures::SOL_LAST_VALID_BLOCK_HEIGHT
+                    }
+                }),
+            ),
+            ("getMinimumBalanceForRentExemption", json!(fixtures::SOL_RENT_EXEMPT_MINIMUM)),
+            ("sendTransaction", json!(fixtures::SOL_SIGNATURE)),
+            (
+                "getRecentPrioritizationFees",
+                json!(fixtures::SOL_PRIORITY_FEES
+                    .iter()
+                    .enumerate()
+                    .map(|(i, fee)| json!({ "slot": i + 1, "prioritizationFee": fee }))
+                    .collect::<Vec<_>>()),
+            ),
+            ("getBlockHeight", json!(fixtures::SOL_BLOCK_HEIGHT)),
+            // Every broadcast lands unless a test mounts otherwise
+            (
+                "getSignatureStatuses",
+                json!({
+                    "context": { "slot": 1 },
+                    "value": [{ "slot": 1, "confirmations": null, "err": null, "confirmationStatus": "finalized" }]
+                }),
+            ),
+        ];
+        self.mount_fixtures(RpcChain::Solana, fixtures).await;
+    }
+
+    /// REST fee APIs the Bitcoin fee oracle reads besides the node
+    async fn mount_fee_api_fixtures(&self) {
+        let (fastest, hour, economy) = fixtures::BTC_MEMPOOL_FEES;
+        let (one, six, day) = fixtures::BTC_ESPLORA_FEES;
+        let apis = [
+            (
+                "/mempool/v1/fees/recommended",
+                json!({
+                    "fastestFee": fastest,
+                    "halfHourFee": (fastest + hour) / 2.0,
+                    "hourFee": hour,
+                    "economyFee": economy,
+                    "minimumFee": 1
+                }),
+            ),
+            ("/esplora/fee-estimates", json!({ "1": one, "3": (one + six) / 2.0, "6": six, "144": day })),
+        ];
+        for (api_path, body) in apis {
+            Mock::given(method("GET"))
+                .and(path(api_path))
+                .respond_with(ResponseTemplate::new(200).set_body_json(body))
+                .with_priority(FIXTURE_PRIORITY)
+                .mount(&self.server)
+                .await;
+        }
+    }
+
+    async fn mount_fixtures<const N: usize>(&self, chain: RpcChain, fixtures: [(&str, Value); N]) {
+        for (rpc_method, result) in fixtures {
+            self.mount(chain, json!({ "method": rpc_method }), success(result), FIXTURE_PRIORITY)
+                .await;
+        }
+    }
+
+    async fn mount(&self, chain: RpcChain, matcher: Value, body: Value, priority: u8) {
+        Mock::given(method("POST"))
+            .and(path(chain.path()))
+            .and(body_partial_json(matcher))
+            .respond_with(ResponseTemplate::new(200).set_body_json(body))
+            .with_priority(priority)
+            .mount(&self.server)
+            .await;
+    }
+}
+
+fn success(result: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "result": result, "id": 1 })
+}
@@ -0,0 +1,12 @@
+//! Hermetic test harness, enabled with the `test-support` feature.
+//!
+//! `MockRpcServer` is an embedded JSON-RPC server with canned EVM, Bitcoin
+//! and Solana responses; `MockChainContext` builds wallet managers and
+//! listeners pointed at it.
+
+mod context;
+pub mod fixtures;
+mod mock_rpc;
+
+pub use context::{MockChainContext, TEST_MNEMONIC};
+pub use mock_rpc::{MockRpcServer, RpcChain};
@@ -0,0 +1,137 @@
+use axum::http::StatusCode;
+use chrono::Utc;
+use exchange_shared::services::totp::{decode_base32, hotp};
+use serde_json::json;
+
+use crate::common::{create_user, test_email, TestContext};
+
+// RFC 6238 test secret; any code is checked against the current time
+const TOTP_SECRET: &str = "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ";
+
+fn current_code() -> String {
+    let key = decode_base32(TOTP_SECRET).unwrap();
+    format!("{:06}", hotp(&key, Utc::now().timestamp() as u64 / 30))
+}
+
+fn new_entry(code: &str) -> serde_json::Value {
+    json!({
+        "label": "Cold wallet",
+        "currency": "eth",
+        "network": "ERC20",
+        "address": "0x742d35Cc6634C0532925a3b844Bc9e7595f0bEb0",
+        "two_factor_code": code
+    })
+}
+
+#[tokio::test]
+async fn address_book_requires_authentication() {
+    let ctx = TestContext::new().await;
+
+    ctx.server.get("/address-book").await.assert_status(StatusCode::UNAUTHORIZED);
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn new_user_has_empty_address_book() {
+    let ctx = TestContext::new().await;
+    let (_, token) = create_user(&ctx, &test_email()).await;
+
+    let response = ctx.server.get("/address-book").authorization_bearer(&token).await;
+
+    response.assert_status_ok();
+    let body: serde_json::Value = response.json();
+    assert!(body["addresses"].as_array().unwrap().is_empty());
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn saving_address_requires_two_factor() {
+    let ctx = TestContext::new().await;
+    let (_, token) = create_user(&ctx, &test_email()).await;
+
+    let response = ctx
+        .server
+        .post("/address-book")
+        .authorization_bearer(&token)
+        .json(&new_entry("123456"))
+        .await;
+
+    response.assert_status(StatusCode::FORBIDDEN);
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn saving_address_rejects_wrong_code() {
+    let ctx = TestContext::new().await;
+    let email = test_email();
+    let (_, token) = create_user(&ctx, &email).await;
+
+    sqlx::query("UPDATE users SET two_factor_enabled = TRUE, two_factor_secret = ? WHERE email = ?")
+        .bind(TOTP_SECRET)
+        .bind(&email)
+        .execute(&ctx.db)
+        .await
+        .unwrap();
+
+    let response = ctx
+        .server
+        .post("/address-book")
+        .authorization_bearer(&token)
+        .json(&new_entry("not-a-code"))
+        .await;
+
+    response.assert_status(StatusCode::UNAUTHORIZED);
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn saving_address_refuses_a_spent_code() {
+    let ctx = TestContext::new().await;
+    let email = test_email();
+    let (_, token) = create_user(&ctx, &email).await;
+
+    sqlx::query("UPDATE users SET two_factor_enabled = TRUE, two_factor_secret = ? WHERE email = ?")
+        .bind(TOTP_SECRET)
+        .bind(&email)
+        .execute(&ctx.db)
+        .await
+        .unwrap();
+
+    // The code is spent once accepted, whatever the address check says
+    let code = current_code();
+    let response = ctx
+        .server
+        .post("/address-book")
+        .authorization_bearer(&token)
+        .json(&new_entry(&code))
+        .await;
+    assert_ne!(response.status_code(), StatusCode::UNAUTHORIZED);
+
+    let response = ctx
+        .server
+        .post("/address-book")
+        .authorization_bearer(&token)
+        .json(&new_entry(&code))
+        .await;
+    response.assert_status(StatusCode::UNAUTHORIZED);
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn deleting_unknown_entry_is_not_found() {
+    let ctx = TestContext::new().await;
+    let (_, token) = create_user(&ctx, &test_email()).await;
+
+    ctx.server
+        .delete("/address-book/00000000-0000-0000-0000-000000000000")
+        .authorization_bearer(&token)
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+
+    ctx.cleanup().await;
+}
@@ -0,0 +1,4 @@
+mod common;
+mod address_book {
+    pub mod address_book_test;
+}
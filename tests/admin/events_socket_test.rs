@@ -0,0 +1,53 @@
+use axum::http::StatusCode;
+
+use crate::common::{create_test_user, test_email, test_password, TestContext};
+
+/// Headers of a WebSocket handshake (RFC 6455 sample key)
+fn handshake(request: axum_test::TestRequest) -> axum_test::TestRequest {
+    request
+        .add_header("connection", "upgrade")
+        .add_header("upgrade", "websocket")
+        .add_header("sec-websocket-version", "13")
+        .add_header("sec-websocket-key", "dGhlIHNhbXBsZSBub25jZQ==")
+}
+
+#[tokio::test]
+async fn test_admin_socket_requires_a_token() {
+    let ctx = TestContext::over_http().await;
+
+    handshake(ctx.server.get("/ws/admin")).await.assert_status(StatusCode::UNAUTHORIZED);
+    handshake(ctx.server.get("/ws/admin").authorization_bearer("not-a-token"))
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_admin_socket_refuses_regular_users() {
+    let ctx = TestContext::over_http().await;
+    let (_, token) = create_test_user(&ctx.server, &test_email(), test_password()).await;
+
+    handshake(ctx.server.get("/ws/admin").authorization_bearer(&token))
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_admin_socket_upgrades_for_admins() {
+    let ctx = TestContext::over_http().await;
+    let (admin_id, token) = create_test_user(&ctx.server, &test_email(), test_password()).await;
+    sqlx::query("UPDATE users SET is_admin = TRUE WHERE id = ?")
+        .bind(&admin_id)
+        .execute(&ctx.db)
+        .await
+        .unwrap();
+
+    handshake(ctx.server.get("/ws/admin?types=kill_switch").authorization_bearer(&token))
+        .await
+        .assert_status(StatusCode::SWITCHING_PROTOCOLS);
+
+    ctx.cleanup().await;
+}
@@ -0,0 +1,4 @@
+mod common;
+mod admin {
+    pub mod events_socket_test;
+}
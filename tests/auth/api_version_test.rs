@@ -0,0 +1,83 @@
+use axum::http::{header, HeaderName, HeaderValue, StatusCode};
+use serde_json::Value;
+
+use crate::common::{create_test_user, test_email, test_password, TestContext};
+
+fn api_version() -> HeaderName {
+    HeaderName::from_static("api-version")
+}
+
+#[tokio::test]
+async fn prefixed_paths_are_served_under_their_version() {
+    let ctx = TestContext::new().await;
+    let (user_id, token) = create_test_user(&ctx.server, &test_email(), test_password()).await;
+
+    let response = ctx.server.get("/v1/auth/me").authorization_bearer(&token).await;
+    response.assert_status_ok();
+    assert_eq!(response.header(api_version()), "1");
+    assert!(response.headers().get("deprecation").is_none());
+    let body: Value = response.json();
+    assert_eq!(body["id"], user_id.as_str());
+
+    // v2 has no handler of its own here yet, so v1's answers
+    let response = ctx.server.get("/v2/auth/me").authorization_bearer(&token).await;
+    response.assert_status_ok();
+    assert_eq!(response.header(api_version()), "2");
+
+    // Routes keep their auth under every prefix
+    for path in ["/v1/auth/me", "/v2/auth/me"] {
+        ctx.server.get(path).await.assert_status(StatusCode::UNAUTHORIZED);
+    }
+    for path in ["/v1/admin/revenue", "/v2/admin/revenue"] {
+        ctx.server.get(path).authorization_bearer(&token).await.assert_status(StatusCode::FORBIDDEN);
+    }
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn unprefixed_paths_are_negotiated_and_deprecated() {
+    let ctx = TestContext::new().await;
+    let (_, token) = create_test_user(&ctx.server, &test_email(), test_password()).await;
+
+    let response = ctx.server.get("/auth/me").authorization_bearer(&token).await;
+    response.assert_status_ok();
+    assert_eq!(response.header(api_version()), "1");
+    assert_eq!(response.header("deprecation"), "true");
+    assert_eq!(response.header(header::LINK), "</v1/auth/me>; rel=\"successor-version\"");
+
+    let response = ctx
+        .server
+        .get("/auth/me")
+        .authorization_bearer(&token)
+        .add_header(api_version(), HeaderValue::from_static("2"))
+        .await;
+    response.assert_status_ok();
+    assert_eq!(response.header(api_version()), "2");
+    assert_eq!(response.header(header::LINK), "</v2/auth/me>; rel=\"successor-version\"");
+
+    let response = ctx
+        .server
+        .get("/auth/me")
+        .authorization_bearer(&token)
+        .add_header(header::ACCEPT, HeaderValue::from_static("application/vnd.exchange.v2+json"))
+        .await;
+    assert_eq!(response.header(api_version()), "2");
+
+    // Unknown versions are refused rather than guessed
+    let response = ctx
+        .server
+        .get("/auth/me")
+        .add_header(api_version(), HeaderValue::from_static("9"))
+        .await;
+    response.assert_status(StatusCode::BAD_REQUEST);
+    let body: Value = response.json();
+    assert_eq!(body["supported"], serde_json::json!(["v1", "v2"]));
+
+    // Health checks stay outside versioning
+    let response = ctx.server.get("/health").await;
+    response.assert_status_ok();
+    assert!(response.headers().get("api-version").is_none());
+
+    ctx.cleanup().await;
+}
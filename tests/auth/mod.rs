@@ -7,3 +7,10 @@ mod reset_password_test;
 mod two_factor_test;
 mod backup_codes_test;
 mod email_verification_test;
+mod oauth_test;
+mod session_test;
+mod retention_test;
+mod scopes_test;
+mod suspension_test;
+mod route_auth_test;
+mod api_version_test;
@@ -0,0 +1,67 @@
+use axum::http::StatusCode;
+use serde_json::json;
+
+use crate::common::TestContext;
+
+#[tokio::test]
+async fn oauth_start_with_unknown_provider_returns_not_found() {
+    let ctx = TestContext::new().await;
+
+    let response = ctx
+        .server
+        .post("/auth/oauth/myspace/start")
+        .json(&json!({ "redirect_uri": "https://app.example.com/oauth/callback" }))
+        .await;
+
+    response.assert_status(StatusCode::NOT_FOUND);
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn oauth_link_without_session_returns_unauthorized() {
+    let ctx = TestContext::new().await;
+
+    let response = ctx
+        .server
+        .post("/auth/oauth/google/start")
+        .json(&json!({
+            "redirect_uri": "https://app.example.com/oauth/callback",
+            "link": true
+        }))
+        .await;
+
+    response.assert_status(StatusCode::UNAUTHORIZED);
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn oauth_callback_with_unknown_state_is_rejected() {
+    let ctx = TestContext::new().await;
+
+    let response = ctx
+        .server
+        .post("/auth/oauth/google/callback")
+        .json(&json!({ "code": "code-from-provider", "state": "never-issued" }))
+        .await;
+
+    response.assert_status(StatusCode::BAD_REQUEST);
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn oauth_two_factor_with_unknown_token_is_rejected() {
+    let ctx = TestContext::new().await;
+
+    let response = ctx
+        .server
+        .post("/auth/oauth/2fa")
+        .json(&json!({ "two_factor_token": "never-issued", "code": "123456" }))
+        .await;
+
+    response.assert_status(StatusCode::BAD_REQUEST);
+
+    ctx.cleanup().await;
+}
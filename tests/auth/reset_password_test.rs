@@ -1,8 +1,42 @@
 use axum::http::StatusCode;
 use serde_json::json;
 
+use exchange_shared::modules::email::crud::{recipient_hash, EmailCrud};
+
 use crate::common::{test_email, test_password, TestContext};
 
+/// Body of the newest email queued for `email`
+async fn latest_email(ctx: &TestContext, email: &str) -> Option<String> {
+    let id: Option<String> =
+        sqlx::query_scalar("SELECT id FROM email_outbox WHERE recipient_hash = ? ORDER BY created_at DESC LIMIT 1")
+            .bind(recipient_hash(email))
+            .fetch_optional(&ctx.db)
+            .await
+            .unwrap();
+    let id = id?;
+    EmailCrud::new(ctx.db.clone()).get(&id).await.unwrap().map(|e| e.body)
+}
+
+/// The token in a reset email, whether sent as a link or on its own
+fn token_in(body: &str) -> String {
+    let start = body
+        .find("token=")
+        .map(|i| i + "token=".len())
+        .or_else(|| body.find("token: ").map(|i| i + "token: ".len()))
+        .expect("reset email should carry a token");
+    body[start..].split_whitespace().next().unwrap().to_string()
+}
+
+async fn reset_count(ctx: &TestContext, email: &str) -> i64 {
+    sqlx::query_scalar(
+        "SELECT COUNT(*) FROM email_outbox WHERE recipient_hash = ? AND subject = 'Reset your password'",
+    )
+    .bind(recipient_hash(email))
+    .fetch_one(&ctx.db)
+    .await
+    .unwrap()
+}
+
 async fn create_user_and_get_reset_token(ctx: &TestContext) -> (String, String) {
     let email = test_email();
 
@@ -22,18 +56,8 @@ async fn create_user_and_get_reset_token(ctx: &TestContext) -> (String, String)
         }))
         .await;
 
-    // Get token from database (in real app, this would be sent via email)
-    let token: String = sqlx::query_scalar(
-        "SELECT token FROM password_resets pr
-         JOIN users u ON pr.user_id = u.id
-         WHERE u.email = ?
-         ORDER BY pr.created_at DESC
-         LIMIT 1"
-    )
-    .bind(&email)
-    .fetch_one(&ctx.db)
-    .await
-    .unwrap();
+    // Only a hash is stored, so the token comes from the email
+    let token = token_in(&latest_email(ctx, &email).await.expect("reset email should be queued"));
 
     (email, token)
 }
@@ -144,11 +168,15 @@ async fn reset_password_with_invalid_token_returns_bad_request() {
 #[tokio::test]
 async fn reset_password_with_expired_token_returns_bad_request() {
     let ctx = TestContext::new().await;
-    let (_, token) = create_user_and_get_reset_token(&ctx).await;
+    let (email, token) = create_user_and_get_reset_token(&ctx).await;
 
     // Manually expire the token
-    sqlx::query("UPDATE password_resets SET expires_at = DATE_SUB(NOW(), INTERVAL 1 HOUR) WHERE token = ?")
-        .bind(&token)
+    sqlx::query(
+        "UPDATE password_resets pr JOIN users u ON pr.user_id = u.id
+         SET pr.expires_at = DATE_SUB(NOW(), INTERVAL 1 HOUR)
+         WHERE u.email = ?",
+    )
+    .bind(&email)
         .execute(&ctx.db)
         .await
         .unwrap();
@@ -271,3 +299,152 @@ async fn reset_password_with_missing_fields_returns_bad_request() {
 
     ctx.cleanup().await;
 }
+
+#[tokio::test]
+async fn reset_token_is_stored_hashed_and_expires_quickly() {
+    let ctx = TestContext::new().await;
+    let (email, token) = create_user_and_get_reset_token(&ctx).await;
+
+    let (token_hash, minutes): (String, i64) = sqlx::query_as(
+        "SELECT pr.token_hash, TIMESTAMPDIFF(MINUTE, pr.created_at, pr.expires_at)
+         FROM password_resets pr JOIN users u ON pr.user_id = u.id
+         WHERE u.email = ?",
+    )
+    .bind(&email)
+    .fetch_one(&ctx.db)
+    .await
+    .unwrap();
+    assert_ne!(token_hash, token);
+    assert!(!token_hash.contains(&token));
+    assert!(minutes <= 15, "reset tokens should live 15 minutes, got {}", minutes);
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn reset_email_names_the_requesting_device() {
+    let ctx = TestContext::new().await;
+    let email = test_email();
+    ctx.server
+        .post("/auth/register")
+        .json(&json!({ "email": &email, "password": test_password(), "password_confirm": test_password() }))
+        .await;
+
+    ctx.server
+        .post("/auth/forgot-password")
+        .add_header("User-Agent", "ResetTest/1.0 (X11; Linux)")
+        .json(&json!({ "email": &email }))
+        .await
+        .assert_status_ok();
+
+    let body = latest_email(&ctx, &email).await.unwrap();
+    assert!(body.contains("Device: ResetTest/1.0 (X11; Linux)"), "{}", body);
+    assert!(body.contains("IP address: "), "{}", body);
+    assert!(body.contains("expires in 15 minutes"), "{}", body);
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn new_reset_request_retires_the_previous_token() {
+    let ctx = TestContext::new().await;
+    let (email, first) = create_user_and_get_reset_token(&ctx).await;
+
+    // Asking again straight away doesn't send another email
+    ctx.server
+        .post("/auth/forgot-password")
+        .json(&json!({ "email": &email }))
+        .await
+        .assert_status_ok();
+    assert_eq!(reset_count(&ctx, &email).await, 1);
+
+    // After the resend window a new token replaces the first
+    sqlx::query(
+        "UPDATE password_resets pr JOIN users u ON pr.user_id = u.id
+         SET pr.created_at = DATE_SUB(pr.created_at, INTERVAL 2 MINUTE)
+         WHERE u.email = ?",
+    )
+    .bind(&email)
+    .execute(&ctx.db)
+    .await
+    .unwrap();
+    sqlx::query("DELETE FROM email_outbox WHERE recipient_hash = ?")
+        .bind(recipient_hash(&email))
+        .execute(&ctx.db)
+        .await
+        .unwrap();
+    ctx.server
+        .post("/auth/forgot-password")
+        .json(&json!({ "email": &email }))
+        .await
+        .assert_status_ok();
+    let second = token_in(&latest_email(&ctx, &email).await.unwrap());
+    assert_ne!(first, second);
+
+    let active: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM password_resets pr JOIN users u ON pr.user_id = u.id
+         WHERE u.email = ? AND pr.used = false",
+    )
+    .bind(&email)
+    .fetch_one(&ctx.db)
+    .await
+    .unwrap();
+    assert_eq!(active, 1);
+
+    let reset = |token: String| {
+        ctx.server.post("/auth/reset-password").json(&json!({
+            "token": token,
+            "password": "NewPassword123!",
+            "password_confirm": "NewPassword123!"
+        }))
+    };
+    reset(first).await.assert_status(StatusCode::BAD_REQUEST);
+    reset(second).await.assert_status_ok();
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn reset_password_ends_existing_sessions() {
+    let ctx = TestContext::new().await;
+    let (email, token) = create_user_and_get_reset_token(&ctx).await;
+
+    let login: serde_json::Value = ctx
+        .server
+        .post("/auth/login")
+        .json(&json!({ "email": &email, "password": test_password() }))
+        .await
+        .json();
+    let access_token = login["access_token"].as_str().unwrap().to_string();
+
+    ctx.server
+        .post("/auth/reset-password")
+        .json(&json!({
+            "token": &token,
+            "password": "NewPassword123!",
+            "password_confirm": "NewPassword123!"
+        }))
+        .await
+        .assert_status_ok();
+
+    ctx.server
+        .get("/auth/oauth/identities")
+        .authorization_bearer(&access_token)
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+
+    // Tokens issued after the reset work, even within the same second
+    let login: serde_json::Value = ctx
+        .server
+        .post("/auth/login")
+        .json(&json!({ "email": &email, "password": "NewPassword123!" }))
+        .await
+        .json();
+    ctx.server
+        .get("/auth/oauth/identities")
+        .authorization_bearer(login["access_token"].as_str().unwrap())
+        .await
+        .assert_status_ok();
+
+    ctx.cleanup().await;
+}
@@ -0,0 +1,124 @@
+use std::time::Duration;
+use uuid::Uuid;
+
+use exchange_shared::services::retention::{AuthRetentionSweeper, RetentionPolicy};
+
+use crate::common::{create_user, test_email, TestContext};
+
+/// Insert a refresh token expiring `expires_in_hours` from now (negative for past)
+async fn insert_refresh_token(ctx: &TestContext, user_id: &str, expires_in_hours: i64, revoked: bool, age_hours: i64) -> String {
+    let id = Uuid::new_v4().to_string();
+    sqlx::query(
+        "INSERT INTO refresh_tokens (id, user_id, token_hash, expires_at, revoked, created_at)
+         VALUES (?, ?, ?, DATE_ADD(NOW(), INTERVAL ? HOUR), ?, DATE_SUB(NOW(), INTERVAL ? HOUR))",
+    )
+    .bind(&id)
+    .bind(user_id)
+    .bind(format!("hash-{}", id))
+    .bind(expires_in_hours)
+    .bind(revoked)
+    .bind(age_hours)
+    .execute(&ctx.db)
+    .await
+    .unwrap();
+    id
+}
+
+async fn insert_token_row(ctx: &TestContext, table: &str, user_id: &str, expires_in_hours: i64) -> String {
+    let id = Uuid::new_v4().to_string();
+    sqlx::query(&format!(
+        "INSERT INTO {} (id, user_id, {}, expires_at) VALUES (?, ?, ?, DATE_ADD(NOW(), INTERVAL ? HOUR))",
+        table,
+        token_column(table)
+    ))
+    .bind(&id)
+    .bind(user_id)
+    .bind(format!("token-{}", id))
+    .bind(expires_in_hours)
+    .execute(&ctx.db)
+    .await
+    .unwrap();
+    id
+}
+
+/// password_resets only keeps a hash of its token
+fn token_column(table: &str) -> &'static str {
+    if table == "password_resets" { "token_hash" } else { "token" }
+}
+
+async fn secret(ctx: &TestContext, table: &str, column: &str, id: &str) -> Option<String> {
+    sqlx::query_scalar(&format!("SELECT {} FROM {} WHERE id = ?", column, table))
+        .bind(id)
+        .fetch_optional(&ctx.db)
+        .await
+        .unwrap()
+}
+
+fn policy() -> RetentionPolicy {
+    RetentionPolicy {
+        email_verifications: Duration::from_secs(24 * 3600),
+        password_resets: Duration::from_secs(24 * 3600),
+        refresh_tokens: Duration::from_secs(24 * 3600),
+        ..RetentionPolicy::default()
+    }
+}
+
+#[tokio::test]
+async fn test_retention_sweep_purges_only_past_retention() {
+    let ctx = TestContext::new().await;
+    let (user_id, _) = create_user(&ctx, &test_email()).await;
+
+    let live = insert_refresh_token(&ctx, &user_id, 24, false, 0).await;
+    let recently_expired = insert_refresh_token(&ctx, &user_id, -2, false, 48).await;
+    let long_expired = insert_refresh_token(&ctx, &user_id, -48, false, 96).await;
+    let revoked_recent = insert_refresh_token(&ctx, &user_id, 24, true, 2).await;
+    let revoked_old = insert_refresh_token(&ctx, &user_id, 24, true, 48).await;
+
+    let live_reset = insert_token_row(&ctx, "password_resets", &user_id, 1).await;
+    let old_reset = insert_token_row(&ctx, "password_resets", &user_id, -48).await;
+    let live_verification = insert_token_row(&ctx, "email_verifications", &user_id, 1).await;
+    let old_verification = insert_token_row(&ctx, "email_verifications", &user_id, -48).await;
+
+    let report = AuthRetentionSweeper::new(ctx.db.clone(), policy()).sweep().await.unwrap();
+    assert!(!report.is_empty());
+
+    // Still redeemable: untouched
+    assert_eq!(secret(&ctx, "refresh_tokens", "token_hash", &live).await, Some(format!("hash-{}", live)));
+    assert_eq!(secret(&ctx, "password_resets", "token_hash", &live_reset).await, Some(format!("token-{}", live_reset)));
+    assert_eq!(
+        secret(&ctx, "email_verifications", "token", &live_verification).await,
+        Some(format!("token-{}", live_verification))
+    );
+
+    // Dead but inside the retention window: kept, secret scrubbed
+    for id in [&recently_expired, &revoked_recent] {
+        let value = secret(&ctx, "refresh_tokens", "token_hash", id).await.expect("row should be kept");
+        assert_eq!(value, format!("scrubbed:{}", id));
+    }
+
+    // Past retention: gone
+    assert!(secret(&ctx, "refresh_tokens", "token_hash", &long_expired).await.is_none());
+    assert!(secret(&ctx, "refresh_tokens", "token_hash", &revoked_old).await.is_none());
+    assert!(secret(&ctx, "password_resets", "token_hash", &old_reset).await.is_none());
+    assert!(secret(&ctx, "email_verifications", "token", &old_verification).await.is_none());
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_retention_sweep_is_idempotent() {
+    let ctx = TestContext::new().await;
+    let (user_id, _) = create_user(&ctx, &test_email()).await;
+    let expired = insert_refresh_token(&ctx, &user_id, -2, false, 4).await;
+
+    let sweeper = AuthRetentionSweeper::new(ctx.db.clone(), policy());
+    sweeper.sweep().await.unwrap();
+    sweeper.sweep().await.unwrap();
+
+    assert_eq!(
+        secret(&ctx, "refresh_tokens", "token_hash", &expired).await,
+        Some(format!("scrubbed:{}", expired))
+    );
+
+    ctx.cleanup().await;
+}
@@ -0,0 +1,91 @@
+use axum::http::{Method, StatusCode};
+use exchange_shared::manifest::{mounted_routes, route_manifest, routes::routes, AuthRequirement, RouteSpec};
+use std::collections::BTreeSet;
+
+use crate::common::{create_test_user, test_password, TestContext};
+
+/// Declared routes needing at least `requirement`, with their path params filled in
+fn protected_routes(requirement: AuthRequirement) -> Vec<(Method, String, RouteSpec)> {
+    route_manifest()
+        .routes
+        .into_iter()
+        .filter(|route| match requirement {
+            AuthRequirement::Admin => route.auth == AuthRequirement::Admin,
+            _ => matches!(route.auth, AuthRequirement::User | AuthRequirement::Admin),
+        })
+        .map(|route| {
+            let method = Method::from_bytes(route.method.as_bytes()).unwrap();
+            let mut path = route.path.clone();
+            for param in &route.path_params {
+                path = path.replace(&format!("{{{}}}", param), "test");
+            }
+            (method, path, route)
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn declared_routes_refuse_requests_without_a_token() {
+    let ctx = TestContext::new().await;
+
+    for (method, path, route) in protected_routes(AuthRequirement::User) {
+        let response = ctx.server.method(method, &path).await;
+        assert_eq!(
+            response.status_code(),
+            StatusCode::UNAUTHORIZED,
+            "{} {} ({}) answered without a token",
+            route.method,
+            route.path,
+            route.name
+        );
+    }
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn declared_admin_routes_refuse_other_users() {
+    let ctx = TestContext::new().await;
+    let (_, token) = create_test_user(&ctx.server, "route-auth@example.com", test_password()).await;
+
+    for (method, path, route) in protected_routes(AuthRequirement::Admin) {
+        let response = ctx.server.method(method, &path).authorization_bearer(&token).await;
+        assert_eq!(
+            response.status_code(),
+            StatusCode::FORBIDDEN,
+            "{} {} ({}) answered a non-admin",
+            route.method,
+            route.path,
+            route.name
+        );
+    }
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn mounted_routes_match_the_manifest() {
+    let ctx = TestContext::new().await;
+
+    let declared: BTreeSet<&str> = routes().iter().map(|route| route.name()).collect();
+    assert_eq!(mounted_routes(), declared);
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn undeclared_routes_are_refused() {
+    let ctx = TestContext::new().await;
+
+    let response = ctx.server.get("/v1/nowhere").await;
+    assert_eq!(response.status_code(), StatusCode::NOT_FOUND);
+    // A declared path under a method it isn't declared for
+    let response = ctx.server.delete("/v1/swap/rates").await;
+    assert_eq!(response.status_code(), StatusCode::NOT_FOUND);
+
+    // Unversioned paths are still served
+    let response = ctx.server.get("/health").await;
+    assert_eq!(response.status_code(), StatusCode::OK);
+
+    ctx.cleanup().await;
+}
@@ -0,0 +1,113 @@
+use axum::http::StatusCode;
+use serde_json::json;
+
+use crate::common::{test_email, test_password, TestContext};
+
+async fn login_with_scope(ctx: &TestContext, scope: Option<&str>) -> serde_json::Value {
+    let email = test_email();
+    ctx.server
+        .post("/auth/register")
+        .json(&json!({
+            "email": &email,
+            "password": test_password(),
+            "password_confirm": test_password()
+        }))
+        .await;
+
+    let mut body = json!({ "email": &email, "password": test_password() });
+    if let Some(scope) = scope {
+        body["scope"] = json!(scope);
+    }
+    let response = ctx.server.post("/auth/login").json(&body).await;
+    response.assert_status(StatusCode::OK);
+    response.json()
+}
+
+fn token(body: &serde_json::Value) -> String {
+    body["access_token"].as_str().unwrap().to_string()
+}
+
+#[tokio::test]
+async fn login_without_scope_grants_every_scope() {
+    let ctx = TestContext::new().await;
+    let body = login_with_scope(&ctx, None).await;
+
+    assert_eq!(body["scope"], "swap:create history:read account:manage");
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn login_with_unknown_scope_is_rejected() {
+    let ctx = TestContext::new().await;
+
+    let response = ctx
+        .server
+        .post("/auth/login")
+        .json(&json!({
+            "email": test_email(),
+            "password": test_password(),
+            "scope": "history:read wallet:drain"
+        }))
+        .await;
+
+    response.assert_status(StatusCode::BAD_REQUEST);
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn scoped_token_only_reaches_its_routes() {
+    let ctx = TestContext::new().await;
+    let body = login_with_scope(&ctx, Some("history:read")).await;
+    assert_eq!(body["scope"], "history:read");
+    let token = token(&body);
+
+    let history = ctx.server.get("/swap/history").authorization_bearer(&token).await;
+    history.assert_status(StatusCode::OK);
+
+    let addresses = ctx.server.get("/address-book").authorization_bearer(&token).await;
+    addresses.assert_status(StatusCode::FORBIDDEN);
+
+    // Routes without a scope need a full-access token
+    let balances = ctx.server.get("/balances").authorization_bearer(&token).await;
+    balances.assert_status(StatusCode::FORBIDDEN);
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn downscoped_token_cannot_widen_its_scopes() {
+    let ctx = TestContext::new().await;
+    let body = login_with_scope(&ctx, None).await;
+
+    let response = ctx
+        .server
+        .post("/auth/token/downscope")
+        .authorization_bearer(token(&body))
+        .json(&json!({ "scope": "history:read", "client": "tax-tool", "expires_in": 600 }))
+        .await;
+    response.assert_status(StatusCode::OK);
+    let downscoped: serde_json::Value = response.json();
+    assert_eq!(downscoped["scope"], "history:read");
+    assert_eq!(downscoped["client"], "tax-tool");
+    assert_eq!(downscoped["expires_in"], 600);
+
+    let escalate = ctx
+        .server
+        .post("/auth/token/downscope")
+        .authorization_bearer(token(&downscoped))
+        .json(&json!({ "scope": "history:read swap:create", "client": "tax-tool" }))
+        .await;
+    escalate.assert_status(StatusCode::FORBIDDEN);
+
+    let orders = ctx
+        .server
+        .post("/swap/orders")
+        .authorization_bearer(token(&downscoped))
+        .json(&json!({}))
+        .await;
+    orders.assert_status(StatusCode::FORBIDDEN);
+
+    ctx.cleanup().await;
+}
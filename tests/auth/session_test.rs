@@ -0,0 +1,41 @@
+use axum::http::StatusCode;
+use serde_json::json;
+
+use crate::common::{test_email, test_password, TestContext};
+
+#[tokio::test]
+async fn cookie_session_login_is_disabled_by_default() {
+    let ctx = TestContext::new().await;
+
+    let response = ctx
+        .server
+        .post("/auth/session")
+        .json(&json!({
+            "email": test_email(),
+            "password": test_password()
+        }))
+        .await;
+
+    response.assert_status(StatusCode::NOT_FOUND);
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn bearer_requests_ignore_csrf_checks() {
+    let ctx = TestContext::new().await;
+
+    // A cookie-less request with no CSRF header still reaches the handler
+    let response = ctx
+        .server
+        .post("/auth/login")
+        .json(&json!({
+            "email": test_email(),
+            "password": "WrongPassword123!"
+        }))
+        .await;
+
+    response.assert_status(StatusCode::UNAUTHORIZED);
+
+    ctx.cleanup().await;
+}
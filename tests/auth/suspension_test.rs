@@ -0,0 +1,199 @@
+use axum::http::StatusCode;
+use serde_json::{json, Value};
+use serial_test::serial;
+
+use exchange_shared::services::address_reputation::{address_hash, AddressReputationService};
+
+use crate::common::{create_admin, create_user, test_email, test_password, TestContext};
+
+#[serial]
+#[tokio::test]
+async fn test_suspended_account_is_refused_until_reinstated() {
+    let ctx = TestContext::new().await;
+    let email = test_email();
+    let (user_id, token) = create_user(&ctx, &email).await;
+    let admin = create_admin(&ctx).await;
+
+    // A restriction needs a reason
+    let response = ctx
+        .server
+        .put(&format!("/admin/users/{}/status", user_id))
+        .authorization_bearer(&admin)
+        .json(&json!({ "status": "suspended" }))
+        .await;
+    response.assert_status(StatusCode::BAD_REQUEST);
+
+    let response = ctx
+        .server
+        .put(&format!("/admin/users/{}/status", user_id))
+        .authorization_bearer(&admin)
+        .json(&json!({ "status": "suspended", "reason": "Repeated chargebacks", "duration_hours": 24 }))
+        .await;
+    response.assert_status(StatusCode::OK);
+    let status: Value = response.json();
+    assert_eq!(status["status"], "suspended");
+    assert_eq!(status["events"][0]["source"], "admin");
+
+    // Tokens issued before the suspension stop working
+    let response = ctx.server.get("/swap/history").authorization_bearer(&token).await;
+    response.assert_status(StatusCode::FORBIDDEN);
+    let body: Value = response.json();
+    assert_eq!(body["status"], "suspended");
+    assert_eq!(body["reason"], "Repeated chargebacks");
+    assert!(body["suspended_until"].is_string());
+
+    // And no new ones are issued
+    let response = ctx
+        .server
+        .post("/auth/login")
+        .json(&json!({ "email": &email, "password": test_password() }))
+        .await;
+    response.assert_status(StatusCode::FORBIDDEN);
+    assert_eq!(response.json::<Value>()["message"], "Repeated chargebacks");
+
+    let response = ctx
+        .server
+        .put(&format!("/admin/users/{}/status", user_id))
+        .authorization_bearer(&admin)
+        .json(&json!({ "status": "active" }))
+        .await;
+    response.assert_status(StatusCode::OK);
+    assert_eq!(response.json::<Value>()["events"].as_array().unwrap().len(), 2);
+
+    let response = ctx.server.get("/swap/history").authorization_bearer(&token).await;
+    response.assert_status(StatusCode::OK);
+
+    ctx.cleanup().await;
+}
+
+#[serial]
+#[tokio::test]
+async fn test_chargebacks_suspend_automatically() {
+    let ctx = TestContext::new().await;
+    let (user_id, token) = create_user(&ctx, &test_email()).await;
+    let admin = create_admin(&ctx).await;
+    let report = || {
+        ctx.server
+            .post(&format!("/admin/users/{}/risk-signals", user_id))
+            .authorization_bearer(&admin)
+            .json(&json!({ "kind": "chargeback", "detail": "Card payment reversed" }))
+    };
+
+    let response = report().await;
+    response.assert_status(StatusCode::CREATED);
+    let first: Value = response.json();
+    assert!(first.get("applied").is_none());
+
+    let response = ctx.server.get("/swap/history").authorization_bearer(&token).await;
+    response.assert_status(StatusCode::OK);
+
+    // With the default weights a second chargeback crosses the threshold
+    let response = report().await;
+    response.assert_status(StatusCode::CREATED);
+    let second: Value = response.json();
+    assert_eq!(second["applied"], "suspended");
+    assert!(second["risk_score"].as_u64().unwrap() > first["risk_score"].as_u64().unwrap());
+
+    let response = ctx.server.get("/swap/history").authorization_bearer(&token).await;
+    response.assert_status(StatusCode::FORBIDDEN);
+
+    let response = ctx
+        .server
+        .get(&format!("/admin/users/{}/status", user_id))
+        .authorization_bearer(&admin)
+        .await;
+    response.assert_status(StatusCode::OK);
+    let status: Value = response.json();
+    assert_eq!(status["status"], "suspended");
+    assert_eq!(status["events"][0]["source"], "risk");
+    assert_eq!(status["signals"].as_array().unwrap().len(), 2);
+
+    ctx.cleanup().await;
+}
+
+#[serial]
+#[tokio::test]
+async fn test_shared_payout_address_is_flagged_and_escalated() {
+    let ctx = TestContext::new().await;
+    let admin = create_admin(&ctx).await;
+    let address = format!("0x{}", uuid::Uuid::new_v4().simple());
+    let hash = address_hash("ethereum", &address);
+    let reputation = AddressReputationService::new(ctx.db.clone());
+
+    let mut user_ids = Vec::new();
+    for _ in 0..5 {
+        let (user_id, _) = create_user(&ctx, &test_email()).await;
+        let swap_id = uuid::Uuid::new_v4().to_string();
+        sqlx::query(
+            "INSERT INTO swaps (
+                id, user_id, provider_id, from_currency, from_network, to_currency, to_network, amount,
+                estimated_receive, rate, deposit_address, recipient_address, status, rate_type,
+                platform_fee, total_fee, is_sandbox, created_at
+            ) VALUES (?, ?, 'changenow', 'btc', 'bitcoin', 'eth', 'ethereum', 0.1, 1.5, 15.0,
+                      'test_deposit', ?, 'waiting', 'floating', 0.01, 0.02, 1, NOW())",
+        )
+        .bind(&swap_id)
+        .bind(&user_id)
+        // Checksummed and lowercase forms are the same address
+        .bind(if user_ids.is_empty() { address.to_uppercase().replace("0X", "0x") } else { address.clone() })
+        .execute(&ctx.db)
+        .await
+        .unwrap();
+        reputation.record_swap(&swap_id).await.unwrap();
+        user_ids.push(user_id);
+
+        if user_ids.len() == 3 {
+            // Flagged for review, not yet reported
+            let response = ctx
+                .server
+                .get(&format!("/admin/address-reputation/{}", hash))
+                .authorization_bearer(&admin)
+                .await;
+            response.assert_status(StatusCode::OK);
+            let detail: Value = response.json();
+            assert_eq!(detail["status"], "flagged");
+            assert_eq!(detail["account_count"], 3);
+            assert!(detail["accounts"].as_array().unwrap().iter().all(|a| a.get("escalated_at").is_none()));
+        }
+    }
+
+    let response = ctx.server.get("/admin/address-reputation").authorization_bearer(&admin).await;
+    response.assert_status(StatusCode::OK);
+    let queue: Value = response.json();
+    assert!(queue["addresses"].as_array().unwrap().iter().any(|a| a["address_hash"] == hash.as_str()));
+
+    // Past the escalation score every account got a signal
+    for user_id in &user_ids {
+        let response = ctx
+            .server
+            .get(&format!("/admin/users/{}/status", user_id))
+            .authorization_bearer(&admin)
+            .await;
+        let status: Value = response.json();
+        let signals = status["signals"].as_array().unwrap();
+        assert_eq!(signals.iter().filter(|s| s["kind"] == "address_reuse").count(), 1);
+    }
+
+    // Confirming doesn't report anyone twice
+    let response = ctx
+        .server
+        .post(&format!("/admin/address-reputation/{}/confirm", hash))
+        .authorization_bearer(&admin)
+        .json(&json!({ "note": "Cash-out wallet" }))
+        .await;
+    response.assert_status(StatusCode::OK);
+    let detail: Value = response.json();
+    assert_eq!(detail["status"], "confirmed");
+    assert_eq!(detail["address"], address.as_str());
+    assert!(detail["accounts"].as_array().unwrap().iter().all(|a| a.get("escalated_at").is_some()));
+
+    let response = ctx
+        .server
+        .get(&format!("/admin/users/{}/status", user_ids[0]))
+        .authorization_bearer(&admin)
+        .await;
+    let status: Value = response.json();
+    assert_eq!(status["signals"].as_array().unwrap().len(), 1);
+
+    ctx.cleanup().await;
+}
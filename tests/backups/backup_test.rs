@@ -0,0 +1,153 @@
+use async_trait::async_trait;
+use exchange_shared::modules::backups::crud::BackupCrud;
+use exchange_shared::modules::jobs::crud::JobCrud;
+use exchange_shared::modules::jobs::model::{JobKind, JobStatus};
+use exchange_shared::services::backup::{restore, BackupCipher, BackupError, BackupService, SnapshotWriter};
+use exchange_shared::services::events::ops::{ops_events, OpsEvent};
+use exchange_shared::services::storage::{ObjectStorage, StorageError};
+use serde_json::Value;
+use serial_test::serial;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::common::{create_admin, TestContext};
+
+/// Keeps uploaded objects in memory, optionally handing back a flipped byte
+#[derive(Default)]
+struct MemoryStorage {
+    objects: Mutex<HashMap<String, Vec<u8>>>,
+    corrupt: bool,
+}
+
+#[async_trait]
+impl ObjectStorage for MemoryStorage {
+    async fn put_object(&self, key: &str, body: Vec<u8>, _content_type: &str) -> Result<(), StorageError> {
+        self.objects.lock().unwrap().insert(key.to_string(), body);
+        Ok(())
+    }
+
+    async fn get_object(&self, key: &str) -> Result<Vec<u8>, StorageError> {
+        let mut body = self
+            .objects
+            .lock()
+            .unwrap()
+            .get(key)
+            .cloned()
+            .ok_or_else(|| StorageError::Status { status: 404, body: "NoSuchKey".to_string() })?;
+        if self.corrupt {
+            let last = body.len() - 1;
+            body[last] ^= 0xff;
+        }
+        Ok(body)
+    }
+
+    async fn delete_object(&self, key: &str) -> Result<(), StorageError> {
+        self.objects.lock().unwrap().remove(key);
+        Ok(())
+    }
+
+    fn presigned_get_url(&self, key: &str, _expires_in: Duration) -> Result<String, StorageError> {
+        Ok(format!("memory://{}", key))
+    }
+}
+
+fn cipher() -> BackupCipher {
+    BackupCipher::new(&[7u8; 32]).unwrap()
+}
+
+#[tokio::test]
+#[serial]
+async fn test_backup_is_uploaded_verified_and_tracked() {
+    let ctx = TestContext::new().await;
+    let admin = create_admin(&ctx).await;
+    let storage = Arc::new(MemoryStorage::default());
+    let service = BackupService::new(ctx.db.clone(), storage.clone(), cipher(), "backups/");
+
+    let run = service.run().await.unwrap();
+    assert!(run.verified_at.is_some());
+    assert!(run.error.is_none());
+    assert_eq!(run.key_fingerprint, cipher().fingerprint());
+
+    // The stored object is sealed and decrypts to a snapshot matching the run
+    let key = run.object_key.clone().unwrap();
+    assert!(key.starts_with("backups/"));
+    assert!(key.ends_with(&format!("{}.ndjson.gz.enc", run.id)));
+    let sealed = storage.objects.lock().unwrap().get(&key).cloned().unwrap();
+    assert_eq!(run.size_bytes, Some(sealed.len() as u64));
+    assert!(BackupCipher::new(&[8u8; 32]).unwrap().open(&sealed).is_err());
+    let manifest = service.verify_object(&key, None).await.unwrap();
+    assert!(manifest.contains_key("swaps"));
+    assert!(manifest.contains_key("ledger_entries"));
+    assert!(manifest.contains_key("hd_derivation_paths"));
+
+    let job = JobCrud::new(ctx.db.clone()).get(&run.id, "", true).await.unwrap();
+    assert_eq!(job.kind, JobKind::Backup);
+    assert_eq!(job.status, JobStatus::Succeeded);
+    assert_eq!(job.progress, 100);
+
+    let response = ctx.server.get("/admin/backups").authorization_bearer(&admin).await;
+    response.assert_status_ok();
+    let body: Value = response.json();
+    let listed = body["runs"].as_array().unwrap().iter().find(|r| r["id"] == run.id.as_str()).cloned().unwrap();
+    assert_eq!(listed["status"], "verified");
+    assert_eq!(listed["object_key"], key.as_str());
+    assert!(listed["tables"]["swaps"]["rows"].is_u64());
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+#[serial]
+async fn test_unreadable_backup_fails_run_and_alerts() {
+    let ctx = TestContext::new().await;
+    let storage = Arc::new(MemoryStorage { corrupt: true, ..Default::default() });
+    let service = BackupService::new(ctx.db.clone(), storage, cipher(), "backups/");
+    let mut events = ops_events().subscribe();
+
+    let run_id = service.start().await.unwrap();
+    let result = service.complete(&run_id).await;
+    assert!(matches!(result, Err(BackupError::Verification(_))));
+
+    let run = BackupCrud::new(ctx.db.clone()).get_run(&run_id).await.unwrap().unwrap();
+    assert!(run.error.is_some());
+    assert!(run.finished_at.is_some());
+    assert!(run.verified_at.is_none());
+
+    let job = JobCrud::new(ctx.db.clone()).get(&run_id, "", true).await.unwrap();
+    assert_eq!(job.status, JobStatus::Failed);
+
+    // Operators are alerted on /ws/admin
+    loop {
+        let notification = tokio::time::timeout(Duration::from_secs(5), events.recv()).await.unwrap().unwrap();
+        if matches!(&notification.event, OpsEvent::BackupFailed { run_id: failed, .. } if *failed == run_id) {
+            break;
+        }
+    }
+
+    // A failed run doesn't block the next one
+    let next = service.start().await.unwrap();
+    assert_ne!(next, run_id);
+    let _ = service.complete(&next).await;
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+#[serial]
+async fn test_restore_rejects_unknown_tables_and_bad_snapshots() {
+    let ctx = TestContext::new().await;
+
+    let mut writer = SnapshotWriter::new(chrono::Utc::now()).unwrap();
+    writer.begin_table("no_such_table", &["id".to_string()], &[]).unwrap();
+    writer.write_row(&[Some("1".to_string())]).unwrap();
+    let (compressed, _) = writer.finish().unwrap();
+
+    let result = restore(&ctx.db, &compressed).await;
+    assert!(matches!(result, Err(BackupError::Restore(ref e)) if e.contains("does not exist")));
+
+    let result = restore(&ctx.db, b"not a snapshot").await;
+    assert!(matches!(result, Err(BackupError::Verification(_))));
+
+    ctx.cleanup().await;
+}
@@ -0,0 +1,4 @@
+mod common;
+mod backups {
+    pub mod backup_test;
+}
@@ -0,0 +1,287 @@
+use axum::http::StatusCode;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::common::{create_user, test_email, TestContext};
+use exchange_shared::modules::balances::crud::{has_sufficient_balance, validate_amount, BalanceCrud};
+use exchange_shared::services::amount::{self, Decimal};
+
+fn dec(s: &str) -> Decimal {
+    amount::parse(s).unwrap()
+}
+
+async fn opt_in(ctx: &TestContext, token: &str) {
+    ctx.server
+        .post("/balances/opt-in")
+        .authorization_bearer(token)
+        .await
+        .assert_status_ok();
+}
+
+/// Seed a balance directly, bypassing the ledger
+async fn seed_balance(ctx: &TestContext, email: &str, currency: &str, network: &str, amount: f64) {
+    sqlx::query(
+        r#"
+        INSERT INTO balance_accounts (id, user_id, currency, network, balance)
+        SELECT UUID(), id, ?, ?, ? FROM users WHERE email = ?
+        "#,
+    )
+    .bind(currency)
+    .bind(network)
+    .bind(amount)
+    .bind(email)
+    .execute(&ctx.db)
+    .await
+    .unwrap();
+}
+
+fn balance_of(body: &serde_json::Value, currency: &str) -> f64 {
+    body["balances"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|b| b["currency"] == currency)
+        .and_then(|b| b["balance"].as_f64())
+        .unwrap_or(0.0)
+}
+
+// =============================================================================
+// BALANCES
+// =============================================================================
+
+#[tokio::test]
+async fn balances_require_authentication() {
+    let ctx = TestContext::new().await;
+
+    ctx.server.get("/balances").await.assert_status(StatusCode::UNAUTHORIZED);
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn opt_in_enables_custody() {
+    let ctx = TestContext::new().await;
+    let (_, token) = create_user(&ctx, &test_email()).await;
+
+    let before: serde_json::Value = ctx.server.get("/balances").authorization_bearer(&token).await.json();
+    assert_eq!(before["custody_enabled"], false);
+
+    opt_in(&ctx, &token).await;
+
+    let after: serde_json::Value = ctx.server.get("/balances").authorization_bearer(&token).await.json();
+    assert_eq!(after["custody_enabled"], true);
+
+    ctx.cleanup().await;
+}
+
+// =============================================================================
+// TRANSFERS
+// =============================================================================
+
+#[tokio::test]
+async fn transfer_requires_custody() {
+    let ctx = TestContext::new().await;
+    let (_, sender) = create_user(&ctx, &test_email()).await;
+    let recipient_email = test_email();
+    create_user(&ctx, &recipient_email).await;
+
+    let response = ctx
+        .server
+        .post("/balances/transfers")
+        .authorization_bearer(&sender)
+        .json(&json!({ "to_email": recipient_email, "currency": "usdt", "network": "erc20", "amount": 1.0 }))
+        .await;
+
+    response.assert_status(StatusCode::FORBIDDEN);
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn transfer_moves_funds_between_users() {
+    let ctx = TestContext::new().await;
+    let sender_email = test_email();
+    let (_, sender) = create_user(&ctx, &sender_email).await;
+    let recipient_email = test_email();
+    let (_, recipient) = create_user(&ctx, &recipient_email).await;
+    opt_in(&ctx, &sender).await;
+    opt_in(&ctx, &recipient).await;
+    seed_balance(&ctx, &sender_email, "usdt", "erc20", 100.0).await;
+
+    let response = ctx
+        .server
+        .post("/balances/transfers")
+        .authorization_bearer(&sender)
+        .json(&json!({ "to_email": recipient_email, "currency": "usdt", "network": "erc20", "amount": 40.0 }))
+        .await;
+
+    response.assert_status(StatusCode::CREATED);
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["balance_after"].as_f64().unwrap(), 60.0);
+
+    let received: serde_json::Value = ctx.server.get("/balances").authorization_bearer(&recipient).await.json();
+    assert_eq!(balance_of(&received, "usdt"), 40.0);
+
+    let ledger: serde_json::Value = ctx.server.get("/balances/ledger").authorization_bearer(&recipient).await.json();
+    assert_eq!(ledger["entries"][0]["entry_type"], "transfer_in");
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn transfer_cannot_overdraw() {
+    let ctx = TestContext::new().await;
+    let sender_email = test_email();
+    let (_, sender) = create_user(&ctx, &sender_email).await;
+    let recipient_email = test_email();
+    let (_, recipient) = create_user(&ctx, &recipient_email).await;
+    opt_in(&ctx, &sender).await;
+    opt_in(&ctx, &recipient).await;
+    seed_balance(&ctx, &sender_email, "usdt", "erc20", 5.0).await;
+
+    let response = ctx
+        .server
+        .post("/balances/transfers")
+        .authorization_bearer(&sender)
+        .json(&json!({ "to_email": recipient_email, "currency": "usdt", "network": "erc20", "amount": 6.0 }))
+        .await;
+
+    response.assert_status(StatusCode::PAYMENT_REQUIRED);
+
+    ctx.cleanup().await;
+}
+
+// =============================================================================
+// WITHDRAWALS
+// =============================================================================
+
+#[tokio::test]
+async fn withdrawal_holds_funds_until_rejected() {
+    let ctx = TestContext::new().await;
+    let email = test_email();
+    let (_, token) = create_user(&ctx, &email).await;
+    let admin_email = test_email();
+    let (_, admin) = create_user(&ctx, &admin_email).await;
+    opt_in(&ctx, &token).await;
+    seed_balance(&ctx, &email, "eth", "ethereum", 1.0).await;
+    sqlx::query("UPDATE users SET is_admin = TRUE WHERE email = ?")
+        .bind(&admin_email)
+        .execute(&ctx.db)
+        .await
+        .unwrap();
+
+    let response = ctx
+        .server
+        .post("/balances/withdrawals")
+        .authorization_bearer(&token)
+        .json(&json!({
+            "currency": "eth",
+            "network": "ethereum",
+            "amount": 0.4,
+            "address": "0x742d35Cc6634C0532925a3b844Bc9e7595f0bEb0"
+        }))
+        .await;
+    response.assert_status(StatusCode::CREATED);
+    let withdrawal: serde_json::Value = response.json();
+    assert_eq!(withdrawal["status"], "pending_approval");
+
+    let held: serde_json::Value = ctx.server.get("/balances").authorization_bearer(&token).await.json();
+    assert!((balance_of(&held, "eth") - 0.6).abs() < 1e-9);
+
+    // Only admins can review withdrawals
+    let path = format!("/admin/withdrawals/{}/reject", withdrawal["id"].as_str().unwrap());
+    ctx.server
+        .post(&path)
+        .authorization_bearer(&token)
+        .json(&json!({}))
+        .await
+        .assert_status(StatusCode::FORBIDDEN);
+
+    let rejected = ctx
+        .server
+        .post(&path)
+        .authorization_bearer(&admin)
+        .json(&json!({ "reason": "Destination flagged" }))
+        .await;
+    rejected.assert_status_ok();
+    let rejected: serde_json::Value = rejected.json();
+    assert_eq!(rejected["status"], "rejected");
+
+    let released: serde_json::Value = ctx.server.get("/balances").authorization_bearer(&token).await.json();
+    assert!((balance_of(&released, "eth") - 1.0).abs() < 1e-9);
+
+    ctx.cleanup().await;
+}
+
+// =============================================================================
+// SWAP CREDITS
+// =============================================================================
+
+#[tokio::test]
+async fn swap_credit_uses_settled_amount() {
+    let ctx = TestContext::new().await;
+    let email = test_email();
+    let (_, token) = create_user(&ctx, &email).await;
+    opt_in(&ctx, &token).await;
+
+    // Quoted 1.0 ETH net of a 0.01 fee, but nothing has settled yet
+    let swap_id = Uuid::new_v4().to_string();
+    sqlx::query(
+        r#"
+        INSERT INTO swaps (
+            id, user_id, provider_id, from_currency, from_network, to_currency, to_network,
+            amount, estimated_receive, platform_fee, rate, deposit_address, recipient_address,
+            status, payout_mode
+        )
+        SELECT ?, id, 'changenow', 'btc', 'bitcoin', 'eth', 'ethereum',
+               0.05, 1.0, 0.01, 20.0, 'dep_addr', 'balance', 'sending', 'balance'
+        FROM users WHERE email = ?
+        "#,
+    )
+    .bind(&swap_id)
+    .bind(&email)
+    .execute(&ctx.db)
+    .await
+    .unwrap();
+
+    let crud = BalanceCrud::new(ctx.db.clone());
+    assert!(crud.credit_swap_proceeds(&swap_id).await.is_err());
+
+    // The provider delivered less than quoted
+    sqlx::query("UPDATE swaps SET actual_receive = 0.9 WHERE id = ?")
+        .bind(&swap_id)
+        .execute(&ctx.db)
+        .await
+        .unwrap();
+
+    assert!(crud.credit_swap_proceeds(&swap_id).await.unwrap());
+    assert!(!crud.credit_swap_proceeds(&swap_id).await.unwrap());
+
+    let body: serde_json::Value = ctx.server.get("/balances").authorization_bearer(&token).await.json();
+    assert!((balance_of(&body, "eth") - 0.89).abs() < 1e-9);
+
+    sqlx::query("DELETE FROM swaps WHERE id = ?").bind(&swap_id).execute(&ctx.db).await.ok();
+    ctx.cleanup().await;
+}
+
+// =============================================================================
+// AMOUNT RULES
+// =============================================================================
+
+#[test]
+fn amounts_must_be_positive_and_fit_the_ledger() {
+    assert!(validate_amount(dec("1.5")).is_ok());
+    assert!(validate_amount(dec("0.000000000000000001")).is_ok());
+    assert!(validate_amount(Decimal::ZERO).is_err());
+    assert!(validate_amount(dec("-1")).is_err());
+    // DECIMAL(36, 18) would round this away
+    assert!(validate_amount(dec("0.0000000000000000001")).is_err());
+}
+
+#[test]
+fn exact_balance_covers_debit() {
+    assert!(has_sufficient_balance(dec("10"), dec("10")));
+    assert!(has_sufficient_balance(dec("0.3"), dec("0.1") + dec("0.2")));
+    assert!(!has_sufficient_balance(dec("10"), dec("10.01")));
+    assert!(!has_sufficient_balance(dec("1"), dec("1.000000000000000001")));
+}
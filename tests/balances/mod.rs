@@ -0,0 +1 @@
+pub mod ledger_test;
@@ -0,0 +1,4 @@
+mod common;
+mod balances {
+    pub mod ledger_test;
+}
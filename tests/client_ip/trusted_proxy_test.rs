@@ -0,0 +1,86 @@
+use axum::http::StatusCode;
+use serde_json::{json, Value};
+use serial_test::serial;
+
+use crate::common::{test_email, test_password, TestContext};
+
+/// Served over a local socket so the peer address is 127.0.0.1. Runs in its
+/// own test binary, so the proxy list set here reaches no other tests.
+async fn context_trusting(proxies: &str) -> TestContext {
+    std::env::set_var("TRUSTED_PROXIES", proxies);
+    std::env::set_var("TRUSTED_PROXY_DEPTH", "1");
+    TestContext::over_http().await
+}
+
+/// Ask for a password reset claiming to come from `forwarded_for` and
+/// return the address the reset was recorded against
+async fn reset_requested_from(ctx: &TestContext, forwarded_for: &str) -> Option<String> {
+    let email = test_email();
+    let response = ctx
+        .server
+        .post("/auth/register")
+        .json(&json!({
+            "email": &email,
+            "password": test_password(),
+            "password_confirm": test_password()
+        }))
+        .await;
+    response.assert_status(StatusCode::CREATED);
+    let user_id = response.json::<Value>()["user"]["id"].as_str().unwrap().to_string();
+
+    ctx.server
+        .post("/auth/forgot-password")
+        .add_header("x-forwarded-for", forwarded_for)
+        .json(&json!({ "email": &email }))
+        .await
+        .assert_status_ok();
+
+    let (ip,): (Option<String>,) =
+        sqlx::query_as("SELECT requested_ip FROM password_resets WHERE user_id = ? ORDER BY created_at DESC LIMIT 1")
+            .bind(&user_id)
+            .fetch_one(&ctx.db)
+            .await
+            .unwrap();
+    ip
+}
+
+#[tokio::test]
+#[serial]
+async fn test_trusted_proxy_forwards_the_client_address() {
+    let ctx = context_trusting("127.0.0.1").await;
+
+    assert_eq!(reset_requested_from(&ctx, "203.0.113.7").await.as_deref(), Some("203.0.113.7"));
+
+    // Each forwarded client gets its own rate-limit bucket (burst of 10)
+    for _ in 0..10 {
+        ctx.server.get("/health").add_header("x-forwarded-for", "198.51.100.1").await.assert_status_ok();
+    }
+    ctx.server
+        .get("/health")
+        .add_header("x-forwarded-for", "198.51.100.1")
+        .await
+        .assert_status(StatusCode::TOO_MANY_REQUESTS);
+    ctx.server.get("/health").add_header("x-forwarded-for", "198.51.100.2").await.assert_status_ok();
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+#[serial]
+async fn test_untrusted_peer_cannot_spoof_its_address() {
+    let ctx = context_trusting("").await;
+
+    assert_eq!(reset_requested_from(&ctx, "203.0.113.7").await.as_deref(), Some("127.0.0.1"));
+
+    // Rotating the header doesn't buy a fresh bucket
+    for i in 1..=10 {
+        ctx.server.get("/health").add_header("x-forwarded-for", format!("198.51.100.{}", i)).await;
+    }
+    ctx.server
+        .get("/health")
+        .add_header("x-forwarded-for", "198.51.100.99")
+        .await
+        .assert_status(StatusCode::TOO_MANY_REQUESTS);
+
+    ctx.cleanup().await;
+}
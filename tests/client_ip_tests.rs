@@ -0,0 +1,4 @@
+mod common;
+mod client_ip {
+    pub mod trusted_proxy_test;
+}
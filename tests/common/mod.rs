@@ -1,7 +1,9 @@
 use axum_test::TestServer;
 use exchange_shared::services::redis_cache::RedisService;
+use exchange_shared::services::warmup::{Warmup, WarmupConfig};
 use sqlx::{MySql, Pool};
 use async_trait::async_trait;
+use exchange_shared::services::amount::Decimal;
 use exchange_shared::services::wallet::rpc::{BlockchainProvider, RpcError};
 
 pub mod rate_limiter;
@@ -17,6 +19,16 @@ pub struct TestContext {
 #[allow(dead_code)]
 impl TestContext {
     pub async fn new() -> Self {
+        Self::start(false).await
+    }
+
+    /// Serves the app on a local port instead of in memory, so handlers see
+    /// the peer address and WebSocket upgrades go through
+    pub async fn over_http() -> Self {
+        Self::start(true).await
+    }
+
+    async fn start(over_http: bool) -> Self {
         dotenvy::dotenv().ok();
 
         let database_url = std::env::var("TEST_DATABASE_URL")
@@ -44,8 +56,25 @@ impl TestContext {
         let wallet_mnemonic = std::env::var("WALLET_MNEMONIC")
             .unwrap_or_else(|_| "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about".to_string());
 
+        // Opt in with TEST_WARMUP=true to run the startup warm-up (tuned by
+        // the usual STARTUP_WARMUP_* variables) before the first request
+        if std::env::var("TEST_WARMUP").map(|v| v == "true").unwrap_or(false) {
+            let config = WarmupConfig::from_env().expect("Invalid startup warm-up configuration");
+            Warmup::new(db.clone(), redis_service.clone(), wallet_mnemonic.clone())
+                .with_config(config)
+                .run()
+                .await;
+        }
+
         let app = exchange_shared::create_app(db.clone(), redis_service.clone(), jwt_service, wallet_mnemonic).await;
-        let server = TestServer::new(app).expect("Failed to create test server");
+        let server = if over_http {
+            TestServer::builder()
+                .http_transport()
+                .build(app.into_make_service_with_connect_info::<std::net::SocketAddr>())
+        } else {
+            TestServer::new(app)
+        }
+        .expect("Failed to create test server");
 
         Self { server, db, redis: redis_service }
     }
@@ -140,7 +169,7 @@ impl BlockchainProvider for NoOpProvider {
     async fn get_transaction_count(&self, _address: &str) -> Result<u64, RpcError> { Ok(0) }
     async fn get_gas_price(&self) -> Result<u64, RpcError> { Ok(0) }
     async fn send_raw_transaction(&self, _signed_hex: &str) -> Result<String, RpcError> { Ok("".to_string()) }
-    async fn get_balance(&self, _address: &str) -> Result<f64, RpcError> { Ok(0.0) }
+    async fn get_balance(&self, _address: &str) -> Result<Decimal, RpcError> { Ok(Decimal::ZERO) }
 }
 
 
@@ -247,3 +276,88 @@ pub async fn create_test_swap(_server: &TestServer, user_id: &str, from: &str, t
     
     swap_id
 }
+
+// =============================================================================
+// FIXTURES
+// =============================================================================
+
+/// Register and log in a user with exactly this email, returning
+/// (user_id, access_token)
+#[allow(dead_code)]
+pub async fn create_user(ctx: &TestContext, email: &str) -> (String, String) {
+    use serde_json::{json, Value};
+
+    let response = ctx
+        .server
+        .post("/auth/register")
+        .json(&json!({
+            "email": email,
+            "password": test_password(),
+            "password_confirm": test_password()
+        }))
+        .await;
+    let user_id = response.json::<Value>()["user"]["id"].as_str().unwrap().to_string();
+
+    let response = ctx
+        .server
+        .post("/auth/login")
+        .json(&json!({ "email": email, "password": test_password() }))
+        .await;
+    (user_id, response.json::<Value>()["access_token"].as_str().unwrap().to_string())
+}
+
+/// Register an admin, returning (user_id, access_token)
+#[allow(dead_code)]
+pub async fn create_admin_user(ctx: &TestContext) -> (String, String) {
+    let (admin_id, token) = create_user(ctx, &test_email()).await;
+    sqlx::query("UPDATE users SET is_admin = TRUE WHERE id = ?")
+        .bind(&admin_id)
+        .execute(&ctx.db)
+        .await
+        .unwrap();
+    (admin_id, token)
+}
+
+/// Register an admin, returning its access token
+#[allow(dead_code)]
+pub async fn create_admin(ctx: &TestContext) -> String {
+    create_admin_user(ctx).await.1
+}
+
+/// A waiting BTC -> ETH swap for provider trade "trade-1", returning its id.
+/// Callers update the row for the case they test.
+#[allow(dead_code)]
+pub async fn insert_swap(ctx: &TestContext) -> String {
+    let swap_id = uuid::Uuid::new_v4().to_string();
+    sqlx::query(
+        r#"
+        INSERT INTO swaps (
+            id, provider_id, provider_swap_id, from_currency, from_network, to_currency, to_network,
+            amount, estimated_receive, rate, deposit_address, recipient_address, status, rate_type, is_sandbox
+        ) VALUES (?, 'changenow', 'trade-1', 'btc', 'bitcoin', 'eth', 'ethereum', 0.05, 1.0, 20.0,
+                  'bc1qdeposit', '0xrecipient', 'waiting', 'floating', 1)
+        "#,
+    )
+    .bind(&swap_id)
+    .execute(&ctx.db)
+    .await
+    .unwrap();
+    swap_id
+}
+
+/// A provider status change of `swap_id` to `status`, `minutes_ago`
+#[allow(dead_code)]
+pub async fn insert_status_history(ctx: &TestContext, swap_id: &str, status: &str, minutes_ago: i64) {
+    sqlx::query(
+        r#"
+        INSERT INTO swap_status_history (swap_id, status, actor, created_at)
+        VALUES (?, ?, 'provider', NOW() - INTERVAL ? MINUTE)
+        "#,
+    )
+    .bind(swap_id)
+    .bind(status)
+    .bind(minutes_ago)
+    .execute(&ctx.db)
+    .await
+    .unwrap();
+}
@@ -0,0 +1,119 @@
+use async_trait::async_trait;
+use axum::http::StatusCode;
+use exchange_shared::modules::email::crud::{EmailCrud, EmailError};
+use exchange_shared::modules::email::model::{OutboxStatus, SuppressionReason};
+use exchange_shared::services::email::{EmailMessage, EmailOutboxWorker, EmailSender};
+use serde_json::json;
+use std::sync::{Arc, Mutex};
+
+use crate::common::{test_email, test_password, TestContext};
+
+/// Records the recipients it was asked to deliver to
+#[derive(Default)]
+struct RecordingSender {
+    sent: Mutex<Vec<String>>,
+}
+
+#[async_trait]
+impl EmailSender for RecordingSender {
+    async fn send(&self, message: &EmailMessage) -> Result<(), String> {
+        self.sent.lock().unwrap().push(message.to.clone());
+        Ok(())
+    }
+}
+
+fn message(to: &str) -> EmailMessage {
+    EmailMessage {
+        to: to.to_string(),
+        subject: "Your swap is complete".to_string(),
+        text: "Details inside".to_string(),
+    }
+}
+
+#[tokio::test]
+async fn queued_email_is_delivered_and_body_cleared() {
+    let ctx = TestContext::new().await;
+    let crud = EmailCrud::new(ctx.db.clone());
+    let to = test_email();
+
+    let id = crud.enqueue(&message(&to)).await.unwrap();
+    let queued = crud.get(&id).await.unwrap().unwrap();
+    assert_eq!(queued.status, OutboxStatus::Pending);
+    assert_eq!(queued.body, "Details inside");
+
+    let sender = Arc::new(RecordingSender::default());
+    let worker = EmailOutboxWorker::new(ctx.db.clone(), sender.clone());
+    worker.process().await.unwrap();
+
+    assert!(sender.sent.lock().unwrap().contains(&to));
+    let sent = crud.get(&id).await.unwrap().unwrap();
+    assert_eq!(sent.status, OutboxStatus::Sent);
+    assert_eq!(sent.attempts, 1);
+    assert!(sent.body.is_empty());
+    assert!(sent.sent_at.is_some());
+}
+
+#[tokio::test]
+async fn suppressed_addresses_are_not_mailed() {
+    let ctx = TestContext::new().await;
+    let crud = EmailCrud::new(ctx.db.clone());
+    let to = test_email();
+
+    let pending = crud.enqueue(&message(&to)).await.unwrap();
+    assert!(crud.suppress(&to.to_uppercase(), SuppressionReason::Bounce, Some("Unknown user")).await.unwrap());
+    assert!(!crud.suppress(&to, SuppressionReason::Complaint, None).await.unwrap());
+
+    // Mail already queued is dropped, new mail is refused
+    assert_eq!(crud.get(&pending).await.unwrap().unwrap().status, OutboxStatus::Suppressed);
+    assert!(matches!(crud.enqueue(&message(&to)).await, Err(EmailError::Suppressed)));
+}
+
+#[tokio::test]
+async fn anti_phishing_code_is_embedded_in_queued_email() {
+    let ctx = TestContext::new().await;
+    let email = test_email();
+    ctx.server
+        .post("/auth/register")
+        .json(&json!({ "email": &email, "password": test_password(), "password_confirm": test_password() }))
+        .await;
+    let login: serde_json::Value = ctx
+        .server
+        .post("/auth/login")
+        .json(&json!({ "email": &email, "password": test_password() }))
+        .await
+        .json();
+    let token = login["access_token"].as_str().unwrap().to_string();
+
+    ctx.server
+        .put("/account/anti-phishing")
+        .authorization_bearer(&token)
+        .json(&json!({ "code": "no spaces" }))
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+
+    let response = ctx
+        .server
+        .put("/account/anti-phishing")
+        .authorization_bearer(&token)
+        .json(&json!({ "code": "Blue42" }))
+        .await;
+    response.assert_status_ok();
+    assert_eq!(response.json::<serde_json::Value>()["code"], "Blue42");
+
+    let crud = EmailCrud::new(ctx.db.clone());
+    let id = crud.enqueue(&message(&email)).await.unwrap();
+    let queued = crud.get(&id).await.unwrap().unwrap();
+    assert!(queued.body.starts_with("Your anti-phishing code: Blue42"));
+    assert!(queued.body.ends_with("Details inside"));
+
+    let rotated: serde_json::Value = ctx
+        .server
+        .post("/account/anti-phishing/rotate")
+        .authorization_bearer(&token)
+        .await
+        .json();
+    assert_ne!(rotated["code"], "Blue42");
+    assert_eq!(rotated["code"].as_str().unwrap().len(), 8);
+
+    ctx.cleanup().await;
+}
@@ -0,0 +1,4 @@
+mod common;
+mod email {
+    pub mod outbox_test;
+}
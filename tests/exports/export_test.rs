@@ -0,0 +1,160 @@
+use async_trait::async_trait;
+use axum::http::StatusCode;
+use exchange_shared::modules::exports::crud::ExportCrud;
+use exchange_shared::modules::exports::model::{ExportKind, ExportStatus};
+use exchange_shared::modules::exports::schema::CreateExportRequest;
+use exchange_shared::modules::jobs::crud::JobCrud;
+use exchange_shared::modules::jobs::model::{JobKind, JobStatus};
+use exchange_shared::services::exports::ExportWorker;
+use exchange_shared::services::storage::{ObjectStorage, StorageError};
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::common::{create_user, test_email, TestContext};
+
+/// Keeps uploaded objects in memory
+#[derive(Default)]
+struct MemoryStorage {
+    objects: Mutex<HashMap<String, Vec<u8>>>,
+}
+
+#[async_trait]
+impl ObjectStorage for MemoryStorage {
+    async fn put_object(&self, key: &str, body: Vec<u8>, _content_type: &str) -> Result<(), StorageError> {
+        self.objects.lock().unwrap().insert(key.to_string(), body);
+        Ok(())
+    }
+
+    async fn get_object(&self, key: &str) -> Result<Vec<u8>, StorageError> {
+        self.objects
+            .lock()
+            .unwrap()
+            .get(key)
+            .cloned()
+            .ok_or_else(|| StorageError::Status { status: 404, body: "NoSuchKey".to_string() })
+    }
+
+    async fn delete_object(&self, key: &str) -> Result<(), StorageError> {
+        self.objects.lock().unwrap().remove(key);
+        Ok(())
+    }
+
+    fn presigned_get_url(&self, key: &str, _expires_in: Duration) -> Result<String, StorageError> {
+        Ok(format!("memory://{}", key))
+    }
+}
+
+#[tokio::test]
+async fn exports_require_authentication() {
+    let ctx = TestContext::new().await;
+
+    ctx.server
+        .post("/exports")
+        .json(&json!({ "kind": "swaps" }))
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn worker_uploads_swap_history_csv() {
+    let ctx = TestContext::new().await;
+    let (user_id, _) = create_user(&ctx, &test_email()).await;
+
+    let swap_id = uuid::Uuid::new_v4().to_string();
+    sqlx::query(
+        "INSERT INTO swaps (
+            id, user_id, provider_id, from_currency, from_network,
+            to_currency, to_network, amount, estimated_receive, rate,
+            deposit_address, recipient_address, status, rate_type,
+            platform_fee, total_fee, is_sandbox, created_at
+        ) VALUES (?, ?, 'changenow', 'btc', 'bitcoin', 'eth', 'ethereum', 0.1, 1.5, 15.0,
+                  'bc1qdeposit', '=cmd|export', 'completed', 'floating', 0.01, 0.02, 1, NOW())",
+    )
+    .bind(&swap_id)
+    .bind(&user_id)
+    .execute(&ctx.db)
+    .await
+    .expect("Failed to insert swap");
+
+    let crud = ExportCrud::new(ctx.db.clone());
+    let job = crud
+        .create(&user_id, &CreateExportRequest { kind: ExportKind::Swaps, from: None, to: None })
+        .await
+        .unwrap();
+    assert_eq!(job.status, ExportStatus::Pending);
+
+    let storage = Arc::new(MemoryStorage::default());
+    let worker = ExportWorker::new(ctx.db.clone(), storage.clone());
+    let report = worker.process().await.unwrap();
+    assert!(report.completed >= 1);
+
+    let job = crud.get(&user_id, &job.id).await.unwrap();
+    assert_eq!(job.status, ExportStatus::Completed);
+    assert_eq!(job.row_count, Some(1));
+    assert!(job.expires_at.is_some());
+
+    let object = storage.objects.lock().unwrap()[job.object_key.as_ref().unwrap()].clone();
+    let csv = String::from_utf8(object).unwrap();
+    assert!(csv.starts_with("swap_id,created_at"));
+    assert!(csv.contains(&swap_id));
+    // Formula-looking cells are neutralized
+    assert!(csv.contains("'=cmd|export"));
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn rejects_inverted_date_range() {
+    let ctx = TestContext::new().await;
+    let (user_id, _) = create_user(&ctx, &test_email()).await;
+    let now = chrono::Utc::now();
+
+    let result = ExportCrud::new(ctx.db.clone())
+        .create(
+            &user_id,
+            &CreateExportRequest {
+                kind: ExportKind::Ledger,
+                from: Some(now),
+                to: Some(now - chrono::Duration::days(1)),
+            },
+        )
+        .await;
+    assert!(result.is_err());
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn export_is_tracked_as_a_job() {
+    let ctx = TestContext::new().await;
+    let (user_id, _) = create_user(&ctx, &test_email()).await;
+    let jobs = JobCrud::new(ctx.db.clone());
+
+    let export = ExportCrud::new(ctx.db.clone())
+        .create(&user_id, &CreateExportRequest { kind: ExportKind::Ledger, from: None, to: None })
+        .await
+        .unwrap();
+
+    let job = jobs.get(&export.id, &user_id, false).await.unwrap();
+    assert_eq!(job.kind, JobKind::Export);
+    assert_eq!(job.status, JobStatus::Queued);
+    assert_eq!(job.progress, 0);
+
+    // Other users can't see it
+    assert!(jobs.get(&export.id, "someone-else", false).await.is_err());
+
+    let worker = ExportWorker::new(ctx.db.clone(), Arc::new(MemoryStorage::default()));
+    worker.process().await.unwrap();
+
+    let job = jobs.get(&export.id, &user_id, false).await.unwrap();
+    assert_eq!(job.status, JobStatus::Succeeded);
+    assert_eq!(job.progress, 100);
+    assert!(job.started_at.is_some());
+    assert!(job.finished_at.is_some());
+
+    ctx.cleanup().await;
+}
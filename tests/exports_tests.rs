@@ -0,0 +1,4 @@
+mod common;
+mod exports {
+    pub mod export_test;
+}
@@ -0,0 +1,62 @@
+use axum::http::StatusCode;
+use serde_json::{json, Value};
+
+use crate::common::TestContext;
+
+/// Runs in its own test binary, so the policy set here reaches no other tests
+async fn blocking_context() -> TestContext {
+    std::env::set_var("GEO_BLOCKED_COUNTRIES", "KP");
+    std::env::set_var("GEO_BLOCKED_TRADE_COUNTRIES", "US");
+    std::env::set_var("GEO_COUNTRY_HEADER", "cf-ipcountry");
+    TestContext::new().await
+}
+
+#[tokio::test]
+async fn test_blocked_country_gets_451_but_health_stays_up() {
+    let ctx = blocking_context().await;
+
+    let response = ctx.server.get("/v1/swap/currencies").add_header("cf-ipcountry", "kp").await;
+    response.assert_status(StatusCode::UNAVAILABLE_FOR_LEGAL_REASONS);
+    let body: Value = response.json();
+    assert_eq!(body["code"], "jurisdiction_restricted");
+    assert_eq!(body["country"], "KP");
+    assert_eq!(body["feature"], "read");
+
+    // Unprefixed paths are covered too
+    ctx.server
+        .get("/swap/currencies")
+        .add_header("cf-ipcountry", "KP")
+        .await
+        .assert_status(StatusCode::UNAVAILABLE_FOR_LEGAL_REASONS);
+
+    // Load balancers and monitors are never refused
+    ctx.server.get("/health").add_header("cf-ipcountry", "KP").await.assert_status_ok();
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_trade_only_block_leaves_reads_open() {
+    let ctx = blocking_context().await;
+
+    let response = ctx
+        .server
+        .post("/v1/swap/create")
+        .add_header("cf-ipcountry", "US")
+        .json(&json!({}))
+        .await;
+    response.assert_status(StatusCode::UNAVAILABLE_FOR_LEGAL_REASONS);
+    let body: Value = response.json();
+    assert_eq!(body["feature"], "trade");
+
+    let response = ctx.server.get("/v1/swap/currencies").add_header("cf-ipcountry", "US").await;
+    assert_ne!(response.status_code(), StatusCode::UNAVAILABLE_FOR_LEGAL_REASONS);
+
+    // Other countries and unresolved clients pass
+    let response = ctx.server.get("/v1/swap/currencies").add_header("cf-ipcountry", "DE").await;
+    assert_ne!(response.status_code(), StatusCode::UNAVAILABLE_FOR_LEGAL_REASONS);
+    let response = ctx.server.get("/v1/swap/currencies").await;
+    assert_ne!(response.status_code(), StatusCode::UNAVAILABLE_FOR_LEGAL_REASONS);
+
+    ctx.cleanup().await;
+}
@@ -0,0 +1,4 @@
+mod common;
+mod geo {
+    pub mod geo_block_test;
+}
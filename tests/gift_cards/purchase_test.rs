@@ -0,0 +1,483 @@
+use async_trait::async_trait;
+use axum::http::StatusCode;
+use serde_json::json;
+use serial_test::serial;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use crate::common::{create_test_swap, create_test_user, test_email, test_password, NoOpProvider, TestContext};
+use exchange_shared::modules::auth::model::User;
+use exchange_shared::modules::balances::crud::BalanceCrud;
+use exchange_shared::modules::gift_cards::crud::{GiftCardCrud, GiftCardError};
+use exchange_shared::modules::gift_cards::model::GiftCardPurchaseStatus;
+use exchange_shared::modules::gift_cards::provider::{
+    parse_trocador_fulfillment, Fulfillment, GiftCardProvider, ProviderOrder,
+};
+use exchange_shared::modules::gift_cards::schema::{GiftCardResponse, PurchaseGiftCardRequest};
+use exchange_shared::services::amount::{self, Decimal};
+use exchange_shared::services::custody::GiftCardPayer;
+use exchange_shared::services::fx::FxStore;
+use exchange_shared::services::payout::{PayoutGuard, PayoutGuardError};
+
+async fn create_verified_user(ctx: &TestContext) -> String {
+    let email = test_email();
+
+    ctx.server
+        .post("/auth/register")
+        .json(&json!({
+            "email": &email,
+            "password": test_password(),
+            "password_confirm": test_password()
+        }))
+        .await;
+
+    sqlx::query("UPDATE users SET email_verified = true WHERE email = ?")
+        .bind(&email)
+        .execute(&ctx.db)
+        .await
+        .unwrap();
+
+    let response = ctx
+        .server
+        .post("/auth/login")
+        .json(&json!({
+            "email": &email,
+            "password": test_password()
+        }))
+        .await;
+
+    let body: serde_json::Value = response.json();
+    body["access_token"].as_str().unwrap().to_string()
+}
+
+fn card(denominations: &[&str], min: Option<&str>, max: Option<&str>) -> GiftCardResponse {
+    GiftCardResponse {
+        card_id: "amazon-us".to_string(),
+        name: "Amazon".to_string(),
+        category: None,
+        description: None,
+        country: Some("US".to_string()),
+        image_url: None,
+        min_amount: min.map(dec),
+        max_amount: max.map(dec),
+        denominations: denominations.iter().copied().map(dec).collect(),
+        provider: "trocador".to_string(),
+    }
+}
+
+fn dec(s: &str) -> Decimal {
+    amount::parse(s).unwrap()
+}
+
+/// Sells one open-range card; every order asks for `payment_amount`
+struct MockProvider {
+    payment_amount: Decimal,
+    orders: AtomicUsize,
+}
+
+impl MockProvider {
+    fn new(payment_amount: &str) -> Arc<Self> {
+        Arc::new(Self { payment_amount: dec(payment_amount), orders: AtomicUsize::new(0) })
+    }
+}
+
+#[async_trait]
+impl GiftCardProvider for MockProvider {
+    fn name(&self) -> &'static str {
+        "mock"
+    }
+
+    async fn list_cards(&self, _country: Option<&str>) -> Result<Vec<GiftCardResponse>, GiftCardError> {
+        Ok(vec![card(&[], Some("10"), Some("500"))])
+    }
+
+    async fn place_order(
+        &self,
+        _card_id: &str,
+        _amount: Decimal,
+        payment_currency: &str,
+        payment_network: &str,
+        _email: &str,
+    ) -> Result<ProviderOrder, GiftCardError> {
+        self.orders.fetch_add(1, Ordering::SeqCst);
+        Ok(ProviderOrder {
+            order_id: uuid::Uuid::new_v4().to_string(),
+            payment_currency: payment_currency.to_string(),
+            payment_network: payment_network.to_string(),
+            payment_amount: self.payment_amount,
+            payment_address: "0x742d35Cc6634C0532925a3b844Bc454e4438f44e".to_string(),
+            payment_extra_id: None,
+        })
+    }
+
+    async fn fetch_fulfillment(&self, _order_id: &str) -> Result<Fulfillment, GiftCardError> {
+        Ok(Fulfillment::Pending)
+    }
+}
+
+/// A verified user with custody enabled and `eth_balance` ETH on ethereum
+async fn buyer(ctx: &TestContext, eth_balance: &str) -> User {
+    let (user_id, _) = create_test_user(&ctx.server, &test_email(), test_password()).await;
+    sqlx::query("UPDATE users SET email_verified = true WHERE id = ?")
+        .bind(&user_id)
+        .execute(&ctx.db)
+        .await
+        .unwrap();
+    BalanceCrud::new(ctx.db.clone()).opt_in(&user_id).await.unwrap();
+    sqlx::query("INSERT INTO balance_accounts (id, user_id, currency, network, balance) VALUES (UUID(), ?, 'eth', 'ethereum', ?)")
+        .bind(&user_id)
+        .bind(dec(eth_balance))
+        .execute(&ctx.db)
+        .await
+        .unwrap();
+    sqlx::query_as::<_, User>("SELECT * FROM users WHERE id = ?")
+        .bind(&user_id)
+        .fetch_one(&ctx.db)
+        .await
+        .unwrap()
+}
+
+async fn eth_balance(ctx: &TestContext, user_id: &str) -> Decimal {
+    BalanceCrud::new(ctx.db.clone())
+        .list_balances(user_id)
+        .await
+        .unwrap()
+        .into_iter()
+        .find(|b| b.currency == "eth")
+        .map(|b| b.balance)
+        .unwrap_or_default()
+}
+
+fn from_balance(amount: f64) -> PurchaseGiftCardRequest {
+    PurchaseGiftCardRequest {
+        card_id: "amazon-us".to_string(),
+        amount: amount.into(),
+        swap_id: None,
+        currency: Some("eth".to_string()),
+        network: Some("ethereum".to_string()),
+        email: None,
+        country: None,
+    }
+}
+
+fn from_swap(swap_id: &str) -> PurchaseGiftCardRequest {
+    PurchaseGiftCardRequest { swap_id: Some(swap_id.to_string()), currency: None, network: None, ..from_balance(50.0) }
+}
+
+/// A completed BTC -> ETH swap of `user_id` that settled 1 ETH with a 0.01
+/// platform fee, paid out per `payout_mode`
+async fn balance_swap(ctx: &TestContext, user_id: &str, payout_mode: &str) -> String {
+    let swap_id = create_test_swap(&ctx.server, user_id, "btc", "eth").await;
+    sqlx::query(
+        r#"
+        UPDATE swaps
+        SET status = 'completed', to_network = 'ethereum', payout_mode = ?, actual_receive = 1, platform_fee = 0.01
+        WHERE id = ?
+        "#,
+    )
+    .bind(payout_mode)
+    .bind(&swap_id)
+    .execute(&ctx.db)
+    .await
+    .unwrap();
+    swap_id
+}
+
+/// Arm the guard and price ETH so the payer sends
+async fn armed_guard(ctx: &TestContext) -> PayoutGuard {
+    FxStore::new(ctx.db.clone())
+        .upsert_prices(&[("eth".to_string(), chrono::Utc::now().date_naive(), Decimal::from(100))], "test")
+        .await
+        .unwrap();
+    let guard = PayoutGuard::new(ctx.db.clone());
+    match guard.rearm("test-setup", "reset before test").await {
+        Ok(_) | Err(PayoutGuardError::NotTripped) => {}
+        Err(e) => panic!("could not arm the payout guard: {}", e),
+    }
+    guard
+}
+
+// =============================================================================
+// PURCHASE ENDPOINT
+// =============================================================================
+
+#[tokio::test]
+async fn purchase_requires_authentication() {
+    let ctx = TestContext::new().await;
+
+    let response = ctx
+        .server
+        .post("/gift-cards/purchase")
+        .json(&json!({ "card_id": "amazon-50", "amount": 50 }))
+        .await;
+
+    response.assert_status(StatusCode::UNAUTHORIZED);
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn purchase_without_funding_swap_is_rejected() {
+    let ctx = TestContext::new().await;
+    let access_token = create_verified_user(&ctx).await;
+
+    let response = ctx
+        .server
+        .post("/gift-cards/purchase")
+        .authorization_bearer(&access_token)
+        .json(&json!({ "card_id": "amazon-50", "amount": 50 }))
+        .await;
+
+    response.assert_status(StatusCode::BAD_REQUEST);
+    let body: serde_json::Value = response.json();
+    assert!(body["error"].as_str().unwrap_or("").contains("swap_id"));
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn purchase_rejects_non_positive_amount() {
+    let ctx = TestContext::new().await;
+    let access_token = create_verified_user(&ctx).await;
+
+    let response = ctx
+        .server
+        .post("/gift-cards/purchase")
+        .authorization_bearer(&access_token)
+        .json(&json!({ "card_id": "amazon-50", "amount": 0, "swap_id": "missing" }))
+        .await;
+
+    response.assert_status(StatusCode::BAD_REQUEST);
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn purchase_status_of_unknown_id_is_not_found() {
+    let ctx = TestContext::new().await;
+    let access_token = create_verified_user(&ctx).await;
+
+    let response = ctx
+        .server
+        .get("/gift-cards/purchases/00000000-0000-0000-0000-000000000000")
+        .authorization_bearer(&access_token)
+        .await;
+
+    response.assert_status(StatusCode::NOT_FOUND);
+
+    ctx.cleanup().await;
+}
+
+// =============================================================================
+// FUNDING AND PAYMENT
+// =============================================================================
+
+#[tokio::test]
+#[serial]
+async fn balance_funded_purchase_debits_the_ledger() {
+    let ctx = TestContext::new().await;
+    let user = buyer(&ctx, "1").await;
+    let crud = GiftCardCrud::new(ctx.db.clone(), None).with_provider(MockProvider::new("0.02"));
+
+    let purchase = crud.purchase(&user, &from_balance(50.0)).await.unwrap();
+    assert_eq!(purchase.status, GiftCardPurchaseStatus::Pending);
+    assert_eq!(purchase.amount, dec("50"));
+    assert_eq!(purchase.payment_amount, dec("0.02"));
+    assert!(purchase.funding_swap_id.is_none());
+    assert!(purchase.funded_at.is_some());
+    assert!(purchase.payment_tx_hash.is_none());
+    assert_eq!(eth_balance(&ctx, &user.id).await, dec("0.98"));
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+#[serial]
+async fn underfunded_purchase_keeps_its_provider_order() {
+    let ctx = TestContext::new().await;
+    let user = buyer(&ctx, "0.01").await;
+    let crud = GiftCardCrud::new(ctx.db.clone(), None).with_provider(MockProvider::new("0.02"));
+
+    let err = crud.purchase(&user, &from_balance(50.0)).await.unwrap_err();
+    assert!(matches!(err, GiftCardError::InsufficientFunds { .. }));
+    assert_eq!(eth_balance(&ctx, &user.id).await, dec("0.01"));
+
+    // The order was placed against a reserved row, which records the failure
+    let (status, order_id): (String, Option<String>) = sqlx::query_as(
+        "SELECT CAST(status AS CHAR), provider_order_id FROM gift_card_purchases WHERE user_id = ?",
+    )
+    .bind(&user.id)
+    .fetch_one(&ctx.db)
+    .await
+    .unwrap();
+    assert_eq!(status, "failed");
+    assert!(order_id.is_some());
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+#[serial]
+async fn used_swap_places_no_second_order() {
+    let ctx = TestContext::new().await;
+    let user = buyer(&ctx, "1").await;
+    let swap_id = balance_swap(&ctx, &user.id, "balance").await;
+    let provider = MockProvider::new("0.5");
+    let crud = GiftCardCrud::new(ctx.db.clone(), None).with_provider(provider.clone());
+
+    let purchase = crud.purchase(&user, &from_swap(&swap_id)).await.unwrap();
+    assert!(purchase.funded_at.is_some());
+    assert_eq!(eth_balance(&ctx, &user.id).await, dec("0.5"));
+
+    let err = crud.purchase(&user, &from_swap(&swap_id)).await.unwrap_err();
+    assert!(matches!(err, GiftCardError::SwapAlreadyUsed));
+    assert_eq!(provider.orders.load(Ordering::SeqCst), 1);
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+#[serial]
+async fn swap_paid_out_to_an_address_cannot_fund_a_purchase() {
+    let ctx = TestContext::new().await;
+    let user = buyer(&ctx, "1").await;
+    let swap_id = balance_swap(&ctx, &user.id, "address").await;
+    let provider = MockProvider::new("0.5");
+    let crud = GiftCardCrud::new(ctx.db.clone(), None).with_provider(provider.clone());
+
+    let err = crud.purchase(&user, &from_swap(&swap_id)).await.unwrap_err();
+    assert!(matches!(err, GiftCardError::SwapNotInCustody));
+    assert_eq!(provider.orders.load(Ordering::SeqCst), 0);
+    assert_eq!(eth_balance(&ctx, &user.id).await, dec("1"));
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+#[serial]
+async fn failed_swap_purchase_releases_the_swap() {
+    let ctx = TestContext::new().await;
+    let user = buyer(&ctx, "1").await;
+    let swap_id = balance_swap(&ctx, &user.id, "balance").await;
+
+    // The swap credited 1 ETH less its 0.01 platform fee
+    let crud = GiftCardCrud::new(ctx.db.clone(), None).with_provider(MockProvider::new("0.995"));
+    let err = crud.purchase(&user, &from_swap(&swap_id)).await.unwrap_err();
+    assert!(matches!(err, GiftCardError::InsufficientFunds { available, .. } if available == dec("0.99")));
+    assert_eq!(eth_balance(&ctx, &user.id).await, dec("1"));
+
+    let crud = GiftCardCrud::new(ctx.db.clone(), None).with_provider(MockProvider::new("0.99"));
+    let purchase = crud.purchase(&user, &from_swap(&swap_id)).await.unwrap();
+    assert_eq!(purchase.funding_swap_id.as_deref(), Some(swap_id.as_str()));
+    assert_eq!(eth_balance(&ctx, &user.id).await, dec("0.01"));
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+#[serial]
+async fn tripped_guard_holds_gift_card_payments() {
+    let ctx = TestContext::new().await;
+    let guard = armed_guard(&ctx).await;
+    let user = buyer(&ctx, "1").await;
+    let crud = GiftCardCrud::new(ctx.db.clone(), None).with_provider(MockProvider::new("0.02"));
+    let purchase = crud.purchase(&user, &from_balance(50.0)).await.unwrap();
+
+    guard.trip_manually("test", "Suspected provider compromise").await.unwrap();
+    let payer = GiftCardPayer::new(ctx.db.clone(), String::new()).with_provider(Arc::new(NoOpProvider));
+    assert_eq!(payer.process_batch().await.unwrap(), 0);
+
+    let held = crud.get_unpaid_purchases(200).await.unwrap();
+    assert!(held.iter().any(|p| p.id == purchase.id));
+    let (attempts,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM payout_attempts WHERE reference = ?")
+        .bind(&purchase.id)
+        .fetch_one(&ctx.db)
+        .await
+        .unwrap();
+    assert_eq!(attempts, 0);
+
+    guard.rearm("test", "gift card test finished").await.unwrap();
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+#[serial]
+async fn failed_payment_refunds_the_balance() {
+    let ctx = TestContext::new().await;
+    let _guard = armed_guard(&ctx).await;
+    let user = buyer(&ctx, "1").await;
+    let crud = GiftCardCrud::new(ctx.db.clone(), None).with_provider(MockProvider::new("0.02"));
+    let purchase = crud.purchase(&user, &from_balance(50.0)).await.unwrap();
+    assert_eq!(eth_balance(&ctx, &user.id).await, dec("0.98"));
+
+    // The mock hot wallet is empty, so the send fails
+    let mnemonic = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+    let payer = GiftCardPayer::new(ctx.db.clone(), mnemonic.to_string()).with_provider(Arc::new(NoOpProvider));
+    payer.process_batch().await.unwrap();
+
+    let (status, error): (String, Option<String>) =
+        sqlx::query_as("SELECT CAST(status AS CHAR), error FROM gift_card_purchases WHERE id = ?")
+            .bind(&purchase.id)
+            .fetch_one(&ctx.db)
+            .await
+            .unwrap();
+    assert_eq!(status, "failed");
+    assert!(error.unwrap().contains("balance too low"));
+    assert_eq!(eth_balance(&ctx, &user.id).await, dec("1"));
+
+    let (kind,): (String,) = sqlx::query_as("SELECT CAST(kind AS CHAR) FROM payout_attempts WHERE reference = ?")
+        .bind(&purchase.id)
+        .fetch_one(&ctx.db)
+        .await
+        .unwrap();
+    assert_eq!(kind, "gift_card");
+
+    ctx.cleanup().await;
+}
+
+// =============================================================================
+// DENOMINATIONS
+// =============================================================================
+
+#[test]
+fn fixed_denominations_must_match_exactly() {
+    let c = card(&["25", "50", "100"], None, None);
+    assert!(c.accepts_amount(dec("50")));
+    assert!(c.accepts_amount(dec("50.00")));
+    assert!(!c.accepts_amount(dec("60")));
+    assert!(!c.accepts_amount(dec("50.000000000000000001")));
+}
+
+#[test]
+fn open_range_cards_respect_min_and_max() {
+    let c = card(&[], Some("10"), Some("500"));
+    assert!(c.accepts_amount(dec("10")));
+    assert!(c.accepts_amount(dec("499.99")));
+    assert!(!c.accepts_amount(dec("5")));
+    assert!(!c.accepts_amount(dec("500.01")));
+}
+
+// =============================================================================
+// PROVIDER FULFILLMENT PARSING
+// =============================================================================
+
+#[test]
+fn trade_without_details_is_pending() {
+    let trade = json!({ "trade_id": "abc", "status": "waiting" });
+    assert_eq!(parse_trocador_fulfillment(&trade), Fulfillment::Pending);
+}
+
+#[test]
+fn trade_with_redeem_code_is_delivered() {
+    let trade = json!([{ "trade_id": "abc", "status": "finished", "details": { "code": "XXXX-YYYY" } }]);
+    assert_eq!(
+        parse_trocador_fulfillment(&trade),
+        Fulfillment::Delivered { code: "XXXX-YYYY".to_string() }
+    );
+}
+
+#[test]
+fn failed_trade_is_reported() {
+    let trade = json!({ "trade_id": "abc", "status": "expired" });
+    assert!(matches!(parse_trocador_fulfillment(&trade), Fulfillment::Failed(_)));
+}
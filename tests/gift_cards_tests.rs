@@ -0,0 +1,4 @@
+mod common;
+mod gift_cards {
+    pub mod purchase_test;
+}
@@ -0,0 +1,69 @@
+use exchange_shared::modules::kyc::crud::KycCrud;
+use exchange_shared::modules::kyc::model::{DocumentSide, DocumentType, KycLevel, KycStatus};
+use exchange_shared::services::kyc::ReviewOutcome;
+
+use crate::common::{create_test_user, test_password, TestContext};
+
+fn applicant_id() -> String {
+    format!("test-{}", uuid::Uuid::new_v4())
+}
+
+#[tokio::test]
+async fn status_is_empty_before_verification() {
+    let ctx = TestContext::new().await;
+    let (_, token) = create_test_user(&ctx.server, "kyc@example.com", test_password()).await;
+
+    let response = ctx.server.get("/kyc").authorization_bearer(&token).await;
+    assert_eq!(response.status_code(), 200);
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["level"], "none");
+    assert!(body.get("status").is_none());
+    assert_eq!(body["documents"].as_array().unwrap().len(), 0);
+}
+
+#[tokio::test]
+async fn approval_grants_the_requested_level_and_keeps_it_through_later_rejections() {
+    let ctx = TestContext::new().await;
+    let (user_id, _) = create_test_user(&ctx.server, "kyc@example.com", test_password()).await;
+    let crud = KycCrud::new(ctx.db.clone());
+    let applicant = applicant_id();
+
+    let created = crud.create_applicant(&user_id, "test", &applicant, KycLevel::Basic).await.unwrap();
+    assert_eq!(created.status, KycStatus::Init);
+    assert_eq!(crud.verified_level(&user_id).await.unwrap(), KycLevel::None);
+
+    let document = crud
+        .record_document(&user_id, DocumentType::IdCard, Some(DocumentSide::Front), "DEU", Some("img-1"))
+        .await
+        .unwrap();
+    assert_eq!(document.side, Some(DocumentSide::Front));
+    assert!(crud.mark_submitted(&user_id).await.unwrap());
+    // Not open for submission while under review
+    assert!(!crud.mark_submitted(&user_id).await.unwrap());
+
+    let owner = crud.apply_review("test", &applicant, &ReviewOutcome::Approved).await.unwrap();
+    assert_eq!(owner.as_deref(), Some(user_id.as_str()));
+    assert_eq!(crud.verified_level(&user_id).await.unwrap(), KycLevel::Basic);
+
+    // A failed upgrade leaves the basic level in place
+    crud.restart(&user_id, KycLevel::Full).await.unwrap();
+    let rejected = ReviewOutcome::Rejected { retry: true, reason: Some("Blurry photo".to_string()) };
+    crud.apply_review("test", &applicant, &rejected).await.unwrap();
+    let after = crud.get_applicant(&user_id).await.unwrap().unwrap();
+    assert_eq!(after.level, KycLevel::Basic);
+    assert_eq!(after.status, KycStatus::Retry);
+    assert_eq!(after.reject_reason.as_deref(), Some("Blurry photo"));
+    assert!(after.status.accepts_documents());
+
+    crud.apply_review("test", &applicant, &ReviewOutcome::Approved).await.unwrap();
+    assert_eq!(crud.verified_level(&user_id).await.unwrap(), KycLevel::Full);
+}
+
+#[tokio::test]
+async fn reviews_for_unknown_applicants_are_ignored() {
+    let ctx = TestContext::new().await;
+    let crud = KycCrud::new(ctx.db.clone());
+
+    let owner = crud.apply_review("test", &applicant_id(), &ReviewOutcome::Approved).await.unwrap();
+    assert!(owner.is_none());
+}
@@ -0,0 +1,4 @@
+mod common;
+mod kyc {
+    pub mod kyc_test;
+}
@@ -94,12 +94,30 @@ fn test_rpc_metrics_collector() {
     collector.record_rpc_request("ethereum", "primary", "eth_blockNumber", "success", 0.123);
     collector.set_circuit_breaker_state("ethereum", "primary", 0.0);
     collector.set_block_height_lag("ethereum", "primary", 2);
+    collector.record_region_request("ethereum", "us-east-1", "cross_region");
     
     let output = metrics.export().unwrap();
     assert!(output.contains("exchange_rpc_endpoint_health_score"));
     assert!(output.contains("exchange_rpc_requests_total"));
     assert!(output.contains("exchange_rpc_circuit_breaker_state"));
     assert!(output.contains("exchange_rpc_block_height_lag"));
+    assert!(output.contains("locality=\"cross_region\""));
+}
+
+#[serial]
+#[test]
+fn test_provider_metrics_collector() {
+    let metrics = MetricsRegistry::new().unwrap();
+    let collector = ProviderMetricsCollector::new(metrics.clone());
+    
+    collector.record_request("trocador", "rates", 0.8, None);
+    collector.record_request("trocador", "create", 4.2, Some("http"));
+    
+    let output = metrics.export().unwrap();
+    assert!(output.contains("exchange_provider_request_duration_seconds"));
+    assert!(output.contains("operation=\"rates\""));
+    assert!(output.contains("exchange_provider_errors_total"));
+    assert!(output.contains("kind=\"http\""));
 }
 
 #[serial]
@@ -0,0 +1,71 @@
+use axum::{extract::Path, routing::get, Router};
+use axum_test::TestServer;
+use serial_test::serial;
+use std::sync::Arc;
+use std::time::Duration;
+
+use exchange_shared::services::metrics::{LatencyBudgetLayer, LatencyBudgets, MetricsRegistry};
+
+// =============================================================================
+// INTEGRATION TESTS - LATENCY BUDGETS
+// =============================================================================
+
+async fn slow(Path(_id): Path<String>) -> &'static str {
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    "slow"
+}
+
+async fn fast() -> &'static str {
+    "fast"
+}
+
+fn budgeted_server(metrics: Arc<MetricsRegistry>) -> TestServer {
+    let budgets = LatencyBudgets::default()
+        .with_route("/slow/{id}", Duration::from_millis(10))
+        .with_route("/fast", Duration::from_millis(1000));
+    let routes = Router::new().route("/slow/{id}", get(slow)).route("/fast", get(fast));
+    let app = Router::new()
+        .merge(routes.clone())
+        .nest("/v1", routes)
+        .layer(LatencyBudgetLayer::new(budgets).with_metrics(metrics));
+    TestServer::new(app).unwrap()
+}
+
+fn exceeded(metrics: &MetricsRegistry, route: &str) -> f64 {
+    metrics.http_latency_budget_exceeded_total.with_label_values(&["GET", route]).get()
+}
+
+#[serial]
+#[tokio::test]
+async fn test_slow_requests_count_against_their_route_template() {
+    let metrics = MetricsRegistry::new().unwrap();
+    let server = budgeted_server(metrics.clone());
+
+    server.get("/slow/a").await.assert_status_ok();
+    server.get("/slow/b").await.assert_status_ok();
+    server.get("/fast").await.assert_status_ok();
+
+    // Counted per template, not per concrete path
+    assert_eq!(exceeded(&metrics, "/slow/{id}"), 2.0);
+    assert_eq!(exceeded(&metrics, "/fast"), 0.0);
+
+    let output = metrics.export().unwrap();
+    assert!(output.contains("exchange_http_latency_budget_exceeded_total"));
+    assert!(output.contains("endpoint=\"/slow/{id}\""));
+}
+
+#[serial]
+#[tokio::test]
+async fn test_versioned_routes_share_the_unversioned_budget() {
+    let metrics = MetricsRegistry::new().unwrap();
+    let server = budgeted_server(metrics.clone());
+
+    server.get("/v1/slow/a").await.assert_status_ok();
+    server.get("/v1/fast").await.assert_status_ok();
+    assert_eq!(exceeded(&metrics, "/v1/slow/{id}"), 1.0);
+    assert_eq!(exceeded(&metrics, "/v1/fast"), 0.0);
+
+    // Unmatched requests have no budget
+    server.get("/nowhere").await.assert_status_not_found();
+    assert!(!metrics.export().unwrap().contains("/nowhere"));
+}
@@ -1,2 +1,3 @@
 pub mod metrics_test;
 pub mod collectors_test;
+pub mod latency_budget_test;
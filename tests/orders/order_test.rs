@@ -0,0 +1,146 @@
+use axum::http::StatusCode;
+use serde_json::json;
+
+use crate::common::{create_user, test_email, TestContext};
+use exchange_shared::modules::swap::schema::{RateResponse, RateType};
+use exchange_shared::services::amount::{self, Decimal};
+use exchange_shared::services::orders::watcher::best_rate;
+
+fn btc_to_eth_order(target_rate: f64) -> serde_json::Value {
+    json!({
+        "from": "btc",
+        "network_from": "Mainnet",
+        "to": "eth",
+        "network_to": "ERC20",
+        "amount": 0.01,
+        "target_rate": target_rate,
+        "recipient_address": "0x742d35Cc6634C0532925a3b844Bc9e7595f0bEb0",
+        "ttl_hours": 48
+    })
+}
+
+fn quote(provider: &str, rate: &str) -> RateResponse {
+    let rate = amount::parse(rate).unwrap();
+    RateResponse {
+        provider: provider.to_string(),
+        provider_name: provider.to_string(),
+        rate,
+        estimated_amount: rate,
+        min_amount: Decimal::ZERO,
+        max_amount: Decimal::ZERO,
+        network_fee: Decimal::ZERO,
+        provider_fee: Decimal::ZERO,
+        platform_fee: Decimal::ZERO,
+        total_fee: Decimal::ZERO,
+        rate_type: RateType::Floating,
+        kyc_required: false,
+        kyc_rating: None,
+        eta_minutes: None,
+        quote: None,
+        promotion: None,
+    }
+}
+
+// =============================================================================
+// ENDPOINTS
+// =============================================================================
+
+#[tokio::test]
+async fn orders_require_authentication() {
+    let ctx = TestContext::new().await;
+
+    ctx.server.get("/swap/orders").await.assert_status(StatusCode::UNAUTHORIZED);
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn created_order_is_listed_as_pending() {
+    let ctx = TestContext::new().await;
+    let (_, token) = create_user(&ctx, &test_email()).await;
+
+    let response = ctx
+        .server
+        .post("/swap/orders")
+        .authorization_bearer(&token)
+        .json(&btc_to_eth_order(25.0))
+        .await;
+    response.assert_status(StatusCode::CREATED);
+    let order: serde_json::Value = response.json();
+    assert_eq!(order["status"], "pending");
+
+    let list: serde_json::Value = ctx.server.get("/swap/orders").authorization_bearer(&token).await.json();
+    let orders = list["orders"].as_array().unwrap();
+    assert!(orders.iter().any(|o| o["id"] == order["id"]));
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn create_rejects_invalid_target_and_ttl() {
+    let ctx = TestContext::new().await;
+    let (_, token) = create_user(&ctx, &test_email()).await;
+
+    ctx.server
+        .post("/swap/orders")
+        .authorization_bearer(&token)
+        .json(&btc_to_eth_order(0.0))
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+
+    let mut body = btc_to_eth_order(25.0);
+    body["ttl_hours"] = json!(24 * 365);
+    ctx.server
+        .post("/swap/orders")
+        .authorization_bearer(&token)
+        .json(&body)
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn cancelled_order_cannot_be_cancelled_again() {
+    let ctx = TestContext::new().await;
+    let (_, token) = create_user(&ctx, &test_email()).await;
+
+    let order: serde_json::Value = ctx
+        .server
+        .post("/swap/orders")
+        .authorization_bearer(&token)
+        .json(&btc_to_eth_order(25.0))
+        .await
+        .json();
+    let path = format!("/swap/orders/{}", order["id"].as_str().unwrap());
+
+    let cancelled: serde_json::Value = ctx.server.delete(&path).authorization_bearer(&token).await.json();
+    assert_eq!(cancelled["status"], "cancelled");
+
+    ctx.server
+        .delete(&path)
+        .authorization_bearer(&token)
+        .await
+        .assert_status(StatusCode::CONFLICT);
+
+    ctx.cleanup().await;
+}
+
+// =============================================================================
+// TRIGGER SELECTION
+// =============================================================================
+
+#[test]
+fn best_rate_picks_highest_quote() {
+    let quotes = vec![quote("changenow", "24.1"), quote("fixedfloat", "24.9"), quote("exolix", "24.5")];
+
+    assert_eq!(best_rate(&quotes, None).unwrap().provider, "fixedfloat");
+}
+
+#[test]
+fn best_rate_respects_provider_restriction() {
+    let quotes = vec![quote("ChangeNOW", "24.1"), quote("fixedfloat", "24.9")];
+
+    assert_eq!(best_rate(&quotes, Some("changenow")).unwrap().rate, amount::parse("24.1").unwrap());
+    assert!(best_rate(&quotes, Some("exolix")).is_none());
+}
@@ -0,0 +1,4 @@
+mod common;
+mod orders {
+    pub mod order_test;
+}
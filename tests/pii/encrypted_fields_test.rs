@@ -0,0 +1,74 @@
+use axum::http::StatusCode;
+use serde_json::{json, Value};
+
+use exchange_shared::services::pii::email_index;
+
+use crate::common::{test_email, test_password, TestContext};
+
+const TEST_KEY: &str = "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f";
+
+/// Runs in its own test binary, so the key set here reaches no other tests
+async fn encrypting_context() -> TestContext {
+    std::env::set_var("DATA_ENCRYPTION_KEYS", format!("1:{}", TEST_KEY));
+    std::env::set_var("DATA_ENCRYPTION_KEY_VERSION", "1");
+    TestContext::new().await
+}
+
+#[tokio::test]
+async fn test_email_is_sealed_at_rest_and_plain_through_the_api() {
+    let ctx = encrypting_context().await;
+    let email = test_email();
+
+    let response = ctx
+        .server
+        .post("/auth/register")
+        .json(&json!({
+            "email": &email,
+            "password": test_password(),
+            "password_confirm": test_password()
+        }))
+        .await;
+    response.assert_status(StatusCode::CREATED);
+    let body: Value = response.json();
+    assert_eq!(body["user"]["email"], email.as_str());
+    let user_id = body["user"]["id"].as_str().unwrap().to_string();
+
+    // The column holds ciphertext; lookups go through the blind index
+    let (stored, hash): (String, Option<String>) =
+        sqlx::query_as("SELECT email, email_hash FROM users WHERE id = ?")
+            .bind(&user_id)
+            .fetch_one(&ctx.db)
+            .await
+            .unwrap();
+    assert!(stored.starts_with("enc:v1:"), "email stored as {}", stored);
+    assert!(!stored.contains(&email));
+    assert_eq!(hash, email_index(&email));
+    assert!(hash.is_some());
+
+    // Login finds the user by the index, whatever the case of the input
+    let response = ctx
+        .server
+        .post("/auth/login")
+        .json(&json!({ "email": email.to_uppercase(), "password": test_password() }))
+        .await;
+    response.assert_status_ok();
+    let token = response.json::<Value>()["access_token"].as_str().unwrap().to_string();
+
+    let response = ctx.server.get("/auth/me").authorization_bearer(&token).await;
+    response.assert_status_ok();
+    assert_eq!(response.json::<Value>()["email"], email.as_str());
+
+    // Registering the same address again is still refused
+    let response = ctx
+        .server
+        .post("/auth/register")
+        .json(&json!({
+            "email": &email,
+            "password": test_password(),
+            "password_confirm": test_password()
+        }))
+        .await;
+    response.assert_status(StatusCode::CONFLICT);
+
+    ctx.cleanup().await;
+}
@@ -0,0 +1,4 @@
+mod common;
+mod pii {
+    pub mod encrypted_fields_test;
+}
@@ -0,0 +1,3 @@
+mod preflight {
+    pub mod startup_test;
+}
@@ -0,0 +1,33 @@
+use axum::http::StatusCode;
+
+use crate::common::TestContext;
+
+/// Runs in its own test binary, so the backend set here reaches no other tests
+async fn redis_backed_context() -> TestContext {
+    std::env::set_var("RATE_LIMIT_BACKEND", "redis");
+    TestContext::new().await
+}
+
+#[tokio::test]
+async fn test_instances_share_one_bucket() {
+    let first = redis_backed_context().await;
+    let second = redis_backed_context().await;
+    // Start from a full bucket
+    first.cleanup().await;
+
+    // The default burst is 10; the in-memory test transport has no peer
+    // address, so every request lands in the same bucket
+    for _ in 0..10 {
+        first.server.get("/health").await.assert_status_ok();
+    }
+
+    // A second instance with its own in-memory limiter still sees the
+    // tokens the first one took
+    let response = second.server.get("/health").await;
+    response.assert_status(StatusCode::TOO_MANY_REQUESTS);
+    assert!(response.headers().contains_key("retry-after"));
+
+    first.server.get("/health").await.assert_status(StatusCode::TOO_MANY_REQUESTS);
+
+    first.cleanup().await;
+}
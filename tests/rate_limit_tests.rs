@@ -0,0 +1,4 @@
+mod common;
+mod rate_limit {
+    pub mod redis_bucket_test;
+}
@@ -219,3 +219,35 @@ async fn test_economic_threshold_check() {
     
     cleanup_test_data(&pool).await;
 }
+
+#[tokio::test]
+#[serial]
+async fn test_refund_fee_breakdown() {
+    let pool = setup_test_db().await;
+    cleanup_test_data(&pool).await;
+    
+    let swap_id = create_test_swap(&pool).await;
+    
+    let calculation = RefundCalculator::new(pool.clone(), RefundConfig::default())
+        .calculate_refund(swap_id)
+        .await
+        .unwrap();
+    
+    // Every deduction is itemised and they add up
+    assert_eq!(calculation.currency, "BTC");
+    assert_eq!(calculation.platform_fee, Decimal::from_str("0.001").unwrap());
+    assert_eq!(calculation.swap_fee, Decimal::from_str("0.002").unwrap());
+    assert!(!calculation.platform_fee_waived);
+    assert_eq!(calculation.deposit_amount - calculation.fees_paid, calculation.refund_amount);
+    
+    // Waiving the platform fee gives it back
+    let config = RefundConfig { waive_platform_fee: true, ..RefundConfig::default() };
+    let waived = RefundCalculator::new(pool.clone(), config)
+        .calculate_refund(swap_id)
+        .await
+        .unwrap();
+    assert!(waived.platform_fee_waived);
+    assert_eq!(waived.refund_amount - calculation.refund_amount, Decimal::from_str("0.001").unwrap());
+    
+    cleanup_test_data(&pool).await;
+}
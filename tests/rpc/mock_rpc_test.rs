@@ -0,0 +1,162 @@
+use exchange_shared::services::amount::{self, Decimal};
+use exchange_shared::services::payout::{BitcoinTxStatus, ChainTxStatus, EvmTxStatus, SolanaTxStatus, TxStatusSource};
+use exchange_shared::services::wallet::btc_fees::FeeTarget;
+use exchange_shared::services::wallet::rpc::RpcError;
+use exchange_shared::services::wallet::solana_rpc::SolanaSignatureStatus;
+use exchange_shared::test_support::{fixtures, MockChainContext, RpcChain};
+use serde_json::json;
+
+// =============================================================================
+// HERMETIC RPC CLIENT TESTS
+// Real RPC clients against the embedded mock JSON-RPC server
+// =============================================================================
+
+#[tokio::test]
+async fn test_evm_client_reads_fixtures() {
+    let chains = MockChainContext::new().await;
+    let provider = chains.evm_provider();
+
+    assert_eq!(provider.get_gas_price().await.unwrap(), fixtures::EVM_GAS_PRICE_WEI);
+    assert_eq!(provider.get_transaction_count("0xabc").await.unwrap(), fixtures::EVM_NONCE);
+    assert_eq!(provider.get_balance("0xabc").await.unwrap(), Decimal::ONE);
+    assert_eq!(provider.send_raw_transaction("0xf86b").await.unwrap(), fixtures::EVM_TX_HASH);
+
+    let sent = chains.rpc.requests(RpcChain::Evm, "eth_sendRawTransaction").await;
+    assert_eq!(sent, vec![json!(["0xf86b"])]);
+}
+
+#[tokio::test]
+async fn test_evm_balance_override_is_per_address() {
+    let chains = MockChainContext::new().await;
+    let funded = "0x1234567890123456789012345678901234567890";
+    chains.rpc.mock_evm_balance(funded, 2_500_000_000_000_000_000).await;
+
+    let provider = chains.evm_provider();
+    assert_eq!(provider.get_balance(funded).await.unwrap(), Decimal::new(25, 1));
+    assert_eq!(provider.get_balance("0xother").await.unwrap(), Decimal::ONE);
+}
+
+#[tokio::test]
+async fn test_rpc_errors_are_surfaced() {
+    let chains = MockChainContext::new().await;
+    chains.rpc.mock_error(RpcChain::Evm, "eth_sendRawTransaction", "nonce too low").await;
+
+    let result = chains.evm_provider().send_raw_transaction("0xf86b").await;
+    assert!(matches!(result, Err(RpcError::Rpc(msg)) if msg == "nonce too low"));
+}
+
+#[tokio::test]
+async fn test_bitcoin_client_reads_fixtures() {
+    let chains = MockChainContext::new().await;
+    let provider = chains.bitcoin_provider();
+
+    let utxos = provider.get_utxos("bc1qtest").await.unwrap();
+    assert_eq!(utxos.len(), 1);
+    assert_eq!(utxos[0].txid, fixtures::BTC_UTXO_TXID);
+    assert_eq!(utxos[0].confirmations, fixtures::BTC_UTXO_CONFIRMATIONS);
+
+    assert_eq!(provider.get_balance("bc1qtest").await.unwrap(), amount::from_f64(fixtures::BTC_UTXO_AMOUNT).unwrap());
+    assert_eq!(provider.estimate_fee(6).await.unwrap(), fixtures::BTC_FEE_RATE);
+    assert_eq!(provider.broadcast_transaction("0200").await.unwrap(), fixtures::BTC_TXID);
+}
+
+#[tokio::test]
+async fn test_solana_client_reads_fixtures() {
+    let chains = MockChainContext::new().await;
+    let provider = chains.solana_provider();
+
+    assert_eq!(provider.get_balance("So1test").await.unwrap(), Decimal::TWO);
+    assert_eq!(provider.get_recent_blockhash().await.unwrap(), fixtures::SOL_BLOCKHASH);
+    assert_eq!(
+        provider.get_minimum_balance_for_rent_exemption().await.unwrap(),
+        fixtures::SOL_RENT_EXEMPT_MINIMUM
+    );
+    assert_eq!(provider.send_transaction("AQID").await.unwrap(), fixtures::SOL_SIGNATURE);
+}
+
+#[tokio::test]
+async fn test_bitcoin_fee_oracle_agrees_across_sources() {
+    let chains = MockChainContext::new().await;
+    let oracle = chains.bitcoin_fee_oracle();
+
+    // The node's flat 10 sat/vB is an outlier for the next block and for a day
+    let rates = oracle.rates().await.unwrap();
+    assert_eq!(rates.get(FeeTarget::Fast), 23.0);
+    assert_eq!(rates.get(FeeTarget::Medium), 10.0);
+    assert_eq!(rates.get(FeeTarget::Slow), 2.0);
+    assert_eq!(chains.rpc.calls(RpcChain::Bitcoin, "estimatesmartfee").await, 3);
+}
+
+#[tokio::test]
+async fn test_bitcoin_fee_oracle_survives_a_failed_source() {
+    let chains = MockChainContext::new().await;
+    chains.rpc.mock_error(RpcChain::Bitcoin, "estimatesmartfee", "Insufficient data").await;
+
+    let rates = chains.bitcoin_fee_oracle().rates().await.unwrap();
+    assert_eq!(rates.get(FeeTarget::Fast), 23.0);
+    assert_eq!(rates.get(FeeTarget::Slow), 2.0);
+}
+
+#[tokio::test]
+async fn test_solana_priority_fee_and_landing_fixtures() {
+    let chains = MockChainContext::new().await;
+    let provider = chains.solana_provider();
+
+    let fees = provider.get_recent_prioritization_fees(&["So1test".to_string()]).await.unwrap();
+    assert_eq!(fees, fixtures::SOL_PRIORITY_FEES);
+    assert_eq!(provider.get_block_height().await.unwrap(), fixtures::SOL_BLOCK_HEIGHT);
+    assert_eq!(
+        provider.get_signature_status(fixtures::SOL_SIGNATURE).await.unwrap(),
+        Some(SolanaSignatureStatus::Finalized)
+    );
+
+    let sent = chains.rpc.requests(RpcChain::Solana, "getRecentPrioritizationFees").await;
+    assert_eq!(sent, vec![json!([["So1test"]])]);
+}
+
+#[tokio::test]
+async fn test_chains_are_isolated() {
+    let chains = MockChainContext::new().await;
+    chains.evm_provider().get_gas_price().await.unwrap();
+
+    assert_eq!(chains.rpc.calls(RpcChain::Evm, "eth_gasPrice").await, 1);
+    assert_eq!(chains.rpc.calls(RpcChain::Bitcoin, "eth_gasPrice").await, 0);
+}
+
+#[tokio::test]
+async fn test_payout_status_sources_read_fixtures() {
+    let chains = MockChainContext::new().await;
+
+    let evm = EvmTxStatus::new(chains.evm_provider(), 12);
+    let depth = fixtures::EVM_BLOCK_NUMBER - fixtures::EVM_TX_BLOCK_NUMBER + 1;
+    assert_eq!(evm.status(fixtures::EVM_TX_HASH).await.unwrap(), ChainTxStatus::Included { confirmations: depth });
+
+    let bitcoin = BitcoinTxStatus::new(chains.bitcoin_provider(), 6);
+    assert_eq!(
+        bitcoin.status(fixtures::BTC_TXID).await.unwrap(),
+        ChainTxStatus::Included { confirmations: fixtures::BTC_TX_CONFIRMATIONS as u64 }
+    );
+
+    let solana = SolanaTxStatus::new(chains.solana_provider());
+    let ChainTxStatus::Included { confirmations } = solana.status(fixtures::SOL_SIGNATURE).await.unwrap() else {
+        panic!("finalized signature should be included");
+    };
+    assert!(confirmations >= solana.finality());
+}
+
+#[tokio::test]
+async fn test_payout_status_sources_report_unknown_transactions() {
+    let chains = MockChainContext::new().await;
+    chains.rpc.mock_result(RpcChain::Evm, "eth_getTransactionReceipt", json!(null)).await;
+    chains
+        .rpc
+        .mock_error(RpcChain::Bitcoin, "getrawtransaction", "No such mempool or blockchain transaction")
+        .await;
+
+    let evm = EvmTxStatus::new(chains.evm_provider(), 12);
+    assert_eq!(evm.status(fixtures::EVM_TX_HASH).await.unwrap(), ChainTxStatus::NotFound);
+    assert_eq!(chains.rpc.calls(RpcChain::Evm, "eth_blockNumber").await, 0);
+
+    let bitcoin = BitcoinTxStatus::new(chains.bitcoin_provider(), 6);
+    assert_eq!(bitcoin.status(fixtures::BTC_TXID).await.unwrap(), ChainTxStatus::NotFound);
+}
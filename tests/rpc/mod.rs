@@ -1 +1,3 @@
 pub mod rpc_manager_test;
+pub mod mock_rpc_test;
+pub mod network_status_test;
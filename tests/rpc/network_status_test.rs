@@ -0,0 +1,116 @@
+use exchange_shared::services::rpc::{
+    config::{CircuitBreakerConfig, LoadBalancingStrategy, RpcConfig, RpcEndpoint},
+    manager::RpcManager,
+};
+use serde_json::Value;
+use serial_test::serial;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+#[path = "../common/mod.rs"]
+mod common;
+
+// =============================================================================
+// INTEGRATION TESTS - NETWORK STATUS
+// =============================================================================
+
+fn config(chain: &str, urls: &[&str]) -> RpcConfig {
+    let circuit_breaker_config = CircuitBreakerConfig {
+        failure_threshold: 0.5,
+        min_requests: 5,
+        ..CircuitBreakerConfig::default()
+    };
+
+    RpcConfig {
+        chain: chain.to_string(),
+        endpoints: urls
+            .iter()
+            .map(|url| RpcEndpoint {
+                url: url.to_string(),
+                priority: 1,
+                weight: 100,
+                max_requests_per_second: None,
+                timeout_ms: 5000,
+                auth: None,
+                region: None,
+            })
+            .collect(),
+        strategy: LoadBalancingStrategy::HealthScoreBased,
+        health_check_interval: 30,
+        circuit_breaker_config,
+    }
+}
+
+#[serial]
+#[tokio::test]
+async fn test_network_status_reports_breaker_states() {
+    let mut configs = HashMap::new();
+    configs.insert(
+        "ethereum".to_string(),
+        config("ethereum", &["https://eth-failing.example.com", "https://eth-backup.example.com"]),
+    );
+    configs.insert("tron".to_string(), config("tron", &["https://tron-failing.example.com"]));
+    configs.insert("bitcoin".to_string(), config("bitcoin", &["https://btc.example.com"]));
+
+    let manager = Arc::new(RpcManager::new(configs));
+    for url in ["https://eth-failing.example.com", "https://tron-failing.example.com"] {
+        for _ in 0..10 {
+            manager.record_result(url, Duration::from_millis(100), false, None).await;
+        }
+    }
+    manager.record_result("https://btc.example.com", Duration::from_millis(100), true, Some(1)).await;
+    manager.install_global();
+
+    let server = common::setup_test_server().await;
+    let response = server.get("/status/networks").await;
+    response.assert_status_ok();
+
+    let body: Value = response.json();
+    assert_eq!(body["rpc_monitoring"], true);
+    assert_eq!(body["overall"], "down");
+
+    let network = |name: &str| {
+        body["networks"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|n| n["network"] == name)
+            .cloned()
+            .unwrap_or_else(|| panic!("{} missing from {}", name, body))
+    };
+
+    let bitcoin = network("bitcoin");
+    assert_eq!(bitcoin["severity"], "operational");
+    assert!(bitcoin["message"].is_null());
+
+    let ethereum = network("ethereum");
+    assert_eq!(ethereum["severity"], "degraded");
+    assert_eq!(ethereum["open_circuits"], 1);
+    assert_eq!(ethereum["healthy_endpoints"], 1);
+    assert!(ethereum["message"].as_str().unwrap().contains("ethereum"));
+
+    let tron = network("tron");
+    assert_eq!(tron["severity"], "down");
+    assert_eq!(tron["total_endpoints"], 1);
+}
+
+#[serial]
+#[tokio::test]
+async fn test_public_status_hides_internals() {
+    use axum::http::header;
+
+    let server = common::setup_test_server().await;
+    let response = server.get("/status/public").await;
+    response.assert_status_ok();
+    assert!(response.header(header::CACHE_CONTROL).to_str().unwrap().starts_with("public"));
+    assert!(!response.header(header::ETAG).is_empty());
+
+    let body: Value = response.json();
+    assert!(body["status"].is_string());
+    assert!(body["uptime"].is_object());
+    for network in body["networks"].as_array().unwrap() {
+        let fields: Vec<&str> = network.as_object().unwrap().keys().map(String::as_str).collect();
+        assert_eq!(fields, vec!["network", "operational", "status"], "unexpected fields in {}", network);
+    }
+}
@@ -19,9 +19,14 @@ fn create_test_endpoint(url: &str, priority: u8, weight: u32) -> RpcEndpoint {
         max_requests_per_second: None,
         timeout_ms: 5000,
         auth: None,
+        region: None,
     }
 }
 
+fn create_regional_endpoint(url: &str, priority: u8, region: Option<&str>) -> RpcEndpoint {
+    RpcEndpoint { region: region.map(str::to_string), ..create_test_endpoint(url, priority, 100) }
+}
+
 fn create_test_config(chain: &str, endpoints: Vec<RpcEndpoint>, strategy: LoadBalancingStrategy) -> RpcConfig {
     RpcConfig {
         chain: chain.to_string(),
@@ -721,3 +726,60 @@ async fn test_concurrent_endpoint_selection() {
         assert!(result.is_ok(), "Concurrent selection should succeed");
     }
 }
+
+// =============================================================================
+// REGION PREFERENCE TESTS
+// =============================================================================
+
+fn regional_manager(region: Option<&str>) -> RpcManager {
+    let endpoints = vec![
+        create_regional_endpoint("https://us.example.com", 1, Some("us-east-1")),
+        create_regional_endpoint("https://global.example.com", 1, None),
+        create_regional_endpoint("https://eu.example.com", 2, Some("eu-west-1")),
+    ];
+    let mut configs = HashMap::new();
+    configs.insert(
+        "ethereum".to_string(),
+        create_test_config("ethereum", endpoints, LoadBalancingStrategy::HealthScoreBased),
+    );
+    RpcManager::new(configs).with_region(region)
+}
+
+async fn open_circuit(manager: &RpcManager, url: &str) {
+    for _ in 0..10 {
+        manager.record_result(url, Duration::from_millis(100), false, None).await;
+    }
+}
+
+#[serial]
+#[tokio::test]
+async fn test_same_region_endpoint_preferred_over_priority() {
+    let manager = regional_manager(Some("EU-West-1"));
+    assert_eq!(manager.region(), Some("eu-west-1"));
+
+    // Same region wins even with a lower priority
+    assert_eq!(manager.select_endpoint("ethereum").await.unwrap(), "https://eu.example.com");
+}
+
+#[serial]
+#[tokio::test]
+async fn test_region_fallback_order() {
+    let manager = regional_manager(Some("eu-west-1"));
+
+    // Untagged endpoints come before other regions...
+    open_circuit(&manager, "https://eu.example.com").await;
+    assert_eq!(manager.select_endpoint("ethereum").await.unwrap(), "https://global.example.com");
+
+    // ...and other regions are the last resort
+    open_circuit(&manager, "https://global.example.com").await;
+    assert_eq!(manager.select_endpoint("ethereum").await.unwrap(), "https://us.example.com");
+}
+
+#[serial]
+#[tokio::test]
+async fn test_no_region_keeps_priority_order() {
+    let manager = regional_manager(None);
+
+    let selected = manager.select_endpoint("ethereum").await.unwrap();
+    assert_ne!(selected, "https://eu.example.com", "Priority 2 should not be picked without a region");
+}
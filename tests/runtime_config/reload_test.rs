@@ -0,0 +1,105 @@
+use axum::http::StatusCode;
+use serde_json::Value;
+use serial_test::serial;
+use sqlx::{MySql, Pool};
+
+use exchange_shared::services::runtime_config::{ConfigReloader, RATE_LIMIT_BURST};
+
+use crate::common::{create_test_user, test_email, test_password, TestContext};
+
+/// Operator override of the HTTP burst. Runs in its own test binary, since
+/// the settings are process-wide and no other binary reloads the table.
+async fn set_override(db: &Pool<MySql>, value: &str) {
+    sqlx::query(
+        "INSERT INTO runtime_settings (setting_key, setting_value) VALUES (?, ?)
+         ON DUPLICATE KEY UPDATE setting_value = VALUES(setting_value)",
+    )
+    .bind(RATE_LIMIT_BURST)
+    .bind(value)
+    .execute(db)
+    .await
+    .unwrap();
+}
+
+async fn clear_override(db: &Pool<MySql>) {
+    sqlx::query("DELETE FROM runtime_settings WHERE setting_key = ?")
+        .bind(RATE_LIMIT_BURST)
+        .execute(db)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+#[serial]
+async fn test_database_override_applies_without_a_restart() {
+    let ctx = TestContext::new().await;
+    let (admin_id, token) = create_test_user(&ctx.server, &test_email(), test_password()).await;
+    sqlx::query("UPDATE users SET is_admin = TRUE WHERE id = ?")
+        .bind(&admin_id)
+        .execute(&ctx.db)
+        .await
+        .unwrap();
+    let reloader = ConfigReloader::new(ctx.db.clone());
+
+    set_override(&ctx.db, "3").await;
+    assert!(reloader.reload().await);
+    assert!(!reloader.reload().await, "an unchanged table is not a change");
+
+    // The running app reports the override...
+    let response = ctx.server.get("/admin/config").authorization_bearer(&token).await;
+    response.assert_status_ok();
+    let body: Value = response.json();
+    let burst = body["settings"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|s| s["key"] == RATE_LIMIT_BURST)
+        .unwrap();
+    assert_eq!(burst["value"], "3");
+    assert_eq!(burst["source"], "database");
+
+    // ...and enforces it: the request above was the first of three
+    ctx.server.get("/health").await.assert_status_ok();
+    ctx.server.get("/health").await.assert_status_ok();
+    ctx.server.get("/health").await.assert_status(StatusCode::TOO_MANY_REQUESTS);
+
+    // Removing the override restores the default burst
+    clear_override(&ctx.db).await;
+    assert!(reloader.reload().await);
+    ctx.server.get("/health").await.assert_status_ok();
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+#[serial]
+async fn test_invalid_override_is_ignored() {
+    let ctx = TestContext::new().await;
+    let (admin_id, token) = create_test_user(&ctx.server, &test_email(), test_password()).await;
+    sqlx::query("UPDATE users SET is_admin = TRUE WHERE id = ?")
+        .bind(&admin_id)
+        .execute(&ctx.db)
+        .await
+        .unwrap();
+
+    set_override(&ctx.db, "0").await;
+    ConfigReloader::new(ctx.db.clone()).reload().await;
+
+    let response = ctx.server.get("/admin/config").authorization_bearer(&token).await;
+    clear_override(&ctx.db).await;
+    ConfigReloader::new(ctx.db.clone()).reload().await;
+
+    response.assert_status_ok();
+    let body: Value = response.json();
+    let burst = body["settings"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|s| s["key"] == RATE_LIMIT_BURST)
+        .unwrap();
+    assert_ne!(burst["source"], "database");
+    let rejected = body["rejected"].as_array().unwrap();
+    assert!(rejected.iter().any(|r| r["key"] == RATE_LIMIT_BURST && r["source"] == "database"));
+
+    ctx.cleanup().await;
+}
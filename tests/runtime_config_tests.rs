@@ -0,0 +1,4 @@
+mod common;
+mod runtime_config {
+    pub mod reload_test;
+}
@@ -0,0 +1,178 @@
+use axum::http::StatusCode;
+use chrono::{TimeZone, Utc};
+use serde_json::json;
+
+use crate::common::{create_user, test_email, TestContext};
+use exchange_shared::modules::schedules::model::ScheduleFrequency;
+
+fn weekly_btc_to_eth() -> serde_json::Value {
+    json!({
+        "from": "btc",
+        "network_from": "Mainnet",
+        "to": "eth",
+        "network_to": "ERC20",
+        "amount": 0.01,
+        "provider": "changenow",
+        "recipient_address": "0x742d35Cc6634C0532925a3b844Bc9e7595f0bEb0",
+        "frequency": "weekly"
+    })
+}
+
+// =============================================================================
+// CREATE / LIST
+// =============================================================================
+
+#[tokio::test]
+async fn schedules_require_authentication() {
+    let ctx = TestContext::new().await;
+
+    ctx.server
+        .post("/swap/schedules")
+        .json(&weekly_btc_to_eth())
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn create_and_list_schedule() {
+    let ctx = TestContext::new().await;
+    let (_, token) = create_user(&ctx, &test_email()).await;
+
+    let response = ctx
+        .server
+        .post("/swap/schedules")
+        .authorization_bearer(&token)
+        .json(&weekly_btc_to_eth())
+        .await;
+
+    response.assert_status(StatusCode::CREATED);
+    let created: serde_json::Value = response.json();
+    assert_eq!(created["status"], "active");
+    assert_eq!(created["frequency"], "weekly");
+
+    let list: serde_json::Value = ctx.server.get("/swap/schedules").authorization_bearer(&token).await.json();
+    let ids: Vec<&str> = list["schedules"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|s| s["id"].as_str())
+        .collect();
+    assert!(ids.contains(&created["id"].as_str().unwrap()));
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn create_rejects_non_positive_amount() {
+    let ctx = TestContext::new().await;
+    let (_, token) = create_user(&ctx, &test_email()).await;
+
+    let mut body = weekly_btc_to_eth();
+    body["amount"] = json!(0);
+
+    ctx.server
+        .post("/swap/schedules")
+        .authorization_bearer(&token)
+        .json(&body)
+        .await
+        .assert_status(StatusCode::BAD_REQUEST);
+
+    ctx.cleanup().await;
+}
+
+// =============================================================================
+// MANAGEMENT
+// =============================================================================
+
+#[tokio::test]
+async fn pause_resume_and_cancel_schedule() {
+    let ctx = TestContext::new().await;
+    let (_, token) = create_user(&ctx, &test_email()).await;
+
+    let created: serde_json::Value = ctx
+        .server
+        .post("/swap/schedules")
+        .authorization_bearer(&token)
+        .json(&weekly_btc_to_eth())
+        .await
+        .json();
+    let path = format!("/swap/schedules/{}", created["id"].as_str().unwrap());
+
+    let paused: serde_json::Value = ctx
+        .server
+        .patch(&path)
+        .authorization_bearer(&token)
+        .json(&json!({ "status": "paused", "amount": 0.02 }))
+        .await
+        .json();
+    assert_eq!(paused["status"], "paused");
+    assert_eq!(paused["amount"].as_f64().unwrap(), 0.02);
+
+    let resumed: serde_json::Value = ctx
+        .server
+        .patch(&path)
+        .authorization_bearer(&token)
+        .json(&json!({ "status": "active" }))
+        .await
+        .json();
+    assert_eq!(resumed["status"], "active");
+
+    ctx.server
+        .delete(&path)
+        .authorization_bearer(&token)
+        .await
+        .assert_status(StatusCode::NO_CONTENT);
+
+    let detail: serde_json::Value = ctx.server.get(&path).authorization_bearer(&token).await.json();
+    assert_eq!(detail["status"], "cancelled");
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn other_users_cannot_see_schedule() {
+    let ctx = TestContext::new().await;
+    let (_, owner) = create_user(&ctx, &test_email()).await;
+    let (_, other) = create_user(&ctx, &test_email()).await;
+
+    let created: serde_json::Value = ctx
+        .server
+        .post("/swap/schedules")
+        .authorization_bearer(&owner)
+        .json(&weekly_btc_to_eth())
+        .await
+        .json();
+
+    ctx.server
+        .get(&format!("/swap/schedules/{}", created["id"].as_str().unwrap()))
+        .authorization_bearer(&other)
+        .await
+        .assert_status(StatusCode::NOT_FOUND);
+
+    ctx.cleanup().await;
+}
+
+// =============================================================================
+// CADENCE
+// =============================================================================
+
+#[test]
+fn next_run_advances_by_frequency() {
+    let start = Utc.with_ymd_and_hms(2026, 3, 2, 9, 0, 0).unwrap();
+
+    assert_eq!(ScheduleFrequency::Daily.next_after(start), Utc.with_ymd_and_hms(2026, 3, 3, 9, 0, 0).unwrap());
+    assert_eq!(ScheduleFrequency::Weekly.next_after(start), Utc.with_ymd_and_hms(2026, 3, 9, 9, 0, 0).unwrap());
+    assert_eq!(ScheduleFrequency::Monthly.next_after(start), Utc.with_ymd_and_hms(2026, 4, 2, 9, 0, 0).unwrap());
+}
+
+#[test]
+fn monthly_run_clamps_to_end_of_short_month() {
+    let jan_31 = Utc.with_ymd_and_hms(2026, 1, 31, 12, 0, 0).unwrap();
+
+    assert_eq!(
+        ScheduleFrequency::Monthly.next_after(jan_31),
+        Utc.with_ymd_and_hms(2026, 2, 28, 12, 0, 0).unwrap()
+    );
+}
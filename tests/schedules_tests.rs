@@ -0,0 +1,4 @@
+mod common;
+mod schedules {
+    pub mod schedule_test;
+}
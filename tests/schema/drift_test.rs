@@ -0,0 +1,46 @@
+use exchange_shared::schema_check::{self, migrations, Severity};
+use std::path::Path;
+
+fn database_url() -> String {
+    dotenvy::dotenv().ok();
+    std::env::var("TEST_DATABASE_URL")
+        .unwrap_or_else(|_| std::env::var("DATABASE_URL").expect("DATABASE_URL must be set"))
+}
+
+#[tokio::test]
+async fn test_migrations_apply_cleanly_and_match_models() {
+    let report = schema_check::verify(&database_url(), Some(Path::new(".sqlx")))
+        .await
+        .expect("schema check should run");
+
+    assert!(!report.has_errors(), "schema drift:\n{}", report);
+    assert!(report.tables > 0);
+    assert_eq!(report.migrations_applied, schema_check::MIGRATOR.iter().count());
+}
+
+#[tokio::test]
+async fn test_migrated_test_database_has_no_history_drift() {
+    let ctx = crate::common::TestContext::new().await;
+
+    // TestContext runs every migration, so nothing may be pending either
+    let issues = migrations::history_drift(&ctx.db).await.expect("history should be readable");
+    assert!(issues.is_empty(), "history drift: {:?}", issues);
+    assert!(issues.iter().all(|i| i.severity != Severity::Error));
+}
+
+#[tokio::test]
+async fn test_scratch_database_is_dropped() {
+    let url = database_url();
+    let scratch = migrations::ScratchDatabase::create(&url).await.expect("scratch database");
+    let name = scratch.name().to_string();
+    scratch.drop_database().await;
+
+    let pool = sqlx::MySqlPool::connect(&url).await.unwrap();
+    let exists: Option<(String,)> =
+        sqlx::query_as("SELECT CAST(SCHEMA_NAME AS CHAR) FROM INFORMATION_SCHEMA.SCHEMATA WHERE SCHEMA_NAME = ?")
+            .bind(&name)
+            .fetch_optional(&pool)
+            .await
+            .unwrap();
+    assert!(exists.is_none());
+}
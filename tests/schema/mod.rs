@@ -0,0 +1 @@
+pub mod drift_test;
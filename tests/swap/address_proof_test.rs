@@ -0,0 +1,78 @@
+use axum::http::StatusCode;
+use serde_json::{json, Value};
+
+use exchange_shared::services::wallet::derive_evm_address;
+use exchange_shared::services::wallet::ownership::{verify_ownership_proof, STATEMENT_HEADER};
+
+use crate::common::{insert_swap, TestContext};
+
+fn wallet_mnemonic() -> String {
+    std::env::var("WALLET_MNEMONIC").unwrap_or_else(|_| {
+        "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about".to_string()
+    })
+}
+
+#[tokio::test]
+async fn test_proof_verifies_against_the_swap_address() {
+    let ctx = TestContext::new().await;
+    let swap_id = insert_swap(&ctx).await;
+    let index = 900_000 + rand::random::<u32>() % 100_000;
+    let address = derive_evm_address(&wallet_mnemonic(), index).await.unwrap();
+    sqlx::query(
+        r#"
+        INSERT INTO swap_address_info (swap_id, our_address, address_index, blockchain_id, coin_type, recipient_address)
+        VALUES (?, ?, ?, 1, 60, '0xrecipient')
+        "#,
+    )
+    .bind(&swap_id)
+    .bind(&address)
+    .bind(index)
+    .execute(&ctx.db)
+    .await
+    .unwrap();
+
+    let response = ctx
+        .server
+        .post(&format!("/swap/{}/address-proof", swap_id))
+        .json(&json!({ "challenge": "partner-nonce-1234" }))
+        .await;
+    response.assert_status_ok();
+    let body: Value = response.json();
+    assert_eq!(body["address"], address.as_str());
+    assert_eq!(body["scheme"], "eip191");
+    let message = body["message"].as_str().unwrap();
+    assert!(message.starts_with(STATEMENT_HEADER));
+    assert!(message.contains("Challenge: partner-nonce-1234"));
+    assert!(verify_ownership_proof(&address, message, body["signature"].as_str().unwrap()).is_ok());
+
+    let bad = ctx
+        .server
+        .post(&format!("/swap/{}/address-proof", swap_id))
+        .json(&json!({ "challenge": "short" }))
+        .await;
+    bad.assert_status(StatusCode::BAD_REQUEST);
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_swap_without_our_address_has_no_proof() {
+    let ctx = TestContext::new().await;
+    let swap_id = insert_swap(&ctx).await;
+
+    let response = ctx
+        .server
+        .post(&format!("/swap/{}/address-proof", swap_id))
+        .json(&json!({ "challenge": "partner-nonce-1234" }))
+        .await;
+    response.assert_status(StatusCode::CONFLICT);
+
+    let missing = ctx
+        .server
+        .post("/swap/no-such-swap/address-proof")
+        .json(&json!({ "challenge": "partner-nonce-1234" }))
+        .await;
+    missing.assert_status(StatusCode::NOT_FOUND);
+
+    ctx.cleanup().await;
+}
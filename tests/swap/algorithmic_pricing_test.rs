@@ -2,8 +2,16 @@ use serial_test::serial;
 #[path = "../common/mod.rs"]
 mod common;
 
+use exchange_shared::services::amount::{self, Decimal};
 use exchange_shared::services::pricing::PricingEngine;
 use exchange_shared::modules::swap::schema::TrocadorQuote;
+use exchange_shared::modules::commissions::crud::CommissionOverrides;
+use exchange_shared::modules::commissions::model::ProviderCommission;
+use std::sync::Arc;
+
+fn dec(s: &str) -> Decimal {
+    amount::parse(s).unwrap()
+}
 
 #[serial]
 #[tokio::test]
@@ -21,16 +29,17 @@ async fn test_gas_floor_protection_on_small_trades() {
             kycrating: Some("A".to_string()),
             waste: Some("0.0".to_string()),
             eta: Some(15.0),
+            fixed: None,
         }
     ];
 
-    let results = engine.apply_optimal_markup(&quotes, 0.004, "ethereum", gas_cost_native);
+    let results = engine.apply_optimal_markup(&quotes, dec("0.004"), "ethereum", gas_cost_native);
     
     // The gas cost is 0.002. Buffer is 1.5x = 0.003.
     // 1.2% of 0.004 is almost nothing. 
     // The platform_fee SHOULD be 0.003 (the gas floor).
-    assert!(results[0].platform_fee >= 0.003);
-    assert!(results[0].estimated_amount <= 0.001); // 0.004 - 0.003
+    assert!(results[0].platform_fee >= dec("0.003"));
+    assert!(results[0].estimated_amount <= dec("0.001")); // 0.004 - 0.003
     
     println!("✅ Gas floor protection verified: Fee {} covers gas cost {}", results[0].platform_fee, gas_cost_native);
 }
@@ -42,7 +51,7 @@ async fn test_whale_discount_on_large_trades() {
     let gas_cost_native = 0.001; 
     
     // Mock quotes for a large trade ($5000 equivalent)
-    let amount_from = 5000.0;
+    let amount_from = dec("5000");
     let quotes = vec![
         TrocadorQuote {
             provider: "whale_provider".to_string(),
@@ -52,6 +61,7 @@ async fn test_whale_discount_on_large_trades() {
             kycrating: Some("A".to_string()),
             waste: Some("0.0".to_string()),
             eta: Some(10.0),
+            fixed: None,
         }
     ];
 
@@ -59,7 +69,7 @@ async fn test_whale_discount_on_large_trades() {
     
     // $5000 is in the > $2000 tier (0.4%)
     // 0.4% of 5000 is 20.
-    assert_eq!(results[0].platform_fee, 20.0);
+    assert_eq!(results[0].platform_fee, dec("20"));
     
     println!("✅ Whale discount verified: Large trade fee is 0.4%");
 }
@@ -75,21 +85,151 @@ async fn test_volatility_premium_during_market_spread() {
         TrocadorQuote {
             provider: "p1".to_string(),
             amount_to: "100.0".to_string(),
-            min_amount: None, max_amount: None, kycrating: None, waste: None, eta: None,
+            min_amount: None, max_amount: None, kycrating: None, waste: None, eta: None, fixed: None,
         },
         TrocadorQuote {
             provider: "p2".to_string(),
             amount_to: "95.0".to_string(), // 5% spread
-            min_amount: None, max_amount: None, kycrating: None, waste: None, eta: None,
+            min_amount: None, max_amount: None, kycrating: None, waste: None, eta: None, fixed: None,
         }
     ];
 
-    let results = engine.apply_optimal_markup(&quotes, 100.0, "ethereum", gas_cost_native);
+    let results = engine.apply_optimal_markup(&quotes, dec("100"), "ethereum", gas_cost_native);
     
     // Spread is > 2%, so 0.5% premium is added to the 1.2% tier (since 100 is small < 200)
     // Total rate should be 1.7% (1.2 + 0.5)
     // 1.7% of 100 is 1.7.
-    assert!((results[0].platform_fee - 1.7).abs() < 0.001);
+    assert_eq!(results[0].platform_fee, dec("1.7"));
     
     println!("✅ Volatility premium verified: Fee increased during high spread");
 }
+
+#[serial]
+#[tokio::test]
+async fn test_detailed_breakdown_matches_markup() {
+    use exchange_shared::modules::swap::schema::EstimateQuery;
+
+    let engine = PricingEngine::new();
+    let gas_cost_native = 0.5;
+
+    let quotes = vec![
+        TrocadorQuote {
+            provider: "p1".to_string(),
+            amount_to: "100.0".to_string(),
+            min_amount: None, max_amount: None, kycrating: None, waste: Some("0.3".to_string()), eta: None, fixed: None,
+        },
+        TrocadorQuote {
+            provider: "p2".to_string(),
+            amount_to: "95.0".to_string(),
+            min_amount: None, max_amount: None, kycrating: None, waste: None, eta: None, fixed: None,
+        }
+    ];
+    let query = EstimateQuery {
+        from: "usdt".to_string(),
+        to: "usdc".to_string(),
+        amount: 100.0.into(),
+        network_from: "ERC20".to_string(),
+        network_to: "ERC20".to_string(),
+    };
+
+    let rates = engine.apply_optimal_markup(&quotes, query.amount.value(), &query.from, gas_cost_native);
+    let detailed = engine.detailed_breakdown(&quotes, &query, gas_cost_native).unwrap();
+
+    // Small tier (1.2%) plus the volatility premium (0.5%)
+    assert_eq!(detailed.commission.tier, "small");
+    assert!((detailed.commission.commission_rate_percentage - 1.7).abs() < 1e-9);
+    assert!((detailed.gas.gas_floor - 0.75).abs() < 1e-9);
+
+    assert_eq!(detailed.best_provider, rates[0].provider);
+    for (row, rate) in detailed.providers.iter().zip(&rates) {
+        assert_eq!(row.provider, rate.provider);
+        assert_eq!(row.platform_fee, rate.platform_fee);
+        assert_eq!(row.estimated_receive, rate.estimated_amount);
+        assert!(!row.gas_floor_applied);
+    }
+    assert_eq!(detailed.providers[0].provider_fee, dec("0.3"));
+
+    assert!(engine.detailed_breakdown(&[], &query, gas_cost_native).is_none());
+
+    println!("✅ Detailed breakdown agrees with applied markup");
+}
+
+#[serial]
+#[tokio::test]
+async fn test_provider_commission_override() {
+    use exchange_shared::modules::swap::schema::EstimateQuery;
+
+    let overrides = CommissionOverrides::new(vec![ProviderCommission {
+        provider_id: "changenow".to_string(),
+        commission_rate: Some(0.002),
+        revenue_share_rate: 0.001,
+        updated_by: None,
+        created_at: chrono::Utc::now(),
+        updated_at: chrono::Utc::now(),
+    }]);
+    let engine = PricingEngine::new().with_commissions(Arc::new(overrides));
+    let gas_cost_native = 0.001;
+
+    let quote = |provider: &str| TrocadorQuote {
+        provider: provider.to_string(),
+        amount_to: "5000.0".to_string(),
+        min_amount: Some(0.1),
+        max_amount: Some(10000.0),
+        kycrating: Some("A".to_string()),
+        waste: Some("0.0".to_string()),
+        eta: Some(10.0),
+        fixed: None,
+    };
+    let quotes = vec![quote("ChangeNOW"), quote("FixedFloat")];
+    let query = EstimateQuery {
+        from: "usdt".to_string(),
+        to: "usdc".to_string(),
+        amount: 5000.0.into(),
+        network_from: "ERC20".to_string(),
+        network_to: "ERC20".to_string(),
+    };
+
+    let rates = engine.apply_optimal_markup(&quotes, query.amount.value(), &query.from, gas_cost_native);
+    let changenow = rates.iter().find(|r| r.provider == "ChangeNOW").unwrap();
+    let fixedfloat = rates.iter().find(|r| r.provider == "FixedFloat").unwrap();
+
+    // Negotiated 0.2% instead of the 0.4% whale tier
+    assert_eq!(changenow.platform_fee, dec("10"));
+    assert_eq!(fixedfloat.platform_fee, dec("20"));
+    assert_eq!(rates[0].provider, "ChangeNOW");
+
+    let detailed = engine.detailed_breakdown(&quotes, &query, gas_cost_native).unwrap();
+    let row = detailed.providers.iter().find(|p| p.provider == "ChangeNOW").unwrap();
+    assert!((row.commission_override_percentage.unwrap() - 0.2).abs() < 1e-9);
+    assert_eq!(row.platform_fee, changenow.platform_fee);
+    let row = detailed.providers.iter().find(|p| p.provider == "FixedFloat").unwrap();
+    assert!(row.commission_override_percentage.is_none());
+
+    println!("✅ Provider commission override applied");
+}
+
+#[serial]
+#[tokio::test]
+async fn test_quote_amounts_rounded_to_currency_decimals() {
+    let engine = PricingEngine::new().with_currency("btc");
+    let quotes = vec![TrocadorQuote {
+        provider: "p1".to_string(),
+        amount_to: "0.123456789".to_string(),
+        min_amount: None,
+        max_amount: None,
+        kycrating: None,
+        waste: Some("0.000000011".to_string()),
+        eta: None,
+        fixed: None,
+    }];
+
+    let rate = &engine.apply_optimal_markup(&quotes, dec("0.1"), "eth", 0.0)[0];
+
+    // Fee rounded up, receive rounded down, both to whole satoshis
+    assert!(rate.platform_fee.scale() <= 8);
+    assert!(rate.estimated_amount.scale() <= 8);
+    assert!(rate.platform_fee > Decimal::ZERO);
+    // Nothing lost or invented between the provider amount and its parts
+    assert_eq!(rate.platform_fee + rate.estimated_amount, dec("0.12345678"));
+    assert_eq!(rate.total_fee, rate.platform_fee + dec("0.00000002"));
+}
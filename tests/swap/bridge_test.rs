@@ -0,0 +1,75 @@
+use serial_test::serial;
+use serde_json::Value;
+
+#[path = "../common/mod.rs"]
+mod common;
+use common::{setup_test_server, timed_get};
+use std::time::Duration;
+use tokio::time::sleep;
+
+// =============================================================================
+// INTEGRATION TESTS - STABLECOIN BRIDGE MODE
+// Same-ticker, different-network requests (USDT ERC20 -> USDT TRC20)
+// =============================================================================
+
+#[serial]
+#[tokio::test]
+async fn test_bridge_rates_labelled() {
+    sleep(Duration::from_secs(1)).await;
+    let server = setup_test_server().await;
+
+    // Networks are checked against the synced currency list
+    timed_get(&server, "/swap/currencies").await.assert_status_ok();
+
+    let url = "/swap/rates?from=usdt&to=usdt&amount=100&network_from=ERC20&network_to=TRC20";
+
+    let response = timed_get(&server, url).await;
+    response.assert_status_ok();
+
+    let json: Value = response.json();
+    assert_eq!(json["swap_type"], "bridge");
+
+    println!("✅ Bridge rates: {} providers", json["rates"].as_array().map(|r| r.len()).unwrap_or(0));
+}
+
+#[serial]
+#[tokio::test]
+async fn test_exchange_rates_labelled() {
+    sleep(Duration::from_secs(1)).await;
+    let server = setup_test_server().await;
+
+    let url = "/swap/rates?from=btc&to=xmr&amount=0.01&network_from=Mainnet&network_to=Mainnet";
+
+    let response = timed_get(&server, url).await;
+    response.assert_status_ok();
+
+    let json: Value = response.json();
+    assert_eq!(json["swap_type"], "exchange");
+}
+
+#[serial]
+#[tokio::test]
+async fn test_bridge_unknown_network_rejected() {
+    sleep(Duration::from_secs(1)).await;
+    let server = setup_test_server().await;
+
+    let url = "/swap/rates?from=usdt&to=usdt&amount=100&network_from=ERC20&network_to=NotANetwork";
+
+    let response = timed_get(&server, url).await;
+    response.assert_status_bad_request();
+
+    let json: Value = response.json();
+    assert!(json["error"].as_str().unwrap().contains("NotANetwork"));
+}
+
+#[serial]
+#[tokio::test]
+async fn test_same_asset_same_network_rejected() {
+    sleep(Duration::from_secs(1)).await;
+    let server = setup_test_server().await;
+
+    let url = "/swap/estimate?from=usdt&to=usdt&amount=100&network_from=ERC20&network_to=ERC20";
+
+    let response = timed_get(&server, url).await;
+    response.assert_status_bad_request();
+}
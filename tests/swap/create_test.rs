@@ -114,6 +114,90 @@ async fn test_create_swap_without_trade_id_should_fail() {
     assert!(err.get("error").is_some(), "Response should contain error field");
 }
 
+#[serial]
+#[tokio::test]
+async fn test_create_swap_rejects_forged_quote() {
+    let server = setup_test_server().await;
+
+    // A quote the server never signed; rejected before any provider call
+    let payload = json!({
+        "trade_id": "forged-trade",
+        "from": "btc",
+        "network_from": "Mainnet",
+        "to": "xmr",
+        "network_to": "Mainnet",
+        "amount": 0.001,
+        "provider": "changenow",
+        "recipient_address": "44AFFq5kSiGBoZ4NMDwYtN18obc8AemS33DBLWs3H7otXft3XjrpDtQGvj85ngqVqWfdn4ufSXIzJRHWKJ32khHA7wGwwve",
+        "quote": {
+            "payload": "eyJwbGF0Zm9ybV9mZWUiOjB9",
+            "signature": "AAAA",
+            "key_id": "0000000000000000"
+        }
+    });
+
+    let response = timed_post(&server, "/swap/create", &payload).await;
+    assert_eq!(response.status_code().as_u16(), 400);
+
+    let err: Value = response.json();
+    assert!(err["error"].as_str().unwrap().starts_with("Invalid quote"), "Unexpected error: {:?}", err);
+}
+
+#[serial]
+#[tokio::test]
+async fn test_create_swap_rejects_oversized_metadata() {
+    let server = setup_test_server().await;
+
+    // Rejected before any provider call
+    let payload = json!({
+        "trade_id": "any-trade",
+        "from": "btc",
+        "network_from": "Mainnet",
+        "to": "xmr",
+        "network_to": "Mainnet",
+        "amount": 0.001,
+        "provider": "changenow",
+        "recipient_address": "44AFFq5kSiGBoZ4NMDwYtN18obc8AemS33DBLWs3H7otXft3XjrpDtQGvj85ngqVqWfdn4ufSXIzJRHWKJ32khHA7wGwwve",
+        "client_reference": "order-1",
+        "metadata": { "blob": "x".repeat(5000) }
+    });
+
+    let response = timed_post(&server, "/swap/create", &payload).await;
+    assert_eq!(response.status_code().as_u16(), 400);
+
+    let err: Value = response.json();
+    assert!(err["error"].as_str().unwrap().starts_with("Invalid client data"), "Unexpected error: {:?}", err);
+}
+
+#[serial]
+#[tokio::test]
+async fn test_create_swap_rejects_excess_precision() {
+    let server = setup_test_server().await;
+
+    // BTC has 8 decimal places; the 9th is refused, not truncated
+    let payload = json!({
+        "from": "btc",
+        "network_from": "Mainnet",
+        "to": "xmr",
+        "network_to": "Mainnet",
+        "amount": "0.001000001",
+        "provider": "changenow",
+        "recipient_address": "44AFFq5kSiGBoZ4NMDwYtN18obc8AemS33DBLWs3H7otXft3XjrpDtQGvj85ngqVqWfdn4ufSXIzJRHWKJ32khHA7wGwwve"
+    });
+
+    let response = timed_post(&server, "/swap/create", &payload).await;
+    assert_eq!(response.status_code().as_u16(), 400);
+
+    let err: Value = response.json();
+    assert!(err["error"].as_str().unwrap().starts_with("Invalid amount"), "Unexpected error: {:?}", err);
+
+    // Malformed strings are rejected by the body parser
+    let mut malformed = payload.clone();
+    malformed["amount"] = json!("0.1btc");
+    let response = timed_post(&server, "/swap/create", &malformed).await;
+    assert!(response.status_code().is_client_error());
+}
+
 #[serial]
 #[tokio::test]
 async fn test_create_swap_invalid_address() {
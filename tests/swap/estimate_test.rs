@@ -200,6 +200,54 @@ async fn test_estimate_missing_parameters() {
     println!("✅ Missing parameters rejected");
 }
 
+#[serial]
+#[tokio::test]
+async fn test_estimate_detailed_breakdown() {
+    sleep(Duration::from_secs(1)).await;
+    let server = setup_test_server().await;
+
+    let url = "/swap/estimate/detailed?from=btc&to=xmr&amount=0.01&network_from=Mainnet&network_to=Mainnet";
+
+    let response = timed_get(&server, url).await;
+    response.assert_status_ok();
+
+    let json: Value = response.json();
+
+    // 0.01 BTC is ~$600, the medium tier
+    assert_eq!(json["commission"]["tier"], "medium");
+    assert!(json["commission"]["commission_rate_percentage"].as_f64().unwrap() >= 0.7);
+    assert!(json["gas"].get("network_fee_estimate").is_some());
+    assert!(json["gas"].get("gas_floor").is_some());
+
+    let providers = json["providers"].as_array().unwrap();
+    assert!(!providers.is_empty());
+    assert_eq!(json["best_provider"], providers[0]["provider"]);
+    for p in providers {
+        let provider_amount = p["provider_amount"].as_f64().unwrap();
+        let platform_fee = p["platform_fee"].as_f64().unwrap();
+        let receive = p["estimated_receive"].as_f64().unwrap();
+        // Receive is rounded down to XMR's 12 decimals
+        let unrounded = (provider_amount - platform_fee).max(0.0);
+        assert!(receive <= unrounded + 1e-12 && unrounded - receive < 1e-9);
+    }
+
+    println!("✅ Detailed estimate: {} providers", providers.len());
+}
+
+#[serial]
+#[tokio::test]
+async fn test_estimate_detailed_invalid_amount() {
+    sleep(Duration::from_secs(1)).await;
+    let server = setup_test_server().await;
+
+    let url = "/swap/estimate/detailed?from=btc&to=eth&amount=-0.1&network_from=Mainnet&network_to=ERC20";
+
+    let response = timed_get(&server, url).await;
+    response.assert_status_bad_request();
+
+    println!("✅ Negative amount rejected");
+}
+
 #[serial]
 #[tokio::test]
 async fn test_estimate_vs_rates_consistency() {
@@ -0,0 +1,106 @@
+use serde_json::Value;
+use std::time::Duration;
+
+use exchange_shared::modules::swap::schema::SwapStatus;
+use exchange_shared::services::swap_eta::{EtaPolicy, SwapEtaEstimator, SwapPair};
+use exchange_shared::services::swap_state::SwapStateMachine;
+
+use crate::common::{insert_status_history, insert_swap, TestContext};
+
+/// A network of its own, so other tests' swaps never count
+fn test_network() -> String {
+    format!("eta-{}", &uuid::Uuid::new_v4().simple().to_string()[..12])
+}
+
+/// A swap on `network` with history rows at the given minutes ago
+async fn insert_swap_with_history(ctx: &TestContext, network: &str, status: &str, history: &[(&str, i64)]) -> String {
+    let swap_id = insert_swap(ctx).await;
+    let completed = history.iter().find(|(s, _)| *s == "completed").map(|(_, minutes)| *minutes);
+    sqlx::query(
+        r#"
+        UPDATE swaps
+        SET from_network = ?, status = ?, completed_at = IF(? IS NULL, NULL, NOW() - INTERVAL ? MINUTE),
+            created_at = NOW() - INTERVAL 2 HOUR
+        WHERE id = ?
+        "#,
+    )
+    .bind(network)
+    .bind(status)
+    .bind(completed)
+    .bind(completed)
+    .bind(&swap_id)
+    .execute(&ctx.db)
+    .await
+    .unwrap();
+
+    for (status, minutes) in history {
+        insert_status_history(ctx, &swap_id, status, *minutes).await;
+    }
+    swap_id
+}
+
+/// Twelve completed swaps, exchanging for 8 to 19 minutes before completing
+async fn seed_completed(ctx: &TestContext, network: &str) {
+    for i in 0..12 {
+        insert_swap_with_history(ctx, network, "completed", &[("confirming", 60), ("exchanging", 58), ("completed", 50 - i)]).await;
+    }
+}
+
+#[tokio::test]
+async fn test_status_response_includes_eta() {
+    let ctx = TestContext::new().await;
+    let network = test_network();
+    seed_completed(&ctx, &network).await;
+    let swap_id = insert_swap_with_history(&ctx, &network, "exchanging", &[("confirming", 5), ("exchanging", 1)]).await;
+
+    let response = ctx.server.get(&format!("/swap/{}", swap_id)).await;
+    response.assert_status_ok();
+    let body: Value = response.json();
+    let eta = &body["eta"];
+    assert_eq!(eta["stage"], "exchanging");
+    assert_eq!(eta["basis"], "pair_provider");
+    assert_eq!(eta["samples"], 12);
+    // Median of 8..=19 minutes is 14, one of which has passed
+    let remaining = eta["remaining_minutes"].as_i64().unwrap();
+    assert!((12..=14).contains(&remaining), "remaining_minutes was {}", remaining);
+    assert!(eta["earliest_at"].as_str().unwrap() <= eta["expected_at"].as_str().unwrap());
+    assert!(eta["expected_at"].as_str().unwrap() <= eta["latest_at"].as_str().unwrap());
+
+    // Finished swaps have no estimate
+    sqlx::query("UPDATE swaps SET status = 'completed', completed_at = NOW() WHERE id = ?")
+        .bind(&swap_id)
+        .execute(&ctx.db)
+        .await
+        .unwrap();
+    let body: Value = ctx.server.get(&format!("/swap/{}", swap_id)).await.json();
+    assert!(body.get("eta").is_none());
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_eta_falls_back_to_pair_and_needs_enough_samples() {
+    let ctx = TestContext::new().await;
+    let network = test_network();
+    seed_completed(&ctx, &network).await;
+    let swap_id = insert_swap_with_history(&ctx, &network, "exchanging", &[("exchanging", 1)]).await;
+    let history = SwapStateMachine::new(ctx.db.clone()).history(&swap_id).await.unwrap();
+
+    let pair = SwapPair::new("btc", &network, "eth", "ethereum");
+    let policy = EtaPolicy { min_samples: 5, cache_ttl: Duration::ZERO, ..EtaPolicy::default() };
+    let estimator = SwapEtaEstimator::new(ctx.db.clone()).with_policy(policy.clone());
+
+    // No swaps with this provider: the pair across providers is used
+    let eta = estimator.estimate(&pair, "another-provider", &SwapStatus::Exchanging, &history).await.unwrap();
+    assert_eq!(eta.basis, "pair");
+    assert_eq!(eta.samples, 12);
+
+    // Waiting on the user's deposit has no estimate
+    assert!(estimator.estimate(&pair, "changenow", &SwapStatus::Waiting, &history).await.is_none());
+
+    // Too few comparable swaps
+    let strict = SwapEtaEstimator::new(ctx.db.clone()).with_policy(EtaPolicy { min_samples: 13, ..policy });
+    assert!(strict.estimate(&pair, "changenow", &SwapStatus::Exchanging, &history).await.is_none());
+
+    ctx.cleanup().await;
+}
@@ -0,0 +1,119 @@
+use serde_json::{json, Value};
+use std::time::Duration;
+
+use exchange_shared::services::events::stream::{EventStream, EventStreamConfig, EventStreamError, StreamOffset};
+use exchange_shared::services::events::{DomainEnvelope, DomainEvent};
+use exchange_shared::services::redis_cache::RedisService;
+
+use crate::common::{create_test_user, test_email, test_password, TestContext};
+
+/// A stream of its own, so tests don't see each other's events
+fn stream() -> EventStream {
+    dotenvy::dotenv().ok();
+    let redis_url = std::env::var("REDIS_URL").unwrap_or_else(|_| "redis://127.0.0.1/".to_string());
+    let config = EventStreamConfig {
+        enabled: true,
+        key: format!("events:test:{}", uuid::Uuid::new_v4()),
+        max_len: 1000,
+    };
+    EventStream::new(RedisService::new(&redis_url), config)
+}
+
+fn refund(swap_id: &str) -> DomainEnvelope {
+    DomainEnvelope::new(DomainEvent::RefundIssued { swap_id: swap_id.to_string() })
+}
+
+#[tokio::test]
+async fn test_replay_from_offset() {
+    let stream = stream();
+    let first = stream.append(&refund("swap-1")).await.unwrap();
+    let envelope = refund("swap-2");
+    stream.append(&envelope).await.unwrap();
+
+    let all = stream.read(&StreamOffset::Start, 10).await.unwrap();
+    assert_eq!(all.len(), 2);
+    assert_eq!(all[0].offset, first);
+    assert_eq!(all[0].kind, "refund_issued");
+
+    let rest = stream.read(&StreamOffset::parse(&first).unwrap(), 10).await.unwrap();
+    assert_eq!(rest.len(), 1);
+    assert_eq!(rest[0].swap_id, "swap-2");
+    assert_eq!(rest[0].id, envelope.id.to_string());
+    assert_eq!(rest[0].event["type"], "refund_issued");
+    assert_eq!(stream.last_offset().await.unwrap(), Some(rest[0].offset.clone()));
+}
+
+#[tokio::test]
+async fn test_consumer_group_delivers_once_until_acked() {
+    let stream = stream();
+    stream.create_group("fraud", &StreamOffset::Start).await.unwrap();
+    assert!(matches!(
+        stream.create_group("fraud", &StreamOffset::Start).await,
+        Err(EventStreamError::GroupExists(_))
+    ));
+    stream.append(&refund("swap-1")).await.unwrap();
+    stream.append(&refund("swap-2")).await.unwrap();
+
+    let first = stream.read_group("fraud", "worker-a", 1, Duration::from_millis(100)).await.unwrap();
+    let second = stream.read_group("fraud", "worker-b", 10, Duration::from_millis(100)).await.unwrap();
+    assert_eq!(first.len(), 1);
+    assert_eq!(first[0].swap_id, "swap-1");
+    assert_eq!(second.len(), 1);
+    assert_eq!(second[0].swap_id, "swap-2");
+    assert!(stream.read_group("fraud", "worker-a", 10, Duration::from_millis(100)).await.unwrap().is_empty());
+
+    // worker-a crashed before acking: its entry is still pending for it
+    let pending = stream.read_pending("fraud", "worker-a", 10).await.unwrap();
+    assert_eq!(pending[0].offset, first[0].offset);
+
+    assert_eq!(stream.ack("fraud", &[first[0].offset.clone()]).await.unwrap(), 1);
+    assert!(stream.read_pending("fraud", "worker-a", 10).await.unwrap().is_empty());
+
+    let groups = stream.groups().await.unwrap();
+    assert_eq!(groups.len(), 1);
+    assert_eq!(groups[0].name, "fraud");
+    assert_eq!(groups[0].pending, 1);
+    assert_eq!(groups[0].last_delivered, second[0].offset);
+}
+
+#[tokio::test]
+async fn test_admin_stream_endpoints() {
+    let ctx = TestContext::new().await;
+    let (admin_id, token) = create_test_user(&ctx.server, &test_email(), test_password()).await;
+    sqlx::query("UPDATE users SET is_admin = TRUE WHERE id = ?")
+        .bind(&admin_id)
+        .execute(&ctx.db)
+        .await
+        .unwrap();
+
+    let group = format!("analytics-{}", &uuid::Uuid::new_v4().to_string()[..8]);
+    let response = ctx
+        .server
+        .post("/admin/events/stream/groups")
+        .authorization_bearer(&token)
+        .json(&json!({ "name": group, "from": "end" }))
+        .await;
+    response.assert_status(axum::http::StatusCode::CREATED);
+    assert_eq!(response.json::<Value>()["name"], group.as_str());
+
+    let duplicate = ctx
+        .server
+        .post("/admin/events/stream/groups")
+        .authorization_bearer(&token)
+        .json(&json!({ "name": group }))
+        .await;
+    duplicate.assert_status(axum::http::StatusCode::CONFLICT);
+
+    let groups = ctx.server.get("/admin/events/stream/groups").authorization_bearer(&token).await;
+    groups.assert_status_ok();
+    assert!(groups.json::<Value>()["groups"].as_array().unwrap().iter().any(|g| g["name"] == group.as_str()));
+
+    let bad_offset = ctx.server.get("/admin/events/stream?after=latest").authorization_bearer(&token).await;
+    bad_offset.assert_status(axum::http::StatusCode::BAD_REQUEST);
+
+    let (_, user_token) = create_test_user(&ctx.server, &test_email(), test_password()).await;
+    let forbidden = ctx.server.get("/admin/events/stream").authorization_bearer(&user_token).await;
+    forbidden.assert_status(axum::http::StatusCode::FORBIDDEN);
+
+    ctx.cleanup().await;
+}
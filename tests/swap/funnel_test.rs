@@ -0,0 +1,105 @@
+use serde_json::Value;
+use std::time::Duration;
+
+use exchange_shared::modules::swap::schema::SwapStatus;
+use exchange_shared::services::funnel::SwapFunnel;
+use exchange_shared::services::liquidity::PairKey;
+
+use crate::common::{create_admin, create_test_user, test_email, test_password, TestContext};
+
+/// A made-up ticker so rows from other tests never share the pair
+fn unique_currency() -> String {
+    format!("f{}", &uuid::Uuid::new_v4().simple().to_string()[..8])
+}
+
+fn pair(from: &str) -> PairKey {
+    PairKey {
+        from: from.to_string(),
+        network_from: "bitcoin".to_string(),
+        to: "eth".to_string(),
+        network_to: "ethereum".to_string(),
+    }
+}
+
+async fn funnel_for(ctx: &TestContext, admin: &str, from: &str) -> Value {
+    let response = ctx
+        .server
+        .get(&format!("/admin/analytics/funnel?from={}&days=1", from))
+        .authorization_bearer(admin)
+        .await;
+    response.assert_status_ok();
+    response.json()
+}
+
+#[tokio::test]
+async fn test_funnel_tracks_stages_and_abandonment() {
+    let ctx = TestContext::new().await;
+    let admin = create_admin(&ctx).await;
+    let funnel = SwapFunnel::new(ctx.db.clone());
+    let from = unique_currency();
+
+    // Quote -> swap -> deposit -> complete
+    let completed_trade = uuid::Uuid::new_v4().to_string();
+    let completed_swap = uuid::Uuid::new_v4().to_string();
+    funnel.record_quote(&completed_trade, &pair(&from)).await.unwrap();
+    funnel
+        .record_created(&completed_swap, Some(&completed_trade), &from, "bitcoin", "eth", "ethereum", "changenow")
+        .await
+        .unwrap();
+    funnel.record_status(&completed_swap, &SwapStatus::Confirming).await.unwrap();
+    funnel.record_status(&completed_swap, &SwapStatus::Completed).await.unwrap();
+    // Replayed events must not count twice
+    funnel.record_status(&completed_swap, &SwapStatus::Completed).await.unwrap();
+
+    // Swap created without a quote that expired before any deposit
+    let expired_swap = uuid::Uuid::new_v4().to_string();
+    funnel
+        .record_created(&expired_swap, None, &from, "bitcoin", "eth", "ethereum", "changenow")
+        .await
+        .unwrap();
+    funnel.record_status(&expired_swap, &SwapStatus::Expired).await.unwrap();
+
+    // Quote nobody used, aged past the TTL
+    let stale_trade = uuid::Uuid::new_v4().to_string();
+    funnel.record_quote(&stale_trade, &pair(&from)).await.unwrap();
+    sqlx::query("UPDATE swap_funnel SET quoted_at = NOW() - INTERVAL 2 HOUR WHERE trade_id = ?")
+        .bind(&stale_trade)
+        .execute(&ctx.db)
+        .await
+        .unwrap();
+    assert!(funnel.sweep_quotes(Duration::from_secs(3600)).await.unwrap() >= 1);
+
+    let body = funnel_for(&ctx, &admin, &from).await;
+    assert_eq!(body["reached"]["quoted"], 2);
+    assert_eq!(body["reached"]["created"], 2);
+    assert_eq!(body["reached"]["created_from_quote"], 1);
+    assert_eq!(body["reached"]["deposited"], 1);
+    assert_eq!(body["reached"]["completed"], 1);
+    assert_eq!(body["abandoned"]["quoted"], 1);
+    assert_eq!(body["abandoned"]["created"], 1);
+    assert_eq!(body["abandoned"]["deposited"], 0);
+    assert_eq!(body["conversion"]["quote_to_create"], 0.5);
+    assert_eq!(body["conversion"]["deposit_to_complete"], 1.0);
+
+    // Quote-stage rows have no provider yet, so they group apart
+    let rows = body["rows"].as_array().unwrap();
+    assert!(rows.iter().any(|r| r["provider"] == "changenow" && r["reached"]["completed"] == 1));
+    assert!(rows.iter().any(|r| r["provider"].is_null() && r["abandoned"]["quoted"] == 1));
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_funnel_rejects_bad_window_and_non_admins() {
+    let ctx = TestContext::new().await;
+    let admin = create_admin(&ctx).await;
+    let (_, user) = create_test_user(&ctx.server, &test_email(), test_password()).await;
+
+    let response = ctx.server.get("/admin/analytics/funnel?days=0").authorization_bearer(&admin).await;
+    response.assert_status(axum::http::StatusCode::BAD_REQUEST);
+
+    let response = ctx.server.get("/admin/analytics/funnel").authorization_bearer(&user).await;
+    response.assert_status(axum::http::StatusCode::FORBIDDEN);
+
+    ctx.cleanup().await;
+}
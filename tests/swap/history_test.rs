@@ -278,3 +278,68 @@ async fn test_history_no_cross_user_access() {
     let json2: Value = response2.json();
     assert_eq!(json2["swaps"].as_array().unwrap().len(), 1);
 }
+
+#[tokio::test]
+#[serial]
+async fn test_history_fields_parameter() {
+    let app = setup_test_app().await;
+    let server = TestServer::new(app).unwrap();
+
+    let (user_id, token) = create_test_user(&server, "history_fields@test.com", "password123").await;
+    create_test_swap(&server, &user_id, "BTC", "ETH").await;
+
+    let response = server
+        .get("/swap/history?fields=id,status")
+        .authorization_bearer(&token)
+        .await;
+
+    assert_eq!(response.status_code(), 200);
+
+    let json: Value = response.json();
+    let swap = json["swaps"][0].as_object().unwrap();
+    assert_eq!(swap.len(), 2);
+    assert!(swap.contains_key("id") && swap.contains_key("status"));
+    // Pagination is not subject to projection
+    assert!(json["pagination"]["has_more"].is_boolean());
+
+    let response = server
+        .get("/swap/history?fields=id,password")
+        .authorization_bearer(&token)
+        .await;
+    assert_eq!(response.status_code(), 400);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_history_filter_by_client_reference() {
+    let app = setup_test_app().await;
+    let server = TestServer::new(app).unwrap();
+
+    let (user_id, token) = create_test_user(&server, "history_reference@test.com", "password123").await;
+    let tagged = create_test_swap(&server, &user_id, "BTC", "ETH").await;
+    create_test_swap(&server, &user_id, "BTC", "ETH").await;
+
+    let db_url = std::env::var("TEST_DATABASE_URL")
+        .unwrap_or_else(|_| std::env::var("DATABASE_URL").expect("DATABASE_URL must be set"));
+    let db = sqlx::mysql::MySqlPool::connect(&db_url).await.unwrap();
+    sqlx::query("UPDATE swaps SET client_reference = 'partner-42', metadata = '{\"tier\":\"gold\"}' WHERE id = ?")
+        .bind(&tagged)
+        .execute(&db)
+        .await
+        .unwrap();
+
+    let response = server
+        .get("/swap/history?client_reference=partner-42")
+        .authorization_bearer(&token)
+        .await;
+
+    assert_eq!(response.status_code(), 200);
+
+    let json: Value = response.json();
+    let swaps = json["swaps"].as_array().unwrap();
+    assert_eq!(swaps.len(), 1);
+    assert_eq!(swaps[0]["id"], tagged);
+    assert_eq!(swaps[0]["client_reference"], "partner-42");
+    assert_eq!(swaps[0]["metadata"]["tier"], "gold");
+    assert_eq!(json["filters_applied"]["client_reference"], "partner-42");
+}
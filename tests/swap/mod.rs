@@ -14,4 +14,6 @@ pub mod commission_test;
 pub mod wallet_validation_test;
 pub mod middleman_flow_test;
 pub mod algorithmic_pricing_test;
+pub mod bridge_test;
+pub mod search_test;
 
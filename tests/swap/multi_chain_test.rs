@@ -161,10 +161,10 @@ async fn test_commission_applies_across_chains() {
         // Platform fee should be part of total (commission logic)
         assert!(total_fee > 0.0, "Total fee should be greater than 0");
         
-        // Total should be sum of components (or close to it, accounting for rounding)
+        // Fees are rounded to the currency's decimals, so the parts add up
         let calculated_total = network_fee + provider_fee + platform_fee;
         let diff = (total_fee - calculated_total).abs();
-        assert!(diff < 0.001, "Total fee should roughly equal sum of components (diff: {})", diff);
+        assert!(diff < 1e-9, "Total fee should equal sum of components (diff: {})", diff);
     }
 }
 
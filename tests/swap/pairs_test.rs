@@ -218,3 +218,46 @@ async fn test_pairs_pagination_navigation() {
 }
 
 
+
+// =============================================================================
+// INTEGRATION TESTS - PAIR MATRIX
+// =============================================================================
+
+#[serial]
+#[tokio::test]
+async fn test_get_pair_matrix_structure() {
+    let server = setup_test_server().await;
+
+    let response = timed_get(&server, "/swap/pairs/matrix").await;
+
+    response.assert_status_ok();
+
+    let body: Value = response.json();
+    let pairs = body["pairs"].as_array().expect("Missing 'pairs' field");
+
+    for pair in pairs {
+        let providers = pair["providers"].as_array().unwrap();
+        assert!(!providers.is_empty(), "Pairs without providers are not tradable");
+        assert!(pair["fixed_rate"].is_boolean());
+        assert!(pair["floating_rate"].is_boolean());
+        if let (Some(min), Some(max)) = (pair["min_amount"].as_f64(), pair["max_amount"].as_f64()) {
+            assert!(min <= max, "min_amount {} above max_amount {}", min, max);
+        }
+    }
+}
+
+#[serial]
+#[tokio::test]
+async fn test_get_pair_matrix_filters() {
+    let server = setup_test_server().await;
+
+    let response = timed_get(&server, "/swap/pairs/matrix?from=btc&rate_type=fixed").await;
+
+    response.assert_status_ok();
+
+    let body: Value = response.json();
+    for pair in body["pairs"].as_array().unwrap() {
+        assert!(pair["from"].as_str().unwrap().eq_ignore_ascii_case("btc"));
+        assert_eq!(pair["fixed_rate"], true);
+    }
+}
@@ -0,0 +1,149 @@
+use axum::http::StatusCode;
+use serde_json::{json, Value};
+use serial_test::serial;
+
+use crate::common::{create_admin, create_user, test_email, TestContext};
+use exchange_shared::modules::commissions::crud::CommissionCrud;
+use exchange_shared::modules::commissions::model::RevenueEntryType;
+use exchange_shared::modules::promotions::crud::{NewRedemption, PromotionCrud};
+use exchange_shared::services::amount::{self, Decimal};
+
+/// A completed 0.1 BTC -> ETH swap that paid no platform fee
+async fn insert_completed_swap(ctx: &TestContext, user_id: &str) -> String {
+    let swap_id = uuid::Uuid::new_v4().to_string();
+
+    sqlx::query(
+        r#"
+        INSERT INTO swaps (
+            id, user_id, provider_id, from_currency, from_network, to_currency, to_network,
+            amount, estimated_receive, rate, platform_fee, deposit_address, recipient_address,
+            status, rate_type, is_sandbox
+        ) VALUES (?, ?, 'changenow', 'btc', 'bitcoin', 'eth', 'ethereum', 0.1, 2.0, 20.0, 0,
+                  ?, '0xrecipient', 'completed', 'floating', 1)
+        "#,
+    )
+    .bind(&swap_id)
+    .bind(user_id)
+    .bind(format!("bc1q{}", uuid::Uuid::new_v4().simple()))
+    .execute(&ctx.db)
+    .await
+    .unwrap();
+
+    swap_id
+}
+
+#[serial]
+#[tokio::test]
+async fn test_promotion_campaigns_and_foregone_revenue() {
+    let ctx = TestContext::new().await;
+    let (user_id, user_token) = create_user(&ctx, &test_email()).await;
+    let admin = create_admin(&ctx).await;
+
+    let weekend = json!({
+        "name": "Zero-fee weekend",
+        "from_currency": "BTC",
+        "to_currency": "ETH",
+        "fee_discount": 1.0,
+        "starts_at": "2026-01-01T00:00:00Z",
+        "ends_at": "2099-01-01T00:00:00Z",
+        "max_uses": 100
+    });
+
+    // Admin only
+    let response = ctx
+        .server
+        .post("/admin/promotions")
+        .json(&weekend)
+        .authorization_bearer(&user_token)
+        .await;
+    response.assert_status(StatusCode::FORBIDDEN);
+
+    // More than the whole fee
+    let mut invalid = weekend.clone();
+    invalid["fee_discount"] = json!(1.5);
+    let response = ctx.server.post("/admin/promotions").json(&invalid).authorization_bearer(&admin).await;
+    response.assert_status(StatusCode::BAD_REQUEST);
+
+    // Ends before it starts
+    let mut invalid = weekend.clone();
+    invalid["ends_at"] = json!("2025-01-01T00:00:00Z");
+    let response = ctx.server.post("/admin/promotions").json(&invalid).authorization_bearer(&admin).await;
+    response.assert_status(StatusCode::BAD_REQUEST);
+
+    let response = ctx.server.post("/admin/promotions").json(&weekend).authorization_bearer(&admin).await;
+    response.assert_status(StatusCode::CREATED);
+    let body: Value = response.json();
+    let promotion_id = body["id"].as_str().unwrap().to_string();
+    assert_eq!(body["from_currency"], "btc");
+    assert_eq!(body["segment"], "all");
+    assert_eq!(body["uses"], 0);
+
+    let response = ctx
+        .server
+        .patch(&format!("/admin/promotions/{}", promotion_id))
+        .json(&json!({ "priority": 5 }))
+        .authorization_bearer(&admin)
+        .await;
+    response.assert_status_ok();
+    assert_eq!(response.json::<Value>()["priority"], 5);
+
+    let body: Value = ctx.server.get("/admin/promotions").authorization_bearer(&admin).await.json();
+    assert!(body["promotions"].as_array().unwrap().iter().any(|p| p["id"] == promotion_id.as_str()));
+
+    // Uses are claimed against the cap and given back for swaps never created
+    let crud = PromotionCrud::new(ctx.db.clone());
+    assert!(crud.claim(&promotion_id, Some(&user_id)).await.unwrap());
+    crud.release(&promotion_id).await.unwrap();
+    assert_eq!(crud.get_promotion(&promotion_id).await.unwrap().unwrap().uses, 0);
+
+    // A fee-free swap books the fee it would have paid as foregone revenue
+    let swap_id = insert_completed_swap(&ctx, &user_id).await;
+    let redemption = NewRedemption {
+        promotion_id: &promotion_id,
+        swap_id: &swap_id,
+        user_id: Some(&user_id),
+        currency: "eth",
+        network: "ethereum",
+        standard_fee: amount::parse("0.024").unwrap(),
+        charged_fee: Decimal::ZERO,
+    };
+    PromotionCrud::insert_redemption(&ctx.db, &redemption).await.unwrap();
+
+    let commissions = CommissionCrud::new(ctx.db.clone());
+    assert_eq!(commissions.record_swap_revenue(&swap_id).await.unwrap(), 1);
+    assert_eq!(commissions.record_swap_revenue(&swap_id).await.unwrap(), 0);
+    let entries = commissions.revenue_entries(&swap_id).await.unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].entry_type, RevenueEntryType::PromotionDiscount);
+    assert_eq!(entries[0].amount.normalize().to_string(), "0.024");
+
+    let body: Value = ctx.server.get("/admin/promotions/report").authorization_bearer(&admin).await.json();
+    let total = body["totals"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|t| t["promotion_id"] == promotion_id.as_str())
+        .unwrap()
+        .clone();
+    assert_eq!(total["redemptions"], 1);
+    assert_eq!(total["foregone"], "0.024");
+
+    let response = ctx
+        .server
+        .delete(&format!("/admin/promotions/{}", promotion_id))
+        .authorization_bearer(&admin)
+        .await;
+    response.assert_status(StatusCode::NO_CONTENT);
+    assert!(!crud.claim(&promotion_id, Some(&user_id)).await.unwrap());
+
+    let response = ctx.server.delete("/admin/promotions/no-such-promotion").authorization_bearer(&admin).await;
+    response.assert_status(StatusCode::NOT_FOUND);
+
+    sqlx::query("DELETE FROM revenue_entries WHERE swap_id = ?")
+        .bind(&swap_id)
+        .execute(&ctx.db)
+        .await
+        .unwrap();
+    sqlx::query("DELETE FROM swaps WHERE id = ?").bind(&swap_id).execute(&ctx.db).await.unwrap();
+    sqlx::query("DELETE FROM promotions WHERE id = ?").bind(&promotion_id).execute(&ctx.db).await.unwrap();
+}
@@ -0,0 +1,166 @@
+use axum::http::StatusCode;
+use serde_json::{json, Value};
+use serial_test::serial;
+
+use crate::common::{create_admin_user, create_user, test_email, TestContext};
+use exchange_shared::modules::commissions::crud::CommissionCrud;
+use exchange_shared::modules::commissions::model::RevenueEntryType;
+use exchange_shared::services::amount::Decimal;
+use exchange_shared::services::fx::FxStore;
+use std::str::FromStr;
+
+/// A completed 2 ETH -> BTC swap through ChangeNOW that earned 0.0005 BTC
+async fn insert_completed_swap(ctx: &TestContext, user_id: &str) -> String {
+    let swap_id = uuid::Uuid::new_v4().to_string();
+
+    sqlx::query(
+        r#"
+        INSERT INTO swaps (
+            id, user_id, provider_id, from_currency, from_network, to_currency, to_network,
+            amount, estimated_receive, rate, platform_fee, deposit_address, recipient_address,
+            status, rate_type, is_sandbox
+        ) VALUES (?, ?, 'changenow', 'eth', 'ethereum', 'btc', 'bitcoin', 2.0, 0.0995, 0.05, 0.0005,
+                  ?, 'bc1qrecipient', 'completed', 'floating', 1)
+        "#,
+    )
+    .bind(&swap_id)
+    .bind(user_id)
+    .bind(format!("0x{}", uuid::Uuid::new_v4().simple()))
+    .execute(&ctx.db)
+    .await
+    .unwrap();
+
+    swap_id
+}
+
+#[serial]
+#[tokio::test]
+async fn test_provider_commission_and_revenue_ledger() {
+    let ctx = TestContext::new().await;
+    let (user_id, user_token) = create_user(&ctx, &test_email()).await;
+    let (admin_id, admin) = create_admin_user(&ctx).await;
+
+    // Admin only
+    let response = ctx
+        .server
+        .put("/admin/providers/changenow/commission")
+        .json(&json!({ "commission_rate": 0.003 }))
+        .authorization_bearer(&user_token)
+        .await;
+    response.assert_status(StatusCode::FORBIDDEN);
+
+    // Above the allowed commission
+    let response = ctx
+        .server
+        .put("/admin/providers/changenow/commission")
+        .json(&json!({ "commission_rate": 0.5 }))
+        .authorization_bearer(&admin)
+        .await;
+    response.assert_status(StatusCode::BAD_REQUEST);
+
+    let response = ctx
+        .server
+        .put("/admin/providers/no-such-provider/commission")
+        .json(&json!({ "commission_rate": 0.003 }))
+        .authorization_bearer(&admin)
+        .await;
+    response.assert_status(StatusCode::NOT_FOUND);
+
+    // Trocador's spelling of the provider resolves to its id
+    let response = ctx
+        .server
+        .put("/admin/providers/ChangeNOW/commission")
+        .json(&json!({ "commission_rate": 0.003, "revenue_share_rate": 0.001 }))
+        .authorization_bearer(&admin)
+        .await;
+    response.assert_status_ok();
+    let body: Value = response.json();
+    assert_eq!(body["provider_id"], "changenow");
+    assert_eq!(body["commission_rate"], 0.003);
+    assert_eq!(body["revenue_share_rate"], 0.001);
+    assert_eq!(body["updated_by"], admin_id.as_str());
+
+    let body: Value = ctx
+        .server
+        .get("/admin/providers/commissions")
+        .authorization_bearer(&admin)
+        .await
+        .json();
+    let listed = body["commissions"].as_array().unwrap();
+    assert!(listed.iter().any(|c| c["provider_id"] == "changenow"));
+
+    // Completion writes the fee and the provider's share, once
+    let swap_id = insert_completed_swap(&ctx, &user_id).await;
+    let crud = CommissionCrud::new(ctx.db.clone());
+    assert_eq!(crud.record_swap_revenue(&swap_id).await.unwrap(), 2);
+    assert_eq!(crud.record_swap_revenue(&swap_id).await.unwrap(), 0);
+
+    let entries = crud.revenue_entries(&swap_id).await.unwrap();
+    assert_eq!(entries.len(), 2);
+    let fee = entries.iter().find(|e| e.entry_type == RevenueEntryType::PlatformFee).unwrap();
+    assert_eq!(fee.currency, "btc");
+    assert_eq!(fee.amount.normalize().to_string(), "0.0005");
+    let share = entries.iter().find(|e| e.entry_type == RevenueEntryType::RevenueShare).unwrap();
+    assert_eq!(share.currency, "eth");
+    assert_eq!(share.amount.normalize().to_string(), "0.002");
+
+    let body: Value = ctx
+        .server
+        .get("/admin/revenue")
+        .authorization_bearer(&admin)
+        .await
+        .json();
+    let totals = body["totals"].as_array().unwrap();
+    assert!(totals
+        .iter()
+        .any(|t| t["provider_id"] == "changenow" && t["entry_type"] == "revenue_share" && t["currency"] == "eth"));
+    assert_eq!(body["reporting_currency"], "USD");
+
+    // Valued in another currency at the latest stored rate (XTS is the ISO
+    // code reserved for testing)
+    let rate_date = chrono::NaiveDate::from_ymd_opt(2000, 1, 3).unwrap();
+    let store = FxStore::new(ctx.db.clone());
+    store.upsert(&[("XTS".to_string(), rate_date, Decimal::from(2))], "test").await.unwrap();
+    store.upsert_prices(&[("eth".to_string(), rate_date, Decimal::from(3000))], "test").await.unwrap();
+    let body: Value = ctx
+        .server
+        .get("/admin/revenue?currency=xts")
+        .authorization_bearer(&admin)
+        .await
+        .json();
+    assert_eq!(body["reporting_currency"], "XTS");
+    let share = body["totals"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|t| t["provider_id"] == "changenow" && t["entry_type"] == "revenue_share" && t["currency"] == "eth")
+        .unwrap()
+        .clone();
+    let total = Decimal::from_str(share["total"].as_str().unwrap()).unwrap();
+    // ETH at the stored $3000, two XTS per dollar
+    assert_eq!(share["value"], (total * Decimal::from(6000)).round_dp(2).to_string());
+
+    let response = ctx.server.get("/admin/revenue?currency=euro").authorization_bearer(&admin).await;
+    response.assert_status(StatusCode::BAD_REQUEST);
+
+    // Removing the override returns the provider to the strategy's rates
+    let response = ctx
+        .server
+        .delete("/admin/providers/changenow/commission")
+        .authorization_bearer(&admin)
+        .await;
+    response.assert_status(StatusCode::NO_CONTENT);
+
+    let response = ctx
+        .server
+        .delete("/admin/providers/changenow/commission")
+        .authorization_bearer(&admin)
+        .await;
+    response.assert_status(StatusCode::NOT_FOUND);
+
+    sqlx::query("DELETE FROM revenue_entries WHERE swap_id = ?")
+        .bind(&swap_id)
+        .execute(&ctx.db)
+        .await
+        .unwrap();
+}
@@ -0,0 +1,66 @@
+use axum::http::StatusCode;
+use serde_json::{json, Value};
+
+use exchange_shared::modules::swap::model::PayloadKind;
+use exchange_shared::services::provider_payloads::ProviderPayloadStore;
+
+use crate::common::{create_admin, create_test_user, insert_swap, test_email, test_password, TestContext};
+
+#[tokio::test]
+async fn test_debug_shows_payloads_and_skips_repeated_status() {
+    let ctx = TestContext::new().await;
+    let admin = create_admin(&ctx).await;
+    let swap_id = insert_swap(&ctx).await;
+    let store = ProviderPayloadStore::new(ctx.db.clone());
+
+    let created = json!({ "trade_id": "trade-1", "status": "new", "amount_to": 0.05 });
+    let waiting = json!({ "trade_id": "trade-1", "status": "waiting" });
+    assert!(store.record(&swap_id, "trocador", PayloadKind::Create, &created).await.unwrap());
+    assert!(store.record(&swap_id, "trocador", PayloadKind::Status, &waiting).await.unwrap());
+    assert!(!store.record(&swap_id, "trocador", PayloadKind::Status, &waiting).await.unwrap());
+    let oversized = json!({ "trade_id": "trade-1", "status": "confirming", "details": "x".repeat(70_000) });
+    assert!(store.record(&swap_id, "trocador", PayloadKind::Status, &oversized).await.unwrap());
+
+    let response = ctx
+        .server
+        .get(&format!("/admin/swaps/{}/debug", swap_id))
+        .authorization_bearer(&admin)
+        .await;
+    response.assert_status_ok();
+    let body: Value = response.json();
+    assert_eq!(body["provider_swap_id"], "trade-1");
+
+    let payloads = body["payloads"].as_array().unwrap();
+    assert_eq!(payloads.len(), 3);
+    assert_eq!(payloads[0]["kind"], "create");
+    assert_eq!(payloads[0]["payload"], created);
+    assert_eq!(payloads[1]["payload"], waiting);
+    assert_eq!(payloads[1]["truncated"], false);
+
+    assert_eq!(payloads[2]["truncated"], true);
+    assert_eq!(payloads[2]["original_bytes"], oversized.to_string().len());
+    assert_eq!(payloads[2]["payload"]["truncated"], true);
+    assert!(oversized.to_string().starts_with(payloads[2]["payload"]["prefix"].as_str().unwrap()));
+}
+
+#[tokio::test]
+async fn test_debug_requires_admin_and_known_swap() {
+    let ctx = TestContext::new().await;
+    let swap_id = insert_swap(&ctx).await;
+    let (_, user) = create_test_user(&ctx.server, &test_email(), test_password()).await;
+
+    let response = ctx
+        .server
+        .get(&format!("/admin/swaps/{}/debug", swap_id))
+        .authorization_bearer(&user)
+        .await;
+    response.assert_status(StatusCode::FORBIDDEN);
+
+    let admin = create_admin(&ctx).await;
+    let response = ctx
+        .server
+        .get("/admin/swaps/00000000-0000-0000-0000-000000000000/debug")
+        .authorization_bearer(&admin)
+        .await;
+    response.assert_status(StatusCode::NOT_FOUND);
+}
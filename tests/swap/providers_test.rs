@@ -448,3 +448,62 @@ async fn test_response_time_acceptable() {
 
     println!("Providers endpoint working correctly");
 }
+
+#[serial]
+#[tokio::test]
+async fn test_fields_parameter_projects_providers() {
+    let server = setup_test_server().await;
+
+    let response = timed_get(&server, "/swap/providers?fields=name,rating").await;
+    response.assert_status_ok();
+
+    let providers: Vec<Value> = response.json();
+    for provider in &providers {
+        let keys: Vec<&String> = provider.as_object().unwrap().keys().collect();
+        assert_eq!(keys, vec!["name", "rating"], "Only requested fields should be returned");
+    }
+}
+
+#[serial]
+#[tokio::test]
+async fn test_fields_parameter_rejects_unknown_field() {
+    let server = setup_test_server().await;
+
+    let response = timed_get(&server, "/swap/providers?fields=name,kyc").await;
+    assert_eq!(response.status_code(), 400);
+
+    let body: Value = response.json();
+    let error = body["error"].as_str().unwrap();
+    assert!(error.contains("kyc"), "Error should name the unknown field: {}", error);
+}
+
+#[serial]
+#[tokio::test]
+async fn test_providers_etag_returns_not_modified() {
+    use axum::http::{header, HeaderValue};
+
+    let server = setup_test_server().await;
+
+    let response = timed_get(&server, "/swap/providers").await;
+    response.assert_status_ok();
+    assert!(response.header(header::CACHE_CONTROL).to_str().unwrap().contains("max-age"));
+
+    // Let the response land in the cache so the next read is byte-identical
+    tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+    let cached = timed_get(&server, "/swap/providers").await;
+    let etag = cached.header(header::ETAG);
+
+    let revalidated = server
+        .get("/swap/providers")
+        .add_header(header::IF_NONE_MATCH, etag.clone())
+        .await;
+    assert_eq!(revalidated.status_code(), 304);
+    assert_eq!(revalidated.header(header::ETAG), etag);
+    assert!(revalidated.as_bytes().is_empty());
+
+    let changed = server
+        .get("/swap/providers")
+        .add_header(header::IF_NONE_MATCH, HeaderValue::from_static("\"stale\""))
+        .await;
+    changed.assert_status_ok();
+}
@@ -0,0 +1,157 @@
+use axum::http::StatusCode;
+use serde_json::{json, Value};
+
+use crate::common::{create_admin, create_user, insert_swap, test_email, TestContext};
+
+/// A completed swap with a deposit hash and a payout from our own address
+async fn insert_paid_out_swap(ctx: &TestContext, user_id: &str, tx_hash_in: &str, payout_tx_hash: &str) -> (String, String) {
+    let swap_id = insert_swap(ctx).await;
+    let deposit_address = format!("0x{}", uuid::Uuid::new_v4().simple());
+
+    sqlx::query(
+        r#"
+        UPDATE swaps
+        SET user_id = ?, deposit_address = ?, recipient_address = 'bc1qrecipient', tx_hash_in = ?, status = 'completed'
+        WHERE id = ?
+        "#,
+    )
+    .bind(user_id)
+    .bind(&deposit_address)
+    .bind(tx_hash_in)
+    .bind(&swap_id)
+    .execute(&ctx.db)
+    .await
+    .unwrap();
+
+    sqlx::query(
+        r#"
+        INSERT INTO swap_address_info (
+            swap_id, our_address, address_index, blockchain_id, coin_type,
+            recipient_address, payout_tx_hash, status
+        )
+        VALUES (?, 'bc1qourpayoutaddress', 0, 0, 0, 'bc1qrecipient', ?, 'success')
+        "#,
+    )
+    .bind(&swap_id)
+    .bind(payout_tx_hash)
+    .execute(&ctx.db)
+    .await
+    .unwrap();
+
+    (swap_id, deposit_address)
+}
+
+fn random_hash() -> String {
+    format!("{}{}", uuid::Uuid::new_v4().simple(), uuid::Uuid::new_v4().simple())
+}
+
+#[tokio::test]
+async fn test_search_by_tx_hash_and_address() {
+    let ctx = TestContext::new().await;
+    let email = test_email();
+    let (user_id, _) = create_user(&ctx, &email).await;
+    let admin = create_admin(&ctx).await;
+
+    let tx_hash_in = format!("0x{}", random_hash());
+    let payout_tx_hash = random_hash();
+    let (swap_id, deposit_address) = insert_paid_out_swap(&ctx, &user_id, &tx_hash_in, &payout_tx_hash).await;
+
+    // Deposit hash pasted without 0x
+    let response = ctx
+        .server
+        .get("/admin/swaps/search")
+        .add_query_param("tx_hash", &tx_hash_in[2..])
+        .authorization_bearer(&admin)
+        .await;
+    response.assert_status_ok();
+    let body: Value = response.json();
+    assert_eq!(body["results"].as_array().unwrap().len(), 1);
+    assert_eq!(body["results"][0]["id"], swap_id.as_str());
+    assert_eq!(body["results"][0]["matched"], json!(["tx_hash_in"]));
+    assert_eq!(body["results"][0]["payout_tx_hash"], payout_tx_hash.as_str());
+    assert_eq!(body["partial"], false);
+
+    // Payout hash truncated the way explorers display it
+    let truncated = format!("{}...{}", &payout_tx_hash[..10], &payout_tx_hash[58..]);
+    let body: Value = ctx
+        .server
+        .get("/admin/swaps/search")
+        .add_query_param("tx_hash", &truncated)
+        .authorization_bearer(&admin)
+        .await
+        .json();
+    assert_eq!(body["results"][0]["id"], swap_id.as_str());
+    assert_eq!(body["results"][0]["matched"], json!(["payout_tx_hash"]));
+    assert_eq!(body["partial"], true);
+
+    // Address plus email narrows to the same swap
+    let body: Value = ctx
+        .server
+        .get("/admin/swaps/search")
+        .add_query_param("address", &deposit_address)
+        .add_query_param("email", &email)
+        .authorization_bearer(&admin)
+        .await
+        .json();
+    assert_eq!(body["results"].as_array().unwrap().len(), 1);
+    assert_eq!(body["results"][0]["matched"], json!(["deposit_address", "email"]));
+    assert_eq!(body["results"][0]["user_email"], email.as_str());
+}
+
+#[tokio::test]
+async fn test_limit_applies_after_criteria_are_combined() {
+    let ctx = TestContext::new().await;
+    let email = test_email();
+    let (user_id, _) = create_user(&ctx, &email).await;
+    let admin = create_admin(&ctx).await;
+
+    let (old_swap, deposit_address) = insert_paid_out_swap(&ctx, &user_id, &random_hash(), &random_hash()).await;
+    sqlx::query("UPDATE swaps SET created_at = NOW() - INTERVAL 1 DAY WHERE id = ?")
+        .bind(&old_swap)
+        .execute(&ctx.db)
+        .await
+        .unwrap();
+    // Newer swaps fill the first page of the email's matches
+    for _ in 0..2 {
+        insert_paid_out_swap(&ctx, &user_id, &random_hash(), &random_hash()).await;
+    }
+
+    let body: Value = ctx
+        .server
+        .get("/admin/swaps/search")
+        .add_query_param("address", &deposit_address)
+        .add_query_param("email", &email)
+        .add_query_param("limit", "1")
+        .authorization_bearer(&admin)
+        .await
+        .json();
+    assert_eq!(body["results"].as_array().unwrap().len(), 1);
+    assert_eq!(body["results"][0]["id"], old_swap.as_str());
+}
+
+#[tokio::test]
+async fn test_search_requires_admin_and_criteria() {
+    let ctx = TestContext::new().await;
+    let (_, token) = create_user(&ctx, &test_email()).await;
+    let admin = create_admin(&ctx).await;
+
+    let response = ctx
+        .server
+        .get("/admin/swaps/search")
+        .add_query_param("tx_hash", random_hash())
+        .authorization_bearer(&token)
+        .await;
+    assert_eq!(response.status_code(), StatusCode::FORBIDDEN);
+
+    let response = ctx.server.get("/admin/swaps/search").authorization_bearer(&admin).await;
+    assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
+
+    // Too short to search by prefix
+    let response = ctx
+        .server
+        .get("/admin/swaps/search")
+        .add_query_param("tx_hash", "0x12...ab")
+        .authorization_bearer(&admin)
+        .await;
+    assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
+}
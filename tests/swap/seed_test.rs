@@ -0,0 +1,115 @@
+use axum::http::StatusCode;
+use serde_json::{json, Value};
+use serial_test::serial;
+
+use crate::common::{test_email, TestContext};
+use exchange_shared::modules::seed::factories::{
+    ProviderFactory, SwapFactory, UserFactory, BASELINE_ADMIN_EMAIL, DEFAULT_SEED_PASSWORD,
+};
+use exchange_shared::modules::swap::schema::SwapStatus;
+
+async fn login(ctx: &TestContext, email: &str, password: &str) -> String {
+    let response = ctx
+        .server
+        .post("/auth/login")
+        .json(&json!({ "email": email, "password": password }))
+        .await;
+    assert_eq!(response.status_code(), StatusCode::OK, "{}", response.text());
+    response.json::<Value>()["access_token"].as_str().unwrap().to_string()
+}
+
+#[tokio::test]
+async fn test_factories_create_records_in_known_states() {
+    let ctx = TestContext::new().await;
+
+    let provider = ProviderFactory::new("seedtest")
+        .name("Seed Test")
+        .rating(3.3)
+        .active(false)
+        .create(&ctx.db)
+        .await
+        .unwrap();
+    let (rating, is_active): (f32, bool) = sqlx::query_as("SELECT rating, is_active FROM providers WHERE id = ?")
+        .bind(&provider)
+        .fetch_one(&ctx.db)
+        .await
+        .unwrap();
+    assert!((rating - 3.3).abs() < 1e-6);
+    assert!(!is_active);
+
+    let user = UserFactory::new().verified().create(&ctx.db).await.unwrap();
+    let swap = SwapFactory::new()
+        .user(&user.id)
+        .status(SwapStatus::Refunded)
+        .create(&ctx.db)
+        .await
+        .unwrap();
+
+    // Seeding again resets the swap rather than failing on the duplicate
+    SwapFactory::new().id(&swap).user(&user.id).status(SwapStatus::Expired).create(&ctx.db).await.unwrap();
+    let (status,): (String,) = sqlx::query_as("SELECT CAST(status AS CHAR) FROM swaps WHERE id = ? AND user_id = ?")
+        .bind(&swap)
+        .bind(&user.id)
+        .fetch_one(&ctx.db)
+        .await
+        .unwrap();
+    assert_eq!(status, "expired");
+
+    // Seeded users can log in with the seed password
+    login(&ctx, &user.email, DEFAULT_SEED_PASSWORD).await;
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+#[serial]
+async fn test_seed_api_restores_baseline() {
+    let ctx = TestContext::new().await;
+    let admin = UserFactory::new().email(&test_email()).admin().create(&ctx.db).await.unwrap();
+    let token = login(&ctx, &admin.email, DEFAULT_SEED_PASSWORD).await;
+
+    std::env::remove_var("SEED_API_ENABLED");
+    let response = ctx
+        .server
+        .post("/_seed")
+        .authorization_bearer(&token)
+        .json(&json!({ "baseline": true }))
+        .await;
+    assert_eq!(response.status_code(), StatusCode::NOT_FOUND, "Disabled unless SEED_API_ENABLED is set");
+
+    // Drift a baseline provider, then reseed
+    sqlx::query("UPDATE providers SET rating = 1.0 WHERE id = 'changenow'")
+        .execute(&ctx.db)
+        .await
+        .unwrap();
+
+    std::env::set_var("SEED_API_ENABLED", "true");
+    let response = ctx
+        .server
+        .post("/_seed")
+        .authorization_bearer(&token)
+        .json(&json!({
+            "baseline": true,
+            "swaps": [{ "from": "eth", "network_from": "ethereum", "to": "btc", "network_to": "bitcoin",
+                        "amount": 2.0, "status": "completed" }]
+        }))
+        .await;
+    std::env::remove_var("SEED_API_ENABLED");
+    assert_eq!(response.status_code(), StatusCode::OK, "{}", response.text());
+
+    let body: Value = response.json();
+    assert_eq!(body["providers"].as_array().unwrap().len(), 8);
+    // One baseline swap per status, plus the extra one
+    assert_eq!(body["swaps"].as_array().unwrap().len(), 10);
+
+    let (rating,): (f32,) = sqlx::query_as("SELECT rating FROM providers WHERE id = 'changenow'")
+        .fetch_one(&ctx.db)
+        .await
+        .unwrap();
+    assert!((rating - 4.5).abs() < 1e-6);
+
+    // The baseline admin works straight away
+    login(&ctx, BASELINE_ADMIN_EMAIL, DEFAULT_SEED_PASSWORD).await;
+
+    ctx.cleanup().await;
+}
@@ -413,3 +413,115 @@ async fn test_get_swap_status_performance() {
     
     println!("Status retrieval completed in: {:?}", duration);
 }
+
+/// A failed swap shows what its refund is made of, and the refund's hash
+/// once it is sent
+#[serial]
+#[tokio::test]
+async fn test_get_swap_status_refund_breakdown() {
+    let ctx = common::TestContext::new().await;
+    let swap_id = uuid::Uuid::new_v4().to_string();
+
+    sqlx::query(
+        r#"
+        INSERT INTO swaps (
+            id, provider_id, from_currency, from_network, to_currency, to_network,
+            amount, estimated_receive, rate, platform_fee, total_fee,
+            deposit_address, recipient_address, status
+        )
+        VALUES (?, 'changenow', 'BTC', 'bitcoin', 'ETH', 'ethereum', 0.1, 1.5, 15.0, 0.001, 0.002,
+                ?, 'rec_addr', 'failed')
+        "#,
+    )
+    .bind(&swap_id)
+    .bind(format!("dep_{}", swap_id))
+    .execute(&ctx.db)
+    .await
+    .unwrap();
+
+    let response = timed_get(&ctx.server, &format!("/swap/{}", swap_id)).await;
+    response.assert_status_ok();
+    let refund = response.json::<Value>()["refund"].clone();
+    assert_eq!(refund["original_amount"].as_f64(), Some(0.1));
+    assert_eq!(refund["currency"], "BTC");
+    assert_eq!(refund["platform_fee"].as_f64(), Some(0.001));
+    assert_eq!(refund["network_fee"].as_f64(), Some(0.0001));
+    // 0.1 - 0.001 platform - 0.002 swap fee - 0.0001 network
+    assert!((refund["refund_amount"].as_f64().unwrap() - 0.0969).abs() < 1e-9);
+    assert_eq!(refund["status"], "estimated");
+    assert!(refund.get("tx_hash").is_none());
+
+    // Once sent, the actual amount, fee and hash replace the estimate
+    sqlx::query(
+        r#"
+        INSERT INTO refunds (
+            id, swap_id, idempotency_key, refund_address, refund_amount, refund_currency,
+            refund_network, tx_hash, total_fee, status
+        )
+        VALUES (?, ?, ?, 'bc1qrefund', 0.09695, 'BTC', 'bitcoin', 'refundtx123', 0.00005, 'COMPLETED')
+        "#,
+    )
+    .bind(uuid::Uuid::new_v4().to_string())
+    .bind(&swap_id)
+    .bind(format!("refund_{}", swap_id))
+    .execute(&ctx.db)
+    .await
+    .unwrap();
+
+    let response = timed_get(&ctx.server, &format!("/swap/{}", swap_id)).await;
+    let refund = response.json::<Value>()["refund"].clone();
+    assert_eq!(refund["refund_amount"].as_f64(), Some(0.09695));
+    assert_eq!(refund["network_fee"].as_f64(), Some(0.00005));
+    assert_eq!(refund["tx_hash"], "refundtx123");
+    assert_eq!(refund["status"], "completed");
+
+    ctx.cleanup().await;
+}
+
+/// A completed swap has a receipt for its owner, with a hash that is the
+/// same on every copy; strangers need a receipt token
+#[serial]
+#[tokio::test]
+async fn test_get_swap_receipt() {
+    let ctx = common::TestContext::new().await;
+    let (user_id, token) = common::create_test_user(&ctx.server, &common::test_email(), common::test_password()).await;
+    let swap_id = common::create_test_swap(&ctx.server, &user_id, "btc", "eth").await;
+
+    // Not completed yet
+    let response = ctx.server.get(&format!("/swap/{}/receipt", swap_id)).authorization_bearer(&token).await;
+    assert_eq!(response.status_code(), 409);
+
+    sqlx::query(
+        "UPDATE swaps SET status = 'completed', actual_receive = 1.49, tx_hash_in = 'txin', tx_hash_out = 'txout', completed_at = NOW() WHERE id = ?",
+    )
+    .bind(&swap_id)
+    .execute(&ctx.db)
+    .await
+    .unwrap();
+
+    let response = ctx.server.get(&format!("/swap/{}/receipt", swap_id)).await;
+    assert_eq!(response.status_code(), 401);
+
+    let response = ctx.server.get(&format!("/swap/{}/receipt", swap_id)).authorization_bearer(&token).await;
+    response.assert_status_ok();
+    let receipt: Value = response.json();
+    assert_eq!(receipt["swap_id"], swap_id);
+    assert_eq!(receipt["received_amount"].as_f64(), Some(1.49));
+    assert_eq!(receipt["deposit_tx_hash"], "txin");
+    assert_eq!(receipt["payout_tx_hash"], "txout");
+    assert_eq!(receipt["recipient_address"], "test_recipient");
+    assert_eq!(receipt["verification_hash"].as_str().unwrap().len(), 64);
+
+    let again: Value = ctx.server.get(&format!("/swap/{}/receipt", swap_id)).authorization_bearer(&token).await.json();
+    assert_eq!(again["verification_hash"], receipt["verification_hash"]);
+
+    // With RECEIPT_SIGNING_KEY set the token opens the receipt without signing in
+    if let Some(receipt_token) = receipt["receipt_token"].as_str() {
+        let response = timed_get(&ctx.server, &format!("/swap/{}/receipt?token={}", swap_id, receipt_token)).await;
+        response.assert_status_ok();
+        assert_eq!(response.json::<Value>()["verification_hash"], receipt["verification_hash"]);
+
+        let response = timed_get(&ctx.server, &format!("/swap/{}/receipt?token=1.forged", swap_id)).await;
+        assert_eq!(response.status_code(), 403);
+    }
+}
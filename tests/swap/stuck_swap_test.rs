@@ -0,0 +1,109 @@
+use async_trait::async_trait;
+use serde_json::Value;
+use std::sync::{Arc, Mutex};
+
+use exchange_shared::services::swap_sla::{SlaPolicy, StuckSwapTicket, SupportDesk, SwapSlaMonitor};
+
+use crate::common::{
+    create_admin, create_test_user, insert_status_history, insert_swap, test_email, test_password, TestContext,
+};
+
+/// Records tickets instead of opening them
+#[derive(Default)]
+struct RecordingDesk {
+    tickets: Mutex<Vec<StuckSwapTicket>>,
+}
+
+#[async_trait]
+impl SupportDesk for RecordingDesk {
+    async fn open_ticket(&self, ticket: &StuckSwapTicket) -> Result<Option<String>, String> {
+        self.tickets.lock().unwrap().push(ticket.clone());
+        Ok(Some(format!("TICKET-{}", ticket.swap_id)))
+    }
+}
+
+/// A swap that entered `status` `minutes` ago
+async fn insert_swap_in_status(ctx: &TestContext, status: &str, minutes: i64) -> String {
+    let swap_id = insert_swap(ctx).await;
+    sqlx::query("UPDATE swaps SET status = ?, created_at = NOW() - INTERVAL ? MINUTE WHERE id = ?")
+        .bind(status)
+        .bind(minutes + 30)
+        .bind(&swap_id)
+        .execute(&ctx.db)
+        .await
+        .unwrap();
+    insert_status_history(ctx, &swap_id, status, minutes).await;
+    swap_id
+}
+
+async fn stuck_queue(ctx: &TestContext, admin: &str) -> Vec<Value> {
+    let response = ctx.server.get("/admin/swaps/stuck?limit=500").authorization_bearer(admin).await;
+    response.assert_status_ok();
+    let body: Value = response.json();
+    body["swaps"].as_array().unwrap().clone()
+}
+
+#[tokio::test]
+async fn test_stuck_swap_is_escalated_and_resolved() {
+    let ctx = TestContext::new().await;
+    let admin = create_admin(&ctx).await;
+    // Exchanging for 4h against a 2h SLA: past every escalation step
+    let swap_id = insert_swap_in_status(&ctx, "exchanging", 240).await;
+
+    let desk = Arc::new(RecordingDesk::default());
+    let monitor = SwapSlaMonitor::new(ctx.db.clone(), SlaPolicy::default()).with_support_desk(Some(desk.clone() as Arc<dyn SupportDesk>));
+    let report = monitor.evaluate().await.unwrap();
+    assert!(report.breached >= 1);
+    assert!(desk.tickets.lock().unwrap().iter().any(|t| t.swap_id == swap_id && t.sla_minutes == 120));
+
+    // The provider is polled again on the monitor's next pass
+    let repolled: (bool,) =
+        sqlx::query_as("SELECT next_poll_at <= NOW() FROM polling_states WHERE swap_id = ?")
+            .bind(&swap_id)
+            .fetch_one(&ctx.db)
+            .await
+            .unwrap();
+    assert!(repolled.0);
+
+    let queue = stuck_queue(&ctx, &admin).await;
+    let entry = queue.iter().find(|s| s["swap_id"] == swap_id.as_str()).expect("swap is in the stuck queue");
+    assert_eq!(entry["status"], "exchanging");
+    assert_eq!(entry["escalation"], "ticketed");
+    assert_eq!(entry["ticket_reference"], format!("TICKET-{}", swap_id));
+    assert!(entry["minutes_in_status"].as_i64().unwrap() >= 240);
+
+    // A second pass leaves the breach alone
+    monitor.evaluate().await.unwrap();
+    assert_eq!(desk.tickets.lock().unwrap().iter().filter(|t| t.swap_id == swap_id).count(), 1);
+
+    sqlx::query("UPDATE swaps SET status = 'completed' WHERE id = ?")
+        .bind(&swap_id)
+        .execute(&ctx.db)
+        .await
+        .unwrap();
+    monitor.evaluate().await.unwrap();
+    assert!(!stuck_queue(&ctx, &admin).await.iter().any(|s| s["swap_id"] == swap_id.as_str()));
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_swap_within_sla_is_not_flagged() {
+    let ctx = TestContext::new().await;
+    let admin = create_admin(&ctx).await;
+    let swap_id = insert_swap_in_status(&ctx, "exchanging", 10).await;
+
+    let monitor = SwapSlaMonitor::new(ctx.db.clone(), SlaPolicy::default()).with_support_desk(None);
+    monitor.evaluate().await.unwrap();
+
+    assert!(!stuck_queue(&ctx, &admin).await.iter().any(|s| s["swap_id"] == swap_id.as_str()));
+
+    let response = ctx
+        .server
+        .get("/admin/swaps/stuck")
+        .authorization_bearer(&create_test_user(&ctx.server, &test_email(), test_password()).await.1)
+        .await;
+    response.assert_status(axum::http::StatusCode::FORBIDDEN);
+
+    ctx.cleanup().await;
+}
@@ -1,9 +1,10 @@
 use serial_test::serial;
 use serde_json::{json, Value};
+use axum::http::{header, HeaderValue};
 
 #[path = "../common/mod.rs"]
 mod common;
-use common::{setup_test_server, timed_post};
+use common::{setup_test_server, timed_get, timed_post};
 use std::time::Duration;
 use tokio::time::sleep;
 
@@ -504,3 +505,59 @@ async fn test_validate_address_response_structure() {
     
     println!("Response structure validated successfully");
 }
+
+// =============================================================================
+// ADDRESS SPEC (GET /swap/currencies/{ticker}/address-spec)
+// =============================================================================
+
+/// The published spec is what validation enforces: its example passes the
+/// pattern, and an address outside it is rejected without asking the provider
+#[serial]
+#[tokio::test]
+async fn test_address_spec_matches_validation() {
+    let server = setup_test_server().await;
+
+    let response = timed_get(&server, "/swap/currencies/usdt/address-spec?network=TRC20").await;
+    response.assert_status_ok();
+    let spec: Value = response.json();
+    assert_eq!(spec["prefixes"], json!(["T"]));
+    assert_eq!(spec["min_length"], 34);
+    assert_eq!(spec["lang"], "en");
+    let pattern = regex::Regex::new(spec["pattern"].as_str().unwrap()).unwrap();
+    assert!(pattern.is_match(spec["example"].as_str().unwrap()));
+
+    // An ERC-20 address for a TRC-20 payout fails locally
+    let payload = json!({
+        "ticker": "usdt",
+        "network": "TRC20",
+        "address": "0x742d35Cc6634C0532925a3b844Bc9e7595f5bE12"
+    });
+    let response = timed_post(&server, "/swap/validate-address", &payload).await;
+    response.assert_status_ok();
+    assert_eq!(response.json::<Value>()["valid"], false);
+}
+
+/// Hints follow `lang`, then Accept-Language; memo chains describe the memo
+#[serial]
+#[tokio::test]
+async fn test_address_spec_hints_and_memo() {
+    let server = setup_test_server().await;
+
+    let response = server
+        .get("/swap/currencies/xrp/address-spec")
+        .add_header(header::ACCEPT_LANGUAGE, HeaderValue::from_static("es-ES,es;q=0.9"))
+        .await;
+    response.assert_status_ok();
+    let spec: Value = response.json();
+    assert_eq!(spec["lang"], "es");
+    assert!(spec["hint"].as_str().unwrap().starts_with("Dirección XRP Ledger"));
+    assert_eq!(spec["memo"]["name"], "Destination Tag");
+
+    let spec: Value = timed_get(&server, "/swap/currencies/btc/address-spec?lang=de").await.json();
+    assert_eq!(spec["lang"], "de");
+    assert!(spec.get("memo").is_none());
+
+    // Tokens need a network to pick a format
+    let response = timed_get(&server, "/swap/currencies/usdt/address-spec").await;
+    assert_eq!(response.status_code().as_u16(), 404);
+}
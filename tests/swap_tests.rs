@@ -11,4 +11,14 @@ mod swap {
     pub mod validate_address_test;
     pub mod middleman_flow_test;
     pub mod algorithmic_pricing_test;
+    pub mod search_test;
+    pub mod provider_commission_test;
+    pub mod promotion_test;
+    pub mod seed_test;
+    pub mod provider_payload_test;
+    pub mod address_proof_test;
+    pub mod stuck_swap_test;
+    pub mod event_stream_test;
+    pub mod funnel_test;
+    pub mod eta_test;
 }
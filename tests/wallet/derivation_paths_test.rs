@@ -0,0 +1,75 @@
+// =============================================================================
+// INTEGRATION TESTS - DERIVATION PATH GUARD
+// Startup refuses a path configuration that would derive different keys for
+// addresses that already exist, unless the change is explicitly allowed
+// =============================================================================
+
+#[path = "../common/mod.rs"]
+mod common;
+
+use exchange_shared::services::wallet::paths::{
+    guard_path_changes, DerivationPaths, PathChain, DEFAULT_BITCOIN_PATH, DEFAULT_EVM_PATH,
+};
+use common::TestContext;
+use uuid::Uuid;
+
+async fn recorded_path(ctx: &TestContext, chain: PathChain) -> Option<String> {
+    sqlx::query_scalar("SELECT path_template FROM hd_derivation_paths WHERE chain = ?")
+        .bind(chain.as_str())
+        .fetch_optional(&ctx.db)
+        .await
+        .unwrap()
+}
+
+// =============================================================================
+// TEST 1: Changing the EVM path over existing addresses needs an override
+// =============================================================================
+
+#[tokio::test]
+async fn test_path_change_over_existing_addresses_is_refused() {
+    let ctx = TestContext::new().await;
+    let defaults = DerivationPaths::default();
+
+    guard_path_changes(&ctx.db, &defaults, false).await.expect("default paths are always accepted");
+    assert_eq!(
+        recorded_path(&ctx, PathChain::Evm).await.as_deref(),
+        Some(defaults.template(PathChain::Evm))
+    );
+
+    let swap_id = Uuid::new_v4().to_string();
+    sqlx::query(
+        r#"
+        INSERT INTO swap_address_info (
+            swap_id, our_address, address_index, blockchain_id, coin_type, recipient_address, status
+        )
+        VALUES (?, '0x000000000000000000000000000000000000dEaD', 0, 1, 60, '0xrecipient', 'pending')
+        "#,
+    )
+    .bind(&swap_id)
+    .execute(&ctx.db)
+    .await
+    .unwrap();
+
+    let moved = DerivationPaths::new(1, 0, DEFAULT_BITCOIN_PATH, DEFAULT_EVM_PATH).unwrap();
+    let err = guard_path_changes(&ctx.db, &moved, false).await.unwrap_err();
+    assert!(err.contains("HD_PATH_ALLOW_CHANGE"), "unexpected error: {}", err);
+    assert_eq!(
+        recorded_path(&ctx, PathChain::Evm).await.as_deref(),
+        Some(defaults.template(PathChain::Evm)),
+        "refused change must not be recorded"
+    );
+
+    guard_path_changes(&ctx.db, &moved, true).await.expect("override allows the change");
+    assert_eq!(recorded_path(&ctx, PathChain::Evm).await.as_deref(), Some("m/44'/60'/1'/0/{index}"));
+
+    // Put the defaults back for other tests sharing the database
+    guard_path_changes(&ctx.db, &defaults, true).await.unwrap();
+    sqlx::query("DELETE FROM swap_address_info WHERE swap_id = ?")
+        .bind(&swap_id)
+        .execute(&ctx.db)
+        .await
+        .unwrap();
+    ctx.cleanup().await;
+
+    println!("✅ Path change over existing addresses refused without override");
+}
@@ -7,8 +7,9 @@
 mod common;
 
 use exchange_shared::modules::wallet::schema::EvmTransaction;
+use exchange_shared::services::amount::Decimal;
 use exchange_shared::services::wallet::derivation::derive_evm_key;
-use exchange_shared::services::wallet::signing::SigningService;
+use exchange_shared::services::wallet::signing::{erc20_transfer_data, SigningService};
 
 // =============================================================================
 // TEST 1: Sign EVM Transaction
@@ -25,11 +26,13 @@ async fn test_sign_evm_transaction() {
     // 2. Real transaction data
     let tx = EvmTransaction {
         to_address: "0x742d35Cc6634C0532925a3b844Bc9e7595f5bE12".to_string(),
-        amount: 7.1993,
+        amount: Decimal::new(71993, 4),
         token: "eth".to_string(),
         chain_id: 1, // Ethereum
         nonce: 42,
         gas_price: 50_000_000_000u64,
+        gas_limit: 21000,
+        data: String::new(),
     };
     
     // 3. Sign transaction
@@ -57,20 +60,24 @@ async fn test_different_txs_different_signatures() {
 
     let tx1 = EvmTransaction {
         to_address: "0x742d35Cc6634C0532925a3b844Bc9e7595f5bE12".to_string(),
-        amount: 1.0,
+        amount: Decimal::from(1),
         token: "eth".to_string(),
         chain_id: 1,
         nonce: 1,
         gas_price: 50_000_000_000,
+        gas_limit: 21000,
+        data: String::new(),
     };
     
     let tx2 = EvmTransaction {
         to_address: "0x742d35Cc6634C0532925a3b844Bc9e7595f5bE12".to_string(),
-        amount: 2.0,
+        amount: Decimal::from(2),
         token: "eth".to_string(),
         chain_id: 1,
         nonce: 2,
         gas_price: 50_000_000_000,
+        gas_limit: 21000,
+        data: String::new(),
     };
     
     let sig1 = SigningService::sign_evm_transaction(&priv_key, &tx1).unwrap();
@@ -93,21 +100,25 @@ async fn test_polygon_signing_same_key() {
     // Ethereum signature
     let eth_tx = EvmTransaction {
         to_address: "0x742d35Cc6634C0532925a3b844Bc9e7595f5bE12".to_string(),
-        amount: 5.0,
+        amount: Decimal::from(5),
         token: "usdc".to_string(),
         chain_id: 1, // Ethereum
         nonce: 1,
         gas_price: 50_000_000_000,
+        gas_limit: 21000,
+        data: String::new(),
     };
     
     // Polygon signature (same key, different chain_id)
     let poly_tx = EvmTransaction {
         to_address: "0x742d35Cc6634C0532925a3b844Bc9e7595f5bE12".to_string(),
-        amount: 5.0,
+        amount: Decimal::from(5),
         token: "usdc".to_string(),
         chain_id: 137, // Polygon
         nonce: 1,
         gas_price: 50_000_000_000,
+        gas_limit: 21000,
+        data: String::new(),
     };
     
     let eth_sig = SigningService::sign_evm_transaction(&priv_key, &eth_tx).unwrap();
@@ -131,11 +142,13 @@ async fn test_nonce_affects_signature() {
 
     let tx1 = EvmTransaction {
         to_address: "0x742d35Cc6634C0532925a3b844Bc9e7595f5bE12".to_string(),
-        amount: 1.0,
+        amount: Decimal::from(1),
         token: "eth".to_string(),
         chain_id: 1,
         nonce: 1,
         gas_price: 50_000_000_000,
+        gas_limit: 21000,
+        data: String::new(),
     };
     
     let mut tx2 = tx1.clone();
@@ -146,4 +159,40 @@ async fn test_nonce_affects_signature() {
     
     assert_ne!(sig1, sig2, "Different nonces should produce different signatures");
     println!("✅ Nonce properly affects signature");
-}
\ No newline at end of file
+}
+
+// =============================================================================
+// TEST 5: ERC-20 Transfers
+// The call data targets the token contract and changes the signature
+// =============================================================================
+
+#[tokio::test]
+async fn test_erc20_transfer_signing() {
+    let seed_phrase = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+    let priv_key = derive_evm_key(seed_phrase).await.unwrap();
+
+    let data = erc20_transfer_data("0x742d35Cc6634C0532925a3b844Bc9e7595f5bE12", 1_000_000).unwrap();
+    assert_eq!(
+        data,
+        "0xa9059cbb000000000000000000000000742d35cc6634c0532925a3b844bc9e7595f5be12\
+         00000000000000000000000000000000000000000000000000000000000f4240"
+    );
+    assert!(erc20_transfer_data("0x1234", 1).is_err());
+
+    let native = EvmTransaction {
+        to_address: "0xdAC17F958D2ee523a2206206994597C13D831ec7".to_string(),
+        amount: Decimal::ZERO,
+        token: "usdt".to_string(),
+        chain_id: 1,
+        nonce: 3,
+        gas_price: 50_000_000_000,
+        gas_limit: 21000,
+        data: String::new(),
+    };
+    let token = EvmTransaction { gas_limit: 65_000, data, ..native.clone() };
+
+    let sig1 = SigningService::sign_evm_transaction(&priv_key, &native).unwrap();
+    let sig2 = SigningService::sign_evm_transaction(&priv_key, &token).unwrap();
+    assert_ne!(sig1, sig2, "Call data and gas limit should be signed");
+    println!("✅ ERC-20 transfer call data is signed");
+}
@@ -3,9 +3,11 @@
 /// Tests for real-time gas price estimation with multi-chain support,
 /// EMA smoothing, and caching strategies.
 
-use exchange_shared::services::gas::{GasEstimator, TxType};
+use exchange_shared::services::gas::{GasEstimator, GasHistory, PayoutGas, TxType};
 use exchange_shared::services::redis_cache::RedisService;
 
+use crate::common::TestContext;
+
 #[tokio::test]
 async fn test_basic_gas_cost_estimation() {
     let estimator = GasEstimator::new(None);
@@ -27,6 +29,65 @@ async fn test_basic_gas_cost_estimation() {
     assert!(sol_cost > 0.0, "Solana gas cost should be positive");
 }
 
+#[tokio::test]
+async fn test_fallback_tuned_from_payout_history() {
+    let ctx = TestContext::new().await;
+    // A network without RPC config always takes the fallback path
+    let network = format!("gt{}", &uuid::Uuid::new_v4().simple().to_string()[..8]);
+
+    let estimator = GasEstimator::new(None).with_history(ctx.db.clone());
+    let default_cost = estimator.get_gas_cost_for_network(&network).await;
+    assert_eq!(default_cost, 0.001, "Too little history keeps the hardcoded fallback");
+
+    let history = GasHistory::new(ctx.db.clone());
+    for i in 1..=20u64 {
+        history
+            .record_payout(&PayoutGas {
+                network: network.clone(),
+                swap_id: uuid::Uuid::new_v4().to_string(),
+                tx_hash: format!("0x{:064x}", i),
+                tx_type: TxType::NativeTransfer,
+                gas_price: i * 1_000_000_000,
+                gas_limit: 21_000,
+                gas_used: Some(21_000),
+                fee_native: i as f64 / 10_000.0,
+            })
+            .await
+            .expect("record payout gas");
+    }
+
+    // The first lookup cached "not enough samples"; a fresh key sees the rows
+    let tuned = history.tuned_fallback(&network, TxType::NativeTransfer).await;
+    assert!(tuned.is_none(), "Lookups are cached for the tuning TTL");
+
+    let other = format!("{}x", network);
+    for i in 1..=20u64 {
+        history
+            .record_payout(&PayoutGas {
+                network: other.clone(),
+                swap_id: uuid::Uuid::new_v4().to_string(),
+                tx_hash: format!("0x{:064x}", 100 + i),
+                tx_type: TxType::NativeTransfer,
+                gas_price: i * 1_000_000_000,
+                gas_limit: 21_000,
+                gas_used: Some(21_000),
+                fee_native: i as f64 / 10_000.0,
+            })
+            .await
+            .expect("record payout gas");
+    }
+
+    let tuned_cost = estimator.get_gas_cost_for_network(&other).await;
+    assert!((tuned_cost - 0.001525).abs() < 1e-9, "Fallback uses the p75 of recent fees, got {}", tuned_cost);
+
+    sqlx::query("DELETE FROM gas_history WHERE network IN (?, ?)")
+        .bind(&network)
+        .bind(&other)
+        .execute(&ctx.db)
+        .await
+        .ok();
+}
+
 #[tokio::test]
 async fn test_transaction_type_gas_limits() {
     let estimator = GasEstimator::new(None);
@@ -0,0 +1,202 @@
+// =============================================================================
+// INTEGRATION TESTS - GAS STATION
+// Token-only deposit addresses are funded from the hot wallet before ERC-20
+// payouts; concurrent top-ups share one batch and the cost is booked
+// =============================================================================
+
+#[path = "../common/mod.rs"]
+mod common;
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use async_trait::async_trait;
+use exchange_shared::modules::wallet::crud::WalletCrud;
+use exchange_shared::modules::wallet::schema::{GenerateAddressRequest, PayoutRequest};
+use exchange_shared::services::amount::{self, Decimal};
+use exchange_shared::services::gas::{GasStation, GasStationConfig, TopUpRequest, HOT_WALLET_INDEX};
+use exchange_shared::services::wallet::derivation::derive_evm_address;
+use exchange_shared::services::wallet::manager::WalletManager;
+use exchange_shared::services::wallet::rpc::{BlockchainProvider, RpcError};
+use common::TestContext;
+use uuid::Uuid;
+
+const SEED: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+// =============================================================================
+// MOCK PROVIDER
+// =============================================================================
+
+/// 20 gwei gas, native balances per address, 100 USDT on every address
+struct MockProvider {
+    balances: Mutex<HashMap<String, Decimal>>,
+    nonce_reads: AtomicUsize,
+    sent: Mutex<Vec<String>>,
+}
+
+impl MockProvider {
+    fn new(hot_wallet: &str, hot_balance: &str) -> Arc<Self> {
+        let hot_balance = amount::parse(hot_balance).unwrap();
+        Arc::new(Self {
+            balances: Mutex::new(HashMap::from([(hot_wallet.to_lowercase(), hot_balance)])),
+            nonce_reads: AtomicUsize::new(0),
+            sent: Mutex::new(Vec::new()),
+        })
+    }
+}
+
+#[async_trait]
+impl BlockchainProvider for MockProvider {
+    async fn get_transaction_count(&self, _address: &str) -> Result<u64, RpcError> {
+        self.nonce_reads.fetch_add(1, Ordering::SeqCst);
+        Ok(9)
+    }
+
+    async fn get_gas_price(&self) -> Result<u64, RpcError> {
+        Ok(20_000_000_000)
+    }
+
+    async fn send_raw_transaction(&self, signed_hex: &str) -> Result<String, RpcError> {
+        let mut sent = self.sent.lock().unwrap();
+        sent.push(signed_hex.to_string());
+        Ok(format!("0xtx{}", sent.len()))
+    }
+
+    async fn get_balance(&self, address: &str) -> Result<Decimal, RpcError> {
+        Ok(self.balances.lock().unwrap().get(&address.to_lowercase()).copied().unwrap_or_default())
+    }
+
+    async fn get_token_balance(&self, _contract: &str, _owner: &str) -> Result<u128, RpcError> {
+        Ok(100_000_000)
+    }
+}
+
+fn fast_config() -> GasStationConfig {
+    GasStationConfig { batch_window_ms: 50, ..GasStationConfig::default() }
+}
+
+fn request(address: &str) -> TopUpRequest {
+    TopUpRequest {
+        network: "ethereum".to_string(),
+        address: address.to_string(),
+        swap_id: None,
+        gas_limit: 65_000,
+    }
+}
+
+// =============================================================================
+// TEST 1: ERC-20 Payout Funds Its Own Gas
+// =============================================================================
+
+#[tokio::test]
+async fn test_token_payout_is_topped_up_and_booked() {
+    let ctx = TestContext::new().await;
+    let hot_wallet = derive_evm_address(SEED, HOT_WALLET_INDEX).await.unwrap();
+    let provider = MockProvider::new(&hot_wallet, "1.0");
+    let station = GasStation::new(ctx.db.clone(), provider.clone(), SEED.to_string()).with_config(fast_config());
+    let manager = WalletManager::new(WalletCrud::new(ctx.db.clone()), SEED.to_string(), provider.clone())
+        .with_gas_station(Arc::new(station));
+
+    let swap_id = Uuid::new_v4().to_string();
+    let recipient = "0x742d35Cc6634C0532925a3b844Bc9e7595f5bE12";
+    sqlx::query(
+        r#"
+        INSERT INTO swaps (
+            id, provider_id, from_currency, from_network, to_currency, to_network,
+            amount, estimated_receive, rate, deposit_address, recipient_address, status
+        )
+        VALUES (?, 'changenow', 'BTC', 'bitcoin', 'USDT', 'ERC20', 0.002, 100.0, 50000.0, 'dep_addr', ?, 'completed')
+        "#,
+    )
+    .bind(&swap_id)
+    .bind(recipient)
+    .execute(&ctx.db)
+    .await
+    .unwrap();
+
+    let address = manager.get_or_generate_address(GenerateAddressRequest {
+        swap_id: swap_id.clone(),
+        ticker: "USDT".to_string(),
+        network: "ethereum".to_string(),
+        user_recipient_address: recipient.to_string(),
+        user_recipient_extra_id: None,
+    }).await.unwrap().address;
+
+    let res = manager.process_payout(PayoutRequest { swap_id: swap_id.clone() }).await.unwrap();
+
+    // 100 USDT less the 1.2% small-trade commission; gas is not taken from it
+    assert_eq!(res.amount, amount::parse("98.8").unwrap(), "Expected 98.8 USDT payout");
+    // The top-up from the hot wallet, then the token transfer
+    assert_eq!(provider.sent.lock().unwrap().len(), 2);
+
+    let (topped_up, amount): (String, Decimal) =
+        sqlx::query_as("SELECT address, amount FROM gas_topups WHERE swap_id = ?")
+            .bind(&swap_id)
+            .fetch_one(&ctx.db)
+            .await
+            .unwrap();
+    assert_eq!(topped_up, address);
+    // 65,000 gas at 20 gwei with 20% headroom
+    assert_eq!(amount.normalize(), amount::parse("0.00156").unwrap());
+
+    // Top-up plus the 21,000 gas it took to send it
+    let (cost,): (Decimal,) =
+        sqlx::query_as("SELECT amount FROM revenue_entries WHERE swap_id = ? AND entry_type = 'gas_cost'")
+            .bind(&swap_id)
+            .fetch_one(&ctx.db)
+            .await
+            .unwrap();
+    assert_eq!(cost.normalize(), amount::parse("0.00198").unwrap());
+
+    println!("✅ Token payout topped up from the hot wallet and booked");
+    ctx.cleanup().await;
+}
+
+// =============================================================================
+// TEST 2: Concurrent Top-ups Share One Batch
+// =============================================================================
+
+#[tokio::test]
+async fn test_concurrent_top_ups_are_batched() {
+    let ctx = TestContext::new().await;
+    let hot_wallet = derive_evm_address(SEED, HOT_WALLET_INDEX).await.unwrap();
+    let funded = "0x00000000000000000000000000000000000000f1";
+    let provider = MockProvider::new(&hot_wallet, "1.0");
+    provider.balances.lock().unwrap().insert(funded.to_string(), amount::parse("0.01").unwrap());
+    let station = GasStation::new(ctx.db.clone(), provider.clone(), SEED.to_string()).with_config(fast_config());
+
+    let (a, b, c) = tokio::join!(
+        station.ensure_gas(request("0x00000000000000000000000000000000000000a1")),
+        station.ensure_gas(request("0x00000000000000000000000000000000000000b1")),
+        station.ensure_gas(request(funded)),
+    );
+
+    assert!(a.unwrap().is_some());
+    assert!(b.unwrap().is_some());
+    assert!(c.unwrap().is_none(), "An address that can already pay is left alone");
+    assert_eq!(provider.nonce_reads.load(Ordering::SeqCst), 1, "One nonce read for the whole batch");
+    assert_eq!(provider.sent.lock().unwrap().len(), 2);
+
+    println!("✅ Concurrent top-ups sent as one batch");
+    ctx.cleanup().await;
+}
+
+// =============================================================================
+// TEST 3: An Empty Hot Wallet Sends Nothing
+// =============================================================================
+
+#[tokio::test]
+async fn test_low_hot_wallet_refuses_batch() {
+    let ctx = TestContext::new().await;
+    let hot_wallet = derive_evm_address(SEED, HOT_WALLET_INDEX).await.unwrap();
+    let provider = MockProvider::new(&hot_wallet, "0.001");
+    let station = GasStation::new(ctx.db.clone(), provider.clone(), SEED.to_string()).with_config(fast_config());
+
+    let err = station.ensure_gas(request("0x00000000000000000000000000000000000000c1")).await.unwrap_err();
+
+    assert!(err.contains("hot wallet"), "Unexpected error: {}", err);
+    assert!(provider.sent.lock().unwrap().is_empty());
+
+    println!("✅ Batch refused when the hot wallet can't cover it");
+    ctx.cleanup().await;
+}
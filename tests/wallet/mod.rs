@@ -6,7 +6,15 @@ pub mod solana_signing_test;
 pub mod multi_chain_signing_test;
 pub mod address_reuse_test;
 pub mod payout_execution_test;
+pub mod gas_station_test;
+pub mod payout_batch_test;
+pub mod derivation_paths_test;
 pub mod non_evm_chain_test;
+pub mod sign_message_test;
+pub mod payout_guard_test;
+pub mod wallet_audit_test;
+pub mod token_permit_test;
+pub mod remote_signer_test;
 
 // Comprehensive blockchain coverage (129 blockchains)
 pub mod blockchains;
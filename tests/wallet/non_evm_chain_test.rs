@@ -1,5 +1,7 @@
+use exchange_shared::services::amount::Decimal;
 use exchange_shared::services::wallet::{
     bitcoin_rpc::{BitcoinUtxo, build_bitcoin_transaction},
+    solana_fees::ComputeBudget,
     solana_rpc::build_solana_transaction,
     derivation,
 };
@@ -60,7 +62,7 @@ async fn test_bitcoin_transaction_building() {
     let result = build_bitcoin_transaction(
         utxos,
         to_address,
-        0.05,
+        Decimal::new(5, 2),
         10.0, // 10 sat/byte
         change_address,
     );
@@ -77,12 +79,29 @@ async fn test_solana_transaction_building() {
     let to = "11111111111111111111111111111112";
     let blockhash = "4sGjMW1sUnHzSxGspuhpqLDx6wiyjNtZ";
     
-    let result = build_solana_transaction(from, to, 1.0, blockhash);
+    let result = build_solana_transaction(from, to, Decimal::ONE, blockhash, None);
     
     // This will fail with invalid pubkey, but tests the function exists
     assert!(result.is_err());
 }
 
+#[tokio::test]
+async fn test_solana_transaction_carries_compute_budget() {
+    let seed = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+    let from = derivation::derive_solana_address(seed, 0).await.unwrap();
+    let to = derivation::derive_solana_address(seed, 1).await.unwrap();
+    let blockhash = "11111111111111111111111111111111";
+    let budget = ComputeBudget { unit_limit: 1_000, unit_price_micro_lamports: 5_000 };
+
+    let plain = build_solana_transaction(&from, &to, Decimal::ONE, blockhash, None).unwrap();
+    let prioritized = build_solana_transaction(&from, &to, Decimal::ONE, blockhash, Some(&budget)).unwrap();
+
+    // Unit limit and unit price ahead of the transfer
+    assert_eq!(plain.message.instructions.len(), 1);
+    assert_eq!(prioritized.message.instructions.len(), 3);
+    assert_eq!(budget.total_fee_lamports(), 5_005);
+}
+
 #[tokio::test]
 async fn test_multi_chain_address_consistency() {
     let seed = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
@@ -120,7 +139,7 @@ async fn test_bitcoin_insufficient_funds() {
     let result = build_bitcoin_transaction(
         utxos,
         to_address,
-        1.0, // Try to send 1 BTC
+        Decimal::ONE, // Try to send 1 BTC
         10.0,
         change_address,
     );
@@ -146,3 +165,27 @@ async fn test_chain_type_detection() {
         assert_eq!(detected_evm, is_evm, "Failed for network: {}", network);
     }
 }
+
+#[tokio::test]
+async fn test_bitcoin_output_is_exact_in_sats() {
+    let utxos = vec![
+        BitcoinUtxo {
+            txid: "a".repeat(64),
+            vout: 0,
+            amount: 0.3,
+            confirmations: 6,
+        },
+    ];
+
+    // 0.29 * 1e8 is 28999999.999... as a float
+    let tx = build_bitcoin_transaction(
+        utxos,
+        "1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa",
+        Decimal::new(29, 2),
+        10.0,
+        "1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN2",
+    )
+    .unwrap();
+
+    assert_eq!(tx.output[0].value.to_sat(), 29_000_000);
+}
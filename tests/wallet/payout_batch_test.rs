@@ -0,0 +1,214 @@
+// =============================================================================
+// INTEGRATION TESTS - PAYOUT BATCHING
+// Native payouts on a chain with a disperse contract are sent from the hot
+// wallet in one transaction, with every recipient recorded in the ledger
+// =============================================================================
+
+#[path = "../common/mod.rs"]
+mod common;
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use async_trait::async_trait;
+use exchange_shared::modules::wallet::crud::WalletCrud;
+use exchange_shared::modules::wallet::schema::{GenerateAddressRequest, PayoutRequest};
+use exchange_shared::services::amount::Decimal;
+use exchange_shared::services::gas::HOT_WALLET_INDEX;
+use exchange_shared::services::payout::{PayoutBatchConfig, PayoutBatcher};
+use exchange_shared::services::wallet::derivation::derive_evm_address;
+use exchange_shared::services::wallet::manager::WalletManager;
+use exchange_shared::services::wallet::rpc::{BlockchainProvider, RpcError};
+use exchange_shared::services::wallet::signing::disperse_ether_data;
+use common::TestContext;
+use uuid::Uuid;
+
+const SEED: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+const DISPERSE: &str = "0xD152f549545093347A162Dce210e7293f1452150";
+
+// =============================================================================
+// MOCK PROVIDER
+// =============================================================================
+
+/// 20 gwei gas, 1 ETH on every deposit address, `hot_balance` on the hot wallet
+struct MockProvider {
+    hot_wallet: String,
+    hot_balance: Decimal,
+    nonce_reads: AtomicUsize,
+    sent: Mutex<Vec<String>>,
+}
+
+impl MockProvider {
+    fn new(hot_wallet: &str, hot_balance: Decimal) -> Arc<Self> {
+        Arc::new(Self {
+            hot_wallet: hot_wallet.to_lowercase(),
+            hot_balance,
+            nonce_reads: AtomicUsize::new(0),
+            sent: Mutex::new(Vec::new()),
+        })
+    }
+}
+
+#[async_trait]
+impl BlockchainProvider for MockProvider {
+    async fn get_transaction_count(&self, _address: &str) -> Result<u64, RpcError> {
+        self.nonce_reads.fetch_add(1, Ordering::SeqCst);
+        Ok(4)
+    }
+
+    async fn get_gas_price(&self) -> Result<u64, RpcError> {
+        Ok(20_000_000_000)
+    }
+
+    async fn send_raw_transaction(&self, signed_hex: &str) -> Result<String, RpcError> {
+        let mut sent = self.sent.lock().unwrap();
+        sent.push(signed_hex.to_string());
+        Ok(format!("0xbatch{}", sent.len()))
+    }
+
+    async fn get_balance(&self, address: &str) -> Result<Decimal, RpcError> {
+        if address.to_lowercase() == self.hot_wallet {
+            Ok(self.hot_balance)
+        } else {
+            Ok(Decimal::ONE)
+        }
+    }
+}
+
+fn batcher(ctx: &TestContext, provider: Arc<MockProvider>) -> Arc<PayoutBatcher> {
+    let config = PayoutBatchConfig {
+        contracts: HashMap::from([("ethereum".to_string(), DISPERSE.to_string())]),
+        window_ms: 100,
+        ..PayoutBatchConfig::default()
+    };
+    Arc::new(PayoutBatcher::new(ctx.db.clone(), provider, SEED.to_string()).with_config(config))
+}
+
+/// An ETH swap ready for payout, with its deposit address generated
+async fn funded_swap(ctx: &TestContext, manager: &WalletManager, recipient: &str) -> String {
+    let swap_id = Uuid::new_v4().to_string();
+    sqlx::query(
+        r#"
+        INSERT INTO swaps (
+            id, provider_id, from_currency, from_network, to_currency, to_network,
+            amount, estimated_receive, rate, deposit_address, recipient_address, status
+        )
+        VALUES (?, 'changenow', 'BTC', 'bitcoin', 'ETH', 'ethereum', 0.05, 1.0, 20.0, 'dep_addr', ?, 'completed')
+        "#,
+    )
+    .bind(&swap_id)
+    .bind(recipient)
+    .execute(&ctx.db)
+    .await
+    .unwrap();
+
+    manager.get_or_generate_address(GenerateAddressRequest {
+        swap_id: swap_id.clone(),
+        ticker: "ETH".to_string(),
+        network: "ethereum".to_string(),
+        user_recipient_address: recipient.to_string(),
+        user_recipient_extra_id: None,
+    }).await.unwrap();
+
+    swap_id
+}
+
+// =============================================================================
+// TEST 1: Disperse Call Data
+// =============================================================================
+
+#[test]
+fn test_disperse_ether_data_encoding() {
+    let data = disperse_ether_data(&[
+        ("0x00000000000000000000000000000000000000a1", 1),
+        ("0x00000000000000000000000000000000000000B2", 2),
+    ])
+    .unwrap();
+
+    let words: Vec<&str> = data
+        .trim_start_matches("0xe63d38ed")
+        .as_bytes()
+        .chunks(64)
+        .map(|w| std::str::from_utf8(w).unwrap())
+        .collect();
+    assert!(data.starts_with("0xe63d38ed"));
+    assert_eq!(words.len(), 8);
+    // Offsets of the two arrays, then each as length + elements
+    assert!(words[0].ends_with("40"));
+    assert!(words[1].ends_with("a0"));
+    assert!(words[2].ends_with("02"));
+    assert!(words[3].ends_with("a1"));
+    assert!(words[4].ends_with("b2"));
+    assert!(words[5].ends_with("02"));
+    assert!(words[6].ends_with("01"));
+    assert!(words[7].ends_with("02"));
+
+    assert!(disperse_ether_data(&[("0x1234", 1)]).is_err());
+}
+
+// =============================================================================
+// TEST 2: Concurrent Payouts Share One Transaction
+// =============================================================================
+
+#[tokio::test]
+async fn test_concurrent_payouts_are_batched() {
+    let ctx = TestContext::new().await;
+    let hot_wallet = derive_evm_address(SEED, HOT_WALLET_INDEX).await.unwrap();
+    let provider = MockProvider::new(&hot_wallet, Decimal::TEN);
+    let batcher = batcher(&ctx, provider.clone());
+    let manager = || {
+        WalletManager::new(WalletCrud::new(ctx.db.clone()), SEED.to_string(), provider.clone())
+            .with_payout_batcher(batcher.clone())
+    };
+    let (first, second) = (manager(), manager());
+
+    let swap_a = funded_swap(&ctx, &first, "0x00000000000000000000000000000000000000a1").await;
+    let swap_b = funded_swap(&ctx, &second, "0x00000000000000000000000000000000000000b2").await;
+
+    let (a, b) = tokio::join!(
+        first.process_payout(PayoutRequest { swap_id: swap_a.clone() }),
+        second.process_payout(PayoutRequest { swap_id: swap_b.clone() }),
+    );
+    let (a, b) = (a.unwrap(), b.unwrap());
+
+    assert_eq!(provider.sent.lock().unwrap().len(), 1, "Both payouts go out in one transaction");
+    assert_eq!(provider.nonce_reads.load(Ordering::SeqCst), 1, "Only the hot wallet nonce is read");
+    assert_eq!(a.tx_hash, b.tx_hash);
+
+    let rows: Vec<(String, String, Decimal)> = sqlx::query_as(
+        "SELECT swap_id, recipient, amount FROM payout_batch_items WHERE tx_hash = ? ORDER BY recipient",
+    )
+    .bind(&a.tx_hash)
+    .fetch_all(&ctx.db)
+    .await
+    .unwrap();
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0].0, swap_a);
+    assert_eq!(rows[1].0, swap_b);
+    assert_eq!(rows[0].2, a.amount);
+
+    println!("✅ Concurrent payouts sent as one disperse batch");
+    ctx.cleanup().await;
+}
+
+// =============================================================================
+// TEST 3: An Underfunded Hot Wallet Sends Nothing
+// =============================================================================
+
+#[tokio::test]
+async fn test_low_hot_wallet_refuses_batch() {
+    let ctx = TestContext::new().await;
+    let hot_wallet = derive_evm_address(SEED, HOT_WALLET_INDEX).await.unwrap();
+    let provider = MockProvider::new(&hot_wallet, Decimal::new(1, 2));
+    let manager = WalletManager::new(WalletCrud::new(ctx.db.clone()), SEED.to_string(), provider.clone())
+        .with_payout_batcher(batcher(&ctx, provider.clone()));
+
+    let swap_id = funded_swap(&ctx, &manager, "0x00000000000000000000000000000000000000c3").await;
+    let err = manager.process_payout(PayoutRequest { swap_id }).await.unwrap_err();
+
+    assert!(err.contains("hot wallet"), "Unexpected error: {}", err);
+    assert!(provider.sent.lock().unwrap().is_empty());
+
+    println!("✅ Batch refused when the hot wallet can't cover it");
+    ctx.cleanup().await;
+}
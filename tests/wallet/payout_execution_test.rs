@@ -11,6 +11,7 @@ use std::sync::{Arc, Mutex};
 use async_trait::async_trait;
 use exchange_shared::modules::wallet::crud::WalletCrud;
 use exchange_shared::modules::wallet::schema::{GenerateAddressRequest, PayoutRequest};
+use exchange_shared::services::amount::Decimal;
 use exchange_shared::services::wallet::manager::WalletManager;
 use exchange_shared::modules::wallet::model::PayoutStatus;
 use exchange_shared::services::wallet::rpc::{BlockchainProvider, RpcError};
@@ -55,8 +56,8 @@ impl BlockchainProvider for MockProvider {
         Ok(self.broadcast_hash.clone())
     }
 
-    async fn get_balance(&self, _address: &str) -> Result<f64, RpcError> {
-        Ok(1.0)  // Return 1.0 to match test expectations
+    async fn get_balance(&self, _address: &str) -> Result<Decimal, RpcError> {
+        Ok(Decimal::ONE)  // Return 1.0 to match test expectations
     }
 }
 
@@ -119,7 +120,7 @@ async fn test_commission_deduction_on_payout() {
     // Gas: 20 Gwei * 21000 = 0.00042 ETH
     // Commission: max(0.012, gas_floor) = 0.012
     // Final payout: 1.0 - 0.012 - 0.00042 = 0.98758
-    assert!((res.amount - 0.987).abs() < 0.002, "Expected ~0.987 payout, got {}", res.amount);
+    assert!((res.amount - Decimal::new(987, 3)).abs() < Decimal::new(2, 3), "Expected ~0.987 payout, got {}", res.amount);
     
     println!("✅ Algorithmic commission deduction verified: {:.3} ETH to user", res.amount);
     ctx.cleanup().await;
@@ -0,0 +1,313 @@
+use axum::http::StatusCode;
+use serde_json::{json, Value};
+use serial_test::serial;
+
+use exchange_shared::modules::payout_guard::model::{PayoutKind, TripCause};
+use exchange_shared::modules::recovery::crud::RecoveryCrud;
+use exchange_shared::modules::recovery::model::{
+    AllocatedIndex, FundsAsset, OrphanedFundsKind, OrphanedFundsStatus, WrongNetworkStatus,
+};
+use exchange_shared::services::amount::{self, Decimal};
+use exchange_shared::services::events::ops::{ops_events, OpsEvent};
+use exchange_shared::services::fx::FxStore;
+use exchange_shared::services::payout::{PayoutGuard, PayoutGuardError, PayoutGuardPolicy, PlannedPayout};
+
+use crate::common::{create_admin, create_test_swap, create_test_user, test_email, test_password, TestContext};
+
+/// Start every test with the guard armed, only this test's attempts counting
+/// and ETH priced at $100
+async fn armed_guard(ctx: &TestContext, policy: PayoutGuardPolicy) -> PayoutGuard {
+    FxStore::new(ctx.db.clone())
+        .upsert_prices(&[("eth".to_string(), chrono::Utc::now().date_naive(), Decimal::from(100))], "test")
+        .await
+        .unwrap();
+    let guard = PayoutGuard::new(ctx.db.clone()).with_policy(policy);
+    // Trip and re-arm so earlier tests' attempts and reservations drop out
+    match guard.trip_manually("test-setup", "reset before test").await {
+        Ok(_) | Err(PayoutGuardError::AlreadyTripped) => {}
+        Err(e) => panic!("could not reset the payout guard: {}", e),
+    }
+    match guard.rearm("test-setup", "reset before test").await {
+        Ok(_) | Err(PayoutGuardError::NotTripped) => {}
+        Err(e) => panic!("could not reset the payout guard: {}", e),
+    }
+    guard
+}
+
+fn planned(amount: &str) -> PlannedPayout {
+    let amount = amount::parse(amount).unwrap();
+    PlannedPayout::new(PayoutKind::Withdrawal, &uuid::Uuid::new_v4().to_string(), "ethereum", "eth", amount)
+}
+
+async fn guard_status(ctx: &TestContext, admin: &str) -> Value {
+    let response = ctx.server.get("/admin/payouts/guard").authorization_bearer(admin).await;
+    response.assert_status_ok();
+    response.json()
+}
+
+#[tokio::test]
+#[serial]
+async fn test_failures_trip_the_guard_until_rearmed() {
+    let ctx = TestContext::new().await;
+    let admin = create_admin(&ctx).await;
+    let policy = PayoutGuardPolicy {
+        min_attempts: 3,
+        max_failure_rate: 0.5,
+        max_usd_per_hour: 0.0,
+        outlier_stddevs: 0.0,
+        ..PayoutGuardPolicy::default()
+    };
+    let guard = armed_guard(&ctx, policy).await;
+    let mut events = ops_events().subscribe();
+
+    let reservation = guard.check(&planned("0.01")).await.unwrap();
+    guard.record(&reservation, None).await;
+    for _ in 0..3 {
+        let reservation = guard.check(&planned("0.01")).await.unwrap();
+        guard.record(&reservation, Some("nonce too low")).await;
+    }
+
+    // 3 of 4 failed: every payout is now refused
+    let err = guard.check(&planned("0.01")).await.unwrap_err();
+    assert!(matches!(err, PayoutGuardError::Halted(_)));
+    assert!(guard.is_halted().await);
+
+    let tripped = loop {
+        let notification = events.recv().await.unwrap();
+        if let OpsEvent::PayoutGuardTripped { cause, .. } = notification.event {
+            break cause;
+        }
+    };
+    assert_eq!(tripped, TripCause::FailureRate.as_str());
+
+    let status = guard_status(&ctx, &admin).await;
+    assert_eq!(status["tripped"], true);
+    assert_eq!(status["active_trip"]["cause"], "failure_rate");
+
+    // A note is required to resume
+    let response = ctx
+        .server
+        .post("/admin/payouts/guard/rearm")
+        .authorization_bearer(&admin)
+        .json(&json!({ "note": "ok" }))
+        .await;
+    response.assert_status(StatusCode::BAD_REQUEST);
+
+    let response = ctx
+        .server
+        .post("/admin/payouts/guard/rearm")
+        .authorization_bearer(&admin)
+        .json(&json!({ "note": "RPC node was out of sync, switched provider" }))
+        .await;
+    response.assert_status_ok();
+    let body: Value = response.json();
+    assert_eq!(body["tripped"], false);
+    assert_eq!(body["recent_trips"][0]["rearm_note"], "RPC node was out of sync, switched provider");
+
+    // Failures before the re-arm no longer count
+    assert_eq!(body["window"]["attempts"], 0);
+    guard.check(&planned("0.01")).await.unwrap();
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+#[serial]
+async fn test_hourly_cap_and_manual_trip() {
+    let ctx = TestContext::new().await;
+    let admin = create_admin(&ctx).await;
+    let (_, user) = create_test_user(&ctx.server, &test_email(), test_password()).await;
+    let policy = PayoutGuardPolicy { max_usd_per_hour: 1_000.0, outlier_stddevs: 0.0, ..PayoutGuardPolicy::default() };
+    let guard = armed_guard(&ctx, policy).await;
+
+    // Over the cap on its own ($1,500): refused before anything is sent
+    let err = guard.check(&planned("15.0")).await.unwrap_err();
+    assert!(err.to_string().contains("cap"));
+    let trip = guard.active_trip().await.unwrap().unwrap();
+    assert_eq!(trip.cause, TripCause::UsdVolume);
+
+    // Already tripped
+    let response = ctx
+        .server
+        .post("/admin/payouts/guard/trip")
+        .authorization_bearer(&admin)
+        .json(&json!({ "reason": "Suspected key leak, investigating" }))
+        .await;
+    response.assert_status(StatusCode::CONFLICT);
+
+    guard.rearm("test", "cap raised for the test").await.unwrap();
+    let response = ctx
+        .server
+        .post("/admin/payouts/guard/trip")
+        .authorization_bearer(&admin)
+        .json(&json!({ "reason": "Suspected key leak, investigating" }))
+        .await;
+    response.assert_status_ok();
+    let body: Value = response.json();
+    assert_eq!(body["active_trip"]["cause"], "manual");
+    assert!(guard.check(&planned("0.01")).await.is_err());
+
+    let response = ctx.server.get("/admin/payouts/guard").authorization_bearer(&user).await;
+    response.assert_status(StatusCode::FORBIDDEN);
+
+    guard.rearm("test", "manual trip test finished").await.unwrap();
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+#[serial]
+async fn test_reservations_count_against_the_cap() {
+    let ctx = TestContext::new().await;
+    let policy = PayoutGuardPolicy { max_usd_per_hour: 1_000.0, outlier_stddevs: 0.0, ..PayoutGuardPolicy::default() };
+    let guard = armed_guard(&ctx, policy).await;
+
+    // $600 cleared but not yet sent still holds its share of the cap
+    let first = guard.check(&planned("6.0")).await.unwrap();
+    assert_eq!(first.usd_value, 600.0);
+    assert_eq!(guard.hourly_stats().await.unwrap().usd_sent, 600.0);
+
+    // A payout that was never sent gives its share back
+    guard.release(&first).await;
+    assert_eq!(guard.hourly_stats().await.unwrap().usd_sent, 0.0);
+
+    let first = guard.check(&planned("6.0")).await.unwrap();
+    let err = guard.check(&planned("6.0")).await.unwrap_err();
+    assert!(matches!(err, PayoutGuardError::Halted(_)));
+    guard.record(&first, None).await;
+
+    guard.rearm("test", "reservation test finished").await.unwrap();
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+#[serial]
+async fn test_assets_without_a_price_are_refused() {
+    let ctx = TestContext::new().await;
+    let guard = armed_guard(&ctx, PayoutGuardPolicy::default()).await;
+
+    let unpriced = PlannedPayout::new(PayoutKind::Refund, "refund-1", "ethereum", "notacoin", Decimal::ONE);
+    let err = guard.check(&unpriced).await.unwrap_err();
+    assert!(matches!(err, PayoutGuardError::Unpriced(_)));
+    // Refused, but payouts of priced assets carry on
+    assert!(!guard.is_halted().await);
+
+    // A stale price is no better than none
+    let stale = chrono::Utc::now().date_naive() - chrono::Duration::days(10);
+    FxStore::new(ctx.db.clone())
+        .upsert_prices(&[("notacoin".to_string(), stale, Decimal::ONE)], "test")
+        .await
+        .unwrap();
+    let err = guard.check(&unpriced).await.unwrap_err();
+    assert!(matches!(err, PayoutGuardError::Unpriced(_)));
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+#[serial]
+async fn test_tripped_guard_blocks_recovery_sweeps() {
+    let ctx = TestContext::new().await;
+    let admin = create_admin(&ctx).await;
+    let guard = armed_guard(&ctx, PayoutGuardPolicy::default()).await;
+
+    let (user_id, _) = create_test_user(&ctx.server, &test_email(), test_password()).await;
+    let swap_id = create_test_swap(&ctx.server, &user_id, "eth", "btc").await;
+    let crud = RecoveryCrud::new(ctx.db.clone());
+    crud.record_detection(&swap_id, "0x742d35cc6634c0532925a3b844bc454e4438f44e", 7, "ethereum", "bsc", Decimal::ONE)
+        .await
+        .unwrap();
+    let case_id = crud
+        .list_cases(Some(WrongNetworkStatus::Open), Some(200))
+        .await
+        .unwrap()
+        .into_iter()
+        .find(|case| case.swap_id == swap_id)
+        .unwrap()
+        .id;
+
+    guard.trip_manually("test", "Suspected admin account compromise").await.unwrap();
+
+    // The sweep is refused before anything is looked up or signed
+    let response = ctx
+        .server
+        .post(&format!("/admin/wrong-network-cases/{}/recover", case_id))
+        .authorization_bearer(&admin)
+        .json(&json!({ "recipient_address": "0x000000000000000000000000000000000000dEaD" }))
+        .await;
+    response.assert_status(StatusCode::SERVICE_UNAVAILABLE);
+    let body: Value = response.json();
+    assert!(body["error"].as_str().unwrap().contains("halted"));
+
+    let case = crud.get_case(&case_id).await.unwrap().unwrap();
+    assert_eq!(case.status, WrongNetworkStatus::Failed);
+    assert!(case.recovery_tx_hash.is_none());
+
+    let (sweeps,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM payout_attempts WHERE reference = ?")
+        .bind(&case_id)
+        .fetch_one(&ctx.db)
+        .await
+        .unwrap();
+    assert_eq!(sweeps, 0);
+
+    guard.rearm("test", "recovery block test finished").await.unwrap();
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+#[serial]
+async fn test_tripped_guard_blocks_orphaned_funds_recovery() {
+    let ctx = TestContext::new().await;
+    let admin = create_admin(&ctx).await;
+    let guard = armed_guard(&ctx, PayoutGuardPolicy::default()).await;
+
+    let (user_id, _) = create_test_user(&ctx.server, &test_email(), test_password()).await;
+    let swap_id = create_test_swap(&ctx.server, &user_id, "eth", "btc").await;
+    let crud = RecoveryCrud::new(ctx.db.clone());
+    let run_id = crud.start_audit_run().await.unwrap();
+    let index = AllocatedIndex {
+        address_index: 0x8000_0000 | (uuid::Uuid::new_v4().as_u128() as u32 >> 1),
+        address: "0x742d35cc6634c0532925a3b844bc454e4438f44e".to_string(),
+        swap_id,
+        swap_status: Some("completed".to_string()),
+        network: Some("ethereum".to_string()),
+        payout_status: Some("success".to_string()),
+        updated_at: None,
+        refund_open: false,
+    };
+    crud.record_orphaned_funds(&run_id, &index, &FundsAsset::native("ethereum", "eth"), OrphanedFundsKind::Unswept, Decimal::ONE)
+        .await
+        .unwrap();
+    let funds_id = crud
+        .list_orphaned_funds(None, None, Some(200))
+        .await
+        .unwrap()
+        .into_iter()
+        .find(|funds| funds.address_index == index.address_index)
+        .unwrap()
+        .id;
+
+    guard.trip_manually("test", "Suspected admin account compromise").await.unwrap();
+
+    let response = ctx
+        .server
+        .post(&format!("/admin/orphaned-funds/{}/recover", funds_id))
+        .authorization_bearer(&admin)
+        .json(&json!({ "recipient_address": "0x000000000000000000000000000000000000dEaD" }))
+        .await;
+    response.assert_status(StatusCode::SERVICE_UNAVAILABLE);
+    let body: Value = response.json();
+    assert!(body["error"].as_str().unwrap().contains("halted"));
+
+    let funds = crud.get_orphaned_funds(&funds_id).await.unwrap().unwrap();
+    assert_eq!(funds.status, OrphanedFundsStatus::Failed);
+
+    let (sweeps,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM payout_attempts WHERE reference = ?")
+        .bind(&funds_id)
+        .fetch_one(&ctx.db)
+        .await
+        .unwrap();
+    assert_eq!(sweeps, 0);
+
+    guard.rearm("test", "recovery block test finished").await.unwrap();
+    ctx.cleanup().await;
+}
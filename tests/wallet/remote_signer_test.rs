@@ -0,0 +1,148 @@
+use axum::{http::HeaderMap, http::StatusCode, routing::post, Json, Router};
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+use exchange_shared::modules::wallet::schema::EvmTransaction;
+use exchange_shared::services::amount::Decimal;
+use exchange_shared::services::wallet::signer::{LocalHdSigner, Signer, SignerBackend};
+
+const TEST_MNEMONIC: &str =
+    "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+const SIGNER_TOKEN: &str = "signer-test-token";
+/// Indexes the reference signer refuses, to exercise RPC errors
+const MAX_INDEX: u64 = 100;
+
+fn sample_tx() -> EvmTransaction {
+    EvmTransaction {
+        to_address: "0x742d35Cc6634C0532925a3b844Bc454e4438f44e".to_string(),
+        amount: Decimal::new(5, 1),
+        token: "ETH".to_string(),
+        chain_id: 1,
+        nonce: 7,
+        gas_price: 20_000_000_000,
+        gas_limit: 21000,
+        data: String::new(),
+    }
+}
+
+fn hex_param(params: &Value, name: &str) -> Vec<u8> {
+    hex::decode(params[name].as_str().unwrap_or_default()).unwrap_or_default()
+}
+
+fn hash_param(params: &Value, name: &str) -> [u8; 32] {
+    hex_param(params, name).try_into().unwrap_or([0u8; 32])
+}
+
+/// Reference signing service speaking the JSON-RPC protocol documented on
+/// `RemoteSignerClient`, backed by the local HD signer
+async fn handle_rpc(signer: Arc<LocalHdSigner>, headers: HeaderMap, request: Value) -> (StatusCode, Json<Value>) {
+    let expected = format!("Bearer {}", SIGNER_TOKEN);
+    if headers.get("authorization").and_then(|v| v.to_str().ok()) != Some(expected.as_str()) {
+        return (StatusCode::UNAUTHORIZED, Json(json!({ "error": "unauthorized" })));
+    }
+
+    let params = &request["params"];
+    let index = params["index"].as_u64().unwrap_or(0);
+    if index > MAX_INDEX {
+        let error = json!({ "code": -32000, "message": format!("index {} is outside the signing policy", index) });
+        return (StatusCode::OK, Json(json!({ "jsonrpc": "2.0", "id": request["id"], "error": error })));
+    }
+    let index = index as u32;
+
+    let signed = match request["method"].as_str().unwrap_or_default() {
+        "sign_evm_transaction" => {
+            let tx: EvmTransaction = serde_json::from_value(params["tx"].clone()).unwrap();
+            signer.sign_evm_transaction(index, &tx).await
+        }
+        "sign_solana_message" => signer.sign_solana_message(index, &hex_param(params, "message")).await,
+        "sign_btc_sighash" => signer.sign_btc_sighash(index, &hex_param(params, "sighash")).await,
+        "sign_evm_message" => signer.sign_evm_message(index, &hex_param(params, "message")).await,
+        "sign_evm_typed_data" => {
+            let domain = hash_param(params, "domain_separator");
+            let hash = hash_param(params, "struct_hash");
+            signer.sign_evm_typed_data(index, &domain, &hash).await
+        }
+        "sign_btc_message" => signer.sign_btc_message(index, &hex_param(params, "message")).await,
+        other => Err(format!("unknown method {}", other)),
+    };
+
+    let body = match signed {
+        Ok(signature) => json!({ "jsonrpc": "2.0", "id": request["id"], "result": { "signature": signature } }),
+        Err(message) => json!({ "jsonrpc": "2.0", "id": request["id"], "error": { "code": -32000, "message": message } }),
+    };
+    (StatusCode::OK, Json(body))
+}
+
+/// Start the reference signer on a free local port and return its URL
+async fn start_reference_signer() -> String {
+    let signer = Arc::new(LocalHdSigner::new(TEST_MNEMONIC.to_string()));
+    let app = Router::new().route(
+        "/",
+        post(move |headers: HeaderMap, Json(request): Json<Value>| handle_rpc(signer.clone(), headers, request)),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    url
+}
+
+fn remote_signer(url: &str, auth_token: Option<&str>) -> Arc<dyn Signer> {
+    SignerBackend::Remote { url: url.to_string(), auth_token: auth_token.map(str::to_string) }.build(TEST_MNEMONIC)
+}
+
+#[tokio::test]
+async fn test_remote_signer_matches_local_signatures() {
+    let url = start_reference_signer().await;
+    let remote = remote_signer(&url, Some(SIGNER_TOKEN));
+    let local = SignerBackend::Local.build(TEST_MNEMONIC);
+    assert_eq!(remote.name(), "remote");
+
+    assert_eq!(
+        remote.sign_evm_transaction(3, &sample_tx()).await.unwrap(),
+        local.sign_evm_transaction(3, &sample_tx()).await.unwrap()
+    );
+    assert_eq!(
+        remote.sign_evm_message(1, b"proof of control").await.unwrap(),
+        local.sign_evm_message(1, b"proof of control").await.unwrap()
+    );
+    assert_eq!(
+        remote.sign_evm_typed_data(0, &[1u8; 32], &[2u8; 32]).await.unwrap(),
+        local.sign_evm_typed_data(0, &[1u8; 32], &[2u8; 32]).await.unwrap()
+    );
+    assert_eq!(
+        remote.sign_solana_message(2, b"message").await.unwrap(),
+        local.sign_solana_message(2, b"message").await.unwrap()
+    );
+    assert_eq!(
+        remote.sign_btc_message(0, b"message").await.unwrap(),
+        local.sign_btc_message(0, b"message").await.unwrap()
+    );
+}
+
+#[tokio::test]
+async fn test_remote_signer_errors_are_reported() {
+    let url = start_reference_signer().await;
+
+    // Missing or wrong token
+    let err = remote_signer(&url, None).sign_evm_message(0, b"hello").await.unwrap_err();
+    assert!(err.contains("HTTP 401"), "{}", err);
+    let err = remote_signer(&url, Some("wrong")).sign_evm_message(0, b"hello").await.unwrap_err();
+    assert!(err.contains("HTTP 401"), "{}", err);
+
+    // Refused by the signer's policy
+    let err = remote_signer(&url, Some(SIGNER_TOKEN))
+        .sign_evm_message(MAX_INDEX as u32 + 1, b"hello")
+        .await
+        .unwrap_err();
+    assert!(err.contains("Remote signer refused sign_evm_message"), "{}", err);
+    assert!(err.contains("outside the signing policy"), "{}", err);
+
+    // Nothing listening
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let closed = format!("http://{}/", listener.local_addr().unwrap());
+    drop(listener);
+    let err = remote_signer(&closed, Some(SIGNER_TOKEN)).sign_evm_message(0, b"hello").await.unwrap_err();
+    assert!(err.contains("unreachable"), "{}", err);
+}
@@ -0,0 +1,132 @@
+use axum::http::StatusCode;
+use chrono::Utc;
+use serde_json::json;
+
+use exchange_shared::modules::auth::model::User;
+use exchange_shared::services::totp::{decode_base32, hotp};
+use exchange_shared::services::wallet::message_signing::{
+    HotWalletChain, HotWalletMessageSigner, MessageSigningError, MessageSigningPolicy, MessageSigningRequest,
+};
+use exchange_shared::services::wallet::ownership::verify_ownership_proof;
+
+use crate::common::{create_test_user, test_email, test_password, TestContext};
+
+const TEST_MNEMONIC: &str =
+    "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+// RFC 6238 test secret
+const TOTP_SECRET: &str = "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ";
+
+fn current_code() -> String {
+    let key = decode_base32(TOTP_SECRET).unwrap();
+    format!("{:06}", hotp(&key, Utc::now().timestamp() as u64 / 30))
+}
+
+fn admin() -> User {
+    User {
+        id: uuid::Uuid::new_v4().to_string(),
+        email: test_email(),
+        password_hash: String::new(),
+        email_verified: true,
+        two_factor_enabled: true,
+        two_factor_secret: Some(TOTP_SECRET.to_string()),
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+        token_version: 0,
+    }
+}
+
+fn request(chain: HotWalletChain, code: String) -> MessageSigningRequest {
+    MessageSigningRequest {
+        chain,
+        message: "Example Exchange listing: we control this wallet\nnonce: 8f2a".to_string(),
+        purpose: "Listing on Example Exchange".to_string(),
+        two_factor_code: code,
+        client_ip: Some("203.0.113.7".to_string()),
+    }
+}
+
+async fn audit_outcomes(ctx: &TestContext, admin_id: &str) -> Vec<(String, Option<String>)> {
+    sqlx::query_as(
+        "SELECT CAST(outcome AS CHAR), signature FROM wallet_message_signatures WHERE admin_id = ? ORDER BY id",
+    )
+    .bind(admin_id)
+    .fetch_all(&ctx.db)
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_permitted_admin_signs_with_hot_wallet() {
+    let ctx = TestContext::new().await;
+    let admin = admin();
+    let policy = MessageSigningPolicy {
+        signers: [admin.id.clone()].into_iter().collect(),
+        ..MessageSigningPolicy::default()
+    };
+    let signer = HotWalletMessageSigner::new(ctx.db.clone(), TEST_MNEMONIC.to_string(), policy);
+
+    for chain in [HotWalletChain::Evm, HotWalletChain::Bitcoin] {
+        let signed = signer.sign(&admin, &request(chain, current_code())).await.unwrap();
+
+        assert_eq!(signed.address, signer.address(chain).await.unwrap());
+        assert!(verify_ownership_proof(&signed.address, &signed.message, &signed.signature).is_ok());
+        assert!(signed.audit_id > 0);
+    }
+
+    let outcomes = audit_outcomes(&ctx, &admin.id).await;
+    assert_eq!(outcomes.len(), 2);
+    assert!(outcomes.iter().all(|(outcome, signature)| outcome == "signed" && signature.is_some()));
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_refused_attempts_are_audited() {
+    let ctx = TestContext::new().await;
+    let admin = admin();
+    let outsider = HotWalletMessageSigner::new(ctx.db.clone(), TEST_MNEMONIC.to_string(), MessageSigningPolicy::default());
+    assert!(matches!(
+        outsider.sign(&admin, &request(HotWalletChain::Evm, current_code())).await,
+        Err(MessageSigningError::NotPermitted)
+    ));
+
+    let policy = MessageSigningPolicy {
+        signers: [admin.id.clone()].into_iter().collect(),
+        ..MessageSigningPolicy::default()
+    };
+    let signer = HotWalletMessageSigner::new(ctx.db.clone(), TEST_MNEMONIC.to_string(), policy);
+    let wrong_code = signer.sign(&admin, &request(HotWalletChain::Evm, "000000".to_string())).await;
+    assert!(matches!(wrong_code, Err(MessageSigningError::InvalidTwoFactorCode)));
+
+    let outcomes = audit_outcomes(&ctx, &admin.id).await;
+    assert_eq!(outcomes, vec![("denied".to_string(), None), ("denied".to_string(), None)]);
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_endpoint_refuses_admins_not_listed_as_signers() {
+    let ctx = TestContext::new().await;
+    let (admin_id, token) = create_test_user(&ctx.server, &test_email(), test_password()).await;
+    sqlx::query("UPDATE users SET is_admin = TRUE WHERE id = ?")
+        .bind(&admin_id)
+        .execute(&ctx.db)
+        .await
+        .unwrap();
+    let body = json!({
+        "chain": "evm",
+        "message": "Example Exchange listing",
+        "purpose": "Listing on Example Exchange",
+        "two_factor_code": current_code(),
+    });
+
+    let response = ctx.server.post("/admin/wallet/sign-message").authorization_bearer(&token).json(&body).await;
+    response.assert_status(StatusCode::FORBIDDEN);
+    assert_eq!(audit_outcomes(&ctx, &admin_id).await, vec![("denied".to_string(), None)]);
+
+    let (_, user_token) = create_test_user(&ctx.server, &test_email(), test_password()).await;
+    let response = ctx.server.post("/admin/wallet/sign-message").authorization_bearer(&user_token).json(&body).await;
+    response.assert_status(StatusCode::FORBIDDEN);
+
+    ctx.cleanup().await;
+}
@@ -0,0 +1,219 @@
+use async_trait::async_trait;
+use std::sync::{Arc, Mutex};
+
+use exchange_shared::modules::wallet::crud::WalletCrud;
+use exchange_shared::modules::wallet::schema::{GenerateAddressRequest, PayoutRequest};
+use exchange_shared::services::amount::{self, Decimal};
+use exchange_shared::services::gas::{GasStation, GasStationConfig, HOT_WALLET_INDEX};
+use exchange_shared::services::token::permit::{PermitPolicy, PermitRelayer, PERMIT2_ADDRESS};
+use exchange_shared::services::wallet::derivation::derive_evm_address;
+use exchange_shared::services::wallet::manager::WalletManager;
+use exchange_shared::services::wallet::rpc::{BlockchainProvider, RpcError};
+
+use crate::common::TestContext;
+
+const SEED: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+/// DAI's permit typehash, which has `allowed` instead of `value`
+const DAI_PERMIT_TYPEHASH: &str = "0xea2aa0a1be11a07ed86d755c93467f4f82362b452371d1ba94d1715123511acb";
+
+/// A token on a 20 gwei chain holding 100 USDT at every address. The hot
+/// wallet has 1 ETH; deposit addresses have none.
+struct MockToken {
+    hot_wallet: String,
+    eip2612: bool,
+    typehash: Option<&'static str>,
+    permit2_allowance: u128,
+    sent: Mutex<Vec<String>>,
+    calls: Mutex<Vec<String>>,
+}
+
+impl MockToken {
+    async fn new(eip2612: bool, typehash: Option<&'static str>, permit2_allowance: u128) -> Arc<Self> {
+        Arc::new(Self {
+            hot_wallet: derive_evm_address(SEED, HOT_WALLET_INDEX).await.unwrap().to_lowercase(),
+            eip2612,
+            typehash,
+            permit2_allowance,
+            sent: Mutex::new(Vec::new()),
+            calls: Mutex::new(Vec::new()),
+        })
+    }
+
+    fn called(&self, selector: &str) -> usize {
+        self.calls.lock().unwrap().iter().filter(|data| data.starts_with(selector)).count()
+    }
+}
+
+fn revert() -> RpcError {
+    RpcError::Rpc("execution reverted".to_string())
+}
+
+fn word(value: u128) -> String {
+    format!("0x{:064x}", value)
+}
+
+#[async_trait]
+impl BlockchainProvider for MockToken {
+    async fn get_transaction_count(&self, _address: &str) -> Result<u64, RpcError> {
+        Ok(4)
+    }
+
+    async fn get_gas_price(&self) -> Result<u64, RpcError> {
+        Ok(20_000_000_000)
+    }
+
+    async fn send_raw_transaction(&self, signed_hex: &str) -> Result<String, RpcError> {
+        let mut sent = self.sent.lock().unwrap();
+        sent.push(signed_hex.to_string());
+        Ok(format!("0xtx{}", sent.len()))
+    }
+
+    async fn get_balance(&self, address: &str) -> Result<Decimal, RpcError> {
+        Ok(if address.eq_ignore_ascii_case(&self.hot_wallet) { Decimal::ONE } else { Decimal::ZERO })
+    }
+
+    async fn get_token_balance(&self, _contract: &str, _owner: &str) -> Result<u128, RpcError> {
+        Ok(100_000_000)
+    }
+
+    async fn call_contract(&self, _from: Option<&str>, _to: &str, data: &str) -> Result<String, RpcError> {
+        self.calls.lock().unwrap().push(data.to_string());
+        match &data[..10] {
+            "0x3644e515" if self.eip2612 => Ok(format!("0x{}", "11".repeat(32))),
+            "0x7ecebe00" if self.eip2612 => Ok(word(0)),
+            "0x30adf81f" => self.typehash.map(str::to_string).ok_or_else(revert),
+            "0xdd62ed3e" => {
+                let spender = &data[data.len() - 40..];
+                Ok(word(if PERMIT2_ADDRESS.ends_with(spender) { self.permit2_allowance } else { 0 }))
+            }
+            "0xd505accf" | "0x30f28b7a" => Ok("0x".to_string()),
+            _ => Err(revert()),
+        }
+    }
+}
+
+/// A completed BTC → USDT (ERC-20) swap with its deposit address generated
+async fn token_swap(ctx: &TestContext, manager: &WalletManager) -> String {
+    let swap_id = uuid::Uuid::new_v4().to_string();
+    let recipient = "0x742d35Cc6634C0532925a3b844Bc9e7595f5bE12";
+    sqlx::query(
+        r#"
+        INSERT INTO swaps (
+            id, provider_id, from_currency, from_network, to_currency, to_network,
+            amount, estimated_receive, rate, deposit_address, recipient_address, status
+        )
+        VALUES (?, 'changenow', 'BTC', 'bitcoin', 'USDT', 'ERC20', 0.002, 100.0, 50000.0, 'dep_addr', ?, 'completed')
+        "#,
+    )
+    .bind(&swap_id)
+    .bind(recipient)
+    .execute(&ctx.db)
+    .await
+    .unwrap();
+
+    manager
+        .get_or_generate_address(GenerateAddressRequest {
+            swap_id: swap_id.clone(),
+            ticker: "USDT".to_string(),
+            network: "ethereum".to_string(),
+            user_recipient_address: recipient.to_string(),
+            user_recipient_extra_id: None,
+        })
+        .await
+        .unwrap();
+    swap_id
+}
+
+fn manager(ctx: &TestContext, provider: Arc<MockToken>) -> WalletManager {
+    let station = GasStation::new(ctx.db.clone(), provider.clone(), SEED.to_string())
+        .with_config(GasStationConfig { batch_window_ms: 10, ..GasStationConfig::default() });
+    let relayer = PermitRelayer::new(ctx.db.clone(), provider.clone(), SEED.to_string())
+        .with_policy(PermitPolicy::default());
+    WalletManager::new(WalletCrud::new(ctx.db.clone()), SEED.to_string(), provider)
+        .with_gas_station(Arc::new(station))
+        .with_permit_relayer(Arc::new(relayer))
+}
+
+async fn top_ups(ctx: &TestContext, swap_id: &str) -> i64 {
+    let (count,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM gas_topups WHERE swap_id = ?")
+        .bind(swap_id)
+        .fetch_one(&ctx.db)
+        .await
+        .unwrap();
+    count
+}
+
+async fn payout_tx_types(ctx: &TestContext, swap_id: &str) -> Vec<String> {
+    let rows: Vec<(String,)> =
+        sqlx::query_as("SELECT tx_type FROM gas_history WHERE swap_id = ? AND kind = 'payout' ORDER BY id")
+            .bind(swap_id)
+            .fetch_all(&ctx.db)
+            .await
+            .unwrap();
+    rows.into_iter().map(|(t,)| t).collect()
+}
+
+async fn gas_cost(ctx: &TestContext, swap_id: &str) -> Decimal {
+    let (cost,): (Decimal,) =
+        sqlx::query_as("SELECT amount FROM revenue_entries WHERE swap_id = ? AND entry_type = 'gas_cost'")
+            .bind(swap_id)
+            .fetch_one(&ctx.db)
+            .await
+            .unwrap();
+    cost.normalize()
+}
+
+#[tokio::test]
+async fn test_eip2612_token_is_paid_out_without_a_top_up() {
+    let ctx = TestContext::new().await;
+    let provider = MockToken::new(true, None, 0).await;
+    let manager = manager(&ctx, provider.clone());
+    let swap_id = token_swap(&ctx, &manager).await;
+
+    let res = manager.process_payout(PayoutRequest { swap_id: swap_id.clone() }).await.unwrap();
+    assert_eq!(res.amount, amount::parse("98.8").unwrap(), "Expected 98.8 USDT payout");
+    // Permit, then transferFrom, both from the hot wallet
+    assert_eq!(provider.sent.lock().unwrap().len(), 2);
+    assert_eq!(res.tx_hash, "0xtx2");
+    assert_eq!(provider.called("0xd505accf"), 1, "the permit is simulated first");
+    assert_eq!(top_ups(&ctx, &swap_id).await, 0);
+    assert_eq!(payout_tx_types(&ctx, &swap_id).await, vec!["token_permit", "token_transfer_from"]);
+    // 155,000 gas at 20 gwei paid by the hot wallet
+    assert_eq!(gas_cost(&ctx, &swap_id).await, amount::parse("0.0031").unwrap());
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_permit2_is_used_when_the_owner_approved_it() {
+    let ctx = TestContext::new().await;
+    let provider = MockToken::new(false, None, u128::MAX).await;
+    let manager = manager(&ctx, provider.clone());
+    let swap_id = token_swap(&ctx, &manager).await;
+
+    manager.process_payout(PayoutRequest { swap_id: swap_id.clone() }).await.unwrap();
+    assert_eq!(provider.sent.lock().unwrap().len(), 1);
+    assert_eq!(provider.called("0x30f28b7a"), 1);
+    assert_eq!(top_ups(&ctx, &swap_id).await, 0);
+    assert_eq!(payout_tx_types(&ctx, &swap_id).await, vec!["permit_transfer"]);
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_non_standard_permit_falls_back_to_the_gas_station() {
+    let ctx = TestContext::new().await;
+    let provider = MockToken::new(true, Some(DAI_PERMIT_TYPEHASH), 0).await;
+    let manager = manager(&ctx, provider.clone());
+    let swap_id = token_swap(&ctx, &manager).await;
+
+    manager.process_payout(PayoutRequest { swap_id: swap_id.clone() }).await.unwrap();
+    // Nothing was signed for the token; the address was topped up and transferred itself
+    assert_eq!(provider.called("0xd505accf"), 0);
+    assert_eq!(top_ups(&ctx, &swap_id).await, 1);
+    assert_eq!(provider.sent.lock().unwrap().len(), 2);
+    assert_eq!(payout_tx_types(&ctx, &swap_id).await, vec!["token_transfer"]);
+
+    ctx.cleanup().await;
+}
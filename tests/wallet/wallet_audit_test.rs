@@ -0,0 +1,356 @@
+use async_trait::async_trait;
+use axum::http::StatusCode;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use exchange_shared::modules::recovery::crud::RecoveryCrud;
+use exchange_shared::modules::recovery::model::{
+    AllocatedIndex, FundsAsset, OrphanedFunds, OrphanedFundsKind, OrphanedFundsStatus,
+};
+use exchange_shared::services::amount::{self, Decimal};
+use exchange_shared::services::reconciliation::{WalletAuditConfig, WalletAuditor};
+use exchange_shared::services::wallet::rpc::{BlockchainProvider, RpcError};
+
+use crate::common::{create_admin, create_test_user, insert_swap, test_email, test_password, TestContext};
+
+const USDT: &str = "0xdac17f958d2ee523a2206206994597c13d831ec7";
+
+/// Native and raw token balances per address; everything else is empty
+#[derive(Default)]
+struct MockChain {
+    balances: Mutex<HashMap<String, Decimal>>,
+    token_balances: Mutex<HashMap<(String, String), u128>>,
+}
+
+impl MockChain {
+    fn fund(&self, address: &str, amount: &str) {
+        self.balances.lock().unwrap().insert(address.to_lowercase(), amount::parse(amount).unwrap());
+    }
+
+    fn fund_token(&self, contract: &str, address: &str, units: u128) {
+        self.token_balances.lock().unwrap().insert((contract.to_lowercase(), address.to_lowercase()), units);
+    }
+}
+
+#[async_trait]
+impl BlockchainProvider for MockChain {
+    async fn get_transaction_count(&self, _address: &str) -> Result<u64, RpcError> {
+        Ok(0)
+    }
+
+    async fn get_gas_price(&self) -> Result<u64, RpcError> {
+        Ok(20_000_000_000)
+    }
+
+    async fn send_raw_transaction(&self, _signed_hex: &str) -> Result<String, RpcError> {
+        Err(RpcError::Rpc("read-only mock".to_string()))
+    }
+
+    async fn get_balance(&self, address: &str) -> Result<Decimal, RpcError> {
+        Ok(self.balances.lock().unwrap().get(&address.to_lowercase()).copied().unwrap_or_default())
+    }
+
+    async fn get_token_balance(&self, contract: &str, owner: &str) -> Result<u128, RpcError> {
+        let key = (contract.to_lowercase(), owner.to_lowercase());
+        Ok(self.token_balances.lock().unwrap().get(&key).copied().unwrap_or_default())
+    }
+}
+
+/// An address and derivation index no other test uses
+fn fresh_index() -> (u32, String) {
+    let id = uuid::Uuid::new_v4();
+    let bytes = id.as_bytes();
+    let index = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) | 0x8000_0000;
+    (index, format!("0x{}{}", id.simple(), &id.simple().to_string()[..8]))
+}
+
+/// A swap settling on ethereum with its deposit address at a fresh index,
+/// last changed `hours_ago`
+async fn insert_settling_swap(ctx: &TestContext, status: &str, payout_status: &str, hours_ago: i64) -> (String, u32, String) {
+    let swap_id = insert_swap(ctx).await;
+    let (index, address) = fresh_index();
+    sqlx::query("UPDATE swaps SET status = ?, updated_at = NOW() - INTERVAL ? HOUR WHERE id = ?")
+        .bind(status)
+        .bind(hours_ago)
+        .bind(&swap_id)
+        .execute(&ctx.db)
+        .await
+        .unwrap();
+
+    sqlx::query(
+        r#"
+        INSERT INTO swap_address_info (
+            swap_id, our_address, address_index, blockchain_id, coin_type, recipient_address, status
+        ) VALUES (?, ?, ?, 1, 60, '0x742d35Cc6634C0532925a3b844Bc454e4438f44e', ?)
+        "#,
+    )
+    .bind(&swap_id)
+    .bind(&address)
+    .bind(index)
+    .bind(payout_status)
+    .execute(&ctx.db)
+    .await
+    .unwrap();
+
+    (swap_id, index, address)
+}
+
+fn auditor(ctx: &TestContext, ethereum: Arc<MockChain>, polygon: Arc<MockChain>) -> WalletAuditor {
+    let providers: HashMap<String, Arc<dyn BlockchainProvider>> = HashMap::from([
+        ("ethereum".to_string(), ethereum as Arc<dyn BlockchainProvider>),
+        ("polygon".to_string(), polygon as Arc<dyn BlockchainProvider>),
+    ]);
+    WalletAuditor::new(ctx.db.clone(), WalletAuditConfig::default()).with_providers(providers)
+}
+
+async fn findings_for(ctx: &TestContext, index: u32) -> Vec<OrphanedFunds> {
+    RecoveryCrud::new(ctx.db.clone())
+        .list_orphaned_funds(None, None, Some(200))
+        .await
+        .unwrap()
+        .into_iter()
+        .filter(|f| f.address_index == index)
+        .collect()
+}
+
+#[tokio::test]
+async fn test_audit_queues_funds_nothing_accounts_for() {
+    let ctx = TestContext::new().await;
+    let ethereum = Arc::new(MockChain::default());
+    let polygon = Arc::new(MockChain::default());
+
+    // Paid out, yet the deposit address still holds ETH
+    let (_, unswept, address) = insert_settling_swap(&ctx, "completed", "success", 24).await;
+    ethereum.fund(&address, "0.3");
+    // Expired without a deposit, then funded on polygon
+    let (_, unmatched, address) = insert_settling_swap(&ctx, "expired", "pending", 24).await;
+    polygon.fund(&address, "2.0");
+    // Still exchanging: its funds are expected
+    let (_, in_flight, address) = insert_settling_swap(&ctx, "exchanging", "pending", 24).await;
+    ethereum.fund(&address, "1.0");
+    // Payout still owed
+    let (_, owed, address) = insert_settling_swap(&ctx, "completed", "failed", 24).await;
+    ethereum.fund(&address, "1.0");
+    // Finished an hour ago; a sweep may still be on its way
+    let (_, settling, address) = insert_settling_swap(&ctx, "completed", "success", 1).await;
+    ethereum.fund(&address, "1.0");
+    // Dust is ignored
+    let (_, dust, address) = insert_settling_swap(&ctx, "completed", "success", 24).await;
+    ethereum.fund(&address, "0.000000000001");
+
+    let run = auditor(&ctx, ethereum.clone(), polygon.clone()).audit().await.unwrap();
+    assert!(run.finished_at.is_some());
+    assert!(run.findings >= 2);
+    assert_eq!(run.errors, 0);
+
+    let found = findings_for(&ctx, unswept).await;
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].chain, "ethereum");
+    assert_eq!(found[0].currency, "eth");
+    assert_eq!(found[0].token_contract, None);
+    assert_eq!(found[0].kind, OrphanedFundsKind::Unswept);
+    assert_eq!(found[0].amount, 0.3);
+    assert_eq!(found[0].status, OrphanedFundsStatus::Open);
+    assert_eq!(found[0].run_id, run.id);
+
+    let found = findings_for(&ctx, unmatched).await;
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].chain, "polygon");
+    assert_eq!(found[0].currency, "pol");
+    assert_eq!(found[0].kind, OrphanedFundsKind::UnmatchedDeposit);
+    assert_eq!(found[0].swap_status.as_deref(), Some("expired"));
+
+    for index in [in_flight, owed, settling, dust] {
+        assert!(findings_for(&ctx, index).await.is_empty(), "index {} should not be queued", index);
+    }
+
+    // A second run refreshes the entry instead of queueing it twice
+    ethereum.fund(&findings_for(&ctx, unswept).await[0].address, "0.4");
+    let rerun = auditor(&ctx, ethereum, polygon).audit().await.unwrap();
+    let found = findings_for(&ctx, unswept).await;
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].amount, 0.4);
+    assert_eq!(found[0].run_id, rerun.id);
+
+    // Each run is tracked as a system job
+    let (status,): (String,) = sqlx::query_as("SELECT CAST(status AS CHAR) FROM jobs WHERE id = ?")
+        .bind(&rerun.id)
+        .fetch_one(&ctx.db)
+        .await
+        .unwrap();
+    assert_eq!(status, "succeeded");
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_admin_orphaned_funds_queue() {
+    let ctx = TestContext::new().await;
+    let ethereum = Arc::new(MockChain::default());
+    let (_, index, address) = insert_settling_swap(&ctx, "refunded", "pending", 24).await;
+    ethereum.fund(&address, "0.5");
+    auditor(&ctx, ethereum, Arc::new(MockChain::default())).audit().await.unwrap();
+    let funds_id = findings_for(&ctx, index).await[0].id.clone();
+
+    let (_, user_token) = create_test_user(&ctx.server, &test_email(), test_password()).await;
+    let response = ctx
+        .server
+        .get("/admin/orphaned-funds")
+        .add_header("Authorization", format!("Bearer {}", user_token))
+        .await;
+    response.assert_status(StatusCode::FORBIDDEN);
+
+    let token = create_admin(&ctx).await;
+    let body: Value = ctx
+        .server
+        .get("/admin/orphaned-funds?status=open&kind=unswept&limit=200")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await
+        .json();
+    let listed = body["funds"].as_array().unwrap().iter().find(|f| f["id"] == funds_id.as_str()).unwrap();
+    assert_eq!(listed["address"], address.as_str());
+    assert_eq!(listed["chain"], "ethereum");
+    assert_eq!(listed["kind"], "unswept");
+
+    let response = ctx
+        .server
+        .post(&format!("/admin/orphaned-funds/{}/recover", funds_id))
+        .add_header("Authorization", format!("Bearer {}", token))
+        .json(&json!({ "recipient_address": "not-an-address" }))
+        .await;
+    response.assert_status(StatusCode::BAD_REQUEST);
+
+    let response = ctx
+        .server
+        .post(&format!("/admin/orphaned-funds/{}/dismiss", funds_id))
+        .add_header("Authorization", format!("Bearer {}", token))
+        .json(&json!({ "reason": "gas money left by the sweep" }))
+        .await;
+    response.assert_status_ok();
+    let body: Value = response.json();
+    assert_eq!(body["status"], "dismissed");
+    assert_eq!(body["dismiss_reason"], "gas money left by the sweep");
+
+    // Closed entries can't be recovered
+    let response = ctx
+        .server
+        .post(&format!("/admin/orphaned-funds/{}/recover", funds_id))
+        .add_header("Authorization", format!("Bearer {}", token))
+        .json(&json!({ "recipient_address": "0x742d35Cc6634C0532925a3b844Bc454e4438f44e" }))
+        .await;
+    response.assert_status(StatusCode::CONFLICT);
+
+    let body: Value = ctx
+        .server
+        .get("/admin/wallet-audit/runs?limit=5")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await
+        .json();
+    assert!(!body["runs"].as_array().unwrap().is_empty());
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_audit_queues_canonical_token_balances() {
+    let ctx = TestContext::new().await;
+    let ethereum = Arc::new(MockChain::default());
+
+    // Swept of ETH, but USDT sent to the deposit address stayed behind
+    let (_, index, address) = insert_settling_swap(&ctx, "completed", "success", 24).await;
+    ethereum.fund_token(USDT, &address, 25_500_000);
+
+    let run = auditor(&ctx, ethereum, Arc::new(MockChain::default())).audit().await.unwrap();
+    assert_eq!(run.errors, 0);
+
+    let found = findings_for(&ctx, index).await;
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].chain, "ethereum");
+    assert_eq!(found[0].currency, "usdt");
+    assert_eq!(found[0].token_contract.as_deref(), Some(USDT));
+    assert_eq!(found[0].amount, 25.5);
+    assert_eq!(found[0].kind, OrphanedFundsKind::Unswept);
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_audit_reports_chains_without_a_node() {
+    let ctx = TestContext::new().await;
+
+    // A Bitcoin deposit address; no Bitcoin node is configured
+    let (swap_id, index, _) = insert_settling_swap(&ctx, "completed", "success", 24).await;
+    sqlx::query("UPDATE swap_address_info SET our_address = ? WHERE swap_id = ?")
+        .bind("1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN2")
+        .bind(&swap_id)
+        .execute(&ctx.db)
+        .await
+        .unwrap();
+
+    let run = auditor(&ctx, Arc::new(MockChain::default()), Arc::new(MockChain::default()))
+        .audit()
+        .await
+        .unwrap();
+    assert!(run.unaudited_indices >= 1);
+    assert!(run.unaudited_chains().contains(&"bitcoin".to_string()));
+    assert!(findings_for(&ctx, index).await.is_empty());
+
+    let token = create_admin(&ctx).await;
+    let body: Value = ctx
+        .server
+        .get("/admin/wallet-audit/runs?limit=50")
+        .add_header("Authorization", format!("Bearer {}", token))
+        .await
+        .json();
+    let listed = body["runs"].as_array().unwrap().iter().find(|r| r["id"] == run.id.as_str()).unwrap();
+    assert!(listed["unaudited_indices"].as_u64().unwrap() >= 1);
+    assert!(listed["unaudited_chains"].as_array().unwrap().iter().any(|c| c == "bitcoin"));
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_non_evm_findings_are_not_swept() {
+    let ctx = TestContext::new().await;
+    let crud = RecoveryCrud::new(ctx.db.clone());
+    let (swap_id, index, _) = insert_settling_swap(&ctx, "completed", "success", 24).await;
+    let run_id = crud.start_audit_run().await.unwrap();
+    let allocated = AllocatedIndex {
+        address_index: index,
+        address: "1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN2".to_string(),
+        swap_id,
+        swap_status: Some("completed".to_string()),
+        network: Some("bitcoin".to_string()),
+        payout_status: Some("success".to_string()),
+        updated_at: None,
+        refund_open: false,
+    };
+    crud.record_orphaned_funds(
+        &run_id,
+        &allocated,
+        &FundsAsset::native("bitcoin", "btc"),
+        OrphanedFundsKind::Unswept,
+        amount::parse("0.01").unwrap(),
+    )
+    .await
+    .unwrap();
+    let funds_id = findings_for(&ctx, index).await[0].id.clone();
+
+    let token = create_admin(&ctx).await;
+    let response = ctx
+        .server
+        .post(&format!("/admin/orphaned-funds/{}/recover", funds_id))
+        .add_header("Authorization", format!("Bearer {}", token))
+        .json(&json!({ "recipient_address": "0x742d35Cc6634C0532925a3b844Bc454e4438f44e" }))
+        .await;
+    response.assert_status(StatusCode::UNPROCESSABLE_ENTITY);
+    let body: Value = response.json();
+    assert!(body["error"].as_str().unwrap().contains("bitcoin"));
+
+    // Still open for the operator to move by hand and dismiss
+    let funds = crud.get_orphaned_funds(&funds_id).await.unwrap().unwrap();
+    assert_eq!(funds.status, OrphanedFundsStatus::Open);
+
+    ctx.cleanup().await;
+}
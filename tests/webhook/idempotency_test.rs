@@ -0,0 +1,66 @@
+use exchange_shared::services::redis_cache::RedisService;
+use exchange_shared::services::webhook::idempotency::{Claim, ConsumeError, Consumed, IdempotencyStore};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use uuid::Uuid;
+
+fn store() -> IdempotencyStore {
+    dotenvy::dotenv().ok();
+    let redis_url = std::env::var("REDIS_URL").unwrap_or_else(|_| "redis://127.0.0.1/".to_string());
+    IdempotencyStore::new(RedisService::new(&redis_url)).with_ttl(60)
+}
+
+#[tokio::test]
+async fn test_duplicate_event_is_handled_once() {
+    let store = store();
+    let event_id = Uuid::new_v4().to_string();
+    let calls = AtomicUsize::new(0);
+
+    for attempt in 0..3 {
+        let result = store
+            .consume("provider:test", &event_id, || async {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Ok::<_, String>("applied")
+            })
+            .await
+            .expect("store available");
+        if attempt == 0 {
+            assert_eq!(result, Consumed::Handled("applied"));
+        } else {
+            assert_eq!(result, Consumed::Duplicate(Claim::Handled));
+        }
+    }
+
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn test_failed_event_is_released_for_retry() {
+    let store = store();
+    let event_id = Uuid::new_v4().to_string();
+
+    let failed = store
+        .consume("provider:test", &event_id, || async { Err::<(), _>("swap not found") })
+        .await;
+    assert!(matches!(failed, Err(ConsumeError::Handler("swap not found"))));
+
+    let retried = store
+        .consume("provider:test", &event_id, || async { Ok::<_, String>(()) })
+        .await
+        .expect("store available");
+    assert_eq!(retried, Consumed::Handled(()));
+}
+
+#[tokio::test]
+async fn test_event_in_flight_is_not_handled_again() {
+    let store = store();
+    let event_id = Uuid::new_v4().to_string();
+
+    assert_eq!(store.claim("provider:test", &event_id).await.unwrap(), Claim::New);
+    assert_eq!(store.claim("provider:test", &event_id).await.unwrap(), Claim::InFlight);
+
+    // Events are keyed per sender
+    assert_eq!(store.claim("provider:other", &event_id).await.unwrap(), Claim::New);
+
+    store.complete("provider:test", &event_id).await.unwrap();
+    assert_eq!(store.claim("provider:test", &event_id).await.unwrap(), Claim::Handled);
+}
@@ -1 +1,3 @@
 pub mod webhook_dispatcher_test;
+pub mod idempotency_test;
+pub mod webhook_replay_test;
@@ -69,6 +69,7 @@ async fn create_test_webhook(pool: &MySqlPool, swap_id: Uuid) -> Webhook {
         url: "https://example.com/webhook".to_string(),
         secret_key: secret_key.to_string(),
         events: vec!["swap.completed".to_string(), "swap.failed".to_string()],
+        payload_version: None,
         enabled: true,
         rate_limit_per_second: 10,
         created_at: Utc::now(),
@@ -0,0 +1,229 @@
+use axum::{extract::State, http::{HeaderMap, StatusCode}, routing::post, Router};
+use serde_json::Value;
+use serial_test::serial;
+use std::sync::atomic::{AtomicU16, Ordering};
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+use exchange_shared::services::webhook::verify_signature;
+
+use crate::common::{create_test_user, test_email, test_password, TestContext};
+
+const SECRET: &str = "test_secret_key_12345678901234567890";
+
+/// Integrator endpoint answering with `status` and keeping what it was sent
+#[derive(Default)]
+struct Receiver {
+    status: AtomicU16,
+    received: Mutex<Vec<(HeaderMap, String)>>,
+}
+
+async fn receive(State(receiver): State<Arc<Receiver>>, headers: HeaderMap, body: String) -> StatusCode {
+    receiver.received.lock().unwrap().push((headers, body));
+    StatusCode::from_u16(receiver.status.load(Ordering::SeqCst)).unwrap()
+}
+
+async fn start_receiver() -> (Arc<Receiver>, String) {
+    let receiver = Arc::new(Receiver { status: AtomicU16::new(200), ..Default::default() });
+    let app = Router::new().route("/hook", post(receive)).with_state(receiver.clone());
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/hook", listener.local_addr().unwrap());
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    (receiver, url)
+}
+
+/// A webhook on a swap owned by `user_id`
+async fn create_webhook(ctx: &TestContext, user_id: &str, url: &str) -> String {
+    let swap_id = Uuid::new_v4().to_string();
+    sqlx::query(
+        r#"
+        INSERT INTO swaps (
+            id, user_id, provider_id, from_currency, from_network, to_currency, to_network,
+            amount, estimated_receive, rate, deposit_address, recipient_address, status
+        )
+        VALUES (?, ?, 'changenow', 'btc', 'bitcoin', 'eth', 'ethereum', 0.01, 0.25, 25.0, 'dep_addr', '0xrecipient', 'waiting')
+        "#,
+    )
+    .bind(&swap_id)
+    .bind(user_id)
+    .execute(&ctx.db)
+    .await
+    .unwrap();
+
+    let webhook_id = Uuid::new_v4().to_string();
+    sqlx::query(
+        r#"
+        INSERT INTO webhooks (id, swap_id, url, secret_key, events, enabled, rate_limit_per_second)
+        VALUES (?, ?, ?, ?, JSON_ARRAY('swap.completed'), true, 10)
+        "#,
+    )
+    .bind(&webhook_id)
+    .bind(&swap_id)
+    .bind(url)
+    .bind(SECRET)
+    .execute(&ctx.db)
+    .await
+    .unwrap();
+    webhook_id
+}
+
+async fn cleanup(ctx: &TestContext, webhook_id: &str) {
+    sqlx::query("DELETE FROM webhook_delivery_attempts WHERE webhook_id = ?")
+        .bind(webhook_id)
+        .execute(&ctx.db)
+        .await
+        .ok();
+    sqlx::query("DELETE FROM webhook_deliveries WHERE webhook_id = ?")
+        .bind(webhook_id)
+        .execute(&ctx.db)
+        .await
+        .ok();
+    sqlx::query("DELETE FROM webhooks WHERE id = ?").bind(webhook_id).execute(&ctx.db).await.ok();
+    ctx.cleanup().await;
+}
+
+// Serial with the dispatcher tests, which clear every delivery
+#[tokio::test]
+#[serial]
+async fn test_fire_and_redeliver_are_recorded() {
+    let ctx = TestContext::new().await;
+    let (receiver, url) = start_receiver().await;
+    let (user_id, token) = create_test_user(&ctx.server, &test_email(), test_password()).await;
+    let webhook_id = create_webhook(&ctx, &user_id, &url).await;
+    let auth = format!("Bearer {}", token);
+
+    // A signed sample event reaches the endpoint
+    let body: Value = ctx
+        .server
+        .post(&format!("/webhooks/{}/test", webhook_id))
+        .add_header("Authorization", auth.clone())
+        .await
+        .json();
+    assert_eq!(body["succeeded"], true);
+    assert_eq!(body["response_status"], 200);
+    assert_eq!(body["event_type"], "swap.completed");
+    {
+        let received = receiver.received.lock().unwrap();
+        let (headers, payload) = &received[0];
+        let header = |name: &str| headers.get(name).unwrap().to_str().unwrap().to_string();
+        let timestamp: i64 = header("x-webhook-timestamp").parse().unwrap();
+        verify_signature(SECRET, &header("x-webhook-signature"), timestamp, payload, 300).unwrap();
+        let payload: Value = serde_json::from_str(payload).unwrap();
+        assert!(payload["id"].as_str().unwrap().starts_with("test_"));
+        assert_eq!(payload["data"]["to"], "completed");
+    }
+
+    // A failing endpoint is reported, not raised
+    receiver.status.store(500, Ordering::SeqCst);
+    let failed: Value = ctx
+        .server
+        .post(&format!("/webhooks/{}/test", webhook_id))
+        .add_header("Authorization", auth.clone())
+        .await
+        .json();
+    assert_eq!(failed["succeeded"], false);
+    assert_eq!(failed["response_status"], 500);
+    let delivery_id = failed["delivery_id"].as_str().unwrap().to_string();
+
+    // Once fixed, the failed delivery is replayed with the same payload
+    receiver.status.store(200, Ordering::SeqCst);
+    let response = ctx
+        .server
+        .post(&format!("/webhooks/{}/redeliver/{}", webhook_id, delivery_id))
+        .add_header("Authorization", auth.clone())
+        .await;
+    response.assert_status_ok();
+    let body: Value = response.json();
+    assert_eq!(body["succeeded"], true);
+    assert_eq!(body["delivery_id"], delivery_id.as_str());
+    {
+        let received = receiver.received.lock().unwrap();
+        assert_eq!(received.len(), 3);
+        let id = |i: usize| serde_json::from_str::<Value>(&received[i].1).unwrap()["id"].clone();
+        assert_eq!(id(1), id(2));
+    }
+
+    // Each send is kept with its response code and timing
+    let attempts: Vec<(String, Option<i32>, bool)> = sqlx::query_as(
+        "SELECT CAST(source AS CHAR), response_status, succeeded FROM webhook_delivery_attempts WHERE delivery_id = ? ORDER BY id",
+    )
+    .bind(&delivery_id)
+    .fetch_all(&ctx.db)
+    .await
+    .unwrap();
+    assert_eq!(
+        attempts,
+        vec![("test".to_string(), Some(500), false), ("redelivery".to_string(), Some(200), true)]
+    );
+
+    let body: Value = ctx
+        .server
+        .get(&format!("/webhooks/{}/deliveries", webhook_id))
+        .add_header("Authorization", auth.clone())
+        .await
+        .json();
+    let deliveries = body["deliveries"].as_array().unwrap();
+    assert_eq!(deliveries.len(), 2);
+    assert!(deliveries.iter().all(|d| d["is_test"] == true && d["delivered"] == true));
+    let replayed = deliveries.iter().find(|d| d["id"] == delivery_id.as_str()).unwrap();
+    assert_eq!(replayed["response_status"], 200);
+    assert!(replayed["response_time_ms"].is_number());
+
+    // Unknown deliveries and other users' webhooks are not found
+    let response = ctx
+        .server
+        .post(&format!("/webhooks/{}/redeliver/{}", webhook_id, Uuid::new_v4()))
+        .add_header("Authorization", auth)
+        .await;
+    response.assert_status(StatusCode::NOT_FOUND);
+
+    let (_, other_token) = create_test_user(&ctx.server, &test_email(), test_password()).await;
+    let response = ctx
+        .server
+        .post(&format!("/webhooks/{}/test", webhook_id))
+        .add_header("Authorization", format!("Bearer {}", other_token))
+        .await;
+    response.assert_status(StatusCode::NOT_FOUND);
+    assert_eq!(receiver.received.lock().unwrap().len(), 3);
+
+    cleanup(&ctx, &webhook_id).await;
+}
+
+#[tokio::test]
+#[serial]
+async fn test_manual_sends_are_capped_per_webhook() {
+    let ctx = TestContext::new().await;
+    let (receiver, url) = start_receiver().await;
+    let (user_id, token) = create_test_user(&ctx.server, &test_email(), test_password()).await;
+    let webhook_id = create_webhook(&ctx, &user_id, &url).await;
+
+    // 29 sends this hour, one from yesterday and one delivered event
+    for (source, hours_ago) in (0..29).map(|_| ("redelivery", 0)).chain([("test", 25), ("event", 0)]) {
+        sqlx::query(
+            r#"
+            INSERT INTO webhook_delivery_attempts (
+                delivery_id, webhook_id, source, response_status, response_time_ms, succeeded, created_at
+            )
+            VALUES (?, ?, ?, 200, 40, true, NOW() - INTERVAL ? HOUR)
+            "#,
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(&webhook_id)
+        .bind(source)
+        .bind(hours_ago)
+        .execute(&ctx.db)
+        .await
+        .unwrap();
+    }
+
+    let send = || {
+        ctx.server
+            .post(&format!("/webhooks/{}/test", webhook_id))
+            .add_header("Authorization", format!("Bearer {}", token))
+    };
+    send().await.assert_status_ok();
+    send().await.assert_status(StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(receiver.received.lock().unwrap().len(), 1);
+
+    cleanup(&ctx, &webhook_id).await;
+}
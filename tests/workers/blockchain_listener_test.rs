@@ -4,7 +4,11 @@
 // =============================================================================
 
 use crate::common::TestContext;
-use exchange_shared::services::blockchain::BlockchainListener;
+use exchange_shared::services::amount::{self, Decimal};
+use exchange_shared::services::blockchain::shards::shard_of;
+use exchange_shared::services::blockchain::{BlockchainListener, Shard};
+use exchange_shared::services::wallet::derivation::derive_evm_address;
+use exchange_shared::test_support::{fixtures, MockChainContext, RpcChain, TEST_MNEMONIC};
 use uuid::Uuid;
 
 // Helper to create a swap waiting for funds
@@ -230,3 +234,408 @@ async fn test_listener_ignores_old_swaps() {
     
     ctx.cleanup().await;
 }
+
+#[tokio::test]
+async fn test_listener_detects_funds_from_mock_rpc() {
+    let ctx = TestContext::new().await;
+    let chains = MockChainContext::new().await;
+    let swap_id = Uuid::new_v4().to_string();
+    let our_address = format!("0x{}", &Uuid::new_v4().simple().to_string()[..32]);
+
+    create_swap_waiting_for_funds(&ctx.db, &swap_id, &our_address, "ethereum", 1.0, 0.012).await;
+
+    // Fixture balance is 1 ETH for every address; fund ours with enough to clear the threshold
+    chains.rpc.mock_evm_balance(&our_address, 1_012_000_000_000_000_000).await;
+
+    let listener = chains.listener(ctx.db.clone());
+    listener.run_once().await.unwrap();
+
+    let (status,): (String,) = sqlx::query_as("SELECT CAST(status AS CHAR) FROM swaps WHERE id = ?")
+        .bind(&swap_id)
+        .fetch_one(&ctx.db)
+        .await
+        .unwrap();
+
+    assert_eq!(status, "funds_received");
+    assert!(chains.rpc.calls(RpcChain::Evm, "eth_getBalance").await >= 1);
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_listener_accepts_top_up_after_underpayment() {
+    let ctx = TestContext::new().await;
+    let chains = MockChainContext::new().await;
+    let swap_id = Uuid::new_v4().to_string();
+    let our_address = format!("0x{}", &Uuid::new_v4().simple().to_string()[..32]);
+
+    create_swap_waiting_for_funds(&ctx.db, &swap_id, &our_address, "ethereum", 1.0, 0.0).await;
+    let listener = chains.listener(ctx.db.clone());
+
+    // First deposit covers half the swap
+    chains.rpc.mock_evm_balance(&our_address, 500_000_000_000_000_000).await;
+    listener.run_once().await.unwrap();
+
+    let (status, expires_at): (String, Option<chrono::DateTime<chrono::Utc>>) =
+        sqlx::query_as("SELECT CAST(status AS CHAR), expires_at FROM swaps WHERE id = ?")
+            .bind(&swap_id)
+            .fetch_one(&ctx.db)
+            .await
+            .unwrap();
+    assert_eq!(status, "sending");
+    assert!(expires_at.unwrap() > chrono::Utc::now(), "top-up window should be open");
+
+    let (received, deposits): (f64, u32) = sqlx::query_as(
+        "SELECT CAST(actual_received AS DOUBLE), deposit_count FROM swap_address_info WHERE swap_id = ?"
+    )
+    .bind(&swap_id)
+    .fetch_one(&ctx.db)
+    .await
+    .unwrap();
+    assert!((received - 0.5).abs() < 1e-9);
+    assert_eq!(deposits, 1);
+
+    // Second deposit brings the total over the threshold
+    chains.rpc.reset().await;
+    chains.rpc.mock_evm_balance(&our_address, 1_000_000_000_000_000_000).await;
+    listener.run_once().await.unwrap();
+
+    let (status,): (String,) = sqlx::query_as("SELECT CAST(status AS CHAR) FROM swaps WHERE id = ?")
+        .bind(&swap_id)
+        .fetch_one(&ctx.db)
+        .await
+        .unwrap();
+    assert_eq!(status, "funds_received");
+
+    let (receipts,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM deposit_receipts WHERE swap_id = ?")
+        .bind(&swap_id)
+        .fetch_one(&ctx.db)
+        .await
+        .unwrap();
+    assert_eq!(receipts, 2);
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_listener_opens_case_for_wrong_network_deposit() {
+    let ctx = TestContext::new().await;
+    // Both chains share the mock endpoint, so the balance shows up on bsc too
+    let chains = MockChainContext::new().await.with_evm_networks(&["ethereum", "bsc"]);
+    let swap_id = Uuid::new_v4().to_string();
+    let our_address = format!("0x{}", &Uuid::new_v4().simple().to_string()[..32]);
+
+    create_swap_waiting_for_funds(&ctx.db, &swap_id, &our_address, "ethereum", 1.0, 0.0).await;
+    chains.rpc.mock_evm_balance(&our_address, 250_000_000_000_000_000).await;
+
+    let listener = chains.listener(ctx.db.clone());
+    listener.scan_wrong_networks().await.unwrap();
+
+    let (expected, detected, amount, status): (String, String, f64, String) = sqlx::query_as(
+        "SELECT expected_network, detected_network, amount, CAST(status AS CHAR) FROM wrong_network_cases WHERE swap_id = ?"
+    )
+    .bind(&swap_id)
+    .fetch_one(&ctx.db)
+    .await
+    .unwrap();
+    assert_eq!(expected, "ethereum");
+    assert_eq!(detected, "bsc");
+    assert!((amount - 0.25).abs() < 1e-9);
+    assert_eq!(status, "open");
+
+    // A second sighting updates the open case rather than opening another
+    listener.scan_wrong_networks().await.unwrap();
+    let (cases,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM wrong_network_cases WHERE swap_id = ?")
+        .bind(&swap_id)
+        .fetch_one(&ctx.db)
+        .await
+        .unwrap();
+    assert_eq!(cases, 1);
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_listener_ignores_spoofed_token_transfers() {
+    let ctx = TestContext::new().await;
+    let chains = MockChainContext::new().await;
+    let swap_id = Uuid::new_v4().to_string();
+    let our_address = format!("0x{}", &Uuid::new_v4().simple().to_string()[..32]);
+
+    create_swap_waiting_for_funds(&ctx.db, &swap_id, &our_address, "ethereum", 100.0, 0.0).await;
+    sqlx::query("UPDATE swaps SET to_currency = 'USDT' WHERE id = ?")
+        .bind(&swap_id)
+        .execute(&ctx.db)
+        .await
+        .unwrap();
+
+    // A look-alike "USDT" contract sends far more than the swap expects
+    let to_topic = format!("0x{:0>64}", our_address.trim_start_matches("0x"));
+    chains.rpc.mock_result(RpcChain::Evm, "eth_getLogs", serde_json::json!([{
+        "address": "0x00000000000000000000000000000000deadbeef",
+        "topics": [
+            "0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef",
+            "0x0000000000000000000000001111111111111111111111111111111111111111",
+            to_topic
+        ],
+        "data": "0x000000000000000000000000000000000000000000000000000000174876e800",
+        "transactionHash": format!("0x{}", Uuid::new_v4().simple()),
+        "logIndex": "0x0",
+        "blockNumber": "0x121eac0"
+    }])).await;
+
+    let listener = chains.listener(ctx.db.clone());
+    listener.run_once().await.unwrap();
+
+    let (status,): (String,) = sqlx::query_as("SELECT CAST(status AS CHAR) FROM swaps WHERE id = ?")
+        .bind(&swap_id)
+        .fetch_one(&ctx.db)
+        .await
+        .unwrap();
+    assert_eq!(status, "sending", "spoofed tokens must not fund the swap");
+
+    let (reason,): (String,) = sqlx::query_as(
+        "SELECT CAST(reason AS CHAR) FROM flagged_token_transfers WHERE swap_id = ?"
+    )
+    .bind(&swap_id)
+    .fetch_one(&ctx.db)
+    .await
+    .unwrap();
+    assert_eq!(reason, "non_canonical");
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_listener_catches_up_on_blocks_missed_while_down() {
+    let ctx = TestContext::new().await;
+    let chains = MockChainContext::new().await;
+    let swap_id = Uuid::new_v4().to_string();
+    let our_address = format!("0x{}", &Uuid::new_v4().simple().to_string()[..32]);
+
+    create_swap_waiting_for_funds(&ctx.db, &swap_id, &our_address, "ethereum", 1.0, 0.0).await;
+    chains.rpc.mock_evm_balance(&our_address, 1_000_000_000_000_000_000).await;
+
+    // Last completed check was three hours and 120k blocks ago
+    sqlx::query(
+        r#"
+        INSERT INTO chain_scan_cursors (network, last_block, updated_at)
+        VALUES ('ethereum', ?, DATE_SUB(NOW(), INTERVAL 3 HOUR))
+        ON DUPLICATE KEY UPDATE last_block = VALUES(last_block), updated_at = VALUES(updated_at)
+        "#
+    )
+    .bind(fixtures::EVM_BLOCK_NUMBER - 120_000)
+    .execute(&ctx.db)
+    .await
+    .unwrap();
+
+    let listener = chains.listener(ctx.db.clone()).with_catch_up_max_blocks(50_000);
+    let reports = listener.catch_up().await;
+
+    assert_eq!(reports.len(), 1);
+    let report = &reports[0];
+    assert_eq!(report.range.gap_blocks, 120_000);
+    assert_eq!(report.range.blocks(), 50_000);
+    assert!(report.range.truncated);
+    assert!(report.deposits_recovered >= 1);
+    assert!(report.downtime_secs() >= 3 * 3600 - 60);
+
+    let (status,): (String,) = sqlx::query_as("SELECT CAST(status AS CHAR) FROM swaps WHERE id = ?")
+        .bind(&swap_id)
+        .fetch_one(&ctx.db)
+        .await
+        .unwrap();
+    assert_eq!(status, "funds_received");
+
+    let (last_block,): (u64,) = sqlx::query_as(
+        "SELECT last_block FROM chain_scan_cursors WHERE network = 'ethereum' AND shard_count = 1 AND shard_index = 0"
+    )
+        .fetch_one(&ctx.db)
+        .await
+        .unwrap();
+    assert_eq!(last_block, fixtures::EVM_BLOCK_NUMBER);
+
+    // Nothing left to catch up on a second start
+    assert!(listener.catch_up().await.is_empty());
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_listener_shard_scans_only_its_addresses() {
+    let ctx = TestContext::new().await;
+    let chains = MockChainContext::new().await;
+
+    // One funded swap in each of two shards
+    let mut swaps: Vec<(String, String)> = Vec::new();
+    while swaps.len() < 2 {
+        let address = format!("0x{}", &Uuid::new_v4().simple().to_string()[..32]);
+        if shard_of(&address, 2) as usize == swaps.len() {
+            let swap_id = Uuid::new_v4().to_string();
+            create_swap_waiting_for_funds(&ctx.db, &swap_id, &address, "ethereum", 1.0, 0.0).await;
+            swaps.push((swap_id, address));
+        }
+    }
+
+    let status = |swap_id: String| {
+        let db = ctx.db.clone();
+        async move {
+            let (status,): (String,) = sqlx::query_as("SELECT CAST(status AS CHAR) FROM swaps WHERE id = ?")
+                .bind(swap_id)
+                .fetch_one(&db)
+                .await
+                .unwrap();
+            status
+        }
+    };
+
+    let second = chains.listener(ctx.db.clone()).with_shard(Shard::new(1, 2).unwrap());
+    second.run_once().await.unwrap();
+    assert_eq!(status(swaps[0].0.clone()).await, "sending", "shard 1 must not touch shard 0's address");
+    assert_eq!(status(swaps[1].0.clone()).await, "funds_received");
+
+    let first = chains.listener(ctx.db.clone()).with_shard(Shard::new(0, 2).unwrap());
+    first.run_once().await.unwrap();
+    assert_eq!(status(swaps[0].0.clone()).await, "funds_received");
+
+    ctx.cleanup().await;
+}
+
+// Helper to create a swap that expired before its deposit arrived; returns
+// the deposit address, derived from the test mnemonic so refunds can be signed
+async fn create_expired_swap(
+    db: &sqlx::Pool<sqlx::MySql>,
+    swap_id: &str,
+    refund_address: Option<&str>,
+) -> String {
+    let address_index = (Uuid::new_v4().as_u128() % 1_000_000) as u32;
+    let our_address = derive_evm_address(TEST_MNEMONIC, address_index).await.unwrap();
+
+    sqlx::query(
+        r#"
+        INSERT INTO swaps (
+            id, provider_id, provider_swap_id, from_currency, from_network,
+            to_currency, to_network, amount, estimated_receive, platform_fee,
+            rate, deposit_address, recipient_address, refund_address, status
+        )
+        VALUES (?, 'changenow', 'test_trade_123', 'BTC', 'bitcoin',
+                'ETH', 'ethereum', 0.1, 1.0, 0.0, 15.0, 'dep_addr', '0x742d35...', ?, 'expired')
+        "#
+    )
+    .bind(swap_id)
+    .bind(refund_address)
+    .execute(db)
+    .await
+    .expect("Failed to create swap");
+
+    sqlx::query(
+        r#"
+        INSERT INTO swap_address_info (
+            swap_id, our_address, address_index, blockchain_id, coin_type,
+            recipient_address, status
+        )
+        VALUES (?, ?, ?, 1, 60, '0x742d35Cc6634C0532925a3b844Bc454e4438f44e', 'pending')
+        "#
+    )
+    .bind(swap_id)
+    .bind(&our_address)
+    .bind(address_index)
+    .execute(db)
+    .await
+    .expect("Failed to create address info");
+
+    our_address
+}
+
+async fn late_deposit_refund(
+    db: &sqlx::Pool<sqlx::MySql>,
+    swap_id: &str,
+) -> (String, Decimal, Option<Decimal>, Option<String>, Option<String>) {
+    sqlx::query_as(
+        "SELECT CAST(status AS CHAR), refund_amount, total_fee, tx_hash, failure_reason FROM refunds WHERE swap_id = ?"
+    )
+    .bind(swap_id)
+    .fetch_one(db)
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_late_deposits_are_refunded_or_held_for_review() {
+    let ctx = TestContext::new().await;
+    let chains = MockChainContext::new().await;
+    let refundable = Uuid::new_v4().to_string();
+    let bitcoin_refund = Uuid::new_v4().to_string();
+    let no_refund = Uuid::new_v4().to_string();
+    let refundable_address =
+        create_expired_swap(&ctx.db, &refundable, Some("0x742d35Cc6634C0532925a3b844Bc454e4438f44e")).await;
+    let bitcoin_address =
+        create_expired_swap(&ctx.db, &bitcoin_refund, Some("bc1qxy2kgdygjrsqtzq2n0yrf2493p83kkfjhx0wlh")).await;
+    let no_refund_address = create_expired_swap(&ctx.db, &no_refund, None).await;
+
+    // 0.25 ETH arrives at each address after its swap expired
+    for address in [&refundable_address, &bitcoin_address, &no_refund_address] {
+        chains.rpc.mock_evm_balance(address, 250_000_000_000_000_000).await;
+    }
+    let listener = chains.listener(ctx.db.clone());
+    assert!(listener.scan_late_deposits().await.unwrap() >= 3);
+
+    let (status, refund_amount, _, tx_hash, reason) = late_deposit_refund(&ctx.db, &refundable).await;
+    assert_eq!(status, "PENDING");
+    assert_eq!(refund_amount, amount::parse("0.25").unwrap());
+    assert!(tx_hash.is_none());
+    assert!(reason.is_none());
+
+    let (status, _, _, _, reason) = late_deposit_refund(&ctx.db, &bitcoin_refund).await;
+    assert_eq!(status, "MANUAL");
+    assert!(reason.unwrap().contains("not an EVM address"));
+
+    let (status, _, _, _, reason) = late_deposit_refund(&ctx.db, &no_refund).await;
+    assert_eq!(status, "MANUAL");
+    assert_eq!(reason.as_deref(), Some("Swap has no refund address"));
+
+    // A second sighting doesn't record another refund
+    listener.scan_late_deposits().await.unwrap();
+    let (refunds,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM refunds WHERE swap_id = ?")
+        .bind(&refundable)
+        .fetch_one(&ctx.db)
+        .await
+        .unwrap();
+    assert_eq!(refunds, 1);
+
+    // More arrives before the refund goes out; only the recorded deposit is sent back
+    chains.rpc.reset().await;
+    chains.rpc.mock_evm_balance(&refundable_address, 400_000_000_000_000_000).await;
+    chains.late_deposit_refunder(ctx.db.clone()).process().await.unwrap();
+
+    let (status, refund_amount, total_fee, tx_hash, _) = late_deposit_refund(&ctx.db, &refundable).await;
+    assert_eq!(status, "COMPLETED");
+    assert_eq!(tx_hash.as_deref(), Some(fixtures::EVM_TX_HASH));
+    assert_eq!(refund_amount, amount::parse("0.25").unwrap());
+    // 21000 gas at the fixture's 20 gwei
+    assert_eq!(total_fee, Some(amount::parse("0.00042").unwrap()));
+    assert!(chains.rpc.calls(RpcChain::Evm, "eth_sendRawTransaction").await >= 1);
+
+    let swap_status = |swap_id: String| {
+        let db = ctx.db.clone();
+        async move {
+            let (status,): (String,) = sqlx::query_as("SELECT CAST(status AS CHAR) FROM swaps WHERE id = ?")
+                .bind(swap_id)
+                .fetch_one(&db)
+                .await
+                .unwrap();
+            status
+        }
+    };
+    assert_eq!(swap_status(refundable.clone()).await, "refunded");
+
+    // Both manual refunds are left for an admin
+    for swap_id in [&bitcoin_refund, &no_refund] {
+        let (status, _, _, tx_hash, _) = late_deposit_refund(&ctx.db, swap_id).await;
+        assert_eq!(status, "MANUAL");
+        assert!(tx_hash.is_none());
+        assert_eq!(swap_status(swap_id.clone()).await, "expired");
+    }
+
+    ctx.cleanup().await;
+}
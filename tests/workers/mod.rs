@@ -3,4 +3,7 @@ pub mod adaptive_polling_test;
 pub mod failure_recovery_test;
 pub mod optimal_polling_test;
 pub mod robustness_test;
-pub mod blockchain_listener_test;
\ No newline at end of file
+pub mod blockchain_listener_test;
+pub mod order_reconciliation_test;
+pub mod swap_state_test;
+pub mod warmup_test;
\ No newline at end of file
@@ -0,0 +1,106 @@
+// =============================================================================
+// INTEGRATION TESTS - PROVIDER ORDER RECONCILIATION
+// Intents left behind by interrupted swap creation are settled
+// =============================================================================
+
+use crate::common::TestContext;
+use exchange_shared::services::reconciliation::OrphanOrderReconciler;
+use uuid::Uuid;
+
+async fn insert_stale_intent(
+    db: &sqlx::Pool<sqlx::MySql>,
+    swap_id: &str,
+    status: &str,
+    provider_swap_id: Option<&str>,
+    address_index: u32,
+) {
+    sqlx::query(
+        r#"
+        INSERT INTO provider_order_intents (
+            swap_id, provider_id, provider_swap_id, our_address, address_index, status, updated_at
+        )
+        VALUES (?, 'changenow', ?, '0xabc', ?, ?, DATE_SUB(NOW(), INTERVAL 1 HOUR))
+        "#
+    )
+    .bind(swap_id)
+    .bind(provider_swap_id)
+    .bind(address_index)
+    .bind(status)
+    .execute(db)
+    .await
+    .expect("Failed to insert intent");
+}
+
+async fn intent_status(db: &sqlx::Pool<sqlx::MySql>, swap_id: &str) -> String {
+    let (status,): (String,) = sqlx::query_as(
+        "SELECT CAST(status AS CHAR) FROM provider_order_intents WHERE swap_id = ?"
+    )
+    .bind(swap_id)
+    .fetch_one(db)
+    .await
+    .unwrap();
+    status
+}
+
+#[tokio::test]
+async fn test_reconciler_settles_interrupted_creations() {
+    let ctx = TestContext::new().await;
+
+    // Provider order placed, swap row never written
+    let orphan_id = Uuid::new_v4().to_string();
+    insert_stale_intent(&ctx.db, &orphan_id, "created", Some("trade_orphan"), 0).await;
+
+    // Crashed before the provider answered
+    let pending_id = Uuid::new_v4().to_string();
+    insert_stale_intent(&ctx.db, &pending_id, "pending", None, 0).await;
+
+    // Swap committed but the intent update was lost
+    let committed_id = Uuid::new_v4().to_string();
+    insert_stale_intent(&ctx.db, &committed_id, "created", Some("trade_committed"), 0).await;
+    sqlx::query(
+        r#"
+        INSERT INTO swaps (
+            id, provider_id, provider_swap_id, from_currency, from_network,
+            to_currency, to_network, amount, estimated_receive, rate,
+            deposit_address, recipient_address, status
+        )
+        VALUES (?, 'changenow', 'trade_committed', 'BTC', 'bitcoin', 'ETH', 'ethereum',
+                0.1, 1.5, 15.0, 'dep_addr', '0x742d35Cc6634C0532925a3b844Bc454e4438f44e', 'waiting')
+        "#
+    )
+    .bind(&committed_id)
+    .execute(&ctx.db)
+    .await
+    .expect("Failed to create swap");
+
+    let report = OrphanOrderReconciler::new(ctx.db.clone()).reconcile().await.unwrap();
+
+    assert!(report.orphaned >= 1);
+    assert!(report.resolved >= 1);
+    assert!(report.committed >= 1);
+    assert_eq!(intent_status(&ctx.db, &orphan_id).await, "orphaned");
+    assert_eq!(intent_status(&ctx.db, &pending_id).await, "resolved");
+    assert_eq!(intent_status(&ctx.db, &committed_id).await, "committed");
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_intent_reserves_address_index() {
+    let ctx = TestContext::new().await;
+    let wallet_crud = exchange_shared::modules::wallet::crud::WalletCrud::new(ctx.db.clone());
+
+    let reserved = wallet_crud.get_next_index().await.unwrap();
+    let swap_id = Uuid::new_v4().to_string();
+    insert_stale_intent(&ctx.db, &swap_id, "orphaned", None, reserved).await;
+
+    // The orphaned order's payout address must never be handed to another swap
+    assert_eq!(wallet_crud.get_next_index().await.unwrap(), reserved + 1);
+
+    sqlx::query("DELETE FROM provider_order_intents WHERE swap_id = ?")
+        .bind(&swap_id)
+        .execute(&ctx.db)
+        .await
+        .ok();
+    ctx.cleanup().await;
+}
@@ -9,6 +9,7 @@ mod common;
 use common::TestContext;
 use uuid::Uuid;
 use exchange_shared::modules::wallet::crud::WalletCrud;
+use exchange_shared::services::amount::Decimal;
 use exchange_shared::services::wallet::manager::WalletManager;
 use exchange_shared::services::monitor::MonitorEngine;
 use exchange_shared::modules::wallet::schema::GenerateAddressRequest;
@@ -34,8 +35,8 @@ impl BlockchainProvider for MockBlockchainProvider {
         Ok("0xmocktxhash123".to_string())
     }
 
-    async fn get_balance(&self, _address: &str) -> Result<f64, RpcError> {
-        Ok(1.0)
+    async fn get_balance(&self, _address: &str) -> Result<Decimal, RpcError> {
+        Ok(Decimal::ONE)
     }
 }
 
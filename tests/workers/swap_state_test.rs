@@ -0,0 +1,126 @@
+// =============================================================================
+// INTEGRATION TESTS - SWAP STATE MACHINE
+// Compare-and-set status transitions shared by all workers
+// =============================================================================
+
+use crate::common::TestContext;
+use exchange_shared::modules::swap::schema::SwapStatus;
+use exchange_shared::services::swap_state::{StatusActor, SwapStateMachine, Transition, TransitionError};
+use uuid::Uuid;
+
+async fn create_swap(db: &sqlx::Pool<sqlx::MySql>, swap_id: &str, status: &str) {
+    sqlx::query(
+        r#"
+        INSERT INTO swaps (
+            id, provider_id, provider_swap_id, from_currency, from_network,
+            to_currency, to_network, amount, estimated_receive, rate,
+            deposit_address, recipient_address, status
+        )
+        VALUES (?, 'changenow', 'trade_state', 'BTC', 'bitcoin', 'ETH', 'ethereum',
+                0.1, 1.5, 15.0, 'dep_addr', '0x742d35Cc6634C0532925a3b844Bc454e4438f44e', ?)
+        "#
+    )
+    .bind(swap_id)
+    .bind(status)
+    .execute(db)
+    .await
+    .expect("Failed to create swap");
+}
+
+#[tokio::test]
+async fn test_transition_bumps_version_and_logs_history() {
+    let ctx = TestContext::new().await;
+    let machine = SwapStateMachine::new(ctx.db.clone());
+    let swap_id = Uuid::new_v4().to_string();
+    create_swap(&ctx.db, &swap_id, "sending").await;
+
+    let applied = machine
+        .apply(&swap_id, 0, &Transition::to(SwapStatus::FundsReceived).message("test"))
+        .await
+        .unwrap();
+    assert!(applied.changed);
+    assert_eq!(applied.version, 1);
+    assert_eq!(machine.current(&swap_id).await.unwrap(), (SwapStatus::FundsReceived, 1));
+
+    let (history,): (i64,) = sqlx::query_as(
+        "SELECT COUNT(*) FROM swap_status_history WHERE swap_id = ? AND status = 'funds_received'"
+    )
+    .bind(&swap_id)
+    .fetch_one(&ctx.db)
+    .await
+    .unwrap();
+    assert_eq!(history, 1);
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_stale_version_is_rejected() {
+    let ctx = TestContext::new().await;
+    let machine = SwapStateMachine::new(ctx.db.clone());
+    let swap_id = Uuid::new_v4().to_string();
+    create_swap(&ctx.db, &swap_id, "confirming").await;
+
+    // Another worker moves the swap first
+    machine.advance(&swap_id, &Transition::to(SwapStatus::Sending)).await.unwrap();
+
+    let result = machine.apply(&swap_id, 0, &Transition::to(SwapStatus::Failed)).await;
+    assert_eq!(
+        result,
+        Err(TransitionError::VersionConflict { swap_id: swap_id.clone(), expected: 0 })
+    );
+    assert_eq!(machine.current(&swap_id).await.unwrap().0, SwapStatus::Sending);
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_illegal_transition_is_rejected() {
+    let ctx = TestContext::new().await;
+    let machine = SwapStateMachine::new(ctx.db.clone());
+    let swap_id = Uuid::new_v4().to_string();
+    create_swap(&ctx.db, &swap_id, "completed").await;
+
+    let result = machine.advance(&swap_id, &Transition::to(SwapStatus::Sending)).await;
+    assert_eq!(
+        result,
+        Err(TransitionError::Illegal { from: SwapStatus::Completed, to: SwapStatus::Sending })
+    );
+
+    // Re-applying the current status is a no-op
+    let applied = machine.advance(&swap_id, &Transition::to(SwapStatus::Completed)).await.unwrap();
+    assert!(!applied.changed);
+
+    ctx.cleanup().await;
+}
+
+#[tokio::test]
+async fn test_history_records_actor_and_stage_durations() {
+    let ctx = TestContext::new().await;
+    let machine = SwapStateMachine::new(ctx.db.clone());
+    let swap_id = Uuid::new_v4().to_string();
+    create_swap(&ctx.db, &swap_id, "waiting").await;
+
+    machine
+        .advance(&swap_id, &Transition::to(SwapStatus::Confirming).actor(StatusActor::Provider))
+        .await
+        .unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    machine
+        .advance(&swap_id, &Transition::to(SwapStatus::FundsReceived).actor(StatusActor::Listener).message("seen"))
+        .await
+        .unwrap();
+
+    let history = machine.history(&swap_id).await.unwrap();
+    assert_eq!(history.len(), 2);
+    assert_eq!(history[0].status, SwapStatus::Confirming);
+    assert_eq!(history[0].actor, "provider");
+    assert!(history[0].duration_secs.unwrap() >= 0.05);
+
+    assert_eq!(history[1].status, SwapStatus::FundsReceived);
+    assert_eq!(history[1].actor, "listener");
+    assert_eq!(history[1].message.as_deref(), Some("seen"));
+    assert!(history[1].duration_secs.is_none());
+
+    ctx.cleanup().await;
+}
@@ -0,0 +1,49 @@
+// =============================================================================
+// INTEGRATION TESTS - STARTUP WARM-UP
+// Connections, prepared statements and statics ready before the first request
+// =============================================================================
+
+use crate::common::TestContext;
+use exchange_shared::services::warmup::{Warmup, WarmupConfig};
+use std::time::Duration;
+
+fn wallet_mnemonic() -> String {
+    std::env::var("WALLET_MNEMONIC").unwrap_or_else(|_| {
+        "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about".to_string()
+    })
+}
+
+#[tokio::test]
+async fn test_warmup_opens_connections_and_prepares_statements() {
+    let ctx = TestContext::new().await;
+    let config = WarmupConfig {
+        db_connections: 3,
+        prime_caches: false,
+        ping_rpcs: false,
+        step_timeout: Duration::from_secs(10),
+        ..WarmupConfig::default()
+    };
+
+    let report = Warmup::new(ctx.db.clone(), ctx.redis.clone(), wallet_mnemonic())
+        .with_config(config)
+        .run()
+        .await;
+
+    assert!(report.step("database").unwrap().result.is_ok(), "{:?}", report.step("database"));
+    assert!(report.step("redis").unwrap().result.is_ok());
+    assert!(report.step("statics").unwrap().result.is_ok());
+    assert!(report.step("caches").is_none(), "Cache priming was not requested");
+    assert!(ctx.db.size() >= 3, "Pool should hold the warmed connections");
+}
+
+#[tokio::test]
+async fn test_warmup_disabled_does_nothing() {
+    let ctx = TestContext::new().await;
+
+    let report = Warmup::new(ctx.db.clone(), ctx.redis.clone(), wallet_mnemonic())
+        .with_config(WarmupConfig::disabled())
+        .run()
+        .await;
+
+    assert!(report.steps.is_empty());
+}